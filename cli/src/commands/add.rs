@@ -1,18 +1,50 @@
-use anyhow::{Context, Result};
-use sentinel::core::ConfigManager;
-use sentinel::models::{Config, ProcessConfig};
+use anyhow::{bail, Context, Result};
+use sentinel::core::templates::{NAME_VARIABLE, PROJECT_DIR_VARIABLE};
+use sentinel::core::{instance, ConfigManager, ControlCommand, TemplateStore};
+use sentinel::models::{Config, HealthCheck, HealthCheckKind, ProcessConfig, ProcessOverrides};
+use sentinel::SentinelError;
 use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
-use crate::{create_spinner, get_default_config_path, print_error, print_info, print_success};
+use crate::{
+    create_spinner, get_default_config_path, print_error, print_info, print_success, print_warning,
+};
+
+/// Restart attempts allowed unless `--restart-limit` says otherwise
+pub const DEFAULT_RESTART_LIMIT: u32 = 3;
+
+/// Delay between restarts unless `--restart-delay` says otherwise
+pub const DEFAULT_RESTART_DELAY_MS: u64 = 1000;
+
+/// Health check timeout unless `--health-timeout` says otherwise
+pub const DEFAULT_HEALTH_TIMEOUT_MS: u64 = 5000;
+
+/// Health check retries unless `--health-retries` says otherwise
+pub const DEFAULT_HEALTH_RETRIES: u32 = 3;
+
+/// Flags of the add command when given a command
+pub struct AddOptions {
+    pub name: String,
+    pub command: String,
+    pub directory: Option<PathBuf>,
+    pub auto_restart: bool,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub port: Option<u16>,
+    pub depends_on: Vec<String>,
+    pub restart_limit: u32,
+    pub restart_delay: u64,
+    pub health_cmd: Option<String>,
+    pub health_interval: Option<u64>,
+    pub health_timeout: u64,
+    pub health_retries: u32,
+    pub start: bool,
+}
 
 /// Execute the add command
-pub async fn execute(
-    name: &str,
-    command: &str,
-    directory: Option<PathBuf>,
-    auto_restart: bool,
-) -> Result<()> {
+pub async fn execute(options: AddOptions) -> Result<()> {
+    let name = options.name.clone();
     let config_path = get_default_config_path();
 
     // Load existing configuration or create new
@@ -24,7 +56,11 @@ pub async fn execute(
         print_info("No existing configuration found, creating new one");
         Config {
             processes: Vec::new(),
+            settings: Default::default(),
             global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
         }
     };
     spinner.finish_and_clear();
@@ -39,34 +75,18 @@ pub async fn execute(
         std::process::exit(1);
     }
 
-    // Parse command and args
-    let parts: Vec<&str> = command.split_whitespace().collect();
-    if parts.is_empty() {
-        print_error("Command cannot be empty");
-        std::process::exit(1);
-    }
-
-    let cmd = parts[0].to_string();
-    let args = parts[1..].iter().map(|s| s.to_string()).collect();
-
-    // Create new process config
-    let process_config = ProcessConfig {
-        name: name.to_string(),
-        command: cmd,
-        args,
-        cwd: directory,
-        env: HashMap::new(),
-        depends_on: Vec::new(),
-        auto_restart: Some(auto_restart),
-        max_restarts: Some(3),
-        restart_delay_ms: Some(1000),
-        health_check: None,
+    let start = options.start;
+    let process_config = match process_config(options) {
+        Ok(process_config) => process_config,
+        Err(e) => {
+            print_error(&e);
+            std::process::exit(1);
+        }
     };
-
-    // Add to config
     config.processes.push(process_config);
 
-    // Validate configuration
+    // Validate configuration, including that dependencies exist, before
+    // anything is written
     let spinner = create_spinner("Validating configuration...");
     if let Err(e) = ConfigManager::validate(&config) {
         spinner.finish_and_clear();
@@ -91,11 +111,210 @@ pub async fn execute(
     print_success(&format!("Added process '{}' to configuration", name));
     print_info(&format!("Configuration saved to {}", config_path.display()));
     println!();
+
+    if start {
+        // The running instance starts it from the config file just written
+        let command = ControlCommand::Start(name.clone());
+        match instance::send(&instance::default_socket_path(), command).await {
+            Ok(()) => {
+                print_success(&format!("Started '{}' in the running Sentinel", name));
+                return Ok(());
+            }
+            Err(e @ (SentinelError::FileIoError { .. } | SentinelError::Unsupported { .. })) => {
+                print_warning(&format!("Could not reach a running Sentinel: {}", e))
+            }
+            Err(e) => {
+                print_error(&format!("Failed to start '{}': {}", name, e));
+                return Ok(());
+            }
+        }
+    }
     print_info("Run 'sentinel start' to start all processes");
-    print_info(&format!(
-        "Or run 'sentinel start {}' to start just this process (when implemented)",
-        name
+
+    Ok(())
+}
+
+/// Builds the process from the add command's flags, or describes the problem with them
+fn process_config(options: AddOptions) -> Result<ProcessConfig, String> {
+    // Parse command and args
+    let mut parts = options.command.split_whitespace().map(str::to_string);
+    let Some(cmd) = parts.next() else {
+        return Err("Command cannot be empty".to_string());
+    };
+    let args = parts.chain(options.args).collect();
+
+    let mut env: HashMap<String, String> = options.env.into_iter().collect();
+    if let Some(port) = options.port {
+        env.insert("PORT".to_string(), port.to_string());
+    }
+
+    let health_check = match options.health_cmd {
+        Some(health_cmd) => {
+            let mut parts = health_cmd.split_whitespace().map(str::to_string);
+            let Some(command) = parts.next() else {
+                return Err("Health check command cannot be empty".to_string());
+            };
+            Some(HealthCheck {
+                check: HealthCheckKind::Command {
+                    command,
+                    args: parts.collect(),
+                },
+                interval_ms: options.health_interval,
+                timeout_ms: options.health_timeout,
+                retries: options.health_retries,
+            })
+        }
+        None => None,
+    };
+
+    Ok(ProcessConfig {
+        name: options.name,
+        command: cmd,
+        args,
+        cwd: options.directory,
+        env,
+        depends_on: options.depends_on,
+        auto_restart: Some(options.auto_restart),
+        restart_limit: options.restart_limit,
+        restart_delay: Some(options.restart_delay),
+        health_check,
+        ..Default::default()
+    })
+}
+
+/// Execute the add command with `--from`, copying an existing process
+pub async fn execute_clone(
+    from: &str,
+    name: &str,
+    overrides: ProcessOverrides,
+    auto_restart: bool,
+) -> Result<()> {
+    if name.is_empty() {
+        bail!(
+            "Give the copy a name, e.g. sentinel add --from {} --name {}-2",
+            from,
+            from
+        );
+    }
+
+    let config_path = get_default_config_path();
+    if !config_path.exists() {
+        bail!("No configuration found at {}", config_path.display());
+    }
+
+    let spinner = create_spinner("Loading configuration...");
+    let mut config = ConfigManager::load_from_file(&config_path)
+        .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+    spinner.finish_and_clear();
+
+    // Also checks that the copy's ports are free
+    let mut process_config = match ConfigManager::clone_process(&config, from, name, &overrides) {
+        Ok(process_config) => process_config,
+        Err(e) => {
+            print_error(&format!("Cannot copy '{}': {}", from, e));
+            std::process::exit(1);
+        }
+    };
+    if auto_restart {
+        process_config.auto_restart = Some(true);
+    }
+    config.processes.push(process_config);
+
+    let spinner = create_spinner("Saving configuration...");
+    ConfigManager::save_to_file(&config, &config_path)
+        .with_context(|| format!("Failed to save config to {}", config_path.display()))?;
+    spinner.finish_and_clear();
+
+    print_success(&format!("Added '{}' as a copy of '{}'", name, from));
+    print_info(&format!("Configuration saved to {}", config_path.display()));
+
+    Ok(())
+}
+
+/// Execute the add command with `--template`, filling in the template's variables
+pub async fn execute_template(
+    name: &str,
+    template: &str,
+    mut vars: HashMap<String, String>,
+    directory: Option<PathBuf>,
+    auto_restart: bool,
+) -> Result<()> {
+    let store = TemplateStore::default();
+    let template = store
+        .get(template)
+        .with_context(|| format!("Failed to load template '{}'", template))?;
+
+    vars.insert(NAME_VARIABLE.to_string(), name.to_string());
+    if !vars.contains_key(PROJECT_DIR_VARIABLE) {
+        let directory = match directory {
+            Some(directory) => directory,
+            None => std::env::current_dir()?,
+        };
+        vars.insert(
+            PROJECT_DIR_VARIABLE.to_string(),
+            directory.to_string_lossy().into_owned(),
+        );
+    }
+
+    // Ask for the rest when someone is there to answer
+    if io::stdin().is_terminal() {
+        for variable in template.variables() {
+            if vars.contains_key(&variable.name) {
+                continue;
+            }
+            match &variable.default {
+                Some(default) => print!("{} [{}]: ", variable.name, default),
+                None => print!("{}: ", variable.name),
+            }
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let input = input.trim();
+            if !input.is_empty() {
+                vars.insert(variable.name, input.to_string());
+            }
+        }
+    }
+
+    let mut process_config = template.instantiate(&vars)?;
+    if auto_restart {
+        process_config.auto_restart = Some(true);
+    }
+
+    let config_path = get_default_config_path();
+    let mut config = if config_path.exists() {
+        ConfigManager::load_from_file(&config_path)
+            .with_context(|| format!("Failed to load config from {}", config_path.display()))?
+    } else {
+        Config {
+            processes: Vec::new(),
+            settings: Default::default(),
+            global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
+        }
+    };
+    if config.processes.iter().any(|p| p.name == name) {
+        bail!("Process '{}' already exists in configuration", name);
+    }
+    config.processes.push(process_config);
+
+    let spinner = create_spinner("Saving configuration...");
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    ConfigManager::save_to_file(&config, &config_path)
+        .with_context(|| format!("Failed to save config to {}", config_path.display()))?;
+    spinner.finish_and_clear();
+
+    print_success(&format!(
+        "Added '{}' from template '{}'",
+        name, template.name
     ));
+    print_info(&format!("Configuration saved to {}", config_path.display()));
 
     Ok(())
 }
@@ -0,0 +1,14 @@
+use anyhow::Result;
+use clap::Command;
+use clap_complete::Shell;
+use std::io;
+
+/// Execute the completions command
+///
+/// Prints the completion script to stdout, e.g.
+/// `sentinel completions zsh > ~/.zfunc/_sentinel`
+pub fn execute(shell: Shell, command: &mut Command) -> Result<()> {
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, command, name, &mut io::stdout());
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use comfy_table::{Cell, Color, Table};
+use sentinel::core::{diagnostics, CheckStatus};
+use std::path::PathBuf;
+
+use crate::{create_spinner, get_default_config_path, print_error, print_success, print_warning};
+
+/// Execute the doctor command
+pub async fn execute(config_file: Option<PathBuf>, format: &str) -> Result<()> {
+    let config_path = config_file.unwrap_or_else(get_default_config_path);
+
+    let spinner = create_spinner("Running diagnostics...");
+    let report = diagnostics::run_checks(&config_path).await;
+    spinner.finish_and_clear();
+
+    match format {
+        "json" => {
+            // JSON output for bug reports
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+
+        _ => {
+            // Pretty table output
+            let mut table = Table::new();
+
+            table.set_header(vec![
+                Cell::new("STATUS").fg(Color::Cyan),
+                Cell::new("CHECK").fg(Color::Cyan),
+                Cell::new("DETAILS").fg(Color::Cyan),
+                Cell::new("HOW TO FIX").fg(Color::Cyan),
+            ]);
+
+            for check in &report.checks {
+                let (status, color) = match check.status {
+                    CheckStatus::Pass => ("PASS", Color::Green),
+                    CheckStatus::Warn => ("WARN", Color::Yellow),
+                    CheckStatus::Fail => ("FAIL", Color::Red),
+                };
+
+                table.add_row(vec![
+                    Cell::new(status).fg(color),
+                    Cell::new(&check.name),
+                    Cell::new(&check.message),
+                    Cell::new(check.hint.as_deref().unwrap_or("-")),
+                ]);
+            }
+
+            println!("Sentinel {} on {}", report.version, report.platform);
+            println!("{table}");
+            println!();
+
+            let count = |status| {
+                report
+                    .checks
+                    .iter()
+                    .filter(|check| check.status == status)
+                    .count()
+            };
+            let (failed, warned) = (count(CheckStatus::Fail), count(CheckStatus::Warn));
+            if failed > 0 {
+                print_error(&format!(
+                    "{} check(s) failed, {} warning(s)",
+                    failed, warned
+                ));
+            } else if warned > 0 {
+                print_warning(&format!("All checks passed with {} warning(s)", warned));
+            } else {
+                print_success("All checks passed");
+            }
+        }
+    }
+
+    if report.has_failures() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+use comfy_table::{Cell, Table};
+use sentinel::core::EventJournal;
+use sentinel::models::{EventActor, EventFilter, EventType};
+
+use crate::print_info;
+
+/// Execute the events command
+pub async fn execute(
+    since: Option<&str>,
+    process: Option<String>,
+    event_types: &[String],
+    limit: usize,
+    format: &str,
+) -> Result<()> {
+    let since = since.map(parse_since).transpose()?;
+    let event_types = event_types
+        .iter()
+        .map(|name| parse_event_type(name))
+        .collect::<Result<Vec<_>>>()?;
+    let filter = EventFilter {
+        subject: process,
+        event_types,
+        actor: None,
+    };
+
+    let journal = EventJournal::new(EventJournal::default_path());
+    let events = journal
+        .query(&filter, since, Some(limit))
+        .with_context(|| format!("Failed to read {}", journal.path().display()))?;
+
+    match format {
+        "json" => {
+            // JSON lines, as stored in the journal
+            for event in &events {
+                println!("{}", serde_json::to_string(event)?);
+            }
+        }
+
+        _ => {
+            // Pretty table output
+            if events.is_empty() {
+                print_info("No events recorded");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+
+            table.set_header(vec![
+                Cell::new("TIME").fg(comfy_table::Color::Cyan),
+                Cell::new("EVENT").fg(comfy_table::Color::Cyan),
+                Cell::new("SUBJECT").fg(comfy_table::Color::Cyan),
+                Cell::new("ACTOR").fg(comfy_table::Color::Cyan),
+                Cell::new("DETAILS").fg(comfy_table::Color::Cyan),
+            ]);
+
+            for event in &events {
+                let time = DateTime::<Local>::from(event.timestamp)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                let event_type = serde_json::to_value(event.event_type)?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                let actor = match event.actor {
+                    EventActor::User => "user",
+                    EventActor::Supervisor => "supervisor",
+                };
+                let details = if event.details.is_empty() {
+                    "-".to_string()
+                } else {
+                    event
+                        .details
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key, value))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+
+                table.add_row(vec![
+                    Cell::new(&time),
+                    Cell::new(&event_type),
+                    Cell::new(&event.subject),
+                    Cell::new(actor),
+                    Cell::new(&details),
+                ]);
+            }
+
+            println!("{table}");
+            println!();
+            print_info(&format!("{} event(s)", events.len()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `--since` as a relative age (`30s`, `15m`, `1h`, `7d`) or an RFC 3339 time
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let split = value.char_indices().last().map_or(0, |(index, _)| index);
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Invalid --since '{}': expected e.g. 30m, 1h or 2d", value))?;
+    let age = match unit {
+        "s" => Duration::seconds(amount),
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => bail!("Invalid --since '{}': expected e.g. 30m, 1h or 2d", value),
+    };
+    Ok(Utc::now() - age)
+}
+
+/// Parse an event type by its journal name, e.g. `process_crashed`
+fn parse_event_type(name: &str) -> Result<EventType> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .with_context(|| format!("Unknown event type '{}'", name))
+}
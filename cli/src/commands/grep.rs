@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use sentinel::core::{
+    ConfigManager, LogLine, LogQuery, LogSearchOptions, LogStream, ProcessManager,
+};
+
+use crate::commands::events::parse_since;
+use crate::{create_spinner, get_default_config_path, print_info, print_warning};
+
+/// Flags of the grep command
+pub struct GrepOptions {
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub context: usize,
+    pub stream: Option<String>,
+    pub since: Option<String>,
+    pub max_count: usize,
+}
+
+/// Execute the grep command
+pub async fn execute(pattern: &str, options: GrepOptions) -> Result<()> {
+    let stream = match options.stream.as_deref() {
+        None => None,
+        Some("stdout") => Some(LogStream::Stdout),
+        Some("stderr") => Some(LogStream::Stderr),
+        Some(other) => anyhow::bail!("Invalid --stream '{}': expected stdout or stderr", other),
+    };
+    let search = LogSearchOptions {
+        regex: options.regex,
+        case_sensitive: options.case_sensitive,
+        since: options.since.as_deref().map(parse_since).transpose()?,
+        until: None,
+        order_by: Default::default(),
+        stream,
+        max_per_process: Some(options.max_count),
+        context: options.context,
+        include_attachments: false,
+    };
+    let query = LogQuery::new(pattern, search)?;
+
+    let config_path = get_default_config_path();
+    let spinner = create_spinner("Loading configuration...");
+    ConfigManager::load_from_file(&config_path)
+        .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+    spinner.finish_and_clear();
+
+    let manager = ProcessManager::new();
+    let results = manager.search_all_logs(&query).await;
+    if results.is_empty() {
+        print_info(&format!("No log lines match '{}'", pattern));
+        return Ok(());
+    }
+
+    let mut total = 0;
+    for result in &results {
+        println!("{}", result.process.cyan().bold());
+        for found in &result.matches {
+            for line in &found.before {
+                print_line(&result.process, line, false);
+            }
+            print_line(&result.process, &found.line, true);
+            for line in &found.after {
+                print_line(&result.process, line, false);
+            }
+            if options.context > 0 {
+                println!("{}", "--".bright_black());
+            }
+        }
+        if result.truncated {
+            print_warning(&format!(
+                "Showing the last {} matches of '{}', use --max-count for more",
+                options.max_count, result.process
+            ));
+        }
+        total += result.matches.len();
+    }
+
+    println!();
+    print_info(&format!(
+        "{} match(es) in {} process(es)",
+        total,
+        results.len()
+    ));
+
+    Ok(())
+}
+
+/// Print a log line, matches highlighted and context lines dimmed
+fn print_line(process: &str, line: &LogLine, is_match: bool) {
+    let time = line.timestamp.format("%H:%M:%S");
+    let prefix = match line.stream {
+        LogStream::Stdout => format!("{} {} |", time, process),
+        LogStream::Stderr => format!("{} {} !", time, process),
+    };
+    if is_match {
+        println!("{} {}", prefix.bright_black(), line.line);
+    } else {
+        println!("{}", format!("{} {}", prefix, line.line).bright_black());
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use sentinel::core::{ImportReport, ImportSource};
+use std::path::Path;
+
+use crate::{print_info, print_success, print_warning};
+
+/// Formats `sentinel import` understands
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    /// PM2 ecosystem file (ecosystem.config.js or .json)
+    Pm2,
+    /// Docker Compose file
+    Compose,
+}
+
+impl From<ImportFormat> for ImportSource {
+    fn from(format: ImportFormat) -> Self {
+        match format {
+            ImportFormat::Pm2 => ImportSource::Pm2,
+            ImportFormat::Compose => ImportSource::Compose,
+        }
+    }
+}
+
+/// Execute the import command
+pub async fn execute(format: ImportFormat, file: &Path, output: &Path, force: bool) -> Result<()> {
+    if output.exists() && !force {
+        bail!(
+            "{} already exists; use --force to overwrite it",
+            output.display()
+        );
+    }
+
+    let report = ImportReport::from_file(format.into(), file)
+        .with_context(|| format!("Failed to import {}", file.display()))?;
+
+    for warning in &report.warnings {
+        print_warning(warning);
+    }
+
+    report
+        .write(output, force)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    print_success(&format!(
+        "Imported {} process(es) into {}",
+        report.config.processes.len(),
+        output.display()
+    ));
+    if !report.warnings.is_empty() {
+        print_info("Skipped options are listed at the top of the file");
+    }
+    print_info(&format!(
+        "Run 'sentinel start {}' to start them",
+        output.display()
+    ));
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use console::style;
 use sentinel::core::ConfigManager;
-use sentinel::models::{Config, HealthCheck, ProcessConfig};
+use sentinel::models::{Config, HealthCheck, HealthCheckKind, ProcessConfig};
 use std::collections::HashMap;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -93,14 +93,16 @@ fn create_simple_template() -> Config {
             command: "node".to_string(),
             args: vec!["server.js".to_string()],
             cwd: Some(PathBuf::from(".")),
-            env: HashMap::new(),
-            depends_on: Vec::new(),
             auto_restart: Some(true),
-            max_restarts: Some(3),
-            restart_delay_ms: Some(1000),
-            health_check: None,
+            restart_limit: 3,
+            restart_delay: Some(1000),
+            ..Default::default()
         }],
+        settings: Default::default(),
         global_env: HashMap::new(),
+        include: vec![],
+        alerts: vec![],
+        defaults: Default::default(),
     }
 }
 
@@ -127,19 +129,19 @@ fn create_fullstack_template() -> Config {
                     "5432:5432".to_string(),
                     "postgres:15".to_string(),
                 ],
-                cwd: None,
-                env: HashMap::new(),
-                depends_on: Vec::new(),
                 auto_restart: Some(true),
-                max_restarts: Some(3),
-                restart_delay_ms: Some(2000),
+                restart_limit: 3,
+                restart_delay: Some(2000),
                 health_check: Some(HealthCheck {
-                    command: "pg_isready".to_string(),
-                    args: vec!["-h".to_string(), "localhost".to_string()],
-                    interval_ms: 5000,
+                    check: HealthCheckKind::Command {
+                        command: "pg_isready".to_string(),
+                        args: vec!["-h".to_string(), "localhost".to_string()],
+                    },
+                    interval_ms: Some(5000),
                     timeout_ms: 3000,
                     retries: 3,
                 }),
+                ..Default::default()
             },
             ProcessConfig {
                 name: "backend".to_string(),
@@ -149,15 +151,19 @@ fn create_fullstack_template() -> Config {
                 env: backend_env,
                 depends_on: vec!["database".to_string()],
                 auto_restart: Some(true),
-                max_restarts: Some(3),
-                restart_delay_ms: Some(1000),
+                restart_limit: 3,
+                restart_delay: Some(1000),
                 health_check: Some(HealthCheck {
-                    command: "curl".to_string(),
-                    args: vec!["-f".to_string(), "http://localhost:8101/health".to_string()],
-                    interval_ms: 10000,
+                    check: HealthCheckKind::Http {
+                        url: "http://localhost:8101/health".to_string(),
+                        method: "GET".to_string(),
+                        expected_status: None,
+                    },
+                    interval_ms: Some(10000),
                     timeout_ms: 5000,
                     retries: 3,
                 }),
+                ..Default::default()
             },
             ProcessConfig {
                 name: "frontend".to_string(),
@@ -167,12 +173,16 @@ fn create_fullstack_template() -> Config {
                 env: frontend_env,
                 depends_on: vec!["backend".to_string()],
                 auto_restart: Some(true),
-                max_restarts: Some(3),
-                restart_delay_ms: Some(1000),
-                health_check: None,
+                restart_limit: 3,
+                restart_delay: Some(1000),
+                ..Default::default()
             },
         ],
+        settings: Default::default(),
         global_env: HashMap::new(),
+        include: vec![],
+        alerts: vec![],
+        defaults: Default::default(),
     }
 }
 
@@ -190,13 +200,10 @@ fn create_microservices_template() -> Config {
                     "6379:6379".to_string(),
                     "redis:7-alpine".to_string(),
                 ],
-                cwd: None,
-                env: HashMap::new(),
-                depends_on: Vec::new(),
                 auto_restart: Some(true),
-                max_restarts: Some(5),
-                restart_delay_ms: Some(2000),
-                health_check: None,
+                restart_limit: 5,
+                restart_delay: Some(2000),
+                ..Default::default()
             },
             ProcessConfig {
                 name: "postgres".to_string(),
@@ -208,56 +215,54 @@ fn create_microservices_template() -> Config {
                     "5432:5432".to_string(),
                     "postgres:15".to_string(),
                 ],
-                cwd: None,
-                env: HashMap::new(),
-                depends_on: Vec::new(),
                 auto_restart: Some(true),
-                max_restarts: Some(5),
-                restart_delay_ms: Some(2000),
-                health_check: None,
+                restart_limit: 5,
+                restart_delay: Some(2000),
+                ..Default::default()
             },
             ProcessConfig {
                 name: "auth-service".to_string(),
                 command: "npm".to_string(),
                 args: vec!["start".to_string()],
                 cwd: Some(PathBuf::from("./services/auth")),
-                env: HashMap::new(),
                 depends_on: vec!["postgres".to_string(), "redis".to_string()],
                 auto_restart: Some(true),
-                max_restarts: Some(3),
-                restart_delay_ms: Some(1000),
-                health_check: None,
+                restart_limit: 3,
+                restart_delay: Some(1000),
+                ..Default::default()
             },
             ProcessConfig {
                 name: "api-gateway".to_string(),
                 command: "npm".to_string(),
                 args: vec!["start".to_string()],
                 cwd: Some(PathBuf::from("./services/gateway")),
-                env: HashMap::new(),
                 depends_on: vec!["auth-service".to_string()],
                 auto_restart: Some(true),
-                max_restarts: Some(3),
-                restart_delay_ms: Some(1000),
-                health_check: None,
+                restart_limit: 3,
+                restart_delay: Some(1000),
+                ..Default::default()
             },
             ProcessConfig {
                 name: "user-service".to_string(),
                 command: "npm".to_string(),
                 args: vec!["start".to_string()],
                 cwd: Some(PathBuf::from("./services/users")),
-                env: HashMap::new(),
                 depends_on: vec!["postgres".to_string(), "redis".to_string()],
                 auto_restart: Some(true),
-                max_restarts: Some(3),
-                restart_delay_ms: Some(1000),
-                health_check: None,
+                restart_limit: 3,
+                restart_delay: Some(1000),
+                ..Default::default()
             },
         ],
+        settings: Default::default(),
         global_env: {
             let mut env = HashMap::new();
             env.insert("NODE_ENV".to_string(), "development".to_string());
             env.insert("LOG_LEVEL".to_string(), "debug".to_string());
             env
         },
+        include: vec![],
+        alerts: vec![],
+        defaults: Default::default(),
     }
 }
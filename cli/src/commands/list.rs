@@ -2,10 +2,10 @@ use anyhow::{Context, Result};
 use comfy_table::{Cell, Table};
 use sentinel::core::ConfigManager;
 
-use crate::{create_spinner, get_default_config_path, print_info};
+use crate::{create_spinner, filter_by_tag, format_tags, get_default_config_path, print_info};
 
 /// Execute the list command
-pub async fn execute(format: &str) -> Result<()> {
+pub async fn execute(format: &str, tag: Option<&str>) -> Result<()> {
     let config_path = get_default_config_path();
 
     // Load configuration
@@ -20,11 +20,12 @@ pub async fn execute(format: &str) -> Result<()> {
         return Ok(());
     }
 
+    let processes = filter_by_tag(config.processes, tag);
+
     match format {
         "json" => {
             // JSON output for scripting
-            let processes: Vec<_> = config
-                .processes
+            let processes: Vec<_> = processes
                 .iter()
                 .map(|p| {
                     serde_json::json!({
@@ -34,20 +35,26 @@ pub async fn execute(format: &str) -> Result<()> {
                         "cwd": p.cwd,
                         "auto_restart": p.auto_restart,
                         "depends_on": p.depends_on,
+                        "tags": p.tags,
                     })
                 })
                 .collect();
 
             let output = serde_json::json!({
                 "processes": processes,
-                "total": config.processes.len(),
+                "total": processes.len(),
             });
 
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
 
-        "table" | _ => {
+        _ => {
             // Pretty table output
+            if let (Some(tag), true) = (tag, processes.is_empty()) {
+                print_info(&format!("No process is tagged '{}'", tag));
+                return Ok(());
+            }
+
             let mut table = Table::new();
 
             table.set_header(vec![
@@ -56,9 +63,10 @@ pub async fn execute(format: &str) -> Result<()> {
                 Cell::new("WORKING DIR").fg(comfy_table::Color::Cyan),
                 Cell::new("AUTO-RESTART").fg(comfy_table::Color::Cyan),
                 Cell::new("DEPENDS ON").fg(comfy_table::Color::Cyan),
+                Cell::new("TAGS").fg(comfy_table::Color::Cyan),
             ]);
 
-            for process in &config.processes {
+            for process in &processes {
                 let full_command = if process.args.is_empty() {
                     process.command.clone()
                 } else {
@@ -88,15 +96,13 @@ pub async fn execute(format: &str) -> Result<()> {
                     Cell::new(&cwd),
                     Cell::new(auto_restart),
                     Cell::new(&depends_on),
+                    Cell::new(format_tags(&process.tags)),
                 ]);
             }
 
             println!("{table}");
             println!();
-            print_info(&format!(
-                "{} process(es) configured",
-                config.processes.len()
-            ));
+            print_info(&format!("{} process(es) configured", processes.len()));
         }
     }
 
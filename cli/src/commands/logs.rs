@@ -1,10 +1,8 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
 use sentinel::core::{ConfigManager, ProcessManager};
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
-use crate::{create_spinner, get_default_config_path, print_error, print_info, print_warning};
+use crate::{create_spinner, get_default_config_path, print_info, print_warning};
 
 /// Execute the logs command
 pub async fn execute(process_name: &str, follow: bool, lines: usize) -> Result<()> {
@@ -17,28 +15,26 @@ pub async fn execute(process_name: &str, follow: bool, lines: usize) -> Result<(
     spinner.finish_and_clear();
 
     // Check if process exists in config
-    let process_config = config
+    config
         .processes
         .iter()
         .find(|p| p.name == process_name)
         .ok_or_else(|| anyhow::anyhow!("Process '{}' not found in configuration", process_name))?;
 
     // Initialize process manager
-    let pm = Arc::new(Mutex::new(ProcessManager::new()));
-    let manager = pm.lock().await;
+    let manager = ProcessManager::new();
 
     // Check if process is running
-    let info = manager.get_process(process_name);
-
-    if info.is_none() {
+    if manager.get(process_name).await.is_none() {
         print_warning(&format!("Process '{}' is not running", process_name));
         return Ok(());
     }
 
-    let info = info.unwrap();
-
     // Get logs from process manager
-    let logs = manager.get_logs(process_name, lines)?;
+    let logs = manager
+        .get_recent_logs(process_name, lines)
+        .await
+        .unwrap_or_default();
 
     if logs.is_empty() {
         print_info(&format!("No logs available for '{}'", process_name));
@@ -55,7 +51,7 @@ pub async fn execute(process_name: &str, follow: bool, lines: usize) -> Result<(
 
     for log_entry in &logs {
         // Color code based on log level keywords
-        let line = &log_entry.message;
+        let line = &log_entry.line;
         if line.to_lowercase().contains("error") || line.to_lowercase().contains("fatal") {
             println!("{}", line.red());
         } else if line.to_lowercase().contains("warn") {
@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use comfy_table::{Cell, Table};
+use sentinel::core::{ConfigManager, HistoryMetric, MetricPoint, MetricsStore};
+use sentinel::models::{MetricsHistorySettings, SYSTEM_TARGET};
+use std::collections::BTreeMap;
+
+use crate::commands::events::parse_since;
+use crate::commands::status::format_memory;
+use crate::{get_default_config_path, print_info};
+
+/// Execute the metrics command
+pub async fn execute(
+    target: &str,
+    last: &str,
+    metrics: &[String],
+    points: usize,
+    format: &str,
+) -> Result<()> {
+    let from = parse_since(last)
+        .map_err(|_| anyhow!("Invalid --last '{}': expected e.g. 30m, 6h or 7d", last))?;
+    let to = Utc::now();
+    let metrics = if metrics.is_empty() {
+        default_metrics(target)
+    } else {
+        metrics
+            .iter()
+            .map(|name| parse_metric(name))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let config_path = get_default_config_path();
+    let settings = if config_path.exists() {
+        ConfigManager::load_from_file(&config_path)
+            .with_context(|| format!("Failed to load config from {}", config_path.display()))?
+            .settings
+            .metrics_history
+    } else {
+        MetricsHistorySettings::default()
+    };
+    let path = MetricsStore::path(&settings);
+    if !path.exists() {
+        print_info("No metrics recorded yet");
+        print_info("Set settings.metricsHistory.enabled to record them");
+        return Ok(());
+    }
+
+    let store =
+        MetricsStore::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut series = BTreeMap::new();
+    for &metric in &metrics {
+        let queried = store
+            .query(target, metric, from, to, points)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        series.insert(metric.as_str(), queried);
+    }
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&series)?);
+        }
+
+        _ => {
+            // Pretty table output, one row per bucket
+            let mut rows: BTreeMap<DateTime<Utc>, Vec<Option<&MetricPoint>>> = BTreeMap::new();
+            for (index, metric) in metrics.iter().enumerate() {
+                for point in &series[metric.as_str()] {
+                    rows.entry(point.timestamp)
+                        .or_insert_with(|| vec![None; metrics.len()])[index] = Some(point);
+                }
+            }
+            if rows.is_empty() {
+                print_info(&format!(
+                    "No metrics recorded for '{}' in the last {}",
+                    target, last
+                ));
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            let mut header = vec![Cell::new("TIME").fg(comfy_table::Color::Cyan)];
+            header.extend(metrics.iter().map(|metric| {
+                Cell::new(format!("{} (AVG / MAX)", metric.as_str().to_uppercase()))
+                    .fg(comfy_table::Color::Cyan)
+            }));
+            table.set_header(header);
+
+            for (timestamp, values) in &rows {
+                let time = DateTime::<Local>::from(*timestamp)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                let mut row = vec![Cell::new(time)];
+                for (metric, point) in metrics.iter().zip(values) {
+                    let value = match point {
+                        Some(point) => format!(
+                            "{} / {}",
+                            format_value(*metric, point.avg),
+                            format_value(*metric, point.max)
+                        ),
+                        None => "-".to_string(),
+                    };
+                    row.push(Cell::new(value));
+                }
+                table.add_row(row);
+            }
+
+            println!("{table}");
+            println!();
+            print_info(&format!(
+                "'{}' over the last {}, {} point(s)",
+                target,
+                last,
+                rows.len()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Metrics shown when none are asked for
+fn default_metrics(target: &str) -> Vec<HistoryMetric> {
+    if target == SYSTEM_TARGET {
+        vec![
+            HistoryMetric::Cpu,
+            HistoryMetric::Memory,
+            HistoryMetric::Disk,
+            HistoryMetric::NetworkRx,
+            HistoryMetric::NetworkTx,
+        ]
+    } else {
+        vec![HistoryMetric::Cpu, HistoryMetric::Memory]
+    }
+}
+
+/// Parse a metric by its stored name, e.g. `cpu` or `network_rx`
+fn parse_metric(name: &str) -> Result<HistoryMetric> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .with_context(|| format!("Unknown metric '{}'", name))
+}
+
+/// Format a value in the unit of `metric`
+fn format_value(metric: HistoryMetric, value: f64) -> String {
+    match metric {
+        HistoryMetric::Cpu | HistoryMetric::Disk => format!("{:.1}%", value),
+        HistoryMetric::Memory => format_memory(value as u64),
+        HistoryMetric::NetworkRx | HistoryMetric::NetworkTx => {
+            format!("{}/s", format_memory(value as u64))
+        }
+    }
+}
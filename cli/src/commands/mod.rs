@@ -1,9 +1,21 @@
 pub mod add;
+pub mod completions;
+pub mod doctor;
+pub mod events;
+pub mod grep;
+pub mod import;
 pub mod init;
 pub mod list;
 pub mod logs;
+pub mod metrics;
+pub mod ps;
 pub mod remove;
 pub mod restart;
+pub mod run;
+pub mod schema;
 pub mod start;
 pub mod status;
 pub mod stop;
+pub mod top;
+pub mod validate;
+pub mod workspace;
@@ -0,0 +1,308 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use comfy_table::{Cell, Table};
+use sentinel::core::{logging, ConfigManager, LiveEvent, ProcessManager};
+use sentinel::models::{ProcessInfo, ProcessState};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep_until, Instant};
+
+use crate::{create_spinner, format_state, get_default_config_path, print_error, state_color};
+
+/// How long a watch stays quiet before printing a heartbeat
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Options for the ps command
+pub struct PsOptions {
+    pub config_file: Option<PathBuf>,
+    pub profiles: Vec<String>,
+    pub watch: bool,
+    /// Seconds between records with --watch, instead of one per change
+    pub interval: Option<u64>,
+    pub format: String,
+}
+
+/// What a record says about one process
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ProcessStatus {
+    name: String,
+    state: ProcessState,
+    pid: Option<u32>,
+    restart_count: u32,
+    ready: bool,
+}
+
+impl From<ProcessInfo> for ProcessStatus {
+    fn from(info: ProcessInfo) -> Self {
+        Self {
+            name: info.name,
+            state: info.state,
+            pid: info.pid,
+            restart_count: info.restart_count,
+            ready: info.ready,
+        }
+    }
+}
+
+/// A process that appeared (`from` is null), went away (`to` is null) or
+/// changed since the previous record
+#[derive(Debug, Serialize)]
+struct Change {
+    name: String,
+    from: Option<ProcessStatus>,
+    to: Option<ProcessStatus>,
+}
+
+/// One line of the feed
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    /// Every process, first in the feed
+    Snapshot { processes: Vec<ProcessStatus> },
+    /// What changed since the previous record
+    Change { changes: Vec<Change> },
+    /// Nothing changed, but the feed is alive
+    Heartbeat,
+}
+
+/// A record with its place in the feed
+#[derive(Serialize)]
+struct Numbered<'a> {
+    seq: u64,
+    time: DateTime<Utc>,
+    #[serde(flatten)]
+    record: &'a Record,
+}
+
+/// Execute the ps command
+pub async fn execute(options: PsOptions) -> Result<()> {
+    let json = options.format == "json";
+    let config_path = options.config_file.unwrap_or_else(get_default_config_path);
+
+    // Log lines would break the feed, which scripts parse line by line
+    logging::set_log_level(if json { "off" } else { "warn" })?;
+
+    let config = ConfigManager::load_from_file(&config_path)
+        .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+    let processes = ConfigManager::select_processes(&config, &options.profiles)
+        .context("Failed to select processes for the requested profiles")?;
+
+    let spinner = (!json).then(|| create_spinner("Starting processes..."));
+    let manager = ProcessManager::with_settings(config.settings.clone());
+    let mut failed = false;
+    for (name, result) in manager.start_all(processes).await {
+        if let Err(e) = result {
+            print_error(&format!("Failed to start {}: {}", name, e));
+            failed = true;
+        }
+    }
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
+
+    let mut feed = Feed::new(json);
+    feed.snapshot(manager.list().await)?;
+    let result = if options.watch {
+        let interval = options.interval.map(Duration::from_secs);
+        watch(&manager, &mut feed, interval).await
+    } else {
+        Ok(())
+    };
+
+    let _ = manager.stop_all().await;
+    if options.watch {
+        // Consumers see the processes stop
+        feed.update(manager.list().await, false)?;
+    }
+    result?;
+
+    if failed && !options.watch {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Supervise the processes and report them until Ctrl+C
+///
+/// Without `interval`, a record follows every state change the manager
+/// reports, and each health check, in case notifications were dropped.
+async fn watch(
+    manager: &ProcessManager,
+    feed: &mut Feed,
+    interval: Option<Duration>,
+) -> Result<()> {
+    let mut events = manager.events().subscribe();
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    let check_interval =
+        Duration::from_millis(manager.settings().health_check_interval_ms.max(100));
+    let mut next_check = Instant::now() + check_interval;
+    let mut next_report = interval.map(|interval| Instant::now() + interval);
+
+    loop {
+        let report_at = next_report.unwrap_or(feed.last_emitted + HEARTBEAT_INTERVAL);
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            event = events.recv() => match event {
+                Ok(LiveEvent::Process(_)) | Err(RecvError::Lagged(_)) if interval.is_none() => {
+                    feed.update(manager.list().await, false)?;
+                }
+                _ => {}
+            },
+            _ = sleep_until(next_check) => {
+                // Restart crashed processes, as the app's supervisor loop does
+                manager.check_health().await;
+                if interval.is_none() {
+                    feed.update(manager.list().await, false)?;
+                }
+                next_check = Instant::now() + check_interval;
+            }
+            _ = sleep_until(report_at) => {
+                feed.update(manager.list().await, true)?;
+                next_report = interval.map(|interval| Instant::now() + interval);
+            }
+        }
+    }
+}
+
+/// Numbered records on stdout, each flushed as it's written
+struct Feed {
+    json: bool,
+    seq: u64,
+    last: BTreeMap<String, ProcessStatus>,
+    last_emitted: Instant,
+}
+
+impl Feed {
+    fn new(json: bool) -> Self {
+        Self {
+            json,
+            seq: 0,
+            last: BTreeMap::new(),
+            last_emitted: Instant::now(),
+        }
+    }
+
+    /// Print every process
+    fn snapshot(&mut self, processes: Vec<ProcessInfo>) -> Result<()> {
+        self.last = by_name(processes);
+        let processes = self.last.values().cloned().collect();
+        self.emit(&Record::Snapshot { processes })
+    }
+
+    /// Print what changed since the previous record, or with `heartbeat` a
+    /// heartbeat if nothing did
+    fn update(&mut self, processes: Vec<ProcessInfo>, heartbeat: bool) -> Result<()> {
+        let current = by_name(processes);
+        let changes = diff(&self.last, &current);
+        self.last = current;
+        if !changes.is_empty() {
+            self.emit(&Record::Change { changes })
+        } else if heartbeat {
+            self.emit(&Record::Heartbeat)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn emit(&mut self, record: &Record) -> Result<()> {
+        self.seq += 1;
+        self.last_emitted = Instant::now();
+        let numbered = Numbered {
+            seq: self.seq,
+            time: Utc::now(),
+            record,
+        };
+
+        let mut stdout = io::stdout().lock();
+        if self.json {
+            writeln!(stdout, "{}", serde_json::to_string(&numbered)?)?;
+        } else {
+            write_text(&mut stdout, &numbered)?;
+        }
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// Statuses keyed by process name
+fn by_name(processes: Vec<ProcessInfo>) -> BTreeMap<String, ProcessStatus> {
+    processes
+        .into_iter()
+        .map(|info| (info.name.clone(), ProcessStatus::from(info)))
+        .collect()
+}
+
+/// The processes that differ between `previous` and `current`, by name
+fn diff(
+    previous: &BTreeMap<String, ProcessStatus>,
+    current: &BTreeMap<String, ProcessStatus>,
+) -> Vec<Change> {
+    let mut names: Vec<&String> = previous.keys().chain(current.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| previous.get(*name) != current.get(*name))
+        .map(|name| Change {
+            name: name.clone(),
+            from: previous.get(name).cloned(),
+            to: current.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Print a record for people: a table first, then a line per change
+fn write_text(out: &mut impl Write, numbered: &Numbered) -> Result<()> {
+    let time = DateTime::<Local>::from(numbered.time).format("%H:%M:%S");
+    match numbered.record {
+        Record::Snapshot { processes } => {
+            let mut table = Table::new();
+            table.set_header(vec![
+                Cell::new("NAME").fg(comfy_table::Color::Cyan),
+                Cell::new("STATE").fg(comfy_table::Color::Cyan),
+                Cell::new("PID").fg(comfy_table::Color::Cyan),
+                Cell::new("RESTARTS").fg(comfy_table::Color::Cyan),
+            ]);
+            for process in processes {
+                table.add_row(vec![
+                    Cell::new(&process.name),
+                    Cell::new(format_state(&process.state)).fg(state_color(&process.state)),
+                    Cell::new(format_pid(process.pid)),
+                    Cell::new(process.restart_count),
+                ]);
+            }
+            writeln!(out, "{table}")?;
+        }
+        Record::Change { changes } => {
+            for change in changes {
+                let state = |status: &Option<ProcessStatus>| {
+                    status
+                        .as_ref()
+                        .map_or_else(|| "-".to_string(), |status| format_state(&status.state))
+                };
+                writeln!(
+                    out,
+                    "{} {}: {} -> {} (PID {})",
+                    time,
+                    change.name,
+                    state(&change.from),
+                    state(&change.to),
+                    format_pid(change.to.as_ref().and_then(|status| status.pid))
+                )?;
+            }
+        }
+        // Only scripts need to know the feed is alive
+        Record::Heartbeat => {}
+    }
+    Ok(())
+}
+
+fn format_pid(pid: Option<u32>) -> String {
+    pid.map_or_else(|| "-".to_string(), |pid| pid.to_string())
+}
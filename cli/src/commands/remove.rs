@@ -3,7 +3,7 @@ use console::style;
 use sentinel::core::ConfigManager;
 use std::io::{self, Write};
 
-use crate::{create_spinner, get_default_config_path, print_error, print_info, print_success};
+use crate::{create_spinner, get_default_config_path, print_info, print_success};
 
 /// Execute the remove command
 pub async fn execute(name: &str, yes: bool) -> Result<()> {
@@ -1,66 +1,90 @@
-use anyhow::{Context, Result};
-use sentinel::core::{ConfigManager, ProcessManager};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use anyhow::{bail, Context, Result};
+use sentinel::core::{messages, ConfigManager, ControlCommand};
+use sentinel::SentinelError;
 
-use crate::{create_spinner, get_default_config_path, print_error, print_info, print_success};
+use crate::{
+    create_spinner, filter_by_tag, get_default_config_path, print_error, print_info, print_success,
+    select_by_name, send_to_instance,
+};
 
 /// Execute the restart command
-pub async fn execute(force: bool) -> Result<()> {
+///
+/// The running `sentinel start` or app restarts the processes, as they belong to it
+pub async fn execute(names: &[String], force: bool, group: Option<&str>) -> Result<()> {
     let config_path = get_default_config_path();
 
     // Load configuration
-    let spinner = create_spinner("Loading configuration...");
-    let config = ConfigManager::load_from_file(&config_path)
-        .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+    let spinner = create_spinner(&messages::text("cli.loading_config", &[]));
+    let config = ConfigManager::load_from_file(&config_path).with_context(|| {
+        messages::text(
+            "cli.load_config_failed",
+            &[("path", config_path.display().to_string())],
+        )
+    })?;
     spinner.finish_and_clear();
+    messages::set_locale(config.settings.locale.as_deref());
 
-    if force {
-        print_info("Force restart enabled");
+    let (named, missing) = select_by_name(config.processes, names);
+    let processes = if let Some(tag) = group {
+        let tagged = filter_by_tag(named, Some(tag));
+        if tagged.is_empty() {
+            bail!(messages::text(
+                "cli.no_process_tagged",
+                &[("tag", tag.to_string())]
+            ));
+        }
+        ConfigManager::dependency_order(&tagged)?
+    } else if !names.is_empty() {
+        ConfigManager::dependency_order(&named)?
+    } else {
+        named
+    };
+
+    // Still restart the processes that were found
+    for name in &missing {
+        print_error(&messages::text(
+            "cli.process_not_found",
+            &[("name", name.clone())],
+        ));
     }
 
-    // Initialize process manager
-    let pm = Arc::new(Mutex::new(ProcessManager::new()));
+    if force {
+        print_info(&messages::text("cli.force_restart", &[]));
+    }
 
-    print_info(&format!(
-        "Restarting {} process(es)...",
-        config.processes.len()
+    print_info(&messages::text(
+        "cli.restarting_count",
+        &[("count", processes.len().to_string())],
     ));
 
     let mut success_count = 0;
-    let mut error_count = 0;
+    let mut error_count = missing.len();
 
-    for process_config in &config.processes {
-        // Stop process
-        let spinner = create_spinner(&format!("Stopping {}...", process_config.name));
-        let mut manager = pm.lock().await;
+    for process_config in &processes {
+        let name = process_config.name.clone();
+        let spinner = create_spinner(&messages::text("cli.restarting", &[("name", name.clone())]));
 
-        if let Err(e) = manager.stop(&process_config.name).await {
-            // Ignore "not found" errors since process might not be running
-            if !e.to_string().contains("not found") {
-                spinner.finish_and_clear();
-                print_error(&format!("Failed to stop {}: {}", process_config.name, e));
-                error_count += 1;
-                continue;
-            }
+        let command = ControlCommand::Restart {
+            name: name.clone(),
+            force,
+        };
+        let mut result = send_to_instance(command).await;
+        // Not run by the instance yet, so it starts it from its config
+        if let Ok(Err(SentinelError::ProcessNotFound { .. })) = result {
+            result = send_to_instance(ControlCommand::Start(name.clone())).await;
         }
         spinner.finish_and_clear();
 
-        // Start process
-        let spinner = create_spinner(&format!("Starting {}...", process_config.name));
-        match manager.start(process_config.clone()).await {
-            Ok(info) => {
-                spinner.finish_and_clear();
-                print_success(&format!(
-                    "Restarted {} (PID: {})",
-                    process_config.name,
-                    info.pid.unwrap_or(0)
-                ));
+        match result? {
+            Ok(()) => {
+                print_success(&messages::text("cli.restarted", &[("name", name)]));
                 success_count += 1;
             }
             Err(e) => {
-                spinner.finish_and_clear();
-                print_error(&format!("Failed to start {}: {}", process_config.name, e));
+                print_error(&messages::text(
+                    "cli.restart_failed",
+                    &[("name", name), ("error", e.localized_message())],
+                ));
                 error_count += 1;
             }
         }
@@ -68,14 +92,17 @@ pub async fn execute(force: bool) -> Result<()> {
 
     println!();
     if error_count == 0 {
-        print_success(&format!(
-            "All {} process(es) restarted successfully!",
-            success_count
+        print_success(&messages::text(
+            "cli.all_restarted",
+            &[("count", success_count.to_string())],
         ));
     } else {
-        print_error(&format!(
-            "Restarted {} process(es), {} failed",
-            success_count, error_count
+        print_error(&messages::text(
+            "cli.restart_summary",
+            &[
+                ("succeeded", success_count.to_string()),
+                ("failed", error_count.to_string()),
+            ],
         ));
         std::process::exit(1);
     }
@@ -0,0 +1,250 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use sentinel::core::{ConfigManager, LogStream, ProcessManager};
+use sentinel::models::{Config, GlobalSettings, ProcessConfig};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{create_spinner, get_default_config_path, print_error, print_info, print_success};
+
+/// How often new log lines are printed and the process is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest name generated from a command
+const GENERATED_NAME_LENGTH: usize = 32;
+
+/// Restart attempts allowed with `--restart`
+const RESTART_LIMIT: u32 = 5;
+
+/// Flags of the run command
+pub struct RunOptions {
+    pub name: Option<String>,
+    pub restart: bool,
+    pub env: Vec<(String, String)>,
+    pub cwd: Option<PathBuf>,
+    pub port: Option<u16>,
+    pub detach: bool,
+    pub command: Vec<String>,
+}
+
+/// Execute the run command
+pub async fn execute(options: RunOptions) -> Result<()> {
+    let Some((program, args)) = options.command.split_first() else {
+        bail!("No command given, e.g. sentinel run -- npm run dev");
+    };
+
+    let mut env: HashMap<String, String> = options.env.into_iter().collect();
+    if let Some(port) = options.port {
+        env.insert("PORT".to_string(), port.to_string());
+    }
+
+    let process_config = ProcessConfig {
+        name: options
+            .name
+            .clone()
+            .unwrap_or_else(|| generated_name(&options.command)),
+        command: program.clone(),
+        args: args.to_vec(),
+        cwd: options.cwd,
+        env,
+        auto_restart: Some(options.restart),
+        restart_limit: RESTART_LIMIT,
+        transient: options.detach,
+        ..Default::default()
+    };
+
+    if options.detach {
+        return save_transient(process_config, options.name.is_some());
+    }
+
+    let code = supervise(process_config).await?;
+    std::process::exit(code);
+}
+
+/// Parse a `--env KEY=VALUE` flag
+pub fn parse_env_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some(("", _)) => Err(format!("missing variable name in '{}'", value)),
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected KEY=VALUE, got '{}'", value)),
+    }
+}
+
+/// Derive a process name from a command, e.g. `npm run dev` becomes `npm-run-dev`
+fn generated_name(command: &[String]) -> String {
+    let program = command
+        .first()
+        .and_then(|program| Path::new(program).file_name())
+        .map(|program| program.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut name = String::new();
+    for c in std::iter::once(program.as_str())
+        .chain(command.iter().skip(1).map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+    {
+        let c = if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            c
+        } else {
+            '-'
+        };
+        if c == '-' && (name.is_empty() || name.ends_with('-')) {
+            continue;
+        }
+        if name.len() == GENERATED_NAME_LENGTH {
+            break;
+        }
+        name.push(c);
+    }
+
+    let name = name.trim_end_matches('-');
+    if name.is_empty() || name == "." || name == ".." {
+        "run".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Run the process here, streaming its logs, and return the exit code to exit with
+async fn supervise(process_config: ProcessConfig) -> Result<i32> {
+    let name = process_config.name.clone();
+    // A quick exit is the command finishing, not a failed start
+    let manager = ProcessManager::with_settings(GlobalSettings {
+        start_grace_ms: 0,
+        ..GlobalSettings::default()
+    });
+    let info = manager
+        .start(process_config)
+        .await
+        .with_context(|| format!("Failed to start '{}'", name))?;
+    print_info(&format!(
+        "Started '{}' (PID {}), press Ctrl+C to stop",
+        name,
+        info.pid.unwrap_or_default()
+    ));
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut ticker = tokio::time::interval(POLL_INTERVAL);
+    let mut printed = 0;
+
+    let interrupted = loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break true,
+            _ = ticker.tick() => {}
+        }
+
+        print_new_logs(&manager, &name, &mut printed).await;
+        if !manager.check_health().await.is_empty() {
+            print_info(&format!("Restarted '{}'", name));
+            printed = 0;
+        }
+        if !manager.is_running(&name).await {
+            break false;
+        }
+    };
+
+    if interrupted {
+        println!();
+        print_info(&format!("Stopping '{}'...", name));
+        manager.stop_gracefully(&name).await?;
+    }
+
+    // Output read before the exit but not yet printed
+    tokio::time::sleep(POLL_INTERVAL).await;
+    print_new_logs(&manager, &name, &mut printed).await;
+
+    let exit_code = manager.get(&name).await.and_then(|info| info.exit_code);
+    let code = match exit_code {
+        Some(code) => code,
+        // Killed by a signal, which the shell reports as 128 + SIGINT after Ctrl+C
+        None if interrupted => 130,
+        None => 1,
+    };
+    if code == 0 {
+        print_success(&format!("'{}' exited", name));
+    } else {
+        print_error(&format!("'{}' exited with code {}", name, code));
+    }
+
+    Ok(code)
+}
+
+/// Print the log lines written since the last call, prefixed by their stream
+async fn print_new_logs(manager: &ProcessManager, name: &str, printed: &mut u64) {
+    let Some(total) = manager.log_lines_total(name).await else {
+        return;
+    };
+    // The counter starts over when the process restarts
+    if total < *printed {
+        *printed = 0;
+    }
+    let new = (total - *printed) as usize;
+    if new == 0 {
+        return;
+    }
+
+    for line in manager.get_recent_logs(name, new).await.unwrap_or_default() {
+        match line.stream {
+            LogStream::Stdout => println!("{} {}", "out |".cyan(), line.line),
+            LogStream::Stderr => eprintln!("{} {}", "err |".red(), line.line),
+        }
+    }
+    *printed = total;
+}
+
+/// Save the process to the configuration, marked transient, instead of running it
+fn save_transient(mut process_config: ProcessConfig, named: bool) -> Result<()> {
+    let config_path = get_default_config_path();
+
+    let spinner = create_spinner("Loading configuration...");
+    let mut config = if config_path.exists() {
+        ConfigManager::load_from_file(&config_path)
+            .with_context(|| format!("Failed to load config from {}", config_path.display()))?
+    } else {
+        Config {
+            processes: Vec::new(),
+            settings: GlobalSettings::default(),
+            global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
+        }
+    };
+    spinner.finish_and_clear();
+
+    let taken = |name: &str| config.processes.iter().any(|p| p.name == name);
+    if taken(&process_config.name) {
+        if named {
+            bail!(
+                "Process '{}' already exists in configuration",
+                process_config.name
+            );
+        }
+        // Generated names get a numeric suffix instead
+        let base = process_config.name.clone();
+        process_config.name = (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|name| !taken(name))
+            .unwrap_or(base);
+    }
+
+    let name = process_config.name.clone();
+    config.processes.push(process_config);
+
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    ConfigManager::save_to_file(&config, &config_path)
+        .with_context(|| format!("Failed to save config to {}", config_path.display()))?;
+
+    print_success(&format!("Saved '{}' to {}", name, config_path.display()));
+    print_info("Run 'sentinel start' to start it");
+    print_info(&format!("Run 'sentinel remove {}' once done", name));
+
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use anyhow::Result;
+use sentinel::core::schema;
+
+/// Execute the schema command
+///
+/// Prints the JSON Schema of configuration files to stdout, e.g.
+/// `sentinel schema > sentinel.schema.json`
+pub fn execute() -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schema::config_schema())?
+    );
+    Ok(())
+}
@@ -1,14 +1,39 @@
-use anyhow::{Context, Result};
-use sentinel::core::{ConfigManager, ProcessManager, SystemMonitor};
-use sentinel::state::AppState;
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use chrono::{Local, Utc};
+use colored::Colorize;
+use comfy_table::{Cell, Table};
+use sentinel::core::{
+    instance, launch, logging, messages, scheduler, shutdown, startup_report, ConfigManager,
+    ControlCommand, ControlListener, EventJournal, EventRecorder, InstanceLock, ProcessManager,
+    PtyProcessManager, StartupReport, StateManager,
+};
+use sentinel::features::port_discovery::PortScanner;
+use sentinel::models::{EventActor, GlobalSettings, OnExit, ProcessConfig, RuntimeState};
+use sentinel::SentinelError;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
-use crate::{create_spinner, get_default_config_path, print_error, print_info, print_success};
+use crate::{
+    create_spinner, format_state, get_default_config_path, print_error, print_info, print_success,
+    print_warning, state_color,
+};
+
+/// How often crashed processes and schedules are checked
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long past its shutdown timeout a replaced instance gets to release the lock
+const TAKEOVER_MARGIN: Duration = Duration::from_secs(5);
 
 /// Execute the start command
-pub async fn execute(config_file: Option<PathBuf>, daemon: bool) -> Result<()> {
+pub async fn execute(
+    config_file: Option<PathBuf>,
+    daemon: bool,
+    profiles: Vec<String>,
+    force: bool,
+    dry_run: bool,
+) -> Result<()> {
     let config_path = config_file.unwrap_or_else(get_default_config_path);
 
     // Show what we're doing
@@ -23,88 +48,450 @@ pub async fn execute(config_file: Option<PathBuf>, daemon: bool) -> Result<()> {
         .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
     spinner.finish_and_clear();
 
+    logging::set_log_level(&config.settings.log_level)?;
+    messages::set_locale(config.settings.locale.as_deref());
+
     print_success(&format!(
         "Loaded configuration with {} process(es)",
         config.processes.len()
     ));
 
+    if dry_run {
+        let processes = ConfigManager::select_processes(&config, &profiles)
+            .context("Failed to select processes for the requested profiles")?;
+        return preview(&config.settings, &processes, &config.processes).await;
+    }
+
     if daemon {
         print_info("Daemon mode is not yet implemented. Starting in foreground mode.");
     }
 
-    // Initialize application state
-    let state = AppState {
-        process_manager: Arc::new(Mutex::new(ProcessManager::new())),
-        system_monitor: Arc::new(Mutex::new(SystemMonitor::new())),
-        config: Arc::new(Mutex::new(config.clone())),
-    };
+    // Only one instance may manage the processes and runtime state
+    let _instance = take_instance_lock(
+        force,
+        Duration::from_millis(config.settings.graceful_shutdown_timeout),
+    )
+    .await?;
 
-    // Start all processes
-    print_info(&format!(
-        "Starting {} process(es)...",
-        config.processes.len()
-    ));
+    // Narrow down to the requested profiles, dependencies first
+    let processes = ConfigManager::select_processes(&config, &profiles)
+        .context("Failed to select processes for the requested profiles")?;
+
+    let manager = Arc::new(ProcessManager::with_settings(config.settings.clone()));
+
+    // Start selected processes
+    print_info(&format!("Starting {} process(es)...", processes.len()));
+
+    let spinner = create_spinner("Starting processes...");
+    let started_at = Utc::now();
+    let results = manager.start_all(processes.clone()).await;
+    let report = startup_report::collect(
+        &manager,
+        &processes,
+        &results,
+        started_at,
+        Some(&PortScanner::new()),
+    )
+    .await;
+    spinner.finish_and_clear();
+
+    let recorder = EventRecorder::open(EventJournal::new(EventJournal::default_path()));
+    recorder.record(report.journal_event(config_path.display().to_string(), EventActor::User));
+    recorder.flush();
+
+    let scheduled: Vec<_> = processes
+        .iter()
+        .filter(|p| p.schedule.is_some())
+        .cloned()
+        .collect();
+
+    print_startup_report(&report);
+    if report.totals.failed > 0 {
+        std::process::exit(1);
+    }
+
+    for process in &scheduled {
+        let schedule = process.schedule.as_deref().unwrap_or_default();
+        let next = scheduler::upcoming(schedule, &Local::now(), 1)?;
+        match next.first() {
+            Some(time) => print_info(&format!(
+                "Scheduled {} ({}), next run at {}",
+                process.name,
+                schedule,
+                time.format("%Y-%m-%d %H:%M")
+            )),
+            None => print_info(&format!("Scheduled {} ({})", process.name, schedule)),
+        }
+    }
+
+    if !daemon {
+        print_info("Press Ctrl+C to stop all processes");
 
-    let mut success_count = 0;
-    let mut error_count = 0;
-
-    for process_config in &config.processes {
-        let spinner = create_spinner(&format!("Starting {}...", process_config.name));
-
-        let mut pm = state.process_manager.lock().await;
-        match pm.start(process_config.clone()).await {
-            Ok(info) => {
-                spinner.finish_and_clear();
-                print_success(&format!(
-                    "Started {} (PID: {})",
-                    process_config.name,
-                    info.pid.unwrap_or(0)
-                ));
-                success_count += 1;
+        // Another instance started with --force asks this one to shut down,
+        // `sentinel add --start` to start the process it added, and
+        // `sentinel stop` and `sentinel restart` to stop or restart processes
+        let (control_tx, mut control_rx) = tokio::sync::mpsc::channel(8);
+        match ControlListener::bind(&instance::default_socket_path()) {
+            Ok(mut control) => {
+                tokio::spawn(async move {
+                    while let Some(request) = control.next().await {
+                        let _ = control_tx.send(request).await;
+                    }
+                });
             }
-            Err(e) => {
-                spinner.finish_and_clear();
-                print_error(&format!("Failed to start {}: {}", process_config.name, e));
-                error_count += 1;
+            Err(e) => print_warning(&format!("Control socket unavailable: {}", e)),
+        }
+
+        // Supervise until Ctrl+C, SIGTERM or a shutdown request
+        let signal = shutdown_signal();
+        tokio::pin!(signal);
+        let mut ticker = tokio::time::interval(SUPERVISE_INTERVAL);
+        loop {
+            tokio::select! {
+                result = &mut signal => {
+                    result?;
+                    break;
+                }
+                Some(request) = control_rx.recv() => match &request.command {
+                    ControlCommand::Shutdown => {
+                        println!();
+                        print_info("Shutdown requested by another instance");
+                        break;
+                    }
+                    ControlCommand::Start(name) => {
+                        let result = start_added(&manager, &config_path, name).await;
+                        request.reply(result);
+                    }
+                    // Stops can take a while, so supervision doesn't wait
+                    ControlCommand::Stop { .. } | ControlCommand::Restart { .. } => {
+                        let manager = manager.clone();
+                        tokio::spawn(async move {
+                            let result = control_process(&manager, &request.command).await;
+                            request.reply(result);
+                        });
+                    }
+                    ControlCommand::Focus => {}
+                },
+                _ = ticker.tick() => {}
+            }
+
+            manager.check_health().await;
+            for (name, result) in manager.check_schedules(&scheduled).await {
+                match result {
+                    Ok(info) => print_success(&format!(
+                        "Started scheduled {} (PID: {})",
+                        name,
+                        info.pid.unwrap_or(0)
+                    )),
+                    Err(e) => print_error(&format!("Failed to start {}: {}", name, e)),
+                }
             }
         }
+
+        println!();
+        print_info("Shutting down...");
+
+        let settings = manager.settings();
+        let mut runtime_state = StateManager::load().unwrap_or_else(|e| {
+            print_error(&format!("Failed to load runtime state: {}", e));
+            RuntimeState::new()
+        });
+        // The CLI never spawns PTY processes
+        let pty = Mutex::new(PtyProcessManager::new());
+        let report = shutdown::shutdown(
+            &manager,
+            &pty,
+            &mut runtime_state,
+            settings.on_exit,
+            Duration::from_millis(settings.graceful_shutdown_timeout),
+            |progress| {
+                if let Some(name) = &progress.process {
+                    print_info(&format!(
+                        "[{}/{}] {}",
+                        progress.completed, progress.total, name
+                    ));
+                }
+            },
+        )
+        .await;
+        if let Err(e) = StateManager::save(&runtime_state) {
+            print_error(&format!("Failed to save runtime state: {}", e));
+        }
+
+        for name in &report.killed {
+            print_error(&format!("Killed {} after the shutdown timeout", name));
+        }
+        match settings.on_exit {
+            OnExit::StopAll => print_success("All processes stopped"),
+            OnExit::DetachAndPersist => print_success(&format!(
+                "Left {} process(es) running",
+                report.detached.len()
+            )),
+        }
+    }
+
+    Ok(())
+}
+
+/// Print how far each process got, the critical path, and the output of
+/// the ones that failed
+fn print_startup_report(report: &StartupReport) {
+    let mut table = Table::new();
+    table.set_header(vec![
+        Cell::new("NAME").fg(comfy_table::Color::Cyan),
+        Cell::new("STATE").fg(comfy_table::Color::Cyan),
+        Cell::new("RUNNING").fg(comfy_table::Color::Cyan),
+        Cell::new("READY").fg(comfy_table::Color::Cyan),
+        Cell::new("PORTS").fg(comfy_table::Color::Cyan),
+        Cell::new("ERROR").fg(comfy_table::Color::Cyan),
+    ]);
+    for process in &report.processes {
+        let state = match process.running_after_ms {
+            None if process.failed => Cell::new("Not started").fg(comfy_table::Color::Red),
+            _ => Cell::new(format_state(&process.state)).fg(state_color(&process.state)),
+        };
+        let ports: Vec<String> = process.ports.iter().map(u16::to_string).collect();
+        let error = process.first_error_line.as_deref().unwrap_or("");
+        table.add_row(vec![
+            Cell::new(&process.name),
+            state,
+            Cell::new(format_ms(process.running_after_ms)),
+            Cell::new(format_ms(process.ready_after_ms)),
+            Cell::new(if ports.is_empty() {
+                "-".to_string()
+            } else {
+                ports.join(", ")
+            }),
+            Cell::new(error).fg(comfy_table::Color::Red),
+        ]);
     }
 
     println!();
-    if error_count == 0 {
+    println!("{table}");
+
+    for process in report.failures().filter(|p| !p.log_excerpt.is_empty()) {
+        println!();
+        println!("{}", format!("{} output:", process.name).red().bold());
+        for line in &process.log_excerpt {
+            if process.first_error_line.as_deref() == Some(line.trim()) {
+                println!("  {}", line.red());
+            } else {
+                println!("  {}", line.dimmed());
+            }
+        }
+    }
+
+    println!();
+    if !report.critical_path.is_empty() {
+        print_info(&format!(
+            "Critical path: {} ({})",
+            report.critical_path.join(" → "),
+            format_ms(Some(report.critical_path_ms))
+        ));
+    }
+    let totals = &report.totals;
+    if totals.failed == 0 {
         print_success(&format!(
-            "All {} process(es) started successfully!",
-            success_count
+            "All {} process(es) started in {}",
+            totals.processes,
+            format_ms(Some(report.duration_ms))
         ));
     } else {
         print_error(&format!(
             "Started {} process(es), {} failed",
-            success_count, error_count
+            totals.processes - totals.failed,
+            totals.failed
         ));
-        std::process::exit(1);
     }
+}
 
-    if !daemon {
-        print_info("Press Ctrl+C to stop all processes");
+/// A duration in milliseconds, in seconds from one second on
+fn format_ms(ms: Option<u64>) -> String {
+    match ms {
+        None => "-".to_string(),
+        Some(ms) if ms < 1000 => format!("{}ms", ms),
+        Some(ms) => format!("{:.1}s", ms as f64 / 1000.0),
+    }
+}
+
+/// Starts `name` as configured in `config_path`, which `sentinel add` just
+/// wrote to.
+async fn start_added(
+    manager: &ProcessManager,
+    config_path: &Path,
+    name: &str,
+) -> sentinel::Result<()> {
+    let process = ConfigManager::load_from_file(config_path).and_then(|config| {
+        config
+            .processes
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| SentinelError::ProcessNotFound {
+                name: name.to_string(),
+            })
+    });
+    let result = match process {
+        Ok(process) => manager.start(process).await,
+        Err(e) => Err(e),
+    };
+    match &result {
+        Ok(info) => print_success(&format!(
+            "Started {} (PID: {})",
+            name,
+            info.pid.unwrap_or(0)
+        )),
+        Err(e) => print_error(&format!("Failed to start {}: {}", name, e)),
+    }
+    result.map(|_| ())
+}
+
+/// Stops or restarts a process for `sentinel stop` or `sentinel restart`.
+async fn control_process(
+    manager: &ProcessManager,
+    command: &ControlCommand,
+) -> sentinel::Result<()> {
+    let result = match command {
+        ControlCommand::Stop { name, force: false } => manager.stop(name).await,
+        ControlCommand::Stop { name, force: true } => manager.kill(name).await,
+        ControlCommand::Restart { name, force } => {
+            if *force {
+                manager.kill(name).await?;
+            }
+            manager.restart(name).await.map(|_| ())
+        }
+        _ => Ok(()),
+    };
+    let name = command.process().unwrap_or_default();
+    match (&result, command) {
+        (Ok(()), ControlCommand::Restart { .. }) => print_success(&format!("Restarted {}", name)),
+        (Ok(()), _) => print_success(&format!("Stopped {}", name)),
+        (Err(SentinelError::ProcessNotFound { .. }), _) => {}
+        (Err(e), _) => print_error(&format!("Failed to handle '{}': {}", command.to_line(), e)),
+    }
+    result
+}
+
+/// Takes the instance lock, first shutting down the running instance if `force`.
+pub(crate) async fn take_instance_lock(
+    force: bool,
+    shutdown_timeout: Duration,
+) -> Result<InstanceLock> {
+    let path = InstanceLock::default_path();
+    match InstanceLock::acquire(&path) {
+        Err(e @ SentinelError::AlreadyRunning { .. }) if force => {
+            print_info(&format!("{}, shutting it down...", e));
+            instance::send(&instance::default_socket_path(), ControlCommand::Shutdown)
+                .await
+                .context("Failed to ask the running instance to shut down")?;
+
+            // It stops its processes before releasing the lock
+            let spinner = create_spinner("Waiting for the running instance to exit...");
+            let deadline = tokio::time::Instant::now() + shutdown_timeout + TAKEOVER_MARGIN;
+            let result = loop {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                match InstanceLock::acquire(&path) {
+                    Err(SentinelError::AlreadyRunning { .. })
+                        if tokio::time::Instant::now() < deadline => {}
+                    result => break result,
+                }
+            };
+            spinner.finish_and_clear();
+            result.context("Failed to take over from the running instance")
+        }
+        Err(e @ SentinelError::AlreadyRunning { .. }) => {
+            bail!("{}; use --force to replace it", e)
+        }
+        result => result.context("Failed to take the instance lock"),
+    }
+}
+
+/// Waits for Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm =
+            signal(SignalKind::terminate()).context("Failed to listen for SIGTERM")?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.context("Failed to listen for Ctrl+C"),
+            _ = sigterm.recv() => Ok(()),
+        }
+    }
 
-        // Wait for Ctrl+C
+    #[cfg(not(unix))]
+    {
         tokio::signal::ctrl_c()
             .await
-            .context("Failed to listen for Ctrl+C")?;
+            .context("Failed to listen for Ctrl+C")
+    }
+}
 
-        println!();
-        print_info("Shutting down...");
+/// Show what starting `processes` would run, without starting anything
+///
+/// Exits with status 1 if any of them would fail to start.
+async fn preview(
+    settings: &GlobalSettings,
+    processes: &[ProcessConfig],
+    all: &[ProcessConfig],
+) -> Result<()> {
+    let mut failing = 0;
+    for config in processes {
+        let preview = launch::preview(config, settings, all).await;
 
-        // Stop all processes
-        let mut pm = state.process_manager.lock().await;
-        for process_config in &config.processes {
-            if let Err(e) = pm.stop(&process_config.name).await {
-                print_error(&format!("Failed to stop {}: {}", process_config.name, e));
-            }
+        println!();
+        println!("{}", preview.name.bold());
+        let exe = preview
+            .exe_path
+            .as_ref()
+            .map_or_else(|| "not found".to_string(), |exe| exe.display().to_string());
+        println!("  {:<8} {}", "Binary", exe);
+        println!("  {:<8} {}", "Command", preview.argv.join(" "));
+        let cwd = preview
+            .cwd
+            .as_ref()
+            .map_or_else(|| "-".to_string(), |cwd| cwd.display().to_string());
+        println!("  {:<8} {}", "Cwd", cwd);
+        if !preview.ports.is_empty() {
+            let ports: Vec<String> = preview
+                .ports
+                .iter()
+                .map(|port| port.port.to_string())
+                .collect();
+            println!("  {:<8} {}", "Ports", ports.join(", "));
+        }
+        println!("  {:<8} {} variable(s)", "Env", preview.env.len());
+        for (key, value) in preview
+            .env
+            .iter()
+            .filter(|(key, _)| config.env.contains_key(*key))
+        {
+            println!("    {}={}", key, value);
         }
 
-        print_success("All processes stopped");
+        for warning in &preview.warnings {
+            print_warning(warning);
+        }
+        for error in &preview.errors {
+            print_error(error);
+        }
+        if !preview.is_startable() {
+            failing += 1;
+        }
     }
 
+    println!();
+    if failing > 0 {
+        print_error(&format!(
+            "{} of {} process(es) would fail to start",
+            failing,
+            processes.len()
+        ));
+        std::process::exit(1);
+    }
+    print_success(&format!(
+        "{} process(es) would start; nothing was run",
+        processes.len()
+    ));
     Ok(())
 }
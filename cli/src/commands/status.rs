@@ -1,57 +1,96 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local};
 use comfy_table::{Cell, Table};
-use sentinel::core::{ConfigManager, ProcessManager, SystemMonitor};
-use sentinel::models::ProcessState;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use sentinel::core::{
+    ports, ConfigManager, EventJournal, ProcessManager, SystemMonitor, MIN_CPU_SAMPLE_INTERVAL,
+};
+use sentinel::models::{
+    EventFilter, JournalEvent, ProcessConfig, ProcessHistory, ProcessInfo, ProcessState,
+};
 
 use crate::{
-    create_spinner, format_state, get_default_config_path, print_error, print_info, state_color,
+    create_spinner, filter_by_tag, format_state, format_tags, get_default_config_path, print_error,
+    print_info, select_by_name, state_color,
 };
 
+/// Events shown for each process in the detail view
+const RECENT_EVENTS: usize = 5;
+
 /// Execute the status command
-pub async fn execute(verbose: bool, format: &str) -> Result<()> {
+pub async fn execute(
+    names: &[String],
+    verbose: bool,
+    format: &str,
+    tag: Option<&str>,
+) -> Result<()> {
     let config_path = get_default_config_path();
 
     // Load configuration
     let spinner = create_spinner("Loading status...");
     let config = ConfigManager::load_from_file(&config_path)
         .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+    let (processes, missing) = select_by_name(filter_by_tag(config.processes, tag), names);
+
+    if !names.is_empty() {
+        let manager = ProcessManager::with_settings(config.settings.clone());
+        manager.update_resource_usage().await;
+        spinner.finish_and_clear();
+
+        show_details(&manager, &processes, format).await?;
+        for name in &missing {
+            print_error(&format!("Process '{}' not found", name));
+        }
+        if !missing.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Initialize managers
-    let pm = Arc::new(Mutex::new(ProcessManager::new()));
+    let manager = ProcessManager::with_settings(config.settings.clone());
     let mut sm = SystemMonitor::new();
+    if verbose {
+        // CPU usage is measured between two samples
+        tokio::time::sleep(MIN_CPU_SAMPLE_INTERVAL).await;
+    }
     sm.refresh();
 
-    let manager = pm.lock().await;
+    manager.update_resource_usage().await;
     spinner.finish_and_clear();
 
     match format {
         "json" => {
             // JSON output for scripting
-            let mut processes = Vec::new();
-            for process_config in &config.processes {
-                if let Some(info) = manager.get_process(&process_config.name) {
-                    processes.push(serde_json::json!({
+            let mut entries = Vec::new();
+            for process_config in &processes {
+                if let Some(info) = manager.get(&process_config.name).await {
+                    entries.push(serde_json::json!({
                         "name": info.name,
                         "state": info.state,
                         "pid": info.pid,
                         "started_at": info.started_at,
                         "command": process_config.command,
+                        "argv": info.argv,
+                        "exe_path": info.exe_path,
+                        "cwd": info.resolved_cwd,
+                        "pgid": info.pgid,
+                        "tags": process_config.tags,
+                        "last_restart_reason": info.last_restart_reason,
+                        "last_restart_at": info.last_restart_at,
                     }));
                 }
             }
 
             let output = serde_json::json!({
-                "processes": processes,
-                "total": config.processes.len(),
+                "processes": entries,
+                "total": entries.len(),
+                "load_average": sm.load_average(),
             });
 
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
 
-        "table" | _ => {
+        _ => {
             // Pretty table output
             let mut table = Table::new();
 
@@ -65,6 +104,8 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
                     Cell::new("MEMORY").fg(comfy_table::Color::Cyan),
                     Cell::new("UPTIME").fg(comfy_table::Color::Cyan),
                     Cell::new("COMMAND").fg(comfy_table::Color::Cyan),
+                    Cell::new("TAGS").fg(comfy_table::Color::Cyan),
+                    Cell::new("LAST RESTART").fg(comfy_table::Color::Cyan),
                 ]);
             } else {
                 table.set_header(vec![
@@ -72,17 +113,18 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
                     Cell::new("STATE").fg(comfy_table::Color::Cyan),
                     Cell::new("PID").fg(comfy_table::Color::Cyan),
                     Cell::new("UPTIME").fg(comfy_table::Color::Cyan),
+                    Cell::new("TAGS").fg(comfy_table::Color::Cyan),
                 ]);
             }
 
             // Add rows
-            for process_config in &config.processes {
-                let info = manager.get_process(&process_config.name);
+            for process_config in &processes {
+                let info = manager.get(&process_config.name).await;
 
                 if let Some(info) = info {
                     let uptime = info
                         .started_at
-                        .map(|started| format_uptime(&started))
+                        .map(|started| format_uptime(&started.into()))
                         .unwrap_or_else(|| "-".to_string());
 
                     let pid_str = info
@@ -91,19 +133,44 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
                         .unwrap_or_else(|| "-".to_string());
 
                     if verbose {
-                        let (cpu, mem) = info
-                            .pid
-                            .and_then(|pid| sm.get_process_stats(pid))
-                            .unwrap_or((0.0, 0));
+                        let usage = info.pid.and_then(|pid| sm.get_process_metrics(pid));
+                        let mem = usage.as_ref().map_or(0, |usage| usage.memory_bytes);
+
+                        // Per core, then as a share of the whole machine
+                        let cpu_str = match usage.as_ref().and_then(|usage| {
+                            usage
+                                .cpu_usage_percent_of_core
+                                .zip(usage.cpu_usage_percent_of_total)
+                        }) {
+                            Some((of_core, of_total)) => {
+                                format!("{:.1} ({:.1} of all cores)", of_core, of_total)
+                            }
+                            None => "-".to_string(),
+                        };
+                        // Include descendants when aggregateChildUsage is enabled
+                        let cpu_str = match info.cpu_usage_tree {
+                            Some(tree) => format!("{} ({:.1} with children)", cpu_str, tree),
+                            None => cpu_str,
+                        };
+                        let mem_str = match info.memory_usage_tree {
+                            Some(tree) => format!(
+                                "{} ({} with children)",
+                                format_memory(mem),
+                                format_memory(tree)
+                            ),
+                            None => format_memory(mem),
+                        };
 
                         table.add_row(vec![
                             Cell::new(&info.name),
                             Cell::new(format_state(&info.state)).fg(state_color(&info.state)),
                             Cell::new(&pid_str),
-                            Cell::new(format!("{:.1}", cpu)),
-                            Cell::new(format_memory(mem)),
+                            Cell::new(cpu_str),
+                            Cell::new(mem_str),
                             Cell::new(&uptime),
-                            Cell::new(&process_config.command),
+                            Cell::new(format_command_line(&info, &process_config.command)),
+                            Cell::new(format_tags(&process_config.tags)),
+                            Cell::new(format_last_restart(&info)),
                         ]);
                     } else {
                         table.add_row(vec![
@@ -111,6 +178,7 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
                             Cell::new(format_state(&info.state)).fg(state_color(&info.state)),
                             Cell::new(&pid_str),
                             Cell::new(&uptime),
+                            Cell::new(format_tags(&process_config.tags)),
                         ]);
                     }
                 } else {
@@ -125,6 +193,8 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
                             Cell::new("-"),
                             Cell::new("-"),
                             Cell::new(&process_config.command),
+                            Cell::new(format_tags(&process_config.tags)),
+                            Cell::new("-"),
                         ]);
                     } else {
                         table.add_row(vec![
@@ -133,33 +203,44 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
                                 .fg(state_color(&ProcessState::Stopped)),
                             Cell::new("-"),
                             Cell::new("-"),
+                            Cell::new(format_tags(&process_config.tags)),
                         ]);
                     }
                 }
             }
 
+            if let Some(load) = sm.load_average() {
+                print_info(&format!(
+                    "Load average: {:.2} {:.2} {:.2}",
+                    load.one, load.five, load.fifteen
+                ));
+                println!();
+            }
+
             println!("{table}");
             println!();
 
             // Summary
             let running = manager
-                .list_processes()
+                .list()
+                .await
                 .iter()
                 .filter(|p| matches!(p.state, ProcessState::Running))
+                .filter(|p| processes.iter().any(|c| c.name == p.name))
                 .count();
-            let total = config.processes.len();
+            let total = processes.len();
 
             print_info(&format!("{} of {} processes running", running, total));
 
             if verbose {
                 let sys_stats = sm.get_stats();
                 println!();
-                print_info(&format!("System CPU: {:.1}%", sys_stats.cpu_usage));
+                print_info(&format!("System CPU: {:.1}%", sys_stats.cpu.overall));
                 print_info(&format!(
                     "System Memory: {} / {} ({:.1}%)",
-                    format_memory(sys_stats.memory_used),
-                    format_memory(sys_stats.memory_total),
-                    (sys_stats.memory_used as f64 / sys_stats.memory_total as f64) * 100.0
+                    format_memory(sys_stats.memory.used),
+                    format_memory(sys_stats.memory.total),
+                    (sys_stats.memory.used as f64 / sys_stats.memory.total as f64) * 100.0
                 ));
             }
         }
@@ -168,8 +249,240 @@ pub async fn execute(verbose: bool, format: &str) -> Result<()> {
     Ok(())
 }
 
+/// Show one process per section with its health, ports, resource history
+/// and recent events
+async fn show_details(
+    manager: &ProcessManager,
+    processes: &[ProcessConfig],
+    format: &str,
+) -> Result<()> {
+    let infos = manager.list_with_history().await;
+    let journal = EventJournal::new(EventJournal::default_path());
+
+    let mut details = Vec::new();
+    for process_config in processes {
+        let info = infos
+            .iter()
+            .find(|info| info.name == process_config.name)
+            .cloned()
+            .unwrap_or_else(|| {
+                ProcessInfo::new(process_config.name.clone(), process_config.command.clone())
+            });
+        let filter = EventFilter {
+            subject: Some(process_config.name.clone()),
+            ..Default::default()
+        };
+        // A missing or unreadable journal just means no events to show
+        let events = journal
+            .query(&filter, None, Some(RECENT_EVENTS))
+            .unwrap_or_default();
+        details.push((process_config, info, events));
+    }
+
+    if format == "json" {
+        let processes: Vec<_> = details
+            .iter()
+            .map(|(process_config, info, events)| {
+                serde_json::json!({
+                    "name": info.name,
+                    "state": info.state,
+                    "pid": info.pid,
+                    "started_at": info.started_at,
+                    "command": process_config.command,
+                    "tags": process_config.tags,
+                    "ports": ports::declared_ports(process_config),
+                    "health": info.health,
+                    "last_restart_reason": info.last_restart_reason,
+                    "last_restart_at": info.last_restart_at,
+                    "open_fds": info.open_fds,
+                    "thread_count": info.thread_count,
+                    "history": info.history.as_ref().map(HistorySummary::from),
+                    "recent_events": events,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&processes)?);
+        return Ok(());
+    }
+
+    for (process_config, info, events) in &details {
+        let mut table = Table::new();
+        table.set_header(vec![
+            Cell::new(&info.name).fg(comfy_table::Color::Cyan),
+            Cell::new(format_state(&info.state)).fg(state_color(&info.state)),
+        ]);
+
+        let pid = info
+            .pid
+            .map_or_else(|| "-".to_string(), |pid| pid.to_string());
+        let uptime = info
+            .started_at
+            .filter(|_| info.is_running())
+            .map_or_else(|| "-".to_string(), |started| format_uptime(&started.into()));
+        let declared = ports::declared_ports(process_config);
+        let ports = if declared.is_empty() {
+            "-".to_string()
+        } else {
+            declared
+                .iter()
+                .map(u16::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let health = match &info.health {
+            Some(health) if health.healthy => format!("healthy ({}ms)", health.latency_ms),
+            Some(health) => format!(
+                "unhealthy, {} failure(s) in a row: {}",
+                health.consecutive_failures,
+                health.error.as_deref().unwrap_or("-")
+            ),
+            None => "-".to_string(),
+        };
+        let usage = info
+            .history
+            .as_ref()
+            .map(HistorySummary::from)
+            .filter(|summary| summary.samples > 0)
+            .map_or_else(|| "-".to_string(), |summary| summary.to_string());
+
+        table.add_row(vec![Cell::new("PID"), Cell::new(pid)]);
+        table.add_row(vec![Cell::new("Uptime"), Cell::new(uptime)]);
+        table.add_row(vec![
+            Cell::new("Command"),
+            Cell::new(format_command_line(info, &process_config.command)),
+        ]);
+        table.add_row(vec![
+            Cell::new("Tags"),
+            Cell::new(format_tags(&process_config.tags)),
+        ]);
+        table.add_row(vec![Cell::new("Ports"), Cell::new(ports)]);
+        table.add_row(vec![Cell::new("Health"), Cell::new(health)]);
+        table.add_row(vec![
+            Cell::new("Last restart"),
+            Cell::new(format_last_restart(info)),
+        ]);
+        table.add_row(vec![Cell::new("Usage"), Cell::new(usage)]);
+        table.add_row(vec![
+            Cell::new("Files / threads"),
+            Cell::new(format_handles(info)),
+        ]);
+        table.add_row(vec![
+            Cell::new("Recent events"),
+            Cell::new(format_events(events)),
+        ]);
+
+        println!("{table}");
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Average and peak usage over a process's resource history
+#[derive(serde::Serialize)]
+struct HistorySummary {
+    samples: usize,
+    cpu_average: Option<f32>,
+    cpu_peak: Option<f32>,
+    memory_average: Option<u64>,
+    memory_peak: Option<u64>,
+}
+
+impl From<&ProcessHistory> for HistorySummary {
+    fn from(history: &ProcessHistory) -> Self {
+        let cpu: Vec<f32> = history.cpu.iter().flatten().copied().collect();
+        let memory: Vec<u64> = history.memory.iter().flatten().copied().collect();
+        Self {
+            samples: memory.len(),
+            cpu_average: (!cpu.is_empty()).then(|| cpu.iter().sum::<f32>() / cpu.len() as f32),
+            cpu_peak: cpu.iter().copied().reduce(f32::max),
+            memory_average: (!memory.is_empty())
+                .then(|| memory.iter().sum::<u64>() / memory.len() as u64),
+            memory_peak: memory.iter().copied().max(),
+        }
+    }
+}
+
+impl std::fmt::Display for HistorySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let (Some(average), Some(peak)) = (self.cpu_average, self.cpu_peak) {
+            write!(f, "CPU {:.1}% avg, {:.1}% peak; ", average, peak)?;
+        }
+        if let (Some(average), Some(peak)) = (self.memory_average, self.memory_peak) {
+            write!(
+                f,
+                "memory {} avg, {} peak",
+                format_memory(average),
+                format_memory(peak)
+            )?;
+        }
+        write!(f, " ({} samples)", self.samples)
+    }
+}
+
+/// Format open file descriptor and thread counts, `-` for those not counted
+fn format_handles(info: &ProcessInfo) -> String {
+    let count = |count: Option<u64>| count.map_or_else(|| "-".to_string(), |n| n.to_string());
+    format!("{} / {}", count(info.open_fds), count(info.thread_count))
+}
+
+/// Format journal events one per line, oldest first
+fn format_events(events: &[JournalEvent]) -> String {
+    if events.is_empty() {
+        return "-".to_string();
+    }
+    events
+        .iter()
+        .map(|event| {
+            let event_type = serde_json::to_value(event.event_type)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default();
+            format!(
+                "{} {}",
+                DateTime::<Local>::from(event.timestamp).format("%Y-%m-%d %H:%M:%S"),
+                event_type
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format why and when a process was last restarted
+fn format_last_restart(info: &ProcessInfo) -> String {
+    match (&info.last_restart_reason, info.last_restart_at) {
+        (Some(reason), Some(at)) => format!(
+            "{} ({})",
+            reason,
+            DateTime::<Local>::from(at).format("%Y-%m-%d %H:%M:%S")
+        ),
+        (Some(reason), None) => reason.to_string(),
+        (None, _) => "-".to_string(),
+    }
+}
+
+/// Format the full command line of a started process, with its executable
+/// and working directory below it
+fn format_command_line(info: &ProcessInfo, command: &str) -> String {
+    if info.argv.is_empty() {
+        return command.to_string();
+    }
+
+    let mut lines = vec![info.argv.join(" ")];
+    if let Some(exe_path) = &info.exe_path {
+        lines.push(format!("exe: {}", exe_path));
+    }
+    if let Some(cwd) = &info.resolved_cwd {
+        lines.push(format!("cwd: {}", cwd));
+    }
+    if let Some(pgid) = info.pgid {
+        lines.push(format!("pgid: {}", pgid));
+    }
+    lines.join("\n")
+}
+
 /// Format uptime from start time
-fn format_uptime(started_at: &DateTime<Local>) -> String {
+pub(crate) fn format_uptime(started_at: &DateTime<Local>) -> String {
     let now = Local::now();
     let duration = now.signed_duration_since(*started_at);
 
@@ -190,7 +503,7 @@ fn format_uptime(started_at: &DateTime<Local>) -> String {
 }
 
 /// Format memory in human-readable format
-fn format_memory(bytes: u64) -> String {
+pub(crate) fn format_memory(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
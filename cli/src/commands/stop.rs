@@ -1,52 +1,105 @@
-use anyhow::{Context, Result};
-use sentinel::core::{ConfigManager, ProcessManager};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use anyhow::{bail, Context, Result};
+use sentinel::core::{messages, ConfigManager, ControlCommand};
+use sentinel::SentinelError;
 
-use crate::{create_spinner, get_default_config_path, print_error, print_info, print_success};
+use crate::{
+    create_spinner, filter_by_tag, get_default_config_path, print_error, print_info, print_success,
+    select_by_name, send_to_instance,
+};
 
 /// Execute the stop command
-pub async fn execute(force: bool) -> Result<()> {
+///
+/// The running `sentinel start` or app stops the processes, as they belong to it
+pub async fn execute(names: &[String], force: bool, group: Option<&str>) -> Result<()> {
     let config_path = get_default_config_path();
 
     // Load configuration
-    let spinner = create_spinner("Loading configuration...");
-    let config = ConfigManager::load_from_file(&config_path)
-        .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+    let spinner = create_spinner(&messages::text("cli.loading_config", &[]));
+    let config = ConfigManager::load_from_file(&config_path).with_context(|| {
+        messages::text(
+            "cli.load_config_failed",
+            &[("path", config_path.display().to_string())],
+        )
+    })?;
     spinner.finish_and_clear();
+    messages::set_locale(config.settings.locale.as_deref());
 
-    if force {
-        print_info("Force stop enabled (SIGKILL)");
+    let (named, missing) = select_by_name(config.processes, names);
+    let processes = if let Some(tag) = group {
+        let tagged = filter_by_tag(named, Some(tag));
+        if tagged.is_empty() {
+            bail!(messages::text(
+                "cli.no_process_tagged",
+                &[("tag", tag.to_string())]
+            ));
+        }
+        let mut processes = ConfigManager::dependency_order(&tagged)?;
+        // Dependents stop before what they depend on
+        processes.reverse();
+        processes
+    } else if !names.is_empty() {
+        let mut processes = ConfigManager::dependency_order(&named)?;
+        processes.reverse();
+        processes
+    } else {
+        named
+    };
+
+    // Still stop the processes that were found
+    for name in &missing {
+        print_error(&messages::text(
+            "cli.process_not_found",
+            &[("name", name.clone())],
+        ));
     }
 
-    // Initialize process manager
-    let pm = Arc::new(Mutex::new(ProcessManager::new()));
+    if force {
+        print_info(&messages::text("cli.force_stop", &[]));
+    }
 
-    print_info(&format!(
-        "Stopping {} process(es)...",
-        config.processes.len()
+    print_info(&messages::text(
+        "cli.stopping_count",
+        &[("count", processes.len().to_string())],
     ));
 
     let mut success_count = 0;
-    let mut error_count = 0;
+    let mut error_count = missing.len();
 
-    for process_config in &config.processes {
-        let spinner = create_spinner(&format!("Stopping {}...", process_config.name));
+    for process_config in &processes {
+        let spinner = create_spinner(&messages::text(
+            "cli.stopping",
+            &[("name", process_config.name.clone())],
+        ));
 
-        let mut manager = pm.lock().await;
-        match manager.stop(&process_config.name).await {
-            Ok(_) => {
-                spinner.finish_and_clear();
-                print_success(&format!("Stopped {}", process_config.name));
+        let command = ControlCommand::Stop {
+            name: process_config.name.clone(),
+            force,
+        };
+        let result = send_to_instance(command).await;
+        spinner.finish_and_clear();
+        match result? {
+            Ok(()) => {
+                print_success(&messages::text(
+                    "cli.stopped",
+                    &[("name", process_config.name.clone())],
+                ));
                 success_count += 1;
             }
             Err(e) => {
-                spinner.finish_and_clear();
-                // Don't fail if process wasn't running
-                if e.to_string().contains("not found") {
-                    print_info(&format!("{} was not running", process_config.name));
+                // Don't fail if the instance doesn't run the process
+                if let SentinelError::ProcessNotFound { .. } = e {
+                    print_info(&messages::text(
+                        "cli.not_running",
+                        &[("name", process_config.name.clone())],
+                    ));
                 } else {
-                    print_error(&format!("Failed to stop {}: {}", process_config.name, e));
+                    print_error(&messages::text(
+                        "cli.stop_failed",
+                        &[
+                            ("name", process_config.name.clone()),
+                            ("error", e.localized_message()),
+                        ],
+                    ));
                     error_count += 1;
                 }
             }
@@ -55,11 +108,14 @@ pub async fn execute(force: bool) -> Result<()> {
 
     println!();
     if error_count == 0 {
-        print_success("All processes stopped successfully!");
+        print_success(&messages::text("cli.all_stopped", &[]));
     } else {
-        print_error(&format!(
-            "Stopped {} process(es), {} failed",
-            success_count, error_count
+        print_error(&messages::text(
+            "cli.stop_summary",
+            &[
+                ("succeeded", success_count.to_string()),
+                ("failed", error_count.to_string()),
+            ],
         ));
         std::process::exit(1);
     }
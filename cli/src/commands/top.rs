@@ -0,0 +1,550 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::crossterm::{cursor, execute};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Gauge, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+use sentinel::core::{logging, ConfigManager, InstanceLock, ProcessManager, SystemMonitor};
+use sentinel::models::{ProcessInfo, ProcessState};
+use std::io::{self, Stdout};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::commands::start::take_instance_lock;
+use crate::commands::status::{format_memory, format_uptime};
+use crate::{create_spinner, get_default_config_path};
+
+/// How often the dashboard refreshes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest wait for a key before checking for Ctrl+C from outside the terminal
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Log lines kept for the log view
+const LOG_LINES: usize = 500;
+
+/// Execute the top command
+pub async fn execute(
+    config_file: Option<PathBuf>,
+    profiles: Vec<String>,
+    force: bool,
+) -> Result<()> {
+    let config_path = config_file.unwrap_or_else(get_default_config_path);
+
+    let spinner = create_spinner("Starting processes...");
+    let (mut source, failures) = LocalSource::start(&config_path, &profiles, force).await?;
+    spinner.finish_and_clear();
+
+    // Log output would scribble over the dashboard
+    logging::set_log_level("off")?;
+
+    // Raw mode turns Ctrl+C into a key press, but a SIGINT can still arrive
+    // from outside the terminal
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        let interrupted = interrupted.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                interrupted.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+
+    let mut app = App::new();
+    if !failures.is_empty() {
+        app.status = Some(failures.join("; "));
+    }
+
+    let mut guard = TerminalGuard::enter()?;
+    let result = run(&mut guard.terminal, &mut app, &mut source, &interrupted).await;
+    drop(guard);
+
+    source.shutdown().await;
+    result
+}
+
+/// Where the dashboard reads processes from and sends actions to
+///
+/// `LocalSource` supervises the processes itself. A source backed by a running
+/// daemon plugs in here once the control socket exists.
+trait ProcessSource {
+    /// Current processes, with fresh resource usage
+    async fn processes(&mut self) -> Vec<ProcessInfo>;
+
+    /// Stop a process
+    async fn stop(&mut self, name: &str) -> Result<()>;
+
+    /// Restart a process
+    async fn restart(&mut self, name: &str) -> Result<()>;
+
+    /// Most recent log lines of a process, oldest first
+    async fn logs(&mut self, name: &str, lines: usize) -> Vec<String>;
+
+    /// Called before every refresh
+    async fn tick(&mut self) {}
+
+    /// Called once the dashboard has closed
+    async fn shutdown(&mut self) {}
+}
+
+/// Runs the configured processes in this process, like `sentinel start`
+struct LocalSource {
+    manager: ProcessManager,
+    _instance: InstanceLock,
+}
+
+impl LocalSource {
+    /// Load the config and start its processes, returning start failures
+    ///
+    /// Takes the instance lock first, shutting down a running instance if `force`.
+    async fn start(
+        config_path: &Path,
+        profiles: &[String],
+        force: bool,
+    ) -> Result<(Self, Vec<String>)> {
+        let config = ConfigManager::load_from_file(config_path)
+            .with_context(|| format!("Failed to load config from {}", config_path.display()))?;
+        let instance = take_instance_lock(
+            force,
+            Duration::from_millis(config.settings.graceful_shutdown_timeout),
+        )
+        .await?;
+        let processes = ConfigManager::select_processes(&config, profiles)
+            .context("Failed to select processes for the requested profiles")?;
+
+        let manager = ProcessManager::with_settings(config.settings.clone());
+        let failures = manager
+            .start_all(processes)
+            .await
+            .into_iter()
+            .filter_map(|(name, result)| {
+                result
+                    .err()
+                    .map(|e| format!("Failed to start {}: {}", name, e))
+            })
+            .collect();
+
+        Ok((
+            Self {
+                manager,
+                _instance: instance,
+            },
+            failures,
+        ))
+    }
+}
+
+impl ProcessSource for LocalSource {
+    async fn processes(&mut self) -> Vec<ProcessInfo> {
+        self.manager.update_resource_usage().await;
+        self.manager.list().await
+    }
+
+    async fn stop(&mut self, name: &str) -> Result<()> {
+        Ok(self.manager.stop(name).await?)
+    }
+
+    async fn restart(&mut self, name: &str) -> Result<()> {
+        self.manager.restart(name).await?;
+        Ok(())
+    }
+
+    async fn logs(&mut self, name: &str, lines: usize) -> Vec<String> {
+        self.manager
+            .get_recent_logs(name, lines)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|line| line.line)
+            .collect()
+    }
+
+    async fn tick(&mut self) {
+        // Restart crashed processes, as the app's supervisor loop does
+        self.manager.check_health().await;
+    }
+
+    async fn shutdown(&mut self) {
+        let _ = self.manager.stop_all().await;
+    }
+}
+
+/// Puts the terminal in dashboard mode and restores it when dropped or on panic
+struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        // Restore the terminal before the panic message is printed
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous_hook(info);
+        }));
+
+        terminal::enable_raw_mode().context("Failed to enable raw mode")?;
+        let mut stdout = io::stdout();
+        if let Err(e) = execute!(stdout, EnterAlternateScreen, cursor::Hide) {
+            restore_terminal();
+            return Err(e).context("Failed to enter the alternate screen");
+        }
+
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        Ok(Self { terminal })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leave raw mode and the alternate screen, ignoring errors
+fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+}
+
+/// Which screen is shown
+enum View {
+    Processes,
+    Logs(String),
+}
+
+/// What a key press asks for
+enum Action {
+    None,
+    Quit,
+    Stop(String),
+    Restart(String),
+    ShowLogs(String),
+}
+
+/// Dashboard state
+struct App {
+    processes: Vec<ProcessInfo>,
+    table: TableState,
+    view: View,
+    logs: Vec<String>,
+    cpu_usage: f32,
+    memory: (u64, u64),
+    status: Option<String>,
+}
+
+impl App {
+    fn new() -> Self {
+        Self {
+            processes: Vec::new(),
+            table: TableState::default().with_selected(Some(0)),
+            view: View::Processes,
+            logs: Vec::new(),
+            cpu_usage: 0.0,
+            memory: (0, 0),
+            status: None,
+        }
+    }
+
+    fn selected_name(&self) -> Option<String> {
+        self.table
+            .selected()
+            .and_then(|index| self.processes.get(index))
+            .map(|info| info.name.clone())
+    }
+
+    fn select_next(&mut self, offset: isize) {
+        if self.processes.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let last = self.processes.len() as isize - 1;
+        self.table
+            .select(Some((current + offset).clamp(0, last) as usize));
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind == KeyEventKind::Release {
+            return Action::None;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::Quit;
+        }
+
+        if let View::Logs(_) = self.view {
+            if matches!(
+                key.code,
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('q') | KeyCode::Char('l')
+            ) {
+                self.view = View::Processes;
+            }
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.select_next(-1);
+                Action::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.select_next(1);
+                Action::None
+            }
+            KeyCode::Char('s') => self.selected_name().map_or(Action::None, Action::Stop),
+            KeyCode::Char('r') => self.selected_name().map_or(Action::None, Action::Restart),
+            KeyCode::Enter | KeyCode::Char('l') => {
+                self.selected_name().map_or(Action::None, Action::ShowLogs)
+            }
+            _ => Action::None,
+        }
+    }
+}
+
+/// Refresh and redraw until the user quits
+async fn run<S: ProcessSource>(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+    source: &mut S,
+    interrupted: &AtomicBool,
+) -> Result<()> {
+    let mut monitor = SystemMonitor::new();
+    let mut next_refresh = Instant::now();
+
+    loop {
+        if Instant::now() >= next_refresh {
+            source.tick().await;
+            app.processes = source.processes().await;
+            app.select_next(0);
+            if let View::Logs(name) = &app.view {
+                app.logs = source.logs(name, LOG_LINES).await;
+            }
+
+            monitor.refresh_cpu();
+            monitor.refresh_memory();
+            app.cpu_usage = monitor.cpu_usage();
+            app.memory = monitor.memory_usage();
+
+            next_refresh = Instant::now() + REFRESH_INTERVAL;
+        }
+
+        terminal.draw(|frame| draw(frame, app))?;
+
+        if interrupted.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let wait = next_refresh
+            .saturating_duration_since(Instant::now())
+            .min(INPUT_POLL_INTERVAL);
+        if !event::poll(wait)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        match app.handle_key(key) {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::Stop(name) => {
+                app.status = Some(match source.stop(&name).await {
+                    Ok(()) => format!("Stopped {}", name),
+                    Err(e) => format!("Failed to stop {}: {}", name, e),
+                });
+                next_refresh = Instant::now();
+            }
+            Action::Restart(name) => {
+                app.status = Some(match source.restart(&name).await {
+                    Ok(()) => format!("Restarted {}", name),
+                    Err(e) => format!("Failed to restart {}: {}", name, e),
+                });
+                next_refresh = Instant::now();
+            }
+            Action::ShowLogs(name) => {
+                app.logs = source.logs(&name, LOG_LINES).await;
+                app.view = View::Logs(name);
+            }
+        }
+    }
+}
+
+/// Draw the whole dashboard
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [gauges, main, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [cpu_area, memory_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(gauges);
+
+    let cpu_ratio = (app.cpu_usage as f64 / 100.0).clamp(0.0, 1.0);
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered().title(" CPU "))
+            .gauge_style(Style::new().fg(usage_color(cpu_ratio)))
+            .ratio(cpu_ratio)
+            .label(format!("{:.1}%", app.cpu_usage)),
+        cpu_area,
+    );
+
+    let (used, total) = app.memory;
+    let memory_ratio = if total > 0 {
+        (used as f64 / total as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered().title(" Memory "))
+            .gauge_style(Style::new().fg(usage_color(memory_ratio)))
+            .ratio(memory_ratio)
+            .label(format!(
+                "{} / {}",
+                format_memory(used),
+                format_memory(total)
+            )),
+        memory_area,
+    );
+
+    let help = match &app.view {
+        View::Logs(name) => {
+            draw_logs(frame, main, name, &app.logs);
+            " esc/q back "
+        }
+        View::Processes => {
+            draw_processes(frame, main, app);
+            " ↑/↓ select  s stop  r restart  l logs  q quit "
+        }
+    };
+
+    let mut spans = vec![Span::styled(help, Style::new().fg(Color::DarkGray))];
+    if let Some(status) = &app.status {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            status.as_str(),
+            Style::new().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), footer);
+}
+
+/// Draw the process table
+fn draw_processes(frame: &mut Frame, area: Rect, app: &mut App) {
+    let header = Row::new([
+        "NAME", "STATE", "PID", "CPU %", "MEMORY", "RESTARTS", "UPTIME",
+    ])
+    .style(Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let rows = app.processes.iter().map(|info| {
+        let running = info.is_running();
+        let uptime = info
+            .started_at
+            .filter(|_| running)
+            .map(|started| format_uptime(&DateTime::<Local>::from(started)))
+            .unwrap_or_else(|| "-".to_string());
+        // Include descendants when aggregateChildUsage is enabled
+        let cpu = info.cpu_usage_tree.unwrap_or(info.cpu_usage);
+        let memory = info.memory_usage_tree.unwrap_or(info.memory_usage);
+
+        Row::new(vec![
+            Cell::from(info.name.clone()),
+            Cell::from(state_label(&info.state)).style(Style::new().fg(state_color(&info.state))),
+            Cell::from(
+                info.pid
+                    .map_or_else(|| "-".to_string(), |pid| pid.to_string()),
+            ),
+            Cell::from(if running {
+                format!("{:.1}", cpu)
+            } else {
+                "-".to_string()
+            }),
+            Cell::from(if running {
+                format_memory(memory)
+            } else {
+                "-".to_string()
+            }),
+            Cell::from(info.restart_count.to_string()),
+            Cell::from(uptime),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(11),
+            Constraint::Length(9),
+            Constraint::Length(9),
+        ],
+    )
+    .header(header)
+    .block(Block::bordered().title(format!(" Processes ({}) ", app.processes.len())))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(table, area, &mut app.table);
+}
+
+/// Draw the log view, scrolled to the newest line
+fn draw_logs(frame: &mut Frame, area: Rect, name: &str, logs: &[String]) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = logs.len().saturating_sub(visible) as u16;
+    let lines: Vec<Line> = logs.iter().map(|line| Line::raw(line.as_str())).collect();
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::bordered().title(format!(" Logs: {} ", name)))
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+/// Short label for a process state
+fn state_label(state: &ProcessState) -> String {
+    match state {
+        ProcessState::Running => "Running".to_string(),
+        ProcessState::Paused => "Paused".to_string(),
+        ProcessState::Stopped => "Stopped".to_string(),
+        ProcessState::Starting => "Starting".to_string(),
+        ProcessState::Stopping => "Stopping".to_string(),
+        ProcessState::Crashed { exit_code } => format!("Crashed ({})", exit_code),
+        ProcessState::Completed { exit_code } => format!("Completed ({})", exit_code),
+        ProcessState::Failed { .. } => "Failed".to_string(),
+    }
+}
+
+/// Color for a process state
+fn state_color(state: &ProcessState) -> Color {
+    match state {
+        ProcessState::Running => Color::Green,
+        ProcessState::Paused => Color::Magenta,
+        ProcessState::Stopped => Color::DarkGray,
+        ProcessState::Starting => Color::Cyan,
+        ProcessState::Stopping => Color::Yellow,
+        ProcessState::Crashed { .. } | ProcessState::Failed { .. } => Color::Red,
+        ProcessState::Completed { .. } => Color::Green,
+    }
+}
+
+/// Gauge color for a usage ratio
+fn usage_color(ratio: f64) -> Color {
+    if ratio >= 0.9 {
+        Color::Red
+    } else if ratio >= 0.7 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
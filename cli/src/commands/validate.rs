@@ -0,0 +1,41 @@
+use anyhow::Result;
+use sentinel::core::{secrets, ConfigManager};
+use std::path::Path;
+
+use crate::{print_error, print_success};
+
+/// Execute the validate command
+///
+/// With `resolved`, a valid config is printed as its processes see it
+/// (secrets masked) instead of the success message.
+pub async fn execute(file: &Path, resolved: bool) -> Result<()> {
+    let issues = ConfigManager::validate_file(file);
+
+    if issues.is_empty() && resolved {
+        let config = ConfigManager::load_from_file(file)?;
+        print!(
+            "{}",
+            ConfigManager::resolved_yaml(&secrets::mask_config(&config))?
+        );
+        return Ok(());
+    }
+    if issues.is_empty() {
+        print_success(&format!("{} is valid", file.display()));
+        return Ok(());
+    }
+
+    for issue in &issues {
+        print_error(&issue.to_string());
+        if let Some(snippet) = &issue.snippet {
+            eprintln!("{}", snippet);
+        }
+        eprintln!();
+    }
+
+    print_error(&format!(
+        "{} problem(s) found in {}",
+        issues.len(),
+        file.display()
+    ));
+    std::process::exit(1);
+}
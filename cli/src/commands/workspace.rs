@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use sentinel::core::workspace::{self, ImportMode, Workspace, WorkspacePaths};
+use std::path::{Path, PathBuf};
+
+use crate::{get_default_config_path, print_info, print_success, print_warning};
+
+/// Execute `sentinel workspace export`
+pub async fn export(path: &Path, config: Option<PathBuf>) -> Result<()> {
+    let paths = WorkspacePaths::new(config.unwrap_or_else(get_default_config_path));
+
+    // Saved terminals live in the desktop app, so there are none to export here
+    let report = workspace::export(&paths, Vec::new(), path)
+        .with_context(|| format!("Failed to export workspace to {}", path.display()))?;
+
+    for warning in &report.warnings {
+        print_warning(warning);
+    }
+    print_success(&format!(
+        "Exported {} config file(s) and {} state entries to {}",
+        report.manifest.config_files.len(),
+        report.state_entries,
+        path.display()
+    ));
+
+    Ok(())
+}
+
+/// Execute `sentinel workspace import`
+pub async fn import(
+    path: &Path,
+    config: Option<PathBuf>,
+    replace: bool,
+    rewrite_home: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let paths = WorkspacePaths::new(config.unwrap_or_else(get_default_config_path));
+    let mode = if replace {
+        ImportMode::Replace
+    } else {
+        ImportMode::Merge
+    };
+
+    let mut workspace = Workspace::read(path)
+        .with_context(|| format!("Failed to read workspace {}", path.display()))?;
+    let preview = workspace.preview(&paths, mode)?;
+
+    println!(
+        "Workspace exported by Sentinel {} on {}",
+        preview.manifest.sentinel_version,
+        preview.manifest.created_at.format("%Y-%m-%d %H:%M")
+    );
+    println!("  Processes: {}", preview.processes.join(", "));
+    if !preview.conflicts.is_empty() {
+        let verb = if replace { "replaced" } else { "overwritten" };
+        print_warning(&format!(
+            "Already configured, will be {}: {}",
+            verb,
+            preview.conflicts.join(", ")
+        ));
+    }
+    if !preview.home_paths.is_empty() {
+        println!(
+            "\n{} path(s) point into {}:",
+            preview.home_paths.len(),
+            preview
+                .manifest
+                .home_dir
+                .as_deref()
+                .unwrap_or(Path::new("?"))
+                .display()
+        );
+        for reference in &preview.home_paths {
+            let location = match reference.line {
+                Some(line) => format!("{}:{}", reference.file, line),
+                None => reference.file.clone(),
+            };
+            println!("  {} {}", location.dimmed(), reference.text);
+        }
+        if !rewrite_home {
+            print_info("Use --rewrite-home to point them into this home directory");
+        }
+    }
+
+    if dry_run {
+        print_info("Dry run; nothing was changed");
+        return Ok(());
+    }
+
+    if rewrite_home {
+        if let Some(home) = &preview.new_home_dir {
+            let rewritten = workspace.rewrite_home(home);
+            print_info(&format!(
+                "Rewrote {} path(s) to {}",
+                rewritten,
+                home.display()
+            ));
+        }
+    }
+
+    let report = workspace.apply(&paths, mode)?;
+    if let Some(backup) = &report.backup {
+        print_info(&format!("Previous config saved to {}", backup.display()));
+    }
+    print_success(&format!(
+        "Imported {} process(es) and {} state entries into {}",
+        report.processes.len(),
+        report.state_entries,
+        paths.config_path.display()
+    ));
+
+    Ok(())
+}
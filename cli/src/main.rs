@@ -1,10 +1,12 @@
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use anyhow::{bail, Result};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use colored::Colorize;
-use comfy_table::{Cell, Color, Table};
-use console::style;
+use comfy_table::Color;
 use indicatif::{ProgressBar, ProgressStyle};
-use sentinel::models::{Config, ProcessConfig, ProcessState};
+use sentinel::core::{instance, messages, ControlCommand};
+use sentinel::models::{ProcessConfig, ProcessOverrides, ProcessState};
+use sentinel::SentinelError;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -35,24 +37,68 @@ enum Commands {
         /// Start in daemon mode (background)
         #[arg(short, long)]
         daemon: bool,
+
+        /// Only start processes in these profiles (repeatable, comma-separated)
+        #[arg(short, long = "profile", value_name = "PROFILE", value_delimiter = ',')]
+        profiles: Vec<String>,
+
+        /// Shut down an already running instance first
+        #[arg(short, long)]
+        force: bool,
+
+        /// Show what would run, with secrets masked, without starting anything
+        #[arg(long, conflicts_with_all = ["daemon", "force"])]
+        dry_run: bool,
     },
 
-    /// Stop all running processes
+    /// Stop running processes, all of them unless names are given
     Stop {
+        /// Names of the processes to stop
+        #[arg(value_name = "PROCESS_NAME")]
+        names: Vec<String>,
+
         /// Force stop without graceful shutdown
         #[arg(short, long)]
         force: bool,
+
+        /// Only stop processes with this tag
+        #[arg(
+            short,
+            long,
+            visible_alias = "tag",
+            value_name = "TAG",
+            conflicts_with = "names"
+        )]
+        group: Option<String>,
     },
 
-    /// Restart all processes
+    /// Restart processes, all of them unless names are given
     Restart {
+        /// Names of the processes to restart
+        #[arg(value_name = "PROCESS_NAME")]
+        names: Vec<String>,
+
         /// Force restart without graceful shutdown
         #[arg(short, long)]
         force: bool,
+
+        /// Only restart processes with this tag
+        #[arg(
+            short,
+            long,
+            visible_alias = "tag",
+            value_name = "TAG",
+            conflicts_with = "names"
+        )]
+        group: Option<String>,
     },
 
-    /// Show status of all processes
+    /// Show status of processes, or the details of the named ones
     Status {
+        /// Names of the processes to show in detail
+        #[arg(value_name = "PROCESS_NAME")]
+        names: Vec<String>,
+
         /// Show detailed information
         #[arg(short, long)]
         verbose: bool,
@@ -60,6 +106,10 @@ enum Commands {
         /// Output format (table, json)
         #[arg(short = 'f', long, default_value = "table")]
         format: String,
+
+        /// Only show processes with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
     },
 
     /// Show logs for a process
@@ -77,25 +127,40 @@ enum Commands {
         lines: usize,
     },
 
-    /// Add a new process to the configuration
-    Add {
-        /// Name of the process
-        #[arg(value_name = "NAME")]
-        name: String,
+    /// Search the logs of all processes
+    Grep {
+        /// Text to find, or a regular expression with --regex
+        #[arg(value_name = "PATTERN")]
+        pattern: String,
 
-        /// Command to run
-        #[arg(value_name = "COMMAND")]
-        command: String,
+        /// Treat the pattern as a regular expression
+        #[arg(short = 'E', long)]
+        regex: bool,
 
-        /// Working directory
-        #[arg(short = 'd', long)]
-        directory: Option<PathBuf>,
+        /// Match case exactly
+        #[arg(short = 's', long)]
+        case_sensitive: bool,
 
-        /// Auto-restart on failure
-        #[arg(short = 'r', long)]
-        auto_restart: bool,
+        /// Lines of context to show around each match
+        #[arg(short = 'C', long, default_value = "0")]
+        context: usize,
+
+        /// Only search this stream (stdout, stderr)
+        #[arg(long)]
+        stream: Option<String>,
+
+        /// Only lines newer than this age (30s, 15m, 1h, 7d) or RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Maximum matches per process, keeping the most recent
+        #[arg(short = 'm', long, default_value = "100")]
+        max_count: usize,
     },
 
+    /// Add a new process to the configuration
+    Add(Box<AddArgs>),
+
     /// Remove a process from the configuration
     Remove {
         /// Name of the process
@@ -112,6 +177,10 @@ enum Commands {
         /// Output format (table, json)
         #[arg(short = 'f', long, default_value = "table")]
         format: String,
+
+        /// Only list processes with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
     },
 
     /// Initialize a new configuration file
@@ -128,31 +197,363 @@ enum Commands {
         #[arg(short = 'f', long)]
         force: bool,
     },
+
+    /// Run a single command under supervision, without editing the configuration
+    Run {
+        /// Process name (derived from the command if omitted)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Restart the command when it exits
+        #[arg(short, long)]
+        restart: bool,
+
+        /// Environment variable for the command (repeatable)
+        #[arg(short, long = "env", value_name = "KEY=VALUE", value_parser = commands::run::parse_env_var)]
+        env: Vec<(String, String)>,
+
+        /// Working directory
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Port the command should listen on, passed to it as PORT
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Save the command to the configuration as transient instead of running it here
+        #[arg(short, long)]
+        detach: bool,
+
+        /// Command to run and its arguments, after `--`
+        #[arg(
+            value_name = "COMMAND",
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
+
+    /// Show a live dashboard of processes and system usage
+    Top {
+        /// Path to the configuration file (YAML or JSON)
+        #[arg(value_name = "CONFIG_FILE")]
+        config_file: Option<PathBuf>,
+
+        /// Only start processes in these profiles (repeatable, comma-separated)
+        #[arg(short, long = "profile", value_name = "PROFILE", value_delimiter = ',')]
+        profiles: Vec<String>,
+
+        /// Shut down an already running instance first
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Run the configured processes and print their status
+    ///
+    /// With --watch, keeps them running and prints a record whenever one
+    /// changes state until Ctrl+C. With --format json, each record is one
+    /// line with a sequence number; heartbeats show the feed is alive.
+    Ps {
+        /// Path to the configuration file (YAML or JSON)
+        #[arg(value_name = "CONFIG_FILE")]
+        config_file: Option<PathBuf>,
+
+        /// Only start processes in these profiles (repeatable, comma-separated)
+        #[arg(short, long = "profile", value_name = "PROFILE", value_delimiter = ',')]
+        profiles: Vec<String>,
+
+        /// Keep running and print what changes
+        #[arg(short, long)]
+        watch: bool,
+
+        /// With --watch, print a record every this many seconds instead of on each change
+        #[arg(short, long, value_name = "SECONDS", requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: Option<u64>,
+
+        /// Output format (table, json)
+        #[arg(short = 'f', long, default_value = "table")]
+        format: String,
+    },
+
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Check the environment for common problems
+    Doctor {
+        /// Path to the configuration file (YAML or JSON)
+        #[arg(value_name = "CONFIG_FILE")]
+        config_file: Option<PathBuf>,
+
+        /// Output format (table, json)
+        #[arg(short = 'f', long, default_value = "table")]
+        format: String,
+    },
+
+    /// Show what Sentinel did, from the event journal
+    Events {
+        /// Only events newer than this age (30s, 15m, 1h, 7d) or RFC 3339 time
+        #[arg(short, long)]
+        since: Option<String>,
+
+        /// Only events about this process
+        #[arg(short, long)]
+        process: Option<String>,
+
+        /// Only events of these types, e.g. process_crashed (repeatable, comma-separated)
+        #[arg(short = 't', long = "type", value_name = "TYPE", value_delimiter = ',')]
+        event_types: Vec<String>,
+
+        /// Maximum number of events, keeping the most recent
+        #[arg(short = 'n', long, default_value = "100")]
+        limit: usize,
+
+        /// Output format (table, json)
+        #[arg(short = 'f', long, default_value = "table")]
+        format: String,
+    },
+
+    /// Show recorded CPU, memory, disk and network usage over time
+    Metrics {
+        /// Process name, or "system" for the whole machine
+        #[arg(value_name = "TARGET")]
+        target: String,
+
+        /// How far back to look (30m, 6h, 7d) or an RFC 3339 time
+        #[arg(short, long, default_value = "1h")]
+        last: String,
+
+        /// Metrics to show, e.g. cpu or network_rx (repeatable, comma-separated)
+        #[arg(short, long = "metric", value_name = "METRIC", value_delimiter = ',')]
+        metrics: Vec<String>,
+
+        /// Maximum number of rows the range is aggregated into
+        #[arg(short, long, default_value = "24")]
+        points: usize,
+
+        /// Output format (table, json)
+        #[arg(short = 'f', long, default_value = "table")]
+        format: String,
+    },
+
+    /// Convert a PM2 ecosystem file or Docker Compose file into a configuration
+    Import {
+        /// Format of the file to import
+        #[arg(value_enum)]
+        format: commands::import::ImportFormat,
+
+        /// File to import
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Where to write the configuration
+        #[arg(short, long, default_value = "sentinel.yaml")]
+        output: PathBuf,
+
+        /// Overwrite the output file if it exists
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Check a configuration file and report every problem found
+    Validate {
+        /// Path to the configuration file (YAML or JSON)
+        #[arg(value_name = "CONFIG_FILE")]
+        file: PathBuf,
+
+        /// Print the config with `defaults` and `extends` merged into each process
+        #[arg(long)]
+        resolved: bool,
+    },
+
+    /// Print the JSON Schema of configuration files, for editor autocompletion
+    Schema,
+
+    /// Export or import the whole workspace (config, state and terminals)
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+}
+
+/// Arguments of the add command, boxed as they outweigh the other commands'
+#[derive(Args)]
+struct AddArgs {
+    /// Name of the process
+    #[arg(value_name = "NAME", required_unless_present = "from")]
+    name: Option<String>,
+
+    /// Command to run
+    #[arg(value_name = "COMMAND", required_unless_present_any = ["from", "template"])]
+    command: Option<String>,
+
+    /// Working directory
+    #[arg(short = 'd', long)]
+    directory: Option<PathBuf>,
+
+    /// Auto-restart on failure
+    #[arg(short = 'r', long)]
+    auto_restart: bool,
+
+    /// Copy an existing process instead of giving a command
+    #[arg(long, value_name = "PROCESS", conflicts_with = "command")]
+    from: Option<String>,
+
+    /// Name of the copy made with --from
+    #[arg(
+        long = "name",
+        value_name = "NAME",
+        requires = "from",
+        conflicts_with = "name"
+    )]
+    new_name: Option<String>,
+
+    /// Environment variable, merged into the copy's with --from (repeatable)
+    #[arg(short, long = "env", value_name = "KEY=VALUE", value_parser = commands::run::parse_env_var, conflicts_with = "template")]
+    env: Vec<(String, String)>,
+
+    /// Port of the process, passed to it as PORT
+    #[arg(short, long, conflicts_with = "template")]
+    port: Option<u16>,
+
+    /// Argument for the command, appended to the copy's with --from (repeatable)
+    #[arg(
+        long = "arg",
+        value_name = "ARG",
+        allow_hyphen_values = true,
+        conflicts_with = "template"
+    )]
+    args: Vec<String>,
+
+    /// Process that must start first (repeatable)
+    #[arg(long = "depends-on", value_name = "PROCESS", conflicts_with_all = ["from", "template"])]
+    depends_on: Vec<String>,
+
+    /// Maximum number of restart attempts (0 = unlimited)
+    #[arg(long, value_name = "COUNT", default_value_t = commands::add::DEFAULT_RESTART_LIMIT, conflicts_with_all = ["from", "template"])]
+    restart_limit: u32,
+
+    /// Delay between restarts in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = commands::add::DEFAULT_RESTART_DELAY_MS, conflicts_with_all = ["from", "template"])]
+    restart_delay: u64,
+
+    /// Command whose success means the process is healthy
+    #[arg(long, value_name = "COMMAND", conflicts_with_all = ["from", "template"])]
+    health_cmd: Option<String>,
+
+    /// Interval between health checks in milliseconds
+    #[arg(long, value_name = "MS", requires = "health_cmd")]
+    health_interval: Option<u64>,
+
+    /// Timeout of a health check in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = commands::add::DEFAULT_HEALTH_TIMEOUT_MS, requires = "health_cmd")]
+    health_timeout: u64,
+
+    /// Failed health checks before the process counts as unhealthy
+    #[arg(long, value_name = "COUNT", default_value_t = commands::add::DEFAULT_HEALTH_RETRIES, requires = "health_cmd")]
+    health_retries: u32,
+
+    /// Start the process in the running Sentinel once added
+    #[arg(long, conflicts_with_all = ["from", "template"])]
+    start: bool,
+
+    /// Create the process from a built-in or saved template
+    #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["command", "from"])]
+    template: Option<String>,
+
+    /// Value of a template variable, e.g. PORT=3000 (repeatable)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = commands::run::parse_env_var, requires = "template")]
+    vars: Vec<(String, String)>,
+}
+
+#[derive(Subcommand)]
+enum WorkspaceAction {
+    /// Write the config files and runtime state to a .tar.gz archive
+    Export {
+        /// Archive to write
+        #[arg(value_name = "ARCHIVE")]
+        path: PathBuf,
+
+        /// Path to the configuration file (YAML or JSON)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+
+    /// Import an archive written by 'sentinel workspace export'
+    Import {
+        /// Archive to import
+        #[arg(value_name = "ARCHIVE")]
+        path: PathBuf,
+
+        /// Path to the configuration file (YAML or JSON)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Replace the current config and state instead of merging into them
+        #[arg(long)]
+        replace: bool,
+
+        /// Point paths in the exporting machine's home directory into this one
+        #[arg(long)]
+        rewrite_home: bool,
+
+        /// Only show what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
+    // Initialize logging; `start` switches to settings.logLevel once the config loads
+    sentinel::core::logging::init("info");
 
     let cli = Cli::parse();
+    let json_output = cli.command.json_output();
+
+    let result = run(cli.command).await;
+    if let Err(e) = &result {
+        if json_output {
+            print_json_error(e);
+            std::process::exit(1);
+        }
+    }
+    result
+}
 
-    match cli.command {
+/// Run a subcommand
+async fn run(command: Commands) -> Result<()> {
+    match command {
         Commands::Start {
             config_file,
             daemon,
-        } => commands::start::execute(config_file, daemon).await?,
+            profiles,
+            force,
+            dry_run,
+        } => commands::start::execute(config_file, daemon, profiles, force, dry_run).await?,
 
-        Commands::Stop { force } => commands::stop::execute(force).await?,
+        Commands::Stop {
+            names,
+            force,
+            group,
+        } => commands::stop::execute(&names, force, group.as_deref()).await?,
 
-        Commands::Restart { force } => commands::restart::execute(force).await?,
+        Commands::Restart {
+            names,
+            force,
+            group,
+        } => commands::restart::execute(&names, force, group.as_deref()).await?,
 
-        Commands::Status { verbose, format } => commands::status::execute(verbose, &format).await?,
+        Commands::Status {
+            names,
+            verbose,
+            format,
+            tag,
+        } => commands::status::execute(&names, verbose, &format, tag.as_deref()).await?,
 
         Commands::Logs {
             process_name,
@@ -160,27 +561,235 @@ async fn main() -> Result<()> {
             lines,
         } => commands::logs::execute(&process_name, follow, lines).await?,
 
-        Commands::Add {
-            name,
-            command,
-            directory,
-            auto_restart,
-        } => commands::add::execute(&name, &command, directory, auto_restart).await?,
+        Commands::Grep {
+            pattern,
+            regex,
+            case_sensitive,
+            context,
+            stream,
+            since,
+            max_count,
+        } => {
+            let options = commands::grep::GrepOptions {
+                regex,
+                case_sensitive,
+                context,
+                stream,
+                since,
+                max_count,
+            };
+            commands::grep::execute(&pattern, options).await?
+        }
+
+        Commands::Add(add) => {
+            let AddArgs {
+                name,
+                command,
+                directory,
+                auto_restart,
+                from,
+                new_name,
+                env,
+                port,
+                args,
+                template,
+                vars,
+                depends_on,
+                restart_limit,
+                restart_delay,
+                health_cmd,
+                health_interval,
+                health_timeout,
+                health_retries,
+                start,
+            } = *add;
+            match (from, template) {
+                (_, Some(template)) => {
+                    commands::add::execute_template(
+                        &name.unwrap_or_default(),
+                        &template,
+                        vars.into_iter().collect(),
+                        directory,
+                        auto_restart,
+                    )
+                    .await?
+                }
+                (Some(from), None) => {
+                    let overrides = ProcessOverrides {
+                        env: env.into_iter().collect(),
+                        port,
+                        args,
+                        cwd: directory,
+                        ..Default::default()
+                    };
+                    let name = new_name.or(name).unwrap_or_default();
+                    commands::add::execute_clone(&from, &name, overrides, auto_restart).await?
+                }
+                (None, None) => {
+                    commands::add::execute(commands::add::AddOptions {
+                        name: name.unwrap_or_default(),
+                        command: command.unwrap_or_default(),
+                        directory,
+                        auto_restart,
+                        args,
+                        env,
+                        port,
+                        depends_on,
+                        restart_limit,
+                        restart_delay,
+                        health_cmd,
+                        health_interval,
+                        health_timeout,
+                        health_retries,
+                        start,
+                    })
+                    .await?
+                }
+            }
+        }
 
         Commands::Remove { name, yes } => commands::remove::execute(&name, yes).await?,
 
-        Commands::List { format } => commands::list::execute(&format).await?,
+        Commands::List { format, tag } => commands::list::execute(&format, tag.as_deref()).await?,
 
         Commands::Init {
             output_file,
             template,
             force,
         } => commands::init::execute(&output_file, template.as_deref(), force).await?,
+
+        Commands::Run {
+            name,
+            restart,
+            env,
+            cwd,
+            port,
+            detach,
+            command,
+        } => {
+            commands::run::execute(commands::run::RunOptions {
+                name,
+                restart,
+                env,
+                cwd,
+                port,
+                detach,
+                command,
+            })
+            .await?
+        }
+
+        Commands::Top {
+            config_file,
+            profiles,
+            force,
+        } => commands::top::execute(config_file, profiles, force).await?,
+
+        Commands::Ps {
+            config_file,
+            profiles,
+            watch,
+            interval,
+            format,
+        } => {
+            commands::ps::execute(commands::ps::PsOptions {
+                config_file,
+                profiles,
+                watch,
+                interval,
+                format,
+            })
+            .await?
+        }
+
+        Commands::Completions { shell } => {
+            commands::completions::execute(shell, &mut Cli::command())?
+        }
+
+        Commands::Doctor {
+            config_file,
+            format,
+        } => commands::doctor::execute(config_file, &format).await?,
+
+        Commands::Events {
+            since,
+            process,
+            event_types,
+            limit,
+            format,
+        } => {
+            commands::events::execute(since.as_deref(), process, &event_types, limit, &format)
+                .await?
+        }
+
+        Commands::Metrics {
+            target,
+            last,
+            metrics,
+            points,
+            format,
+        } => commands::metrics::execute(&target, &last, &metrics, points, &format).await?,
+
+        Commands::Import {
+            format,
+            file,
+            output,
+            force,
+        } => commands::import::execute(format, &file, &output, force).await?,
+
+        Commands::Validate { file, resolved } => {
+            commands::validate::execute(&file, resolved).await?
+        }
+
+        Commands::Schema => commands::schema::execute()?,
+
+        Commands::Workspace { action } => match action {
+            WorkspaceAction::Export { path, config } => {
+                commands::workspace::export(&path, config).await?
+            }
+            WorkspaceAction::Import {
+                path,
+                config,
+                replace,
+                rewrite_home,
+                dry_run,
+            } => commands::workspace::import(&path, config, replace, rewrite_home, dry_run).await?,
+        },
     }
 
     Ok(())
 }
 
+impl Commands {
+    /// Whether the command was asked for JSON output
+    fn json_output(&self) -> bool {
+        match self {
+            Commands::Status { format, .. }
+            | Commands::List { format, .. }
+            | Commands::Doctor { format, .. }
+            | Commands::Events { format, .. }
+            | Commands::Metrics { format, .. }
+            | Commands::Ps { format, .. } => format == "json",
+            _ => false,
+        }
+    }
+}
+
+/// Print an error as `{code, message, details}`, with the same codes the app uses
+fn print_json_error(err: &anyhow::Error) {
+    let output = match err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<SentinelError>())
+    {
+        Some(error) => serde_json::to_value(error),
+        None => serde_json::to_value(SentinelError::Other(format!("{:#}", err))),
+    };
+    match output.and_then(|output| serde_json::to_string_pretty(&output)) {
+        Ok(json) => println!("{}", json),
+        Err(_) => print_error(&format!("{:#}", err)),
+    }
+}
+
 /// Create a spinner with consistent styling
 pub fn create_spinner(msg: &str) -> ProgressBar {
     let pb = ProgressBar::new_spinner();
@@ -219,10 +828,12 @@ pub fn print_info(msg: &str) {
 pub fn state_color(state: &ProcessState) -> Color {
     match state {
         ProcessState::Running => Color::Green,
+        ProcessState::Paused => Color::Magenta,
         ProcessState::Stopped => Color::Grey,
         ProcessState::Starting => Color::Cyan,
         ProcessState::Stopping => Color::Yellow,
         ProcessState::Crashed { .. } => Color::Red,
+        ProcessState::Completed { .. } => Color::Green,
         ProcessState::Failed { .. } => Color::Red,
     }
 }
@@ -231,10 +842,14 @@ pub fn state_color(state: &ProcessState) -> Color {
 pub fn format_state(state: &ProcessState) -> String {
     match state {
         ProcessState::Running => "Running".green().to_string(),
+        ProcessState::Paused => "Paused".magenta().to_string(),
         ProcessState::Stopped => "Stopped".bright_black().to_string(),
         ProcessState::Starting => "Starting".cyan().to_string(),
         ProcessState::Stopping => "Stopping".yellow().to_string(),
         ProcessState::Crashed { exit_code } => format!("Crashed ({})", exit_code).red().to_string(),
+        ProcessState::Completed { exit_code } => {
+            format!("Completed ({})", exit_code).green().to_string()
+        }
         ProcessState::Failed { reason } => format!("Failed: {}", reason).red().to_string(),
     }
 }
@@ -246,3 +861,63 @@ pub fn get_default_config_path() -> PathBuf {
         .join("sentinel")
         .join("config.yaml")
 }
+
+/// Keep only the processes with `tag`, if given
+pub fn filter_by_tag(processes: Vec<ProcessConfig>, tag: Option<&str>) -> Vec<ProcessConfig> {
+    match tag {
+        Some(tag) => processes
+            .into_iter()
+            .filter(|p| p.tags.iter().any(|t| t == tag))
+            .collect(),
+        None => processes,
+    }
+}
+
+/// Keep only the processes named in `names`, if any are given
+///
+/// Returns the kept processes and the names that match none
+pub fn select_by_name(
+    processes: Vec<ProcessConfig>,
+    names: &[String],
+) -> (Vec<ProcessConfig>, Vec<String>) {
+    if names.is_empty() {
+        return (processes, Vec::new());
+    }
+    let missing = names
+        .iter()
+        .filter(|name| !processes.iter().any(|p| &p.name == *name))
+        .cloned()
+        .collect();
+    let selected = processes
+        .into_iter()
+        .filter(|p| names.contains(&p.name))
+        .collect();
+    (selected, missing)
+}
+
+/// Sends `command` to the running `sentinel start` or app
+///
+/// Fails when no instance can be reached, otherwise returns how the command went
+pub async fn send_to_instance(command: ControlCommand) -> Result<Result<(), SentinelError>> {
+    match instance::send(&instance::default_socket_path(), command).await {
+        Err(SentinelError::FileIoError { source, .. })
+            if matches!(
+                source.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            bail!(messages::text("cli.no_instance", &[]))
+        }
+        Err(e @ SentinelError::Unsupported { .. }) => Err(e.into()),
+        result => Ok(result),
+    }
+}
+
+/// Format tags for a table cell
+pub fn format_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "-".to_string()
+    } else {
+        tags.join(", ")
+    }
+}
@@ -195,6 +195,137 @@ fn test_add_duplicate_process() {
         .stderr(predicate::str::contains("already exists"));
 }
 
+/// Test add writes args, env, port, restart and health check flags
+#[test]
+fn test_add_command_flags() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join(".config/sentinel/config.yaml");
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["add", "db", "postgres -D data"])
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args([
+            "add",
+            "api",
+            "node server.js",
+            "--arg",
+            "--inspect",
+            "--env",
+            "NODE_ENV=development",
+            "--port",
+            "47921",
+            "--depends-on",
+            "db",
+            "--restart-limit",
+            "7",
+            "--restart-delay",
+            "2500",
+            "--health-cmd",
+            "curl -f localhost:47921/health",
+            "--health-interval",
+            "10000",
+            "--health-retries",
+            "5",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added process 'api'"));
+
+    let config: serde_yaml::Value =
+        serde_yaml::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    let api = &config["processes"][1];
+    assert_eq!(api["command"], "node");
+    assert_eq!(
+        api["args"],
+        serde_yaml::from_str::<serde_yaml::Value>("[server.js, --inspect]").unwrap()
+    );
+    assert_eq!(api["env"]["NODE_ENV"], "development");
+    assert_eq!(api["env"]["PORT"], "47921");
+    assert_eq!(api["dependsOn"][0], "db");
+    assert_eq!(api["restartLimit"], 7);
+    assert_eq!(api["restartDelay"], 2500);
+    assert_eq!(api["healthCheck"]["command"], "curl");
+    assert_eq!(api["healthCheck"]["args"][1], "localhost:47921/health");
+    assert_eq!(api["healthCheck"]["intervalMs"], 10000);
+    assert_eq!(api["healthCheck"]["timeoutMs"], 5000);
+    assert_eq!(api["healthCheck"]["retries"], 5);
+}
+
+/// Test add writes nothing when the process fails validation
+#[test]
+fn test_add_command_rejects_unknown_dependency() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join(".config/sentinel/config.yaml");
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["add", "api", "node server.js", "--depends-on", "db"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("db"));
+    assert!(!config_path.exists());
+
+    // Health check settings need a health check command
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["add", "api", "node server.js", "--health-retries", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--health-cmd"));
+    assert!(!config_path.exists());
+}
+
+/// Test add --from copies a process with overrides
+#[test]
+fn test_add_from_existing_process() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join(".config/sentinel/config.yaml");
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .arg("add")
+        .arg("api")
+        .arg("node server.js")
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args([
+            "add",
+            "--from",
+            "api",
+            "--name",
+            "api-2",
+            "--env",
+            "PORT=47915",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added 'api-2' as a copy of 'api'"));
+
+    let content = fs::read_to_string(&config_path).unwrap();
+    assert!(content.contains("api-2"));
+    assert!(content.contains("47915"));
+
+    // The copy's port is taken by api-2 now
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["add", "--from", "api-2", "--name", "api-3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("also used by process 'api-2'"));
+}
+
 /// Test remove command with --yes flag
 #[test]
 fn test_remove_command_yes() {
@@ -225,11 +356,596 @@ fn test_remove_command_yes() {
     assert!(!content.contains("test-process"));
 }
 
+/// Test events command filters the journal
+#[test]
+fn test_events_command_filters() {
+    let tmp = TempDir::new().unwrap();
+    let journal_dir = tmp.path().join(".config/sentinel");
+    fs::create_dir_all(&journal_dir).unwrap();
+    fs::write(
+        journal_dir.join("events.jsonl"),
+        concat!(
+            r#"{"timestamp":"2020-01-01T00:00:00Z","event_type":"process_started","subject":"api","actor":"user"}"#,
+            "\n",
+            r#"{"timestamp":"2099-01-01T00:00:00Z","event_type":"process_crashed","subject":"api","details":{"exit_code":"1"},"actor":"supervisor"}"#,
+            "\n",
+            r#"{"timestamp":"2099-01-01T00:00:01Z","event_type":"process_started","subject":"worker","actor":"user"}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args([
+            "events",
+            "--since",
+            "1h",
+            "--process",
+            "api",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("process_crashed"))
+        .stdout(predicate::str::contains("process_started").not())
+        .stdout(predicate::str::contains("worker").not());
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["events", "--type", "process_started", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("worker"))
+        .stdout(predicate::str::contains("process_crashed").not());
+}
+
+/// Test events command rejects a malformed --since
+#[test]
+fn test_events_invalid_since() {
+    let tmp = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["events", "--since", "yesterday"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --since"));
+}
+
+/// Test metrics command aggregates the recorded history
+#[test]
+fn test_metrics_command() {
+    use sentinel::core::{HistoryMetric, MetricSample, MetricsStore};
+
+    let tmp = TempDir::new().unwrap();
+    let now = chrono::Utc::now();
+    let mut store = MetricsStore::open(&tmp.path().join(".config/sentinel/metrics.db")).unwrap();
+    let samples: Vec<_> = (1..=10)
+        .map(|i| {
+            MetricSample::new(
+                "api",
+                HistoryMetric::Cpu,
+                now - chrono::Duration::minutes(i),
+                i as f64,
+            )
+        })
+        .collect();
+    store.insert(&samples).unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args([
+            "metrics", "api", "--last", "1h", "--metric", "cpu", "--points", "1", "--format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""samples": 10"#))
+        .stdout(predicate::str::contains(r#""max": 10.0"#));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["metrics", "api", "--last", "6h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CPU (AVG / MAX)"));
+}
+
+/// Test metrics command rejects unknown metrics and a malformed --last
+#[test]
+fn test_metrics_invalid_arguments() {
+    let tmp = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["metrics", "api", "--metric", "gpu"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown metric 'gpu'"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["metrics", "api", "--last", "yesterday"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --last"));
+}
+
+/// Path of an importer fixture shared with the library tests
+fn import_fixture(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../src-tauri/tests/fixtures/import")
+        .join(name)
+}
+
+/// Test importing a PM2 ecosystem file, without overwriting the output
+#[test]
+fn test_import_pm2() {
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("sentinel.yaml");
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.arg("import")
+        .arg("pm2")
+        .arg(import_fixture("ecosystem.config.js"))
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 3 process(es)"))
+        .stdout(predicate::str::contains(
+            "'instances' has no Sentinel equivalent",
+        ));
+
+    let written = fs::read_to_string(&output).unwrap();
+    assert!(written.contains("name: api"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.arg("validate").arg(&output).assert().success();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.arg("import")
+        .arg("pm2")
+        .arg(import_fixture("ecosystem.config.js"))
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --force"));
+    assert_eq!(fs::read_to_string(&output).unwrap(), written);
+}
+
+/// Test importing a Docker Compose file flags services it can't run
+#[test]
+fn test_import_compose() {
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("sentinel.yaml");
+    fs::write(&output, "processes: []\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.arg("import")
+        .arg("compose")
+        .arg(import_fixture("docker-compose.yml"))
+        .arg("-o")
+        .arg(&output)
+        .arg("--force")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("requires the Docker feature"));
+
+    let written = fs::read_to_string(&output).unwrap();
+    assert!(written.contains("command: docker"));
+    assert!(written.contains("# - Service 'web' builds its image"));
+}
+
+/// Test doctor reports a broken config as a failure in JSON
+#[test]
+fn test_doctor_json_reports_config_failure() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("broken.yaml");
+    fs::write(&config_path, "processes: [").unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .arg("doctor")
+        .arg(&config_path)
+        .args(["--format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(r#""name": "Config file""#))
+        .stdout(predicate::str::contains(r#""status": "fail""#));
+}
+
+/// Test completion scripts are generated for each shell
+#[test]
+fn test_completions() {
+    for (shell, marker) in [
+        ("bash", "complete -F"),
+        ("zsh", "#compdef sentinel"),
+        ("fish", "complete -c sentinel"),
+        ("powershell", "Register-ArgumentCompleter"),
+    ] {
+        let mut cmd = Command::cargo_bin("sentinel").unwrap();
+        cmd.args(["completions", shell])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(marker))
+            .stdout(predicate::str::contains("doctor"));
+    }
+}
+
+/// Test the config schema is printed as JSON with field descriptions
+#[test]
+fn test_schema() {
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    let output = cmd
+        .arg("schema")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let schema: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(schema["title"], "Sentinel configuration");
+    let process = &schema["definitions"]["ProcessConfig"]["properties"];
+    assert!(process["command"]["description"].is_string());
+    assert!(process["healthCheck"].is_object());
+}
+
+/// Test ps --watch streams each transition once, then stops on SIGINT
+#[cfg(unix)]
+#[test]
+fn test_ps_watch_streams_transitions() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let tmp = TempDir::new().unwrap();
+    let config = tmp.path().join("sentinel.yaml");
+    fs::write(
+        &config,
+        r#"
+processes:
+  - name: crasher
+    command: sh
+    args: ["-c", "sleep 1; exit 3"]
+    autoRestart: false
+  - name: sleeper
+    command: sleep
+    args: ["30"]
+settings:
+  startGraceMs: 0
+  healthCheckIntervalMs: 200
+"#,
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("sentinel"))
+        .env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("RUST_LOG")
+        .args(["ps", "--watch", "--format", "json"])
+        .arg(&config)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+
+    let mut records: Vec<serde_json::Value> = Vec::new();
+    while !records.iter().any(|record| record["type"] == "heartbeat") {
+        let line = lines.next().expect("feed ended early").unwrap();
+        records.push(serde_json::from_str(&line).unwrap());
+    }
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    for line in lines {
+        records.push(serde_json::from_str(&line.unwrap()).unwrap());
+    }
+    assert!(child.wait().unwrap().success());
+
+    let seqs: Vec<u64> = records.iter().map(|r| r["seq"].as_u64().unwrap()).collect();
+    assert_eq!(seqs, (1..=records.len() as u64).collect::<Vec<_>>());
+    assert_eq!(records[0]["type"], "snapshot");
+    assert_eq!(records[0]["processes"].as_array().unwrap().len(), 2);
+
+    let transitions = |name: &str| -> Vec<serde_json::Value> {
+        records
+            .iter()
+            .filter(|r| r["type"] == "change")
+            .flat_map(|r| r["changes"].as_array().unwrap().clone())
+            .filter(|change| change["name"] == name)
+            .map(|change| change["to"]["state"].clone())
+            .collect()
+    };
+    assert_eq!(
+        transitions("crasher"),
+        [serde_json::json!({"crashed": {"exit_code": 3}})]
+    );
+    // Stopped on SIGINT, reported in the last record
+    assert_eq!(transitions("sleeper"), [serde_json::json!("stopped")]);
+}
+
+/// Test run exits with the command's exit code
+#[test]
+fn test_run_propagates_exit_code() {
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.args([
+        "run",
+        "--name",
+        "fails",
+        "--",
+        "sh",
+        "-c",
+        "echo done; exit 3",
+    ])
+    .assert()
+    .code(3)
+    .stdout(predicate::str::contains("done"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.args(["run", "--", "true"]).assert().success();
+}
+
+/// Test run passes --env and --port to the command
+#[test]
+fn test_run_env_flags() {
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.args([
+        "run",
+        "--env",
+        "GREETING=hello=world",
+        "-e",
+        "EMPTY=",
+        "--port",
+        "4321",
+        "--",
+        "sh",
+        "-c",
+        "echo \"$GREETING [$EMPTY] $PORT\"",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("hello=world [] 4321"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.args(["run", "--env", "GREETING", "--", "true"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected KEY=VALUE"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.args(["run", "--env", "=value", "--", "true"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing variable name"));
+}
+
+/// Test list --tag shows only the tagged processes, with their tags
+#[test]
+fn test_list_tag_filter() {
+    let tmp = TempDir::new().unwrap();
+    let config_dir = tmp.path().join(".config/sentinel");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.yaml"),
+        r#"
+processes:
+  - name: api
+    command: "true"
+    tags: [backend]
+  - name: worker
+    command: "true"
+    tags: [backend, jobs]
+  - name: web
+    command: "true"
+    tags: [frontend]
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["list", "--tag", "backend"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TAGS"))
+        .stdout(predicate::str::contains("backend, jobs"))
+        .stdout(predicate::str::contains("web").not());
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["stop", "--group", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No process is tagged 'missing'"));
+}
+
+/// Test stop, restart and status act on the named processes only
+#[cfg(unix)]
+#[test]
+fn test_named_process_selection() {
+    use std::process::Stdio;
+
+    /// Kills the instance should the test fail while it runs
+    struct Instance(std::process::Child);
+
+    impl Drop for Instance {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    let tmp = TempDir::new().unwrap();
+    let config_dir = tmp.path().join(".config/sentinel");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.yaml"),
+        r#"
+processes:
+  - name: api
+    command: sleep
+    args: ["30"]
+    tags: [backend]
+    env:
+      PORT: "4811"
+  - name: worker
+    command: sleep
+    args: ["30"]
+    tags: [backend]
+  - name: web
+    command: sleep
+    args: ["30"]
+settings:
+  startGraceMs: 0
+"#,
+    )
+    .unwrap();
+
+    // The processes belong to a running instance, so there is nothing to stop
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["stop", "api"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No Sentinel instance is running"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["status", "api", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""ports": ["#))
+        .stdout(predicate::str::contains("4811"))
+        .stdout(predicate::str::contains("recent_events"))
+        .stdout(predicate::str::contains("last_restart_reason"))
+        .stdout(predicate::str::contains("worker").not());
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["status", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Process 'nope' not found"));
+
+    let mut instance = Instance(
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("sentinel"))
+            .env("HOME", tmp.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("RUST_LOG")
+            .arg("start")
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap(),
+    );
+    let socket = config_dir.join(".sentinel.sock");
+    for _ in 0..100 {
+        if socket.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(socket.exists(), "sentinel start never listened");
+
+    // Found processes are still stopped when another name is unknown
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["stop", "api", "missing"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Stopping 1 process(es)"))
+        .stdout(predicate::str::contains("Stopped api"))
+        .stdout(predicate::str::contains("worker").not())
+        .stderr(predicate::str::contains("Process 'missing' not found"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["restart", "api", "worker", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restarted api"))
+        .stdout(predicate::str::contains("Restarted worker"))
+        .stdout(predicate::str::contains("web").not());
+
+    // --tag selects like --group, but not together with names
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["stop", "--tag", "backend"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Stopping 2 process(es)"))
+        .stdout(predicate::str::contains("Stopped worker"));
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["stop", "api", "--tag", "backend"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    std::process::Command::new("kill")
+        .args(["-INT", &instance.0.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(instance.0.wait().unwrap().success());
+}
+
+/// Test grep reports an invalid regular expression
+#[test]
+fn test_grep_invalid_regex() {
+    let tmp = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["grep", "--regex", "(unclosed"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid search pattern '(unclosed'",
+        ));
+}
+
 /// Test help for each subcommand
 #[test]
 fn test_subcommand_help() {
     let subcommands = vec![
-        "start", "stop", "restart", "status", "logs", "add", "remove", "list", "init",
+        "start",
+        "stop",
+        "restart",
+        "status",
+        "logs",
+        "grep",
+        "add",
+        "remove",
+        "list",
+        "init",
+        "events",
+        "metrics",
+        "doctor",
+        "top",
+        "ps",
+        "completions",
+        "run",
+        "import",
+        "schema",
+        "workspace",
     ];
 
     for subcommand in subcommands {
@@ -241,3 +957,194 @@ fn test_subcommand_help() {
             .stdout(predicate::str::contains("Usage:"));
     }
 }
+
+/// Test start offers to replace a running instance
+#[test]
+fn test_start_help_shows_force() {
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.args(["start", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--force"));
+}
+
+/// Test start --dry-run previews without running, masking secrets
+#[test]
+fn test_start_dry_run() {
+    let tmp = TempDir::new().unwrap();
+    let marker = tmp.path().join("ran");
+    let config = tmp.path().join("sentinel.yaml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+processes:
+  - name: toucher
+    command: touch
+    args: ["{}"]
+    env:
+      API_TOKEN: hunter2
+      MODE: dev
+  - name: ghost
+    command: no-such-program-here
+"#,
+            marker.display()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["start", "--dry-run"])
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("API_TOKEN=••••"))
+        .stdout(predicate::str::contains("MODE=dev"))
+        .stdout(predicate::str::contains("hunter2").not())
+        .stdout(predicate::str::contains(
+            "Command 'no-such-program-here' was not found on PATH",
+        ));
+    assert!(!marker.exists(), "dry run started a process");
+
+    fs::write(
+        &config,
+        "processes:\n  - name: root\n    command: sudo true\n",
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["start", "--dry-run"])
+        .arg(&config)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("blocked command 'sudo'"));
+}
+
+/// Test JSON mode reports errors with the app's error codes
+#[test]
+fn test_json_error_uses_error_code() {
+    let tmp = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .args(["list", "--format", "json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(r#""code": "CONFIG_NOT_FOUND""#))
+        .stdout(predicate::str::contains(
+            r#""message": "Configuration file not found"#,
+        ));
+}
+
+/// Test workspace export and import round-trip through a wiped config directory
+#[test]
+fn test_workspace_export_import() {
+    let tmp = TempDir::new().unwrap();
+    let config_dir = tmp.path().join("sentinel");
+    let config = config_dir.join("sentinel.yaml");
+    let archive = tmp.path().join("workspace.tar.gz");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        &config,
+        "# Team setup\nprocesses:\n  - name: web\n    command: npm run dev\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["workspace", "export"])
+        .arg(&archive)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    std::fs::remove_dir_all(&config_dir).unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["workspace", "import", "--replace"])
+        .arg(&archive)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 process(es)"));
+
+    assert!(std::fs::read_to_string(&config)
+        .unwrap()
+        .starts_with("# Team setup"));
+}
+
+/// Test adding a process from a built-in template
+#[test]
+fn test_add_from_template() {
+    let tmp = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.env("HOME", tmp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args([
+            "add",
+            "web",
+            "--template",
+            "Vite Development Server",
+            "--var",
+            "PORT=5200",
+            "--directory",
+        ])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("from template"));
+
+    let config = std::fs::read_to_string(tmp.path().join(".config/sentinel/config.yaml")).unwrap();
+    assert!(config.contains("name: web"));
+    assert!(config.contains("localhost:5200"));
+}
+
+/// Test printing a config with inheritance resolved
+#[test]
+fn test_validate_resolved() {
+    let tmp = TempDir::new().unwrap();
+    let config = tmp.path().join("sentinel.yaml");
+    std::fs::write(
+        &config,
+        r#"
+defaults:
+  node:
+    command: node
+    env:
+      NODE_ENV: development
+      API_TOKEN: abc123
+processes:
+  - name: api
+    extends: node
+    args: [api.js]
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.arg("validate")
+        .arg(&config)
+        .arg("--resolved")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("command: node"))
+        .stdout(predicate::str::contains("NODE_ENV: development"))
+        .stdout(predicate::str::contains("extends").not())
+        .stdout(predicate::str::contains("abc123").not());
+
+    std::fs::write(&config, "processes:\n  - name: api\n    extends: missing\n").unwrap();
+    let mut cmd = Command::cargo_bin("sentinel").unwrap();
+    cmd.arg("validate")
+        .arg(&config)
+        .arg("--resolved")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'api' extends 'missing'"));
+}
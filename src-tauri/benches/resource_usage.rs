@@ -0,0 +1,81 @@
+//! Resource usage sampling with 50 managed processes.
+//!
+//! Compares finding every process's descendants with one index over the
+//! process table against a scan of the table per process, and measures an
+//! `update_resource_usage` call that reuses the latest refresh.
+//!
+//! Run with `cargo bench --bench resource_usage`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use sentinel::core::{process_tree, ProcessIndex, ProcessManager};
+use sentinel::models::{GlobalSettings, ProcessConfig};
+use std::hint::black_box;
+use std::process::{Child, Command};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+const PROCESSES: usize = 50;
+
+fn spawn_sleepers() -> Vec<Child> {
+    (0..PROCESSES)
+        .map(|_| Command::new("sleep").arg("30").spawn().unwrap())
+        .collect()
+}
+
+fn bench_descendants(c: &mut Criterion) {
+    let mut children = spawn_sleepers();
+    let roots: Vec<u32> = children.iter().map(Child::id).collect();
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+
+    let mut group = c.benchmark_group("descendants");
+    group.bench_function("table scan per process", |b| {
+        b.iter(|| {
+            for &root in &roots {
+                black_box(process_tree::descendants(&system, root));
+            }
+        })
+    });
+    group.bench_function("one index", |b| {
+        b.iter(|| {
+            let index = ProcessIndex::new(&system);
+            for &root in &roots {
+                black_box(index.descendants(&[root]));
+            }
+        })
+    });
+    group.finish();
+
+    for child in &mut children {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+fn bench_update_resource_usage(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let manager = ProcessManager::with_settings(GlobalSettings {
+        start_grace_ms: 0,
+        ..GlobalSettings::default()
+    });
+    runtime.block_on(async {
+        for i in 0..PROCESSES {
+            let config: ProcessConfig = serde_yaml::from_str(&format!(
+                "name: worker-{}\ncommand: sleep\nargs: ['30']\nautoRestart: false",
+                i
+            ))
+            .unwrap();
+            manager.start(config).await.unwrap();
+        }
+    });
+
+    // Calls within `RESOURCE_REFRESH_INTERVAL` of a refresh reuse it, so most
+    // iterations here skip refreshing the process table
+    c.bench_function("update_resource_usage", |b| {
+        b.iter(|| runtime.block_on(manager.update_resource_usage()))
+    });
+
+    runtime.block_on(manager.stop_all()).unwrap();
+}
+
+criterion_group!(benches, bench_descendants, bench_update_resource_usage);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+//! Alert commands.
+
+use crate::models::Alert;
+use crate::state::AppState;
+use tauri::State;
+
+/// Default number of alerts returned by `get_alert_history`.
+const DEFAULT_ALERT_HISTORY_LIMIT: usize = 100;
+
+/// Lists alerts that are currently firing.
+///
+/// # Arguments
+/// * `state` - Application state
+///
+/// # Returns
+/// Firing alerts, oldest first, including acknowledged ones
+#[tauri::command]
+pub async fn list_active_alerts(state: State<'_, AppState>) -> Result<Vec<Alert>, String> {
+    Ok(state.alert_manager.lock().await.active())
+}
+
+/// Acknowledges a firing alert.
+///
+/// # Arguments
+/// * `id` - Alert ID
+/// * `state` - Application state
+///
+/// # Returns
+/// The acknowledged alert, or an error if no firing alert has that ID
+#[tauri::command]
+pub async fn ack_alert(id: String, state: State<'_, AppState>) -> Result<Alert, String> {
+    state
+        .alert_manager
+        .lock()
+        .await
+        .ack(&id)
+        .map_err(|e| e.to_string())
+}
+
+/// Gets past and firing alerts.
+///
+/// # Arguments
+/// * `limit` - Maximum number of alerts (default: 100)
+/// * `state` - Application state
+///
+/// # Returns
+/// Alerts, most recently fired first
+#[tauri::command]
+pub async fn get_alert_history(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Alert>, String> {
+    Ok(state
+        .alert_manager
+        .lock()
+        .await
+        .history(limit.unwrap_or(DEFAULT_ALERT_HISTORY_LIMIT)))
+}
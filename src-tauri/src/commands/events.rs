@@ -0,0 +1,38 @@
+//! Event journal commands.
+
+use crate::models::{EventFilter, JournalEvent};
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+/// Default number of events returned by `query_events`.
+const DEFAULT_EVENT_LIMIT: usize = 500;
+
+/// Queries the event journal.
+///
+/// # Arguments
+/// * `filter` - Criteria events must meet (default: all events)
+/// * `since` - Only events at or after this time
+/// * `limit` - Maximum number of events, keeping the most recent (default: 500)
+/// * `state` - Application state
+///
+/// # Returns
+/// Matching events, oldest first
+#[tauri::command]
+pub async fn query_events(
+    filter: Option<EventFilter>,
+    since: Option<DateTime<Utc>>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<JournalEvent>, String> {
+    let Some(journal) = state.event_recorder.journal() else {
+        return Ok(Vec::new());
+    };
+    journal
+        .query(
+            &filter.unwrap_or_default(),
+            since,
+            Some(limit.unwrap_or(DEFAULT_EVENT_LIMIT)),
+        )
+        .map_err(|e| e.to_string())
+}
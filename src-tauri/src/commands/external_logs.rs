@@ -1,6 +1,6 @@
 //! Commands for external process log monitoring.
 
-use crate::core::ProcessAttachment;
+use crate::core::{AttachmentInfo, ProcessAttachment};
 use crate::state::AppState;
 use tauri::{AppHandle, State};
 
@@ -18,20 +18,54 @@ pub async fn attach_to_external_process(
         .map_err(|e| e.to_string())
 }
 
-/// Tail a log file and stream lines to the frontend
+/// Tail a log file, or every file matching a glob such as `logs/*.log`, and
+/// stream lines to the frontend
 #[tauri::command]
 pub async fn tail_log_file(
     path: String,
+    last_lines: Option<usize>,
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
+    let allowed_roots = state.process_manager.settings().allowed_roots;
+
     let monitor = state.inner().external_process_monitor.lock().await;
     monitor
-        .tail_log_file(path, app)
+        .tail_log_file(path, last_lines, &allowed_roots, app)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Stream the logs of a process found by `attach_to_external_process`,
+/// optionally following it to a new PID when it restarts on the same port
+#[tauri::command]
+pub async fn follow_external_process(
+    attachment: ProcessAttachment,
+    reattach_on_restart: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let allowed_roots = state.process_manager.settings().allowed_roots;
+
+    let monitor = state.inner().external_process_monitor.lock().await;
+    monitor
+        .follow_process(
+            attachment,
+            reattach_on_restart.unwrap_or(false),
+            &allowed_roots,
+            app,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List active log attachments
+#[tauri::command]
+pub async fn list_attachments(state: State<'_, AppState>) -> Result<Vec<AttachmentInfo>, String> {
+    let monitor = state.inner().external_process_monitor.lock().await;
+    Ok(monitor.list_attachments().await)
+}
+
 /// Capture logs using dtrace (macOS only)
 #[cfg(target_os = "macos")]
 #[tauri::command]
@@ -59,3 +93,32 @@ pub async fn detach_external_logs(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Stream a Docker container's logs to the frontend
+#[tauri::command]
+pub async fn stream_container_logs(
+    container_id: String,
+    tail: Option<usize>,
+    follow: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let monitor = state.inner().external_process_monitor.lock().await;
+    monitor
+        .stream_container_logs(container_id, tail, follow.unwrap_or(true), app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stop streaming a Docker container's logs
+#[tauri::command]
+pub async fn stop_container_log_stream(
+    attachment_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let monitor = state.inner().external_process_monitor.lock().await;
+    monitor
+        .detach(&attachment_id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -3,39 +3,60 @@
 use tauri::{AppHandle, State};
 
 use crate::core::{
-    detect_framework, get_framework_templates, DetectedProject, FrameworkDetection,
-    ManagedProcessConfig, ProcessStatusInfo, ProcessTemplate,
+    detect_framework, get_framework_templates, secrets, FrameworkDetection, ManagedProcessConfig,
+    ProcessStatusInfo, ProcessTemplate, ProjectScanResult,
 };
+use crate::models::GlobalSettings;
 use crate::state::AppState;
 
+/// Secret patterns from the loaded config file, or the defaults
+async fn secret_patterns(state: &AppState) -> Vec<String> {
+    state
+        .config
+        .read()
+        .await
+        .as_ref()
+        .map(|config| config.settings.secret_patterns.clone())
+        .unwrap_or_else(|| GlobalSettings::default().secret_patterns)
+}
+
 /// Create a new process configuration
 #[tauri::command]
 pub async fn create_process_config(
     config: ManagedProcessConfig,
     state: State<'_, AppState>,
 ) -> Result<ManagedProcessConfig, String> {
-    state
+    let created = state
         .process_config_store
         .lock()
         .await
         .create(config)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    Ok(secrets::mask_managed_config(
+        &created,
+        &secret_patterns(&state).await,
+    ))
 }
 
 /// Update an existing configuration
 #[tauri::command]
 pub async fn update_process_config(
-    config: ManagedProcessConfig,
+    mut config: ManagedProcessConfig,
     state: State<'_, AppState>,
 ) -> Result<ManagedProcessConfig, String> {
-    state
-        .process_config_store
-        .lock()
-        .await
-        .update(config)
-        .await
-        .map_err(|e| e.to_string())
+    let store = state.process_config_store.lock().await;
+
+    // Secrets the frontend only saw masked keep their stored value
+    if let Ok(existing) = store.get(&config.id).await {
+        secrets::restore_masked(&mut config.env_vars, &existing.env_vars);
+    }
+
+    let updated = store.update(config).await.map_err(|e| e.to_string())?;
+    Ok(secrets::mask_managed_config(
+        &updated,
+        &secret_patterns(&state).await,
+    ))
 }
 
 /// Delete a configuration
@@ -62,7 +83,12 @@ pub async fn delete_process_config(id: String, state: State<'_, AppState>) -> Re
 pub async fn list_process_configs(
     state: State<'_, AppState>,
 ) -> Result<Vec<ManagedProcessConfig>, String> {
-    Ok(state.process_config_store.lock().await.list().await)
+    let patterns = secret_patterns(&state).await;
+    let configs = state.process_config_store.lock().await.list().await;
+    Ok(configs
+        .iter()
+        .map(|config| secrets::mask_managed_config(config, &patterns))
+        .collect())
 }
 
 /// Get a single configuration by ID
@@ -71,13 +97,17 @@ pub async fn get_process_config(
     id: String,
     state: State<'_, AppState>,
 ) -> Result<ManagedProcessConfig, String> {
-    state
+    let config = state
         .process_config_store
         .lock()
         .await
         .get(&id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    Ok(secrets::mask_managed_config(
+        &config,
+        &secret_patterns(&state).await,
+    ))
 }
 
 /// Detect framework from a working directory
@@ -194,19 +224,28 @@ pub async fn import_process_configs(
     json: String,
     state: State<'_, AppState>,
 ) -> Result<Vec<ManagedProcessConfig>, String> {
-    state
+    let imported = state
         .process_config_store
         .lock()
         .await
         .import(&json)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    let patterns = secret_patterns(&state).await;
+    Ok(imported
+        .iter()
+        .map(|config| secrets::mask_managed_config(config, &patterns))
+        .collect())
 }
 
-/// Scan a directory for projects (supports monorepos)
+/// Scan a directory tree for projects (supports monorepos)
 #[tauri::command]
-pub async fn scan_directory_for_projects(dir_path: String) -> Result<Vec<DetectedProject>, String> {
-    crate::core::scan_directory_for_projects(&dir_path)
+pub async fn scan_directory_for_projects(
+    dir_path: String,
+    max_depth: Option<usize>,
+) -> Result<ProjectScanResult, String> {
+    crate::core::scan_directory_for_projects(&dir_path, max_depth)
         .await
         .map_err(|e| e.to_string())
 }
@@ -0,0 +1,77 @@
+//! Metrics history and group usage commands.
+
+use crate::core::metrics_history::DEFAULT_MAX_POINTS;
+use crate::core::{group_metrics, GroupMetrics, HistoryMetric, MetricPoint, MetricsStore};
+use crate::error::SentinelError;
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+/// Queries the metrics history.
+///
+/// # Arguments
+/// * `target` - `system` or a process name
+/// * `metric` - Metric to read, e.g. `cpu` or `memory`
+/// * `from` - Start of the range
+/// * `to` - End of the range (default: now)
+/// * `max_points` - Buckets the range is aggregated into (default: 200)
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<MetricPoint>)` - Average, minimum and maximum of each bucket
+///   with samples, oldest first; empty if no history was ever recorded
+/// * `Err(SentinelError)` - The history database couldn't be read
+#[tauri::command]
+pub async fn query_metrics(
+    target: String,
+    metric: HistoryMetric,
+    from: DateTime<Utc>,
+    to: Option<DateTime<Utc>>,
+    max_points: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<MetricPoint>, SentinelError> {
+    let path = MetricsStore::path(&state.process_manager.settings().metrics_history);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        MetricsStore::open(&path)?.query(
+            &target,
+            metric,
+            from,
+            to.unwrap_or_else(Utc::now),
+            max_points.unwrap_or(DEFAULT_MAX_POINTS),
+        )
+    })
+    .await
+    .map_err(|e| SentinelError::Other(format!("Metrics query failed: {}", e)))?
+}
+
+/// Sums the recent CPU and memory usage of the processes with a tag.
+///
+/// Only the in-memory usage history of each process is read, so the series
+/// is no longer than that history.
+///
+/// # Arguments
+/// * `tag` - Tag of the managed processes to sum
+/// * `seconds` - How far back the series goes
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(GroupMetrics)` - Summed series, current totals and the members;
+///   processes without samples for part of the range count as zero there
+/// * `Err(SentinelError)` - No managed process has the tag
+#[tauri::command]
+pub async fn get_group_metrics(
+    tag: String,
+    seconds: u64,
+    state: State<'_, AppState>,
+) -> Result<GroupMetrics, SentinelError> {
+    let members = state.process_manager.tagged_usage(&tag).await?;
+    let window = chrono::Duration::seconds(seconds.min(MAX_GROUP_WINDOW_SECS) as i64);
+    Ok(group_metrics::aggregate(&tag, &members, Utc::now(), window))
+}
+
+/// Longest range `get_group_metrics` covers.
+const MAX_GROUP_WINDOW_SECS: u64 = 24 * 60 * 60;
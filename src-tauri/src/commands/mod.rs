@@ -2,14 +2,26 @@
 //!
 //! This module defines all commands that can be invoked from the frontend.
 
+pub mod alerts;
+pub mod events;
 pub mod external_logs;
 pub mod managed_process;
+pub mod metrics;
+pub mod notifications;
 pub mod process;
 pub mod pty;
 pub mod system;
+pub mod templates;
+pub mod workspace;
 
+pub use alerts::*;
+pub use events::*;
 pub use external_logs::*;
 pub use managed_process::*;
+pub use metrics::*;
+pub use notifications::*;
 pub use process::*;
 pub use pty::*;
 pub use system::*;
+pub use templates::*;
+pub use workspace::*;
@@ -0,0 +1,41 @@
+//! Notification sink commands.
+
+use crate::core::NotificationDispatcher;
+use crate::models::{EventActor, EventType, JournalEvent};
+use crate::state::AppState;
+use tauri::State;
+
+/// Sends a sample event to a notification sink to check that it is reachable.
+///
+/// # Arguments
+/// * `name` - Name of the sink in `settings.notificationSinks`
+/// * `state` - Application state
+///
+/// # Returns
+/// Error if no sink has that name or every delivery attempt failed
+#[tauri::command]
+pub async fn test_notification_sink(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let sink = state
+        .process_manager
+        .settings()
+        .notification_sinks
+        .iter()
+        .find(|sink| sink.name == name)
+        .cloned()
+        .ok_or_else(|| format!("No notification sink named '{}'", name))?;
+
+    state.event_recorder.record(JournalEvent::new(
+        EventType::NotificationTest,
+        &sink.name,
+        EventActor::User,
+    ));
+
+    // Sent without holding the process manager, since retries can take a while
+    NotificationDispatcher::new()
+        .send_test(&sink)
+        .await
+        .map_err(|e| e.to_string())
+}
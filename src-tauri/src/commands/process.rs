@@ -1,10 +1,26 @@
 //! Process management commands.
 
-use crate::core::{ConfigManager, LogLine};
-use crate::models::{Config, ProcessConfig, ProcessInfo};
+use crate::core::{
+    detect_framework, disk_usage, launch, log_retention, log_timestamp, logging, messages,
+    readiness, scheduler, scheduling, schema, secrets, startup_report, CleanReport, ConfigDiff,
+    ConfigManager, DiskUsageReport, ImportReport, ImportSource, LogLine, LogMemoryStats,
+    LogPruneSummary, LogQuery, LogSearchOptions, MergedLogLine, PreviewResult, ProcessLogMatches,
+    StartupReport, TimestampOrder,
+};
+use crate::error::SentinelError;
+use crate::features::capabilities::CapabilitiesState;
+use crate::features::network_monitor::NetworkMonitorState;
+use crate::features::service_detection::ServiceDetectorState;
+use crate::models::{
+    BulkOutcome, Config, ConfigIssue, EventActor, EventType, JournalEvent, ManagedProcessSummary,
+    OrphanInfo, ProcessConfig, ProcessInfo, ProcessKind, ProcessOverrides, ProcessTreeNode,
+};
 use crate::state::AppState;
+use chrono::{DateTime, Local, Utc};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, State};
 
 /// Starts a process from configuration.
 ///
@@ -14,14 +30,40 @@ use tauri::State;
 ///
 /// # Returns
 /// * `Ok(ProcessInfo)` - Successfully started process
-/// * `Err(String)` - Error message
+/// * `Err(SentinelError)` - Error message
 #[tauri::command]
 pub async fn start_process(
     config: ProcessConfig,
     state: State<'_, AppState>,
-) -> Result<ProcessInfo, String> {
-    let mut manager = state.process_manager.lock().await;
-    manager.start(config).await.map_err(|e| e.to_string())
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.start(config).await
+}
+
+/// Previews starting a process without spawning it.
+///
+/// Resolves the configuration the way `start_process` does and reports what
+/// would run, with secrets masked, along with the validation errors that
+/// would stop it and warnings such as a missing binary or a port in use.
+///
+/// # Arguments
+/// * `config` - Process configuration
+/// * `state` - Application state
+///
+/// # Returns
+/// What starting the process would do
+#[tauri::command]
+pub async fn preview_process_start(
+    config: ProcessConfig,
+    state: State<'_, AppState>,
+) -> Result<PreviewResult, SentinelError> {
+    let others = state
+        .config
+        .read()
+        .await
+        .as_ref()
+        .map(|loaded| loaded.processes.clone())
+        .unwrap_or_default();
+    Ok(launch::preview(&config, &state.process_manager.settings(), &others).await)
 }
 
 /// Stops a running process.
@@ -32,11 +74,10 @@ pub async fn start_process(
 ///
 /// # Returns
 /// * `Ok(())` - Process stopped
-/// * `Err(String)` - Error message
+/// * `Err(SentinelError)` - Error message
 #[tauri::command]
-pub async fn stop_process(name: String, state: State<'_, AppState>) -> Result<(), String> {
-    let mut manager = state.process_manager.lock().await;
-    manager.stop(&name).await.map_err(|e| e.to_string())
+pub async fn stop_process(name: String, state: State<'_, AppState>) -> Result<(), SentinelError> {
+    state.process_manager.stop(&name).await
 }
 
 /// Restarts a process.
@@ -47,14 +88,13 @@ pub async fn stop_process(name: String, state: State<'_, AppState>) -> Result<()
 ///
 /// # Returns
 /// * `Ok(ProcessInfo)` - Restarted process info
-/// * `Err(String)` - Error message
+/// * `Err(SentinelError)` - Error message
 #[tauri::command]
 pub async fn restart_process(
     name: String,
     state: State<'_, AppState>,
-) -> Result<ProcessInfo, String> {
-    let mut manager = state.process_manager.lock().await;
-    manager.restart(&name).await.map_err(|e| e.to_string())
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.restart(&name).await
 }
 
 /// Starts a stopped process by name.
@@ -68,17 +108,13 @@ pub async fn restart_process(
 ///
 /// # Returns
 /// * `Ok(ProcessInfo)` - Started process info
-/// * `Err(String)` - Error message if process not found or already running
+/// * `Err(SentinelError)` - Error message if process not found or already running
 #[tauri::command]
 pub async fn start_process_by_name(
     name: String,
     state: State<'_, AppState>,
-) -> Result<ProcessInfo, String> {
-    let mut manager = state.process_manager.lock().await;
-    manager
-        .start_by_name(&name)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.start_by_name(&name).await
 }
 
 /// Gets information about a specific process.
@@ -88,30 +124,348 @@ pub async fn start_process_by_name(
 /// * `state` - Application state
 ///
 /// # Returns
-/// * `Ok(ProcessInfo)` - Process information
-/// * `Err(String)` - Process not found
+/// * `Ok(ProcessInfo)` - Process information, with its current priority
+/// * `Err(SentinelError)` - Process not found
 #[tauri::command]
-pub async fn get_process(name: String, state: State<'_, AppState>) -> Result<ProcessInfo, String> {
-    let manager = state.process_manager.lock().await;
-    manager
+pub async fn get_process(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<ProcessInfo, SentinelError> {
+    let mut info = state
+        .process_manager
         .get(&name)
-        .cloned()
-        .ok_or_else(|| format!("Process '{}' not found", name))
+        .await
+        .ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })?;
+
+    if info.is_running() {
+        info.nice = info.pid.and_then(scheduling::priority);
+    }
+    Ok(info)
+}
+
+/// Changes the priority (nice value) of a running process.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `nice` - Priority from -20 (highest) to 19 (lowest)
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ProcessInfo)` - Process info with the new priority
+/// * `Err(SentinelError)` - Process not running, invalid value or permission denied
+#[tauri::command]
+pub async fn set_process_priority(
+    name: String,
+    nice: i32,
+    state: State<'_, AppState>,
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.set_priority(&name, nice).await
+}
+
+/// Gets a running process and the tree of processes it spawned.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ProcessTreeNode)` - The process with its CPU and memory usage and its descendants
+/// * `Err(SentinelError)` - Process not found or not running
+#[tauri::command]
+pub async fn get_process_tree(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<ProcessTreeNode, SentinelError> {
+    state.process_manager.get_process_tree(&name).await
+}
+
+/// Gets the processes a crashed process left running.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<OrphanInfo>)` - Orphans from its last crash that are still alive
+/// * `Err(SentinelError)` - Process not found
+#[tauri::command]
+pub async fn get_orphaned_children(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<OrphanInfo>, SentinelError> {
+    state.process_manager.orphaned_children(&name).await
+}
+
+/// Working directory of a process and the cache directories that may be
+/// cleaned in it.
+///
+/// Processes from the config file are looked up first, then managed process
+/// configs; the framework comes from the managed config or is detected.
+async fn process_workspace(
+    state: &AppState,
+    name: &str,
+) -> Result<(PathBuf, Vec<String>), SentinelError> {
+    let (cwd, framework) = match state.process_manager.get_config(name).await {
+        Some(config) => {
+            let cwd = config.cwd.ok_or_else(|| SentinelError::InvalidInput {
+                message: format!("Process '{}' has no working directory", name),
+            })?;
+            (cwd, None)
+        }
+        None => {
+            let managed = state
+                .process_config_store
+                .lock()
+                .await
+                .list()
+                .await
+                .into_iter()
+                .find(|config| config.name == name)
+                .ok_or_else(|| SentinelError::ProcessNotFound {
+                    name: name.to_string(),
+                })?;
+            (PathBuf::from(managed.working_dir), managed.framework_type)
+        }
+    };
+
+    let framework = match framework {
+        Some(framework) => framework,
+        None => {
+            detect_framework(&cwd.to_string_lossy())
+                .await?
+                .framework_type
+        }
+    };
+    let overrides = state.process_manager.settings().cache_dirs;
+    Ok((cwd, disk_usage::cache_dirs_for(&framework, &overrides)))
+}
+
+/// Measures the disk usage of a process's working directory.
+///
+/// Reports are cached for a few minutes; a new scan of the same directory
+/// cancels the one already running.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `refresh` - Measure again even if a cached report is available
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(DiskUsageReport)` - Total size, largest subdirectories and cleanable caches
+/// * `Err(SentinelError)` - Process not found, directory unreadable, or scan cancelled
+#[tauri::command]
+pub async fn get_process_disk_usage(
+    name: String,
+    refresh: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<DiskUsageReport, SentinelError> {
+    let (cwd, cache_dirs) = process_workspace(&state, &name).await?;
+    if !refresh.unwrap_or(false) {
+        if let Some(report) = state.disk_usage.lock().await.get(&cwd) {
+            return Ok(report);
+        }
+    }
+
+    let cancel = state.disk_usage.lock().await.begin_scan(cwd.clone());
+    let result = disk_usage::measure_async(cwd.clone(), cache_dirs, cancel.clone()).await;
+    let mut cache = state.disk_usage.lock().await;
+    cache.end_scan(&cwd, &cancel);
+    let report = result?;
+    cache.insert(cwd, report.clone());
+    Ok(report)
+}
+
+/// Cancels a running disk usage scan of a process's working directory.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(bool)` - Whether a scan was running
+/// * `Err(SentinelError)` - Process not found
+#[tauri::command]
+pub async fn cancel_process_disk_usage(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<bool, SentinelError> {
+    let (cwd, _) = process_workspace(&state, &name).await?;
+    Ok(state.disk_usage.lock().await.cancel_scan(&cwd))
+}
+
+/// Deletes cache directories from a process's working directory.
+///
+/// Only the framework's cache directories (see `settings.cacheDirs`) can be
+/// removed, and only when they are real directories inside the working
+/// directory.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `paths` - Cache directories relative to the working directory
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(CleanReport)` - Removed directories and bytes freed
+/// * `Err(SentinelError)` - A path isn't a cache directory or leaves the working directory
+#[tauri::command]
+pub async fn clean_process_cache(
+    name: String,
+    paths: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<CleanReport, SentinelError> {
+    let (cwd, cache_dirs) = process_workspace(&state, &name).await?;
+    let report = tokio::task::spawn_blocking({
+        let cwd = cwd.clone();
+        move || disk_usage::clean(&cwd, &paths, &cache_dirs)
+    })
+    .await
+    .map_err(|e| SentinelError::Other(format!("Cleaning caches failed: {}", e)))??;
+
+    state.disk_usage.lock().await.invalidate(&cwd);
+    Ok(report)
+}
+
+/// Deletes the rotated process log files that `settings.logRetention` no
+/// longer keeps, without waiting for the daily pruning.
+///
+/// # Arguments
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(LogPruneSummary)` - Files removed and bytes reclaimed
+/// * `Err(SentinelError)` - The log directory couldn't be read
+#[tauri::command]
+pub async fn prune_logs_now(state: State<'_, AppState>) -> Result<LogPruneSummary, SentinelError> {
+    let settings = state.process_manager.settings();
+    let recorder = state.event_recorder.clone();
+    tokio::task::spawn_blocking(move || {
+        log_retention::prune_logs(&settings, &recorder, EventActor::User)
+    })
+    .await
+    .map_err(|e| SentinelError::Other(format!("Pruning logs failed: {}", e)))?
+}
+
+/// Restricts a running process to the given CPU cores.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `cores` - 0-based core indices
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ProcessInfo)` - Process info
+/// * `Err(SentinelError)` - Process not running, unknown core or unsupported platform
+#[tauri::command]
+pub async fn set_process_affinity(
+    name: String,
+    cores: Vec<usize>,
+    state: State<'_, AppState>,
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.set_affinity(&name, cores).await
 }
 
 /// Lists all processes.
 ///
 /// # Arguments
+/// * `include_history` - Add the last 30 CPU and memory samples of each process (default: false)
 /// * `state` - Application state
 ///
 /// # Returns
 /// Vector of all process information
 #[tauri::command]
-pub async fn list_processes(state: State<'_, AppState>) -> Result<Vec<ProcessInfo>, String> {
-    let mut manager = state.process_manager.lock().await;
+pub async fn list_processes(
+    include_history: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProcessInfo>, SentinelError> {
+    let manager = &state.process_manager;
     // Update CPU and memory usage before returning list
-    manager.update_resource_usage();
-    Ok(manager.list())
+    manager.update_resource_usage().await;
+    if include_history.unwrap_or(false) {
+        Ok(manager.list_with_history().await)
+    } else {
+        Ok(manager.list().await)
+    }
+}
+
+/// Lists piped, PTY and external processes together.
+///
+/// # Arguments
+/// * `state` - Application state
+///
+/// # Returns
+/// Summaries of all processes, tagged with the kind of manager that runs them
+#[tauri::command]
+pub async fn list_all_managed_processes(
+    state: State<'_, AppState>,
+) -> Result<Vec<ManagedProcessSummary>, SentinelError> {
+    Ok(state.process_registry.list().await)
+}
+
+/// Lists a process started outside Sentinel with the managed processes.
+///
+/// Sentinel shows its resource usage and ports and can stop it, but never
+/// restarts it. It stays listed until it exits or is stopped.
+///
+/// # Arguments
+/// * `pid` - OS process ID
+/// * `display_name` - Name to list the process under
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ManagedProcessSummary)` - The process as listed
+/// * `Err(SentinelError)` - No such process, or it is already listed
+#[tauri::command]
+pub async fn monitor_external_process(
+    pid: u32,
+    display_name: String,
+    state: State<'_, AppState>,
+) -> Result<ManagedProcessSummary, SentinelError> {
+    state
+        .process_registry
+        .monitor_external(pid, &display_name)
+        .await
+}
+
+/// Stops a piped, PTY or external process.
+///
+/// # Arguments
+/// * `kind` - Kind of the process
+/// * `id` - Process name, PTY process ID or external display name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(())` - Process stopped
+/// * `Err(SentinelError)` - Error message
+#[tauri::command]
+pub async fn stop_any_process(
+    kind: ProcessKind,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), SentinelError> {
+    state.process_registry.stop(kind, &id).await
+}
+
+/// Restarts a piped or PTY process.
+///
+/// External processes can't be restarted.
+///
+/// # Arguments
+/// * `kind` - Kind of the process
+/// * `id` - Process name or PTY process ID
+/// * `app` - App handle, for PTY output events
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(())` - Process restarted
+/// * `Err(SentinelError)` - Error message
+#[tauri::command]
+pub async fn restart_any_process(
+    kind: ProcessKind,
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), SentinelError> {
+    state.process_registry.restart(kind, &id, app).await
 }
 
 /// Stops all running processes.
@@ -121,11 +475,267 @@ pub async fn list_processes(state: State<'_, AppState>) -> Result<Vec<ProcessInf
 ///
 /// # Returns
 /// * `Ok(())` - All processes stopped
-/// * `Err(String)` - Error message
+/// * `Err(SentinelError)` - Error message
 #[tauri::command]
-pub async fn stop_all_processes(state: State<'_, AppState>) -> Result<(), String> {
-    let mut manager = state.process_manager.lock().await;
-    manager.stop_all().await.map_err(|e| e.to_string())
+pub async fn stop_all_processes(state: State<'_, AppState>) -> Result<(), SentinelError> {
+    state.process_manager.stop_all().await
+}
+
+/// Starts the processes in the config file, dependencies first, and
+/// reports what came up.
+///
+/// The report is also recorded in the event journal.
+///
+/// # Arguments
+/// * `profiles` - Only start processes in these profiles; all if empty
+/// * `state` - Application state
+/// * `capabilities` - Finds the ports the processes listen on
+///
+/// # Returns
+/// * `Ok(StartupReport)` - How far each process got and how long it took
+/// * `Err(SentinelError)` - The config file can't be loaded or a profile is unknown
+#[tauri::command]
+pub async fn start_all_processes(
+    profiles: Option<Vec<String>>,
+    state: State<'_, AppState>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<StartupReport, SentinelError> {
+    let config_path = get_config_path();
+    let config = ConfigManager::load_from_file(&config_path)?;
+    let processes = ConfigManager::select_processes(&config, &profiles.unwrap_or_default())?;
+
+    let started_at = Utc::now();
+    let results = state.process_manager.start_all(processes.clone()).await;
+    let report = startup_report::collect(
+        &state.process_manager,
+        &processes,
+        &results,
+        started_at,
+        capabilities.scanner().as_ref(),
+    )
+    .await;
+    state
+        .event_recorder
+        .record(report.journal_event(config_path.display().to_string(), EventActor::User));
+    Ok(report)
+}
+
+/// Starts every process with a tag, dependencies first.
+///
+/// Tagged processes come from the config file and from the processes
+/// already managed. Dependencies without the tag must already be running.
+///
+/// # Arguments
+/// * `tag` - Tag to start
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<BulkOutcome>)` - Outcome per process, in start order
+/// * `Err(SentinelError)` - Unknown tag or a dependency outside the tag isn't running
+#[tauri::command]
+pub async fn start_processes_by_tag(
+    tag: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<BulkOutcome>, SentinelError> {
+    let config_path = get_config_path();
+    let configs = if config_path.exists() {
+        ConfigManager::load_from_file(&config_path)?.processes
+    } else {
+        vec![]
+    };
+
+    let results = state.process_manager.start_tagged(configs, &tag).await?;
+    Ok(results.into_iter().map(BulkOutcome::from).collect())
+}
+
+/// Stops every running process with a tag, dependents first.
+///
+/// # Arguments
+/// * `tag` - Tag to stop
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<BulkOutcome>)` - Outcome per process, in stop order
+/// * `Err(SentinelError)` - No managed process has the tag
+#[tauri::command]
+pub async fn stop_processes_by_tag(
+    tag: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<BulkOutcome>, SentinelError> {
+    let results = state.process_manager.stop_tagged(&tag).await?;
+    Ok(results.into_iter().map(BulkOutcome::from).collect())
+}
+
+/// Restarts every managed process with a tag, respecting dependency order.
+///
+/// # Arguments
+/// * `tag` - Tag to restart
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<BulkOutcome>)` - Outcome per process, in start order
+/// * `Err(SentinelError)` - Unknown tag or a dependency outside the tag isn't running
+#[tauri::command]
+pub async fn restart_processes_by_tag(
+    tag: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<BulkOutcome>, SentinelError> {
+    let results = state.process_manager.restart_tagged(&tag).await?;
+    Ok(results.into_iter().map(BulkOutcome::from).collect())
+}
+
+/// Lists the next times a scheduled process will start.
+///
+/// Looks the process up in the loaded configuration, then in the config file.
+///
+/// # Arguments
+/// * `name` - Name of the process
+/// * `count` - Number of fire times to list
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<DateTime<Local>>)` - Upcoming fire times in local time
+/// * `Err(SentinelError)` - Process not found, without a schedule or with an invalid one
+#[tauri::command]
+pub async fn get_schedule_preview(
+    name: String,
+    count: usize,
+    state: State<'_, AppState>,
+) -> Result<Vec<DateTime<Local>>, SentinelError> {
+    let loaded = state
+        .config
+        .read()
+        .await
+        .as_ref()
+        .and_then(|config| config.processes.iter().find(|p| p.name == name).cloned());
+    let process = match loaded {
+        Some(process) => Some(process),
+        None => {
+            let config_path = get_config_path();
+            if config_path.exists() {
+                ConfigManager::load_from_file(&config_path)?
+                    .processes
+                    .into_iter()
+                    .find(|p| p.name == name)
+            } else {
+                None
+            }
+        }
+    };
+
+    let process = process.ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })?;
+    let schedule = process
+        .schedule
+        .ok_or_else(|| SentinelError::Other(format!("Process '{}' has no schedule", name)))?;
+    scheduler::upcoming(&schedule, &Local::now(), count)
+}
+
+/// Waits for a running process to become ready.
+///
+/// The process manager isn't locked while waiting, so other commands keep
+/// working.
+///
+/// # Arguments
+/// * `name` - Name of the process
+/// * `timeout_ms` - How long to wait, in milliseconds
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ProcessInfo)` - Process information once ready
+/// * `Err(SentinelError)` - Not running, or not ready within the timeout
+#[tauri::command]
+pub async fn wait_for_ready(
+    name: String,
+    timeout_ms: u64,
+    state: State<'_, AppState>,
+) -> Result<ProcessInfo, SentinelError> {
+    let watched = state.process_manager.readiness(&name).await?;
+    readiness::wait(&name, watched, Duration::from_millis(timeout_ms)).await?;
+
+    state
+        .process_manager
+        .list()
+        .await
+        .into_iter()
+        .find(|info| info.name == name)
+        .ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })
+}
+
+/// Writes text to the stdin of a running process.
+///
+/// The process must have been started with `stdinMode: piped`.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `data` - Text to write, including any trailing newline
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(())` - Data queued for writing
+/// * `Err(SentinelError)` - Process not found, not running or without piped stdin
+#[tauri::command]
+pub async fn write_process_stdin(
+    name: String,
+    data: String,
+    state: State<'_, AppState>,
+) -> Result<(), SentinelError> {
+    state
+        .process_manager
+        .write_stdin(&name, data.into_bytes())
+        .await
+}
+
+/// Sends a signal such as `HUP` or `SIGUSR1` to a running process (Unix only).
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `signal` - Signal name, with or without the `SIG` prefix
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(())` - Signal sent
+/// * `Err(SentinelError)` - Process not running, unknown signal or unsupported platform
+#[tauri::command]
+pub async fn send_process_signal(
+    name: String,
+    signal: String,
+    state: State<'_, AppState>,
+) -> Result<(), SentinelError> {
+    state.process_manager.send_signal(&name, &signal).await
+}
+
+/// Pauses a running process and its children, keeping their memory.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ProcessInfo)` - The paused process
+/// * `Err(SentinelError)` - Process not running or unsupported platform
+#[tauri::command]
+pub async fn pause_process(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.pause(&name).await
+}
+
+/// Resumes a paused process and its children.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(ProcessInfo)` - The resumed process
+/// * `Err(SentinelError)` - Process not running or unsupported platform
+#[tauri::command]
+pub async fn resume_process(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<ProcessInfo, SentinelError> {
+    state.process_manager.resume(&name).await
 }
 
 /// Gets all logs for a process.
@@ -136,17 +746,17 @@ pub async fn stop_all_processes(state: State<'_, AppState>) -> Result<(), String
 ///
 /// # Returns
 /// * `Ok(Vec<LogLine>)` - All log lines
-/// * `Err(String)` - Process not found
+/// * `Err(SentinelError)` - Process not found
 #[tauri::command]
 pub async fn get_process_logs(
     name: String,
     state: State<'_, AppState>,
-) -> Result<Vec<LogLine>, String> {
-    let manager = state.process_manager.lock().await;
-    manager
+) -> Result<Vec<LogLine>, SentinelError> {
+    state
+        .process_manager
         .get_logs(&name)
         .await
-        .ok_or_else(|| format!("Process '{}' not found", name))
+        .ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })
 }
 
 /// Gets the most recent N logs for a process.
@@ -158,18 +768,18 @@ pub async fn get_process_logs(
 ///
 /// # Returns
 /// * `Ok(Vec<LogLine>)` - Recent log lines
-/// * `Err(String)` - Process not found
+/// * `Err(SentinelError)` - Process not found
 #[tauri::command]
 pub async fn get_recent_process_logs(
     name: String,
     count: usize,
     state: State<'_, AppState>,
-) -> Result<Vec<LogLine>, String> {
-    let manager = state.process_manager.lock().await;
-    manager
+) -> Result<Vec<LogLine>, SentinelError> {
+    state
+        .process_manager
         .get_recent_logs(&name, count)
         .await
-        .ok_or_else(|| format!("Process '{}' not found", name))
+        .ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })
 }
 
 /// Searches logs for a process.
@@ -181,18 +791,133 @@ pub async fn get_recent_process_logs(
 ///
 /// # Returns
 /// * `Ok(Vec<LogLine>)` - Matching log lines
-/// * `Err(String)` - Process not found
+/// * `Err(SentinelError)` - Process not found
 #[tauri::command]
 pub async fn search_process_logs(
     name: String,
     query: String,
     state: State<'_, AppState>,
-) -> Result<Vec<LogLine>, String> {
-    let manager = state.process_manager.lock().await;
-    manager
+) -> Result<Vec<LogLine>, SentinelError> {
+    state
+        .process_manager
         .search_logs(&name, &query)
         .await
-        .ok_or_else(|| format!("Process '{}' not found", name))
+        .ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })
+}
+
+/// Gets the structured (JSON) log lines of a process, filtered by field.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `filter` - Field values the lines must have, e.g. `{"request_id": "abc"}`;
+///   `level` matches the parsed level. None returns every structured line.
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<LogLine>)` - Matching structured lines
+/// * `Err(SentinelError)` - Process not found
+#[tauri::command]
+pub async fn get_structured_logs(
+    name: String,
+    filter: Option<HashMap<String, String>>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LogLine>, SentinelError> {
+    state
+        .process_manager
+        .get_structured_logs(&name, &filter.unwrap_or_default())
+        .await
+        .ok_or_else(|| SentinelError::ProcessNotFound { name: name.clone() })
+}
+
+/// Searches the logs of every managed process, and optionally of attached
+/// external processes.
+///
+/// # Arguments
+/// * `query` - Text to find, or a regular expression with `options.regex`
+/// * `options` - Case sensitivity, time range, stream, context lines and limits
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<ProcessLogMatches>)` - Matches grouped by process, managed processes first
+/// * `Err(SentinelError)` - Invalid or too complex pattern
+#[tauri::command]
+pub async fn search_all_logs(
+    query: String,
+    options: Option<LogSearchOptions>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProcessLogMatches>, SentinelError> {
+    let query = LogQuery::new(&query, options.unwrap_or_default())?;
+
+    let mut results = state.process_manager.search_all_logs(&query).await;
+    if query.options().include_attachments {
+        let monitor = state.external_process_monitor.lock().await;
+        results.extend(monitor.search_logs(&query).await);
+    }
+    Ok(results)
+}
+
+/// Gets the logs of several processes as one list, in time order.
+///
+/// Lines are ordered by the time written at their start when they have
+/// one, so a source that is read late still lands where it belongs.
+///
+/// # Arguments
+/// * `names` - Processes to include; all managed processes when omitted
+/// * `order_by` - `source` (default) or `received`; also what `since` and `until` apply to
+/// * `since` - Only lines at or after this time
+/// * `until` - Only lines at or before this time
+/// * `include_attachments` - Also include the lines of attached external processes
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(Vec<MergedLogLine>)` - Lines oldest first, each with its process
+/// * `Err(SentinelError)` - Unknown process name
+#[tauri::command]
+pub async fn get_merged_logs(
+    names: Option<Vec<String>>,
+    order_by: Option<TimestampOrder>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    include_attachments: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<MergedLogLine>, SentinelError> {
+    let order = order_by.unwrap_or_default();
+    let mut lines = state.process_manager.merged_logs(names.as_deref()).await?;
+    if include_attachments.unwrap_or(false) {
+        let monitor = state.external_process_monitor.lock().await;
+        lines.extend(monitor.merged_logs().await);
+    }
+
+    lines.retain(|merged| {
+        let at = merged.line.ordered_at(order);
+        since.is_none_or(|since| at >= since) && until.is_none_or(|until| at <= until)
+    });
+    log_timestamp::sort_lines(&mut lines, order);
+    Ok(lines)
+}
+
+/// Gets the memory held by the log buffers of all processes.
+///
+/// # Arguments
+/// * `state` - Application state
+///
+/// # Returns
+/// Totals and each buffer's size and limits, largest first, including the
+/// buffers of attached external processes
+#[tauri::command]
+pub async fn get_log_memory_stats(
+    state: State<'_, AppState>,
+) -> Result<LogMemoryStats, SentinelError> {
+    let mut buffers = state.process_manager.log_memory_usage().await;
+    buffers.extend(
+        state
+            .external_process_monitor
+            .lock()
+            .await
+            .log_memory_usage()
+            .await,
+    );
+    Ok(LogMemoryStats::new(buffers))
 }
 
 /// Checks health of all processes and auto-restarts crashed ones.
@@ -206,11 +931,12 @@ pub async fn search_process_logs(
 ///
 /// # Returns
 /// * `Ok(Vec<String>)` - List of process names that were restarted
-/// * `Err(String)` - Error message
+/// * `Err(SentinelError)` - Error message
 #[tauri::command]
-pub async fn check_process_health(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    let mut manager = state.process_manager.lock().await;
-    Ok(manager.check_health().await)
+pub async fn check_process_health(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, SentinelError> {
+    Ok(state.process_manager.check_health().await)
 }
 
 /// Gracefully stops a process with timeout and force kill fallback.
@@ -224,17 +950,13 @@ pub async fn check_process_health(state: State<'_, AppState>) -> Result<Vec<Stri
 ///
 /// # Returns
 /// * `Ok(())` - Process stopped gracefully
-/// * `Err(String)` - Error message
+/// * `Err(SentinelError)` - Error message
 #[tauri::command]
 pub async fn stop_process_gracefully(
     name: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let mut manager = state.process_manager.lock().await;
-    manager
-        .stop_gracefully(&name)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<(), SentinelError> {
+    state.process_manager.stop_gracefully(&name).await
 }
 
 /// Clears all buffered logs for a process.
@@ -245,11 +967,13 @@ pub async fn stop_process_gracefully(
 ///
 /// # Returns
 /// * `Ok(())` - Logs cleared
-/// * `Err(String)` - Process not found
+/// * `Err(SentinelError)` - Process not found
 #[tauri::command]
-pub async fn clear_process_logs(name: String, state: State<'_, AppState>) -> Result<(), String> {
-    let manager = state.process_manager.lock().await;
-    manager.clear_logs(&name).await.map_err(|e| e.to_string())
+pub async fn clear_process_logs(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<(), SentinelError> {
+    state.process_manager.clear_logs(&name).await
 }
 
 /// Gets the default config file path.
@@ -260,7 +984,7 @@ pub async fn clear_process_logs(name: String, state: State<'_, AppState>) -> Res
 ///
 /// # Returns
 /// Path to config file (may not exist yet)
-fn get_config_path() -> PathBuf {
+pub(crate) fn get_config_path() -> PathBuf {
     // Try user config directory first
     if let Some(config_dir) = dirs::config_dir() {
         let sentinel_dir = config_dir.join("sentinel");
@@ -283,9 +1007,14 @@ fn get_config_path() -> PathBuf {
 ///
 /// # Returns
 /// * `Ok(Config)` - Loaded configuration
-/// * `Err(String)` - Error loading config
+/// * `Err(SentinelError)` - Error loading config
 #[tauri::command]
-pub async fn load_config(path: Option<String>) -> Result<Config, String> {
+pub async fn load_config(
+    path: Option<String>,
+    state: State<'_, AppState>,
+    network: State<'_, NetworkMonitorState>,
+    services: State<'_, ServiceDetectorState>,
+) -> Result<Config, SentinelError> {
     let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
 
     // If file doesn't exist, return default config
@@ -293,7 +1022,86 @@ pub async fn load_config(path: Option<String>) -> Result<Config, String> {
         return Ok(ConfigManager::default_config());
     }
 
-    ConfigManager::load_from_file(&config_path).map_err(|e| e.to_string())
+    let config = ConfigManager::load_from_file(&config_path)?;
+    apply_config(&state, &network, &services, &config).await?;
+    state.event_recorder.record(JournalEvent::new(
+        EventType::ConfigLoaded,
+        config_path.display().to_string(),
+        EventActor::User,
+    ));
+    Ok(secrets::mask_config(&config))
+}
+
+/// Reloads the config file and applies its settings without restarting.
+///
+/// The log level, restart policy defaults, log buffer size, health check
+/// interval, network interface filter and service cache TTL take effect
+/// immediately. Running processes keep their command, environment and other
+/// per-process settings until they are restarted.
+///
+/// # Arguments
+/// * `path` - Optional custom path. If None, uses default location.
+///
+/// # Returns
+/// * `Ok(Config)` - The reloaded configuration
+/// * `Err(SentinelError)` - Error loading config or applying its settings
+#[tauri::command]
+pub async fn reload_config(
+    path: Option<String>,
+    state: State<'_, AppState>,
+    network: State<'_, NetworkMonitorState>,
+    services: State<'_, ServiceDetectorState>,
+) -> Result<Config, SentinelError> {
+    let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
+
+    let config = if config_path.exists() {
+        ConfigManager::load_from_file(&config_path)?
+    } else {
+        ConfigManager::default_config()
+    };
+
+    if let Some(previous) = state.config.read().await.as_ref() {
+        tracing::info!(
+            "Config reloaded: {}",
+            ConfigDiff::between(previous, &config).summary()
+        );
+    }
+    apply_config(&state, &network, &services, &config).await?;
+    state.event_recorder.record(JournalEvent::new(
+        EventType::ConfigReloaded,
+        config_path.display().to_string(),
+        EventActor::User,
+    ));
+    Ok(secrets::mask_config(&config))
+}
+
+/// Makes `config` the current configuration and applies its global settings.
+async fn apply_config(
+    state: &AppState,
+    network: &NetworkMonitorState,
+    services: &ServiceDetectorState,
+    config: &Config,
+) -> Result<(), SentinelError> {
+    logging::set_log_level(&config.settings.log_level)?;
+    messages::set_locale(config.settings.locale.as_deref());
+    network
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .set_interface_filter(config.settings.network.clone());
+    services
+        .lock()
+        .set_cache_ttl(Duration::from_secs(config.settings.service_cache_ttl_secs));
+    state
+        .process_manager
+        .apply_settings(config.settings.clone())
+        .await;
+    state
+        .process_manager
+        .apply_log_limits(&config.processes)
+        .await;
+    *state.config.write().await = Some(config.clone());
+    Ok(())
 }
 
 /// Saves a process to the config file.
@@ -304,38 +1112,45 @@ pub async fn load_config(path: Option<String>) -> Result<Config, String> {
 ///
 /// # Returns
 /// * `Ok(())` - Process saved successfully
-/// * `Err(String)` - Error saving config
+/// * `Err(SentinelError)` - Error saving config
 #[tauri::command]
 pub async fn save_process_to_config(
     process_config: ProcessConfig,
     path: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), SentinelError> {
     let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
 
     // Ensure parent directory exists
     if let Some(parent) = config_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        std::fs::create_dir_all(parent).map_err(|e| SentinelError::FileIoError {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
     }
 
     // Load existing config or create new
     let mut config = if config_path.exists() {
-        ConfigManager::load_from_file(&config_path).map_err(|e| e.to_string())?
+        ConfigManager::load_from_file(&config_path)?
     } else {
         Config {
             processes: vec![],
             settings: Default::default(),
             global_env: Default::default(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
         }
     };
 
     // Check if process already exists
+    let mut process_config = process_config;
     if let Some(existing) = config
         .processes
         .iter_mut()
         .find(|p| p.name == process_config.name)
     {
-        // Update existing process
+        // Update existing process, keeping secrets the frontend only saw masked
+        secrets::restore_masked(&mut process_config.env, &existing.env);
         *existing = process_config;
     } else {
         // Add new process
@@ -343,7 +1158,74 @@ pub async fn save_process_to_config(
     }
 
     // Save config
-    ConfigManager::save_to_file(&config, &config_path).map_err(|e| e.to_string())
+    ConfigManager::save_to_file(&config, &config_path)
+}
+
+/// Duplicates a process configuration under a new name, with overrides.
+///
+/// The source is looked up in the loaded config, then the config file. The
+/// copy's ports are checked against every configured and running process
+/// before anything is saved or started.
+///
+/// # Arguments
+/// * `name` - Process to copy
+/// * `new_name` - Name of the copy
+/// * `overrides` - Environment, port, arguments and working directory to change
+/// * `save` - Also save the copy to the config file
+/// * `start` - Also start the copy
+/// * `path` - Optional custom config path
+///
+/// # Returns
+/// * `Ok(ProcessConfig)` - The copy, with secret values masked
+/// * `Err(SentinelError)` - Source not found, name taken, invalid copy or port conflict
+#[tauri::command]
+pub async fn clone_process(
+    name: String,
+    new_name: String,
+    overrides: ProcessOverrides,
+    save: Option<bool>,
+    start: Option<bool>,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ProcessConfig, SentinelError> {
+    let loaded = state.config.read().await.clone();
+    let mut config = match loaded {
+        Some(config) if path.is_none() => config,
+        _ => {
+            let config_path = path
+                .clone()
+                .map(PathBuf::from)
+                .unwrap_or_else(get_config_path);
+            if config_path.exists() {
+                ConfigManager::load_from_file(&config_path)?
+            } else {
+                ConfigManager::default_config()
+            }
+        }
+    };
+
+    // Processes started without being saved can clash as well
+    for info in state.process_manager.list().await {
+        if config.processes.iter().any(|p| p.name == info.name) {
+            continue;
+        }
+        if let Some(running) = state.process_manager.get_config(&info.name).await {
+            config.processes.push(running);
+        }
+    }
+
+    let copy = ConfigManager::clone_process(&config, &name, &new_name, &overrides)?;
+
+    if save.unwrap_or(false) {
+        save_process_to_config(copy.clone(), path).await?;
+    }
+    if start.unwrap_or(false) {
+        state.process_manager.start(copy.clone()).await?;
+    }
+
+    let mut masked = copy;
+    masked.env = secrets::mask_env(&masked.env, &config.settings.secret_patterns);
+    Ok(masked)
 }
 
 /// Removes a process from the config file.
@@ -354,35 +1236,165 @@ pub async fn save_process_to_config(
 ///
 /// # Returns
 /// * `Ok(())` - Process removed successfully
-/// * `Err(String)` - Error updating config
+/// * `Err(SentinelError)` - Error updating config
 #[tauri::command]
-pub async fn remove_process_from_config(name: String, path: Option<String>) -> Result<(), String> {
+pub async fn remove_process_from_config(
+    name: String,
+    path: Option<String>,
+) -> Result<(), SentinelError> {
     let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
 
     // Load existing config
     if !config_path.exists() {
-        return Err(format!(
+        return Err(SentinelError::Other(format!(
             "Process '{}' is not saved to config. No config file exists at {}",
             name,
             config_path.display()
-        ));
+        )));
     }
 
-    let mut config = ConfigManager::load_from_file(&config_path).map_err(|e| e.to_string())?;
+    let mut config = ConfigManager::load_from_file(&config_path)?;
 
     // Remove process
     let original_len = config.processes.len();
     config.processes.retain(|p| p.name != name);
 
     if config.processes.len() == original_len {
-        return Err(format!(
+        return Err(SentinelError::Other(format!(
             "Process '{}' not found in config file. It may have been started without saving to config.",
             name
-        ));
+        )));
     }
 
     // Save updated config
-    ConfigManager::save_to_file(&config, &config_path).map_err(|e| e.to_string())
+    ConfigManager::save_to_file(&config, &config_path)
+}
+
+/// Validates a config file and reports every problem found.
+///
+/// # Arguments
+/// * `path` - Optional custom path. If None, uses default location.
+///
+/// # Returns
+/// * `Ok(Vec<ConfigIssue>)` - Problems found (empty if the config is valid)
+#[tauri::command]
+pub async fn validate_config(path: Option<String>) -> Result<Vec<ConfigIssue>, SentinelError> {
+    let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
+    Ok(ConfigManager::validate_file(&config_path))
+}
+
+/// Shows a config file as its processes see it, with `defaults` and
+/// `extends` merged in.
+///
+/// # Arguments
+/// * `path` - Optional custom path. If None, uses default location.
+///
+/// # Returns
+/// * `Ok(String)` - The resolved config as YAML, with secret values masked
+/// * `Err(SentinelError)` - Error loading config, e.g. an unknown `extends`
+#[tauri::command]
+pub async fn show_resolved_config(path: Option<String>) -> Result<String, SentinelError> {
+    let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
+    let config = ConfigManager::load_from_file(&config_path)?;
+    ConfigManager::resolved_yaml(&secrets::mask_config(&config))
+}
+
+/// Gets the JSON Schema of config files, for editor autocompletion.
+///
+/// # Returns
+/// * `Ok(Value)` - The schema, with a description for every field
+#[tauri::command]
+pub async fn get_config_schema() -> Result<serde_json::Value, SentinelError> {
+    Ok(schema::config_schema())
+}
+
+/// Converts a PM2 ecosystem file or Docker Compose file into a Sentinel config.
+///
+/// # Arguments
+/// * `source` - Format of the file (`pm2` or `compose`)
+/// * `path` - File to import
+/// * `output` - Where to write the generated config; only previewed if None
+/// * `force` - Overwrite `output` if it already exists
+///
+/// # Returns
+/// * `Ok(ImportReport)` - Generated config and the options that weren't imported
+/// * `Err(SentinelError)` - File unreadable or unparseable, or `output` already exists
+#[tauri::command]
+pub async fn import_config(
+    source: ImportSource,
+    path: String,
+    output: Option<String>,
+    force: bool,
+) -> Result<ImportReport, SentinelError> {
+    let mut report = ImportReport::from_file(source, &PathBuf::from(path))?;
+    if let Some(output) = output {
+        report.write(&PathBuf::from(output), force)?;
+    }
+
+    report.config = secrets::mask_config(&report.config);
+    Ok(report)
+}
+
+/// Reveals the real value of a masked environment variable.
+///
+/// Values of secret-looking keys are masked in every other response. Only
+/// call this in response to an explicit user action, such as clicking "show"
+/// next to a masked value; each call is logged (without the value).
+///
+/// Looks in the running process first, then the loaded config file, then
+/// the managed process configurations.
+///
+/// # Arguments
+/// * `name` - Process name
+/// * `key` - Environment variable name
+///
+/// # Returns
+/// * `Ok(String)` - The unmasked value
+/// * `Err(SentinelError)` - Process or variable not found
+#[tauri::command]
+pub async fn reveal_process_env_value(
+    name: String,
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<String, SentinelError> {
+    let mut value = state
+        .process_manager
+        .get_config(&name)
+        .await
+        .and_then(|config| config.env.get(&key).cloned());
+
+    if value.is_none() {
+        value = state.config.read().await.as_ref().and_then(|config| {
+            config
+                .processes
+                .iter()
+                .find(|p| p.name == name)
+                .and_then(|p| p.env.get(&key).cloned())
+        });
+    }
+
+    if value.is_none() {
+        value = state
+            .process_config_store
+            .lock()
+            .await
+            .list()
+            .await
+            .into_iter()
+            .find(|c| c.name == name)
+            .and_then(|c| c.env_vars.get(&key).cloned());
+    }
+
+    match value {
+        Some(value) => {
+            tracing::info!("Revealed env var '{}' of process '{}'", key, name);
+            Ok(value)
+        }
+        None => Err(SentinelError::Other(format!(
+            "Process '{}' has no environment variable '{}'",
+            name, key
+        ))),
+    }
 }
 
 /// Gets the current config file path.
@@ -390,7 +1402,7 @@ pub async fn remove_process_from_config(name: String, path: Option<String>) -> R
 /// # Returns
 /// Path to the config file that would be used
 #[tauri::command]
-pub async fn get_config_file_path() -> Result<String, String> {
+pub async fn get_config_file_path() -> Result<String, SentinelError> {
     Ok(get_config_path().to_string_lossy().to_string())
 }
 
@@ -405,15 +1417,19 @@ pub async fn get_config_file_path() -> Result<String, String> {
 /// # Arguments
 /// * `state` - Application state
 /// * `auto_start_only` - If true, only starts processes marked with auto_restart
+/// * `profiles` - Profiles to start (defaults to `settings.defaultProfiles`)
 ///
 /// # Returns
 /// * `Ok(Vec<String>)` - Names of processes that were started
-/// * `Err(String)` - Error loading config or starting processes
+/// * `Err(SentinelError)` - Error loading config or starting processes
 #[tauri::command]
 pub async fn start_processes_from_config(
     state: State<'_, AppState>,
+    network: State<'_, NetworkMonitorState>,
+    services: State<'_, ServiceDetectorState>,
     auto_start_only: Option<bool>,
-) -> Result<Vec<String>, String> {
+    profiles: Option<Vec<String>>,
+) -> Result<Vec<String>, SentinelError> {
     use crate::core::{ConfigManager, StateManager};
     use crate::models::ProcessRuntimeInfo;
     use sysinfo::{Pid, ProcessRefreshKind, System};
@@ -425,10 +1441,14 @@ pub async fn start_processes_from_config(
         return Ok(vec![]); // No config file, nothing to start
     }
 
-    let config = ConfigManager::load_from_file(&config_path).map_err(|e| e.to_string())?;
+    let config = ConfigManager::load_from_file(&config_path)?;
+    apply_config(&state, &network, &services, &config).await?;
+
+    // Only the processes in the requested profiles, dependencies first
+    let processes = ConfigManager::select_processes(&config, &profiles.unwrap_or_default())?;
 
     // Load runtime state
-    let mut runtime_state = StateManager::load().map_err(|e| e.to_string())?;
+    let mut runtime_state = StateManager::load()?;
 
     // Get system info to check running processes
     let mut sys = System::new();
@@ -439,21 +1459,23 @@ pub async fn start_processes_from_config(
     );
 
     let mut started = Vec::new();
-    let mut manager = state.process_manager.lock().await;
+    let manager = &state.process_manager;
 
-    for process_config in config.processes {
+    for process_config in processes {
         // Skip if auto_start_only is true and process doesn't have auto_restart
         let should_auto_start = auto_start_only.unwrap_or(false);
-        if should_auto_start && !process_config.auto_restart {
+        if should_auto_start && !process_config.effective_auto_restart(&config.settings) {
             continue;
         }
 
         // Check runtime state
         let is_running = if let Some(runtime_info) = runtime_state.get_process(&process_config.name)
         {
-            // Check if PID from state is still running
+            // Check if PID from state is still running the same binary, as
+            // PIDs get reused
             if let Some(pid) = runtime_info.pid {
-                sys.process(Pid::from_u32(pid)).is_some()
+                sys.process(Pid::from_u32(pid))
+                    .is_some_and(|process| runtime_info.runs_executable(process.exe()))
             } else {
                 false
             }
@@ -467,10 +1489,10 @@ pub async fn start_processes_from_config(
                 Ok(info) => {
                     // Update runtime state
                     if let Some(pid) = info.pid {
-                        let config_hash = format!("{:?}", process_config); // Simple hash
                         runtime_state.upsert_process(
                             process_config.name.clone(),
-                            ProcessRuntimeInfo::new(pid, config_hash),
+                            ProcessRuntimeInfo::new(pid, process_config.config_hash())
+                                .with_command(info.exe_path.map(PathBuf::from), info.argv),
                         );
                     }
                     started.push(process_config.name);
@@ -484,7 +1506,7 @@ pub async fn start_processes_from_config(
 
     // Save updated state
     if !started.is_empty() {
-        StateManager::save(&runtime_state).map_err(|e| e.to_string())?;
+        StateManager::save(&runtime_state)?;
     }
 
     Ok(started)
@@ -493,7 +1515,6 @@ pub async fn start_processes_from_config(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
 
     #[allow(dead_code)]
     fn test_state() -> AppState {
@@ -505,14 +1526,10 @@ mod tests {
         ProcessConfig {
             name: name.to_string(),
             command: "echo test".to_string(),
-            args: vec![],
-            cwd: None,
-            env: HashMap::new(),
-            auto_restart: false,
+            auto_restart: Some(false),
             restart_limit: 0,
-            restart_delay: 100,
-            depends_on: vec![],
-            health_check: None,
+            restart_delay: Some(100),
+            ..Default::default()
         }
     }
 }
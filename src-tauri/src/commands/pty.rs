@@ -1,10 +1,14 @@
 //! PTY process management commands
 use crate::core::{ProcessInfo, PtyProcessConfig};
+use crate::models::EnvMode;
 use crate::state::AppState;
 use std::collections::HashMap;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 
 /// Spawn a new process with PTY
+///
+/// `env_mode` picks the environment the process starts from; Sentinel's own
+/// when not given.
 #[tauri::command]
 pub async fn spawn_pty_process(
     process_id: String,
@@ -12,8 +16,8 @@ pub async fn spawn_pty_process(
     args: Vec<String>,
     cwd: Option<String>,
     env: Option<HashMap<String, String>>,
+    env_mode: Option<EnvMode>,
     app: AppHandle,
-    state: State<'_, AppState>,
 ) -> Result<u32, String> {
     tracing::info!(
         "spawn_pty_process called: id={}, command={}, args={:?}",
@@ -22,13 +26,19 @@ pub async fn spawn_pty_process(
         args
     );
 
-    state
-        .pty_manager
-        .lock()
-        .await
-        .spawn_process(process_id, command, args, cwd, env, app)
-        .await
-        .map_err(|e| e.to_string())
+    let config = PtyProcessConfig {
+        process_id,
+        command,
+        args,
+        cwd,
+        env,
+        env_mode: env_mode.unwrap_or_default(),
+    };
+    // The manager is taken from the app rather than passed in, to keep the
+    // argument count down
+    let pty_manager = app.state::<AppState>().pty_manager.clone();
+    let pid = pty_manager.lock().await.spawn_process(config, app).await;
+    pid.map_err(|e| e.to_string())
 }
 
 /// Kill a PTY process
@@ -86,3 +96,51 @@ pub async fn restart_pty_process(
 pub async fn get_pty_configs(state: State<'_, AppState>) -> Result<Vec<PtyProcessConfig>, String> {
     Ok(state.pty_manager.lock().await.get_all_configs().await)
 }
+
+/// Resize the terminal of a PTY process
+#[tauri::command]
+pub async fn resize_pty(
+    process_id: String,
+    rows: u16,
+    cols: u16,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .pty_manager
+        .lock()
+        .await
+        .resize_pty(&process_id, rows, cols)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write input to the terminal of a PTY process
+#[tauri::command]
+pub async fn write_to_pty(
+    process_id: String,
+    data: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .pty_manager
+        .lock()
+        .await
+        .write_to_pty(&process_id, data.as_bytes())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the buffered output of a PTY process
+#[tauri::command]
+pub async fn get_pty_scrollback(
+    process_id: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    state
+        .pty_manager
+        .lock()
+        .await
+        .get_scrollback(&process_id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,13 @@
 //! System monitoring commands.
 
-use crate::models::SystemStats;
+use crate::core::dashboard::{self, DashboardSections, DashboardSnapshot, DashboardSources};
+use crate::core::metrics_buffer::MetricBucket;
+use crate::error::SentinelError;
+use crate::features::docker::DockerMonitorState;
+use crate::features::network_monitor::NetworkMonitorState;
+use crate::models::{
+    GpuStats, LoadAverage, SystemDetails, SystemStats, TopProcessSort, TopProcesses,
+};
 use crate::state::AppState;
 use tauri::State;
 
@@ -12,7 +19,7 @@ use tauri::State;
 /// # Returns
 /// Current system statistics (CPU, memory, disk)
 #[tauri::command]
-pub async fn get_system_stats(state: State<'_, AppState>) -> Result<SystemStats, String> {
+pub async fn get_system_stats(state: State<'_, AppState>) -> Result<SystemStats, SentinelError> {
     tracing::info!("get_system_stats command called");
     let mut monitor = state.system_monitor.lock().await;
     monitor.refresh();
@@ -28,6 +35,122 @@ pub async fn get_system_stats(state: State<'_, AppState>) -> Result<SystemStats,
     Ok(stats)
 }
 
+/// Gets CPU and memory history aggregated for charting.
+///
+/// Each bucket keeps the min and max of its samples as well as the average,
+/// so short spikes stay visible however long the range.
+///
+/// # Arguments
+/// * `duration_seconds` - Number of seconds of history to include
+/// * `max_points` - Maximum number of buckets per metric
+/// * `state` - Application state
+///
+/// # Returns
+/// CPU and memory buckets (oldest first)
+#[tauri::command]
+pub async fn get_system_history_downsampled(
+    duration_seconds: u64,
+    max_points: usize,
+    state: State<'_, AppState>,
+) -> Result<DownsampledSystemHistory, SentinelError> {
+    let monitor = state.system_monitor.lock().await;
+    Ok(DownsampledSystemHistory {
+        cpu_percent: monitor.get_cpu_history_downsampled(duration_seconds, max_points),
+        memory_bytes: monitor.get_memory_history_downsampled(duration_seconds, max_points),
+    })
+}
+
+/// Gets everything the dashboard shows in one call.
+///
+/// Replaces polling `list_processes`, `get_system_stats`, `get_network_stats`,
+/// `scan_ports` and `get_docker_info` separately. A section that fails
+/// carries its error instead of failing the whole call.
+///
+/// # Arguments
+/// * `options` - Sections to collect (default: all)
+/// * `state` - Application state
+/// * `network` - Network monitor state
+/// * `docker` - Docker monitor state
+///
+/// # Returns
+/// The requested sections, each with the time it was collected
+#[tauri::command]
+pub async fn get_dashboard_snapshot(
+    options: Option<DashboardSections>,
+    state: State<'_, AppState>,
+    network: State<'_, NetworkMonitorState>,
+    docker: State<'_, DockerMonitorState>,
+) -> Result<DashboardSnapshot, SentinelError> {
+    let sources = DashboardSources {
+        process_manager: state.process_manager.clone(),
+        system_monitor: state.system_monitor.clone(),
+        network: network.0.clone(),
+        docker: docker.0.clone(),
+    };
+    Ok(dashboard::collect_snapshot(&sources, options.unwrap_or_default()).await)
+}
+
+/// Gets system details that are needed less often than the regular stats.
+///
+/// # Arguments
+/// * `state` - Application state
+///
+/// # Returns
+/// Load averages, component temperatures and all mounted disks
+#[tauri::command]
+pub async fn get_system_details(
+    state: State<'_, AppState>,
+) -> Result<SystemDetails, SentinelError> {
+    let mut monitor = state.system_monitor.lock().await;
+    Ok(monitor.get_details())
+}
+
+/// Gets the usage of the machine's GPUs.
+///
+/// GPUs are read every few seconds in the background, so this returns the
+/// latest reading rather than waiting for a new one.
+///
+/// # Arguments
+/// * `state` - Application state
+///
+/// # Returns
+/// Utilization, video memory and temperature of each GPU; empty when no
+/// GPU can be read
+#[tauri::command]
+pub async fn get_gpu_stats(state: State<'_, AppState>) -> Result<Vec<GpuStats>, SentinelError> {
+    Ok(state.system_monitor.lock().await.gpus())
+}
+
+/// Default page size for `get_top_processes`.
+const DEFAULT_TOP_PROCESSES_LIMIT: usize = 50;
+
+/// Gets a page of the heaviest processes on the system.
+///
+/// # Arguments
+/// * `sort_by` - Column to sort by, heaviest first (default: CPU)
+/// * `limit` - Page size (default: 50)
+/// * `offset` - Number of processes to skip (default: 0)
+/// * `state` - Application state
+///
+/// # Returns
+/// The page of processes, with Sentinel-managed ones marked, and the total count
+#[tauri::command]
+pub async fn get_top_processes(
+    sort_by: Option<TopProcessSort>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<TopProcesses, SentinelError> {
+    let managed = state.process_registry.managed_pids().await;
+    let mut monitor = state.system_monitor.lock().await;
+    Ok(monitor.get_top_processes(
+        sort_by.unwrap_or_default(),
+        limit.unwrap_or(DEFAULT_TOP_PROCESSES_LIMIT),
+        offset.unwrap_or(0),
+        &managed,
+    ))
+}
+
 /// Gets resource usage for a specific process.
 ///
 /// # Arguments
@@ -36,13 +159,16 @@ pub async fn get_system_stats(state: State<'_, AppState>) -> Result<SystemStats,
 ///
 /// # Returns
 /// * `Ok((cpu_percent, memory_bytes))` - Resource usage
-/// * `Err(String)` - Process not found
+/// * `Err(SentinelError)` - Process not found
 #[tauri::command]
-pub async fn get_process_stats(pid: u32, state: State<'_, AppState>) -> Result<(f32, u64), String> {
+pub async fn get_process_stats(
+    pid: u32,
+    state: State<'_, AppState>,
+) -> Result<(f32, u64), SentinelError> {
     let monitor = state.system_monitor.lock().await;
     monitor
         .get_process_stats(pid)
-        .ok_or_else(|| format!("Process with PID {} not found", pid))
+        .ok_or_else(|| SentinelError::Other(format!("Process with PID {} not found", pid)))
 }
 
 /// Gets system information.
@@ -51,9 +177,9 @@ pub async fn get_process_stats(pid: u32, state: State<'_, AppState>) -> Result<(
 /// * `state` - Application state
 ///
 /// # Returns
-/// System information (OS name, hostname, uptime, etc.)
+/// System information (OS name, hostname, uptime, load average, etc.)
 #[tauri::command]
-pub async fn get_system_info(state: State<'_, AppState>) -> Result<SystemInfo, String> {
+pub async fn get_system_info(state: State<'_, AppState>) -> Result<SystemInfo, SentinelError> {
     let monitor = state.system_monitor.lock().await;
 
     Ok(SystemInfo {
@@ -62,6 +188,7 @@ pub async fn get_system_info(state: State<'_, AppState>) -> Result<SystemInfo, S
         hostname: monitor.hostname(),
         uptime: monitor.uptime(),
         process_count: monitor.process_count(),
+        load_average: monitor.load_average(),
     })
 }
 
@@ -73,6 +200,17 @@ pub struct SystemInfo {
     pub hostname: Option<String>,
     pub uptime: u64,
     pub process_count: usize,
+    pub load_average: Option<LoadAverage>,
+}
+
+/// Downsampled system history.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownsampledSystemHistory {
+    /// CPU usage buckets in percent (oldest first)
+    pub cpu_percent: Vec<MetricBucket>,
+    /// Memory usage buckets in bytes (oldest first)
+    pub memory_bytes: Vec<MetricBucket>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,89 @@
+//! Process template commands.
+
+use crate::core::{TemplateSource, TemplateStore, TemplateSummary, UserTemplate};
+use crate::error::SentinelError;
+use crate::models::ProcessConfig;
+use crate::state::AppState;
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+
+/// Saves a configured process as a user template.
+///
+/// # Arguments
+/// * `process` - Name of the configured process
+/// * `name` - Template name
+/// * `description` - Shown next to the template
+/// * `variables` - Variable names and the values they replace, e.g. `PORT` → `3000`
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(TemplateSummary)` - The saved template and its variables
+/// * `Err(SentinelError)` - Unknown process, invalid variable name or a built-in template's name
+#[tauri::command]
+pub async fn save_process_as_template(
+    process: String,
+    name: String,
+    description: Option<String>,
+    variables: Option<HashMap<String, String>>,
+    state: State<'_, AppState>,
+) -> Result<TemplateSummary, SentinelError> {
+    let config = state
+        .process_manager
+        .get_config(&process)
+        .await
+        .ok_or(SentinelError::ProcessNotFound { name: process })?;
+    let variables: BTreeMap<String, String> = variables.unwrap_or_default().into_iter().collect();
+
+    let template = UserTemplate::from_process(
+        &name,
+        description.as_deref().unwrap_or_default(),
+        &config,
+        &variables,
+        &state.process_manager.settings().secret_patterns,
+    )?;
+    let summary = template.summary(TemplateSource::User, None);
+    TemplateStore::default().save(template)?;
+    Ok(summary)
+}
+
+/// Lists built-in and user templates.
+///
+/// # Returns
+/// * `Ok(Vec<TemplateSummary>)` - Built-in templates first, each with its source
+/// * `Err(SentinelError)` - The user template file couldn't be read
+#[tauri::command]
+pub async fn list_templates() -> Result<Vec<TemplateSummary>, SentinelError> {
+    TemplateStore::default().list()
+}
+
+/// Fills in a template's variables.
+///
+/// The result isn't added to the config; pass it to `save_process_to_config` for that.
+///
+/// # Arguments
+/// * `name` - Template name
+/// * `vars` - Variable values; defaults are used for the rest
+///
+/// # Returns
+/// * `Ok(ProcessConfig)` - Process config that passes validation
+/// * `Err(SentinelError)` - Unknown template, variables without a value, or an invalid result
+#[tauri::command]
+pub async fn instantiate_template(
+    name: String,
+    vars: HashMap<String, String>,
+) -> Result<ProcessConfig, SentinelError> {
+    TemplateStore::default().instantiate(&name, &vars)
+}
+
+/// Deletes a user template.
+///
+/// # Arguments
+/// * `name` - Template name
+///
+/// # Returns
+/// * `Ok(())` - Template deleted
+/// * `Err(SentinelError)` - No user template with that name
+#[tauri::command]
+pub async fn delete_template(name: String) -> Result<(), SentinelError> {
+    TemplateStore::default().delete(&name)
+}
@@ -0,0 +1,94 @@
+//! Workspace export and import commands.
+
+use super::process::get_config_path;
+use crate::core::{
+    ImportMode, Workspace, WorkspaceExportReport, WorkspaceImportReport, WorkspacePaths,
+    WorkspacePreview,
+};
+use crate::error::SentinelError;
+use crate::state::AppState;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Writes the config, runtime state and saved terminals to one archive.
+///
+/// # Arguments
+/// * `path` - Archive to write (`.tar.gz`)
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(WorkspaceExportReport)` - What was exported, and files that were left out
+/// * `Err(SentinelError)` - A file couldn't be read or the archive couldn't be written
+#[tauri::command]
+pub async fn export_workspace(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceExportReport, SentinelError> {
+    let pty_configs = state.pty_manager.lock().await.get_all_configs().await;
+    let paths = WorkspacePaths::new(get_config_path());
+
+    tokio::task::spawn_blocking(move || {
+        crate::core::workspace::export(&paths, pty_configs, &PathBuf::from(path))
+    })
+    .await
+    .map_err(|e| SentinelError::Other(format!("Workspace export failed: {}", e)))?
+}
+
+/// Shows what importing a workspace archive would change.
+///
+/// # Arguments
+/// * `path` - Archive written by `export_workspace`
+/// * `mode` - `merge` or `replace`
+///
+/// # Returns
+/// * `Ok(WorkspacePreview)` - Processes, name conflicts and old home directory paths
+/// * `Err(SentinelError)` - Archive unreadable, from a newer version, or its config is invalid
+#[tauri::command]
+pub async fn preview_workspace_import(
+    path: String,
+    mode: ImportMode,
+) -> Result<WorkspacePreview, SentinelError> {
+    let workspace = Workspace::read(&PathBuf::from(path))?;
+    workspace.preview(&WorkspacePaths::new(get_config_path()), mode)
+}
+
+/// Imports a workspace archive.
+///
+/// The imported config takes effect on the next `reload_config`.
+///
+/// # Arguments
+/// * `path` - Archive written by `export_workspace`
+/// * `mode` - `merge` or `replace`
+/// * `rewrite_home` - Point paths in the old home directory into this one
+/// * `state` - Application state
+///
+/// # Returns
+/// * `Ok(WorkspaceImportReport)` - Files written and processes replaced
+/// * `Err(SentinelError)` - Archive unreadable or invalid, or a file couldn't be written
+#[tauri::command]
+pub async fn import_workspace(
+    path: String,
+    mode: ImportMode,
+    rewrite_home: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceImportReport, SentinelError> {
+    let mut workspace = Workspace::read(&PathBuf::from(path))?;
+    if rewrite_home.unwrap_or(false) {
+        if let Some(home) = dirs::home_dir() {
+            workspace.rewrite_home(&home);
+        }
+    }
+    let report = workspace.apply(&WorkspacePaths::new(get_config_path()), mode)?;
+
+    let pty_manager = state.pty_manager.lock().await;
+    if mode == ImportMode::Replace {
+        for config in pty_manager.get_all_configs().await {
+            pty_manager.remove_config(&config.process_id).await;
+        }
+    }
+    for config in workspace.pty_configs {
+        pty_manager.save_config(config).await;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,635 @@
+//! Alert rule evaluation.
+//!
+//! The supervisor loop samples process and system metrics into an
+//! [`AlertSnapshot`] and passes it to [`AlertManager::evaluate`], which tracks
+//! how long each rule's condition has held and reports the alerts that fire
+//! or resolve. Snapshots are plain data, so the engine can be driven with
+//! synthetic samples.
+
+use crate::core::{ProcessManager, SystemMonitor};
+use crate::error::{Result, SentinelError};
+use crate::models::{Alert, AlertMetric, AlertRule, ProcessState};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+
+/// Number of fired alerts kept in the history.
+pub const MAX_ALERT_HISTORY: usize = 500;
+
+/// Machine-wide metrics at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemSample {
+    /// Overall CPU usage percentage.
+    pub cpu_usage: f64,
+    /// Percentage of memory in use.
+    pub memory_percent: f64,
+    /// Free space percentage of the fullest disk, if disks were sampled.
+    pub min_disk_free_percent: Option<f64>,
+}
+
+impl SystemSample {
+    /// Samples `monitor`, scanning disks only when `with_disks` is set.
+    pub fn collect(monitor: &mut SystemMonitor, with_disks: bool) -> Self {
+        monitor.refresh_cpu();
+        monitor.refresh_memory();
+
+        let min_disk_free_percent = if with_disks {
+            monitor
+                .get_details()
+                .disks
+                .iter()
+                .filter(|disk| disk.total_space > 0)
+                .map(|disk| disk.available_space as f64 * 100.0 / disk.total_space as f64)
+                .min_by(|a, b| a.total_cmp(b))
+        } else {
+            None
+        };
+
+        Self {
+            cpu_usage: monitor.cpu_usage() as f64,
+            memory_percent: monitor.memory_usage_percent() as f64,
+            min_disk_free_percent,
+        }
+    }
+}
+
+/// Metrics of a managed process at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessSample {
+    /// Whether the process is in the crashed state.
+    pub crashed: bool,
+    /// Whether the process crashed with no restart attempts left.
+    pub restart_limit_exceeded: bool,
+    /// CPU usage percentage, including children when aggregation is enabled.
+    pub cpu_usage: f64,
+    /// Memory usage in MB, including children when aggregation is enabled.
+    pub memory_mb: f64,
+    /// Open file descriptors, if they could be counted.
+    pub open_fds: Option<f64>,
+}
+
+impl ProcessSample {
+    /// Samples every process known to `manager`, keyed by name.
+    ///
+    /// Resource usage is whatever the manager last recorded; call
+    /// `update_resource_usage` first for fresh values.
+    pub async fn collect_all(manager: &ProcessManager) -> HashMap<String, Self> {
+        let settings = manager.settings();
+        let mut samples = HashMap::new();
+
+        for info in manager.list().await {
+            let crashed = matches!(info.state, ProcessState::Crashed { .. });
+            let restart_limit_exceeded = crashed
+                && manager.get_config(&info.name).await.is_some_and(|config| {
+                    config.effective_auto_restart(&settings)
+                        && config.restart_limit > 0
+                        && info.restart_count >= config.restart_limit
+                });
+            let memory = info.memory_usage_tree.unwrap_or(info.memory_usage);
+
+            let sample = Self {
+                crashed,
+                restart_limit_exceeded,
+                cpu_usage: info.cpu_usage_tree.unwrap_or(info.cpu_usage) as f64,
+                memory_mb: memory as f64 / (1024.0 * 1024.0),
+                open_fds: info.open_fds.map(|fds| fds as f64),
+            };
+            samples.insert(info.name, sample);
+        }
+        samples
+    }
+}
+
+/// Everything alert rules are evaluated against.
+#[derive(Debug, Clone, Default)]
+pub struct AlertSnapshot {
+    /// Machine-wide metrics, `None` if not sampled.
+    pub system: Option<SystemSample>,
+    /// Managed process metrics keyed by process name.
+    pub processes: HashMap<String, ProcessSample>,
+}
+
+/// A change in an alert's state reported by [`AlertManager::evaluate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertTransition {
+    /// A rule's condition held for its full duration.
+    Fired(Alert),
+    /// A firing alert's condition stopped holding.
+    Resolved(Alert),
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    /// When the condition started holding, if it currently does.
+    pending_since: Option<DateTime<Utc>>,
+    /// The alert raised by the rule, while it is firing.
+    alert: Option<Alert>,
+}
+
+/// Evaluates alert rules and keeps firing and past alerts.
+///
+/// Each rule raises at most one alert at a time; it fires once the condition
+/// has held for the rule's duration and resolves as soon as it stops holding.
+#[derive(Debug, Default)]
+pub struct AlertManager {
+    rules: Vec<AlertRule>,
+    states: HashMap<String, RuleState>,
+    history: VecDeque<Alert>,
+}
+
+impl AlertManager {
+    /// Creates a manager with no rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the rules.
+    ///
+    /// Unchanged rules keep their state; alerts of removed or changed rules
+    /// are resolved without a transition.
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        if rules == self.rules {
+            return;
+        }
+
+        let now = Utc::now();
+        let stale: Vec<String> = self
+            .rules
+            .iter()
+            .filter(|rule| !rules.contains(rule))
+            .map(|rule| rule.name.clone())
+            .collect();
+        for name in stale {
+            if let Some(mut alert) = self.states.remove(&name).and_then(|state| state.alert) {
+                alert.resolved_at = Some(now);
+                self.record(&alert);
+            }
+        }
+
+        self.rules = rules;
+    }
+
+    /// Whether any rule watches machine-wide metrics.
+    pub fn needs_system(&self) -> bool {
+        self.rules.iter().any(AlertRule::is_system)
+    }
+
+    /// Whether any rule watches disk space.
+    pub fn needs_disks(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.metric == AlertMetric::DiskFree)
+    }
+
+    /// Whether any rule watches process CPU, memory or open files.
+    pub fn needs_process_usage(&self) -> bool {
+        self.rules.iter().any(|rule| {
+            !rule.is_system()
+                && matches!(
+                    rule.metric,
+                    AlertMetric::Cpu | AlertMetric::Memory | AlertMetric::OpenFiles
+                )
+        })
+    }
+
+    /// Evaluates every rule against `snapshot` taken at `now`.
+    ///
+    /// A rule whose target is missing from the snapshot counts as not
+    /// holding.
+    pub fn evaluate(
+        &mut self,
+        snapshot: &AlertSnapshot,
+        now: DateTime<Utc>,
+    ) -> Vec<AlertTransition> {
+        let mut transitions = Vec::new();
+
+        for rule in &self.rules {
+            let state = self.states.entry(rule.name.clone()).or_default();
+
+            match breach(rule, snapshot) {
+                Some(value) => {
+                    // Already firing; don't raise it again
+                    if state.alert.is_some() {
+                        continue;
+                    }
+                    let since = *state.pending_since.get_or_insert(now);
+                    let held_ms = (now - since).num_milliseconds().max(0) as u64;
+                    if held_ms < rule.duration_ms {
+                        continue;
+                    }
+
+                    let alert = Alert {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        rule: rule.name.clone(),
+                        target: rule.target.clone(),
+                        metric: rule.metric,
+                        severity: rule.severity,
+                        message: message(rule, value),
+                        value,
+                        fired_at: now,
+                        acknowledged: false,
+                        resolved_at: None,
+                    };
+                    state.alert = Some(alert.clone());
+                    transitions.push(AlertTransition::Fired(alert));
+                }
+                None => {
+                    state.pending_since = None;
+                    if let Some(mut alert) = state.alert.take() {
+                        alert.resolved_at = Some(now);
+                        transitions.push(AlertTransition::Resolved(alert));
+                    }
+                }
+            }
+        }
+
+        for transition in &transitions {
+            let (AlertTransition::Fired(alert) | AlertTransition::Resolved(alert)) = transition;
+            self.record(alert);
+        }
+
+        transitions
+    }
+
+    /// Gets every firing alert, oldest first.
+    pub fn active(&self) -> Vec<Alert> {
+        let mut alerts: Vec<Alert> = self
+            .states
+            .values()
+            .filter_map(|state| state.alert.clone())
+            .collect();
+        alerts.sort_by(|a, b| a.fired_at.cmp(&b.fired_at).then(a.rule.cmp(&b.rule)));
+        alerts
+    }
+
+    /// Acknowledges a firing alert.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if no firing alert has the given ID.
+    pub fn ack(&mut self, id: &str) -> Result<Alert> {
+        let alert = self
+            .states
+            .values_mut()
+            .filter_map(|state| state.alert.as_mut())
+            .find(|alert| alert.id == id)
+            .ok_or_else(|| SentinelError::InvalidInput {
+                message: format!("No active alert with id '{}'", id),
+            })?;
+        alert.acknowledged = true;
+
+        let alert = alert.clone();
+        self.record(&alert);
+        Ok(alert)
+    }
+
+    /// Gets up to `limit` alerts, most recently fired first.
+    pub fn history(&self, limit: usize) -> Vec<Alert> {
+        self.history.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Adds `alert` to the history, or updates it if already there.
+    fn record(&mut self, alert: &Alert) {
+        if let Some(existing) = self.history.iter_mut().find(|a| a.id == alert.id) {
+            *existing = alert.clone();
+            return;
+        }
+        if self.history.len() == MAX_ALERT_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(alert.clone());
+    }
+}
+
+/// Checks whether `rule` holds in `snapshot`.
+///
+/// # Returns
+/// `Some` with the metric value (`None` for events) if the condition holds.
+fn breach(rule: &AlertRule, snapshot: &AlertSnapshot) -> Option<Option<f64>> {
+    let value = if rule.is_system() {
+        let system = snapshot.system.as_ref()?;
+        match rule.metric {
+            AlertMetric::Cpu => system.cpu_usage,
+            AlertMetric::Memory => system.memory_percent,
+            AlertMetric::DiskFree => system.min_disk_free_percent?,
+            AlertMetric::OpenFiles | AlertMetric::Crashed | AlertMetric::RestartLimitExceeded => {
+                return None
+            }
+        }
+    } else {
+        let process = snapshot.processes.get(&rule.target)?;
+        match rule.metric {
+            AlertMetric::Cpu => process.cpu_usage,
+            AlertMetric::Memory => process.memory_mb,
+            // Descriptors that can't be counted never breach
+            AlertMetric::OpenFiles => process.open_fds?,
+            AlertMetric::Crashed => return process.crashed.then_some(None),
+            AlertMetric::RestartLimitExceeded => {
+                return process.restart_limit_exceeded.then_some(None)
+            }
+            AlertMetric::DiskFree => return None,
+        }
+    };
+
+    let threshold = rule.threshold?;
+    let breached = match rule.metric {
+        AlertMetric::DiskFree => value < threshold,
+        _ => value > threshold,
+    };
+    breached.then_some(Some(value))
+}
+
+/// Describes a firing alert for notifications.
+fn message(rule: &AlertRule, value: Option<f64>) -> String {
+    let subject = if rule.is_system() {
+        "System".to_string()
+    } else {
+        format!("Process '{}'", rule.target)
+    };
+    let value = value.unwrap_or_default();
+    let threshold = rule.threshold.unwrap_or_default();
+
+    match rule.metric {
+        AlertMetric::Crashed => format!("{} crashed", subject),
+        AlertMetric::RestartLimitExceeded => format!("{} exceeded its restart limit", subject),
+        AlertMetric::Cpu => format!(
+            "{} CPU usage is {:.1}% (threshold {}%)",
+            subject, value, threshold
+        ),
+        AlertMetric::Memory if rule.is_system() => format!(
+            "{} memory usage is {:.1}% (threshold {}%)",
+            subject, value, threshold
+        ),
+        AlertMetric::Memory => format!(
+            "{} memory usage is {:.0} MB (threshold {} MB)",
+            subject, value, threshold
+        ),
+        AlertMetric::OpenFiles => format!(
+            "{} has {:.0} open files (threshold {})",
+            subject, value, threshold
+        ),
+        AlertMetric::DiskFree => format!(
+            "Free disk space is {:.1}% (threshold {}%)",
+            value, threshold
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AlertSeverity, SYSTEM_TARGET};
+    use chrono::Duration;
+
+    fn rule(name: &str, target: &str, metric: AlertMetric, threshold: Option<f64>) -> AlertRule {
+        AlertRule {
+            name: name.to_string(),
+            target: target.to_string(),
+            metric,
+            threshold,
+            duration_ms: 0,
+            severity: AlertSeverity::Warning,
+        }
+    }
+
+    fn system(cpu_usage: f64) -> AlertSnapshot {
+        AlertSnapshot {
+            system: Some(SystemSample {
+                cpu_usage,
+                memory_percent: 50.0,
+                min_disk_free_percent: Some(40.0),
+            }),
+            processes: HashMap::new(),
+        }
+    }
+
+    fn process(name: &str, sample: ProcessSample) -> AlertSnapshot {
+        AlertSnapshot {
+            system: None,
+            processes: HashMap::from([(name.to_string(), sample)]),
+        }
+    }
+
+    fn fired(transitions: &[AlertTransition]) -> Vec<&Alert> {
+        transitions
+            .iter()
+            .filter_map(|t| match t {
+                AlertTransition::Fired(alert) => Some(alert),
+                AlertTransition::Resolved(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fires_after_duration_and_resolves() {
+        let mut manager = AlertManager::new();
+        let mut hot = rule("hot", SYSTEM_TARGET, AlertMetric::Cpu, Some(90.0));
+        hot.duration_ms = 5_000;
+        manager.set_rules(vec![hot]);
+        let start = Utc::now();
+
+        assert!(manager.evaluate(&system(95.0), start).is_empty());
+        assert!(manager
+            .evaluate(&system(97.0), start + Duration::seconds(3))
+            .is_empty());
+
+        let transitions = manager.evaluate(&system(99.0), start + Duration::seconds(5));
+        let alerts = fired(&transitions);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "hot");
+        assert_eq!(alerts[0].value, Some(99.0));
+        assert_eq!(
+            alerts[0].message,
+            "System CPU usage is 99.0% (threshold 90%)"
+        );
+        assert_eq!(manager.active().len(), 1);
+
+        let transitions = manager.evaluate(&system(10.0), start + Duration::seconds(6));
+        assert!(
+            matches!(&transitions[..], [AlertTransition::Resolved(alert)] if alert.resolved_at.is_some())
+        );
+        assert!(manager.active().is_empty());
+    }
+
+    #[test]
+    fn test_dip_resets_duration() {
+        let mut manager = AlertManager::new();
+        let mut hot = rule("hot", SYSTEM_TARGET, AlertMetric::Cpu, Some(90.0));
+        hot.duration_ms = 5_000;
+        manager.set_rules(vec![hot]);
+        let start = Utc::now();
+
+        manager.evaluate(&system(95.0), start);
+        manager.evaluate(&system(50.0), start + Duration::seconds(3));
+        let transitions = manager.evaluate(&system(95.0), start + Duration::seconds(6));
+        assert!(transitions.is_empty());
+    }
+
+    #[test]
+    fn test_firing_alert_is_deduplicated() {
+        let mut manager = AlertManager::new();
+        manager.set_rules(vec![rule("api-down", "api", AlertMetric::Crashed, None)]);
+        let crashed = process(
+            "api",
+            ProcessSample {
+                crashed: true,
+                ..Default::default()
+            },
+        );
+        let start = Utc::now();
+
+        let first = manager.evaluate(&crashed, start);
+        assert_eq!(fired(&first)[0].message, "Process 'api' crashed");
+        assert_eq!(fired(&first)[0].value, None);
+        for i in 1..5 {
+            assert!(manager
+                .evaluate(&crashed, start + Duration::seconds(i))
+                .is_empty());
+        }
+        assert_eq!(manager.active().len(), 1);
+        assert_eq!(manager.history(10).len(), 1);
+
+        // Resolving and breaching again raises a new alert
+        manager.evaluate(&process("api", ProcessSample::default()), start);
+        let again = manager.evaluate(&crashed, start + Duration::seconds(10));
+        assert_ne!(fired(&again)[0].id, fired(&first)[0].id);
+        assert_eq!(manager.history(10).len(), 2);
+    }
+
+    #[test]
+    fn test_thresholds_by_metric() {
+        let mut manager = AlertManager::new();
+        manager.set_rules(vec![
+            rule("disk", SYSTEM_TARGET, AlertMetric::DiskFree, Some(50.0)),
+            rule("mem", SYSTEM_TARGET, AlertMetric::Memory, Some(50.0)),
+            rule("api-mem", "api", AlertMetric::Memory, Some(512.0)),
+        ]);
+        let mut snapshot = system(0.0);
+        snapshot.processes.insert(
+            "api".to_string(),
+            ProcessSample {
+                memory_mb: 600.0,
+                ..Default::default()
+            },
+        );
+
+        let transitions = manager.evaluate(&snapshot, Utc::now());
+        let mut rules: Vec<&str> = fired(&transitions)
+            .iter()
+            .map(|alert| alert.rule.as_str())
+            .collect();
+        rules.sort_unstable();
+        // 40% free is below 50%; 50% memory is not above 50%
+        assert_eq!(rules, vec!["api-mem", "disk"]);
+    }
+
+    #[test]
+    fn test_open_files() {
+        let mut manager = AlertManager::new();
+        manager.set_rules(vec![rule(
+            "fds",
+            "api",
+            AlertMetric::OpenFiles,
+            Some(1000.0),
+        )]);
+        assert!(manager.needs_process_usage());
+
+        // Counts that couldn't be read don't fire
+        let uncounted = ProcessSample::default();
+        assert!(manager
+            .evaluate(&process("api", uncounted), Utc::now())
+            .is_empty());
+
+        let leaking = ProcessSample {
+            open_fds: Some(1500.0),
+            ..Default::default()
+        };
+        let transitions = manager.evaluate(&process("api", leaking), Utc::now());
+        let alert = &fired(&transitions)[0];
+        assert_eq!(alert.value, Some(1500.0));
+        assert_eq!(
+            alert.message,
+            "Process 'api' has 1500 open files (threshold 1000)"
+        );
+    }
+
+    #[test]
+    fn test_missing_target_resolves() {
+        let mut manager = AlertManager::new();
+        manager.set_rules(vec![rule("api-down", "api", AlertMetric::Crashed, None)]);
+        let crashed = ProcessSample {
+            crashed: true,
+            ..Default::default()
+        };
+
+        manager.evaluate(&process("api", crashed), Utc::now());
+        let transitions = manager.evaluate(&AlertSnapshot::default(), Utc::now());
+        assert!(matches!(&transitions[..], [AlertTransition::Resolved(_)]));
+    }
+
+    #[test]
+    fn test_ack() {
+        let mut manager = AlertManager::new();
+        manager.set_rules(vec![rule(
+            "hot",
+            SYSTEM_TARGET,
+            AlertMetric::Cpu,
+            Some(90.0),
+        )]);
+        let transitions = manager.evaluate(&system(95.0), Utc::now());
+        let id = fired(&transitions)[0].id.clone();
+
+        let alert = manager.ack(&id).unwrap();
+        assert!(alert.acknowledged);
+        assert!(manager.active()[0].acknowledged);
+        assert!(manager.history(1)[0].acknowledged);
+
+        // Acknowledged alerts stay firing until resolved
+        assert!(manager.evaluate(&system(95.0), Utc::now()).is_empty());
+        manager.evaluate(&system(10.0), Utc::now());
+        assert!(matches!(
+            manager.ack(&id),
+            Err(SentinelError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_set_rules_keeps_unchanged_state() {
+        let mut manager = AlertManager::new();
+        let hot = rule("hot", SYSTEM_TARGET, AlertMetric::Cpu, Some(90.0));
+        let mem = rule("mem", SYSTEM_TARGET, AlertMetric::Memory, Some(10.0));
+        manager.set_rules(vec![hot.clone(), mem.clone()]);
+        manager.evaluate(&system(95.0), Utc::now());
+        assert_eq!(manager.active().len(), 2);
+
+        let mut cooler = hot.clone();
+        cooler.threshold = Some(99.0);
+        manager.set_rules(vec![cooler, mem]);
+        let active = manager.active();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].rule, "mem");
+        assert!(manager
+            .history(10)
+            .iter()
+            .any(|alert| alert.rule == "hot" && alert.resolved_at.is_some()));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut manager = AlertManager::new();
+        manager.set_rules(vec![rule(
+            "hot",
+            SYSTEM_TARGET,
+            AlertMetric::Cpu,
+            Some(90.0),
+        )]);
+        let start = Utc::now();
+        for i in 0..2 * (MAX_ALERT_HISTORY as i64 + 10) {
+            let at = start + Duration::seconds(i);
+            manager.evaluate(&system(if i % 2 == 0 { 95.0 } else { 10.0 }), at);
+        }
+
+        let history = manager.history(usize::MAX);
+        assert_eq!(history.len(), MAX_ALERT_HISTORY);
+        assert!(history[0].fired_at > history[1].fired_at);
+        assert_eq!(manager.history(3).len(), 3);
+    }
+}
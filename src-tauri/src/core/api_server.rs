@@ -0,0 +1,741 @@
+//! HTTP API for headless use.
+//!
+//! With `settings.api.enabled`, Sentinel serves a small REST API so scripts,
+//! and browsers on other machines, can control it without the app window:
+//!
+//! - `GET /processes`
+//! - `POST /processes/{name}/start`, `/stop` and `/restart`
+//! - `GET /processes/{name}/logs?lines=N`
+//! - `GET /system/stats`
+//! - `GET /ports`
+//! - `GET /events`, a WebSocket of live events (see [`event_stream`])
+//!
+//! Each route wraps the same manager call as the matching Tauri command and
+//! returns the same JSON; failures return the `{code, message, details}`
+//! body of [`SentinelError`]. Every request must carry
+//! `Authorization: Bearer <settings.api.token>`; browsers can't set headers
+//! on a WebSocket, so `/events` also takes `?token=<settings.api.token>`.
+
+use crate::core::event_stream;
+use crate::core::{validation, LiveEvent, ProcessManager, SystemMonitor};
+use crate::error::{ErrorCode, Result, SentinelError};
+use crate::features::port_discovery::PortScanner;
+use crate::models::ApiSettings;
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{
+    HeaderValue, AUTHORIZATION, CONNECTION, CONTENT_TYPE, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
+    SEC_WEBSOCKET_VERSION, UPGRADE, WWW_AUTHENTICATE,
+};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tracing::{debug, info};
+
+/// How often `/events` clients get a system stats sample.
+pub const STATS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The managers the API works on, shared with the Tauri commands.
+#[derive(Clone)]
+pub struct ApiContext {
+    pub process_manager: Arc<ProcessManager>,
+    pub system_monitor: Arc<Mutex<SystemMonitor>>,
+}
+
+/// Serves the API while `settings.api` enables it.
+#[derive(Default)]
+pub struct ApiServer {
+    /// Settings last applied, even if serving them failed.
+    settings: Option<ApiSettings>,
+    server: Option<Server>,
+}
+
+/// A running listener.
+struct Server {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+    /// Publishes system stats while `/events` has clients.
+    stats_task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    /// Creates a stopped server.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves the API as `settings` say, or stops serving when disabled.
+    ///
+    /// Does nothing if `settings` are the ones last applied, so an address
+    /// that failed to bind isn't retried on every call.
+    ///
+    /// # Errors
+    /// Returns `InvalidConfig` if enabled without a token, or `Other` if the
+    /// address can't be bound.
+    pub async fn configure(&mut self, settings: &ApiSettings, context: &ApiContext) -> Result<()> {
+        if self.settings.as_ref() == Some(settings) {
+            return Ok(());
+        }
+        self.stop();
+        self.settings = Some(settings.clone());
+
+        if !settings.enabled {
+            return Ok(());
+        }
+        if let Some(problem) = validation::check_api(settings).into_iter().next() {
+            return Err(problem);
+        }
+        let token = settings.token.clone().unwrap_or_default();
+        let address = settings.address();
+        let listener = TcpListener::bind(address).await.map_err(|e| {
+            SentinelError::Other(format!("Failed to start API server on {}: {}", address, e))
+        })?;
+        let local_addr = listener.local_addr()?;
+        let task = tokio::spawn(serve(listener, context.clone(), Arc::from(token)));
+        let stats_task = tokio::spawn(publish_stats(context.clone()));
+
+        info!("Serving the API on http://{}", local_addr);
+        self.server = Some(Server {
+            local_addr,
+            task,
+            stats_task,
+        });
+        Ok(())
+    }
+
+    /// Whether the listener is running.
+    pub fn is_enabled(&self) -> bool {
+        self.server.is_some()
+    }
+
+    /// Address the listener is bound to, if running.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.as_ref().map(|server| server.local_addr)
+    }
+
+    /// Stops the listener, if running.
+    pub fn stop(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.task.abort();
+            server.stats_task.abort();
+            info!("Stopped API server on {}", server.local_addr);
+        }
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Accepts connections until the task is aborted.
+async fn serve(listener: TcpListener, context: ApiContext, token: Arc<str>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                debug!("API listener accept failed: {}", e);
+                continue;
+            }
+        };
+        let context = context.clone();
+        let token = token.clone();
+        let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+            let context = context.clone();
+            let token = token.clone();
+            async move { Ok::<_, Infallible>(respond(request, &context, &token).await) }
+        });
+        tokio::spawn(async move {
+            let connection = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades();
+            if let Err(e) = connection.await {
+                debug!("API connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Samples system stats every [`STATS_INTERVAL`] for `/events` clients, until
+/// the task is aborted.
+async fn publish_stats(context: ApiContext) {
+    let events = context.process_manager.events();
+    let mut interval = tokio::time::interval(STATS_INTERVAL);
+    loop {
+        interval.tick().await;
+        if !events.has_subscribers() {
+            continue;
+        }
+        let stats = {
+            let mut monitor = context.system_monitor.lock().await;
+            monitor.refresh();
+            monitor.get_stats()
+        };
+        events.publish(LiveEvent::SystemStats(stats));
+    }
+}
+
+async fn respond(
+    request: Request<Incoming>,
+    context: &ApiContext,
+    token: &str,
+) -> Response<Full<Bytes>> {
+    let is_events = request.uri().path() == "/events";
+    if !is_authorized(&request, token) {
+        let mut response = empty(StatusCode::UNAUTHORIZED);
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        return response;
+    }
+
+    if is_events {
+        return open_event_stream(request, context);
+    }
+
+    let segments: Vec<&str> = request
+        .uri()
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let manager = &context.process_manager;
+    match (request.method(), segments.as_slice()) {
+        (&Method::GET, ["processes"]) => {
+            manager.update_resource_usage().await;
+            json(Ok(manager.list().await))
+        }
+        (&Method::POST, ["processes", name, "start"]) => json(manager.start_by_name(name).await),
+        (&Method::POST, ["processes", name, "stop"]) => {
+            let stopped = match manager.stop(name).await {
+                Ok(()) => manager.get(name).await.ok_or_else(|| not_found(name)),
+                Err(e) => Err(e),
+            };
+            json(stopped)
+        }
+        (&Method::POST, ["processes", name, "restart"]) => json(manager.restart(name).await),
+        (&Method::GET, ["processes", name, "logs"]) => {
+            let logs = match lines_param(request.uri().query()) {
+                Ok(Some(lines)) => manager.get_recent_logs(name, lines).await,
+                Ok(None) => manager.get_logs(name).await,
+                Err(e) => return json::<()>(Err(e)),
+            };
+            json(logs.ok_or_else(|| not_found(name)))
+        }
+        (&Method::GET, ["system", "stats"]) => {
+            let mut monitor = context.system_monitor.lock().await;
+            monitor.refresh();
+            json(Ok(monitor.get_stats()))
+        }
+        (&Method::GET, ["ports"]) => {
+            json(PortScanner::new().scan().await.map_err(SentinelError::from))
+        }
+        (_, ["processes"] | ["processes", _, "start" | "stop" | "restart" | "logs"])
+        | (_, ["system", "stats"] | ["ports"]) => empty(StatusCode::METHOD_NOT_ALLOWED),
+        _ => empty(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Upgrades `GET /events` to a WebSocket and streams live events on it.
+fn open_event_stream(request: Request<Incoming>, context: &ApiContext) -> Response<Full<Bytes>> {
+    if request.method() != Method::GET {
+        return empty(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let headers = request.headers();
+    let has_token = |name, expected: &str| {
+        headers.get_all(name).iter().any(|value| {
+            value.to_str().is_ok_and(|value| {
+                value
+                    .split(',')
+                    .any(|token| token.trim().eq_ignore_ascii_case(expected))
+            })
+        })
+    };
+    let is_upgrade = has_token(UPGRADE, "websocket") && has_token(CONNECTION, "upgrade");
+    let Some(key) = headers.get(SEC_WEBSOCKET_KEY).filter(|_| is_upgrade) else {
+        return json::<()>(Err(SentinelError::InvalidInput {
+            message: "/events is a WebSocket; connect with a WebSocket client".to_string(),
+        }));
+    };
+    if headers
+        .get(SEC_WEBSOCKET_VERSION)
+        .map(HeaderValue::as_bytes)
+        != Some(b"13")
+    {
+        let mut response = empty(StatusCode::UPGRADE_REQUIRED);
+        response
+            .headers_mut()
+            .insert(SEC_WEBSOCKET_VERSION, HeaderValue::from_static("13"));
+        return response;
+    }
+    let accept = derive_accept_key(key.as_bytes());
+
+    let events = context.process_manager.events().clone();
+    tokio::spawn(async move {
+        match hyper::upgrade::on(request).await {
+            Ok(upgraded) => event_stream::run(TokioIo::new(upgraded), &events).await,
+            Err(e) => debug!("Event stream upgrade failed: {}", e),
+        }
+    });
+
+    let mut response = empty(StatusCode::SWITCHING_PROTOCOLS);
+    let headers = response.headers_mut();
+    headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+    if let Ok(accept) = HeaderValue::from_str(&accept) {
+        headers.insert(SEC_WEBSOCKET_ACCEPT, accept);
+    }
+    response
+}
+
+/// Whether `request` carries `token`, in the `Authorization` header or, for
+/// `/events`, the query.
+fn is_authorized<B>(request: &Request<B>, token: &str) -> bool {
+    let header = request.headers().get(AUTHORIZATION);
+    if header
+        .and_then(|header| header.as_bytes().strip_prefix(b"Bearer "))
+        .is_some_and(|given| tokens_match(given, token))
+    {
+        return true;
+    }
+    request.uri().path() == "/events"
+        && query_token(request.uri().query())
+            .is_some_and(|given| tokens_match(given.as_bytes(), token))
+}
+
+/// The `token` query parameter, if given.
+fn query_token(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Whether `given` is `token`, compared in constant time so the token can't
+/// be guessed byte by byte from response times.
+fn tokens_match(given: &[u8], token: &str) -> bool {
+    let token = token.as_bytes();
+    given.len() == token.len()
+        && given
+            .iter()
+            .zip(token)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The `lines` query parameter, if given.
+fn lines_param(query: Option<&str>) -> Result<Option<usize>> {
+    let Some(value) = query
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("lines="))
+    else {
+        return Ok(None);
+    };
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| SentinelError::InvalidInput {
+            message: format!("lines must be a non-negative number, not '{}'", value),
+        })
+}
+
+fn not_found(name: &str) -> SentinelError {
+    SentinelError::ProcessNotFound {
+        name: name.to_string(),
+    }
+}
+
+/// The HTTP status an error is reported with.
+fn status_of(error: &SentinelError) -> StatusCode {
+    match error.code() {
+        ErrorCode::ProcessNotFound | ErrorCode::PortNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ProcessAlreadyRunning | ErrorCode::ProcessNotRunning => StatusCode::CONFLICT,
+        ErrorCode::InvalidInput | ErrorCode::InvalidConfig => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// `value` as JSON, or the error with its status.
+fn json<T: Serialize>(value: Result<T>) -> Response<Full<Bytes>> {
+    let (status, body) = match value {
+        Ok(value) => (StatusCode::OK, serde_json::to_vec(&value)),
+        Err(e) => (status_of(&e), serde_json::to_vec(&e)),
+    };
+    let Ok(body) = body else {
+        return empty(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+fn empty(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProcessConfig;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::WebSocketStream;
+
+    const TOKEN: &str = "s3cret-token";
+
+    /// Sends a request, returning the status code and body.
+    async fn request(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+    ) -> (u16, String) {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let authorization = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: 0\r\nConnection: close\r\n\r\n",
+            method, path, authorization
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    /// Opens `path` with a WebSocket client, returning the connection once
+    /// the handshake is done.
+    async fn open_events(addr: SocketAddr, path: &str) -> WebSocketStream<TcpStream> {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let url = format!("ws://{}{}", addr, path);
+        let (socket, response) = tokio_tungstenite::client_async(url, stream).await.unwrap();
+        assert_eq!(response.status(), 101);
+        socket
+    }
+
+    async fn send(socket: &mut WebSocketStream<TcpStream>, message: Value) {
+        socket
+            .send(Message::Text(message.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// The next message other than a system stats sample, which come on
+    /// their own schedule.
+    async fn receive(socket: &mut WebSocketStream<TcpStream>) -> Value {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+                .await
+                .expect("no event within 5s");
+            let event: Value = match message {
+                Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+                other => panic!("unexpected message {:?}", other),
+            };
+            if event["type"] != "systemStats" {
+                return event;
+            }
+        }
+    }
+
+    fn settings() -> ApiSettings {
+        ApiSettings {
+            enabled: true,
+            port: 0,
+            token: Some(TOKEN.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn context() -> ApiContext {
+        ApiContext {
+            process_manager: Arc::new(ProcessManager::new()),
+            system_monitor: Arc::new(Mutex::new(SystemMonitor::new())),
+        }
+    }
+
+    fn config(name: &str, script: &str) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            auto_restart: Some(false),
+            restart_limit: 0,
+            restart_delay: Some(10),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_route_needs_the_token() {
+        let mut server = ApiServer::new();
+        server.configure(&settings(), &context()).await.unwrap();
+        let addr = server.local_addr().unwrap();
+        assert!(addr.ip().is_loopback());
+
+        for (method, path) in [
+            ("GET", "/processes"),
+            ("POST", "/processes/api/start"),
+            ("POST", "/processes/api/stop"),
+            ("POST", "/processes/api/restart"),
+            ("GET", "/processes/api/logs?lines=10"),
+            ("GET", "/system/stats"),
+            ("GET", "/ports"),
+            ("GET", "/events"),
+            ("GET", "/events?token=s3cret-tokem"),
+            ("GET", "/processes?token=s3cret-token"),
+            ("GET", "/nowhere"),
+        ] {
+            assert_eq!(request(addr, method, path, None).await.0, 401, "{}", path);
+            let (status, _) = request(addr, method, path, Some("s3cret-tokem")).await;
+            assert_eq!(status, 401, "{}", path);
+        }
+        assert_eq!(request(addr, "GET", "/processes", Some(TOKEN)).await.0, 200);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_process_routes() {
+        let context = context();
+        context
+            .process_manager
+            .start(config("api", "echo listening; sleep 30"))
+            .await
+            .unwrap();
+        let mut server = ApiServer::new();
+        server.configure(&settings(), &context).await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let call = |method, path| request(addr, method, path, Some(TOKEN));
+
+        let (status, body) = call("GET", "/processes").await;
+        assert_eq!(status, 200);
+        let processes: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(processes[0]["name"], "api");
+
+        let (status, body) = call("GET", "/processes/api/logs?lines=1").await;
+        assert_eq!(status, 200);
+        let logs: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(logs[0]["line"], "listening");
+
+        let (status, body) = call("POST", "/processes/api/stop").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap()["state"],
+            "stopped"
+        );
+
+        let (status, body) = call("POST", "/processes/api/start").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap()["state"],
+            "running"
+        );
+
+        let (status, body) = call("POST", "/processes/api/start").await;
+        assert_eq!(status, 409);
+        let error: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(error["code"], "PROCESS_ALREADY_RUNNING");
+
+        let (status, body) = call("POST", "/processes/api/restart").await;
+        assert_eq!(status, 200);
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["name"], "api");
+
+        let (status, body) = call("GET", "/processes/missing/logs").await;
+        assert_eq!(status, 404);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap()["code"],
+            "PROCESS_NOT_FOUND"
+        );
+        assert_eq!(call("GET", "/processes/api/logs?lines=all").await.0, 400);
+        assert_eq!(call("DELETE", "/processes").await.0, 405);
+        assert_eq!(call("GET", "/processes/api").await.0, 404);
+        assert_eq!(call("GET", "/events").await.0, 400);
+
+        let (status, body) = call("GET", "/system/stats").await;
+        assert_eq!(status, 200);
+        assert!(serde_json::from_str::<Value>(&body).unwrap()["memory"]["total"].is_number());
+
+        context.process_manager.stop_all().await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_event_stream() {
+        let context = context();
+        let mut server = ApiServer::new();
+        server.configure(&settings(), &context).await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let mut socket = open_events(addr, &format!("/events?token={}", TOKEN)).await;
+
+        send(
+            &mut socket,
+            json!({"type": "subscribe", "processes": ["ticker"]}),
+        )
+        .await;
+        assert_eq!(
+            receive(&mut socket).await,
+            json!({"type": "subscribed", "processes": ["ticker"]})
+        );
+
+        let manager = &context.process_manager;
+        let echo_loop = |word| format!("while true; do echo {}; sleep 0.05; done", word);
+        manager
+            .start(config("ticker", &echo_loop("tick")))
+            .await
+            .unwrap();
+        manager
+            .start(config("other", &echo_loop("noise")))
+            .await
+            .unwrap();
+
+        let mut started = false;
+        let mut ticks = Vec::new();
+        while ticks.len() < 3 {
+            let event = receive(&mut socket).await;
+            match event["type"].as_str().unwrap() {
+                "process" => started |= event["process"] == "ticker" && event["event"] == "started",
+                "log" => {
+                    assert_eq!(event["process"], "ticker", "not subscribed: {}", event);
+                    ticks.push(event["line"]["line"].clone());
+                }
+                other => panic!("unexpected event {}", other),
+            }
+        }
+        assert!(started);
+        assert!(ticks.iter().all(|line| line == "tick"));
+
+        send(
+            &mut socket,
+            json!({"type": "unsubscribe", "processes": ["ticker"]}),
+        )
+        .await;
+        send(&mut socket, json!({"type": "ping"})).await;
+        let mut replies = Vec::new();
+        while replies.len() < 2 {
+            let event = receive(&mut socket).await;
+            if !matches!(event["type"].as_str(), Some("log" | "process")) {
+                replies.push(event);
+            }
+        }
+        assert_eq!(replies[0], json!({"type": "subscribed", "processes": []}));
+        assert_eq!(replies[1], json!({"type": "pong"}));
+
+        manager.stop_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_rejects_unmasked_frames() {
+        let context = context();
+        let mut server = ApiServer::new();
+        server.configure(&settings(), &context).await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let mut socket = open_events(addr, &format!("/events?token={}", TOKEN)).await;
+
+        // A text frame as a client must never send it, without a mask
+        let payload = json!({"type": "ping"}).to_string();
+        let mut frame = vec![0x81, payload.len() as u8];
+        frame.extend_from_slice(payload.as_bytes());
+        socket.get_mut().write_all(&frame).await.unwrap();
+
+        // Events sent before the frame was read may come first
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+                .await
+                .expect("no close within 5s");
+            match message {
+                Some(Ok(Message::Text(_))) => continue,
+                Some(Ok(Message::Close(Some(frame)))) => {
+                    assert_eq!(frame.code, CloseCode::Protocol);
+                    break;
+                }
+                other => panic!("expected a close, got {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configure() {
+        let context = context();
+        let mut server = ApiServer::new();
+
+        // Disabled by default, and never served without a token
+        server
+            .configure(&ApiSettings::default(), &context)
+            .await
+            .unwrap();
+        assert!(!server.is_enabled());
+        let without_token = ApiSettings {
+            token: None,
+            ..settings()
+        };
+        assert!(server.configure(&without_token, &context).await.is_err());
+        assert!(!server.is_enabled());
+
+        server.configure(&settings(), &context).await.unwrap();
+        let addr = server.local_addr().unwrap();
+        server
+            .configure(&ApiSettings::default(), &context)
+            .await
+            .unwrap();
+        assert!(!server.is_enabled());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        let request = |uri: &str, header: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(header) = header {
+                request = request.header(AUTHORIZATION, header);
+            }
+            request.body(()).unwrap()
+        };
+        assert!(is_authorized(&request("/ports", Some("Bearer abc")), "abc"));
+        assert!(!is_authorized(
+            &request("/ports", Some("Bearer abd")),
+            "abc"
+        ));
+        assert!(!is_authorized(
+            &request("/ports", Some("Bearer abcd")),
+            "abc"
+        ));
+        assert!(!is_authorized(&request("/ports", Some("Basic abc")), "abc"));
+        assert!(!is_authorized(&request("/ports", None), "abc"));
+
+        // Only the WebSocket takes the token in the query
+        assert!(is_authorized(&request("/events?token=abc", None), "abc"));
+        assert!(is_authorized(
+            &request("/events?x=1&token=abc", None),
+            "abc"
+        ));
+        assert!(!is_authorized(&request("/events?token=abd", None), "abc"));
+        assert!(!is_authorized(&request("/events", None), "abc"));
+        assert!(!is_authorized(&request("/ports?token=abc", None), "abc"));
+    }
+}
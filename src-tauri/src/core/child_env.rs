@@ -0,0 +1,286 @@
+//! The environment spawned processes start with.
+//!
+//! A process's [`EnvMode`] picks the base: Sentinel's own environment, a
+//! short allowlist of it, or the environment of the user's login shell. The
+//! login shell is run once, the first time it's needed, and its environment
+//! is kept for the life of the app; when it can't be read the base falls
+//! back to Sentinel's own environment. Variables that change how programs
+//! are loaded ([`DANGEROUS_VARS`]) are then removed from the base, and the
+//! process's `env` is added on top, so they only reach a process that sets
+//! them itself.
+
+use crate::error::{Result, SentinelError};
+use crate::models::EnvMode;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Variables [`EnvMode::Clean`] keeps from Sentinel's environment.
+pub const CLEAN_ALLOWLIST: &[&str] = &["PATH", "HOME", "USER", "LANG", "TERM"];
+
+/// Variables that make the dynamic loader run other code in the process.
+pub const DANGEROUS_VARS: &[&str] = &[
+    "LD_PRELOAD",
+    "LD_LIBRARY_PATH",
+    "LD_AUDIT",
+    "DYLD_INSERT_LIBRARIES",
+    "DYLD_LIBRARY_PATH",
+    "DYLD_FRAMEWORK_PATH",
+    "DYLD_FALLBACK_LIBRARY_PATH",
+];
+
+/// Variables the login shell sets for itself rather than for its children.
+const SHELL_OWN_VARS: &[&str] = &["PWD", "OLDPWD", "SHLVL", "_"];
+
+/// How long the login shell may take to print its environment.
+pub const SHELL_ENV_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An environment, sorted so it reads the same way every time.
+pub type Environment = BTreeMap<OsString, OsString>;
+
+/// The full environment of a process with `mode` and `env`.
+pub fn build(mode: EnvMode, env: &HashMap<String, String>) -> Environment {
+    let base = match mode {
+        EnvMode::Inherit => std::env::vars_os().collect(),
+        EnvMode::Clean => clean(std::env::vars_os()),
+        EnvMode::Shell => shell_env()
+            .cloned()
+            .unwrap_or_else(|| std::env::vars_os().collect()),
+    };
+    with_process_env(base, env)
+}
+
+/// The environment of the user's login shell, read on first use.
+///
+/// `None` if it couldn't be read; the reason is logged once.
+pub fn shell_env() -> Option<&'static Environment> {
+    static SHELL_ENV: OnceLock<Option<Environment>> = OnceLock::new();
+    SHELL_ENV
+        .get_or_init(|| {
+            let shell = login_shell();
+            match load_login_env(Path::new(&shell), SHELL_ENV_TIMEOUT) {
+                Ok(env) => {
+                    info!(
+                        "Read {} variables from the login shell {}",
+                        env.len(),
+                        shell.to_string_lossy()
+                    );
+                    Some(env)
+                }
+                Err(e) => {
+                    warn!("{}; shell env mode falls back to Sentinel's environment", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// `$SHELL`, or `/bin/sh` if unset.
+fn login_shell() -> OsString {
+    std::env::var_os("SHELL")
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| OsString::from("/bin/sh"))
+}
+
+/// Runs `shell -lc env` and reads the environment it prints.
+///
+/// # Errors
+/// Returns `Other` if the shell can't be run, fails, or takes longer than
+/// `timeout`.
+pub fn load_login_env(shell: &Path, timeout: Duration) -> Result<Environment> {
+    let fail = |reason: String| {
+        SentinelError::Other(format!(
+            "Failed to read the environment of {}: {}",
+            shell.display(),
+            reason
+        ))
+    };
+    let mut child = Command::new(shell)
+        .args(["-l", "-c", "env"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| fail(e.to_string()))?;
+
+    // Read on a thread so a large environment can't fill the pipe while
+    // the deadline is being waited on
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| fail("no output".into()))?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| fail(e.to_string()))? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(fail(format!("no answer within {:?}", timeout)));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    if !status.success() {
+        return Err(fail(format!("exited with {}", status)));
+    }
+
+    let output = reader
+        .join()
+        .map_err(|_| fail("reading the output panicked".into()))?
+        .map_err(|e| fail(e.to_string()))?;
+    let mut env = parse_env_output(&output);
+    for var in SHELL_OWN_VARS {
+        env.remove(OsStr::new(var));
+    }
+    Ok(env)
+}
+
+/// Parses the output of `env`. A line that doesn't start with `NAME=`
+/// continues the value of the variable before it, which had a newline in it.
+fn parse_env_output(output: &[u8]) -> Environment {
+    let text = String::from_utf8_lossy(output);
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        match line.split_once('=') {
+            Some((name, value)) if is_var_name(name) => {
+                vars.push((name.to_string(), value.to_string()))
+            }
+            _ => {
+                if let Some((_, value)) = vars.last_mut() {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+    vars.into_iter()
+        .map(|(name, value)| (name.into(), value.into()))
+        .collect()
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The variables of `vars` that [`EnvMode::Clean`] keeps.
+fn clean(vars: impl IntoIterator<Item = (OsString, OsString)>) -> Environment {
+    vars.into_iter()
+        .filter(|(name, _)| CLEAN_ALLOWLIST.iter().any(|allowed| name == allowed))
+        .collect()
+}
+
+/// `base` without [`DANGEROUS_VARS`], with `env` added over it.
+fn with_process_env(mut base: Environment, env: &HashMap<String, String>) -> Environment {
+    base.retain(|name, _| !is_dangerous(name));
+    base.extend(
+        env.iter()
+            .map(|(name, value)| (OsString::from(name), OsString::from(value))),
+    );
+    base
+}
+
+fn is_dangerous(name: &OsStr) -> bool {
+    DANGEROUS_VARS.iter().any(|dangerous| name == *dangerous)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (OsString::from(name), OsString::from(value)))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_keeps_the_allowlist() {
+        let env = clean(vars(&[
+            ("PATH", "/usr/bin"),
+            ("HOME", "/home/dev"),
+            ("AWS_SECRET_ACCESS_KEY", "hunter2"),
+            ("LANG", "en_US.UTF-8"),
+            ("LD_PRELOAD", "/tmp/evil.so"),
+        ]));
+        let names: Vec<_> = env.keys().map(|name| name.to_str().unwrap()).collect();
+        assert_eq!(names, ["HOME", "LANG", "PATH"]);
+    }
+
+    #[test]
+    fn test_dangerous_vars_need_the_process_env() {
+        let base: Environment = vars(&[
+            ("PATH", "/usr/bin"),
+            ("LD_PRELOAD", "/tmp/evil.so"),
+            ("DYLD_INSERT_LIBRARIES", "/tmp/evil.dylib"),
+        ])
+        .into_iter()
+        .collect();
+        let env = HashMap::from([
+            ("LD_LIBRARY_PATH".to_string(), "/opt/lib".to_string()),
+            ("PATH".to_string(), "/opt/bin".to_string()),
+        ]);
+
+        let env = with_process_env(base, &env);
+        assert_eq!(env[OsStr::new("PATH")], "/opt/bin");
+        assert_eq!(env[OsStr::new("LD_LIBRARY_PATH")], "/opt/lib");
+        assert!(!env.contains_key(OsStr::new("LD_PRELOAD")));
+        assert!(!env.contains_key(OsStr::new("DYLD_INSERT_LIBRARIES")));
+    }
+
+    #[test]
+    fn test_parse_env_output() {
+        let env = parse_env_output(
+            b"PATH=/usr/local/bin:/usr/bin\nGREETING=hello\nworld\nEMPTY=\nEQUALS=a=b\n",
+        );
+        assert_eq!(env[OsStr::new("PATH")], "/usr/local/bin:/usr/bin");
+        assert_eq!(env[OsStr::new("GREETING")], "hello\nworld");
+        assert_eq!(env[OsStr::new("EMPTY")], "");
+        assert_eq!(env[OsStr::new("EQUALS")], "a=b");
+        assert_eq!(env.len(), 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_login_env() {
+        let env = load_login_env(Path::new("/bin/sh"), SHELL_ENV_TIMEOUT).unwrap();
+        assert!(env.contains_key(OsStr::new("PATH")));
+        assert!(!env.contains_key(OsStr::new("PWD")));
+        assert!(!env.contains_key(OsStr::new("SHLVL")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_login_env_failures() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let hanging = dir.path().join("hanging-shell");
+        std::fs::write(&hanging, "#!/bin/sh\nsleep 10\n").unwrap();
+        std::fs::set_permissions(&hanging, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let error = load_login_env(&hanging, Duration::from_millis(200)).unwrap_err();
+        assert!(error.to_string().contains("no answer"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert!(load_login_env(Path::new("false"), SHELL_ENV_TIMEOUT).is_err());
+        assert!(load_login_env(&dir.path().join("missing"), SHELL_ENV_TIMEOUT).is_err());
+    }
+}
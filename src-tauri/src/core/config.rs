@@ -2,19 +2,61 @@
 //!
 //! This module handles loading, validation, and saving of configuration files.
 
+use crate::core::config_inheritance;
+use crate::core::config_schema::{self, LimitExceeded};
+use crate::core::gitignore::glob_match;
+use crate::core::{ports, validation};
 use crate::error::{Result, SentinelError};
-use crate::models::{Config, ProcessConfig};
-use regex::Regex;
+use crate::models::{Config, ConfigIssue, ProcessConfig, ProcessOverrides};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Deepest chain of nested includes before loading gives up.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Limits that keep a hostile or broken config file from exhausting memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigLimits {
+    /// Largest combined size in bytes of a config file and everything it includes.
+    pub max_file_size: u64,
+    /// Most YAML nodes one file may expand to, counting every use of an alias.
+    pub max_nodes: usize,
+    /// Deepest nesting of YAML sequences and mappings.
+    pub max_depth: usize,
+}
+
+impl Default for ConfigLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: 5 * 1024 * 1024,
+            max_nodes: 100_000,
+            max_depth: 64,
+        }
+    }
+}
 
 /// Manages configuration loading, validation, and persistence.
 pub struct ConfigManager;
 
+/// What to do when the variable in a `${...}` expression is unset.
+enum Fallback<'a> {
+    /// `${VAR}`: keep the expression as written.
+    None,
+    /// `${VAR:-default}`
+    Default(&'a str),
+    /// `${VAR:?message}`
+    Required(&'a str),
+}
+
 impl ConfigManager {
     /// Loads configuration from a YAML file.
     ///
+    /// Files listed under `include` are loaded and merged recursively; see
+    /// [`Self::load_with_includes`] for the merge rules. The default
+    /// [`ConfigLimits`] apply.
+    ///
     /// # Arguments
     /// * `path` - Path to the configuration file
     ///
@@ -31,6 +73,15 @@ impl ConfigManager {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn load_from_file(path: &Path) -> Result<Config> {
+        Self::load_from_file_with_limits(path, &ConfigLimits::default())
+    }
+
+    /// Loads configuration from a YAML file, enforcing `limits`.
+    ///
+    /// # Errors
+    /// Returns `InvalidConfig` naming the limit if the file and its includes
+    /// are too large, expand to too many nodes or nest too deeply.
+    pub fn load_from_file_with_limits(path: &Path, limits: &ConfigLimits) -> Result<Config> {
         // Check if file exists
         if !path.exists() {
             return Err(SentinelError::ConfigNotFound {
@@ -38,14 +89,52 @@ impl ConfigManager {
             });
         }
 
-        // Read file contents
-        let contents = fs::read_to_string(path).map_err(|source| SentinelError::FileIoError {
-            path: path.to_path_buf(),
-            source,
-        })?;
+        // Load the file and everything it includes
+        let mut stack = Vec::new();
+        let mut loaded = HashSet::new();
+        let config = Self::load_with_includes(path, &mut stack, &mut loaded, limits, &mut 0)?;
+
+        // Validate configuration
+        Self::validate(&config)?;
+        for warning in Self::warnings(&config) {
+            tracing::warn!("{}: {}", path.display(), warning);
+        }
+
+        Ok(config)
+    }
+
+    /// The config file at `path` and every file it includes, as
+    /// [`Self::load_from_file`] reads them.
+    ///
+    /// Included files are listed up to the first one that fails to load;
+    /// `path` is always listed first, even if it doesn't exist.
+    pub fn resolved_files(path: &Path) -> Vec<PathBuf> {
+        let mut stack = Vec::new();
+        let mut loaded = HashSet::new();
+        let limits = ConfigLimits::default();
+        let _ = Self::load_with_includes(path, &mut stack, &mut loaded, &limits, &mut 0);
+
+        let root = path.canonicalize().ok();
+        let mut included: Vec<PathBuf> = loaded
+            .into_iter()
+            .filter(|file| Some(file) != root.as_ref())
+            .collect();
+        included.sort();
+
+        let mut files = vec![path.to_path_buf()];
+        files.extend(included);
+        files
+    }
+
+    /// Reads, interpolates and parses a single configuration file.
+    ///
+    /// `total_size` is the size of the files read so far in this load.
+    fn read_file(path: &Path, limits: &ConfigLimits, total_size: &mut u64) -> Result<Config> {
+        let contents = Self::read_contents(path, limits, total_size)?;
 
         // Interpolate environment variables in the contents
-        let interpolated = Self::interpolate_env_vars(&contents);
+        let interpolated = Self::interpolate_env_vars(&contents)?;
+        Self::check_limits(&interpolated, path, limits)?;
 
         // Parse based on extension
         let config = if path.extension().and_then(|s| s.to_str()) == Some("json") {
@@ -54,36 +143,347 @@ impl ConfigManager {
             Self::parse_yaml(&interpolated, path)?
         };
 
-        // Validate configuration
-        Self::validate(&config)?;
+        // Unknown keys are ignored by serde; point them out without failing the load
+        if let Ok(document) = serde_yaml::from_str::<serde_yaml::Value>(&interpolated) {
+            for unknown in config_schema::find_unknown_keys(&document) {
+                tracing::warn!("{}: {}", path.display(), unknown.message());
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads a file, failing before reading if it would take `total_size` over the limit.
+    fn read_contents(path: &Path, limits: &ConfigLimits, total_size: &mut u64) -> Result<String> {
+        let io_error = |source| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let size = fs::metadata(path).map_err(io_error)?.len();
+        *total_size = total_size.saturating_add(size);
+        if *total_size > limits.max_file_size {
+            return Err(SentinelError::InvalidConfig {
+                reason: format!(
+                    "{} takes the config to {} bytes, over the limit of {} bytes",
+                    path.display(),
+                    total_size,
+                    limits.max_file_size
+                ),
+            });
+        }
+
+        fs::read_to_string(path).map_err(io_error)
+    }
+
+    /// Rejects documents that expand to too many nodes or nest too deeply.
+    fn check_limits(contents: &str, path: &Path, limits: &ConfigLimits) -> Result<()> {
+        let reason = match config_schema::check_limits(contents, limits.max_nodes, limits.max_depth)
+        {
+            None => return Ok(()),
+            Some(LimitExceeded::Nodes) => format!(
+                "{} expands to more than {} YAML nodes; check for aliases that repeat large values",
+                path.display(),
+                limits.max_nodes
+            ),
+            Some(LimitExceeded::Depth) => format!(
+                "{} nests deeper than {} levels",
+                path.display(),
+                limits.max_depth
+            ),
+        };
+        Err(SentinelError::InvalidConfig { reason })
+    }
+
+    /// Checks a configuration file and reports every problem found.
+    ///
+    /// Unlike [`Self::load_from_file`], this does not stop at the first problem:
+    /// it reports syntax and type errors with their location, unknown keys with
+    /// the closest known key, and duplicate names, empty commands, unknown
+    /// dependencies and dependency cycles in the merged configuration.
+    ///
+    /// # Returns
+    /// Every issue found; empty when the file is valid.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use sentinel::core::ConfigManager;
+    /// use std::path::Path;
+    ///
+    /// for issue in ConfigManager::validate_file(Path::new("sentinel.yaml")) {
+    ///     eprintln!("{}", issue);
+    /// }
+    /// ```
+    pub fn validate_file(path: &Path) -> Vec<ConfigIssue> {
+        if !path.exists() {
+            let error = SentinelError::ConfigNotFound {
+                path: path.to_path_buf(),
+            };
+            return vec![ConfigIssue::new(error.to_string())];
+        }
+
+        let limits = ConfigLimits::default();
+        let contents = match Self::read_contents(path, &limits, &mut 0) {
+            Ok(contents) => contents,
+            Err(SentinelError::FileIoError { source, .. }) => {
+                return vec![ConfigIssue::new(format!("Failed to read file: {}", source))]
+            }
+            Err(e) => return vec![ConfigIssue::new(e.to_string())],
+        };
+        let interpolated = match Self::interpolate_env_vars(&contents) {
+            Ok(interpolated) => interpolated,
+            Err(e) => return vec![ConfigIssue::new(e.to_string())],
+        };
+        if let Err(e) = Self::check_limits(&interpolated, path, &limits) {
+            return vec![ConfigIssue::new(e.to_string())];
+        }
+
+        // Syntax errors hide everything else
+        let document = match serde_yaml::from_str::<serde_yaml::Value>(&interpolated) {
+            Ok(document) => document,
+            Err(e) => {
+                return vec![Self::issue_from_error(
+                    Self::parse_error(&interpolated, path, e),
+                    path,
+                )]
+            }
+        };
 
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut issues: Vec<ConfigIssue> = config_schema::find_unknown_keys(&document)
+            .into_iter()
+            .map(|unknown| {
+                let occurrence = seen.entry(unknown.key.clone()).or_insert(0);
+                let location = config_schema::locate_key(&interpolated, &unknown.key, *occurrence);
+                *occurrence += 1;
+
+                ConfigIssue {
+                    message: unknown.message(),
+                    path: None,
+                    line: location.map(|(line, _)| line),
+                    column: location.map(|(_, column)| column),
+                    snippet: location.map(|(line, column)| {
+                        config_schema::source_context(&interpolated, line, column)
+                    }),
+                }
+            })
+            .collect();
+
+        let mut stack = Vec::new();
+        let mut loaded = HashSet::new();
+        match Self::load_with_includes(path, &mut stack, &mut loaded, &limits, &mut 0) {
+            Ok(config) => {
+                issues.extend(
+                    Self::problems(&config)
+                        .into_iter()
+                        .map(|problem| ConfigIssue::new(problem.to_string())),
+                );
+                issues.extend(Self::warnings(&config).into_iter().map(ConfigIssue::new));
+            }
+            Err(e) => issues.push(Self::issue_from_error(e, path)),
+        }
+
+        issues
+    }
+
+    /// Converts a load error into an issue, keeping the location of parse errors.
+    fn issue_from_error(error: SentinelError, root: &Path) -> ConfigIssue {
+        match error {
+            SentinelError::ConfigParseFailed {
+                path,
+                line,
+                column,
+                snippet,
+                source,
+            } => ConfigIssue {
+                message: source.to_string(),
+                path: (path != root).then_some(path),
+                line,
+                column,
+                snippet,
+            },
+            other => ConfigIssue::new(other.to_string()),
+        }
+    }
+
+    /// Loads a file and recursively merges the files it includes.
+    ///
+    /// Included processes and alert rules are appended after the including
+    /// file's own, with relative `cwd` paths rebased onto the included file's
+    /// directory. Global env vars from the including file win; settings come
+    /// from the root only.
+    ///
+    /// `stack` holds the files currently being loaded (to detect cycles),
+    /// `loaded` every file merged so far (so a file included twice is merged once)
+    /// and `total_size` their combined size, which `limits` caps.
+    fn load_with_includes(
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        loaded: &mut HashSet<PathBuf>,
+        limits: &ConfigLimits,
+        total_size: &mut u64,
+    ) -> Result<Config> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|source| SentinelError::FileIoError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        if stack.contains(&canonical) || stack.len() >= MAX_INCLUDE_DEPTH {
+            let mut chain = stack.clone();
+            chain.push(canonical);
+            return Err(SentinelError::IncludeCycle { chain });
+        }
+        stack.push(canonical.clone());
+        loaded.insert(canonical);
+
+        let mut config = Self::read_file(path, limits, total_size)?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        for pattern in config.include.clone() {
+            let files = Self::expand_include(base_dir, &pattern);
+            if files.is_empty() {
+                tracing::warn!(
+                    "Include '{}' in {} matched no files",
+                    pattern,
+                    path.display()
+                );
+            }
+
+            for file in files {
+                let is_merged = file
+                    .canonicalize()
+                    .is_ok_and(|c| loaded.contains(&c) && !stack.contains(&c));
+                if is_merged {
+                    continue;
+                }
+
+                let included = Self::load_with_includes(&file, stack, loaded, limits, total_size)?;
+                let relative_dir = file
+                    .parent()
+                    .and_then(|dir| dir.strip_prefix(base_dir).ok())
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default();
+
+                for mut process in included.processes {
+                    if let Some(cwd) = process.cwd.as_mut().filter(|cwd| cwd.is_relative()) {
+                        *cwd = relative_dir.join(&*cwd);
+                    }
+                    config.processes.push(process);
+                }
+                for (key, value) in included.global_env {
+                    config.global_env.entry(key).or_insert(value);
+                }
+                for (name, value) in included.defaults {
+                    config.defaults.entry(name).or_insert(value);
+                }
+                config.alerts.extend(included.alerts);
+            }
+        }
+
+        stack.pop();
         Ok(config)
     }
 
+    /// Expands an include pattern relative to `base_dir` into matching files.
+    ///
+    /// Each path component may use `*`, `?` and `[...]` wildcards; results are sorted.
+    pub(crate) fn expand_include(base_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+        let pattern = Path::new(pattern);
+        let mut candidates = vec![if pattern.is_absolute() {
+            PathBuf::new()
+        } else {
+            base_dir.to_path_buf()
+        }];
+
+        for component in pattern.components() {
+            let part = component.as_os_str().to_string_lossy();
+            if !part.contains(['*', '?', '[']) {
+                for candidate in &mut candidates {
+                    candidate.push(component);
+                }
+                continue;
+            }
+
+            let mut matched = Vec::new();
+            for dir in &candidates {
+                let dir = if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    dir
+                };
+                let Ok(entries) = fs::read_dir(dir) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    // Like shells, wildcards don't match hidden files
+                    if name.starts_with('.') && !part.starts_with('.') {
+                        continue;
+                    }
+                    if glob_match(part.as_bytes(), name.as_bytes()) {
+                        matched.push(entry.path());
+                    }
+                }
+            }
+            candidates = matched;
+        }
+
+        let mut files: Vec<PathBuf> = candidates.into_iter().filter(|p| p.is_file()).collect();
+        files.sort();
+        files
+    }
+
     /// Saves configuration to a YAML file.
     ///
     /// # Arguments
     /// * `config` - Configuration to save
     /// * `path` - Path where the file should be saved
     ///
+    /// # Errors
+    /// Refuses configs with an `include` list, since they hold the merged
+    /// processes of every included file.
+    ///
+    /// Processes that `extends` another are saved without what they inherit
+    /// unchanged; see [`config_inheritance`].
+    ///
     /// # Examples
     /// ```no_run
     /// use sentinel::core::ConfigManager;
     /// use sentinel::models::Config;
     /// use std::path::Path;
     ///
-    /// # let config = Config { processes: vec![], settings: Default::default(), global_env: Default::default() };
+    /// # let config = Config { processes: vec![], settings: Default::default(), global_env: Default::default(), include: vec![], alerts: vec![], defaults: Default::default() };
     /// ConfigManager::save_to_file(&config, Path::new("sentinel.yaml"))?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn save_to_file(config: &Config, path: &Path) -> Result<()> {
+        // Saving a merged config would flatten every included file into this one
+        if !config.include.is_empty() {
+            return Err(SentinelError::InvalidConfig {
+                reason: format!(
+                    "{} includes other config files ({}); edit those files directly instead of saving the merged configuration",
+                    path.display(),
+                    config.include.join(", ")
+                ),
+            });
+        }
+
         // Validate before saving
         Self::validate(config)?;
 
+        let mut document =
+            serde_yaml::to_value(config).map_err(|e| SentinelError::Other(e.to_string()))?;
+        if config_inheritance::uses_inheritance(&document) {
+            config_inheritance::unresolve(&mut document)?;
+        }
+
         let contents = if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            serde_json::to_string_pretty(config).map_err(|e| SentinelError::Other(e.to_string()))?
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| SentinelError::Other(e.to_string()))?
         } else {
-            serde_yaml::to_string(config).map_err(|e| SentinelError::Other(e.to_string()))?
+            serde_yaml::to_string(&document).map_err(|e| SentinelError::Other(e.to_string()))?
         };
 
         fs::write(path, contents).map_err(|source| SentinelError::FileIoError {
@@ -108,18 +508,200 @@ impl ConfigManager {
             processes: vec![ProcessConfig {
                 name: "example".to_string(),
                 command: "echo 'Hello from Sentinel'".to_string(),
-                args: vec![],
-                cwd: None,
-                env: HashMap::new(),
-                auto_restart: true,
-                restart_limit: 5,
-                restart_delay: 1000,
-                depends_on: vec![],
-                health_check: None,
+                auto_restart: Some(true),
+                restart_delay: Some(1000),
+                ..Default::default()
             }],
             settings: Default::default(),
             global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
+        }
+    }
+
+    /// Selects the processes to start for the given profiles, in dependency order.
+    ///
+    /// When `profiles` is empty, `settings.default_profiles` is used; when that is
+    /// empty too, every process is selected. Processes without profiles are always
+    /// selected.
+    ///
+    /// # Errors
+    /// Returns an error if a requested profile is not used by any process, if a
+    /// selected process depends on one the profiles exclude, or on a dependency cycle.
+    ///
+    /// # Examples
+    /// ```
+    /// use sentinel::core::ConfigManager;
+    ///
+    /// let config = ConfigManager::default_config();
+    /// let selected = ConfigManager::select_processes(&config, &[])?;
+    /// assert_eq!(selected.len(), 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn select_processes(config: &Config, profiles: &[String]) -> Result<Vec<ProcessConfig>> {
+        let active = if profiles.is_empty() {
+            &config.settings.default_profiles
+        } else {
+            profiles
+        };
+
+        // Reject typos instead of silently starting nothing
+        let known: HashSet<&String> = config
+            .processes
+            .iter()
+            .flat_map(|p| p.profiles.iter())
+            .collect();
+        if let Some(unknown) = active.iter().find(|p| !known.contains(p)) {
+            let mut available: Vec<&str> = known.iter().map(|p| p.as_str()).collect();
+            available.sort_unstable();
+            return Err(SentinelError::InvalidConfig {
+                reason: format!(
+                    "Unknown profile '{}' (available: {})",
+                    unknown,
+                    available.join(", ")
+                ),
+            });
+        }
+
+        let selected: HashMap<&str, &ProcessConfig> = config
+            .processes
+            .iter()
+            .filter(|p| {
+                active.is_empty()
+                    || p.profiles.is_empty()
+                    || p.profiles.iter().any(|profile| active.contains(profile))
+            })
+            .map(|p| (p.name.as_str(), p))
+            .collect();
+
+        // Every dependency of a selected process must be selected as well
+        for process in config
+            .processes
+            .iter()
+            .filter(|p| selected.contains_key(p.name.as_str()))
+        {
+            for dep in &process.depends_on {
+                if selected.contains_key(dep.as_str()) {
+                    continue;
+                }
+                return Err(match config.processes.iter().find(|p| &p.name == dep) {
+                    Some(dependency) => SentinelError::ExcludedDependency {
+                        process: process.name.clone(),
+                        dependency: dep.clone(),
+                        profiles: dependency.profiles.clone(),
+                    },
+                    None => SentinelError::UnknownDependency {
+                        process: process.name.clone(),
+                        dependency: dep.clone(),
+                    },
+                });
+            }
+        }
+
+        // Dependencies first, otherwise in file order
+        let selected: Vec<ProcessConfig> = config
+            .processes
+            .iter()
+            .filter(|p| selected.contains_key(p.name.as_str()))
+            .cloned()
+            .collect();
+        Self::dependency_order(&selected)
+    }
+
+    /// Copies the process `source` of `config` as `name`, with `overrides`
+    /// applied.
+    ///
+    /// The copy is checked like any other process, and the ports it declares
+    /// must not clash with another process in `config` or be in use already.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` if there is no process `source`,
+    /// `InvalidConfig` if `name` is taken or the copy is invalid, and
+    /// `PortConflict` or `PortInUse` if one of its ports is unavailable.
+    pub fn clone_process(
+        config: &Config,
+        source: &str,
+        name: &str,
+        overrides: &ProcessOverrides,
+    ) -> Result<ProcessConfig> {
+        let source = config
+            .processes
+            .iter()
+            .find(|p| p.name == source)
+            .ok_or_else(|| SentinelError::ProcessNotFound {
+                name: source.to_string(),
+            })?;
+        if config.processes.iter().any(|p| p.name == name) {
+            return Err(SentinelError::InvalidConfig {
+                reason: format!("Process '{}' already exists", name),
+            });
+        }
+
+        let copy = overrides.apply(source, name);
+        validation::validate_process(&copy, &config.settings)?;
+        ports::preflight(&copy, &config.processes)?;
+        Ok(copy)
+    }
+
+    /// Orders processes so that dependencies come before their dependents,
+    /// otherwise keeping the given order.
+    ///
+    /// Dependencies that are not in `processes` are ignored.
+    ///
+    /// # Errors
+    /// Returns [`SentinelError::DependencyCycle`] if the processes depend on
+    /// each other in a cycle.
+    pub fn dependency_order(processes: &[ProcessConfig]) -> Result<Vec<ProcessConfig>> {
+        let selected: HashMap<&str, &ProcessConfig> =
+            processes.iter().map(|p| (p.name.as_str(), p)).collect();
+
+        let mut ordered = Vec::with_capacity(processes.len());
+        let mut visited = HashSet::new();
+        let mut in_progress = Vec::new();
+        for process in processes {
+            Self::visit_in_order(
+                process,
+                &selected,
+                &mut visited,
+                &mut in_progress,
+                &mut ordered,
+            )?;
+        }
+
+        Ok(ordered)
+    }
+
+    /// Appends `process` to `ordered` after its dependencies (depth-first).
+    fn visit_in_order<'a>(
+        process: &'a ProcessConfig,
+        selected: &HashMap<&'a str, &'a ProcessConfig>,
+        visited: &mut HashSet<&'a str>,
+        in_progress: &mut Vec<&'a str>,
+        ordered: &mut Vec<ProcessConfig>,
+    ) -> Result<()> {
+        let name = process.name.as_str();
+        if visited.contains(name) {
+            return Ok(());
+        }
+        if let Some(start) = in_progress.iter().position(|n| *n == name) {
+            let mut deps: Vec<String> =
+                in_progress[start..].iter().map(|s| s.to_string()).collect();
+            deps.push(name.to_string());
+            return Err(SentinelError::DependencyCycle { deps });
+        }
+        in_progress.push(name);
+
+        for dep in &process.depends_on {
+            if let Some(dependency) = selected.get(dep.as_str()) {
+                Self::visit_in_order(dependency, selected, visited, in_progress, ordered)?;
+            }
         }
+
+        in_progress.pop();
+        visited.insert(name);
+        ordered.push(process.clone());
+        Ok(())
     }
 
     /// Validates a configuration.
@@ -132,12 +714,22 @@ impl ConfigManager {
     ///
     /// # Errors
     /// Returns an error if validation fails.
-    fn validate(config: &Config) -> Result<()> {
+    pub fn validate(config: &Config) -> Result<()> {
+        match Self::problems(config).into_iter().next() {
+            Some(problem) => Err(problem),
+            None => Ok(()),
+        }
+    }
+
+    /// Collects every validation problem instead of stopping at the first.
+    fn problems(config: &Config) -> Vec<SentinelError> {
+        let mut problems = Vec::new();
+
         // Check for duplicate process names
         let mut names = HashSet::new();
         for process in &config.processes {
             if !names.insert(&process.name) {
-                return Err(SentinelError::InvalidConfig {
+                problems.push(SentinelError::InvalidConfig {
                     reason: format!("Duplicate process name: '{}'", process.name),
                 });
             }
@@ -145,42 +737,64 @@ impl ConfigManager {
 
         // Validate each process
         for process in &config.processes {
-            Self::validate_process(process, &names)?;
+            problems.extend(validation::check_process(process, &config.settings));
+            problems.extend(Self::dependency_problems(process, &names));
         }
 
         // Check for dependency cycles
-        Self::check_dependency_cycles(config)?;
-
-        Ok(())
-    }
-
-    /// Validates a single process configuration.
-    fn validate_process(process: &ProcessConfig, all_names: &HashSet<&String>) -> Result<()> {
-        // Check name is not empty
-        if process.name.trim().is_empty() {
-            return Err(SentinelError::InvalidConfig {
-                reason: "Process name cannot be empty".to_string(),
-            });
+        if let Err(e) = Self::check_dependency_cycles(config) {
+            problems.push(e);
         }
 
-        // Check command is not empty
-        if process.command.trim().is_empty() {
-            return Err(SentinelError::InvalidConfig {
-                reason: format!("Process '{}' has empty command", process.name),
-            });
+        // Validate alert rules
+        let mut rule_names = HashSet::new();
+        for rule in &config.alerts {
+            if !rule_names.insert(&rule.name) {
+                problems.push(SentinelError::InvalidConfig {
+                    reason: format!("Duplicate alert rule name: '{}'", rule.name),
+                });
+            }
+            problems.extend(validation::check_alert_rule(rule, &names));
         }
 
-        // Check dependencies exist
-        for dep in &process.depends_on {
-            if !all_names.contains(dep) {
-                return Err(SentinelError::UnknownDependency {
-                    process: process.name.clone(),
-                    dependency: dep.clone(),
+        // Validate notification sinks
+        let mut sink_names = HashSet::new();
+        for sink in &config.settings.notification_sinks {
+            if !sink_names.insert(&sink.name) {
+                problems.push(SentinelError::InvalidConfig {
+                    reason: format!("Duplicate notification sink name: '{}'", sink.name),
                 });
             }
+            problems.extend(validation::check_notification_sink(sink));
         }
+        problems.extend(validation::check_api(&config.settings.api));
 
-        Ok(())
+        problems
+    }
+
+    /// Settings that are valid but probably not what was meant.
+    fn warnings(config: &Config) -> Vec<String> {
+        config
+            .processes
+            .iter()
+            .flat_map(|process| validation::process_warnings(process, &config.settings))
+            .collect()
+    }
+
+    /// Checks that every dependency of a process exists.
+    fn dependency_problems(
+        process: &ProcessConfig,
+        all_names: &HashSet<&String>,
+    ) -> Vec<SentinelError> {
+        process
+            .depends_on
+            .iter()
+            .filter(|dep| !all_names.contains(dep))
+            .map(|dep| SentinelError::UnknownDependency {
+                process: process.name.clone(),
+                dependency: dep.clone(),
+            })
+            .collect()
     }
 
     /// Checks for circular dependencies using depth-first search.
@@ -239,26 +853,80 @@ impl ConfigManager {
         None
     }
 
-    /// Parses YAML configuration.
+    /// Parses YAML configuration, resolving `defaults` and `extends`.
     fn parse_yaml(contents: &str, path: &Path) -> Result<Config> {
-        serde_yaml::from_str(contents).map_err(|source| SentinelError::ConfigParseFailed {
+        let document: serde_yaml::Value = serde_yaml::from_str(contents)
+            .map_err(|source| Self::parse_error(contents, path, source))?;
+        if config_inheritance::uses_inheritance(&document) {
+            return Self::parse_resolved(document, path);
+        }
+        serde_yaml::from_str(contents).map_err(|source| Self::parse_error(contents, path, source))
+    }
+
+    /// Resolves inheritance in a parsed document and reads the result.
+    ///
+    /// Errors can't point into the file, as the resolved document isn't what
+    /// was written.
+    fn parse_resolved(mut document: serde_yaml::Value, path: &Path) -> Result<Config> {
+        config_inheritance::resolve(&mut document)?;
+        // Read back from text, where scalars like `PORT: 3000` may become strings
+        let resolved =
+            serde_yaml::to_string(&document).map_err(|e| SentinelError::Other(e.to_string()))?;
+        serde_yaml::from_str(&resolved).map_err(|source| SentinelError::ConfigParseFailed {
             path: path.to_path_buf(),
+            line: None,
+            column: None,
+            snippet: None,
             source,
         })
     }
 
-    /// Parses JSON configuration.
-    fn parse_json(contents: &str, _path: &Path) -> Result<Config> {
-        serde_json::from_str(contents).map_err(|e| SentinelError::InvalidConfig {
+    /// Wraps a YAML error with its location and the surrounding source lines.
+    fn parse_error(contents: &str, path: &Path, source: serde_yaml::Error) -> SentinelError {
+        let location = source.location();
+        SentinelError::ConfigParseFailed {
+            path: path.to_path_buf(),
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            snippet: location
+                .map(|l| config_schema::source_context(contents, l.line(), l.column())),
+            source,
+        }
+    }
+
+    /// Parses JSON configuration, resolving `defaults` and `extends`.
+    fn parse_json(contents: &str, path: &Path) -> Result<Config> {
+        let json_error = |e: serde_json::Error| SentinelError::InvalidConfig {
             reason: format!("JSON parse error: {}", e),
-        })
+        };
+        let document: serde_yaml::Value = serde_json::from_str(contents).map_err(json_error)?;
+        if config_inheritance::uses_inheritance(&document) {
+            return Self::parse_resolved(document, path);
+        }
+        serde_json::from_str(contents).map_err(json_error)
+    }
+
+    /// The config as YAML with inheritance resolved, to check what `defaults`
+    /// and `extends` add up to.
+    pub fn resolved_yaml(config: &Config) -> Result<String> {
+        let mut resolved = config.clone();
+        resolved.defaults.clear();
+        for process in &mut resolved.processes {
+            process.extends = None;
+        }
+        serde_yaml::to_string(&resolved).map_err(|e| SentinelError::Other(e.to_string()))
     }
 
     /// Interpolates environment variables in config strings.
     ///
-    /// Supports two syntax forms:
-    /// - `${VAR}` - Simple variable substitution
+    /// Supports these syntax forms:
+    /// - `${VAR}` - Simple variable substitution (left as-is if unset)
     /// - `${VAR:-default}` - Variable with default value if unset
+    /// - `${VAR:?message}` - Required variable; fails with `message` if unset
+    /// - `$${VAR}` - Escaped, produces a literal `${VAR}`
+    ///
+    /// Defaults and messages may contain further expressions, e.g.
+    /// `${API_URL:-http://${HOST:-localhost}:3000}`.
     ///
     /// # Arguments
     /// * `input` - String with potential environment variable references
@@ -266,38 +934,236 @@ impl ConfigManager {
     /// # Returns
     /// String with all environment variables interpolated
     ///
+    /// # Errors
+    /// Returns `InvalidConfig` naming every required variable that is unset.
+    ///
     /// # Examples
     /// ```
     /// use sentinel::core::ConfigManager;
     /// std::env::set_var("TEST_PORT", "3000");
     ///
-    /// let result = ConfigManager::interpolate_env_vars("http://localhost:${TEST_PORT}");
+    /// let result = ConfigManager::interpolate_env_vars("http://localhost:${TEST_PORT}")?;
     /// assert_eq!(result, "http://localhost:3000");
     ///
-    /// let with_default = ConfigManager::interpolate_env_vars("${MISSING:-8080}");
+    /// let with_default = ConfigManager::interpolate_env_vars("${MISSING:-8080}")?;
     /// assert_eq!(with_default, "8080");
+    ///
+    /// let escaped = ConfigManager::interpolate_env_vars("echo $${HOME}")?;
+    /// assert_eq!(escaped, "echo ${HOME}");
+    ///
+    /// let required = ConfigManager::interpolate_env_vars("${MISSING:?set it in .env}");
+    /// assert!(required.is_err());
+    /// # Ok::<(), sentinel::SentinelError>(())
     /// ```
-    pub fn interpolate_env_vars(input: &str) -> String {
-        // Regex pattern to match ${VAR} or ${VAR:-default}
-        // Capture groups:
-        // 1: Variable name
-        // 2: Optional :- and default value
-        // 3: Default value (if present)
-        let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(:-([^}]*))?\}").unwrap();
-
-        re.replace_all(input, |caps: &regex::Captures| {
-            let var_name = &caps[1];
-            let default_value = caps.get(3).map(|m| m.as_str());
-
-            match std::env::var(var_name) {
-                Ok(value) => value,
-                Err(_) => default_value.unwrap_or(&caps[0]).to_string(),
-            }
+    pub fn interpolate_env_vars(input: &str) -> Result<String> {
+        let mut missing = Vec::new();
+        let output = Self::expand(input, &mut missing);
+
+        if missing.is_empty() {
+            return Ok(output);
+        }
+
+        let variables: Vec<String> = missing
+            .iter()
+            .map(|(name, message)| {
+                if message.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{} ({})", name, message)
+                }
+            })
+            .collect();
+        Err(SentinelError::InvalidConfig {
+            reason: format!(
+                "Required environment variables are not set: {}",
+                variables.join(", ")
+            ),
         })
-        .to_string()
+    }
+
+    /// Expands every expression in `input`, collecting unset required variables.
+    fn expand(input: &str, missing: &mut Vec<(String, String)>) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(start) = rest.find('$') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            if let Some(after) = rest.strip_prefix("$${") {
+                output.push_str("${");
+                rest = after;
+                continue;
+            }
+
+            match Self::parse_expression(rest) {
+                Some((name, fallback, len)) => {
+                    let value = match (std::env::var(name), fallback) {
+                        (Ok(value), _) => value,
+                        (Err(_), Fallback::Default(default)) => Self::expand(default, missing),
+                        (Err(_), Fallback::Required(message)) => {
+                            let message = Self::expand(message, missing);
+                            if !missing.iter().any(|(seen, _)| seen == name) {
+                                missing.push((name.to_string(), message));
+                            }
+                            rest[..len].to_string()
+                        }
+                        (Err(_), Fallback::None) => rest[..len].to_string(),
+                    };
+                    output.push_str(&value);
+                    rest = &rest[len..];
+                }
+                None => {
+                    output.push('$');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        output.push_str(rest);
+        output
+    }
+
+    /// Parses a `${...}` expression at the start of `text`.
+    ///
+    /// Returns the variable name, its fallback and the length of the expression.
+    fn parse_expression(text: &str) -> Option<(&str, Fallback<'_>, usize)> {
+        let body = text.strip_prefix("${")?;
+        let name_len = body
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(body.len());
+        let name = &body[..name_len];
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let after = &body[name_len..];
+        if after.starts_with('}') {
+            return Some((name, Fallback::None, 2 + name_len + 1));
+        }
+
+        let (arg, required) = match (after.strip_prefix(":-"), after.strip_prefix(":?")) {
+            (Some(arg), _) => (arg, false),
+            (_, Some(arg)) => (arg, true),
+            _ => return None,
+        };
+        let arg_len = Self::closing_brace(arg)?;
+        let fallback = if required {
+            Fallback::Required(&arg[..arg_len])
+        } else {
+            Fallback::Default(&arg[..arg_len])
+        };
+
+        Some((name, fallback, 2 + name_len + 2 + arg_len + 1))
+    }
+
+    /// Position of the `}` closing an expression, skipping nested `${...}`.
+    fn closing_brace(text: &str) -> Option<usize> {
+        let bytes = text.as_bytes();
+        let mut depth = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                    depth += 1;
+                    i += 1;
+                }
+                b'}' if depth == 0 => return Some(i),
+                b'}' => depth -= 1,
+                _ => {}
+            }
+            i += 1;
+        }
+
+        None
     }
 }
 
+/// What changed between two configurations, by process name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    /// Processes only in the new configuration.
+    pub added: Vec<String>,
+    /// Processes only in the old configuration.
+    pub removed: Vec<String>,
+    /// Processes in both whose settings differ.
+    pub modified: Vec<String>,
+    /// Whether global settings, global environment or alert rules differ.
+    pub settings_changed: bool,
+}
+
+impl ConfigDiff {
+    /// Compares `new` against `old`, keeping the order processes are listed in.
+    pub fn between(old: &Config, new: &Config) -> Self {
+        let old_processes: HashMap<&str, &ProcessConfig> = old
+            .processes
+            .iter()
+            .map(|process| (process.name.as_str(), process))
+            .collect();
+        let new_names: HashSet<&str> = new.processes.iter().map(|p| p.name.as_str()).collect();
+
+        let mut diff = Self::default();
+        for process in &new.processes {
+            match old_processes.get(process.name.as_str()) {
+                None => diff.added.push(process.name.clone()),
+                Some(old) if !same(*old, process) => diff.modified.push(process.name.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .processes
+            .iter()
+            .filter(|process| !new_names.contains(process.name.as_str()))
+            .map(|process| process.name.clone())
+            .collect();
+        diff.settings_changed = !same(&old.settings, &new.settings)
+            || old.global_env != new.global_env
+            || !same(&old.alerts, &new.alerts);
+        diff
+    }
+
+    /// Whether the configurations are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && !self.settings_changed
+    }
+
+    /// One line for logs and prompts, like `2 processes modified, 1 added`.
+    pub fn summary(&self) -> String {
+        let count = |n: usize, what: &str| {
+            let noun = if n == 1 { "process" } else { "processes" };
+            format!("{} {} {}", n, noun, what)
+        };
+        let mut parts = Vec::new();
+        if !self.modified.is_empty() {
+            parts.push(count(self.modified.len(), "modified"));
+        }
+        if !self.added.is_empty() {
+            parts.push(count(self.added.len(), "added"));
+        }
+        if !self.removed.is_empty() {
+            parts.push(count(self.removed.len(), "removed"));
+        }
+        if self.settings_changed {
+            parts.push("settings changed".to_string());
+        }
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Whether two values serialize the same; the config models don't implement `PartialEq`.
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,30 +1201,23 @@ settings:
                 ProcessConfig {
                     name: "dup".to_string(),
                     command: "cmd1".to_string(),
-                    args: vec![],
-                    cwd: None,
-                    env: HashMap::new(),
-                    auto_restart: true,
-                    restart_limit: 5,
-                    restart_delay: 1000,
-                    depends_on: vec![],
-                    health_check: None,
+                    auto_restart: Some(true),
+                    restart_delay: Some(1000),
+                    ..Default::default()
                 },
                 ProcessConfig {
                     name: "dup".to_string(),
                     command: "cmd2".to_string(),
-                    args: vec![],
-                    cwd: None,
-                    env: HashMap::new(),
-                    auto_restart: true,
-                    restart_limit: 5,
-                    restart_delay: 1000,
-                    depends_on: vec![],
-                    health_check: None,
+                    auto_restart: Some(true),
+                    restart_delay: Some(1000),
+                    ..Default::default()
                 },
             ],
             settings: Default::default(),
             global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
         };
 
         let result = ConfigManager::validate(&config);
@@ -371,17 +1230,16 @@ settings:
             processes: vec![ProcessConfig {
                 name: "test".to_string(),
                 command: "cmd".to_string(),
-                args: vec![],
-                cwd: None,
-                env: HashMap::new(),
-                auto_restart: true,
-                restart_limit: 5,
-                restart_delay: 1000,
+                auto_restart: Some(true),
+                restart_delay: Some(1000),
                 depends_on: vec!["nonexistent".to_string()],
-                health_check: None,
+                ..Default::default()
             }],
             settings: Default::default(),
             global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
         };
 
         let result = ConfigManager::validate(&config);
@@ -398,36 +1256,783 @@ settings:
                 ProcessConfig {
                     name: "A".to_string(),
                     command: "cmd".to_string(),
-                    args: vec![],
-                    cwd: None,
-                    env: HashMap::new(),
-                    auto_restart: true,
-                    restart_limit: 5,
-                    restart_delay: 1000,
+                    auto_restart: Some(true),
+                    restart_delay: Some(1000),
                     depends_on: vec!["B".to_string()],
-                    health_check: None,
+                    ..Default::default()
                 },
                 ProcessConfig {
                     name: "B".to_string(),
                     command: "cmd".to_string(),
-                    args: vec![],
-                    cwd: None,
-                    env: HashMap::new(),
-                    auto_restart: true,
-                    restart_limit: 5,
-                    restart_delay: 1000,
+                    auto_restart: Some(true),
+                    restart_delay: Some(1000),
                     depends_on: vec!["A".to_string()],
-                    health_check: None,
+                    ..Default::default()
                 },
             ],
             settings: Default::default(),
             global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
         };
 
         let result = ConfigManager::validate(&config);
         assert!(matches!(result, Err(SentinelError::DependencyCycle { .. })));
     }
 
+    fn process(name: &str, profiles: &[&str], depends_on: &[&str]) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: "cmd".to_string(),
+            auto_restart: Some(true),
+            restart_delay: Some(1000),
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            profiles: profiles.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn team_config() -> Config {
+        Config {
+            processes: vec![
+                process("web", &["frontend"], &["mock-api"]),
+                process("api", &["backend"], &["db"]),
+                process("mock-api", &["frontend"], &["proxy"]),
+                process("db", &["backend"], &[]),
+                process("proxy", &[], &[]),
+            ],
+            settings: Default::default(),
+            global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_clone_process() {
+        let mut config = team_config();
+        config.processes[1]
+            .env
+            .insert("PORT".to_string(), "47913".to_string());
+
+        let overrides = ProcessOverrides {
+            port: Some(47914),
+            ..Default::default()
+        };
+        let copy = ConfigManager::clone_process(&config, "api", "api-2", &overrides).unwrap();
+        assert_eq!(copy.name, "api-2");
+        assert_eq!(copy.depends_on, ["db"]);
+        assert_eq!(copy.env["PORT"], "47914");
+
+        // Same fixed port as the source
+        let err =
+            ConfigManager::clone_process(&config, "api", "api-2", &Default::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            SentinelError::PortConflict { port: 47913, .. }
+        ));
+
+        let err = ConfigManager::clone_process(&config, "api", "db", &overrides).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: Process 'db' already exists"
+        );
+
+        let err = ConfigManager::clone_process(&config, "nope", "api-2", &overrides).unwrap_err();
+        assert!(matches!(err, SentinelError::ProcessNotFound { .. }));
+    }
+
+    fn names(processes: &[ProcessConfig]) -> Vec<&str> {
+        processes.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_select_profile_in_dependency_order() {
+        let config = team_config();
+
+        let frontend = ConfigManager::select_processes(&config, &["frontend".to_string()]).unwrap();
+        assert_eq!(names(&frontend), vec!["proxy", "mock-api", "web"]);
+
+        let backend = ConfigManager::select_processes(&config, &["backend".to_string()]).unwrap();
+        assert_eq!(names(&backend), vec!["db", "api", "proxy"]);
+    }
+
+    #[test]
+    fn test_select_without_profiles_starts_everything() {
+        let config = team_config();
+
+        let all = ConfigManager::select_processes(&config, &[]).unwrap();
+        assert_eq!(names(&all), vec!["proxy", "mock-api", "web", "db", "api"]);
+    }
+
+    #[test]
+    fn test_select_uses_default_profiles() {
+        let mut config = team_config();
+        config.settings.default_profiles = vec!["backend".to_string()];
+
+        let selected = ConfigManager::select_processes(&config, &[]).unwrap();
+        assert_eq!(names(&selected), vec!["db", "api", "proxy"]);
+
+        // An explicit selection overrides the default
+        let selected = ConfigManager::select_processes(&config, &["frontend".to_string()]).unwrap();
+        assert_eq!(names(&selected), vec!["proxy", "mock-api", "web"]);
+    }
+
+    #[test]
+    fn test_select_rejects_excluded_dependency() {
+        let mut config = team_config();
+        config.processes[0].depends_on.push("api".to_string());
+
+        let err = ConfigManager::select_processes(&config, &["frontend".to_string()]).unwrap_err();
+        assert!(matches!(err, SentinelError::ExcludedDependency { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Process 'web' depends on 'api', which the selected profiles exclude; add profile 'backend'"
+        );
+
+        // Selecting both profiles satisfies the dependency
+        let both = ["frontend".to_string(), "backend".to_string()];
+        let selected = ConfigManager::select_processes(&config, &both).unwrap();
+        let order = names(&selected);
+        let position = |name| order.iter().position(|n| *n == name).unwrap();
+        assert!(position("db") < position("api"));
+        assert!(position("api") < position("web"));
+    }
+
+    #[test]
+    fn test_select_rejects_unknown_profile() {
+        let config = team_config();
+
+        let err = ConfigManager::select_processes(&config, &["qa".to_string()]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown profile 'qa' (available: backend, frontend)"));
+    }
+
+    fn write_file(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_nested_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            r#"
+include: [./services/*.sentinel.yaml]
+processes:
+  - name: proxy
+    command: caddy run
+globalEnv:
+  LOG_LEVEL: debug
+settings:
+  logLevel: warn
+"#,
+        );
+        write_file(
+            dir.path(),
+            "services/api.sentinel.yaml",
+            r#"
+include: [workers/*.yaml]
+processes:
+  - name: api
+    command: cargo run
+    cwd: ./api
+globalEnv:
+  LOG_LEVEL: info
+  DATABASE_URL: postgres://localhost/dev
+settings:
+  logLevel: trace
+"#,
+        );
+        write_file(
+            dir.path(),
+            "services/web.sentinel.yaml",
+            "processes:\n  - name: web\n    command: npm run dev\n",
+        );
+        write_file(
+            dir.path(),
+            "services/workers/email.yaml",
+            "processes:\n  - name: email\n    command: node worker.js\n    cwd: email\n",
+        );
+
+        let config = ConfigManager::load_from_file(&root).unwrap();
+
+        let names: Vec<&str> = config.processes.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["proxy", "api", "email", "web"]);
+        assert_eq!(config.processes[1].cwd, Some(PathBuf::from("services/api")));
+        assert_eq!(
+            config.processes[2].cwd,
+            Some(PathBuf::from("services/workers/email"))
+        );
+        // The including file wins on conflicts; settings come from the root
+        assert_eq!(config.global_env["LOG_LEVEL"], "debug");
+        assert_eq!(
+            config.global_env["DATABASE_URL"],
+            "postgres://localhost/dev"
+        );
+        assert_eq!(config.settings.log_level, "warn");
+    }
+
+    #[test]
+    fn test_resolved_files_follow_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "include: [services/*.yaml]\nprocesses: []\n",
+        );
+        let api = write_file(
+            dir.path(),
+            "services/api.yaml",
+            "include: [../shared.yaml]\n",
+        );
+        let shared = write_file(dir.path(), "shared.yaml", "processes: []\n");
+
+        let files = ConfigManager::resolved_files(&root);
+        let mut included = [api.canonicalize().unwrap(), shared.canonicalize().unwrap()];
+        included.sort();
+        assert_eq!(files[0], root);
+        assert_eq!(files[1..], included[..]);
+
+        // Files that fail to load are still listed, the missing root too
+        fs::write(&api, "include: [../shared.yaml\n").unwrap();
+        assert_eq!(ConfigManager::resolved_files(&root).len(), 2);
+        let missing = dir.path().join("missing.yaml");
+        assert_eq!(ConfigManager::resolved_files(&missing), vec![missing]);
+    }
+
+    #[test]
+    fn test_include_glob_without_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "include: [./services/*.sentinel.yaml, missing/dir/*.yaml]\nprocesses:\n  - name: web\n    command: npm run dev\n",
+        );
+
+        let config = ConfigManager::load_from_file(&root).unwrap();
+        assert_eq!(config.processes.len(), 1);
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "include: [a.yaml]\nprocesses: []\n",
+        );
+        write_file(dir.path(), "a.yaml", "include: [b.yaml]\n");
+        write_file(dir.path(), "b.yaml", "include: [sentinel.yaml]\n");
+
+        let err = ConfigManager::load_from_file(&root).unwrap_err();
+        match &err {
+            SentinelError::IncludeCycle { chain } => {
+                assert_eq!(chain.len(), 4);
+                assert_eq!(chain.first(), chain.last());
+            }
+            other => panic!("expected an include cycle, got {other:?}"),
+        }
+        assert!(err.to_string().contains("a.yaml -> "));
+    }
+
+    const BILLION_LAUGHS: &str = r#"
+a: &a ["lol","lol","lol","lol","lol","lol","lol","lol","lol"]
+b: &b [*a,*a,*a,*a,*a,*a,*a,*a,*a]
+c: &c [*b,*b,*b,*b,*b,*b,*b,*b,*b]
+d: &d [*c,*c,*c,*c,*c,*c,*c,*c,*c]
+e: &e [*d,*d,*d,*d,*d,*d,*d,*d,*d]
+f: &f [*e,*e,*e,*e,*e,*e,*e,*e,*e]
+g: &g [*f,*f,*f,*f,*f,*f,*f,*f,*f]
+h: &h [*g,*g,*g,*g,*g,*g,*g,*g,*g]
+i: &i [*h,*h,*h,*h,*h,*h,*h,*h,*h]
+"#;
+
+    #[test]
+    fn test_load_rejects_oversized_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = "processes:\n".to_string() + &"  - name: test\n".repeat(750_000);
+        let path = write_file(dir.path(), "huge.yaml", &contents);
+        assert!(contents.len() > 10 * 1024 * 1024);
+
+        let started = std::time::Instant::now();
+        let err = ConfigManager::load_from_file(&path).unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(matches!(err, SentinelError::InvalidConfig { .. }));
+        assert!(err.to_string().contains("over the limit of 5242880 bytes"));
+    }
+
+    #[test]
+    fn test_load_rejects_billion_laughs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "bomb.yaml", BILLION_LAUGHS);
+
+        let started = std::time::Instant::now();
+        let err = ConfigManager::load_from_file(&path).unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(matches!(err, SentinelError::InvalidConfig { .. }));
+        assert!(err.to_string().contains("more than 100000 YAML nodes"));
+
+        let issues = ConfigManager::validate_file(&path);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("more than 100000 YAML nodes"));
+    }
+
+    #[test]
+    fn test_load_rejects_deep_nesting() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = "[".repeat(20) + &"]".repeat(20);
+        let path = write_file(dir.path(), "deep.yaml", &format!("globalEnv: {}\n", nested));
+        let limits = ConfigLimits {
+            max_depth: 10,
+            ..ConfigLimits::default()
+        };
+
+        let err = ConfigManager::load_from_file_with_limits(&path, &limits).unwrap_err();
+        assert!(err.to_string().contains("nests deeper than 10 levels"));
+    }
+
+    #[test]
+    fn test_size_limit_counts_included_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let process = "processes:\n  - name: {}\n    command: echo hi\n";
+        let root = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            &format!("include: [a.yaml]\n{}", process.replace("{}", "root")),
+        );
+        write_file(dir.path(), "a.yaml", &process.replace("{}", "a"));
+        let root_size = fs::metadata(&root).unwrap().len();
+
+        // Each file fits on its own, but not together
+        let limits = ConfigLimits {
+            max_file_size: root_size + 10,
+            ..ConfigLimits::default()
+        };
+        let err = ConfigManager::load_from_file_with_limits(&root, &limits).unwrap_err();
+        assert!(err.to_string().contains("a.yaml takes the config to"));
+
+        let limits = ConfigLimits {
+            max_file_size: root_size * 2,
+            ..ConfigLimits::default()
+        };
+        assert!(ConfigManager::load_from_file_with_limits(&root, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_save_refuses_config_with_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "include: [services/*.yaml]\nprocesses:\n  - name: web\n    command: npm run dev\n",
+        );
+        write_file(
+            dir.path(),
+            "services/api.yaml",
+            "processes:\n  - name: api\n    command: cargo run\n",
+        );
+
+        let config = ConfigManager::load_from_file(&root).unwrap();
+        let err = ConfigManager::save_to_file(&config, &root).unwrap_err();
+        assert!(err.to_string().contains("edit those files directly"));
+
+        // The root file is left untouched
+        let reloaded = ConfigManager::load_from_file(&root).unwrap();
+        assert_eq!(reloaded.processes.len(), 2);
+    }
+
+    const INHERITING_CONFIG: &str = r#"
+defaults:
+  node:
+    command: node
+    env:
+      NODE_ENV: development
+      PORT: 3000
+    restartLimit: 5
+processes:
+  - name: db
+    command: postgres
+  - name: api
+    extends: node
+    args: [api.js]
+    dependsOn: [db]
+  - name: worker
+    extends: api
+    args: [worker.js]
+    env:
+      PORT: 3001
+"#;
+
+    #[test]
+    fn test_load_resolves_inheritance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "sentinel.yaml", INHERITING_CONFIG);
+
+        let config = ConfigManager::load_from_file(&path).unwrap();
+        let worker = &config.processes[2];
+        assert_eq!(worker.extends.as_deref(), Some("api"));
+        assert_eq!(worker.command, "node");
+        assert_eq!(worker.args, vec!["worker.js"]);
+        assert_eq!(worker.depends_on, vec!["db"]);
+        assert_eq!(worker.restart_limit, 5);
+        assert_eq!(worker.env["NODE_ENV"], "development");
+        assert_eq!(worker.env["PORT"], "3001");
+        assert!(config.defaults.contains_key("node"));
+
+        // JSON configs inherit the same way
+        let document: serde_yaml::Value = serde_yaml::from_str(INHERITING_CONFIG).unwrap();
+        let json = write_file(
+            dir.path(),
+            "sentinel.json",
+            &serde_json::to_string(&document).unwrap(),
+        );
+        let config = ConfigManager::load_from_file(&json).unwrap();
+        assert_eq!(config.processes[2].command, "node");
+    }
+
+    #[test]
+    fn test_load_reports_inheritance_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "processes:\n  - name: api\n    extends: nod\n",
+        );
+        let err = ConfigManager::load_from_file(&path).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'api' extends 'nod', which is neither in defaults nor a process"));
+
+        let path = write_file(
+            dir.path(),
+            "cycle.yaml",
+            "processes:\n  - {name: a, extends: b, command: x}\n  - {name: b, extends: a}\n",
+        );
+        let issues = ConfigManager::validate_file(&path);
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(issues[0]
+            .message
+            .contains("Config inheritance cycle: a -> b -> a"));
+
+        // Nothing to inherit the command from
+        let path = write_file(
+            dir.path(),
+            "missing.yaml",
+            "defaults:\n  node: {env: {A: b}}\nprocesses:\n  - name: api\n    extends: node\n",
+        );
+        let err = ConfigManager::load_from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("command"), "{}", err);
+    }
+
+    #[test]
+    fn test_save_keeps_inheritance_unresolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "sentinel.yaml", INHERITING_CONFIG);
+
+        let mut config = ConfigManager::load_from_file(&path).unwrap();
+        config.processes[1].depends_on.push("cache".to_string());
+        config.processes[2]
+            .env
+            .insert("DEBUG".to_string(), "1".to_string());
+        config
+            .processes
+            .push(ConfigManager::default_config().processes[0].clone());
+        config.processes[3].name = "cache".to_string();
+        ConfigManager::save_to_file(&config, &path).unwrap();
+
+        let saved: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let expected: serde_yaml::Value = serde_yaml::from_str(
+            "{name: api, extends: node, args: [api.js], dependsOn: [db, cache]}",
+        )
+        .unwrap();
+        assert_eq!(saved["processes"][1], expected);
+        // The worker keeps the dependencies it had, rather than picking up
+        // the one added to api
+        let expected: serde_yaml::Value = serde_yaml::from_str(
+            "{name: worker, extends: api, args: [worker.js], env: {PORT: '3001', DEBUG: '1'}, dependsOn: [db]}",
+        )
+        .unwrap();
+        assert_eq!(saved["processes"][2], expected);
+        assert_eq!(saved["defaults"]["node"]["restartLimit"], 5);
+
+        // And loads back to the same processes
+        let reloaded = ConfigManager::load_from_file(&path).unwrap();
+        assert!(ConfigDiff::between(&config, &reloaded).is_empty());
+    }
+
+    #[test]
+    fn test_resolved_yaml_drops_inheritance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "sentinel.yaml", INHERITING_CONFIG);
+        let config = ConfigManager::load_from_file(&path).unwrap();
+
+        let resolved = ConfigManager::resolved_yaml(&config).unwrap();
+        assert!(!resolved.contains("extends"));
+        assert!(!resolved.contains("defaults"));
+
+        let resolved: Config = serde_yaml::from_str(&resolved).unwrap();
+        assert_eq!(resolved.processes[2].command, "node");
+        assert_eq!(resolved.processes[2].env["NODE_ENV"], "development");
+    }
+
+    #[test]
+    fn test_parse_error_reports_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "processes:\n  - name: api\n    command: cargo run\n    autoRestart: yess\n",
+        );
+
+        match ConfigManager::load_from_file(&path) {
+            Err(SentinelError::ConfigParseFailed {
+                line,
+                column,
+                snippet,
+                ..
+            }) => {
+                assert_eq!(line, Some(4));
+                assert_eq!(column, Some(18));
+                assert!(snippet
+                    .unwrap()
+                    .ends_with("4 |     autoRestart: yess\n  |                  ^"));
+            }
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_file_reports_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "processes:\n  - name: api\n    command: cargo run\n    restrat_delay: 500\n",
+        );
+
+        let issues = ConfigManager::validate_file(&path);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Unknown key 'restrat_delay' in process 'api' (did you mean 'restartDelay'?)"
+        );
+        assert_eq!((issues[0].line, issues[0].column), (Some(4), Some(5)));
+    }
+
+    #[test]
+    fn test_validate_file_collects_every_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            r#"
+processes:
+  - name: api
+    command: cargo run
+    dependsOn: [db]
+  - name: api
+    command: ""
+settings:
+  logLevl: debug
+"#,
+        );
+
+        let messages: Vec<String> = ConfigManager::validate_file(&path)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown key 'logLevl' in settings (did you mean 'logLevel'?)",
+                "Invalid configuration: Duplicate process name: 'api'",
+                "Process 'api' depends on unknown process 'db'",
+                "Invalid configuration: Process 'api' has empty command",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_file_checks_alert_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            r#"
+processes:
+  - name: api
+    command: cargo run
+alerts:
+  - name: api-down
+    target: api
+    metric: crashed
+  - name: worker-down
+    target: worker
+    metric: crashed
+  - name: hot
+    target: system
+    metric: cpu
+  - name: api-disk
+    target: api
+    metric: disk_free
+    threshold: 10
+"#,
+        );
+
+        let messages: Vec<String> = ConfigManager::validate_file(&path)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Invalid configuration: Alert rule 'worker-down' targets unknown process 'worker'",
+                "Invalid configuration: Alert rule 'hot' needs a threshold for Cpu",
+                "Invalid configuration: Alert rule 'api-disk' watches DiskFree, which only applies to the system target",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_file_checks_notification_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            r#"
+processes:
+  - name: worker
+    command: ./worker
+settings:
+  notificationSinks:
+    - name: slack
+      type: webhook
+      url: https://hooks.slack.com/services/T0/B0/x
+      events: [crashed]
+      processes: [worker]
+    - name: slack
+      type: webhook
+      url: hooks.example.com
+      retries: 3
+"#,
+        );
+
+        let messages: Vec<String> = ConfigManager::validate_file(&path)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown key 'retries' in notification sink 'slack'",
+                "Invalid configuration: Duplicate notification sink name: 'slack'",
+                "Invalid configuration: Notification sink 'slack' needs an http:// or https:// URL",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_file_checks_api() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            r#"
+processes: []
+settings:
+  api:
+    enabled: true
+    port: 9000
+    tokne: abc
+"#,
+        );
+
+        let messages: Vec<String> = ConfigManager::validate_file(&path)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown key 'tokne' in settings.api (did you mean 'token'?)",
+                "Invalid configuration: The API needs a token when enabled",
+            ]
+        );
+
+        let config: Config =
+            serde_yaml::from_str("settings:\n  api:\n    enabled: true\n    token: abc\n").unwrap();
+        assert_eq!(
+            config.settings.api.address(),
+            "127.0.0.1:9740".parse().unwrap()
+        );
+        assert!(validation::check_api(&config.settings.api).is_empty());
+    }
+
+    #[test]
+    fn test_validate_file_rejects_unsafe_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            r#"
+processes:
+  - name: "api; rm -rf /"
+    command: echo
+  - name: maintenance
+    command: sudo
+    args: [systemctl, restart, nginx]
+"#,
+        );
+
+        let messages: Vec<String> = ConfigManager::validate_file(&path)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Invalid configuration: Invalid process name 'api; rm -rf /': use up to 128 letters, digits, '.', '_' or '-'",
+                "Invalid configuration: Process 'maintenance' uses blocked command 'sudo' (set allowPrivilegedCommands to permit it)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_file_reports_syntax_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "processes:\n  - name: api\n    command: [cargo\n",
+        );
+
+        let issues = ConfigManager::validate_file(&path);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].line.is_some());
+        assert!(issues[0].snippet.is_some());
+    }
+
+    #[test]
+    fn test_validate_file_accepts_valid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "processes:\n  - name: api\n    command: cargo run\n",
+        );
+
+        assert!(ConfigManager::validate_file(&path).is_empty());
+    }
+
     #[test]
     fn test_default_config() {
         let config = ConfigManager::default_config();
@@ -458,7 +2063,7 @@ settings:
     fn test_interpolate_env_vars_simple() {
         std::env::set_var("TEST_VAR", "test_value");
 
-        let result = ConfigManager::interpolate_env_vars("Value is ${TEST_VAR}");
+        let result = ConfigManager::interpolate_env_vars("Value is ${TEST_VAR}").unwrap();
         assert_eq!(result, "Value is test_value");
 
         std::env::remove_var("TEST_VAR");
@@ -469,7 +2074,8 @@ settings:
         // Make sure variable doesn't exist
         std::env::remove_var("NONEXISTENT_VAR");
 
-        let result = ConfigManager::interpolate_env_vars("${NONEXISTENT_VAR:-default_value}");
+        let result =
+            ConfigManager::interpolate_env_vars("${NONEXISTENT_VAR:-default_value}").unwrap();
         assert_eq!(result, "default_value");
     }
 
@@ -478,7 +2084,7 @@ settings:
         std::env::set_var("HOST", "localhost");
         std::env::set_var("PORT", "3000");
 
-        let result = ConfigManager::interpolate_env_vars("http://${HOST}:${PORT}/api");
+        let result = ConfigManager::interpolate_env_vars("http://${HOST}:${PORT}/api").unwrap();
         assert_eq!(result, "http://localhost:3000/api");
 
         std::env::remove_var("HOST");
@@ -490,7 +2096,7 @@ settings:
         std::env::remove_var("MISSING");
 
         // Should keep original syntax if no default provided
-        let result = ConfigManager::interpolate_env_vars("Value: ${MISSING}");
+        let result = ConfigManager::interpolate_env_vars("Value: ${MISSING}").unwrap();
         assert_eq!(result, "Value: ${MISSING}");
     }
 
@@ -498,7 +2104,7 @@ settings:
     fn test_interpolate_env_vars_with_numbers() {
         std::env::set_var("VAR_123", "value");
 
-        let result = ConfigManager::interpolate_env_vars("${VAR_123}");
+        let result = ConfigManager::interpolate_env_vars("${VAR_123}").unwrap();
         assert_eq!(result, "value");
 
         std::env::remove_var("VAR_123");
@@ -508,10 +2114,112 @@ settings:
     fn test_interpolate_env_vars_empty_default() {
         std::env::remove_var("EMPTY_TEST");
 
-        let result = ConfigManager::interpolate_env_vars("${EMPTY_TEST:-}");
+        let result = ConfigManager::interpolate_env_vars("${EMPTY_TEST:-}").unwrap();
         assert_eq!(result, "");
     }
 
+    #[test]
+    fn test_interpolate_env_vars_required() {
+        std::env::set_var("REQUIRED_SET", "secret");
+        std::env::remove_var("REQUIRED_UNSET");
+
+        let result = ConfigManager::interpolate_env_vars("${REQUIRED_SET:?needed}").unwrap();
+        assert_eq!(result, "secret");
+
+        match ConfigManager::interpolate_env_vars("token: ${REQUIRED_UNSET:?create one at /settings}") {
+            Err(SentinelError::InvalidConfig { reason }) => assert_eq!(
+                reason,
+                "Required environment variables are not set: REQUIRED_UNSET (create one at /settings)"
+            ),
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+
+        std::env::remove_var("REQUIRED_SET");
+    }
+
+    #[test]
+    fn test_interpolate_env_vars_reports_all_required() {
+        std::env::remove_var("MISSING_DB_URL");
+        std::env::remove_var("MISSING_API_KEY");
+
+        let input =
+            "${MISSING_DB_URL:?database}\n${MISSING_API_KEY:?}\n${MISSING_DB_URL:?database}";
+        match ConfigManager::interpolate_env_vars(input) {
+            Err(SentinelError::InvalidConfig { reason }) => assert_eq!(
+                reason,
+                "Required environment variables are not set: MISSING_DB_URL (database), MISSING_API_KEY"
+            ),
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_interpolate_env_vars_escaped() {
+        std::env::set_var("ESCAPED_VAR", "value");
+
+        let result = ConfigManager::interpolate_env_vars(
+            "sh -c 'echo $${ESCAPED_VAR}' ${ESCAPED_VAR} $$ $5",
+        )
+        .unwrap();
+        assert_eq!(result, "sh -c 'echo ${ESCAPED_VAR}' value $$ $5");
+
+        std::env::remove_var("ESCAPED_VAR");
+    }
+
+    #[test]
+    fn test_interpolate_env_vars_nested_defaults() {
+        std::env::set_var("NESTED_HOST", "example.com");
+        std::env::remove_var("NESTED_URL");
+        std::env::remove_var("NESTED_PORT");
+
+        let result = ConfigManager::interpolate_env_vars(
+            "${NESTED_URL:-http://${NESTED_HOST}:${NESTED_PORT:-80}}",
+        )
+        .unwrap();
+        assert_eq!(result, "http://example.com:80");
+
+        // A default that isn't used is never expanded, so its requirements don't apply
+        std::env::set_var("NESTED_URL", "http://set");
+        let result =
+            ConfigManager::interpolate_env_vars("${NESTED_URL:-${NESTED_PORT:?unused}}").unwrap();
+        assert_eq!(result, "http://set");
+
+        std::env::remove_var("NESTED_HOST");
+        std::env::remove_var("NESTED_URL");
+    }
+
+    #[test]
+    fn test_interpolate_env_vars_adjacent() {
+        std::env::set_var("ADJ_A", "a");
+        std::env::set_var("ADJ_B", "b");
+        std::env::remove_var("ADJ_C");
+
+        let result =
+            ConfigManager::interpolate_env_vars("${ADJ_A}${ADJ_B}${ADJ_C:-c}${ADJ_A}").unwrap();
+        assert_eq!(result, "abca");
+
+        // Unterminated or malformed expressions are left alone
+        let result = ConfigManager::interpolate_env_vars("${ADJ_A ${1X} ${ADJ_B:-x").unwrap();
+        assert_eq!(result, "${ADJ_A ${1X} ${ADJ_B:-x");
+
+        std::env::remove_var("ADJ_A");
+        std::env::remove_var("ADJ_B");
+    }
+
+    #[test]
+    fn test_load_fails_on_missing_required_variable() {
+        std::env::remove_var("LOAD_REQUIRED_TOKEN");
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "sentinel.yaml",
+            "processes:\n  - name: api\n    command: run --token ${LOAD_REQUIRED_TOKEN:?needed by api}\n",
+        );
+
+        let result = ConfigManager::load_from_file(&path);
+        assert!(matches!(result, Err(SentinelError::InvalidConfig { .. })));
+    }
+
     #[test]
     fn test_interpolate_env_vars_in_config() {
         std::env::set_var("API_PORT", "8080");
@@ -526,10 +2234,34 @@ settings:
   logLevel: info
 "#;
 
-        let interpolated = ConfigManager::interpolate_env_vars(yaml);
+        let interpolated = ConfigManager::interpolate_env_vars(yaml).unwrap();
         assert!(interpolated.contains("PORT: 8080"));
         assert!(!interpolated.contains("${API_PORT"));
 
         std::env::remove_var("API_PORT");
     }
+
+    #[test]
+    fn test_config_diff() {
+        let parse = |yaml: &str| serde_yaml::from_str::<Config>(yaml).unwrap();
+        let old = parse(
+            "processes:\n  - name: api\n    command: npm start\n  - name: web\n    command: vite\n  - name: db\n    command: postgres\n",
+        );
+
+        assert!(ConfigDiff::between(&old, &old).is_empty());
+        assert_eq!(ConfigDiff::between(&old, &old).summary(), "no changes");
+
+        let new = parse(
+            "processes:\n  - name: api\n    command: npm run dev\n  - name: web\n    command: vite\n    env:\n      PORT: \"5174\"\n  - name: worker\n    command: node worker.js\nsettings:\n  logLevel: debug\n",
+        );
+        let diff = ConfigDiff::between(&old, &new);
+        assert_eq!(diff.modified, vec!["api", "web"]);
+        assert_eq!(diff.added, vec!["worker"]);
+        assert_eq!(diff.removed, vec!["db"]);
+        assert!(diff.settings_changed);
+        assert_eq!(
+            diff.summary(),
+            "2 processes modified, 1 process added, 1 process removed, settings changed"
+        );
+    }
 }
@@ -0,0 +1,1194 @@
+//! Converting PM2 ecosystem files and Docker Compose files into a [`Config`].
+//!
+//! Importers map what they can and report everything else as warnings, so a
+//! migration never fails just because Sentinel has no equivalent for an option.
+//! The result is validated like any other config before it is handed back.
+
+use crate::core::validation::{is_valid_process_name, MAX_PROCESS_NAME_LENGTH};
+use crate::core::ConfigManager;
+use crate::error::{Result, SentinelError};
+use crate::models::{Config, GlobalSettings, ProcessConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use serde_yaml::Value as Yaml;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// PM2 restarts apps unless `autorestart: false`.
+const PM2_DEFAULT_AUTORESTART: bool = true;
+
+/// PM2's default for `max_restarts`.
+const PM2_DEFAULT_MAX_RESTARTS: u32 = 16;
+
+/// Restart limit for Compose `on-failure` without a count.
+const COMPOSE_DEFAULT_RESTART_LIMIT: u32 = 5;
+
+/// Interpreters PM2 picks from a script's extension.
+const PM2_INTERPRETERS: &[(&str, &str)] = &[
+    ("js", "node"),
+    ("mjs", "node"),
+    ("cjs", "node"),
+    ("py", "python3"),
+    ("sh", "bash"),
+    ("rb", "ruby"),
+    ("php", "php"),
+    ("pl", "perl"),
+];
+
+/// Format of a file to import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// A PM2 ecosystem file (`ecosystem.config.js` or `.json`).
+    Pm2,
+    /// A Docker Compose file.
+    Compose,
+}
+
+impl ImportSource {
+    fn label(self) -> &'static str {
+        match self {
+            ImportSource::Pm2 => "PM2 ecosystem file",
+            ImportSource::Compose => "Docker Compose file",
+        }
+    }
+}
+
+/// A configuration converted from another tool.
+#[derive(Clone, Serialize)]
+pub struct ImportReport {
+    /// Format the configuration was imported from.
+    pub source: ImportSource,
+    /// The generated configuration, already validated.
+    pub config: Config,
+    /// Options that were skipped or need a manual look.
+    pub warnings: Vec<String>,
+}
+
+impl ImportReport {
+    /// Imports a file, resolving relative paths in it against its directory.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or parsed, or if the
+    /// generated configuration doesn't validate.
+    pub fn from_file(source: ImportSource, path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        match source {
+            ImportSource::Pm2 => import_pm2(&contents),
+            ImportSource::Compose => {
+                let base_dir = fs::canonicalize(path)
+                    .ok()
+                    .and_then(|path| path.parent().map(Path::to_path_buf))
+                    .unwrap_or_default();
+                import_compose(&contents, &base_dir)
+            }
+        }
+    }
+
+    /// Renders the configuration as YAML, with the warnings in a leading comment.
+    pub fn to_yaml(&self) -> Result<String> {
+        let mut yaml = format!(
+            "# Imported from a {} by `sentinel import`\n",
+            self.source.label()
+        );
+        if !self.warnings.is_empty() {
+            yaml.push_str("#\n# Not imported, check these by hand:\n");
+            for warning in &self.warnings {
+                yaml.push_str(&format!("# - {}\n", warning.replace('\n', " ")));
+            }
+        }
+        yaml.push('\n');
+        yaml.push_str(
+            &serde_yaml::to_string(&self.config)
+                .map_err(|e| SentinelError::Other(e.to_string()))?,
+        );
+        Ok(yaml)
+    }
+
+    /// Writes [`Self::to_yaml`] to `path`, refusing to replace an existing file
+    /// unless `force` is set.
+    pub fn write(&self, path: &Path, force: bool) -> Result<()> {
+        if path.exists() && !force {
+            return Err(SentinelError::InvalidInput {
+                message: format!(
+                    "{} already exists; pass --force to overwrite it",
+                    path.display()
+                ),
+            });
+        }
+
+        fs::write(path, self.to_yaml()?).map_err(|e| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+}
+
+/// Converts a PM2 ecosystem file.
+///
+/// Accepts JSON and the usual `module.exports = { apps: [...] }` JavaScript,
+/// as long as it is plain data: unquoted keys, single quotes, comments and
+/// trailing commas are fine, but `require()`, `process.env` and other code
+/// are rejected with the line they appear on.
+///
+/// # Examples
+/// ```
+/// use sentinel::core::config_import::import_pm2;
+///
+/// let report = import_pm2("module.exports = { apps: [{ name: 'api', script: 'server.js' }] }")?;
+/// assert_eq!(report.config.processes[0].command, "node");
+/// # Ok::<(), sentinel::error::SentinelError>(())
+/// ```
+pub fn import_pm2(contents: &str) -> Result<ImportReport> {
+    let root = JsParser::new(contents).ecosystem()?;
+    let mut warnings = Vec::new();
+
+    let apps = match &root {
+        Json::Array(apps) => apps,
+        Json::Object(object) => {
+            for key in object.keys().filter(|key| *key != "apps") {
+                warnings.push(format!("Top-level '{}' has no Sentinel equivalent", key));
+            }
+            match object.get("apps") {
+                Some(Json::Array(apps)) => apps,
+                Some(Json::Object(_)) => std::slice::from_ref(&object["apps"]),
+                _ => return Err(invalid("PM2 ecosystem file has no 'apps' list")),
+            }
+        }
+        _ => {
+            return Err(invalid(
+                "PM2 ecosystem file must export an object or a list of apps",
+            ))
+        }
+    };
+
+    let mut names = HashSet::new();
+    let mut processes = Vec::new();
+    for (index, app) in apps.iter().enumerate() {
+        let Json::Object(app) = app else {
+            warnings.push(format!("App #{} is not an object, skipped", index + 1));
+            continue;
+        };
+        let Some(script) = app.get("script").and_then(Json::as_str) else {
+            warnings.push(format!("App #{} has no script, skipped", index + 1));
+            continue;
+        };
+
+        let original = app
+            .get("name")
+            .and_then(Json::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                Path::new(script)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| format!("app-{}", index + 1))
+            });
+        let name = unique_name(&original, &mut names, &mut warnings);
+        let mut config = process_config(&name, PM2_DEFAULT_MAX_RESTARTS);
+
+        let args = app.get("args").map(json_words).unwrap_or_default();
+        let interpreter_args = app
+            .get("interpreter_args")
+            .or_else(|| app.get("node_args"))
+            .map(json_words)
+            .unwrap_or_default();
+        let interpreter = match app.get("interpreter").and_then(Json::as_str) {
+            Some("none") => None,
+            Some(interpreter) => Some(interpreter.to_string()),
+            None => Path::new(script)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| PM2_INTERPRETERS.iter().find(|(known, _)| *known == ext))
+                .map(|(_, interpreter)| interpreter.to_string()),
+        };
+        match interpreter {
+            Some(interpreter) => {
+                config.command = interpreter;
+                config.args = interpreter_args;
+                config.args.push(script.to_string());
+                config.args.extend(args);
+            }
+            None => {
+                config.command = script.to_string();
+                config.args = args;
+            }
+        }
+
+        if let Some(cwd) = app.get("cwd").and_then(Json::as_str) {
+            config.cwd = Some(PathBuf::from(cwd));
+        }
+        if let Some(Json::Object(env)) = app.get("env") {
+            for (key, value) in env {
+                match json_scalar(value) {
+                    Some(value) => {
+                        config.env.insert(key.clone(), value);
+                    }
+                    None => warnings.push(format!(
+                        "{}: env '{}' is not a string or number, skipped",
+                        name, key
+                    )),
+                }
+            }
+        }
+        config.auto_restart = Some(
+            app.get("autorestart")
+                .and_then(Json::as_bool)
+                .unwrap_or(PM2_DEFAULT_AUTORESTART),
+        );
+        config.restart_limit = app
+            .get("max_restarts")
+            .and_then(Json::as_u64)
+            .map_or(PM2_DEFAULT_MAX_RESTARTS, |limit| limit as u32);
+        config.restart_delay = app.get("restart_delay").and_then(Json::as_u64);
+
+        const MAPPED: &[&str] = &[
+            "name",
+            "script",
+            "args",
+            "interpreter",
+            "interpreter_args",
+            "node_args",
+            "cwd",
+            "env",
+            "autorestart",
+            "max_restarts",
+            "restart_delay",
+        ];
+        for key in app.keys().filter(|key| !MAPPED.contains(&key.as_str())) {
+            warnings.push(format!("{}: '{}' has no Sentinel equivalent", name, key));
+        }
+
+        processes.push(config);
+    }
+
+    finish(ImportSource::Pm2, processes, warnings)
+}
+
+/// Converts a Docker Compose file into `docker run` processes.
+///
+/// Services that only have a `build` section need the Docker feature to build
+/// their image first, so they are listed in the warnings instead. Relative
+/// bind mounts and env files are resolved against `base_dir`, the directory
+/// the Compose file lives in.
+pub fn import_compose(contents: &str, base_dir: &Path) -> Result<ImportReport> {
+    let root: Yaml = serde_yaml::from_str(contents)
+        .map_err(|e| invalid(&format!("Invalid Docker Compose file: {}", e)))?;
+    let Some(root) = root.as_mapping() else {
+        return Err(invalid("Docker Compose file must be a mapping"));
+    };
+    let Some(services) = root.get("services").and_then(Yaml::as_mapping) else {
+        return Err(invalid("Docker Compose file has no 'services'"));
+    };
+
+    let mut warnings = Vec::new();
+    for key in root.keys().filter_map(Yaml::as_str) {
+        if !matches!(key, "services" | "version" | "name") {
+            warnings.push(format!("Top-level '{}' has no Sentinel equivalent", key));
+        }
+    }
+
+    let mut names = HashSet::new();
+    let mut renamed = HashMap::new();
+    let mut processes = Vec::new();
+    for (service_name, service) in services {
+        let Some(service_name) = service_name.as_str() else {
+            continue;
+        };
+        let Some(service) = service.as_mapping() else {
+            warnings.push(format!(
+                "Service '{}' is not a mapping, skipped",
+                service_name
+            ));
+            continue;
+        };
+        let ports: Vec<String> = yaml_list(service.get("ports"))
+            .iter()
+            .filter_map(compose_port)
+            .collect();
+
+        let Some(image) = service.get("image").and_then(yaml_scalar) else {
+            let reason = match service.get("build") {
+                Some(build) => format!(
+                    "builds its image from {} and requires the Docker feature",
+                    compose_build_context(build)
+                ),
+                None => "has no image".to_string(),
+            };
+            let ports = if ports.is_empty() {
+                String::new()
+            } else {
+                format!(" (ports {})", ports.join(", "))
+            };
+            warnings.push(format!(
+                "Service '{}' {}, skipped{}",
+                service_name, reason, ports
+            ));
+            continue;
+        };
+
+        let name = unique_name(service_name, &mut names, &mut warnings);
+        renamed.insert(service_name.to_string(), name.clone());
+        let mut config = process_config(&name, COMPOSE_DEFAULT_RESTART_LIMIT);
+        config.command = "docker".to_string();
+
+        let container = service
+            .get("container_name")
+            .and_then(yaml_scalar)
+            .unwrap_or_else(|| name.clone());
+        let mut args = vec![
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            container,
+        ];
+        for port in ports {
+            args.extend(["-p".to_string(), port]);
+        }
+
+        // Values go in the process environment and `-e KEY` passes them through,
+        // so secrets don't show up in the command line
+        match service.get("environment") {
+            Some(Yaml::Mapping(environment)) => {
+                for (key, value) in environment {
+                    let Some(key) = key.as_str() else { continue };
+                    if let Some(value) = yaml_scalar(value) {
+                        config.env.insert(key.to_string(), value);
+                    }
+                    args.extend(["-e".to_string(), key.to_string()]);
+                }
+            }
+            environment => {
+                for entry in yaml_list(environment).iter().filter_map(yaml_scalar) {
+                    let key = match entry.split_once('=') {
+                        Some((key, value)) => {
+                            config.env.insert(key.to_string(), value.to_string());
+                            key.to_string()
+                        }
+                        None => entry,
+                    };
+                    args.extend(["-e".to_string(), key]);
+                }
+            }
+        }
+        for env_file in yaml_list(service.get("env_file")) {
+            let path = env_file
+                .as_mapping()
+                .and_then(|env_file| env_file.get("path"))
+                .unwrap_or(&env_file);
+            if let Some(path) = yaml_scalar(path) {
+                args.extend([
+                    "--env-file".to_string(),
+                    base_dir.join(path).display().to_string(),
+                ]);
+            }
+        }
+        for volume in yaml_list(service.get("volumes")) {
+            match yaml_scalar(&volume) {
+                Some(volume) => {
+                    args.extend(["-v".to_string(), compose_volume(&volume, base_dir)]);
+                }
+                None => warnings.push(format!(
+                    "{}: long-syntax volumes are not supported, skipped one",
+                    name
+                )),
+            }
+        }
+        if let Some(working_dir) = service.get("working_dir").and_then(yaml_scalar) {
+            args.extend(["-w".to_string(), working_dir]);
+        }
+        if let Some(user) = service.get("user").and_then(yaml_scalar) {
+            args.extend(["-u".to_string(), user]);
+        }
+
+        // `--entrypoint` takes only the executable; its arguments go before the command
+        let mut entrypoint = yaml_words(service.get("entrypoint")).into_iter();
+        if let Some(executable) = entrypoint.next() {
+            args.extend(["--entrypoint".to_string(), executable]);
+        }
+        if service.contains_key("build") {
+            warnings.push(format!(
+                "{}: 'build' ignored, runs the '{}' image",
+                name, image
+            ));
+        }
+        args.push(image);
+        args.extend(entrypoint);
+        args.extend(yaml_words(service.get("command")));
+        config.args = args;
+
+        match service.get("depends_on") {
+            Some(Yaml::Mapping(depends_on)) => {
+                config.depends_on = depends_on.keys().filter_map(yaml_scalar).collect()
+            }
+            depends_on => {
+                config.depends_on = yaml_list(depends_on)
+                    .iter()
+                    .filter_map(yaml_scalar)
+                    .collect()
+            }
+        }
+
+        let restart = service.get("restart").and_then(yaml_scalar);
+        match restart.as_deref() {
+            None | Some("no") => config.auto_restart = Some(false),
+            Some("always") | Some("unless-stopped") => {
+                config.auto_restart = Some(true);
+                config.restart_limit = 0;
+            }
+            Some(policy) if policy.starts_with("on-failure") => {
+                config.auto_restart = Some(true);
+                config.restart_limit = policy
+                    .strip_prefix("on-failure:")
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or(COMPOSE_DEFAULT_RESTART_LIMIT);
+            }
+            Some(policy) => warnings.push(format!("{}: unknown restart policy '{}'", name, policy)),
+        }
+
+        const MAPPED: &[&str] = &[
+            "image",
+            "build",
+            "container_name",
+            "ports",
+            "environment",
+            "env_file",
+            "volumes",
+            "working_dir",
+            "user",
+            "entrypoint",
+            "command",
+            "depends_on",
+            "restart",
+        ];
+        for key in service.keys().filter_map(Yaml::as_str) {
+            if !MAPPED.contains(&key) {
+                warnings.push(format!("{}: '{}' has no Sentinel equivalent", name, key));
+            }
+        }
+
+        processes.push(config);
+    }
+
+    // Dependencies refer to service names, which may have been renamed or skipped
+    let imported: HashSet<String> = processes.iter().map(|p| p.name.clone()).collect();
+    for process in &mut processes {
+        let depends_on = std::mem::take(&mut process.depends_on);
+        for dependency in depends_on {
+            match renamed
+                .get(&dependency)
+                .filter(|name| imported.contains(*name))
+            {
+                Some(name) => process.depends_on.push(name.clone()),
+                None => warnings.push(format!(
+                    "{}: depends on '{}', which was not imported",
+                    process.name, dependency
+                )),
+            }
+        }
+    }
+
+    finish(ImportSource::Compose, processes, warnings)
+}
+
+/// Builds and validates the config.
+fn finish(
+    source: ImportSource,
+    processes: Vec<ProcessConfig>,
+    warnings: Vec<String>,
+) -> Result<ImportReport> {
+    let config = Config {
+        processes,
+        settings: GlobalSettings::default(),
+        global_env: HashMap::new(),
+        include: vec![],
+        alerts: vec![],
+        defaults: Default::default(),
+    };
+    ConfigManager::validate(&config)?;
+
+    Ok(ImportReport {
+        source,
+        config,
+        warnings,
+    })
+}
+
+/// A process with only a name, for the importers to fill in.
+fn process_config(name: &str, restart_limit: u32) -> ProcessConfig {
+    ProcessConfig {
+        name: name.to_string(),
+        command: String::new(),
+        restart_limit,
+        ..Default::default()
+    }
+}
+
+/// Turns `original` into a valid process name not in `taken`, noting any change.
+fn unique_name(original: &str, taken: &mut HashSet<String>, warnings: &mut Vec<String>) -> String {
+    let mut name: String = original
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(MAX_PROCESS_NAME_LENGTH - 4)
+        .collect();
+    if !is_valid_process_name(&name) {
+        name = "app".to_string();
+    }
+    if taken.contains(&name) {
+        let base = name.clone();
+        name = (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|name| !taken.contains(name))
+            .unwrap_or(base);
+    }
+    if name != original {
+        warnings.push(format!("'{}' was renamed to '{}'", original, name));
+    }
+    taken.insert(name.clone());
+    name
+}
+
+fn invalid(reason: &str) -> SentinelError {
+    SentinelError::InvalidConfig {
+        reason: reason.to_string(),
+    }
+}
+
+/// A string or number as a string.
+fn json_scalar(value: &Json) -> Option<String> {
+    match value {
+        Json::String(s) => Some(s.clone()),
+        Json::Number(n) => Some(n.to_string()),
+        Json::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Arguments given as a list or as one string.
+fn json_words(value: &Json) -> Vec<String> {
+    match value {
+        Json::Array(items) => items.iter().filter_map(json_scalar).collect(),
+        value => json_scalar(value)
+            .map(|s| split_words(&s))
+            .unwrap_or_default(),
+    }
+}
+
+/// A string, number or boolean as a string.
+fn yaml_scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) => Some(s.clone()),
+        Yaml::Number(n) => Some(n.to_string()),
+        Yaml::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A list, or a single value as a list of one.
+fn yaml_list(value: Option<&Yaml>) -> Vec<Yaml> {
+    match value {
+        Some(Yaml::Sequence(items)) => items.clone(),
+        Some(Yaml::Null) | None => vec![],
+        Some(value) => vec![value.clone()],
+    }
+}
+
+/// A command given as a list or as one shell-style string.
+fn yaml_words(value: Option<&Yaml>) -> Vec<String> {
+    match value {
+        Some(Yaml::Sequence(items)) => items.iter().filter_map(yaml_scalar).collect(),
+        Some(value) => yaml_scalar(value)
+            .map(|s| split_words(&s))
+            .unwrap_or_default(),
+        None => vec![],
+    }
+}
+
+/// A Compose port, short (`"8080:80"`) or long syntax, as a `-p` value.
+fn compose_port(port: &Yaml) -> Option<String> {
+    if let Some(port) = yaml_scalar(port) {
+        return Some(port);
+    }
+    let port = port.as_mapping()?;
+    let target = port.get("target").and_then(yaml_scalar)?;
+    let mut spec = match port.get("published").and_then(yaml_scalar) {
+        Some(published) => format!("{}:{}", published, target),
+        None => target,
+    };
+    if let Some(host_ip) = port.get("host_ip").and_then(yaml_scalar) {
+        spec = format!("{}:{}", host_ip, spec);
+    }
+    if let Some(protocol) = port.get("protocol").and_then(yaml_scalar) {
+        spec = format!("{}/{}", spec, protocol);
+    }
+    Some(spec)
+}
+
+/// Where a `build` section builds from.
+fn compose_build_context(build: &Yaml) -> String {
+    yaml_scalar(build)
+        .or_else(|| build.get("context").and_then(yaml_scalar))
+        .unwrap_or_else(|| ".".to_string())
+}
+
+/// A short-syntax volume with a relative bind mount made absolute.
+fn compose_volume(volume: &str, base_dir: &Path) -> String {
+    match volume.split_once(':') {
+        Some((source, rest)) if source.starts_with('.') => {
+            let source = source.strip_prefix("./").unwrap_or(source);
+            format!("{}:{}", base_dir.join(source).display(), rest)
+        }
+        _ => volume.to_string(),
+    }
+}
+
+/// Splits a command line on whitespace, keeping quoted parts together.
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => word.extend(chars.next()),
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Reads the data in an ecosystem file: JSON, optionally behind
+/// `module.exports =` or `export default`, plus the JavaScript object literal
+/// conveniences people write by hand.
+struct JsParser<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsParser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, pos: 0 }
+    }
+
+    fn ecosystem(mut self) -> Result<Json> {
+        self.skip_trivia();
+        if self.eat_word("module.exports") {
+            self.skip_trivia();
+            self.expect('=')?;
+        } else if self.eat_word("export") {
+            self.skip_trivia();
+            if !self.eat_word("default") {
+                return Err(self.dynamic());
+            }
+        }
+
+        let value = self.value()?;
+        self.skip_trivia();
+        self.eat(';');
+        self.skip_trivia();
+        if self.pos < self.source.len() {
+            return Err(self.dynamic());
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json> {
+        self.skip_trivia();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some(quote @ ('"' | '\'' | '`')) => self.string(quote).map(Json::String),
+            Some(c) if c == '-' || c == '.' || c.is_ascii_digit() => self.number(),
+            Some(c) if is_identifier_start(c) => match self.identifier() {
+                "true" => Ok(Json::Bool(true)),
+                "false" => Ok(Json::Bool(false)),
+                "null" | "undefined" => Ok(Json::Null),
+                word => {
+                    self.pos -= word.len();
+                    Err(self.dynamic())
+                }
+            },
+            Some(_) => Err(self.dynamic()),
+            None => Err(self.syntax("unexpected end of file")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json> {
+        self.expect('{')?;
+        let mut object = serde_json::Map::new();
+        loop {
+            self.skip_trivia();
+            if self.eat('}') {
+                return Ok(Json::Object(object));
+            }
+
+            let start = self.pos;
+            let key = match self.peek() {
+                Some(quote @ ('"' | '\'')) => self.string(quote)?,
+                Some(c) if is_identifier_start(c) || c.is_ascii_digit() => {
+                    self.identifier().to_string()
+                }
+                _ => return Err(self.dynamic()),
+            };
+            self.skip_trivia();
+            if !self.eat(':') {
+                // Shorthand properties and methods refer to code
+                self.pos = start;
+                return Err(self.dynamic());
+            }
+            let value = self.value()?;
+            object.insert(key, value);
+
+            self.skip_trivia();
+            if !self.eat(',') {
+                self.skip_trivia();
+                self.expect('}')?;
+                return Ok(Json::Object(object));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia();
+            if self.eat(']') {
+                return Ok(Json::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_trivia();
+            if !self.eat(',') {
+                self.skip_trivia();
+                self.expect(']')?;
+                return Ok(Json::Array(items));
+            }
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.next() else {
+                self.pos = start;
+                return Err(self.syntax("unterminated string"));
+            };
+            match c {
+                c if c == quote => return Ok(value),
+                '$' if quote == '`' && self.peek() == Some('{') => {
+                    self.pos -= 1;
+                    return Err(self.dynamic());
+                }
+                '\\' => match self.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('0') => value.push('\0'),
+                    Some('u') => {
+                        let hex = self.source.get(self.pos..self.pos + 4).unwrap_or_default();
+                        let c = u32::from_str_radix(hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.syntax("invalid \\u escape"))?;
+                        value.push(c);
+                        self.pos += 4;
+                    }
+                    Some(c) => value.push(c),
+                    None => return Err(self.syntax("unterminated string")),
+                },
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E' | '_'))
+        {
+            self.pos += 1;
+        }
+        let literal = self.source[start..self.pos].replace('_', "");
+        let literal = literal
+            .strip_prefix('.')
+            .map_or(literal.clone(), |l| format!("0.{}", l));
+        serde_json::from_str(&literal).map_err(|_| {
+            self.pos = start;
+            self.syntax("invalid number")
+        })
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if is_identifier_start(c) || c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        &self.source[start..self.pos]
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let rest = &self.source[self.pos..];
+        let boundary = rest[word.len().min(rest.len())..]
+            .chars()
+            .next()
+            .is_none_or(|c| !is_identifier_start(c) && !c.is_ascii_digit());
+        if rest.starts_with(word) && boundary {
+            self.pos += word.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = &self.source[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                self.pos += comment.find("*/").map_or(trimmed.len(), |end| end + 4);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.syntax(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn line(&self) -> usize {
+        self.source[..self.pos].matches('\n').count() + 1
+    }
+
+    fn syntax(&self, message: &str) -> SentinelError {
+        invalid(&format!(
+            "PM2 ecosystem file, line {}: {}",
+            self.line(),
+            message
+        ))
+    }
+
+    /// Error for code the parser won't evaluate.
+    fn dynamic(&self) -> SentinelError {
+        let snippet: String = self.source[self.pos..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .chars()
+            .take(40)
+            .collect();
+        invalid(&format!(
+            "PM2 ecosystem file, line {}: `{}` is JavaScript code, which can't be imported. \
+             Convert the file to JSON first: node -e \"console.log(JSON.stringify(require('./ecosystem.config.js'), null, 2))\" > ecosystem.json",
+            self.line(),
+            snippet
+        ))
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ECOSYSTEM: &str = include_str!("../../tests/fixtures/import/ecosystem.config.js");
+    const COMPOSE: &str = include_str!("../../tests/fixtures/import/docker-compose.yml");
+
+    fn process<'a>(report: &'a ImportReport, name: &str) -> &'a ProcessConfig {
+        report
+            .config
+            .processes
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("no process '{}'", name))
+    }
+
+    #[test]
+    fn test_import_pm2_fixture() {
+        let report = import_pm2(ECOSYSTEM).unwrap();
+        let names: Vec<_> = report
+            .config
+            .processes
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["api", "worker", "Web-UI"]);
+
+        let api = process(&report, "api");
+        assert_eq!(api.command, "node");
+        assert_eq!(
+            api.args,
+            vec!["--max-old-space-size=512", "server.js", "--port", "3000"]
+        );
+        assert_eq!(api.cwd, Some(PathBuf::from("./backend")));
+        assert_eq!(api.env["NODE_ENV"], "development");
+        assert_eq!(api.env["PORT"], "3000");
+        assert_eq!(api.auto_restart, Some(true));
+        assert_eq!(api.restart_limit, 10);
+        assert_eq!(api.restart_delay, Some(2000));
+
+        let worker = process(&report, "worker");
+        assert_eq!(worker.command, "python3");
+        assert_eq!(worker.args, vec!["worker.py", "--queue", "high priority"]);
+        assert_eq!(worker.auto_restart, Some(false));
+        assert_eq!(worker.restart_limit, PM2_DEFAULT_MAX_RESTARTS);
+
+        let web = process(&report, "Web-UI");
+        assert_eq!(web.command, "npm");
+        assert_eq!(web.args, vec!["run", "dev"]);
+
+        assert!(report
+            .warnings
+            .contains(&"api: 'instances' has no Sentinel equivalent".to_string()));
+        assert!(report
+            .warnings
+            .contains(&"api: 'env_production' has no Sentinel equivalent".to_string()));
+        assert!(report
+            .warnings
+            .contains(&"'Web UI' was renamed to 'Web-UI'".to_string()));
+        assert!(report
+            .warnings
+            .contains(&"Top-level 'deploy' has no Sentinel equivalent".to_string()));
+    }
+
+    #[test]
+    fn test_import_pm2_json() {
+        let report =
+            import_pm2(r#"[{"name": "api", "script": "./bin/server", "args": ["-v"]}]"#).unwrap();
+        let api = process(&report, "api");
+        assert_eq!(api.command, "./bin/server");
+        assert_eq!(api.args, vec!["-v"]);
+    }
+
+    #[test]
+    fn test_import_pm2_rejects_dynamic_js() {
+        let err = import_pm2("const path = require('path');\nmodule.exports = { apps: [] };")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("line 1"), "{}", err);
+        assert!(err.contains("`const path = require('path');`"), "{}", err);
+
+        let err =
+            import_pm2("module.exports = {\n  apps: [{\n    name: process.env.APP,\n  }],\n};")
+                .err()
+                .unwrap()
+                .to_string();
+        assert!(err.contains("line 3"), "{}", err);
+        assert!(err.contains("process.env.APP"), "{}", err);
+
+        let err = import_pm2("module.exports = { apps: [{ script: `${dir}/a.js` }] }")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("can't be imported"), "{}", err);
+    }
+
+    #[test]
+    fn test_import_pm2_without_apps() {
+        assert!(import_pm2("{ deploy: {} }").is_err());
+        assert!(import_pm2("module.exports = { apps: [ { name: 'a', ").is_err());
+    }
+
+    #[test]
+    fn test_import_compose_fixture() {
+        let report = import_compose(COMPOSE, Path::new("/srv/app")).unwrap();
+        let names: Vec<_> = report
+            .config
+            .processes
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["db", "cache", "api"]);
+
+        let db = process(&report, "db");
+        assert_eq!(db.command, "docker");
+        assert_eq!(
+            db.args,
+            vec![
+                "run",
+                "--rm",
+                "--name",
+                "app-db",
+                "-p",
+                "5432:5432",
+                "-e",
+                "POSTGRES_PASSWORD",
+                "-e",
+                "POSTGRES_DB",
+                "-v",
+                "/srv/app/data:/var/lib/postgresql/data",
+                "-v",
+                "pgdata:/backup",
+                "postgres:16",
+            ]
+        );
+        assert_eq!(db.env["POSTGRES_PASSWORD"], "secret");
+        assert_eq!(db.auto_restart, Some(true));
+        assert_eq!(db.restart_limit, 0);
+
+        let cache = process(&report, "cache");
+        assert_eq!(
+            cache.args,
+            vec![
+                "run",
+                "--rm",
+                "--name",
+                "cache",
+                "-p",
+                "127.0.0.1:6379:6379/tcp",
+                "redis:7",
+                "redis-server",
+                "--appendonly",
+                "yes",
+            ]
+        );
+        assert_eq!(cache.auto_restart, Some(true));
+        assert_eq!(cache.restart_limit, 3);
+
+        let api = process(&report, "api");
+        assert_eq!(api.depends_on, vec!["db", "cache"]);
+        assert_eq!(api.env["LOG_LEVEL"], "debug");
+        assert!(!api.env.contains_key("API_KEY"));
+        assert_eq!(
+            api.args,
+            vec![
+                "run",
+                "--rm",
+                "--name",
+                "api",
+                "-p",
+                "8080:80",
+                "-e",
+                "LOG_LEVEL",
+                "-e",
+                "API_KEY",
+                "--env-file",
+                "/srv/app/.env",
+                "-w",
+                "/app",
+                "--entrypoint",
+                "python",
+                "example/api:latest",
+                "-m",
+                "api",
+            ]
+        );
+        assert_eq!(api.auto_restart, Some(false));
+
+        assert!(report.warnings.contains(
+            &"Service 'web' builds its image from ./web and requires the Docker feature, skipped (ports 3000:3000)"
+                .to_string()
+        ));
+        assert!(report
+            .warnings
+            .contains(&"api: 'healthcheck' has no Sentinel equivalent".to_string()));
+        assert!(report
+            .warnings
+            .contains(&"api: depends on 'web', which was not imported".to_string()));
+        assert!(report
+            .warnings
+            .contains(&"Top-level 'volumes' has no Sentinel equivalent".to_string()));
+    }
+
+    #[test]
+    fn test_import_compose_without_services() {
+        assert!(import_compose("version: '3'\n", Path::new(".")).is_err());
+        assert!(import_compose("- not a mapping\n", Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_report_round_trips_and_refuses_overwrite() {
+        let report = import_pm2(ECOSYSTEM).unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sentinel.yaml");
+
+        report.write(&path, false).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Imported from a PM2 ecosystem file"));
+        assert!(written.contains("# - api: 'instances' has no Sentinel equivalent"));
+
+        let loaded = ConfigManager::load_from_file(&path).unwrap();
+        assert_eq!(loaded.processes.len(), report.config.processes.len());
+        assert_eq!(loaded.processes[0].args, report.config.processes[0].args);
+
+        assert!(report.write(&path, false).is_err());
+        report.write(&path, true).unwrap();
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words(r#"run "a b" 'c d' e\ f  g"#),
+            vec!["run", "a b", "c d", "e f", "g"]
+        );
+        assert!(split_words("   ").is_empty());
+        assert_eq!(split_words("''"), vec![""]);
+    }
+}
@@ -0,0 +1,665 @@
+//! Process config inheritance.
+//!
+//! A config may name partial process configs under `defaults`, and a process
+//! may `extends` one of them or another process in the same file:
+//!
+//! ```yaml
+//! defaults:
+//!   node:
+//!     env: { NODE_ENV: development }
+//!     restartLimit: 5
+//! processes:
+//!   - name: api
+//!     extends: node
+//!     command: npm run api
+//!     env: { PORT: 3000 }
+//!   - name: worker
+//!     extends: api
+//!     args+: [--queue]
+//! ```
+//!
+//! Inheritance is resolved on the parsed document, before it is read into a
+//! [`Config`](crate::models::Config), so a process may leave out anything it
+//! inherits, even its command. Merging follows these rules:
+//! - Maps, like `env` or `healthCheck`, are merged key by key.
+//! - Scalars and lists replace what is inherited.
+//! - A list key ending in `+`, like `args+` or `dependsOn+`, appends to the
+//!   inherited list instead.
+//!
+//! Defaults entries may extend each other. A target is looked up in
+//! `defaults` first, then among the processes. Saving strips from each
+//! extending process whatever it inherits unchanged, so the file keeps its
+//! unresolved form. Entries removed from an inherited map can't be expressed
+//! that way; they are inherited again on the next load.
+
+use crate::error::{Result, SentinelError};
+use serde_yaml::{Mapping, Value};
+
+/// Suffix of a list key that appends to the inherited list.
+pub const APPEND_SUFFIX: char = '+';
+
+const DEFAULTS: &str = "defaults";
+const PROCESSES: &str = "processes";
+const EXTENDS: &str = "extends";
+const NAME: &str = "name";
+
+/// Whether a parsed config document uses `defaults`, `extends` or `+` keys.
+pub fn uses_inheritance(document: &Value) -> bool {
+    let has_defaults = document
+        .get(DEFAULTS)
+        .and_then(Value::as_mapping)
+        .is_some_and(|defaults| !defaults.is_empty());
+    has_defaults || processes(document).any(|process| has_inheritance_keys(process, true))
+}
+
+fn has_inheritance_keys(value: &Value, top_level: bool) -> bool {
+    let Some(mapping) = value.as_mapping() else {
+        return false;
+    };
+    mapping.iter().any(|(key, value)| {
+        key.as_str()
+            .is_some_and(|key| key.ends_with(APPEND_SUFFIX) || (top_level && key == EXTENDS))
+            || has_inheritance_keys(value, false)
+    })
+}
+
+fn processes(document: &Value) -> impl Iterator<Item = &Value> {
+    document
+        .get(PROCESSES)
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+}
+
+/// Merges `overlay` onto `base`.
+///
+/// Maps are merged recursively, `key+` lists are appended to the inherited
+/// `key`, and everything else in `overlay` replaces what `base` has.
+///
+/// # Errors
+/// `InvalidConfig` if a `key+` value, or what it appends to, isn't a list.
+pub fn merge(base: Option<&Value>, overlay: &Value) -> Result<Value> {
+    let Value::Mapping(overlay) = overlay else {
+        return Ok(overlay.clone());
+    };
+    let mut merged = match base {
+        Some(Value::Mapping(base)) => base.clone(),
+        _ => Mapping::new(),
+    };
+
+    for (key, value) in overlay {
+        let append = key.as_str().and_then(|key| key.strip_suffix(APPEND_SUFFIX));
+        let Some(list_key) = append else {
+            let value = merge(merged.get(key), value)?;
+            merged.insert(key.clone(), value);
+            continue;
+        };
+
+        let not_a_list = || SentinelError::InvalidConfig {
+            reason: format!(
+                "'{}{}' appends to a list, but '{}' is not one",
+                list_key, APPEND_SUFFIX, list_key
+            ),
+        };
+        let Value::Sequence(items) = value else {
+            return Err(not_a_list());
+        };
+        let list_key = Value::String(list_key.to_string());
+        match merged.get_mut(&list_key) {
+            Some(Value::Sequence(inherited)) => inherited.extend(items.iter().cloned()),
+            Some(Value::Null) | None => {
+                merged.insert(list_key, Value::Sequence(items.clone()));
+            }
+            Some(_) => return Err(not_a_list()),
+        }
+    }
+
+    Ok(Value::Mapping(merged))
+}
+
+/// Resolves the `extends` of every process in a config document.
+///
+/// Each resolved process keeps its `extends`, so it can be saved in its
+/// unresolved form again; the `defaults` section is left as written.
+///
+/// # Errors
+/// `InvalidConfig` for unknown targets, inheritance cycles and `+` keys
+/// that don't append to a list.
+pub fn resolve(document: &mut Value) -> Result<()> {
+    let resolver = Resolver::new(document.clone());
+    let Some(processes) = document.get_mut(PROCESSES).and_then(Value::as_sequence_mut) else {
+        return Ok(());
+    };
+
+    for (index, process) in processes.iter_mut().enumerate() {
+        let base = match process.get(EXTENDS) {
+            Some(_) => Some(resolver.base_of(process, &label(process, index))?),
+            None => None,
+        };
+        *process = merge(base.as_ref(), process)?;
+    }
+    Ok(())
+}
+
+/// Strips from every extending process of a resolved config document what
+/// it inherits unchanged, the reverse of [`resolve`].
+///
+/// A list that only adds to the inherited one is written as a `key+` append.
+///
+/// # Errors
+/// `InvalidConfig` for unknown targets and inheritance cycles.
+pub fn unresolve(document: &mut Value) -> Result<()> {
+    let resolver = Resolver::new(document.clone());
+    let Some(processes) = document.get_mut(PROCESSES).and_then(Value::as_sequence_mut) else {
+        return Ok(());
+    };
+
+    for (index, process) in processes.iter_mut().enumerate() {
+        if process.get(EXTENDS).is_none() {
+            continue;
+        }
+        let base = resolver.base_of(process, &label(process, index))?;
+        *process = strip(Some(&base), process).unwrap_or(Value::Mapping(Mapping::new()));
+    }
+    Ok(())
+}
+
+/// How a process is named in errors.
+fn label(process: &Value, index: usize) -> String {
+    match process.get(NAME).and_then(Value::as_str) {
+        Some(name) => name.to_string(),
+        None => format!("process #{}", index + 1),
+    }
+}
+
+/// Looks up and resolves inheritance targets in one document.
+struct Resolver {
+    document: Value,
+}
+
+impl Resolver {
+    fn new(document: Value) -> Self {
+        Self { document }
+    }
+
+    /// What a process inherits: its resolved target, without the target's
+    /// own name and `extends`.
+    fn base_of(&self, process: &Value, label: &str) -> Result<Value> {
+        let mut chain = vec![label.to_string()];
+        let target = target_name(process, label)?;
+        let mut base = self.resolved(target, &mut chain)?;
+        if let Value::Mapping(base) = &mut base {
+            base.remove(NAME);
+            base.remove(EXTENDS);
+        }
+        Ok(base)
+    }
+
+    /// A target with its own inheritance resolved.
+    ///
+    /// `chain` holds the names being resolved, to report cycles.
+    fn resolved(&self, name: &str, chain: &mut Vec<String>) -> Result<Value> {
+        // The chain starts with the extending process, which `name` only
+        // refers to if no defaults entry has that name
+        let in_chain = chain
+            .iter()
+            .enumerate()
+            .any(|(index, seen)| seen == name && (index > 0 || self.default_entry(name).is_none()));
+        if in_chain {
+            chain.push(name.to_string());
+            return Err(SentinelError::InvalidConfig {
+                reason: format!("Config inheritance cycle: {}", chain.join(" -> ")),
+            });
+        }
+        let Some(target) = self.lookup(name) else {
+            return Err(SentinelError::InvalidConfig {
+                reason: format!(
+                    "'{}' extends '{}', which is neither in defaults nor a process",
+                    chain.last().map(String::as_str).unwrap_or_default(),
+                    name
+                ),
+            });
+        };
+
+        chain.push(name.to_string());
+        let base = match target.get(EXTENDS) {
+            Some(_) => Some(self.resolved(target_name(target, name)?, chain)?),
+            None => None,
+        };
+        chain.pop();
+
+        merge(base.as_ref(), target)
+    }
+
+    fn default_entry(&self, name: &str) -> Option<&Value> {
+        self.document
+            .get(DEFAULTS)
+            .and_then(|defaults| defaults.get(name))
+    }
+
+    /// A defaults entry, or failing that a process, called `name`.
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.default_entry(name).or_else(|| {
+            processes(&self.document)
+                .find(|process| process.get(NAME).and_then(Value::as_str) == Some(name))
+        })
+    }
+}
+
+/// The `extends` of a process or defaults entry.
+fn target_name<'a>(value: &'a Value, label: &str) -> Result<&'a str> {
+    value
+        .get(EXTENDS)
+        .and_then(Value::as_str)
+        .ok_or_else(|| SentinelError::InvalidConfig {
+            reason: format!("'extends' of '{}' must be a name", label),
+        })
+}
+
+/// The part of `value` that differs from `base`, or `None` if nothing does.
+fn strip(base: Option<&Value>, value: &Value) -> Option<Value> {
+    let (Some(Value::Mapping(base)), Value::Mapping(value)) = (base, value) else {
+        return match base {
+            Some(base) if same(base, value) => None,
+            _ => Some(value.clone()),
+        };
+    };
+
+    let mut own = Mapping::new();
+    for (key, item) in value {
+        match (base.get(key), item) {
+            // Absent means empty for these, so there's nothing to write
+            (None, Value::Sequence(items)) if items.is_empty() => {}
+            (None, Value::Mapping(entries)) if entries.is_empty() => {}
+            (Some(Value::Sequence(inherited)), Value::Sequence(items))
+                if !inherited.is_empty()
+                    && items.len() > inherited.len()
+                    && same_items(inherited, &items[..inherited.len()]) =>
+            {
+                if let Some(key) = key.as_str() {
+                    let appended = items[inherited.len()..].to_vec();
+                    own.insert(
+                        Value::String(format!("{}{}", key, APPEND_SUFFIX)),
+                        Value::Sequence(appended),
+                    );
+                }
+            }
+            (inherited, item) => {
+                if let Some(item) = strip(inherited, item) {
+                    own.insert(key.clone(), item);
+                }
+            }
+        }
+    }
+    (!own.is_empty()).then_some(Value::Mapping(own))
+}
+
+/// Whether two values mean the same, treating scalars as their text, like
+/// `env` values are read.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Sequence(a), Value::Sequence(b)) => a.len() == b.len() && same_items(a, b),
+        (Value::Mapping(a), Value::Mapping(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, value)| b.get(key).is_some_and(|other| same(value, other)))
+        }
+        (a, b) => match (scalar_text(a), scalar_text(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+    }
+}
+
+fn same_items(a: &[Value], b: &[Value]) -> bool {
+    a.iter().zip(b).all(|(a, b)| same(a, b))
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Value {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    fn resolved(text: &str) -> Value {
+        let mut document = yaml(text);
+        resolve(&mut document).unwrap();
+        document
+    }
+
+    fn process<'a>(document: &'a Value, name: &str) -> &'a Value {
+        processes(document)
+            .find(|process| process.get(NAME).and_then(Value::as_str) == Some(name))
+            .unwrap()
+    }
+
+    fn reason(error: SentinelError) -> String {
+        match error {
+            SentinelError::InvalidConfig { reason } => reason,
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_env_maps_key_by_key() {
+        let merged = merge(
+            Some(&yaml("env: {NODE_ENV: development, LOG: info}")),
+            &yaml("env: {LOG: debug, PORT: 3000}"),
+        )
+        .unwrap();
+        assert_eq!(
+            merged,
+            yaml("env: {NODE_ENV: development, LOG: debug, PORT: 3000}")
+        );
+    }
+
+    #[test]
+    fn test_merge_nested_maps() {
+        let merged = merge(
+            Some(&yaml(
+                "healthCheck: {type: http, url: 'http://localhost', retries: 3}",
+            )),
+            &yaml("healthCheck: {retries: 5}"),
+        )
+        .unwrap();
+        assert_eq!(
+            merged,
+            yaml("healthCheck: {type: http, url: 'http://localhost', retries: 5}")
+        );
+    }
+
+    #[test]
+    fn test_merge_scalars_override() {
+        let merged = merge(
+            Some(&yaml("restartLimit: 3\nautoRestart: true\ncwd: ./app")),
+            &yaml("restartLimit: 0\nautoRestart: false\ncwd: null"),
+        )
+        .unwrap();
+        assert_eq!(
+            merged,
+            yaml("restartLimit: 0\nautoRestart: false\ncwd: null")
+        );
+    }
+
+    #[test]
+    fn test_merge_replaces_lists() {
+        let merged = merge(
+            Some(&yaml("dependsOn: [db, cache]\nargs: [--verbose]")),
+            &yaml("dependsOn: [queue]\nargs: []"),
+        )
+        .unwrap();
+        assert_eq!(merged, yaml("dependsOn: [queue]\nargs: []"));
+    }
+
+    #[test]
+    fn test_merge_appends_with_plus_keys() {
+        let merged = merge(
+            Some(&yaml("dependsOn: [db]\nargs: [--verbose]")),
+            &yaml("dependsOn+: [cache]\nargs+: [--port, '3000']\ntags+: [web]"),
+        )
+        .unwrap();
+        assert_eq!(
+            merged,
+            yaml("dependsOn: [db, cache]\nargs: [--verbose, --port, '3000']\ntags: [web]")
+        );
+
+        // Appending to nothing starts the list
+        let merged = merge(None, &yaml("dependsOn+: [db]")).unwrap();
+        assert_eq!(merged, yaml("dependsOn: [db]"));
+    }
+
+    #[test]
+    fn test_merge_rejects_appending_to_non_lists() {
+        let error = merge(None, &yaml("env+: {A: b}")).unwrap_err();
+        assert!(reason(error).contains("'env+' appends to a list"));
+
+        let error = merge(Some(&yaml("cwd: ./app")), &yaml("cwd+: [x]")).unwrap_err();
+        assert!(reason(error).contains("'cwd' is not one"));
+    }
+
+    #[test]
+    fn test_uses_inheritance() {
+        assert!(!uses_inheritance(&yaml(
+            "processes: [{name: api, command: node}]"
+        )));
+        assert!(!uses_inheritance(&yaml("defaults: {}\nprocesses: []")));
+        assert!(uses_inheritance(&yaml("defaults: {node: {}}")));
+        assert!(uses_inheritance(&yaml(
+            "processes: [{name: api, extends: node}]"
+        )));
+        assert!(uses_inheritance(&yaml(
+            "processes: [{name: api, command: node, args+: [x]}]"
+        )));
+        // An env var called `extends` is just an env var
+        assert!(!uses_inheritance(&yaml(
+            "processes: [{name: api, command: node, env: {extends: x}}]"
+        )));
+    }
+
+    #[test]
+    fn test_resolve_from_defaults() {
+        let document = resolved(
+            r#"
+defaults:
+  node:
+    command: node
+    env: { NODE_ENV: development }
+    restartLimit: 5
+    dependsOn: [db]
+processes:
+  - name: db
+    command: postgres
+  - name: api
+    extends: node
+    args: [api.js]
+    env: { PORT: 3000 }
+"#,
+        );
+        assert_eq!(
+            process(&document, "api"),
+            &yaml(
+                r#"
+command: node
+env: { NODE_ENV: development, PORT: 3000 }
+restartLimit: 5
+dependsOn: [db]
+name: api
+extends: node
+args: [api.js]
+"#
+            )
+        );
+        // Processes without extends are untouched
+        assert_eq!(
+            process(&document, "db"),
+            &yaml("{name: db, command: postgres}")
+        );
+    }
+
+    #[test]
+    fn test_resolve_chains_through_defaults_and_processes() {
+        let document = resolved(
+            r#"
+defaults:
+  base:
+    env: { LOG: info }
+    restartLimit: 2
+  node:
+    extends: base
+    command: node
+    env: { NODE_ENV: development }
+processes:
+  - name: api
+    extends: node
+    args: [api.js]
+    dependsOn: [db]
+  - name: worker
+    extends: api
+    args: [worker.js]
+    dependsOn+: [queue]
+    env: { LOG: debug }
+"#,
+        );
+        let worker = process(&document, "worker");
+        assert_eq!(worker["name"], yaml("worker"));
+        assert_eq!(worker["extends"], yaml("api"));
+        assert_eq!(worker["command"], yaml("node"));
+        assert_eq!(worker["args"], yaml("[worker.js]"));
+        assert_eq!(worker["dependsOn"], yaml("[db, queue]"));
+        assert_eq!(worker["restartLimit"], yaml("2"));
+        assert_eq!(worker["env"], yaml("{LOG: debug, NODE_ENV: development}"));
+    }
+
+    #[test]
+    fn test_resolve_prefers_defaults_over_processes() {
+        let document = resolved(
+            r#"
+defaults:
+  api: { command: from-defaults }
+processes:
+  - { name: api, command: from-process }
+  - { name: copy, extends: api }
+"#,
+        );
+        assert_eq!(process(&document, "copy")["command"], yaml("from-defaults"));
+    }
+
+    #[test]
+    fn test_resolve_process_named_like_its_defaults() {
+        let document = resolved(
+            r#"
+defaults:
+  api: { command: node, env: { PORT: 3000 } }
+processes:
+  - { name: api, extends: api, args: [api.js] }
+"#,
+        );
+        assert_eq!(process(&document, "api")["command"], yaml("node"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_target() {
+        let mut document = yaml("processes: [{name: api, extends: nodee}]");
+        let error = reason(resolve(&mut document).unwrap_err());
+        assert_eq!(
+            error,
+            "'api' extends 'nodee', which is neither in defaults nor a process"
+        );
+
+        let mut document =
+            yaml("defaults: {node: {extends: missing}}\nprocesses: [{name: api, extends: node}]");
+        let error = reason(resolve(&mut document).unwrap_err());
+        assert!(error.starts_with("'node' extends 'missing'"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_cycles() {
+        let mut document = yaml(
+            r#"
+defaults:
+  a: { extends: b }
+  b: { extends: a }
+processes:
+  - { name: api, extends: a }
+"#,
+        );
+        let error = reason(resolve(&mut document).unwrap_err());
+        assert_eq!(error, "Config inheritance cycle: api -> a -> b -> a");
+
+        let mut document = yaml("processes: [{name: a, extends: b}, {name: b, extends: a}]");
+        let error = reason(resolve(&mut document).unwrap_err());
+        assert_eq!(error, "Config inheritance cycle: a -> b -> a");
+
+        let mut document = yaml("processes: [{name: a, extends: a}]");
+        let error = reason(resolve(&mut document).unwrap_err());
+        assert_eq!(error, "Config inheritance cycle: a -> a");
+    }
+
+    #[test]
+    fn test_resolve_rejects_non_name_extends() {
+        let mut document = yaml("processes: [{name: api, extends: [a, b]}]");
+        let error = reason(resolve(&mut document).unwrap_err());
+        assert_eq!(error, "'extends' of 'api' must be a name");
+    }
+
+    #[test]
+    fn test_unresolve_restores_written_form() {
+        let written = yaml(
+            r#"
+defaults:
+  node:
+    command: node
+    env: { NODE_ENV: development, PORT: 3000 }
+    dependsOn: [db]
+processes:
+  - name: db
+    command: postgres
+  - name: api
+    extends: node
+    env: { PORT: 4000 }
+    dependsOn+: [cache]
+"#,
+        );
+        let mut document = written.clone();
+        resolve(&mut document).unwrap();
+        unresolve(&mut document).unwrap();
+
+        assert_eq!(
+            process(&document, "api"),
+            &yaml("{name: api, extends: node, env: {PORT: 4000}, dependsOn+: [cache]}")
+        );
+        assert_eq!(process(&document, "db"), process(&written, "db"));
+        assert_eq!(document["defaults"], written["defaults"]);
+    }
+
+    #[test]
+    fn test_unresolve_keeps_edits() {
+        let mut document = resolved(
+            r#"
+defaults:
+  node: { command: node, env: { PORT: 3000 }, dependsOn: [db, cache] }
+processes:
+  - { name: api, extends: node }
+"#,
+        );
+        // As a saved ProcessConfig serializes: env values as text, empty
+        // collections written out, and an edited dependency list
+        let api = &mut document["processes"][0];
+        api["env"] = yaml("{PORT: '3000', DEBUG: '1'}");
+        api["dependsOn"] = yaml("[db]");
+        api["args"] = yaml("[]");
+        api["tags"] = yaml("[]");
+
+        unresolve(&mut document).unwrap();
+        assert_eq!(
+            document["processes"][0],
+            yaml("{name: api, extends: node, env: {DEBUG: '1'}, dependsOn: [db]}")
+        );
+    }
+
+    #[test]
+    fn test_unresolve_rejects_unknown_target() {
+        let mut document = yaml("processes: [{name: api, command: node, extends: gone}]");
+        assert!(unresolve(&mut document).is_err());
+    }
+
+    #[test]
+    fn test_same_treats_scalars_as_text() {
+        assert!(same(&yaml("3000"), &yaml("'3000'")));
+        assert!(same(&yaml("true"), &yaml("'true'")));
+        assert!(same(&yaml("{A: 1}"), &yaml("{A: '1'}")));
+        assert!(!same(&yaml("[a]"), &yaml("[a, b]")));
+        assert!(!same(&yaml("~"), &yaml("''")));
+    }
+}
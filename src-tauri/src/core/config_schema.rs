@@ -0,0 +1,697 @@
+//! Structural checks for configuration files.
+//!
+//! serde ignores unknown keys, so a typo like `restrat_delay` silently falls back
+//! to the default. This module finds such keys, suggests the closest known one,
+//! and maps problems back to a line in the source file. It also bounds how
+//! large a document may expand to before it is parsed.
+
+use crate::core::config_inheritance::APPEND_SUFFIX;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_yaml::Value;
+use std::cell::Cell;
+use std::fmt;
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "processes",
+    "settings",
+    "globalEnv",
+    "include",
+    "alerts",
+    "defaults",
+];
+
+const PROCESS_KEYS: &[&str] = &[
+    "name",
+    "extends",
+    "command",
+    "args",
+    "cwd",
+    "env",
+    "autoRestart",
+    "restartLimit",
+    "max_restarts",
+    "restartDelay",
+    "restart_delay_ms",
+    "successExitCodes",
+    "dependsOn",
+    "waitForCompletion",
+    "profiles",
+    "tags",
+    "logBufferLines",
+    "logBufferBytes",
+    "nice",
+    "cpuAffinity",
+    "stdinMode",
+    "outputEncoding",
+    "logFormat",
+    "envMode",
+    "envFile",
+    "restartOnEnvChange",
+    "healthCheck",
+    "readiness",
+    "startDelayMs",
+    "schedule",
+    "maxRuntimeMs",
+    "restartPolicy",
+    "containerRef",
+    "warmRestart",
+    "warmRestartFreePort",
+    "reapOrphans",
+    "transient",
+];
+
+const RESTART_POLICY_KEYS: &[&str] = &["maxMemoryBytes", "maxUptimeHours", "cron"];
+
+const HEALTH_CHECK_KEYS: &[&str] = &[
+    "type",
+    "command",
+    "args",
+    "url",
+    "method",
+    "expectedStatus",
+    "host",
+    "port",
+    "intervalMs",
+    "timeoutMs",
+    "retries",
+];
+
+const READINESS_KEYS: &[&str] = &[
+    "type",
+    "host",
+    "port",
+    "url",
+    "expectedStatus",
+    "command",
+    "args",
+    "pattern",
+    "intervalMs",
+    "timeoutMs",
+    "maxWaitMs",
+];
+
+const ALERT_KEYS: &[&str] = &[
+    "name",
+    "target",
+    "metric",
+    "threshold",
+    "durationMs",
+    "severity",
+];
+
+const SETTINGS_KEYS: &[&str] = &[
+    "logLevel",
+    "locale",
+    "logDirectory",
+    "persistLogs",
+    "maxLogSize",
+    "maxLogFiles",
+    "gracefulShutdownTimeout",
+    "defaultProfiles",
+    "defaultAutoRestart",
+    "defaultRestartDelayMs",
+    "logBufferLines",
+    "logBufferBytes",
+    "maxLogLineBytes",
+    "healthCheckIntervalMs",
+    "startGraceMs",
+    "serviceCacheTtlSecs",
+    "secretPatterns",
+    "blockedCommands",
+    "allowPrivilegedCommands",
+    "allowPrivilegedForwards",
+    "allowedRoots",
+    "aggregateChildUsage",
+    "notificationSinks",
+    "metricsAddress",
+    "network",
+    "api",
+    "onExit",
+    "cacheDirs",
+    "logRetention",
+    "metricsHistory",
+];
+
+const NETWORK_KEYS: &[&str] = &["excludeInterfaces", "includeLoopback", "geoipDatabase"];
+
+const API_KEYS: &[&str] = &["enabled", "bind", "port", "token"];
+
+const LOG_RETENTION_KEYS: &[&str] = &["maxTotalLogBytes", "maxAgeDays", "processes"];
+
+const METRICS_HISTORY_KEYS: &[&str] = &["enabled", "intervalSecs", "retentionDays", "path"];
+
+const LOG_RETENTION_LIMIT_KEYS: &[&str] = &["maxTotalLogBytes", "maxAgeDays"];
+
+const NOTIFICATION_SINK_KEYS: &[&str] = &["name", "type", "url", "headers", "events", "processes"];
+
+/// A key that does not belong where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnknownKey {
+    /// The key as written.
+    pub key: String,
+    /// Where it was found, e.g. "process 'api'".
+    pub context: String,
+    /// Closest known key, if any is close enough.
+    pub suggestion: Option<&'static str>,
+}
+
+impl UnknownKey {
+    pub(crate) fn message(&self) -> String {
+        match self.suggestion {
+            Some(suggestion) => format!(
+                "Unknown key '{}' in {} (did you mean '{}'?)",
+                self.key, self.context, suggestion
+            ),
+            None => format!("Unknown key '{}' in {}", self.key, self.context),
+        }
+    }
+}
+
+/// Finds every unknown key in a parsed config document.
+pub(crate) fn find_unknown_keys(document: &Value) -> Vec<UnknownKey> {
+    let mut unknown = Vec::new();
+    let Some(root) = document.as_mapping() else {
+        return unknown;
+    };
+
+    check_keys(root, TOP_LEVEL_KEYS, "the top level", &mut unknown);
+
+    if let Some(settings) = root.get("settings").and_then(Value::as_mapping) {
+        check_keys(settings, SETTINGS_KEYS, "settings", &mut unknown);
+
+        let sinks = settings
+            .get("notificationSinks")
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten();
+        for (index, sink) in sinks.enumerate() {
+            let Some(sink) = sink.as_mapping() else {
+                continue;
+            };
+            let context = match sink.get("name").and_then(Value::as_str) {
+                Some(name) => format!("notification sink '{}'", name),
+                None => format!("notification sink #{}", index + 1),
+            };
+            check_keys(sink, NOTIFICATION_SINK_KEYS, &context, &mut unknown);
+        }
+
+        if let Some(network) = settings.get("network").and_then(Value::as_mapping) {
+            check_keys(network, NETWORK_KEYS, "settings.network", &mut unknown);
+        }
+
+        if let Some(api) = settings.get("api").and_then(Value::as_mapping) {
+            check_keys(api, API_KEYS, "settings.api", &mut unknown);
+        }
+
+        if let Some(retention) = settings.get("logRetention").and_then(Value::as_mapping) {
+            check_keys(
+                retention,
+                LOG_RETENTION_KEYS,
+                "settings.logRetention",
+                &mut unknown,
+            );
+            let processes = retention.get("processes").and_then(Value::as_mapping);
+            for (name, limits) in processes.into_iter().flatten() {
+                if let (Some(name), Some(limits)) = (name.as_str(), limits.as_mapping()) {
+                    check_keys(
+                        limits,
+                        LOG_RETENTION_LIMIT_KEYS,
+                        &format!("settings.logRetention.processes.{}", name),
+                        &mut unknown,
+                    );
+                }
+            }
+        }
+
+        if let Some(history) = settings.get("metricsHistory").and_then(Value::as_mapping) {
+            check_keys(
+                history,
+                METRICS_HISTORY_KEYS,
+                "settings.metricsHistory",
+                &mut unknown,
+            );
+        }
+    }
+
+    let defaults = root.get("defaults").and_then(Value::as_mapping);
+    for (name, entry) in defaults.into_iter().flatten() {
+        if let (Some(name), Some(entry)) = (name.as_str(), entry.as_mapping()) {
+            check_process_keys(entry, &format!("defaults.{}", name), &mut unknown);
+        }
+    }
+
+    let processes = root
+        .get("processes")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten();
+    for (index, process) in processes.enumerate() {
+        let Some(process) = process.as_mapping() else {
+            continue;
+        };
+        let context = match process.get("name").and_then(Value::as_str) {
+            Some(name) => format!("process '{}'", name),
+            None => format!("process #{}", index + 1),
+        };
+        check_process_keys(process, &context, &mut unknown);
+    }
+
+    let alerts = root
+        .get("alerts")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten();
+    for (index, rule) in alerts.enumerate() {
+        let Some(rule) = rule.as_mapping() else {
+            continue;
+        };
+        let context = match rule.get("name").and_then(Value::as_str) {
+            Some(name) => format!("alert rule '{}'", name),
+            None => format!("alert rule #{}", index + 1),
+        };
+        check_keys(rule, ALERT_KEYS, &context, &mut unknown);
+    }
+
+    unknown
+}
+
+/// Checks a process, or a defaults entry, and its nested sections.
+fn check_process_keys(process: &serde_yaml::Mapping, context: &str, unknown: &mut Vec<UnknownKey>) {
+    check_keys(process, PROCESS_KEYS, context, unknown);
+    if let Some(health_check) = process.get("healthCheck").and_then(Value::as_mapping) {
+        let context = format!("healthCheck of {}", context);
+        check_keys(health_check, HEALTH_CHECK_KEYS, &context, unknown);
+    }
+    if let Some(readiness) = process.get("readiness").and_then(Value::as_mapping) {
+        let context = format!("readiness of {}", context);
+        check_keys(readiness, READINESS_KEYS, &context, unknown);
+    }
+    if let Some(policy) = process.get("restartPolicy").and_then(Value::as_mapping) {
+        let context = format!("restartPolicy of {}", context);
+        check_keys(policy, RESTART_POLICY_KEYS, &context, unknown);
+    }
+}
+
+fn check_keys(
+    mapping: &serde_yaml::Mapping,
+    known: &[&'static str],
+    context: &str,
+    unknown: &mut Vec<UnknownKey>,
+) {
+    for key in mapping.keys() {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        // `args+` appends to the inherited `args`
+        let name = key.strip_suffix(APPEND_SUFFIX).unwrap_or(key);
+        if !known.contains(&name) {
+            unknown.push(UnknownKey {
+                key: key.to_string(),
+                context: context.to_string(),
+                suggestion: suggest(key, known),
+            });
+        }
+    }
+}
+
+/// Closest known key, ignoring case and `_`/`-` so `auto_restart` finds `autoRestart`.
+fn suggest(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    let normalized = normalize(key);
+    let max_distance = (normalized.len() / 3).max(2);
+
+    known
+        .iter()
+        .map(|candidate| {
+            (
+                edit_distance(&normalized, &normalize(candidate)),
+                *candidate,
+            )
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Line and column (1-based) of the `occurrence`-th (0-based) line defining `key`.
+pub(crate) fn locate_key(contents: &str, key: &str, occurrence: usize) -> Option<(usize, usize)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let indent = line.len() - line.trim_start().len();
+            let rest = &line[indent..];
+            // Keys may start a sequence item (`- name: api`)
+            let (offset, rest) = match rest.strip_prefix("- ") {
+                Some(item) => {
+                    let item_indent = item.len() - item.trim_start().len();
+                    (indent + 2 + item_indent, item.trim_start())
+                }
+                None => (indent, rest),
+            };
+
+            let defines_key = [
+                key.to_string(),
+                format!("\"{}\"", key),
+                format!("'{}'", key),
+            ]
+            .iter()
+            .any(|quoted| {
+                rest.strip_prefix(quoted.as_str())
+                    .is_some_and(|after| after.trim_start().starts_with(':'))
+            });
+            defines_key.then_some((index + 1, offset + 1))
+        })
+        .nth(occurrence)
+}
+
+/// A few numbered source lines ending at `line`, with a caret under `column`.
+pub(crate) fn source_context(contents: &str, line: usize, column: usize) -> String {
+    const CONTEXT_LINES: usize = 2;
+
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+    let width = line.to_string().len();
+    let mut context = String::new();
+
+    for (number, text) in contents
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text))
+        .skip(first - 1)
+        .take(line + 1 - first)
+    {
+        context.push_str(&format!("{:>width$} | {}\n", number, text));
+    }
+    context.push_str(&format!(
+        "{:>width$} | {}^",
+        "",
+        " ".repeat(column.saturating_sub(1))
+    ));
+
+    context
+}
+
+/// Which limit a document broke in [`check_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LimitExceeded {
+    /// It expands to more than `max_nodes` nodes.
+    Nodes,
+    /// It nests deeper than `max_depth` sequences and mappings.
+    Depth,
+}
+
+/// Walks a YAML document without building it, stopping as soon as it expands
+/// to more than `max_nodes` nodes or nests deeper than `max_depth`.
+///
+/// The deserializer replays an alias each time it is used, so every use counts
+/// in full; this is what stops "billion laughs" documents early. Syntax errors
+/// are left for the real parse to report.
+pub(crate) fn check_limits(
+    contents: &str,
+    max_nodes: usize,
+    max_depth: usize,
+) -> Option<LimitExceeded> {
+    let budget = Budget {
+        nodes: Cell::new(0),
+        max_nodes,
+        max_depth,
+        exceeded: Cell::new(None),
+    };
+
+    let result = Walker {
+        budget: &budget,
+        depth: 0,
+    }
+    .deserialize(serde_yaml::Deserializer::from_str(contents));
+
+    // serde_yaml has coarser guards of its own that can trip first
+    match result {
+        Err(e) if budget.exceeded.get().is_none() => {
+            let message = e.to_string();
+            if message.starts_with("repetition limit exceeded") {
+                Some(LimitExceeded::Nodes)
+            } else if message.starts_with("recursion limit exceeded") {
+                Some(LimitExceeded::Depth)
+            } else {
+                None
+            }
+        }
+        _ => budget.exceeded.get(),
+    }
+}
+
+struct Budget {
+    nodes: Cell<usize>,
+    max_nodes: usize,
+    max_depth: usize,
+    exceeded: Cell<Option<LimitExceeded>>,
+}
+
+/// Visits one node and everything under it, charging each to the budget.
+#[derive(Clone, Copy)]
+struct Walker<'a> {
+    budget: &'a Budget,
+    depth: usize,
+}
+
+impl Walker<'_> {
+    fn enter<E: de::Error>(&self) -> Result<(), E> {
+        let nodes = self.budget.nodes.get() + 1;
+        self.budget.nodes.set(nodes);
+        if nodes > self.budget.max_nodes {
+            return Err(self.exceed(LimitExceeded::Nodes));
+        }
+        Ok(())
+    }
+
+    fn child<E: de::Error>(&self) -> Result<Self, E> {
+        self.enter()?;
+        if self.depth >= self.budget.max_depth {
+            return Err(self.exceed(LimitExceeded::Depth));
+        }
+        Ok(Walker {
+            budget: self.budget,
+            depth: self.depth + 1,
+        })
+    }
+
+    fn exceed<E: de::Error>(&self, limit: LimitExceeded) -> E {
+        self.budget.exceeded.set(Some(limit));
+        E::custom("config size limit exceeded")
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Walker<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Walker<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any YAML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        self.enter()
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        self.enter()
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        self.enter()
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        self.enter()
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<(), E> {
+        self.enter()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.enter()
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let child = self.child()?;
+        while seq.next_element_seed(child)?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let child = self.child()?;
+        while map.next_key_seed(child)?.is_some() {
+            map.next_value_seed(child)?;
+        }
+        Ok(())
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<(), A::Error> {
+        // A tagged value such as `!Env value`
+        use de::VariantAccess;
+        let (_, variant) = data.variant::<de::IgnoredAny>()?;
+        variant.newtype_variant_seed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unknown_keys(yaml: &str) -> Vec<String> {
+        find_unknown_keys(&serde_yaml::from_str(yaml).unwrap())
+            .iter()
+            .map(UnknownKey::message)
+            .collect()
+    }
+
+    #[test]
+    fn test_unknown_keys_with_suggestions() {
+        let messages = unknown_keys(
+            r#"
+proceses: []
+processes:
+  - name: api
+    command: cargo run
+    restrat_delay: 500
+    auto_restart: true
+    healthCheck:
+      command: curl
+      intervalMs: 1000
+      timeoutMs: 500
+      retries: 3
+      timeout: 2
+    restartPolicy:
+      maxUptimeHour: 4
+settings:
+  logLevl: debug
+  colour: true
+  logRetention:
+    maxAgeDays: 7
+    processes:
+      api:
+        maxTotalBytes: 1000
+alerts:
+  - name: api-cpu
+    target: api
+    metric: cpu
+    threshold: 90
+    duration: 5000
+"#,
+        );
+
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown key 'proceses' in the top level (did you mean 'processes'?)",
+                "Unknown key 'logLevl' in settings (did you mean 'logLevel'?)",
+                "Unknown key 'colour' in settings",
+                "Unknown key 'maxTotalBytes' in settings.logRetention.processes.api (did you mean 'maxTotalLogBytes'?)",
+                "Unknown key 'restrat_delay' in process 'api' (did you mean 'restartDelay'?)",
+                "Unknown key 'auto_restart' in process 'api' (did you mean 'autoRestart'?)",
+                "Unknown key 'timeout' in healthCheck of process 'api' (did you mean 'timeoutMs'?)",
+                "Unknown key 'maxUptimeHour' in restartPolicy of process 'api' (did you mean 'maxUptimeHours'?)",
+                "Unknown key 'duration' in alert rule 'api-cpu' (did you mean 'durationMs'?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aliases_are_known() {
+        let yaml = "processes:\n  - name: api\n    command: x\n    max_restarts: 3\n    restart_delay_ms: 10\n";
+        assert!(unknown_keys(yaml).is_empty());
+    }
+
+    #[test]
+    fn test_inheritance_keys_are_known() {
+        let yaml = r#"
+defaults:
+  node:
+    command: node
+    dependsOn+: [db]
+    restrat_delay: 500
+processes:
+  - name: api
+    extends: node
+    args+: [--verbose]
+"#;
+        assert_eq!(
+            unknown_keys(yaml),
+            vec!["Unknown key 'restrat_delay' in defaults.node (did you mean 'restartDelay'?)"]
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("restratdelay", "restartdelay"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_locate_key() {
+        let yaml = "processes:\n  - name: a\n    restrat_delay: 1\n  - restrat_delay: 2\n    \"name\": b\n";
+
+        assert_eq!(locate_key(yaml, "restrat_delay", 0), Some((3, 5)));
+        assert_eq!(locate_key(yaml, "restrat_delay", 1), Some((4, 5)));
+        assert_eq!(locate_key(yaml, "name", 1), Some((5, 5)));
+        assert_eq!(locate_key(yaml, "restrat_delay", 2), None);
+    }
+
+    #[test]
+    fn test_source_context() {
+        let yaml = "processes:\n  - name: api\n    autoRestart: yess\n";
+
+        assert_eq!(
+            source_context(yaml, 3, 18),
+            "1 | processes:\n2 |   - name: api\n3 |     autoRestart: yess\n  |                  ^"
+        );
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let config = "processes:\n  - name: api\n    command: npm start\n    args: [a, b]\n";
+        assert_eq!(check_limits(config, 100, 8), None);
+        assert_eq!(check_limits(config, 5, 8), Some(LimitExceeded::Nodes));
+        assert_eq!(check_limits(config, 100, 2), Some(LimitExceeded::Depth));
+
+        // Each use of an alias counts in full
+        let aliases = "a: &a [1, 2, 3, 4]\nb: [*a, *a, *a, *a]\n";
+        assert_eq!(check_limits(aliases, 30, 8), None);
+        assert_eq!(check_limits(aliases, 20, 8), Some(LimitExceeded::Nodes));
+
+        // Syntax errors are left for the parser
+        assert_eq!(check_limits("processes: [", 100, 8), None);
+    }
+}
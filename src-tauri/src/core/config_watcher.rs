@@ -0,0 +1,378 @@
+//! Watching the config file, and the files it includes, for edits made
+//! outside the app.
+//!
+//! The files are polled rather than watched through inotify and friends
+//! (e.g. the `notify` crate). A poll looks at the path, not an open handle,
+//! so it keeps working when an editor saves by renaming a new file over the
+//! old one, and when the file doesn't exist yet; a watch would have to be
+//! set on each file's directory and matched back to the file, and redone as
+//! includes come and go. A handful of `stat` calls every [`POLL_INTERVAL`]
+//! costs next to nothing. A change is only reported once the file has
+//! stopped changing for [`DEBOUNCE`], so a save that writes in several steps
+//! is seen once.
+
+use crate::core::config::{ConfigDiff, ConfigManager};
+use crate::models::Config;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+
+/// Event emitted when the config file has changed on disk
+pub const CONFIG_FILE_CHANGED_EVENT: &str = "config-file-changed";
+
+/// How often the file is checked
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the file must stay unchanged before a change is reported
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Payload of the config change event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFileChangedEvent {
+    /// The config file
+    pub path: String,
+    /// Whether the file loads and validates
+    pub valid: bool,
+    /// Why it doesn't, when it doesn't
+    pub error: Option<String>,
+    /// What a reload would change, when the file is valid
+    pub diff: Option<ConfigDiff>,
+    /// The diff in one line, or the error
+    pub summary: String,
+}
+
+impl ConfigFileChangedEvent {
+    /// Loads `path` the way `reload_config` does and compares it with `current`.
+    ///
+    /// A missing file stands for the default configuration.
+    pub fn check(path: &Path, current: Option<&Config>) -> Self {
+        let loaded = if path.exists() {
+            ConfigManager::load_from_file(path)
+        } else {
+            Ok(ConfigManager::default_config())
+        };
+
+        match loaded {
+            Ok(config) => {
+                let diff = ConfigDiff::between(
+                    current.unwrap_or(&ConfigManager::default_config()),
+                    &config,
+                );
+                Self {
+                    path: path.display().to_string(),
+                    valid: true,
+                    error: None,
+                    summary: diff.summary(),
+                    diff: Some(diff),
+                }
+            }
+            Err(e) => Self {
+                path: path.display().to_string(),
+                valid: false,
+                error: Some(e.to_string()),
+                diff: None,
+                summary: e.to_string(),
+            },
+        }
+    }
+}
+
+/// What the file looked like at the last poll; `None` while it doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+    /// A file renamed over the old one is a different file, even if its
+    /// size and modification time match
+    #[cfg(unix)]
+    inode: u64,
+}
+
+impl Fingerprint {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+            #[cfg(unix)]
+            inode: std::os::unix::fs::MetadataExt::ino(&metadata),
+        })
+    }
+}
+
+/// Notices changes to one file, debounced
+pub struct FileWatch {
+    path: PathBuf,
+    last: Option<Fingerprint>,
+    changed_at: Option<Instant>,
+}
+
+impl FileWatch {
+    /// Starts watching `path` as it is now, whether or not it exists
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            last: Fingerprint::of(&path),
+            path,
+            changed_at: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks the file, returning true once it has changed and then stayed
+    /// unchanged for [`DEBOUNCE`]
+    pub fn poll(&mut self, now: Instant) -> bool {
+        let current = Fingerprint::of(&self.path);
+        if current != self.last {
+            self.last = current;
+            self.changed_at = Some(now);
+            return false;
+        }
+
+        match self.changed_at {
+            Some(changed_at) if now.duration_since(changed_at) >= DEBOUNCE => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Notices changes to a config file and every file it includes, debounced
+pub struct ConfigWatch {
+    root: PathBuf,
+    files: Vec<FileWatch>,
+}
+
+impl ConfigWatch {
+    /// Starts watching `root` and the files it includes as they are now
+    pub fn new(root: PathBuf) -> Self {
+        let mut watch = Self {
+            root,
+            files: Vec::new(),
+        };
+        watch.refresh();
+        watch
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The files being watched, `root` first
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(FileWatch::path)
+    }
+
+    /// Checks every file, returning true once one has changed and then
+    /// stayed unchanged for [`DEBOUNCE`]
+    ///
+    /// The files included are looked up again after a change, since it may
+    /// have added or removed includes.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        let mut changed = false;
+        for file in &mut self.files {
+            changed |= file.poll(now);
+        }
+        if changed {
+            self.refresh();
+        }
+        changed
+    }
+
+    /// Watches the files `root` includes now, keeping the watches of those
+    /// it still includes
+    fn refresh(&mut self) {
+        let mut previous = std::mem::take(&mut self.files);
+        self.files = ConfigManager::resolved_files(&self.root)
+            .into_iter()
+            .map(
+                |path| match previous.iter().position(|w| w.path() == path) {
+                    Some(index) => previous.swap_remove(index),
+                    None => FileWatch::new(path),
+                },
+            )
+            .collect();
+    }
+}
+
+/// Polls `path` and its includes until the app exits, emitting
+/// [`CONFIG_FILE_CHANGED_EVENT`] with what a reload would change compared to
+/// `config`
+pub async fn watch(path: PathBuf, config: Arc<RwLock<Option<Config>>>, app: AppHandle) {
+    let mut watch = ConfigWatch::new(path);
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if !watch.poll(Instant::now()) {
+            continue;
+        }
+
+        let current = config.read().await.clone();
+        let event = ConfigFileChangedEvent::check(watch.root(), current.as_ref());
+        tracing::info!("Config file changed: {}", event.summary);
+        if let Err(e) = app.emit(CONFIG_FILE_CHANGED_EVENT, &event) {
+            tracing::warn!("Failed to emit config change: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Polls the way the background task does until the watch fires
+    fn settle(watch: &mut FileWatch, start: Instant) -> bool {
+        let mut now = start;
+        while now < start + DEBOUNCE * 2 {
+            if watch.poll(now) {
+                return true;
+            }
+            now += POLL_INTERVAL;
+        }
+        false
+    }
+
+    #[test]
+    fn test_change_is_reported_once_after_debounce() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sentinel.yaml");
+        fs::write(&path, "processes: []\n").unwrap();
+
+        let mut watch = FileWatch::new(path.clone());
+        let start = Instant::now();
+        assert!(!watch.poll(start));
+
+        fs::write(&path, "processes:\n  - name: api\n    command: npm start\n").unwrap();
+        assert!(!watch.poll(start));
+        assert!(!watch.poll(start + DEBOUNCE / 2));
+        // Still being written: the debounce starts over
+        fs::write(
+            &path,
+            "processes:\n  - name: api\n    command: npm run dev\n",
+        )
+        .unwrap();
+        assert!(!watch.poll(start + DEBOUNCE));
+        assert!(!watch.poll(start + DEBOUNCE + DEBOUNCE / 2));
+        assert!(watch.poll(start + DEBOUNCE * 2));
+        assert!(!watch.poll(start + DEBOUNCE * 3));
+    }
+
+    #[test]
+    fn test_atomic_rename_over_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sentinel.yaml");
+        fs::write(&path, "processes: []\n").unwrap();
+        let mut watch = FileWatch::new(path.clone());
+
+        // What vim and VS Code do: write a sibling, then rename it over
+        for round in 0..3 {
+            let temp = dir.path().join(".sentinel.yaml.swp");
+            fs::write(&temp, format!("processes: []\n# save {}\n", round)).unwrap();
+            fs::rename(&temp, &path).unwrap();
+            assert!(settle(&mut watch, Instant::now()), "save {}", round);
+        }
+
+        // Same size as the last save, but a different file
+        let temp = dir.path().join(".sentinel.yaml.swp");
+        fs::write(&temp, "processes: []\n# save 9\n").unwrap();
+        fs::rename(&temp, &path).unwrap();
+        assert!(settle(&mut watch, Instant::now()));
+    }
+
+    #[test]
+    fn test_file_created_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sentinel.yaml");
+        let mut watch = FileWatch::new(path.clone());
+        assert!(!settle(&mut watch, Instant::now()));
+
+        fs::write(&path, "processes: []\n").unwrap();
+        assert!(settle(&mut watch, Instant::now()));
+
+        fs::remove_file(&path).unwrap();
+        assert!(settle(&mut watch, Instant::now()));
+    }
+
+    /// Polls a config watch the way the background task does until it fires
+    fn settle_config(watch: &mut ConfigWatch, start: Instant) -> bool {
+        let mut now = start;
+        while now < start + DEBOUNCE * 2 {
+            if watch.poll(now) {
+                return true;
+            }
+            now += POLL_INTERVAL;
+        }
+        false
+    }
+
+    #[test]
+    fn test_included_files_are_watched() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("sentinel.yaml");
+        let api = dir.path().join("api.yaml");
+        let web = dir.path().join("web.yaml");
+        fs::write(&root, "include: [api.yaml]\nprocesses: []\n").unwrap();
+        fs::write(&api, "processes:\n  - name: api\n    command: npm start\n").unwrap();
+        fs::write(&web, "processes:\n  - name: web\n    command: vite\n").unwrap();
+
+        let mut watch = ConfigWatch::new(root.clone());
+        assert_eq!(watch.files().count(), 2);
+        assert!(!settle_config(&mut watch, Instant::now()));
+
+        fs::write(
+            &api,
+            "processes:\n  - name: api\n    command: npm run dev\n",
+        )
+        .unwrap();
+        assert!(settle_config(&mut watch, Instant::now()));
+
+        // Not included yet
+        fs::write(&web, "processes:\n  - name: web\n    command: vite dev\n").unwrap();
+        assert!(!settle_config(&mut watch, Instant::now()));
+
+        // Included once the root says so, and dropped once it no longer does
+        fs::write(&root, "include: [api.yaml, web.yaml]\nprocesses: []\n").unwrap();
+        assert!(settle_config(&mut watch, Instant::now()));
+        assert_eq!(watch.files().count(), 3);
+        fs::write(&web, "processes:\n  - name: web\n    command: vite build\n").unwrap();
+        assert!(settle_config(&mut watch, Instant::now()));
+
+        fs::write(&root, "processes: []\n").unwrap();
+        assert!(settle_config(&mut watch, Instant::now()));
+        assert_eq!(watch.files().collect::<Vec<_>>(), vec![root.as_path()]);
+    }
+
+    #[test]
+    fn test_check_reports_diff_or_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sentinel.yaml");
+        fs::write(&path, "processes:\n  - name: api\n    command: npm start\n").unwrap();
+        let current = ConfigManager::load_from_file(&path).unwrap();
+
+        fs::write(
+            &path,
+            "processes:\n  - name: api\n    command: npm run dev\n  - name: web\n    command: vite\n",
+        )
+        .unwrap();
+        let event = ConfigFileChangedEvent::check(&path, Some(&current));
+        assert!(event.valid);
+        let diff = event.diff.unwrap();
+        assert_eq!(diff.modified, vec!["api"]);
+        assert_eq!(diff.added, vec!["web"]);
+        assert_eq!(event.summary, "1 process modified, 1 process added");
+
+        fs::write(&path, "processes:\n  - name: api\n  - name: api\n").unwrap();
+        let event = ConfigFileChangedEvent::check(&path, Some(&current));
+        assert!(!event.valid);
+        assert!(event.diff.is_none());
+        assert_eq!(event.error.as_deref(), Some(event.summary.as_str()));
+    }
+}
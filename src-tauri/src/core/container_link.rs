@@ -0,0 +1,567 @@
+//! Linking managed processes to the Docker containers they run.
+//!
+//! A process that only calls `docker run` or `docker compose up` uses almost
+//! no CPU itself; the work happens in the container. Such processes are
+//! linked to their containers by name or labels, never by ID, so the link
+//! survives the container being recreated. [`refresh`] looks the containers
+//! up again each time and hands their usage to the [`ProcessManager`], which
+//! adds it to the process's own.
+
+use crate::core::ProcessManager;
+use crate::features::docker::{ContainerInfo, ContainerStats, DockerMonitor};
+use crate::models::{ContainerUsage, ProcessConfig};
+use chrono::{DateTime, Duration, Utc};
+use std::path::Path;
+use tokio::sync::Mutex;
+
+/// How often linked containers are measured.
+pub const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How long a measurement counts; older ones are ignored, e.g. once Docker
+/// stops answering.
+pub const USAGE_TTL: Duration = Duration::seconds(30);
+
+/// Label Docker Compose puts on containers with the project name.
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+
+/// Label Docker Compose puts on containers with the service name.
+const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+
+/// `docker run` options that take a value, so the value isn't taken for the image.
+const RUN_VALUE_FLAGS: &[&str] = &[
+    "-e",
+    "--env",
+    "-v",
+    "--volume",
+    "-p",
+    "--publish",
+    "-w",
+    "--workdir",
+    "-u",
+    "--user",
+    "--name",
+    "--entrypoint",
+    "-l",
+    "--label",
+    "--network",
+    "--env-file",
+    "-m",
+    "--memory",
+];
+
+/// `docker compose` options that take a value.
+const COMPOSE_VALUE_FLAGS: &[&str] = &[
+    "-f",
+    "--file",
+    "--env-file",
+    "--profile",
+    "--project-directory",
+    "--scale",
+    "-t",
+    "--timeout",
+    "--exit-code-from",
+    "--attach",
+    "--no-attach",
+    "--pull",
+    "--wait-timeout",
+];
+
+/// How a process's containers are found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContainerRef {
+    /// The container with this name.
+    Name(String),
+    /// Containers with this label.
+    Label { key: String, value: String },
+    /// Containers of a Compose project, limited to `services` unless empty.
+    /// Any project matches when it is unknown.
+    Compose {
+        project: Option<String>,
+        services: Vec<String>,
+    },
+}
+
+impl ContainerRef {
+    /// Reads a `containerRef` setting: a container name, or `key=value` for
+    /// a label.
+    pub fn parse(reference: &str) -> Self {
+        match reference.split_once('=') {
+            Some((key, value)) => Self::Label {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            },
+            None => Self::Name(reference.trim().trim_start_matches('/').to_string()),
+        }
+    }
+
+    /// The reference for a process: its `containerRef`, or one read from a
+    /// `docker run` or `docker compose` command. `None` for other processes
+    /// and for `docker run` without a `--name` or `--label`.
+    pub fn from_config(config: &ProcessConfig) -> Option<Self> {
+        if let Some(reference) = config.container_ref.as_deref() {
+            return Some(Self::parse(reference));
+        }
+
+        let program = Path::new(&config.command).file_name()?.to_str()?;
+        let args: Vec<&str> = config.args.iter().map(String::as_str).collect();
+        match (program, args.split_first()) {
+            ("docker", Some((&"run", rest))) => from_run(rest),
+            ("docker", Some((&"container", rest))) if rest.first() == Some(&"run") => {
+                from_run(&rest[1..])
+            }
+            ("docker", Some((&"compose", rest))) => from_compose(rest, config),
+            ("docker-compose", _) => from_compose(&args, config),
+            _ => None,
+        }
+    }
+
+    /// Whether `container` is one of the process's.
+    pub fn matches(&self, container: &ContainerInfo) -> bool {
+        let label = |key: &str| {
+            container
+                .labels
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        match self {
+            Self::Name(name) => container.name.trim_start_matches('/') == name,
+            Self::Label { key, value } => label(key) == Some(value.as_str()),
+            Self::Compose { project, services } => {
+                let Some(service) = label(COMPOSE_SERVICE_LABEL) else {
+                    return false;
+                };
+                (services.is_empty() || services.iter().any(|s| s == service))
+                    && project
+                        .as_deref()
+                        .is_none_or(|project| label(COMPOSE_PROJECT_LABEL) == Some(project))
+            }
+        }
+    }
+
+    /// The running containers among `containers` that are the process's.
+    pub fn resolve<'a>(&self, containers: &'a [ContainerInfo]) -> Vec<&'a ContainerInfo> {
+        containers
+            .iter()
+            .filter(|container| container.state == "running" && self.matches(container))
+            .collect()
+    }
+}
+
+/// The container named by `--name`, or else labelled by the first `--label`.
+fn from_run(args: &[&str]) -> Option<ContainerRef> {
+    let mut label = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with('-') {
+            // The image; what follows is the container's command
+            break;
+        }
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (*arg, None),
+        };
+        let value = || inline.or_else(|| iter.clone().next().copied());
+        match flag {
+            "--name" => return value().map(|name| ContainerRef::Name(name.to_string())),
+            "-l" | "--label" if label.is_none() => {
+                label = value().and_then(|l| l.split_once('=')).map(|(key, value)| {
+                    ContainerRef::Label {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    }
+                });
+            }
+            _ => {}
+        }
+        if inline.is_none() && RUN_VALUE_FLAGS.contains(&flag) {
+            iter.next();
+        }
+    }
+    label
+}
+
+/// The services started by `docker compose up` or `run`, in the project
+/// named by `-p`, `COMPOSE_PROJECT_NAME` or the working directory.
+fn from_compose(args: &[&str], config: &ProcessConfig) -> Option<ContainerRef> {
+    let mut project = config.env.get("COMPOSE_PROJECT_NAME").cloned();
+    let mut project_dir = config.cwd.clone();
+    let mut subcommand = None;
+    let mut services = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (*arg, None),
+        };
+        if flag.starts_with('-') {
+            let takes_value = matches!(flag, "-p" | "--project-name")
+                || COMPOSE_VALUE_FLAGS.contains(&flag)
+                || (subcommand == Some("run") && RUN_VALUE_FLAGS.contains(&flag));
+            let value = match inline {
+                Some(value) => Some(value),
+                None if takes_value => iter.next().copied(),
+                None => None,
+            };
+            match (flag, value) {
+                ("-p" | "--project-name", Some(name)) if subcommand.is_none() => {
+                    project = Some(name.to_string());
+                }
+                ("--project-directory", Some(dir)) if subcommand.is_none() => {
+                    project_dir = Some(dir.into());
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match subcommand {
+            None => subcommand = Some(*arg),
+            Some("up") | Some("start") => services.push(arg.to_string()),
+            Some("run") => {
+                services.push(arg.to_string());
+                // The rest is the command run in the service
+                break;
+            }
+            Some(_) => {}
+        }
+    }
+
+    if !matches!(subcommand, Some("up" | "start" | "run")) {
+        return None;
+    }
+    let project = project
+        .or_else(|| {
+            project_dir
+                .as_deref()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .map(|name| normalize_project_name(&name));
+    Some(ContainerRef::Compose { project, services })
+}
+
+/// A project name as Compose stores it: lowercase letters, digits, `-` and `_`.
+fn normalize_project_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect()
+}
+
+/// Adds up the stats of a process's containers.
+pub fn aggregate(
+    containers: Vec<String>,
+    stats: &[&ContainerStats],
+    now: DateTime<Utc>,
+) -> ContainerUsage {
+    ContainerUsage {
+        containers,
+        cpu_percent: stats.iter().map(|s| s.cpu_percent).sum::<f64>() as f32,
+        memory_bytes: stats.iter().map(|s| s.memory_usage).sum(),
+        memory_limit: stats.iter().map(|s| s.memory_limit).sum(),
+        network_rx_bytes: stats.iter().map(|s| s.network_rx_bytes).sum(),
+        network_tx_bytes: stats.iter().map(|s| s.network_tx_bytes).sum(),
+        block_read_bytes: stats.iter().map(|s| s.block_io_read).sum(),
+        block_write_bytes: stats.iter().map(|s| s.block_io_write).sum(),
+        sampled_at: now,
+    }
+}
+
+/// Whether `usage` is recent enough to merge in.
+pub fn is_fresh(usage: &ContainerUsage, now: DateTime<Utc>) -> bool {
+    now - usage.sampled_at <= USAGE_TTL
+}
+
+/// Finds the containers of every linked running process and reports their
+/// usage to `process_manager`.
+///
+/// Processes whose containers aren't running, or can't be found because
+/// Docker is unavailable, are reported without container usage.
+pub async fn refresh(process_manager: &ProcessManager, docker: &Mutex<DockerMonitor>) {
+    let links = process_manager.container_refs().await;
+    if links.is_empty() {
+        return;
+    }
+
+    let (linked, measurement) = {
+        let monitor = docker.lock().await;
+        let containers = if monitor.is_available() {
+            monitor.list_containers(false).await.unwrap_or_else(|e| {
+                tracing::debug!("Failed to list containers: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        let linked: Vec<(String, Vec<&ContainerInfo>)> = links
+            .iter()
+            .map(|(name, reference)| (name.clone(), reference.resolve(&containers)))
+            .collect();
+        let mut ids: Vec<String> = linked
+            .iter()
+            .flat_map(|(_, containers)| containers.iter().map(|c| c.full_id.clone()))
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        let linked: Vec<(String, Vec<(String, String)>)> = linked
+            .into_iter()
+            .map(|(name, containers)| {
+                let containers = containers
+                    .into_iter()
+                    .map(|c| (c.full_id.clone(), c.name.clone()))
+                    .collect();
+                (name, containers)
+            })
+            .collect();
+        // Measured without holding the monitor, as it takes about a second
+        (linked, monitor.measure_stats(ids))
+    };
+    let stats = measurement.await;
+
+    let now = Utc::now();
+    for (name, containers) in linked {
+        let measured: Vec<&ContainerStats> = stats
+            .iter()
+            .filter(|s| containers.iter().any(|(id, _)| *id == s.container_id))
+            .collect();
+        let usage = (!measured.is_empty()).then(|| {
+            let names = containers.into_iter().map(|(_, name)| name).collect();
+            aggregate(names, &measured, now)
+        });
+        process_manager.set_container_usage(&name, usage).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::docker::ContainerHealth;
+
+    fn config(command: &str, args: &[&str]) -> ProcessConfig {
+        let mut config: ProcessConfig =
+            serde_yaml::from_str(&format!("name: db\ncommand: {}", command)).unwrap();
+        config.args = args.iter().map(|a| a.to_string()).collect();
+        config
+    }
+
+    fn container(id: &str, name: &str, labels: &[(&str, &str)]) -> ContainerInfo {
+        ContainerInfo {
+            id: id[..id.len().min(12)].to_string(),
+            full_id: id.to_string(),
+            name: name.to_string(),
+            image: "postgres:16".to_string(),
+            status: "Up 2 minutes".to_string(),
+            state: "running".to_string(),
+            ports: vec![],
+            cpu_percent: None,
+            memory_usage: None,
+            memory_limit: None,
+            network_rx_bytes: None,
+            network_tx_bytes: None,
+            created: Utc::now(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            health: ContainerHealth::from_summary("running", "Up 2 minutes"),
+        }
+    }
+
+    fn stats(container_id: &str, cpu_percent: f64, memory_usage: u64) -> ContainerStats {
+        ContainerStats {
+            container_id: container_id.to_string(),
+            cpu_percent,
+            memory_usage,
+            memory_limit: 1_000_000_000,
+            memory_percent: 0.0,
+            network_rx_bytes: 100,
+            network_tx_bytes: 200,
+            block_io_read: 300,
+            block_io_write: 400,
+            pids: 3,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_docker_run_reference() {
+        let run = |args: &[&str]| ContainerRef::from_config(&config("docker", args));
+
+        assert_eq!(
+            run(&[
+                "run",
+                "--rm",
+                "-p",
+                "5432:5432",
+                "--name",
+                "pg",
+                "postgres:16"
+            ]),
+            Some(ContainerRef::Name("pg".to_string()))
+        );
+        assert_eq!(
+            run(&["container", "run", "--name=pg", "postgres:16"]),
+            Some(ContainerRef::Name("pg".to_string()))
+        );
+        assert_eq!(
+            run(&["run", "-l", "app=shop", "postgres:16"]),
+            Some(ContainerRef::Label {
+                key: "app".to_string(),
+                value: "shop".to_string()
+            })
+        );
+        // `--name` after the image belongs to the container's command
+        assert_eq!(run(&["run", "alpine", "echo", "--name", "x"]), None);
+        assert_eq!(run(&["ps"]), None);
+        assert_eq!(ContainerRef::from_config(&config("npm", &["start"])), None);
+    }
+
+    #[test]
+    fn test_compose_reference() {
+        let mut compose = config("docker", &["compose", "-p", "Shop", "up", "db", "cache"]);
+        assert_eq!(
+            ContainerRef::from_config(&compose),
+            Some(ContainerRef::Compose {
+                project: Some("shop".to_string()),
+                services: vec!["db".to_string(), "cache".to_string()],
+            })
+        );
+
+        // The project defaults to the working directory's name
+        compose = config("/usr/local/bin/docker-compose", &["up", "--build"]);
+        compose.cwd = Some("/home/dev/My Shop".into());
+        assert_eq!(
+            ContainerRef::from_config(&compose),
+            Some(ContainerRef::Compose {
+                project: Some("myshop".to_string()),
+                services: vec![],
+            })
+        );
+
+        compose = config("docker", &["compose", "run", "-e", "X=1", "worker", "rake"]);
+        assert_eq!(
+            ContainerRef::from_config(&compose),
+            Some(ContainerRef::Compose {
+                project: None,
+                services: vec!["worker".to_string()],
+            })
+        );
+        assert_eq!(
+            ContainerRef::from_config(&config("docker", &["compose", "logs", "-f"])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_container_ref_setting_wins() {
+        let mut process = config("./run-db.sh", &[]);
+        process.container_ref = Some("/pg".to_string());
+        assert_eq!(
+            ContainerRef::from_config(&process),
+            Some(ContainerRef::Name("pg".to_string()))
+        );
+        process.container_ref = Some("com.example.role=db".to_string());
+        assert_eq!(
+            ContainerRef::from_config(&process),
+            Some(ContainerRef::Label {
+                key: "com.example.role".to_string(),
+                value: "db".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_rematches_recreated_container_by_name() {
+        let reference = ContainerRef::Name("pg".to_string());
+        let before = [
+            container("aaaa1111", "pg", &[]),
+            container("bbbb2222", "redis", &[]),
+        ];
+        let after = [
+            container("cccc3333", "pg", &[]),
+            container("bbbb2222", "redis", &[]),
+        ];
+
+        assert_eq!(reference.resolve(&before)[0].full_id, "aaaa1111");
+        assert_eq!(reference.resolve(&after)[0].full_id, "cccc3333");
+
+        let mut stopped = container("cccc3333", "pg", &[]);
+        stopped.state = "exited".to_string();
+        assert!(reference.resolve(&[stopped]).is_empty());
+    }
+
+    #[test]
+    fn test_compose_matches_labels() {
+        let db = container(
+            "aaaa1111",
+            "shop-db-1",
+            &[
+                (COMPOSE_PROJECT_LABEL, "shop"),
+                (COMPOSE_SERVICE_LABEL, "db"),
+            ],
+        );
+        let other = container(
+            "bbbb2222",
+            "blog-db-1",
+            &[
+                (COMPOSE_PROJECT_LABEL, "blog"),
+                (COMPOSE_SERVICE_LABEL, "db"),
+            ],
+        );
+        let containers = [db, other];
+
+        let shop = ContainerRef::Compose {
+            project: Some("shop".to_string()),
+            services: vec![],
+        };
+        assert_eq!(shop.resolve(&containers).len(), 1);
+        let any_db = ContainerRef::Compose {
+            project: None,
+            services: vec!["db".to_string()],
+        };
+        assert_eq!(any_db.resolve(&containers).len(), 2);
+    }
+
+    #[test]
+    fn test_aggregate_sums_containers() {
+        let now = Utc::now();
+        let (db, cache) = (stats("a", 50.0, 300), stats("b", 25.5, 200));
+        let usage = aggregate(
+            vec!["db".to_string(), "cache".to_string()],
+            &[&db, &cache],
+            now,
+        );
+
+        assert_eq!(usage.cpu_percent, 75.5);
+        assert_eq!(usage.memory_bytes, 500);
+        assert_eq!(usage.memory_limit, 2_000_000_000);
+        assert_eq!(usage.network_rx_bytes, 200);
+        assert_eq!(usage.block_write_bytes, 800);
+        assert!(is_fresh(&usage, now + USAGE_TTL));
+        assert!(!is_fresh(&usage, now + USAGE_TTL + Duration::seconds(1)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_refresh_without_docker_clears_usage() {
+        let manager = ProcessManager::new();
+        let mut process = config("sleep", &["10"]);
+        process.container_ref = Some("pg".to_string());
+        manager.start(process).await.unwrap();
+        let stale = aggregate(vec!["pg".to_string()], &[&stats("a", 10.0, 1)], Utc::now());
+        manager.set_container_usage("db", Some(stale)).await;
+
+        let docker = Mutex::new(DockerMonitor::disconnected());
+        refresh(&manager, &docker).await;
+        manager.update_resource_usage().await;
+        let info = manager.get("db").await.unwrap();
+        manager.stop("db").await.unwrap();
+
+        assert_eq!(info.container, None);
+        assert_eq!(info.usage_source, crate::models::UsageSource::Process);
+    }
+}
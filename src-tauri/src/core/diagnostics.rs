@@ -0,0 +1,443 @@
+//! Environment checks behind `sentinel doctor`.
+//!
+//! Each check is split into a probe that touches the system and a `check_*`
+//! function that turns the probe's outcome into a [`CheckResult`], so the
+//! verdicts and remediation hints can be tested without a broken machine.
+
+use crate::core::{ConfigManager, StateManager};
+use crate::features::docker::DockerMonitor;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+/// How long a tool may take to print its version.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Everything is fine.
+    Pass,
+    /// Works, but some features are limited.
+    Warn,
+    /// Sentinel won't work properly until this is fixed.
+    Fail,
+}
+
+/// Result of one diagnostic check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    /// What was checked.
+    pub name: String,
+    /// Outcome.
+    pub status: CheckStatus,
+    /// What was found.
+    pub message: String,
+    /// How to fix a warning or failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Results of every check, in the order they ran.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// Sentinel version.
+    pub version: String,
+    /// Operating system and architecture.
+    pub platform: String,
+    /// Check results.
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// Whether any check failed.
+    pub fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+/// Runs every check against the real environment.
+pub async fn run_checks(config_path: &Path) -> DoctorReport {
+    let state_dir = StateManager::get_state_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let checks = vec![
+        check_config(config_path),
+        check_dir_writable("State directory", &state_dir),
+        check_port_tools(
+            tool_version("lsof", &["-v"]).await,
+            tool_version("netstat", &["--version"]).await,
+            is_privileged(),
+        ),
+        check_docker(probe_docker().await, docker_socket_exists()),
+        check_pty(probe_pty()),
+    ];
+
+    DoctorReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        checks,
+    }
+}
+
+/// Checks that the config file parses and passes validation.
+pub fn check_config(path: &Path) -> CheckResult {
+    const NAME: &str = "Config file";
+
+    if !path.exists() {
+        return CheckResult::warn(
+            NAME,
+            format!("No config file at {}", path.display()),
+            "Run `sentinel init` to create one",
+        );
+    }
+
+    let issues = ConfigManager::validate_file(path);
+    match issues.first() {
+        None => CheckResult::pass(NAME, format!("{} is valid", path.display())),
+        Some(first) => CheckResult::fail(
+            NAME,
+            format!(
+                "{} problem(s) in {}: {}",
+                issues.len(),
+                path.display(),
+                first
+            ),
+            format!("Run `sentinel validate {}` for details", path.display()),
+        ),
+    }
+}
+
+/// Checks that files can be created in `dir`, creating it if needed.
+pub fn check_dir_writable(name: &str, dir: &Path) -> CheckResult {
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    let written = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+
+    match written {
+        Ok(()) => CheckResult::pass(name, format!("{} is writable", dir.display())),
+        Err(e) => CheckResult::fail(
+            name,
+            format!("Can't write to {}: {}", dir.display(), e),
+            format!(
+                "Make sure {} is a directory owned by your user",
+                dir.display()
+            ),
+        ),
+    }
+}
+
+/// Checks the tools port scanning shells out to.
+///
+/// # Arguments
+/// * `lsof` - Version of lsof, `None` if it isn't installed
+/// * `netstat` - Version of netstat, `None` if it isn't installed
+/// * `privileged` - Whether Sentinel runs as root
+pub fn check_port_tools(
+    lsof: Option<String>,
+    netstat: Option<String>,
+    privileged: bool,
+) -> CheckResult {
+    const NAME: &str = "Port scanning";
+
+    let describe = |tool: &str, version: &Option<String>| match version {
+        Some(version) => format!("{} {}", tool, version),
+        None => format!("{} not found", tool),
+    };
+    let found = format!(
+        "{}; {}",
+        describe("lsof", &lsof),
+        describe("netstat", &netstat)
+    );
+
+    if cfg!(windows) {
+        return match netstat {
+            Some(_) => CheckResult::pass(NAME, found),
+            None => CheckResult::fail(
+                NAME,
+                found,
+                "netstat ships with Windows; make sure C:\\Windows\\System32 is on PATH",
+            ),
+        };
+    }
+
+    if lsof.is_none() {
+        return CheckResult::fail(
+            NAME,
+            found,
+            "Install lsof (e.g. `apt install lsof` or `dnf install lsof`)",
+        );
+    }
+    if cfg!(target_os = "linux") && !privileged {
+        return CheckResult::warn(
+            NAME,
+            found,
+            "Without root, lsof only sees your own processes; ports held by other users show no owner",
+        );
+    }
+    CheckResult::pass(NAME, found)
+}
+
+/// Checks whether the Docker daemon answers.
+///
+/// # Arguments
+/// * `version` - Daemon version, `None` if it didn't answer
+/// * `socket_exists` - Whether the Docker socket file exists
+pub fn check_docker(version: Option<String>, socket_exists: bool) -> CheckResult {
+    const NAME: &str = "Docker";
+
+    match version {
+        Some(version) => CheckResult::pass(NAME, format!("Docker {} is reachable", version)),
+        None if socket_exists => CheckResult::warn(
+            NAME,
+            "The Docker socket exists but the daemon can't be reached",
+            "Check that your user may use the socket, e.g. `sudo usermod -aG docker $USER` and log in again",
+        ),
+        None => CheckResult::warn(
+            NAME,
+            "Docker is not running; container features are unavailable",
+            "Start Docker if you want to manage containers",
+        ),
+    }
+}
+
+/// Checks that a pseudo-terminal could be allocated.
+pub fn check_pty(opened: std::result::Result<(), String>) -> CheckResult {
+    const NAME: &str = "Pseudo-terminals";
+
+    match opened {
+        Ok(()) => CheckResult::pass(NAME, "A pseudo-terminal can be allocated"),
+        Err(e) => CheckResult::fail(
+            NAME,
+            format!("Failed to allocate a pseudo-terminal: {}", e),
+            "Interactive processes need PTYs; make sure /dev/ptmx and /dev/pts are available",
+        ),
+    }
+}
+
+/// Runs `program args` and returns the first line mentioning a version, or
+/// `None` if the program isn't installed.
+pub async fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = match timeout(TOOL_TIMEOUT, Command::new(program).args(args).output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) if e.kind() == ErrorKind::NotFound => return None,
+        // Installed, but it hung or failed to run
+        _ => return Some("(version unknown)".to_string()),
+    };
+
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let version = text
+        .lines()
+        .map(str::trim)
+        .find(|line| line.to_lowercase().contains("revision") || line.contains("net-tools"))
+        .or_else(|| {
+            text.lines()
+                .map(str::trim)
+                .find(|line| line.chars().any(|c| c.is_ascii_digit()))
+        })
+        .map(|line| line.trim_start_matches("revision:").trim().to_string());
+    Some(version.unwrap_or_else(|| "(version unknown)".to_string()))
+}
+
+/// Asks the Docker daemon for its version.
+pub async fn probe_docker() -> Option<String> {
+    let mut monitor = DockerMonitor::new();
+    if !monitor.refresh_availability().await {
+        return None;
+    }
+    let info = monitor.get_info().await.ok()?;
+    Some(
+        info.version
+            .unwrap_or_else(|| "(version unknown)".to_string()),
+    )
+}
+
+/// Whether the default Docker socket file exists.
+pub fn docker_socket_exists() -> bool {
+    cfg!(unix) && Path::new("/var/run/docker.sock").exists()
+}
+
+/// Opens and closes a pseudo-terminal.
+pub fn probe_pty() -> std::result::Result<(), String> {
+    use portable_pty::{native_pty_system, PtySize};
+
+    native_pty_system()
+        .openpty(PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether Sentinel runs as root.
+fn is_privileged() -> bool {
+    #[cfg(unix)]
+    {
+        unsafe { libc::geteuid() == 0 }
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sentinel.yaml");
+
+        let missing = check_config(&path);
+        assert_eq!(missing.status, CheckStatus::Warn);
+        assert!(missing.hint.unwrap().contains("sentinel init"));
+
+        fs::write(&path, "processes:\n  - name: api\n    command: node\n").unwrap();
+        assert_eq!(check_config(&path).status, CheckStatus::Pass);
+
+        fs::write(&path, "processes:\n  - name: api\n    command: \"\"\n").unwrap();
+        let invalid = check_config(&path);
+        assert_eq!(invalid.status, CheckStatus::Fail);
+        assert!(invalid.hint.unwrap().contains("sentinel validate"));
+    }
+
+    #[test]
+    fn test_check_dir_writable() {
+        let dir = TempDir::new().unwrap();
+        let state_dir = dir.path().join("sentinel");
+        assert_eq!(
+            check_dir_writable("State directory", &state_dir).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(fs::read_dir(&state_dir).unwrap().count(), 0);
+
+        // A file in the way makes the directory impossible to create
+        let blocked = dir.path().join("file");
+        fs::write(&blocked, "").unwrap();
+        let result = check_dir_writable("State directory", &blocked.join("sentinel"));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_port_tools() {
+        let missing = check_port_tools(None, Some("net-tools 2.10".to_string()), true);
+        assert_eq!(missing.status, CheckStatus::Fail);
+        assert!(missing.message.contains("lsof not found"));
+        assert!(missing.hint.unwrap().contains("Install lsof"));
+
+        let found = check_port_tools(Some("4.95.0".to_string()), None, true);
+        assert_eq!(found.status, CheckStatus::Pass);
+        assert_eq!(found.message, "lsof 4.95.0; netstat not found");
+
+        let unprivileged = check_port_tools(Some("4.95.0".to_string()), None, false);
+        let expected = if cfg!(target_os = "linux") {
+            CheckStatus::Warn
+        } else {
+            CheckStatus::Pass
+        };
+        assert_eq!(unprivileged.status, expected);
+    }
+
+    #[test]
+    fn test_check_docker() {
+        assert_eq!(
+            check_docker(Some("27.0.1".to_string()), true).status,
+            CheckStatus::Pass
+        );
+
+        let denied = check_docker(None, true);
+        assert_eq!(denied.status, CheckStatus::Warn);
+        assert!(denied.hint.unwrap().contains("usermod"));
+
+        let stopped = check_docker(None, false);
+        assert_eq!(stopped.status, CheckStatus::Warn);
+        assert!(stopped.hint.unwrap().contains("Start Docker"));
+    }
+
+    #[test]
+    fn test_check_pty() {
+        assert_eq!(check_pty(Ok(())).status, CheckStatus::Pass);
+        let failed = check_pty(Err("No such file or directory".to_string()));
+        assert_eq!(failed.status, CheckStatus::Fail);
+        assert!(failed.message.contains("No such file or directory"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_version_missing_tool() {
+        assert_eq!(
+            tool_version("sentinel-doctor-no-such-tool", &["--version"]).await,
+            None
+        );
+        assert!(tool_version("sh", &["-c", "echo 'revision: 4.95.0' >&2"])
+            .await
+            .is_some_and(|version| version == "4.95.0"));
+    }
+
+    #[test]
+    fn test_report_failures_and_json() {
+        let mut report = DoctorReport {
+            version: "0.1.0".to_string(),
+            platform: "linux x86_64".to_string(),
+            checks: vec![check_pty(Ok(())), check_docker(None, false)],
+        };
+        assert!(!report.has_failures());
+
+        report.checks.push(check_pty(Err("denied".to_string())));
+        assert!(report.has_failures());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["status"], "pass");
+        assert!(json["checks"][0].get("hint").is_none());
+        assert_eq!(json["checks"][2]["status"], "fail");
+    }
+}
@@ -0,0 +1,648 @@
+//! Disk usage of process working directories.
+//!
+//! Dev servers fill build caches like `.next/` and `target/` silently until
+//! the disk is full. This module measures a working directory without
+//! following symlinks out of it, and removes the cache directories a
+//! framework is known to regenerate.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::core::process_config::FrameworkType;
+use crate::error::{Result, SentinelError};
+
+/// Directory levels below the working directory a scan descends.
+pub const MAX_SCAN_DEPTH: usize = 32;
+
+/// Largest subdirectories listed in a report.
+pub const TOP_DIRS: usize = 10;
+
+/// How long a report is served from the cache.
+pub const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Cache directories that can be removed for a framework unless
+/// `settings.cacheDirs` lists others for it.
+pub fn default_cache_dirs(framework: &FrameworkType) -> &'static [&'static str] {
+    match framework {
+        FrameworkType::NextJs => &[".next", "node_modules/.cache"],
+        FrameworkType::Vite => &["node_modules/.vite", "node_modules/.cache"],
+        FrameworkType::Express => &["node_modules/.cache"],
+        FrameworkType::Rust => &["target/debug/incremental"],
+        FrameworkType::FastAPI | FrameworkType::Django | FrameworkType::Flask => &["__pycache__"],
+        FrameworkType::SpringBoot
+        | FrameworkType::Go
+        | FrameworkType::Rails
+        | FrameworkType::Laravel
+        | FrameworkType::Unknown => &[],
+    }
+}
+
+/// Cache directories for `framework`, from `overrides` (keyed by framework
+/// name as written in config files, e.g. `nextJs`) or the defaults.
+pub fn cache_dirs_for(
+    framework: &FrameworkType,
+    overrides: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let key = serde_json::to_value(framework)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string));
+    match key.and_then(|key| overrides.get(&key)) {
+        Some(dirs) => dirs.clone(),
+        None => default_cache_dirs(framework)
+            .iter()
+            .map(|dir| dir.to_string())
+            .collect(),
+    }
+}
+
+/// Stops a running scan when cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every scan holding this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Size of a directory inside a working directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirUsage {
+    /// Path relative to the working directory, with `/` separators.
+    pub path: String,
+    /// Apparent size of the files below it.
+    pub bytes: u64,
+}
+
+/// Disk usage of a working directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageReport {
+    /// The working directory, with symlinks resolved.
+    pub root: PathBuf,
+    /// Apparent size of every file below the root.
+    pub total_bytes: u64,
+    /// Number of files below the root.
+    pub file_count: u64,
+    /// Largest immediate subdirectories, largest first.
+    pub top_dirs: Vec<DirUsage>,
+    /// Cache directories that exist and can be cleaned.
+    pub cache_dirs: Vec<DirUsage>,
+    /// Whether directories deeper than the scan limit were left out.
+    pub truncated: bool,
+    pub measured_at: DateTime<Utc>,
+}
+
+/// Result of removing cache directories.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanReport {
+    /// Directories that were removed, with their size beforehand.
+    pub removed: Vec<DirUsage>,
+    /// Total size of the removed directories.
+    pub freed_bytes: u64,
+}
+
+/// Totals from walking one directory.
+#[derive(Debug, Default)]
+struct Walk {
+    bytes: u64,
+    files: u64,
+    truncated: bool,
+}
+
+/// Sums the files below `dir`, skipping symlinks so the walk stays inside it.
+fn walk(dir: &Path, max_depth: usize, cancel: &CancellationToken) -> Result<Walk> {
+    let mut totals = Walk::default();
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+
+    while let Some((path, depth)) = pending.pop() {
+        if cancel.is_cancelled() {
+            return Err(SentinelError::Other(format!(
+                "Disk usage scan of {} was cancelled",
+                dir.display()
+            )));
+        }
+        // Directories can vanish or be unreadable mid-scan; count what's left
+        let Ok(entries) = fs::read_dir(&path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if depth < max_depth {
+                    pending.push((entry.path(), depth + 1));
+                } else {
+                    totals.truncated = true;
+                }
+            } else if file_type.is_file() {
+                if let Ok(metadata) = entry.metadata() {
+                    totals.bytes += metadata.len();
+                    totals.files += 1;
+                }
+            }
+        }
+    }
+
+    Ok(totals)
+}
+
+/// Relative path `rel` with separators normalised, or `None` if it is empty,
+/// absolute or climbs out of its base.
+fn normalize_relative(rel: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in Path::new(rel).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+fn display_relative(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Canonical form of the working directory `root`.
+fn resolve_root(root: &Path) -> Result<PathBuf> {
+    let resolved = root
+        .canonicalize()
+        .map_err(|source| SentinelError::FileIoError {
+            path: root.to_path_buf(),
+            source,
+        })?;
+    if !resolved.is_dir() {
+        return Err(SentinelError::InvalidInput {
+            message: format!("{} is not a directory", root.display()),
+        });
+    }
+    Ok(resolved)
+}
+
+/// `root.join(rel)` if it is a real directory inside `root`; `None` if it
+/// doesn't exist.
+///
+/// A path that reaches its target through a symlink is rejected, so a link
+/// can't point a clean at something outside the working directory.
+fn resolve_inside(root: &Path, rel: &Path) -> Result<Option<PathBuf>> {
+    let path = root.join(rel);
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(SentinelError::FileIoError { path, source }),
+    };
+    let resolved = path
+        .canonicalize()
+        .map_err(|source| SentinelError::FileIoError {
+            path: path.clone(),
+            source,
+        })?;
+    if !resolved.starts_with(root) {
+        return Err(SentinelError::PathNotAllowed {
+            path: resolved,
+            allowed: vec![root.to_path_buf()],
+        });
+    }
+    if metadata.file_type().is_symlink() || resolved != path {
+        return Err(SentinelError::InvalidInput {
+            message: format!("{} goes through a symlink", display_relative(rel)),
+        });
+    }
+    if !metadata.is_dir() {
+        return Err(SentinelError::InvalidInput {
+            message: format!("{} is not a directory", display_relative(rel)),
+        });
+    }
+    Ok(Some(path))
+}
+
+/// Measures `root`, listing its `top_n` largest subdirectories and the size
+/// of each of `cache_dirs` that exists.
+pub fn measure(
+    root: &Path,
+    cache_dirs: &[String],
+    top_n: usize,
+    cancel: &CancellationToken,
+) -> Result<DiskUsageReport> {
+    let root = resolve_root(root)?;
+    let mut report = DiskUsageReport {
+        root: root.clone(),
+        total_bytes: 0,
+        file_count: 0,
+        top_dirs: Vec::new(),
+        cache_dirs: Vec::new(),
+        truncated: false,
+        measured_at: Utc::now(),
+    };
+
+    let entries = fs::read_dir(&root).map_err(|source| SentinelError::FileIoError {
+        path: root.clone(),
+        source,
+    })?;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            let totals = walk(&entry.path(), MAX_SCAN_DEPTH - 1, cancel)?;
+            report.total_bytes += totals.bytes;
+            report.file_count += totals.files;
+            report.truncated |= totals.truncated;
+            report.top_dirs.push(DirUsage {
+                path: entry.file_name().to_string_lossy().into_owned(),
+                bytes: totals.bytes,
+            });
+        } else if file_type.is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                report.total_bytes += metadata.len();
+                report.file_count += 1;
+            }
+        }
+    }
+    report
+        .top_dirs
+        .sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    report.top_dirs.truncate(top_n);
+
+    for dir in cache_dirs {
+        let Some(rel) = normalize_relative(dir) else {
+            continue;
+        };
+        // Cache entries that are missing or unsafe to clean aren't offered
+        let Ok(Some(path)) = resolve_inside(&root, &rel) else {
+            continue;
+        };
+        let totals = walk(&path, MAX_SCAN_DEPTH, cancel)?;
+        report.cache_dirs.push(DirUsage {
+            path: display_relative(&rel),
+            bytes: totals.bytes,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Runs [`measure`] on the blocking thread pool.
+pub async fn measure_async(
+    root: PathBuf,
+    cache_dirs: Vec<String>,
+    cancel: CancellationToken,
+) -> Result<DiskUsageReport> {
+    tokio::task::spawn_blocking(move || measure(&root, &cache_dirs, TOP_DIRS, &cancel))
+        .await
+        .map_err(|e| SentinelError::Other(format!("Disk usage scan failed: {}", e)))?
+}
+
+/// Removes the `requested` directories of `root`.
+///
+/// Every path must be listed in `allowed` and be a real directory inside
+/// `root`; nothing is removed unless all of them pass. Paths that don't
+/// exist are skipped.
+pub fn clean(root: &Path, requested: &[String], allowed: &[String]) -> Result<CleanReport> {
+    let root = resolve_root(root)?;
+    let allowed: Vec<PathBuf> = allowed
+        .iter()
+        .filter_map(|dir| normalize_relative(dir))
+        .collect();
+
+    let mut targets = Vec::new();
+    for dir in requested {
+        let rel = normalize_relative(dir)
+            .filter(|rel| allowed.contains(rel))
+            .ok_or_else(|| SentinelError::InvalidInput {
+                message: format!("{} is not a cache directory that can be cleaned", dir),
+            })?;
+        if let Some(path) = resolve_inside(&root, &rel)? {
+            if !targets.iter().any(|(_, existing)| existing == &path) {
+                targets.push((rel, path));
+            }
+        }
+    }
+
+    let cancel = CancellationToken::new();
+    let mut report = CleanReport {
+        removed: Vec::new(),
+        freed_bytes: 0,
+    };
+    for (rel, path) in targets {
+        // An earlier entry may have contained this one
+        if !path.exists() {
+            continue;
+        }
+        let bytes = walk(&path, MAX_SCAN_DEPTH, &cancel)?.bytes;
+        fs::remove_dir_all(&path).map_err(|source| SentinelError::FileIoError {
+            path: path.clone(),
+            source,
+        })?;
+        report.freed_bytes += bytes;
+        report.removed.push(DirUsage {
+            path: display_relative(&rel),
+            bytes,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Recent reports and running scans, keyed by working directory.
+#[derive(Debug, Default)]
+pub struct DiskUsageCache {
+    reports: HashMap<PathBuf, (Instant, DiskUsageReport)>,
+    scans: HashMap<PathBuf, CancellationToken>,
+}
+
+impl DiskUsageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The report for `root` if it was measured within [`CACHE_TTL`].
+    pub fn get(&self, root: &Path) -> Option<DiskUsageReport> {
+        self.reports
+            .get(root)
+            .filter(|(at, _)| at.elapsed() < CACHE_TTL)
+            .map(|(_, report)| report.clone())
+    }
+
+    pub fn insert(&mut self, root: PathBuf, report: DiskUsageReport) {
+        self.reports.insert(root, (Instant::now(), report));
+    }
+
+    /// Drops the report for `root`, e.g. after cleaning it.
+    pub fn invalidate(&mut self, root: &Path) {
+        self.reports.remove(root);
+    }
+
+    /// Registers a scan of `root`, cancelling any scan already running there.
+    pub fn begin_scan(&mut self, root: PathBuf) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Some(previous) = self.scans.insert(root, token.clone()) {
+            previous.cancel();
+        }
+        token
+    }
+
+    /// Forgets the scan of `root` holding `token`.
+    pub fn end_scan(&mut self, root: &Path, token: &CancellationToken) {
+        if self
+            .scans
+            .get(root)
+            .is_some_and(|current| Arc::ptr_eq(&current.0, &token.0))
+        {
+            self.scans.remove(root);
+        }
+    }
+
+    /// Cancels the scan of `root`; returns whether one was running.
+    pub fn cancel_scan(&mut self, root: &Path) -> bool {
+        match self.scans.remove(root) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, bytes: usize) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "package.json", 10);
+        write(dir.path(), ".next/cache/a.bin", 400);
+        write(dir.path(), ".next/server/b.js", 100);
+        write(dir.path(), "node_modules/react/index.js", 300);
+        write(dir.path(), "node_modules/.cache/babel/c.json", 50);
+        write(dir.path(), "src/page.tsx", 20);
+        write(dir.path(), "public/logo.svg", 5);
+        dir
+    }
+
+    fn strings(dirs: &[&str]) -> Vec<String> {
+        dirs.iter().map(|dir| dir.to_string()).collect()
+    }
+
+    #[test]
+    fn test_measure_totals_and_top_dirs() {
+        let dir = project();
+        let cache_dirs = strings(default_cache_dirs(&FrameworkType::NextJs));
+        let report = measure(dir.path(), &cache_dirs, 2, &CancellationToken::new()).unwrap();
+
+        assert_eq!(report.root, dir.path().canonicalize().unwrap());
+        assert_eq!(report.total_bytes, 885);
+        assert_eq!(report.file_count, 7);
+        assert!(!report.truncated);
+        assert_eq!(
+            report.top_dirs,
+            vec![
+                DirUsage {
+                    path: ".next".to_string(),
+                    bytes: 500
+                },
+                DirUsage {
+                    path: "node_modules".to_string(),
+                    bytes: 350
+                },
+            ]
+        );
+        assert_eq!(
+            report.cache_dirs,
+            vec![
+                DirUsage {
+                    path: ".next".to_string(),
+                    bytes: 500
+                },
+                DirUsage {
+                    path: "node_modules/.cache".to_string(),
+                    bytes: 50
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_measure_respects_cancellation() {
+        let dir = project();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        assert!(measure(dir.path(), &[], TOP_DIRS, &cancel).is_err());
+    }
+
+    #[test]
+    fn test_measure_marks_truncated_trees() {
+        let dir = TempDir::new().unwrap();
+        let deep = (0..=MAX_SCAN_DEPTH)
+            .map(|i| format!("d{}", i))
+            .collect::<Vec<_>>()
+            .join("/");
+        write(dir.path(), &format!("{}/deep.bin", deep), 7);
+        write(dir.path(), "d0/shallow.bin", 3);
+
+        let report = measure(dir.path(), &[], TOP_DIRS, &CancellationToken::new()).unwrap();
+        assert!(report.truncated);
+        assert_eq!(report.total_bytes, 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_measure_does_not_follow_symlinks() {
+        let outside = TempDir::new().unwrap();
+        write(outside.path(), "huge.bin", 10_000);
+        let dir = project();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("linked")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("node_modules/.vite")).unwrap();
+
+        let cache_dirs = strings(default_cache_dirs(&FrameworkType::Vite));
+        let report = measure(dir.path(), &cache_dirs, TOP_DIRS, &CancellationToken::new()).unwrap();
+        assert_eq!(report.total_bytes, 885);
+        assert!(report.top_dirs.iter().all(|usage| usage.path != "linked"));
+        // The symlinked cache isn't offered for cleaning
+        assert_eq!(
+            report
+                .cache_dirs
+                .iter()
+                .map(|usage| usage.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["node_modules/.cache"]
+        );
+    }
+
+    #[test]
+    fn test_clean_removes_whitelisted_dirs() {
+        let dir = project();
+        let allowed = strings(default_cache_dirs(&FrameworkType::NextJs));
+        let report = clean(
+            dir.path(),
+            &strings(&[".next/", "node_modules/.cache", "node_modules/.vite"]),
+            &allowed,
+        );
+        // node_modules/.vite isn't a Next.js cache
+        assert!(matches!(report, Err(SentinelError::InvalidInput { .. })));
+        assert!(dir.path().join(".next").exists());
+
+        let report = clean(
+            dir.path(),
+            &strings(&[".next/", "node_modules/.cache"]),
+            &allowed,
+        )
+        .unwrap();
+        assert_eq!(report.freed_bytes, 550);
+        assert_eq!(report.removed.len(), 2);
+        assert!(!dir.path().join(".next").exists());
+        assert!(!dir.path().join("node_modules/.cache").exists());
+        assert!(dir.path().join("node_modules/react/index.js").exists());
+
+        // Already clean
+        let report = clean(dir.path(), &strings(&[".next"]), &allowed).unwrap();
+        assert!(report.removed.is_empty());
+    }
+
+    #[test]
+    fn test_clean_rejects_paths_leaving_the_root() {
+        let dir = project();
+        let allowed = strings(&["../elsewhere", "/tmp", ".next"]);
+        for path in ["../elsewhere", "/tmp", "", "src"] {
+            assert!(
+                matches!(
+                    clean(dir.path(), &strings(&[path]), &allowed),
+                    Err(SentinelError::InvalidInput { .. })
+                ),
+                "{} should be rejected",
+                path
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_rejects_symlinked_dirs() {
+        let outside = TempDir::new().unwrap();
+        write(outside.path(), "keep.bin", 10);
+        let dir = project();
+        fs::remove_dir_all(dir.path().join("node_modules/.cache")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("node_modules/.cache")).unwrap();
+
+        let allowed = strings(default_cache_dirs(&FrameworkType::NextJs));
+        let result = clean(dir.path(), &strings(&["node_modules/.cache"]), &allowed);
+        assert!(matches!(result, Err(SentinelError::PathNotAllowed { .. })));
+        assert!(outside.path().join("keep.bin").exists());
+
+        // A link that stays inside the root is refused too
+        fs::remove_file(dir.path().join("node_modules/.cache")).unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("src"),
+            dir.path().join("node_modules/.cache"),
+        )
+        .unwrap();
+        let result = clean(dir.path(), &strings(&["node_modules/.cache"]), &allowed);
+        assert!(matches!(result, Err(SentinelError::InvalidInput { .. })));
+        assert!(dir.path().join("src/page.tsx").exists());
+    }
+
+    #[test]
+    fn test_cache_dirs_for_uses_overrides() {
+        let overrides = HashMap::from([("nextJs".to_string(), strings(&[".turbo"]))]);
+        assert_eq!(
+            cache_dirs_for(&FrameworkType::NextJs, &overrides),
+            strings(&[".turbo"])
+        );
+        assert_eq!(
+            cache_dirs_for(&FrameworkType::Rust, &overrides),
+            strings(&["target/debug/incremental"])
+        );
+    }
+
+    #[test]
+    fn test_cache_serves_fresh_reports_and_cancels_scans() {
+        let dir = project();
+        let report = measure(dir.path(), &[], TOP_DIRS, &CancellationToken::new()).unwrap();
+        let mut cache = DiskUsageCache::new();
+        cache.insert(report.root.clone(), report.clone());
+        assert_eq!(cache.get(&report.root), Some(report.clone()));
+        cache.invalidate(&report.root);
+        assert_eq!(cache.get(&report.root), None);
+
+        let first = cache.begin_scan(report.root.clone());
+        let second = cache.begin_scan(report.root.clone());
+        assert!(first.is_cancelled());
+        cache.end_scan(&report.root, &first);
+        assert!(cache.cancel_scan(&report.root));
+        assert!(second.is_cancelled());
+        assert!(!cache.cancel_scan(&report.root));
+    }
+}
@@ -0,0 +1,231 @@
+//! `.env` files: parsing, and what changed between two versions.
+//!
+//! Framework detection reads a project's `.env` for its port, processes
+//! load their `envFile` into their environment, and the env file watcher
+//! compares versions of it, all with the parsing here.
+
+use crate::core::secrets;
+use crate::models::ProcessConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Parses `KEY=value` lines, skipping blank lines and `#` comments.
+///
+/// Surrounding whitespace is trimmed, and so is one pair of matching quotes
+/// around a value. A later line for the same key wins.
+pub fn parse(content: &str) -> HashMap<String, String> {
+    let mut env_vars = HashMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        // Skip comments and empty lines
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Parse KEY=VALUE format
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim().to_string();
+            let mut value = value.trim().to_string();
+
+            // Remove quotes if present
+            if value.len() >= 2
+                && ((value.starts_with('"') && value.ends_with('"'))
+                    || (value.starts_with('\'') && value.ends_with('\'')))
+            {
+                value = value[1..value.len() - 1].to_string();
+            }
+
+            env_vars.insert(key, value);
+        }
+    }
+
+    env_vars
+}
+
+/// Reads and parses the env file at `path`; `None` if it can't be read.
+pub fn read(path: &Path) -> Option<HashMap<String, String>> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|content| parse(&content))
+}
+
+/// The env file `config` loads, relative paths taken from its `cwd`.
+pub fn configured_path(config: &ProcessConfig) -> Option<PathBuf> {
+    let env_file = config.env_file.as_ref()?;
+    Some(match &config.cwd {
+        Some(cwd) if env_file.is_relative() => cwd.join(env_file),
+        _ => env_file.clone(),
+    })
+}
+
+/// The variables of `config`'s env file with its `env` on top.
+///
+/// An env file that can't be read adds nothing; validation warns about it.
+pub fn process_env(config: &ProcessConfig) -> HashMap<String, String> {
+    let mut env = configured_path(config)
+        .and_then(|path| read(&path))
+        .unwrap_or_default();
+    env.extend(config.env.clone());
+    env
+}
+
+/// Keys that differ between two versions of an env file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvDiff {
+    /// New keys, with their values.
+    pub added: BTreeMap<String, String>,
+    /// Keys no longer set.
+    pub removed: Vec<String>,
+    /// Keys with a new value, with that value.
+    pub changed: BTreeMap<String, String>,
+}
+
+impl EnvDiff {
+    /// Compares the `before` and `after` versions of a file.
+    pub fn between(before: &HashMap<String, String>, after: &HashMap<String, String>) -> Self {
+        let mut diff = Self::default();
+        for (key, value) in after {
+            match before.get(key) {
+                None => {
+                    diff.added.insert(key.clone(), value.clone());
+                }
+                Some(old) if old != value => {
+                    diff.changed.insert(key.clone(), value.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = before
+            .keys()
+            .filter(|key| !after.contains_key(*key))
+            .cloned()
+            .collect();
+        diff.removed.sort();
+        diff
+    }
+
+    /// Whether nothing differs, as after editing only comments.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Copy with the values of secret keys replaced by [`secrets::MASK`].
+    pub fn masked(&self, patterns: &[String]) -> Self {
+        let mask = |values: &BTreeMap<String, String>| {
+            values
+                .iter()
+                .map(|(key, value)| {
+                    let value = if secrets::is_secret(key, patterns) {
+                        secrets::MASK.to_string()
+                    } else {
+                        value.clone()
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        };
+        Self {
+            added: mask(&self.added),
+            removed: self.removed.clone(),
+            changed: mask(&self.changed),
+        }
+    }
+
+    /// The changed keys in one line, e.g. `+PORT ~API_URL -DEBUG`.
+    pub fn summary(&self) -> String {
+        let added = self.added.keys().map(|key| format!("+{}", key));
+        let changed = self.changed.keys().map(|key| format!("~{}", key));
+        let removed = self.removed.iter().map(|key| format!("-{}", key));
+        added
+            .chain(changed)
+            .chain(removed)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = parse(
+            "# comment\n\nPORT=3000\nAPI_URL = \"http://localhost:3000\"\nNAME='app'\nEMPTY=\nQUOTE=\"\nnot a pair\nPORT=4000\n",
+        );
+        assert_eq!(
+            parsed,
+            env(&[
+                ("PORT", "4000"),
+                ("API_URL", "http://localhost:3000"),
+                ("NAME", "app"),
+                ("EMPTY", ""),
+                ("QUOTE", "\""),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed_keys() {
+        let before = env(&[("PORT", "3000"), ("DEBUG", "1"), ("MODE", "dev")]);
+        let after = env(&[("PORT", "4000"), ("MODE", "dev"), ("API_KEY", "abc")]);
+
+        let diff = EnvDiff::between(&before, &after);
+        assert_eq!(
+            diff.added,
+            BTreeMap::from([("API_KEY".into(), "abc".into())])
+        );
+        assert_eq!(diff.removed, vec!["DEBUG"]);
+        assert_eq!(
+            diff.changed,
+            BTreeMap::from([("PORT".into(), "4000".into())])
+        );
+        assert_eq!(diff.summary(), "+API_KEY ~PORT -DEBUG");
+        assert!(EnvDiff::between(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_diff_masks_secret_values() {
+        let before = env(&[("DB_PASSWORD", "old")]);
+        let after = env(&[("DB_PASSWORD", "new"), ("API_TOKEN", "t"), ("PORT", "1")]);
+
+        let masked = EnvDiff::between(&before, &after).masked(&["PASSWORD".into(), "TOKEN".into()]);
+        assert_eq!(masked.changed["DB_PASSWORD"], secrets::MASK);
+        assert_eq!(masked.added["API_TOKEN"], secrets::MASK);
+        assert_eq!(masked.added["PORT"], "1");
+    }
+
+    #[test]
+    fn test_process_env_puts_env_over_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".env.local"), "PORT=3000\nMODE=dev\n").unwrap();
+        let mut config: ProcessConfig = serde_yaml::from_str(
+            "name: web\ncommand: npm start\nenvFile: .env.local\nenv:\n  MODE: prod\n",
+        )
+        .unwrap();
+        config.cwd = Some(dir.path().to_path_buf());
+
+        assert_eq!(
+            configured_path(&config),
+            Some(dir.path().join(".env.local"))
+        );
+        assert_eq!(
+            process_env(&config),
+            env(&[("PORT", "3000"), ("MODE", "prod")])
+        );
+
+        config.env_file = Some("missing.env".into());
+        assert_eq!(process_env(&config), env(&[("MODE", "prod")]));
+    }
+}
@@ -0,0 +1,316 @@
+//! Watching the env files of running processes.
+//!
+//! A process reads its environment once, at start, so editing its env file
+//! does nothing until it restarts. Each running process's `envFile`, or the
+//! `.env` in the directory it runs in, is polled like the config file (see
+//! [`config_watcher`](crate::core::config_watcher)). When what it sets
+//! changes, the app is told, and processes with `restartOnEnvChange: auto`
+//! are restarted.
+
+use crate::core::config_watcher::{FileWatch, POLL_INTERVAL};
+use crate::core::env_file::{self, EnvDiff};
+use crate::core::ProcessManager;
+use crate::models::{EnvChangeAction, ProcessConfig, ProcessInfo, RestartReason};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted when the env file of a running process has changed
+pub const ENV_FILE_CHANGED_EVENT: &str = "env-file-changed";
+
+/// Payload of the env file change event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFileChangedEvent {
+    /// The process whose env file changed
+    pub process: String,
+    /// The env file
+    pub path: String,
+    /// What changed, with the values of secret keys masked
+    #[serde(flatten)]
+    pub diff: EnvDiff,
+    /// The changed keys in one line
+    pub summary: String,
+    /// What the process is set to do about it
+    pub action: EnvChangeAction,
+    /// Whether the process was restarted
+    pub restarted: bool,
+    /// Why restarting it failed, when it did
+    pub error: Option<String>,
+}
+
+/// The env file of a running process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedEnvFile {
+    pub name: String,
+    pub pid: u32,
+    pub path: PathBuf,
+    pub action: EnvChangeAction,
+}
+
+impl WatchedEnvFile {
+    /// The env file to watch for a process: its `envFile`, or the `.env` in
+    /// the directory it runs in if there is one
+    ///
+    /// `None` if it isn't running or ignores env file changes.
+    pub fn of(config: &ProcessConfig, info: &ProcessInfo) -> Option<Self> {
+        if config.restart_on_env_change == EnvChangeAction::Ignore || !info.is_running() {
+            return None;
+        }
+        let path = env_file::configured_path(config).or_else(|| {
+            let dotenv = Path::new(info.resolved_cwd.as_deref()?).join(".env");
+            dotenv.is_file().then_some(dotenv)
+        })?;
+        Some(Self {
+            name: config.name.clone(),
+            pid: info.pid?,
+            path,
+            action: config.restart_on_env_change,
+        })
+    }
+}
+
+/// A file being watched, and what it set when last read
+struct Watched {
+    pid: u32,
+    watch: FileWatch,
+    env: HashMap<String, String>,
+}
+
+/// Notices changes to the env files of running processes
+#[derive(Default)]
+pub struct EnvFileWatcher {
+    watched: HashMap<String, Watched>,
+}
+
+impl EnvFileWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the env files of the processes `manager` runs, restarting the
+    /// ones set to restart, and returns what changed
+    ///
+    /// A file is compared with what it set when the process started, or
+    /// rather when it was first checked after that.
+    pub async fn check(
+        &mut self,
+        manager: &ProcessManager,
+        now: Instant,
+    ) -> Vec<EnvFileChangedEvent> {
+        let files = manager.env_files().await;
+        self.watched
+            .retain(|name, _| files.iter().any(|file| &file.name == name));
+
+        let mut changed = Vec::new();
+        for file in files {
+            let watched = match self.watched.get_mut(&file.name) {
+                Some(watched) if watched.pid == file.pid && watched.watch.path() == file.path => {
+                    watched
+                }
+                // Started, restarted, or its env file moved
+                _ => {
+                    self.watched.insert(
+                        file.name.clone(),
+                        Watched {
+                            pid: file.pid,
+                            env: env_file::read(&file.path).unwrap_or_default(),
+                            watch: FileWatch::new(file.path.clone()),
+                        },
+                    );
+                    continue;
+                }
+            };
+            if !watched.watch.poll(now) {
+                continue;
+            }
+
+            let env = env_file::read(&file.path).unwrap_or_default();
+            let diff = EnvDiff::between(&watched.env, &env);
+            watched.env = env;
+            // Only comments or formatting changed
+            if !diff.is_empty() {
+                changed.push((file, diff));
+            }
+        }
+
+        let patterns = manager.settings().secret_patterns;
+        let mut events = Vec::with_capacity(changed.len());
+        for (file, diff) in changed {
+            let path = file.path.display().to_string();
+            let mut event = EnvFileChangedEvent {
+                process: file.name.clone(),
+                path: path.clone(),
+                summary: diff.summary(),
+                diff: diff.masked(&patterns),
+                action: file.action,
+                restarted: false,
+                error: None,
+            };
+            if file.action == EnvChangeAction::Auto {
+                match manager
+                    .restart_with(&file.name, RestartReason::FileChanged { path })
+                    .await
+                {
+                    Ok(_) => event.restarted = true,
+                    Err(e) => event.error = Some(e.to_string()),
+                }
+            }
+            events.push(event);
+        }
+        events
+    }
+}
+
+/// Polls the env files of the processes `manager` runs until the app exits,
+/// emitting [`ENV_FILE_CHANGED_EVENT`] for each change
+pub async fn watch(manager: Arc<ProcessManager>, app: AppHandle) {
+    let mut watcher = EnvFileWatcher::new();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        for event in watcher.check(&manager, Instant::now()).await {
+            tracing::info!(
+                "Env file of process '{}' changed: {}",
+                event.process,
+                event.summary
+            );
+            if let Err(e) = app.emit(ENV_FILE_CHANGED_EVENT, &event) {
+                tracing::warn!("Failed to emit env file change: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::core::config_watcher::DEBOUNCE;
+    use std::fs;
+
+    fn config(dir: &Path, action: &str) -> ProcessConfig {
+        let mut config: ProcessConfig = serde_yaml::from_str(&format!(
+            "name: api\ncommand: sleep 30\nautoRestart: false\nrestartDelay: 50\n\
+             envFile: .env.local\nrestartOnEnvChange: {}\n",
+            action
+        ))
+        .unwrap();
+        config.cwd = Some(dir.to_path_buf());
+        config
+    }
+
+    /// Checks the way the background task does until something changed
+    async fn settle(
+        watcher: &mut EnvFileWatcher,
+        manager: &ProcessManager,
+        start: Instant,
+    ) -> Vec<EnvFileChangedEvent> {
+        let mut now = start;
+        while now < start + DEBOUNCE * 2 {
+            let events = watcher.check(manager, now).await;
+            if !events.is_empty() {
+                return events;
+            }
+            now += POLL_INTERVAL;
+        }
+        Vec::new()
+    }
+
+    #[tokio::test]
+    async fn test_auto_restarts_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env.local");
+        fs::write(&path, "PORT=3000\nDB_PASSWORD=old\n").unwrap();
+        let manager = ProcessManager::new();
+        let pid = manager.start(config(dir.path(), "auto")).await.unwrap().pid;
+
+        let mut watcher = EnvFileWatcher::new();
+        assert!(watcher.check(&manager, Instant::now()).await.is_empty());
+
+        // Comments alone don't count
+        fs::write(&path, "# ports\nPORT=3000\nDB_PASSWORD=old\n").unwrap();
+        assert!(settle(&mut watcher, &manager, Instant::now())
+            .await
+            .is_empty());
+
+        fs::write(&path, "PORT=4000\nDB_PASSWORD=new\nDEBUG=1\n").unwrap();
+        let events = settle(&mut watcher, &manager, Instant::now()).await;
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.process, "api");
+        assert_eq!(event.summary, "+DEBUG ~DB_PASSWORD ~PORT");
+        assert_eq!(
+            event.diff.changed["DB_PASSWORD"],
+            crate::core::secrets::MASK
+        );
+        assert_eq!(event.diff.changed["PORT"], "4000");
+        assert!(event.restarted, "{:?}", event.error);
+
+        let info = manager.get("api").await.unwrap();
+        assert!(info.is_running());
+        assert_ne!(info.pid, pid);
+
+        // The restarted process is the new baseline
+        assert!(watcher.check(&manager, Instant::now()).await.is_empty());
+        assert!(settle(&mut watcher, &manager, Instant::now())
+            .await
+            .is_empty());
+
+        manager.stop_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prompt_only_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env.local");
+        fs::write(&path, "PORT=3000\n").unwrap();
+        let manager = ProcessManager::new();
+        let pid = manager
+            .start(config(dir.path(), "prompt"))
+            .await
+            .unwrap()
+            .pid;
+
+        let mut watcher = EnvFileWatcher::new();
+        watcher.check(&manager, Instant::now()).await;
+        fs::remove_file(&path).unwrap();
+        let events = settle(&mut watcher, &manager, Instant::now()).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].diff.removed, vec!["PORT"]);
+        assert!(!events[0].restarted);
+        assert_eq!(manager.get("api").await.unwrap().pid, pid);
+
+        manager.stop_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_which_files_are_watched() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = ProcessManager::new();
+
+        let mut ignored = config(dir.path(), "ignore");
+        ignored.name = "ignored".to_string();
+        manager.start(ignored).await.unwrap();
+
+        // Without an envFile, the project's .env if it has one
+        let mut plain = config(dir.path(), "prompt");
+        plain.name = "plain".to_string();
+        plain.env_file = None;
+        manager.start(plain).await.unwrap();
+        assert!(manager.env_files().await.is_empty());
+
+        fs::write(dir.path().join(".env"), "PORT=3000\n").unwrap();
+        let files = manager.env_files().await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "plain");
+        assert_eq!(
+            files[0].path.canonicalize().unwrap(),
+            dir.path().join(".env").canonicalize().unwrap()
+        );
+
+        manager.stop_all().await.unwrap();
+        assert!(manager.env_files().await.is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+//! Live events for the API's `/events` stream.
+//!
+//! Producers publish to an [`EventBus`] whether or not anyone listens; with
+//! no subscribers a publish costs a counter check. Each subscriber reads at
+//! its own pace from a shared buffer of [`EVENT_BUS_CAPACITY`] events, so a
+//! slow one falls behind and loses the oldest events instead of holding up
+//! the process readers that publish them.
+
+use crate::core::log_buffer::LogLine;
+use crate::models::{Alert, ProcessEvent, SystemStats};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Events a subscriber can fall behind by before it loses the oldest.
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Something that happened, as sent to `/events` clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveEvent {
+    /// A managed process changed state.
+    Process(ProcessEvent),
+    /// A managed process wrote a line.
+    Log { process: String, line: LogLine },
+    /// A system stats sample.
+    SystemStats(SystemStats),
+    /// An alert fired.
+    AlertFired(Alert),
+    /// A firing alert resolved.
+    AlertResolved(Alert),
+}
+
+/// Fan-out of [`LiveEvent`]s to any number of subscribers.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl EventBus {
+    /// Creates a bus with no subscribers.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Sends `event` to the current subscribers, if any.
+    pub fn publish(&self, event: LiveEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Whether anyone is subscribed, to skip building events no one reads.
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// Receives the events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+
+    /// A publisher of the log lines of `process`.
+    pub fn logs(&self, process: &str) -> LogPublisher {
+        LogPublisher {
+            bus: self.clone(),
+            process: Arc::from(process),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Publishes the lines one process writes.
+#[derive(Debug, Clone)]
+pub struct LogPublisher {
+    bus: EventBus,
+    process: Arc<str>,
+}
+
+impl LogPublisher {
+    /// Name of the process the lines are published under.
+    pub fn process(&self) -> &str {
+        &self.process
+    }
+
+    /// Publishes the lines `lines` returns; it isn't called without
+    /// subscribers.
+    pub fn publish(&self, lines: impl FnOnce() -> Vec<LogLine>) {
+        if !self.bus.has_subscribers() {
+            return;
+        }
+        for line in lines() {
+            self.bus.publish(LiveEvent::Log {
+                process: self.process.to_string(),
+                line,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log_buffer::LogStream;
+    use chrono::Utc;
+    use tokio::sync::broadcast::error::RecvError;
+
+    fn line(text: &str) -> LogLine {
+        LogLine::new(Utc::now(), LogStream::Stdout, text.to_string())
+    }
+
+    #[test]
+    fn test_log_lines_are_built_only_for_subscribers() {
+        let bus = EventBus::new();
+        let logs = bus.logs("api");
+        logs.publish(|| panic!("built without subscribers"));
+
+        let mut events = bus.subscribe();
+        logs.publish(|| vec![line("one"), line("two")]);
+        for text in ["one", "two"] {
+            match events.try_recv().unwrap() {
+                LiveEvent::Log { process, line } => {
+                    assert_eq!(process, "api");
+                    assert_eq!(line.line, text);
+                }
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_loses_oldest_events() {
+        let bus = EventBus::new();
+        let mut slow = bus.subscribe();
+        let logs = bus.logs("chatty");
+        for i in 0..EVENT_BUS_CAPACITY + 10 {
+            logs.publish(|| vec![line(&i.to_string())]);
+        }
+
+        assert!(matches!(slow.recv().await, Err(RecvError::Lagged(10))));
+        match slow.recv().await.unwrap() {
+            LiveEvent::Log { line, .. } => assert_eq!(line.line, "10"),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
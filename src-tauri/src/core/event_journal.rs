@@ -0,0 +1,447 @@
+//! Append-only JSONL journal of everything Sentinel did.
+//!
+//! Callers hand events to an [`EventRecorder`], which queues them for a
+//! background writer thread so recording never blocks or fails an operation.
+//! When the queue is full or the journal can't be written (a full disk, say)
+//! events are dropped with a warning. The journal rotates by size, keeping a
+//! few older files next to it (`events.jsonl.1` is the newest of those).
+
+use crate::error::{Result, SentinelError};
+use crate::models::{EventFilter, JournalEvent};
+use chrono::{DateTime, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Events waiting to be written; more are dropped.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// Size at which the journal is rotated.
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated journal files kept.
+const DEFAULT_MAX_FILES: usize = 3;
+
+/// Location and rotation policy of the event journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventJournal {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl EventJournal {
+    /// Creates a journal at `path` with the default rotation policy.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+        }
+    }
+
+    /// Rotates once the journal would grow past `max_bytes`, keeping
+    /// `max_files` rotated files.
+    pub fn with_rotation(mut self, max_bytes: u64, max_files: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self.max_files = max_files;
+        self
+    }
+
+    /// Gets the default journal path.
+    ///
+    /// Returns: `~/.config/sentinel/events.jsonl`
+    pub fn default_path() -> PathBuf {
+        if let Some(config_dir) = dirs::config_dir() {
+            config_dir.join("sentinel").join("events.jsonl")
+        } else {
+            PathBuf::from("events.jsonl")
+        }
+    }
+
+    /// Path of the live journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of the `index`th rotated file, 1 being the newest.
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        PathBuf::from(path)
+    }
+
+    /// Reads events matching `filter` that happened at or after `since`,
+    /// oldest first.
+    ///
+    /// With `limit`, only the most recent `limit` matches are returned.
+    /// Lines that can't be parsed, such as one cut short by a full disk, are
+    /// skipped.
+    ///
+    /// # Errors
+    /// Returns `FileIoError` if an existing journal file can't be read.
+    pub fn query(
+        &self,
+        filter: &EventFilter,
+        since: Option<DateTime<Utc>>,
+        limit: Option<usize>,
+    ) -> Result<Vec<JournalEvent>> {
+        let mut events = Vec::new();
+        let files = (1..=self.max_files)
+            .rev()
+            .map(|index| self.rotated_path(index))
+            .chain([self.path.clone()]);
+
+        for path in files {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(source) => return Err(SentinelError::FileIoError { path, source }),
+            };
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|source| SentinelError::FileIoError {
+                    path: path.clone(),
+                    source,
+                })?;
+                let Ok(event) = serde_json::from_str::<JournalEvent>(&line) else {
+                    continue;
+                };
+                if since.is_none_or(|since| event.timestamp >= since) && filter.matches(&event) {
+                    events.push(event);
+                }
+            }
+        }
+
+        if let Some(limit) = limit {
+            let excess = events.len().saturating_sub(limit);
+            events.drain(..excess);
+        }
+        Ok(events)
+    }
+}
+
+/// Message to the writer thread.
+enum Message {
+    Event(JournalEvent),
+    /// Acknowledged once everything queued before it is written.
+    Flush(mpsc::Sender<()>),
+}
+
+/// State shared between recorder handles.
+struct Shared {
+    journal: EventJournal,
+    sender: SyncSender<Message>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Records events to an [`EventJournal`] without blocking.
+///
+/// Cheap to clone; clones share the writer. A disabled recorder discards
+/// everything, which is what [`Default`] gives so tests and embedders don't
+/// write to the user's journal by accident.
+#[derive(Clone, Default)]
+pub struct EventRecorder {
+    shared: Option<Arc<Shared>>,
+}
+
+impl EventRecorder {
+    /// Creates a recorder that discards events.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Creates a recorder writing to `journal` from a background thread.
+    ///
+    /// The thread exits once every clone of the recorder is dropped.
+    pub fn open(journal: EventJournal) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let writer = JournalWriter {
+            journal: journal.clone(),
+            file: None,
+            size: 0,
+            failing: false,
+            dropped: dropped.clone(),
+        };
+        let spawned = std::thread::Builder::new()
+            .name("event-journal".to_string())
+            .spawn(move || writer.run(receiver));
+        if let Err(e) = spawned {
+            warn!("Failed to start event journal writer: {}", e);
+            return Self::disabled();
+        }
+
+        Self {
+            shared: Some(Arc::new(Shared {
+                journal,
+                sender,
+                dropped,
+            })),
+        }
+    }
+
+    /// Journal written to, `None` when disabled.
+    pub fn journal(&self) -> Option<&EventJournal> {
+        self.shared.as_ref().map(|shared| &shared.journal)
+    }
+
+    /// Queues `event` for writing, dropping it if the queue is full.
+    pub fn record(&self, event: JournalEvent) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+        match shared.sender.try_send(Message::Event(event)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(Message::Event(event))) => {
+                shared.dropped.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "Event journal queue is full; dropped {:?} event for '{}'",
+                    event.event_type, event.subject
+                );
+            }
+            Err(_) => {
+                shared.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of events dropped because the queue was full or the journal
+    /// couldn't be written.
+    pub fn dropped(&self) -> u64 {
+        self.shared
+            .as_ref()
+            .map_or(0, |shared| shared.dropped.load(Ordering::Relaxed))
+    }
+
+    /// Blocks until every event recorded so far has been written or dropped.
+    pub fn flush(&self) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+        let (ack, done) = mpsc::channel();
+        if shared.sender.send(Message::Flush(ack)).is_ok() {
+            let _ = done.recv();
+        }
+    }
+}
+
+/// Owns the journal file on the writer thread.
+struct JournalWriter {
+    journal: EventJournal,
+    file: Option<File>,
+    /// Size of the live file.
+    size: u64,
+    /// Whether the last write failed, so failures are logged once.
+    failing: bool,
+    dropped: Arc<AtomicU64>,
+}
+
+impl JournalWriter {
+    fn run(mut self, receiver: Receiver<Message>) {
+        for message in receiver {
+            match message {
+                Message::Event(event) => self.write(&event),
+                Message::Flush(ack) => {
+                    let _ = ack.send(());
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, event: &JournalEvent) {
+        let mut line = match serde_json::to_string(event) {
+            Ok(line) => line,
+            Err(e) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                warn!("Failed to serialize journal event: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        match self.append(line.as_bytes()) {
+            Ok(()) => {
+                if self.failing {
+                    info!("Event journal is writable again");
+                    self.failing = false;
+                }
+            }
+            Err(e) => {
+                // Reopen next time in case the file was moved or deleted
+                self.file = None;
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                if !self.failing {
+                    warn!(
+                        "Failed to write event journal {}, dropping events until it recovers: {}",
+                        self.journal.path.display(),
+                        e
+                    );
+                    self.failing = true;
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, line: &[u8]) -> std::io::Result<()> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size + line.len() as u64 > self.journal.max_bytes {
+            self.rotate()?;
+            self.open()?;
+        }
+
+        if let Some(file) = &mut self.file {
+            file.write_all(line)?;
+            self.size += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> std::io::Result<()> {
+        if let Some(parent) = self.journal.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.journal.path)?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Shifts rotated files up one place and moves the live file to `.1`.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file = None;
+        self.size = 0;
+
+        if self.journal.max_files == 0 {
+            return fs::remove_file(&self.journal.path);
+        }
+        for index in (1..self.journal.max_files).rev() {
+            match fs::rename(
+                self.journal.rotated_path(index),
+                self.journal.rotated_path(index + 1),
+            ) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        fs::rename(&self.journal.path, self.journal.rotated_path(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EventActor, EventType};
+    use tempfile::TempDir;
+
+    fn event(event_type: EventType, subject: &str) -> JournalEvent {
+        JournalEvent::new(event_type, subject, EventActor::User)
+    }
+
+    #[test]
+    fn test_records_in_order() {
+        let dir = TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
+
+        for i in 0..50 {
+            recorder.record(event(EventType::ProcessStarted, &format!("p{}", i)));
+        }
+        recorder.flush();
+
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        let subjects: Vec<_> = events.iter().map(|e| e.subject.clone()).collect();
+        let expected: Vec<_> = (0..50).map(|i| format!("p{}", i)).collect();
+        assert_eq!(subjects, expected);
+        assert_eq!(recorder.dropped(), 0);
+    }
+
+    #[test]
+    fn test_query_filters_and_limits() {
+        let dir = TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
+
+        recorder.record(event(EventType::ProcessStarted, "api"));
+        recorder.record(event(EventType::ProcessStarted, "worker"));
+        recorder.record(event(EventType::ProcessStopped, "api"));
+        recorder.record(event(EventType::ProcessStarted, "api"));
+        recorder.flush();
+
+        let filter = EventFilter {
+            subject: Some("api".to_string()),
+            ..Default::default()
+        };
+        let events = journal.query(&filter, None, Some(2)).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, EventType::ProcessStopped);
+        assert_eq!(events[1].event_type, EventType::ProcessStarted);
+
+        let future = Utc::now() + chrono::Duration::hours(1);
+        assert!(journal
+            .query(&filter, Some(future), None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rotation() {
+        let dir = TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl")).with_rotation(500, 2);
+        let recorder = EventRecorder::open(journal.clone());
+
+        for i in 0..40 {
+            recorder.record(event(EventType::ProcessStarted, &format!("p{}", i)));
+        }
+        recorder.flush();
+
+        assert!(journal.rotated_path(1).exists());
+        assert!(journal.rotated_path(2).exists());
+        assert!(!journal.rotated_path(3).exists());
+        for path in [journal.path().to_path_buf(), journal.rotated_path(1)] {
+            assert!(fs::metadata(path).unwrap().len() <= 500);
+        }
+
+        // The oldest events were rotated away; the rest are still in order
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        assert!(events.len() < 40);
+        assert_eq!(events.last().unwrap().subject, "p39");
+        let numbers: Vec<usize> = events
+            .iter()
+            .map(|e| e.subject[1..].parse().unwrap())
+            .collect();
+        assert!(numbers.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+
+    #[test]
+    fn test_write_failure_drops_events() {
+        let dir = TempDir::new().unwrap();
+        // A directory where the journal file should be makes every write fail
+        let path = dir.path().join("events.jsonl");
+        fs::create_dir(&path).unwrap();
+        let recorder = EventRecorder::open(EventJournal::new(&path));
+
+        recorder.record(event(EventType::ProcessStarted, "api"));
+        recorder.record(event(EventType::ProcessStopped, "api"));
+        recorder.flush();
+
+        assert_eq!(recorder.dropped(), 2);
+    }
+
+    #[test]
+    fn test_disabled_recorder() {
+        let recorder = EventRecorder::disabled();
+        recorder.record(event(EventType::ProcessStarted, "api"));
+        recorder.flush();
+        assert!(recorder.journal().is_none());
+        assert_eq!(recorder.dropped(), 0);
+    }
+}
@@ -0,0 +1,259 @@
+//! The `/events` WebSocket protocol.
+//!
+//! Every frame is one JSON object with a `type`. The server sends each
+//! [`LiveEvent`] as it is published (`process`, `systemStats`, `alertFired`,
+//! `alertResolved`), except `log` events, which only go to clients that
+//! subscribed to the process. Clients send [`ClientMessage`]s and get a
+//! [`Reply`] to each:
+//!
+//! ```text
+//! → {"type": "subscribe", "processes": ["api", "worker"]}
+//! ← {"type": "subscribed", "processes": ["api", "worker"]}
+//! ← {"type": "log", "process": "api", "line": {"line": "listening", ...}}
+//! → {"type": "ping"}
+//! ← {"type": "pong"}
+//! ```
+//!
+//! A client that reads slower than events arrive loses the oldest ones and
+//! is told how many it has lost so far with `{"type": "dropped", "count": N}`.
+//!
+//! The WebSocket protocol itself is tungstenite's; the HTTP upgrade is done
+//! by hyper in the API server.
+
+use crate::core::event_bus::{EventBus, LiveEvent};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role, WebSocketConfig};
+use tokio_tungstenite::tungstenite::{Error, Message};
+use tokio_tungstenite::WebSocketStream;
+use tracing::debug;
+
+/// Largest message a client may send; subscriptions are a few names.
+pub const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// A message from the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ClientMessage {
+    /// Receive the log lines of these processes too.
+    Subscribe {
+        processes: Vec<String>,
+    },
+    /// Stop receiving the log lines of these processes.
+    Unsubscribe {
+        processes: Vec<String>,
+    },
+    Ping,
+}
+
+/// The server's answer to a [`ClientMessage`], or news about the stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Reply {
+    /// Every process the client now receives log lines of.
+    Subscribed {
+        processes: Vec<String>,
+    },
+    Pong,
+    /// Events lost so far because the client fell behind.
+    Dropped {
+        count: u64,
+    },
+    /// The client's message couldn't be read.
+    Error {
+        message: String,
+    },
+}
+
+/// Any message from the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ServerMessage {
+    Event(LiveEvent),
+    Reply(Reply),
+}
+
+/// What one client has asked for.
+#[derive(Debug, Default)]
+struct Session {
+    subscribed: BTreeSet<String>,
+    dropped: u64,
+}
+
+impl Session {
+    fn handle(&mut self, text: &str) -> Reply {
+        let message = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => {
+                return Reply::Error {
+                    message: format!("Unreadable message: {}", e),
+                }
+            }
+        };
+        match message {
+            ClientMessage::Subscribe { processes } => self.subscribed.extend(processes),
+            ClientMessage::Unsubscribe { processes } => {
+                for process in &processes {
+                    self.subscribed.remove(process);
+                }
+            }
+            ClientMessage::Ping => return Reply::Pong,
+        }
+        Reply::Subscribed {
+            processes: self.subscribed.iter().cloned().collect(),
+        }
+    }
+
+    fn wants(&self, event: &LiveEvent) -> bool {
+        match event {
+            LiveEvent::Log { process, .. } => self.subscribed.contains(process),
+            _ => true,
+        }
+    }
+}
+
+/// Streams `events` to the client on `stream`, a connection already upgraded
+/// to a WebSocket, until either side closes.
+pub async fn run<S>(stream: S, events: &EventBus)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut receiver = events.subscribe();
+    let config = WebSocketConfig {
+        max_message_size: Some(MAX_MESSAGE_BYTES),
+        max_frame_size: Some(MAX_MESSAGE_BYTES),
+        ..Default::default()
+    };
+    let mut socket = WebSocketStream::from_raw_socket(stream, Role::Server, Some(config)).await;
+
+    let mut session = Session::default();
+    let mut close = Some(CloseCode::Normal);
+    loop {
+        let outgoing = tokio::select! {
+            // Cancel safe: a partly read message stays buffered in the socket
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => Some(reply(session.handle(&text))),
+                // The close is echoed by tungstenite, as the RFC suggests
+                Some(Ok(Message::Close(_))) | None => break,
+                // Pings are answered by tungstenite too
+                Some(Ok(_)) => None,
+                Some(Err(e)) => {
+                    debug!("Closing event stream: {}", e);
+                    close = close_code(&e);
+                    break;
+                }
+            },
+            event = receiver.recv() => match event {
+                Ok(event) if session.wants(&event) => Some(text(&ServerMessage::Event(event))),
+                Ok(_) => None,
+                Err(RecvError::Lagged(count)) => {
+                    session.dropped += count;
+                    Some(reply(Reply::Dropped { count: session.dropped }))
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        let Some(outgoing) = outgoing else {
+            continue;
+        };
+        if let Err(e) = socket.send(outgoing).await {
+            debug!("Event stream client went away: {}", e);
+            return;
+        }
+    }
+
+    let close = close.map(|code| CloseFrame {
+        code,
+        reason: "".into(),
+    });
+    let _ = socket.close(close).await;
+}
+
+/// The status code to close the connection with after `error`, or `None`
+/// if the connection itself failed.
+fn close_code(error: &Error) -> Option<CloseCode> {
+    match error {
+        Error::Protocol(_) => Some(CloseCode::Protocol),
+        Error::Capacity(_) => Some(CloseCode::Size),
+        Error::Utf8 => Some(CloseCode::Invalid),
+        _ => None,
+    }
+}
+
+fn reply(reply: Reply) -> Message {
+    text(&ServerMessage::Reply(reply))
+}
+
+fn text(message: &ServerMessage) -> Message {
+    // These types always serialize
+    Message::Text(serde_json::to_string(message).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log_buffer::{LogLine, LogStream};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn log(process: &str) -> LiveEvent {
+        LiveEvent::Log {
+            process: process.to_string(),
+            line: LogLine::new(Utc::now(), LogStream::Stdout, "hi".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_session_subscriptions() {
+        let mut session = Session::default();
+        assert!(!session.wants(&log("api")));
+
+        let reply = session.handle(r#"{"type":"subscribe","processes":["api","worker"]}"#);
+        assert_eq!(
+            reply,
+            Reply::Subscribed {
+                processes: vec!["api".to_string(), "worker".to_string()]
+            }
+        );
+        assert!(session.wants(&log("api")));
+
+        session.handle(r#"{"type":"unsubscribe","processes":["api","web"]}"#);
+        assert!(!session.wants(&log("api")));
+        assert!(session.wants(&log("worker")));
+
+        assert_eq!(session.handle(r#"{"type":"ping"}"#), Reply::Pong);
+        assert!(matches!(
+            session.handle(r#"{"type":"subscribe"}"#),
+            Reply::Error { .. }
+        ));
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let to_json = |message: &ServerMessage| serde_json::to_value(message).unwrap();
+
+        let event = to_json(&ServerMessage::Event(log("api")));
+        assert_eq!(event["type"], "log");
+        assert_eq!(event["process"], "api");
+        assert_eq!(event["line"]["line"], "hi");
+        assert_eq!(
+            to_json(&ServerMessage::Reply(Reply::Dropped { count: 3 })),
+            json!({"type": "dropped", "count": 3})
+        );
+        assert_eq!(
+            to_json(&ServerMessage::Reply(Reply::Pong)),
+            json!({"type": "pong"})
+        );
+
+        let parsed: ServerMessage =
+            serde_json::from_str(r#"{"type":"subscribed","processes":[]}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            ServerMessage::Reply(Reply::Subscribed { .. })
+        ));
+    }
+}
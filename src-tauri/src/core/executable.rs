@@ -0,0 +1,145 @@
+//! Finding out what a spawned process actually runs.
+//!
+//! The configured command is often a bare name such as `npm`, so the binary
+//! behind it is resolved after spawning: from `/proc/<pid>/exe` on Linux, and
+//! elsewhere by looking the name up the way a shell would.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// Resolves the absolute path of the executable `program` run by the process
+/// `pid`.
+///
+/// # Arguments
+/// * `pid` - PID of the running process
+/// * `program` - Program as passed to spawn
+/// * `cwd` - Working directory the process was started in
+/// * `path` - `PATH` the process was started with
+pub fn resolve(
+    pid: u32,
+    program: &str,
+    cwd: Option<&Path>,
+    path: Option<&OsStr>,
+) -> Option<PathBuf> {
+    #[cfg(target_os = "linux")]
+    if let Ok(exe) = std::fs::read_link(format!("/proc/{}/exe", pid)) {
+        return Some(exe);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = pid;
+
+    which(program, cwd, path)
+}
+
+/// Looks `program` up like a shell: relative to `cwd` if it contains a slash,
+/// otherwise in each directory of `path`.
+///
+/// # Returns
+/// The canonical path of the first executable file found.
+pub fn which(program: &str, cwd: Option<&Path>, path: Option<&OsStr>) -> Option<PathBuf> {
+    if program.is_empty() {
+        return None;
+    }
+
+    if program.contains(std::path::MAIN_SEPARATOR) || program.contains('/') {
+        let candidate = match cwd {
+            Some(cwd) => cwd.join(program),
+            None => PathBuf::from(program),
+        };
+        return is_executable(&candidate)
+            .then(|| candidate.canonicalize().ok())
+            .flatten();
+    }
+
+    std::env::split_paths(path?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+        .and_then(|candidate| candidate.canonicalize().ok())
+}
+
+/// PID of the leader of the process group `pid` belongs to.
+pub fn process_group(pid: u32) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        let pgid = unsafe { libc::getpgid(pid as libc::pid_t) };
+        (pgid > 0).then_some(pgid as u32)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = path.metadata() else {
+        return false;
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_spawned_sh() {
+        let path = std::env::var_os("PATH");
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "sleep 5"])
+            .spawn()
+            .unwrap();
+
+        let exe = resolve(child.id(), "sh", None, path.as_deref()).unwrap();
+        assert!(exe.is_absolute());
+        assert!(exe.is_file(), "{} is not a file", exe.display());
+
+        #[cfg(unix)]
+        assert_eq!(process_group(child.id()), process_group(std::process::id()));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_which_searches_path() {
+        let path = std::env::var_os("PATH");
+        let sh = which("sh", None, path.as_deref()).unwrap();
+        assert!(sh.is_absolute());
+        assert!(sh.is_file());
+
+        assert!(which("sh", None, None).is_none());
+        assert!(which("no-such-program-here", None, path.as_deref()).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_which_resolves_relative_paths_against_cwd() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("serve.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+
+        // Not executable yet
+        assert!(which("./serve.sh", Some(dir.path()), None).is_none());
+
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(
+            which("./serve.sh", Some(dir.path()), None),
+            Some(script.canonicalize().unwrap())
+        );
+    }
+}
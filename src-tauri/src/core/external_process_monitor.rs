@@ -3,18 +3,28 @@
 //! This module allows attaching to processes started outside of Sentinel
 //! to monitor their logs without managing their lifecycle.
 
+use crate::core::log_buffer::{LogBuffer, LogLine, LogMemoryUsage, LogStream};
+use crate::core::log_search::{LogQuery, ProcessLogMatches};
+use crate::core::log_tail::{Follower, LogOffsets, TailStart, TailTarget};
+use crate::core::log_timestamp::MergedLogLine;
 use crate::error::{Result, SentinelError};
+use crate::features::docker::DockerMonitor;
+use crate::features::port_discovery::{PortScanner, PortState};
 use chrono::Utc;
+use futures_util::future::{BoxFuture, FutureExt};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
 use tauri::{AppHandle, Emitter};
-use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, BufReader};
+#[cfg(target_os = "macos")]
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 
 /// Information about an attached external process
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,21 +57,211 @@ pub enum LogSource {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogLineEvent {
     pub attachment_id: String,
+    /// When the line was received
     pub timestamp: chrono::DateTime<Utc>,
+    /// When the line was written, from Docker or the start of the line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_timestamp: Option<chrono::DateTime<Utc>>,
+    /// Position among the attachment's lines, counting from 0
+    #[serde(default)]
+    pub seq: u64,
     pub line: String,
     pub stream: String,
+    /// Name of the log file the line came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// An attachment that is streaming lines, as listed by `list_attachments`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub attachment_id: String,
+    /// The external process being followed, when attached to a process
+    /// rather than directly to a file or container
+    pub process: Option<ProcessAttachment>,
+    /// Where the lines come from
+    pub log_source: LogSource,
+    pub started_at: chrono::DateTime<Utc>,
+    /// Number of lines streamed so far
+    pub line_count: u64,
+    /// Whether the attachment follows its process to a new PID on restart
+    pub reattach_on_restart: bool,
+}
+
+/// Event emitted when an attachment switches to a restarted process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentTargetChangedEvent {
+    pub attachment_id: String,
+    pub old_pid: u32,
+    pub process: ProcessAttachment,
+}
+
+/// Event emitted when an attachment ends on its own, e.g. because its
+/// process exited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentDetachedEvent {
+    pub attachment_id: String,
+    pub reason: String,
+}
+
+/// Notification from an attachment, emitted to the frontend as a Tauri event
+enum MonitorEvent {
+    Line(LogLineEvent),
+    TargetChanged(AttachmentTargetChangedEvent),
+    Detached(AttachmentDetachedEvent),
+}
+
+type EventSink = Arc<dyn Fn(MonitorEvent) + Send + Sync>;
+
+/// Sink that emits events to the frontend
+fn app_sink(app: AppHandle) -> EventSink {
+    Arc::new(move |event| {
+        let _ = match event {
+            MonitorEvent::Line(line) => app.emit("log-line", &line),
+            MonitorEvent::TargetChanged(changed) => app.emit("attachment-target-changed", &changed),
+            MonitorEvent::Detached(detached) => app.emit("attachment-detached", &detached),
+        };
+    })
+}
+
+/// How often attached processes are checked for exit
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lines of backlog shown when following a container's logs
+const CONTAINER_TAIL_LINES: usize = 500;
+
+/// Emits the lines of one attachment, counts them and keeps the recent ones
+#[derive(Clone)]
+struct LineEmitter {
+    attachment_id: String,
+    lines: Arc<AtomicU64>,
+    /// Recent lines, for searching
+    recent: Arc<std::sync::Mutex<LogBuffer>>,
+    sink: EventSink,
+}
+
+impl LineEmitter {
+    /// Emitter for a new attachment
+    fn new(sink: EventSink) -> Self {
+        Self {
+            attachment_id: uuid::Uuid::new_v4().to_string(),
+            lines: Arc::new(AtomicU64::new(0)),
+            recent: Arc::new(std::sync::Mutex::new(LogBuffer::new())),
+            sink,
+        }
+    }
+
+    /// Emits a line received at `timestamp`. `written` is the time the
+    /// source reported for it, such as Docker's; without one it is read
+    /// from the start of the line.
+    fn emit(
+        &self,
+        timestamp: chrono::DateTime<Utc>,
+        written: Option<chrono::DateTime<Utc>>,
+        line: String,
+        stream: &str,
+        source: Option<String>,
+    ) {
+        self.lines.fetch_add(1, Ordering::Relaxed);
+        let stream_type = if stream == "stderr" {
+            LogStream::Stderr
+        } else {
+            LogStream::Stdout
+        };
+        let mut log_line = LogLine::new(timestamp, stream_type, line);
+        if written.is_some() {
+            log_line.source_timestamp = written;
+        }
+        let source_timestamp = log_line.source_timestamp;
+        let seq = self
+            .recent
+            .lock()
+            .map_or(0, |mut recent| recent.push(log_line.clone()));
+        (self.sink)(MonitorEvent::Line(LogLineEvent {
+            attachment_id: self.attachment_id.clone(),
+            timestamp,
+            source_timestamp,
+            seq,
+            line: log_line.line,
+            stream: stream.to_string(),
+            source,
+        }));
+    }
+
+    fn detached(&self, reason: String) {
+        tracing::info!("Attachment {} detached: {}", self.attachment_id, reason);
+        (self.sink)(MonitorEvent::Detached(AttachmentDetachedEvent {
+            attachment_id: self.attachment_id.clone(),
+            reason,
+        }));
+    }
+}
+
+/// A running attachment
+struct Attachment {
+    process: Option<ProcessAttachment>,
+    log_source: LogSource,
+    started_at: chrono::DateTime<Utc>,
+    lines: Arc<AtomicU64>,
+    recent: Arc<std::sync::Mutex<LogBuffer>>,
+    reattach_on_restart: bool,
+    /// Task streaming the lines
+    stream: JoinHandle<()>,
+    /// Task watching the attached process
+    watcher: Option<JoinHandle<()>>,
+}
+
+impl Attachment {
+    fn abort(&self) {
+        self.stream.abort();
+        if let Some(watcher) = &self.watcher {
+            watcher.abort();
+        }
+    }
+
+    fn info(&self, attachment_id: &str) -> AttachmentInfo {
+        AttachmentInfo {
+            attachment_id: attachment_id.to_string(),
+            process: self.process.clone(),
+            log_source: self.log_source.clone(),
+            started_at: self.started_at,
+            line_count: self.lines.load(Ordering::Relaxed),
+            reattach_on_restart: self.reattach_on_restart,
+        }
+    }
+
+    /// Name shown for the attachment's lines: the process, file or container
+    fn name(&self, attachment_id: &str) -> String {
+        if let Some(process) = &self.process {
+            return process.name.clone();
+        }
+        match &self.log_source {
+            LogSource::File { path } => path.clone(),
+            LogSource::DockerLogs { container_id } => container_id.clone(),
+            _ => attachment_id.to_string(),
+        }
+    }
 }
 
 /// Manager for external process attachments
+#[derive(Clone)]
 pub struct ExternalProcessMonitor {
-    /// Map of attachment_id -> running task handle
-    attachments: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
+    /// Map of attachment_id -> running attachment
+    attachments: Arc<Mutex<HashMap<String, Attachment>>>,
+    /// Read offsets of tailed log files, so re-attaching resumes where it left off
+    offsets: Arc<LogOffsets>,
 }
 
 impl ExternalProcessMonitor {
     pub fn new() -> Self {
+        Self::with_offsets_path(LogOffsets::default_path())
+    }
+
+    /// Create a monitor that keeps log file offsets in `path`
+    pub fn with_offsets_path(path: PathBuf) -> Self {
         Self {
             attachments: Arc::new(Mutex::new(HashMap::new())),
+            offsets: Arc::new(LogOffsets::load(path)),
         }
     }
 
@@ -169,103 +369,195 @@ impl ExternalProcessMonitor {
         }
     }
 
-    /// Tail a log file and stream to frontend
-    pub async fn tail_log_file(&self, path: String, app: AppHandle) -> Result<String> {
-        let path_buf = PathBuf::from(&path);
+    /// Tail a log file, or every file matching a glob, and stream to frontend
+    ///
+    /// A file that was tailed before resumes from where it was left, unless
+    /// `last_lines` asks for its last lines; otherwise tailing starts at the
+    /// end. Files matching a glob are picked up as they are created, and
+    /// truncated or rotated files are reopened from the start.
+    ///
+    /// When `allowed_roots` is non-empty files must resolve to a path under
+    /// one of them, otherwise `PathNotAllowed` is returned.
+    pub async fn tail_log_file(
+        &self,
+        path: String,
+        last_lines: Option<usize>,
+        allowed_roots: &[PathBuf],
+        app: AppHandle,
+    ) -> Result<String> {
+        self.tail_with_sink(path, last_lines, allowed_roots, app_sink(app))
+            .await
+    }
 
-        if !path_buf.exists() {
-            return Err(SentinelError::Other(format!(
-                "Log file does not exist: {}",
-                path
-            )));
-        }
+    async fn tail_with_sink(
+        &self,
+        path: String,
+        last_lines: Option<usize>,
+        allowed_roots: &[PathBuf],
+        sink: EventSink,
+    ) -> Result<String> {
+        let emitter = LineEmitter::new(sink);
+        let stream = self.file_stream(&path, last_lines, allowed_roots, emitter.clone())?;
+        Ok(self
+            .insert(
+                emitter,
+                None,
+                LogSource::File { path },
+                false,
+                stream,
+                Vec::new(),
+            )
+            .await)
+    }
 
-        let file = File::open(&path_buf)
-            .await
-            .map_err(|e| SentinelError::Other(format!("Failed to open log file: {}", e)))?;
-
-        // Generate unique attachment ID
-        let attachment_id = uuid::Uuid::new_v4().to_string();
-        let attachment_id_clone = attachment_id.clone();
-
-        // Spawn task to stream lines
-        let handle = tokio::spawn(async move {
-            let mut reader = BufReader::new(file);
-            let mut line = String::new();
-
-            // First, read and emit all existing content
-            tracing::info!("Reading existing log content from file");
-            loop {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => {
-                        // Reached EOF, now start tailing for new content
-                        tracing::info!("Reached EOF, starting tail mode");
-                        break;
-                    }
-                    Ok(_) => {
-                        let timestamp = Utc::now();
-                        let _ = app.emit(
-                            "log-line",
-                            &LogLineEvent {
-                                attachment_id: attachment_id_clone.clone(),
-                                timestamp,
-                                line: line.trim_end().to_string(),
-                                stream: "file".to_string(),
-                            },
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!("Error reading initial log content: {}", e);
-                        return;
-                    }
-                }
-            }
+    /// Stream a Docker container's logs to the frontend
+    ///
+    /// Lines are emitted as `log-line` events with stream `docker-stdout` or
+    /// `docker-stderr`. When a followed stream ends, a final `docker-exit`
+    /// line reports why.
+    pub async fn stream_container_logs(
+        &self,
+        container_id: String,
+        tail: Option<usize>,
+        follow: bool,
+        app: AppHandle,
+    ) -> Result<String> {
+        let emitter = LineEmitter::new(app_sink(app));
+        let stream = Self::container_stream(container_id.clone(), tail, follow, emitter.clone())?;
+        let log_source = LogSource::DockerLogs { container_id };
+        Ok(self
+            .insert(emitter, None, log_source, false, stream, Vec::new())
+            .await)
+    }
 
-            // Now tail for new content
-            loop {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => {
-                        // EOF - wait a bit and try again (tailing behavior)
-                        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                        continue;
-                    }
-                    Ok(_) => {
-                        let timestamp = Utc::now();
-                        let _ = app.emit(
-                            "log-line",
-                            &LogLineEvent {
-                                attachment_id: attachment_id_clone.clone(),
-                                timestamp,
-                                line: line.trim_end().to_string(),
-                                stream: "file".to_string(),
-                            },
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!("Error reading log file: {}", e);
-                        break;
-                    }
-                }
-            }
-        });
+    /// Capture stdout/stderr using dtrace (macOS only)
+    #[cfg(target_os = "macos")]
+    pub async fn capture_with_dtrace(&self, pid: u32, app: AppHandle) -> Result<String> {
+        let emitter = LineEmitter::new(app_sink(app));
+        let stream = Self::dtrace_stream(pid, emitter.clone());
+        Ok(self
+            .insert(
+                emitter,
+                None,
+                LogSource::DTrace { pid },
+                false,
+                stream,
+                Vec::new(),
+            )
+            .await)
+    }
 
-        // Store the handle so we can cancel it later
-        self.attachments
-            .lock()
+    /// Stream the logs of a process found by `attach_to_process`
+    ///
+    /// The attachment is detached when the process exits. With
+    /// `reattach_on_restart` and a known port, it instead waits for a new
+    /// process to listen on that port, switches to it and emits an
+    /// `attachment-target-changed` event.
+    pub async fn follow_process(
+        &self,
+        process: ProcessAttachment,
+        reattach_on_restart: bool,
+        allowed_roots: &[PathBuf],
+        app: AppHandle,
+    ) -> Result<String> {
+        self.follow_process_with_sink(process, reattach_on_restart, allowed_roots, app_sink(app))
             .await
-            .insert(attachment_id.clone(), handle);
+    }
 
-        Ok(attachment_id)
+    async fn follow_process_with_sink(
+        &self,
+        process: ProcessAttachment,
+        reattach_on_restart: bool,
+        allowed_roots: &[PathBuf],
+        sink: EventSink,
+    ) -> Result<String> {
+        let emitter = LineEmitter::new(sink);
+        let stream = self.source_stream(&process.log_source, allowed_roots, emitter.clone())?;
+        let log_source = process.log_source.clone();
+        Ok(self
+            .insert(
+                emitter,
+                Some(process),
+                log_source,
+                reattach_on_restart,
+                stream,
+                allowed_roots.to_vec(),
+            )
+            .await)
+    }
+
+    /// List the current attachments, oldest first
+    pub async fn list_attachments(&self) -> Vec<AttachmentInfo> {
+        let attachments = self.attachments.lock().await;
+        let mut list: Vec<AttachmentInfo> = attachments
+            .iter()
+            .map(|(attachment_id, attachment)| attachment.info(attachment_id))
+            .collect();
+        list.sort_by_key(|info| info.started_at);
+        list
+    }
+
+    /// Search the recent lines of every attachment, oldest attachment first
+    pub async fn search_logs(&self, query: &LogQuery) -> Vec<ProcessLogMatches> {
+        let attachments = self.attachments.lock().await;
+        let mut ordered: Vec<(&String, &Attachment)> = attachments.iter().collect();
+        ordered.sort_by_key(|(_, attachment)| attachment.started_at);
+
+        ordered
+            .into_iter()
+            .filter_map(|(attachment_id, attachment)| {
+                let (matches, truncated) = attachment.recent.lock().ok()?.query(query);
+                (!matches.is_empty()).then(|| ProcessLogMatches {
+                    process: attachment.name(attachment_id),
+                    attachment_id: Some(attachment_id.clone()),
+                    matches,
+                    truncated,
+                })
+            })
+            .collect()
+    }
+
+    /// The recent lines of every attachment, for a merged view
+    pub async fn merged_logs(&self) -> Vec<MergedLogLine> {
+        let attachments = self.attachments.lock().await;
+        let mut merged = Vec::new();
+        for (attachment_id, attachment) in attachments.iter() {
+            let Ok(recent) = attachment.recent.lock() else {
+                continue;
+            };
+            let process = attachment.name(attachment_id);
+            merged.extend(recent.get_all().into_iter().map(|line| MergedLogLine {
+                process: process.clone(),
+                attachment_id: Some(attachment_id.clone()),
+                line,
+            }));
+        }
+        merged
+    }
+
+    /// Memory held by the recent lines of every attachment
+    pub async fn log_memory_usage(&self) -> Vec<LogMemoryUsage> {
+        let attachments = self.attachments.lock().await;
+        attachments
+            .iter()
+            .filter_map(|(attachment_id, attachment)| {
+                let recent = attachment.recent.lock().ok()?;
+                let mut usage = recent.memory_usage(attachment.name(attachment_id));
+                usage.attachment_id = Some(attachment_id.clone());
+                Some(usage)
+            })
+            .collect()
     }
 
     /// Stop tailing a log file
     pub async fn detach(&self, attachment_id: &str) -> Result<()> {
         let mut attachments = self.attachments.lock().await;
 
-        if let Some(handle) = attachments.remove(attachment_id) {
-            handle.abort();
+        if let Some(attachment) = attachments.remove(attachment_id) {
+            attachment.abort();
+            if let Err(e) = self.offsets.save() {
+                tracing::warn!("Failed to save log offsets: {}", e);
+            }
             Ok(())
         } else {
             Err(SentinelError::Other(format!(
@@ -275,21 +567,305 @@ impl ExternalProcessMonitor {
         }
     }
 
-    /// Capture stdout/stderr using dtrace (macOS only)
-    #[cfg(target_os = "macos")]
-    pub async fn capture_with_dtrace(&self, pid: u32, app: AppHandle) -> Result<String> {
-        // Generate unique attachment ID
-        let attachment_id = uuid::Uuid::new_v4().to_string();
-        let attachment_id_clone = attachment_id.clone();
+    /// Start tracking an attachment that streams `stream`
+    ///
+    /// Attachments to a process also get a task watching that process.
+    async fn insert(
+        &self,
+        emitter: LineEmitter,
+        process: Option<ProcessAttachment>,
+        log_source: LogSource,
+        reattach_on_restart: bool,
+        stream: BoxFuture<'static, ()>,
+        allowed_roots: Vec<PathBuf>,
+    ) -> String {
+        // Hold the lock while spawning, so a stream that ends at once still finds its attachment
+        let mut attachments = self.attachments.lock().await;
+
+        let watcher = process.as_ref().map(|process| {
+            tokio::spawn(self.clone().watch(
+                emitter.clone(),
+                process.pid,
+                process.port,
+                allowed_roots,
+            ))
+        });
+        let attachment = Attachment {
+            process,
+            log_source,
+            started_at: Utc::now(),
+            lines: emitter.lines.clone(),
+            recent: emitter.recent.clone(),
+            reattach_on_restart,
+            stream: self.spawn_stream(emitter.clone(), stream),
+            watcher,
+        };
 
+        attachments.insert(emitter.attachment_id.clone(), attachment);
+        emitter.attachment_id
+    }
+
+    /// Spawn `stream`, detaching its attachment if it ends on its own
+    fn spawn_stream(&self, emitter: LineEmitter, stream: BoxFuture<'static, ()>) -> JoinHandle<()> {
+        let attachments = self.attachments.clone();
+        tokio::spawn(async move {
+            stream.await;
+
+            // A process that may restart is left to the watcher
+            let mut attachments = attachments.lock().await;
+            let restartable = attachments
+                .get(&emitter.attachment_id)
+                .is_some_and(|attachment| attachment.reattach_on_restart);
+            if restartable {
+                return;
+            }
+            if let Some(attachment) = attachments.remove(&emitter.attachment_id) {
+                emitter.detached("Log stream ended".to_string());
+                attachment.abort();
+            }
+        })
+    }
+
+    /// Watch the process of an attachment until it exits
+    ///
+    /// The attachment is then detached, or, if it re-attaches on restart and
+    /// the port is known, moved to the next process listening on the port.
+    async fn watch(
+        self,
+        emitter: LineEmitter,
+        mut pid: u32,
+        port: Option<u16>,
+        allowed_roots: Vec<PathBuf>,
+    ) {
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+
+            let reason = if process_alive(pid) {
+                let attachments = self.attachments.lock().await;
+                match attachments.get(&emitter.attachment_id) {
+                    None => return,
+                    Some(attachment) if attachment.stream.is_finished() => {
+                        "Log stream ended".to_string()
+                    }
+                    Some(_) => continue,
+                }
+            } else {
+                let reattach = self
+                    .attachments
+                    .lock()
+                    .await
+                    .get(&emitter.attachment_id)
+                    .map(|attachment| attachment.reattach_on_restart);
+                match (reattach, port) {
+                    (None, _) => return,
+                    (Some(true), Some(port)) => {
+                        match self.reattach(&emitter, pid, port, &allowed_roots).await {
+                            Some(new_pid) => {
+                                pid = new_pid;
+                                continue;
+                            }
+                            None => return,
+                        }
+                    }
+                    _ => format!("Process {} exited", pid),
+                }
+            };
+
+            if let Some(attachment) = self.attachments.lock().await.remove(&emitter.attachment_id) {
+                emitter.detached(reason);
+                attachment.abort();
+            }
+            return;
+        }
+    }
+
+    /// Wait for a new process to listen on `port`, then stream its logs
+    ///
+    /// Returns the new PID, or `None` if the attachment was detached first.
+    async fn reattach(
+        &self,
+        emitter: &LineEmitter,
+        old_pid: u32,
+        port: u16,
+        allowed_roots: &[PathBuf],
+    ) -> Option<u32> {
+        tracing::info!(
+            "Process {} exited, waiting for a new process on port {}",
+            old_pid,
+            port
+        );
+        let scanner = PortScanner::new();
+
+        loop {
+            if !self
+                .attachments
+                .lock()
+                .await
+                .contains_key(&emitter.attachment_id)
+            {
+                return None;
+            }
+
+            if let Some(pid) = listening_pid(&scanner, port)
+                .await
+                .filter(|&pid| pid != old_pid)
+            {
+                match self.switch_process(emitter, pid, port, allowed_roots).await {
+                    Ok(process) => {
+                        (emitter.sink)(MonitorEvent::TargetChanged(AttachmentTargetChangedEvent {
+                            attachment_id: emitter.attachment_id.clone(),
+                            old_pid,
+                            process,
+                        }));
+                        return Some(pid);
+                    }
+                    Err(e) => tracing::warn!("Failed to re-attach to PID {}: {}", pid, e),
+                }
+            }
+
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    }
+
+    /// Point an attachment at a new process
+    async fn switch_process(
+        &self,
+        emitter: &LineEmitter,
+        pid: u32,
+        port: u16,
+        allowed_roots: &[PathBuf],
+    ) -> Result<ProcessAttachment> {
+        let process = self.attach_to_process(pid, Some(port)).await?;
+        let stream = self.source_stream(&process.log_source, allowed_roots, emitter.clone())?;
+
+        let mut attachments = self.attachments.lock().await;
+        let attachment = attachments.get_mut(&emitter.attachment_id).ok_or_else(|| {
+            SentinelError::Other(format!("Attachment not found: {}", emitter.attachment_id))
+        })?;
+
+        attachment.stream.abort();
+        attachment.stream = self.spawn_stream(emitter.clone(), stream);
+        attachment.log_source = process.log_source.clone();
+        attachment.process = Some(process.clone());
+        Ok(process)
+    }
+
+    /// Stream for a detected log source
+    fn source_stream(
+        &self,
+        log_source: &LogSource,
+        allowed_roots: &[PathBuf],
+        emitter: LineEmitter,
+    ) -> Result<BoxFuture<'static, ()>> {
+        match log_source {
+            LogSource::File { path } => self.file_stream(path, None, allowed_roots, emitter),
+            LogSource::DockerLogs { container_id } => Self::container_stream(
+                container_id.clone(),
+                Some(CONTAINER_TAIL_LINES),
+                true,
+                emitter,
+            ),
+            #[cfg(target_os = "macos")]
+            LogSource::DTrace { pid } => Ok(Self::dtrace_stream(*pid, emitter)),
+            #[cfg(not(target_os = "macos"))]
+            LogSource::DTrace { .. } => Err(SentinelError::Unsupported {
+                operation: "dtrace capture".to_string(),
+            }),
+            LogSource::Manual { instructions } => Err(SentinelError::InvalidInput {
+                message: instructions.clone(),
+            }),
+        }
+    }
+
+    /// Stream that follows a log file or glob
+    fn file_stream(
+        &self,
+        path: &str,
+        last_lines: Option<usize>,
+        allowed_roots: &[PathBuf],
+        emitter: LineEmitter,
+    ) -> Result<BoxFuture<'static, ()>> {
+        let target = TailTarget::parse(path)?;
+        if let TailTarget::File(file) = &target {
+            if !file.exists() {
+                return Err(SentinelError::Other(format!(
+                    "Log file does not exist: {}",
+                    path
+                )));
+            }
+        }
+
+        let start = last_lines.map_or(TailStart::End, TailStart::LastLines);
+        let follower = Follower::new(target, start, allowed_roots.to_vec(), self.offsets.clone())?;
+
+        Ok(follower
+            .run(move |source, line| {
+                emitter.emit(Utc::now(), None, line, "file", Some(source.to_string()));
+            })
+            .boxed())
+    }
+
+    /// Stream of a Docker container's logs
+    fn container_stream(
+        container_id: String,
+        tail: Option<usize>,
+        follow: bool,
+        emitter: LineEmitter,
+    ) -> Result<BoxFuture<'static, ()>> {
+        let docker = DockerMonitor::new();
+        let logs = docker
+            .stream_logs(&container_id, tail, follow)
+            .ok_or_else(|| SentinelError::DockerError("Docker is not available".to_string()))?;
+
+        Ok(async move {
+            let mut logs = std::pin::pin!(logs);
+            let mut failure = None;
+            while let Some(chunk) = logs.next().await {
+                match chunk {
+                    Ok(lines) => {
+                        for line in lines {
+                            emitter.emit(Utc::now(), line.timestamp, line.line, &line.stream, None);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Log stream for container {} failed: {}", container_id, e);
+                        failure = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            // Without follow the stream simply runs out of backlog
+            if !follow && failure.is_none() {
+                return;
+            }
+
+            let message = match docker.get_exit_status(&container_id).await {
+                Ok(Some(status)) => match status.exit_code {
+                    Some(code) => format!("Container {} with code {}", status.state, code),
+                    None => format!("Log stream ended (container {})", status.state),
+                },
+                _ => match failure {
+                    Some(e) => format!("Log stream ended: {}", e),
+                    None => "Log stream ended".to_string(),
+                },
+            };
+            emitter.emit(Utc::now(), None, message, "docker-exit", None);
+        }
+        .boxed())
+    }
+
+    /// Stream that explains why dtrace can't capture output (macOS only)
+    #[cfg(target_os = "macos")]
+    fn dtrace_stream(pid: u32, emitter: LineEmitter) -> BoxFuture<'static, ()> {
         tracing::info!("Starting dtrace capture for PID {}", pid);
 
         // Show helpful message about SIP limitations and alternatives
-        let handle = tokio::spawn(async move {
-            let _ = app.emit("log-line", &LogLineEvent {
-                attachment_id: attachment_id_clone.clone(),
-                timestamp: Utc::now(),
-                line: "⚠️  macOS System Integrity Protection (SIP) Blocks Direct Log Capture\n\n\
+        async move {
+            emitter.emit(
+                Utc::now(),
+                None,
+                "⚠️  macOS System Integrity Protection (SIP) Blocks Direct Log Capture\n\n\
                       Unfortunately, Sentinel cannot directly capture stdout/stderr from already-running processes \n\
                       because macOS System Integrity Protection blocks the dtrace syscall provider.\n\n\
                       ✅ RECOMMENDED SOLUTION: Use Log Files\n\n\
@@ -306,17 +882,11 @@ impl ExternalProcessMonitor {
                       Note: This reduces security protections on your Mac.\n\n\
                       📖 Learn more: https://developer.apple.com/documentation/security/disabling_and_enabling_system_integrity_protection"
                     .to_string(),
-                stream: "info".to_string(),
-            });
-        });
-
-        // Store the handle
-        self.attachments
-            .lock()
-            .await
-            .insert(attachment_id.clone(), handle);
-
-        Ok(attachment_id)
+                "info",
+                None,
+            );
+        }
+        .boxed()
     }
 
     /// Check if dtrace is available (macOS only)
@@ -504,13 +1074,39 @@ impl ExternalProcessMonitor {
 
     /// Get Docker container ID by port
     async fn get_docker_container_by_port(&self, port: u16) -> Result<Option<String>> {
-        // This would integrate with the Docker monitoring feature
-        // For now, return None (will implement when integrating with Docker module)
-        let _ = port; // Suppress unused warning
-        Ok(None)
+        let docker = DockerMonitor::new();
+        if !docker.is_available() {
+            return Ok(None);
+        }
+
+        Ok(docker
+            .find_container_by_port(port)
+            .await?
+            .map(|container| container.full_id))
     }
 }
 
+/// Whether `pid` is a live (not zombie) process
+fn process_alive(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    system
+        .process(pid)
+        .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+}
+
+/// PID of the process listening on `port`, if any
+async fn listening_pid(scanner: &PortScanner, port: u16) -> Option<u32> {
+    scanner
+        .scan()
+        .await
+        .ok()?
+        .into_iter()
+        .find(|info| info.port == port && info.state == PortState::Listen)
+        .map(|info| info.pid)
+}
+
 impl Default for ExternalProcessMonitor {
     fn default() -> Self {
         Self::new()
@@ -520,6 +1116,148 @@ impl Default for ExternalProcessMonitor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use tokio::sync::mpsc;
+
+    fn channel_sink() -> (EventSink, mpsc::UnboundedReceiver<MonitorEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let sink: EventSink = Arc::new(move |event| {
+            let _ = tx.send(event);
+        });
+        (sink, rx)
+    }
+
+    async fn next_event(rx: &mut mpsc::UnboundedReceiver<MonitorEvent>) -> MonitorEvent {
+        tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("timed out waiting for an event")
+            .expect("event channel closed")
+    }
+
+    #[tokio::test]
+    async fn test_list_and_detach() {
+        let dir = tempdir().unwrap();
+        let log = dir.path().join("app.log");
+        std::fs::write(&log, "old line\n").unwrap();
+        let monitor = ExternalProcessMonitor::with_offsets_path(dir.path().join("offsets.json"));
+        let (sink, mut rx) = channel_sink();
+
+        let path = log.to_string_lossy().to_string();
+        let attachment_id = monitor
+            .tail_with_sink(path.clone(), None, &[], sink)
+            .await
+            .unwrap();
+
+        let list = monitor.list_attachments().await;
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].attachment_id, attachment_id);
+        assert!(list[0].process.is_none());
+        assert!(matches!(&list[0].log_source, LogSource::File { path: p } if *p == path));
+        assert_eq!(list[0].line_count, 0);
+
+        // Give the follower time to open the file at its end
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut file = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+        writeln!(file, "new line").unwrap();
+
+        match next_event(&mut rx).await {
+            MonitorEvent::Line(event) => {
+                assert_eq!(event.attachment_id, attachment_id);
+                assert_eq!(event.line, "new line");
+            }
+            _ => panic!("expected a log line"),
+        }
+        assert_eq!(monitor.list_attachments().await[0].line_count, 1);
+
+        let query = LogQuery::new("NEW", Default::default()).unwrap();
+        let found = monitor.search_logs(&query).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].process, path);
+        assert_eq!(
+            found[0].attachment_id.as_deref(),
+            Some(attachment_id.as_str())
+        );
+        assert_eq!(found[0].matches[0].line.line, "new line");
+
+        monitor.detach(&attachment_id).await.unwrap();
+        assert!(monitor.list_attachments().await.is_empty());
+        assert!(monitor.detach(&attachment_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_detaches_when_stream_ends() {
+        let dir = tempdir().unwrap();
+        let monitor = ExternalProcessMonitor::with_offsets_path(dir.path().join("offsets.json"));
+        let (sink, mut rx) = channel_sink();
+
+        let emitter = LineEmitter::new(sink);
+        let log_source = LogSource::Manual {
+            instructions: String::new(),
+        };
+        let attachment_id = monitor
+            .insert(
+                emitter,
+                None,
+                log_source,
+                false,
+                async {}.boxed(),
+                Vec::new(),
+            )
+            .await;
+
+        match next_event(&mut rx).await {
+            MonitorEvent::Detached(event) => assert_eq!(event.attachment_id, attachment_id),
+            _ => panic!("expected a detach"),
+        }
+        assert!(monitor.list_attachments().await.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_detaches_when_process_exits() {
+        let dir = tempdir().unwrap();
+        let log = dir.path().join("app.log");
+        std::fs::write(&log, "").unwrap();
+        let monitor = ExternalProcessMonitor::with_offsets_path(dir.path().join("offsets.json"));
+        let (sink, mut rx) = channel_sink();
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let process = ProcessAttachment {
+            pid,
+            name: "sleep".to_string(),
+            command: "sleep 30".to_string(),
+            port: None,
+            log_source: LogSource::File {
+                path: log.to_string_lossy().to_string(),
+            },
+        };
+
+        // Without a port there is nothing to re-attach to
+        let attachment_id = monitor
+            .follow_process_with_sink(process, true, &[], sink)
+            .await
+            .unwrap();
+        let list = monitor.list_attachments().await;
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].process.as_ref().map(|p| p.pid), Some(pid));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        match next_event(&mut rx).await {
+            MonitorEvent::Detached(event) => {
+                assert_eq!(event.attachment_id, attachment_id);
+                assert_eq!(event.reason, format!("Process {} exited", pid));
+            }
+            _ => panic!("expected a detach"),
+        }
+        assert!(monitor.list_attachments().await.is_empty());
+    }
 
     #[test]
     fn test_extract_log_file_from_cmd() {
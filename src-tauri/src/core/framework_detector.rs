@@ -2,11 +2,16 @@
 //!
 //! This module detects development frameworks from project directories.
 
-use std::collections::HashMap;
-use std::path::Path;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
-use crate::core::process_config::{FrameworkDetection, FrameworkType};
+use crate::core::env_file;
+use crate::core::gitignore::Gitignore;
+use crate::core::process_config::{
+    DetectedProject, FrameworkDetection, FrameworkType, ProjectScanResult,
+};
 use crate::error::Result as SentinelResult;
 
 /// Detect framework from a working directory
@@ -51,12 +56,33 @@ pub async fn detect_framework(working_dir: &str) -> SentinelResult<FrameworkDete
         detections.push(detection);
     }
 
+    // Rust (Cargo) detection
+    if let Some(detection) = detect_rust(path).await {
+        detections.push(detection);
+    }
+
+    // Go detection
+    if let Some(detection) = detect_go(path).await {
+        detections.push(detection);
+    }
+
+    // Rails detection
+    if let Some(detection) = detect_rails(path).await {
+        detections.push(detection);
+    }
+
+    // Laravel detection
+    if let Some(detection) = detect_laravel(path).await {
+        detections.push(detection);
+    }
+
     // Return the detection with highest confidence, or Unknown
-    if let Some(best) = detections.into_iter().max_by(|a, b| {
+    if let Some(mut best) = detections.into_iter().max_by(|a, b| {
         a.confidence
             .partial_cmp(&b.confidence)
             .unwrap_or(std::cmp::Ordering::Equal)
     }) {
+        refine_detection(path, &mut best).await;
         Ok(best)
     } else {
         Ok(FrameworkDetection {
@@ -66,6 +92,7 @@ pub async fn detect_framework(working_dir: &str) -> SentinelResult<FrameworkDete
             suggested_command: String::new(),
             suggested_args: vec![],
             suggested_port: None,
+            available_scripts: vec![],
         })
     }
 }
@@ -96,6 +123,7 @@ async fn detect_nextjs(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "npm".to_string(),
             suggested_args: vec!["run".to_string(), "dev".to_string()],
             suggested_port: Some(3000),
+            available_scripts: vec![],
         })
     } else {
         None
@@ -128,6 +156,7 @@ async fn detect_vite(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "npm".to_string(),
             suggested_args: vec!["run".to_string(), "dev".to_string()],
             suggested_port: Some(5173),
+            available_scripts: vec![],
         })
     } else {
         None
@@ -162,6 +191,7 @@ async fn detect_fastapi(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "uvicorn".to_string(),
             suggested_args: vec!["main:app".to_string(), "--reload".to_string()],
             suggested_port: Some(8000),
+            available_scripts: vec![],
         })
     } else {
         None
@@ -200,6 +230,7 @@ async fn detect_spring_boot(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "./mvnw".to_string(),
             suggested_args: vec!["spring-boot:run".to_string()],
             suggested_port: Some(8080),
+            available_scripts: vec![],
         })
     } else {
         None
@@ -232,6 +263,7 @@ async fn detect_django(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "python".to_string(),
             suggested_args: vec!["manage.py".to_string(), "runserver".to_string()],
             suggested_port: Some(8000),
+            available_scripts: vec![],
         })
     } else {
         None
@@ -271,6 +303,7 @@ async fn detect_express(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "node".to_string(),
             suggested_args: vec!["server.js".to_string()],
             suggested_port: Some(3000),
+            available_scripts: vec![],
         })
     } else {
         None
@@ -305,103 +338,539 @@ async fn detect_flask(path: &Path) -> Option<FrameworkDetection> {
             suggested_command: "flask".to_string(),
             suggested_args: vec!["run".to_string()],
             suggested_port: Some(5000),
+            available_scripts: vec![],
+        })
+    } else {
+        None
+    }
+}
+
+async fn detect_rust(path: &Path) -> Option<FrameworkDetection> {
+    let mut detected_files = Vec::new();
+    let mut confidence = 0.0;
+
+    // Check for Cargo.toml with a package (virtual workspace manifests have none)
+    let manifest = fs::read_to_string(path.join("Cargo.toml")).await.ok()?;
+    if manifest.contains("[package]") {
+        detected_files.push("Cargo.toml".to_string());
+        confidence += 0.6;
+    }
+
+    // Check for a binary entry point
+    if confidence > 0.0 && path.join("src/main.rs").exists() {
+        detected_files.push("src/main.rs".to_string());
+        confidence += 0.35;
+    }
+
+    // Web frameworks have well-known default ports
+    let suggested_port = if manifest.contains("actix-web") {
+        Some(8080)
+    } else if manifest.contains("rocket") {
+        Some(8000)
+    } else if manifest.contains("axum") {
+        Some(3000)
+    } else {
+        None
+    };
+
+    if confidence > 0.0 {
+        Some(FrameworkDetection {
+            framework_type: FrameworkType::Rust,
+            confidence,
+            detected_files,
+            suggested_command: "cargo".to_string(),
+            suggested_args: vec!["run".to_string()],
+            suggested_port,
+            available_scripts: vec![],
+        })
+    } else {
+        None
+    }
+}
+
+async fn detect_go(path: &Path) -> Option<FrameworkDetection> {
+    let mut detected_files = Vec::new();
+    let mut confidence = 0.0;
+
+    // Check for go.mod
+    if path.join("go.mod").exists() {
+        detected_files.push("go.mod".to_string());
+        confidence += 0.6;
+    }
+
+    // Check for main.go with a main package
+    if let Ok(contents) = fs::read_to_string(path.join("main.go")).await {
+        if contents.contains("package main") {
+            detected_files.push("main.go".to_string());
+            confidence += 0.35;
+        }
+    }
+
+    if confidence > 0.0 {
+        Some(FrameworkDetection {
+            framework_type: FrameworkType::Go,
+            confidence,
+            detected_files,
+            suggested_command: "go".to_string(),
+            suggested_args: vec!["run".to_string(), ".".to_string()],
+            suggested_port: Some(8080),
+            available_scripts: vec![],
+        })
+    } else {
+        None
+    }
+}
+
+async fn detect_rails(path: &Path) -> Option<FrameworkDetection> {
+    let mut detected_files = Vec::new();
+    let mut confidence = 0.0;
+
+    // Check for Gemfile with rails
+    if let Ok(contents) = fs::read_to_string(path.join("Gemfile")).await {
+        if contents.contains("\"rails\"") || contents.contains("'rails'") {
+            detected_files.push("Gemfile".to_string());
+            confidence += 0.5;
+        }
+    }
+
+    // Check for config/application.rb
+    if path.join("config/application.rb").exists() {
+        detected_files.push("config/application.rb".to_string());
+        confidence += 0.45;
+    }
+
+    if confidence > 0.0 {
+        Some(FrameworkDetection {
+            framework_type: FrameworkType::Rails,
+            confidence,
+            detected_files,
+            suggested_command: "bin/rails".to_string(),
+            suggested_args: vec!["server".to_string()],
+            suggested_port: Some(3000),
+            available_scripts: vec![],
         })
     } else {
         None
     }
 }
 
+async fn detect_laravel(path: &Path) -> Option<FrameworkDetection> {
+    let mut detected_files = Vec::new();
+    let mut confidence = 0.0;
+
+    // Check for artisan
+    if path.join("artisan").exists() {
+        detected_files.push("artisan".to_string());
+        confidence += 0.9;
+    }
+
+    // Check for composer.json with laravel/framework
+    if let Ok(contents) = fs::read_to_string(path.join("composer.json")).await {
+        if contents.contains("laravel/framework") {
+            detected_files.push("composer.json".to_string());
+            confidence += 0.05;
+        }
+    }
+
+    if confidence > 0.0 {
+        Some(FrameworkDetection {
+            framework_type: FrameworkType::Laravel,
+            confidence,
+            detected_files,
+            suggested_command: "php".to_string(),
+            suggested_args: vec!["artisan".to_string(), "serve".to_string()],
+            suggested_port: Some(8000),
+            available_scripts: vec![],
+        })
+    } else {
+        None
+    }
+}
+
+/// Dev scripts in order of preference
+const PREFERRED_SCRIPTS: [&str; 4] = ["dev", "start:dev", "start", "serve"];
+
+/// .env keys that commonly hold the listening port
+const PORT_ENV_KEYS: [&str; 4] = ["PORT", "APP_PORT", "SERVER_PORT", "HTTP_PORT"];
+
+/// The parts of package.json that detection cares about
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageJson {
+    #[serde(default)]
+    scripts: BTreeMap<String, String>,
+    /// Corepack field, e.g. "pnpm@9.1.0"
+    package_manager: Option<String>,
+}
+
+/// Read package.json; malformed files are logged and treated as absent
+async fn read_package_json(path: &Path) -> Option<PackageJson> {
+    let contents = fs::read_to_string(path.join("package.json")).await.ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(package) => Some(package),
+        Err(e) => {
+            tracing::debug!(
+                "Ignoring malformed package.json in {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Refine a detection with the project's own scripts and port settings
+async fn refine_detection(path: &Path, detection: &mut FrameworkDetection) {
+    if let Some(package) = read_package_json(path).await {
+        detection.available_scripts = package.scripts.keys().cloned().collect();
+
+        let is_node = matches!(
+            detection.framework_type,
+            FrameworkType::NextJs | FrameworkType::Vite | FrameworkType::Express
+        );
+        if let Some(script) = is_node.then(|| pick_script(&package.scripts)).flatten() {
+            detection.suggested_command = node_package_manager(path, &package).await;
+            detection.suggested_args = vec!["run".to_string(), script.to_string()];
+
+            // A port spelled out in the script beats anything in .env
+            if let Some(port) = port_from_script(&package.scripts[script]) {
+                detection.suggested_port = Some(port);
+                return;
+            }
+        }
+    }
+
+    let env_vars = parse_env_file(path).await;
+    if let Some(port) = PORT_ENV_KEYS
+        .iter()
+        .find_map(|key| env_vars.get(*key).and_then(|v| v.parse().ok()))
+    {
+        detection.suggested_port = Some(port);
+    }
+}
+
+/// Pick the most suitable script to run in development
+fn pick_script(scripts: &BTreeMap<String, String>) -> Option<&'static str> {
+    PREFERRED_SCRIPTS
+        .into_iter()
+        .find(|name| scripts.contains_key(*name))
+}
+
+/// Extract a port from a script such as `next dev -p 4000` or `PORT=4000 node .`
+fn port_from_script(script: &str) -> Option<u16> {
+    let tokens: Vec<&str> = script.split_whitespace().collect();
+
+    tokens.iter().enumerate().find_map(|(i, token)| {
+        if let Some(value) = token
+            .strip_prefix("--port=")
+            .or_else(|| token.strip_prefix("-p="))
+            .or_else(|| token.strip_prefix("PORT="))
+        {
+            return value.parse().ok();
+        }
+        if matches!(*token, "--port" | "-p") {
+            return tokens.get(i + 1).and_then(|v| v.parse().ok());
+        }
+        None
+    })
+}
+
+/// Node package manager for a project, checking parent directories so
+/// monorepo packages pick up the workspace lockfile
+async fn node_package_manager(path: &Path, package: &PackageJson) -> String {
+    if let Some(manager) = package
+        .package_manager
+        .as_deref()
+        .and_then(|spec| spec.split('@').next())
+        .filter(|name| matches!(*name, "npm" | "pnpm" | "yarn" | "bun"))
+    {
+        return manager.to_string();
+    }
+
+    for dir in path.ancestors() {
+        for (lockfile, manager) in [
+            ("pnpm-lock.yaml", "pnpm"),
+            ("yarn.lock", "yarn"),
+            ("bun.lockb", "bun"),
+            ("package-lock.json", "npm"),
+        ] {
+            if dir.join(lockfile).exists() {
+                return manager.to_string();
+            }
+        }
+        // Don't wander out of the repository
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    "npm".to_string()
+}
+
+/// Default number of directory levels below the root to scan
+pub const DEFAULT_SCAN_DEPTH: usize = 3;
+
+/// Directories visited before a scan gives up and reports truncation
+pub const MAX_SCAN_DIRECTORIES: usize = 2000;
+
+/// Directories that never contain projects worth detecting
+const SKIPPED_DIRS: [&str; 5] = ["node_modules", "dist", "build", "target", "__pycache__"];
+
 /// Scan a directory for projects (supports monorepos)
+///
+/// Walks up to `max_depth` levels below `dir_path` (default
+/// [`DEFAULT_SCAN_DEPTH`]), skipping directories ignored by the root
+/// `.gitignore`. Cargo and Go workspace members are always included.
 pub async fn scan_directory_for_projects(
     dir_path: &str,
-) -> SentinelResult<Vec<crate::core::process_config::DetectedProject>> {
-    use crate::core::process_config::DetectedProject;
+    max_depth: Option<usize>,
+) -> SentinelResult<ProjectScanResult> {
+    scan_projects(
+        Path::new(dir_path),
+        max_depth.unwrap_or(DEFAULT_SCAN_DEPTH),
+        MAX_SCAN_DIRECTORIES,
+    )
+    .await
+}
 
-    let path = Path::new(dir_path);
+async fn scan_projects(
+    root: &Path,
+    max_depth: usize,
+    max_directories: usize,
+) -> SentinelResult<ProjectScanResult> {
+    let gitignore = Gitignore::load(root).await;
     let mut projects = Vec::new();
+    let mut truncated = false;
+    let mut directories_visited = 0;
 
-    // First, check the root directory itself
-    if let Ok(detection) = detect_framework(dir_path).await {
-        if detection.confidence > 0.0 {
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("project")
-                .to_string();
-
-            // Parse .env file for environment variables
-            let env_vars = parse_env_file(path).await;
-
-            projects.push(DetectedProject {
-                path: dir_path.to_string(),
-                name,
-                framework_type: detection.framework_type,
-                confidence: detection.confidence,
-                suggested_command: detection.suggested_command,
-                suggested_args: detection.suggested_args,
-                suggested_port: detection.suggested_port,
-                package_manager: detect_package_manager(path).await,
-                detected_files: detection.detected_files,
-                env_vars,
-            });
+    let mut seen = HashSet::from([root.to_path_buf()]);
+    let mut queue = VecDeque::from([(root.to_path_buf(), 0)]);
+
+    while let Some((dir, depth)) = queue.pop_front() {
+        if directories_visited == max_directories {
+            truncated = true;
+            tracing::warn!(
+                "Project scan of {} stopped after {} directories",
+                root.display(),
+                max_directories
+            );
+            break;
         }
-    }
+        directories_visited += 1;
 
-    // Then, scan subdirectories (for monorepos)
-    if let Ok(mut entries) = fs::read_dir(path).await {
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            if let Ok(metadata) = entry.metadata().await {
-                if metadata.is_dir() {
-                    let subdir_path = entry.path();
-
-                    // Skip common non-project directories
-                    if let Some(dir_name) = subdir_path.file_name().and_then(|n| n.to_str()) {
-                        if dir_name.starts_with('.')
-                            || dir_name == "node_modules"
-                            || dir_name == "dist"
-                            || dir_name == "build"
-                            || dir_name == "target"
-                            || dir_name == "__pycache__"
-                        {
-                            continue;
-                        }
-                    }
-
-                    // Try to detect framework in subdirectory
-                    if let Some(subdir_str) = subdir_path.to_str() {
-                        if let Ok(detection) = detect_framework(subdir_str).await {
-                            if detection.confidence > 0.3 {
-                                // Only include if confidence is decent
-                                let name = subdir_path
-                                    .file_name()
-                                    .and_then(|n| n.to_str())
-                                    .unwrap_or("project")
-                                    .to_string();
-
-                                // Parse .env file for environment variables
-                                let env_vars = parse_env_file(&subdir_path).await;
-
-                                projects.push(DetectedProject {
-                                    path: subdir_str.to_string(),
-                                    name,
-                                    framework_type: detection.framework_type,
-                                    confidence: detection.confidence,
-                                    suggested_command: detection.suggested_command,
-                                    suggested_args: detection.suggested_args,
-                                    suggested_port: detection.suggested_port,
-                                    package_manager: detect_package_manager(&subdir_path).await,
-                                    detected_files: detection.detected_files,
-                                    env_vars,
-                                });
-                            }
-                        }
-                    }
+        // The root is reported on any signal; nested directories need a decent match
+        let threshold = if depth == 0 { 0.0 } else { 0.3 };
+        if let Some(dir_str) = dir.to_str() {
+            if let Ok(detection) = detect_framework(dir_str).await {
+                if detection.confidence > threshold {
+                    projects.push(build_detected_project(root, &dir, detection).await);
                 }
             }
         }
+
+        // Cargo and Go workspace members, wherever they live
+        for member in workspace_members(&dir).await {
+            if seen.insert(member.clone()) {
+                queue.push_back((member, depth + 1));
+            }
+        }
+
+        if depth >= max_depth {
+            continue;
+        }
+
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        let mut subdirs = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            // Symlinks are not followed, so cycles cannot occur
+            if !entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let subdir_path = entry.path();
+
+            // Skip common non-project directories
+            let Some(dir_name) = subdir_path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if dir_name.starts_with('.') || SKIPPED_DIRS.contains(&dir_name) {
+                continue;
+            }
+            if gitignore.is_ignored(&relative_path(root, &subdir_path), true) {
+                continue;
+            }
+
+            subdirs.push(subdir_path);
+        }
+
+        // Sort for a stable, predictable result order
+        subdirs.sort();
+        for subdir in subdirs {
+            if seen.insert(subdir.clone()) {
+                queue.push_back((subdir, depth + 1));
+            }
+        }
     }
 
-    Ok(projects)
+    Ok(ProjectScanResult {
+        projects,
+        truncated,
+        directories_visited,
+    })
+}
+
+/// Path of `path` below `root` with `/` separators, or "." for the root
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if relative.is_empty() {
+        ".".to_string()
+    } else {
+        relative
+    }
+}
+
+async fn build_detected_project(
+    root: &Path,
+    path: &Path,
+    detection: FrameworkDetection,
+) -> DetectedProject {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project")
+        .to_string();
+
+    DetectedProject {
+        path: path.to_string_lossy().to_string(),
+        relative_path: relative_path(root, path),
+        name,
+        framework_type: detection.framework_type,
+        confidence: detection.confidence,
+        suggested_command: detection.suggested_command,
+        suggested_args: detection.suggested_args,
+        suggested_port: detection.suggested_port,
+        package_manager: detect_package_manager(path).await,
+        detected_files: detection.detected_files,
+        // Parse .env file for environment variables
+        env_vars: parse_env_file(path).await,
+        available_scripts: detection.available_scripts,
+    }
+}
+
+/// Member directories of a Cargo workspace or go.work file at `path`
+async fn workspace_members(path: &Path) -> Vec<PathBuf> {
+    let mut patterns = Vec::new();
+
+    if let Ok(manifest) = fs::read_to_string(path.join("Cargo.toml")).await {
+        patterns.extend(parse_cargo_workspace_members(&manifest));
+    }
+    if let Ok(go_work) = fs::read_to_string(path.join("go.work")).await {
+        patterns.extend(parse_go_work_uses(&go_work));
+    }
+
+    let mut members = Vec::new();
+    for pattern in patterns {
+        for member in expand_member_pattern(path, &pattern).await {
+            if member != path && !members.contains(&member) {
+                members.push(member);
+            }
+        }
+    }
+    members
+}
+
+/// Extract `members = [...]` from the `[workspace]` table of a Cargo.toml
+fn parse_cargo_workspace_members(manifest: &str) -> Vec<String> {
+    let Some(start) = manifest.find("[workspace]") else {
+        return Vec::new();
+    };
+    let table = &manifest[start + "[workspace]".len()..];
+    // Stop at the next table header
+    let table = match table.find("\n[") {
+        Some(end) => &table[..end],
+        None => table,
+    };
+
+    let Some(members) = table
+        .lines()
+        .position(|line| line.trim_start().starts_with("members"))
+        .map(|i| table.lines().skip(i).collect::<Vec<_>>().join("\n"))
+    else {
+        return Vec::new();
+    };
+    let Some(open) = members.find('[') else {
+        return Vec::new();
+    };
+    let Some(close) = members[open..].find(']') else {
+        return Vec::new();
+    };
+
+    members[open + 1..open + close]
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.split(','))
+        .map(|item| item.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Extract the directories listed by `use` directives in a go.work file
+fn parse_go_work_uses(go_work: &str) -> Vec<String> {
+    let mut uses = Vec::new();
+    let mut in_block = false;
+
+    for line in go_work.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                uses.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                uses.push(rest.to_string());
+            }
+        }
+    }
+
+    uses
+}
+
+/// Resolve a workspace member path, expanding a trailing `/*`
+async fn expand_member_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./");
+
+    let Some(parent) = pattern.strip_suffix("/*") else {
+        let member = root.join(pattern);
+        return if member.is_dir() {
+            vec![member]
+        } else {
+            Vec::new()
+        };
+    };
+
+    let mut members = Vec::new();
+    if let Ok(mut entries) = fs::read_dir(root.join(parent)).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.metadata().await.is_ok_and(|m| m.is_dir()) {
+                members.push(entry.path());
+            }
+        }
+    }
+    members.sort();
+    members
 }
 
 /// Detect the package manager used in a project
@@ -424,41 +893,27 @@ async fn detect_package_manager(path: &Path) -> Option<String> {
     if path.join("build.gradle").exists() || path.join("build.gradle.kts").exists() {
         return Some("gradle".to_string());
     }
+    if path.join("Cargo.toml").exists() {
+        return Some("cargo".to_string());
+    }
+    if path.join("go.mod").exists() {
+        return Some("go".to_string());
+    }
+    if path.join("Gemfile").exists() {
+        return Some("bundler".to_string());
+    }
+    if path.join("composer.json").exists() {
+        return Some("composer".to_string());
+    }
     None
 }
 
 /// Parse .env file and return environment variables
 async fn parse_env_file(path: &Path) -> HashMap<String, String> {
-    let mut env_vars = HashMap::new();
-
-    let env_path = path.join(".env");
-    if let Ok(content) = fs::read_to_string(&env_path).await {
-        for line in content.lines() {
-            let line = line.trim();
-
-            // Skip comments and empty lines
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-
-            // Parse KEY=VALUE format
-            if let Some((key, value)) = line.split_once('=') {
-                let key = key.trim().to_string();
-                let mut value = value.trim().to_string();
-
-                // Remove quotes if present
-                if (value.starts_with('"') && value.ends_with('"'))
-                    || (value.starts_with('\'') && value.ends_with('\''))
-                {
-                    value = value[1..value.len() - 1].to_string();
-                }
-
-                env_vars.insert(key, value);
-            }
-        }
-    }
-
-    env_vars
+    fs::read_to_string(path.join(".env"))
+        .await
+        .map(|content| env_file::parse(&content))
+        .unwrap_or_default()
 }
 
 /// Get built-in framework templates
@@ -557,5 +1012,433 @@ pub fn get_framework_templates() -> Vec<crate::core::process_config::ProcessTemp
             health_check_url: Some("http://localhost:5000".to_string()),
             icon: "🌶️".to_string(),
         },
+        ProcessTemplate {
+            name: "Rust (Cargo Watch)".to_string(),
+            framework_type: FrameworkType::Rust,
+            description: "Rebuild and rerun a Cargo binary on change".to_string(),
+            command: "cargo".to_string(),
+            args: vec!["watch".to_string(), "-x".to_string(), "run".to_string()],
+            default_port: None,
+            default_env_vars: HashMap::from([("RUST_LOG".to_string(), "info".to_string())]),
+            health_check_url: None,
+            icon: "🦀".to_string(),
+        },
+        ProcessTemplate {
+            name: "Go Service".to_string(),
+            framework_type: FrameworkType::Go,
+            description: "Go module with a main package".to_string(),
+            command: "go".to_string(),
+            args: vec!["run".to_string(), ".".to_string()],
+            default_port: Some(8080),
+            default_env_vars: HashMap::new(),
+            health_check_url: Some("http://localhost:8080".to_string()),
+            icon: "🐹".to_string(),
+        },
+        ProcessTemplate {
+            name: "Rails Server".to_string(),
+            framework_type: FrameworkType::Rails,
+            description: "Ruby on Rails web framework".to_string(),
+            command: "bin/rails".to_string(),
+            args: vec!["server".to_string()],
+            default_port: Some(3000),
+            default_env_vars: HashMap::from([("RAILS_ENV".to_string(), "development".to_string())]),
+            health_check_url: Some("http://localhost:3000/up".to_string()),
+            icon: "💎".to_string(),
+        },
+        ProcessTemplate {
+            name: "Laravel Development Server".to_string(),
+            framework_type: FrameworkType::Laravel,
+            description: "PHP web framework".to_string(),
+            command: "php".to_string(),
+            args: vec!["artisan".to_string(), "serve".to_string()],
+            default_port: Some(8000),
+            default_env_vars: HashMap::from([("APP_ENV".to_string(), "local".to_string())]),
+            health_check_url: Some("http://localhost:8000".to_string()),
+            icon: "🔺".to_string(),
+        },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs as std_fs;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, file: &str, contents: &str) {
+        let path = dir.join(file);
+        std_fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std_fs::write(path, contents).unwrap();
+    }
+
+    fn path_str(dir: &Path) -> &str {
+        dir.to_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_detect_rust_binary() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "Cargo.toml",
+            "[package]\nname = \"api\"\n\n[dependencies]\naxum = \"0.7\"\n",
+        );
+        write(dir.path(), "src/main.rs", "fn main() {}\n");
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.framework_type, FrameworkType::Rust);
+        assert_eq!(detection.suggested_args, vec!["run"]);
+        assert_eq!(detection.suggested_port, Some(3000));
+        assert_eq!(
+            detect_package_manager(dir.path()).await.as_deref(),
+            Some("cargo")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_virtual_cargo_workspace_is_not_a_project() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+
+        assert!(detect_rust(dir.path()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_detect_go() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "go.mod", "module example.com/api\n\ngo 1.22\n");
+        write(dir.path(), "main.go", "package main\n\nfunc main() {}\n");
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.framework_type, FrameworkType::Go);
+        assert_eq!(detection.suggested_command, "go");
+        assert_eq!(detection.suggested_port, Some(8080));
+    }
+
+    #[tokio::test]
+    async fn test_detect_rails() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "Gemfile",
+            "source \"https://rubygems.org\"\ngem \"rails\", \"~> 7.1\"\n",
+        );
+        write(dir.path(), "config/application.rb", "module App; end\n");
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.framework_type, FrameworkType::Rails);
+        assert_eq!(detection.suggested_command, "bin/rails");
+        assert_eq!(detection.detected_files.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_detect_laravel() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "artisan", "#!/usr/bin/env php\n");
+        write(
+            dir.path(),
+            "composer.json",
+            r#"{"require": {"laravel/framework": "^11.0"}}"#,
+        );
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.framework_type, FrameworkType::Laravel);
+        assert_eq!(detection.suggested_args, vec!["artisan", "serve"]);
+        assert_eq!(
+            detect_package_manager(dir.path()).await.as_deref(),
+            Some("composer")
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_workspace_members() {
+        let manifest = r#"
+[workspace]
+resolver = "2"
+members = [
+    "crates/*",   # libraries
+    "apps/server",
+]
+
+[workspace.dependencies]
+serde = "1"
+"#;
+        assert_eq!(
+            parse_cargo_workspace_members(manifest),
+            vec!["crates/*", "apps/server"]
+        );
+        assert!(parse_cargo_workspace_members("[package]\nname = \"a\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_go_work_uses() {
+        let go_work = "go 1.22\n\nuse ./tools\n\nuse (\n\t./api // service\n\t./worker\n)\n";
+        assert_eq!(
+            parse_go_work_uses(go_work),
+            vec!["./tools", "./api", "./worker"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_finds_cargo_workspace_members() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        for name in ["api", "worker"] {
+            write(
+                dir.path(),
+                &format!("crates/{}/Cargo.toml", name),
+                &format!("[package]\nname = \"{}\"\n", name),
+            );
+            write(
+                dir.path(),
+                &format!("crates/{}/src/main.rs", name),
+                "fn main() {}\n",
+            );
+        }
+
+        let projects = scan_directory_for_projects(path_str(dir.path()), None)
+            .await
+            .unwrap()
+            .projects;
+
+        let mut names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["api", "worker"]);
+        assert!(projects
+            .iter()
+            .all(|p| p.framework_type == FrameworkType::Rust));
+    }
+
+    #[tokio::test]
+    async fn test_scan_finds_go_work_members() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "go.work",
+            "go 1.22\n\nuse (\n\t./services/api\n)\n",
+        );
+        write(
+            dir.path(),
+            "services/api/go.mod",
+            "module example.com/api\n",
+        );
+        write(
+            dir.path(),
+            "services/api/main.go",
+            "package main\n\nfunc main() {}\n",
+        );
+
+        let projects = scan_directory_for_projects(path_str(dir.path()), None)
+            .await
+            .unwrap()
+            .projects;
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "api");
+        assert_eq!(projects[0].framework_type, FrameworkType::Go);
+    }
+
+    /// apps/web (Next.js), services/api/auth (Go), an ignored `out/` with a
+    /// decoy package.json, and a project four levels down
+    fn nested_fixture() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), ".gitignore", "# build output\n/out/\n*.tmp\n");
+        write(
+            dir.path(),
+            "apps/web/package.json",
+            r#"{"dependencies": {"next": "14.0.0"}}"#,
+        );
+        write(
+            dir.path(),
+            "apps/web/next.config.js",
+            "module.exports = {}\n",
+        );
+        write(dir.path(), "services/api/auth/go.mod", "module auth\n");
+        write(
+            dir.path(),
+            "services/api/auth/main.go",
+            "package main\n\nfunc main() {}\n",
+        );
+        write(
+            dir.path(),
+            "out/package.json",
+            r#"{"dependencies": {"express": "4.0.0"}}"#,
+        );
+        write(
+            dir.path(),
+            "deep/a/b/c/package.json",
+            r#"{"dependencies": {"express": "4.0.0"}}"#,
+        );
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_scan_recurses_and_respects_gitignore() {
+        let dir = nested_fixture();
+
+        let result = scan_directory_for_projects(path_str(dir.path()), None)
+            .await
+            .unwrap();
+
+        let found: Vec<(&str, FrameworkType)> = result
+            .projects
+            .iter()
+            .map(|p| (p.relative_path.as_str(), p.framework_type.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("apps/web", FrameworkType::NextJs),
+                ("services/api/auth", FrameworkType::Go),
+            ]
+        );
+        assert!(!result.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_scan_depth_is_configurable() {
+        let dir = nested_fixture();
+
+        let shallow = scan_directory_for_projects(path_str(dir.path()), Some(2))
+            .await
+            .unwrap();
+        let names: Vec<&str> = shallow.projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["web"]);
+
+        let deep = scan_directory_for_projects(path_str(dir.path()), Some(4))
+            .await
+            .unwrap();
+        assert!(deep
+            .projects
+            .iter()
+            .any(|p| p.relative_path == "deep/a/b/c"));
+        assert!(!deep.projects.iter().any(|p| p.relative_path == "out"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_truncates_at_directory_limit() {
+        let dir = nested_fixture();
+
+        let result = scan_projects(dir.path(), DEFAULT_SCAN_DEPTH, 3)
+            .await
+            .unwrap();
+
+        assert!(result.truncated);
+        assert_eq!(result.directories_visited, 3);
+    }
+
+    #[tokio::test]
+    async fn test_root_project_has_dot_relative_path() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "go.mod", "module example.com/api\n");
+
+        let result = scan_directory_for_projects(path_str(dir.path()), None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.projects.len(), 1);
+        assert_eq!(result.projects[0].relative_path, ".");
+    }
+
+    #[tokio::test]
+    async fn test_package_scripts_drive_suggested_command() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "package.json",
+            r#"{
+                "dependencies": {"next": "14.0.0"},
+                "scripts": {"start": "next start", "start:dev": "next dev --port 4000"}
+            }"#,
+        );
+        write(dir.path(), "pnpm-lock.yaml", "lockfileVersion: '9.0'\n");
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.framework_type, FrameworkType::NextJs);
+        assert_eq!(detection.suggested_command, "pnpm");
+        assert_eq!(detection.suggested_args, vec!["run", "start:dev"]);
+        assert_eq!(detection.suggested_port, Some(4000));
+        assert_eq!(detection.available_scripts, vec!["start", "start:dev"]);
+    }
+
+    #[tokio::test]
+    async fn test_env_port_used_when_script_has_none() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "package.json",
+            r#"{
+                "dependencies": {"express": "4.0.0"},
+                "scripts": {"serve": "node server.js", "dev": "nodemon server.js"}
+            }"#,
+        );
+        write(dir.path(), "yarn.lock", "");
+        write(dir.path(), ".env", "# local\nPORT=5050\n");
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.suggested_command, "yarn");
+        assert_eq!(detection.suggested_args, vec!["run", "dev"]);
+        assert_eq!(detection.suggested_port, Some(5050));
+    }
+
+    #[tokio::test]
+    async fn test_workspace_lockfile_and_package_manager_field() {
+        let dir = TempDir::new().unwrap();
+        std_fs::create_dir(dir.path().join(".git")).unwrap();
+        write(dir.path(), "pnpm-lock.yaml", "");
+        write(
+            dir.path(),
+            "apps/web/package.json",
+            r#"{"devDependencies": {"vite": "5.0.0"}, "scripts": {"dev": "vite"}}"#,
+        );
+        write(
+            dir.path(),
+            "apps/docs/package.json",
+            r#"{"packageManager": "yarn@4.1.0", "devDependencies": {"vite": "5.0.0"}, "scripts": {"dev": "vite"}}"#,
+        );
+
+        let web = detect_framework(path_str(&dir.path().join("apps/web")))
+            .await
+            .unwrap();
+        assert_eq!(web.suggested_command, "pnpm");
+
+        let docs = detect_framework(path_str(&dir.path().join("apps/docs")))
+            .await
+            .unwrap();
+        assert_eq!(docs.suggested_command, "yarn");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_package_json_keeps_detection() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "package.json",
+            r#"{"dependencies": {"next": "14.0.0"}, "scripts": {"dev": "#,
+        );
+        write(dir.path(), "next.config.js", "module.exports = {}\n");
+
+        let detection = detect_framework(path_str(dir.path())).await.unwrap();
+        assert_eq!(detection.framework_type, FrameworkType::NextJs);
+        assert_eq!(detection.suggested_command, "npm");
+        assert_eq!(detection.suggested_args, vec!["run", "dev"]);
+        assert!(detection.available_scripts.is_empty());
+    }
+
+    #[test]
+    fn test_port_from_script() {
+        assert_eq!(port_from_script("next dev -p 4000"), Some(4000));
+        assert_eq!(port_from_script("vite --port=5174 --host"), Some(5174));
+        assert_eq!(port_from_script("PORT=8081 node server.js"), Some(8081));
+        assert_eq!(port_from_script("turbo run dev --filter web"), None);
+        assert_eq!(port_from_script("vite --port"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Minimal `.gitignore` matcher used when scanning project trees.
+//!
+//! Supports comments, negation, directory-only and anchored patterns, and the
+//! `*`, `**`, `?` and `[...]` wildcards. Only the root `.gitignore` is read.
+
+use std::path::Path;
+use tokio::fs;
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: String,
+    negated: bool,
+    anchored: bool,
+    dir_only: bool,
+}
+
+/// Parsed set of ignore rules; later rules override earlier ones
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Gitignore {
+    rules: Vec<Rule>,
+}
+
+impl Gitignore {
+    /// Load `<root>/.gitignore`; a missing file yields an empty rule set
+    pub(crate) async fn load(root: &Path) -> Self {
+        fs::read_to_string(root.join(".gitignore"))
+            .await
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn parse(contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                // A slash anywhere but the end anchors the pattern to the root
+                let anchored = line.contains('/');
+                let pattern = line.trim_start_matches('/').to_string();
+                if pattern.is_empty() {
+                    return None;
+                }
+
+                Some(Rule {
+                    pattern,
+                    negated,
+                    anchored,
+                    dir_only,
+                })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Check a path relative to the root, using `/` separators
+    pub(crate) fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let target = if rule.anchored { relative } else { name };
+            if glob_match(rule.pattern.as_bytes(), target.as_bytes()) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Match `text` against a glob; `*` and `?` never cross a `/`
+pub(crate) fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let rest = &pattern[2..];
+            match rest.strip_prefix(b"/") {
+                // `**/` matches zero or more whole directories
+                Some(rest) => {
+                    glob_match(rest, text)
+                        || (0..text.len())
+                            .any(|i| text[i] == b'/' && glob_match(rest, &text[i + 1..]))
+                }
+                None => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+            }
+        }
+        Some(b'*') => {
+            let end = text.iter().position(|c| *c == b'/').unwrap_or(text.len());
+            (0..=end).any(|i| glob_match(&pattern[1..], &text[i..]))
+        }
+        Some(b'?') => {
+            text.first().is_some_and(|c| *c != b'/') && glob_match(&pattern[1..], &text[1..])
+        }
+        Some(b'[') => match (text.first(), match_class(&pattern[1..], text.first())) {
+            (Some(_), Some((true, rest))) => glob_match(rest, &text[1..]),
+            (_, Some((false, _))) | (None, _) => false,
+            // Unterminated class: treat `[` literally
+            (Some(c), None) => *c == b'[' && glob_match(&pattern[1..], &text[1..]),
+        },
+        Some(b'\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Match one character against a `[...]` class (pattern starts after `[`)
+///
+/// Returns whether it matched and the pattern after the closing `]`, or
+/// `None` when the class is not terminated.
+fn match_class<'a>(pattern: &'a [u8], c: Option<&u8>) -> Option<(bool, &'a [u8])> {
+    let (negated, mut body) = match pattern.first() {
+        Some(b'!') | Some(b'^') => (true, &pattern[1..]),
+        _ => (false, pattern),
+    };
+
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        match body {
+            [] => return None,
+            [b']', rest @ ..] if !first => {
+                let matched = c.is_some_and(|c| *c != b'/') && matched != negated;
+                return Some((matched, rest));
+            }
+            [lo, b'-', hi, rest @ ..] if *hi != b']' => {
+                matched |= c.is_some_and(|c| (*lo..=*hi).contains(c));
+                body = rest;
+            }
+            [ch, rest @ ..] => {
+                matched |= c == Some(ch);
+                body = rest;
+            }
+        }
+        first = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unanchored_patterns_match_any_depth() {
+        let ignore = Gitignore::parse("# build output\nbuild/\n*.log\n");
+
+        assert!(ignore.is_ignored("build", true));
+        assert!(ignore.is_ignored("apps/web/build", true));
+        assert!(!ignore.is_ignored("build", false));
+        assert!(ignore.is_ignored("apps/web/debug.log", false));
+        assert!(!ignore.is_ignored("apps/web", true));
+    }
+
+    #[test]
+    fn test_anchored_patterns_match_from_root() {
+        let ignore = Gitignore::parse("/out\ndocs/generated\n");
+
+        assert!(ignore.is_ignored("out", true));
+        assert!(!ignore.is_ignored("apps/out", true));
+        assert!(ignore.is_ignored("docs/generated", true));
+        assert!(!ignore.is_ignored("apps/docs/generated", true));
+    }
+
+    #[test]
+    fn test_negation_and_double_star() {
+        let ignore = Gitignore::parse("**/fixtures/*\n!**/fixtures/keep\n");
+
+        assert!(ignore.is_ignored("fixtures/tmp", true));
+        assert!(ignore.is_ignored("services/api/fixtures/tmp", true));
+        assert!(!ignore.is_ignored("services/api/fixtures/keep", true));
+    }
+
+    #[test]
+    fn test_character_classes() {
+        let ignore = Gitignore::parse("*.py[cod]\ntmp[0-9]\n");
+
+        assert!(ignore.is_ignored("module.pyc", false));
+        assert!(!ignore.is_ignored("module.py", false));
+        assert!(ignore.is_ignored("tmp3", true));
+        assert!(!ignore.is_ignored("tmpx", true));
+    }
+}
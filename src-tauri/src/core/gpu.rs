@@ -0,0 +1,471 @@
+//! GPU usage, from whatever source the machine has.
+//!
+//! Each source is a [`GpuCollector`]: `nvidia-smi` for NVIDIA cards, sysfs
+//! under `/sys/class/drm` for AMD and Intel cards on Linux, and the PDH GPU
+//! counters (read through `typeperf`) on Windows. macOS has no collector yet,
+//! as its GPU statistics are only exposed through Metal and IOKit, so it
+//! reports no GPUs. Sources that aren't there report nothing.
+//!
+//! `nvidia-smi` alone can take 100ms or more, so a [`GpuMonitor`] runs the
+//! collectors on their own cadence off the async runtime and hands out the
+//! latest results.
+
+use crate::models::GpuStats;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::debug;
+
+/// Time between two collections by the refresh loop.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// PCI vendor ID of NVIDIA, whose cards `nvidia-smi` reports.
+const NVIDIA_VENDOR: &str = "0x10de";
+
+/// A source of GPU statistics.
+pub trait GpuCollector: Send + Sync {
+    /// Reads the current usage of the GPUs this source knows about.
+    ///
+    /// Blocks while the source is read. Returns an empty list when the
+    /// source isn't available.
+    fn collect(&self) -> Vec<GpuStats>;
+}
+
+/// Collector for machines without a supported source.
+pub struct NoopCollector;
+
+impl GpuCollector for NoopCollector {
+    fn collect(&self) -> Vec<GpuStats> {
+        Vec::new()
+    }
+}
+
+/// Reads NVIDIA cards from `nvidia-smi`, if it is on the `PATH`.
+pub struct NvidiaSmiCollector;
+
+impl GpuCollector for NvidiaSmiCollector {
+    fn collect(&self) -> Vec<GpuStats> {
+        let output = Command::new("nvidia-smi")
+            .args([
+                "--query-gpu=name,utilization.gpu,memory.used,memory.total,temperature.gpu",
+                "--format=csv,noheader,nounits",
+            ])
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout))
+            }
+            Ok(output) => {
+                debug!("nvidia-smi exited with {}", output.status);
+                Vec::new()
+            }
+            Err(e) => {
+                debug!("nvidia-smi unavailable: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Reads AMD and Intel cards from the DRM sysfs tree.
+///
+/// amdgpu reports busy percentage, video memory and temperature; other
+/// drivers report less, and cards reporting nothing are left out. NVIDIA
+/// cards are left to [`NvidiaSmiCollector`].
+pub struct DrmCollector {
+    root: PathBuf,
+}
+
+impl DrmCollector {
+    /// Reads the cards under `/sys/class/drm`.
+    pub fn new() -> Self {
+        Self::with_root("/sys/class/drm")
+    }
+
+    /// Reads the cards under `root`, laid out like `/sys/class/drm`.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Default for DrmCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuCollector for DrmCollector {
+    fn collect(&self) -> Vec<GpuStats> {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+        // Connectors such as card0-DP-1 sit next to the cards
+        let mut cards: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| {
+                name.strip_prefix("card")
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .collect();
+        cards.sort();
+
+        cards
+            .iter()
+            .filter_map(|card| read_drm_card(&self.root.join(card).join("device"), card))
+            .collect()
+    }
+}
+
+/// Reads the Windows PDH GPU counters through `typeperf`.
+pub struct PdhCollector;
+
+impl GpuCollector for PdhCollector {
+    fn collect(&self) -> Vec<GpuStats> {
+        let output = Command::new("typeperf")
+            .args([
+                r"\GPU Engine(*engtype_3D)\Utilization Percentage",
+                r"\GPU Adapter Memory(*)\Dedicated Usage",
+                "-sc",
+                "1",
+            ])
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                parse_typeperf(&String::from_utf8_lossy(&output.stdout))
+            }
+            Ok(output) => {
+                debug!("typeperf exited with {}", output.status);
+                Vec::new()
+            }
+            Err(e) => {
+                debug!("typeperf unavailable: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// The collectors for this platform.
+pub fn default_collectors() -> Vec<Box<dyn GpuCollector>> {
+    if cfg!(target_os = "linux") {
+        vec![Box::new(NvidiaSmiCollector), Box::new(DrmCollector::new())]
+    } else if cfg!(windows) {
+        vec![Box::new(NvidiaSmiCollector), Box::new(PdhCollector)]
+    } else {
+        vec![Box::new(NoopCollector)]
+    }
+}
+
+/// Runs GPU collectors and keeps their latest results.
+///
+/// Clones share the results, so one clone can refresh while others read.
+#[derive(Clone)]
+pub struct GpuMonitor {
+    collectors: Arc<Vec<Box<dyn GpuCollector>>>,
+    latest: Arc<RwLock<Vec<GpuStats>>>,
+}
+
+impl GpuMonitor {
+    /// Creates a monitor with the collectors for this platform.
+    ///
+    /// Nothing is collected until the first [`refresh`](Self::refresh).
+    pub fn new() -> Self {
+        Self::with_collectors(default_collectors())
+    }
+
+    /// Creates a monitor with the given collectors.
+    pub fn with_collectors(collectors: Vec<Box<dyn GpuCollector>>) -> Self {
+        Self {
+            collectors: Arc::new(collectors),
+            latest: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// The GPUs found by the last refresh.
+    pub fn latest(&self) -> Vec<GpuStats> {
+        self.latest
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Runs every collector on a blocking thread and keeps the results.
+    pub async fn refresh(&self) {
+        let collectors = self.collectors.clone();
+        let gpus = tokio::task::spawn_blocking(move || {
+            collectors
+                .iter()
+                .flat_map(|collector| collector.collect())
+                .collect()
+        })
+        .await
+        .unwrap_or_default();
+        *self.latest.write().unwrap_or_else(|e| e.into_inner()) = gpus;
+    }
+}
+
+impl Default for GpuMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses `nvidia-smi --query-gpu=name,utilization.gpu,memory.used,memory.total,temperature.gpu
+/// --format=csv,noheader,nounits`.
+///
+/// Values a card doesn't support, such as `[N/A]`, are left out.
+pub fn parse_nvidia_smi(output: &str) -> Vec<GpuStats> {
+    output
+        .lines()
+        .filter_map(|line| {
+            // The name may contain commas, the numbers can't
+            let mut fields = line.rsplitn(5, ',').map(str::trim);
+            let temperature = fields.next()?.parse().ok();
+            let memory_total = fields.next()?.parse::<u64>().ok();
+            let memory_used = fields.next()?.parse::<u64>().ok();
+            let utilization = fields.next()?.parse().ok();
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            Some(GpuStats {
+                name: name.to_string(),
+                utilization,
+                // Reported in MiB
+                memory_used: memory_used.map(|mib| mib * 1024 * 1024),
+                memory_total: memory_total.map(|mib| mib * 1024 * 1024),
+                temperature,
+            })
+        })
+        .collect()
+}
+
+/// Reads the sysfs `device` directory of a DRM card.
+fn read_drm_card(device: &Path, card: &str) -> Option<GpuStats> {
+    let read = |file: &str| {
+        std::fs::read_to_string(device.join(file))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+
+    let vendor = read("vendor")?;
+    if vendor == NVIDIA_VENDOR {
+        return None;
+    }
+
+    let utilization = read("gpu_busy_percent").and_then(|value| value.parse().ok());
+    let memory_used = read("mem_info_vram_used").and_then(|value| value.parse().ok());
+    let memory_total = read("mem_info_vram_total").and_then(|value| value.parse().ok());
+    let temperature = std::fs::read_dir(device.join("hwmon"))
+        .ok()
+        .and_then(|hwmons| {
+            hwmons
+                .flatten()
+                .find_map(|hwmon| std::fs::read_to_string(hwmon.path().join("temp1_input")).ok())
+        })
+        .and_then(|millidegrees| millidegrees.trim().parse::<f32>().ok())
+        .map(|millidegrees| millidegrees / 1000.0);
+    if utilization.is_none() && memory_used.is_none() && temperature.is_none() {
+        return None;
+    }
+
+    let maker = match vendor.as_str() {
+        "0x1002" => "AMD",
+        "0x8086" => "Intel",
+        _ => "GPU",
+    };
+    Some(GpuStats {
+        name: format!("{} {}", maker, card),
+        utilization,
+        memory_used,
+        memory_total,
+        temperature,
+    })
+}
+
+/// Parses the CSV `typeperf` prints for the 3D engine utilization and
+/// dedicated memory counters, one GPU per adapter.
+///
+/// Utilization is summed over the engines of an adapter, as each process
+/// using it has its own engine instance.
+fn parse_typeperf(output: &str) -> Vec<GpuStats> {
+    let split = |line: &str| -> Vec<String> {
+        line.split("\",\"")
+            .map(|field| field.trim().trim_matches('"').to_string())
+            .collect()
+    };
+    let mut lines = output.lines().filter(|line| line.starts_with('"'));
+    let (Some(header), Some(values)) = (lines.next(), lines.next()) else {
+        return Vec::new();
+    };
+
+    let mut gpus: Vec<(String, GpuStats)> = Vec::new();
+    // The first column is the sample time
+    for (counter, value) in split(header).iter().zip(split(values)).skip(1) {
+        let Some(luid) = counter.find("luid_").map(|at| {
+            counter[at..]
+                .splitn(4, '_')
+                .take(3)
+                .collect::<Vec<_>>()
+                .join("_")
+        }) else {
+            continue;
+        };
+        let Ok(value) = value.parse::<f64>() else {
+            continue;
+        };
+        let index = match gpus.iter().position(|(id, _)| *id == luid) {
+            Some(index) => index,
+            None => {
+                let name = format!("GPU {}", gpus.len());
+                gpus.push((
+                    luid.clone(),
+                    GpuStats {
+                        name,
+                        utilization: None,
+                        memory_used: None,
+                        memory_total: None,
+                        temperature: None,
+                    },
+                ));
+                gpus.len() - 1
+            }
+        };
+        let gpu = &mut gpus[index].1;
+        if counter.ends_with(r"\Utilization Percentage") {
+            let total = gpu.utilization.unwrap_or(0.0) + value as f32;
+            gpu.utilization = Some(total.min(100.0));
+        } else if counter.ends_with(r"\Dedicated Usage") {
+            gpu.memory_used = Some(gpu.memory_used.unwrap_or(0) + value as u64);
+        }
+    }
+    gpus.into_iter().map(|(_, gpu)| gpu).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        // Captured from a machine with two cards, the second a datacenter
+        // card that doesn't report its temperature
+        let output = "NVIDIA GeForce RTX 3080, 37, 2841, 10240, 54\n\
+                      Tesla T4, 0, 3, 15360, [N/A]\n";
+        let gpus = parse_nvidia_smi(output);
+        assert_eq!(
+            gpus,
+            [
+                GpuStats {
+                    name: "NVIDIA GeForce RTX 3080".to_string(),
+                    utilization: Some(37.0),
+                    memory_used: Some(2841 * MIB),
+                    memory_total: Some(10240 * MIB),
+                    temperature: Some(54.0),
+                },
+                GpuStats {
+                    name: "Tesla T4".to_string(),
+                    utilization: Some(0.0),
+                    memory_used: Some(3 * MIB),
+                    memory_total: Some(15360 * MIB),
+                    temperature: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi_odd_output() {
+        // Commas in the name, and a laptop GPU without utilization
+        let gpus = parse_nvidia_smi("NVIDIA RTX A2000, Laptop, [Not Supported], 512, 4096, 41");
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name, "NVIDIA RTX A2000, Laptop");
+        assert_eq!(gpus[0].utilization, None);
+        assert_eq!(gpus[0].memory_used, Some(512 * MIB));
+
+        // Errors printed instead of the query results
+        let error = "NVIDIA-SMI has failed because it couldn't communicate with the NVIDIA driver.";
+        assert!(parse_nvidia_smi(error).is_empty());
+        assert!(parse_nvidia_smi("").is_empty());
+    }
+
+    #[test]
+    fn test_drm_collector() {
+        let root = tempfile::TempDir::new().unwrap();
+        let write = |path: &str, value: &str| {
+            let path = root.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, value).unwrap();
+        };
+        write("card0/device/vendor", "0x1002\n");
+        write("card0/device/gpu_busy_percent", "12\n");
+        write("card0/device/mem_info_vram_used", "1073741824\n");
+        write("card0/device/mem_info_vram_total", "8589934592\n");
+        write("card0/device/hwmon/hwmon3/temp1_input", "47000\n");
+        // A connector, an NVIDIA card and a card without statistics
+        write("card0-DP-1/status", "connected\n");
+        write("card1/device/vendor", "0x10de\n");
+        write("card1/device/gpu_busy_percent", "50\n");
+        write("card2/device/vendor", "0x8086\n");
+        std::fs::create_dir_all(root.path().join("card2/device/hwmon")).unwrap();
+
+        let gpus = DrmCollector::with_root(root.path()).collect();
+        assert_eq!(
+            gpus,
+            [GpuStats {
+                name: "AMD card0".to_string(),
+                utilization: Some(12.0),
+                memory_used: Some(1024 * MIB),
+                memory_total: Some(8192 * MIB),
+                temperature: Some(47.0),
+            }]
+        );
+
+        assert!(DrmCollector::with_root(root.path().join("missing"))
+            .collect()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_typeperf() {
+        let output = concat!(
+            "\r\n",
+            r#""(PDH-CSV 4.0)","\\PC\GPU Engine(pid_1204_luid_0x00000000_0x0000D1A5_phys_0_eng_0_engtype_3D)\Utilization Percentage","\\PC\GPU Engine(pid_8812_luid_0x00000000_0x0000D1A5_phys_0_eng_0_engtype_3D)\Utilization Percentage","\\PC\GPU Adapter Memory(luid_0x00000000_0x0000D1A5_phys_0)\Dedicated Usage""#,
+            "\r\n",
+            r#""10/15/2026 10:00:00.000","12.5","30.25","536870912""#,
+            "\r\n",
+            "Exiting, please wait...\r\n",
+            "The command completed successfully.\r\n",
+        );
+        let gpus = parse_typeperf(output);
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name, "GPU 0");
+        assert_eq!(gpus[0].utilization, Some(42.75));
+        assert_eq!(gpus[0].memory_used, Some(512 * MIB));
+        assert_eq!(gpus[0].memory_total, None);
+
+        assert!(parse_typeperf("No valid counters.").is_empty());
+    }
+
+    struct Fixed(Vec<GpuStats>);
+
+    impl GpuCollector for Fixed {
+        fn collect(&self) -> Vec<GpuStats> {
+            self.0.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_monitor_caches_results() {
+        let gpu = parse_nvidia_smi("Tesla T4, 5, 3, 15360, 40").remove(0);
+        let monitor = GpuMonitor::with_collectors(vec![
+            Box::new(NoopCollector),
+            Box::new(Fixed(vec![gpu.clone()])),
+        ]);
+        assert!(monitor.latest().is_empty());
+
+        monitor.clone().refresh().await;
+        assert_eq!(monitor.latest(), [gpu]);
+    }
+}
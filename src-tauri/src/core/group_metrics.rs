@@ -0,0 +1,225 @@
+//! Usage rollups over the processes sharing a tag.
+//!
+//! The usage histories of the members are sampled independently, so they
+//! are aligned on a common grid ending at the time of the query. Each grid
+//! point takes the latest sample of every member at or shortly before it; a
+//! member without one (not started yet, or stopped) counts as zero and
+//! marks the point as partial.
+
+use crate::core::metrics_buffer::TimedMetric;
+use crate::models::UsageSample;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Grid spacing when no member has two samples to measure it from.
+const DEFAULT_STEP_MS: i64 = 1000;
+
+/// Summed usage of a group at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPoint {
+    pub timestamp: DateTime<Utc>,
+    /// CPU usage in percent of one core.
+    pub cpu: f32,
+    /// Memory usage in bytes.
+    pub memory: u64,
+    /// Members with a sample at this point.
+    pub reporting: usize,
+    /// Whether some member had no sample and counted as zero.
+    pub partial: bool,
+}
+
+/// Usage of the processes tagged `tag`, summed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMetrics {
+    pub tag: String,
+    /// Names of the tagged processes.
+    pub members: Vec<String>,
+    /// Time between two points in milliseconds.
+    pub sample_interval_ms: u64,
+    /// Oldest first; points where no member had a sample are left out.
+    pub series: Vec<GroupPoint>,
+    /// Current CPU usage in percent of one core.
+    pub cpu: f32,
+    /// Current memory usage in bytes.
+    pub memory: u64,
+    /// Whether any point, or the current totals, is missing a member.
+    pub partial: bool,
+}
+
+/// Sums the usage histories of `members` over the `window` ending at `end`.
+///
+/// # Arguments
+/// * `tag` - Tag the members share
+/// * `members` - Name and usage samples (oldest first) of each member
+/// * `end` - Time of the last point, usually now
+/// * `window` - How far back the series goes
+pub fn aggregate(
+    tag: &str,
+    members: &[(String, Vec<TimedMetric<UsageSample>>)],
+    end: DateTime<Utc>,
+    window: Duration,
+) -> GroupMetrics {
+    let step = sample_interval(members);
+    // A sample counts for a point until the next one is overdue
+    let stale_after = step * 2;
+    let points = (window.num_milliseconds() / step.num_milliseconds()).max(1);
+
+    let totals_at = |timestamp: DateTime<Utc>| {
+        let mut point = GroupPoint {
+            timestamp,
+            cpu: 0.0,
+            memory: 0,
+            reporting: 0,
+            partial: false,
+        };
+        for (_, samples) in members {
+            let sample = samples
+                .iter()
+                .rev()
+                .find(|sample| sample.timestamp <= timestamp)
+                .filter(|sample| timestamp - sample.timestamp < stale_after);
+            match sample {
+                Some(sample) => {
+                    point.cpu += sample.value.cpu.unwrap_or(0.0);
+                    point.memory += sample.value.memory;
+                    point.reporting += 1;
+                }
+                None => point.partial = true,
+            }
+        }
+        point
+    };
+
+    let series: Vec<GroupPoint> = (0..points)
+        .rev()
+        .map(|back| totals_at(end - step * back as i32))
+        .filter(|point| point.reporting > 0)
+        .collect();
+    let current = totals_at(end);
+
+    GroupMetrics {
+        tag: tag.to_string(),
+        members: members.iter().map(|(name, _)| name.clone()).collect(),
+        sample_interval_ms: step.num_milliseconds() as u64,
+        partial: current.partial || series.iter().any(|point| point.partial),
+        series,
+        cpu: current.cpu,
+        memory: current.memory,
+    }
+}
+
+/// Average time between the samples of the members.
+fn sample_interval(members: &[(String, Vec<TimedMetric<UsageSample>>)]) -> Duration {
+    let intervals: Vec<i64> = members
+        .iter()
+        .filter_map(|(_, samples)| match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) if samples.len() > 1 => Some(
+                (last.timestamp - first.timestamp).num_milliseconds() / (samples.len() as i64 - 1),
+            ),
+            _ => None,
+        })
+        .filter(|interval| *interval > 0)
+        .collect();
+    if intervals.is_empty() {
+        return Duration::milliseconds(DEFAULT_STEP_MS);
+    }
+    Duration::milliseconds(intervals.iter().sum::<i64>() / intervals.len() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Samples taken every second, the last `ago_secs` before `end`.
+    fn samples(end: DateTime<Utc>, ago_secs: i64, memory: &[u64]) -> Vec<TimedMetric<UsageSample>> {
+        let count = memory.len() as i64;
+        memory
+            .iter()
+            .enumerate()
+            .map(|(i, memory)| TimedMetric {
+                timestamp: end - Duration::seconds(ago_secs + count - 1 - i as i64),
+                value: UsageSample {
+                    cpu: Some(10.0),
+                    memory: *memory,
+                    ..Default::default()
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_buffers_of_different_lengths_are_aligned() {
+        let end = Utc::now();
+        let members = vec![
+            (
+                "api".to_string(),
+                samples(end, 0, &[100, 100, 100, 100, 100]),
+            ),
+            ("worker".to_string(), samples(end, 0, &[20, 30])),
+        ];
+
+        let metrics = aggregate("backend", &members, end, Duration::seconds(5));
+        assert_eq!(metrics.members, vec!["api", "worker"]);
+        assert_eq!(metrics.sample_interval_ms, 1000);
+        let memory: Vec<u64> = metrics.series.iter().map(|p| p.memory).collect();
+        assert_eq!(memory, vec![100, 100, 100, 120, 130]);
+        assert_eq!(metrics.series.last().unwrap().timestamp, end);
+        assert_eq!(metrics.memory, 130);
+        assert_eq!(metrics.cpu, 20.0);
+    }
+
+    #[test]
+    fn test_member_started_mid_window_is_partial() {
+        let end = Utc::now();
+        let members = vec![
+            ("api".to_string(), samples(end, 0, &[100, 100, 100, 100])),
+            ("worker".to_string(), samples(end, 0, &[20, 30])),
+        ];
+
+        let metrics = aggregate("backend", &members, end, Duration::seconds(4));
+        let partial: Vec<bool> = metrics.series.iter().map(|p| p.partial).collect();
+        assert_eq!(partial, vec![true, true, false, false]);
+        assert_eq!(metrics.series[0].reporting, 1);
+        assert!(metrics.partial);
+
+        // Once every member covers the window, nothing is partial
+        let metrics = aggregate("backend", &members, end, Duration::seconds(2));
+        assert!(!metrics.partial);
+    }
+
+    #[test]
+    fn test_stopped_member_counts_as_zero() {
+        let end = Utc::now();
+        let members = vec![
+            ("api".to_string(), samples(end, 0, &[100, 100, 100])),
+            ("worker".to_string(), samples(end, 2, &[50])),
+        ];
+
+        let metrics = aggregate("backend", &members, end, Duration::seconds(3));
+        let memory: Vec<u64> = metrics.series.iter().map(|p| p.memory).collect();
+        assert_eq!(memory, vec![150, 150, 100]);
+        assert_eq!(metrics.memory, 100);
+        assert!(metrics.partial);
+    }
+
+    #[test]
+    fn test_points_without_samples_are_left_out() {
+        let end = Utc::now();
+        let members = vec![("api".to_string(), samples(end, 0, &[100, 100]))];
+
+        let metrics = aggregate("backend", &members, end, Duration::seconds(60));
+        assert_eq!(metrics.series.len(), 2);
+
+        let metrics = aggregate(
+            "backend",
+            &[("api".to_string(), vec![])],
+            end,
+            Duration::seconds(60),
+        );
+        assert!(metrics.series.is_empty());
+        assert_eq!(metrics.memory, 0);
+        assert!(metrics.partial);
+    }
+}
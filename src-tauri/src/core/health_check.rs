@@ -0,0 +1,268 @@
+//! Health checks of running processes: commands, HTTP requests and TCP
+//! connections.
+//!
+//! HTTP and TCP checks run in-process rather than through a command such as
+//! `curl`, so they work wherever Sentinel does and don't spawn a process on
+//! every check.
+
+use crate::models::{HealthCheck, HealthCheckKind, HealthResult};
+use chrono::Utc;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+/// Runs one health check.
+///
+/// Command checks run in `cwd`. The result's `consecutive_failures` is 1 if
+/// the check failed and 0 otherwise; the caller keeps the count.
+pub async fn run(health_check: &HealthCheck, cwd: Option<&Path>) -> HealthResult {
+    let started = Instant::now();
+    let attempt_timeout = Duration::from_millis(health_check.timeout_ms);
+
+    let outcome = timeout(
+        attempt_timeout,
+        attempt(&health_check.check, attempt_timeout, cwd),
+    )
+    .await
+    .unwrap_or_else(|_| Outcome {
+        status_code: None,
+        error: Some(format!("Timed out after {}ms", health_check.timeout_ms)),
+    });
+
+    let healthy = outcome.error.is_none();
+    HealthResult {
+        healthy,
+        latency_ms: started.elapsed().as_millis() as u64,
+        status_code: outcome.status_code,
+        error: outcome.error,
+        consecutive_failures: u32::from(!healthy),
+        checked_at: Utc::now(),
+    }
+}
+
+/// What one attempt found out.
+struct Outcome {
+    status_code: Option<u16>,
+    /// Why the check failed, `None` if it passed.
+    error: Option<String>,
+}
+
+impl Outcome {
+    fn passed() -> Self {
+        Self {
+            status_code: None,
+            error: None,
+        }
+    }
+
+    fn failed(error: String) -> Self {
+        Self {
+            status_code: None,
+            error: Some(error),
+        }
+    }
+}
+
+async fn attempt(
+    check: &HealthCheckKind,
+    attempt_timeout: Duration,
+    cwd: Option<&Path>,
+) -> Outcome {
+    match check {
+        HealthCheckKind::Command { command, args } => {
+            let mut cmd = Command::new(command);
+            cmd.args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .kill_on_drop(true);
+            if let Some(cwd) = cwd {
+                cmd.current_dir(cwd);
+            }
+            match cmd.status().await {
+                Ok(status) if status.success() => Outcome::passed(),
+                Ok(status) => Outcome::failed(format!("Command exited with {}", status)),
+                Err(e) => Outcome::failed(format!("Failed to run '{}': {}", command, e)),
+            }
+        }
+        HealthCheckKind::Http {
+            url,
+            method,
+            expected_status,
+        } => {
+            let Ok(method) = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+            else {
+                return Outcome::failed(format!("Invalid HTTP method '{}'", method));
+            };
+            let response = reqwest::Client::new()
+                .request(method, url)
+                .timeout(attempt_timeout)
+                .send()
+                .await;
+
+            match response {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    let passed = match expected_status {
+                        Some(expected) => status == *expected,
+                        None => (200..300).contains(&status),
+                    };
+                    Outcome {
+                        status_code: Some(status),
+                        error: (!passed).then(|| format!("Unexpected status {}", status)),
+                    }
+                }
+                Err(e) => Outcome::failed(format!("Request to {} failed: {}", url, e)),
+            }
+        }
+        HealthCheckKind::Tcp { host, port } => {
+            match TcpStream::connect((host.as_str(), *port)).await {
+                Ok(_) => Outcome::passed(),
+                Err(e) => Outcome::failed(format!("Connecting to {}:{} failed: {}", host, port, e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn health_check(check: HealthCheckKind, timeout_ms: u64) -> HealthCheck {
+        HealthCheck {
+            check,
+            interval_ms: None,
+            timeout_ms,
+            retries: 0,
+        }
+    }
+
+    /// Serves `response` to every connection, after `delay`.
+    async fn serve(response: &'static str, delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = [0u8; 1024];
+                    let _ = stream.read(&mut request).await;
+                    tokio::time::sleep(delay).await;
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
+    }
+
+    fn http(url: &str, expected_status: Option<u16>) -> HealthCheckKind {
+        HealthCheckKind::Http {
+            url: url.to_string(),
+            method: "GET".to_string(),
+            expected_status,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_check_records_status() {
+        let url = serve(
+            "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n",
+            Duration::ZERO,
+        )
+        .await;
+
+        let result = run(&health_check(http(&url, None), 2_000), None).await;
+        assert!(result.healthy, "{:?}", result.error);
+        assert_eq!(result.status_code, Some(204));
+        assert_eq!(result.consecutive_failures, 0);
+
+        let result = run(&health_check(http(&url, Some(200)), 2_000), None).await;
+        assert!(!result.healthy);
+        assert_eq!(result.status_code, Some(204));
+    }
+
+    #[tokio::test]
+    async fn test_http_check_fails_on_non_2xx() {
+        let url = serve(
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            Duration::ZERO,
+        )
+        .await;
+
+        let result = run(&health_check(http(&url, None), 2_000), None).await;
+        assert!(!result.healthy);
+        assert_eq!(result.status_code, Some(503));
+        assert_eq!(result.error.as_deref(), Some("Unexpected status 503"));
+        assert_eq!(result.consecutive_failures, 1);
+
+        // Unless that status is expected
+        let result = run(&health_check(http(&url, Some(503)), 2_000), None).await;
+        assert!(result.healthy);
+    }
+
+    #[tokio::test]
+    async fn test_http_check_times_out() {
+        let url = serve(
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+            Duration::from_secs(5),
+        )
+        .await;
+
+        let started = Instant::now();
+        let result = run(&health_check(http(&url, None), 200), None).await;
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(!result.healthy);
+        assert_eq!(result.status_code, None);
+        assert!(result.latency_ms >= 200);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_check() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let tcp = HealthCheckKind::Tcp {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+
+        assert!(run(&health_check(tcp.clone(), 1_000), None).await.healthy);
+
+        drop(listener);
+        let result = run(&health_check(tcp, 1_000), None).await;
+        assert!(!result.healthy);
+        assert!(result
+            .error
+            .unwrap()
+            .starts_with("Connecting to 127.0.0.1:"));
+    }
+
+    #[tokio::test]
+    async fn test_command_check() {
+        let command = |command: &str| HealthCheckKind::Command {
+            command: command.to_string(),
+            args: vec![],
+        };
+
+        assert!(
+            run(&health_check(command("true"), 1_000), None)
+                .await
+                .healthy
+        );
+        assert!(
+            !run(&health_check(command("false"), 1_000), None)
+                .await
+                .healthy
+        );
+
+        let slow = HealthCheckKind::Command {
+            command: "sleep".to_string(),
+            args: vec!["5".to_string()],
+        };
+        let result = run(&health_check(slow, 200), None).await;
+        assert_eq!(result.error.as_deref(), Some("Timed out after 200ms"));
+    }
+}
@@ -0,0 +1,618 @@
+//! Keeping a single Sentinel instance in charge of the config and state.
+//!
+//! Two instances would both rewrite the runtime state and start every process
+//! twice. The first one takes the [`InstanceLock`] and listens on a control
+//! socket; a later one finds the lock taken and uses the socket to hand over
+//! to it: the app asks it to focus its window, `sentinel start --force`
+//! asks it to shut down, `sentinel add --start` asks it to start the
+//! process just added, and `sentinel stop` and `sentinel restart` ask it to
+//! stop or restart the processes it runs.
+
+use crate::core::StateManager;
+use crate::error::{Result, SentinelError};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a control request waits for the other instance to read it.
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a control request waits for a stop or restart to finish.
+const HANDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// An exclusive lock showing this is the running Sentinel instance.
+///
+/// The lock file holds the owner's PID. The lock is released when this value
+/// is dropped or the process dies, so a crashed instance never keeps it.
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Gets the default lock file path, next to the state file.
+    pub fn default_path() -> PathBuf {
+        StateManager::get_state_path().with_file_name(".sentinel.lock")
+    }
+
+    /// Takes the lock at `path` and records this process's PID in it.
+    ///
+    /// A lock left behind by a process that no longer exists is reclaimed.
+    ///
+    /// # Returns
+    /// * `Ok(InstanceLock)` - This is the only instance
+    /// * `Err(SentinelError::AlreadyRunning)` - Another live instance holds the lock
+    pub fn acquire(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| SentinelError::FileIoError {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+
+        let mut file = open(path)?;
+        if !try_lock(&file)? {
+            match read_pid(&mut file) {
+                // Held through a descriptor a dead instance leaked to a child;
+                // a fresh file gets a fresh lock
+                Some(pid) if !is_alive(pid) => {
+                    tracing::warn!("Reclaiming instance lock of dead PID {}", pid);
+                    fs::remove_file(path).map_err(|e| SentinelError::FileIoError {
+                        path: path.to_path_buf(),
+                        source: e,
+                    })?;
+                    file = open(path)?;
+                    if !try_lock(&file)? {
+                        return Err(SentinelError::AlreadyRunning {
+                            pid: read_pid(&mut file),
+                        });
+                    }
+                }
+                pid => return Err(SentinelError::AlreadyRunning { pid }),
+            }
+        }
+
+        write_pid(&mut file, std::process::id()).map_err(|e| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Ok(Self { _file: file })
+    }
+}
+
+fn open(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+}
+
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut contents).ok()?;
+    contents.trim().parse().ok()
+}
+
+fn write_pid(file: &mut File, pid: u32) -> std::io::Result<()> {
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    writeln!(file, "{}", pid)?;
+    file.flush()
+}
+
+/// Takes a non-blocking exclusive lock, returning whether it was free.
+#[cfg(unix)]
+fn try_lock(file: &File) -> Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+    let err = std::io::Error::last_os_error();
+    if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+        Ok(false)
+    } else {
+        Err(err.into())
+    }
+}
+
+/// Without advisory locks the PID in the file decides.
+#[cfg(not(unix))]
+fn try_lock(file: &File) -> Result<bool> {
+    let mut file = file.try_clone()?;
+    Ok(read_pid(&mut file).is_none_or(|pid| pid == std::process::id() || !is_alive(pid)))
+}
+
+/// Whether a process with this PID exists.
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    // EPERM means it exists but belongs to someone else
+    let alive = unsafe { libc::kill(pid as i32, 0) } == 0;
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_alive(pid: u32) -> bool {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).is_some()
+}
+
+/// A request from another instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Show and focus the main window.
+    Focus,
+    /// Stop or detach processes as configured, then exit.
+    Shutdown,
+    /// Start the named process from the instance's config file.
+    Start(String),
+    /// Stop the named process, killing it at once with `force`.
+    Stop { name: String, force: bool },
+    /// Restart the named process, killing it at once with `force`.
+    Restart { name: String, force: bool },
+}
+
+impl ControlCommand {
+    /// The command as sent over the socket.
+    pub fn to_line(&self) -> String {
+        match self {
+            ControlCommand::Focus => "focus".to_string(),
+            ControlCommand::Shutdown => "shutdown".to_string(),
+            ControlCommand::Start(name) => format!("start {}", name),
+            ControlCommand::Stop { name, force: false } => format!("stop {}", name),
+            ControlCommand::Stop { name, force: true } => format!("kill {}", name),
+            ControlCommand::Restart { name, force: false } => format!("restart {}", name),
+            ControlCommand::Restart { name, force: true } => format!("force-restart {}", name),
+        }
+    }
+
+    /// Parses a command sent over the socket.
+    pub fn parse(command: &str) -> Option<Self> {
+        match command.trim() {
+            "focus" => Some(ControlCommand::Focus),
+            "shutdown" => Some(ControlCommand::Shutdown),
+            command => {
+                let (verb, name) = command.split_once(' ')?;
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return None;
+                }
+                match verb {
+                    "start" => Some(ControlCommand::Start(name)),
+                    "stop" => Some(ControlCommand::Stop { name, force: false }),
+                    "kill" => Some(ControlCommand::Stop { name, force: true }),
+                    "restart" => Some(ControlCommand::Restart { name, force: false }),
+                    "force-restart" => Some(ControlCommand::Restart { name, force: true }),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The process the command is about, if any.
+    pub fn process(&self) -> Option<&str> {
+        match self {
+            ControlCommand::Focus | ControlCommand::Shutdown => None,
+            ControlCommand::Start(name)
+            | ControlCommand::Stop { name, .. }
+            | ControlCommand::Restart { name, .. } => Some(name),
+        }
+    }
+}
+
+/// A [`ControlCommand`] whose sender waits to hear how it went.
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    reply: tokio::sync::oneshot::Sender<String>,
+}
+
+impl ControlRequest {
+    /// Tells the sender how the command went.
+    ///
+    /// A request dropped without a reply is reported as done.
+    pub fn reply(self, result: Result<()>) {
+        let line = match result {
+            Ok(()) => "ok".to_string(),
+            Err(SentinelError::ProcessNotFound { .. }) => "not-found".to_string(),
+            Err(e) => format!("error: {}", e.to_string().replace('\n', " ")),
+        };
+        let _ = self.reply.send(line);
+    }
+}
+
+/// Gets the default control socket path, next to the state file.
+pub fn default_socket_path() -> PathBuf {
+    StateManager::get_state_path().with_file_name(".sentinel.sock")
+}
+
+/// Receives [`ControlCommand`]s from other instances.
+///
+/// Each connection is read in a task of its own, so a client that connects
+/// and never sends a command holds up nobody but itself.
+pub struct ControlListener {
+    #[cfg(unix)]
+    requests: tokio::sync::mpsc::Receiver<ControlRequest>,
+    #[cfg(unix)]
+    accept: tokio::task::JoinHandle<()>,
+}
+
+impl ControlListener {
+    /// Listens on `path`, replacing a socket left behind by an earlier instance.
+    ///
+    /// Only the lock holder should bind. Must be called from within a Tokio
+    /// runtime.
+    #[cfg(unix)]
+    pub fn bind(path: &Path) -> Result<Self> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(SentinelError::FileIoError {
+                    path: path.to_path_buf(),
+                    source: e,
+                })
+            }
+            _ => {}
+        }
+        let listener =
+            tokio::net::UnixListener::bind(path).map_err(|e| SentinelError::FileIoError {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+
+        let (sender, requests) = tokio::sync::mpsc::channel(16);
+        let accept = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(serve(stream, sender.clone()));
+                    }
+                    Err(e) => {
+                        tracing::error!("Control socket stopped accepting connections: {}", e);
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self { requests, accept })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(_path: &Path) -> Result<Self> {
+        Err(SentinelError::Unsupported {
+            operation: "Control socket".to_string(),
+        })
+    }
+
+    /// Waits for the next valid command; its sender waits for
+    /// [`ControlRequest::reply`].
+    ///
+    /// Returns `None` once the socket can no longer accept connections.
+    #[cfg(unix)]
+    pub async fn next(&mut self) -> Option<ControlRequest> {
+        self.requests.recv().await
+    }
+
+    #[cfg(not(unix))]
+    pub async fn next(&mut self) -> Option<ControlRequest> {
+        None
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ControlListener {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+/// Reads one command from `stream`, hands it to the listener and sends
+/// back its reply.
+#[cfg(unix)]
+async fn serve(
+    stream: tokio::net::UnixStream,
+    requests: tokio::sync::mpsc::Sender<ControlRequest>,
+) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    let mut reader = BufReader::new(reader);
+    match tokio::time::timeout(CONTROL_TIMEOUT, reader.read_line(&mut line)).await {
+        Ok(Ok(_)) => {}
+        _ => return,
+    }
+    match ControlCommand::parse(&line) {
+        // Replied to once handled, so commands sent one after another are
+        // handled in order
+        Some(command) => {
+            let (reply, replied) = tokio::sync::oneshot::channel();
+            if requests
+                .send(ControlRequest { command, reply })
+                .await
+                .is_ok()
+            {
+                let reply = replied.await.unwrap_or_else(|_| "ok".to_string());
+                let _ = writer.write_all(format!("{}\n", reply).as_bytes()).await;
+            }
+        }
+        None => {
+            tracing::warn!("Unknown control command: {}", line.trim());
+            let _ = writer.write_all(b"error: unknown command\n").await;
+        }
+    }
+}
+
+/// Sends `command` to the instance listening on `path`.
+///
+/// Waits for the instance to carry the command out, up to a minute for a
+/// stop or restart.
+///
+/// # Returns
+/// * `Ok(())` - The instance carried out the command
+/// * `Err(SentinelError::ProcessNotFound)` - The instance doesn't run the process
+/// * `Err(SentinelError::FileIoError)` - No instance is listening
+/// * `Err(SentinelError)` - The instance refused the command or it failed
+#[cfg(unix)]
+pub async fn send(path: &Path, command: ControlCommand) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let io_error = |e| SentinelError::FileIoError {
+        path: path.to_path_buf(),
+        source: e,
+    };
+    let no_answer = |_| SentinelError::Other("Sentinel instance did not respond".to_string());
+
+    let connect = async {
+        let mut stream = tokio::net::UnixStream::connect(path).await?;
+        stream
+            .write_all(format!("{}\n", command.to_line()).as_bytes())
+            .await?;
+        Ok::<_, std::io::Error>(stream)
+    };
+    let stream = tokio::time::timeout(CONTROL_TIMEOUT, connect)
+        .await
+        .map_err(no_answer)?
+        .map_err(io_error)?;
+
+    let timeout = match command {
+        ControlCommand::Stop { .. } | ControlCommand::Restart { .. } => HANDLE_TIMEOUT,
+        _ => CONTROL_TIMEOUT,
+    };
+    let mut reply = String::new();
+    tokio::time::timeout(timeout, BufReader::new(stream).read_line(&mut reply))
+        .await
+        .map_err(no_answer)?
+        .map_err(io_error)?;
+
+    match reply.trim() {
+        "ok" => Ok(()),
+        "not-found" => Err(SentinelError::ProcessNotFound {
+            name: command.process().unwrap_or_default().to_string(),
+        }),
+        reply => Err(SentinelError::Other(format!(
+            "Sentinel instance refused '{}': {}",
+            command.to_line(),
+            reply.strip_prefix("error: ").unwrap_or(reply)
+        ))),
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn send(_path: &Path, _command: ControlCommand) -> Result<()> {
+    Err(SentinelError::Unsupported {
+        operation: "Control socket".to_string(),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// PID of a process that has exited and been reaped.
+    fn dead_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn test_lock_records_pid_and_blocks_second_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join(".sentinel.lock");
+
+        let lock = InstanceLock::acquire(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            std::process::id().to_string()
+        );
+
+        match InstanceLock::acquire(&path) {
+            Err(SentinelError::AlreadyRunning { pid }) => {
+                assert_eq!(pid, Some(std::process::id()))
+            }
+            other => panic!("expected AlreadyRunning, got {:?}", other),
+        }
+
+        drop(lock);
+        assert!(InstanceLock::acquire(&path).is_ok());
+    }
+
+    #[test]
+    fn test_stale_lock_file_is_reclaimed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sentinel.lock");
+
+        // Left behind by a crashed instance
+        fs::write(&path, format!("{}\n", dead_pid())).unwrap();
+        let _lock = InstanceLock::acquire(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            std::process::id().to_string()
+        );
+    }
+
+    #[test]
+    fn test_lock_held_for_dead_pid_is_reclaimed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sentinel.lock");
+
+        // Still locked, as when a dead instance's descriptor lives on in a child
+        let mut leaked = open(&path).unwrap();
+        assert!(try_lock(&leaked).unwrap());
+        write_pid(&mut leaked, dead_pid()).unwrap();
+
+        let _lock = InstanceLock::acquire(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            std::process::id().to_string()
+        );
+    }
+
+    #[test]
+    fn test_control_command_round_trip() {
+        for command in [
+            ControlCommand::Focus,
+            ControlCommand::Shutdown,
+            ControlCommand::Start("web".to_string()),
+            ControlCommand::Stop {
+                name: "web".to_string(),
+                force: false,
+            },
+            ControlCommand::Stop {
+                name: "web".to_string(),
+                force: true,
+            },
+            ControlCommand::Restart {
+                name: "web".to_string(),
+                force: false,
+            },
+            ControlCommand::Restart {
+                name: "web".to_string(),
+                force: true,
+            },
+        ] {
+            assert_eq!(ControlCommand::parse(&command.to_line()), Some(command));
+        }
+        assert_eq!(
+            ControlCommand::parse("shutdown\n"),
+            Some(ControlCommand::Shutdown)
+        );
+        assert_eq!(ControlCommand::parse("reboot"), None);
+        assert_eq!(ControlCommand::parse("start "), None);
+        assert_eq!(ControlCommand::parse("stop"), None);
+    }
+
+    #[tokio::test]
+    async fn test_control_socket_delivers_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sentinel.sock");
+        // A socket file from a crashed instance is replaced
+        fs::write(&path, "").unwrap();
+
+        let mut listener = ControlListener::bind(&path).unwrap();
+        let received = tokio::spawn(async move {
+            let first = listener.next().await.map(|request| request.command);
+            let second = listener.next().await.map(|request| request.command);
+            (first, second)
+        });
+
+        // Unknown commands are refused without ending the listener
+        {
+            use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+            let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+            stream.write_all(b"reboot\n").await.unwrap();
+            let mut reply = String::new();
+            BufReader::new(stream).read_line(&mut reply).await.unwrap();
+            assert!(reply.starts_with("error"));
+        }
+
+        send(&path, ControlCommand::Focus).await.unwrap();
+        send(&path, ControlCommand::Shutdown).await.unwrap();
+        assert_eq!(
+            received.await.unwrap(),
+            (Some(ControlCommand::Focus), Some(ControlCommand::Shutdown))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_silent_client_does_not_block_others() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sentinel.sock");
+        let mut listener = ControlListener::bind(&path).unwrap();
+
+        // Connects but never sends a command
+        let _silent = tokio::net::UnixStream::connect(&path).await.unwrap();
+
+        // The request is dropped once read, which answers the sender
+        let (sent, reply) = tokio::time::timeout(Duration::from_secs(1), async {
+            tokio::join!(
+                async { listener.next().await.map(|request| request.command) },
+                send(&path, ControlCommand::Focus),
+            )
+        })
+        .await
+        .expect("a silent client held up the control socket");
+        assert_eq!(sent, Some(ControlCommand::Focus));
+        reply.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replies_carry_the_outcome() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sentinel.sock");
+        let mut listener = ControlListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            while let Some(request) = listener.next().await {
+                let result = match request.command.process() {
+                    Some("api") => Ok(()),
+                    Some("broken") => Err(SentinelError::Other("no\nway".to_string())),
+                    Some(name) => Err(SentinelError::ProcessNotFound {
+                        name: name.to_string(),
+                    }),
+                    None => Ok(()),
+                };
+                request.reply(result);
+            }
+        });
+
+        let stop = |name: &str| ControlCommand::Stop {
+            name: name.to_string(),
+            force: false,
+        };
+        send(&path, stop("api")).await.unwrap();
+        match send(&path, stop("web")).await {
+            Err(SentinelError::ProcessNotFound { name }) => assert_eq!(name, "web"),
+            other => panic!("expected ProcessNotFound, got {:?}", other),
+        }
+        match send(&path, stop("broken")).await {
+            Err(SentinelError::Other(message)) => {
+                assert_eq!(message, "Sentinel instance refused 'stop broken': no way")
+            }
+            other => panic!("expected the failure, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_without_listener_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".sentinel.sock");
+        assert!(send(&path, ControlCommand::Focus).await.is_err());
+    }
+}
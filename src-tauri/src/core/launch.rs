@@ -0,0 +1,418 @@
+//! What starting a process runs.
+//!
+//! [`resolve`] turns a process configuration into the argv, working
+//! directory and environment it is spawned with. The process manager spawns
+//! from its result, and [`preview`] reports the same resolution without
+//! spawning anything, so a preview can't drift from what a start does.
+
+use crate::core::child_env::{self, Environment, DANGEROUS_VARS};
+use crate::core::{env_file, executable, ports, secrets, validation};
+use crate::error::{Result, SentinelError};
+use crate::features::port_discovery::PortScanner;
+use crate::models::{GlobalSettings, ProcessConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+/// A process configuration resolved for spawning.
+#[derive(Debug, Clone)]
+pub struct Launch {
+    /// Program followed by its arguments, never empty
+    pub argv: Vec<String>,
+    /// Working directory, checked against `settings.allowedRoots`
+    pub cwd: Option<PathBuf>,
+    /// The full environment of the process
+    pub env: Environment,
+}
+
+impl Launch {
+    /// The program to run, as configured.
+    pub fn program(&self) -> &str {
+        &self.argv[0]
+    }
+
+    /// The arguments passed to the program.
+    pub fn args(&self) -> &[String] {
+        &self.argv[1..]
+    }
+
+    /// The canonical directory the process runs in: its working directory,
+    /// or Sentinel's own.
+    pub fn resolved_cwd(&self) -> Option<PathBuf> {
+        self.cwd
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .and_then(|cwd| cwd.canonicalize().ok())
+    }
+
+    /// The `PATH` the process starts with.
+    pub fn path(&self) -> Option<&OsStr> {
+        self.env
+            .get(OsStr::new("PATH"))
+            .map(|path| path.as_os_str())
+    }
+}
+
+/// Resolves how `config` is spawned.
+///
+/// # Errors
+/// Returns `InvalidConfig` for an empty command, and the errors of
+/// [`validation::resolve_allowed_path`] for a working directory that can't
+/// be used.
+pub fn resolve(config: &ProcessConfig, settings: &GlobalSettings) -> Result<Launch> {
+    Ok(Launch {
+        argv: argv(config)?,
+        cwd: cwd(config, settings)?,
+        env: child_env::build(config.env_mode, &env_file::process_env(config)),
+    })
+}
+
+/// The argv of `config`: its command split on whitespace, or the command
+/// followed by `args` when it has any.
+///
+/// # Errors
+/// Returns `InvalidConfig` if there is no program to run.
+pub fn argv(config: &ProcessConfig) -> Result<Vec<String>> {
+    let argv: Vec<String> = if config.args.is_empty() {
+        config
+            .command
+            .split_whitespace()
+            .map(str::to_string)
+            .collect()
+    } else {
+        std::iter::once(config.command.clone())
+            .chain(config.args.iter().cloned())
+            .collect()
+    };
+    if argv.is_empty() {
+        return Err(SentinelError::InvalidConfig {
+            reason: format!("Empty command for process '{}'", config.name),
+        });
+    }
+    Ok(argv)
+}
+
+/// The working directory of `config`, resolved so it can't leave
+/// `settings.allowedRoots`.
+pub fn cwd(config: &ProcessConfig, settings: &GlobalSettings) -> Result<Option<PathBuf>> {
+    config
+        .cwd
+        .as_ref()
+        .map(|cwd| validation::resolve_allowed_path(cwd, &settings.allowed_roots))
+        .transpose()
+}
+
+/// A port a process declares, as a preview sees it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortPreview {
+    pub port: u16,
+    /// Whether something else already listens on it
+    pub in_use: bool,
+    /// Name of the process listening on it, if it could be found
+    pub owner: Option<String>,
+}
+
+/// What starting a process would do, worked out without spawning it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewResult {
+    pub name: String,
+    /// Absolute path of the binary that would run, if it can be found
+    pub exe_path: Option<PathBuf>,
+    /// Program and arguments, with secret option values masked
+    pub argv: Vec<String>,
+    /// The full environment, with secret values masked
+    pub env: BTreeMap<String, String>,
+    /// Canonical working directory
+    pub cwd: Option<PathBuf>,
+    /// Ports the process declares
+    pub ports: Vec<PortPreview>,
+    /// Problems that would make the start fail
+    pub errors: Vec<String>,
+    /// Things that would not stop the start but probably need a look
+    pub warnings: Vec<String>,
+}
+
+impl PreviewResult {
+    /// Whether a start would get as far as spawning the process.
+    pub fn is_startable(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Previews starting `config` with `settings`, next to the processes in
+/// `others`.
+///
+/// Runs the same resolution as a real start, and the same validation, but
+/// only reads: nothing is spawned, and ports are probed by binding them for
+/// a moment. Environment values and option values whose names match
+/// `settings.secretPatterns` are masked.
+pub async fn preview(
+    config: &ProcessConfig,
+    settings: &GlobalSettings,
+    others: &[ProcessConfig],
+) -> PreviewResult {
+    let patterns = &settings.secret_patterns;
+    let mut errors: Vec<String> = validation::check_process(config, settings)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let mut warnings = validation::process_warnings(config, settings);
+
+    // An empty command is already reported by validation
+    let argv = argv(config).unwrap_or_default();
+    let cwd = match cwd(config, settings) {
+        Ok(cwd) => cwd,
+        Err(e) => {
+            errors.push(e.to_string());
+            // Still show where it points
+            config.cwd.clone()
+        }
+    };
+    let launch = Launch {
+        argv,
+        cwd,
+        env: child_env::build(config.env_mode, &env_file::process_env(config)),
+    };
+
+    if let Some(cwd) = config.cwd.as_ref().filter(|cwd| !cwd.is_dir()) {
+        warnings.push(format!(
+            "Working directory {} does not exist",
+            cwd.display()
+        ));
+    }
+    if let Some(path) = env_file::configured_path(config).filter(|path| !path.is_file()) {
+        warnings.push(format!(
+            "Env file {} does not exist, so it adds nothing",
+            path.display()
+        ));
+    }
+
+    let resolved_cwd = launch.resolved_cwd();
+    let exe_path = match launch.argv.first() {
+        Some(program) => {
+            let exe_path = executable::which(program, resolved_cwd.as_deref(), launch.path());
+            if exe_path.is_none() {
+                warnings.push(format!("Command '{}' was not found on PATH", program));
+            }
+            exe_path
+        }
+        None => None,
+    };
+
+    let mut dangerous: Vec<&String> = config
+        .env
+        .keys()
+        .filter(|key| DANGEROUS_VARS.contains(&key.as_str()))
+        .collect();
+    dangerous.sort();
+    for key in dangerous {
+        warnings.push(format!(
+            "Environment variable {} changes how the program is loaded",
+            key
+        ));
+    }
+
+    let mut port_previews = Vec::new();
+    for port in ports::declared_ports(config) {
+        if let Some(other) = others
+            .iter()
+            .filter(|other| other.name != config.name)
+            .find(|other| ports::declared_ports(other).contains(&port))
+        {
+            warnings.push(format!(
+                "Port {} is also declared by process '{}'",
+                port, other.name
+            ));
+        }
+
+        let in_use = !ports::is_free(port);
+        let owner = if in_use {
+            // The scanner relies on lsof/netstat, which may be missing
+            PortScanner::new()
+                .get_port_info(port)
+                .await
+                .ok()
+                .flatten()
+                .map(|info| info.process_name)
+        } else {
+            None
+        };
+        if in_use {
+            warnings.push(match &owner {
+                Some(owner) => format!("Port {} is already in use by {}", port, owner),
+                None => format!("Port {} is already in use", port),
+            });
+        }
+        port_previews.push(PortPreview {
+            port,
+            in_use,
+            owner,
+        });
+    }
+
+    let env = launch
+        .env
+        .iter()
+        .map(|(key, value)| {
+            let key = key.to_string_lossy().into_owned();
+            let value = if secrets::is_secret(&key, patterns) {
+                secrets::MASK.to_string()
+            } else {
+                value.to_string_lossy().into_owned()
+            };
+            (key, value)
+        })
+        .collect();
+
+    PreviewResult {
+        name: config.name.clone(),
+        exe_path,
+        argv: secrets::mask_argv(&launch.argv, patterns),
+        env,
+        cwd: resolved_cwd,
+        ports: port_previews,
+        errors,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn process(yaml: &str) -> ProcessConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_splits_command() {
+        let settings = GlobalSettings::default();
+        let launch = resolve(&process("name: web\ncommand: npm run dev"), &settings).unwrap();
+        assert_eq!(launch.program(), "npm");
+        assert_eq!(launch.args(), ["run", "dev"]);
+
+        let config = process("name: web\ncommand: node\nargs: [\"server with spaces.js\"]");
+        let launch = resolve(&config, &settings).unwrap();
+        assert_eq!(launch.argv, ["node", "server with spaces.js"]);
+
+        let err = resolve(&process("name: web\ncommand: \"  \""), &settings).unwrap_err();
+        assert!(matches!(err, SentinelError::InvalidConfig { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_preview_reports_missing_binary() {
+        let settings = GlobalSettings::default();
+        let config = process("name: ghost\ncommand: no-such-program-here --flag");
+        let result = preview(&config, &settings, &[]).await;
+
+        assert_eq!(result.argv, ["no-such-program-here", "--flag"]);
+        assert!(result.exe_path.is_none());
+        assert!(result.is_startable());
+        assert!(result
+            .warnings
+            .contains(&"Command 'no-such-program-here' was not found on PATH".to_string()));
+
+        let result = preview(&process("name: shell\ncommand: sh -c true"), &settings, &[]).await;
+        assert!(result.exe_path.unwrap().is_absolute());
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    }
+
+    #[tokio::test]
+    async fn test_preview_reports_occupied_port() {
+        let settings = GlobalSettings::default();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = listener.local_addr().unwrap().port();
+
+        let api = process(&format!("name: api\ncommand: sh --port {}", taken));
+        let other = process(&format!(
+            "name: other\ncommand: sh\nenv:\n  PORT: \"{}\"",
+            taken
+        ));
+        let result = preview(&api, &settings, &[api.clone(), other]).await;
+
+        assert_eq!(result.ports.len(), 1);
+        assert_eq!(result.ports[0].port, taken);
+        assert!(result.ports[0].in_use);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|warning| warning.starts_with(&format!("Port {} is already in use", taken))));
+        assert!(result.warnings.contains(&format!(
+            "Port {} is also declared by process 'other'",
+            taken
+        )));
+
+        drop(listener);
+        let result = preview(
+            &process(&format!("name: api\ncommand: sh --port {}", taken)),
+            &settings,
+            &[],
+        )
+        .await;
+        assert!(!result.ports[0].in_use);
+        assert!(result.ports[0].owner.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preview_masks_secrets_and_flags_dangerous_env() {
+        let settings = GlobalSettings::default();
+        let config = process(
+            r#"
+name: api
+command: sh
+args: ["--api-token", "hunter2", "--verbose"]
+env:
+  DATABASE_PASSWORD: hunter2
+  LD_PRELOAD: /tmp/evil.so
+  MODE: dev
+"#,
+        );
+        let result = preview(&config, &settings, &[]).await;
+
+        assert_eq!(
+            result.argv,
+            ["sh", "--api-token", secrets::MASK, "--verbose"]
+        );
+        assert_eq!(result.env["DATABASE_PASSWORD"], secrets::MASK);
+        assert_eq!(result.env["MODE"], "dev");
+        assert_eq!(result.env["LD_PRELOAD"], "/tmp/evil.so");
+        assert!(!serde_json::to_string(&result).unwrap().contains("hunter2"));
+        assert!(result.warnings.contains(
+            &"Environment variable LD_PRELOAD changes how the program is loaded".to_string()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_preview_reports_cwd_and_validation_problems() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let settings = GlobalSettings {
+            allowed_roots: vec![dir.path().to_path_buf()],
+            ..GlobalSettings::default()
+        };
+
+        let config = process(&format!(
+            "name: web\ncommand: sh\ncwd: {}",
+            dir.path().display()
+        ));
+        let result = preview(&config, &settings, &[]).await;
+        assert_eq!(result.cwd, Some(dir.path().canonicalize().unwrap()));
+        assert!(result.is_startable(), "{:?}", result.errors);
+
+        let missing = dir.path().join("missing");
+        let config = process(&format!(
+            "name: web\ncommand: sudo sh\ncwd: {}",
+            missing.display()
+        ));
+        let result = preview(&config, &settings, &[]).await;
+        assert_eq!(result.errors.len(), 2, "{:?}", result.errors);
+        assert!(result.errors[0].contains("blocked command 'sudo'"));
+        assert!(result.warnings.contains(&format!(
+            "Working directory {} does not exist",
+            missing.display()
+        )));
+    }
+}
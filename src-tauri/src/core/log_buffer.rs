@@ -1,17 +1,24 @@
 //! Log buffer implementation with circular buffer pattern.
 //!
-//! Efficiently stores last N log lines per process using VecDeque.
+//! Efficiently stores last N log lines per process using VecDeque, up to a
+//! line count and a total size, whichever is reached first.
 //!
 //! Part of Sentinel - Your Development Guardian
 //! Built by Glincker (A GLINR Product)
 
+use crate::core::log_format::LogLevel;
+use crate::core::log_search::{LogMatch, LogQuery};
+use crate::core::log_timestamp::{self, TimestampOrder};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 /// Maximum log lines to retain per process (10,000 lines).
 const DEFAULT_MAX_LINES: usize = 10_000;
 
+/// Maximum bytes of line text to retain per process (32 MiB).
+pub const DEFAULT_MAX_BYTES: usize = 32 * 1024 * 1024;
+
 /// Log line with timestamp and stream information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +29,64 @@ pub struct LogLine {
     pub stream: LogStream,
     /// The actual log line content
     pub line: String,
+    /// Time written at the start of the line, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_timestamp: Option<DateTime<Utc>>,
+    /// Position among all lines of the process, counting from 0; set when
+    /// the line is pushed to a buffer
+    #[serde(default)]
+    pub seq: u64,
+    /// Severity, for a structured line that gives one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LogLevel>,
+    /// Fields of a structured line other than its message, level and time,
+    /// nested objects flattened to dotted keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+impl LogLine {
+    /// Creates a line received at `timestamp`, reading the time it carries
+    /// from its start.
+    pub fn new(timestamp: DateTime<Utc>, stream: LogStream, line: String) -> Self {
+        Self {
+            source_timestamp: log_timestamp::extract(&line, timestamp),
+            timestamp,
+            stream,
+            line,
+            seq: 0,
+            level: None,
+            fields: None,
+        }
+    }
+
+    /// The time the line is ordered and filtered by.
+    pub fn ordered_at(&self, order: TimestampOrder) -> DateTime<Utc> {
+        match order {
+            TimestampOrder::Source => self.source_timestamp.unwrap_or(self.timestamp),
+            TimestampOrder::Received => self.timestamp,
+        }
+    }
+
+    /// Sort key for `order`: the time, then the sequence number for lines
+    /// of the same process stamped with the same time.
+    pub fn order_key(&self, order: TimestampOrder) -> (DateTime<Utc>, u64) {
+        (self.ordered_at(order), self.seq)
+    }
+
+    /// Bytes of text the line holds, counting its fields' keys and values.
+    fn text_len(&self) -> usize {
+        let fields = self.fields.as_ref().map_or(0, |fields| {
+            fields
+                .iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(text) => key.len() + text.len(),
+                    other => key.len() + other.to_string().len(),
+                })
+                .sum()
+        });
+        self.line.len() + fields
+    }
 }
 
 /// Log stream type (stdout or stderr).
@@ -34,9 +99,53 @@ pub enum LogStream {
     Stderr,
 }
 
+/// Memory held by one log buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogMemoryUsage {
+    /// Managed process name, or the name of the attached process or file.
+    pub process: String,
+    /// Set for the buffer of an attached external process.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<String>,
+    pub lines: usize,
+    /// Bytes of line text.
+    pub bytes: usize,
+    pub max_lines: usize,
+    /// Byte limit, `None` when unlimited.
+    pub max_bytes: Option<usize>,
+}
+
+/// Memory held by all log buffers, as returned by `get_log_memory_stats`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogMemoryStats {
+    pub total_lines: usize,
+    pub total_bytes: usize,
+    /// Largest first.
+    pub buffers: Vec<LogMemoryUsage>,
+}
+
+impl LogMemoryStats {
+    /// Adds up `buffers` and orders them largest first.
+    pub fn new(mut buffers: Vec<LogMemoryUsage>) -> Self {
+        buffers.sort_by(|a, b| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then_with(|| a.process.cmp(&b.process))
+        });
+        Self {
+            total_lines: buffers.iter().map(|b| b.lines).sum(),
+            total_bytes: buffers.iter().map(|b| b.bytes).sum(),
+            buffers,
+        }
+    }
+}
+
 /// Circular buffer for storing log lines.
 ///
-/// Automatically drops oldest lines when capacity is reached.
+/// Automatically drops oldest lines when the line or byte limit is reached.
+/// A single line larger than the byte limit is still kept, on its own.
 /// Uses VecDeque for O(1) push/pop at both ends.
 ///
 /// # Examples
@@ -46,11 +155,11 @@ pub enum LogStream {
 ///
 /// let mut buffer = LogBuffer::new();
 ///
-/// buffer.push(LogLine {
-///     timestamp: Utc::now(),
-///     stream: LogStream::Stdout,
-///     line: "Hello, world!".to_string(),
-/// });
+/// buffer.push(LogLine::new(
+///     Utc::now(),
+///     LogStream::Stdout,
+///     "Hello, world!".to_string(),
+/// ));
 ///
 /// assert_eq!(buffer.len(), 1);
 /// ```
@@ -59,30 +168,76 @@ pub struct LogBuffer {
     lines: VecDeque<LogLine>,
     /// Maximum number of lines to retain
     max_lines: usize,
+    /// Maximum bytes of line text to retain, if limited
+    max_bytes: Option<usize>,
+    /// Bytes of line text currently stored
+    bytes: usize,
+    /// Lines pushed since creation, including dropped and cleared ones
+    total_pushed: u64,
 }
 
 impl LogBuffer {
-    /// Creates a new log buffer with default capacity (10,000 lines).
+    /// Creates a new log buffer with default capacity (10,000 lines, 32 MiB).
     pub fn new() -> Self {
-        Self::with_capacity(DEFAULT_MAX_LINES)
+        Self::with_limits(DEFAULT_MAX_LINES, Some(DEFAULT_MAX_BYTES))
     }
 
-    /// Creates a new log buffer with specified capacity.
+    /// Creates a new log buffer with specified capacity and no byte limit.
     pub fn with_capacity(max_lines: usize) -> Self {
+        Self::with_limits(max_lines, None)
+    }
+
+    /// Creates a new log buffer holding at most `max_lines` lines and
+    /// `max_bytes` bytes of line text.
+    pub fn with_limits(max_lines: usize, max_bytes: Option<usize>) -> Self {
         Self {
-            lines: VecDeque::with_capacity(max_lines),
+            lines: VecDeque::with_capacity(max_lines.min(DEFAULT_MAX_LINES)),
             max_lines,
+            max_bytes,
+            bytes: 0,
+            total_pushed: 0,
         }
     }
 
-    /// Pushes a new log line to the buffer.
+    /// Pushes a new log line to the buffer, numbering it after the last.
     ///
-    /// If buffer is at capacity, drops the oldest line (FIFO).
-    pub fn push(&mut self, line: LogLine) {
-        if self.lines.len() >= self.max_lines {
-            self.lines.pop_front();
+    /// Drops the oldest lines (FIFO) until the buffer is within its limits
+    /// again, keeping at least the new line.
+    pub fn push(&mut self, line: LogLine) -> u64 {
+        let seq = self.append(line);
+        self.trim();
+        seq
+    }
+
+    /// Pushes several lines at once, in order, then drops the oldest lines
+    /// as [`push`](Self::push) does.
+    pub fn push_all(&mut self, lines: impl IntoIterator<Item = LogLine>) {
+        for line in lines {
+            self.append(line);
         }
+        self.trim();
+    }
+
+    /// Numbers and appends a line without trimming, returning its number.
+    fn append(&mut self, mut line: LogLine) -> u64 {
+        let seq = self.total_pushed;
+        line.seq = seq;
+        self.bytes += line.text_len();
         self.lines.push_back(line);
+        self.total_pushed += 1;
+        seq
+    }
+
+    /// Drops the oldest lines while over either limit. Each line is dropped
+    /// at most once, so pushing stays O(1) amortized.
+    fn trim(&mut self) {
+        while self.lines.len() > self.max_lines.max(1)
+            || (self.lines.len() > 1 && self.max_bytes.is_some_and(|max| self.bytes > max))
+        {
+            if let Some(dropped) = self.lines.pop_front() {
+                self.bytes -= dropped.text_len();
+            }
+        }
     }
 
     /// Returns all log lines as a vector (cloned).
@@ -105,6 +260,15 @@ impl LogBuffer {
             .collect()
     }
 
+    /// Runs a compiled search over the buffered lines.
+    ///
+    /// # Returns
+    /// The matches with their context lines, and whether the result was cut
+    /// short by the query's per-process limit.
+    pub fn query(&self, query: &LogQuery) -> (Vec<LogMatch>, bool) {
+        query.search(&self.lines)
+    }
+
     /// Filters logs by stream type.
     pub fn filter_by_stream(&self, stream: LogStream) -> Vec<LogLine> {
         self.lines
@@ -114,25 +278,77 @@ impl LogBuffer {
             .collect()
     }
 
+    /// Filters structured logs by field equality (see
+    /// [`LogLine::matches_fields`]); plain lines never match.
+    pub fn filter_by_fields(&self, filter: &HashMap<String, String>) -> Vec<LogLine> {
+        self.lines
+            .iter()
+            .filter(|line| line.matches_fields(filter))
+            .cloned()
+            .collect()
+    }
+
     /// Returns the number of lines currently stored.
     pub fn len(&self) -> usize {
         self.lines.len()
     }
 
+    /// Returns the number of lines ever pushed, including ones since dropped
+    /// or cleared.
+    pub fn total_pushed(&self) -> u64 {
+        self.total_pushed
+    }
+
     /// Returns true if buffer is empty.
     pub fn is_empty(&self) -> bool {
         self.lines.is_empty()
     }
 
+    /// Returns the bytes of line text currently stored.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Describes the buffer's size and limits, as the buffer of `process`.
+    pub fn memory_usage(&self, process: String) -> LogMemoryUsage {
+        LogMemoryUsage {
+            process,
+            attachment_id: None,
+            lines: self.lines.len(),
+            bytes: self.bytes,
+            max_lines: self.max_lines,
+            max_bytes: self.max_bytes,
+        }
+    }
+
     /// Clears all log lines from the buffer.
     pub fn clear(&mut self) {
         self.lines.clear();
+        self.bytes = 0;
     }
 
     /// Returns the maximum capacity of the buffer.
     pub fn capacity(&self) -> usize {
         self.max_lines
     }
+
+    /// Returns the maximum bytes of line text, if limited.
+    pub fn byte_limit(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Changes the capacity, dropping the oldest lines if the buffer shrinks.
+    pub fn set_capacity(&mut self, max_lines: usize) {
+        self.max_lines = max_lines;
+        self.trim();
+    }
+
+    /// Changes both limits, dropping the oldest lines if the buffer shrinks.
+    pub fn set_limits(&mut self, max_lines: usize, max_bytes: Option<usize>) {
+        self.max_lines = max_lines;
+        self.max_bytes = max_bytes;
+        self.trim();
+    }
 }
 
 impl Default for LogBuffer {
@@ -146,11 +362,11 @@ mod tests {
     use super::*;
 
     fn create_log_line(content: &str, stream: LogStream) -> LogLine {
-        LogLine {
-            timestamp: Utc::now(),
-            stream,
-            line: content.to_string(),
-        }
+        LogLine::new(Utc::now(), stream, content.to_string())
+    }
+
+    fn texts(buffer: &LogBuffer) -> Vec<String> {
+        buffer.get_all().into_iter().map(|line| line.line).collect()
     }
 
     #[test]
@@ -191,6 +407,72 @@ mod tests {
         assert_eq!(lines[2].line, "line 4");
     }
 
+    #[test]
+    fn test_set_capacity_drops_oldest() {
+        let mut buffer = LogBuffer::with_capacity(5);
+
+        for i in 0..5 {
+            buffer.push(create_log_line(&format!("line {}", i), LogStream::Stdout));
+        }
+        buffer.set_capacity(2);
+
+        assert_eq!(buffer.capacity(), 2);
+        let lines = buffer.get_all();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].line, "line 3");
+        assert_eq!(lines[1].line, "line 4");
+    }
+
+    #[test]
+    fn test_byte_limit_drops_oldest() {
+        let mut buffer = LogBuffer::with_limits(100, Some(20));
+
+        for i in 0..5 {
+            buffer.push(create_log_line(&format!("line {}", i), LogStream::Stdout));
+        }
+
+        // Six bytes per line, so three fit
+        assert_eq!(texts(&buffer), ["line 2", "line 3", "line 4"]);
+        assert_eq!(buffer.bytes(), 18);
+
+        buffer.set_limits(100, Some(12));
+        assert_eq!(texts(&buffer), ["line 3", "line 4"]);
+        assert_eq!(buffer.bytes(), 12);
+
+        buffer.clear();
+        assert_eq!(buffer.bytes(), 0);
+    }
+
+    #[test]
+    fn test_oversized_line_is_kept_alone() {
+        let mut buffer = LogBuffer::with_limits(100, Some(1024));
+        buffer.push(create_log_line("before", LogStream::Stdout));
+
+        let blob = format!("{{\"data\":\"{}\"}}", "x".repeat(4096));
+        buffer.push(create_log_line(&blob, LogStream::Stdout));
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.get_all()[0].line, blob);
+        assert_eq!(buffer.bytes(), blob.len());
+
+        // The next line pushes it out
+        buffer.push(create_log_line("after", LogStream::Stdout));
+        assert_eq!(texts(&buffer), ["after"]);
+        assert_eq!(buffer.bytes(), 5);
+    }
+
+    #[test]
+    fn test_line_limit_applies_with_byte_limit() {
+        let mut buffer = LogBuffer::with_limits(2, Some(1024));
+
+        for i in 0..5 {
+            buffer.push(create_log_line(&format!("line {}", i), LogStream::Stdout));
+        }
+
+        assert_eq!(texts(&buffer), ["line 3", "line 4"]);
+        assert_eq!(buffer.bytes(), 12);
+        assert_eq!(buffer.total_pushed(), 5);
+    }
+
     #[test]
     fn test_get_last_n() {
         let mut buffer = LogBuffer::with_capacity(10);
@@ -252,4 +534,30 @@ mod tests {
         assert_eq!(buffer.len(), 0);
         assert!(buffer.is_empty());
     }
+
+    #[test]
+    fn test_lines_are_numbered_as_pushed() {
+        let mut buffer = LogBuffer::with_capacity(3);
+        assert_eq!(buffer.push(create_log_line("a", LogStream::Stdout)), 0);
+        buffer.push_all(["b", "c", "d"].map(|t| create_log_line(t, LogStream::Stderr)));
+        buffer.clear();
+        assert_eq!(buffer.push(create_log_line("e", LogStream::Stdout)), 4);
+
+        buffer.push_all(["f", "g", "h"].map(|t| create_log_line(t, LogStream::Stdout)));
+        let seqs: Vec<u64> = buffer.get_all().iter().map(|line| line.seq).collect();
+        assert_eq!(seqs, [5, 6, 7]);
+        assert_eq!(texts(&buffer), ["f", "g", "h"]);
+    }
+
+    #[test]
+    fn test_total_pushed_survives_drops_and_clear() {
+        let mut buffer = LogBuffer::with_capacity(2);
+        for i in 0..5 {
+            buffer.push(create_log_line(&format!("line {}", i), LogStream::Stdout));
+        }
+        buffer.clear();
+
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.total_pushed(), 5);
+    }
 }
@@ -0,0 +1,340 @@
+//! Splitting process output into lines, whatever its encoding.
+//!
+//! Output is read as bytes and split on `\n` (with a trailing `\r`
+//! dropped) before being decoded, so text that isn't valid UTF-8 never
+//! stops a reader: invalid sequences become U+FFFD. The encoding is taken
+//! from the process's `outputEncoding`, or detected from the first bytes:
+//! a byte order mark, or the zero bytes of ASCII text in UTF-16.
+//!
+//! Lines longer than a limit are cut at a character boundary and end with
+//! [`CONTINUATION_MARKER`]; the rest follows as the next line.
+
+use crate::models::OutputEncoding;
+
+/// Appended to a line that was cut at the length limit.
+pub const CONTINUATION_MARKER: &str = " …";
+
+/// Longest line, in bytes of output, before it is cut when the settings
+/// don't say otherwise (64 KiB).
+pub const DEFAULT_MAX_LINE_BYTES: usize = 64 * 1024;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF16LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF16BE_BOM: &[u8] = b"\xFE\xFF";
+
+/// Splits a stream of output bytes into decoded lines.
+///
+/// # Examples
+/// ```
+/// use sentinel::core::log_decoder::LineDecoder;
+/// use sentinel::models::OutputEncoding;
+///
+/// let mut decoder = LineDecoder::new(OutputEncoding::Latin1, None);
+/// assert_eq!(decoder.feed(b"caf\xE9\r\nna"), ["café"]);
+/// assert_eq!(decoder.feed(b"\xEFve\n"), ["naïve"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineDecoder {
+    encoding: OutputEncoding,
+    max_line_bytes: Option<usize>,
+    /// Bytes of a line that hasn't been terminated yet.
+    pending: Vec<u8>,
+}
+
+impl LineDecoder {
+    /// Decoder for output in `encoding`, cutting lines longer than
+    /// `max_line_bytes` bytes when set.
+    pub fn new(encoding: OutputEncoding, max_line_bytes: Option<usize>) -> Self {
+        Self {
+            encoding,
+            max_line_bytes: max_line_bytes.map(|max| max.max(4)),
+            pending: Vec::new(),
+        }
+    }
+
+    /// The encoding being decoded, once detected.
+    pub fn encoding(&self) -> OutputEncoding {
+        self.encoding
+    }
+
+    /// Bytes received that are not part of a returned line yet.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Adds `bytes` of output, returning the lines they complete.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+        if self.encoding == OutputEncoding::Auto && !self.detect() {
+            return Vec::new();
+        }
+
+        let unit = self.unit_len();
+        let mut lines = Vec::new();
+        let mut start = 0;
+        loop {
+            let rest = &self.pending[start..];
+            match self.find_newline(rest) {
+                Some(end) if self.max_line_bytes.is_none_or(|max| end <= max) => {
+                    lines.push(self.decode(self.trim_cr(&rest[..end])));
+                    start += end + unit;
+                }
+                _ => match self.max_line_bytes {
+                    Some(max) if rest.len() > max => {
+                        let cut = self.boundary(rest, max);
+                        let mut line = self.decode(&rest[..cut]);
+                        line.push_str(CONTINUATION_MARKER);
+                        lines.push(line);
+                        start += cut;
+                    }
+                    _ => break,
+                },
+            }
+        }
+        self.pending.drain(..start);
+        lines
+    }
+
+    /// Decodes whatever complete characters have been received, without
+    /// splitting them into lines, for output shown as a terminal stream.
+    pub fn feed_text(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        if self.encoding == OutputEncoding::Auto && !self.detect() {
+            return String::new();
+        }
+        let end = self.boundary(&self.pending, self.pending.len());
+        let text = self.decode(&self.pending[..end]);
+        self.pending.drain(..end);
+        text
+    }
+
+    /// The unterminated last line once the output has ended, if any.
+    pub fn finish(&mut self) -> Option<String> {
+        if self.encoding == OutputEncoding::Auto {
+            self.detect();
+            if self.encoding == OutputEncoding::Auto {
+                self.encoding = OutputEncoding::Utf8;
+            }
+        }
+        if self.pending.is_empty() {
+            return None;
+        }
+        let pending = std::mem::take(&mut self.pending);
+        Some(self.decode(self.trim_cr(&pending)))
+    }
+
+    /// Works out the encoding from the start of the output, removing a byte
+    /// order mark. Returns false while there are too few bytes to tell.
+    fn detect(&mut self) -> bool {
+        let start = &self.pending;
+        if start.len() < 2 || (start.len() < 3 && UTF8_BOM.starts_with(start)) {
+            return false;
+        }
+        let (encoding, bom) = if start.starts_with(UTF8_BOM) {
+            (OutputEncoding::Utf8, UTF8_BOM.len())
+        } else if start.starts_with(UTF16LE_BOM) {
+            (OutputEncoding::Utf16Le, UTF16LE_BOM.len())
+        } else if start.starts_with(UTF16BE_BOM) {
+            (OutputEncoding::Utf16Be, UTF16BE_BOM.len())
+        } else if start[0] != 0 && start[1] == 0 {
+            (OutputEncoding::Utf16Le, 0)
+        } else if start[0] == 0 && start[1] != 0 {
+            (OutputEncoding::Utf16Be, 0)
+        } else {
+            (OutputEncoding::Utf8, 0)
+        };
+        self.encoding = encoding;
+        self.pending.drain(..bom);
+        true
+    }
+
+    /// Bytes per code unit, and so per newline.
+    fn unit_len(&self) -> usize {
+        match self.encoding {
+            OutputEncoding::Utf16Le | OutputEncoding::Utf16Be => 2,
+            _ => 1,
+        }
+    }
+
+    /// Offset of the first newline in `bytes`.
+    fn find_newline(&self, bytes: &[u8]) -> Option<usize> {
+        let newline: &[u8] = match self.encoding {
+            OutputEncoding::Utf16Le => b"\n\0",
+            OutputEncoding::Utf16Be => b"\0\n",
+            _ => return bytes.iter().position(|&b| b == b'\n'),
+        };
+        bytes
+            .chunks_exact(2)
+            .position(|unit| unit == newline)
+            .map(|index| index * 2)
+    }
+
+    fn trim_cr<'a>(&self, line: &'a [u8]) -> &'a [u8] {
+        let cr: &[u8] = match self.encoding {
+            OutputEncoding::Utf16Le => b"\r\0",
+            OutputEncoding::Utf16Be => b"\0\r",
+            _ => b"\r",
+        };
+        line.strip_suffix(cr).unwrap_or(line)
+    }
+
+    /// The largest offset up to `max` that doesn't split a character,
+    /// or `max` itself when the character there is invalid anyway.
+    fn boundary(&self, bytes: &[u8], max: usize) -> usize {
+        match self.encoding {
+            OutputEncoding::Utf16Le | OutputEncoding::Utf16Be => {
+                let end = max - max % 2;
+                let last = match self.encoding {
+                    OutputEncoding::Utf16Le => bytes.get(end.wrapping_sub(1)),
+                    _ => bytes.get(end.wrapping_sub(2)),
+                };
+                // Keep a surrogate pair together
+                match last {
+                    Some(&byte) if end >= 2 && (0xD8..0xDC).contains(&byte) => end - 2,
+                    _ => end,
+                }
+            }
+            OutputEncoding::Utf8 | OutputEncoding::Auto => {
+                // Back up over at most three continuation bytes to a lead byte
+                let lead = (max.saturating_sub(3)..max)
+                    .rev()
+                    .find(|&i| bytes[i] & 0xC0 != 0x80);
+                match lead {
+                    Some(i) if i + utf8_len(bytes[i]) > max => i,
+                    _ => max,
+                }
+            }
+            OutputEncoding::Latin1 => max,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        match self.encoding {
+            OutputEncoding::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+            OutputEncoding::Utf16Le | OutputEncoding::Utf16Be => {
+                let little_endian = self.encoding == OutputEncoding::Utf16Le;
+                let units = bytes.chunks(2).map(|unit| match unit {
+                    [a, b] if little_endian => u16::from_le_bytes([*a, *b]),
+                    [a, b] => u16::from_be_bytes([*a, *b]),
+                    // A lone byte at the end can't be a character
+                    _ => 0xFFFD,
+                });
+                char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect()
+            }
+            OutputEncoding::Utf8 | OutputEncoding::Auto => {
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+        }
+    }
+}
+
+/// Length of the UTF-8 sequence `lead` starts, 1 for an invalid lead byte.
+fn utf8_len(lead: u8) -> usize {
+    match lead {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    /// Feeds `bytes` a few at a time, as a pipe might deliver them.
+    fn lines_in_pieces(decoder: &mut LineDecoder, bytes: &[u8], piece: usize) -> Vec<String> {
+        let mut lines: Vec<String> = bytes
+            .chunks(piece)
+            .flat_map(|chunk| decoder.feed(chunk))
+            .collect();
+        lines.extend(decoder.finish());
+        lines
+    }
+
+    #[test]
+    fn test_splits_on_lf_and_crlf() {
+        let mut decoder = LineDecoder::new(OutputEncoding::Utf8, None);
+        assert_eq!(decoder.feed(b"one\r\ntwo\nthr"), ["one", "two"]);
+        assert_eq!(decoder.pending_len(), 3);
+        assert_eq!(decoder.feed(b"ee\n\n"), ["three", ""]);
+        assert_eq!(decoder.feed(b"last"), Vec::<String>::new());
+        assert_eq!(decoder.finish().as_deref(), Some("last"));
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_replaced_not_fatal() {
+        let mut decoder = LineDecoder::new(OutputEncoding::Auto, None);
+        let lines = decoder.feed(b"caf\xE9 ol\xE9\n\x00\xFF\xFEbinary\nstill here\n");
+        assert_eq!(decoder.encoding(), OutputEncoding::Utf8);
+        assert_eq!(lines[0], "caf\u{FFFD} ol\u{FFFD}");
+        assert_eq!(lines[2], "still here");
+    }
+
+    #[test]
+    fn test_latin1_override() {
+        let fixture = b"Gr\xFC\xDFe aus K\xF6ln\r\nna\xEFve caf\xE9\n";
+        let mut decoder = LineDecoder::new(OutputEncoding::Latin1, None);
+        assert_eq!(
+            lines_in_pieces(&mut decoder, fixture, 3),
+            ["Grüße aus Köln", "naïve café"]
+        );
+    }
+
+    #[test]
+    fn test_utf16le_is_detected() {
+        let text = "Building…\r\nDone ✓ 𝄞\r\n";
+
+        // With a byte order mark, split anywhere
+        let mut fixture = UTF16LE_BOM.to_vec();
+        fixture.extend(utf16le(text));
+        let mut decoder = LineDecoder::new(OutputEncoding::Auto, None);
+        assert_eq!(
+            lines_in_pieces(&mut decoder, &fixture, 3),
+            ["Building…", "Done ✓ 𝄞"]
+        );
+        assert_eq!(decoder.encoding(), OutputEncoding::Utf16Le);
+
+        // Without one, from the zero bytes of ASCII
+        let mut decoder = LineDecoder::new(OutputEncoding::Auto, None);
+        assert_eq!(
+            lines_in_pieces(&mut decoder, &utf16le(text), 1),
+            ["Building…", "Done ✓ 𝄞"]
+        );
+    }
+
+    #[test]
+    fn test_long_lines_are_cut_at_character_boundaries() {
+        let mut decoder = LineDecoder::new(OutputEncoding::Utf8, Some(8));
+        // 'é' is two bytes and would straddle the limit
+        let lines = decoder.feed("abcdefgé rest\nshort\n".as_bytes());
+        assert_eq!(lines, ["abcdefg …", "é rest", "short"]);
+
+        // Progress output that never ends a line is still cut
+        let mut decoder = LineDecoder::new(OutputEncoding::Utf8, Some(10));
+        let lines = decoder.feed(&b"\r[=====]".repeat(5));
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.ends_with(CONTINUATION_MARKER)));
+        assert!(decoder.pending_len() <= 10);
+
+        // Surrogate pairs stay whole in UTF-16
+        let mut decoder = LineDecoder::new(OutputEncoding::Utf16Le, Some(6));
+        let lines = lines_in_pieces(&mut decoder, &utf16le("ab𝄞c\n"), 64);
+        assert_eq!(lines, ["ab …", "𝄞c"]);
+    }
+
+    #[test]
+    fn test_feed_text_keeps_split_characters() {
+        let mut decoder = LineDecoder::new(OutputEncoding::Auto, None);
+        let bytes = "✓ ok\r\n".as_bytes();
+        let text: String = bytes.iter().map(|b| decoder.feed_text(&[*b])).collect();
+        assert_eq!(text, "✓ ok\r\n");
+    }
+}
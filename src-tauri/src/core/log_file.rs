@@ -0,0 +1,397 @@
+//! Process output persisted to log files.
+//!
+//! With `settings.persistLogs`, every line a process writes is appended to
+//! `<name>.log` in the log directory (see
+//! [`log_retention::log_directory`]) as `<time> <stream> <line>`. A file is
+//! rotated once it would grow past `settings.maxLogSize`, like the event
+//! journal: it moves to `<name>.log.1`, older rotated files move up one
+//! place, and the ones beyond `settings.maxLogFiles` are deleted.
+//! [`log_retention`] prunes the rotated files further by age and total size.
+//!
+//! Lines are queued for a background writer thread, so a slow disk never
+//! holds up reading a process's output. When the queue is full or a file
+//! can't be written, lines are left out of the file with a warning; they
+//! are still in the process's log buffer.
+
+use crate::core::log_buffer::LogStream;
+use crate::core::log_retention;
+use crate::models::GlobalSettings;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Batches of lines waiting to be written; more are dropped.
+const QUEUE_CAPACITY: usize = 4096;
+
+/// Location and rotation policy of the process log files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFiles {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl LogFiles {
+    /// Log files in `dir`, rotated once they would grow past `max_bytes`,
+    /// keeping `max_files` rotated files per process.
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+            max_files,
+        }
+    }
+
+    /// The log files `settings` asks for; `None` without `persistLogs`.
+    pub fn from_settings(settings: &GlobalSettings) -> Option<Self> {
+        settings.persist_logs.then(|| {
+            Self::new(
+                log_retention::log_directory(settings),
+                settings.max_log_size,
+                settings.max_log_files as usize,
+            )
+        })
+    }
+
+    /// Directory the files are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the file `process` writes to.
+    pub fn path(&self, process: &str) -> PathBuf {
+        self.dir.join(format!("{}.log", process))
+    }
+
+    /// Path of the `index`th rotated file of `process`, 1 being the newest.
+    fn rotated_path(&self, process: &str, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.log.{}", process, index))
+    }
+}
+
+/// Queues process output for the log file writer thread.
+///
+/// Cheap to clone; the thread exits once every clone is dropped.
+#[derive(Clone, Default)]
+pub struct LogWriter {
+    shared: Option<Arc<Shared>>,
+}
+
+struct Shared {
+    files: LogFiles,
+    sender: SyncSender<Message>,
+    dropped: Arc<AtomicU64>,
+}
+
+enum Message {
+    /// Formatted lines of a process, each ending with a newline.
+    Lines {
+        process: String,
+        text: String,
+    },
+    Flush(mpsc::Sender<()>),
+}
+
+impl LogWriter {
+    /// Creates a writer that discards lines.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Creates a writer for the log files `settings` asks for, disabled
+    /// without `persistLogs`.
+    pub fn from_settings(settings: &GlobalSettings) -> Self {
+        LogFiles::from_settings(settings).map_or_else(Self::disabled, Self::open)
+    }
+
+    /// Creates a writer appending to `files` from a background thread.
+    pub fn open(files: LogFiles) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let writer = FileWriter {
+            files: files.clone(),
+            open: HashMap::new(),
+            failing: false,
+            dropped: dropped.clone(),
+        };
+        let spawned = std::thread::Builder::new()
+            .name("process-logs".to_string())
+            .spawn(move || writer.run(receiver));
+        if let Err(e) = spawned {
+            warn!("Failed to start process log writer: {}", e);
+            return Self::disabled();
+        }
+
+        Self {
+            shared: Some(Arc::new(Shared {
+                files,
+                sender,
+                dropped,
+            })),
+        }
+    }
+
+    /// Files written to, `None` when disabled.
+    pub fn files(&self) -> Option<&LogFiles> {
+        self.shared.as_ref().map(|shared| &shared.files)
+    }
+
+    /// Queues `lines` that `process` wrote to `stream`, received at
+    /// `received`, dropping them if the queue is full.
+    pub fn write(
+        &self,
+        process: &str,
+        received: DateTime<Utc>,
+        stream: LogStream,
+        lines: &[String],
+    ) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+        if lines.is_empty() {
+            return;
+        }
+        let time = received.to_rfc3339_opts(SecondsFormat::Millis, true);
+        let stream = match stream {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+        };
+        let mut text = String::new();
+        for line in lines {
+            let _ = writeln!(text, "{} {} {}", time, stream, line);
+        }
+
+        let message = Message::Lines {
+            process: process.to_string(),
+            text,
+        };
+        match shared.sender.try_send(message) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                shared
+                    .dropped
+                    .fetch_add(lines.len() as u64, Ordering::Relaxed);
+                warn!(
+                    "Process log queue is full; left {} line(s) of '{}' out of its log file",
+                    lines.len(),
+                    process
+                );
+            }
+            Err(_) => {
+                shared
+                    .dropped
+                    .fetch_add(lines.len() as u64, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of lines left out of the files because the queue was full or
+    /// a file couldn't be written.
+    pub fn dropped(&self) -> u64 {
+        self.shared
+            .as_ref()
+            .map_or(0, |shared| shared.dropped.load(Ordering::Relaxed))
+    }
+
+    /// Blocks until every line queued so far has been written or dropped.
+    pub fn flush(&self) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+        let (ack, done) = mpsc::channel();
+        if shared.sender.send(Message::Flush(ack)).is_ok() {
+            let _ = done.recv();
+        }
+    }
+}
+
+/// A log file being appended to.
+struct OpenFile {
+    file: File,
+    /// Size of the file.
+    size: u64,
+}
+
+/// Owns the open log files on the writer thread.
+struct FileWriter {
+    files: LogFiles,
+    open: HashMap<String, OpenFile>,
+    /// Whether the last write failed, so failures are logged once.
+    failing: bool,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FileWriter {
+    fn run(mut self, receiver: Receiver<Message>) {
+        for message in receiver {
+            match message {
+                Message::Lines { process, text } => self.write(&process, &text),
+                Message::Flush(ack) => {
+                    let _ = ack.send(());
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, process: &str, text: &str) {
+        match self.append(process, text.as_bytes()) {
+            Ok(()) => {
+                if self.failing {
+                    info!("Process log files are writable again");
+                    self.failing = false;
+                }
+            }
+            Err(e) => {
+                // Reopen next time in case the file was moved or deleted
+                self.open.remove(process);
+                self.dropped
+                    .fetch_add(text.lines().count() as u64, Ordering::Relaxed);
+                if !self.failing {
+                    warn!(
+                        "Failed to write log file {}, leaving lines out until it recovers: {}",
+                        self.files.path(process).display(),
+                        e
+                    );
+                    self.failing = true;
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, process: &str, text: &[u8]) -> std::io::Result<()> {
+        let size = match self.open.get(process) {
+            Some(open) => open.size,
+            None => self.open(process)?,
+        };
+        if size > 0 && size + text.len() as u64 > self.files.max_bytes {
+            self.rotate(process)?;
+            self.open(process)?;
+        }
+
+        if let Some(open) = self.open.get_mut(process) {
+            open.file.write_all(text)?;
+            open.size += text.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Opens the file of `process` for appending, returning its size.
+    fn open(&mut self, process: &str) -> std::io::Result<u64> {
+        fs::create_dir_all(&self.files.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.files.path(process))?;
+        let size = file.metadata()?.len();
+        self.open
+            .insert(process.to_string(), OpenFile { file, size });
+        Ok(size)
+    }
+
+    /// Shifts the rotated files of `process` up one place and moves its
+    /// file to `.1`.
+    fn rotate(&mut self, process: &str) -> std::io::Result<()> {
+        self.open.remove(process);
+
+        let path = self.files.path(process);
+        let max_files = self.files.max_files;
+        if max_files == 0 {
+            return fs::remove_file(path);
+        }
+        for index in (1..max_files).rev() {
+            match fs::rename(
+                self.files.rotated_path(process, index),
+                self.files.rotated_path(process, index + 1),
+            ) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        fs::rename(path, self.files.rotated_path(process, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_writes_lines_with_time_and_stream() {
+        let dir = TempDir::new().unwrap();
+        let files = LogFiles::new(dir.path().join("logs"), 1024, 2);
+        let writer = LogWriter::open(files.clone());
+
+        let received = "2026-10-15T12:00:00.250Z".parse().unwrap();
+        writer.write("api", received, LogStream::Stdout, &lines(&["listening"]));
+        writer.write(
+            "api",
+            received,
+            LogStream::Stderr,
+            &lines(&["warn: slow", ""]),
+        );
+        writer.flush();
+
+        let content = fs::read_to_string(files.path("api")).unwrap();
+        assert_eq!(
+            content,
+            "2026-10-15T12:00:00.250Z stdout listening\n\
+             2026-10-15T12:00:00.250Z stderr warn: slow\n\
+             2026-10-15T12:00:00.250Z stderr \n"
+        );
+        assert_eq!(writer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_rotates_by_size() {
+        let dir = TempDir::new().unwrap();
+        // Room for two lines of 39 bytes each
+        let files = LogFiles::new(dir.path(), 80, 2);
+        let writer = LogWriter::open(files.clone());
+
+        let received = Utc::now();
+        for i in 0..7 {
+            writer.write("api", received, LogStream::Stdout, &[format!("line {}", i)]);
+        }
+        writer.write("web", received, LogStream::Stdout, &lines(&["other"]));
+        writer.flush();
+
+        let read = |path: PathBuf| -> Vec<String> {
+            fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| line.rsplit(' ').next().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(read(files.path("api")), ["6"]);
+        assert_eq!(read(files.rotated_path("api", 1)), ["4", "5"]);
+        assert_eq!(read(files.rotated_path("api", 2)), ["2", "3"]);
+        // Older files beyond maxLogFiles were deleted
+        assert!(!files.rotated_path("api", 3).exists());
+        assert_eq!(read(files.path("web")), ["other"]);
+    }
+
+    #[test]
+    fn test_disabled_without_persist_logs() {
+        let mut settings = GlobalSettings::default();
+        assert!(LogWriter::from_settings(&settings).files().is_none());
+
+        settings.persist_logs = true;
+        settings.log_directory = Some(PathBuf::from("/var/log/sentinel"));
+        let writer = LogWriter::from_settings(&settings);
+        let files = writer.files().unwrap();
+        assert_eq!(files.dir(), Path::new("/var/log/sentinel"));
+        assert_eq!(files.max_bytes, settings.max_log_size);
+    }
+}
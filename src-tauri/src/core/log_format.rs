@@ -0,0 +1,322 @@
+//! Structured (JSON) log lines.
+//!
+//! A process with `logFormat: {type: json}` writes one JSON object per line,
+//! as pino, zap, bunyan and structlog do. Each line is parsed when it is
+//! pushed: the message becomes the line's text, the level and time fill in
+//! [`LogLine::level`] and [`LogLine::source_timestamp`], and every other
+//! field is kept, flattened to dotted keys (`req.id`), in
+//! [`LogLine::fields`]. Lines that aren't JSON objects, or are longer than
+//! [`MAX_STRUCTURED_LINE_BYTES`], are kept as plain text.
+
+use crate::core::log_buffer::LogLine;
+use crate::core::log_timestamp;
+use crate::models::LogFormat;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Longest line that is parsed; longer ones are kept as plain text so one
+/// huge object can't stall the reader.
+pub const MAX_STRUCTURED_LINE_BYTES: usize = 16 * 1024;
+
+/// Most fields kept from one line; the rest are dropped.
+const MAX_FIELDS: usize = 64;
+
+/// Severity of a structured log line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    /// Reads a level written as a name (`warn`, `WARNING`, `E`) or as a
+    /// pino/bunyan number (10 trace to 60 fatal).
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(name) => Self::from_name(name),
+            Value::Number(number) => match number.as_u64()? {
+                0..=10 => Some(Self::Trace),
+                11..=20 => Some(Self::Debug),
+                21..=30 => Some(Self::Info),
+                31..=40 => Some(Self::Warn),
+                41..=50 => Some(Self::Error),
+                _ => Some(Self::Fatal),
+            },
+            _ => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "trace" | "t" => Some(Self::Trace),
+            "debug" | "d" => Some(Self::Debug),
+            "info" | "information" | "notice" | "i" => Some(Self::Info),
+            "warn" | "warning" | "w" => Some(Self::Warn),
+            "error" | "err" | "e" => Some(Self::Error),
+            "fatal" | "critical" | "crit" | "panic" | "dpanic" | "emergency" | "alert" | "f" => {
+                Some(Self::Fatal)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl LogLine {
+    /// Parses the line as `format` says, leaving it as it is when it's
+    /// plain text or can't be parsed.
+    pub fn parsed(mut self, format: &LogFormat) -> Self {
+        let LogFormat::Json {
+            message_key,
+            level_key,
+            timestamp_key,
+        } = format
+        else {
+            return self;
+        };
+        if self.line.len() > MAX_STRUCTURED_LINE_BYTES || !self.line.trim_start().starts_with('{') {
+            return self;
+        }
+        let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(&self.line) else {
+            return self;
+        };
+
+        if let Some(level) = object.remove(level_key) {
+            self.level = LogLevel::from_value(&level);
+            if self.level.is_none() {
+                object.insert(level_key.clone(), level);
+            }
+        }
+        if let Some(time) = object.remove(timestamp_key) {
+            let text = match &time {
+                Value::String(text) => Some(text.clone()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            };
+            match text.and_then(|text| log_timestamp::extract(&text, self.timestamp)) {
+                Some(at) => self.source_timestamp = Some(at),
+                None => {
+                    object.insert(timestamp_key.clone(), time);
+                }
+            }
+        }
+        let message = match object.remove(message_key) {
+            Some(Value::String(message)) => Some(message),
+            Some(other) => Some(other.to_string()),
+            None => None,
+        };
+
+        let mut fields = BTreeMap::new();
+        flatten(String::new(), object, &mut fields);
+        if let Some(message) = message {
+            self.line = message;
+        }
+        self.fields = Some(fields);
+        self
+    }
+
+    /// Whether every filter matches: `level` the line's level, any other key
+    /// the field of that name, compared as text.
+    pub fn matches_fields(&self, filter: &HashMap<String, String>) -> bool {
+        let Some(fields) = &self.fields else {
+            return false;
+        };
+        filter.iter().all(|(key, expected)| {
+            if key == "level" && !fields.contains_key("level") {
+                return self.level.is_some_and(|level| {
+                    LogLevel::from_name(expected).is_some_and(|expected| level == expected)
+                });
+            }
+            fields.get(key).is_some_and(|value| match value {
+                Value::String(text) => text == expected,
+                other => {
+                    let text = other.to_string();
+                    text == *expected
+                }
+            })
+        })
+    }
+}
+
+/// Adds the fields of `object` to `fields`, nested objects under dotted keys.
+fn flatten(prefix: String, object: Map<String, Value>, fields: &mut BTreeMap<String, Value>) {
+    for (key, value) in object {
+        if fields.len() >= MAX_FIELDS {
+            return;
+        }
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Object(nested) if !nested.is_empty() => flatten(key, nested, fields),
+            value => {
+                fields.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log_buffer::{LogBuffer, LogStream};
+    use chrono::{TimeZone, Utc};
+
+    fn json() -> LogFormat {
+        LogFormat::Json {
+            message_key: "msg".to_string(),
+            level_key: "level".to_string(),
+            timestamp_key: "time".to_string(),
+        }
+    }
+
+    fn zap() -> LogFormat {
+        LogFormat::Json {
+            message_key: "msg".to_string(),
+            level_key: "level".to_string(),
+            timestamp_key: "ts".to_string(),
+        }
+    }
+
+    fn parse(format: &LogFormat, line: &str) -> LogLine {
+        LogLine::new(Utc::now(), LogStream::Stdout, line.to_string()).parsed(format)
+    }
+
+    #[test]
+    fn test_pino_line() {
+        let line = parse(
+            &json(),
+            r#"{"level":30,"time":1714564800123,"pid":4242,"hostname":"api-1","req":{"id":"abc","method":"GET"},"msg":"request completed"}"#,
+        );
+
+        assert_eq!(line.line, "request completed");
+        assert_eq!(line.level, Some(LogLevel::Info));
+        assert_eq!(
+            line.source_timestamp,
+            Some(Utc.timestamp_millis_opt(1_714_564_800_123).unwrap())
+        );
+        let fields = line.fields.unwrap();
+        assert_eq!(fields["req.id"], "abc");
+        assert_eq!(fields["req.method"], "GET");
+        assert_eq!(fields["pid"], 4242);
+        assert!(!fields.contains_key("msg"));
+        assert!(!fields.contains_key("level"));
+        assert!(!fields.contains_key("time"));
+    }
+
+    #[test]
+    fn test_zap_line() {
+        let line = parse(
+            &zap(),
+            r#"{"level":"warn","ts":1714564800.5,"caller":"server/main.go:42","msg":"slow query","duration":1.25,"query":{"table":"users"}}"#,
+        );
+
+        assert_eq!(line.line, "slow query");
+        assert_eq!(line.level, Some(LogLevel::Warn));
+        assert_eq!(
+            line.source_timestamp,
+            Some(Utc.timestamp_millis_opt(1_714_564_800_500).unwrap())
+        );
+        let fields = line.fields.unwrap();
+        assert_eq!(fields["caller"], "server/main.go:42");
+        assert_eq!(fields["duration"], 1.25);
+        assert_eq!(fields["query.table"], "users");
+    }
+
+    #[test]
+    fn test_plain_and_malformed_lines_are_kept_as_text() {
+        let mut buffer = LogBuffer::new();
+        let stream = [
+            r#"{"level":"info","time":"2024-05-01T12:00:00Z","msg":"listening","port":3000}"#,
+            "> api@1.0.0 start",
+            r#"{"level":"error","msg":"truncated"#,
+            r#"["not", "an", "object"]"#,
+            r#"{"level":"error","time":"2024-05-01T12:00:01Z","msg":"boom","err":{"type":"TypeError"}}"#,
+        ];
+        buffer.push_all(stream.iter().map(|line| parse(&json(), line)));
+        let lines = buffer.get_all();
+
+        assert_eq!(lines[0].line, "listening");
+        assert_eq!(lines[0].level, Some(LogLevel::Info));
+        for line in &lines[1..4] {
+            assert!(line.fields.is_none());
+            assert_eq!(line.level, None);
+        }
+        assert_eq!(lines[2].line, stream[2]);
+        assert_eq!(lines[4].line, "boom");
+        assert_eq!(lines[4].level, Some(LogLevel::Error));
+        assert_eq!(lines[4].fields.as_ref().unwrap()["err.type"], "TypeError");
+    }
+
+    #[test]
+    fn test_unknown_level_and_time_stay_fields() {
+        let line = parse(
+            &json(),
+            r#"{"level":"verbose","time":"yesterday","msg":"hi"}"#,
+        );
+
+        assert_eq!(line.level, None);
+        let fields = line.fields.unwrap();
+        assert_eq!(fields["level"], "verbose");
+        assert_eq!(fields["time"], "yesterday");
+
+        // Without a message the whole line stays as the text
+        let raw = r#"{"level":"info","event":"started"}"#;
+        let line = parse(&json(), raw);
+        assert_eq!(line.line, raw);
+        assert_eq!(line.level, Some(LogLevel::Info));
+    }
+
+    #[test]
+    fn test_parsing_is_bounded() {
+        let long = format!(
+            r#"{{"level":"info","msg":"{}"}}"#,
+            "x".repeat(MAX_STRUCTURED_LINE_BYTES)
+        );
+        let line = parse(&json(), &long);
+        assert!(line.fields.is_none());
+        assert_eq!(line.line, long);
+
+        let many: Vec<String> = (0..200).map(|i| format!(r#""k{:03}":{}"#, i, i)).collect();
+        let line = parse(&json(), &format!("{{{}}}", many.join(",")));
+        assert_eq!(line.fields.unwrap().len(), MAX_FIELDS);
+
+        // Plain processes are never parsed
+        let raw = r#"{"level":"info","msg":"hi"}"#;
+        let line = parse(&LogFormat::Plain, raw);
+        assert_eq!(line.line, raw);
+        assert!(line.fields.is_none());
+    }
+
+    #[test]
+    fn test_matches_fields() {
+        let line = parse(
+            &json(),
+            r#"{"level":50,"msg":"failed","req":{"id":"abc"},"status":500,"retry":false}"#,
+        );
+        let filter = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert!(line.matches_fields(&filter(&[])));
+        assert!(line.matches_fields(&filter(&[("req.id", "abc")])));
+        assert!(line.matches_fields(&filter(&[("status", "500"), ("retry", "false")])));
+        assert!(line.matches_fields(&filter(&[("level", "error")])));
+        assert!(!line.matches_fields(&filter(&[("level", "info")])));
+        assert!(!line.matches_fields(&filter(&[("req.id", "abd")])));
+        assert!(!line.matches_fields(&filter(&[("missing", "abc")])));
+
+        let plain = parse(&LogFormat::Plain, "request_id=abc");
+        assert!(!plain.matches_fields(&filter(&[])));
+    }
+}
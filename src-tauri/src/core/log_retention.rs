@@ -0,0 +1,398 @@
+//! Pruning of persisted process logs.
+//!
+//! With `settings.persistLogs`, [`log_file`](crate::core::log_file) writes
+//! the output of each process to `<name>.log` in the log directory, and
+//! rotation moves it to `<name>.log.1`, `<name>.log.2` and so on, like the
+//! event journal. Without limits the rotated files pile up forever, so
+//! `settings.logRetention` caps their age and total size. Only rotated files
+//! are ever deleted, oldest first by modification time; the file a process
+//! is writing to is counted towards the size but kept. Anything else in the
+//! directory is left alone.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::core::EventRecorder;
+use crate::error::{Result, SentinelError};
+use crate::models::{EventActor, EventType, GlobalSettings, JournalEvent, LogRetention};
+
+/// How often logs are pruned while Sentinel runs, after once at startup.
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Result of pruning the log directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPruneSummary {
+    /// Rotated log files that were deleted.
+    pub files_removed: usize,
+    /// Total size of the deleted files.
+    pub bytes_reclaimed: u64,
+    /// Entries that aren't process logs and were left alone.
+    pub files_skipped: usize,
+}
+
+/// A process log file found in the log directory.
+#[derive(Debug)]
+struct LogFile {
+    path: PathBuf,
+    process: String,
+    /// Rotated away, rather than the file the process writes to.
+    rotated: bool,
+    bytes: u64,
+    modified: SystemTime,
+    removed: bool,
+}
+
+/// The directory process logs are written to: `settings.logDirectory`, or
+/// `sentinel/logs` in the user's config directory.
+pub fn log_directory(settings: &GlobalSettings) -> PathBuf {
+    if let Some(dir) = &settings.log_directory {
+        return dir.clone();
+    }
+    match dirs::config_dir() {
+        Some(config_dir) => config_dir.join("sentinel").join("logs"),
+        None => PathBuf::from("logs"),
+    }
+}
+
+/// Prunes the log directory by `settings.logRetention` and records a
+/// `LogsPruned` event with the summary on behalf of `actor`.
+///
+/// # Errors
+/// Returns `FileIoError` if the directory can't be read. Files that can't be
+/// deleted are skipped with a warning.
+pub fn prune_logs(
+    settings: &GlobalSettings,
+    recorder: &EventRecorder,
+    actor: EventActor,
+) -> Result<LogPruneSummary> {
+    let dir = log_directory(settings);
+    let summary = prune(&dir, &settings.log_retention, SystemTime::now())?;
+    if summary.files_removed > 0 {
+        info!(
+            "Pruned {} log file(s) from {}, reclaiming {} bytes",
+            summary.files_removed,
+            dir.display(),
+            summary.bytes_reclaimed
+        );
+    }
+    recorder.record(
+        JournalEvent::new(EventType::LogsPruned, dir.display().to_string(), actor)
+            .with_detail("files_removed", summary.files_removed)
+            .with_detail("bytes_reclaimed", summary.bytes_reclaimed),
+    );
+    Ok(summary)
+}
+
+/// Deletes rotated log files in `dir` that `retention` no longer keeps as
+/// of `now`.
+///
+/// Files older than the process's `maxAgeDays` go first. Then, while a
+/// process's files exceed its own `maxTotalLogBytes`, and while the whole
+/// directory exceeds the global one, the oldest rotated files are deleted.
+/// A directory that doesn't exist has nothing to prune.
+pub fn prune(dir: &Path, retention: &LogRetention, now: SystemTime) -> Result<LogPruneSummary> {
+    let mut summary = LogPruneSummary::default();
+    if !dir.exists() {
+        return Ok(summary);
+    }
+
+    let io_error = |source| SentinelError::FileIoError {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        let path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => {
+                warn!("Skipping {}: not a log file", path.display());
+                summary.files_skipped += 1;
+                continue;
+            }
+        };
+        let Some((process, rotated)) = parse_log_name(&entry.file_name().to_string_lossy()) else {
+            warn!("Skipping {}: not a process log", path.display());
+            summary.files_skipped += 1;
+            continue;
+        };
+        files.push(LogFile {
+            path,
+            process,
+            rotated,
+            bytes: metadata.len(),
+            modified: metadata.modified().unwrap_or(now),
+            removed: false,
+        });
+    }
+    // Oldest first, so limits delete from the front
+    files.sort_by(|a, b| a.modified.cmp(&b.modified).then(a.path.cmp(&b.path)));
+
+    for file in files.iter_mut().filter(|file| file.rotated) {
+        let max_age_days = retention
+            .processes
+            .get(&file.process)
+            .and_then(|limits| limits.max_age_days)
+            .or(retention.max_age_days);
+        let age = now.duration_since(file.modified).unwrap_or_default();
+        if max_age_days.is_some_and(|days| age.as_secs() > u64::from(days) * SECONDS_PER_DAY) {
+            file.removed = true;
+        }
+    }
+
+    for (process, limits) in &retention.processes {
+        if let Some(max_bytes) = limits.max_total_log_bytes {
+            remove_oldest(&mut files, max_bytes, |file| file.process == *process);
+        }
+    }
+    if let Some(max_bytes) = retention.max_total_log_bytes {
+        remove_oldest(&mut files, max_bytes, |_| true);
+    }
+
+    for file in files.iter().filter(|file| file.removed) {
+        match fs::remove_file(&file.path) {
+            Ok(()) => {
+                summary.files_removed += 1;
+                summary.bytes_reclaimed += file.bytes;
+            }
+            Err(e) => warn!("Failed to delete {}: {}", file.path.display(), e),
+        }
+    }
+    Ok(summary)
+}
+
+/// Marks the oldest rotated files among those `selected` as removed until
+/// the ones kept add up to at most `max_bytes`.
+fn remove_oldest(files: &mut [LogFile], max_bytes: u64, selected: impl Fn(&LogFile) -> bool) {
+    let mut total: u64 = files
+        .iter()
+        .filter(|file| !file.removed && selected(file))
+        .map(|file| file.bytes)
+        .sum();
+    for file in files.iter_mut() {
+        if total <= max_bytes {
+            break;
+        }
+        if file.rotated && !file.removed && selected(file) {
+            file.removed = true;
+            total -= file.bytes;
+        }
+    }
+}
+
+/// The process a log file belongs to and whether it was rotated away, for
+/// `<name>.log` and `<name>.log.<n>`.
+fn parse_log_name(file_name: &str) -> Option<(String, bool)> {
+    if let Some(process) = file_name.strip_suffix(".log") {
+        return (!process.is_empty()).then(|| (process.to_string(), false));
+    }
+    let (base, index) = file_name.rsplit_once('.')?;
+    if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let process = base.strip_suffix(".log")?;
+    (!process.is_empty()).then(|| (process.to_string(), true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LogRetentionLimits;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(SECONDS_PER_DAY);
+
+    /// Writes `bytes` bytes to `name`, last modified `days_old` days before `now`.
+    fn log_file(dir: &Path, name: &str, bytes: usize, days_old: u32, now: SystemTime) {
+        let path = dir.join(name);
+        fs::write(&path, vec![b'x'; bytes]).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(now - DAY * days_old)
+            .unwrap();
+    }
+
+    fn remaining(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_parse_log_name() {
+        assert_eq!(parse_log_name("api.log"), Some(("api".to_string(), false)));
+        assert_eq!(parse_log_name("api.log.3"), Some(("api".to_string(), true)));
+        assert_eq!(
+            parse_log_name("api.v2.log.12"),
+            Some(("api.v2".to_string(), true))
+        );
+        assert_eq!(parse_log_name(".log"), None);
+        assert_eq!(parse_log_name("api.log.old"), None);
+        assert_eq!(parse_log_name("notes.txt"), None);
+    }
+
+    #[test]
+    fn test_prunes_by_age() {
+        let dir = TempDir::new().unwrap();
+        let now = SystemTime::now();
+        log_file(dir.path(), "api.log", 10, 30, now);
+        log_file(dir.path(), "api.log.1", 10, 3, now);
+        log_file(dir.path(), "api.log.2", 10, 8, now);
+        log_file(dir.path(), "worker.log.1", 10, 20, now);
+        log_file(dir.path(), "worker.log.2", 10, 40, now);
+
+        let retention = LogRetention {
+            max_age_days: Some(7),
+            processes: HashMap::from([(
+                "worker".to_string(),
+                LogRetentionLimits {
+                    max_age_days: Some(30),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let summary = prune(dir.path(), &retention, now).unwrap();
+
+        assert_eq!(
+            summary,
+            LogPruneSummary {
+                files_removed: 2,
+                bytes_reclaimed: 20,
+                files_skipped: 0,
+            }
+        );
+        // The open file is kept however old it is
+        assert_eq!(
+            remaining(dir.path()),
+            ["api.log", "api.log.1", "worker.log.1"]
+        );
+    }
+
+    #[test]
+    fn test_prunes_oldest_first_by_size() {
+        let dir = TempDir::new().unwrap();
+        let now = SystemTime::now();
+        log_file(dir.path(), "api.log", 100, 0, now);
+        log_file(dir.path(), "api.log.1", 100, 1, now);
+        log_file(dir.path(), "api.log.2", 100, 4, now);
+        log_file(dir.path(), "web.log", 50, 0, now);
+        log_file(dir.path(), "web.log.1", 50, 2, now);
+        log_file(dir.path(), "web.log.2", 50, 3, now);
+
+        let retention = LogRetention {
+            max_total_log_bytes: Some(300),
+            ..Default::default()
+        };
+        let summary = prune(dir.path(), &retention, now).unwrap();
+
+        assert_eq!(summary.files_removed, 2);
+        assert_eq!(summary.bytes_reclaimed, 150);
+        assert_eq!(
+            remaining(dir.path()),
+            ["api.log", "api.log.1", "web.log", "web.log.1"]
+        );
+    }
+
+    #[test]
+    fn test_process_size_limit_applies_to_its_files_only() {
+        let dir = TempDir::new().unwrap();
+        let now = SystemTime::now();
+        log_file(dir.path(), "api.log", 100, 0, now);
+        log_file(dir.path(), "api.log.1", 100, 1, now);
+        log_file(dir.path(), "api.log.2", 100, 2, now);
+        log_file(dir.path(), "web.log.1", 100, 9, now);
+
+        let retention = LogRetention {
+            processes: HashMap::from([(
+                "api".to_string(),
+                LogRetentionLimits {
+                    max_total_log_bytes: Some(150),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        prune(dir.path(), &retention, now).unwrap();
+
+        assert_eq!(remaining(dir.path()), ["api.log", "web.log.1"]);
+    }
+
+    #[test]
+    fn test_open_files_and_unknown_entries_are_kept() {
+        let dir = TempDir::new().unwrap();
+        let now = SystemTime::now();
+        log_file(dir.path(), "api.log", 1_000, 100, now);
+        log_file(dir.path(), "notes.txt", 1_000, 100, now);
+        log_file(dir.path(), "api.log.bak", 1_000, 100, now);
+        fs::create_dir(dir.path().join("archive.log.1")).unwrap();
+
+        let retention = LogRetention {
+            max_total_log_bytes: Some(0),
+            max_age_days: Some(1),
+            ..Default::default()
+        };
+        let summary = prune(dir.path(), &retention, now).unwrap();
+
+        assert_eq!(summary.files_removed, 0);
+        assert_eq!(summary.files_skipped, 3);
+        assert_eq!(remaining(dir.path()).len(), 4);
+
+        // Nothing to prune without limits or without a directory
+        assert_eq!(
+            prune(dir.path(), &LogRetention::default(), now).unwrap(),
+            summary
+        );
+        assert_eq!(
+            prune(&dir.path().join("missing"), &retention, now).unwrap(),
+            LogPruneSummary::default()
+        );
+    }
+
+    #[test]
+    fn test_prune_logs_records_summary() {
+        use crate::core::EventJournal;
+        use crate::models::EventFilter;
+
+        let dir = TempDir::new().unwrap();
+        let now = SystemTime::now();
+        let logs = dir.path().join("logs");
+        fs::create_dir(&logs).unwrap();
+        log_file(&logs, "api.log.1", 64, 10, now);
+
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
+        let settings = GlobalSettings {
+            log_directory: Some(logs.clone()),
+            log_retention: LogRetention {
+                max_age_days: Some(7),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let summary = prune_logs(&settings, &recorder, EventActor::User).unwrap();
+        assert_eq!(summary.files_removed, 1);
+        recorder.flush();
+
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::LogsPruned);
+        assert_eq!(events[0].subject, logs.display().to_string());
+        assert_eq!(events[0].details["files_removed"], "1");
+        assert_eq!(events[0].details["bytes_reclaimed"], "64");
+    }
+}
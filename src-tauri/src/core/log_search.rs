@@ -0,0 +1,368 @@
+//! Searching the logs of many processes at once.
+//!
+//! A [`LogQuery`] is compiled once from a pattern and [`LogSearchOptions`],
+//! then run against each process's buffered lines. Literal patterns are
+//! escaped and go through the same regex engine, which matches in linear
+//! time; patterns are also limited in length and compiled size so a
+//! pathological one fails up front instead of using unbounded memory.
+
+use crate::core::log_buffer::{LogLine, LogStream};
+use crate::core::log_timestamp::TimestampOrder;
+use crate::error::{Result, SentinelError};
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Longest pattern accepted, in bytes.
+pub const MAX_PATTERN_LENGTH: usize = 1_000;
+
+/// Largest compiled size of a pattern, in bytes.
+const MAX_COMPILED_SIZE: usize = 1 << 20;
+
+/// Matches returned per process unless `maxPerProcess` says otherwise.
+const DEFAULT_MAX_PER_PROCESS: usize = 100;
+
+/// Most context lines shown before or after a match.
+const MAX_CONTEXT_LINES: usize = 50;
+
+/// How to search logs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchOptions {
+    /// Treat the pattern as a regular expression rather than literal text.
+    #[serde(default)]
+    pub regex: bool,
+    /// Match case exactly.
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Only lines logged at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only lines logged at or before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    /// Which time `since` and `until` apply to; by default the time in the
+    /// line when it has one.
+    #[serde(default)]
+    pub order_by: TimestampOrder,
+    /// Only lines from this stream.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<LogStream>,
+    /// Most matches returned per process, the most recent ones (default 100).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_process: Option<usize>,
+    /// Lines shown before and after each match (at most 50).
+    #[serde(default)]
+    pub context: usize,
+    /// Also search the lines of attached external processes.
+    #[serde(default)]
+    pub include_attachments: bool,
+}
+
+/// A matching line with the lines around it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogMatch {
+    pub line: LogLine,
+    /// Up to `context` lines before, oldest first.
+    pub before: Vec<LogLine>,
+    /// Up to `context` lines after, oldest first.
+    pub after: Vec<LogLine>,
+}
+
+/// The matches found in one process's logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessLogMatches {
+    /// Managed process name, or the name of the attached process or file.
+    pub process: String,
+    /// Set for lines of an attached external process.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<String>,
+    /// Matches, oldest first.
+    pub matches: Vec<LogMatch>,
+    /// More lines matched than `maxPerProcess`; the oldest were left out.
+    pub truncated: bool,
+}
+
+/// A compiled search.
+#[derive(Debug, Clone)]
+pub struct LogQuery {
+    pattern: Regex,
+    options: LogSearchOptions,
+}
+
+impl LogQuery {
+    /// Compiles `pattern` with `options`.
+    ///
+    /// # Errors
+    /// Returns `InvalidSearchPattern` if the pattern is empty, too long, not
+    /// a valid regular expression or compiles too large.
+    pub fn new(pattern: &str, options: LogSearchOptions) -> Result<Self> {
+        let invalid = |reason: String| SentinelError::InvalidSearchPattern {
+            pattern: pattern.to_string(),
+            reason,
+        };
+        if pattern.is_empty() {
+            return Err(invalid("pattern is empty".to_string()));
+        }
+        if pattern.len() > MAX_PATTERN_LENGTH {
+            return Err(invalid(format!("longer than {} bytes", MAX_PATTERN_LENGTH)));
+        }
+
+        let source = if options.regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        let compiled = RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .size_limit(MAX_COMPILED_SIZE)
+            .build()
+            .map_err(|e| match e {
+                regex::Error::CompiledTooBig(limit) => {
+                    invalid(format!("compiles larger than {} bytes", limit))
+                }
+                // The message points at the pattern above its last line
+                e => invalid(e.to_string().lines().last().unwrap_or_default().to_string()),
+            })?;
+
+        Ok(Self {
+            pattern: compiled,
+            options,
+        })
+    }
+
+    /// Options the query was compiled with.
+    pub fn options(&self) -> &LogSearchOptions {
+        &self.options
+    }
+
+    /// Whether `line` passes the filters and matches the pattern.
+    pub fn matches(&self, line: &LogLine) -> bool {
+        let options = &self.options;
+        options.stream.is_none_or(|stream| line.stream == stream)
+            && options
+                .since
+                .is_none_or(|since| line.ordered_at(options.order_by) >= since)
+            && options
+                .until
+                .is_none_or(|until| line.ordered_at(options.order_by) <= until)
+            && self.pattern.is_match(&line.line)
+    }
+
+    /// Finds the matching lines in `lines`, with their context.
+    ///
+    /// Context lines are the neighbours in `lines` whether or not they pass
+    /// the filters, cut short at either end of the buffer.
+    ///
+    /// # Returns
+    /// The most recent matches up to `maxPerProcess`, oldest first, and
+    /// whether older ones were left out.
+    pub fn search(&self, lines: &VecDeque<LogLine>) -> (Vec<LogMatch>, bool) {
+        let limit = self
+            .options
+            .max_per_process
+            .unwrap_or(DEFAULT_MAX_PER_PROCESS);
+        let context = self.options.context.min(MAX_CONTEXT_LINES);
+
+        // Newest first, so the limit keeps the most recent matches
+        let mut found: Vec<usize> = Vec::new();
+        let mut truncated = false;
+        for (index, line) in lines.iter().enumerate().rev() {
+            if !self.matches(line) {
+                continue;
+            }
+            if found.len() == limit {
+                truncated = true;
+                break;
+            }
+            found.push(index);
+        }
+
+        let matches = found
+            .into_iter()
+            .rev()
+            .map(|index| LogMatch {
+                line: lines[index].clone(),
+                before: lines
+                    .range(index.saturating_sub(context)..index)
+                    .cloned()
+                    .collect(),
+                after: lines
+                    .range(index + 1..(index + 1 + context).min(lines.len()))
+                    .cloned()
+                    .collect(),
+            })
+            .collect();
+
+        (matches, truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn lines(texts: &[&str]) -> VecDeque<LogLine> {
+        let start = Utc::now();
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let stream = if text.starts_with("ERR") {
+                    LogStream::Stderr
+                } else {
+                    LogStream::Stdout
+                };
+                LogLine::new(
+                    start + Duration::seconds(i as i64),
+                    stream,
+                    text.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn texts(lines: &[LogLine]) -> Vec<&str> {
+        lines.iter().map(|line| line.line.as_str()).collect()
+    }
+
+    fn query(pattern: &str, options: LogSearchOptions) -> LogQuery {
+        LogQuery::new(pattern, options).unwrap()
+    }
+
+    #[test]
+    fn test_context_is_cut_at_buffer_boundaries() {
+        let buffer = lines(&["match 1", "a", "b", "c", "match 2"]);
+        let options = LogSearchOptions {
+            context: 2,
+            ..Default::default()
+        };
+
+        let (matches, truncated) = query("match", options).search(&buffer);
+        assert!(!truncated);
+        assert_eq!(matches.len(), 2);
+        assert!(matches[0].before.is_empty());
+        assert_eq!(texts(&matches[0].after), ["a", "b"]);
+        assert_eq!(texts(&matches[1].before), ["b", "c"]);
+        assert!(matches[1].after.is_empty());
+
+        // Context wider than the buffer
+        let options = LogSearchOptions {
+            context: 10,
+            ..Default::default()
+        };
+        let (matches, _) = query("b", options).search(&buffer);
+        assert_eq!(texts(&matches[0].before), ["match 1", "a"]);
+        assert_eq!(texts(&matches[0].after), ["c", "match 2"]);
+    }
+
+    #[test]
+    fn test_regex_and_literal_patterns() {
+        let buffer = lines(&["GET /a.b 200", "GET /axb 200", "get /a.b 500"]);
+
+        // Literal: the dot is only a dot, case ignored by default
+        let (matches, _) = query("/a.b", LogSearchOptions::default()).search(&buffer);
+        assert_eq!(matches.len(), 2);
+
+        let regex = LogSearchOptions {
+            regex: true,
+            ..Default::default()
+        };
+        let (matches, _) = query("/a.b", regex.clone()).search(&buffer);
+        assert_eq!(matches.len(), 3);
+        let (matches, _) = query(r"GET .* [45]\d\d", regex.clone()).search(&buffer);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line.line, "get /a.b 500");
+
+        let case_sensitive = LogSearchOptions {
+            regex: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let (matches, _) = query(r"GET .* [45]\d\d", case_sensitive).search(&buffer);
+        assert!(matches.is_empty());
+
+        // Regex syntax in a literal pattern is fine
+        assert!(LogQuery::new("(unclosed", LogSearchOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_filters_and_limit() {
+        let buffer = lines(&["ERR one", "out one", "ERR two", "ERR three"]);
+
+        let stderr = LogSearchOptions {
+            stream: Some(LogStream::Stderr),
+            max_per_process: Some(2),
+            ..Default::default()
+        };
+        let (matches, truncated) = query("e", stderr).search(&buffer);
+        assert!(truncated);
+        // The most recent ones are kept
+        let found: Vec<&str> = matches.iter().map(|m| m.line.line.as_str()).collect();
+        assert_eq!(found, ["ERR two", "ERR three"]);
+
+        let since = LogSearchOptions {
+            since: Some(buffer[1].timestamp),
+            until: Some(buffer[2].timestamp),
+            ..Default::default()
+        };
+        let (matches, _) = query("one", since).search(&buffer);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line.line, "out one");
+    }
+
+    #[test]
+    fn test_time_range_uses_chosen_timestamp() {
+        // Written long before it was read
+        let buffer = lines(&["2020-01-01T12:00:00Z late line"]);
+        let written = buffer[0].source_timestamp.unwrap();
+        let received = buffer[0].timestamp;
+
+        let by_source = LogSearchOptions {
+            until: Some(written),
+            ..Default::default()
+        };
+        assert_eq!(query("late", by_source).search(&buffer).0.len(), 1);
+
+        let by_received = LogSearchOptions {
+            since: Some(received),
+            order_by: TimestampOrder::Received,
+            ..Default::default()
+        };
+        assert_eq!(
+            query("late", by_received.clone()).search(&buffer).0.len(),
+            1
+        );
+        let until_written = LogSearchOptions {
+            until: Some(written),
+            ..by_received
+        };
+        assert!(query("late", until_written).search(&buffer).0.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_typed_errors() {
+        let regex = LogSearchOptions {
+            regex: true,
+            ..Default::default()
+        };
+
+        let err = LogQuery::new("(unclosed", regex.clone()).unwrap_err();
+        assert!(matches!(err, SentinelError::InvalidSearchPattern { .. }));
+        assert!(err
+            .to_string()
+            .starts_with("Invalid search pattern '(unclosed': "));
+
+        let long = "a".repeat(MAX_PATTERN_LENGTH + 1);
+        assert!(LogQuery::new(&long, regex.clone()).is_err());
+        assert!(LogQuery::new("", regex.clone()).is_err());
+
+        // Short but huge once compiled
+        let err = LogQuery::new(r"\w{1000}{1000}", regex).unwrap_err();
+        assert!(err.to_string().contains("compiles larger than"));
+    }
+}
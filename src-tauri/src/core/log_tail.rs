@@ -0,0 +1,637 @@
+//! Log file tailing for external processes.
+//!
+//! Follows a single file, or every file matching a glob such as `logs/*.log`,
+//! and reopens files that are truncated or rotated. How far each file has been
+//! read is kept in [`LogOffsets`], so attaching again resumes where the last
+//! attachment stopped.
+
+use crate::core::log_decoder::{LineDecoder, DEFAULT_MAX_LINE_BYTES};
+use crate::core::{validation, StateManager};
+use crate::error::{Result, SentinelError};
+use crate::models::OutputEncoding;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+
+/// How often files are checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Bytes read from a file at a time.
+const READ_CHUNK_BYTES: usize = 8 * 1024;
+
+/// How often a glob is expanded again to pick up new files.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often read offsets are written to disk.
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where to start reading a file when attaching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TailStart {
+    /// Resume from the saved offset, or start at the end of the file.
+    End,
+    /// Start at the last `n` lines.
+    LastLines(usize),
+    /// Read the whole file.
+    Beginning,
+}
+
+/// A file or glob of files to tail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TailTarget {
+    File(PathBuf),
+    Glob(String),
+}
+
+impl TailTarget {
+    /// Parses `path`, treating it as a glob if it contains `*`, `?` or `[`.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` for a malformed glob.
+    pub fn parse(path: &str) -> Result<Self> {
+        if !path.contains(['*', '?', '[']) {
+            return Ok(Self::File(PathBuf::from(path)));
+        }
+
+        glob::Pattern::new(path).map_err(|e| SentinelError::InvalidInput {
+            message: format!("Invalid log file pattern '{}': {}", path, e),
+        })?;
+        Ok(Self::Glob(path.to_string()))
+    }
+}
+
+/// Read position saved for a log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct SavedOffset {
+    offset: u64,
+    /// Identity (inode) of the file the offset belongs to, where the platform has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_id: Option<u64>,
+}
+
+#[derive(Default)]
+struct OffsetsState {
+    offsets: HashMap<PathBuf, SavedOffset>,
+    dirty: bool,
+}
+
+/// Read offsets of tailed log files, keyed by canonical path and saved as JSON.
+pub struct LogOffsets {
+    path: PathBuf,
+    state: Mutex<OffsetsState>,
+}
+
+impl LogOffsets {
+    /// Gets the default offsets file path, next to the runtime state file.
+    ///
+    /// Returns: `~/.config/sentinel/.sentinel-log-offsets.json`
+    pub fn default_path() -> PathBuf {
+        StateManager::get_state_path().with_file_name(".sentinel-log-offsets.json")
+    }
+
+    /// Loads offsets from `path`.
+    ///
+    /// A missing or unreadable file gives an empty set of offsets.
+    pub fn load(path: PathBuf) -> Self {
+        let offsets = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid log offsets in {}: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Self {
+            path,
+            state: Mutex::new(OffsetsState {
+                offsets,
+                dirty: false,
+            }),
+        }
+    }
+
+    /// Saves the offsets to disk if they changed since the last save.
+    pub fn save(&self) -> Result<()> {
+        let mut state = self.lock();
+        if !state.dirty {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|source| SentinelError::FileIoError {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+
+        let contents = serde_json::to_string_pretty(&state.offsets)
+            .map_err(|e| SentinelError::Other(format!("Failed to serialize log offsets: {}", e)))?;
+
+        fs::write(&self.path, contents).map_err(|source| SentinelError::FileIoError {
+            path: self.path.clone(),
+            source,
+        })?;
+
+        state.dirty = false;
+        Ok(())
+    }
+
+    fn get(&self, path: &Path) -> Option<SavedOffset> {
+        self.lock().offsets.get(path).copied()
+    }
+
+    fn set(&self, path: &Path, offset: SavedOffset) {
+        let mut state = self.lock();
+        if state.offsets.insert(path.to_path_buf(), offset) != Some(offset) {
+            state.dirty = true;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, OffsetsState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Follows a [`TailTarget`], passing each new line to a callback.
+pub struct Follower {
+    target: TailTarget,
+    allowed_roots: Vec<PathBuf>,
+    offsets: Arc<LogOffsets>,
+    tails: Vec<FileTail>,
+}
+
+impl Follower {
+    /// Opens the files to follow.
+    ///
+    /// A glob that matches nothing yet is not an error: matching files are
+    /// picked up as they appear. Files outside `allowed_roots` (when it is
+    /// non-empty) are skipped.
+    ///
+    /// # Errors
+    /// For a single file, returns `PathNotAllowed` if it is outside the
+    /// allowed roots or `FileIoError` if it can't be opened.
+    pub fn new(
+        target: TailTarget,
+        start: TailStart,
+        allowed_roots: Vec<PathBuf>,
+        offsets: Arc<LogOffsets>,
+    ) -> Result<Self> {
+        let mut follower = Self {
+            target,
+            allowed_roots,
+            offsets,
+            tails: Vec::new(),
+        };
+
+        match &follower.target {
+            TailTarget::File(path) => {
+                let path = resolve(path, &follower.allowed_roots)?;
+                let tail = FileTail::open(path.clone(), start, &follower.offsets)
+                    .map_err(|source| SentinelError::FileIoError { path, source })?;
+                follower.tails.push(tail);
+            }
+            TailTarget::Glob(_) => follower.scan(start),
+        }
+
+        Ok(follower)
+    }
+
+    /// Follows the files until the task running it is cancelled.
+    ///
+    /// `on_line` receives the file name and the line, without its line ending.
+    pub async fn run(mut self, on_line: impl Fn(&str, String) + Send + Sync + 'static) {
+        let mut last_scan = Instant::now();
+        let mut last_save = Instant::now();
+
+        loop {
+            for tail in &mut self.tails {
+                if let Err(e) = tail.poll(&self.offsets, &on_line).await {
+                    tracing::debug!("Failed to read log file {}: {}", tail.path.display(), e);
+                }
+            }
+
+            if last_scan.elapsed() >= RESCAN_INTERVAL {
+                // Files created after attaching are read in full
+                self.scan(TailStart::Beginning);
+                last_scan = Instant::now();
+            }
+
+            if last_save.elapsed() >= SAVE_INTERVAL {
+                if let Err(e) = self.offsets.save() {
+                    tracing::warn!("Failed to save log offsets: {}", e);
+                }
+                last_save = Instant::now();
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Starts following glob matches that aren't followed yet.
+    fn scan(&mut self, start: TailStart) {
+        let TailTarget::Glob(pattern) = &self.target else {
+            return;
+        };
+        let Ok(paths) = glob::glob(pattern) else {
+            return;
+        };
+
+        for path in paths.filter_map(|entry| entry.ok()) {
+            if !path.is_file() {
+                continue;
+            }
+            let path = match resolve(&path, &self.allowed_roots) {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::debug!("Skipping log file: {}", e);
+                    continue;
+                }
+            };
+            if self.tails.iter().any(|tail| tail.path == path) {
+                continue;
+            }
+
+            match FileTail::open(path, start, &self.offsets) {
+                Ok(tail) => {
+                    tracing::info!("Tailing log file {}", tail.path.display());
+                    self.tails.push(tail);
+                }
+                Err(e) => tracing::debug!("Failed to open log file: {}", e),
+            }
+        }
+    }
+}
+
+/// A single file being followed.
+struct FileTail {
+    /// Canonical path of the file.
+    path: PathBuf,
+    /// File name, passed along with each line.
+    name: String,
+    reader: tokio::fs::File,
+    file_id: Option<u64>,
+    /// Offset just past the last complete line.
+    offset: u64,
+    /// Splits what is read into lines, holding the start of a line that
+    /// hasn't been terminated yet.
+    decoder: LineDecoder,
+}
+
+impl FileTail {
+    fn open(path: PathBuf, start: TailStart, offsets: &LogOffsets) -> io::Result<Self> {
+        let mut file = fs::File::open(&path)?;
+        let metadata = file.metadata()?;
+        let file_id = file_id(&metadata);
+
+        let offset = match start {
+            TailStart::Beginning => 0,
+            TailStart::LastLines(count) => start_of_last_lines(&mut file, count)?,
+            TailStart::End => match offsets.get(&path) {
+                Some(saved) if saved.file_id == file_id && saved.offset <= metadata.len() => {
+                    saved.offset
+                }
+                _ => metadata.len(),
+            },
+        };
+        file.seek(SeekFrom::Start(offset))?;
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(Self {
+            path,
+            name,
+            reader: tokio::fs::File::from_std(file),
+            file_id,
+            offset,
+            decoder: LineDecoder::new(OutputEncoding::Auto, Some(DEFAULT_MAX_LINE_BYTES)),
+        })
+    }
+
+    /// Reads the lines completed since the last poll, then reopens the file
+    /// if it was truncated or replaced.
+    async fn poll(
+        &mut self,
+        offsets: &LogOffsets,
+        on_line: &(impl Fn(&str, String) + Sync),
+    ) -> io::Result<()> {
+        let start = self.offset;
+        let mut chunk = [0u8; READ_CHUNK_BYTES];
+        loop {
+            let n = self.reader.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            let position = self.offset + (self.decoder.pending_len() + n) as u64;
+            for line in self.decoder.feed(&chunk[..n]) {
+                on_line(&self.name, line);
+            }
+            self.offset = position - self.decoder.pending_len() as u64;
+        }
+        if self.offset != start {
+            self.save_offset(offsets);
+        }
+
+        // Between a rotation and the new file being created, there is no file
+        let Ok(metadata) = fs::metadata(&self.path) else {
+            return Ok(());
+        };
+        let position = self.offset + self.decoder.pending_len() as u64;
+        if file_id(&metadata) != self.file_id || metadata.len() < position {
+            tracing::info!(
+                "Log file {} was rotated, reading it from the start",
+                self.path.display()
+            );
+            *self = Self::open(self.path.clone(), TailStart::Beginning, offsets)?;
+            self.save_offset(offsets);
+        }
+
+        Ok(())
+    }
+
+    fn save_offset(&self, offsets: &LogOffsets) {
+        offsets.set(
+            &self.path,
+            SavedOffset {
+                offset: self.offset,
+                file_id: self.file_id,
+            },
+        );
+    }
+}
+
+/// Canonical path of a log file, checked against the allowed roots.
+fn resolve(path: &Path, allowed_roots: &[PathBuf]) -> Result<PathBuf> {
+    let path = validation::resolve_allowed_path(path, allowed_roots)?;
+    path.canonicalize()
+        .map_err(|source| SentinelError::FileIoError { path, source })
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// Offset where the last `count` lines of `file` start.
+fn start_of_last_lines(file: &mut fs::File, count: usize) -> io::Result<u64> {
+    let len = file.metadata()?.len();
+    if count == 0 {
+        return Ok(len);
+    }
+
+    let mut buffer = [0u8; 8192];
+    let mut end = len;
+    let mut newlines = 0;
+    while end > 0 {
+        let start = end.saturating_sub(buffer.len() as u64);
+        let chunk = &mut buffer[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+
+        for (i, &byte) in chunk.iter().enumerate().rev() {
+            let position = start + i as u64;
+            // The newline that ends the file doesn't start another line
+            if byte == b'\n' && position + 1 != len {
+                newlines += 1;
+                if newlines == count {
+                    return Ok(position + 1);
+                }
+            }
+        }
+        end = start;
+    }
+
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use tokio::sync::mpsc;
+    use tokio::task::JoinHandle;
+
+    type Lines = mpsc::UnboundedReceiver<(String, String)>;
+
+    fn follow(
+        target: TailTarget,
+        start: TailStart,
+        offsets: Arc<LogOffsets>,
+    ) -> (JoinHandle<()>, Lines) {
+        let follower = Follower::new(target, start, Vec::new(), offsets).unwrap();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(follower.run(move |source, line| {
+            let _ = sender.send((source.to_string(), line));
+        }));
+        (handle, receiver)
+    }
+
+    async fn next_line(lines: &mut Lines) -> String {
+        next_line_with_source(lines).await.1
+    }
+
+    async fn next_line_with_source(lines: &mut Lines) -> (String, String) {
+        tokio::time::timeout(Duration::from_secs(5), lines.recv())
+            .await
+            .expect("no log line within 5s")
+            .unwrap()
+    }
+
+    fn append(path: &Path, text: &str) {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap()
+            .write_all(text.as_bytes())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            TailTarget::parse("logs/app.log").unwrap(),
+            TailTarget::File(PathBuf::from("logs/app.log"))
+        );
+        assert_eq!(
+            TailTarget::parse("logs/*.log").unwrap(),
+            TailTarget::Glob("logs/*.log".to_string())
+        );
+        assert!(matches!(
+            TailTarget::parse("logs/[.log"),
+            Err(SentinelError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_start_of_last_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+
+        fs::write(&path, "1\n2\n3\n4\n5\n").unwrap();
+        let mut file = fs::File::open(&path).unwrap();
+        assert_eq!(start_of_last_lines(&mut file, 2).unwrap(), 6);
+        assert_eq!(start_of_last_lines(&mut file, 0).unwrap(), 10);
+        assert_eq!(start_of_last_lines(&mut file, 9).unwrap(), 0);
+
+        // An unterminated last line still counts as a line
+        fs::write(&path, "1\n2\n3").unwrap();
+        let mut file = fs::File::open(&path).unwrap();
+        assert_eq!(start_of_last_lines(&mut file, 1).unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_tail_starts_at_end() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old\n").unwrap();
+
+        let offsets = Arc::new(LogOffsets::load(dir.path().join("offsets.json")));
+        let (handle, mut lines) = follow(TailTarget::File(path.clone()), TailStart::End, offsets);
+
+        append(&path, "new\n");
+        assert_eq!(next_line(&mut lines).await, "new");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_tail_last_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "1\n2\n3\n4\n5\n").unwrap();
+
+        let offsets = Arc::new(LogOffsets::load(dir.path().join("offsets.json")));
+        let (handle, mut lines) = follow(TailTarget::File(path), TailStart::LastLines(2), offsets);
+
+        assert_eq!(next_line(&mut lines).await, "4");
+        assert_eq!(next_line(&mut lines).await, "5");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_tail_survives_invalid_utf8_and_reads_utf16() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, b"caf\xE9\nstill here\n").unwrap();
+
+        let offsets = Arc::new(LogOffsets::load(dir.path().join("offsets.json")));
+        let (handle, mut lines) = follow(
+            TailTarget::File(path.clone()),
+            TailStart::Beginning,
+            offsets.clone(),
+        );
+        assert_eq!(next_line(&mut lines).await, "caf\u{FFFD}");
+        assert_eq!(next_line(&mut lines).await, "still here");
+        handle.abort();
+
+        let wide = dir.path().join("wide.log");
+        let utf16: Vec<u8> = "\u{FEFF}Grüße\r\nready\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        fs::write(&wide, utf16).unwrap();
+        let (handle, mut lines) = follow(TailTarget::File(wide), TailStart::Beginning, offsets);
+        assert_eq!(next_line(&mut lines).await, "Grüße");
+        assert_eq!(next_line(&mut lines).await, "ready");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_saved_offset() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let offsets_path = dir.path().join("offsets.json");
+        fs::write(&path, "old\n").unwrap();
+
+        let offsets = Arc::new(LogOffsets::load(offsets_path.clone()));
+        let (handle, mut lines) = follow(
+            TailTarget::File(path.clone()),
+            TailStart::End,
+            offsets.clone(),
+        );
+        append(&path, "one\n");
+        assert_eq!(next_line(&mut lines).await, "one");
+        tokio::time::sleep(POLL_INTERVAL * 3).await;
+        handle.abort();
+        offsets.save().unwrap();
+
+        // Lines written while detached are delivered on the next attach
+        append(&path, "two\n");
+        let offsets = Arc::new(LogOffsets::load(offsets_path));
+        let (handle, mut lines) = follow(TailTarget::File(path), TailStart::End, offsets);
+        assert_eq!(next_line(&mut lines).await, "two");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_rotation_by_truncation() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "before rotation\n").unwrap();
+
+        let offsets = Arc::new(LogOffsets::load(dir.path().join("offsets.json")));
+        let (handle, mut lines) = follow(TailTarget::File(path.clone()), TailStart::End, offsets);
+        append(&path, "a\n");
+        assert_eq!(next_line(&mut lines).await, "a");
+
+        fs::write(&path, "b\n").unwrap();
+        assert_eq!(next_line(&mut lines).await, "b");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_rotation_by_recreation() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "before rotation\n").unwrap();
+
+        let offsets = Arc::new(LogOffsets::load(dir.path().join("offsets.json")));
+        let (handle, mut lines) = follow(TailTarget::File(path.clone()), TailStart::End, offsets);
+        append(&path, "a\n");
+        assert_eq!(next_line(&mut lines).await, "a");
+
+        fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        fs::write(&path, "c\n").unwrap();
+        assert_eq!(next_line(&mut lines).await, "c");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_glob_picks_up_new_files() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("a.log");
+        fs::write(&first, "old\n").unwrap();
+
+        let pattern = dir.path().join("*.log").to_string_lossy().into_owned();
+        let offsets = Arc::new(LogOffsets::load(dir.path().join("offsets.json")));
+        let (handle, mut lines) = follow(TailTarget::Glob(pattern), TailStart::End, offsets);
+
+        append(&first, "a1\n");
+        assert_eq!(
+            next_line_with_source(&mut lines).await,
+            ("a.log".to_string(), "a1".to_string())
+        );
+
+        fs::write(dir.path().join("c.txt"), "not a log\n").unwrap();
+        fs::write(dir.path().join("b.log"), "b1\n").unwrap();
+        assert_eq!(
+            next_line_with_source(&mut lines).await,
+            ("b.log".to_string(), "b1".to_string())
+        );
+        handle.abort();
+    }
+}
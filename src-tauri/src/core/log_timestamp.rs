@@ -0,0 +1,368 @@
+//! Timestamps written at the start of log lines.
+//!
+//! Lines are stamped with the time Sentinel received them, which lags behind
+//! the time they were written whenever a source is buffered or slow to read.
+//! [`extract`] finds the time a line carries itself, so views that combine
+//! several sources can order lines by when they were written.
+//!
+//! Recognized at the start of a line, optionally inside `[...]`:
+//! - ISO 8601 / RFC 3339: `2024-05-01T12:00:00.123Z`, `2024-05-01 12:00:00,123`
+//! - syslog: `May  1 12:00:00`
+//! - time of day: `[12:00:00]`, `[12:00:00.123]`
+//! - Unix epoch in seconds or milliseconds: `1714564800`, `1714564800.123`,
+//!   `1714564800123`
+//!
+//! Times without an offset are taken as local time; those without a date
+//! or year get the one closest to when the line was received.
+
+use crate::core::log_buffer::LogLine;
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc,
+};
+use regex::{Captures, Regex, RegexSet};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Bytes at the start of a line that are looked at.
+const PREFIX_LEN: usize = 40;
+
+/// Earliest epoch timestamp accepted, 2000-01-01; smaller numbers are
+/// more likely counters or IDs.
+const MIN_EPOCH_SECS: i64 = 946_684_800;
+
+/// Latest epoch timestamp accepted, 2100-01-01.
+const MAX_EPOCH_SECS: i64 = 4_102_444_800;
+
+const ISO_8601: &str = r"^\[?(\d{4})-(\d{2})-(\d{2})[T ](\d{2}):(\d{2}):(\d{2})(?:[.,](\d{1,9}))?(Z|[+-]\d{2}:?\d{2})?";
+const SYSLOG: &str =
+    r"^\[?(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) +(\d{1,2}) (\d{2}):(\d{2}):(\d{2})\b";
+const TIME_OF_DAY: &str = r"^\[(\d{2}):(\d{2}):(\d{2})(?:[.,](\d{1,9}))?\]";
+const EPOCH: &str = r"^\[?(\d{10})(?:(\d{3})|\.(\d{1,9}))?\b";
+
+/// Which time orders lines and bounds time ranges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampOrder {
+    /// The time in the line when it has one, otherwise when it was received.
+    #[default]
+    Source,
+    /// When Sentinel received the line.
+    Received,
+}
+
+/// A line of a merged view, with the process it came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedLogLine {
+    /// Managed process name, or the name of the attached process or file.
+    pub process: String,
+    /// Set for lines of an attached external process.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<String>,
+    #[serde(flatten)]
+    pub line: LogLine,
+}
+
+struct Patterns {
+    set: RegexSet,
+    iso_8601: Regex,
+    syslog: Regex,
+    time_of_day: Regex,
+    epoch: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let compile = |pattern| Regex::new(pattern).expect("valid timestamp pattern");
+        Patterns {
+            set: RegexSet::new([ISO_8601, SYSLOG, TIME_OF_DAY, EPOCH])
+                .expect("valid timestamp patterns"),
+            iso_8601: compile(ISO_8601),
+            syslog: compile(SYSLOG),
+            time_of_day: compile(TIME_OF_DAY),
+            epoch: compile(EPOCH),
+        }
+    })
+}
+
+/// The time at the start of `line`, if it has one.
+///
+/// `received` is when the line arrived; it fills in the date of a bare time
+/// of day and the year of a syslog timestamp.
+pub fn extract(line: &str, received: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let first = *line.as_bytes().first()?;
+    if !(first.is_ascii_digit() || first.is_ascii_uppercase() || first == b'[') {
+        return None;
+    }
+    let mut end = line.len().min(PREFIX_LEN);
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    let prefix = &line[..end];
+
+    let patterns = patterns();
+    let matched = patterns.set.matches(prefix);
+    let pattern = matched.iter().next()?;
+    match pattern {
+        0 => from_iso_8601(&patterns.iso_8601.captures(prefix)?),
+        1 => from_syslog(&patterns.syslog.captures(prefix)?, received),
+        2 => from_time_of_day(&patterns.time_of_day.captures(prefix)?, received),
+        _ => from_epoch(&patterns.epoch.captures(prefix)?),
+    }
+}
+
+/// Sorts lines by `order`, then by sequence number, keeping lines that
+/// still tie in their order.
+pub fn sort_lines(lines: &mut [MergedLogLine], order: TimestampOrder) {
+    lines.sort_by_key(|merged| merged.line.order_key(order));
+}
+
+fn number<T: std::str::FromStr>(captures: &Captures, group: usize) -> Option<T> {
+    captures.get(group)?.as_str().parse().ok()
+}
+
+/// Nanoseconds from a fraction of a second's digits.
+fn nanos(captures: &Captures, group: usize) -> u32 {
+    captures.get(group).map_or(0, |digits| {
+        let digits = digits.as_str();
+        let value: u32 = digits.parse().unwrap_or(0);
+        value * 10u32.pow(9 - digits.len() as u32)
+    })
+}
+
+fn time(captures: &Captures, first: usize, fraction: usize) -> Option<NaiveTime> {
+    NaiveTime::from_hms_nano_opt(
+        number(captures, first)?,
+        number(captures, first + 1)?,
+        number(captures, first + 2)?,
+        nanos(captures, fraction),
+    )
+}
+
+fn local(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn from_iso_8601(captures: &Captures) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::from_ymd_opt(
+        number(captures, 1)?,
+        number(captures, 2)?,
+        number(captures, 3)?,
+    )?;
+    let naive = date.and_time(time(captures, 4, 7)?);
+
+    match captures.get(8).map(|offset| offset.as_str()) {
+        None => local(naive),
+        Some("Z") => Some(Utc.from_utc_datetime(&naive)),
+        Some(offset) => {
+            let digits = offset[1..].replace(':', "");
+            let hours: i32 = digits[..2].parse().ok()?;
+            let minutes: i32 = digits[2..].parse().ok()?;
+            let seconds =
+                (hours * 3600 + minutes * 60) * if offset.starts_with('-') { -1 } else { 1 };
+            let offset = FixedOffset::east_opt(seconds)?;
+            offset
+                .from_local_datetime(&naive)
+                .single()
+                .map(|time| time.with_timezone(&Utc))
+        }
+    }
+}
+
+fn from_syslog(captures: &Captures, received: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let month = MONTHS.iter().position(|m| *m == &captures[1])? as u32 + 1;
+    let day = number(captures, 2)?;
+    let time = time(captures, 3, usize::MAX)?;
+    let year = received.with_timezone(&Local).year();
+
+    // A line from late December received in January is from last year
+    let this_year = local(NaiveDate::from_ymd_opt(year, month, day)?.and_time(time))?;
+    if this_year > received + Duration::days(1) {
+        local(NaiveDate::from_ymd_opt(year - 1, month, day)?.and_time(time))
+    } else {
+        Some(this_year)
+    }
+}
+
+fn from_time_of_day(captures: &Captures, received: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let time = time(captures, 1, 4)?;
+    let today = received.with_timezone(&Local).date_naive();
+
+    // A line from before midnight received after it is from yesterday
+    let candidate = local(today.and_time(time))?;
+    if candidate > received + Duration::hours(12) {
+        local(today.pred_opt()?.and_time(time))
+    } else {
+        Some(candidate)
+    }
+}
+
+fn from_epoch(captures: &Captures) -> Option<DateTime<Utc>> {
+    let seconds: i64 = number(captures, 1)?;
+    if !(MIN_EPOCH_SECS..MAX_EPOCH_SECS).contains(&seconds) {
+        return None;
+    }
+    let nanos = match captures.get(2) {
+        Some(millis) => millis.as_str().parse::<u32>().ok()? * 1_000_000,
+        None => nanos(captures, 3),
+    };
+    DateTime::from_timestamp(seconds, nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::log_buffer::LogStream;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn local_time(year: i32, month: u32, day: u32, h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        local(
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(h, m, s)
+                .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn merged(process: &str, received: DateTime<Utc>, text: &str) -> MergedLogLine {
+        MergedLogLine {
+            process: process.to_string(),
+            attachment_id: None,
+            line: LogLine::new(received, LogStream::Stdout, text.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_iso_8601() {
+        let received = Utc::now();
+        assert_eq!(
+            extract("2024-05-01T12:00:00.123Z GET /", received),
+            Some(utc("2024-05-01T12:00:00.123Z"))
+        );
+        assert_eq!(
+            extract("[2024-05-01 14:00:00,5+02:00] INFO ready", received),
+            Some(utc("2024-05-01T12:00:00.5Z"))
+        );
+        assert_eq!(
+            extract("2024-05-01T07:00:00-0500 worker", received),
+            Some(utc("2024-05-01T12:00:00Z"))
+        );
+        assert_eq!(
+            extract("2024-05-01 12:00:00 started", received),
+            Some(local_time(2024, 5, 1, 12, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_syslog_takes_year_from_receive_time() {
+        let received = local_time(2024, 5, 1, 12, 0, 0);
+        assert_eq!(
+            extract("May  1 11:59:58 host sshd[42]: accepted", received),
+            Some(local_time(2024, 5, 1, 11, 59, 58))
+        );
+
+        let new_year = local_time(2025, 1, 1, 0, 0, 5);
+        assert_eq!(
+            extract("Dec 31 23:59:59 host cron: tick", new_year),
+            Some(local_time(2024, 12, 31, 23, 59, 59))
+        );
+    }
+
+    #[test]
+    fn test_time_of_day_takes_date_from_receive_time() {
+        let received = local_time(2024, 5, 1, 12, 0, 0);
+        assert_eq!(
+            extract("[11:59:59] webpack compiled", received),
+            Some(local_time(2024, 5, 1, 11, 59, 59))
+        );
+
+        let after_midnight = local_time(2024, 5, 2, 0, 0, 1);
+        assert_eq!(
+            extract("[23:59:59.250] build done", after_midnight),
+            Some(local_time(2024, 5, 1, 23, 59, 59) + Duration::milliseconds(250))
+        );
+    }
+
+    #[test]
+    fn test_epoch() {
+        let received = Utc::now();
+        assert_eq!(
+            extract("1714564800 tick", received),
+            Some(utc("2024-05-01T12:00:00Z"))
+        );
+        assert_eq!(
+            extract("1714564800250 tick", received),
+            Some(utc("2024-05-01T12:00:00.25Z"))
+        );
+        assert_eq!(
+            extract("[1714564800.5] tick", received),
+            Some(utc("2024-05-01T12:00:00.5Z"))
+        );
+    }
+
+    #[test]
+    fn test_garbage_has_no_timestamp() {
+        let received = Utc::now();
+        for line in [
+            "",
+            "  indented 2024-05-01T12:00:00Z",
+            "Listening on port 3000",
+            "2024-13-45T99:00:00Z bad date",
+            "[25:61:00] bad time",
+            "123456 small number",
+            "12345678901234567890 long number",
+            "Mayday 1 12:00:00",
+            "[2024-05-01",
+            "🚀 2024-05-01T12:00:00Z",
+        ] {
+            assert_eq!(extract(line, received), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_merge_orders_by_source_under_skew() {
+        // The file tail lags four seconds behind; Docker lines arrive on time
+        let t = utc("2024-05-01T12:00:00Z");
+        let at = |s: i64| t + Duration::seconds(s);
+        let mut lines = vec![
+            merged("api", at(1), "2024-05-01T12:00:01Z request 1"),
+            merged("db", at(2), "May  1 12:00:02 db: query"),
+            merged("file", at(5), "[1714564800] rotated"),
+            merged("file", at(6), "2024-05-01T12:00:04Z flushed"),
+            merged("web", at(3), "no timestamp here"),
+        ];
+        // The syslog line is local time; shift it to the same instant in UTC
+        lines[1].line.source_timestamp = Some(at(2));
+
+        sort_lines(&mut lines, TimestampOrder::Source);
+        let order: Vec<&str> = lines.iter().map(|l| l.line.line.as_str()).collect();
+        assert_eq!(
+            order,
+            [
+                "[1714564800] rotated",
+                "2024-05-01T12:00:01Z request 1",
+                "May  1 12:00:02 db: query",
+                "no timestamp here",
+                "2024-05-01T12:00:04Z flushed",
+            ]
+        );
+
+        sort_lines(&mut lines, TimestampOrder::Received);
+        let processes: Vec<&str> = lines.iter().map(|l| l.process.as_str()).collect();
+        assert_eq!(processes, ["api", "db", "web", "file", "file"]);
+    }
+}
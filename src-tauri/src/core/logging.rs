@@ -0,0 +1,69 @@
+//! Tracing setup with a log level that can change at runtime.
+//!
+//! The filter is installed behind a reload handle so `settings.logLevel`
+//! takes effect whenever a config is loaded or reloaded.
+
+use crate::error::{Result, SentinelError};
+use std::sync::OnceLock;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Installs the global tracing subscriber.
+///
+/// `RUST_LOG` takes precedence over `default_level` and over any level set
+/// later through [`set_log_level`].
+pub fn init(default_level: &str) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_level));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .try_init()
+        .is_ok()
+    {
+        let _ = FILTER.set(handle);
+    }
+}
+
+/// Changes the log level of the subscriber installed by [`init`].
+///
+/// Does nothing if `RUST_LOG` is set or [`init`] was not called.
+///
+/// # Errors
+/// Returns `InvalidConfig` if `level` is not a valid filter directive.
+pub fn set_log_level(level: &str) -> Result<()> {
+    let filter = EnvFilter::try_new(level).map_err(|e| SentinelError::InvalidConfig {
+        reason: format!("Invalid log level '{}': {}", level, e),
+    })?;
+
+    if std::env::var_os(EnvFilter::DEFAULT_ENV).is_some() {
+        return Ok(());
+    }
+    if let Some(handle) = FILTER.get() {
+        handle
+            .reload(filter)
+            .map_err(|e| SentinelError::Other(format!("Failed to change log level: {}", e)))?;
+        tracing::info!("Log level set to '{}'", level);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_log_level_is_rejected() {
+        assert!(set_log_level("debug").is_ok());
+        assert!(matches!(
+            set_log_level("api=loud"),
+            Err(SentinelError::InvalidConfig { .. })
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+//! Message catalog for user-facing text.
+//!
+//! Error messages shown in the UI and CLI output are looked up by key, so they
+//! can be translated without touching the code. English is built in; other
+//! locales are read from `<config dir>/sentinel/locales/<locale>.txt`, with
+//! any key a translation leaves out taken from English.
+//!
+//! A catalog file has one `key = text` entry per line. Blank lines and lines
+//! starting with `#` are skipped, `{name}` is replaced with the parameter of
+//! that name, and `\n` starts a new line.
+//!
+//! The locale comes from `SENTINEL_LOCALE`, then `settings.locale`. Tracing
+//! output is not translated.
+
+use crate::error::{Result, SentinelError};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Environment variable that overrides `settings.locale`.
+pub const LOCALE_ENV: &str = "SENTINEL_LOCALE";
+
+/// Locale of the built-in catalog.
+pub const DEFAULT_LOCALE: &str = "en";
+
+const ENGLISH: &str = include_str!("../../locales/en.txt");
+
+static BUILT_IN: OnceLock<Catalog> = OnceLock::new();
+static ACTIVE: RwLock<Option<Arc<Catalog>>> = RwLock::new(None);
+
+/// Messages of one locale, by key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    locale: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Parses a catalog file.
+    ///
+    /// # Errors
+    /// Returns `InvalidConfig` for a line that isn't a comment or `key = text`.
+    pub fn parse(locale: &str, source: &str) -> Result<Self> {
+        let mut messages = HashMap::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, text)) = line.split_once('=') else {
+                return Err(SentinelError::InvalidConfig {
+                    reason: format!(
+                        "Line {} of the '{}' messages is not 'key = text'",
+                        index + 1,
+                        locale
+                    ),
+                });
+            };
+            messages.insert(key.trim().to_string(), unescape(text.trim()));
+        }
+
+        Ok(Self {
+            locale: locale.to_string(),
+            messages,
+        })
+    }
+
+    /// The built-in English catalog.
+    pub fn english() -> &'static Catalog {
+        BUILT_IN.get_or_init(|| {
+            Catalog::parse(DEFAULT_LOCALE, ENGLISH).expect("built-in messages are valid")
+        })
+    }
+
+    /// Loads `locale` from `dir`, trying `de_DE` before `de`.
+    ///
+    /// Returns `None` for English or when no file matches.
+    ///
+    /// # Errors
+    /// Returns `FileIoError` if a matching file can't be read and
+    /// `InvalidConfig` if it can't be parsed.
+    pub fn load(dir: &Path, locale: &str) -> Result<Option<Self>> {
+        for candidate in candidates(locale) {
+            if candidate == DEFAULT_LOCALE {
+                return Ok(None);
+            }
+            let path = dir.join(format!("{}.txt", candidate));
+            if !path.is_file() {
+                continue;
+            }
+            let source = fs::read_to_string(&path)
+                .map_err(|source| SentinelError::FileIoError { path, source })?;
+            return Catalog::parse(&candidate, &source).map(Some);
+        }
+        Ok(None)
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// The text for `key`, if this catalog has it.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.messages.contains_key(key)
+    }
+}
+
+/// `locales` in the Sentinel config directory.
+pub fn locales_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("sentinel")
+        .join("locales")
+}
+
+/// Switches the active locale.
+///
+/// `SENTINEL_LOCALE` takes precedence over `configured`. A locale without a
+/// catalog falls back to English with a warning.
+pub fn set_locale(configured: Option<&str>) {
+    let requested = std::env::var(LOCALE_ENV)
+        .ok()
+        .filter(|locale| !locale.trim().is_empty())
+        .or_else(|| configured.map(str::to_string));
+
+    let catalog = match requested.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(locale) => match Catalog::load(&locales_dir(), locale) {
+            Ok(Some(catalog)) => Some(catalog),
+            Ok(None) => {
+                if !candidates(locale).iter().any(|c| c == DEFAULT_LOCALE) {
+                    tracing::warn!("No messages for locale '{}', using English", locale);
+                }
+                None
+            }
+            Err(e) => {
+                tracing::warn!("{}; using English", e);
+                None
+            }
+        },
+    };
+
+    if let Some(catalog) = &catalog {
+        tracing::info!("Using '{}' messages", catalog.locale());
+    }
+    let mut active = ACTIVE.write().unwrap_or_else(|e| e.into_inner());
+    *active = Some(Arc::new(
+        catalog.unwrap_or_else(|| Catalog::english().clone()),
+    ));
+}
+
+/// The active locale.
+pub fn locale() -> String {
+    active().locale().to_string()
+}
+
+/// The active text for `key` with `params` filled in.
+///
+/// Falls back to English, then to the key itself.
+pub fn text(key: &str, params: &[(&str, String)]) -> String {
+    let active = active();
+    let template = active
+        .get(key)
+        .or_else(|| Catalog::english().get(key))
+        .unwrap_or(key);
+    format(template, params)
+}
+
+/// Replaces each `{name}` in `template` with the matching parameter.
+///
+/// Placeholders without a parameter are left as they are.
+pub fn format(template: &str, params: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn active() -> Arc<Catalog> {
+    if let Some(catalog) = ACTIVE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Arc::clone(catalog);
+    }
+    // Nothing set yet, so only SENTINEL_LOCALE applies
+    set_locale(None);
+    active()
+}
+
+/// `de_DE.UTF-8` → `["de_DE", "de"]`.
+fn candidates(locale: &str) -> Vec<String> {
+    let locale = locale.split('.').next().unwrap_or(locale).replace('-', "_");
+    let mut candidates = vec![locale.clone()];
+    if let Some((language, _)) = locale.split_once('_') {
+        candidates.push(language.to_string());
+    }
+    candidates
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_skips_comments_and_unescapes() {
+        let catalog = Catalog::parse(
+            "de",
+            "# Kommentar\n\ngreeting = Hallo {name}\nmulti = a\\nb\n",
+        )
+        .unwrap();
+        assert_eq!(catalog.get("greeting"), Some("Hallo {name}"));
+        assert_eq!(catalog.get("multi"), Some("a\nb"));
+        assert_eq!(catalog.get("missing"), None);
+
+        assert!(matches!(
+            Catalog::parse("de", "no separator"),
+            Err(SentinelError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn test_format_fills_known_placeholders() {
+        let params = [("name", "api".to_string()), ("pid", "42".to_string())];
+        assert_eq!(
+            format("Process '{name}' ({pid}) {unknown} {", &params),
+            "Process 'api' (42) {unknown} {"
+        );
+    }
+
+    #[test]
+    fn test_load_falls_back_to_language() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("de.txt"), "cli.stopped = {name} gestoppt\n").unwrap();
+
+        let catalog = Catalog::load(dir.path(), "de_AT.UTF-8").unwrap().unwrap();
+        assert_eq!(catalog.locale(), "de");
+        assert_eq!(catalog.get("cli.stopped"), Some("{name} gestoppt"));
+
+        assert!(Catalog::load(dir.path(), "fr").unwrap().is_none());
+        assert!(Catalog::load(dir.path(), "en_US").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_english_is_built_in() {
+        assert_eq!(Catalog::english().locale(), DEFAULT_LOCALE);
+        assert_eq!(
+            text("cli.stopped", &[("name", "api".to_string())]),
+            "Stopped api"
+        );
+        assert_eq!(text("no.such.key", &[]), "no.such.key");
+    }
+}
@@ -19,6 +19,117 @@ pub struct TimedMetric<T> {
     pub value: T,
 }
 
+/// Min/max/avg aggregate of the samples that fell into one time bucket.
+///
+/// Keeping min and max alongside the average means a short spike is still
+/// visible after downsampling.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricBucket {
+    /// Timestamp of the first sample in the bucket.
+    pub timestamp: DateTime<Utc>,
+    /// Smallest sample value.
+    pub min: f64,
+    /// Largest sample value.
+    pub max: f64,
+    /// Mean sample value.
+    pub avg: f64,
+    /// Number of samples aggregated into this bucket.
+    pub count: usize,
+}
+
+/// Metric values that can be aggregated into a [`MetricBucket`].
+pub trait MetricValue {
+    /// Converts the value to `f64` for aggregation.
+    fn as_f64(&self) -> f64;
+}
+
+macro_rules! impl_metric_value {
+    ($($t:ty),*) => {
+        $(impl MetricValue for $t {
+            fn as_f64(&self) -> f64 {
+                *self as f64
+            }
+        })*
+    };
+}
+
+impl_metric_value!(f32, f64, u32, u64, usize, i32, i64);
+
+/// Downsamples chronologically ordered samples into at most `max_points` buckets.
+///
+/// Buckets are equal slices of the time span covered by the samples, not of
+/// the sample count, so gaps (e.g. while the machine was asleep) show up as
+/// missing buckets instead of squashing unrelated samples together. Empty
+/// buckets are omitted.
+///
+/// # Arguments
+/// * `samples` - `(timestamp, value)` pairs, oldest first
+/// * `max_points` - Maximum number of buckets to return
+///
+/// # Examples
+/// ```
+/// use chrono::{Duration, Utc};
+/// use sentinel::core::metrics_buffer::downsample;
+///
+/// let start = Utc::now();
+/// let samples: Vec<_> = (0..100)
+///     .map(|i| (start + Duration::seconds(i), if i == 42 { 99.0 } else { 1.0 }))
+///     .collect();
+///
+/// let buckets = downsample(&samples, 10);
+/// assert_eq!(buckets.len(), 10);
+/// assert!(buckets.iter().any(|b| b.max == 99.0));
+/// ```
+pub fn downsample(samples: &[(DateTime<Utc>, f64)], max_points: usize) -> Vec<MetricBucket> {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return Vec::new();
+    };
+    if max_points == 0 {
+        return Vec::new();
+    }
+
+    let span_ms = (last.0 - first.0).num_milliseconds().max(0) as f64;
+    let bucket_ms = span_ms / max_points as f64;
+
+    let mut buckets: Vec<MetricBucket> = Vec::new();
+    let mut current_index = None;
+    for (timestamp, value) in samples {
+        let offset_ms = (*timestamp - first.0).num_milliseconds().max(0) as f64;
+        let index = if bucket_ms > 0.0 {
+            ((offset_ms / bucket_ms) as usize).min(max_points - 1)
+        } else {
+            0
+        };
+
+        match buckets.last_mut() {
+            Some(bucket) if current_index == Some(index) => {
+                bucket.min = bucket.min.min(*value);
+                bucket.max = bucket.max.max(*value);
+                // Running sum; divided by count once the bucket is complete
+                bucket.avg += value;
+                bucket.count += 1;
+            }
+            _ => {
+                buckets.push(MetricBucket {
+                    timestamp: *timestamp,
+                    min: *value,
+                    max: *value,
+                    avg: *value,
+                    count: 1,
+                });
+                current_index = Some(index);
+            }
+        }
+    }
+
+    for bucket in &mut buckets {
+        bucket.avg /= bucket.count as f64;
+    }
+
+    buckets
+}
+
 /// Circular buffer for storing time-series metrics.
 ///
 /// Automatically drops oldest data when capacity is reached.
@@ -182,6 +293,28 @@ impl<T: Clone> MetricsBuffer<T> {
     }
 }
 
+impl<T: Clone + MetricValue> MetricsBuffer<T> {
+    /// Gets the last `seconds` of history aggregated into at most `max_points` buckets.
+    ///
+    /// # Arguments
+    /// * `seconds` - How far back to look
+    /// * `max_points` - Maximum number of buckets to return
+    ///
+    /// # Returns
+    /// Min/max/avg buckets in chronological order (oldest first)
+    pub fn get_downsampled(&self, seconds: u64, max_points: usize) -> Vec<MetricBucket> {
+        let cutoff = Utc::now() - chrono::Duration::seconds(seconds as i64);
+        let samples: Vec<(DateTime<Utc>, f64)> = self
+            .data
+            .iter()
+            .filter(|m| m.timestamp >= cutoff)
+            .map(|m| (m.timestamp, m.value.as_f64()))
+            .collect();
+
+        downsample(&samples, max_points)
+    }
+}
+
 impl<T: Clone> Default for MetricsBuffer<T> {
     /// Creates a default metrics buffer with 60-second capacity.
     fn default() -> Self {
@@ -298,6 +431,74 @@ mod tests {
         assert_eq!(buffer.len(), 0);
     }
 
+    fn samples_at(offsets: &[(i64, f64)]) -> Vec<(DateTime<Utc>, f64)> {
+        let start = Utc::now();
+        offsets
+            .iter()
+            .map(|(secs, value)| (start + chrono::Duration::seconds(*secs), *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_downsample_empty() {
+        assert!(downsample(&[], 10).is_empty());
+        assert!(downsample(&samples_at(&[(0, 1.0)]), 0).is_empty());
+    }
+
+    #[test]
+    fn test_downsample_spike_survives_in_max() {
+        // One hour at 1Hz with a single spike
+        let offsets: Vec<(i64, f64)> = (0..3600)
+            .map(|i| (i, if i == 1234 { 100.0 } else { 10.0 }))
+            .collect();
+
+        let buckets = downsample(&samples_at(&offsets), 60);
+
+        assert!(buckets.len() <= 60);
+        let spiked: Vec<_> = buckets.iter().filter(|b| b.max == 100.0).collect();
+        assert_eq!(spiked.len(), 1);
+        assert_eq!(spiked[0].min, 10.0);
+        assert!(spiked[0].avg > 10.0 && spiked[0].avg < 100.0);
+        assert_eq!(buckets.iter().map(|b| b.count).sum::<usize>(), 3600);
+    }
+
+    #[test]
+    fn test_downsample_irregular_intervals() {
+        // Two bursts separated by a long gap (machine asleep)
+        let samples = samples_at(&[(0, 1.0), (1, 2.0), (2, 3.0), (1000, 4.0), (1001, 5.0)]);
+
+        let buckets = downsample(&samples, 10);
+
+        // Gap buckets are omitted rather than filled
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].count, 3);
+        assert_eq!(buckets[0].avg, 2.0);
+        assert_eq!(buckets[1].count, 2);
+        assert_eq!(buckets[1].max, 5.0);
+    }
+
+    #[test]
+    fn test_downsample_fewer_samples_than_points() {
+        let samples = samples_at(&[(0, 1.0), (10, 2.0), (20, 3.0)]);
+        let buckets = downsample(&samples, 100);
+
+        assert_eq!(buckets.len(), 3);
+        assert!(buckets.iter().all(|b| b.count == 1));
+    }
+
+    #[test]
+    fn test_get_downsampled() {
+        let mut buffer = MetricsBuffer::new(10);
+        buffer.push(5u64);
+        buffer.push(50u64);
+
+        let buckets = buffer.get_downsampled(60, 1);
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].min, 5.0);
+        assert_eq!(buckets[0].max, 50.0);
+        assert_eq!(buckets[0].count, 2);
+    }
+
     #[test]
     fn test_timestamps_are_set() {
         let mut buffer = MetricsBuffer::new(3);
@@ -0,0 +1,624 @@
+//! Prometheus metrics endpoint.
+//!
+//! The supervisor loop collects a [`MetricsSnapshot`] on each pass and hands
+//! it to the [`MetricsExporter`], whose embedded HTTP listener renders the
+//! latest snapshot on `GET /metrics`. Scrapes never refresh anything, so they
+//! cost the same however often Prometheus polls.
+
+use crate::core::{ProcessManager, SystemMonitor};
+use crate::error::{Result, SentinelError};
+use chrono::Utc;
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// Content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Metrics of a managed process.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessMetrics {
+    /// Process name.
+    pub name: String,
+    /// Whether the process is running.
+    pub up: bool,
+    /// CPU usage percentage.
+    pub cpu_percent: f64,
+    /// Memory usage in bytes.
+    pub memory_bytes: u64,
+    /// Restarts performed by the supervisor.
+    pub restart_count: u32,
+    /// Seconds since the process started, 0 when not running.
+    pub uptime_seconds: u64,
+    /// Log lines written since the process was last started.
+    pub log_lines_total: u64,
+}
+
+impl ProcessMetrics {
+    /// Collects metrics for every process known to `manager`.
+    ///
+    /// Resource usage is whatever the manager last recorded.
+    pub async fn collect_all(manager: &ProcessManager) -> Vec<Self> {
+        let now = Utc::now();
+        let mut metrics = Vec::new();
+
+        for info in manager.list().await {
+            let up = info.is_running();
+            let uptime_seconds = match info.started_at {
+                Some(started_at) if up => (now - started_at).num_seconds().max(0) as u64,
+                _ => 0,
+            };
+            metrics.push(Self {
+                log_lines_total: manager.log_lines_total(&info.name).await.unwrap_or(0),
+                up,
+                cpu_percent: info.cpu_usage as f64,
+                memory_bytes: info.memory_usage,
+                restart_count: info.restart_count,
+                uptime_seconds,
+                name: info.name,
+            });
+        }
+
+        metrics.sort_by(|a, b| a.name.cmp(&b.name));
+        metrics
+    }
+}
+
+/// Space on a mounted disk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskMetrics {
+    /// Mount point.
+    pub mount_point: String,
+    /// Total space in bytes.
+    pub total_bytes: u64,
+    /// Available space in bytes.
+    pub available_bytes: u64,
+}
+
+/// Machine-wide metrics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemMetrics {
+    /// Overall CPU usage percentage.
+    pub cpu_percent: f64,
+    /// Memory in use in bytes.
+    pub memory_used_bytes: u64,
+    /// Total memory in bytes.
+    pub memory_total_bytes: u64,
+    /// Mounted disks.
+    pub disks: Vec<DiskMetrics>,
+}
+
+impl SystemMetrics {
+    /// Refreshes CPU and memory on `monitor` and samples it.
+    ///
+    /// Disk space is whatever the monitor last scanned, since re-scanning
+    /// mounts is too costly to do on every pass.
+    pub fn collect(monitor: &mut SystemMonitor) -> Self {
+        monitor.refresh_cpu();
+        monitor.refresh_memory();
+        let disks = monitor
+            .disks()
+            .into_iter()
+            .map(|disk| DiskMetrics {
+                mount_point: disk.mount_point,
+                total_bytes: disk.total_space,
+                available_bytes: disk.available_space,
+            })
+            .collect();
+        let (memory_used_bytes, memory_total_bytes) = monitor.memory_usage();
+
+        Self {
+            cpu_percent: monitor.cpu_usage() as f64,
+            memory_used_bytes,
+            memory_total_bytes,
+            disks,
+        }
+    }
+}
+
+/// Everything exported at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Managed processes.
+    pub processes: Vec<ProcessMetrics>,
+    /// Machine-wide metrics, if collected.
+    pub system: Option<SystemMetrics>,
+    /// Crashed processes restarted by the supervisor.
+    pub supervisor_restarts_total: u64,
+}
+
+impl MetricsSnapshot {
+    /// Renders the snapshot in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let processes = &self.processes;
+
+        family(
+            &mut out,
+            "sentinel_process_up",
+            "gauge",
+            "Whether the managed process is running.",
+        );
+        for p in processes {
+            sample(
+                &mut out,
+                "sentinel_process_up",
+                &[("process", &p.name)],
+                p.up as u8,
+            );
+        }
+        family(
+            &mut out,
+            "sentinel_process_cpu_percent",
+            "gauge",
+            "CPU usage of the managed process in percent.",
+        );
+        for p in processes {
+            sample(
+                &mut out,
+                "sentinel_process_cpu_percent",
+                &[("process", &p.name)],
+                p.cpu_percent,
+            );
+        }
+        family(
+            &mut out,
+            "sentinel_process_memory_bytes",
+            "gauge",
+            "Memory usage of the managed process in bytes.",
+        );
+        for p in processes {
+            sample(
+                &mut out,
+                "sentinel_process_memory_bytes",
+                &[("process", &p.name)],
+                p.memory_bytes,
+            );
+        }
+        family(
+            &mut out,
+            "sentinel_process_restart_count",
+            "gauge",
+            "Restarts of the managed process by the supervisor.",
+        );
+        for p in processes {
+            sample(
+                &mut out,
+                "sentinel_process_restart_count",
+                &[("process", &p.name)],
+                p.restart_count,
+            );
+        }
+        family(
+            &mut out,
+            "sentinel_process_uptime_seconds",
+            "gauge",
+            "Seconds since the managed process started.",
+        );
+        for p in processes {
+            sample(
+                &mut out,
+                "sentinel_process_uptime_seconds",
+                &[("process", &p.name)],
+                p.uptime_seconds,
+            );
+        }
+        family(
+            &mut out,
+            "sentinel_process_log_lines_total",
+            "counter",
+            "Log lines written by the managed process since it last started.",
+        );
+        for p in processes {
+            sample(
+                &mut out,
+                "sentinel_process_log_lines_total",
+                &[("process", &p.name)],
+                p.log_lines_total,
+            );
+        }
+
+        family(
+            &mut out,
+            "sentinel_supervisor_restarts_total",
+            "counter",
+            "Crashed processes restarted by the supervisor.",
+        );
+        sample(
+            &mut out,
+            "sentinel_supervisor_restarts_total",
+            &[],
+            self.supervisor_restarts_total,
+        );
+
+        if let Some(system) = &self.system {
+            family(
+                &mut out,
+                "sentinel_system_cpu_percent",
+                "gauge",
+                "Overall CPU usage in percent.",
+            );
+            sample(
+                &mut out,
+                "sentinel_system_cpu_percent",
+                &[],
+                system.cpu_percent,
+            );
+            family(
+                &mut out,
+                "sentinel_system_memory_used_bytes",
+                "gauge",
+                "Memory in use in bytes.",
+            );
+            sample(
+                &mut out,
+                "sentinel_system_memory_used_bytes",
+                &[],
+                system.memory_used_bytes,
+            );
+            family(
+                &mut out,
+                "sentinel_system_memory_total_bytes",
+                "gauge",
+                "Total memory in bytes.",
+            );
+            sample(
+                &mut out,
+                "sentinel_system_memory_total_bytes",
+                &[],
+                system.memory_total_bytes,
+            );
+            family(
+                &mut out,
+                "sentinel_system_disk_total_bytes",
+                "gauge",
+                "Total space of the mounted disk in bytes.",
+            );
+            for disk in &system.disks {
+                sample(
+                    &mut out,
+                    "sentinel_system_disk_total_bytes",
+                    &[("mount_point", &disk.mount_point)],
+                    disk.total_bytes,
+                );
+            }
+            family(
+                &mut out,
+                "sentinel_system_disk_available_bytes",
+                "gauge",
+                "Available space on the mounted disk in bytes.",
+            );
+            for disk in &system.disks {
+                sample(
+                    &mut out,
+                    "sentinel_system_disk_available_bytes",
+                    &[("mount_point", &disk.mount_point)],
+                    disk.available_bytes,
+                );
+            }
+        }
+
+        out
+    }
+}
+
+/// Writes the `HELP` and `TYPE` lines of a metric family.
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Writes one sample line.
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+/// Escapes a label value so it can't break out of its quotes or line.
+fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Serves the latest [`MetricsSnapshot`] over HTTP while enabled.
+#[derive(Default)]
+pub struct MetricsExporter {
+    snapshot: Arc<RwLock<MetricsSnapshot>>,
+    /// Address last requested, even if binding it failed.
+    address: Option<SocketAddr>,
+    server: Option<Server>,
+}
+
+/// A running listener.
+struct Server {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MetricsExporter {
+    /// Creates a disabled exporter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves metrics on `address`, or stops serving when it is `None`.
+    ///
+    /// Does nothing if `address` is the one last requested, so a port that
+    /// failed to bind isn't retried on every call.
+    ///
+    /// # Errors
+    /// Returns `Other` if the address can't be bound.
+    pub async fn set_address(&mut self, address: Option<SocketAddr>) -> Result<()> {
+        if address == self.address {
+            return Ok(());
+        }
+        self.stop();
+        self.address = address;
+
+        let Some(address) = address else {
+            return Ok(());
+        };
+        let listener = TcpListener::bind(address).await.map_err(|e| {
+            SentinelError::Other(format!(
+                "Failed to start metrics listener on {}: {}",
+                address, e
+            ))
+        })?;
+        let local_addr = listener.local_addr()?;
+        let task = tokio::spawn(serve(listener, self.snapshot.clone()));
+
+        info!(
+            "Serving Prometheus metrics on http://{}/metrics",
+            local_addr
+        );
+        self.server = Some(Server { local_addr, task });
+        Ok(())
+    }
+
+    /// Whether the listener is running.
+    pub fn is_enabled(&self) -> bool {
+        self.server.is_some()
+    }
+
+    /// Address the listener is bound to, if running.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.as_ref().map(|server| server.local_addr)
+    }
+
+    /// Replaces the snapshot served to scrapes.
+    pub fn update(&self, snapshot: MetricsSnapshot) {
+        *self.snapshot.write().unwrap_or_else(|e| e.into_inner()) = snapshot;
+    }
+
+    /// Stops the listener, if running.
+    pub fn stop(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.task.abort();
+            info!("Stopped metrics listener on {}", server.local_addr);
+        }
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Accepts connections until the task is aborted.
+async fn serve(listener: TcpListener, snapshot: Arc<RwLock<MetricsSnapshot>>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                debug!("Metrics listener accept failed: {}", e);
+                continue;
+            }
+        };
+        let snapshot = snapshot.clone();
+        let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+            let response = respond(&request, &snapshot);
+            async move { Ok::<_, Infallible>(response) }
+        });
+        tokio::spawn(async move {
+            let connection = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service);
+            if let Err(e) = connection.await {
+                debug!("Metrics connection failed: {}", e);
+            }
+        });
+    }
+}
+
+fn respond(
+    request: &Request<Incoming>,
+    snapshot: &RwLock<MetricsSnapshot>,
+) -> Response<Full<Bytes>> {
+    let (status, body) = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => {
+            let body = snapshot.read().unwrap_or_else(|e| e.into_inner()).render();
+            (StatusCode::OK, body)
+        }
+        (_, "/metrics") => (StatusCode::METHOD_NOT_ALLOWED, String::new()),
+        _ => (StatusCode::NOT_FOUND, String::new()),
+    };
+
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    if status == StatusCode::OK {
+        response.headers_mut().insert(
+            hyper::header::CONTENT_TYPE,
+            hyper::header::HeaderValue::from_static(CONTENT_TYPE),
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProcessConfig;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            path
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    fn process(name: &str, up: bool) -> ProcessMetrics {
+        ProcessMetrics {
+            name: name.to_string(),
+            up,
+            cpu_percent: 12.5,
+            memory_bytes: 1024,
+            restart_count: 2,
+            uptime_seconds: 60,
+            log_lines_total: 7,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let snapshot = MetricsSnapshot {
+            processes: vec![process("api", true), process("worker", false)],
+            system: Some(SystemMetrics {
+                cpu_percent: 42.0,
+                memory_used_bytes: 512,
+                memory_total_bytes: 2048,
+                disks: vec![DiskMetrics {
+                    mount_point: "/".to_string(),
+                    total_bytes: 100,
+                    available_bytes: 40,
+                }],
+            }),
+            supervisor_restarts_total: 3,
+        };
+
+        let text = snapshot.render();
+        assert!(text.contains("# TYPE sentinel_process_up gauge\n"));
+        assert!(text.contains("sentinel_process_up{process=\"api\"} 1\n"));
+        assert!(text.contains("sentinel_process_up{process=\"worker\"} 0\n"));
+        assert!(text.contains("sentinel_process_cpu_percent{process=\"api\"} 12.5\n"));
+        assert!(text.contains("# TYPE sentinel_process_log_lines_total counter\n"));
+        assert!(text.contains("sentinel_supervisor_restarts_total 3\n"));
+        assert!(text.contains("sentinel_system_memory_total_bytes 2048\n"));
+        assert!(text.contains("sentinel_system_disk_available_bytes{mount_point=\"/\"} 40\n"));
+    }
+
+    #[test]
+    fn test_render_without_system() {
+        let text = MetricsSnapshot::default().render();
+        assert!(text.contains("sentinel_supervisor_restarts_total 0\n"));
+        assert!(!text.contains("sentinel_system_"));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("api"), "api");
+        assert_eq!(escape_label_value("a\"b\\c\nd\re"), "a\\\"b\\\\c\\nde");
+    }
+
+    #[tokio::test]
+    async fn test_serves_metrics_on_ephemeral_port() {
+        let mut exporter = MetricsExporter::new();
+        exporter
+            .set_address(Some("127.0.0.1:0".parse().unwrap()))
+            .await
+            .unwrap();
+        let addr = exporter.local_addr().unwrap();
+
+        exporter.update(MetricsSnapshot {
+            processes: vec![process("api", true), process("evil\"} 1\nfake", true)],
+            system: None,
+            supervisor_restarts_total: 1,
+        });
+
+        let response = get(addr, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("content-type: text/plain; version=0.0.4"));
+        assert!(response.contains("sentinel_process_up{process=\"api\"} 1\n"));
+        assert!(response.contains("sentinel_process_memory_bytes{process=\"api\"} 1024\n"));
+        assert!(response.contains("sentinel_process_restart_count{process=\"api\"} 2\n"));
+        assert!(response.contains("sentinel_process_uptime_seconds{process=\"api\"} 60\n"));
+        assert!(response.contains("sentinel_supervisor_restarts_total 1\n"));
+        // The quote and newline stay inside the label value
+        assert!(response.contains("sentinel_process_up{process=\"evil\\\"} 1\\nfake\"} 1\n"));
+        assert!(!response.lines().any(|line| line.starts_with("fake")));
+
+        assert!(get(addr, "/").await.starts_with("HTTP/1.1 404"));
+
+        exporter.set_address(None).await.unwrap();
+        assert!(!exporter.is_enabled());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bind_failure_is_not_retried() {
+        let taken = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = Some(taken.local_addr().unwrap());
+        let mut exporter = MetricsExporter::new();
+
+        assert!(exporter.set_address(address).await.is_err());
+        assert!(exporter.set_address(address).await.is_ok());
+        assert!(!exporter.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_collect_process_metrics() {
+        let manager = ProcessManager::new();
+        manager
+            .start(ProcessConfig {
+                name: "sleeper".to_string(),
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "echo ready; sleep 30".to_string()],
+                auto_restart: Some(false),
+                restart_limit: 0,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+        let metrics = ProcessMetrics::collect_all(&manager).await;
+        manager.stop_all().await.unwrap();
+
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].name, "sleeper");
+        assert!(metrics[0].up);
+        assert_eq!(metrics[0].log_lines_total, 1);
+        assert_eq!(metrics[0].restart_count, 0);
+    }
+}
@@ -0,0 +1,755 @@
+//! Metrics history kept in SQLite.
+//!
+//! The in-memory histories cover the last minute or so. With
+//! `settings.metricsHistory.enabled`, system and per-process metrics are
+//! also recorded to `metrics.db` in the state directory every
+//! `intervalSecs`, so questions like "what was eating my CPU during the 2pm
+//! build" can still be answered days later. Samples older than
+//! `retentionDays` are deleted as new ones arrive.
+//!
+//! Like the event journal, samples go through a [`MetricsRecorder`], whose
+//! writer thread batches them into transactions; a slow or full disk drops
+//! samples rather than holding up monitoring.
+//!
+//! Unlike the journal, the history lives in a database rather than
+//! rotated files: range queries over days of samples need an index and
+//! aggregation to stay fast, and the CLI reads the file while the app
+//! writes it, which SQLite's write-ahead log allows.
+
+use crate::core::{ProcessMetrics, SystemMetrics};
+use crate::error::{Result, SentinelError};
+use crate::features::network_monitor::NetworkRates;
+use crate::models::{MetricsHistorySettings, SYSTEM_TARGET};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Batches waiting to be written; more are dropped.
+const QUEUE_CAPACITY: usize = 64;
+
+/// How often the writer deletes samples past the retention period.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Default number of points a range query is aggregated into.
+pub const DEFAULT_MAX_POINTS: usize = 200;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS samples (
+        target TEXT NOT NULL,
+        metric TEXT NOT NULL,
+        ts INTEGER NOT NULL,
+        value REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS samples_by_series ON samples (target, metric, ts);
+    CREATE INDEX IF NOT EXISTS samples_by_time ON samples (ts);
+";
+
+/// A metric kept in the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryMetric {
+    /// CPU usage percentage.
+    Cpu,
+    /// Memory in use in bytes.
+    Memory,
+    /// Used space percentage of the fullest disk; system only.
+    Disk,
+    /// Bytes received per second; system only.
+    NetworkRx,
+    /// Bytes sent per second; system only.
+    NetworkTx,
+}
+
+impl HistoryMetric {
+    /// Name the metric is stored under.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryMetric::Cpu => "cpu",
+            HistoryMetric::Memory => "memory",
+            HistoryMetric::Disk => "disk",
+            HistoryMetric::NetworkRx => "network_rx",
+            HistoryMetric::NetworkTx => "network_tx",
+        }
+    }
+}
+
+/// One recorded value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    /// `system` or a process name.
+    pub target: String,
+    pub metric: HistoryMetric,
+    pub timestamp: DateTime<Utc>,
+    pub value: f64,
+}
+
+impl MetricSample {
+    pub fn new(
+        target: impl Into<String>,
+        metric: HistoryMetric,
+        timestamp: DateTime<Utc>,
+        value: f64,
+    ) -> Self {
+        Self {
+            target: target.into(),
+            metric,
+            timestamp,
+            value,
+        }
+    }
+}
+
+/// Samples aggregated over one bucket of a range query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricPoint {
+    /// Start of the bucket.
+    pub timestamp: DateTime<Utc>,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Samples in the bucket.
+    pub samples: u64,
+}
+
+/// The metrics history database.
+pub struct MetricsStore {
+    connection: Connection,
+}
+
+impl MetricsStore {
+    /// Opens the database at `path`, creating it and its directory if needed.
+    ///
+    /// # Errors
+    /// Returns `MetricsHistoryError` if the database can't be opened or isn't
+    /// one.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| SentinelError::FileIoError {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let connection = Connection::open(path)?;
+        // Let the app write while the CLI reads
+        connection.busy_timeout(Duration::from_secs(5))?;
+        connection.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+        Self::init(connection)
+    }
+
+    /// Opens a database that lives in memory, for tests.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Gets the default database path.
+    ///
+    /// Returns: `~/.config/sentinel/metrics.db`
+    pub fn default_path() -> PathBuf {
+        if let Some(config_dir) = dirs::config_dir() {
+            config_dir.join("sentinel").join("metrics.db")
+        } else {
+            PathBuf::from("metrics.db")
+        }
+    }
+
+    /// The database `settings` record to.
+    pub fn path(settings: &MetricsHistorySettings) -> PathBuf {
+        settings.path.clone().unwrap_or_else(Self::default_path)
+    }
+
+    /// Writes `samples` in one transaction.
+    pub fn insert(&mut self, samples: &[MetricSample]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT INTO samples (target, metric, ts, value) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for sample in samples {
+                statement.execute(params![
+                    sample.target,
+                    sample.metric.as_str(),
+                    sample.timestamp.timestamp_millis(),
+                    sample.value
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Aggregates the samples of `metric` for `target` between `from` and
+    /// `to` (inclusive) into at most `max_points` equal buckets, oldest
+    /// first. Buckets without samples are left out.
+    pub fn query(
+        &self,
+        target: &str,
+        metric: HistoryMetric,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        max_points: usize,
+    ) -> Result<Vec<MetricPoint>> {
+        let from_ms = from.timestamp_millis();
+        let to_ms = to.timestamp_millis();
+        if to_ms < from_ms {
+            return Ok(Vec::new());
+        }
+        let max_points = max_points.max(1) as i64;
+        // Round up so the last millisecond still falls in bucket max_points - 1
+        let bucket_ms = ((to_ms - from_ms + 1) + max_points - 1) / max_points;
+
+        let mut statement = self.connection.prepare_cached(
+            "SELECT (ts - ?3) / ?5 AS bucket, AVG(value), MIN(value), MAX(value), COUNT(*)
+             FROM samples
+             WHERE target = ?1 AND metric = ?2 AND ts >= ?3 AND ts <= ?4
+             GROUP BY bucket
+             ORDER BY bucket",
+        )?;
+        let rows = statement.query_map(
+            params![target, metric.as_str(), from_ms, to_ms, bucket_ms],
+            |row| {
+                let bucket: i64 = row.get(0)?;
+                Ok(MetricPoint {
+                    timestamp: DateTime::from_timestamp_millis(from_ms + bucket * bucket_ms)
+                        .unwrap_or(from),
+                    avg: row.get(1)?,
+                    min: row.get(2)?,
+                    max: row.get(3)?,
+                    samples: row.get::<_, i64>(4)? as u64,
+                })
+            },
+        )?;
+        let points = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(points)
+    }
+
+    /// Deletes samples taken before `before`, returning how many.
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize> {
+        Ok(self.connection.execute(
+            "DELETE FROM samples WHERE ts < ?1",
+            params![before.timestamp_millis()],
+        )?)
+    }
+}
+
+/// Turns one pass of the history loop into samples: system CPU, memory,
+/// fullest disk and network throughput, and the CPU and memory of each
+/// running process.
+pub fn samples_from(
+    timestamp: DateTime<Utc>,
+    system: &SystemMetrics,
+    network: Option<&NetworkRates>,
+    processes: &[ProcessMetrics],
+) -> Vec<MetricSample> {
+    let mut samples = vec![
+        MetricSample::new(
+            SYSTEM_TARGET,
+            HistoryMetric::Cpu,
+            timestamp,
+            system.cpu_percent,
+        ),
+        MetricSample::new(
+            SYSTEM_TARGET,
+            HistoryMetric::Memory,
+            timestamp,
+            system.memory_used_bytes as f64,
+        ),
+    ];
+    let fullest_disk = system
+        .disks
+        .iter()
+        .filter(|disk| disk.total_bytes > 0)
+        .map(|disk| {
+            disk.total_bytes.saturating_sub(disk.available_bytes) as f64 * 100.0
+                / disk.total_bytes as f64
+        })
+        .max_by(|a, b| a.total_cmp(b));
+    if let Some(used_percent) = fullest_disk {
+        samples.push(MetricSample::new(
+            SYSTEM_TARGET,
+            HistoryMetric::Disk,
+            timestamp,
+            used_percent,
+        ));
+    }
+    if let Some(rates) = network {
+        samples.push(MetricSample::new(
+            SYSTEM_TARGET,
+            HistoryMetric::NetworkRx,
+            timestamp,
+            rates.bytes_received_per_sec,
+        ));
+        samples.push(MetricSample::new(
+            SYSTEM_TARGET,
+            HistoryMetric::NetworkTx,
+            timestamp,
+            rates.bytes_sent_per_sec,
+        ));
+    }
+
+    for process in processes.iter().filter(|process| process.up) {
+        samples.push(MetricSample::new(
+            &process.name,
+            HistoryMetric::Cpu,
+            timestamp,
+            process.cpu_percent,
+        ));
+        samples.push(MetricSample::new(
+            &process.name,
+            HistoryMetric::Memory,
+            timestamp,
+            process.memory_bytes as f64,
+        ));
+    }
+    samples
+}
+
+/// Message to the writer thread.
+enum Message {
+    Samples(Vec<MetricSample>),
+    /// Acknowledged once everything queued before it is written.
+    Flush(mpsc::Sender<()>),
+}
+
+/// State shared between recorder handles.
+struct Shared {
+    path: PathBuf,
+    sender: SyncSender<Message>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Records samples to a [`MetricsStore`] without blocking.
+///
+/// Cheap to clone; clones share the writer. [`Default`] gives a disabled
+/// recorder, which discards everything.
+#[derive(Clone, Default)]
+pub struct MetricsRecorder {
+    shared: Option<Arc<Shared>>,
+}
+
+impl MetricsRecorder {
+    /// Creates a recorder that discards samples.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Creates a recorder writing to the database at `path` from a
+    /// background thread, deleting samples older than `retention`.
+    ///
+    /// The database is opened on that thread; if it can't be, samples are
+    /// dropped with a warning. The thread exits once every clone of the
+    /// recorder is dropped.
+    pub fn open(path: impl Into<PathBuf>, retention: Duration) -> Self {
+        let path = path.into();
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let writer = HistoryWriter {
+            path: path.clone(),
+            retention,
+            dropped: dropped.clone(),
+        };
+        let spawned = std::thread::Builder::new()
+            .name("metrics-history".to_string())
+            .spawn(move || writer.run(receiver));
+        if let Err(e) = spawned {
+            warn!("Failed to start metrics history writer: {}", e);
+            return Self::disabled();
+        }
+
+        Self {
+            shared: Some(Arc::new(Shared {
+                path,
+                sender,
+                dropped,
+            })),
+        }
+    }
+
+    /// Database written to, `None` when disabled.
+    pub fn path(&self) -> Option<&Path> {
+        self.shared.as_ref().map(|shared| shared.path.as_path())
+    }
+
+    /// Queues `samples` for writing, dropping them if the queue is full.
+    pub fn record(&self, samples: Vec<MetricSample>) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+        if samples.is_empty() {
+            return;
+        }
+        match shared.sender.try_send(Message::Samples(samples)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(Message::Samples(samples))) => {
+                shared
+                    .dropped
+                    .fetch_add(samples.len() as u64, Ordering::Relaxed);
+                warn!(
+                    "Metrics history queue is full; dropped {} sample(s)",
+                    samples.len()
+                );
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Number of samples dropped because the queue was full or the database
+    /// couldn't be written.
+    pub fn dropped(&self) -> u64 {
+        self.shared
+            .as_ref()
+            .map_or(0, |shared| shared.dropped.load(Ordering::Relaxed))
+    }
+
+    /// Blocks until every sample recorded so far has been written or dropped.
+    pub fn flush(&self) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+        let (ack, done) = mpsc::channel();
+        if shared.sender.send(Message::Flush(ack)).is_ok() {
+            let _ = done.recv();
+        }
+    }
+}
+
+/// Owns the database on the writer thread.
+struct HistoryWriter {
+    path: PathBuf,
+    retention: Duration,
+    dropped: Arc<AtomicU64>,
+}
+
+impl HistoryWriter {
+    fn run(self, receiver: Receiver<Message>) {
+        let mut store = match MetricsStore::open(&self.path) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                warn!(
+                    "Failed to open metrics history {}, dropping samples: {}",
+                    self.path.display(),
+                    e
+                );
+                None
+            }
+        };
+        let mut last_prune: Option<Instant> = None;
+        let mut failing = false;
+
+        while let Ok(message) = receiver.recv() {
+            let mut batch = Vec::new();
+            let mut acks = Vec::new();
+            let mut next = Some(message);
+            // Write whatever queued up behind the first message together
+            while let Some(message) = next {
+                match message {
+                    Message::Samples(samples) => batch.extend(samples),
+                    Message::Flush(ack) => acks.push(ack),
+                }
+                next = receiver.try_recv().ok();
+            }
+
+            match &mut store {
+                Some(store) => {
+                    if !batch.is_empty() {
+                        match store.insert(&batch) {
+                            Ok(()) if failing => {
+                                info!("Metrics history is writable again");
+                                failing = false;
+                            }
+                            Ok(()) => {}
+                            Err(e) => {
+                                self.dropped
+                                    .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                                if !failing {
+                                    warn!(
+                                        "Failed to write metrics history {}, dropping samples until it recovers: {}",
+                                        self.path.display(),
+                                        e
+                                    );
+                                    failing = true;
+                                }
+                            }
+                        }
+                    }
+                    if last_prune.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                        self.prune(store);
+                        last_prune = Some(Instant::now());
+                    }
+                }
+                None => {
+                    self.dropped
+                        .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                }
+            }
+
+            for ack in acks {
+                let _ = ack.send(());
+            }
+        }
+    }
+
+    fn prune(&self, store: &MetricsStore) {
+        let Ok(retention) = chrono::Duration::from_std(self.retention) else {
+            return;
+        };
+        match store.prune(Utc::now() - retention) {
+            Ok(0) => {}
+            Ok(removed) => info!("Pruned {} metrics history sample(s)", removed),
+            Err(e) => warn!("Failed to prune metrics history: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    /// CPU samples for `target` every 10 seconds over `minutes`, rising by
+    /// one per sample from 0.
+    fn cpu_series(target: &str, minutes: i64) -> Vec<MetricSample> {
+        (0..minutes * 6)
+            .map(|i| MetricSample::new(target, HistoryMetric::Cpu, at(i * 10), i as f64))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_aggregates_into_buckets() {
+        let mut store = MetricsStore::open_in_memory().unwrap();
+        store.insert(&cpu_series("api", 10)).unwrap();
+        store.insert(&cpu_series("worker", 10)).unwrap();
+
+        // 10 minutes into 5 buckets of 2 minutes, 12 samples each
+        let end = at(600) - chrono::Duration::milliseconds(1);
+        let points = store
+            .query("api", HistoryMetric::Cpu, at(0), end, 5)
+            .unwrap();
+        assert_eq!(points.len(), 5);
+        for (index, point) in points.iter().enumerate() {
+            let first = index as f64 * 12.0;
+            assert_eq!(point.timestamp, at(index as i64 * 120));
+            assert_eq!(point.samples, 12);
+            assert_eq!(point.min, first);
+            assert_eq!(point.max, first + 11.0);
+            assert_eq!(point.avg, first + 5.5);
+        }
+    }
+
+    #[test]
+    fn test_query_filters_series_and_range() {
+        let mut store = MetricsStore::open_in_memory().unwrap();
+        store.insert(&cpu_series("api", 10)).unwrap();
+        store
+            .insert(&[MetricSample::new(
+                "api",
+                HistoryMetric::Memory,
+                at(30),
+                1024.0,
+            )])
+            .unwrap();
+
+        // One bucket covering 2 minutes of samples
+        let points = store
+            .query("api", HistoryMetric::Cpu, at(60), at(179), 1)
+            .unwrap();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].samples, 12);
+        assert_eq!(points[0].min, 6.0);
+
+        let memory = store
+            .query("api", HistoryMetric::Memory, at(0), at(600), 100)
+            .unwrap();
+        assert_eq!(memory.len(), 1);
+        assert_eq!(memory[0].avg, 1024.0);
+
+        assert!(store
+            .query("other", HistoryMetric::Cpu, at(0), at(600), 100)
+            .unwrap()
+            .is_empty());
+        assert!(store
+            .query("api", HistoryMetric::Cpu, at(600), at(0), 100)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_query_never_exceeds_max_points() {
+        let mut store = MetricsStore::open_in_memory().unwrap();
+        store.insert(&cpu_series("api", 60)).unwrap();
+
+        for max_points in [1, 7, 100, 360, 1000] {
+            let points = store
+                .query("api", HistoryMetric::Cpu, at(0), at(3590), max_points)
+                .unwrap();
+            assert!(points.len() <= max_points);
+            assert_eq!(points.iter().map(|p| p.samples).sum::<u64>(), 360);
+        }
+    }
+
+    #[test]
+    fn test_prune_removes_old_samples() {
+        let mut store = MetricsStore::open_in_memory().unwrap();
+        store.insert(&cpu_series("api", 10)).unwrap();
+
+        assert_eq!(store.prune(at(300)).unwrap(), 30);
+        let points = store
+            .query("api", HistoryMetric::Cpu, at(0), at(600), 1)
+            .unwrap();
+        assert_eq!(points[0].samples, 30);
+        assert_eq!(points[0].min, 30.0);
+        assert_eq!(store.prune(at(0)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_recorder_writes_batches() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("metrics.db");
+        let recorder = MetricsRecorder::open(&path, Duration::from_secs(365 * 24 * 60 * 60));
+
+        let now = Utc::now();
+        for i in 0..20 {
+            recorder.record(vec![
+                MetricSample::new("system", HistoryMetric::Cpu, now, i as f64),
+                MetricSample::new("api", HistoryMetric::Cpu, now, 1.0),
+            ]);
+        }
+        recorder.flush();
+        assert_eq!(recorder.dropped(), 0);
+
+        let store = MetricsStore::open(&path).unwrap();
+        let points = store
+            .query(
+                "system",
+                HistoryMetric::Cpu,
+                now - chrono::Duration::minutes(1),
+                now,
+                1,
+            )
+            .unwrap();
+        assert_eq!(points[0].samples, 20);
+        assert_eq!(points[0].max, 19.0);
+    }
+
+    #[test]
+    fn test_recorder_prunes_on_write() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("metrics.db");
+        let old = Utc::now() - chrono::Duration::days(8);
+        MetricsStore::open(&path)
+            .unwrap()
+            .insert(&[MetricSample::new("api", HistoryMetric::Cpu, old, 5.0)])
+            .unwrap();
+
+        let recorder = MetricsRecorder::open(&path, Duration::from_secs(7 * 24 * 60 * 60));
+        recorder.record(vec![MetricSample::new(
+            "api",
+            HistoryMetric::Cpu,
+            Utc::now(),
+            1.0,
+        )]);
+        recorder.flush();
+
+        let store = MetricsStore::open(&path).unwrap();
+        let points = store
+            .query("api", HistoryMetric::Cpu, old, Utc::now(), 1)
+            .unwrap();
+        assert_eq!(points[0].samples, 1);
+        assert_eq!(points[0].avg, 1.0);
+    }
+
+    #[test]
+    fn test_disabled_recorder_discards() {
+        let recorder = MetricsRecorder::disabled();
+        recorder.record(vec![MetricSample::new(
+            "api",
+            HistoryMetric::Cpu,
+            Utc::now(),
+            1.0,
+        )]);
+        recorder.flush();
+        assert!(recorder.path().is_none());
+        assert_eq!(recorder.dropped(), 0);
+    }
+
+    #[test]
+    fn test_samples_from_pass() {
+        let system = SystemMetrics {
+            cpu_percent: 40.0,
+            memory_used_bytes: 4096,
+            memory_total_bytes: 8192,
+            disks: vec![
+                crate::core::DiskMetrics {
+                    mount_point: "/".to_string(),
+                    total_bytes: 100,
+                    available_bytes: 75,
+                },
+                crate::core::DiskMetrics {
+                    mount_point: "/data".to_string(),
+                    total_bytes: 100,
+                    available_bytes: 10,
+                },
+            ],
+        };
+        let process = |name: &str, up| ProcessMetrics {
+            name: name.to_string(),
+            up,
+            cpu_percent: 12.5,
+            memory_bytes: 2048,
+            ..Default::default()
+        };
+
+        let samples = samples_from(
+            at(0),
+            &system,
+            None,
+            &[process("api", true), process("worker", false)],
+        );
+        let value = |target: &str, metric| {
+            samples
+                .iter()
+                .find(|s| s.target == target && s.metric == metric)
+                .map(|s| s.value)
+        };
+        assert_eq!(value("system", HistoryMetric::Cpu), Some(40.0));
+        assert_eq!(value("system", HistoryMetric::Memory), Some(4096.0));
+        assert_eq!(value("system", HistoryMetric::Disk), Some(90.0));
+        assert_eq!(value("system", HistoryMetric::NetworkRx), None);
+        assert_eq!(value("api", HistoryMetric::Cpu), Some(12.5));
+        assert_eq!(value("api", HistoryMetric::Memory), Some(2048.0));
+        // Stopped processes have nothing to record
+        assert_eq!(value("worker", HistoryMetric::Cpu), None);
+    }
+
+    #[test]
+    fn test_metric_names() {
+        for metric in [
+            HistoryMetric::Cpu,
+            HistoryMetric::Memory,
+            HistoryMetric::Disk,
+            HistoryMetric::NetworkRx,
+            HistoryMetric::NetworkTx,
+        ] {
+            assert_eq!(
+                serde_json::to_value(metric).unwrap(),
+                serde_json::Value::from(metric.as_str())
+            );
+        }
+    }
+}
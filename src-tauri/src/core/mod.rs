@@ -3,40 +3,155 @@
 //! This module contains the main components:
 //! - Configuration system
 //! - Process manager
+//! - Open file descriptor and thread counts
 //! - System monitor
 //! - External process monitoring
+//! - Alerting
+//! - Health checks
+//! - Cron schedules
+//! - Readiness probes
+//! - Restart policies
+//! - Port pre-flight checks
+//! - Start previews
+//! - Startup reports
+//! - Process log files, rotated and pruned
+//! - Prometheus metrics
+//! - Metrics history in SQLite
+//! - Usage rollups by tag
+//! - HTTP API for headless use, with a WebSocket event stream
+//! - Dashboard snapshots
+//! - Docker container usage for the processes that run them
+//! - Working directory disk usage
+//! - Watching env files of running processes
+//! - Graceful shutdown
+//! - Single-instance lock and control socket
+//! - Message catalog for user-facing text
 
+pub mod alerts;
+pub mod api_server;
+pub mod child_env;
 pub mod config;
+pub mod config_import;
+pub mod config_inheritance;
+mod config_schema;
+pub mod config_watcher;
+pub mod container_link;
+pub mod dashboard;
+pub mod diagnostics;
+pub mod disk_usage;
+pub mod env_file;
+pub mod env_watcher;
+pub mod event_bus;
+pub mod event_journal;
+pub mod event_stream;
+pub mod executable;
 pub mod external_process_monitor;
 pub mod framework_detector;
+mod gitignore;
+pub mod gpu;
+pub mod group_metrics;
+pub mod health_check;
+pub mod instance;
+pub mod launch;
 pub mod log_buffer;
+pub mod log_decoder;
+pub mod log_file;
+pub mod log_format;
+pub mod log_retention;
+pub mod log_search;
+pub mod log_tail;
+pub mod log_timestamp;
+pub mod logging;
+pub mod messages;
 pub mod metrics_buffer;
+pub mod metrics_exporter;
+pub mod metrics_history;
+pub mod notifications;
+pub mod ports;
 pub mod process_config;
 pub mod process_control;
+pub mod process_handles;
 pub mod process_manager;
+pub mod process_registry;
+pub mod process_tree;
 pub mod pty_process_manager;
+pub mod readiness;
+pub mod restart_policy;
+pub mod scheduler;
+pub mod scheduling;
+pub mod schema;
+pub mod secrets;
+pub mod shutdown;
+pub mod startup_report;
 pub mod state_manager;
 pub mod system_monitor;
+pub mod templates;
+pub mod validation;
+pub mod workspace;
 
-pub use config::ConfigManager;
+pub use alerts::{AlertManager, AlertSnapshot, AlertTransition, ProcessSample, SystemSample};
+pub use api_server::{ApiContext, ApiServer};
+pub use config::{ConfigDiff, ConfigLimits, ConfigManager};
+pub use config_import::{ImportReport, ImportSource};
+pub use container_link::ContainerRef;
+pub use dashboard::{DashboardSection, DashboardSections, DashboardSnapshot, DashboardSources};
+pub use diagnostics::{CheckResult, CheckStatus, DoctorReport};
+pub use disk_usage::{CleanReport, DirUsage, DiskUsageCache, DiskUsageReport};
+pub use env_file::EnvDiff;
+pub use env_watcher::{EnvFileChangedEvent, EnvFileWatcher, WatchedEnvFile};
+pub use event_bus::{EventBus, LiveEvent, LogPublisher};
+pub use event_journal::{EventJournal, EventRecorder};
 pub use external_process_monitor::{
-    ExternalProcessMonitor, LogLineEvent, LogSource, ProcessAttachment,
+    AttachmentDetachedEvent, AttachmentInfo, AttachmentTargetChangedEvent, ExternalProcessMonitor,
+    LogLineEvent, LogSource, ProcessAttachment,
 };
 pub use framework_detector::{
     detect_framework, get_framework_templates, scan_directory_for_projects,
 };
-pub use log_buffer::{LogBuffer, LogLine, LogStream};
-pub use metrics_buffer::{MetricsBuffer, TimedMetric};
+pub(crate) use gitignore::glob_match;
+pub use gpu::{GpuCollector, GpuMonitor};
+pub use group_metrics::{GroupMetrics, GroupPoint};
+pub use instance::{ControlCommand, ControlListener, ControlRequest, InstanceLock};
+pub use launch::{Launch, PortPreview, PreviewResult};
+pub use log_buffer::{LogBuffer, LogLine, LogMemoryStats, LogMemoryUsage, LogStream};
+pub use log_file::{LogFiles, LogWriter};
+pub use log_format::LogLevel;
+pub use log_retention::LogPruneSummary;
+pub use log_search::{LogMatch, LogQuery, LogSearchOptions, ProcessLogMatches};
+pub use log_tail::{LogOffsets, TailStart, TailTarget};
+pub use log_timestamp::{MergedLogLine, TimestampOrder};
+pub use metrics_buffer::{MetricBucket, MetricsBuffer, TimedMetric};
+pub use metrics_exporter::{
+    DiskMetrics, MetricsExporter, MetricsSnapshot, ProcessMetrics, SystemMetrics,
+};
+pub use metrics_history::{
+    HistoryMetric, MetricPoint, MetricSample, MetricsRecorder, MetricsStore,
+};
+pub use notifications::{NotificationDispatcher, NotificationPayload, RetryPolicy};
 pub use process_config::{
     DetectedProject, FrameworkDetection, FrameworkType, HealthCheckResult,
     ProcessConfig as ManagedProcessConfig, ProcessConfigStore, ProcessStatus, ProcessStatusInfo,
-    ProcessTemplate,
+    ProcessTemplate, ProjectScanResult,
 };
 pub use process_control::ProcessController;
-pub use process_manager::ProcessManager;
+pub use process_manager::{ProcessManager, RESOURCE_REFRESH_INTERVAL};
+pub use process_registry::ProcessRegistry;
+pub use process_tree::{build_tree, ProcessEntry, ProcessIndex, ProcessTable};
 pub use pty_process_manager::{
     ProcessConfig as PtyProcessConfig, ProcessExitEvent, ProcessInfo, ProcessOutputEvent,
     PtyProcessManager,
 };
+pub use readiness::Readiness;
+pub use restart_policy::PolicyTrigger;
+pub use scheduler::Scheduler;
+pub use shutdown::{ShutdownProgress, ShutdownReport};
+pub use startup_report::{ProcessStartup, StartupReport, StartupTotals};
 pub use state_manager::StateManager;
-pub use system_monitor::SystemMonitor;
+pub use system_monitor::{SystemMonitor, MIN_CPU_SAMPLE_INTERVAL};
+pub use templates::{
+    TemplateSource, TemplateStore, TemplateSummary, TemplateVariable, UserTemplate,
+};
+pub use workspace::{
+    ImportMode, Workspace, WorkspaceExportReport, WorkspaceImportReport, WorkspacePaths,
+    WorkspacePreview,
+};
@@ -0,0 +1,520 @@
+//! Delivery of process lifecycle events to notification sinks.
+//!
+//! [`NotificationDispatcher::dispatch`] never waits on the network: events
+//! that match a sink's filters are queued for a background task that posts
+//! them, retrying failed attempts with exponential backoff. When the queue is
+//! full, events are dropped and counted so a slow endpoint can't hold up the
+//! supervisor.
+
+use crate::error::{Result, SentinelError};
+use crate::models::{NotificationSink, NotificationSinkKind, ProcessEvent, ProcessEventKind};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, warn};
+
+/// Events waiting for delivery before new ones are dropped.
+pub const QUEUE_CAPACITY: usize = 256;
+
+/// JSON body posted to webhook sinks.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPayload {
+    /// The event being reported.
+    #[serde(flatten)]
+    pub event: ProcessEvent,
+    /// Host Sentinel is running on.
+    pub hostname: String,
+    /// One-line summary, shown as the message by Slack incoming webhooks.
+    pub text: String,
+}
+
+impl NotificationPayload {
+    /// Creates the payload for `event` on this host.
+    pub fn new(event: ProcessEvent) -> Self {
+        let hostname = sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string());
+        let text = format!("{} on {}", summary(&event), hostname);
+        Self {
+            event,
+            hostname,
+            text,
+        }
+    }
+}
+
+/// How failed deliveries are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per delivery, including the first.
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each further retry.
+    pub initial_backoff: Duration,
+    /// Timeout for each attempt.
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// An event and the sinks it is going to.
+struct Job {
+    sinks: Vec<NotificationSink>,
+    payload: NotificationPayload,
+}
+
+/// Queues lifecycle events and delivers them to notification sinks.
+///
+/// The delivery task is started on the first dispatch, so the dispatcher can
+/// be created outside a Tokio runtime.
+pub struct NotificationDispatcher {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    capacity: usize,
+    queue: OnceLock<mpsc::Sender<Job>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl NotificationDispatcher {
+    /// Creates a dispatcher with the default retry policy and queue size.
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
+            capacity: QUEUE_CAPACITY,
+            queue: OnceLock::new(),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Uses `retry` for deliveries.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Keeps at most `capacity` events waiting for delivery.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Number of events dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Queues `event` for every sink in `sinks` that accepts it.
+    ///
+    /// Returns immediately; the event is dropped (and counted) if the queue
+    /// is full or there is no Tokio runtime to deliver it on.
+    pub fn dispatch(&self, sinks: &[NotificationSink], event: ProcessEvent) {
+        let sinks: Vec<NotificationSink> = sinks
+            .iter()
+            .filter(|sink| sink.accepts(event.event, &event.process))
+            .cloned()
+            .collect();
+        if sinks.is_empty() {
+            return;
+        }
+
+        let process = event.process.clone();
+        let job = Job {
+            sinks,
+            payload: NotificationPayload::new(event),
+        };
+        let queued = match self.queue() {
+            Some(queue) => match queue.try_send(job) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_) | TrySendError::Closed(_)) => false,
+            },
+            None => false,
+        };
+        if !queued {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                "Dropped notification for process '{}' ({} dropped so far)",
+                process, dropped
+            );
+        }
+    }
+
+    /// Sends a sample event to `sink`, ignoring its filters, to check that
+    /// it is reachable.
+    ///
+    /// # Errors
+    /// Returns `NotificationFailed` if every attempt fails.
+    pub async fn send_test(&self, sink: &NotificationSink) -> Result<()> {
+        let event = ProcessEvent::new(ProcessEventKind::Test, "sentinel", None);
+        deliver(
+            &self.client,
+            sink,
+            &NotificationPayload::new(event),
+            self.retry,
+        )
+        .await
+    }
+
+    /// Gets the queue, starting the delivery task on first use.
+    fn queue(&self) -> Option<&mpsc::Sender<Job>> {
+        if let Some(queue) = self.queue.get() {
+            return Some(queue);
+        }
+        let runtime = tokio::runtime::Handle::try_current().ok()?;
+
+        Some(self.queue.get_or_init(|| {
+            let (tx, mut rx) = mpsc::channel::<Job>(self.capacity);
+            let client = self.client.clone();
+            let retry = self.retry;
+            runtime.spawn(async move {
+                while let Some(job) = rx.recv().await {
+                    for sink in &job.sinks {
+                        if let Err(e) = deliver(&client, sink, &job.payload, retry).await {
+                            warn!("{}", e);
+                        }
+                    }
+                }
+            });
+            tx
+        }))
+    }
+}
+
+impl Default for NotificationDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Posts `payload` to `sink`, retrying with exponential backoff.
+///
+/// Client errors other than 429 are not retried, since they won't succeed
+/// on a later attempt.
+async fn deliver(
+    client: &reqwest::Client,
+    sink: &NotificationSink,
+    payload: &NotificationPayload,
+    retry: RetryPolicy,
+) -> Result<()> {
+    let mut backoff = retry.initial_backoff;
+    let mut attempt = 1;
+
+    loop {
+        let reason = match post(client, sink, payload, retry.timeout).await {
+            Ok(status) if status.is_success() => return Ok(()),
+            Ok(status) => {
+                let reason = format!("HTTP {}", status);
+                if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    return Err(SentinelError::NotificationFailed {
+                        sink: sink.name.clone(),
+                        reason,
+                    });
+                }
+                reason
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if attempt >= retry.max_attempts {
+            return Err(SentinelError::NotificationFailed {
+                sink: sink.name.clone(),
+                reason: format!("{} (gave up after {} attempts)", reason, attempt),
+            });
+        }
+        debug!(
+            "Notification to '{}' failed ({}), retrying in {:?}",
+            sink.name, reason, backoff
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = backoff.saturating_mul(2);
+        attempt += 1;
+    }
+}
+
+/// Makes one delivery attempt.
+async fn post(
+    client: &reqwest::Client,
+    sink: &NotificationSink,
+    payload: &NotificationPayload,
+    timeout: Duration,
+) -> reqwest::Result<reqwest::StatusCode> {
+    match sink.kind {
+        NotificationSinkKind::Webhook => {
+            let mut request = client.post(&sink.url).timeout(timeout).json(payload);
+            for (name, value) in &sink.headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            Ok(request.send().await?.status())
+        }
+    }
+}
+
+/// Describes `event` in a sentence.
+fn summary(event: &ProcessEvent) -> String {
+    let process = &event.process;
+    match event.event {
+        ProcessEventKind::Started => format!("Process '{}' started", process),
+        ProcessEventKind::Stopped => format!("Process '{}' stopped", process),
+        ProcessEventKind::Crashed => match event.exit_code {
+            Some(code) => format!("Process '{}' crashed with exit code {}", process, code),
+            None => format!("Process '{}' crashed", process),
+        },
+        ProcessEventKind::Completed => format!("Process '{}' completed", process),
+        ProcessEventKind::Restarted => format!("Process '{}' was restarted", process),
+        ProcessEventKind::RestartLimitExceeded => {
+            format!("Process '{}' exceeded its restart limit", process)
+        }
+        ProcessEventKind::Test => "Test notification from Sentinel".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::{Bytes, Incoming};
+    use hyper::{Request, Response};
+    use hyper_util::rt::TokioIo;
+    use std::collections::{HashMap, VecDeque};
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+
+    /// A received request's headers and JSON body.
+    type Received = Arc<Mutex<Vec<(hyper::HeaderMap, serde_json::Value)>>>;
+
+    /// Starts an HTTP server that answers with `statuses` in turn (then 200).
+    async fn serve(statuses: &[u16]) -> (String, Received) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received: Received = Arc::default();
+        let statuses = Arc::new(Mutex::new(
+            statuses.iter().copied().collect::<VecDeque<_>>(),
+        ));
+
+        let requests = received.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let requests = requests.clone();
+                let statuses = statuses.clone();
+                let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+                    let requests = requests.clone();
+                    let statuses = statuses.clone();
+                    async move {
+                        let (parts, body) = request.into_parts();
+                        let body = body.collect().await.unwrap().to_bytes();
+                        let json = serde_json::from_slice(&body).unwrap();
+                        requests.lock().unwrap().push((parts.headers, json));
+
+                        let status = statuses.lock().unwrap().pop_front().unwrap_or(200);
+                        let response = Response::builder()
+                            .status(status)
+                            .body(Full::new(Bytes::new()))
+                            .unwrap();
+                        Ok::<_, Infallible>(response)
+                    }
+                });
+                tokio::spawn(
+                    hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service),
+                );
+            }
+        });
+
+        (url, received)
+    }
+
+    fn sink(name: &str, url: &str) -> NotificationSink {
+        NotificationSink {
+            name: name.to_string(),
+            kind: NotificationSinkKind::Webhook,
+            url: url.to_string(),
+            headers: HashMap::new(),
+            events: vec![],
+            processes: vec![],
+        }
+    }
+
+    fn fast_retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(10),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn payload() -> NotificationPayload {
+        NotificationPayload::new(ProcessEvent::new(
+            ProcessEventKind::Crashed,
+            "worker",
+            Some(137),
+        ))
+    }
+
+    async fn wait_for(received: &Received, count: usize) {
+        for _ in 0..200 {
+            if received.lock().unwrap().len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("expected {} requests", count);
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let (url, received) = serve(&[500, 503]).await;
+        let client = reqwest::Client::new();
+
+        deliver(&client, &sink("hook", &url), &payload(), fast_retry(4))
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        let body = &received[2].1;
+        assert_eq!(body["event"], "crashed");
+        assert_eq!(body["process"], "worker");
+        assert_eq!(body["exit_code"], 137);
+        assert!(body["timestamp"].is_string());
+        assert!(body["hostname"].is_string());
+        assert!(body["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Process 'worker' crashed with exit code 137 on "));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (url, received) = serve(&[500, 500, 500, 500]).await;
+        let client = reqwest::Client::new();
+
+        let result = deliver(&client, &sink("hook", &url), &payload(), fast_retry(3)).await;
+        assert!(matches!(
+            result,
+            Err(SentinelError::NotificationFailed { ref sink, .. }) if sink == "hook"
+        ));
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (url, received) = serve(&[404]).await;
+        let client = reqwest::Client::new();
+
+        let result = deliver(&client, &sink("hook", &url), &payload(), fast_retry(4)).await;
+        assert!(result.is_err());
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // Rate limiting is worth retrying
+        let (url, received) = serve(&[429]).await;
+        deliver(&client, &sink("hook", &url), &payload(), fast_retry(4))
+            .await
+            .unwrap();
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_applies_filters() {
+        let (url, received) = serve(&[]).await;
+        let mut crashes = sink("crashes", &url);
+        crashes.events = vec![ProcessEventKind::Crashed];
+        crashes
+            .headers
+            .insert("X-Sink".to_string(), "crashes".to_string());
+        let mut api = sink("api", &url);
+        api.processes = vec!["api".to_string()];
+        api.headers.insert("X-Sink".to_string(), "api".to_string());
+        let sinks = vec![crashes, api];
+
+        let dispatcher = NotificationDispatcher::new().with_retry_policy(fast_retry(1));
+        let events = [
+            (ProcessEventKind::Started, "worker"),
+            (ProcessEventKind::Crashed, "worker"),
+            (ProcessEventKind::Started, "api"),
+        ];
+        for (kind, process) in events {
+            dispatcher.dispatch(&sinks, ProcessEvent::new(kind, process, None));
+        }
+        wait_for(&received, 2).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut delivered: Vec<(String, String)> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(headers, body)| {
+                let sink = headers["x-sink"].to_str().unwrap().to_string();
+                (sink, format!("{}:{}", body["event"], body["process"]))
+            })
+            .collect();
+        delivered.sort();
+        assert_eq!(
+            delivered,
+            vec![
+                ("api".to_string(), r#""started":"api""#.to_string()),
+                ("crashes".to_string(), r#""crashed":"worker""#.to_string()),
+            ]
+        );
+        assert_eq!(dispatcher.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_drops_when_queue_full() {
+        let (url, received) = serve(&[]).await;
+        let sinks = vec![sink("hook", &url)];
+        let dispatcher = NotificationDispatcher::new()
+            .with_retry_policy(fast_retry(1))
+            .with_capacity(2);
+
+        // The delivery task can't run until this test yields
+        for _ in 0..5 {
+            dispatcher.dispatch(
+                &sinks,
+                ProcessEvent::new(ProcessEventKind::Crashed, "worker", Some(1)),
+            );
+        }
+        assert_eq!(dispatcher.dropped(), 3);
+
+        wait_for(&received, 2).await;
+    }
+
+    #[tokio::test]
+    async fn test_send_test_ignores_filters() {
+        let (url, received) = serve(&[]).await;
+        let mut hook = sink("hook", &url);
+        hook.events = vec![ProcessEventKind::Crashed];
+
+        NotificationDispatcher::new()
+            .send_test(&hook)
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received[0].1["event"], "test");
+    }
+
+    #[test]
+    fn test_dispatch_without_runtime_drops() {
+        let dispatcher = NotificationDispatcher::new();
+        let sinks = vec![sink("hook", "http://127.0.0.1:9/hook")];
+        dispatcher.dispatch(
+            &sinks,
+            ProcessEvent::new(ProcessEventKind::Started, "api", None),
+        );
+        assert_eq!(dispatcher.dropped(), 1);
+
+        // Events no sink accepts are not counted
+        dispatcher.dispatch(
+            &[],
+            ProcessEvent::new(ProcessEventKind::Started, "api", None),
+        );
+        assert_eq!(dispatcher.dropped(), 1);
+    }
+}
@@ -0,0 +1,163 @@
+//! Fixed ports declared by process configurations.
+//!
+//! A process declares a port through its `PORT` environment variable or a
+//! `--port`/`-p` argument. Checking these before starting catches two
+//! instances of the same service fighting over one port, which otherwise only
+//! shows up as the second one crashing with "address in use".
+
+use crate::error::{Result, SentinelError};
+use crate::models::ProcessConfig;
+use std::net::TcpListener;
+
+/// Ports `config` declares, sorted and without duplicates.
+pub fn declared_ports(config: &ProcessConfig) -> Vec<u16> {
+    let mut ports = Vec::new();
+
+    if let Some(port) = config.env.get("PORT").and_then(|port| parse_port(port)) {
+        ports.push(port);
+    }
+
+    // Arguments given inline in the command are split off when starting
+    let args: Vec<&str> = if config.args.is_empty() {
+        config.command.split_whitespace().skip(1).collect()
+    } else {
+        config.args.iter().map(String::as_str).collect()
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = match arg {
+            "--port" | "-p" => args.next(),
+            _ => arg.strip_prefix("--port="),
+        };
+        if let Some(port) = value.and_then(parse_port) {
+            ports.push(port);
+        }
+    }
+
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Checks that the ports `config` declares are neither declared by another
+/// process in `others` nor already in use.
+///
+/// # Errors
+/// Returns `PortConflict` naming the other process, or `PortInUse`.
+pub fn preflight(config: &ProcessConfig, others: &[ProcessConfig]) -> Result<()> {
+    for port in declared_ports(config) {
+        if let Some(other) = others
+            .iter()
+            .filter(|other| other.name != config.name)
+            .find(|other| declared_ports(other).contains(&port))
+        {
+            return Err(SentinelError::PortConflict {
+                port,
+                process: config.name.clone(),
+                other: other.name.clone(),
+            });
+        }
+
+        if !is_free(port) {
+            return Err(SentinelError::PortInUse {
+                port,
+                process: config.name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Whether `port` can be bound, on loopback and on all interfaces.
+pub fn is_free(port: u16) -> bool {
+    ["127.0.0.1", "0.0.0.0"]
+        .iter()
+        .all(|host| TcpListener::bind((*host, port)).is_ok())
+}
+
+/// A port that is free on loopback right now, chosen by the OS.
+///
+/// The port is released before returning, so another program could take
+/// it before the caller binds it.
+pub fn free_port() -> Result<u16> {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| SentinelError::Other(format!("No free port: {}", e)))
+}
+
+/// Parses a port, or the host port of a mapping such as `8080:80/tcp`.
+fn parse_port(value: &str) -> Option<u16> {
+    let value = value.split('/').next()?;
+    let parts: Vec<&str> = value.split(':').collect();
+    let host_port = match parts.len() {
+        1 => parts[0],
+        n => parts[n - 2],
+    };
+    host_port.parse().ok().filter(|port| *port != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(yaml: &str) -> ProcessConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_declared_ports() {
+        let config = process(
+            r#"
+name: api
+command: node server.js --port 3000
+env:
+  PORT: "3000"
+"#,
+        );
+        assert_eq!(declared_ports(&config), [3000]);
+
+        let config = process(
+            r#"
+name: db
+command: docker
+args: ["run", "-p", "127.0.0.1:5433:5432/tcp", "--port=6000", "postgres"]
+"#,
+        );
+        assert_eq!(declared_ports(&config), [5433, 6000]);
+
+        let config = process("name: worker\ncommand: node worker.js --port nope");
+        assert!(declared_ports(&config).is_empty());
+    }
+
+    #[test]
+    fn test_preflight_detects_conflicts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = listener.local_addr().unwrap().port();
+
+        let api = process(&format!(
+            "name: api\ncommand: node server.js --port {}",
+            taken
+        ));
+        let err = preflight(&api, &[]).unwrap_err();
+        assert!(matches!(err, SentinelError::PortInUse { port, .. } if port == taken));
+
+        drop(listener);
+        let api2 = process(&format!(
+            "name: api-2\ncommand: node\nenv:\n  PORT: \"{}\"",
+            taken
+        ));
+        let err = preflight(&api2, &[api.clone(), api2.clone()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Port {} of process 'api-2' is also used by process 'api'",
+                taken
+            )
+        );
+
+        // A process doesn't conflict with itself
+        assert!(preflight(&api, std::slice::from_ref(&api)).is_ok());
+    }
+}
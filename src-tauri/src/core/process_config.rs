@@ -10,6 +10,7 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::error::{Result as SentinelResult, SentinelError};
+use crate::models::EnvMode;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +22,10 @@ pub enum FrameworkType {
     Django,
     Express,
     Flask,
+    Rust,
+    Go,
+    Rails,
+    Laravel,
     Unknown,
 }
 
@@ -39,6 +44,15 @@ pub struct ProcessConfig {
     pub port: Option<u16>,
     pub auto_start: bool,
     pub health_check_url: Option<String>,
+    /// Scheduling priority, from -20 (highest) to 19 (lowest)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    /// CPU cores (0-based) the process may run on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Environment the process starts from, before `env_vars` is added
+    #[serde(default)]
+    pub env_mode: EnvMode,
     #[serde(default = "default_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(default = "default_datetime")]
@@ -59,6 +73,9 @@ pub struct FrameworkDetection {
     pub suggested_command: String,
     pub suggested_args: Vec<String>,
     pub suggested_port: Option<u16>,
+    /// Script names from package.json, if the project has one
+    #[serde(default)]
+    pub available_scripts: Vec<String>,
 }
 
 /// Detected project (for monorepo support)
@@ -66,6 +83,9 @@ pub struct FrameworkDetection {
 #[serde(rename_all = "camelCase")]
 pub struct DetectedProject {
     pub path: String,
+    /// Path relative to the scan root ("." for the root itself)
+    #[serde(default)]
+    pub relative_path: String,
     pub name: String,
     pub framework_type: FrameworkType,
     pub confidence: f32,
@@ -75,6 +95,19 @@ pub struct DetectedProject {
     pub package_manager: Option<String>,
     pub detected_files: Vec<String>,
     pub env_vars: HashMap<String, String>,
+    /// Script names from package.json, if the project has one
+    #[serde(default)]
+    pub available_scripts: Vec<String>,
+}
+
+/// Result of scanning a directory tree for projects
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectScanResult {
+    pub projects: Vec<DetectedProject>,
+    /// Whether the scan stopped early at the directory limit
+    pub truncated: bool,
+    pub directories_visited: usize,
 }
 
 /// Process template for quick setup
@@ -105,7 +138,7 @@ pub struct ProcessStatusInfo {
     pub last_health_check: Option<HealthCheckResult>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub enum ProcessStatus {
     Starting,
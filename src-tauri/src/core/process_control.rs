@@ -9,7 +9,7 @@ use tauri::AppHandle;
 use tokio::sync::Mutex;
 
 use crate::core::process_config::{ProcessConfig, ProcessStatus, ProcessStatusInfo};
-use crate::core::PtyProcessManager;
+use crate::core::{scheduling, PtyProcessConfig, PtyProcessManager};
 use crate::error::Result as SentinelResult;
 
 /// Tracks running processes from configurations
@@ -50,6 +50,14 @@ impl ProcessController {
             }
         }
 
+        // Reject bad scheduling values before anything is spawned
+        if let Some(nice) = config.nice {
+            scheduling::validate_priority(nice)?;
+        }
+        if let Some(cores) = &config.cpu_affinity {
+            scheduling::validate_affinity(cores)?;
+        }
+
         // Use config.name as process_id for PTY
         let process_id = config.name.clone();
 
@@ -59,19 +67,31 @@ impl ProcessController {
             .lock()
             .await
             .spawn_process(
-                process_id.clone(),
-                config.command.clone(),
-                config.args.clone(),
-                Some(config.working_dir.clone()),
-                if config.env_vars.is_empty() {
-                    None
-                } else {
-                    Some(config.env_vars.clone())
+                PtyProcessConfig {
+                    process_id: process_id.clone(),
+                    command: config.command.clone(),
+                    args: config.args.clone(),
+                    cwd: Some(config.working_dir.clone()),
+                    env: if config.env_vars.is_empty() {
+                        None
+                    } else {
+                        Some(config.env_vars.clone())
+                    },
+                    env_mode: config.env_mode,
                 },
                 app,
             )
             .await?;
 
+        // PTY spawns have no pre-exec hook, so apply these to the live process
+        if let Err(e) = scheduling::apply(pid, config.nice, config.cpu_affinity.as_deref()) {
+            tracing::warn!(
+                "Failed to apply scheduling to process '{}': {}",
+                config.name,
+                e
+            );
+        }
+
         // Track running process
         {
             let mut running = self.running.lock().await;
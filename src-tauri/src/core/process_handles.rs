@@ -0,0 +1,253 @@
+//! Open file descriptor and thread counts of running processes.
+//!
+//! Linux reads `/proc/<pid>/fd` and `/proc/<pid>/status`, macOS asks
+//! `proc_pidinfo`, and Windows counts the process's handles and walks a
+//! Toolhelp thread snapshot. A count that can't be read, usually because the
+//! process belongs to another user, is `None`.
+
+/// Open file descriptors (handles on Windows) and threads of a process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandleCounts {
+    pub open_fds: Option<u64>,
+    pub threads: Option<u64>,
+}
+
+/// Counts the open file descriptors and threads of `pid`.
+pub fn sample(pid: u32) -> HandleCounts {
+    HandleCounts {
+        open_fds: imp::open_fds(pid),
+        threads: imp::threads(pid),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::fs;
+
+    pub fn open_fds(pid: u32) -> Option<u64> {
+        let entries = fs::read_dir(format!("/proc/{}/fd", pid)).ok()?;
+        Some(entries.count() as u64)
+    }
+
+    pub fn threads(pid: u32) -> Option<u64> {
+        let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Threads:"))?
+            .trim()
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::mem;
+
+    pub fn open_fds(pid: u32) -> Option<u64> {
+        let pid = pid as libc::c_int;
+        let entry = mem::size_of::<libc::proc_fdinfo>();
+        // Without a buffer the kernel returns the size it needs, with room to spare
+        let size =
+            unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+        if size <= 0 {
+            return None;
+        }
+
+        let mut fds: Vec<libc::proc_fdinfo> = vec![unsafe { mem::zeroed() }; size as usize / entry];
+        let filled = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDLISTFDS,
+                0,
+                fds.as_mut_ptr().cast(),
+                (fds.len() * entry) as libc::c_int,
+            )
+        };
+        (filled > 0).then(|| filled as u64 / entry as u64)
+    }
+
+    pub fn threads(pid: u32) -> Option<u64> {
+        let mut info: libc::proc_taskinfo = unsafe { mem::zeroed() };
+        let size = mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+        let filled = unsafe {
+            libc::proc_pidinfo(
+                pid as libc::c_int,
+                libc::PROC_PIDTASKINFO,
+                0,
+                (&mut info as *mut libc::proc_taskinfo).cast(),
+                size,
+            )
+        };
+        (filled == size).then_some(info.pti_threadnum.max(0) as u64)
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::ffi::c_void;
+    use std::mem;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const TH32CS_SNAPTHREAD: u32 = 0x0004;
+    const INVALID_HANDLE_VALUE: *mut c_void = -1isize as *mut c_void;
+
+    /// `THREADENTRY32` from `<tlhelp32.h>`
+    #[repr(C)]
+    struct ThreadEntry {
+        size: u32,
+        usage: u32,
+        thread_id: u32,
+        owner_process_id: u32,
+        base_priority: i32,
+        delta_priority: i32,
+        flags: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+        fn GetProcessHandleCount(handle: *mut c_void, count: *mut u32) -> i32;
+        fn CreateToolhelp32Snapshot(flags: u32, pid: u32) -> *mut c_void;
+        fn Thread32First(snapshot: *mut c_void, entry: *mut ThreadEntry) -> i32;
+        fn Thread32Next(snapshot: *mut c_void, entry: *mut ThreadEntry) -> i32;
+    }
+
+    /// Handle closed on drop.
+    struct Handle(*mut c_void);
+
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    pub fn open_fds(pid: u32) -> Option<u64> {
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if handle.is_null() {
+            return None;
+        }
+        let handle = Handle(handle);
+
+        let mut count = 0u32;
+        (unsafe { GetProcessHandleCount(handle.0, &mut count) } != 0).then_some(count as u64)
+    }
+
+    pub fn threads(pid: u32) -> Option<u64> {
+        // The snapshot holds the threads of every process
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            return None;
+        }
+        let snapshot = Handle(snapshot);
+
+        let mut entry: ThreadEntry = unsafe { mem::zeroed() };
+        entry.size = mem::size_of::<ThreadEntry>() as u32;
+        if unsafe { Thread32First(snapshot.0, &mut entry) } == 0 {
+            return None;
+        }
+        let mut count = 0;
+        loop {
+            if entry.owner_process_id == pid {
+                count += 1;
+            }
+            if unsafe { Thread32Next(snapshot.0, &mut entry) } == 0 {
+                break;
+            }
+        }
+        // A process that exited has no threads left in the snapshot
+        (count > 0).then_some(count)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod imp {
+    pub fn open_fds(_pid: u32) -> Option<u64> {
+        None
+    }
+
+    pub fn threads(_pid: u32) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn test_sample_current_process() {
+        let counts = sample(std::process::id());
+        // At least stdin, stdout and stderr
+        assert!(counts.open_fds.is_some_and(|fds| fds >= 3));
+        assert!(counts.threads.is_some_and(|threads| threads >= 1));
+    }
+
+    #[test]
+    fn test_sample_missing_process() {
+        assert_eq!(sample(u32::MAX), HandleCounts::default());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_open_fds_rise_with_open_files() {
+        use std::process::{Command, Stdio};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut redirects = String::new();
+        for fd in 3..8 {
+            let path = dir.path().join(format!("file{}", fd));
+            std::fs::write(&path, "x").unwrap();
+            redirects.push_str(&format!("exec {}<'{}'; ", fd, path.display()));
+        }
+
+        let spawn = |script: String| {
+            Command::new("sh")
+                .arg("-c")
+                .arg(script)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap()
+        };
+        let mut plain = spawn("sleep 30".to_string());
+        let mut opened = spawn(format!("{}sleep 30", redirects));
+
+        // Wait for the shell to run its redirections
+        let count = |pid| sample(pid).open_fds.unwrap_or(0);
+        let baseline = count(plain.id());
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while count(opened.id()) < baseline + 5 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let with_files = count(opened.id());
+
+        plain.kill().ok();
+        opened.kill().ok();
+        plain.wait().ok();
+        opened.wait().ok();
+
+        assert!(baseline > 0);
+        assert!(
+            with_files >= baseline + 5,
+            "{} open fds, {} without the files",
+            with_files,
+            baseline
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_threads_include_spawned_thread() {
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let worker = std::thread::spawn(move || rx.recv().ok());
+
+        let threads = sample(std::process::id()).threads;
+        drop(tx);
+        worker.join().unwrap();
+        assert!(threads.is_some_and(|threads| threads >= 2));
+    }
+}
@@ -1,41 +1,70 @@
 //! Process lifecycle management.
 //!
 //! This module handles spawning, monitoring, and managing child processes.
-use crate::core::log_buffer::{LogBuffer, LogLine, LogStream};
+use crate::core::container_link::{self, ContainerRef};
+use crate::core::env_watcher::WatchedEnvFile;
+use crate::core::log_buffer::{LogBuffer, LogLine, LogMemoryUsage, LogStream};
+use crate::core::log_decoder::LineDecoder;
+use crate::core::log_file::{LogFiles, LogWriter};
+use crate::core::log_search::{LogQuery, ProcessLogMatches};
+use crate::core::log_timestamp::MergedLogLine;
+use crate::core::metrics_buffer::{MetricsBuffer, TimedMetric};
+use crate::core::process_handles::{self, HandleCounts};
+use crate::core::readiness::{self, LogWatch, Readiness};
+use crate::core::restart_policy::{self, PolicyTrigger};
+use crate::core::{
+    executable, health_check, launch, ports, process_tree, scheduling, validation, ConfigManager,
+    EventBus, EventRecorder, LiveEvent, LogPublisher, NotificationDispatcher, ProcessIndex,
+    Scheduler,
+};
 use crate::error::{Result, SentinelError};
-use crate::models::{ProcessConfig, ProcessInfo, ProcessState};
-use chrono::Utc;
-use std::collections::HashMap;
-use std::process::Stdio;
-use std::sync::Arc;
+use crate::features::port_discovery::{self, PortScanner};
+use crate::models::{
+    ContainerUsage, EventActor, EventType, GlobalSettings, HealthCheck, HealthCheckKind,
+    JournalEvent, LogFormat, OrphanInfo, ProcessConfig, ProcessEvent, ProcessEventKind,
+    ProcessHistory, ProcessInfo, ProcessState, ProcessTreeNode, ReadinessCheck, RestartReason,
+    StdinMode, UsageSample, UsageSource, PROCESS_HISTORY_LEN,
+};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use futures_util::future::join_all;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LockResult, RwLock};
+use std::time::Instant;
 use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{mpsc, watch, Mutex, OwnedMutexGuard};
+use tokio::task::JoinHandle;
 use tokio::time::{sleep, Duration};
 use tracing::{debug, error, info, warn};
 
 /// Manages the lifecycle of multiple processes.
 ///
+/// Every process has its own lock, so operations on different processes run
+/// concurrently. It is only held briefly: a stop waits for the process to
+/// exit without it, so even a slow stop doesn't hold up listing or checking
+/// the processes. The map of processes is only locked to look up, add or
+/// remove a handle.
+///
 /// # Examples
 /// ```no_run
 /// use sentinel::core::ProcessManager;
 /// use sentinel::models::ProcessConfig;
-/// use std::collections::HashMap;
 ///
 /// # tokio_test::block_on(async {
-/// let mut manager = ProcessManager::new();
+/// let manager = ProcessManager::new();
 /// let config = ProcessConfig {
 ///     name: "test".to_string(),
 ///     command: "echo".to_string(),
 ///     args: vec!["hello".to_string()],
-///     cwd: None,
-///     env: HashMap::new(),
-///     auto_restart: false,
+///     auto_restart: Some(false),
 ///     restart_limit: 0,
-///     restart_delay: 1000,
-///     depends_on: vec![],
-///     health_check: None,
+///     restart_delay: Some(1000),
+///     ..Default::default()
 /// };
 ///
 /// let info = manager.start(config).await?;
@@ -45,7 +74,26 @@ use tracing::{debug, error, info, warn};
 /// ```
 pub struct ProcessManager {
     /// Map of process name to process handle and info.
-    processes: HashMap<String, ProcessHandle>,
+    processes: RwLock<HashMap<String, Arc<Mutex<ProcessHandle>>>>,
+    /// Held while a process is added or removed, so two starts of the same
+    /// name can't both spawn it.
+    membership: Mutex<()>,
+    /// Defaults for processes that don't override them.
+    settings: RwLock<GlobalSettings>,
+    /// Process tables for resource usage and parent links.
+    resources: std::sync::Mutex<ResourceTable>,
+    /// Sends lifecycle events to `settings.notificationSinks`.
+    notifier: NotificationDispatcher,
+    /// Records lifecycle events to the event journal.
+    recorder: EventRecorder,
+    /// Writes process output to log files with `settings.persistLogs`.
+    log_writer: RwLock<LogWriter>,
+    /// Crashed processes restarted by `check_health`.
+    restarts_total: AtomicU64,
+    /// Next fire times of scheduled processes, for `check_schedules`.
+    scheduler: std::sync::Mutex<Scheduler>,
+    /// Lifecycle events and log lines for live subscribers.
+    events: EventBus,
 }
 
 /// Handle for a running process.
@@ -56,12 +104,32 @@ struct ProcessHandle {
     child: Option<Child>,
     /// Configuration used to spawn the process.
     config: ProcessConfig,
-    /// Log buffer (last 10,000 lines). Thread-safe with Arc<Mutex>.
+    /// Log buffer (last `logBufferLines` lines). Thread-safe with Arc<Mutex>.
     log_buffer: Arc<Mutex<LogBuffer>>,
+    /// Queue of input for the child's stdin (when `stdinMode` is piped).
+    stdin: Option<mpsc::UnboundedSender<Vec<u8>>>,
+    /// Result of the readiness probe while running (when there is one).
+    readiness: Option<watch::Receiver<Readiness>>,
+    /// Resolved working directory, for command health checks.
+    cwd: Option<PathBuf>,
+    /// When the next health check is due (when there is one).
+    next_health_check: Option<std::time::Instant>,
     /// Number of restarts performed.
     restart_count: u32,
     /// Last restart timestamp (for exponential backoff).
     last_restart: Option<std::time::Instant>,
+    /// Recent resource usage, for sparklines.
+    history: UsageHistory,
+    /// Latest usage of the Docker containers the process runs.
+    container_usage: Option<ContainerUsage>,
+    /// Descendants at the latest resource update, by PID with their start
+    /// times, to find the ones left running once the process exits.
+    children: HashMap<u32, u64>,
+    /// Processes left running when it last crashed.
+    orphans: Vec<OrphanInfo>,
+    /// Tasks reading stdout and stderr, awaited for the output of a process
+    /// that exits while starting.
+    log_readers: Vec<JoinHandle<()>>,
 }
 
 impl ProcessHandle {
@@ -72,22 +140,181 @@ impl ProcessHandle {
             child: Some(child),
             config,
             log_buffer: Arc::new(Mutex::new(LogBuffer::new())),
+            stdin: None,
+            readiness: None,
+            cwd: None,
+            next_health_check: None,
             restart_count: 0,
             last_restart: None,
+            history: UsageHistory::new(),
+            container_usage: None,
+            children: HashMap::new(),
+            orphans: Vec::new(),
+            log_readers: Vec::new(),
         }
     }
+
+    /// Copies the readiness probe's latest result into `info`.
+    fn sync_readiness(&mut self) {
+        apply_readiness(&mut self.info, self.readiness.as_ref());
+    }
 }
 
 impl ProcessManager {
     /// Creates a new ProcessManager.
     pub fn new() -> Self {
+        Self::with_settings(GlobalSettings::default())
+    }
+
+    /// Creates a new ProcessManager using the given global settings.
+    pub fn with_settings(settings: GlobalSettings) -> Self {
         Self {
-            processes: HashMap::new(),
+            processes: RwLock::new(HashMap::new()),
+            membership: Mutex::new(()),
+            log_writer: RwLock::new(LogWriter::from_settings(&settings)),
+            settings: RwLock::new(settings),
+            resources: std::sync::Mutex::new(ResourceTable::new()),
+            notifier: NotificationDispatcher::new(),
+            recorder: EventRecorder::disabled(),
+            restarts_total: AtomicU64::new(0),
+            scheduler: std::sync::Mutex::new(Scheduler::new()),
+            events: EventBus::new(),
+        }
+    }
+
+    /// Where lifecycle events and log lines are published as they happen.
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Returns the global settings in effect.
+    pub fn settings(&self) -> GlobalSettings {
+        recover(self.settings.read(), "settings").clone()
+    }
+
+    /// Where process output is written to log files.
+    fn log_writer(&self) -> LogWriter {
+        recover(self.log_writer.read(), "log writer").clone()
+    }
+
+    /// Records lifecycle events to `recorder` from now on.
+    pub fn set_event_recorder(&mut self, recorder: EventRecorder) {
+        self.recorder = recorder;
+    }
+
+    /// Records a lifecycle event and sends it to the notification sinks that
+    /// accept it.
+    fn notify(
+        &self,
+        kind: ProcessEventKind,
+        name: &str,
+        exit_code: Option<i32>,
+        actor: EventActor,
+    ) {
+        let mut event = JournalEvent::new(kind.into(), name, actor);
+        if let Some(exit_code) = exit_code {
+            event = event.with_detail("exit_code", exit_code);
+        }
+        self.recorder.record(event);
+        self.dispatch(ProcessEvent::new(kind, name, exit_code));
+    }
+
+    /// Sends `event` to the notification sinks that accept it and to live
+    /// subscribers.
+    fn dispatch(&self, event: ProcessEvent) {
+        self.events.publish(LiveEvent::Process(event.clone()));
+        let settings = recover(self.settings.read(), "settings");
+        self.notifier.dispatch(&settings.notification_sinks, event);
+    }
+
+    /// The handle of a managed process.
+    fn handle(&self, name: &str) -> Option<Arc<Mutex<ProcessHandle>>> {
+        recover(self.processes.read(), "process map")
+            .get(name)
+            .cloned()
+    }
+
+    /// The handle of a managed process, or `ProcessNotFound`.
+    fn find(&self, name: &str) -> Result<Arc<Mutex<ProcessHandle>>> {
+        self.handle(name)
+            .ok_or_else(|| SentinelError::ProcessNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// The handles of all managed processes, ordered by name.
+    ///
+    /// The map isn't locked while the handles are used, so a process added
+    /// meanwhile is left out and a removed one is still visited.
+    fn handles(&self) -> Vec<(String, Arc<Mutex<ProcessHandle>>)> {
+        let mut handles: Vec<_> = recover(self.processes.read(), "process map")
+            .iter()
+            .map(|(name, handle)| (name.clone(), handle.clone()))
+            .collect();
+        handles.sort_by(|a, b| a.0.cmp(&b.0));
+        handles
+    }
+
+    /// Replaces the global settings.
+    ///
+    /// Restart policy changes apply on the next crash. Log buffers of
+    /// processes without their own `logBufferLines` or `logBufferBytes` are
+    /// resized right away. Log file changes apply to processes started
+    /// afterwards.
+    pub async fn apply_settings(&self, settings: GlobalSettings) {
+        *recover(self.settings.write(), "settings") = settings.clone();
+        {
+            let mut log_writer = recover(self.log_writer.write(), "log writer");
+            if log_writer.files() != LogFiles::from_settings(&settings).as_ref() {
+                *log_writer = LogWriter::from_settings(&settings);
+            }
+        }
+
+        for (_, handle) in self.handles() {
+            let handle = handle.lock().await;
+            handle.log_buffer.lock().await.set_limits(
+                handle.config.effective_log_buffer_lines(&settings),
+                handle.config.effective_log_buffer_bytes(&settings),
+            );
+        }
+    }
+
+    /// Takes `logBufferLines` and `logBufferBytes` from reloaded process
+    /// configs and resizes the log buffers of those processes, dropping
+    /// their oldest lines if the buffers shrink.
+    pub async fn apply_log_limits(&self, configs: &[ProcessConfig]) {
+        let settings = self.settings();
+        for (name, handle) in self.handles() {
+            let Some(config) = configs.iter().find(|config| config.name == name) else {
+                continue;
+            };
+            let mut handle = handle.lock().await;
+            handle.config.log_buffer_lines = config.log_buffer_lines;
+            handle.config.log_buffer_bytes = config.log_buffer_bytes;
+            handle.log_buffer.lock().await.set_limits(
+                handle.config.effective_log_buffer_lines(&settings),
+                handle.config.effective_log_buffer_bytes(&settings),
+            );
+        }
+    }
+
+    /// Memory held by the log buffer of every process.
+    pub async fn log_memory_usage(&self) -> Vec<LogMemoryUsage> {
+        let mut usage = Vec::new();
+        for (name, handle) in self.handles() {
+            let buffer = handle.lock().await.log_buffer.clone();
+            usage.push(buffer.lock().await.memory_usage(name));
         }
+        usage
     }
 
     /// Starts a process from configuration.
     ///
+    /// Waits up to `settings.startGraceMs` before returning, so a command
+    /// that fails straight away (a missing module, a bad flag) is reported
+    /// here with its output instead of by a later health check. A process
+    /// that finishes successfully within the wait is returned `Stopped`.
+    ///
     /// # Arguments
     /// * `config` - Process configuration
     ///
@@ -98,27 +325,26 @@ impl ProcessManager {
     /// # Errors
     /// Returns error if:
     /// - Process with same name is already running
+    /// - Name, command or arguments fail validation (see [`crate::core::validation`])
     /// - Failed to spawn the process
-    /// - Working directory doesn't exist
+    /// - Working directory doesn't exist or is outside `settings.allowedRoots`
+    /// - The process exited with an error within the wait (`StartFailedEarly`);
+    ///   it is left `Crashed`
     ///
     /// # Examples
     /// ```no_run
     /// # use sentinel::core::ProcessManager;
     /// # use sentinel::models::ProcessConfig;
-    /// # use std::collections::HashMap;
     /// # tokio_test::block_on(async {
-    /// let mut manager = ProcessManager::new();
+    /// let manager = ProcessManager::new();
     /// let config = ProcessConfig {
     ///     name: "api".to_string(),
     ///     command: "npm".to_string(),
     ///     args: vec!["start".to_string()],
     ///     cwd: Some("./backend".into()),
-    ///     env: HashMap::new(),
-    ///     auto_restart: true,
-    ///     restart_limit: 5,
-    ///     restart_delay: 1000,
-    ///     depends_on: vec![],
-    ///     health_check: None,
+    ///     auto_restart: Some(true),
+    ///     restart_delay: Some(1000),
+    ///     ..Default::default()
     /// };
     ///
     /// let info = manager.start(config).await?;
@@ -126,110 +352,129 @@ impl ProcessManager {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// # });
     /// ```
-    pub async fn start(&mut self, config: ProcessConfig) -> Result<ProcessInfo> {
-        let name = config.name.clone();
+    pub async fn start(&self, config: ProcessConfig) -> Result<ProcessInfo> {
+        let info = self.start_as(config, EventActor::User).await?;
+        self.check_early_exit(info).await
+    }
 
-        // Check if process already exists
-        if let Some(handle) = self.processes.get(&name) {
-            if handle.info.is_running() {
-                return Err(SentinelError::ProcessAlreadyRunning {
-                    name: name.clone(),
-                    pid: handle.info.pid.unwrap_or(0),
-                });
-            }
+    /// Watches a just-started process for `settings.startGraceMs`.
+    ///
+    /// # Returns
+    /// * `Ok(ProcessInfo)` - Still running, or exited with a success exit code
+    ///   (now `Completed`)
+    /// * `Err(StartFailedEarly)` - Exited with an error; now `Crashed`
+    async fn check_early_exit(&self, info: ProcessInfo) -> Result<ProcessInfo> {
+        let grace = Duration::from_millis(self.settings().start_grace_ms);
+        let (Some(pid), Some(handle)) = (info.pid, self.handle(&info.name)) else {
+            return Ok(info);
+        };
+        if grace.is_zero() {
+            return Ok(info);
         }
-
-        info!("Starting process: {}", name);
-
-        let mut cmd = if config.args.is_empty() {
-            let parts: Vec<&str> = config.command.split_whitespace().collect();
-            if parts.is_empty() {
-                return Err(SentinelError::InvalidConfig {
-                    reason: format!("Empty command for process '{}'", name),
-                });
+        let deadline = tokio::time::Instant::now() + grace;
+
+        let (info, log_buffer, log_readers) = loop {
+            let mut guard = handle.lock().await;
+            let handle = &mut *guard;
+            // Stopped, restarted or reaped by a health check meanwhile
+            if handle.info.pid != Some(pid) {
+                return Ok(handle.info.clone());
             }
-            let (program, args) = (parts[0], &parts[1..]);
-            let mut cmd = Command::new(program);
-            cmd.args(args);
-            cmd
-        } else {
-            let mut cmd = Command::new(&config.command);
-            cmd.args(&config.args);
-            cmd
-        };
+            let Some(Ok(Some(status))) = handle.child.as_mut().map(Child::try_wait) else {
+                if tokio::time::Instant::now() >= deadline {
+                    return Ok(handle.info.clone());
+                }
+                drop(guard);
+                sleep(EARLY_EXIT_POLL).await;
+                continue;
+            };
 
-        // Set working directory
-        if let Some(cwd) = &config.cwd {
-            cmd.current_dir(cwd);
-        }
+            let exit_code = status.code().unwrap_or(-1);
+            handle.info.state = if handle.config.is_success_exit(exit_code) {
+                ProcessState::Completed { exit_code }
+            } else {
+                ProcessState::Crashed { exit_code }
+            };
+            handle.info.exit_code = Some(exit_code);
+            handle.info.pid = None;
+            handle.info.pgid = None;
+            handle.info.stopped_at = Some(Utc::now());
+            handle.child = None;
+            handle.stdin = None;
+            handle.readiness = None;
+            handle.info.ready = false;
+            break (
+                handle.info.clone(),
+                handle.log_buffer.clone(),
+                std::mem::take(&mut handle.log_readers),
+            );
+        };
 
-        // Set environment variables
-        for (key, value) in &config.env {
-            cmd.env(key, value);
+        let exit_code = info.exit_code.unwrap_or(-1);
+        if info.is_completed() {
+            info!("Process '{}' finished while starting", info.name);
+            self.notify(
+                ProcessEventKind::Completed,
+                &info.name,
+                Some(exit_code),
+                EventActor::Supervisor,
+            );
+            return Ok(info);
         }
 
-        // Configure stdio
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-        cmd.stdin(Stdio::null());
-
-        // Spawn process
-        let mut child = cmd.spawn().map_err(|source| SentinelError::SpawnFailed {
-            name: name.clone(),
-            source,
-        })?;
-
-        let pid = child.id().unwrap_or(0);
-
-        debug!("Process '{}' spawned with PID {}", name, pid);
-
-        // Create log buffer (shared between log readers)
-        let log_buffer = Arc::new(Mutex::new(LogBuffer::new()));
+        warn!(
+            "Process '{}' exited with code {} while starting",
+            info.name, exit_code
+        );
+        self.notify(
+            ProcessEventKind::Crashed,
+            &info.name,
+            Some(exit_code),
+            EventActor::Supervisor,
+        );
+        // A child the process left behind can hold the pipes open
+        let _ = tokio::time::timeout(EARLY_EXIT_OUTPUT_WAIT, join_all(log_readers)).await;
+        let lines = log_buffer.lock().await.get_all();
+        Err(SentinelError::StartFailedEarly {
+            name: info.name,
+            exit_code,
+            stderr_excerpt: output_excerpt(&lines),
+        })
+    }
 
-        // Spawn log reader tasks for stdout and stderr
-        if let Some(stdout) = child.stdout.take() {
-            let buffer = log_buffer.clone();
-            let process_name = name.clone();
-            tokio::spawn(async move {
-                read_stream(stdout, buffer, LogStream::Stdout, &process_name).await;
-            });
-        }
+    /// Starts a process on behalf of `actor`.
+    async fn start_as(&self, config: ProcessConfig, actor: EventActor) -> Result<ProcessInfo> {
+        let name = config.name.clone();
+        let _membership = self.membership.lock().await;
 
-        if let Some(stderr) = child.stderr.take() {
-            let buffer = log_buffer.clone();
-            let process_name = name.clone();
-            tokio::spawn(async move {
-                read_stream(stderr, buffer, LogStream::Stderr, &process_name).await;
-            });
+        // Check if process already exists
+        if let Some(handle) = self.handle(&name) {
+            let handle = handle.lock().await;
+            if handle.info.is_running() {
+                return Err(SentinelError::ProcessAlreadyRunning {
+                    name: name.clone(),
+                    pid: handle.info.pid.unwrap_or(0),
+                });
+            }
         }
 
-        // Create process info
-        let info = ProcessInfo {
-            name: name.clone(),
-            state: ProcessState::Running,
-            pid: Some(pid),
-            command: config.command.clone(),
-            cwd: config.cwd.as_ref().map(|p| p.display().to_string()),
-            cpu_usage: 0.0,
-            memory_usage: 0,
-            restart_count: 0,
-            started_at: Some(Utc::now()),
-            stopped_at: None,
-        };
+        // Configs can come straight from the frontend, so check them again here
+        let settings = self.settings();
+        validation::validate_process(&config, &settings)?;
 
-        // Store process handle
-        let handle = ProcessHandle {
-            info: info.clone(),
-            child: Some(child),
-            config,
-            log_buffer,
-            restart_count: 0,
-            last_restart: None,
-        };
+        info!("Starting process: {}", name);
+        let log_buffer = Arc::new(Mutex::new(LogBuffer::with_limits(
+            config.effective_log_buffer_lines(&settings),
+            config.effective_log_buffer_bytes(&settings),
+        )));
+        let logs = self.events.logs(&name);
+        let handle = spawn_process(config, &settings, log_buffer, logs, self.log_writer())?;
+        let info = handle.info.clone();
 
-        self.processes.insert(name, handle);
+        recover(self.processes.write(), "process map").insert(name, Arc::new(Mutex::new(handle)));
 
         info!("Process '{}' started successfully", info.name);
+        self.notify(ProcessEventKind::Started, &info.name, None, actor);
 
         Ok(info)
     }
@@ -249,35 +494,60 @@ impl ProcessManager {
     /// ```no_run
     /// # use sentinel::core::ProcessManager;
     /// # tokio_test::block_on(async {
-    /// # let mut manager = ProcessManager::new();
+    /// # let manager = ProcessManager::new();
     /// manager.stop("api").await?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// # });
     /// ```
-    pub async fn stop(&mut self, name: &str) -> Result<()> {
-        let handle =
-            self.processes
-                .get_mut(name)
-                .ok_or_else(|| SentinelError::ProcessNotFound {
-                    name: name.to_string(),
-                })?;
+    pub async fn stop(&self, name: &str) -> Result<()> {
+        self.stop_as(name, EventActor::User).await
+    }
 
-        if !handle.info.is_running() {
+    /// Stops a running process at once, without a graceful shutdown.
+    ///
+    /// Sends SIGKILL (Unix) or terminates (Windows) instead of SIGTERM.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Process stopped successfully
+    /// * `Err(SentinelError)` - Process not found
+    pub async fn kill(&self, name: &str) -> Result<()> {
+        self.stop_with(name, EventActor::User, true).await
+    }
+
+    /// Stops a process on behalf of `actor`.
+    async fn stop_as(&self, name: &str, actor: EventActor) -> Result<()> {
+        self.stop_with(name, actor, false).await
+    }
+
+    /// Stops a process on behalf of `actor`, killing it at once with `force`.
+    ///
+    /// Waits up to [`STOP_TIMEOUT`] for it to exit, without holding its
+    /// lock, so it can be listed and checked meanwhile as `Stopping`.
+    async fn stop_with(&self, name: &str, actor: EventActor, force: bool) -> Result<()> {
+        let handle = self.find(name)?;
+        let Some((child, info)) = begin_stop(&handle).await else {
             return Ok(());
-        }
+        };
 
         info!("Stopping process: {}", name);
-        handle.info.state = ProcessState::Stopping;
+        let mut status = None;
+        if let Some(mut child) = child {
+            if force {
+                let _ = child.kill().await;
+            }
 
-        if let Some(mut child) = handle.child.take() {
             // Try to kill the process
             #[cfg(unix)]
-            {
+            if !force {
                 // Send SIGTERM for graceful shutdown
                 if let Some(pid) = child.id() {
                     unsafe {
                         libc::kill(pid as i32, libc::SIGTERM);
                     }
+                    // A stopped process only handles SIGTERM once continued
+                    if info.is_paused() {
+                        let _ = self.suspend_tree(name, pid, info.pgid, false);
+                    }
                 }
             }
 
@@ -287,10 +557,10 @@ impl ProcessManager {
             }
 
             // Wait for process to exit (with timeout)
-            let timeout = Duration::from_secs(10);
-            match tokio::time::timeout(timeout, child.wait()).await {
-                Ok(Ok(status)) => {
-                    debug!("Process '{}' exited with status: {:?}", name, status);
+            match tokio::time::timeout(STOP_TIMEOUT, child.wait()).await {
+                Ok(Ok(exit_status)) => {
+                    debug!("Process '{}' exited with status: {:?}", name, exit_status);
+                    status = Some(exit_status);
                 }
                 Ok(Err(e)) => {
                     warn!("Error waiting for process '{}': {}", name, e);
@@ -305,16 +575,17 @@ impl ProcessManager {
             }
         }
 
-        handle.info.state = ProcessState::Stopped;
-        handle.info.pid = None;
-        handle.info.stopped_at = Some(Utc::now());
+        finish_stop(&handle, status).await;
+        self.notify(ProcessEventKind::Stopped, name, None, actor);
 
         Ok(())
     }
 
     /// Restarts a process.
     ///
-    /// Stops the process if running, then starts it again.
+    /// Stops the process if running, then starts it again. A running process
+    /// with `warmRestart` is replaced without going down, see
+    /// [`Self::warm_restart`].
     ///
     /// # Arguments
     /// * `name` - Name of the process to restart
@@ -323,32 +594,216 @@ impl ProcessManager {
     /// ```no_run
     /// # use sentinel::core::ProcessManager;
     /// # tokio_test::block_on(async {
-    /// # let mut manager = ProcessManager::new();
+    /// # let manager = ProcessManager::new();
     /// manager.restart("api").await?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// # });
     /// ```
-    pub async fn restart(&mut self, name: &str) -> Result<ProcessInfo> {
-        info!("Restarting process: {}", name);
+    pub async fn restart(&self, name: &str) -> Result<ProcessInfo> {
+        self.restart_with(name, RestartReason::UserRequested).await
+    }
+
+    /// Restarts a process for `reason`, like [`Self::restart`].
+    ///
+    /// The reason and the time are kept in the process's `last_restart_reason`
+    /// and `last_restart_at`, and recorded with a `ProcessRestarted` event.
+    pub async fn restart_with(&self, name: &str, reason: RestartReason) -> Result<ProcessInfo> {
+        info!("Restarting process '{}': {}", name, reason);
 
         // Get config before stopping
-        let config = self
-            .processes
-            .get(name)
-            .ok_or_else(|| SentinelError::ProcessNotFound {
-                name: name.to_string(),
-            })?
-            .config
-            .clone();
+        let (config, running) = {
+            let handle = self.find(name)?;
+            let handle = handle.lock().await;
+            (handle.config.clone(), handle.info.is_running())
+        };
+        if config.warm_restart && running {
+            return self.warm_restart(name, reason).await;
+        }
+        self.cold_restart(name, config, reason).await
+    }
 
-        // Stop if running
-        let _ = self.stop(name).await;
+    /// Stops `name` if running and starts it again with `config` after its
+    /// restart delay.
+    async fn cold_restart(
+        &self,
+        name: &str,
+        config: ProcessConfig,
+        reason: RestartReason,
+    ) -> Result<ProcessInfo> {
+        let actor = reason.actor();
+        let _ = self.stop_as(name, actor).await;
 
         // Wait a bit before restarting
-        sleep(Duration::from_millis(config.restart_delay)).await;
+        sleep(Duration::from_millis(
+            config.effective_restart_delay(&self.settings()),
+        ))
+        .await;
 
         // Start again
-        self.start(config).await
+        let info = self.start_as(config, actor).await?;
+        let info = self.record_restart(name, reason).await.unwrap_or(info);
+        self.check_early_exit(info).await
+    }
+
+    /// Keeps `reason` as why `name` was last restarted and records it in the
+    /// journal.
+    ///
+    /// # Returns
+    /// The process's updated info, or `None` if it has been removed meanwhile.
+    async fn record_restart(&self, name: &str, reason: RestartReason) -> Option<ProcessInfo> {
+        let mut event = JournalEvent::new(EventType::ProcessRestarted, name, reason.actor())
+            .with_detail("reason", reason.kind());
+        event = match &reason {
+            RestartReason::CrashAutoRestart { exit_code } => {
+                event.with_detail("exit_code", exit_code)
+            }
+            RestartReason::FileChanged { path } => event.with_detail("path", path),
+            RestartReason::Policy { name } => event.with_detail("policy", name),
+            RestartReason::UserRequested | RestartReason::ConfigReload => event,
+        };
+        self.recorder.record(event);
+
+        let handle = self.handle(name)?;
+        let mut handle = handle.lock().await;
+        handle.info.last_restart_reason = Some(reason);
+        handle.info.last_restart_at = Some(Utc::now());
+        Some(handle.info.clone())
+    }
+
+    /// Replaces a running process with a new instance of it, stopping the
+    /// old one only once the new one is ready.
+    ///
+    /// The new instance runs under a temporary name outside the process map
+    /// until it takes over, writing to the same log buffer and keeping the
+    /// restart count. It gets the same `PORT`, which it must bind with
+    /// `SO_REUSEPORT` to run next to the old instance; if it exits instead,
+    /// or isn't listening on the port once ready, it is stopped and the
+    /// process is restarted in place. With `warmRestartFreePort` it gets a
+    /// free port instead and keeps it: the process's config, readiness probe
+    /// and health check are moved to the new port.
+    ///
+    /// # Errors
+    /// Returns `ReadinessTimeout` if the new instance isn't ready within its
+    /// probe's `maxWaitMs`, or `Other` if it exits first. The new instance is
+    /// killed, the old one is left running and a `WarmRestartFailed` event
+    /// is recorded.
+    async fn warm_restart(&self, name: &str, reason: RestartReason) -> Result<ProcessInfo> {
+        let actor = reason.actor();
+        let old = self.find(name)?;
+        let (config, log_buffer, restart_count) = {
+            let handle = old.lock().await;
+            (
+                handle.config.clone(),
+                handle.log_buffer.clone(),
+                handle.restart_count,
+            )
+        };
+        let settings = self.settings();
+        validation::validate_process(&config, &settings)?;
+
+        // The config the process runs with from now on
+        let mut next = config.clone();
+        let port = match config.warm_restart_free_port {
+            true => {
+                let port = ports::free_port()?;
+                move_port(&mut next, port);
+                Some(port)
+            }
+            false => None,
+        };
+        // Not a valid process name, so it can't clash with one
+        let standby_name = format!("{}~next", name);
+        info!("Warm restarting '{}' as '{}'", name, standby_name);
+        let mut standby_config = next.clone();
+        standby_config.name = standby_name;
+        let mut standby = spawn_process(
+            standby_config,
+            &settings,
+            log_buffer,
+            // Published under the name the lines will be kept under
+            self.events.logs(name),
+            self.log_writer(),
+        )?;
+
+        let grace = Duration::from_millis(settings.start_grace_ms);
+        let ready = wait_for_standby(name, &mut standby, grace).await;
+        // Without SO_REUSEPORT the new instance can't bind the port next to
+        // the old one, so it exits or listens elsewhere
+        let shared = port.is_none().then_some(standby.info.port).flatten();
+        let unshared = match (&ready, shared, standby.info.pid) {
+            (Err(_), Some(_), _) => standby
+                .child
+                .as_mut()
+                .is_some_and(|child| matches!(child.try_wait(), Ok(Some(_)))),
+            (Ok(()), Some(port), Some(pid)) => listens_on(pid, port).await == Some(false),
+            _ => false,
+        };
+        if unshared || ready.is_err() {
+            if let Some(mut child) = standby.child.take() {
+                let _ = child.kill().await;
+            }
+        }
+        if unshared {
+            info!(
+                "New instance of '{}' couldn't share its port, restarting in place",
+                name
+            );
+            return self.cold_restart(name, config, reason).await;
+        }
+        if let Err(e) = ready {
+            warn!(
+                "Warm restart of '{}' failed, keeping the running instance: {}",
+                name, e
+            );
+            let mut event = JournalEvent::new(EventType::WarmRestartFailed, name, actor)
+                .with_detail("error", &e);
+            if let Some(port) = port {
+                event = event.with_detail("port", port);
+            }
+            self.recorder.record(event);
+            return Err(e);
+        }
+
+        if let Err(e) = self.stop_as(name, actor).await {
+            if let Some(mut child) = standby.child.take() {
+                let _ = child.kill().await;
+            }
+            return Err(e);
+        }
+
+        // Swap the handles unless the process was started or removed while
+        // the old instance was stopping
+        let membership = self.membership.lock().await;
+        if !self
+            .handle(name)
+            .is_some_and(|current| Arc::ptr_eq(&current, &old))
+        {
+            if let Some(mut child) = standby.child.take() {
+                let _ = child.kill().await;
+            }
+            return Err(SentinelError::Other(format!(
+                "Process '{}' was replaced while warm restarting",
+                name
+            )));
+        }
+        standby.info.name = name.to_string();
+        standby.config = next;
+        standby.restart_count = restart_count;
+        standby.info.restart_count = restart_count;
+        standby.sync_readiness();
+        let info = standby.info.clone();
+        recover(self.processes.write(), "process map")
+            .insert(name.to_string(), Arc::new(Mutex::new(standby)));
+        drop(membership);
+
+        info!(
+            "Process '{}' warm restarted with PID {}",
+            name,
+            info.pid.unwrap_or(0)
+        );
+        self.notify(ProcessEventKind::Started, name, None, actor);
+
+        Ok(self.record_restart(name, reason).await.unwrap_or(info))
     }
 
     /// Starts a stopped process by name using its stored configuration.
@@ -368,715 +823,4460 @@ impl ProcessManager {
     /// - Process with this name doesn't exist in manager
     /// - Process is already running
     /// - Failed to spawn the process
-    pub async fn start_by_name(&mut self, name: &str) -> Result<ProcessInfo> {
+    pub async fn start_by_name(&self, name: &str) -> Result<ProcessInfo> {
         // Get the stored config
-        let handle = self
-            .processes
-            .get(name)
-            .ok_or_else(|| SentinelError::ProcessNotFound {
-                name: name.to_string(),
-            })?;
-
-        // Check if already running
-        if handle.info.is_running() {
-            let pid = handle.info.pid.unwrap_or(0);
-            return Err(SentinelError::ProcessAlreadyRunning {
-                name: name.to_string(),
-                pid,
-            });
-        }
+        let config = {
+            let handle = self.find(name)?;
+            let handle = handle.lock().await;
 
-        let config = handle.config.clone();
+            // Check if already running
+            if handle.info.is_running() {
+                let pid = handle.info.pid.unwrap_or(0);
+                return Err(SentinelError::ProcessAlreadyRunning {
+                    name: name.to_string(),
+                    pid,
+                });
+            }
 
-        // Remove the stopped process handle
-        self.processes.remove(name);
+            handle.config.clone()
+        };
 
-        // Start with the stored config
+        // Start with the stored config, replacing the stopped process handle
         self.start(config).await
     }
 
-    /// Gets information about a process.
+    /// Starts several processes in order.
     ///
-    /// # Arguments
-    /// * `name` - Name of the process
+    /// Expects dependencies to come before their dependents, as returned by
+    /// [`ConfigManager::select_processes`](crate::core::ConfigManager::select_processes).
+    /// A process waits for its dependencies with a `readiness` probe to become
+    /// ready, and for those with `waitForCompletion` to complete, and is
+    /// skipped if one doesn't or failed to start. A process with
+    /// `startDelayMs` waits that long after the ones before it. Processes with
+    /// a `schedule` are left out; [`Self::check_schedules`] starts them.
     ///
-    /// # Returns
-    /// * `Some(ProcessInfo)` - Process information
-    /// * `None` - Process not found
-    pub fn get(&self, name: &str) -> Option<&ProcessInfo> {
-        self.processes.get(name).map(|h| &h.info)
-    }
-
-    /// Lists all processes.
+    /// The start grace period of the processes started so far is watched
+    /// together, once a process depends on one of them or all are started,
+    /// rather than after each one.
     ///
-    /// # Returns
-    /// Vector of all process information.
-    pub fn list(&self) -> Vec<ProcessInfo> {
-        self.processes.values().map(|h| h.info.clone()).collect()
-    }
-
-    /// Updates CPU and memory usage for all running processes.
+    /// # Arguments
+    /// * `configs` - Process configurations in start order
     ///
-    /// This should be called periodically to keep resource usage up-to-date.
-    pub fn update_resource_usage(&mut self) {
-        let mut sys = System::new();
-
-        // Collect PIDs of all running processes
-        let pids: Vec<Pid> = self
-            .processes
-            .values()
-            .filter_map(|h| h.info.pid.map(Pid::from_u32))
-            .collect();
+    /// # Returns
+    /// The name and start result of every process, in the given order.
+    pub async fn start_all(
+        &self,
+        configs: Vec<ProcessConfig>,
+    ) -> Vec<(String, Result<ProcessInfo>)> {
+        let mut results: Vec<(String, Result<ProcessInfo>)> = Vec::with_capacity(configs.len());
+        // Started processes still to be watched through their start grace period
+        let mut unchecked: Vec<usize> = Vec::new();
+
+        for config in configs {
+            if config.schedule.is_some() {
+                continue;
+            }
 
-        // Refresh all processes at once
-        sys.refresh_processes_specifics(
-            ProcessesToUpdate::Some(&pids),
-            true,
-            ProcessRefreshKind::everything(),
-        );
+            let unchecked_dependency = config
+                .depends_on
+                .iter()
+                .any(|dep| unchecked.iter().any(|&i| results[i].0 == *dep));
+            if unchecked_dependency {
+                self.check_started(&mut results, &mut unchecked).await;
+            }
 
-        // Update resource usage for each process
-        for handle in self.processes.values_mut() {
-            if let Some(pid_u32) = handle.info.pid {
-                let pid = Pid::from_u32(pid_u32);
+            let failed_dependency = config.depends_on.iter().find(|dep| {
+                results
+                    .iter()
+                    .any(|(name, result)| name == *dep && result.is_err())
+            });
 
-                if let Some(process) = sys.process(pid) {
-                    // Update CPU usage (percentage per core)
-                    handle.info.cpu_usage = process.cpu_usage();
+            let unready_dependency = match failed_dependency {
+                Some(_) => None,
+                None => self.wait_for_dependencies(&config).await,
+            };
 
-                    // Update memory usage (in bytes)
-                    handle.info.memory_usage = process.memory();
+            let result = match (failed_dependency, unready_dependency) {
+                (Some(dep), _) => Err(SentinelError::Other(format!(
+                    "Skipped '{}' because its dependency '{}' failed to start",
+                    config.name, dep
+                ))),
+                (None, Some((dep, e))) => Err(SentinelError::Other(format!(
+                    "Skipped '{}' because its dependency '{}' is not ready ({})",
+                    config.name, dep, e
+                ))),
+                (None, None) => {
+                    if let Some(delay) = config.start_delay_ms.filter(|delay| *delay > 0) {
+                        debug!("Delaying start of '{}' by {}ms", config.name, delay);
+                        sleep(Duration::from_millis(delay)).await;
+                    }
+                    self.start_as(config.clone(), EventActor::User).await
                 }
+            };
+
+            match &result {
+                Ok(_) => unchecked.push(results.len()),
+                Err(e) => warn!("Failed to start process '{}': {}", config.name, e),
             }
+            results.push((config.name, result));
         }
+
+        self.check_started(&mut results, &mut unchecked).await;
+        results
     }
 
-    /// Checks if a process is running.
-    ///
-    /// # Arguments
-    /// * `name` - Name of the process
-    ///
-    /// # Returns
-    /// * `true` - Process is running
-    /// * `false` - Process is not running or doesn't exist
-    pub fn is_running(&self, name: &str) -> bool {
-        self.processes
-            .get(name)
-            .map(|h| h.info.is_running())
-            .unwrap_or(false)
+    /// Watches the processes [`Self::start_all`] started at `unchecked` in
+    /// `results` through their start grace period at the same time, and
+    /// replaces the results of the ones that failed.
+    async fn check_started(
+        &self,
+        results: &mut [(String, Result<ProcessInfo>)],
+        unchecked: &mut Vec<usize>,
+    ) {
+        let started: Vec<(usize, ProcessInfo)> = unchecked
+            .drain(..)
+            .filter_map(|i| Some((i, results[i].1.as_ref().ok()?.clone())))
+            .collect();
+        let checked = join_all(
+            started
+                .iter()
+                .map(|(_, info)| self.check_early_exit(info.clone())),
+        )
+        .await;
+
+        for ((i, _), result) in started.into_iter().zip(checked) {
+            if let Err(e) = &result {
+                warn!("Failed to start process '{}': {}", results[i].0, e);
+            }
+            results[i].1 = result;
+        }
     }
 
-    /// Stops all running processes.
+    /// Waits for the dependencies of `config` with `waitForCompletion` to
+    /// complete, and for its running dependencies that have a readiness probe,
+    /// each for up to its `maxWaitMs`.
     ///
-    /// # Examples
-    /// ```no_run
-    /// # use sentinel::core::ProcessManager;
-    /// # tokio_test::block_on(async {
-    /// # let mut manager = ProcessManager::new();
-    /// manager.stop_all().await?;
-    /// # Ok::<(), Box<dyn std::error::Error>>(())
-    /// # });
-    /// ```
-    pub async fn stop_all(&mut self) -> Result<()> {
-        info!("Stopping all processes");
+    /// # Returns
+    /// The first dependency that didn't complete or become ready, with the
+    /// reason.
+    async fn wait_for_dependencies(
+        &self,
+        config: &ProcessConfig,
+    ) -> Option<(String, SentinelError)> {
+        for dep in &config.depends_on {
+            let Some(handle) = self.handle(dep) else {
+                continue;
+            };
+            let (completion, probe) = {
+                let handle = handle.lock().await;
+                let completion = handle.config.wait_for_completion.then(|| {
+                    handle
+                        .config
+                        .max_runtime_ms
+                        .map_or(DEFAULT_COMPLETION_WAIT, Duration::from_millis)
+                });
+                let probe = handle
+                    .config
+                    .readiness
+                    .clone()
+                    .filter(|_| handle.info.is_running());
+                (completion, probe)
+            };
 
-        let names: Vec<String> = self.processes.keys().cloned().collect();
+            if let Some(wait) = completion {
+                debug!("Waiting for '{}' to complete", dep);
+                if let Err(e) = self.wait_for_completion(dep, wait).await {
+                    return Some((dep.clone(), e));
+                }
+                continue;
+            }
+            let Some(probe) = probe else {
+                continue;
+            };
 
-        for name in names {
-            if let Err(e) = self.stop(&name).await {
-                error!("Failed to stop process '{}': {}", name, e);
+            debug!("Waiting for '{}' to become ready", dep);
+            if let Err(e) = self
+                .wait_for_ready(dep, Duration::from_millis(probe.max_wait_ms))
+                .await
+            {
+                return Some((dep.clone(), e));
             }
         }
 
-        Ok(())
+        None
     }
 
-    /// Removes a stopped process from management.
+    /// Starts every process tagged `tag`, dependencies first.
     ///
-    /// # Arguments
-    /// * `name` - Name of the process to remove
+    /// Candidates are `configs` (typically the config file) plus the processes
+    /// this manager already knows; `configs` wins when both have a process.
+    /// Tagged processes that are already running are left alone.
     ///
-    /// # Returns
-    /// * `Ok(())` - Process removed
-    /// * `Err(SentinelError)` - Process is still running or doesn't exist
-    pub fn remove(&mut self, name: &str) -> Result<()> {
-        if self.is_running(name) {
-            return Err(SentinelError::Other(
-                "Cannot remove running process. Stop it first.".to_string(),
-            ));
+    /// # Errors
+    /// Returns an error, without starting anything, if no process has the tag,
+    /// if a tagged process depends on an untagged one that isn't running (or
+    /// completed, when it's waited for to complete), or
+    /// if the tagged processes depend on each other in a cycle.
+    pub async fn start_tagged(
+        &self,
+        configs: Vec<ProcessConfig>,
+        tag: &str,
+    ) -> Result<Vec<(String, Result<ProcessInfo>)>> {
+        let mut group: Vec<ProcessConfig> = configs
+            .iter()
+            .filter(|config| config.tags.iter().any(|t| t == tag))
+            .cloned()
+            .collect();
+        for config in self.tagged_configs(tag).await {
+            if !group.iter().any(|c| c.name == config.name) {
+                group.push(config);
+            }
+        }
+        if group.is_empty() {
+            return Err(self.unknown_tag(tag, &configs).await);
         }
 
-        self.processes.remove(name);
-        Ok(())
+        let ordered = self.check_group(&group, tag).await?;
+        let mut pending = Vec::with_capacity(ordered.len());
+        for config in ordered {
+            if !self.is_running(&config.name).await {
+                pending.push(config);
+            }
+        }
+        Ok(self.start_all(pending).await)
     }
 
-    /// Gets logs for a specific process.
-    ///
-    /// # Arguments
-    /// * `name` - Name of the process
+    /// Stops every managed process tagged `tag`, dependents first.
     ///
-    /// # Returns
-    /// * `Some(Vec<LogLine>)` - Log lines for the process
-    /// * `None` - Process not found
-    pub async fn get_logs(&self, name: &str) -> Option<Vec<LogLine>> {
-        let handle = self.processes.get(name)?;
-        let buffer = handle.log_buffer.lock().await;
-        Some(buffer.get_all())
+    /// # Errors
+    /// Returns an error, without stopping anything, if no managed process has
+    /// the tag or if the tagged processes depend on each other in a cycle.
+    pub async fn stop_tagged(&self, tag: &str) -> Result<Vec<(String, Result<()>)>> {
+        let group = self.tagged_configs(tag).await;
+        if group.is_empty() {
+            return Err(self.unknown_tag(tag, &[]).await);
+        }
+
+        let mut results = Vec::with_capacity(group.len());
+        for config in ConfigManager::dependency_order(&group)?.into_iter().rev() {
+            let result = self.stop(&config.name).await;
+            results.push((config.name, result));
+        }
+        Ok(results)
     }
 
-    /// Gets last N logs for a specific process.
+    /// Restarts every managed process tagged `tag`, stopping dependents first
+    /// and then starting dependencies first.
+    ///
+    /// # Errors
+    /// Same as [`Self::start_tagged`]; the checks run before anything is stopped.
+    pub async fn restart_tagged(&self, tag: &str) -> Result<Vec<(String, Result<ProcessInfo>)>> {
+        let group = self.tagged_configs(tag).await;
+        let ordered = self.check_group(&group, tag).await?;
+
+        for config in ordered.iter().rev() {
+            if let Err(e) = self.stop(&config.name).await {
+                warn!("Failed to stop process '{}': {}", config.name, e);
+            }
+        }
+        let mut results = self.start_all(ordered).await;
+        for (name, result) in &mut results {
+            if let Ok(info) = result {
+                if let Some(updated) = self
+                    .record_restart(name, RestartReason::UserRequested)
+                    .await
+                {
+                    *info = updated;
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Recent usage samples of the managed processes tagged `tag`, by name.
+    ///
+    /// # Errors
+    /// Returns an error if no managed process has the tag.
+    pub async fn tagged_usage(
+        &self,
+        tag: &str,
+    ) -> Result<Vec<(String, Vec<TimedMetric<UsageSample>>)>> {
+        let mut members = Vec::new();
+        for (name, handle) in self.handles() {
+            let handle = handle.lock().await;
+            if handle.config.tags.iter().any(|t| t == tag) {
+                members.push((name, handle.history.samples.get_all()));
+            }
+        }
+        if members.is_empty() {
+            return Err(self.unknown_tag(tag, &[]).await);
+        }
+        Ok(members)
+    }
+
+    /// Error for a tag no process has, listing the tags of the managed
+    /// processes and `configs`.
+    async fn unknown_tag(&self, tag: &str, configs: &[ProcessConfig]) -> SentinelError {
+        let mut known: Vec<String> = configs
+            .iter()
+            .flat_map(|config| config.tags.iter().cloned())
+            .collect();
+        for (_, handle) in self.handles() {
+            known.extend(handle.lock().await.config.tags.iter().cloned());
+        }
+        known.sort();
+        known.dedup();
+        SentinelError::UnknownTag {
+            tag: tag.to_string(),
+            known,
+        }
+    }
+
+    /// Configurations of the managed processes tagged `tag`, by name.
+    async fn tagged_configs(&self, tag: &str) -> Vec<ProcessConfig> {
+        let mut configs = Vec::new();
+        for (_, handle) in self.handles() {
+            let handle = handle.lock().await;
+            if handle.config.tags.iter().any(|t| t == tag) {
+                configs.push(handle.config.clone());
+            }
+        }
+        configs
+    }
+
+    /// Checks that a tagged group can start and returns it in start order.
+    ///
+    /// Dependencies outside the group must already be running, or have
+    /// completed if the group waits for them to.
+    async fn check_group(&self, group: &[ProcessConfig], tag: &str) -> Result<Vec<ProcessConfig>> {
+        if group.is_empty() {
+            return Err(self.unknown_tag(tag, &[]).await);
+        }
+
+        for config in group {
+            let outside = config
+                .depends_on
+                .iter()
+                .filter(|dep| !group.iter().any(|c| &c.name == *dep));
+            for dep in outside {
+                let satisfied = match self.handle(dep) {
+                    Some(handle) => {
+                        let handle = handle.lock().await;
+                        handle.info.is_running()
+                            || (handle.config.wait_for_completion && handle.info.is_completed())
+                    }
+                    None => false,
+                };
+                if !satisfied {
+                    return Err(SentinelError::DependencyNotRunning {
+                        process: config.name.clone(),
+                        dependency: dep.clone(),
+                        tag: tag.to_string(),
+                    });
+                }
+            }
+        }
+
+        ConfigManager::dependency_order(group)
+    }
+
+    /// Gets the configuration a process was started with.
     ///
     /// # Arguments
     /// * `name` - Name of the process
-    /// * `n` - Number of recent logs to retrieve
     ///
     /// # Returns
-    /// * `Some(Vec<LogLine>)` - Last N log lines
+    /// * `Some(ProcessConfig)` - Configuration, including unmasked env values
     /// * `None` - Process not found
-    pub async fn get_recent_logs(&self, name: &str, n: usize) -> Option<Vec<LogLine>> {
-        let handle = self.processes.get(name)?;
-        let buffer = handle.log_buffer.lock().await;
-        Some(buffer.get_last_n(n))
+    pub async fn get_config(&self, name: &str) -> Option<ProcessConfig> {
+        Some(self.handle(name)?.lock().await.config.clone())
     }
 
-    /// Searches logs for a specific process.
+    /// Gets information about a process.
     ///
     /// # Arguments
     /// * `name` - Name of the process
-    /// * `query` - Search query (case-insensitive)
     ///
     /// # Returns
-    /// * `Some(Vec<LogLine>)` - Matching log lines
+    /// * `Some(ProcessInfo)` - Process information
     /// * `None` - Process not found
-    pub async fn search_logs(&self, name: &str, query: &str) -> Option<Vec<LogLine>> {
-        let handle = self.processes.get(name)?;
-        let buffer = handle.log_buffer.lock().await;
-        Some(buffer.search(query))
+    pub async fn get(&self, name: &str) -> Option<ProcessInfo> {
+        Some(self.handle(name)?.lock().await.info.clone())
     }
 
-    /// Clears all logs for a specific process.
-    ///
-    /// # Arguments
-    /// * `name` - Name of the process
+    /// Lists all processes.
     ///
     /// # Returns
-    /// * `Ok(())` - Logs cleared successfully
-    /// * `Err(SentinelError)` - Process not found
-    pub async fn clear_logs(&self, name: &str) -> Result<()> {
-        let handle = self
-            .processes
-            .get(name)
-            .ok_or_else(|| SentinelError::ProcessNotFound {
-                name: name.to_string(),
-            })?;
-        let mut buffer = handle.log_buffer.lock().await;
-        buffer.clear();
-        Ok(())
+    /// Vector of all process information, ordered by name.
+    pub async fn list(&self) -> Vec<ProcessInfo> {
+        let mut processes = Vec::new();
+        for (_, handle) in self.handles() {
+            let handle = handle.lock().await;
+            let mut info = handle.info.clone();
+            apply_readiness(&mut info, handle.readiness.as_ref());
+            processes.push(info);
+        }
+        processes
     }
 
-    /// Checks health of all processes and restarts crashed ones with auto_restart enabled.
+    /// Lists all processes with their recent CPU and memory usage.
     ///
-    /// Uses exponential backoff for restart delays:
-    /// - First restart: restart_delay ms
-    /// - Second restart: restart_delay * 2 ms
-    /// - Third restart: restart_delay * 4 ms
-    /// - Max: restart_delay * 2^(restart_count)
+    /// A sample is recorded each time [`update_resource_usage`](Self::update_resource_usage)
+    /// runs, at most once per second.
     ///
-    /// Returns list of process names that were restarted.
-    pub async fn check_health(&mut self) -> Vec<String> {
-        let mut restarted = Vec::new();
-        let process_names: Vec<String> = self.processes.keys().cloned().collect();
-
-        for name in process_names {
-            let should_restart = {
-                let handle = match self.processes.get_mut(&name) {
-                    Some(h) => h,
-                    None => continue,
-                };
-
-                // Check if process has exited
-                if let Some(child) = &mut handle.child {
-                    match child.try_wait() {
-                        Ok(Some(exit_status)) => {
-                            // Process has exited
-                            let exit_code = exit_status.code().unwrap_or(-1);
-                            warn!("Process '{}' exited with status: {:?}", name, exit_status);
-                            handle.info.state = ProcessState::Crashed { exit_code };
-                            handle.info.pid = None;
-                            handle.info.stopped_at = Some(Utc::now());
-                            handle.child = None;
-
-                            // Check if auto-restart is enabled and limit not exceeded
-                            if handle.config.auto_restart {
-                                if handle.config.restart_limit == 0
-                                    || handle.restart_count < handle.config.restart_limit
-                                {
-                                    true
-                                } else {
-                                    error!(
-                                        "Process '{}' exceeded restart limit ({})",
-                                        name, handle.config.restart_limit
-                                    );
-                                    false
-                                }
-                            } else {
-                                false
-                            }
-                        }
-                        Ok(None) => {
-                            // Process still running
-                            false
-                        }
-                        Err(e) => {
-                            error!("Error checking process '{}' status: {}", name, e);
-                            false
-                        }
-                    }
-                } else {
-                    false
-                }
-            };
-
-            if should_restart {
-                // Calculate exponential backoff delay
-                let handle = self.processes.get(&name).unwrap();
-                let base_delay = handle.config.restart_delay;
-                let backoff_multiplier = 2_u64.pow(handle.restart_count);
-                let delay_ms = base_delay.saturating_mul(backoff_multiplier);
-
-                info!(
-                    "Auto-restarting process '{}' (attempt {}) after {}ms",
-                    name,
-                    handle.restart_count + 1,
-                    delay_ms
-                );
-
-                // Wait with exponential backoff
-                sleep(Duration::from_millis(delay_ms)).await;
+    /// # Returns
+    /// Vector of all process information with `history` set, ordered by name.
+    pub async fn list_with_history(&self) -> Vec<ProcessInfo> {
+        let mut processes = Vec::new();
+        for (_, handle) in self.handles() {
+            let handle = handle.lock().await;
+            let mut info = handle.info.clone();
+            apply_readiness(&mut info, handle.readiness.as_ref());
+            info.history = Some(handle.history.snapshot());
+            processes.push(info);
+        }
+        processes
+    }
 
-                // Get config and increment restart counter
-                let config = handle.config.clone();
-                let restart_count = handle.restart_count;
-                let last_restart = Some(std::time::Instant::now());
+    /// Watches the readiness of a running process.
+    ///
+    /// A process without a readiness probe is ready as soon as it runs.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running.
+    pub async fn readiness(&self, name: &str) -> Result<watch::Receiver<Readiness>> {
+        let handle = self.find(name)?;
+        let handle = handle.lock().await;
 
-                // Try to restart
-                match self.start(config).await {
-                    Ok(_) => {
-                        // Update restart tracking
-                        if let Some(handle) = self.processes.get_mut(&name) {
-                            handle.restart_count = restart_count + 1;
-                            handle.last_restart = last_restart;
-                            handle.info.restart_count = restart_count + 1;
-                        }
-                        restarted.push(name.clone());
-                    }
-                    Err(e) => {
-                        error!("Failed to auto-restart process '{}': {}", name, e);
-                    }
-                }
-            }
+        if !handle.info.is_running() {
+            return Err(SentinelError::ProcessNotRunning {
+                name: name.to_string(),
+            });
         }
 
-        restarted
+        Ok(match &handle.readiness {
+            Some(readiness) => readiness.clone(),
+            None => watch::channel(Readiness::Ready { after_ms: 0 }).1,
+        })
     }
 
-    /// Gracefully stops a process with timeout and force kill fallback.
+    /// Waits up to `wait` for a running process to become ready.
     ///
-    /// On Unix: Sends SIGTERM, waits 5 seconds, then sends SIGKILL if needed.
-    /// On Windows: Terminates the process after 5 second timeout.
+    /// # Returns
+    /// The process information, with `ready` and `time_to_ready_ms` set.
     ///
-    /// # Arguments
-    /// * `name` - Name of the process to stop
+    /// # Errors
+    /// Returns `ReadinessTimeout` if the probe gave up or `wait` ran out, and
+    /// `ProcessNotFound` or `ProcessNotRunning` if the process isn't running.
+    pub async fn wait_for_ready(&self, name: &str, wait: Duration) -> Result<ProcessInfo> {
+        readiness::wait(name, self.readiness(name).await?, wait).await?;
+
+        let handle = self.find(name)?;
+        let mut handle = handle.lock().await;
+        handle.sync_readiness();
+        Ok(handle.info.clone())
+    }
+
+    /// Waits up to `wait` for a process to exit with a success exit code.
+    ///
+    /// Exits are noticed by [`check_health`](Self::check_health), which this
+    /// calls while waiting, so a crashed process is restarted as usual and
+    /// waited for again.
     ///
     /// # Returns
-    /// * `Ok(())` - Process stopped
-    /// * `Err(SentinelError)` - Process not found or error occurred
-    pub async fn stop_gracefully(&mut self, name: &str) -> Result<()> {
-        let handle =
-            self.processes
-                .get_mut(name)
+    /// The process information, in the `Completed` state.
+    ///
+    /// # Errors
+    /// Returns `NotCompleted` if the process ended any other way,
+    /// `CompletionTimeout` if `wait` ran out, and `ProcessNotFound` if there
+    /// is no such process.
+    pub async fn wait_for_completion(&self, name: &str, wait: Duration) -> Result<ProcessInfo> {
+        let started = Instant::now();
+        loop {
+            self.check_health().await;
+            let info = self
+                .get(name)
+                .await
                 .ok_or_else(|| SentinelError::ProcessNotFound {
                     name: name.to_string(),
                 })?;
+            match info.state {
+                ProcessState::Completed { .. } => return Ok(info),
+                ProcessState::Starting
+                | ProcessState::Running
+                | ProcessState::Paused
+                | ProcessState::Stopping => {}
+                ProcessState::Crashed { exit_code } => {
+                    return Err(SentinelError::NotCompleted {
+                        name: name.to_string(),
+                        exit_code: Some(exit_code),
+                    })
+                }
+                ProcessState::Stopped | ProcessState::Failed { .. } => {
+                    return Err(SentinelError::NotCompleted {
+                        name: name.to_string(),
+                        exit_code: info.exit_code,
+                    })
+                }
+            }
 
-        if !handle.info.is_running() {
-            return Ok(());
+            if started.elapsed() >= wait {
+                return Err(SentinelError::CompletionTimeout {
+                    name: name.to_string(),
+                    waited_ms: wait.as_millis() as u64,
+                });
+            }
+            sleep(COMPLETION_POLL).await;
         }
+    }
 
-        info!("Gracefully stopping process: {}", name);
-        handle.info.state = ProcessState::Stopping;
+    /// Updates CPU and memory usage for all running processes.
+    ///
+    /// With `aggregateChildUsage` enabled the whole process table is
+    /// refreshed, and each process's descendants are added to its
+    /// `cpu_usage_tree` and `memory_usage_tree`. Either way each process's
+    /// descendants are noted, so the ones it leaves running when it crashes
+    /// can be found.
+    ///
+    /// CPU usage is only reported from a process's second sample on, as
+    /// the first one has nothing to compare against. Calls less than
+    /// [`RESOURCE_REFRESH_INTERVAL`] apart reuse the same sample.
+    ///
+    /// Usage of the Docker containers a process runs, as last reported to
+    /// [`set_container_usage`](Self::set_container_usage), is added on top.
+    ///
+    /// This should be called periodically to keep resource usage up-to-date.
+    pub async fn update_resource_usage(&self) {
+        let aggregate = recover(self.settings.read(), "settings").aggregate_child_usage;
+        let core_count = scheduling::core_count();
+
+        let handles = self.handles();
+        let mut pids = Vec::with_capacity(handles.len());
+        for (_, handle) in &handles {
+            let mut handle = handle.lock().await;
+            handle.sync_readiness();
+            pids.push(handle.info.pid);
+        }
 
-        if let Some(mut child) = handle.child.take() {
-            #[cfg(unix)]
-            {
-                // Send SIGTERM for graceful shutdown
-                if let Some(pid) = child.id() {
-                    debug!("Sending SIGTERM to process '{}' (PID: {})", name, pid);
-                    unsafe {
-                        libc::kill(pid as i32, libc::SIGTERM);
-                    }
+        // Sample under the process table's lock alone, then hand out the results
+        let samples: Vec<Option<ResourceSample>> = {
+            let mut table = recover(self.resources.lock(), "process table");
+            if aggregate {
+                // Descendants can be anywhere in the process table
+                table.refresh(None);
+            } else {
+                // Refresh all running processes at once
+                let running: Vec<Pid> = pids.iter().flatten().copied().map(Pid::from_u32).collect();
+                if table.refresh(Some(&running)) {
+                    table.refresh_links();
                 }
+            }
 
-                // Wait up to 5 seconds for graceful shutdown
-                let graceful_timeout = Duration::from_secs(5);
-                match tokio::time::timeout(graceful_timeout, child.wait()).await {
-                    Ok(Ok(status)) => {
-                        debug!(
-                            "Process '{}' gracefully exited with status: {:?}",
-                            name, status
-                        );
-                    }
-                    Ok(Err(e)) => {
-                        warn!("Error waiting for process '{}': {}", name, e);
-                    }
-                    Err(_) => {
-                        warn!(
-                            "Process '{}' did not stop gracefully, sending SIGKILL",
-                            name
-                        );
-                        if let Some(pid) = child.id() {
-                            unsafe {
-                                libc::kill(pid as i32, libc::SIGKILL);
-                            }
+            // One pass over the table finds the descendants of every process
+            let index = ProcessIndex::new(if aggregate {
+                &table.system
+            } else {
+                &table.links
+            });
+            pids.iter()
+                .map(|pid| {
+                    let pid = (*pid)?;
+                    let measured = table.measured.contains(&Pid::from_u32(pid));
+                    Some(ResourceSample::take(
+                        &table.system,
+                        &index,
+                        pid,
+                        measured,
+                        aggregate,
+                    ))
+                })
+                .collect()
+        };
+
+        // Update resource usage for each process
+        for ((_, handle), sample) in handles.iter().zip(samples) {
+            let Some(mut sample) = sample else {
+                continue;
+            };
+            let mut handle = handle.lock().await;
+            // Skip a process that was restarted meanwhile
+            if handle.info.pid == Some(sample.pid) {
+                handle.children = std::mem::take(&mut sample.children);
+                let found = sample.usage.is_some();
+                sample.apply(&mut handle.info, core_count);
+                if found {
+                    let usage = handle
+                        .container_usage
+                        .clone()
+                        .filter(|usage| container_link::is_fresh(usage, Utc::now()));
+                    match usage {
+                        Some(usage) => handle.info.merge_container_usage(&usage, core_count),
+                        None => {
+                            handle.info.usage_source = UsageSource::Process;
+                            handle.info.container = None;
                         }
-                        let _ = child.wait().await;
                     }
+                    let sample = UsageSample {
+                        cpu: handle.info.cpu_usage_percent_of_core,
+                        memory: handle.info.memory_usage,
+                        open_fds: handle.info.open_fds,
+                        threads: handle.info.thread_count,
+                    };
+                    handle.history.record(sample);
                 }
             }
+        }
+    }
 
-            #[cfg(not(unix))]
-            {
-                // Windows: just kill with timeout
-                let timeout = Duration::from_secs(5);
-                match tokio::time::timeout(timeout, child.wait()).await {
-                    Ok(Ok(status)) => {
-                        debug!("Process '{}' exited with status: {:?}", name, status);
-                    }
-                    Ok(Err(e)) => {
-                        warn!("Error waiting for process '{}': {}", name, e);
-                    }
-                    Err(_) => {
-                        warn!(
-                            "Process '{}' did not stop within timeout, force killing",
-                            name
-                        );
-                        let _ = child.kill().await;
-                    }
-                }
+    /// Running processes linked to Docker containers, with the reference
+    /// to find their containers by.
+    pub async fn container_refs(&self) -> Vec<(String, ContainerRef)> {
+        let mut refs = Vec::new();
+        for (name, handle) in self.handles() {
+            let handle = handle.lock().await;
+            if !handle.info.is_running() {
+                continue;
+            }
+            if let Some(reference) = ContainerRef::from_config(&handle.config) {
+                refs.push((name, reference));
             }
         }
+        refs
+    }
 
-        handle.info.state = ProcessState::Stopped;
-        handle.info.pid = None;
-        handle.info.stopped_at = Some(Utc::now());
-
-        Ok(())
+    /// Env files of the running processes, for the env file watcher.
+    pub async fn env_files(&self) -> Vec<WatchedEnvFile> {
+        let mut files = Vec::new();
+        for (_, handle) in self.handles() {
+            let handle = handle.lock().await;
+            files.extend(WatchedEnvFile::of(&handle.config, &handle.info));
+        }
+        files
     }
-}
 
-impl Default for ProcessManager {
-    fn default() -> Self {
-        Self::new()
+    /// Records the usage of the containers a process runs, or that none
+    /// were found. It's merged in by the next
+    /// [`update_resource_usage`](Self::update_resource_usage).
+    pub async fn set_container_usage(&self, name: &str, usage: Option<ContainerUsage>) {
+        let handle = recover(self.processes.read(), "process map")
+            .get(name)
+            .cloned();
+        if let Some(handle) = handle {
+            handle.lock().await.container_usage = usage;
+        }
     }
-}
+
+    /// Gets a running process and its descendants.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running.
+    pub async fn get_process_tree(&self, name: &str) -> Result<ProcessTreeNode> {
+        let pid = self.running_handle(name).await?.1;
+
+        let mut table = recover(self.resources.lock(), "process table");
+        table.refresh(None);
+
+        process_tree::build_tree(&table.system, pid).ok_or_else(|| {
+            SentinelError::ProcessNotRunning {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    /// Changes the priority of a running process.
+    ///
+    /// The new value is kept in its configuration, so it survives restarts.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running, `InvalidPriority` for an out-of-range value, or
+    /// `SchedulingFailed` if the OS refuses the change.
+    pub async fn set_priority(&self, name: &str, nice: i32) -> Result<ProcessInfo> {
+        let (mut handle, pid) = self.running_handle(name).await?;
+
+        scheduling::set_priority(pid, nice)?;
+        handle.config.nice = Some(nice);
+        handle.info.nice = scheduling::priority(pid);
+
+        info!("Set priority of process '{}' to {}", name, nice);
+        Ok(handle.info.clone())
+    }
+
+    /// Restricts a running process to the given CPU cores.
+    ///
+    /// The new value is kept in its configuration, so it survives restarts.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running, `InvalidCpuAffinity` for cores that can't be used, or
+    /// `SchedulingFailed` if the OS refuses the change.
+    pub async fn set_affinity(&self, name: &str, cores: Vec<usize>) -> Result<ProcessInfo> {
+        let (mut handle, pid) = self.running_handle(name).await?;
+
+        scheduling::set_affinity(pid, &cores)?;
+        info!("Set CPU affinity of process '{}' to {:?}", name, cores);
+        handle.config.cpu_affinity = Some(cores);
+
+        Ok(handle.info.clone())
+    }
+
+    /// Writes `data` to the stdin of a running process.
+    ///
+    /// Writes are queued and performed in order, so concurrent callers never
+    /// interleave their bytes. Include a trailing newline for line-based input.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running, or `InvalidInput` if it wasn't started with `stdinMode: piped`.
+    pub async fn write_stdin(&self, name: &str, data: Vec<u8>) -> Result<()> {
+        let (handle, _) = self.running_handle(name).await?;
+        let stdin = handle
+            .stdin
+            .as_ref()
+            .ok_or_else(|| SentinelError::InvalidInput {
+                message: format!("Process '{}' was not started with stdinMode: piped", name),
+            })?;
+
+        // The writer stops once the pipe breaks, i.e. the process has exited
+        stdin
+            .send(data)
+            .map_err(|_| SentinelError::ProcessNotRunning {
+                name: name.to_string(),
+            })
+    }
+
+    /// Sends a signal, given by name (e.g. `HUP` or `SIGUSR1`), to a running process.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running, `InvalidInput` for an unknown signal, or `Unsupported` on
+    /// platforms without signals.
+    pub async fn send_signal(&self, name: &str, signal: &str) -> Result<()> {
+        let (_, pid) = self.running_handle(name).await?;
+
+        #[cfg(unix)]
+        {
+            let number = signal_number(signal).ok_or_else(|| SentinelError::InvalidInput {
+                message: format!("Unknown signal '{}'", signal),
+            })?;
+
+            if unsafe { libc::kill(pid as i32, number) } != 0 {
+                return Err(SentinelError::Other(format!(
+                    "Failed to send {} to process '{}': {}",
+                    signal,
+                    name,
+                    std::io::Error::last_os_error()
+                )));
+            }
+
+            info!("Sent {} to process '{}'", signal, name);
+            Ok(())
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = (pid, signal);
+            Err(SentinelError::Unsupported {
+                operation: "Sending signals".to_string(),
+            })
+        }
+    }
+
+    /// Pauses a running process and its children with SIGSTOP.
+    ///
+    /// They keep their memory and carry on where they left off once
+    /// [`resume`](Self::resume)d. Health checks and restart policies leave a
+    /// paused process alone; its usage is still sampled. Pausing a paused
+    /// process does nothing.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running, or `Unsupported` on platforms without signals.
+    pub async fn pause(&self, name: &str) -> Result<ProcessInfo> {
+        self.set_paused(name, true).await
+    }
+
+    /// Resumes a paused process and its children with SIGCONT.
+    ///
+    /// Resuming a process that isn't paused does nothing.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` or `ProcessNotRunning` if the process isn't
+    /// running, or `Unsupported` on platforms without signals.
+    pub async fn resume(&self, name: &str) -> Result<ProcessInfo> {
+        self.set_paused(name, false).await
+    }
+
+    async fn set_paused(&self, name: &str, paused: bool) -> Result<ProcessInfo> {
+        let (mut handle, pid) = self.running_handle(name).await?;
+        if handle.info.is_paused() == paused {
+            return Ok(handle.info.clone());
+        }
+
+        let (action, state, event) = if paused {
+            ("Paused", ProcessState::Paused, EventType::ProcessPaused)
+        } else {
+            ("Resumed", ProcessState::Running, EventType::ProcessResumed)
+        };
+        self.suspend_tree(name, pid, handle.info.pgid, paused)?;
+
+        handle.info.state = state;
+        info!("{} process '{}'", action, name);
+        self.recorder
+            .record(JournalEvent::new(event, name, EventActor::User));
+        Ok(handle.info.clone())
+    }
+
+    /// Stops (`paused`) or continues a process and its descendants.
+    ///
+    /// A process leading its own process group is signalled through the
+    /// group. Otherwise it shares Sentinel's group, so each process in its
+    /// tree is signalled instead, parents first.
+    fn suspend_tree(&self, name: &str, pid: u32, pgid: Option<u32>, paused: bool) -> Result<()> {
+        #[cfg(unix)]
+        {
+            let signal = if paused { libc::SIGSTOP } else { libc::SIGCONT };
+            let failed = || {
+                SentinelError::Other(format!(
+                    "Failed to {} process '{}': {}",
+                    if paused { "pause" } else { "resume" },
+                    name,
+                    std::io::Error::last_os_error()
+                ))
+            };
+
+            if pgid == Some(pid) {
+                return match unsafe { libc::killpg(pid as i32, signal) } {
+                    0 => Ok(()),
+                    _ => Err(failed()),
+                };
+            }
+
+            let tree = {
+                let mut table = recover(self.resources.lock(), "process table");
+                process_tree::build_tree(table.refresh_links(), pid)
+            };
+
+            if unsafe { libc::kill(pid as i32, signal) } != 0 {
+                return Err(failed());
+            }
+            // A child may have exited since the table was read
+            for child in tree.iter().flat_map(ProcessTreeNode::pids).skip(1) {
+                unsafe {
+                    libc::kill(child as i32, signal);
+                }
+            }
+            Ok(())
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = (name, pid, pgid, paused);
+            Err(SentinelError::Unsupported {
+                operation: "Pausing processes".to_string(),
+            })
+        }
+    }
+
+    /// The locked handle and PID of a running process.
+    async fn running_handle(&self, name: &str) -> Result<(OwnedMutexGuard<ProcessHandle>, u32)> {
+        let handle = self.find(name)?.lock_owned().await;
+
+        match handle.info.pid {
+            Some(pid) if handle.info.is_running() => Ok((handle, pid)),
+            _ => Err(SentinelError::ProcessNotRunning {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    /// Checks if a process is running.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process
+    ///
+    /// # Returns
+    /// * `true` - Process is running
+    /// * `false` - Process is not running or doesn't exist
+    pub async fn is_running(&self, name: &str) -> bool {
+        match self.handle(name) {
+            Some(handle) => handle.lock().await.info.is_running(),
+            None => false,
+        }
+    }
+
+    /// Stops all running processes.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use sentinel::core::ProcessManager;
+    /// # tokio_test::block_on(async {
+    /// # let manager = ProcessManager::new();
+    /// manager.stop_all().await?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn stop_all(&self) -> Result<()> {
+        info!("Stopping all processes");
+
+        for (name, _) in self.handles() {
+            if let Err(e) = self.stop(&name).await {
+                error!("Failed to stop process '{}': {}", name, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes a stopped process from management.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process to remove
+    ///
+    /// # Returns
+    /// * `Ok(())` - Process removed
+    /// * `Err(SentinelError)` - Process is still running or doesn't exist
+    pub async fn remove(&self, name: &str) -> Result<()> {
+        let _membership = self.membership.lock().await;
+        if self.is_running(name).await {
+            return Err(SentinelError::Other(
+                "Cannot remove running process. Stop it first.".to_string(),
+            ));
+        }
+
+        recover(self.processes.write(), "process map").remove(name);
+        Ok(())
+    }
+
+    /// Gets logs for a specific process.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process
+    ///
+    /// # Returns
+    /// * `Some(Vec<LogLine>)` - Log lines for the process
+    /// * `None` - Process not found
+    pub async fn get_logs(&self, name: &str) -> Option<Vec<LogLine>> {
+        let buffer = self.log_buffer(name).await?;
+        let buffer = buffer.lock().await;
+        Some(buffer.get_all())
+    }
+
+    /// Gets the logs of `names`, or of every process, for a merged view.
+    ///
+    /// # Returns
+    /// Each process's lines, ordered by process name and then as received.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` for a name that isn't a managed process.
+    pub async fn merged_logs(&self, names: Option<&[String]>) -> Result<Vec<MergedLogLine>> {
+        let handles = self.handles();
+        if let Some(names) = names {
+            if let Some(unknown) = names.iter().find(|n| !handles.iter().any(|(h, _)| h == *n)) {
+                return Err(SentinelError::ProcessNotFound {
+                    name: unknown.clone(),
+                });
+            }
+        }
+
+        let mut merged = Vec::new();
+        for (name, handle) in handles {
+            if names.is_some_and(|names| !names.contains(&name)) {
+                continue;
+            }
+            let buffer = handle.lock().await.log_buffer.clone();
+            let lines = buffer.lock().await.get_all();
+            merged.extend(lines.into_iter().map(|line| MergedLogLine {
+                process: name.clone(),
+                attachment_id: None,
+                line,
+            }));
+        }
+        Ok(merged)
+    }
+
+    /// Gets the number of log lines a process has written since it was
+    /// last started.
+    pub async fn log_lines_total(&self, name: &str) -> Option<u64> {
+        let buffer = self.log_buffer(name).await?;
+        let buffer = buffer.lock().await;
+        Some(buffer.total_pushed())
+    }
+
+    /// Gets last N logs for a specific process.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process
+    /// * `n` - Number of recent logs to retrieve
+    ///
+    /// # Returns
+    /// * `Some(Vec<LogLine>)` - Last N log lines
+    /// * `None` - Process not found
+    pub async fn get_recent_logs(&self, name: &str, n: usize) -> Option<Vec<LogLine>> {
+        let buffer = self.log_buffer(name).await?;
+        let buffer = buffer.lock().await;
+        Some(buffer.get_last_n(n))
+    }
+
+    /// Searches logs for a specific process.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process
+    /// * `query` - Search query (case-insensitive)
+    ///
+    /// # Returns
+    /// * `Some(Vec<LogLine>)` - Matching log lines
+    /// * `None` - Process not found
+    pub async fn search_logs(&self, name: &str, query: &str) -> Option<Vec<LogLine>> {
+        let buffer = self.log_buffer(name).await?;
+        let buffer = buffer.lock().await;
+        Some(buffer.search(query))
+    }
+
+    /// Gets the structured log lines of a process whose fields match `filter`.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process
+    /// * `filter` - Field values to match, e.g. `request_id` = `abc`; `level`
+    ///   matches the line's level. Empty matches every structured line.
+    ///
+    /// # Returns
+    /// * `Some(Vec<LogLine>)` - Matching structured lines; plain lines are left out
+    /// * `None` - Process not found
+    pub async fn get_structured_logs(
+        &self,
+        name: &str,
+        filter: &HashMap<String, String>,
+    ) -> Option<Vec<LogLine>> {
+        let buffer = self.log_buffer(name).await?;
+        let buffer = buffer.lock().await;
+        Some(buffer.filter_by_fields(filter))
+    }
+
+    /// Searches the logs of every managed process.
+    ///
+    /// # Returns
+    /// The matches of each process that has any, ordered by process name.
+    pub async fn search_all_logs(&self, query: &LogQuery) -> Vec<ProcessLogMatches> {
+        let mut results = Vec::new();
+        for (name, handle) in self.handles() {
+            let buffer = handle.lock().await.log_buffer.clone();
+            let (matches, truncated) = buffer.lock().await.query(query);
+            if !matches.is_empty() {
+                results.push(ProcessLogMatches {
+                    process: name,
+                    attachment_id: None,
+                    matches,
+                    truncated,
+                });
+            }
+        }
+        results
+    }
+
+    /// Clears all logs for a specific process.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process
+    ///
+    /// # Returns
+    /// * `Ok(())` - Logs cleared successfully
+    /// * `Err(SentinelError)` - Process not found
+    pub async fn clear_logs(&self, name: &str) -> Result<()> {
+        let buffer = self
+            .log_buffer(name)
+            .await
+            .ok_or_else(|| SentinelError::ProcessNotFound {
+                name: name.to_string(),
+            })?;
+        buffer.lock().await.clear();
+        Ok(())
+    }
+
+    /// The log buffer of a process.
+    async fn log_buffer(&self, name: &str) -> Option<Arc<Mutex<LogBuffer>>> {
+        Some(self.handle(name)?.lock().await.log_buffer.clone())
+    }
+
+    /// Starts scheduled processes whose cron time has come, stops processes
+    /// that have run longer than their `maxRuntimeMs`, and restarts those
+    /// whose `restartPolicy` is due.
+    ///
+    /// Meant to be called periodically with the configured processes; a
+    /// schedule first seen here waits for its next fire time. Every trigger is
+    /// recorded in the event journal, including one skipped because the
+    /// previous run is still going.
+    ///
+    /// # Returns
+    /// The name and start result of every process that was due.
+    pub async fn check_schedules(
+        &self,
+        configs: &[ProcessConfig],
+    ) -> Vec<(String, Result<ProcessInfo>)> {
+        let now = Utc::now();
+        self.stop_overdue(now).await;
+        self.restart_by_policy(now, &Local).await;
+
+        let due: Vec<ProcessConfig> = recover(self.scheduler.lock(), "scheduler")
+            .due(configs, now)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let mut results = Vec::with_capacity(due.len());
+        for config in due {
+            let expression = config.schedule.clone().unwrap_or_default();
+            info!("Schedule '{}' triggered for '{}'", expression, config.name);
+            let mut event = JournalEvent::new(
+                EventType::ScheduleTriggered,
+                &config.name,
+                EventActor::Supervisor,
+            )
+            .with_detail("schedule", &expression);
+            if self.is_running(&config.name).await {
+                event = event.with_detail("skipped", "still running");
+            }
+            self.recorder.record(event);
+
+            let name = config.name.clone();
+            let result = self.start_as(config, EventActor::Supervisor).await;
+            if let Err(e) = &result {
+                warn!("Failed to start scheduled process '{}': {}", name, e);
+            }
+            results.push((name, result));
+        }
+
+        results
+    }
+
+    /// Next time the scheduled process `name` fires, as of the last
+    /// [`Self::check_schedules`].
+    pub fn next_scheduled_run(&self, name: &str) -> Option<DateTime<Utc>> {
+        recover(self.scheduler.lock(), "scheduler").next_run(name)
+    }
+
+    /// Stops running processes that have outlived their `maxRuntimeMs`.
+    async fn stop_overdue(&self, now: DateTime<Utc>) {
+        let mut overdue: Vec<(String, u64)> = Vec::new();
+        for (name, handle) in self.handles() {
+            let handle = handle.lock().await;
+            if !handle.info.is_running() {
+                continue;
+            }
+            let (Some(max_runtime_ms), Some(started_at)) =
+                (handle.config.max_runtime_ms, handle.info.started_at)
+            else {
+                continue;
+            };
+            let runtime_ms = (now - started_at).num_milliseconds();
+            if runtime_ms >= i64::try_from(max_runtime_ms).unwrap_or(i64::MAX) {
+                overdue.push((name, max_runtime_ms));
+            }
+        }
+
+        for (name, max_runtime_ms) in overdue {
+            warn!(
+                "Process '{}' exceeded its maximum runtime ({}ms), stopping",
+                name, max_runtime_ms
+            );
+            self.recorder.record(
+                JournalEvent::new(EventType::MaxRuntimeExceeded, &name, EventActor::Supervisor)
+                    .with_detail("max_runtime_ms", max_runtime_ms),
+            );
+            if let Err(e) = self.stop_as(&name, EventActor::Supervisor).await {
+                warn!("Failed to stop '{}': {}", name, e);
+            }
+        }
+    }
+
+    /// Restarts running processes whose `restartPolicy` is due at `now`.
+    ///
+    /// The process is stopped gracefully and started again with its restart
+    /// count unchanged, so planned restarts don't use up `restartLimit` or
+    /// lengthen the crash backoff. Processes with `warmRestart` are replaced
+    /// by a new instance once it is ready instead.
+    async fn restart_by_policy<Tz: TimeZone>(&self, now: DateTime<Utc>, timezone: &Tz) {
+        let mut due: Vec<(String, PolicyTrigger)> = Vec::new();
+        for (name, handle) in self.handles() {
+            let handle = handle.lock().await;
+            if !handle.info.is_running() || handle.info.is_paused() {
+                continue;
+            }
+            let (Some(policy), Some(started_at)) =
+                (&handle.config.restart_policy, handle.info.started_at)
+            else {
+                continue;
+            };
+            if let Some(trigger) = restart_policy::evaluate(
+                policy,
+                started_at,
+                handle.info.memory_usage,
+                now,
+                timezone,
+            ) {
+                due.push((name, trigger));
+            }
+        }
+
+        for (name, trigger) in due {
+            info!(
+                "Restarting '{}' by its {} restart policy: {}",
+                name,
+                trigger.policy(),
+                trigger.reason()
+            );
+            self.recorder.record(
+                JournalEvent::new(
+                    EventType::RestartPolicyTriggered,
+                    &name,
+                    EventActor::Supervisor,
+                )
+                .with_detail("policy", trigger.policy())
+                .with_detail("reason", trigger.reason()),
+            );
+
+            let reason = RestartReason::Policy {
+                name: trigger.policy().to_string(),
+            };
+            let Some(handle) = self.handle(&name) else {
+                continue;
+            };
+            let (config, restart_count) = {
+                let handle = handle.lock().await;
+                (handle.config.clone(), handle.restart_count)
+            };
+            if config.warm_restart {
+                // Records its own event when it fails
+                let _ = self.warm_restart(&name, reason).await;
+                continue;
+            }
+            if let Err(e) = self.stop_as(&name, EventActor::Supervisor).await {
+                warn!("Failed to stop '{}': {}", name, e);
+                continue;
+            }
+            match self.start_as(config, EventActor::Supervisor).await {
+                Ok(_) => {
+                    if let Some(handle) = self.handle(&name) {
+                        let mut handle = handle.lock().await;
+                        handle.restart_count = restart_count;
+                        handle.info.restart_count = restart_count;
+                    }
+                    self.record_restart(&name, reason).await;
+                }
+                Err(e) => {
+                    error!("Failed to restart '{}' by its restart policy: {}", name, e);
+                    self.recorder.record(
+                        JournalEvent::new(EventType::RestartFailed, &name, EventActor::Supervisor)
+                            .with_detail("error", e),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks health of all processes and restarts crashed ones with auto_restart enabled.
+    ///
+    /// Runs the health checks that are due first, and kills processes that
+    /// fail too many of them in a row when they would be auto-restarted.
+    ///
+    /// Uses exponential backoff for restart delays:
+    /// - First restart: restart_delay ms
+    /// - Second restart: restart_delay * 2 ms
+    /// - Third restart: restart_delay * 4 ms
+    /// - Max: restart_delay * 2^(restart_count)
+    ///
+    /// Returns list of process names that were restarted.
+    pub async fn check_health(&self) -> Vec<String> {
+        self.run_health_checks().await;
+
+        let settings = self.settings();
+        let mut restarted = Vec::new();
+
+        for (name, handle) in self.handles() {
+            let mut guard = handle.lock().await;
+            let should_restart = {
+                let handle = &mut *guard;
+                handle.sync_readiness();
+
+                // Check if process has exited
+                if let Some(child) = &mut handle.child {
+                    match child.try_wait() {
+                        Ok(Some(exit_status)) => {
+                            // Process has exited
+                            let exit_code = exit_status.code().unwrap_or(-1);
+                            let completed = handle.config.is_success_exit(exit_code);
+                            let kind = if completed {
+                                info!("Process '{}' completed with exit code {}", name, exit_code);
+                                handle.info.state = ProcessState::Completed { exit_code };
+                                ProcessEventKind::Completed
+                            } else {
+                                warn!("Process '{}' exited with status: {:?}", name, exit_status);
+                                handle.info.state = ProcessState::Crashed { exit_code };
+                                ProcessEventKind::Crashed
+                            };
+                            handle.info.exit_code = Some(exit_code);
+                            handle.info.pid = None;
+                            handle.info.pgid = None;
+                            handle.info.stopped_at = Some(Utc::now());
+                            handle.child = None;
+                            handle.stdin = None;
+                            handle.readiness = None;
+                            handle.info.ready = false;
+
+                            // Children it leaves behind keep their ports and CPU
+                            let children = std::mem::take(&mut handle.children);
+                            handle.orphans =
+                                self.find_orphans(&children, handle.config.reap_orphans);
+                            let mut event =
+                                JournalEvent::new(kind.into(), &name, EventActor::Supervisor)
+                                    .with_detail("exit_code", exit_code);
+                            if !handle.orphans.is_empty() {
+                                let orphans: Vec<String> =
+                                    handle.orphans.iter().map(ToString::to_string).collect();
+                                let reaped =
+                                    handle.orphans.iter().filter(|orphan| orphan.reaped).count();
+                                warn!(
+                                    "Process '{}' left {} process(es) running: {}",
+                                    name,
+                                    orphans.len(),
+                                    orphans.join(", ")
+                                );
+                                event = event
+                                    .with_detail("orphans", orphans.join(", "))
+                                    .with_detail("orphans_reaped", reaped);
+                            }
+                            self.recorder.record(event);
+                            self.dispatch(ProcessEvent::new(kind, &name, Some(exit_code)));
+
+                            // Check if auto-restart is enabled and limit not exceeded;
+                            // a process that finished its work, like a scheduled run,
+                            // waits to be started again
+                            if handle.config.effective_auto_restart(&settings) && !completed {
+                                if handle.config.restart_limit == 0
+                                    || handle.restart_count < handle.config.restart_limit
+                                {
+                                    true
+                                } else {
+                                    error!(
+                                        "Process '{}' exceeded restart limit ({})",
+                                        name, handle.config.restart_limit
+                                    );
+                                    self.recorder.record(
+                                        JournalEvent::new(
+                                            EventType::RestartLimitExceeded,
+                                            &name,
+                                            EventActor::Supervisor,
+                                        )
+                                        .with_detail("restart_limit", handle.config.restart_limit),
+                                    );
+                                    self.dispatch(ProcessEvent::new(
+                                        ProcessEventKind::RestartLimitExceeded,
+                                        &name,
+                                        Some(exit_code),
+                                    ));
+                                    false
+                                }
+                            } else {
+                                false
+                            }
+                        }
+                        Ok(None) => {
+                            // Process still running; a paused one is not
+                            // reported, as only an exit counts as a crash
+                            false
+                        }
+                        Err(e) => {
+                            error!("Error checking process '{}' status: {}", name, e);
+                            self.recorder.record(
+                                JournalEvent::new(
+                                    EventType::HealthCheckFailed,
+                                    &name,
+                                    EventActor::Supervisor,
+                                )
+                                .with_detail("error", e),
+                            );
+                            false
+                        }
+                    }
+                } else {
+                    false
+                }
+            };
+
+            if should_restart {
+                // Calculate exponential backoff delay
+                let base_delay = guard.config.effective_restart_delay(&settings);
+                let backoff_multiplier = 2_u64.pow(guard.restart_count);
+                let delay_ms = base_delay.saturating_mul(backoff_multiplier);
+
+                info!(
+                    "Auto-restarting process '{}' (attempt {}) after {}ms",
+                    name,
+                    guard.restart_count + 1,
+                    delay_ms
+                );
+
+                // Get config and increment restart counter
+                let config = guard.config.clone();
+                let restart_count = guard.restart_count;
+                let exit_code = guard.info.exit_code.unwrap_or(-1);
+                let orphans = guard.orphans.clone();
+                drop(guard);
+
+                // Wait with exponential backoff
+                sleep(Duration::from_millis(delay_ms)).await;
+                let last_restart = Some(std::time::Instant::now());
+
+                // Try to restart
+                match self.start_as(config, EventActor::Supervisor).await {
+                    Ok(_) => {
+                        // Update restart tracking
+                        if let Some(handle) = self.handle(&name) {
+                            let mut handle = handle.lock().await;
+                            handle.restart_count = restart_count + 1;
+                            handle.last_restart = last_restart;
+                            handle.info.restart_count = restart_count + 1;
+                            handle.orphans = orphans;
+                        }
+                        self.restarts_total.fetch_add(1, Ordering::Relaxed);
+                        self.record_restart(&name, RestartReason::CrashAutoRestart { exit_code })
+                            .await;
+                        self.dispatch(ProcessEvent::new(ProcessEventKind::Restarted, &name, None));
+                        restarted.push(name.clone());
+                    }
+                    Err(e) => {
+                        error!("Failed to auto-restart process '{}': {}", name, e);
+                        self.recorder.record(
+                            JournalEvent::new(
+                                EventType::RestartFailed,
+                                &name,
+                                EventActor::Supervisor,
+                            )
+                            .with_detail("error", e),
+                        );
+                    }
+                }
+            }
+        }
+
+        restarted
+    }
+
+    /// The processes still running from `children`, the child set of a
+    /// process that exited, and their descendants; killed with `reap`.
+    fn find_orphans(&self, children: &HashMap<u32, u64>, reap: bool) -> Vec<OrphanInfo> {
+        if children.is_empty() {
+            return Vec::new();
+        }
+
+        let mut table = recover(self.resources.lock(), "process table");
+        let system = table.refresh_links();
+        process_tree::orphans(system, children)
+            .into_iter()
+            .map(|entry| {
+                let reaped = reap
+                    && system
+                        .process(Pid::from_u32(entry.pid))
+                        .is_some_and(|process| process.kill());
+                if reaped {
+                    info!("Killed orphaned process {} ({})", entry.name, entry.pid);
+                }
+                OrphanInfo {
+                    pid: entry.pid,
+                    parent_pid: entry.parent_pid,
+                    name: entry.name,
+                    start_time: entry.start_time,
+                    reaped,
+                }
+            })
+            .collect()
+    }
+
+    /// Processes left running by the last crash of a process that are
+    /// still alive, including any they started since.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` if no process has this name.
+    pub async fn orphaned_children(&self, name: &str) -> Result<Vec<OrphanInfo>> {
+        let known: HashMap<u32, u64> = self
+            .find(name)?
+            .lock()
+            .await
+            .orphans
+            .iter()
+            .map(|orphan| (orphan.pid, orphan.start_time))
+            .collect();
+        Ok(self.find_orphans(&known, false))
+    }
+
+    /// Runs the due health checks of running processes, all at once.
+    ///
+    /// A process that fails more than `retries` checks in a row is killed if
+    /// it would be auto-restarted, so it's restarted like a crashed one.
+    async fn run_health_checks(&self) {
+        let settings = self.settings();
+        let now = std::time::Instant::now();
+        let mut due: Vec<(String, HealthCheck, Option<PathBuf>)> = Vec::new();
+        for (name, handle) in self.handles() {
+            let mut handle = handle.lock().await;
+            // A paused process can't answer; it isn't unhealthy
+            if !handle.info.is_running()
+                || handle.info.is_paused()
+                || handle.next_health_check.is_none_or(|at| at > now)
+            {
+                continue;
+            }
+            let Some(health_check) = handle.config.health_check.clone() else {
+                continue;
+            };
+            let interval = health_check.effective_interval_ms(&settings);
+            handle.next_health_check = Some(now + Duration::from_millis(interval));
+            due.push((name, health_check, handle.cwd.clone()));
+        }
+
+        let results = join_all(
+            due.iter()
+                .map(|(_, health_check, cwd)| health_check::run(health_check, cwd.as_deref())),
+        )
+        .await;
+
+        for ((name, health_check, _), mut result) in due.into_iter().zip(results) {
+            let Some(handle) = self.handle(&name) else {
+                continue;
+            };
+            let mut handle = handle.lock().await;
+
+            if !result.healthy {
+                result.consecutive_failures += handle
+                    .info
+                    .health
+                    .as_ref()
+                    .map_or(0, |health| health.consecutive_failures);
+            }
+            let failures = result.consecutive_failures;
+            let error = result.error.clone().unwrap_or_default();
+            handle.info.health = Some(result);
+
+            if failures != health_check.retries + 1 {
+                continue;
+            }
+
+            warn!(
+                "Process '{}' failed {} health checks in a row: {}",
+                name, failures, error
+            );
+            self.recorder.record(
+                JournalEvent::new(EventType::HealthCheckFailed, &name, EventActor::Supervisor)
+                    .with_detail("error", &error)
+                    .with_detail("failures", failures),
+            );
+            if handle.config.effective_auto_restart(&settings) {
+                if let Some(child) = &mut handle.child {
+                    let _ = child.kill().await;
+                }
+            }
+        }
+    }
+
+    /// Number of crashed processes restarted by [`Self::check_health`].
+    pub fn restarts_total(&self) -> u64 {
+        self.restarts_total.load(Ordering::Relaxed)
+    }
+
+    /// Gracefully stops a process with timeout and force kill fallback.
+    ///
+    /// On Unix: Sends SIGTERM, waits 5 seconds, then sends SIGKILL if needed.
+    /// On Windows: Terminates the process after 5 second timeout.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the process to stop
+    ///
+    /// # Returns
+    /// * `Ok(())` - Process stopped
+    /// * `Err(SentinelError)` - Process not found or error occurred
+    pub async fn stop_gracefully(&self, name: &str) -> Result<()> {
+        let handle = self.find(name)?;
+        let Some((child, info)) = begin_stop(&handle).await else {
+            return Ok(());
+        };
+
+        info!("Gracefully stopping process: {}", name);
+        let mut status = None;
+        if let Some(mut child) = child {
+            #[cfg(unix)]
+            {
+                // Send SIGTERM for graceful shutdown
+                if let Some(pid) = child.id() {
+                    debug!("Sending SIGTERM to process '{}' (PID: {})", name, pid);
+                    unsafe {
+                        libc::kill(pid as i32, libc::SIGTERM);
+                    }
+                    // A stopped process only handles SIGTERM once continued
+                    if info.is_paused() {
+                        let _ = self.suspend_tree(name, pid, info.pgid, false);
+                    }
+                }
+
+                // Wait up to 5 seconds for graceful shutdown
+                let graceful_timeout = Duration::from_secs(5);
+                match tokio::time::timeout(graceful_timeout, child.wait()).await {
+                    Ok(Ok(exit_status)) => {
+                        debug!(
+                            "Process '{}' gracefully exited with status: {:?}",
+                            name, exit_status
+                        );
+                        status = Some(exit_status);
+                    }
+                    Ok(Err(e)) => {
+                        warn!("Error waiting for process '{}': {}", name, e);
+                    }
+                    Err(_) => {
+                        warn!(
+                            "Process '{}' did not stop gracefully, sending SIGKILL",
+                            name
+                        );
+                        if let Some(pid) = child.id() {
+                            unsafe {
+                                libc::kill(pid as i32, libc::SIGKILL);
+                            }
+                        }
+                        let _ = child.wait().await;
+                    }
+                }
+            }
+
+            #[cfg(not(unix))]
+            {
+                // Windows: just kill with timeout
+                let timeout = Duration::from_secs(5);
+                match tokio::time::timeout(timeout, child.wait()).await {
+                    Ok(Ok(exit_status)) => {
+                        debug!("Process '{}' exited with status: {:?}", name, exit_status);
+                        status = Some(exit_status);
+                    }
+                    Ok(Err(e)) => {
+                        warn!("Error waiting for process '{}': {}", name, e);
+                    }
+                    Err(_) => {
+                        warn!(
+                            "Process '{}' did not stop within timeout, force killing",
+                            name
+                        );
+                        let _ = child.kill().await;
+                    }
+                }
+            }
+        }
+
+        finish_stop(&handle, status).await;
+        self.notify(ProcessEventKind::Stopped, name, None, EventActor::User);
+
+        Ok(())
+    }
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shortest time between two refreshes of the process table; resource
+/// updates in between reuse the latest one. Well above
+/// [`MIN_CPU_SAMPLE_INTERVAL`](crate::core::MIN_CPU_SAMPLE_INTERVAL), so CPU
+/// usage is always measured over a long enough interval.
+pub const RESOURCE_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The process tables resource usage is sampled from.
+struct ResourceTable {
+    /// Kept between refreshes so CPU usage is measured over the interval
+    /// since the last one.
+    system: System,
+    /// Parent links of every process. Kept apart from `system`, as
+    /// refreshing a process there restarts the measurement of its CPU usage.
+    links: System,
+    /// Processes whose CPU usage the latest refresh of `system` measured.
+    measured: HashSet<Pid>,
+    refreshed_at: Option<Instant>,
+    /// Whether the latest refresh covered every process.
+    whole: bool,
+}
+
+impl ResourceTable {
+    fn new() -> Self {
+        Self {
+            system: System::new(),
+            links: System::new(),
+            measured: HashSet::new(),
+            refreshed_at: None,
+            whole: false,
+        }
+    }
+
+    /// Refreshes `pids`, or every process for `None`, unless the latest
+    /// refresh covered them less than [`RESOURCE_REFRESH_INTERVAL`] ago.
+    ///
+    /// # Returns
+    /// Whether the table was refreshed.
+    fn refresh(&mut self, pids: Option<&[Pid]>) -> bool {
+        let covered = self.whole || pids.is_some();
+        if covered
+            && self
+                .refreshed_at
+                .is_some_and(|at| at.elapsed() < RESOURCE_REFRESH_INTERVAL)
+        {
+            return false;
+        }
+
+        // Processes sampled before; sysinfo reports 0% CPU for the others
+        self.measured = self.system.processes().keys().copied().collect();
+        match pids {
+            None => self.system.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::nothing().with_cpu().with_memory(),
+            ),
+            Some(pids) => self.system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(pids),
+                true,
+                ProcessRefreshKind::everything(),
+            ),
+        };
+        self.refreshed_at = Some(Instant::now());
+        self.whole = pids.is_none();
+        true
+    }
+
+    /// Refreshes the parent links of every process.
+    fn refresh_links(&mut self) -> &System {
+        self.links.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        &self.links
+    }
+}
+
+/// Resource usage of one process, sampled from the process table.
+struct ResourceSample {
+    pid: u32,
+    /// CPU usage (when measured), memory and priority, if the process was found.
+    usage: Option<(Option<f32>, u64, Option<i32>)>,
+    /// Open file descriptors and threads, counted when the process was found.
+    handles: HandleCounts,
+    cpu_usage_tree: Option<f32>,
+    memory_usage_tree: Option<u64>,
+    /// Descendants by PID, with their start times.
+    children: HashMap<u32, u64>,
+}
+
+impl ResourceSample {
+    /// Samples `pid`, whose CPU usage is only `measured` if it was in the
+    /// table before the latest refresh, and its descendants in `index`.
+    fn take(
+        system: &System,
+        index: &ProcessIndex,
+        pid: u32,
+        measured: bool,
+        aggregate: bool,
+    ) -> Self {
+        let usage = system.process(Pid::from_u32(pid)).map(|process| {
+            (
+                measured.then(|| process.cpu_usage()),
+                process.memory(),
+                scheduling::priority(pid),
+            )
+        });
+
+        let handles = if usage.is_some() {
+            process_handles::sample(pid)
+        } else {
+            HandleCounts::default()
+        };
+
+        let tree = aggregate.then(|| index.tree(pid)).flatten();
+        Self {
+            pid,
+            usage,
+            handles,
+            cpu_usage_tree: tree
+                .as_ref()
+                .filter(|_| measured)
+                .map(ProcessTreeNode::total_cpu_usage),
+            memory_usage_tree: tree.as_ref().map(ProcessTreeNode::total_memory),
+            children: index
+                .descendants(&[pid])
+                .into_iter()
+                .map(|entry| (entry.pid, entry.start_time))
+                .collect(),
+        }
+    }
+
+    fn apply(self, info: &mut ProcessInfo, core_count: usize) {
+        if let Some((cpu, memory, nice)) = self.usage {
+            info.set_cpu_usage(cpu, core_count);
+            // Memory usage in bytes
+            info.memory_usage = memory;
+            info.nice = nice;
+            info.open_fds = self.handles.open_fds;
+            info.thread_count = self.handles.threads;
+        }
+        info.cpu_usage_tree = self.cpu_usage_tree;
+        info.memory_usage_tree = self.memory_usage_tree;
+    }
+}
+
+/// Shortest time between two samples of a process's usage history.
+const HISTORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The latest resource usage samples of a process.
+struct UsageHistory {
+    samples: MetricsBuffer<UsageSample>,
+}
+
+impl UsageHistory {
+    fn new() -> Self {
+        Self {
+            samples: MetricsBuffer::new(PROCESS_HISTORY_LEN),
+        }
+    }
+
+    /// Records a sample unless the previous one is too recent.
+    fn record(&mut self, sample: UsageSample) {
+        let min_interval = chrono::Duration::from_std(HISTORY_SAMPLE_INTERVAL).unwrap_or_default();
+        let recent = self
+            .samples
+            .get_last_n(1)
+            .first()
+            .is_some_and(|last| Utc::now() - last.timestamp < min_interval);
+        if !recent {
+            self.samples.push(sample);
+        }
+    }
+
+    fn snapshot(&self) -> ProcessHistory {
+        let samples = self.samples.get_all();
+        let interval_ms = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) if samples.len() > 1 => {
+                let span = (last.timestamp - first.timestamp).num_milliseconds().max(0) as u64;
+                span / (samples.len() as u64 - 1)
+            }
+            _ => HISTORY_SAMPLE_INTERVAL.as_millis() as u64,
+        };
+        let values: Vec<_> = samples.into_iter().map(|sample| sample.value).collect();
+        ProcessHistory::from_samples(interval_ms, &values)
+    }
+}
+
+/// Spawns the process of a validated `config`, with its log readers writing
+/// to `log_buffer`.
+///
+/// The handle isn't added to the process map; the caller decides what name
+/// it goes under.
+fn spawn_process(
+    config: ProcessConfig,
+    settings: &GlobalSettings,
+    log_buffer: Arc<Mutex<LogBuffer>>,
+    logs: LogPublisher,
+    log_writer: LogWriter,
+) -> Result<ProcessHandle> {
+    let name = config.name.clone();
+
+    // Resolved the same way as a preview, so the two can't disagree
+    let launch = launch::resolve(&config, settings)?;
+    let mut cmd = Command::new(launch.program());
+    cmd.args(launch.args());
+    if let Some(cwd) = &launch.cwd {
+        cmd.current_dir(cwd);
+    }
+    cmd.env_clear();
+    cmd.envs(&launch.env);
+
+    // Apply priority and affinity in the child before it execs
+    #[cfg(unix)]
+    if config.nice.is_some() || config.cpu_affinity.is_some() {
+        let nice = config.nice;
+        let cores = config.cpu_affinity.clone();
+        // SAFETY: apply_to_self only makes async-signal-safe system calls
+        unsafe {
+            cmd.pre_exec(move || scheduling::apply_to_self(nice, cores.as_deref()));
+        }
+    }
+
+    // Configure stdio
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.stdin(match config.stdin_mode {
+        StdinMode::Null => Stdio::null(),
+        StdinMode::Piped => Stdio::piped(),
+    });
+
+    // Probe readiness from the moment of spawning
+    let started = std::time::Instant::now();
+    let (readiness, log_watch) = match &config.readiness {
+        Some(probe) => {
+            let (readiness, log_watch) = readiness::spawn(probe, launch.cwd.clone(), started)?;
+            (Some(readiness), log_watch)
+        }
+        None => (None, None),
+    };
+
+    // Spawn process
+    let mut child = cmd.spawn().map_err(|source| SentinelError::SpawnFailed {
+        name: name.clone(),
+        source,
+    })?;
+
+    let pid = child.id().unwrap_or(0);
+
+    debug!("Process '{}' spawned with PID {}", name, pid);
+
+    // What actually runs, as the configured command may be a bare name
+    let resolved_cwd = launch.resolved_cwd();
+    let exe_path = executable::resolve(
+        pid,
+        launch.program(),
+        resolved_cwd.as_deref(),
+        launch.path(),
+    );
+
+    #[cfg(not(unix))]
+    if let Err(e) = scheduling::apply(pid, config.nice, config.cpu_affinity.as_deref()) {
+        warn!("Failed to apply scheduling to process '{}': {}", name, e);
+    }
+
+    // Spawn log reader tasks for stdout and stderr
+    let max_line_bytes = (settings.max_log_line_bytes > 0)
+        .then(|| usize::try_from(settings.max_log_line_bytes).unwrap_or(usize::MAX));
+    let decoder = LineDecoder::new(config.output_encoding, max_line_bytes);
+    let output = LogOutput {
+        buffer: log_buffer.clone(),
+        logs,
+        writer: log_writer,
+    };
+    let mut log_readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let decoder = decoder.clone();
+        let log_format = config.log_format.clone();
+        let output = output.clone();
+        let log_watch = log_watch.clone();
+        log_readers.push(tokio::spawn(async move {
+            read_stream(
+                stdout,
+                decoder,
+                &log_format,
+                output,
+                log_watch,
+                LogStream::Stdout,
+            )
+            .await;
+        }));
+    }
+
+    if let Some(stderr) = child.stderr.take() {
+        let log_format = config.log_format.clone();
+        log_readers.push(tokio::spawn(async move {
+            read_stream(
+                stderr,
+                decoder,
+                &log_format,
+                output,
+                log_watch,
+                LogStream::Stderr,
+            )
+            .await;
+        }));
+    }
+
+    let stdin = child
+        .stdin
+        .take()
+        .map(|pipe| spawn_stdin_writer(pipe, name.clone()));
+
+    // Create process info
+    let info = ProcessInfo {
+        name: name.clone(),
+        state: ProcessState::Running,
+        pid: Some(pid),
+        command: config.command.clone(),
+        cwd: config.cwd.as_ref().map(|p| p.display().to_string()),
+        cpu_usage: 0.0,
+        cpu_usage_percent_of_core: None,
+        cpu_usage_percent_of_total: None,
+        memory_usage: 0,
+        cpu_usage_tree: None,
+        memory_usage_tree: None,
+        nice: scheduling::priority(pid),
+        open_fds: None,
+        thread_count: None,
+        restart_count: 0,
+        started_at: Some(Utc::now()),
+        stopped_at: None,
+        exit_code: None,
+        tags: config.tags.clone(),
+        ready: readiness.is_none(),
+        time_to_ready_ms: None,
+        health: None,
+        exe_path: exe_path.map(|exe| exe.display().to_string()),
+        argv: launch.argv.clone(),
+        resolved_cwd: resolved_cwd.map(|cwd| cwd.display().to_string()),
+        pgid: executable::process_group(pid),
+        port: launch
+            .env
+            .get(OsStr::new("PORT"))
+            .and_then(|port| port.to_str()?.parse().ok()),
+        history: None,
+        usage_source: UsageSource::Process,
+        container: None,
+        last_restart_reason: None,
+        last_restart_at: None,
+    };
+
+    let next_health_check = config.health_check.as_ref().map(|health_check| {
+        started + Duration::from_millis(health_check.effective_interval_ms(settings))
+    });
+
+    Ok(ProcessHandle {
+        info,
+        child: Some(child),
+        config,
+        log_buffer,
+        stdin,
+        readiness,
+        cwd: launch.cwd,
+        next_health_check,
+        restart_count: 0,
+        last_restart: None,
+        history: UsageHistory::new(),
+        container_usage: None,
+        children: HashMap::new(),
+        orphans: Vec::new(),
+        log_readers,
+    })
+}
+
+/// Moves `config` to `port`: sets `PORT`, and points a readiness probe or
+/// health check on the old `PORT` at the new one.
+fn move_port(config: &mut ProcessConfig, port: u16) {
+    let old = config
+        .env
+        .insert("PORT".to_string(), port.to_string())
+        .and_then(|old| old.parse::<u16>().ok());
+    let Some(old) = old else {
+        return;
+    };
+    if let Some(probe) = config.readiness.as_mut() {
+        match &mut probe.check {
+            ReadinessCheck::Tcp {
+                port: probe_port, ..
+            } if *probe_port == old => *probe_port = port,
+            ReadinessCheck::Http { url, .. } => move_url_port(url, old, port),
+            _ => {}
+        }
+    }
+    if let Some(health_check) = config.health_check.as_mut() {
+        match &mut health_check.check {
+            HealthCheckKind::Tcp {
+                port: check_port, ..
+            } if *check_port == old => *check_port = port,
+            HealthCheckKind::Http { url, .. } => move_url_port(url, old, port),
+            _ => {}
+        }
+    }
+}
+
+/// Replaces the port `old` in `url` with `new`.
+fn move_url_port(url: &mut String, old: u16, new: u16) {
+    let from = format!(":{}", old);
+    let at = url
+        .find(&from)
+        .filter(|at| !url[at + from.len()..].starts_with(|c: char| c.is_ascii_digit()));
+    if let Some(at) = at {
+        url.replace_range(at..at + from.len(), &format!(":{}", new));
+    }
+}
+
+/// Waits for a warm restart's new instance to become ready.
+///
+/// Fails as soon as the instance exits rather than waiting for its probe to
+/// give up. Without a probe it is ready once it has run for `grace`.
+async fn wait_for_standby(name: &str, standby: &mut ProcessHandle, grace: Duration) -> Result<()> {
+    // The probe gives up on its own after maxWaitMs
+    let probe = standby.readiness.clone().zip(
+        standby
+            .config
+            .readiness
+            .as_ref()
+            .map(|probe| Duration::from_millis(probe.max_wait_ms) + Duration::from_secs(1)),
+    );
+    let Some(child) = standby.child.as_mut() else {
+        return Ok(());
+    };
+    let ready = async move {
+        match probe {
+            Some((readiness, wait)) => readiness::wait(name, readiness, wait).await.map(|_| ()),
+            None => {
+                sleep(grace).await;
+                Ok(())
+            }
+        }
+    };
+
+    tokio::select! {
+        ready = ready => ready,
+        status = child.wait() => Err(SentinelError::Other(format!(
+            "New instance of '{}' exited before becoming ready ({})",
+            name,
+            status.map_or_else(|e| e.to_string(), |status| status.to_string())
+        ))),
+    }
+}
+
+/// Whether `pid` or one of its descendants listens on `port`; `None` if the
+/// ports can't be scanned.
+async fn listens_on(pid: u32, port: u16) -> Option<bool> {
+    let connections = match PortScanner::new().scan().await {
+        Ok(connections) => connections,
+        Err(e) => {
+            debug!("Failed to scan the ports of process {}: {}", pid, e);
+            return None;
+        }
+    };
+    let ports = port_discovery::listening_ports_by_root(&connections, &[pid]);
+    Some(ports.get(&pid).is_some_and(|ports| ports.contains(&port)))
+}
+
+/// Marks a running process as stopping and takes its child, so the caller
+/// can wait for it to exit without holding the process's lock.
+///
+/// A stop already in progress is waited for first, for up to
+/// [`STOP_TIMEOUT`] in case its caller gave up on it.
+///
+/// # Returns
+/// The child and the process's info from before, or `None` if it isn't
+/// running.
+async fn begin_stop(handle: &Mutex<ProcessHandle>) -> Option<(Option<Child>, ProcessInfo)> {
+    let deadline = tokio::time::Instant::now() + STOP_TIMEOUT;
+    loop {
+        let mut handle = handle.lock().await;
+        if matches!(handle.info.state, ProcessState::Stopping)
+            && tokio::time::Instant::now() < deadline
+        {
+            drop(handle);
+            sleep(STOP_POLL).await;
+            continue;
+        }
+        if !handle.info.is_running() {
+            return None;
+        }
+
+        let info = handle.info.clone();
+        handle.info.state = ProcessState::Stopping;
+        handle.stdin = None;
+        handle.readiness = None;
+        handle.info.ready = false;
+        return Some((handle.child.take(), info));
+    }
+}
+
+/// Marks a process whose child was taken by [`begin_stop`] as stopped, with
+/// the child's exit `status` if it exited on its own.
+async fn finish_stop(handle: &Mutex<ProcessHandle>, status: Option<ExitStatus>) {
+    let mut handle = handle.lock().await;
+    if let Some(status) = status {
+        handle.info.exit_code = status.code();
+    }
+    handle.info.state = ProcessState::Stopped;
+    handle.info.pid = None;
+    handle.info.pgid = None;
+    handle.info.stopped_at = Some(Utc::now());
+}
+
+/// Takes the guard of a poisoned lock anyway; whoever panicked while holding
+/// it left the data consistent enough to keep going.
+fn recover<G>(result: LockResult<G>, what: &str) -> G {
+    result.unwrap_or_else(|e| {
+        error!("Failed to lock {}: {}", what, e);
+        e.into_inner()
+    })
+}
+
+/// Spawns a task that writes queued input to a child's stdin.
+///
+/// Each message is written whole before the next, and the pipe is closed
+/// once every sender is dropped.
+fn spawn_stdin_writer(
+    mut stdin: ChildStdin,
+    process_name: String,
+) -> mpsc::UnboundedSender<Vec<u8>> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+
+    tokio::spawn(async move {
+        while let Some(data) = receiver.recv().await {
+            let written = match stdin.write_all(&data).await {
+                Ok(()) => stdin.flush().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
+                warn!("Failed to write to stdin of '{}': {}", process_name, e);
+                break;
+            }
+        }
+        debug!("Stdin closed for process: {}", process_name);
+    });
+
+    sender
+}
+
+/// Maps a signal name, with or without the `SIG` prefix, to its number.
+#[cfg(unix)]
+fn signal_number(signal: &str) -> Option<i32> {
+    let signal = signal.trim().to_ascii_uppercase();
+    let number = match signal.strip_prefix("SIG").unwrap_or(&signal) {
+        "HUP" => libc::SIGHUP,
+        "INT" => libc::SIGINT,
+        "QUIT" => libc::SIGQUIT,
+        "KILL" => libc::SIGKILL,
+        "TERM" => libc::SIGTERM,
+        "USR1" => libc::SIGUSR1,
+        "USR2" => libc::SIGUSR2,
+        "STOP" => libc::SIGSTOP,
+        "CONT" => libc::SIGCONT,
+        "TSTP" => libc::SIGTSTP,
+        "WINCH" => libc::SIGWINCH,
+        _ => return None,
+    };
+    Some(number)
+}
+
+/// Sets `ready` and `time_to_ready_ms` from a readiness probe's latest result.
+///
+/// Without a probe `info` is left as is.
+fn apply_readiness(info: &mut ProcessInfo, readiness: Option<&watch::Receiver<Readiness>>) {
+    let Some(readiness) = readiness else {
+        return;
+    };
+
+    match *readiness.borrow() {
+        Readiness::Ready { after_ms } => {
+            info.ready = true;
+            info.time_to_ready_ms = Some(after_ms);
+        }
+        Readiness::Probing | Readiness::GaveUp { .. } => info.ready = false,
+    }
+}
+
+/// How long a dependency with `waitForCompletion` and no `maxRuntimeMs` is
+/// waited for.
+const DEFAULT_COMPLETION_WAIT: Duration = Duration::from_secs(600);
+
+/// How often a dependency with `waitForCompletion` is checked for having
+/// completed.
+const COMPLETION_POLL: Duration = Duration::from_millis(100);
+
+/// How long a stopped process gets to exit before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a stop checks whether another stop of the process has finished.
+const STOP_POLL: Duration = Duration::from_millis(50);
+
+/// How often a starting process is checked for having exited.
+const EARLY_EXIT_POLL: Duration = Duration::from_millis(25);
+
+/// How long the output of a process that failed while starting is waited for.
+const EARLY_EXIT_OUTPUT_WAIT: Duration = Duration::from_millis(500);
+
+/// Lines kept from each end of the output of a process that failed while
+/// starting.
+const EXCERPT_LINES: usize = 10;
+
+/// The stderr of a process that failed while starting, or its stdout if it
+/// wrote nothing to stderr, with the middle left out when it's long.
+///
+/// Both ends are kept: Node prints the error first and a stack after it,
+/// Python a traceback first and the error last.
+fn output_excerpt(lines: &[LogLine]) -> String {
+    let stderr: Vec<&str> = lines
+        .iter()
+        .filter(|line| line.stream == LogStream::Stderr)
+        .map(|line| line.line.as_str())
+        .collect();
+    let lines = match stderr.is_empty() {
+        true => lines.iter().map(|line| line.line.as_str()).collect(),
+        false => stderr,
+    };
+
+    if lines.len() <= EXCERPT_LINES * 2 {
+        return lines.join("\n");
+    }
+    let omitted = lines.len() - EXCERPT_LINES * 2;
+    format!(
+        "{}\n... {} more lines ...\n{}",
+        lines[..EXCERPT_LINES].join("\n"),
+        omitted,
+        lines[lines.len() - EXCERPT_LINES..].join("\n")
+    )
+}
+
+/// Where the lines read from a process's output go.
+#[derive(Clone)]
+struct LogOutput {
+    /// Shared log buffer
+    buffer: Arc<Mutex<LogBuffer>>,
+    /// Publishes the new lines to live subscribers, under the process's name
+    logs: LogPublisher,
+    /// Persists the lines to the process's log file
+    writer: LogWriter,
+}
+
+/// Bytes read from a process stream at a time.
+const READ_CHUNK_BYTES: usize = 8 * 1024;
 
 /// Asynchronously reads lines from a process stream (stdout/stderr).
 ///
-/// Pushes log lines to the shared buffer. Runs until stream closes.
+/// Pushes log lines to the shared buffer. The lines completed by one read
+/// are pushed together under one lock, so the other stream can't slip
+/// between them. Output that isn't valid in its encoding is decoded
+/// lossily rather than ending the reader, and JSON lines are parsed when
+/// the process has a JSON log format. Runs until stream closes.
 ///
 /// # Arguments
 /// * `stream` - The stdout or stderr stream from the child process
-/// * `buffer` - Shared log buffer (Arc<Mutex<LogBuffer>>)
+/// * `decoder` - Splits the output into lines in the process's encoding
+/// * `log_format` - How the process formats its log lines
+/// * `output` - Where the lines go
+/// * `log_watch` - Log readiness probe to check each line against, if any
 /// * `stream_type` - Whether this is stdout or stderr
-/// * `process_name` - Name of the process for logging
 async fn read_stream<R>(
-    stream: R,
-    buffer: Arc<Mutex<LogBuffer>>,
+    mut stream: R,
+    mut decoder: LineDecoder,
+    log_format: &LogFormat,
+    output: LogOutput,
+    log_watch: Option<Arc<LogWatch>>,
     stream_type: LogStream,
-    process_name: &str,
 ) where
     R: tokio::io::AsyncRead + Unpin,
 {
-    let reader = BufReader::new(stream);
-    let mut lines = reader.lines();
-
-    while let Ok(Some(line)) = lines.next_line().await {
-        let log_line = LogLine {
-            timestamp: Utc::now(),
-            stream: stream_type,
-            line,
+    let mut chunk = vec![0u8; READ_CHUNK_BYTES];
+    loop {
+        let (batch, done) = match stream.read(&mut chunk).await {
+            Ok(0) => (decoder.finish().into_iter().collect(), true),
+            Ok(n) => (decoder.feed(&chunk[..n]), false),
+            Err(e) => {
+                warn!(
+                    "Failed to read {:?} of process {}: {}",
+                    stream_type,
+                    output.logs.process(),
+                    e
+                );
+                (decoder.finish().into_iter().collect(), true)
+            }
+        };
+
+        if !batch.is_empty() {
+            if let Some(log_watch) = &log_watch {
+                for line in &batch {
+                    log_watch.check(line);
+                }
+            }
+
+            // Stamped under the lock so times rise with sequence numbers
+            let mut buf = output.buffer.lock().await;
+            let received = Utc::now();
+            let count = batch.len();
+            output
+                .writer
+                .write(output.logs.process(), received, stream_type, &batch);
+            buf.push_all(
+                batch
+                    .into_iter()
+                    .map(|line| LogLine::new(received, stream_type, line).parsed(log_format)),
+            );
+            // Read back so subscribers see the sequence numbers
+            output.logs.publish(|| buf.get_last_n(count));
+        }
+        if done {
+            break;
+        }
+    }
+
+    debug!(
+        "Log stream ({:?}) closed for process: {}",
+        stream_type,
+        output.logs.process()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::child_env;
+    use crate::core::log_buffer::LogMemoryStats;
+    use crate::core::log_search::LogSearchOptions;
+    use crate::core::log_timestamp::{self, TimestampOrder};
+    use crate::models::{EnvMode, HealthCheckKind, LogFormat, OutputEncoding, ReadinessProbe};
+
+    fn test_config(name: &str, command: &str) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            auto_restart: Some(false),
+            restart_limit: 0,
+            restart_delay: Some(100),
+            ..Default::default()
+        }
+    }
+
+    /// A manager whose `start` returns at once, for tests of what happens
+    /// once a running process exits
+    fn without_start_grace(settings: GlobalSettings) -> ProcessManager {
+        ProcessManager::with_settings(GlobalSettings {
+            start_grace_ms: 0,
+            ..settings
+        })
+    }
+
+    #[tokio::test]
+    async fn test_start_process() {
+        let manager = without_start_grace(GlobalSettings::default());
+        let config = test_config("test", "echo hello");
+
+        let info = manager.start(config).await.unwrap();
+        assert_eq!(info.name, "test");
+        assert_eq!(info.state, ProcessState::Running);
+        assert!(info.pid.is_some());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_start_waits_for_early_exit() {
+        let manager = ProcessManager::new();
+
+        // Still running at the end of the wait
+        let info = manager
+            .start(test_config("server", "sleep 10"))
+            .await
+            .unwrap();
+        assert_eq!(info.state, ProcessState::Running);
+
+        // Finished successfully within it
+        let mut config = test_config("script", "sh");
+        config.args = vec!["-c".to_string(), "echo done".to_string()];
+        let info = manager.start(config).await.unwrap();
+        assert_eq!(info.state, ProcessState::Completed { exit_code: 0 });
+        assert_eq!(info.exit_code, Some(0));
+        assert_eq!(info.pid, None);
+
+        // Failed within it, as node does without its dependencies
+        let mut config = test_config("api", "sh");
+        let script = concat!(
+            "echo 'node:internal/modules/cjs/loader:1080' >&2; ",
+            "echo \"Error: Cannot find module 'express'\" >&2; ",
+            "exit 1"
+        );
+        config.args = vec!["-c".to_string(), script.to_string()];
+        config.auto_restart = Some(true);
+        match manager.start(config).await {
+            Err(SentinelError::StartFailedEarly {
+                name,
+                exit_code,
+                stderr_excerpt,
+            }) => {
+                assert_eq!(name, "api");
+                assert_eq!(exit_code, 1);
+                assert_eq!(
+                    stderr_excerpt,
+                    "node:internal/modules/cjs/loader:1080\nError: Cannot find module 'express'"
+                );
+            }
+            other => panic!("expected StartFailedEarly, got {:?}", other),
+        }
+        let info = manager.get("api").await.unwrap();
+        assert_eq!(info.state, ProcessState::Crashed { exit_code: 1 });
+        // Failing to start isn't a crash to restart from
+        assert!(manager.check_health().await.is_empty());
+    }
+
+    #[test]
+    fn test_output_excerpt() {
+        let line = |stream, text: String| LogLine::new(Utc::now(), stream, text);
+        let mut lines = vec![line(LogStream::Stdout, "starting".to_string())];
+        assert_eq!(output_excerpt(&lines), "starting");
+
+        lines.push(line(LogStream::Stderr, "Traceback:".to_string()));
+        lines.extend((1..=30).map(|i| line(LogStream::Stderr, format!("frame {}", i))));
+        lines.push(line(LogStream::Stderr, "ImportError: no flask".to_string()));
+        let excerpt = output_excerpt(&lines);
+        let excerpt: Vec<&str> = excerpt.lines().collect();
+        assert_eq!(excerpt.len(), EXCERPT_LINES * 2 + 1);
+        assert_eq!(excerpt[0], "Traceback:");
+        assert_eq!(excerpt[EXCERPT_LINES], "... 12 more lines ...");
+        assert_eq!(excerpt.last(), Some(&"ImportError: no flask"));
+    }
+
+    #[tokio::test]
+    async fn test_start_records_command_line() {
+        let manager = ProcessManager::new();
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("sh", "sh");
+        config.args = vec!["-c".to_string(), "sleep 30".to_string()];
+        config.cwd = Some(dir.path().to_path_buf());
+
+        let info = manager.start(config).await.unwrap();
+        let exe = PathBuf::from(info.exe_path.unwrap());
+        assert!(exe.is_absolute());
+        assert!(exe.is_file(), "{} is not a file", exe.display());
+        assert_eq!(info.argv, ["sh", "-c", "sleep 30"]);
+        assert_eq!(
+            info.resolved_cwd,
+            Some(dir.path().canonicalize().unwrap().display().to_string())
+        );
+        #[cfg(unix)]
+        assert!(info.pgid.is_some());
+
+        // A command without args is split into argv
+        let info = manager
+            .start(test_config("split", "sleep 30"))
+            .await
+            .unwrap();
+        assert_eq!(info.argv, ["sleep", "30"]);
+
+        manager.stop("sh").await.unwrap();
+        assert_eq!(manager.get("sh").await.unwrap().pgid, None);
+        manager.stop("split").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_start_rejects_unsafe_config() {
+        let manager = ProcessManager::new();
+
+        let result = manager.start(test_config("api; rm -rf /", "echo hi")).await;
+        assert!(matches!(result, Err(SentinelError::InvalidConfig { .. })));
+
+        let result = manager.start(test_config("api", "sudo echo hi")).await;
+        assert!(matches!(result, Err(SentinelError::InvalidConfig { .. })));
+        assert!(manager.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_start_confines_cwd_to_allowed_roots() {
+        let root = std::env::current_dir().unwrap();
+        let manager = ProcessManager::with_settings(GlobalSettings {
+            allowed_roots: vec![root.clone()],
+            ..GlobalSettings::default()
+        });
+
+        // Relative paths resolve against the current directory
+        let mut config = test_config("inside", "echo hi");
+        config.cwd = Some("src/../src".into());
+        manager.start(config).await.unwrap();
+
+        let mut config = test_config("outside", "echo hi");
+        config.cwd = Some("..".into());
+        match manager.start(config).await {
+            Err(SentinelError::PathNotAllowed { path, allowed }) => {
+                assert_eq!(path, root.parent().unwrap().canonicalize().unwrap());
+                assert_eq!(allowed, vec![root]);
+            }
+            other => panic!(
+                "cwd outside roots was accepted: {:?}",
+                other.map(|i| i.name)
+            ),
+        }
+        assert!(manager.get("outside").await.is_none());
+    }
+
+    /// Nice value of a process as reported by `ps`.
+    #[cfg(unix)]
+    fn ps_nice(pid: u32) -> i32 {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "nice=", "-p", &pid.to_string()])
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_start_applies_nice() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("build", "sleep 10");
+        config.nice = Some(10);
+
+        let info = manager.start(config).await.unwrap();
+        assert_eq!(ps_nice(info.pid.unwrap()), 10);
+        assert_eq!(info.nice, Some(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_set_priority_of_running_process() {
+        let manager = ProcessManager::new();
+        let info = manager
+            .start(test_config("build", "sleep 10"))
+            .await
+            .unwrap();
+
+        let updated = manager.set_priority("build", 15).await.unwrap();
+        assert_eq!(updated.nice, Some(15));
+        assert_eq!(ps_nice(info.pid.unwrap()), 15);
+        assert_eq!(manager.get_config("build").await.unwrap().nice, Some(15));
+
+        assert!(matches!(
+            manager.set_priority("build", 99).await,
+            Err(SentinelError::InvalidPriority { nice: 99 })
+        ));
+        assert!(matches!(
+            manager.set_priority("missing", 5).await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_update_resource_usage_aggregates_children() {
+        let manager = ProcessManager::with_settings(GlobalSettings {
+            aggregate_child_usage: true,
+            ..GlobalSettings::default()
+        });
+        let mut config = test_config("tree", "sh");
+        config.args = vec!["-c".to_string(), "sleep 30 & wait".to_string()];
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(300)).await;
+
+        // CPU usage needs a second sample
+        manager.update_resource_usage().await;
+        assert!(manager.get("tree").await.unwrap().cpu_usage_tree.is_none());
+        sleep(RESOURCE_REFRESH_INTERVAL).await;
+        manager.update_resource_usage().await;
+        let info = manager.get("tree").await.unwrap();
+        let tree = manager.get_process_tree("tree").await.unwrap();
+
+        for child in &tree.children {
+            let _ = std::process::Command::new("kill")
+                .arg(child.pid.to_string())
+                .status();
+        }
+        manager.stop("tree").await.unwrap();
+
+        assert_eq!(tree.pid, info.pid.unwrap());
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].name, "sleep");
+        assert!(info.cpu_usage_tree.is_some());
+        assert!(info.memory_usage_tree.unwrap() > info.memory_usage);
+        assert!(manager.get_process_tree("tree").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_update_resource_usage_without_aggregation() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("single", "sleep 10"))
+            .await
+            .unwrap();
+
+        manager.update_resource_usage().await;
+        let first = manager.get("single").await.unwrap();
+        sleep(RESOURCE_REFRESH_INTERVAL).await;
+        manager.update_resource_usage().await;
+        let info = manager.get("single").await.unwrap();
+        manager.stop("single").await.unwrap();
+
+        // Nothing to compare the first sample against
+        assert_eq!(first.cpu_usage_percent_of_core, None);
+        assert_eq!(first.cpu_usage_percent_of_total, None);
+        assert!(info.cpu_usage_percent_of_core.is_some());
+        assert!(info.cpu_usage_percent_of_total.is_some());
+        assert!(info.memory_usage > 0);
+        assert!(info.cpu_usage_tree.is_none());
+        assert!(info.memory_usage_tree.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_update_resource_usage_with_many_processes() {
+        let manager = without_start_grace(GlobalSettings::default());
+        for i in 0..50 {
+            manager
+                .start(test_config(&format!("worker-{}", i), "sleep 30"))
+                .await
+                .unwrap();
+        }
+        let refreshed_at = || recover(manager.resources.lock(), "process table").refreshed_at;
+
+        manager.update_resource_usage().await;
+        let first = refreshed_at();
+        // A call within the refresh interval reuses the table
+        manager.update_resource_usage().await;
+        assert_eq!(refreshed_at(), first);
+
+        sleep(RESOURCE_REFRESH_INTERVAL).await;
+        manager.update_resource_usage().await;
+        assert_ne!(refreshed_at(), first);
+        let infos = manager.list().await;
+        manager.stop_all().await.unwrap();
+
+        assert_eq!(infos.len(), 50);
+        for info in &infos {
+            assert!(info.cpu_usage_percent_of_core.is_some(), "{}", info.name);
+            assert!(info.memory_usage > 0, "{}", info.name);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_update_resource_usage_merges_container_usage() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("db", "sleep 10");
+        config.container_ref = Some("pg".to_string());
+        manager.start(config).await.unwrap();
+        assert_eq!(
+            manager.container_refs().await,
+            vec![("db".to_string(), ContainerRef::Name("pg".to_string()))]
+        );
+
+        let usage = ContainerUsage {
+            containers: vec!["pg".to_string()],
+            cpu_percent: 40.0,
+            memory_bytes: 512 * 1024 * 1024,
+            memory_limit: 0,
+            network_rx_bytes: 0,
+            network_tx_bytes: 0,
+            block_read_bytes: 0,
+            block_write_bytes: 0,
+            sampled_at: Utc::now(),
+        };
+        manager.set_container_usage("db", Some(usage.clone())).await;
+        manager.update_resource_usage().await;
+        sleep(RESOURCE_REFRESH_INTERVAL).await;
+        manager.update_resource_usage().await;
+        let merged = manager.get("db").await.unwrap();
+
+        // A measurement from before the TTL is left out
+        let stale = ContainerUsage {
+            sampled_at: Utc::now() - container_link::USAGE_TTL - chrono::Duration::seconds(1),
+            ..usage
+        };
+        manager.set_container_usage("db", Some(stale)).await;
+        manager.update_resource_usage().await;
+        let own = manager.get("db").await.unwrap();
+        manager.stop("db").await.unwrap();
+
+        assert_eq!(merged.usage_source, UsageSource::Container);
+        assert!(merged.memory_usage > 512 * 1024 * 1024);
+        assert!(merged.cpu_usage_percent_of_core.unwrap() >= 40.0);
+        assert_eq!(merged.container.unwrap().containers, vec!["pg"]);
+        assert_eq!(own.usage_source, UsageSource::Process);
+        assert!(own.memory_usage < 512 * 1024 * 1024);
+        assert!(own.container.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_with_history() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("sparkline", "sleep 10"))
+            .await
+            .unwrap();
+
+        // Samples closer together than the history interval count once
+        manager.update_resource_usage().await;
+        manager.update_resource_usage().await;
+        sleep(HISTORY_SAMPLE_INTERVAL).await;
+        manager.update_resource_usage().await;
+
+        assert!(manager.list().await[0].history.is_none());
+        let history = manager.list_with_history().await[0]
+            .history
+            .clone()
+            .unwrap();
+        manager.stop("sparkline").await.unwrap();
+
+        let last = PROCESS_HISTORY_LEN - 1;
+        assert!(history.sample_interval_ms >= 1000);
+        assert!(history.memory[..last - 1].iter().all(Option::is_none));
+        assert!(history.memory[last - 1..]
+            .iter()
+            .all(|m| m.is_some_and(|m| m > 0)));
+        // The first sample has no CPU usage yet
+        assert_eq!(history.cpu[last - 1], None);
+        assert!(history.cpu[last].is_some());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_open_fds_and_threads() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("plain", "sleep 10"))
+            .await
+            .unwrap();
+        let mut files = test_config("files", "sh");
+        files.args = vec![
+            "-c".to_string(),
+            "exec 3</dev/null 4</dev/null 5</dev/null 6</dev/null; sleep 10".to_string(),
+        ];
+        manager.start(files).await.unwrap();
+        sleep(Duration::from_millis(300)).await;
+        manager.update_resource_usage().await;
+
+        let plain = manager.get("plain").await.unwrap();
+        let files = manager.list_with_history().await;
+        let files = files.iter().find(|info| info.name == "files").unwrap();
+        manager.stop_all().await.unwrap();
+
+        let baseline = plain.open_fds.unwrap();
+        assert!(files.open_fds.unwrap() >= baseline + 4);
+        assert_eq!(plain.thread_count, Some(1));
+        let history = files.history.as_ref().unwrap();
+        assert_eq!(history.open_fds[PROCESS_HISTORY_LEN - 1], files.open_fds);
+        assert_eq!(history.threads[PROCESS_HISTORY_LEN - 1], Some(1));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_cpu_affinity() {
+        let allowed_cpus = |pid: u32| {
+            std::fs::read_to_string(format!("/proc/{}/status", pid))
+                .unwrap()
+                .lines()
+                .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+                .map(|list| list.trim().to_string())
+                .unwrap()
+        };
+
+        let manager = ProcessManager::new();
+        let mut config = test_config("pinned", "sleep 10");
+        config.cpu_affinity = Some(vec![0]);
+        let info = manager.start(config).await.unwrap();
+        assert_eq!(allowed_cpus(info.pid.unwrap()), "0");
+
+        let last = scheduling::core_count() - 1;
+        manager.set_affinity("pinned", vec![last]).await.unwrap();
+        assert_eq!(allowed_cpus(info.pid.unwrap()), last.to_string());
+
+        assert!(matches!(
+            manager.set_affinity("pinned", vec![last + 1]).await,
+            Err(SentinelError::InvalidCpuAffinity { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_stdin_echoes_through_cat() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("echo", "cat");
+        config.stdin_mode = StdinMode::Piped;
+        manager.start(config).await.unwrap();
+
+        manager
+            .write_stdin("echo", b"hello\n".to_vec())
+            .await
+            .unwrap();
+        manager.write_stdin("echo", b"rs\n".to_vec()).await.unwrap();
+
+        let mut lines = Vec::new();
+        for _ in 0..50 {
+            lines = manager.get_logs("echo").await.unwrap();
+            if lines.len() >= 2 {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        let lines: Vec<&str> = lines.iter().map(|l| l.line.as_str()).collect();
+        assert_eq!(lines, ["hello", "rs"]);
+
+        manager.stop("echo").await.unwrap();
+        assert!(matches!(
+            manager.write_stdin("echo", b"late\n".to_vec()).await,
+            Err(SentinelError::ProcessNotRunning { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_stdin_requires_piped_stdin() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("sleeper", "sleep 10"))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager.write_stdin("sleeper", b"x".to_vec()).await,
+            Err(SentinelError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            manager.write_stdin("missing", b"x".to_vec()).await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_send_signal() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("sleeper", "sleep 10"))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager.send_signal("sleeper", "SIGBOGUS").await,
+            Err(SentinelError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            manager.send_signal("missing", "HUP").await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
+
+        manager.send_signal("sleeper", "term").await.unwrap();
+        for _ in 0..50 {
+            manager.check_health().await;
+            if !manager.is_running("sleeper").await {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        assert!(manager.get("sleeper").await.unwrap().is_crashed());
+        assert!(matches!(
+            manager.send_signal("sleeper", "HUP").await,
+            Err(SentinelError::ProcessNotRunning { .. })
+        ));
+    }
+
+    /// CPU usage of a process over the last of a few sampling intervals
+    async fn sampled_cpu(manager: &ProcessManager, name: &str) -> f32 {
+        for _ in 0..3 {
+            manager.update_resource_usage().await;
+            sleep(RESOURCE_REFRESH_INTERVAL).await;
+        }
+        manager.update_resource_usage().await;
+        manager
+            .get(name)
+            .await
+            .unwrap()
+            .cpu_usage_percent_of_core
+            .unwrap_or(0.0)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("spinner", "sh");
+        config.args = vec!["-c".to_string(), "while :; do :; done".to_string()];
+        config.auto_restart = Some(true);
+        manager.start(config).await.unwrap();
+
+        assert!(sampled_cpu(&manager, "spinner").await > 5.0);
+
+        let info = manager.pause("spinner").await.unwrap();
+        assert_eq!(info.state, ProcessState::Paused);
+        assert!(info.is_running());
+        // Pausing again does nothing
+        assert!(manager.pause("spinner").await.unwrap().is_paused());
+        assert!(sampled_cpu(&manager, "spinner").await < 1.0);
+
+        // Neither crashed nor restarted while paused
+        assert!(manager.check_health().await.is_empty());
+        let info = manager.get("spinner").await.unwrap();
+        assert_eq!(info.state, ProcessState::Paused);
+        assert_eq!(info.restart_count, 0);
+
+        let info = manager.resume("spinner").await.unwrap();
+        assert_eq!(info.state, ProcessState::Running);
+        assert!(sampled_cpu(&manager, "spinner").await > 5.0);
+
+        // A paused process still stops promptly
+        manager.pause("spinner").await.unwrap();
+        let started = std::time::Instant::now();
+        manager.stop("spinner").await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(
+            manager.resume("spinner").await,
+            Err(SentinelError::ProcessNotRunning { .. })
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_pause_reaches_children() {
+        // Waits for the process to be stopped or not, as signals are
+        // delivered asynchronously
+        async fn becomes_stopped(pid: u32, stopped: bool) -> bool {
+            for _ in 0..50 {
+                let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap();
+                let state = stat[stat.rfind(')').unwrap() + 2..].chars().next();
+                if (state == Some('T')) == stopped {
+                    return true;
+                }
+                sleep(Duration::from_millis(10)).await;
+            }
+            false
+        }
+
+        let manager = ProcessManager::new();
+        let mut config = test_config("parent", "sh");
+        config.args = vec!["-c".to_string(), "sleep 30 & wait".to_string()];
+        let pid = manager.start(config).await.unwrap().pid.unwrap();
+
+        let mut child = None;
+        for _ in 0..50 {
+            child = manager
+                .get_process_tree("parent")
+                .await
+                .ok()
+                .and_then(|tree| tree.children.first().map(|child| child.pid));
+            if child.is_some() {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        let child = child.expect("sleep was not started");
+
+        manager.pause("parent").await.unwrap();
+        assert!(becomes_stopped(pid, true).await);
+        assert!(becomes_stopped(child, true).await);
+
+        manager.resume("parent").await.unwrap();
+        assert!(becomes_stopped(pid, false).await);
+        assert!(becomes_stopped(child, false).await);
+
+        manager.stop("parent").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_process_already_running() {
+        let manager = ProcessManager::new();
+        let config = test_config("test", "sleep 10");
+
+        manager.start(config.clone()).await.unwrap();
+        let result = manager.start(config).await;
+
+        assert!(matches!(
+            result,
+            Err(SentinelError::ProcessAlreadyRunning { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stop_process() {
+        let manager = ProcessManager::new();
+        let config = test_config("test", "sleep 5");
+
+        manager.start(config).await.unwrap();
+        assert!(manager.is_running("test").await);
+
+        manager.stop("test").await.unwrap();
+        assert!(!manager.is_running("test").await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_does_not_wait_for_graceful_stop() {
+        let manager = ProcessManager::new();
+        let mut stubborn = test_config("stubborn", "sh");
+        stubborn.args = vec![
+            "-c".to_string(),
+            "trap '' TERM; while :; do sleep 0.1; done".to_string(),
+        ];
+        manager.start(stubborn).await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        tokio::time::timeout(Duration::from_secs(2), manager.kill("stubborn"))
+            .await
+            .expect("kill shouldn't wait for SIGTERM")
+            .unwrap();
+        assert!(!manager.is_running("stubborn").await);
+    }
+
+    #[tokio::test]
+    async fn test_stop_nonexistent_process() {
+        let manager = ProcessManager::new();
+        let result = manager.stop("nonexistent").await;
+
+        assert!(matches!(result, Err(SentinelError::ProcessNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_restart_process() {
+        let manager = without_start_grace(GlobalSettings::default());
+        let config = test_config("test", "echo test");
+
+        manager.start(config).await.unwrap();
+        let old_pid = manager.get("test").await.unwrap().pid;
+
+        sleep(Duration::from_millis(100)).await;
+
+        let info = manager.restart("test").await.unwrap();
+        let new_pid = info.pid;
+
+        // PIDs should be different (new process)
+        assert_ne!(old_pid, new_pid);
+        assert_eq!(info.last_restart_reason, Some(RestartReason::UserRequested));
+        assert!(info.last_restart_at.is_some());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_restart_records_reason() {
+        use crate::core::EventJournal;
+        use crate::models::EventFilter;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
+        let mut manager = without_start_grace(GlobalSettings::default());
+        manager.set_event_recorder(recorder.clone());
+        let mut config = test_config("watched", "sleep 30");
+        config.restart_delay = Some(10);
+        config.tags = vec!["web".to_string()];
+        manager.start(config).await.unwrap();
+
+        let changed = RestartReason::FileChanged {
+            path: "src/main.rs".to_string(),
         };
+        let info = manager
+            .restart_with("watched", changed.clone())
+            .await
+            .unwrap();
+        assert_eq!(info.last_restart_reason, Some(changed));
+        let info = manager
+            .restart_with("watched", RestartReason::ConfigReload)
+            .await
+            .unwrap();
+        assert_eq!(info.last_restart_reason, Some(RestartReason::ConfigReload));
+        let restarted = manager.restart_tagged("web").await.unwrap();
+        let info = restarted[0].1.as_ref().unwrap();
+        assert_eq!(info.last_restart_reason, Some(RestartReason::UserRequested));
+        assert_eq!(
+            manager.get("watched").await.unwrap().last_restart_reason,
+            Some(RestartReason::UserRequested)
+        );
 
-        let mut buf = buffer.lock().await;
-        buf.push(log_line);
+        // A plain start begins without one, like the restart count
+        manager.stop("watched").await.unwrap();
+        let info = manager
+            .start(test_config("watched", "sleep 30"))
+            .await
+            .unwrap();
+        assert_eq!(info.last_restart_reason, None);
+        manager.stop("watched").await.unwrap();
+        recorder.flush();
+
+        let filter = EventFilter {
+            event_types: vec![EventType::ProcessRestarted],
+            ..Default::default()
+        };
+        let events = journal.query(&filter, None, None).unwrap();
+        let recorded: Vec<_> = events
+            .iter()
+            .map(|e| (e.details["reason"].as_str(), e.actor))
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                ("file_changed", EventActor::Supervisor),
+                ("config_reload", EventActor::User),
+                ("user_requested", EventActor::User),
+            ]
+        );
+        assert_eq!(events[0].details["path"], "src/main.rs");
     }
 
-    debug!(
-        "Log stream ({:?}) closed for process: {}",
-        stream_type, process_name
-    );
-}
+    #[tokio::test]
+    async fn test_list_processes() {
+        let manager = ProcessManager::new();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        manager.start(test_config("proc1", "echo 1")).await.unwrap();
+        manager.start(test_config("proc2", "echo 2")).await.unwrap();
+
+        let list = manager.list().await;
+        assert_eq!(list.len(), 2);
+
+        let names: Vec<&str> = list.iter().map(|p| p.name.as_str()).collect();
+        assert!(names.contains(&"proc1"));
+        assert!(names.contains(&"proc2"));
+    }
+
+    #[tokio::test]
+    async fn test_get_process() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("test", "echo test"))
+            .await
+            .unwrap();
+
+        let info = manager.get("test").await;
+        assert!(info.is_some());
+        assert_eq!(info.unwrap().name, "test");
+
+        let nonexistent = manager.get("nonexistent").await;
+        assert!(nonexistent.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stop_all() {
+        let manager = ProcessManager::new();
+
+        manager
+            .start(test_config("proc1", "sleep 10"))
+            .await
+            .unwrap();
+        manager
+            .start(test_config("proc2", "sleep 10"))
+            .await
+            .unwrap();
+
+        assert!(manager.is_running("proc1").await);
+        assert!(manager.is_running("proc2").await);
+
+        manager.stop_all().await.unwrap();
+
+        assert!(!manager.is_running("proc1").await);
+        assert!(!manager.is_running("proc2").await);
+    }
+
+    #[tokio::test]
+    async fn test_remove_stopped_process() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("test", "echo test"))
+            .await
+            .unwrap();
+
+        sleep(Duration::from_millis(100)).await;
+        manager.stop("test").await.unwrap();
+
+        manager.remove("test").await.unwrap();
+        assert!(manager.get("test").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cannot_remove_running_process() {
+        let manager = ProcessManager::new();
+        manager
+            .start(test_config("test", "sleep 10"))
+            .await
+            .unwrap();
+
+        let result = manager.remove("test").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_log_capture() {
+        let manager = ProcessManager::new();
+
+        // Start a process that outputs to stdout
+        let config = test_config("logger", "echo 'Hello from stdout'");
+        manager.start(config).await.unwrap();
+
+        // Give time for log capture
+        sleep(Duration::from_millis(200)).await;
+
+        // Retrieve logs
+        let logs = manager.get_logs("logger").await.unwrap();
+
+        assert!(!logs.is_empty(), "Logs should be captured");
+        assert!(
+            logs.iter()
+                .any(|log| log.line.contains("Hello from stdout")),
+            "Log should contain output"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_logs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = ProcessManager::with_settings(GlobalSettings {
+            persist_logs: true,
+            log_directory: Some(dir.path().to_path_buf()),
+            ..GlobalSettings::default()
+        });
+
+        let config = test_config("logger", "echo Hello from stdout");
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+        manager.log_writer().flush();
+
+        let content = std::fs::read_to_string(dir.path().join("logger.log")).unwrap();
+        assert!(
+            content.contains(" stdout Hello from stdout\n"),
+            "unexpected log file: {:?}",
+            content
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_search() {
+        let manager = without_start_grace(GlobalSettings::default());
+
+        // Process that outputs multiple lines
+        let config = test_config(
+            "multi-logger",
+            "sh -c 'echo Error: test failed; echo Info: test passed'",
+        );
+        manager.start(config).await.unwrap();
+
+        sleep(Duration::from_millis(200)).await;
+
+        // Search for "Error"
+        let results = manager.search_logs("multi-logger", "Error").await.unwrap();
+        assert!(!results.is_empty(), "Should find error logs");
+        assert!(
+            results.iter().any(|log| log.line.contains("Error")),
+            "Should match error line"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_all_logs() {
+        let manager = ProcessManager::new();
+        for (name, script) in [
+            ("api", "echo 'GET /users 200'; echo 'GET /orders 503'"),
+            ("worker", "echo 'job 1 failed: 503'; echo 'job 2 done'"),
+            ("quiet", "echo nothing here"),
+        ] {
+            let mut config = test_config(name, "sh");
+            config.args = vec!["-c".to_string(), script.to_string()];
+            manager.start(config).await.unwrap();
+        }
+        sleep(Duration::from_millis(300)).await;
+
+        let options = LogSearchOptions {
+            regex: true,
+            context: 1,
+            ..Default::default()
+        };
+        let query = LogQuery::new(r"\b5\d\d\b", options).unwrap();
+        let results = manager.search_all_logs(&query).await;
+
+        let processes: Vec<&str> = results.iter().map(|r| r.process.as_str()).collect();
+        assert_eq!(processes, ["api", "worker"]);
+        assert_eq!(results[0].matches[0].line.line, "GET /orders 503");
+        assert_eq!(results[0].matches[0].before[0].line, "GET /users 200");
+        assert_eq!(results[1].matches[0].after[0].line, "job 2 done");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interleaved_streams_keep_their_order() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("chatty", "sh");
+        // A burst on stdout, then pairs once both readers are running
+        let script = concat!(
+            "seq 1 300; sleep 0.2; ",
+            "for i in 1 2 3 4 5; do echo out $i; echo err $i >&2; sleep 0.05; done"
+        );
+        config.args = vec!["-c".to_string(), script.to_string()];
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(1000)).await;
+
+        let logs = manager.get_logs("chatty").await.unwrap();
+        assert_eq!(logs.len(), 310);
+        assert!(logs.windows(2).all(|pair| pair[0].seq + 1 == pair[1].seq));
+        // The burst is read in batches but stays in order
+        let burst: Vec<String> = (1..=300).map(|i| i.to_string()).collect();
+        let texts: Vec<&str> = logs.iter().map(|log| log.line.as_str()).collect();
+        assert_eq!(texts[..300], burst);
+
+        // Each pair is written before the next starts, so never overtakes it
+        let position = |text: String| texts.iter().position(|t| *t == text).unwrap();
+        for i in 1..5 {
+            let next = position(format!("out {}", i + 1));
+            assert!(position(format!("out {}", i)) < next);
+            assert!(position(format!("err {}", i)) < next);
+            assert!(position(format!("err {}", i)) < position(format!("err {}", i + 1)));
+        }
+
+        // Merged views sort the same way
+        let mut merged = manager.merged_logs(None).await.unwrap();
+        log_timestamp::sort_lines(&mut merged, TimestampOrder::Received);
+        let seqs: Vec<u64> = merged.iter().map(|m| m.line.seq).collect();
+        assert_eq!(seqs, (0..310).collect::<Vec<u64>>());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_output_in_other_encodings_is_readable() {
+        let manager = ProcessManager::new();
+        for (name, encoding, script) in [
+            // UTF-16LE with a byte order mark, as many Windows tools write
+            (
+                "utf16",
+                OutputEncoding::Auto,
+                r"printf '\377\376o\000k\000\r\000\n\000\351\000\n\000'",
+            ),
+            // Latin-1 read as UTF-8 is replaced, and reading carries on
+            (
+                "latin1-auto",
+                OutputEncoding::Auto,
+                r"printf 'caf\351\n'; printf '\000\377 binary\n'; echo after",
+            ),
+            (
+                "latin1",
+                OutputEncoding::Latin1,
+                r"printf 'caf\351\r\nna\357ve\n'",
+            ),
+        ] {
+            let mut config = test_config(name, "sh");
+            config.args = vec!["-c".to_string(), script.to_string()];
+            config.output_encoding = encoding;
+            manager.start(config).await.unwrap();
+        }
+        sleep(Duration::from_millis(300)).await;
+
+        let texts =
+            |logs: Vec<LogLine>| -> Vec<String> { logs.into_iter().map(|log| log.line).collect() };
+        let logs = manager.get_logs("utf16").await.unwrap();
+        assert_eq!(texts(logs), ["ok", "é"]);
+        let logs = manager.get_logs("latin1-auto").await.unwrap();
+        assert_eq!(texts(logs), ["caf\u{FFFD}", "\0\u{FFFD} binary", "after"]);
+        let logs = manager.get_logs("latin1").await.unwrap();
+        assert_eq!(texts(logs), ["café", "naïve"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_structured_logs() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("pino", "sh");
+        let script = concat!(
+            r#"echo '{"level":30,"msg":"start","request_id":"abc"}'; "#,
+            r#"echo '{"level":50,"msg":"cut off'; "#,
+            r#"echo '{"level":40,"msg":"slow","request_id":"abc"}'; "#,
+            r#"echo '{"level":30,"msg":"other","request_id":"xyz"}' >&2"#
+        );
+        config.args = vec!["-c".to_string(), script.to_string()];
+        config.log_format = LogFormat::Json {
+            message_key: "msg".to_string(),
+            level_key: "level".to_string(),
+            timestamp_key: "time".to_string(),
+        };
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(300)).await;
+
+        let logs = manager.get_logs("pino").await.unwrap();
+        assert_eq!(logs.len(), 4);
+        assert!(logs
+            .iter()
+            .any(|log| log.line == r#"{"level":50,"msg":"cut off"#));
+
+        let filter = HashMap::from([("request_id".to_string(), "abc".to_string())]);
+        let matches = manager.get_structured_logs("pino", &filter).await.unwrap();
+        let texts: Vec<&str> = matches.iter().map(|log| log.line.as_str()).collect();
+        assert_eq!(texts, ["start", "slow"]);
+
+        let filter = HashMap::from([("level".to_string(), "info".to_string())]);
+        let matches = manager.get_structured_logs("pino", &filter).await.unwrap();
+        assert_eq!(matches.len(), 2);
+        assert!(manager
+            .get_structured_logs("missing", &HashMap::new())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_logs() {
+        let manager = without_start_grace(GlobalSettings::default());
+
+        let config = test_config(
+            "counter",
+            "sh -c 'for i in 1 2 3 4 5; do echo Line $i; done'",
+        );
+        manager.start(config).await.unwrap();
+
+        sleep(Duration::from_millis(300)).await;
+
+        // Get last 3 logs
+        let recent = manager.get_recent_logs("counter", 3).await.unwrap();
+        assert!(recent.len() <= 5, "Should have at most 5 logs");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_slow_stop_does_not_block_other_processes() {
+        let manager = Arc::new(ProcessManager::new());
+
+        let mut stubborn = test_config("stubborn", "sh");
+        stubborn.args = vec![
+            "-c".to_string(),
+            "trap '' TERM; while :; do sleep 0.1; done".to_string(),
+        ];
+        let pid = manager.start(stubborn).await.unwrap().pid.unwrap();
+        let mut chatty = test_config("chatty", "sh");
+        chatty.args = vec!["-c".to_string(), "echo hello; sleep 30".to_string()];
+        manager.start(chatty).await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        // Ignores SIGTERM, so the stop waits for up to 10 seconds
+        let stopping = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.stop("stubborn").await }
+        });
+        sleep(Duration::from_millis(100)).await;
+        assert!(!stopping.is_finished());
+        let stopping_again = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.stop("stubborn").await }
+        });
+
+        // Nor does listing, sampling or checking the stopping process
+        let quickly = Duration::from_millis(500);
+        let listed = tokio::time::timeout(quickly, manager.list())
+            .await
+            .expect("listing shouldn't wait for the stop");
+        let stubborn = listed.iter().find(|p| p.name == "stubborn").unwrap();
+        assert_eq!(stubborn.state, ProcessState::Stopping);
+        tokio::time::timeout(quickly, manager.update_resource_usage())
+            .await
+            .expect("sampling shouldn't wait for the stop");
+        tokio::time::timeout(quickly, manager.check_health())
+            .await
+            .expect("health checks shouldn't wait for the stop");
+        assert!(!stopping_again.is_finished());
+
+        let logs = tokio::time::timeout(
+            Duration::from_millis(500),
+            manager.get_recent_logs("chatty", 10),
+        )
+        .await
+        .expect("logs of another process shouldn't wait for the stop")
+        .unwrap();
+        assert_eq!(logs[0].line, "hello");
+
+        unsafe {
+            libc::kill(pid as i32, libc::SIGKILL);
+        }
+        stopping.await.unwrap().unwrap();
+        // A second stop returns once the first has finished
+        stopping_again.await.unwrap().unwrap();
+        assert_eq!(
+            manager.get("stubborn").await.unwrap().state,
+            ProcessState::Stopped
+        );
+        manager.stop("chatty").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_secret_env_reaches_child_but_not_responses() {
+        let manager = ProcessManager::new();
+        let mut config = test_config("secretive", "sh");
+        config.args = vec!["-c".to_string(), "echo $DB_PASSWORD".to_string()];
+        config
+            .env
+            .insert("DB_PASSWORD".to_string(), "hunter2".to_string());
+
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        // The child sees the real value...
+        let logs = manager.get_logs("secretive").await.unwrap();
+        assert_eq!(logs[0].line, "hunter2");
+
+        // ...while what goes to the frontend is masked
+        let mut app_config = crate::core::ConfigManager::default_config();
+        app_config.processes = vec![manager.get_config("secretive").await.unwrap()];
+        let response =
+            serde_json::to_string(&crate::core::secrets::mask_config(&app_config)).unwrap();
+        assert!(response.contains(crate::core::secrets::MASK));
+        assert!(!response.contains("hunter2"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_env_modes() {
+        let manager = without_start_grace(GlobalSettings::default());
+        let mut seen = HashMap::new();
+        for mode in [EnvMode::Inherit, EnvMode::Clean, EnvMode::Shell] {
+            let name = format!("env-{:?}", mode).to_lowercase();
+            let mut config = test_config(&name, "env");
+            config.env_mode = mode;
+            config.env = HashMap::from([
+                ("APP_PORT".to_string(), "3000".to_string()),
+                ("LD_LIBRARY_PATH".to_string(), "/opt/lib".to_string()),
+            ]);
+            manager.start(config).await.unwrap();
+            sleep(Duration::from_millis(300)).await;
+
+            let vars: HashMap<String, String> = manager
+                .get_logs(&name)
+                .await
+                .unwrap()
+                .into_iter()
+                .filter_map(|line| {
+                    let (name, value) = line.line.split_once('=')?;
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect();
+            // The process's own env always gets through, dangerous or not
+            assert_eq!(vars["APP_PORT"], "3000", "{:?}", mode);
+            assert_eq!(vars["LD_LIBRARY_PATH"], "/opt/lib", "{:?}", mode);
+            assert!(vars.contains_key("PATH"), "{:?}", mode);
+            seen.insert(mode, vars);
+        }
+
+        let clean = &seen[&EnvMode::Clean];
+        for name in clean.keys() {
+            assert!(
+                child_env::CLEAN_ALLOWLIST.contains(&name.as_str())
+                    || name == "APP_PORT"
+                    || name == "LD_LIBRARY_PATH",
+                "{} in a clean environment",
+                name
+            );
+        }
+        // Inherit passes on all of Sentinel's environment but the loader
+        // variables; Clean only the allowlisted part of it
+        let inherited = &seen[&EnvMode::Inherit];
+        for (name, value) in clean {
+            assert_eq!(inherited.get(name), Some(value), "{}", name);
+        }
+        assert_eq!(
+            inherited.get("CARGO_MANIFEST_DIR"),
+            std::env::var("CARGO_MANIFEST_DIR").ok().as_ref()
+        );
+        assert!(inherited.len() > clean.len());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_auto_restart() {
+        let manager = without_start_grace(GlobalSettings::default());
+
+        // Create a process that exits immediately but has auto_restart enabled
+        let mut config = test_config("auto-restart", "sh");
+        config.args = vec!["-c".to_string(), "echo Starting; exit 1".to_string()];
+        config.auto_restart = Some(true);
+        config.restart_limit = 2;
+        config.restart_delay = Some(50);
+
+        manager.start(config).await.unwrap();
+
+        // Wait for process to exit
+        sleep(Duration::from_millis(100)).await;
+
+        // Run health check - should detect crash and restart
+        let restarted = manager.check_health().await;
+
+        assert!(
+            !restarted.is_empty(),
+            "Health check should restart crashed process"
+        );
+        assert_eq!(restarted[0], "auto-restart");
+
+        // Check restart count incremented
+        let handle = manager.handle("auto-restart").unwrap();
+        assert_eq!(
+            handle.lock().await.restart_count,
+            1,
+            "Restart count should be 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_successful_exit_completes_without_restart() {
+        let manager = without_start_grace(GlobalSettings::default());
+
+        let mut job = test_config("job", "sh");
+        job.args = vec!["-c".to_string(), "exit 0".to_string()];
+        job.auto_restart = Some(true);
+        job.restart_delay = Some(10);
+        let mut seed = test_config("seed", "sh");
+        seed.args = vec!["-c".to_string(), "exit 3".to_string()];
+        seed.auto_restart = Some(true);
+        seed.restart_delay = Some(10);
+        seed.success_exit_codes = vec![3];
+        manager.start(job).await.unwrap();
+        manager.start(seed).await.unwrap();
+
+        sleep(Duration::from_millis(100)).await;
+        assert!(manager.check_health().await.is_empty());
+        let job = manager.get("job").await.unwrap();
+        assert_eq!(job.state, ProcessState::Completed { exit_code: 0 });
+        assert!(job.is_completed() && !job.is_running());
+        assert_eq!(job.restart_count, 0);
+        assert_eq!(
+            manager.get("seed").await.unwrap().state,
+            ProcessState::Completed { exit_code: 3 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_exit_code_still_restarts() {
+        let manager = without_start_grace(GlobalSettings::default());
+
+        let mut config = test_config("seed", "sh");
+        config.args = vec!["-c".to_string(), "exit 1".to_string()];
+        config.auto_restart = Some(true);
+        config.restart_delay = Some(10);
+        config.success_exit_codes = vec![3];
+        manager.start(config).await.unwrap();
+
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(manager.check_health().await, vec!["seed".to_string()]);
+        assert_eq!(
+            manager.get("seed").await.unwrap().last_restart_reason,
+            Some(RestartReason::CrashAutoRestart { exit_code: 1 })
+        );
+        manager.stop("seed").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failing_health_checks_restart_process() {
+        let manager = without_start_grace(GlobalSettings::default());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let mut config = test_config("unhealthy", "sleep 30");
+        config.auto_restart = Some(true);
+        config.restart_delay = Some(10);
+        config.health_check = Some(HealthCheck {
+            check: HealthCheckKind::Tcp {
+                host: "127.0.0.1".to_string(),
+                port,
+            },
+            interval_ms: Some(50),
+            timeout_ms: 500,
+            retries: 1,
+        });
+        let pid = manager.start(config).await.unwrap().pid;
+
+        // Not due yet
+        assert!(manager.check_health().await.is_empty());
+        assert!(manager.get("unhealthy").await.unwrap().health.is_none());
+
+        sleep(Duration::from_millis(60)).await;
+        assert!(manager.check_health().await.is_empty());
+        let health = manager
+            .get("unhealthy")
+            .await
+            .unwrap()
+            .health
+            .clone()
+            .unwrap();
+        assert!(!health.healthy);
+        assert_eq!(health.consecutive_failures, 1);
+
+        // The second failure in a row is one more than `retries`
+        sleep(Duration::from_millis(60)).await;
+        assert_eq!(manager.check_health().await, ["unhealthy"]);
+        let info = manager.get("unhealthy").await.unwrap();
+        assert!(info.is_running());
+        assert_ne!(info.pid, pid);
+        assert!(info.health.is_none());
+
+        manager.stop("unhealthy").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_records_lifecycle_events() {
+        use crate::core::EventJournal;
+        use crate::models::EventFilter;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
+        let mut manager = without_start_grace(GlobalSettings::default());
+        manager.set_event_recorder(recorder.clone());
+
+        let mut config = test_config("journaled", "sh");
+        config.args = vec!["-c".to_string(), "exit 3".to_string()];
+        config.auto_restart = Some(true);
+        config.restart_delay = Some(10);
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(100)).await;
+        manager.check_health().await;
+        let info = manager.get("journaled").await.unwrap();
+        assert_eq!(
+            info.last_restart_reason,
+            Some(RestartReason::CrashAutoRestart { exit_code: 3 })
+        );
+        assert!(info.last_restart_at.is_some());
+        manager.stop("journaled").await.unwrap();
+        recorder.flush();
+
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        let recorded: Vec<_> = events.iter().map(|e| (e.event_type, e.actor)).collect();
+        assert_eq!(
+            recorded,
+            vec![
+                (EventType::ProcessStarted, EventActor::User),
+                (EventType::ProcessCrashed, EventActor::Supervisor),
+                (EventType::ProcessStarted, EventActor::Supervisor),
+                (EventType::ProcessRestarted, EventActor::Supervisor),
+                (EventType::ProcessStopped, EventActor::User),
+            ]
+        );
+        assert!(events.iter().all(|e| e.subject == "journaled"));
+        assert_eq!(events[1].details["exit_code"], "3");
+        assert_eq!(events[3].details["reason"], "crash_auto_restart");
+        assert_eq!(events[3].details["exit_code"], "3");
+    }
 
-    fn test_config(name: &str, command: &str) -> ProcessConfig {
-        ProcessConfig {
-            name: name.to_string(),
-            command: command.to_string(),
-            args: vec![],
-            cwd: None,
-            env: HashMap::new(),
-            auto_restart: false,
-            restart_limit: 0,
-            restart_delay: 100,
-            depends_on: vec![],
-            health_check: None,
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_crash_reports_orphaned_children() {
+        use crate::core::EventJournal;
+        use crate::models::EventFilter;
+
+        for reap in [false, true] {
+            let dir = tempfile::TempDir::new().unwrap();
+            let journal = EventJournal::new(dir.path().join("events.jsonl"));
+            let recorder = EventRecorder::open(journal.clone());
+            let mut manager = without_start_grace(GlobalSettings::default());
+            manager.set_event_recorder(recorder.clone());
+
+            let mut config = test_config("spawner", "sh");
+            config.args = vec!["-c".to_string(), "sleep 30 & sleep 1; exit 3".to_string()];
+            config.auto_restart = Some(false);
+            config.reap_orphans = reap;
+            manager.start(config).await.unwrap();
+
+            // The resource update notes the children while the shell runs
+            sleep(Duration::from_millis(300)).await;
+            manager.update_resource_usage().await;
+            sleep(Duration::from_millis(1200)).await;
+            manager.check_health().await;
+            let info = manager.get("spawner").await.unwrap();
+            assert_eq!(info.state, ProcessState::Crashed { exit_code: 3 });
+
+            recorder.flush();
+            let events = journal.query(&EventFilter::default(), None, None).unwrap();
+            let crashed = events
+                .iter()
+                .find(|e| e.event_type == EventType::ProcessCrashed)
+                .unwrap();
+            let orphans = &crashed.details["orphans"];
+            assert!(orphans.starts_with("sleep ("), "{}", orphans);
+            assert_eq!(
+                crashed.details["orphans_reaped"],
+                if reap { "1" } else { "0" }
+            );
+
+            let left = manager.orphaned_children("spawner").await.unwrap();
+            if reap {
+                assert!(left.is_empty(), "{:?}", left);
+            } else {
+                assert_eq!(left.len(), 1);
+                assert_eq!(left[0].name, "sleep");
+                assert!(!left[0].reaped);
+                unsafe { libc::kill(left[0].pid as libc::pid_t, libc::SIGKILL) };
+            }
         }
+
+        let manager = ProcessManager::new();
+        assert!(matches!(
+            manager.orphaned_children("missing").await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_start_process() {
-        let mut manager = ProcessManager::new();
-        let config = test_config("test", "echo hello");
-
-        let info = manager.start(config).await.unwrap();
-        assert_eq!(info.name, "test");
-        assert_eq!(info.state, ProcessState::Running);
-        assert!(info.pid.is_some());
+    async fn test_start_all_delays_and_leaves_scheduled_processes() {
+        let manager = ProcessManager::new();
+        let database = test_config("database", "sleep 30");
+        let mut api = test_config("api", "sleep 30");
+        api.depends_on = vec!["database".to_string()];
+        api.start_delay_ms = Some(300);
+        let mut sync = test_config("sync", "sleep 30");
+        sync.schedule = Some("0 2 * * *".to_string());
+
+        let started = std::time::Instant::now();
+        let results = manager.start_all(vec![database, api, sync]).await;
+        assert!(started.elapsed() >= Duration::from_millis(300));
+
+        let names: Vec<_> = results.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["database", "api"]);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert!(!manager.is_running("sync").await);
+
+        manager.stop("api").await.unwrap();
+        manager.stop("database").await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_process_already_running() {
-        let mut manager = ProcessManager::new();
-        let config = test_config("test", "sleep 10");
+    async fn test_start_all_checks_start_grace_together() {
+        let manager = ProcessManager::new();
+        let grace = Duration::from_millis(manager.settings().start_grace_ms);
+        let mut configs: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| test_config(name, "sleep 30"))
+            .collect();
+        let mut broken = test_config("broken", "sh");
+        broken.args = vec!["-c".to_string(), "exit 3".to_string()];
+        let mut web = test_config("web", "sleep 30");
+        web.depends_on = vec!["broken".to_string()];
+        configs.extend([broken, web]);
 
-        manager.start(config.clone()).await.unwrap();
-        let result = manager.start(config).await;
+        let started = std::time::Instant::now();
+        let results = manager.start_all(configs).await;
+        assert!(started.elapsed() < grace * 2);
 
+        assert!(results[..3].iter().all(|(_, result)| result.is_ok()));
         assert!(matches!(
-            result,
-            Err(SentinelError::ProcessAlreadyRunning { .. })
+            results[3].1,
+            Err(SentinelError::StartFailedEarly { exit_code: 3, .. })
         ));
+        let Err(SentinelError::Other(skipped)) = &results[4].1 else {
+            panic!("web should be skipped");
+        };
+        assert!(skipped.contains("'broken' failed to start"));
+
+        manager.stop_all().await.unwrap();
+    }
+
+    fn log_readiness(pattern: &str, max_wait_ms: u64) -> Option<ReadinessProbe> {
+        Some(ReadinessProbe {
+            check: ReadinessCheck::Log {
+                pattern: pattern.to_string(),
+            },
+            interval_ms: 50,
+            timeout_ms: 500,
+            max_wait_ms,
+        })
     }
 
     #[tokio::test]
-    async fn test_stop_process() {
-        let mut manager = ProcessManager::new();
-        let config = test_config("test", "sleep 5");
+    async fn test_start_all_waits_for_dependency_readiness() {
+        let manager = ProcessManager::new();
+        let mut database = test_config("database", "sh");
+        database.args = vec![
+            "-c".to_string(),
+            "sleep 0.3; echo 'accepting connections'; sleep 30".to_string(),
+        ];
+        database.readiness = log_readiness("accepting connections", 5_000);
+        let mut api = test_config("api", "sleep 30");
+        api.depends_on = vec!["database".to_string()];
+
+        let started = std::time::Instant::now();
+        let results = manager.start_all(vec![database, api]).await;
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        let database = manager
+            .list()
+            .await
+            .into_iter()
+            .find(|info| info.name == "database")
+            .unwrap();
+        assert!(database.ready);
+        assert!(database.time_to_ready_ms.unwrap() >= 300);
+        // Without a probe a process is ready once running
+        assert!(manager.get("api").await.unwrap().ready);
+
+        manager.stop("database").await.unwrap();
+        assert!(!manager.get("database").await.unwrap().ready);
+        manager.stop("api").await.unwrap();
+    }
 
-        manager.start(config).await.unwrap();
-        assert!(manager.is_running("test"));
+    #[tokio::test]
+    async fn test_start_all_skips_dependents_of_unready_process() {
+        let manager = ProcessManager::new();
+        let mut database = test_config("database", "sleep 30");
+        database.readiness = log_readiness("accepting connections", 300);
+        let mut api = test_config("api", "sleep 30");
+        api.depends_on = vec!["database".to_string()];
+
+        let results = manager.start_all(vec![database, api]).await;
+        assert!(results[0].1.is_ok());
+        let err = results[1].1.as_ref().unwrap_err().to_string();
+        assert!(err.contains("dependency 'database' is not ready"));
+        assert!(!manager.is_running("api").await);
+
+        let err = manager
+            .wait_for_ready("database", Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SentinelError::ReadinessTimeout { .. }));
 
-        manager.stop("test").await.unwrap();
-        assert!(!manager.is_running("test"));
+        manager.stop("database").await.unwrap();
+        assert!(manager
+            .wait_for_ready("database", Duration::from_secs(1))
+            .await
+            .is_err());
+    }
+
+    fn migration(script: &str) -> ProcessConfig {
+        let mut config = test_config("migrate", "sh");
+        config.args = vec!["-c".to_string(), script.to_string()];
+        config.auto_restart = Some(false);
+        config.wait_for_completion = true;
+        config
     }
 
     #[tokio::test]
-    async fn test_stop_nonexistent_process() {
-        let mut manager = ProcessManager::new();
-        let result = manager.stop("nonexistent").await;
+    async fn test_start_all_waits_for_dependency_completion() {
+        let manager = without_start_grace(GlobalSettings::default());
+        let migrate = migration("sleep 0.5; exit 0");
+        let mut api = test_config("api", "sleep 30");
+        api.depends_on = vec!["migrate".to_string()];
+
+        let started = std::time::Instant::now();
+        let results = manager.start_all(vec![migrate, api]).await;
+        assert!(started.elapsed() >= Duration::from_millis(500));
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert!(manager.get("migrate").await.unwrap().is_completed());
+        assert!(manager.is_running("api").await);
+
+        // A completed dependency lets a tagged group start on its own
+        let worker = tagged_config("worker", &["jobs"], &["migrate"]);
+        let results = manager.start_tagged(vec![worker], "jobs").await.unwrap();
+        assert!(results[0].1.is_ok());
 
-        assert!(matches!(result, Err(SentinelError::ProcessNotFound { .. })));
+        manager.stop_all().await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_restart_process() {
-        let mut manager = ProcessManager::new();
-        let config = test_config("test", "echo test");
-
-        manager.start(config).await.unwrap();
-        let old_pid = manager.get("test").unwrap().pid;
+    async fn test_start_all_skips_dependents_of_failed_completion() {
+        let manager = without_start_grace(GlobalSettings::default());
+        let migrate = migration("sleep 0.5; exit 2");
+        let mut api = test_config("api", "sleep 30");
+        api.depends_on = vec!["migrate".to_string()];
+
+        let results = manager.start_all(vec![migrate, api]).await;
+        assert!(results[0].1.is_ok());
+        let err = results[1].1.as_ref().unwrap_err().to_string();
+        assert!(
+            err.contains("exited with code 2 instead of completing"),
+            "{}",
+            err
+        );
+        assert!(!manager.is_running("api").await);
 
-        sleep(Duration::from_millis(100)).await;
+        let mut migrate = migration("sleep 30");
+        migrate.name = "slow".to_string();
+        manager.start(migrate).await.unwrap();
+        let err = manager
+            .wait_for_completion("slow", Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SentinelError::CompletionTimeout { .. }));
+        manager.stop("slow").await.unwrap();
+    }
 
-        let info = manager.restart("test").await.unwrap();
-        let new_pid = info.pid;
+    #[tokio::test]
+    async fn test_schedule_triggers_and_max_runtime() {
+        use crate::core::EventJournal;
+        use crate::models::EventFilter;
 
-        // PIDs should be different (new process)
-        assert_ne!(old_pid, new_pid);
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
+        let mut manager = ProcessManager::new();
+        manager.set_event_recorder(recorder.clone());
+
+        // Every second, stopped after 300ms
+        let mut config = test_config("ticker", "sleep 30");
+        config.schedule = Some("* * * * * *".to_string());
+        config.max_runtime_ms = Some(300);
+        let configs = vec![config];
+
+        assert!(manager.check_schedules(&configs).await.is_empty());
+        assert!(manager.next_scheduled_run("ticker").is_some());
+        sleep(Duration::from_millis(1100)).await;
+
+        let results = manager.check_schedules(&configs).await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1.is_ok());
+        assert!(manager.is_running("ticker").await);
+
+        // Without the schedule, so it doesn't fire again
+        sleep(Duration::from_millis(400)).await;
+        manager.check_schedules(&[]).await;
+        assert!(!manager.is_running("ticker").await);
+        recorder.flush();
+
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        let recorded: Vec<_> = events.iter().map(|e| (e.event_type, e.actor)).collect();
+        assert_eq!(
+            recorded[..4],
+            [
+                (EventType::ScheduleTriggered, EventActor::Supervisor),
+                (EventType::ProcessStarted, EventActor::Supervisor),
+                (EventType::MaxRuntimeExceeded, EventActor::Supervisor),
+                (EventType::ProcessStopped, EventActor::Supervisor),
+            ]
+        );
+        assert_eq!(events[0].details["schedule"], "* * * * * *");
+        assert_eq!(events[2].details["max_runtime_ms"], "300");
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_list_processes() {
+    async fn test_restart_policy_keeps_restart_count() {
+        use crate::core::EventJournal;
+        use crate::models::{EventFilter, RestartPolicy};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
         let mut manager = ProcessManager::new();
+        manager.set_event_recorder(recorder.clone());
+
+        let mut config = test_config("leaky", "sleep 30");
+        config.restart_policy = Some(RestartPolicy {
+            max_uptime_hours: Some(4),
+            ..Default::default()
+        });
+        let first = manager.start(config).await.unwrap();
+        if let Some(handle) = manager.handle("leaky") {
+            let mut handle = handle.lock().await;
+            handle.restart_count = 2;
+            handle.info.restart_count = 2;
+        }
 
-        manager.start(test_config("proc1", "echo 1")).await.unwrap();
-        manager.start(test_config("proc2", "echo 2")).await.unwrap();
+        // Not due yet
+        let started_at = first.started_at.unwrap();
+        manager
+            .restart_by_policy(started_at + chrono::Duration::hours(3), &Utc)
+            .await;
+        assert_eq!(manager.get("leaky").await.unwrap().pid, first.pid);
 
-        let list = manager.list();
-        assert_eq!(list.len(), 2);
+        manager
+            .restart_by_policy(started_at + chrono::Duration::hours(4), &Utc)
+            .await;
+        let restarted = manager.get("leaky").await.unwrap();
+        assert!(restarted.is_running());
+        assert_ne!(restarted.pid, first.pid);
+        assert_eq!(restarted.restart_count, 2);
+        assert_eq!(
+            restarted.last_restart_reason,
+            Some(RestartReason::Policy {
+                name: "maxUptimeHours".to_string()
+            })
+        );
+        assert_eq!(
+            manager.handle("leaky").unwrap().lock().await.restart_count,
+            2
+        );
+        manager.stop("leaky").await.unwrap();
+        recorder.flush();
+
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        let recorded: Vec<_> = events.iter().map(|e| (e.event_type, e.actor)).collect();
+        assert_eq!(
+            recorded[1..5],
+            [
+                (EventType::RestartPolicyTriggered, EventActor::Supervisor),
+                (EventType::ProcessStopped, EventActor::Supervisor),
+                (EventType::ProcessStarted, EventActor::Supervisor),
+                (EventType::ProcessRestarted, EventActor::Supervisor),
+            ]
+        );
+        assert_eq!(events[1].details["policy"], "maxUptimeHours");
+        assert_eq!(events[1].details["reason"], "running for 4 hours");
+        assert_eq!(events[4].details["reason"], "policy");
+        assert_eq!(events[4].details["policy"], "maxUptimeHours");
+    }
 
-        let names: Vec<&str> = list.iter().map(|p| p.name.as_str()).collect();
-        assert!(names.contains(&"proc1"));
-        assert!(names.contains(&"proc2"));
+    /// A warm-restarted HTTP server on `$PORT`, ready once it answers.
+    ///
+    /// With `marker`, only the first instance serves; later ones create no
+    /// server and never become ready.
+    fn warm_http_server(name: &str, marker: Option<&std::path::Path>) -> ProcessConfig {
+        let port = ports::free_port().unwrap();
+        let serve = "exec python3 -m http.server \"$PORT\" --bind 127.0.0.1";
+        let script = match marker {
+            Some(marker) => format!(
+                "if [ -e '{0}' ]; then exec sleep 30; fi; touch '{0}'; {1}",
+                marker.display(),
+                serve
+            ),
+            None => serve.to_string(),
+        };
+        let mut config = test_config(name, "sh");
+        config.args = vec!["-c".to_string(), script];
+        config.env.insert("PORT".to_string(), port.to_string());
+        config.readiness = Some(ReadinessProbe {
+            check: ReadinessCheck::Http {
+                url: format!("http://127.0.0.1:{}/", port),
+                expected_status: 200,
+            },
+            interval_ms: 50,
+            timeout_ms: 500,
+            max_wait_ms: 5_000,
+        });
+        config.warm_restart = true;
+        config
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_get_process() {
-        let mut manager = ProcessManager::new();
-        manager
-            .start(test_config("test", "echo test"))
+    async fn test_warm_restart_replaces_ready_instance() {
+        let manager = ProcessManager::new();
+        let mut config = warm_http_server("web", None);
+        config.warm_restart_free_port = true;
+        let old_port: u16 = config.env["PORT"].parse().unwrap();
+        config.health_check = Some(HealthCheck {
+            check: HealthCheckKind::Tcp {
+                host: "127.0.0.1".to_string(),
+                port: old_port,
+            },
+            interval_ms: Some(60_000),
+            timeout_ms: 500,
+            retries: 0,
+        });
+        manager.start(config).await.unwrap();
+        let first = manager
+            .wait_for_ready("web", Duration::from_secs(10))
             .await
             .unwrap();
+        if let Some(handle) = manager.handle("web") {
+            let mut handle = handle.lock().await;
+            handle.restart_count = 1;
+            handle.info.restart_count = 1;
+        }
 
-        let info = manager.get("test");
-        assert!(info.is_some());
-        assert_eq!(info.unwrap().name, "test");
-
-        let nonexistent = manager.get("nonexistent");
-        assert!(nonexistent.is_none());
+        let restarted = manager.restart("web").await.unwrap();
+        assert_eq!(restarted.name, "web");
+        assert!(restarted.is_running());
+        assert!(restarted.ready);
+        assert_ne!(restarted.pid, first.pid);
+        assert_eq!(restarted.restart_count, 1);
+        assert_eq!(
+            restarted.last_restart_reason,
+            Some(RestartReason::UserRequested)
+        );
+        assert_eq!(manager.list().await.len(), 1);
+
+        // The old instance was stopped; the new one keeps serving on its port
+        let new_port = restarted.port.unwrap();
+        assert_eq!(first.port, Some(old_port));
+        assert_ne!(new_port, old_port);
+        assert!(ports::is_free(old_port));
+        assert!(!ports::is_free(new_port));
+        let handle = manager.handle("web").unwrap();
+        let handle = handle.lock().await;
+        assert_eq!(handle.config.env["PORT"], new_port.to_string());
+        assert!(matches!(
+            &handle.config.readiness.as_ref().unwrap().check,
+            ReadinessCheck::Http { url, .. } if *url == format!("http://127.0.0.1:{}/", new_port)
+        ));
+        assert!(matches!(
+            handle.config.health_check.as_ref().unwrap().check,
+            HealthCheckKind::Tcp { port, .. } if port == new_port
+        ));
+        drop(handle);
+        manager.stop("web").await.unwrap();
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_stop_all() {
-        let mut manager = ProcessManager::new();
-
-        manager
-            .start(test_config("proc1", "sleep 10"))
+    async fn test_warm_restart_in_place_when_port_cannot_be_shared() {
+        // The server doesn't bind with SO_REUSEPORT, so the new instance
+        // exits until the old one is stopped
+        let manager = ProcessManager::new();
+        let config = warm_http_server("web", None);
+        let port: u16 = config.env["PORT"].parse().unwrap();
+        manager.start(config).await.unwrap();
+        let first = manager
+            .wait_for_ready("web", Duration::from_secs(10))
             .await
             .unwrap();
-        manager
-            .start(test_config("proc2", "sleep 10"))
+
+        let restarted = manager.restart("web").await.unwrap();
+        assert!(restarted.is_running());
+        assert_ne!(restarted.pid, first.pid);
+        assert_eq!(restarted.port, Some(port));
+        assert_eq!(
+            restarted.last_restart_reason,
+            Some(RestartReason::UserRequested)
+        );
+        let ready = manager
+            .wait_for_ready("web", Duration::from_secs(10))
             .await
             .unwrap();
-
-        assert!(manager.is_running("proc1"));
-        assert!(manager.is_running("proc2"));
-
-        manager.stop_all().await.unwrap();
-
-        assert!(!manager.is_running("proc1"));
-        assert!(!manager.is_running("proc2"));
+        assert_eq!(ready.pid, restarted.pid);
+        assert!(!ports::is_free(port));
+        manager.stop("web").await.unwrap();
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_remove_stopped_process() {
+    async fn test_warm_restart_keeps_old_instance_when_new_one_fails() {
+        use crate::core::EventJournal;
+        use crate::models::EventFilter;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = EventJournal::new(dir.path().join("events.jsonl"));
+        let recorder = EventRecorder::open(journal.clone());
         let mut manager = ProcessManager::new();
-        manager
-            .start(test_config("test", "echo test"))
+        manager.set_event_recorder(recorder.clone());
+
+        let mut config = warm_http_server("web", Some(&dir.path().join("served")));
+        config.warm_restart_free_port = true;
+        if let Some(probe) = config.readiness.as_mut() {
+            probe.max_wait_ms = 1_000;
+        }
+        manager.start(config).await.unwrap();
+        let first = manager
+            .wait_for_ready("web", Duration::from_secs(10))
             .await
             .unwrap();
 
-        sleep(Duration::from_millis(100)).await;
-        manager.stop("test").await.unwrap();
-
-        manager.remove("test").unwrap();
-        assert!(manager.get("test").is_none());
+        let err = manager.restart("web").await.unwrap_err();
+        assert!(matches!(err, SentinelError::ReadinessTimeout { ref name, .. } if name == "web"));
+
+        let kept = manager.get("web").await.unwrap();
+        assert!(kept.is_running());
+        assert_eq!(kept.pid, first.pid);
+        manager.stop("web").await.unwrap();
+        recorder.flush();
+
+        let events = journal.query(&EventFilter::default(), None, None).unwrap();
+        let recorded: Vec<_> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            recorded,
+            [
+                EventType::ProcessStarted,
+                EventType::WarmRestartFailed,
+                EventType::ProcessStopped,
+            ]
+        );
+        assert_eq!(events[1].subject, "web");
+        assert!(events[1].details["error"].contains("did not become ready"));
     }
 
     #[tokio::test]
-    async fn test_cannot_remove_running_process() {
+    async fn test_journal_write_failure_does_not_fail_operations() {
+        use crate::core::EventJournal;
+
+        // A directory where the journal file should be makes every write fail
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("events.jsonl");
+        std::fs::create_dir(&path).unwrap();
+        let recorder = EventRecorder::open(EventJournal::new(path));
         let mut manager = ProcessManager::new();
+        manager.set_event_recorder(recorder.clone());
+
         manager
-            .start(test_config("test", "sleep 10"))
+            .start(test_config("unjournaled", "sleep 30"))
             .await
             .unwrap();
+        manager.stop("unjournaled").await.unwrap();
+        recorder.flush();
 
-        let result = manager.remove("test");
-        assert!(result.is_err());
+        assert_eq!(recorder.dropped(), 2);
     }
 
     #[tokio::test]
-    async fn test_log_capture() {
-        let mut manager = ProcessManager::new();
-
-        // Start a process that outputs to stdout
-        let config = test_config("logger", "echo 'Hello from stdout'");
-        manager.start(config).await.unwrap();
+    async fn test_auto_restart_inherits_global_default() {
+        let settings = GlobalSettings {
+            default_auto_restart: true,
+            default_restart_delay_ms: 10,
+            ..GlobalSettings::default()
+        };
+        let manager = without_start_grace(settings);
 
-        // Give time for log capture
-        sleep(Duration::from_millis(200)).await;
+        // No auto_restart or restart_delay of its own
+        let mut config = test_config("inherits", "sh -c 'exit 1'");
+        config.auto_restart = None;
+        config.restart_delay = None;
+        config.restart_limit = 1;
 
-        // Retrieve logs
-        let logs = manager.get_logs("logger").await.unwrap();
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(100)).await;
 
-        assert!(!logs.is_empty(), "Logs should be captured");
-        assert!(
-            logs.iter()
-                .any(|log| log.line.contains("Hello from stdout")),
-            "Log should contain output"
-        );
+        assert_eq!(manager.check_health().await, vec!["inherits"]);
     }
 
     #[tokio::test]
-    async fn test_log_search() {
-        let mut manager = ProcessManager::new();
-
-        // Process that outputs multiple lines
-        let config = test_config(
-            "multi-logger",
-            "sh -c 'echo Error: test failed; echo Info: test passed'",
-        );
-        manager.start(config).await.unwrap();
+    async fn test_process_override_beats_global_default() {
+        let settings = GlobalSettings {
+            default_auto_restart: true,
+            ..GlobalSettings::default()
+        };
+        let manager = without_start_grace(settings);
 
-        sleep(Duration::from_millis(200)).await;
+        // test_config sets auto_restart: Some(false)
+        manager
+            .start(test_config("opted-out", "sh -c 'exit 1'"))
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(100)).await;
 
-        // Search for "Error"
-        let results = manager.search_logs("multi-logger", "Error").await.unwrap();
-        assert!(!results.is_empty(), "Should find error logs");
-        assert!(
-            results.iter().any(|log| log.line.contains("Error")),
-            "Should match error line"
-        );
+        assert!(manager.check_health().await.is_empty());
     }
 
     #[tokio::test]
-    async fn test_get_recent_logs() {
-        let mut manager = ProcessManager::new();
+    async fn test_apply_settings_resizes_log_buffers() {
+        let manager = ProcessManager::new();
+        let mut pinned = test_config("pinned", "sleep 10");
+        pinned.log_buffer_lines = Some(100);
 
-        let config = test_config(
-            "counter",
-            "sh -c 'for i in 1 2 3 4 5; do echo Line $i; done'",
-        );
-        manager.start(config).await.unwrap();
+        manager
+            .start(test_config("inherits", "sleep 10"))
+            .await
+            .unwrap();
+        manager.start(pinned).await.unwrap();
 
-        sleep(Duration::from_millis(300)).await;
+        manager
+            .apply_settings(GlobalSettings {
+                log_buffer_lines: 20,
+                ..GlobalSettings::default()
+            })
+            .await;
+
+        for (name, capacity) in [("inherits", 20), ("pinned", 100)] {
+            let buffer = manager.log_buffer(name).await.unwrap();
+            assert_eq!(buffer.lock().await.capacity(), capacity);
+        }
 
-        // Get last 3 logs
-        let recent = manager.get_recent_logs("counter", 3).await.unwrap();
-        assert!(recent.len() <= 5, "Should have at most 5 logs");
+        manager.stop_all().await.unwrap();
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_health_check_auto_restart() {
-        let mut manager = ProcessManager::new();
-
-        // Create a process that exits immediately but has auto_restart enabled
-        let mut config = test_config("auto-restart", "echo 'Starting'; exit 1");
-        config.auto_restart = true;
-        config.restart_limit = 2;
-        config.restart_delay = 50;
-
-        manager.start(config).await.unwrap();
-
-        // Wait for process to exit
-        sleep(Duration::from_millis(100)).await;
+    async fn test_reload_trims_log_buffers() {
+        let manager = ProcessManager::new();
+        let mut chatty = test_config("chatty", "sh");
+        chatty.args = vec![
+            "-c".to_string(),
+            "for i in 1 2 3 4 5; do echo line$i; done; sleep 10".to_string(),
+        ];
+        chatty.log_buffer_bytes = Some(1024);
+        manager.start(chatty.clone()).await.unwrap();
+        manager
+            .start(test_config("quiet", "sleep 10"))
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(300)).await;
 
-        // Run health check - should detect crash and restart
-        let restarted = manager.check_health().await;
+        chatty.log_buffer_bytes = Some(10);
+        manager.apply_log_limits(&[chatty]).await;
+        let stats = LogMemoryStats::new(manager.log_memory_usage().await);
+        manager.stop_all().await.unwrap();
 
-        assert!(
-            !restarted.is_empty(),
-            "Health check should restart crashed process"
+        // Two five-byte lines fit in ten bytes
+        let logs = &stats.buffers[0];
+        assert_eq!(logs.process, "chatty");
+        assert_eq!((logs.lines, logs.bytes, logs.max_bytes), (2, 10, Some(10)));
+        assert_eq!(stats.buffers[1].process, "quiet");
+        assert_eq!(
+            stats.buffers[1].max_bytes,
+            Some(crate::core::log_buffer::DEFAULT_MAX_BYTES)
         );
-        assert_eq!(restarted[0], "auto-restart");
-
-        // Check restart count incremented
-        let handle = manager.processes.get("auto-restart").unwrap();
-        assert_eq!(handle.restart_count, 1, "Restart count should be 1");
+        assert_eq!(stats.total_bytes, 10);
     }
 
     #[tokio::test]
     async fn test_health_check_respects_restart_limit() {
-        let mut manager = ProcessManager::new();
+        let manager = without_start_grace(GlobalSettings::default());
 
         // Create a process with restart_limit = 1
         let mut config = test_config("limited-restart", "sh -c 'exit 1'");
-        config.auto_restart = true;
+        config.auto_restart = Some(true);
         config.restart_limit = 1;
-        config.restart_delay = 50;
+        config.restart_delay = Some(50);
 
         manager.start(config).await.unwrap();
         sleep(Duration::from_millis(100)).await;
@@ -1088,24 +5288,143 @@ mod tests {
         // Process will exit again, but restart limit reached
         manager.check_health().await;
 
-        let handle = manager.processes.get("limited-restart").unwrap();
-        assert!(handle.restart_count <= 1, "Should not exceed restart limit");
+        let handle = manager.handle("limited-restart").unwrap();
+        assert!(
+            handle.lock().await.restart_count <= 1,
+            "Should not exceed restart limit"
+        );
     }
 
     #[tokio::test]
     async fn test_graceful_shutdown() {
-        let mut manager = ProcessManager::new();
+        let manager = ProcessManager::new();
 
         // Start a long-running process
         let config = test_config("graceful-test", "sleep 30");
         manager.start(config).await.unwrap();
-        assert!(manager.is_running("graceful-test"));
+        assert!(manager.is_running("graceful-test").await);
 
         // Stop gracefully
         manager.stop_gracefully("graceful-test").await.unwrap();
-        assert!(!manager.is_running("graceful-test"));
+        assert!(!manager.is_running("graceful-test").await);
 
-        let info = manager.get("graceful-test").unwrap();
+        let info = manager.get("graceful-test").await.unwrap();
         assert_eq!(info.state, ProcessState::Stopped);
     }
+
+    #[tokio::test]
+    async fn test_exit_code_is_recorded() {
+        let manager = without_start_grace(GlobalSettings::default());
+
+        let mut config = test_config("exits", "sh");
+        config.args = vec!["-c".to_string(), "exit 3".to_string()];
+        manager.start(config).await.unwrap();
+        assert_eq!(manager.get("exits").await.unwrap().exit_code, None);
+        sleep(Duration::from_millis(100)).await;
+        manager.check_health().await;
+        assert_eq!(manager.get("exits").await.unwrap().exit_code, Some(3));
+
+        // A process that handles SIGTERM reports its own exit code
+        let mut config = test_config("trapped", "sh");
+        config.args = vec![
+            "-c".to_string(),
+            "trap 'exit 7' TERM; while true; do sleep 0.05; done".to_string(),
+        ];
+        manager.start(config).await.unwrap();
+        sleep(Duration::from_millis(100)).await;
+        manager.stop_gracefully("trapped").await.unwrap();
+        assert_eq!(manager.get("trapped").await.unwrap().exit_code, Some(7));
+    }
+
+    fn tagged_config(name: &str, tags: &[&str], depends_on: &[&str]) -> ProcessConfig {
+        let mut config = test_config(name, "sleep 30");
+        config.tags = tags.iter().map(|t| t.to_string()).collect();
+        config.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_tagged_groups_overlap() {
+        let manager = ProcessManager::new();
+        let configs = vec![
+            tagged_config("cache", &["backend"], &["db"]),
+            tagged_config("db", &["backend", "data"], &[]),
+            tagged_config("etl", &["data"], &[]),
+            tagged_config("web", &[], &[]),
+        ];
+
+        let started = manager
+            .start_tagged(configs.clone(), "backend")
+            .await
+            .unwrap();
+        let names: Vec<_> = started.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["db", "cache"]);
+        assert!(started.iter().all(|(_, result)| result.is_ok()));
+        assert_eq!(
+            manager.get("db").await.unwrap().tags,
+            vec!["backend", "data"]
+        );
+
+        // db is shared with "backend" and already running
+        let started = manager.start_tagged(configs, "data").await.unwrap();
+        let names: Vec<_> = started.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["etl"]);
+
+        let stopped = manager.stop_tagged("backend").await.unwrap();
+        let names: Vec<_> = stopped.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["cache", "db"]);
+        assert!(!manager.is_running("db").await);
+        assert!(manager.is_running("etl").await);
+        assert!(manager.get("web").await.is_none());
+
+        let restarted = manager.restart_tagged("data").await.unwrap();
+        let names: Vec<_> = restarted.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["db", "etl"]);
+        assert!(manager.is_running("db").await && manager.is_running("etl").await);
+        assert!(!manager.is_running("cache").await);
+
+        let err = manager.stop_tagged("frontend").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No process is tagged 'frontend'; known tags: backend, data"
+        );
+        let usage = manager.tagged_usage("data").await.unwrap();
+        let names: Vec<_> = usage.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["db", "etl"]);
+
+        manager.stop_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tagged_dependency_outside_group() {
+        let manager = ProcessManager::new();
+        let db = tagged_config("db", &[], &[]);
+        let configs = vec![db.clone(), tagged_config("api", &["backend"], &["db"])];
+
+        let err = manager
+            .start_tagged(configs.clone(), "backend")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SentinelError::DependencyNotRunning { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Process 'api' depends on 'db', which is not tagged 'backend' and not running; start it first or tag it 'backend'"
+        );
+        assert!(
+            manager.list().await.is_empty(),
+            "nothing should have started"
+        );
+
+        manager.start(db).await.unwrap();
+        let started = manager.start_tagged(configs, "backend").await.unwrap();
+        assert_eq!(started.len(), 1);
+        assert!(manager.is_running("api").await);
+
+        assert!(matches!(
+            manager.stop_tagged("frontend").await,
+            Err(SentinelError::UnknownTag { .. })
+        ));
+
+        manager.stop_all().await.unwrap();
+    }
 }
@@ -0,0 +1,560 @@
+//! One view over piped, PTY and external processes.
+//!
+//! Processes are started either by [`ProcessManager`] (piped output) or by
+//! [`PtyProcessManager`] (pseudo-terminal). The registry lists both together
+//! and dispatches stop and restart to whichever manager owns a process.
+//!
+//! It also lists external processes: ones started outside Sentinel that the
+//! user asked to monitor by PID. Sentinel can stop them but doesn't own their
+//! lifecycle, so they can't be restarted. They are kept in the state file
+//! across restarts of Sentinel and dropped once their PID is gone.
+
+use crate::core::{ProcessManager, PtyProcessManager, StateManager, SystemMonitor};
+use crate::error::{Result, SentinelError};
+use crate::features::port_discovery::{PortScanner, PortState};
+use crate::models::{
+    ExternalProcessEntry, ManagedProcessRef, ManagedProcessSummary, ProcessKind, ProcessState,
+};
+use chrono::DateTime;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Façade over the piped and PTY process managers and external processes.
+pub struct ProcessRegistry {
+    process_manager: Arc<ProcessManager>,
+    pty_manager: Arc<Mutex<PtyProcessManager>>,
+    system_monitor: Arc<Mutex<SystemMonitor>>,
+    /// Monitored external processes, by display name
+    external: Mutex<HashMap<String, ExternalProcessEntry>>,
+    /// State file the external processes are kept in
+    state_path: PathBuf,
+}
+
+impl ProcessRegistry {
+    /// Creates a registry over the given managers, with the external
+    /// processes recorded in the state file at `state_path`.
+    ///
+    /// `system_monitor` supplies resource usage for PTY and external
+    /// processes.
+    pub fn new(
+        process_manager: Arc<ProcessManager>,
+        pty_manager: Arc<Mutex<PtyProcessManager>>,
+        system_monitor: Arc<Mutex<SystemMonitor>>,
+        state_path: PathBuf,
+    ) -> Self {
+        let external = StateManager::load_from(&state_path)
+            .map(|state| state.external_processes)
+            .unwrap_or_else(|e| {
+                warn!("Failed to load external processes: {}", e);
+                HashMap::new()
+            });
+        Self {
+            process_manager,
+            pty_manager,
+            system_monitor,
+            external: Mutex::new(external),
+            state_path,
+        }
+    }
+
+    /// Lists the processes of both managers and the external processes,
+    /// sorted by kind and ID.
+    ///
+    /// External processes that exited are dropped.
+    pub async fn list(&self) -> Vec<ManagedProcessSummary> {
+        self.process_manager.update_resource_usage().await;
+        let mut summaries: Vec<ManagedProcessSummary> = self
+            .process_manager
+            .list()
+            .await
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let pty_processes = self.pty_manager.lock().await.list_processes().await;
+        if !pty_processes.is_empty() {
+            let mut monitor = self.system_monitor.lock().await;
+            let pids: Vec<u32> = pty_processes.iter().map(|info| info.pid).collect();
+            monitor.refresh_processes(&pids);
+
+            summaries.extend(pty_processes.into_iter().map(|info| {
+                let stats = monitor.get_process_stats(info.pid);
+                ManagedProcessSummary {
+                    id: info.process_id,
+                    kind: ProcessKind::Pty,
+                    pid: Some(info.pid),
+                    state: ProcessState::Running,
+                    started_at: Some(info.started_at),
+                    cpu_usage: stats.map(|(cpu, _)| cpu),
+                    memory_usage: stats.map(|(_, memory)| memory),
+                    ports: Vec::new(),
+                }
+            }));
+        }
+
+        summaries.extend(self.external_summaries().await);
+        summaries.sort_by(|a, b| (a.kind, &a.id).cmp(&(b.kind, &b.id)));
+        summaries
+    }
+
+    /// Maps the PIDs of running processes of both managers, and of external
+    /// processes, to their owner.
+    ///
+    /// Cheaper than `list`, which also collects resource usage.
+    pub async fn managed_pids(&self) -> HashMap<u32, ManagedProcessRef> {
+        let mut pids: HashMap<u32, ManagedProcessRef> = self
+            .process_manager
+            .list()
+            .await
+            .into_iter()
+            .filter_map(|info| {
+                let pid = info.pid?;
+                Some((
+                    pid,
+                    ManagedProcessRef {
+                        id: info.name,
+                        kind: ProcessKind::Piped,
+                    },
+                ))
+            })
+            .collect();
+
+        let pty_processes = self.pty_manager.lock().await.list_processes().await;
+        pids.extend(pty_processes.into_iter().map(|info| {
+            (
+                info.pid,
+                ManagedProcessRef {
+                    id: info.process_id,
+                    kind: ProcessKind::Pty,
+                },
+            )
+        }));
+
+        let external = self.external.lock().await;
+        pids.extend(external.iter().map(|(name, entry)| {
+            (
+                entry.pid,
+                ManagedProcessRef {
+                    id: name.clone(),
+                    kind: ProcessKind::External,
+                },
+            )
+        }));
+        pids
+    }
+
+    /// Starts monitoring the running process `pid`, listed as `display_name`.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if the name is empty, no process has that PID
+    /// or Sentinel already lists it, and `ProcessAlreadyRunning` if a running
+    /// external process already has the name.
+    pub async fn monitor_external(
+        &self,
+        pid: u32,
+        display_name: &str,
+    ) -> Result<ManagedProcessSummary> {
+        let name = display_name.trim();
+        if name.is_empty() {
+            return Err(SentinelError::InvalidInput {
+                message: "Display name is empty".to_string(),
+            });
+        }
+        let Some(&start_time) = start_times(&[pid]).get(&pid) else {
+            return Err(SentinelError::InvalidInput {
+                message: format!("No process with PID {}", pid),
+            });
+        };
+        if let Some(owner) = self.managed_pids().await.get(&pid) {
+            return Err(SentinelError::InvalidInput {
+                message: format!("PID {} is already listed as '{}'", pid, owner.id),
+            });
+        }
+
+        {
+            let mut external = self.external.lock().await;
+            if let Some(existing) = external.get(name) {
+                if is_running(existing) {
+                    return Err(SentinelError::ProcessAlreadyRunning {
+                        name: name.to_string(),
+                        pid: existing.pid,
+                    });
+                }
+            }
+            external.insert(name.to_string(), ExternalProcessEntry { pid, start_time });
+            self.save_external(&external);
+        }
+        info!("Monitoring external process '{}' (PID {})", name, pid);
+
+        self.external_summaries()
+            .await
+            .into_iter()
+            .find(|summary| summary.id == name)
+            .ok_or_else(|| SentinelError::ProcessNotRunning {
+                name: name.to_string(),
+            })
+    }
+
+    /// Summaries of the external processes, dropping those that exited.
+    async fn external_summaries(&self) -> Vec<ManagedProcessSummary> {
+        let entries = {
+            let mut external = self.external.lock().await;
+            let pids: Vec<u32> = external.values().map(|entry| entry.pid).collect();
+            let running = start_times(&pids);
+            let count = external.len();
+            external.retain(|name, entry| {
+                let alive = running.get(&entry.pid) == Some(&entry.start_time);
+                if !alive {
+                    info!("External process '{}' (PID {}) is gone", name, entry.pid);
+                }
+                alive
+            });
+            if external.len() != count {
+                self.save_external(&external);
+            }
+            external.clone()
+        };
+        if entries.is_empty() {
+            return Vec::new();
+        }
+
+        let ports = listening_ports().await;
+        let mut monitor = self.system_monitor.lock().await;
+        let pids: Vec<u32> = entries.values().map(|entry| entry.pid).collect();
+        monitor.refresh_processes(&pids);
+        entries
+            .into_iter()
+            .map(|(name, entry)| {
+                let metrics = monitor.get_process_metrics(entry.pid);
+                ManagedProcessSummary {
+                    id: name,
+                    kind: ProcessKind::External,
+                    pid: Some(entry.pid),
+                    state: ProcessState::Running,
+                    started_at: i64::try_from(entry.start_time)
+                        .ok()
+                        .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+                    cpu_usage: metrics
+                        .as_ref()
+                        .and_then(|usage| usage.cpu_usage_percent_of_core),
+                    memory_usage: metrics.map(|usage| usage.memory_bytes),
+                    ports: ports.get(&entry.pid).cloned().unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
+    /// Stops an external process with SIGTERM, escalating to SIGKILL, and
+    /// stops monitoring it.
+    async fn stop_external(&self, name: &str) -> Result<()> {
+        let entry = self
+            .external
+            .lock()
+            .await
+            .get(name)
+            .cloned()
+            .ok_or_else(|| SentinelError::ProcessNotFound {
+                name: name.to_string(),
+            })?;
+        if is_running(&entry) {
+            PortScanner::new().kill_pid(entry.pid, name, true).await?;
+        }
+
+        let mut external = self.external.lock().await;
+        external.remove(name);
+        self.save_external(&external);
+        Ok(())
+    }
+
+    /// Writes the external processes to the state file, keeping the rest of it.
+    fn save_external(&self, external: &HashMap<String, ExternalProcessEntry>) {
+        let saved = StateManager::load_from(&self.state_path).and_then(|mut state| {
+            state.external_processes = external.clone();
+            StateManager::save_to(&state, &self.state_path)
+        });
+        if let Err(e) = saved {
+            warn!("Failed to save external processes: {}", e);
+        }
+    }
+
+    /// Stops a process with the manager for `kind`, or an external process
+    /// with signals.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` if that manager has no process `id`.
+    pub async fn stop(&self, kind: ProcessKind, id: &str) -> Result<()> {
+        match kind {
+            ProcessKind::Piped => self.process_manager.stop(id).await,
+            ProcessKind::Pty => self.pty_manager.lock().await.kill_process(id).await,
+            ProcessKind::External => self.stop_external(id).await,
+        }
+    }
+
+    /// Restarts a process with the manager for `kind`.
+    ///
+    /// # Errors
+    /// Returns `ProcessNotFound` if that manager has no process `id`,
+    /// `RestartUnavailable` for an external process, or the error from
+    /// starting it again.
+    pub async fn restart(&self, kind: ProcessKind, id: &str, app: AppHandle) -> Result<()> {
+        match kind {
+            ProcessKind::Piped => self.process_manager.restart(id).await.map(|_| ()),
+            ProcessKind::Pty => self
+                .pty_manager
+                .lock()
+                .await
+                .restart_process(id, app)
+                .await
+                .map(|_| ()),
+            ProcessKind::External => Err(SentinelError::RestartUnavailable {
+                name: id.to_string(),
+            }),
+        }
+    }
+}
+
+/// Whether the process of `entry` is still the one that was monitored.
+fn is_running(entry: &ExternalProcessEntry) -> bool {
+    start_times(&[entry.pid]).get(&entry.pid) == Some(&entry.start_time)
+}
+
+/// Start times of the processes among `pids` that are still running, in
+/// seconds since the epoch.
+fn start_times(pids: &[u32]) -> HashMap<u32, u64> {
+    let pids: Vec<Pid> = pids.iter().map(|&pid| Pid::from_u32(pid)).collect();
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system
+        .processes()
+        .iter()
+        .filter(|(_, process)| {
+            !matches!(
+                process.status(),
+                ProcessStatus::Zombie | ProcessStatus::Dead
+            )
+        })
+        .map(|(pid, process)| (pid.as_u32(), process.start_time()))
+        .collect()
+}
+
+/// Ports each process listens on, or none if ports can't be scanned.
+async fn listening_ports() -> HashMap<u32, Vec<u16>> {
+    let connections = match PortScanner::new().scan().await {
+        Ok(connections) => connections,
+        Err(e) => {
+            debug!("Failed to scan ports of external processes: {}", e);
+            return HashMap::new();
+        }
+    };
+    let mut ports: HashMap<u32, Vec<u16>> = HashMap::new();
+    for connection in connections {
+        if connection.state == PortState::Listen {
+            ports
+                .entry(connection.pid)
+                .or_default()
+                .push(connection.port);
+        }
+    }
+    for ports in ports.values_mut() {
+        ports.sort_unstable();
+        ports.dedup();
+    }
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PtyProcessConfig;
+    use crate::models::ProcessConfig;
+
+    fn registry_at(state_path: PathBuf) -> ProcessRegistry {
+        ProcessRegistry::new(
+            Arc::new(ProcessManager::new()),
+            Arc::new(Mutex::new(PtyProcessManager::new())),
+            Arc::new(Mutex::new(SystemMonitor::new())),
+            state_path,
+        )
+    }
+
+    fn piped_config(name: &str) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: "sleep 30".to_string(),
+            auto_restart: Some(false),
+            restart_limit: 0,
+            restart_delay: Some(100),
+            ..Default::default()
+        }
+    }
+
+    async fn start_both(registry: &ProcessRegistry) {
+        registry
+            .process_manager
+            .start(piped_config("worker"))
+            .await
+            .unwrap();
+        registry
+            .pty_manager
+            .lock()
+            .await
+            .spawn_with_events(
+                PtyProcessConfig::new("shell", "sleep", vec!["30".to_string()]),
+                |_| {},
+            )
+            .await
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_includes_both_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = registry_at(dir.path().join("state.json"));
+        start_both(&registry).await;
+
+        let summaries = registry.list().await;
+        let kinds: Vec<(&str, ProcessKind)> = summaries
+            .iter()
+            .map(|summary| (summary.id.as_str(), summary.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("worker", ProcessKind::Piped), ("shell", ProcessKind::Pty)]
+        );
+        assert!(summaries
+            .iter()
+            .all(|summary| summary.pid.is_some() && summary.state == ProcessState::Running));
+        assert!(summaries[1].memory_usage.is_some());
+
+        let pids = registry.managed_pids().await;
+        assert_eq!(pids.len(), 2);
+        for summary in &summaries {
+            let owner = &pids[&summary.pid.unwrap()];
+            assert_eq!(
+                (owner.id.as_str(), owner.kind),
+                (summary.id.as_str(), summary.kind)
+            );
+        }
+
+        registry.stop(ProcessKind::Piped, "worker").await.unwrap();
+        registry.stop(ProcessKind::Pty, "shell").await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stop_dispatches_by_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = registry_at(dir.path().join("state.json"));
+        start_both(&registry).await;
+
+        // Each manager only knows its own processes
+        assert!(matches!(
+            registry.stop(ProcessKind::Pty, "worker").await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
+        assert!(matches!(
+            registry.stop(ProcessKind::Piped, "shell").await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
+
+        registry.stop(ProcessKind::Piped, "worker").await.unwrap();
+        assert!(!registry.process_manager.is_running("worker").await);
+        assert!(registry.pty_manager.lock().await.is_running("shell").await);
+
+        registry.stop(ProcessKind::Pty, "shell").await.unwrap();
+        assert!(!registry.pty_manager.lock().await.is_running("shell").await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_external_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("state.json");
+        let registry = registry_at(state_path.clone());
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+
+        let summary = registry.monitor_external(pid, " db ").await.unwrap();
+        assert_eq!(
+            (summary.id.as_str(), summary.kind, summary.pid),
+            ("db", ProcessKind::External, Some(pid))
+        );
+        assert_eq!(summary.state, ProcessState::Running);
+        assert!(summary.started_at.is_some());
+        assert!(summary.memory_usage.is_some());
+
+        // Listed once, under one name
+        assert!(matches!(
+            registry.monitor_external(pid, "other").await,
+            Err(SentinelError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            registry.monitor_external(std::process::id(), "db").await,
+            Err(SentinelError::ProcessAlreadyRunning { .. })
+        ));
+
+        // Sentinel starting again finds it in the state file
+        let reopened = registry_at(state_path.clone());
+        let summaries = reopened.list().await;
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].id, "db");
+        assert_eq!(
+            reopened.managed_pids().await[&pid].kind,
+            ProcessKind::External
+        );
+
+        registry.stop(ProcessKind::External, "db").await.unwrap();
+        assert!(!child.wait().unwrap().success());
+        assert!(registry.list().await.is_empty());
+        assert!(StateManager::load_from(&state_path)
+            .unwrap()
+            .external_processes
+            .is_empty());
+        assert!(matches!(
+            registry.stop(ProcessKind::External, "db").await,
+            Err(SentinelError::ProcessNotFound { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exited_external_process_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("state.json");
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        registry_at(state_path.clone())
+            .monitor_external(child.id(), "db")
+            .await
+            .unwrap();
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        let registry = registry_at(state_path.clone());
+        assert!(registry.list().await.is_empty());
+        assert!(registry.managed_pids().await.is_empty());
+        assert!(StateManager::load_from(&state_path)
+            .unwrap()
+            .external_processes
+            .is_empty());
+        assert!(matches!(
+            registry.monitor_external(child.id(), "db").await,
+            Err(SentinelError::InvalidInput { .. })
+        ));
+    }
+}
@@ -0,0 +1,335 @@
+//! Process trees built from parent PID links.
+//!
+//! A managed process often spawns children (`npm run dev` starts node,
+//! which starts esbuild, ...) whose resource usage would otherwise not be
+//! attributed to it. The tree is built from a [`ProcessTable`] snapshot, so
+//! it can be tested without real processes.
+
+use crate::models::ProcessTreeNode;
+use std::collections::{HashMap, HashSet};
+use sysinfo::{ProcessStatus, System};
+
+/// A live process as seen in a process table snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessEntry {
+    /// OS process ID.
+    pub pid: u32,
+    /// Parent process ID, if known.
+    pub parent_pid: Option<u32>,
+    /// Process name.
+    pub name: String,
+    /// CPU usage percentage (0-100 per core).
+    pub cpu_usage: f32,
+    /// Memory usage in bytes.
+    pub memory: u64,
+    /// Start time in seconds since the epoch.
+    pub start_time: u64,
+}
+
+/// Source of live processes and their parent links.
+pub trait ProcessTable {
+    /// Returns every live process.
+    ///
+    /// Exited and zombie processes must not be included, so they never show
+    /// up as stale tree nodes.
+    fn processes(&self) -> Vec<ProcessEntry>;
+}
+
+impl ProcessTable for System {
+    fn processes(&self) -> Vec<ProcessEntry> {
+        System::processes(self)
+            .iter()
+            // Linux lists threads as tasks of their process; skip them
+            .filter(|(_, process)| {
+                process.thread_kind().is_none() && process.status() != ProcessStatus::Zombie
+            })
+            .map(|(pid, process)| ProcessEntry {
+                pid: pid.as_u32(),
+                parent_pid: process.parent().map(|parent| parent.as_u32()),
+                name: process.name().to_string_lossy().to_string(),
+                cpu_usage: process.cpu_usage(),
+                memory: process.memory(),
+                start_time: process.start_time(),
+            })
+            .collect()
+    }
+}
+
+/// Builds the tree of `root` and its live descendants.
+///
+/// # Returns
+/// `None` if `root` is not in the table.
+pub fn build_tree(table: &impl ProcessTable, root: u32) -> Option<ProcessTreeNode> {
+    ProcessIndex::new(table).tree(root)
+}
+
+/// Live descendants of `root`, not including `root` itself.
+pub fn descendants(table: &impl ProcessTable, root: u32) -> Vec<ProcessEntry> {
+    ProcessIndex::new(table).descendants(&[root])
+}
+
+/// Live processes from `known`, a set of PIDs with their start times, and
+/// their descendants.
+///
+/// `known` is the child set of a process recorded while it ran; once it has
+/// exited its children are re-parented, so the links to it are gone.
+/// Processes whose start time differs took over a PID of one that exited.
+pub fn orphans(table: &impl ProcessTable, known: &HashMap<u32, u64>) -> Vec<ProcessEntry> {
+    let index = ProcessIndex::new(table);
+    let mut found: Vec<ProcessEntry> = known
+        .iter()
+        .filter_map(|(pid, start_time)| {
+            index
+                .entries
+                .get(pid)
+                .filter(|entry| entry.start_time == *start_time)
+        })
+        .cloned()
+        .collect();
+    let roots: Vec<u32> = found.iter().map(|entry| entry.pid).collect();
+    found.extend(
+        index
+            .descendants(&roots)
+            .into_iter()
+            .filter(|entry| !roots.contains(&entry.pid)),
+    );
+    found.sort_by_key(|entry| entry.pid);
+    found
+}
+
+/// A process table snapshot indexed by parent, for looking up the trees of
+/// many processes with one pass over the table.
+pub struct ProcessIndex {
+    entries: HashMap<u32, ProcessEntry>,
+    /// Child PIDs by parent, sorted.
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessIndex {
+    pub fn new(table: &impl ProcessTable) -> Self {
+        let entries: HashMap<u32, ProcessEntry> = table
+            .processes()
+            .into_iter()
+            .map(|entry| (entry.pid, entry))
+            .collect();
+
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for entry in entries.values() {
+            if let Some(parent) = entry.parent_pid.filter(|&parent| parent != entry.pid) {
+                children.entry(parent).or_default().push(entry.pid);
+            }
+        }
+        for pids in children.values_mut() {
+            pids.sort_unstable();
+        }
+
+        Self { entries, children }
+    }
+
+    /// The tree of `root` and its live descendants, or `None` if `root` is
+    /// not in the table.
+    pub fn tree(&self, root: u32) -> Option<ProcessTreeNode> {
+        self.build_node(root, &mut HashSet::new())
+    }
+
+    /// Live descendants of `roots`, not including the roots themselves.
+    pub fn descendants(&self, roots: &[u32]) -> Vec<ProcessEntry> {
+        let mut visited: HashSet<u32> = roots.iter().copied().collect();
+        let mut pending = roots.to_vec();
+        let mut found = Vec::new();
+        while let Some(pid) = pending.pop() {
+            for &child in self.children.get(&pid).into_iter().flatten() {
+                // A reused PID can make parent links loop
+                if visited.insert(child) {
+                    pending.push(child);
+                    found.extend(self.entries.get(&child).cloned());
+                }
+            }
+        }
+        found
+    }
+
+    fn build_node(&self, pid: u32, visited: &mut HashSet<u32>) -> Option<ProcessTreeNode> {
+        // A reused PID can make parent links loop
+        if !visited.insert(pid) {
+            return None;
+        }
+        let entry = self.entries.get(&pid)?;
+
+        let children = self
+            .children
+            .get(&pid)
+            .map(|pids| {
+                pids.iter()
+                    .filter_map(|&child| self.build_node(child, visited))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(ProcessTreeNode {
+            pid,
+            name: entry.name.clone(),
+            cpu_usage: entry.cpu_usage,
+            memory: entry.memory,
+            children,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeTable(Vec<ProcessEntry>);
+
+    impl ProcessTable for FakeTable {
+        fn processes(&self) -> Vec<ProcessEntry> {
+            self.0.clone()
+        }
+    }
+
+    fn entry(pid: u32, parent_pid: Option<u32>, cpu_usage: f32, memory: u64) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent_pid,
+            name: format!("proc{}", pid),
+            cpu_usage,
+            memory,
+            start_time: u64::from(pid),
+        }
+    }
+
+    fn pids(node: &ProcessTreeNode) -> Vec<u32> {
+        node.children.iter().map(|child| child.pid).collect()
+    }
+
+    #[test]
+    fn test_build_tree_collects_descendants() {
+        let table = FakeTable(vec![
+            entry(1, None, 0.0, 10),
+            entry(100, Some(1), 0.5, 100),
+            entry(102, Some(100), 40.0, 300),
+            entry(101, Some(100), 10.0, 200),
+            entry(103, Some(101), 5.0, 50),
+            entry(200, Some(1), 99.0, 999),
+        ]);
+
+        let tree = build_tree(&table, 100).unwrap();
+        assert_eq!(tree.pid, 100);
+        assert_eq!(tree.name, "proc100");
+        assert_eq!(pids(&tree), vec![101, 102]);
+        assert_eq!(pids(&tree.children[0]), vec![103]);
+        assert!(tree.children[1].children.is_empty());
+
+        // Siblings of the root are not included
+        assert!((tree.total_cpu_usage() - 55.5).abs() < 0.001);
+        assert_eq!(tree.total_memory(), 650);
+    }
+
+    #[test]
+    fn test_build_tree_missing_root() {
+        let table = FakeTable(vec![entry(1, None, 0.0, 10)]);
+        assert!(build_tree(&table, 42).is_none());
+    }
+
+    #[test]
+    fn test_build_tree_skips_exited_children() {
+        // 101 exited between refreshes; its orphaned child was re-parented to init
+        let table = FakeTable(vec![
+            entry(1, None, 0.0, 10),
+            entry(100, Some(1), 1.0, 100),
+            entry(103, Some(1), 5.0, 50),
+        ]);
+
+        let tree = build_tree(&table, 100).unwrap();
+        assert!(tree.children.is_empty());
+        assert_eq!(tree.total_memory(), 100);
+    }
+
+    #[test]
+    fn test_build_tree_survives_parent_cycles() {
+        let table = FakeTable(vec![
+            entry(100, Some(101), 1.0, 100),
+            entry(101, Some(100), 1.0, 100),
+            entry(102, Some(102), 1.0, 100),
+        ]);
+
+        let tree = build_tree(&table, 100).unwrap();
+        assert_eq!(pids(&tree), vec![101]);
+        assert!(tree.children[0].children.is_empty());
+        assert!(build_tree(&table, 102).unwrap().children.is_empty());
+    }
+
+    #[test]
+    fn test_descendants_and_orphans() {
+        let table = FakeTable(vec![
+            entry(1, None, 0.0, 10),
+            entry(100, Some(1), 0.5, 100),
+            entry(101, Some(100), 10.0, 200),
+            entry(103, Some(101), 5.0, 50),
+            // Re-parented to init when its parent exited
+            entry(104, Some(1), 1.0, 20),
+            entry(105, Some(104), 1.0, 20),
+            entry(106, Some(106), 1.0, 20),
+        ]);
+
+        let mut pids: Vec<u32> = descendants(&table, 100).iter().map(|e| e.pid).collect();
+        pids.sort_unstable();
+        assert_eq!(pids, vec![101, 103]);
+        assert!(descendants(&table, 106).is_empty());
+
+        // 107 exited; 104 is still the process that was recorded, but the
+        // process now using PID 101 started later
+        let known = HashMap::from([(104, 104), (107, 107), (101, 1)]);
+        let found: Vec<u32> = orphans(&table, &known).iter().map(|e| e.pid).collect();
+        assert_eq!(found, vec![104, 105]);
+    }
+
+    #[test]
+    fn test_index_serves_many_roots() {
+        let table = FakeTable(vec![
+            entry(1, None, 0.0, 10),
+            entry(100, Some(1), 1.0, 100),
+            entry(101, Some(100), 2.0, 200),
+            entry(200, Some(1), 3.0, 300),
+            entry(201, Some(200), 4.0, 400),
+        ]);
+        let index = ProcessIndex::new(&table);
+
+        assert_eq!(index.tree(100).unwrap().total_memory(), 300);
+        assert_eq!(index.tree(200).unwrap().total_memory(), 700);
+        assert!(index.tree(300).is_none());
+        let mut pids: Vec<u32> = index
+            .descendants(&[100, 200])
+            .iter()
+            .map(|e| e.pid)
+            .collect();
+        pids.sort_unstable();
+        assert_eq!(pids, vec![101, 201]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_tree_from_system() {
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "sleep 30 & wait"])
+            .spawn()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+
+        let mut system = System::new();
+        system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        let tree = build_tree(&system, child.id()).unwrap();
+
+        for node in &tree.children {
+            let _ = std::process::Command::new("kill")
+                .arg(node.pid.to_string())
+                .status();
+        }
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].name, "sleep");
+    }
+}
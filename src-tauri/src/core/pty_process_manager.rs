@@ -1,14 +1,17 @@
 use chrono::{DateTime, Utc};
 use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::io::Read;
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Write};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
+use crate::core::child_env;
+use crate::core::log_decoder::LineDecoder;
 use crate::error::{Result as SentinelResult, SentinelError};
+use crate::models::{EnvMode, OutputEncoding};
 
 /// Event emitted when process produces output
 #[derive(Clone, Serialize, Deserialize)]
@@ -27,6 +30,47 @@ pub struct ProcessExitEvent {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Output or exit notification from a PTY process.
+pub(crate) enum PtyEvent {
+    Output(ProcessOutputEvent),
+    Exit(ProcessExitEvent),
+}
+
+/// Default scrollback kept per PTY process, in bytes.
+pub const DEFAULT_SCROLLBACK_BYTES: usize = 256 * 1024;
+
+/// The most recent output of a PTY process, capped at a byte limit.
+struct Scrollback {
+    data: VecDeque<u8>,
+    max_bytes: usize,
+}
+
+impl Scrollback {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            max_bytes,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend(bytes);
+        let excess = self.data.len().saturating_sub(self.max_bytes);
+        self.data.drain(..excess);
+    }
+
+    fn contents(&self) -> String {
+        // Trimming can split a UTF-8 sequence; skip its leftover continuation bytes
+        let bytes: Vec<u8> = self
+            .data
+            .iter()
+            .copied()
+            .skip_while(|b| b & 0xC0 == 0x80)
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
 /// Process configuration for persistence
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ProcessConfig {
@@ -35,6 +79,24 @@ pub struct ProcessConfig {
     pub args: Vec<String>,
     pub cwd: Option<String>,
     pub env: Option<HashMap<String, String>>,
+    /// Environment the process starts from, before `env` is added.
+    #[serde(default)]
+    pub env_mode: EnvMode,
+}
+
+impl ProcessConfig {
+    /// A config that runs `command` with `args` in Sentinel's directory and
+    /// environment
+    pub fn new(process_id: &str, command: &str, args: Vec<String>) -> Self {
+        Self {
+            process_id: process_id.to_string(),
+            command: command.to_string(),
+            args,
+            cwd: None,
+            env: None,
+            env_mode: EnvMode::Inherit,
+        }
+    }
 }
 
 /// Handle to a running PTY process
@@ -43,7 +105,11 @@ struct ProcessHandle {
     pid: u32,
     #[allow(dead_code)]
     config: ProcessConfig,
-    _pty_pair: PtyPair,
+    started_at: DateTime<Utc>,
+    pty_pair: PtyPair,
+    /// Writes to the terminal, i.e. the process's input.
+    writer: Box<dyn Write + Send>,
+    scrollback: Arc<std::sync::Mutex<Scrollback>>,
     reader_handle: JoinHandle<()>,
 }
 
@@ -51,26 +117,52 @@ struct ProcessHandle {
 pub struct PtyProcessManager {
     processes: Arc<Mutex<HashMap<String, ProcessHandle>>>,
     configs: Arc<Mutex<HashMap<String, ProcessConfig>>>, // Store configs for restart
+    scrollback_bytes: usize,
 }
 
 impl PtyProcessManager {
     pub fn new() -> Self {
+        Self::with_scrollback_bytes(DEFAULT_SCROLLBACK_BYTES)
+    }
+
+    /// Create a manager that keeps up to `scrollback_bytes` of output per process
+    pub fn with_scrollback_bytes(scrollback_bytes: usize) -> Self {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
             configs: Arc::new(Mutex::new(HashMap::new())),
+            scrollback_bytes,
         }
     }
 
     /// Spawn a process with PTY for terminal emulation
     pub async fn spawn_process(
         &self,
-        process_id: String,
-        command: String,
-        args: Vec<String>,
-        cwd: Option<String>,
-        env: Option<HashMap<String, String>>,
+        config: ProcessConfig,
         app: AppHandle,
     ) -> SentinelResult<u32> {
+        self.spawn_with_events(config, move |event| {
+            let _ = match event {
+                PtyEvent::Output(output) => app.emit("process-output", output),
+                PtyEvent::Exit(exit) => app.emit("process-exit", exit),
+            };
+        })
+        .await
+    }
+
+    /// Spawn a process with PTY, passing its output and exit to `on_event`
+    pub(crate) async fn spawn_with_events(
+        &self,
+        config: ProcessConfig,
+        on_event: impl Fn(PtyEvent) + Send + 'static,
+    ) -> SentinelResult<u32> {
+        let ProcessConfig {
+            process_id,
+            command,
+            args,
+            cwd,
+            env,
+            env_mode,
+        } = config.clone();
         tracing::info!(
             "Spawning PTY process: {} with command: {} {:?}",
             process_id,
@@ -78,10 +170,6 @@ impl PtyProcessManager {
             args
         );
 
-        // Clone for config storage (before they get moved)
-        let cwd_clone = cwd.clone();
-        let env_clone = env.clone();
-
         // 1. Create PTY pair with reasonable terminal size
         let pty_system = native_pty_system();
         let pty_pair = pty_system
@@ -101,10 +189,9 @@ impl PtyProcessManager {
             cmd.cwd(cwd_path);
         }
 
-        if let Some(ref env_vars) = env {
-            for (key, value) in env_vars {
-                cmd.env(key, value);
-            }
+        cmd.env_clear();
+        for (key, value) in child_env::build(env_mode, &env.unwrap_or_default()) {
+            cmd.env(key, value);
         }
 
         // 3. Spawn process in PTY
@@ -125,11 +212,21 @@ impl PtyProcessManager {
             .try_clone_reader()
             .map_err(|e| SentinelError::Other(format!("Failed to clone PTY reader: {}", e)))?;
 
+        let writer = pty_pair
+            .master
+            .take_writer()
+            .map_err(|e| SentinelError::Other(format!("Failed to open PTY writer: {}", e)))?;
+
+        let scrollback = Arc::new(std::sync::Mutex::new(Scrollback::new(
+            self.scrollback_bytes,
+        )));
+        let scrollback_clone = scrollback.clone();
         let process_id_clone = process_id.clone();
-        let app_clone = app.clone();
 
         let reader_handle = tokio::task::spawn_blocking(move || {
             let mut buffer = [0u8; 8192];
+            // Characters split between reads are held back until complete
+            let mut decoder = LineDecoder::new(OutputEncoding::Auto, None);
 
             loop {
                 match reader.read(&mut buffer) {
@@ -137,29 +234,31 @@ impl PtyProcessManager {
                         // EOF - process exited
                         tracing::info!("Process {} exited (EOF)", process_id_clone);
 
-                        let _ = app_clone.emit(
-                            "process-exit",
-                            ProcessExitEvent {
-                                process_id: process_id_clone.clone(),
-                                exit_code: None,
-                                timestamp: Utc::now(),
-                            },
-                        );
+                        on_event(PtyEvent::Exit(ProcessExitEvent {
+                            process_id: process_id_clone.clone(),
+                            exit_code: None,
+                            timestamp: Utc::now(),
+                        }));
 
                         break;
                     }
                     Ok(n) => {
-                        let output = String::from_utf8_lossy(&buffer[..n]).to_string();
-
-                        let _ = app_clone.emit(
-                            "process-output",
-                            ProcessOutputEvent {
-                                process_id: process_id_clone.clone(),
-                                output,
-                                stream: "stdout".to_string(),
-                                timestamp: Utc::now(),
-                            },
-                        );
+                        scrollback_clone
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push(&buffer[..n]);
+
+                        let output = decoder.feed_text(&buffer[..n]);
+                        if output.is_empty() {
+                            continue;
+                        }
+
+                        on_event(PtyEvent::Output(ProcessOutputEvent {
+                            process_id: process_id_clone.clone(),
+                            output,
+                            stream: "stdout".to_string(),
+                            timestamp: Utc::now(),
+                        }));
                     }
                     Err(e) => {
                         tracing::error!("Error reading PTY for {}: {}", process_id_clone, e);
@@ -178,13 +277,6 @@ impl PtyProcessManager {
         });
 
         // 5. Store config for restart capability
-        let config = ProcessConfig {
-            process_id: process_id.clone(),
-            command,
-            args,
-            cwd: cwd_clone,
-            env: env_clone,
-        };
         self.configs
             .lock()
             .await
@@ -195,7 +287,10 @@ impl PtyProcessManager {
             process_id: process_id.clone(),
             pid,
             config,
-            _pty_pair: pty_pair,
+            started_at: Utc::now(),
+            pty_pair,
+            writer,
+            scrollback,
             reader_handle,
         };
 
@@ -237,6 +332,68 @@ impl PtyProcessManager {
         }
     }
 
+    /// Resize the terminal of a process
+    pub async fn resize_pty(&self, process_id: &str, rows: u16, cols: u16) -> SentinelResult<()> {
+        if rows == 0 || cols == 0 {
+            return Err(SentinelError::InvalidInput {
+                message: format!("Invalid terminal size {}x{}", cols, rows),
+            });
+        }
+
+        let processes = self.processes.lock().await;
+        let handle = Self::handle(&processes, process_id)?;
+
+        handle
+            .pty_pair
+            .master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| SentinelError::Other(format!("Failed to resize PTY: {}", e)))
+    }
+
+    /// Write input (e.g. keystrokes) to the terminal of a process
+    ///
+    /// Writes hold the process table lock, so concurrent writes never interleave.
+    pub async fn write_to_pty(&self, process_id: &str, data: &[u8]) -> SentinelResult<()> {
+        let mut processes = self.processes.lock().await;
+        let handle =
+            processes
+                .get_mut(process_id)
+                .ok_or_else(|| SentinelError::ProcessNotFound {
+                    name: process_id.to_string(),
+                })?;
+
+        handle
+            .writer
+            .write_all(data)
+            .and_then(|()| handle.writer.flush())
+            .map_err(|e| SentinelError::Other(format!("Failed to write to PTY: {}", e)))
+    }
+
+    /// Get the buffered output of a process, so a new terminal view can show its history
+    pub async fn get_scrollback(&self, process_id: &str) -> SentinelResult<String> {
+        let processes = self.processes.lock().await;
+        let handle = Self::handle(&processes, process_id)?;
+
+        let scrollback = handle.scrollback.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(scrollback.contents())
+    }
+
+    fn handle<'a>(
+        processes: &'a HashMap<String, ProcessHandle>,
+        process_id: &str,
+    ) -> SentinelResult<&'a ProcessHandle> {
+        processes
+            .get(process_id)
+            .ok_or_else(|| SentinelError::ProcessNotFound {
+                name: process_id.to_string(),
+            })
+    }
+
     /// Get list of running processes managed by this manager
     pub async fn list_processes(&self) -> Vec<ProcessInfo> {
         let processes = self.processes.lock().await;
@@ -246,6 +403,7 @@ impl PtyProcessManager {
             .map(|handle| ProcessInfo {
                 process_id: handle.process_id.clone(),
                 pid: handle.pid,
+                started_at: handle.started_at,
             })
             .collect()
     }
@@ -276,15 +434,7 @@ impl PtyProcessManager {
         }
 
         // Respawn with same config
-        self.spawn_process(
-            config.process_id,
-            config.command,
-            config.args,
-            config.cwd,
-            config.env,
-            app,
-        )
-        .await
+        self.spawn_process(config, app).await
     }
 
     /// Get all stored process configurations
@@ -310,6 +460,7 @@ impl PtyProcessManager {
 pub struct ProcessInfo {
     pub process_id: String,
     pub pid: u32,
+    pub started_at: DateTime<Utc>,
 }
 
 impl Default for PtyProcessManager {
@@ -335,11 +486,7 @@ mod tests {
 
         let result = manager
             .spawn_process(
-                "test-echo".to_string(),
-                "echo".to_string(),
-                vec!["Hello World".to_string()],
-                None,
-                None,
+                ProcessConfig::new("test-echo", "echo", vec!["Hello World".to_string()]),
                 app,
             )
             .await;
@@ -349,6 +496,74 @@ mod tests {
         assert!(pid > 0);
     }
 
+    #[test]
+    fn test_scrollback_keeps_latest_bytes() {
+        let mut scrollback = Scrollback::new(8);
+        scrollback.push(b"hello ");
+        scrollback.push(b"world");
+        assert_eq!(scrollback.contents(), "lo world");
+
+        // A multi-byte character cut in half is dropped rather than garbled
+        let mut scrollback = Scrollback::new(4);
+        scrollback.push("aé€".as_bytes());
+        assert_eq!(scrollback.contents(), "€");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interactive_shell() {
+        let manager = PtyProcessManager::new();
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        manager
+            .spawn_with_events(
+                ProcessConfig::new(
+                    "shell",
+                    "bash",
+                    vec!["--norc".to_string(), "-i".to_string()],
+                ),
+                move |event| {
+                    if let PtyEvent::Output(output) = event {
+                        let _ = sender.send(output.output);
+                    }
+                },
+            )
+            .await
+            .unwrap();
+
+        manager.resize_pty("shell", 40, 120).await.unwrap();
+        manager.write_to_pty("shell", b"echo hi\n").await.unwrap();
+
+        // The terminal echoes the command line, then bash prints "hi" on a line of its own
+        let echoed = |output: &str| output.matches("hi\r\n").count() >= 2;
+        let mut output = String::new();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !echoed(&output) && std::time::Instant::now() < deadline {
+            if let Ok(chunk) = receiver.recv_timeout(std::time::Duration::from_millis(100)) {
+                output.push_str(&chunk);
+            }
+        }
+        assert!(echoed(&output), "no output from echo in {:?}", output);
+        assert!(echoed(&manager.get_scrollback("shell").await.unwrap()));
+
+        // An interactive shell ignores SIGTERM, so have it exit on its own
+        manager.write_to_pty("shell", b"exit\n").await.unwrap();
+        manager.kill_process("shell").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_process_id() {
+        let manager = PtyProcessManager::new();
+
+        for result in [
+            manager.resize_pty("missing", 24, 80).await,
+            manager.write_to_pty("missing", b"x").await,
+            manager.get_scrollback("missing").await.map(|_| ()),
+        ] {
+            assert!(matches!(result, Err(SentinelError::ProcessNotFound { .. })));
+        }
+    }
+
     #[tokio::test]
     async fn test_list_processes() {
         let manager = PtyProcessManager::new();
@@ -0,0 +1,326 @@
+//! Readiness probes: deciding when a started process can be relied on.
+//!
+//! Each started process with a `readiness` probe gets a [`watch`] channel
+//! that starts out [`Readiness::Probing`] and moves to `Ready` or `GaveUp`
+//! exactly once. TCP, HTTP and command probes run in a background task until
+//! they succeed or `maxWaitMs` runs out; log probes are checked against every
+//! line on its way into the process's log buffer. The probe task ends early
+//! once nobody holds a receiver any more, e.g. after the process is stopped.
+
+use crate::error::{Result, SentinelError};
+use crate::models::{ReadinessCheck, ReadinessProbe};
+use regex::Regex;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::process::Command;
+use tokio::sync::watch;
+use tokio::time::{sleep, timeout, Duration};
+use tracing::debug;
+
+/// Readiness of a started process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Readiness {
+    /// The probe hasn't succeeded yet.
+    Probing,
+    /// The probe succeeded this long after the process started.
+    Ready { after_ms: u64 },
+    /// The probe didn't succeed within `maxWaitMs`, given up after this long.
+    GaveUp { after_ms: u64 },
+}
+
+/// Matches output lines against a log probe's pattern.
+pub struct LogWatch {
+    pattern: Regex,
+    readiness: watch::Sender<Readiness>,
+    started: Instant,
+}
+
+impl LogWatch {
+    /// Marks the process ready if `line` matches and it isn't decided yet.
+    pub fn check(&self, line: &str) {
+        if *self.readiness.borrow() != Readiness::Probing || !self.pattern.is_match(line) {
+            return;
+        }
+        let after_ms = elapsed_ms(self.started);
+        self.readiness.send_if_modified(|readiness| {
+            if *readiness == Readiness::Probing {
+                *readiness = Readiness::Ready { after_ms };
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+/// Starts probing a process that was started at `started`.
+///
+/// Command probes run in `cwd`. For log probes the returned [`LogWatch`]
+/// must be fed every output line.
+///
+/// # Errors
+/// Returns `InvalidConfig` if a log probe's pattern isn't a valid regular
+/// expression.
+pub fn spawn(
+    probe: &ReadinessProbe,
+    cwd: Option<PathBuf>,
+    started: Instant,
+) -> Result<(watch::Receiver<Readiness>, Option<Arc<LogWatch>>)> {
+    let (sender, receiver) = watch::channel(Readiness::Probing);
+    let max_wait = Duration::from_millis(probe.max_wait_ms);
+
+    if let ReadinessCheck::Log { pattern } = &probe.check {
+        let watch = Arc::new(LogWatch {
+            pattern: compile_pattern(pattern)?,
+            readiness: sender,
+            started,
+        });
+        let deadline = watch.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = sleep(max_wait.saturating_sub(started.elapsed())) => {
+                    give_up(&deadline.readiness, started);
+                }
+                _ = deadline.readiness.closed() => {}
+            }
+        });
+        return Ok((receiver, Some(watch)));
+    }
+
+    let check = probe.check.clone();
+    let interval = Duration::from_millis(probe.interval_ms.max(1));
+    let attempt_timeout = Duration::from_millis(probe.timeout_ms);
+    tokio::spawn(async move {
+        while !sender.is_closed() {
+            if probe_once(&check, attempt_timeout, cwd.as_ref()).await {
+                let after_ms = elapsed_ms(started);
+                sender.send_replace(Readiness::Ready { after_ms });
+                return;
+            }
+            if started.elapsed() + interval >= max_wait {
+                give_up(&sender, started);
+                return;
+            }
+            sleep(interval).await;
+        }
+    });
+
+    Ok((receiver, None))
+}
+
+/// Waits until `readiness` is decided or `wait` runs out.
+///
+/// # Returns
+/// Milliseconds from starting to being ready.
+///
+/// # Errors
+/// Returns `ReadinessTimeout` if the probe gave up or `wait` ran out first.
+pub async fn wait(
+    name: &str,
+    mut readiness: watch::Receiver<Readiness>,
+    wait: Duration,
+) -> Result<u64> {
+    let timed_out = |waited_ms| SentinelError::ReadinessTimeout {
+        name: name.to_string(),
+        waited_ms,
+    };
+    let wait_ms = wait.as_millis() as u64;
+
+    let decided = timeout(
+        wait,
+        readiness.wait_for(|readiness| *readiness != Readiness::Probing),
+    )
+    .await
+    .map_err(|_| timed_out(wait_ms))?
+    .map(|readiness| *readiness);
+
+    match decided {
+        Ok(Readiness::Ready { after_ms }) => Ok(after_ms),
+        Ok(Readiness::GaveUp { after_ms }) => Err(timed_out(after_ms)),
+        // The probe task ended without deciding
+        Ok(Readiness::Probing) | Err(_) => Err(timed_out(wait_ms)),
+    }
+}
+
+/// Compiles a log probe's pattern.
+///
+/// # Errors
+/// Returns `InvalidConfig` with the pattern quoted if it doesn't compile.
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| SentinelError::InvalidConfig {
+        reason: format!("Invalid readiness pattern '{}': {}", pattern, e),
+    })
+}
+
+/// Makes one attempt at a TCP, HTTP or command probe.
+async fn probe_once(
+    check: &ReadinessCheck,
+    attempt_timeout: Duration,
+    cwd: Option<&PathBuf>,
+) -> bool {
+    let attempt = async {
+        match check {
+            ReadinessCheck::Tcp { host, port } => {
+                TcpStream::connect((host.as_str(), *port)).await.is_ok()
+            }
+            ReadinessCheck::Http {
+                url,
+                expected_status,
+            } => reqwest::Client::new()
+                .get(url)
+                .timeout(attempt_timeout)
+                .send()
+                .await
+                .is_ok_and(|response| response.status().as_u16() == *expected_status),
+            ReadinessCheck::Command { command, args } => {
+                let mut cmd = Command::new(command);
+                cmd.args(args)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true);
+                if let Some(cwd) = cwd {
+                    cmd.current_dir(cwd);
+                }
+                cmd.status().await.is_ok_and(|status| status.success())
+            }
+            // Checked line by line by LogWatch
+            ReadinessCheck::Log { .. } => false,
+        }
+    };
+
+    let ready = timeout(attempt_timeout, attempt).await.unwrap_or(false);
+    debug!("Readiness probe {:?}: {}", check, ready);
+    ready
+}
+
+/// Marks readiness as given up if it isn't decided yet.
+fn give_up(readiness: &watch::Sender<Readiness>, started: Instant) {
+    let after_ms = elapsed_ms(started);
+    readiness.send_if_modified(|readiness| {
+        if *readiness == Readiness::Probing {
+            *readiness = Readiness::GaveUp { after_ms };
+            true
+        } else {
+            false
+        }
+    });
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn probe(check: ReadinessCheck, max_wait_ms: u64) -> ReadinessProbe {
+        ReadinessProbe {
+            check,
+            interval_ms: 50,
+            timeout_ms: 500,
+            max_wait_ms,
+        }
+    }
+
+    async fn wait_for(probe: &ReadinessProbe) -> Result<u64> {
+        let (readiness, _) = spawn(probe, None, Instant::now())?;
+        wait("test", readiness, Duration::from_secs(5)).await
+    }
+
+    #[tokio::test]
+    async fn test_tcp_probe() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let tcp = |port| ReadinessCheck::Tcp {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+
+        assert!(wait_for(&probe(tcp(port), 2_000)).await.is_ok());
+
+        drop(listener);
+        let err = wait_for(&probe(tcp(port), 300)).await.unwrap_err();
+        assert!(matches!(err, SentinelError::ReadinessTimeout { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_http_probe_checks_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/ready", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+        let http = |expected_status| ReadinessCheck::Http {
+            url: url.clone(),
+            expected_status,
+        };
+
+        assert!(wait_for(&probe(http(204), 2_000)).await.is_ok());
+        assert!(wait_for(&probe(http(200), 300)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_command_probe() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let marker = dir.path().join("ready");
+        let check = ReadinessCheck::Command {
+            command: "test".to_string(),
+            args: vec!["-f".to_string(), marker.display().to_string()],
+        };
+
+        let marker_later = marker.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(200)).await;
+            std::fs::write(marker_later, "").unwrap();
+        });
+        let after_ms = wait_for(&probe(check.clone(), 2_000)).await.unwrap();
+        assert!(after_ms >= 200);
+
+        std::fs::remove_file(&marker).unwrap();
+        assert!(wait_for(&probe(check, 300)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_log_probe_matches_lines() {
+        let check = ReadinessCheck::Log {
+            pattern: r"listening on \d+".to_string(),
+        };
+        let (readiness, watch) = spawn(&probe(check, 2_000), None, Instant::now()).unwrap();
+        let watch = watch.unwrap();
+
+        watch.check("starting up");
+        watch.check("listening on port");
+        assert_eq!(*readiness.borrow(), Readiness::Probing);
+        watch.check("listening on 8080");
+        assert!(matches!(*readiness.borrow(), Readiness::Ready { .. }));
+
+        let check = ReadinessCheck::Log {
+            pattern: "never".to_string(),
+        };
+        let (readiness, _watch) = spawn(&probe(check, 200), None, Instant::now()).unwrap();
+        assert!(wait("test", readiness, Duration::from_secs(5))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_quoted() {
+        let err = compile_pattern("(unclosed").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid readiness pattern '(unclosed'"));
+    }
+}
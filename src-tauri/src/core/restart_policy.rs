@@ -0,0 +1,190 @@
+//! Restart policies for processes that degrade while running.
+//!
+//! A policy restarts a healthy process once it uses too much memory, has run
+//! too long, or reaches a cron time. The supervisor loop evaluates it; this
+//! module only decides whether a restart is due, so it can be driven with any
+//! clock and memory readings.
+
+use crate::core::scheduler;
+use crate::models::RestartPolicy;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// How long a process runs before its memory limit applies, so a limit that
+/// is too low can't restart it over and over.
+pub const MEMORY_GRACE: Duration = Duration::seconds(30);
+
+/// The policy setting that made a restart due.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyTrigger {
+    /// Memory use went over `maxMemoryBytes`.
+    MaxMemory { memory_bytes: u64, limit: u64 },
+    /// The process has run `maxUptimeHours`.
+    MaxUptime { hours: u64 },
+    /// A cron time came after the process started.
+    Cron { expression: String },
+}
+
+impl PolicyTrigger {
+    /// Name of the setting, as written in config files.
+    pub fn policy(&self) -> &'static str {
+        match self {
+            Self::MaxMemory { .. } => "maxMemoryBytes",
+            Self::MaxUptime { .. } => "maxUptimeHours",
+            Self::Cron { .. } => "cron",
+        }
+    }
+
+    /// Why the restart is due, for logs and the event journal.
+    pub fn reason(&self) -> String {
+        match self {
+            Self::MaxMemory {
+                memory_bytes,
+                limit,
+            } => format!("using {} bytes of memory (limit {})", memory_bytes, limit),
+            Self::MaxUptime { hours } => format!("running for {} hours", hours),
+            Self::Cron { expression } => format!("cron time '{}' reached", expression),
+        }
+    }
+}
+
+/// Decides whether `policy` restarts a process that started at `started_at`
+/// and uses `memory_bytes` of memory, as of `now`.
+///
+/// Cron times are read in `timezone`. A cron time fires if one has passed
+/// since the process started, so a restart that was missed happens once.
+pub fn evaluate<Tz: TimeZone>(
+    policy: &RestartPolicy,
+    started_at: DateTime<Utc>,
+    memory_bytes: u64,
+    now: DateTime<Utc>,
+    timezone: &Tz,
+) -> Option<PolicyTrigger> {
+    let uptime = now - started_at;
+
+    if let Some(limit) = policy.max_memory_bytes {
+        if memory_bytes > limit && uptime >= MEMORY_GRACE {
+            return Some(PolicyTrigger::MaxMemory {
+                memory_bytes,
+                limit,
+            });
+        }
+    }
+
+    if let Some(hours) = policy.max_uptime_hours {
+        let max_uptime = i64::try_from(hours)
+            .ok()
+            .and_then(Duration::try_hours)
+            .unwrap_or(Duration::MAX);
+        if uptime >= max_uptime {
+            return Some(PolicyTrigger::MaxUptime { hours });
+        }
+    }
+
+    if let Some(expression) = &policy.cron {
+        let started_local = started_at.with_timezone(timezone);
+        let next = scheduler::upcoming(expression, &started_local, 1)
+            .ok()
+            .and_then(|times| times.into_iter().next());
+        if next.is_some_and(|at| at.with_timezone(&Utc) <= now) {
+            return Some(PolicyTrigger::Cron {
+                expression: expression.clone(),
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_empty_policy_never_triggers() {
+        let policy = RestartPolicy::default();
+        assert!(policy.is_empty());
+        assert_eq!(evaluate(&policy, at(9, 0), u64::MAX, at(23, 0), &Utc), None);
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let policy = RestartPolicy {
+            max_memory_bytes: Some(500),
+            ..Default::default()
+        };
+
+        assert_eq!(evaluate(&policy, at(9, 0), 500, at(10, 0), &Utc), None);
+        assert_eq!(
+            evaluate(&policy, at(9, 0), 501, at(10, 0), &Utc),
+            Some(PolicyTrigger::MaxMemory {
+                memory_bytes: 501,
+                limit: 500
+            })
+        );
+        // Not right after a start
+        let just_started = at(9, 0) + Duration::seconds(10);
+        assert_eq!(evaluate(&policy, at(9, 0), 501, just_started, &Utc), None);
+    }
+
+    #[test]
+    fn test_max_uptime() {
+        let policy = RestartPolicy {
+            max_uptime_hours: Some(4),
+            ..Default::default()
+        };
+
+        assert_eq!(evaluate(&policy, at(9, 0), 0, at(12, 59), &Utc), None);
+        let trigger = evaluate(&policy, at(9, 0), 0, at(13, 0), &Utc).unwrap();
+        assert_eq!(trigger, PolicyTrigger::MaxUptime { hours: 4 });
+        assert_eq!(trigger.policy(), "maxUptimeHours");
+    }
+
+    #[test]
+    fn test_cron_fires_once_per_start() {
+        let policy = RestartPolicy {
+            cron: Some("0 14 * * *".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(evaluate(&policy, at(9, 0), 0, at(13, 59), &Utc), None);
+        assert_eq!(
+            evaluate(&policy, at(9, 0), 0, at(14, 0), &Utc),
+            Some(PolicyTrigger::Cron {
+                expression: "0 14 * * *".to_string()
+            })
+        );
+        // Once restarted, the next time is tomorrow's
+        assert_eq!(evaluate(&policy, at(14, 0), 0, at(14, 1), &Utc), None);
+        // A missed time still fires
+        assert!(evaluate(&policy, at(9, 0), 0, at(20, 0), &Utc).is_some());
+    }
+
+    #[test]
+    fn test_cron_uses_timezone() {
+        let policy = RestartPolicy {
+            cron: Some("0 14 * * *".to_string()),
+            ..Default::default()
+        };
+        let new_york = chrono_tz::America::New_York;
+
+        // 14:00 in New York is 19:00 UTC in March (before DST starts)
+        assert_eq!(evaluate(&policy, at(9, 0), 0, at(18, 59), &new_york), None);
+        assert!(evaluate(&policy, at(9, 0), 0, at(19, 0), &new_york).is_some());
+    }
+
+    #[test]
+    fn test_memory_takes_precedence() {
+        let policy = RestartPolicy {
+            max_memory_bytes: Some(100),
+            max_uptime_hours: Some(1),
+            cron: Some("0 10 * * *".to_string()),
+        };
+        let trigger = evaluate(&policy, at(9, 0), 200, at(11, 0), &Utc).unwrap();
+        assert_eq!(trigger.policy(), "maxMemoryBytes");
+        assert_eq!(trigger.reason(), "using 200 bytes of memory (limit 100)");
+    }
+}
@@ -0,0 +1,310 @@
+//! Cron schedules for processes that run at set times.
+//!
+//! Expressions use the usual five fields (`minute hour day month weekday`);
+//! six or seven fields add seconds and years, and shorthands such as `@daily`
+//! work too. Days of the week are best written by name (`Mon-Fri`), as
+//! numbers count from Sunday = 1.
+//!
+//! Times are wall-clock times in the schedule's time zone. When clocks go
+//! forward, a time that doesn't exist that day is skipped; when they go back,
+//! a time that happens twice fires only the first time.
+
+use crate::error::{Result, SentinelError};
+use crate::models::ProcessConfig;
+use chrono::offset::LocalResult;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use cron::Schedule;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Parses a cron expression.
+///
+/// # Errors
+/// Returns `InvalidSchedule`, quoting the expression, if it can't be parsed.
+pub fn parse(expression: &str) -> Result<Schedule> {
+    let trimmed = expression.trim();
+    // The cron crate expects a seconds field first
+    let normalized = if trimmed.split_whitespace().count() == 5 {
+        format!("0 {}", trimmed)
+    } else {
+        trimmed.to_string()
+    };
+
+    Schedule::from_str(&normalized).map_err(|e| {
+        // The message points at the (normalized) expression above its last line
+        let message = e.to_string();
+        SentinelError::InvalidSchedule {
+            expression: expression.to_string(),
+            reason: message.lines().last().unwrap_or_default().to_string(),
+        }
+    })
+}
+
+/// Returns the next `count` times `expression` fires after `after`.
+///
+/// # Errors
+/// Returns `InvalidSchedule` if the expression can't be parsed.
+pub fn upcoming<Tz: TimeZone>(
+    expression: &str,
+    after: &DateTime<Tz>,
+    count: usize,
+) -> Result<Vec<DateTime<Tz>>> {
+    let schedule = parse(expression)?;
+    Ok(fire_times(&schedule, after).take(count).collect())
+}
+
+/// Times `schedule` fires after `after`, without the repeat of a wall-clock
+/// time that happens twice when clocks go back.
+fn fire_times<'a, Tz: TimeZone + 'a>(
+    schedule: &'a Schedule,
+    after: &DateTime<Tz>,
+) -> impl Iterator<Item = DateTime<Tz>> + 'a {
+    let timezone = after.timezone();
+    let after = after.clone();
+    schedule.after(&after).filter(move |time| {
+        // Inside a repeated hour the cron crate can return the earlier
+        // (already past) occurrence of a time
+        *time > after
+            && match timezone.from_local_datetime(&time.naive_local()) {
+                LocalResult::Ambiguous(earliest, _) => earliest == *time,
+                _ => true,
+            }
+    })
+}
+
+/// Next fire time of one scheduled process.
+struct NextRun {
+    /// Expression the time was computed from.
+    expression: String,
+    /// `None` once the schedule has no more fire times.
+    at: Option<DateTime<Utc>>,
+}
+
+/// Decides when scheduled processes are due.
+///
+/// Remembers each process's next fire time; a process first seen by
+/// [`Scheduler::due`] waits for its next time rather than firing right away.
+pub struct Scheduler<Tz: TimeZone = Local> {
+    timezone: Tz,
+    next_runs: HashMap<String, NextRun>,
+}
+
+impl Scheduler<Local> {
+    /// Creates a scheduler using the local time zone.
+    pub fn new() -> Self {
+        Self::with_timezone(Local)
+    }
+}
+
+impl Default for Scheduler<Local> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Tz: TimeZone> Scheduler<Tz> {
+    /// Creates a scheduler that reads schedules in `timezone`.
+    pub fn with_timezone(timezone: Tz) -> Self {
+        Self {
+            timezone,
+            next_runs: HashMap::new(),
+        }
+    }
+
+    /// Returns the scheduled processes in `configs` whose fire time has come
+    /// by `now`, and moves their next fire time past `now`.
+    ///
+    /// Several fire times missed since the last call fire once. Processes
+    /// that are gone from `configs`, or whose expression doesn't parse, are
+    /// forgotten.
+    pub fn due<'a>(
+        &mut self,
+        configs: &'a [ProcessConfig],
+        now: DateTime<Utc>,
+    ) -> Vec<&'a ProcessConfig> {
+        let local_now = now.with_timezone(&self.timezone);
+        let next_after = |expression: &str| {
+            parse(expression)
+                .ok()
+                .and_then(|schedule| fire_times(&schedule, &local_now).next())
+                .map(|time| time.with_timezone(&Utc))
+        };
+
+        self.next_runs.retain(|name, _| {
+            configs
+                .iter()
+                .any(|config| &config.name == name && config.schedule.is_some())
+        });
+
+        let mut due = Vec::new();
+        for config in configs {
+            let Some(expression) = &config.schedule else {
+                continue;
+            };
+
+            match self.next_runs.get_mut(&config.name) {
+                Some(next) if next.expression == *expression => {
+                    if next.at.is_some_and(|at| at <= now) {
+                        next.at = next_after(expression);
+                        due.push(config);
+                    }
+                }
+                _ => {
+                    self.next_runs.insert(
+                        config.name.clone(),
+                        NextRun {
+                            expression: expression.clone(),
+                            at: next_after(expression),
+                        },
+                    );
+                }
+            }
+        }
+
+        due
+    }
+
+    /// Next fire time of the process `name`, as of the last [`Scheduler::due`].
+    pub fn next_run(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.next_runs.get(name).and_then(|next| next.at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono_tz::America::New_York;
+
+    fn scheduled(name: &str, schedule: &str) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: "true".to_string(),
+            auto_restart: Some(false),
+            restart_limit: 0,
+            schedule: Some(schedule.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn new_york(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<chrono_tz::Tz> {
+        New_York
+            .with_ymd_and_hms(y, m, d, h, min, 0)
+            .earliest()
+            .unwrap()
+    }
+
+    fn wall_clock<Tz: TimeZone>(times: &[DateTime<Tz>]) -> Vec<String>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        times
+            .iter()
+            .map(|t| t.format("%m-%d %H:%M %Z").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_accepts_five_fields_and_shorthands() {
+        assert!(parse("30 2 * * *").is_ok());
+        assert!(parse("0 30 2 * * *").is_ok());
+        assert!(parse("*/15 9-17 * * Mon-Fri").is_ok());
+        assert!(parse("@daily").is_ok());
+    }
+
+    #[test]
+    fn test_parse_error_quotes_expression() {
+        let err = parse("61 * * * *").unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("Invalid schedule '61 * * * *': "));
+        assert!(!message.contains('\n'));
+
+        assert!(parse("every night").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let after = new_york(2025, 1, 1, 12, 0);
+        let times = upcoming("0 9 * * Mon-Fri", &after, 3).unwrap();
+        assert_eq!(
+            wall_clock(&times),
+            ["01-02 09:00 EST", "01-03 09:00 EST", "01-06 09:00 EST"]
+        );
+    }
+
+    #[test]
+    fn test_spring_forward_skips_missing_time() {
+        // Clocks go from 02:00 to 03:00 on 2025-03-09
+        let after = new_york(2025, 3, 8, 0, 0);
+        let times = upcoming("30 2 * * *", &after, 2).unwrap();
+        assert_eq!(wall_clock(&times), ["03-08 02:30 EST", "03-10 02:30 EDT"]);
+
+        let times = upcoming("0 * * * *", &new_york(2025, 3, 9, 0, 30), 3).unwrap();
+        assert_eq!(
+            wall_clock(&times),
+            ["03-09 01:00 EST", "03-09 03:00 EDT", "03-09 04:00 EDT"]
+        );
+    }
+
+    #[test]
+    fn test_fall_back_fires_repeated_time_once() {
+        // Clocks go from 02:00 back to 01:00 on 2025-11-02
+        let after = new_york(2025, 11, 1, 12, 0);
+        let times = upcoming("30 1 * * *", &after, 3).unwrap();
+        assert_eq!(
+            wall_clock(&times),
+            ["11-02 01:30 EDT", "11-03 01:30 EST", "11-04 01:30 EST"]
+        );
+
+        // Starting inside the repeated hour doesn't fire it again
+        let second_one_thirty = New_York
+            .with_ymd_and_hms(2025, 11, 2, 1, 0, 0)
+            .latest()
+            .unwrap();
+        let times = upcoming("30 1 * * *", &second_one_thirty, 1).unwrap();
+        assert_eq!(wall_clock(&times), ["11-03 01:30 EST"]);
+    }
+
+    #[test]
+    fn test_scheduler_fires_at_cron_times() {
+        let configs = vec![
+            scheduled("sync", "0 2 * * *"),
+            scheduled("other", "0 3 * * *"),
+        ];
+        let mut scheduler = Scheduler::with_timezone(New_York);
+        let at = |h, m| new_york(2025, 6, 1, h, m).with_timezone(&Utc);
+
+        // First sight only computes the next time
+        assert!(scheduler.due(&configs, at(1, 0)).is_empty());
+        assert_eq!(scheduler.next_run("sync"), Some(at(2, 0)));
+        assert!(scheduler.due(&configs, at(1, 59)).is_empty());
+
+        let due: Vec<_> = scheduler
+            .due(&configs, at(2, 0))
+            .iter()
+            .map(|c| c.name.clone())
+            .collect();
+        assert_eq!(due, ["sync"]);
+        assert!(scheduler.due(&configs, at(2, 1)).is_empty());
+        assert_eq!(
+            scheduler.next_run("sync"),
+            Some(new_york(2025, 6, 2, 2, 0).with_timezone(&Utc))
+        );
+    }
+
+    #[test]
+    fn test_scheduler_forgets_changed_and_removed_schedules() {
+        let mut scheduler = Scheduler::with_timezone(New_York);
+        let at = |h, m| new_york(2025, 6, 1, h, m).with_timezone(&Utc);
+
+        scheduler.due(&[scheduled("sync", "0 2 * * *")], at(1, 0));
+        // A new expression starts over from its own next time
+        let changed = [scheduled("sync", "0 4 * * *")];
+        assert!(scheduler.due(&changed, at(3, 0)).is_empty());
+        assert_eq!(scheduler.next_run("sync"), Some(at(4, 0)));
+
+        scheduler.due(&[], at(3, 30));
+        assert_eq!(scheduler.next_run("sync"), None);
+    }
+}
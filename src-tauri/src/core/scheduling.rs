@@ -0,0 +1,331 @@
+//! Process priority (nice) and CPU affinity.
+//!
+//! Priorities use Unix nice values everywhere; on Windows they map onto the
+//! nearest priority class. CPU affinity is supported on Linux and Windows.
+
+use crate::error::{Result, SentinelError};
+use std::sync::OnceLock;
+use sysinfo::{CpuRefreshKind, RefreshKind, System};
+
+/// Highest priority a process can be given.
+pub const MIN_NICE: i32 = -20;
+
+/// Lowest priority a process can be given.
+pub const MAX_NICE: i32 = 19;
+
+/// Whether CPU affinity can be set on this platform.
+pub const AFFINITY_SUPPORTED: bool = cfg!(any(target_os = "linux", windows));
+
+/// Number of logical CPU cores on this machine.
+pub fn core_count() -> usize {
+    static CORES: OnceLock<usize> = OnceLock::new();
+    *CORES.get_or_init(|| {
+        let system =
+            System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing()));
+        system.cpus().len().max(1)
+    })
+}
+
+/// Checks that `nice` is a valid priority.
+///
+/// # Errors
+/// Returns `InvalidPriority` if it is outside [`MIN_NICE`]..=[`MAX_NICE`].
+pub fn validate_priority(nice: i32) -> Result<()> {
+    if (MIN_NICE..=MAX_NICE).contains(&nice) {
+        Ok(())
+    } else {
+        Err(SentinelError::InvalidPriority { nice })
+    }
+}
+
+/// Checks that every core in `cores` exists on this machine.
+///
+/// # Errors
+/// Returns `InvalidCpuAffinity` if `cores` is empty, names a core that
+/// doesn't exist, or affinity is unsupported on this platform.
+pub fn validate_affinity(cores: &[usize]) -> Result<()> {
+    let invalid = |reason: String| {
+        Err(SentinelError::InvalidCpuAffinity {
+            cores: cores.to_vec(),
+            reason,
+        })
+    };
+
+    if !AFFINITY_SUPPORTED {
+        return invalid("CPU affinity is not supported on this platform".to_string());
+    }
+    if cores.is_empty() {
+        return invalid("at least one core is required".to_string());
+    }
+
+    let count = core_count();
+    match cores.iter().find(|&&core| core >= count) {
+        Some(core) => invalid(format!(
+            "core {} does not exist; this machine has cores 0-{}",
+            core,
+            count - 1
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Current nice value of a process, if it can be read.
+pub fn priority(pid: u32) -> Option<i32> {
+    imp::priority(pid)
+}
+
+/// Sets the priority of a running process.
+///
+/// Raising priority (a negative nice value) usually needs elevated privileges.
+///
+/// # Errors
+/// Returns `InvalidPriority` for an out-of-range value, or `SchedulingFailed`
+/// if the OS refuses the change.
+pub fn set_priority(pid: u32, nice: i32) -> Result<()> {
+    validate_priority(nice)?;
+    imp::set_priority(pid, nice).map_err(|source| SentinelError::SchedulingFailed { pid, source })
+}
+
+/// Restricts a running process to `cores`.
+///
+/// # Errors
+/// Returns `InvalidCpuAffinity` for cores that can't be used, or
+/// `SchedulingFailed` if the OS refuses the change.
+pub fn set_affinity(pid: u32, cores: &[usize]) -> Result<()> {
+    validate_affinity(cores)?;
+    imp::set_affinity(pid, cores).map_err(|source| SentinelError::SchedulingFailed { pid, source })
+}
+
+/// Applies whichever of `nice` and `cores` are set to a running process.
+pub fn apply(pid: u32, nice: Option<i32>, cores: Option<&[usize]>) -> Result<()> {
+    if let Some(nice) = nice {
+        set_priority(pid, nice)?;
+    }
+    if let Some(cores) = cores {
+        set_affinity(pid, cores)?;
+    }
+    Ok(())
+}
+
+/// Applies `nice` and `cores` to the calling process.
+///
+/// Meant for a `pre_exec` hook, so the child runs with them from its first
+/// instruction. Only makes async-signal-safe calls; values must already be
+/// validated.
+#[cfg(unix)]
+pub(crate) fn apply_to_self(nice: Option<i32>, cores: Option<&[usize]>) -> std::io::Result<()> {
+    if let Some(nice) = nice {
+        imp::set_thread_priority(0, nice)?;
+    }
+    #[cfg(target_os = "linux")]
+    if let Some(cores) = cores {
+        imp::set_thread_affinity(0, cores)?;
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = cores;
+    Ok(())
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io;
+
+    pub fn priority(pid: u32) -> Option<i32> {
+        // -1 is a valid nice value, so errors are only visible through errno
+        clear_errno();
+        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+        if nice == -1 && io::Error::last_os_error().raw_os_error() != Some(0) {
+            return None;
+        }
+        Some(nice)
+    }
+
+    pub fn set_priority(pid: u32, nice: i32) -> io::Result<()> {
+        // Linux keeps a nice value per thread, so change every thread
+        for tid in threads(pid) {
+            set_thread_priority(tid, nice)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_thread_priority(tid: u32, nice: i32) -> io::Result<()> {
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_affinity(pid: u32, cores: &[usize]) -> io::Result<()> {
+        for tid in threads(pid) {
+            set_thread_affinity(tid, cores)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_affinity(_pid: u32, _cores: &[usize]) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_thread_affinity(tid: u32, cores: &[usize]) -> io::Result<()> {
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for &core in cores {
+            unsafe { libc::CPU_SET(core, &mut set) };
+        }
+
+        let size = std::mem::size_of::<libc::cpu_set_t>();
+        if unsafe { libc::sched_setaffinity(tid as libc::pid_t, size, &set) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Thread IDs of a process; just the process itself where threads can't be listed.
+    fn threads(pid: u32) -> Vec<u32> {
+        #[cfg(target_os = "linux")]
+        if let Ok(entries) = std::fs::read_dir(format!("/proc/{}/task", pid)) {
+            let tids: Vec<u32> = entries
+                .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+                .collect();
+            if !tids.is_empty() {
+                return tids;
+            }
+        }
+        vec![pid]
+    }
+
+    fn clear_errno() {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        unsafe {
+            *libc::__errno_location() = 0
+        };
+        #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+        unsafe {
+            *libc::__error() = 0
+        };
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use super::{MAX_NICE, MIN_NICE};
+    use std::ffi::c_void;
+    use std::io;
+
+    const PROCESS_SET_INFORMATION: u32 = 0x0200;
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+    const IDLE_PRIORITY_CLASS: u32 = 0x0040;
+    const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x4000;
+    const NORMAL_PRIORITY_CLASS: u32 = 0x0020;
+    const ABOVE_NORMAL_PRIORITY_CLASS: u32 = 0x8000;
+    const HIGH_PRIORITY_CLASS: u32 = 0x0080;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+        fn GetPriorityClass(handle: *mut c_void) -> u32;
+        fn SetPriorityClass(handle: *mut c_void, class: u32) -> i32;
+        fn SetProcessAffinityMask(handle: *mut c_void, mask: usize) -> i32;
+    }
+
+    /// Process handle closed on drop.
+    struct Handle(*mut c_void);
+
+    impl Handle {
+        fn open(pid: u32, access: u32) -> io::Result<Self> {
+            let handle = unsafe { OpenProcess(access, 0, pid) };
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self(handle))
+        }
+    }
+
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    pub fn priority(pid: u32) -> Option<i32> {
+        let handle = Handle::open(pid, PROCESS_QUERY_LIMITED_INFORMATION).ok()?;
+        match unsafe { GetPriorityClass(handle.0) } {
+            IDLE_PRIORITY_CLASS => Some(MAX_NICE),
+            BELOW_NORMAL_PRIORITY_CLASS => Some(10),
+            NORMAL_PRIORITY_CLASS => Some(0),
+            ABOVE_NORMAL_PRIORITY_CLASS => Some(-10),
+            HIGH_PRIORITY_CLASS => Some(MIN_NICE),
+            _ => None,
+        }
+    }
+
+    pub fn set_priority(pid: u32, nice: i32) -> io::Result<()> {
+        let class = match nice {
+            15.. => IDLE_PRIORITY_CLASS,
+            5..=14 => BELOW_NORMAL_PRIORITY_CLASS,
+            -4..=4 => NORMAL_PRIORITY_CLASS,
+            -14..=-5 => ABOVE_NORMAL_PRIORITY_CLASS,
+            _ => HIGH_PRIORITY_CLASS,
+        };
+
+        let handle = Handle::open(pid, PROCESS_SET_INFORMATION)?;
+        if unsafe { SetPriorityClass(handle.0, class) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub fn set_affinity(pid: u32, cores: &[usize]) -> io::Result<()> {
+        let mut mask = 0usize;
+        for &core in cores {
+            if core >= usize::BITS as usize {
+                return Err(io::Error::from(io::ErrorKind::InvalidInput));
+            }
+            mask |= 1 << core;
+        }
+
+        let handle = Handle::open(pid, PROCESS_SET_INFORMATION)?;
+        if unsafe { SetProcessAffinityMask(handle.0, mask) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_priority() {
+        assert!(validate_priority(MIN_NICE).is_ok());
+        assert!(validate_priority(0).is_ok());
+        assert!(validate_priority(MAX_NICE).is_ok());
+        assert!(matches!(
+            validate_priority(20),
+            Err(SentinelError::InvalidPriority { nice: 20 })
+        ));
+        assert!(validate_priority(-21).is_err());
+    }
+
+    #[test]
+    fn test_validate_affinity() {
+        assert!(validate_affinity(&[]).is_err());
+        assert!(matches!(
+            validate_affinity(&[core_count()]),
+            Err(SentinelError::InvalidCpuAffinity { .. })
+        ));
+        assert_eq!(validate_affinity(&[0]).is_ok(), AFFINITY_SUPPORTED);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_priority_of_current_process() {
+        assert!(priority(std::process::id()).is_some());
+        assert!(priority(u32::MAX).is_none());
+    }
+}
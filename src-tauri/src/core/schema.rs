@@ -0,0 +1,258 @@
+//! JSON Schema of the configuration file.
+//!
+//! Editors use it to complete and check `sentinel.yaml` as it is typed, e.g.
+//! VS Code with the YAML extension and a `# yaml-language-server: $schema=`
+//! comment. The schema is derived from the config structs, so it follows
+//! their serde names and defaults, and their doc comments become the
+//! descriptions shown in tooltips.
+
+use crate::models::Config;
+use schemars::gen::SchemaSettings;
+use schemars::schema::Schema;
+use serde_json::Value;
+
+/// Title of the schema, shown by editors that list known schemas.
+pub const SCHEMA_TITLE: &str = "Sentinel configuration";
+
+/// The JSON Schema (draft 7) of a configuration file.
+pub fn config_schema() -> Value {
+    let generator = SchemaSettings::draft07().into_generator();
+    let mut schema = generator.into_root_schema_for::<Config>();
+    schema.schema.metadata().title = Some(SCHEMA_TITLE.to_string());
+    // A process that `extends` another may inherit its command
+    if let Some(Schema::Object(process)) = schema.definitions.get_mut("ProcessConfig") {
+        process.object().required.remove("command");
+    }
+    // Plain data, which always serializes
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ConfigManager;
+    use std::path::Path;
+
+    /// Checks `value` against the parts of JSON Schema the derived schema
+    /// uses, collecting a message for each mismatch.
+    fn validate(root: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/definitions/");
+            return validate(root, &root["definitions"][name], value, path, errors);
+        }
+
+        if let Some(types) = schema.get("type") {
+            let types: Vec<&str> = match types {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                other => other.as_str().into_iter().collect(),
+            };
+            if !types.iter().any(|&kind| has_type(value, kind)) {
+                errors.push(format!("{}: {} is not of type {:?}", path, value, types));
+                return;
+            }
+        }
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(value) {
+                errors.push(format!("{}: {} is not one of {:?}", path, value, allowed));
+            }
+        }
+        if let (Some(minimum), Some(number)) = (
+            schema.get("minimum").and_then(Value::as_f64),
+            value.as_f64(),
+        ) {
+            if number < minimum {
+                errors.push(format!("{}: {} is below {}", path, number, minimum));
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(required) {
+                    errors.push(format!("{}: missing {}", path, required));
+                }
+            }
+            for (key, field) in object {
+                let field_path = format!("{}.{}", path, key);
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(property) => validate(root, property, field, &field_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unknown key", field_path))
+                        }
+                        Some(additional @ Value::Object(_)) => {
+                            validate(root, additional, field, &field_path, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+            for (index, item) in array.iter().enumerate() {
+                validate(root, items, item, &format!("{}[{}]", path, index), errors);
+            }
+        }
+
+        let passes = |schema: &Value| {
+            let mut inner = Vec::new();
+            validate(root, schema, value, path, &mut inner);
+            inner.is_empty()
+        };
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            for schema in all_of {
+                validate(root, schema, value, path, errors);
+            }
+        }
+        if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array) {
+            if !any_of.iter().any(passes) {
+                errors.push(format!("{}: {} matches no allowed schema", path, value));
+            }
+        }
+        if let Some(one_of) = schema.get("oneOf").and_then(Value::as_array) {
+            let count = one_of.iter().filter(|schema| passes(schema)).count();
+            if count != 1 {
+                errors.push(format!("{}: {} matches {} of oneOf", path, value, count));
+            }
+        }
+    }
+
+    fn has_type(value: &Value, kind: &str) -> bool {
+        match kind {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => true,
+        }
+    }
+
+    fn check(schema: &Value, config: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        validate(schema, schema, config, "$", &mut errors);
+        errors
+    }
+
+    /// Every field of every config struct, found by walking the schema.
+    fn fields(schema: &Value, path: &str, found: &mut Vec<(String, Value)>) {
+        match schema {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    if key == "properties" {
+                        for (name, property) in value.as_object().into_iter().flatten() {
+                            found.push((format!("{}.{}", path, name), property.clone()));
+                        }
+                    }
+                    fields(value, &format!("{}/{}", path, key), found);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    fields(item, &format!("{}/{}", path, index), found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_every_field_is_documented() {
+        let schema = config_schema();
+        let mut found = Vec::new();
+        fields(&schema, "", &mut found);
+        assert!(found.len() > 80, "only {} fields found", found.len());
+
+        // The `type` of a tagged enum only names its variant
+        let undocumented: Vec<&str> = found
+            .iter()
+            .filter(|(_, property)| property.get("description").is_none())
+            .filter(|(_, property)| {
+                property["enum"]
+                    .as_array()
+                    .is_none_or(|values| values.len() > 1)
+            })
+            .map(|(path, _)| path.as_str())
+            .collect();
+        assert!(
+            undocumented.is_empty(),
+            "config fields need a doc comment, which becomes their description: {:?}",
+            undocumented
+        );
+    }
+
+    #[test]
+    fn test_schema_shape() {
+        let schema = config_schema();
+        assert_eq!(schema["title"], SCHEMA_TITLE);
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+
+        let process = &schema["definitions"]["ProcessConfig"];
+        assert_eq!(process["required"], serde_json::json!(["name"]));
+        assert!(process["properties"]["autoRestart"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("restart on crash"));
+        assert_eq!(
+            schema["definitions"]["GlobalSettings"]["properties"]["logLevel"]["default"],
+            "info"
+        );
+        let health_check = &schema["definitions"]["HealthCheck"];
+        assert_eq!(
+            health_check["properties"]["type"]["enum"],
+            serde_json::json!(["command", "http", "tcp"])
+        );
+    }
+
+    #[test]
+    fn test_default_config_and_examples_match_the_schema() {
+        let schema = config_schema();
+
+        let default_config = serde_json::to_value(ConfigManager::default_config()).unwrap();
+        assert_eq!(check(&schema, &default_config), Vec::<String>::new());
+
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+        let mut files: Vec<_> = glob::glob(&format!("{}/**/*.yaml", examples.display()))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        files.sort();
+        assert!(
+            files.len() >= 5,
+            "examples not found in {}",
+            examples.display()
+        );
+        for file in files {
+            let contents = std::fs::read_to_string(&file).unwrap();
+            // Each example is a config Sentinel loads
+            serde_yaml::from_str::<Config>(&contents).unwrap();
+            let example: Value = serde_yaml::from_str(&contents).unwrap();
+            assert_eq!(
+                check(&schema, &example),
+                Vec::<String>::new(),
+                "{}",
+                file.display()
+            );
+        }
+
+        // The checks themselves catch mistakes
+        let typo = serde_json::json!({
+            "processes": [{"name": "api", "command": "npm start", "restartLimit": "five"}],
+            "settings": {"onExit": "explode"},
+        });
+        assert_eq!(
+            check(&schema, &typo).len(),
+            2,
+            "{:?}",
+            check(&schema, &typo)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Masking of secret environment variable values.
+//!
+//! Values whose key contains one of `settings.secretPatterns` are replaced
+//! with [`MASK`] before a configuration leaves the backend. The real values
+//! stay in memory and are still passed to spawned processes.
+
+use crate::core::process_config::ProcessConfig as ManagedProcessConfig;
+use crate::models::Config;
+use std::collections::HashMap;
+
+/// Placeholder shown instead of a secret value.
+pub const MASK: &str = "••••";
+
+/// Whether `key` names a secret, i.e. contains any of `patterns` ignoring case.
+pub fn is_secret(key: &str, patterns: &[String]) -> bool {
+    let key = key.to_uppercase();
+    patterns
+        .iter()
+        .any(|pattern| !pattern.is_empty() && key.contains(&pattern.to_uppercase()))
+}
+
+/// Copy of `env` with secret values replaced by [`MASK`].
+pub fn mask_env(env: &HashMap<String, String>, patterns: &[String]) -> HashMap<String, String> {
+    env.iter()
+        .map(|(key, value)| {
+            let value = if is_secret(key, patterns) {
+                MASK.to_string()
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// Puts back real values for entries that still hold [`MASK`].
+///
+/// Used when the frontend sends a masked config back to be saved: untouched
+/// secrets keep their value from `previous`, edited ones take the new value.
+pub fn restore_masked(env: &mut HashMap<String, String>, previous: &HashMap<String, String>) {
+    for (key, value) in env.iter_mut() {
+        if value == MASK {
+            if let Some(real) = previous.get(key) {
+                *value = real.clone();
+            }
+        }
+    }
+}
+
+/// Copy of `argv` with the values of secret options replaced by [`MASK`].
+///
+/// Covers `KEY=value`, `--key=value` and `--key value`, where the key names
+/// a secret.
+pub fn mask_argv(argv: &[String], patterns: &[String]) -> Vec<String> {
+    let mut masked = Vec::with_capacity(argv.len());
+    let mut mask_next = false;
+    for arg in argv {
+        if std::mem::take(&mut mask_next) && !arg.starts_with('-') {
+            masked.push(MASK.to_string());
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((key, _)) if is_secret(key.trim_start_matches('-'), patterns) => {
+                masked.push(format!("{}={}", key, MASK));
+            }
+            Some(_) => masked.push(arg.clone()),
+            None => {
+                mask_next =
+                    arg.starts_with('-') && is_secret(arg.trim_start_matches('-'), patterns);
+                masked.push(arg.clone());
+            }
+        }
+    }
+    masked
+}
+
+/// Copy of `config` safe to send to the frontend.
+pub fn mask_config(config: &Config) -> Config {
+    let patterns = &config.settings.secret_patterns;
+    let mut masked = config.clone();
+
+    masked.global_env = mask_env(&config.global_env, patterns);
+    for process in &mut masked.processes {
+        process.env = mask_env(&process.env, patterns);
+    }
+    for entry in masked.defaults.values_mut() {
+        let env = entry
+            .get_mut("env")
+            .and_then(serde_yaml::Value::as_mapping_mut);
+        for (key, value) in env.into_iter().flatten() {
+            if key.as_str().is_some_and(|key| is_secret(key, patterns)) {
+                *value = serde_yaml::Value::String(MASK.to_string());
+            }
+        }
+    }
+    // Webhook headers are mostly credentials, whatever their names
+    for sink in &mut masked.settings.notification_sinks {
+        for value in sink.headers.values_mut() {
+            *value = MASK.to_string();
+        }
+    }
+
+    masked
+}
+
+/// Copy of a managed process config safe to send to the frontend.
+pub fn mask_managed_config(
+    config: &ManagedProcessConfig,
+    patterns: &[String],
+) -> ManagedProcessConfig {
+    let mut masked = config.clone();
+    masked.env_vars = mask_env(&config.env_vars, patterns);
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GlobalSettings;
+
+    fn patterns() -> Vec<String> {
+        GlobalSettings::default().secret_patterns
+    }
+
+    #[test]
+    fn test_is_secret_matches_substrings_ignoring_case() {
+        let patterns = patterns();
+
+        assert!(is_secret("DB_PASSWORD", &patterns));
+        assert!(is_secret("github_token", &patterns));
+        assert!(is_secret("STRIPE_SECRET_KEY", &patterns));
+        assert!(is_secret("ssh_private_path", &patterns));
+        assert!(!is_secret("PORT", &patterns));
+        assert!(!is_secret("NODE_ENV", &patterns));
+        assert!(!is_secret("PORT", &[String::new()]));
+    }
+
+    #[test]
+    fn test_mask_config() {
+        let yaml = r#"
+processes:
+  - name: api
+    command: npm start
+    env:
+      PORT: "3000"
+      DB_PASSWORD: hunter2
+globalEnv:
+  API_TOKEN: abc123
+defaults:
+  node:
+    env:
+      NODE_ENV: development
+      NPM_TOKEN: def456
+settings:
+  secretPatterns: [PASSWORD, TOKEN]
+  notificationSinks:
+    - name: hook
+      type: webhook
+      url: https://example.com/hook
+      headers:
+        Authorization: Bearer abc123
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+        let masked = mask_config(&config);
+        assert_eq!(masked.processes[0].env["PORT"], "3000");
+        assert_eq!(masked.processes[0].env["DB_PASSWORD"], MASK);
+        assert_eq!(masked.global_env["API_TOKEN"], MASK);
+        assert_eq!(masked.defaults["node"]["env"]["NODE_ENV"], "development");
+        assert_eq!(masked.defaults["node"]["env"]["NPM_TOKEN"], MASK);
+        assert_eq!(
+            masked.settings.notification_sinks[0].headers["Authorization"],
+            MASK
+        );
+
+        // The original keeps its real values
+        assert_eq!(config.processes[0].env["DB_PASSWORD"], "hunter2");
+    }
+
+    #[test]
+    fn test_mask_argv() {
+        let argv: Vec<String> = [
+            "node",
+            "server.js",
+            "DB_PASSWORD=hunter2",
+            "--api-token=abc123",
+            "--auth-token",
+            "def456",
+            "--port",
+            "3000",
+            "--secret-file",
+            "--verbose",
+        ]
+        .map(String::from)
+        .into();
+
+        assert_eq!(
+            mask_argv(&argv, &patterns()),
+            [
+                "node",
+                "server.js",
+                "DB_PASSWORD=••••",
+                "--api-token=••••",
+                "--auth-token",
+                "••••",
+                "--port",
+                "3000",
+                "--secret-file",
+                "--verbose",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_restore_masked_keeps_untouched_secrets() {
+        let previous: HashMap<String, String> = [
+            ("DB_PASSWORD".to_string(), "hunter2".to_string()),
+            ("API_TOKEN".to_string(), "abc123".to_string()),
+        ]
+        .into();
+        let mut edited: HashMap<String, String> = [
+            ("DB_PASSWORD".to_string(), MASK.to_string()),
+            ("API_TOKEN".to_string(), "rotated".to_string()),
+            ("NEW_SECRET".to_string(), MASK.to_string()),
+        ]
+        .into();
+
+        restore_masked(&mut edited, &previous);
+        assert_eq!(edited["DB_PASSWORD"], "hunter2");
+        assert_eq!(edited["API_TOKEN"], "rotated");
+        assert_eq!(edited["NEW_SECRET"], MASK);
+    }
+}
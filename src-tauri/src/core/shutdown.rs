@@ -0,0 +1,400 @@
+//! Stopping or detaching managed processes when Sentinel quits.
+//!
+//! Without this, quitting left every child running without a SIGTERM and the
+//! runtime state file pointing at PIDs nobody tracked. [`shutdown`] does what
+//! `settings.onExit` asks for before the app exits: stop everything within
+//! one overall timeout, or leave processes running and record them so the
+//! next launch adopts them instead of starting duplicates.
+
+use crate::core::{ProcessManager, PtyProcessManager};
+use crate::models::{OnExit, ProcessInfo, ProcessRuntimeInfo, RuntimeState};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// How long processes killed at the deadline get to exit before shutdown
+/// gives up on them.
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// How often PTY processes are checked for having exited.
+const PTY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Progress of a shutdown, emitted to the UI as `shutdown-progress`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownProgress {
+    /// What happens to running processes.
+    pub mode: OnExit,
+    /// Number of processes to stop or detach.
+    pub total: usize,
+    /// Number of processes stopped or detached so far.
+    pub completed: usize,
+    /// The process that just finished, if any.
+    pub process: Option<String>,
+    /// Whether the shutdown is over.
+    pub done: bool,
+}
+
+/// What happened to each process during a shutdown.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// Processes that exited before the timeout.
+    pub stopped: Vec<String>,
+    /// Processes still running at the timeout, which were sent SIGKILL.
+    pub killed: Vec<String>,
+    /// Processes left running and recorded in the runtime state.
+    pub detached: Vec<String>,
+}
+
+/// Stops or detaches every running process, piped and PTY alike.
+///
+/// With [`OnExit::StopAll`] all processes get SIGTERM at once; whatever is
+/// still running when `timeout` is up gets SIGKILL. Their runtime state
+/// entries are marked stopped. With [`OnExit::DetachAndPersist`] piped
+/// processes keep running and their PID, config hash and command line are
+/// written to `runtime_state`; PTY processes can't outlive their terminal and
+/// are stopped either way.
+///
+/// `progress` is called after each process and once more at the end.
+/// Saving `runtime_state` is left to the caller.
+///
+/// # Arguments
+/// * `processes` - Manager of the piped processes
+/// * `pty` - Manager of the PTY processes
+/// * `runtime_state` - Runtime state to update
+/// * `mode` - What to do with running processes
+/// * `timeout` - Total time processes get to exit after SIGTERM
+/// * `progress` - Called with the progress so far
+///
+/// # Returns
+/// What happened to each process
+pub async fn shutdown(
+    processes: &ProcessManager,
+    pty: &Mutex<PtyProcessManager>,
+    runtime_state: &mut RuntimeState,
+    mode: OnExit,
+    timeout: Duration,
+    mut progress: impl FnMut(ShutdownProgress),
+) -> ShutdownReport {
+    let deadline = Instant::now() + timeout;
+    let running: Vec<ProcessInfo> = processes
+        .list()
+        .await
+        .into_iter()
+        .filter(|info| info.is_running())
+        .collect();
+    let pty_processes = pty.lock().await.list_processes().await;
+
+    info!(
+        "Shutting down ({:?}): {} processes, {} PTY processes",
+        mode,
+        running.len(),
+        pty_processes.len()
+    );
+
+    let mut report = ShutdownReport::default();
+    let mut completed = 0;
+    let total = running.len() + pty_processes.len();
+    let mut report_progress = |process: Option<String>, done: bool| {
+        if process.is_some() {
+            completed += 1;
+        }
+        progress(ShutdownProgress {
+            mode,
+            total,
+            completed,
+            process,
+            done,
+        });
+    };
+
+    // PTY processes stop in the background while piped ones are handled
+    {
+        let pty = pty.lock().await;
+        for process in &pty_processes {
+            if let Err(e) = pty.kill_process(&process.process_id).await {
+                warn!("Failed to stop PTY process '{}': {}", process.process_id, e);
+            }
+        }
+    }
+
+    let to_stop: Vec<&ProcessInfo> = match mode {
+        OnExit::StopAll => running.iter().collect(),
+        OnExit::DetachAndPersist => {
+            for info in &running {
+                if let Some(entry) = detached_entry(processes, info).await {
+                    runtime_state.upsert_process(info.name.clone(), entry);
+                    report.detached.push(info.name.clone());
+                    report_progress(Some(info.name.clone()), false);
+                }
+            }
+            Vec::new()
+        }
+    };
+
+    let mut stops: FuturesUnordered<_> = to_stop
+        .iter()
+        .map(|info| async move { (info.name.clone(), processes.stop(&info.name).await) })
+        .collect();
+    let mut finished = HashSet::new();
+    let mut killed = false;
+    loop {
+        let next = if killed {
+            tokio::time::timeout(KILL_GRACE, stops.next()).await
+        } else {
+            tokio::time::timeout_at(deadline, stops.next()).await
+        };
+        match next {
+            Ok(Some((name, result))) => {
+                if let Err(e) = result {
+                    warn!("Failed to stop process '{}': {}", name, e);
+                }
+                finished.insert(name.clone());
+                if killed {
+                    report.killed.push(name.clone());
+                } else {
+                    report.stopped.push(name.clone());
+                }
+                report_progress(Some(name), false);
+            }
+            Ok(None) => break,
+            Err(_) if !killed => {
+                for info in to_stop.iter().filter(|info| !finished.contains(&info.name)) {
+                    warn!(
+                        "Process '{}' did not stop within the shutdown timeout, force killing",
+                        info.name
+                    );
+                    if let Some(pid) = info.pid {
+                        force_kill(pid);
+                    }
+                }
+                killed = true;
+            }
+            Err(_) => {
+                warn!("Giving up on processes that survived SIGKILL");
+                break;
+            }
+        }
+    }
+    drop(stops);
+
+    for process in pty_processes {
+        while is_alive(process.pid) && Instant::now() < deadline {
+            tokio::time::sleep(PTY_POLL_INTERVAL).await;
+        }
+        if is_alive(process.pid) {
+            warn!(
+                "PTY process '{}' did not stop within the shutdown timeout, force killing",
+                process.process_id
+            );
+            force_kill(process.pid);
+            report.killed.push(process.process_id.clone());
+        } else {
+            report.stopped.push(process.process_id.clone());
+        }
+        report_progress(Some(process.process_id), false);
+    }
+
+    if mode == OnExit::StopAll {
+        for name in report.stopped.iter().chain(&report.killed) {
+            if let Some(entry) = runtime_state.processes.get_mut(name) {
+                let exit_code = processes.get(name).await.and_then(|info| info.exit_code);
+                entry.mark_stopped(exit_code);
+            }
+        }
+    }
+    runtime_state.mark_synced();
+
+    report_progress(None, true);
+    info!(
+        "Shutdown finished: {} stopped, {} killed, {} detached",
+        report.stopped.len(),
+        report.killed.len(),
+        report.detached.len()
+    );
+    report
+}
+
+/// The runtime state entry that lets the next launch adopt `info`.
+async fn detached_entry(
+    processes: &ProcessManager,
+    info: &ProcessInfo,
+) -> Option<ProcessRuntimeInfo> {
+    let pid = info.pid?;
+    let config = processes.get_config(&info.name).await?;
+    let mut entry = ProcessRuntimeInfo::new(pid, config.config_hash())
+        .with_command(info.exe_path.as_ref().map(PathBuf::from), info.argv.clone());
+    entry.started_at = info.started_at;
+    entry.restart_count = info.restart_count;
+    Some(entry)
+}
+
+/// Whether a process with this PID exists.
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Sends SIGKILL to a process.
+#[cfg(unix)]
+fn force_kill(pid: u32) {
+    unsafe {
+        libc::kill(pid as i32, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn force_kill(_pid: u32) {}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::core::PtyProcessConfig;
+    use crate::models::ProcessConfig;
+
+    fn config(name: &str, command: &str, args: &[&str]) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            auto_restart: Some(false),
+            restart_limit: 0,
+            restart_delay: Some(100),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_all_stops_every_process() {
+        let manager = ProcessManager::new();
+        let pty = Mutex::new(PtyProcessManager::new());
+        manager
+            .start(config("sleeper", "sleep", &["30"]))
+            .await
+            .unwrap();
+        // Ignores SIGTERM, so only the deadline's SIGKILL ends it
+        manager
+            .start(config(
+                "stubborn",
+                "sh",
+                &["-c", "trap '' TERM; while true; do sleep 1; done"],
+            ))
+            .await
+            .unwrap();
+        let pty_pid = pty
+            .lock()
+            .await
+            .spawn_with_events(
+                PtyProcessConfig::new("terminal", "sleep", vec!["30".to_string()]),
+                |_| {},
+            )
+            .await
+            .unwrap();
+        // A PTY child signalled right after spawning can miss the SIGTERM
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let mut runtime_state = RuntimeState::new();
+        for info in manager.list().await {
+            runtime_state.upsert_process(
+                info.name.clone(),
+                ProcessRuntimeInfo::new(info.pid.unwrap(), "hash".to_string()),
+            );
+        }
+
+        let mut events = Vec::new();
+        let started = Instant::now();
+        let report = shutdown(
+            &manager,
+            &pty,
+            &mut runtime_state,
+            OnExit::StopAll,
+            Duration::from_millis(500),
+            |progress| events.push(progress),
+        )
+        .await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(report.killed, vec!["stubborn"]);
+        let mut stopped = report.stopped.clone();
+        stopped.sort();
+        assert_eq!(stopped, vec!["sleeper", "terminal"]);
+        assert!(report.detached.is_empty());
+
+        assert!(!manager.is_running("sleeper").await);
+        assert!(!manager.is_running("stubborn").await);
+        assert!(pty.lock().await.list_processes().await.is_empty());
+        assert!(!is_alive(pty_pid));
+        for name in ["sleeper", "stubborn"] {
+            assert_eq!(runtime_state.get_process(name).unwrap().pid, None);
+        }
+        assert!(runtime_state.last_sync.is_some());
+
+        let last = events.last().unwrap();
+        assert!(last.done);
+        assert_eq!((last.completed, last.total), (3, 3));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.process.is_some())
+                .count(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detach_keeps_processes_running_and_records_them() {
+        let manager = ProcessManager::new();
+        let pty = Mutex::new(PtyProcessManager::new());
+        let sleeper = config("sleeper", "sleep", &["30"]);
+        let info = manager.start(sleeper.clone()).await.unwrap();
+        let pid = info.pid.unwrap();
+        pty.lock()
+            .await
+            .spawn_with_events(
+                PtyProcessConfig::new("terminal", "sleep", vec!["30".to_string()]),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let mut runtime_state = RuntimeState::new();
+        let mut events = Vec::new();
+        let report = shutdown(
+            &manager,
+            &pty,
+            &mut runtime_state,
+            OnExit::DetachAndPersist,
+            Duration::from_secs(2),
+            |progress| events.push(progress),
+        )
+        .await;
+
+        assert_eq!(report.detached, vec!["sleeper"]);
+        assert_eq!(report.stopped, vec!["terminal"]);
+        assert!(report.killed.is_empty());
+        assert!(manager.is_running("sleeper").await);
+        assert!(is_alive(pid));
+        assert!(pty.lock().await.list_processes().await.is_empty());
+
+        let entry = runtime_state.get_process("sleeper").unwrap();
+        assert_eq!(entry.pid, Some(pid));
+        assert_eq!(entry.config_hash, sleeper.config_hash());
+        assert_eq!(entry.started_at, info.started_at);
+        assert!(entry.managed_by_sentinel);
+        assert!(entry.argv.ends_with(&["30".to_string()]));
+        assert!(events.last().unwrap().done);
+
+        manager.stop("sleeper").await.unwrap();
+    }
+}
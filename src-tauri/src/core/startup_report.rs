@@ -0,0 +1,581 @@
+//! What came up after starting a set of processes together.
+//!
+//! Once [`ProcessManager::start_all`] returns, the report lists for every
+//! process how far it got and how long that took, counted from the start of
+//! the run, the ports it listens on, and for the ones that failed the first
+//! line of their output that looks like an error. The critical path is the
+//! chain of dependencies that held up the process that was up last; no other
+//! process starting faster would have shortened the run.
+
+use crate::core::log_buffer::{LogLine, LogStream};
+use crate::core::log_format::LogLevel;
+use crate::core::ProcessManager;
+use crate::error::Result;
+use crate::features::port_discovery::{self, PortScanner};
+use crate::models::{
+    EventActor, EventType, JournalEvent, ProcessConfig, ProcessInfo, ProcessState,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::debug;
+
+/// Recent output lines read from a process that failed.
+const RECENT_LOG_LINES: usize = 200;
+
+/// Lines of output in a failure excerpt.
+const EXCERPT_LINES: usize = 8;
+
+/// Lines of a failure excerpt before the first error line.
+const EXCERPT_LINES_BEFORE: usize = 2;
+
+/// Words that make a line without a log level an error, lowercase.
+const ERROR_MARKERS: &[&str] = &[
+    "error",
+    "exception",
+    "panic",
+    "fatal",
+    "traceback",
+    "failed",
+    "refused",
+];
+
+/// How far one process got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessStartup {
+    pub name: String,
+    /// State at the end of the run; `Stopped` if it was never started.
+    pub state: ProcessState,
+    /// Milliseconds from the start of the run until it was running.
+    pub running_after_ms: Option<u64>,
+    /// Milliseconds from the start of the run until its readiness probe
+    /// passed, for a process with one.
+    pub ready_after_ms: Option<u64>,
+    /// Ports it or its descendants listen on.
+    pub ports: Vec<u16>,
+    /// Whether it failed to start, was skipped, or exited with an error.
+    pub failed: bool,
+    /// Why starting it failed, or why it was skipped.
+    pub error: Option<String>,
+    /// First line of its output that looks like an error, or else of
+    /// `error`, for a process that failed.
+    pub first_error_line: Option<String>,
+    /// Its output around the first error line, or its last lines, for a
+    /// process that failed.
+    pub log_excerpt: Vec<String>,
+}
+
+/// Counts over the processes of a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupTotals {
+    pub processes: usize,
+    /// Running at the end of the run.
+    pub running: usize,
+    /// Running with their readiness probe, if any, passed.
+    pub ready: usize,
+    /// Exited after finishing their work.
+    pub completed: usize,
+    pub failed: usize,
+}
+
+/// What came up after starting a set of processes, in start order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub started_at: DateTime<Utc>,
+    /// Milliseconds the run took.
+    pub duration_ms: u64,
+    pub processes: Vec<ProcessStartup>,
+    pub totals: StartupTotals,
+    /// Dependencies that held up the process that was up last, ending with
+    /// it; empty if nothing came up.
+    pub critical_path: Vec<String>,
+    /// Milliseconds from the start of the run until the end of the critical
+    /// path was up.
+    pub critical_path_ms: u64,
+}
+
+impl StartupReport {
+    /// The processes that failed, in start order.
+    pub fn failures(&self) -> impl Iterator<Item = &ProcessStartup> {
+        self.processes.iter().filter(|process| process.failed)
+    }
+
+    /// The report in one journal event about `subject`, usually the config
+    /// file.
+    pub fn journal_event(&self, subject: impl Into<String>, actor: EventActor) -> JournalEvent {
+        let mut event = JournalEvent::new(EventType::StartupFinished, subject, actor)
+            .with_detail("processes", self.totals.processes)
+            .with_detail("running", self.totals.running)
+            .with_detail("ready", self.totals.ready)
+            .with_detail("completed", self.totals.completed)
+            .with_detail("failed", self.totals.failed)
+            .with_detail("duration_ms", self.duration_ms);
+        if !self.critical_path.is_empty() {
+            event = event
+                .with_detail("critical_path", self.critical_path.join(" > "))
+                .with_detail("critical_path_ms", self.critical_path_ms);
+        }
+        let failures: Vec<&str> = self.failures().map(|p| p.name.as_str()).collect();
+        if !failures.is_empty() {
+            event = event.with_detail("failures", failures.join(", "));
+        }
+        event
+    }
+}
+
+/// What is known about one process once the run is over, for [`assemble`].
+#[derive(Debug, Clone)]
+pub struct StartOutcome {
+    pub name: String,
+    pub depends_on: Vec<String>,
+    /// Whether it has a readiness probe.
+    pub has_readiness: bool,
+    /// Its information now, if the manager knows it.
+    pub info: Option<ProcessInfo>,
+    /// Why starting it failed, or why it was skipped.
+    pub error: Option<String>,
+    /// Its recent output, oldest first.
+    pub logs: Vec<LogLine>,
+    /// Ports it or its descendants listen on.
+    pub ports: Vec<u16>,
+}
+
+impl StartOutcome {
+    fn failed(&self) -> bool {
+        self.error.is_some()
+            || self.info.as_ref().is_some_and(|info| {
+                matches!(
+                    info.state,
+                    ProcessState::Crashed { .. } | ProcessState::Failed { .. }
+                )
+            })
+    }
+}
+
+/// Gathers what `manager` knows about the processes of a run and assembles
+/// the report.
+///
+/// # Arguments
+/// * `manager` - Manager that started them
+/// * `configs` - The configurations passed to `start_all`
+/// * `results` - What `start_all` returned
+/// * `started_at` - When the run began
+/// * `scanner` - Finds the ports they listen on; without one none are listed
+pub async fn collect(
+    manager: &ProcessManager,
+    configs: &[ProcessConfig],
+    results: &[(String, Result<ProcessInfo>)],
+    started_at: DateTime<Utc>,
+    scanner: Option<&PortScanner>,
+) -> StartupReport {
+    let finished_at = Utc::now();
+
+    let mut outcomes = Vec::with_capacity(results.len());
+    for (name, result) in results {
+        let config = configs.iter().find(|config| &config.name == name);
+        let mut outcome = StartOutcome {
+            name: name.clone(),
+            depends_on: config.map(|c| c.depends_on.clone()).unwrap_or_default(),
+            has_readiness: config.is_some_and(|c| c.readiness.is_some()),
+            info: manager.get(name).await,
+            error: result.as_ref().err().map(ToString::to_string),
+            logs: Vec::new(),
+            ports: Vec::new(),
+        };
+        if outcome.failed() {
+            outcome.logs = manager
+                .get_recent_logs(name, RECENT_LOG_LINES)
+                .await
+                .unwrap_or_default();
+        }
+        outcomes.push(outcome);
+    }
+
+    if let Some(scanner) = scanner {
+        match scanner.scan().await {
+            Ok(connections) => {
+                let pid = |outcome: &StartOutcome| outcome.info.as_ref()?.pid;
+                let roots: Vec<u32> = outcomes.iter().filter_map(pid).collect();
+                let ports = port_discovery::listening_ports_by_root(&connections, &roots);
+                for outcome in &mut outcomes {
+                    if let Some(found) = pid(outcome).and_then(|pid| ports.get(&pid)) {
+                        outcome.ports = found.clone();
+                    }
+                }
+            }
+            Err(e) => debug!("Failed to scan the ports of the started processes: {}", e),
+        }
+    }
+
+    assemble(started_at, finished_at, &outcomes)
+}
+
+/// Builds the report of a run from what is known about its processes.
+///
+/// # Arguments
+/// * `started_at` - When the run began
+/// * `finished_at` - When the last process was started or skipped
+/// * `outcomes` - Every process of the run, in start order
+pub fn assemble(
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    outcomes: &[StartOutcome],
+) -> StartupReport {
+    let after = |time: DateTime<Utc>| (time - started_at).num_milliseconds().max(0) as u64;
+
+    let mut processes = Vec::with_capacity(outcomes.len());
+    let mut up_after = Vec::with_capacity(outcomes.len());
+    let mut totals = StartupTotals {
+        processes: outcomes.len(),
+        ..Default::default()
+    };
+    for outcome in outcomes {
+        let info = outcome.info.as_ref();
+        let failed = outcome.failed();
+        let running_after_ms = info.and_then(|info| info.started_at).map(after);
+        let ready_after_ms = match info {
+            Some(info) if outcome.has_readiness => running_after_ms
+                .zip(info.time_to_ready_ms)
+                .map(|(running, ready)| running + ready),
+            _ => None,
+        };
+
+        // When its dependents could go ahead
+        up_after.push(match info {
+            _ if failed => None,
+            Some(info) if info.is_completed() => info.stopped_at.map(after),
+            _ if outcome.has_readiness => ready_after_ms,
+            _ => running_after_ms,
+        });
+
+        let (first_error_line, log_excerpt) = if failed {
+            let (line, excerpt) = failure_excerpt(&outcome.logs);
+            let error_line = outcome
+                .error
+                .as_deref()
+                .and_then(|error| error.lines().next())
+                .map(str::to_string);
+            (line.or(error_line), excerpt)
+        } else {
+            (None, Vec::new())
+        };
+
+        totals.running += usize::from(info.is_some_and(ProcessInfo::is_running));
+        totals.ready += usize::from(info.is_some_and(|info| info.ready));
+        totals.completed += usize::from(info.is_some_and(ProcessInfo::is_completed));
+        totals.failed += usize::from(failed);
+
+        processes.push(ProcessStartup {
+            name: outcome.name.clone(),
+            state: info.map_or(ProcessState::Stopped, |info| info.state.clone()),
+            running_after_ms,
+            ready_after_ms,
+            ports: outcome.ports.clone(),
+            failed,
+            error: outcome.error.clone(),
+            first_error_line,
+            log_excerpt,
+        });
+    }
+
+    let nodes: Vec<(&str, &[String], Option<u64>)> = outcomes
+        .iter()
+        .zip(up_after)
+        .map(|(outcome, up)| (outcome.name.as_str(), outcome.depends_on.as_slice(), up))
+        .collect();
+    let (critical_path, critical_path_ms) = critical_path(&nodes);
+
+    StartupReport {
+        started_at,
+        duration_ms: after(finished_at),
+        processes,
+        totals,
+        critical_path,
+        critical_path_ms,
+    }
+}
+
+/// A process in the dependency graph: its name, its dependencies, and the
+/// milliseconds from the start of the run until it was up, if it came up.
+type Node<'a> = (&'a str, &'a [String], Option<u64>);
+
+/// The chain of dependencies ending at the process that was up last, first
+/// to last, and when that one was up.
+///
+/// Each step back goes to the dependency that was up last. Processes that
+/// never came up aren't on it; of two up at the same time, the one earlier
+/// in `nodes` is taken.
+fn critical_path(nodes: &[Node]) -> (Vec<String>, u64) {
+    let Some((mut node, end_ms)) = latest(nodes.iter()) else {
+        return (Vec::new(), 0);
+    };
+    let mut path = vec![node.0.to_string()];
+    let mut seen = HashSet::from([node.0]);
+    while let Some((dependency, _)) = latest(
+        nodes
+            .iter()
+            .filter(|other| node.1.iter().any(|dep| dep == other.0) && !seen.contains(other.0)),
+    ) {
+        path.push(dependency.0.to_string());
+        seen.insert(dependency.0);
+        node = dependency;
+    }
+
+    path.reverse();
+    (path, end_ms)
+}
+
+/// The node of `candidates` that was up last, the first one on a tie.
+fn latest<'a, 'b>(candidates: impl Iterator<Item = &'a Node<'b>>) -> Option<(&'a Node<'b>, u64)>
+where
+    'b: 'a,
+{
+    candidates.filter_map(|node| Some((node, node.2?))).fold(
+        None,
+        |latest, (node, up)| match latest {
+            Some((_, latest_up)) if latest_up >= up => latest,
+            _ => Some((node, up)),
+        },
+    )
+}
+
+/// The first line of `logs` that looks like an error, and the output around
+/// it; without one, the last lines of output.
+///
+/// Stderr is searched before stdout, where servers log requests and progress
+/// that may well mention errors. A line with a log level is an error if its
+/// level is `error` or worse, whatever it says.
+fn failure_excerpt(logs: &[LogLine]) -> (Option<String>, Vec<String>) {
+    let is_error = |line: &LogLine| match line.level {
+        Some(level) => level >= LogLevel::Error,
+        None => {
+            let text = line.line.to_lowercase();
+            ERROR_MARKERS.iter().any(|marker| text.contains(marker))
+        }
+    };
+
+    let first = logs
+        .iter()
+        .position(|line| line.stream == LogStream::Stderr && is_error(line))
+        .or_else(|| logs.iter().position(is_error));
+    let (first_line, excerpt) = match first {
+        Some(index) => {
+            let start = index.saturating_sub(EXCERPT_LINES_BEFORE);
+            let end = (start + EXCERPT_LINES).min(logs.len());
+            (Some(logs[index].line.trim().to_string()), &logs[start..end])
+        }
+        None => (None, &logs[logs.len().saturating_sub(EXCERPT_LINES)..]),
+    };
+    (
+        first_line,
+        excerpt.iter().map(|line| line.line.clone()).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn node<'a>(name: &'a str, depends_on: &'a [String], up_ms: Option<u64>) -> Node<'a> {
+        (name, depends_on, up_ms)
+    }
+
+    fn deps(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_critical_path_follows_the_latest_dependency() {
+        let none = deps(&[]);
+        let api_deps = deps(&["db", "cache"]);
+        let web_deps = deps(&["api"]);
+        let worker_deps = deps(&["db"]);
+        let nodes = vec![
+            node("db", &none, Some(100)),
+            node("cache", &none, Some(300)),
+            node("api", &api_deps, Some(500)),
+            node("worker", &worker_deps, Some(200)),
+            node("web", &web_deps, Some(900)),
+        ];
+
+        let (path, ms) = critical_path(&nodes);
+        assert_eq!(path, vec!["cache", "api", "web"]);
+        assert_eq!(ms, 900);
+
+        // A process that never came up isn't on it
+        let nodes = vec![
+            node("db", &none, Some(100)),
+            node("cache", &none, None),
+            node("api", &api_deps, Some(500)),
+            node("web", &web_deps, None),
+        ];
+        let (path, ms) = critical_path(&nodes);
+        assert_eq!(path, vec!["db", "api"]);
+        assert_eq!(ms, 500);
+
+        assert_eq!(critical_path(&[node("db", &none, None)]), (vec![], 0));
+    }
+
+    #[test]
+    fn test_critical_path_ties_go_to_the_first_process() {
+        let none = deps(&[]);
+        let api_deps = deps(&["db", "cache"]);
+        let nodes = vec![
+            node("db", &none, Some(200)),
+            node("cache", &none, Some(200)),
+            node("api", &api_deps, Some(300)),
+        ];
+
+        let (path, _) = critical_path(&nodes);
+        assert_eq!(path, vec!["db", "api"]);
+    }
+
+    fn line(stream: LogStream, text: &str) -> LogLine {
+        LogLine::new(Utc::now(), stream, text.to_string())
+    }
+
+    #[test]
+    fn test_failure_excerpt_prefers_stderr() {
+        let logs = vec![
+            line(LogStream::Stdout, "GET /errors 200"),
+            line(LogStream::Stdout, "compiling"),
+            line(LogStream::Stdout, "still compiling"),
+            line(LogStream::Stderr, "  Error: listen EADDRINUSE :::3000"),
+            line(LogStream::Stderr, "    at Server.listen (net.js:1)"),
+        ];
+
+        let (first, excerpt) = failure_excerpt(&logs);
+        assert_eq!(first.as_deref(), Some("Error: listen EADDRINUSE :::3000"));
+        assert_eq!(excerpt.len(), 4);
+        assert_eq!(excerpt[0], "compiling");
+        assert_eq!(excerpt[3], "    at Server.listen (net.js:1)");
+
+        // Stdout is searched when stderr has no error
+        let (first, _) = failure_excerpt(&logs[..3]);
+        assert_eq!(first.as_deref(), Some("GET /errors 200"));
+    }
+
+    #[test]
+    fn test_failure_excerpt_uses_log_levels_and_falls_back_to_the_tail() {
+        let mut structured = line(LogStream::Stdout, "connection failed, retrying");
+        structured.level = Some(LogLevel::Warn);
+        let mut fatal = line(LogStream::Stdout, "database unreachable");
+        fatal.level = Some(LogLevel::Fatal);
+        let (first, _) = failure_excerpt(&[structured.clone(), fatal]);
+        assert_eq!(first.as_deref(), Some("database unreachable"));
+
+        let logs: Vec<LogLine> = (0..20)
+            .map(|i| line(LogStream::Stdout, &format!("line {}", i)))
+            .collect();
+        let (first, excerpt) = failure_excerpt(&logs);
+        assert_eq!(first, None);
+        assert_eq!(excerpt.len(), EXCERPT_LINES);
+        assert_eq!(excerpt.last().unwrap(), "line 19");
+
+        assert_eq!(failure_excerpt(&[]), (None, vec![]));
+    }
+
+    fn outcome(name: &str, depends_on: &[&str], info: Option<ProcessInfo>) -> StartOutcome {
+        StartOutcome {
+            name: name.to_string(),
+            depends_on: deps(depends_on),
+            has_readiness: false,
+            info,
+            error: None,
+            logs: Vec::new(),
+            ports: Vec::new(),
+        }
+    }
+
+    fn info(name: &str, state: ProcessState, started_at: DateTime<Utc>) -> ProcessInfo {
+        let mut info = ProcessInfo::new(name.to_string(), "run".to_string());
+        info.state = state;
+        info.started_at = Some(started_at);
+        info
+    }
+
+    #[test]
+    fn test_assemble() {
+        let start = Utc::now();
+        let ms = |ms: i64| start + Duration::milliseconds(ms);
+
+        let mut migrate = info("migrate", ProcessState::Completed { exit_code: 0 }, ms(10));
+        migrate.stopped_at = Some(ms(400));
+        let mut db = info("db", ProcessState::Running, ms(20));
+        db.ready = true;
+        db.time_to_ready_ms = Some(250);
+        let mut api = outcome(
+            "api",
+            &["db", "migrate"],
+            Some(info("api", ProcessState::Running, ms(450))),
+        );
+        api.ports = vec![3000];
+        let mut web = outcome(
+            "web",
+            &["api"],
+            Some(info("web", ProcessState::Crashed { exit_code: 1 }, ms(500))),
+        );
+        web.error = Some("Process 'web' exited with code 1 right after starting:\nboom".into());
+        web.logs = vec![
+            line(LogStream::Stdout, "> vite"),
+            line(LogStream::Stderr, "Error: Cannot find module 'vite'"),
+        ];
+        let mut docs = outcome("docs", &["web"], None);
+        docs.error = Some("Skipped 'docs' because its dependency 'web' failed to start".into());
+        let outcomes = vec![
+            outcome("migrate", &[], Some(migrate)),
+            StartOutcome {
+                has_readiness: true,
+                ..outcome("db", &[], Some(db))
+            },
+            api,
+            web,
+            docs,
+        ];
+
+        let report = assemble(start, ms(1200), &outcomes);
+        assert_eq!(report.duration_ms, 1200);
+        assert_eq!(
+            report.totals,
+            StartupTotals {
+                processes: 5,
+                running: 2,
+                ready: 1,
+                completed: 1,
+                failed: 2,
+            }
+        );
+        assert_eq!(report.processes[1].running_after_ms, Some(20));
+        assert_eq!(report.processes[1].ready_after_ms, Some(270));
+        assert_eq!(report.processes[2].ready_after_ms, None);
+        assert_eq!(report.processes[2].ports, vec![3000]);
+        // The migration held up the api longer than the database
+        assert_eq!(report.critical_path, vec!["migrate", "api"]);
+        assert_eq!(report.critical_path_ms, 450);
+
+        let failures: Vec<&str> = report.failures().map(|p| p.name.as_str()).collect();
+        assert_eq!(failures, vec!["web", "docs"]);
+        let web = &report.processes[3];
+        assert_eq!(
+            web.first_error_line.as_deref(),
+            Some("Error: Cannot find module 'vite'")
+        );
+        assert_eq!(web.log_excerpt.len(), 2);
+        // Without output, the first line of why it failed
+        let docs = &report.processes[4];
+        assert_eq!(docs.state, ProcessState::Stopped);
+        assert_eq!(
+            docs.first_error_line.as_deref(),
+            Some("Skipped 'docs' because its dependency 'web' failed to start")
+        );
+
+        let event = report.journal_event("sentinel.yaml", EventActor::User);
+        assert_eq!(event.event_type, EventType::StartupFinished);
+        assert_eq!(event.details["critical_path"], "migrate > api");
+        assert_eq!(event.details["failures"], "web, docs");
+    }
+}
@@ -3,7 +3,7 @@
 use crate::error::{Result, SentinelError};
 use crate::models::RuntimeState;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Manages runtime state persistence.
 pub struct StateManager;
@@ -24,14 +24,17 @@ impl StateManager {
     ///
     /// If file doesn't exist, returns empty state.
     pub fn load() -> Result<RuntimeState> {
-        let path = Self::get_state_path();
+        Self::load_from(&Self::get_state_path())
+    }
 
+    /// Loads runtime state from `path`, or empty state if it doesn't exist.
+    pub fn load_from(path: &Path) -> Result<RuntimeState> {
         if !path.exists() {
             return Ok(RuntimeState::new());
         }
 
-        let contents = fs::read_to_string(&path).map_err(|source| SentinelError::FileIoError {
-            path: path.clone(),
+        let contents = fs::read_to_string(path).map_err(|source| SentinelError::FileIoError {
+            path: path.to_path_buf(),
             source,
         })?;
 
@@ -41,8 +44,11 @@ impl StateManager {
 
     /// Saves runtime state to file.
     pub fn save(state: &RuntimeState) -> Result<()> {
-        let path = Self::get_state_path();
+        Self::save_to(state, &Self::get_state_path())
+    }
 
+    /// Saves runtime state to `path`, creating its directory if needed.
+    pub fn save_to(state: &RuntimeState, path: &Path) -> Result<()> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|e| {
@@ -53,8 +59,8 @@ impl StateManager {
         let contents = serde_json::to_string_pretty(state)
             .map_err(|e| SentinelError::Other(format!("Failed to serialize state: {}", e)))?;
 
-        fs::write(&path, contents).map_err(|source| SentinelError::FileIoError {
-            path: path.clone(),
+        fs::write(path, contents).map_err(|source| SentinelError::FileIoError {
+            path: path.to_path_buf(),
             source,
         })?;
 
@@ -94,6 +100,25 @@ mod tests {
         assert_eq!(state.processes.len(), 0);
     }
 
+    #[test]
+    fn test_runtime_info_recognizes_executable() {
+        use crate::models::ProcessRuntimeInfo;
+        use std::path::Path;
+
+        let info = ProcessRuntimeInfo::new(12345, "hash123".to_string())
+            .with_command(Some("/usr/bin/node".into()), vec!["node".to_string()]);
+        assert!(info.runs_executable(Some(Path::new("/usr/bin/node"))));
+        assert!(!info.runs_executable(Some(Path::new("/usr/bin/python3"))));
+        assert!(info.runs_executable(None));
+
+        // State files written before executables were recorded
+        let old: ProcessRuntimeInfo = serde_json::from_str(
+            r#"{"pid":1,"started_at":null,"config_hash":"h","managed_by_sentinel":true,"restart_count":0,"last_exit_code":null}"#,
+        )
+        .unwrap();
+        assert!(old.runs_executable(Some(Path::new("/usr/bin/node"))));
+    }
+
     #[test]
     fn test_save_and_load() {
         use crate::models::ProcessRuntimeInfo;
@@ -1,15 +1,28 @@
 //! System resource monitoring.
 //!
 //! This module provides real-time monitoring of system resources including
-//! CPU, memory, and disk I/O with historical data tracking.
+//! CPU, memory, disk I/O, load and temperatures with historical data tracking.
 
+use crate::core::gpu::GpuMonitor;
 use crate::core::metrics_buffer::MetricsBuffer;
-use crate::models::{CpuStats, DiskStats, MemoryStats, SystemStats};
+use crate::models::{
+    cpu_percent_of_total, ComponentTemperature, CpuStats, DiskInfo, DiskStats, GpuStats,
+    LoadAverage, ManagedProcessRef, MemoryStats, ProcessResourceUsage, SystemDetails,
+    SystemProcess, SystemStats, TopProcessSort, TopProcesses,
+};
 use chrono::Utc;
-use std::time::Instant;
-use sysinfo::{Disks, System};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use sysinfo::{Components, Disks, ProcessRefreshKind, System, UpdateKind, Users};
 use tracing::debug;
 
+/// `get_top_processes` reuses a process table refreshed more recently than this.
+const PROCESS_TABLE_MAX_AGE: Duration = Duration::from_secs(1);
+
+/// Shortest time between two refreshes over which process CPU usage is
+/// meaningful.
+pub const MIN_CPU_SAMPLE_INTERVAL: Duration = sysinfo::MINIMUM_CPU_UPDATE_INTERVAL;
+
 /// Monitors system resources.
 ///
 /// Uses the `sysinfo` crate to collect CPU, memory, and disk metrics.
@@ -28,12 +41,28 @@ pub struct SystemMonitor {
     system: System,
     /// Disk information.
     disks: Disks,
+    /// Hardware sensors (temperatures).
+    components: Components,
+    /// User accounts, to name process owners.
+    users: Users,
+    /// When the full process table was last refreshed.
+    last_process_refresh: Option<Instant>,
+    /// Time between the last two full process refreshes, over which
+    /// per-process disk I/O was accumulated.
+    process_sample_interval: Option<Duration>,
+    /// Processes that had been sampled before the latest refresh, whose CPU
+    /// usage is therefore measured over an interval.
+    cpu_baseline: HashSet<u32>,
     /// Last disk I/O measurement (timestamp, total_read_bytes, total_write_bytes).
     last_disk_io: Option<(Instant, u64, u64)>,
+    /// Stats returned by the last `get_stats` call, and when.
+    last_stats: Option<(Instant, SystemStats)>,
     /// Historical CPU usage (last 60 seconds at 1Hz sampling).
     cpu_history: MetricsBuffer<f32>,
     /// Historical memory usage (last 60 seconds at 1Hz sampling).
     memory_history: MetricsBuffer<u64>,
+    /// GPU usage, refreshed on its own cadence.
+    gpus: GpuMonitor,
 }
 
 impl SystemMonitor {
@@ -49,14 +78,22 @@ impl SystemMonitor {
     /// ```
     pub fn new() -> Self {
         let mut system = System::new_all();
+        let cpu_baseline = pids(&system);
         system.refresh_all();
 
         Self {
             system,
             disks: Disks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+            users: Users::new_with_refreshed_list(),
+            last_process_refresh: Some(Instant::now()),
+            process_sample_interval: None,
+            cpu_baseline,
             last_disk_io: None,
+            last_stats: None,
             cpu_history: MetricsBuffer::new(60), // 60 seconds of history
             memory_history: MetricsBuffer::new(60), // 60 seconds of history
+            gpus: GpuMonitor::new(),
         }
     }
 
@@ -73,24 +110,78 @@ impl SystemMonitor {
     /// ```
     pub fn refresh(&mut self) {
         self.system.refresh_cpu_usage();
+        self.system.refresh_cpu_frequency();
         self.system.refresh_memory();
-        self.system
-            .refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        self.refresh_process_table();
         self.disks.refresh(true);
+        self.components.refresh(false);
 
         debug!("System metrics refreshed");
     }
 
+    /// Refreshes every process, recording when for disk I/O rates.
+    fn refresh_process_table(&mut self) {
+        let now = Instant::now();
+        self.cpu_baseline = pids(&self.system);
+        self.system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_cpu()
+                .with_disk_usage()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet)
+                .with_tasks(),
+        );
+        self.process_sample_interval = self
+            .last_process_refresh
+            .map(|last| now.duration_since(last));
+        self.last_process_refresh = Some(now);
+    }
+
     /// Refreshes only CPU information (lighter than full refresh).
     pub fn refresh_cpu(&mut self) {
         self.system.refresh_cpu_usage();
     }
 
+    /// Refreshes only the given processes (lighter than full refresh).
+    pub fn refresh_processes(&mut self, pids: &[u32]) {
+        let known = pids
+            .iter()
+            .filter(|&&pid| self.system.process(sysinfo::Pid::from_u32(pid)).is_some());
+        self.cpu_baseline.extend(known);
+        let pids: Vec<sysinfo::Pid> = pids
+            .iter()
+            .map(|&pid| sysinfo::Pid::from_u32(pid))
+            .collect();
+        self.system
+            .refresh_processes(sysinfo::ProcessesToUpdate::Some(&pids), true);
+    }
+
     /// Refreshes only memory information.
     pub fn refresh_memory(&mut self) {
         self.system.refresh_memory();
     }
 
+    /// Gets overall CPU usage as of the last refresh, without recording history.
+    pub fn cpu_usage(&self) -> f32 {
+        self.system.global_cpu_usage()
+    }
+
+    /// Gets memory usage percentage as of the last refresh, without recording
+    /// history.
+    pub fn memory_usage_percent(&self) -> f32 {
+        self.get_memory_stats().usage_percent
+    }
+
+    /// Gets used and total memory in bytes as of the last refresh, without
+    /// recording history.
+    pub fn memory_usage(&self) -> (u64, u64) {
+        let memory = self.get_memory_stats();
+        (memory.used, memory.total)
+    }
+
     /// Gets current system statistics and records them to history.
     ///
     /// Returns a snapshot of CPU, memory, and disk metrics.
@@ -115,14 +206,117 @@ impl SystemMonitor {
         self.cpu_history.push(cpu.overall);
         self.memory_history.push(memory.used);
 
-        SystemStats {
+        let stats = SystemStats {
             cpu,
             memory,
             disk,
+            load_average: self.load_average(),
+            temperatures: self.temperatures(),
+            gpus: self.gpus(),
             timestamp: Utc::now().timestamp(),
+        };
+        self.last_stats = Some((Instant::now(), stats.clone()));
+        stats
+    }
+
+    /// Gets the stats returned by the last `get_stats` call if that was
+    /// within `max_age`, otherwise refreshes and gets new ones.
+    ///
+    /// Reusing recent stats also keeps a second caller from recording them
+    /// to the history twice.
+    pub fn get_stats_cached(&mut self, max_age: Duration) -> SystemStats {
+        if let Some((at, stats)) = &self.last_stats {
+            if at.elapsed() < max_age {
+                return stats.clone();
+            }
+        }
+        self.refresh();
+        self.get_stats()
+    }
+
+    /// Gets system details that change rarely or are costly to collect.
+    ///
+    /// Re-scans mounted disks and sensors, so prefer `get_stats` for
+    /// frequent polling.
+    pub fn get_details(&mut self) -> SystemDetails {
+        self.disks.refresh(true);
+        self.components.refresh(true);
+
+        SystemDetails {
+            load_average: self.load_average(),
+            temperatures: self.temperatures(),
+            disks: self.disks(),
         }
     }
 
+    /// Gets the 1, 5 and 15 minute load averages.
+    ///
+    /// # Returns
+    /// `None` on Windows, which has no load average.
+    pub fn load_average(&self) -> Option<LoadAverage> {
+        if cfg!(target_os = "windows") {
+            return None;
+        }
+
+        let load = System::load_average();
+        Some(LoadAverage {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+        })
+    }
+
+    /// Gets the temperatures of hardware components.
+    ///
+    /// # Returns
+    /// `None` if no sensor reports a temperature.
+    pub fn temperatures(&self) -> Option<Vec<ComponentTemperature>> {
+        let temperatures: Vec<ComponentTemperature> = self
+            .components
+            .iter()
+            .filter_map(|component| {
+                let temperature = component.temperature().filter(|t| t.is_finite())?;
+                Some(ComponentTemperature {
+                    label: component.label().to_string(),
+                    temperature,
+                    max: component.max().filter(|t| t.is_finite()),
+                    critical: component.critical().filter(|t| t.is_finite()),
+                })
+            })
+            .collect();
+
+        (!temperatures.is_empty()).then_some(temperatures)
+    }
+
+    /// Gets GPU usage as of the last GPU refresh.
+    ///
+    /// Collecting it is slow, so `refresh` leaves it alone; the monitor from
+    /// [`gpu_monitor`](Self::gpu_monitor) refreshes it.
+    pub fn gpus(&self) -> Vec<GpuStats> {
+        self.gpus.latest()
+    }
+
+    /// The GPU monitor whose results `get_stats` reports, for refreshing
+    /// it without holding this monitor.
+    pub fn gpu_monitor(&self) -> GpuMonitor {
+        self.gpus.clone()
+    }
+
+    /// Gets all mounted disks.
+    pub fn disks(&self) -> Vec<DiskInfo> {
+        self.disks
+            .iter()
+            .map(|disk| DiskInfo {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                file_system: disk.file_system().to_string_lossy().to_string(),
+                total_space: disk.total_space(),
+                available_space: disk.available_space(),
+                is_removable: disk.is_removable(),
+            })
+            .collect()
+    }
+
     /// Gets CPU statistics.
     ///
     /// # Returns
@@ -134,10 +328,18 @@ impl SystemMonitor {
         let cores: Vec<f32> = cpus.iter().map(|cpu| cpu.cpu_usage()).collect();
         let core_count = cpus.len();
 
+        // Platforms that can't read frequencies report 0
+        let frequencies: Vec<u64> = cpus.iter().map(|cpu| cpu.frequency()).collect();
+        let frequencies = frequencies
+            .iter()
+            .any(|&mhz| mhz > 0)
+            .then_some(frequencies);
+
         CpuStats {
             overall,
             cores,
             core_count,
+            frequencies,
         }
     }
 
@@ -216,7 +418,8 @@ impl SystemMonitor {
     /// * `pid` - Process ID
     ///
     /// # Returns
-    /// * `Some((cpu_percent, memory_bytes))` - Resource usage
+    /// * `Some((cpu_percent, memory_bytes))` - Resource usage, with CPU as a
+    ///   percentage of one core and 0 until it has been measured
     /// * `None` - Process not found
     ///
     /// # Examples
@@ -231,8 +434,79 @@ impl SystemMonitor {
     /// }
     /// ```
     pub fn get_process_stats(&self, pid: u32) -> Option<(f32, u64)> {
-        self.get_process_metrics(pid)
-            .map(|(cpu, mem, _, _)| (cpu, mem))
+        self.get_process_metrics(pid).map(|usage| {
+            (
+                usage.cpu_usage_percent_of_core.unwrap_or(0.0),
+                usage.memory_bytes,
+            )
+        })
+    }
+
+    /// Gets a page of the heaviest processes on the system.
+    ///
+    /// Reuses the process table if it was refreshed within the last second,
+    /// so polling this alongside `refresh` doesn't double the cost.
+    ///
+    /// # Arguments
+    /// * `sort_by` - Column to sort by, heaviest first
+    /// * `limit` - Maximum number of processes to return
+    /// * `offset` - Number of processes to skip
+    /// * `managed` - Sentinel-managed processes by PID, marked in the result
+    ///
+    /// # Returns
+    /// The requested page and the total number of processes
+    pub fn get_top_processes(
+        &mut self,
+        sort_by: TopProcessSort,
+        limit: usize,
+        offset: usize,
+        managed: &HashMap<u32, ManagedProcessRef>,
+    ) -> TopProcesses {
+        let fresh = self
+            .last_process_refresh
+            .is_some_and(|last| last.elapsed() < PROCESS_TABLE_MAX_AGE);
+        if !fresh {
+            self.refresh_process_table();
+        }
+
+        let seconds = self
+            .process_sample_interval
+            .map_or(0.0, |interval| interval.as_secs_f64());
+        let rate = |bytes: u64| {
+            if seconds > 0.0 {
+                (bytes as f64 / seconds) as u64
+            } else {
+                0
+            }
+        };
+
+        let processes = self
+            .system
+            .processes()
+            .iter()
+            // Linux lists threads as tasks of their process; skip them
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .map(|(pid, process)| {
+                let disk_usage = process.disk_usage();
+                SystemProcess {
+                    pid: pid.as_u32(),
+                    name: process.name().to_string_lossy().to_string(),
+                    exe: process.exe().map(|exe| exe.to_string_lossy().to_string()),
+                    cpu_usage: process.cpu_usage(),
+                    memory: process.memory(),
+                    disk_read_bytes_per_sec: rate(disk_usage.read_bytes),
+                    disk_write_bytes_per_sec: rate(disk_usage.written_bytes),
+                    parent_pid: process.parent().map(|parent| parent.as_u32()),
+                    user: process
+                        .user_id()
+                        .and_then(|uid| self.users.get_user_by_id(uid))
+                        .map(|user| user.name().to_string()),
+                    managed: managed.get(&pid.as_u32()).cloned(),
+                }
+            })
+            .collect();
+
+        sort_and_page(processes, sort_by, limit, offset)
     }
 
     /// Gets the number of running processes.
@@ -307,13 +581,48 @@ impl SystemMonitor {
         self.memory_history.get_last_n(seconds)
     }
 
+    /// Gets CPU usage history aggregated for charting.
+    ///
+    /// # Arguments
+    /// * `seconds` - Number of seconds of history to include
+    /// * `max_points` - Maximum number of buckets to return
+    ///
+    /// # Returns
+    /// Min/max/avg CPU usage buckets (oldest first)
+    pub fn get_cpu_history_downsampled(
+        &self,
+        seconds: u64,
+        max_points: usize,
+    ) -> Vec<crate::core::metrics_buffer::MetricBucket> {
+        self.cpu_history.get_downsampled(seconds, max_points)
+    }
+
+    /// Gets memory usage history aggregated for charting.
+    ///
+    /// # Arguments
+    /// * `seconds` - Number of seconds of history to include
+    /// * `max_points` - Maximum number of buckets to return
+    ///
+    /// # Returns
+    /// Min/max/avg memory usage buckets in bytes (oldest first)
+    pub fn get_memory_history_downsampled(
+        &self,
+        seconds: u64,
+        max_points: usize,
+    ) -> Vec<crate::core::metrics_buffer::MetricBucket> {
+        self.memory_history.get_downsampled(seconds, max_points)
+    }
+
     /// Gets detailed process metrics including disk I/O.
     ///
+    /// CPU usage is measured between refreshes, so it's `None` until the
+    /// process has been seen by two of them.
+    ///
     /// # Arguments
     /// * `pid` - Process ID
     ///
     /// # Returns
-    /// * `Some((cpu_percent, memory_bytes, disk_read_bytes, disk_write_bytes))` - Resource usage
+    /// * `Some(usage)` - Resource usage
     /// * `None` - Process not found
     ///
     /// # Examples
@@ -323,26 +632,65 @@ impl SystemMonitor {
     /// let mut monitor = SystemMonitor::new();
     /// monitor.refresh();
     ///
-    /// if let Some((cpu, mem, disk_read, disk_write)) = monitor.get_process_metrics(std::process::id()) {
-    ///     println!("Process: CPU={:.2}%, Memory={} bytes", cpu, mem);
-    ///     println!("Disk I/O: Read={}, Write={}", disk_read, disk_write);
+    /// if let Some(usage) = monitor.get_process_metrics(std::process::id()) {
+    ///     if let Some(cpu) = usage.cpu_usage_percent_of_total {
+    ///         println!("Process: CPU={:.2}% of all cores", cpu);
+    ///     }
+    ///     println!("Memory={} bytes", usage.memory_bytes);
+    ///     println!("Disk I/O: Read={}, Write={}", usage.disk_read_bytes, usage.disk_write_bytes);
     /// }
     /// ```
-    pub fn get_process_metrics(&self, pid: u32) -> Option<(f32, u64, u64, u64)> {
-        use sysinfo::Pid;
-
-        let pid = Pid::from_u32(pid);
-        self.system.process(pid).map(|process| {
-            let cpu = process.cpu_usage();
-            let memory = process.memory();
-            let disk_usage = process.disk_usage();
-            let disk_read = disk_usage.read_bytes;
-            let disk_write = disk_usage.written_bytes;
-            (cpu, memory, disk_read, disk_write)
+    pub fn get_process_metrics(&self, pid: u32) -> Option<ProcessResourceUsage> {
+        let process = self.system.process(sysinfo::Pid::from_u32(pid))?;
+        let core_count = self.system.cpus().len();
+        let percent_of_core = self
+            .cpu_baseline
+            .contains(&pid)
+            .then(|| process.cpu_usage());
+        let disk_usage = process.disk_usage();
+
+        Some(ProcessResourceUsage {
+            cpu_usage_percent_of_core: percent_of_core,
+            cpu_usage_percent_of_total: percent_of_core
+                .map(|percent| cpu_percent_of_total(percent, core_count)),
+            memory_bytes: process.memory(),
+            disk_read_bytes: disk_usage.read_bytes,
+            disk_write_bytes: disk_usage.written_bytes,
         })
     }
 }
 
+/// PIDs in the process table of `system`.
+fn pids(system: &System) -> HashSet<u32> {
+    system.processes().keys().map(|pid| pid.as_u32()).collect()
+}
+
+/// Sorts processes heaviest first by `sort_by` and returns one page.
+///
+/// Ties are ordered by PID so pages stay stable between calls.
+fn sort_and_page(
+    mut processes: Vec<SystemProcess>,
+    sort_by: TopProcessSort,
+    limit: usize,
+    offset: usize,
+) -> TopProcesses {
+    processes.sort_by(|a, b| {
+        let heavier = match sort_by {
+            TopProcessSort::Cpu => b.cpu_usage.total_cmp(&a.cpu_usage),
+            TopProcessSort::Memory => b.memory.cmp(&a.memory),
+            TopProcessSort::DiskRead => b.disk_read_bytes_per_sec.cmp(&a.disk_read_bytes_per_sec),
+            TopProcessSort::DiskWrite => {
+                b.disk_write_bytes_per_sec.cmp(&a.disk_write_bytes_per_sec)
+            }
+        };
+        heavier.then_with(|| a.pid.cmp(&b.pid))
+    });
+
+    let total = processes.len();
+    let processes = processes.into_iter().skip(offset).take(limit).collect();
+    TopProcesses { processes, total }
+}
+
 impl Default for SystemMonitor {
     fn default() -> Self {
         Self::new()
@@ -371,6 +719,9 @@ mod tests {
         assert!(stats.core_count > 0);
         assert_eq!(stats.cores.len(), stats.core_count);
         assert!(stats.overall >= 0.0 && stats.overall <= 100.0 * stats.core_count as f32);
+        if let Some(frequencies) = stats.frequencies {
+            assert_eq!(frequencies.len(), stats.core_count);
+        }
     }
 
     #[test]
@@ -407,6 +758,18 @@ mod tests {
         assert!(stats.timestamp > 0);
     }
 
+    #[test]
+    fn test_get_stats_cached() {
+        let mut monitor = SystemMonitor::new();
+        let first = monitor.get_stats_cached(Duration::from_secs(60));
+        let cached = monitor.get_stats_cached(Duration::from_secs(60));
+        assert_eq!(cached.cpu.overall, first.cpu.overall);
+        assert_eq!(monitor.get_cpu_history(60).len(), 1);
+
+        monitor.get_stats_cached(Duration::ZERO);
+        assert_eq!(monitor.get_cpu_history(60).len(), 2);
+    }
+
     #[test]
     fn test_get_process_stats() {
         let mut monitor = SystemMonitor::new();
@@ -421,6 +784,156 @@ mod tests {
         assert!(memory > 0);
     }
 
+    #[test]
+    fn test_get_process_metrics_cpu_needs_two_samples() {
+        let mut monitor = SystemMonitor::new();
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+
+        // First seen by this refresh, nothing to compare against
+        monitor.refresh();
+        let first = monitor.get_process_metrics(pid).unwrap();
+        assert_eq!(first.cpu_usage_percent_of_core, None);
+        assert_eq!(first.cpu_usage_percent_of_total, None);
+
+        thread::sleep(MIN_CPU_SAMPLE_INTERVAL);
+        monitor.refresh();
+        let usage = monitor.get_process_metrics(pid).unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let cores = monitor.system.cpus().len().max(1) as f32;
+        let of_core = usage.cpu_usage_percent_of_core.unwrap();
+        let of_total = usage.cpu_usage_percent_of_total.unwrap();
+        assert!((of_total * cores - of_core).abs() < 1e-3);
+        assert!(usage.memory_bytes > 0);
+    }
+
+    #[test]
+    fn test_load_average() {
+        let monitor = SystemMonitor::new();
+        let load = monitor.load_average();
+
+        if cfg!(target_os = "windows") {
+            assert!(load.is_none());
+        } else {
+            let load = load.unwrap();
+            assert!(load.one >= 0.0 && load.five >= 0.0 && load.fifteen >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_temperatures() {
+        let monitor = SystemMonitor::new();
+
+        // Machines without sensors (VMs, CI) report None rather than an empty list
+        if let Some(temperatures) = monitor.temperatures() {
+            assert!(!temperatures.is_empty());
+            assert!(temperatures.iter().all(|t| t.temperature.is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_get_details() {
+        let mut monitor = SystemMonitor::new();
+        let details = monitor.get_details();
+
+        assert_eq!(details.load_average.is_some(), !cfg!(target_os = "windows"));
+        assert!(details
+            .disks
+            .iter()
+            .all(|disk| disk.available_space <= disk.total_space));
+    }
+
+    fn fixture(pid: u32, cpu_usage: f32, memory: u64, read: u64, write: u64) -> SystemProcess {
+        SystemProcess {
+            pid,
+            name: format!("proc{}", pid),
+            exe: None,
+            cpu_usage,
+            memory,
+            disk_read_bytes_per_sec: read,
+            disk_write_bytes_per_sec: write,
+            parent_pid: Some(1),
+            user: None,
+            managed: None,
+        }
+    }
+
+    fn fixtures() -> Vec<SystemProcess> {
+        vec![
+            fixture(10, 5.0, 300, 0, 70),
+            fixture(20, 50.0, 100, 900, 10),
+            fixture(30, 20.0, 200, 400, 0),
+            fixture(40, 20.0, 400, 100, 30),
+        ]
+    }
+
+    fn pids(page: &TopProcesses) -> Vec<u32> {
+        page.processes.iter().map(|process| process.pid).collect()
+    }
+
+    #[test]
+    fn test_sort_and_page_by_each_column() {
+        let by = |sort_by| pids(&sort_and_page(fixtures(), sort_by, 10, 0));
+
+        // Equal CPU usage falls back to PID order
+        assert_eq!(by(TopProcessSort::Cpu), vec![20, 30, 40, 10]);
+        assert_eq!(by(TopProcessSort::Memory), vec![40, 10, 30, 20]);
+        assert_eq!(by(TopProcessSort::DiskRead), vec![20, 30, 40, 10]);
+        assert_eq!(by(TopProcessSort::DiskWrite), vec![10, 40, 20, 30]);
+    }
+
+    #[test]
+    fn test_sort_and_page_pagination() {
+        let first = sort_and_page(fixtures(), TopProcessSort::Memory, 2, 0);
+        assert_eq!(pids(&first), vec![40, 10]);
+        assert_eq!(first.total, 4);
+
+        let second = sort_and_page(fixtures(), TopProcessSort::Memory, 2, 2);
+        assert_eq!(pids(&second), vec![30, 20]);
+        assert_eq!(second.total, 4);
+
+        let past_end = sort_and_page(fixtures(), TopProcessSort::Memory, 2, 10);
+        assert!(past_end.processes.is_empty());
+        assert_eq!(past_end.total, 4);
+    }
+
+    #[test]
+    fn test_get_top_processes_marks_managed() {
+        let mut monitor = SystemMonitor::new();
+        let current_pid = std::process::id();
+        let managed = HashMap::from([(
+            current_pid,
+            ManagedProcessRef {
+                id: "self".to_string(),
+                kind: crate::models::ProcessKind::Piped,
+            },
+        )]);
+
+        let page = monitor.get_top_processes(TopProcessSort::Memory, usize::MAX, 0, &managed);
+        assert_eq!(page.processes.len(), page.total);
+        assert!(page
+            .processes
+            .windows(2)
+            .all(|pair| pair[0].memory >= pair[1].memory));
+
+        let current = page
+            .processes
+            .iter()
+            .find(|process| process.pid == current_pid)
+            .unwrap();
+        assert_eq!(current.managed.as_ref().unwrap().id, "self");
+        assert!(page
+            .processes
+            .iter()
+            .filter(|process| process.pid != current_pid)
+            .all(|process| process.managed.is_none()));
+    }
+
     #[test]
     fn test_process_count() {
         let monitor = SystemMonitor::new();
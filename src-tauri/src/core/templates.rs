@@ -0,0 +1,691 @@
+//! User process templates.
+//!
+//! A template is a process config whose strings can hold `{{VARIABLE}}`
+//! placeholders, filled in when the template is used. User templates are
+//! saved to `templates.yaml` in the config directory and listed together with
+//! the built-in framework templates.
+//!
+//! Placeholders work in any string of the config (command, arguments, working
+//! directory, environment values, health check URL and so on), but not in
+//! numbers such as a TCP health check's port.
+
+use crate::core::{get_framework_templates, secrets, ConfigManager, ProcessTemplate};
+use crate::error::{Result, SentinelError};
+use crate::models::{Config, GlobalSettings, ProcessConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Variable holding the name of the new process; every template has it.
+pub const NAME_VARIABLE: &str = "NAME";
+
+/// Variable holding the project directory, used as the working directory.
+pub const PROJECT_DIR_VARIABLE: &str = "PROJECT_DIR";
+
+/// Variable holding the port, passed to built-in templates as `PORT`.
+pub const PORT_VARIABLE: &str = "PORT";
+
+/// Where a template comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TemplateSource {
+    Builtin,
+    User,
+}
+
+/// A template saved by the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// The process config, with placeholders.
+    pub process: ProcessConfig,
+    /// Values offered for variables the user doesn't fill in.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UserTemplate {
+    /// Makes a template from a configured process.
+    ///
+    /// Every occurrence of a value in `variables` is replaced by its
+    /// placeholder, and the value becomes the variable's default. The name
+    /// becomes `{{NAME}}` and the working directory `{{PROJECT_DIR}}`.
+    /// Dependencies are dropped, since they name processes of one config.
+    /// Environment values of keys matching `secret_patterns` aren't kept;
+    /// they become a placeholder named after the key, without a default.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if the template name is empty or a variable
+    /// name isn't made of letters, digits and `_`.
+    pub fn from_process(
+        name: &str,
+        description: &str,
+        process: &ProcessConfig,
+        variables: &BTreeMap<String, String>,
+        secret_patterns: &[String],
+    ) -> Result<Self> {
+        if name.trim().is_empty() {
+            return Err(SentinelError::InvalidInput {
+                message: "Template name cannot be empty".to_string(),
+            });
+        }
+        if let Some(invalid) = variables.keys().find(|key| !is_variable_name(key)) {
+            return Err(SentinelError::InvalidInput {
+                message: format!(
+                    "Invalid template variable '{}': use letters, digits and _",
+                    invalid
+                ),
+            });
+        }
+
+        let mut defaults = variables.clone();
+        defaults.remove(NAME_VARIABLE);
+        if let Some(cwd) = &process.cwd {
+            defaults
+                .entry(PROJECT_DIR_VARIABLE.to_string())
+                .or_insert_with(|| cwd.to_string_lossy().into_owned());
+        }
+
+        let mut process = process.clone();
+        process.name = placeholder(NAME_VARIABLE);
+        process.depends_on.clear();
+        for (key, value) in process.env.iter_mut() {
+            if secrets::is_secret(key, secret_patterns) {
+                *value = if is_variable_name(key) {
+                    placeholder(key)
+                } else {
+                    String::new()
+                };
+            }
+        }
+
+        // Longest values first, so a value inside another isn't replaced in it
+        let mut replacements: Vec<(&String, &String)> = defaults
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        replacements.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+
+        let mut value = to_value(&process)?;
+        map_strings(&mut value, &mut |text| {
+            let mut text = text.to_string();
+            for (variable, value) in &replacements {
+                text = text.replace(value.as_str(), &placeholder(variable));
+            }
+            text
+        });
+
+        Ok(Self {
+            name: name.trim().to_string(),
+            description: description.to_string(),
+            process: from_value(value)?,
+            defaults,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Template for a built-in framework template, with `{{PORT}}` in place
+    /// of its default port.
+    pub fn from_builtin(template: &ProcessTemplate) -> Result<Self> {
+        let mut env: Mapping = template
+            .default_env_vars
+            .iter()
+            .map(|(key, value)| (Value::from(key.as_str()), Value::from(value.as_str())))
+            .collect();
+        let mut process = Mapping::new();
+        process.insert("name".into(), "".into());
+        process.insert("command".into(), template.command.as_str().into());
+        process.insert(
+            "args".into(),
+            template.args.iter().map(|a| a.as_str()).collect(),
+        );
+        if let Some(port) = template.default_port {
+            env.insert("PORT".into(), port.to_string().into());
+        }
+        process.insert("env".into(), Value::Mapping(env));
+        if let Some(url) = &template.health_check_url {
+            let mut health_check = Mapping::new();
+            health_check.insert("type".into(), "http".into());
+            health_check.insert("url".into(), url.as_str().into());
+            health_check.insert("timeoutMs".into(), 5000.into());
+            health_check.insert("retries".into(), 3.into());
+            process.insert("healthCheck".into(), Value::Mapping(health_check));
+        }
+        let process = from_value(Value::Mapping(process))?;
+
+        let variables = template
+            .default_port
+            .map(|port| BTreeMap::from([(PORT_VARIABLE.to_string(), port.to_string())]))
+            .unwrap_or_default();
+        let mut builtin = Self::from_process(
+            &template.name,
+            &template.description,
+            &process,
+            &variables,
+            &[],
+        )?;
+        // No default: the project is wherever the user wants it
+        builtin.process.cwd = Some(PathBuf::from(placeholder(PROJECT_DIR_VARIABLE)));
+        Ok(builtin)
+    }
+
+    /// Variables used by the template, sorted by name.
+    pub fn variables(&self) -> Vec<TemplateVariable> {
+        let mut names = BTreeSet::new();
+        if let Ok(mut value) = to_value(&self.process) {
+            map_strings(&mut value, &mut |text| {
+                fill(text, &mut |name| {
+                    names.insert(name.to_string());
+                    None
+                })
+            });
+        }
+        names
+            .into_iter()
+            .map(|name| TemplateVariable {
+                default: self.defaults.get(&name).cloned(),
+                name,
+            })
+            .collect()
+    }
+
+    /// The template as listed to the UI.
+    pub fn summary(&self, source: TemplateSource, icon: Option<String>) -> TemplateSummary {
+        TemplateSummary {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            source,
+            icon,
+            command: self.process.command.clone(),
+            args: self.process.args.clone(),
+            variables: self.variables(),
+        }
+    }
+
+    /// Fills in the placeholders with `values`, or the defaults.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` naming every variable without a value, or
+    /// `InvalidConfig` if the filled-in config doesn't validate.
+    pub fn instantiate(&self, values: &HashMap<String, String>) -> Result<ProcessConfig> {
+        let mut missing = BTreeSet::new();
+        let mut value = to_value(&self.process)?;
+        map_strings(&mut value, &mut |text| {
+            fill(text, &mut |name| {
+                let filled = values
+                    .get(name)
+                    .or_else(|| self.defaults.get(name))
+                    .cloned();
+                if filled.is_none() {
+                    missing.insert(name.to_string());
+                }
+                filled
+            })
+        });
+        if !missing.is_empty() {
+            return Err(SentinelError::InvalidInput {
+                message: format!(
+                    "Template '{}' needs values for: {}",
+                    self.name,
+                    missing.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            });
+        }
+
+        let process: ProcessConfig = from_value(value)?;
+        ConfigManager::validate(&Config {
+            processes: vec![process.clone()],
+            settings: GlobalSettings::default(),
+            global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
+        })?;
+        Ok(process)
+    }
+}
+
+/// A placeholder in a template and its default value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// A built-in or user template, as listed to the UI.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSummary {
+    pub name: String,
+    pub description: String,
+    pub source: TemplateSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    pub command: String,
+    pub args: Vec<String>,
+    /// Variables to fill in, `NAME` included.
+    pub variables: Vec<TemplateVariable>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct TemplateFile {
+    #[serde(default)]
+    templates: Vec<UserTemplate>,
+}
+
+/// Saves user templates to a YAML file.
+#[derive(Debug, Clone)]
+pub struct TemplateStore {
+    path: PathBuf,
+}
+
+impl TemplateStore {
+    /// A store backed by the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `templates.yaml` in the Sentinel config directory.
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("sentinel")
+            .join("templates.yaml")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Saved user templates.
+    ///
+    /// # Errors
+    /// Returns `FileIoError` or `ConfigParseError` if the file can't be read.
+    pub fn load(&self) -> Result<Vec<UserTemplate>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents =
+            fs::read_to_string(&self.path).map_err(|source| SentinelError::FileIoError {
+                path: self.path.clone(),
+                source,
+            })?;
+        let file: TemplateFile =
+            serde_yaml::from_str(&contents).map_err(|e| SentinelError::InvalidConfig {
+                reason: format!("{}: {}", self.path.display(), e),
+            })?;
+        Ok(file.templates)
+    }
+
+    fn write(&self, templates: Vec<UserTemplate>) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|source| SentinelError::FileIoError {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let contents = serde_yaml::to_string(&TemplateFile { templates })
+            .map_err(|e| SentinelError::Other(e.to_string()))?;
+        fs::write(&self.path, contents).map_err(|source| SentinelError::FileIoError {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    /// Saves `template`, replacing a user template with the same name.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if a built-in template has the name.
+    pub fn save(&self, template: UserTemplate) -> Result<()> {
+        if get_framework_templates()
+            .iter()
+            .any(|builtin| builtin.name == template.name)
+        {
+            return Err(SentinelError::InvalidInput {
+                message: format!("'{}' is a built-in template", template.name),
+            });
+        }
+
+        let mut templates = self.load()?;
+        match templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => templates.push(template),
+        }
+        self.write(templates)
+    }
+
+    /// Deletes the user template `name`.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if there is no user template with that name.
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let mut templates = self.load()?;
+        let count = templates.len();
+        templates.retain(|t| t.name != name);
+        if templates.len() == count {
+            return Err(SentinelError::InvalidInput {
+                message: format!("No user template named '{}'", name),
+            });
+        }
+        self.write(templates)
+    }
+
+    /// The built-in or user template `name`.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if there is no template with that name.
+    pub fn get(&self, name: &str) -> Result<UserTemplate> {
+        if let Some(builtin) = get_framework_templates().iter().find(|t| t.name == name) {
+            return UserTemplate::from_builtin(builtin);
+        }
+        self.load()?
+            .into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| SentinelError::InvalidInput {
+                message: format!("No template named '{}'", name),
+            })
+    }
+
+    /// Built-in templates followed by user templates.
+    pub fn list(&self) -> Result<Vec<TemplateSummary>> {
+        let mut summaries = Vec::new();
+        for builtin in get_framework_templates() {
+            let template = UserTemplate::from_builtin(&builtin)?;
+            summaries.push(template.summary(TemplateSource::Builtin, Some(builtin.icon)));
+        }
+        for template in self.load()? {
+            summaries.push(template.summary(TemplateSource::User, None));
+        }
+        Ok(summaries)
+    }
+
+    /// Fills in the template `name` with `values`.
+    pub fn instantiate(
+        &self,
+        name: &str,
+        values: &HashMap<String, String>,
+    ) -> Result<ProcessConfig> {
+        self.get(name)?.instantiate(values)
+    }
+}
+
+impl Default for TemplateStore {
+    fn default() -> Self {
+        Self::new(Self::default_path())
+    }
+}
+
+fn placeholder(variable: &str) -> String {
+    format!("{{{{{}}}}}", variable)
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Replaces each `{{VARIABLE}}` in `text` with what `lookup` returns for it,
+/// leaving it as is when that is `None`.
+fn fill(text: &str, lookup: &mut impl FnMut(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let name = after[..end].trim();
+        match is_variable_name(name).then(|| lookup(name)).flatten() {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Applies `f` to every string in `value`, leaving mapping keys alone.
+fn map_strings(value: &mut Value, f: &mut impl FnMut(&str) -> String) {
+    match value {
+        Value::String(text) => *text = f(text),
+        Value::Sequence(items) => items.iter_mut().for_each(|item| map_strings(item, f)),
+        Value::Mapping(mapping) => mapping
+            .iter_mut()
+            .for_each(|(_, value)| map_strings(value, f)),
+        Value::Tagged(tagged) => map_strings(&mut tagged.value, f),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn to_value(process: &ProcessConfig) -> Result<Value> {
+    serde_yaml::to_value(process).map_err(|e| SentinelError::Other(e.to_string()))
+}
+
+fn from_value(value: Value) -> Result<ProcessConfig> {
+    serde_yaml::from_value(value).map_err(|e| SentinelError::InvalidConfig {
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn process() -> ProcessConfig {
+        serde_yaml::from_str(
+            r#"
+name: shop-api
+command: npm
+args: ["run", "dev", "--", "--port", "4100"]
+cwd: /home/dev/shop
+env:
+  PORT: "4100"
+  API_URL: http://localhost:4100/api
+dependsOn: [db]
+healthCheck:
+  type: http
+  url: http://localhost:4100/health
+  timeoutMs: 2000
+  retries: 3
+"#,
+        )
+        .unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn template() -> UserTemplate {
+        let variables = BTreeMap::from([("PORT".to_string(), "4100".to_string())]);
+        UserTemplate::from_process("Node API", "Express app", &process(), &variables, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_from_process_inserts_placeholders() {
+        let template = template();
+        let process = &template.process;
+
+        assert_eq!(process.name, "{{NAME}}");
+        assert_eq!(process.args[4], "{{PORT}}");
+        assert_eq!(process.cwd, Some(PathBuf::from("{{PROJECT_DIR}}")));
+        assert_eq!(process.env["API_URL"], "http://localhost:{{PORT}}/api");
+        assert!(process.depends_on.is_empty());
+        assert_eq!(
+            template.variables(),
+            [
+                TemplateVariable {
+                    name: "NAME".to_string(),
+                    default: None
+                },
+                TemplateVariable {
+                    name: "PORT".to_string(),
+                    default: Some("4100".to_string())
+                },
+                TemplateVariable {
+                    name: "PROJECT_DIR".to_string(),
+                    default: Some("/home/dev/shop".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instantiate_substitutes_everywhere() {
+        let process = template()
+            .instantiate(&values(&[
+                ("NAME", "cart-api"),
+                ("PORT", "5200"),
+                ("PROJECT_DIR", "/srv/cart"),
+            ]))
+            .unwrap();
+
+        assert_eq!(process.name, "cart-api");
+        assert_eq!(process.args, ["run", "dev", "--", "--port", "5200"]);
+        assert_eq!(process.cwd, Some(PathBuf::from("/srv/cart")));
+        assert_eq!(process.env["PORT"], "5200");
+        let health_check = serde_yaml::to_string(&process.health_check).unwrap();
+        assert!(health_check.contains("http://localhost:5200/health"));
+    }
+
+    #[test]
+    fn test_instantiate_uses_defaults() {
+        let process = template()
+            .instantiate(&values(&[("NAME", "cart-api")]))
+            .unwrap();
+        assert_eq!(process.env["PORT"], "4100");
+        assert_eq!(process.cwd, Some(PathBuf::from("/home/dev/shop")));
+    }
+
+    #[test]
+    fn test_missing_variables_are_all_reported() {
+        let mut template = template();
+        template.defaults.clear();
+
+        let error = template.instantiate(&HashMap::new()).unwrap_err();
+        assert!(matches!(error, SentinelError::InvalidInput { .. }));
+        assert!(error
+            .to_string()
+            .contains("needs values for: NAME, PORT, PROJECT_DIR"));
+    }
+
+    #[test]
+    fn test_instantiated_config_is_validated() {
+        let error = template()
+            .instantiate(&values(&[("NAME", "")]))
+            .unwrap_err();
+        assert!(matches!(error, SentinelError::InvalidConfig { .. }));
+    }
+
+    #[test]
+    fn test_fill_leaves_unknown_text() {
+        let text = "{{ NAME }}-{{not closed";
+        let filled = fill(text, &mut |name| Some(name.to_lowercase()));
+        assert_eq!(filled, "name-{{not closed");
+        assert_eq!(
+            fill("{{1X}} {{}}", &mut |_| Some("v".to_string())),
+            "{{1X}} {{}}"
+        );
+    }
+
+    #[test]
+    fn test_invalid_variable_name_is_rejected() {
+        let variables = BTreeMap::from([("MY-PORT".to_string(), "4100".to_string())]);
+        assert!(UserTemplate::from_process("x", "", &process(), &variables, &[]).is_err());
+    }
+
+    #[test]
+    fn test_secret_env_values_become_variables() {
+        let mut process = process();
+        process
+            .env
+            .insert("STRIPE_SECRET".to_string(), "sk_live_123".to_string());
+        let patterns = GlobalSettings::default().secret_patterns;
+        let template =
+            UserTemplate::from_process("x", "", &process, &BTreeMap::new(), &patterns).unwrap();
+
+        assert_eq!(template.process.env["STRIPE_SECRET"], "{{STRIPE_SECRET}}");
+        assert!(!serde_yaml::to_string(&template)
+            .unwrap()
+            .contains("sk_live_123"));
+        assert!(template.variables().contains(&TemplateVariable {
+            name: "STRIPE_SECRET".to_string(),
+            default: None
+        }));
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let store = TemplateStore::new(dir.path().join("templates.yaml"));
+
+        store.save(template()).unwrap();
+        let summaries = store.list().unwrap();
+        let user: Vec<_> = summaries
+            .iter()
+            .filter(|s| s.source == TemplateSource::User)
+            .collect();
+        assert_eq!(user.len(), 1);
+        assert_eq!(user[0].name, "Node API");
+        assert!(summaries
+            .iter()
+            .any(|s| s.source == TemplateSource::Builtin));
+
+        let process = store
+            .instantiate("Node API", &values(&[("NAME", "cart-api")]))
+            .unwrap();
+        assert_eq!(process.name, "cart-api");
+
+        store.delete("Node API").unwrap();
+        assert!(store.load().unwrap().is_empty());
+        assert!(store.delete("Node API").is_err());
+    }
+
+    #[test]
+    fn test_builtin_templates_instantiate() {
+        let dir = TempDir::new().unwrap();
+        let store = TemplateStore::new(dir.path().join("templates.yaml"));
+        let builtin = get_framework_templates().remove(0);
+
+        let error = store
+            .instantiate(&builtin.name, &values(&[("NAME", "web")]))
+            .unwrap_err();
+        assert!(error.to_string().contains("PROJECT_DIR"));
+
+        let process = store
+            .instantiate(
+                &builtin.name,
+                &values(&[
+                    ("NAME", "web"),
+                    ("PROJECT_DIR", "/srv/web"),
+                    ("PORT", "3100"),
+                ]),
+            )
+            .unwrap();
+        assert_eq!(process.env["PORT"], "3100");
+        assert!(serde_yaml::to_string(&process.health_check)
+            .unwrap()
+            .contains("localhost:3100"));
+
+        // Built-in names can't be taken by user templates
+        let mut shadow = template();
+        shadow.name = builtin.name;
+        assert!(store.save(shadow).is_err());
+    }
+}
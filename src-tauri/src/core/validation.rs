@@ -0,0 +1,872 @@
+//! Safety checks for process configurations.
+//!
+//! Configs reach the process manager both from config files and directly
+//! from the frontend, so these checks run at load time and again before a
+//! process is spawned.
+
+use crate::core::{readiness, scheduler, scheduling};
+use crate::error::{Result, SentinelError};
+use crate::models::{
+    AlertRule, ApiSettings, GlobalSettings, HealthCheck, HealthCheckKind, NotificationSink,
+    ProcessConfig, ReadinessCheck, ReadinessProbe, RestartPolicy,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Longest allowed process name.
+pub const MAX_PROCESS_NAME_LENGTH: usize = 128;
+
+/// Whether `name` is a safe process name.
+///
+/// Names are 1 to [`MAX_PROCESS_NAME_LENGTH`] characters from `[A-Za-z0-9._-]`
+/// and are not `.` or `..`, so they are safe in file names and log output.
+///
+/// # Examples
+/// ```
+/// use sentinel::core::validation::is_valid_process_name;
+///
+/// assert!(is_valid_process_name("api.server-2"));
+/// assert!(!is_valid_process_name("api; rm -rf /"));
+/// ```
+pub fn is_valid_process_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROCESS_NAME_LENGTH
+        && name != "."
+        && name != ".."
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Checks a process configuration, returning every problem found.
+pub fn check_process(config: &ProcessConfig, settings: &GlobalSettings) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let mut invalid = |reason: String| problems.push(SentinelError::InvalidConfig { reason });
+
+    if config.name.trim().is_empty() {
+        invalid("Process name cannot be empty".to_string());
+    } else if !is_valid_process_name(&config.name) {
+        invalid(format!(
+            "Invalid process name '{}': use up to {} letters, digits, '.', '_' or '-'",
+            config.name.escape_debug(),
+            MAX_PROCESS_NAME_LENGTH
+        ));
+    }
+
+    for tag in config.tags.iter().filter(|tag| !is_valid_process_name(tag)) {
+        invalid(format!(
+            "Invalid tag '{}' on process '{}': use up to {} letters, digits, '.', '_' or '-'",
+            tag.escape_debug(),
+            config.name,
+            MAX_PROCESS_NAME_LENGTH
+        ));
+    }
+
+    if config.command.trim().is_empty() {
+        invalid(format!("Process '{}' has empty command", config.name));
+    }
+
+    let has_control_bytes = |s: &String| s.contains(['\0', '\n', '\r']);
+    if has_control_bytes(&config.command) || config.args.iter().any(has_control_bytes) {
+        invalid(format!(
+            "Process '{}' has a NUL or newline in its command or arguments",
+            config.name
+        ));
+    }
+
+    if !settings.allow_privileged_commands {
+        if let Some(program) = blocked_program(config, &settings.blocked_commands) {
+            invalid(format!(
+                "Process '{}' uses blocked command '{}' (set allowPrivilegedCommands to permit it)",
+                config.name, program
+            ));
+        }
+    }
+
+    if let Some(Err(e)) = config.nice.map(scheduling::validate_priority) {
+        problems.push(e);
+    }
+    if let Some(Err(e)) = config
+        .cpu_affinity
+        .as_deref()
+        .map(scheduling::validate_affinity)
+    {
+        problems.push(e);
+    }
+    if let Some(Err(e)) = config.schedule.as_deref().map(scheduler::parse) {
+        problems.push(e);
+    }
+    if let Some(health_check) = &config.health_check {
+        problems.extend(check_health_check(&config.name, health_check));
+    }
+    if let Some(probe) = &config.readiness {
+        problems.extend(check_readiness(&config.name, probe));
+    }
+    if let Some(policy) = &config.restart_policy {
+        problems.extend(check_restart_policy(config, policy));
+    }
+
+    problems
+}
+
+/// Checks the restart policy of a process.
+fn check_restart_policy(config: &ProcessConfig, policy: &RestartPolicy) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let mut invalid = |reason: String| problems.push(SentinelError::InvalidConfig { reason });
+    let name = &config.name;
+
+    if policy.max_memory_bytes == Some(0) {
+        invalid(format!(
+            "Restart policy of process '{}' has maxMemoryBytes 0",
+            name
+        ));
+    }
+    if policy.max_uptime_hours == Some(0) {
+        invalid(format!(
+            "Restart policy of process '{}' has maxUptimeHours 0",
+            name
+        ));
+    }
+    if config.schedule.is_some() {
+        invalid(format!(
+            "Process '{}' has a schedule, so it can't have a restart policy too",
+            name
+        ));
+    }
+    if let Some(Err(e)) = policy.cron.as_deref().map(scheduler::parse) {
+        problems.push(e);
+    }
+
+    problems
+}
+
+/// Settings of a process that are valid but probably not what was meant.
+pub fn process_warnings(config: &ProcessConfig, settings: &GlobalSettings) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let Some(policy) = &config.restart_policy else {
+        return warnings;
+    };
+
+    if policy.is_empty() {
+        warnings.push(format!(
+            "Restart policy of process '{}' has no settings, so it never restarts it",
+            config.name
+        ));
+    }
+    if !config.effective_auto_restart(settings) && !policy.is_empty() {
+        warnings.push(format!(
+            "Process '{}' has a restart policy but autoRestart is off, so it stays down if it \
+             crashes after a policy restart",
+            config.name
+        ));
+    }
+    if let (Some(max_runtime_ms), Some(hours)) = (config.max_runtime_ms, policy.max_uptime_hours) {
+        if max_runtime_ms <= hours.saturating_mul(3_600_000) {
+            warnings.push(format!(
+                "Process '{}' is stopped by maxRuntimeMs before its restart policy's \
+                 maxUptimeHours restarts it",
+                config.name
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Checks the health check of the process `name`.
+fn check_health_check(name: &str, health_check: &HealthCheck) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let mut invalid = |reason: String| problems.push(SentinelError::InvalidConfig { reason });
+
+    if health_check.timeout_ms == 0 {
+        invalid(format!(
+            "Health check of process '{}' needs a timeoutMs above 0",
+            name
+        ));
+    }
+
+    match &health_check.check {
+        HealthCheckKind::Command { command, .. } if command.trim().is_empty() => {
+            invalid(format!(
+                "Health check of process '{}' has empty command",
+                name
+            ));
+        }
+        HealthCheckKind::Http { url, method, .. } => {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                invalid(format!(
+                    "Health check of process '{}' has URL '{}' (must start with http:// or https://)",
+                    name, url
+                ));
+            }
+            if reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes()).is_err() {
+                invalid(format!(
+                    "Health check of process '{}' has invalid HTTP method '{}'",
+                    name,
+                    method.escape_debug()
+                ));
+            }
+        }
+        HealthCheckKind::Tcp { port: 0, .. } => {
+            invalid(format!("Health check of process '{}' has port 0", name));
+        }
+        _ => {}
+    }
+
+    problems
+}
+
+/// Checks the readiness probe of the process `name`.
+fn check_readiness(name: &str, probe: &ReadinessProbe) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let mut invalid = |reason: String| problems.push(SentinelError::InvalidConfig { reason });
+
+    if probe.interval_ms == 0 {
+        invalid(format!(
+            "Readiness probe of process '{}' needs an intervalMs above 0",
+            name
+        ));
+    }
+
+    match &probe.check {
+        ReadinessCheck::Tcp { port: 0, .. } => {
+            invalid(format!("Readiness probe of process '{}' has port 0", name));
+        }
+        ReadinessCheck::Http { url, .. }
+            if !url.starts_with("http://") && !url.starts_with("https://") =>
+        {
+            invalid(format!(
+                "Readiness probe of process '{}' has URL '{}' (must start with http:// or https://)",
+                name, url
+            ));
+        }
+        ReadinessCheck::Command { command, .. } if command.trim().is_empty() => {
+            invalid(format!(
+                "Readiness probe of process '{}' has empty command",
+                name
+            ));
+        }
+        ReadinessCheck::Log { pattern } => {
+            if let Err(e) = readiness::compile_pattern(pattern) {
+                problems.push(e);
+            }
+        }
+        _ => {}
+    }
+
+    problems
+}
+
+/// Checks an alert rule against the configured process names, returning
+/// every problem found.
+pub fn check_alert_rule(rule: &AlertRule, process_names: &HashSet<&String>) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let mut invalid = |reason: String| problems.push(SentinelError::InvalidConfig { reason });
+
+    if rule.name.trim().is_empty() {
+        invalid("Alert rule name cannot be empty".to_string());
+    }
+
+    if rule.is_system() {
+        if !rule.metric.applies_to_system() {
+            invalid(format!(
+                "Alert rule '{}' watches {:?}, which only applies to processes",
+                rule.name, rule.metric
+            ));
+        }
+    } else if !process_names.contains(&rule.target) {
+        invalid(format!(
+            "Alert rule '{}' targets unknown process '{}'",
+            rule.name, rule.target
+        ));
+    } else if !rule.metric.applies_to_process() {
+        invalid(format!(
+            "Alert rule '{}' watches {:?}, which only applies to the system target",
+            rule.name, rule.metric
+        ));
+    }
+
+    match rule.threshold {
+        None if rule.metric.has_threshold() => invalid(format!(
+            "Alert rule '{}' needs a threshold for {:?}",
+            rule.name, rule.metric
+        )),
+        Some(threshold) if !threshold.is_finite() || threshold < 0.0 => invalid(format!(
+            "Alert rule '{}' has invalid threshold {}",
+            rule.name, threshold
+        )),
+        _ => {}
+    }
+
+    problems
+}
+
+/// Checks a notification sink, returning every problem found.
+pub fn check_notification_sink(sink: &NotificationSink) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let mut invalid = |reason: String| problems.push(SentinelError::InvalidConfig { reason });
+
+    if sink.name.trim().is_empty() {
+        invalid("Notification sink name cannot be empty".to_string());
+    }
+
+    if !(sink.url.starts_with("http://") || sink.url.starts_with("https://")) {
+        invalid(format!(
+            "Notification sink '{}' needs an http:// or https:// URL",
+            sink.name
+        ));
+    }
+
+    let has_control_bytes = |s: &String| s.contains(['\0', '\n', '\r']);
+    if sink
+        .headers
+        .iter()
+        .any(|(name, value)| name.is_empty() || has_control_bytes(name) || has_control_bytes(value))
+    {
+        invalid(format!(
+            "Notification sink '{}' has an empty header name or a NUL or newline in a header",
+            sink.name
+        ));
+    }
+
+    problems
+}
+
+/// Checks the API settings, returning every problem found.
+pub fn check_api(api: &ApiSettings) -> Vec<SentinelError> {
+    let mut problems = Vec::new();
+    let has_token = api
+        .token
+        .as_deref()
+        .is_some_and(|token| !token.trim().is_empty());
+    if api.enabled && !has_token {
+        problems.push(SentinelError::InvalidConfig {
+            reason: "The API needs a token when enabled".to_string(),
+        });
+    }
+    problems
+}
+
+/// Checks a process configuration, failing on the first problem.
+///
+/// # Errors
+/// Returns `InvalidConfig` describing the problem, or `InvalidPriority` /
+/// `InvalidCpuAffinity` for scheduling values that can't be applied.
+pub fn validate_process(config: &ProcessConfig, settings: &GlobalSettings) -> Result<()> {
+    match check_process(config, settings).into_iter().next() {
+        Some(problem) => Err(problem),
+        None => Ok(()),
+    }
+}
+
+/// Resolves `path` and checks that it lies under one of `allowed_roots`.
+///
+/// Symlinks and `..` components are resolved first, so neither can be used to
+/// escape a root. Relative paths resolve against the current directory. An
+/// empty `allowed_roots` allows any path and returns it unchanged.
+///
+/// # Errors
+/// Returns `FileIoError` if the path cannot be resolved, or `PathNotAllowed`
+/// if it is outside every root.
+pub fn resolve_allowed_path(path: &Path, allowed_roots: &[PathBuf]) -> Result<PathBuf> {
+    if allowed_roots.is_empty() {
+        return Ok(path.to_path_buf());
+    }
+
+    let resolved = path
+        .canonicalize()
+        .map_err(|source| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    // A root that doesn't exist can't contain anything
+    let allowed = allowed_roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+
+    if allowed {
+        Ok(resolved)
+    } else {
+        Err(SentinelError::PathNotAllowed {
+            path: resolved,
+            allowed: allowed_roots.to_vec(),
+        })
+    }
+}
+
+/// Programs that run the rest of their arguments as a command.
+const WRAPPERS: &[&str] = &[
+    "chrt", "command", "env", "exec", "ionice", "nice", "nohup", "setsid", "stdbuf", "taskset",
+    "time", "timeout", "xargs",
+];
+
+/// Shells whose `-c` (or `/c`) argument is a script to look into.
+const SHELLS: &[&str] = &["ash", "bash", "cmd", "dash", "fish", "ksh", "sh", "zsh"];
+
+/// The program a process runs, if it is on the blocklist.
+///
+/// Wrappers such as `env` or `nohup` and the first command of each
+/// statement in a `sh -c` script are looked through as well. This is a
+/// best-effort guard against running these by mistake, not a security
+/// boundary: a process that may run a shell or interpreter can still reach
+/// any program, e.g. through a script file or a variable.
+fn blocked_program(config: &ProcessConfig, blocklist: &[String]) -> Option<String> {
+    // Without args the command is split on whitespace before spawning
+    let words: Vec<&str> = if config.args.is_empty() {
+        config.command.split_whitespace().collect()
+    } else {
+        std::iter::once(config.command.trim())
+            .chain(config.args.iter().map(String::as_str))
+            .collect()
+    };
+
+    blocked_in(&words, blocklist)
+}
+
+fn blocked_in(mut words: &[&str], blocklist: &[String]) -> Option<String> {
+    while let Some((&program, rest)) = words.split_first() {
+        let name = Path::new(program).file_name()?.to_str()?.to_lowercase();
+        let name = name.strip_suffix(".exe").unwrap_or(&name);
+
+        if blocklist
+            .iter()
+            .any(|blocked| blocked.eq_ignore_ascii_case(name))
+        {
+            return Some(program.to_string());
+        }
+        if SHELLS.contains(&name) {
+            let script = rest.iter().position(|arg| is_script_flag(arg))?;
+            return blocked_in_script(&rest[script + 1..].join(" "), blocklist);
+        }
+        if !WRAPPERS.contains(&name) {
+            return None;
+        }
+
+        // Skip the wrapper's options, variable assignments and durations
+        let skip = rest
+            .iter()
+            .take_while(|arg| {
+                arg.starts_with('-')
+                    || arg.contains('=')
+                    || arg.starts_with(|c: char| c.is_ascii_digit())
+            })
+            .count();
+        words = &rest[skip..];
+    }
+
+    None
+}
+
+/// Checks the first word of every statement in a shell script.
+fn blocked_in_script(script: &str, blocklist: &[String]) -> Option<String> {
+    script
+        .split([';', '&', '|', '\n', '(', ')', '`'])
+        .find_map(|statement| {
+            let words: Vec<&str> = statement
+                .split_whitespace()
+                .map(|word| word.trim_matches(['"', '\'', '{', '}']))
+                .filter(|word| !word.is_empty())
+                .collect();
+            blocked_in(&words, blocklist)
+        })
+}
+
+/// Whether a shell argument means the next one is a script: `-c`, `-lc`, `/c`.
+fn is_script_flag(arg: &str) -> bool {
+    match arg.strip_prefix('-') {
+        Some(flags) => flags.contains('c') && flags.chars().all(|c| c.is_ascii_alphabetic()),
+        None => arg.eq_ignore_ascii_case("/c") || arg.eq_ignore_ascii_case("/k"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(name: &str, command: &str, args: &[&str]) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            restart_limit: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_process_names() {
+        for name in ["my-app", "backend_service", "api.server", "worker123"] {
+            assert!(is_valid_process_name(name), "rejected {}", name);
+        }
+
+        let too_long = "a".repeat(MAX_PROCESS_NAME_LENGTH + 1);
+        for name in [
+            "",
+            too_long.as_str(),
+            "my app",
+            "api; rm -rf /",
+            "../../../etc/passwd",
+            "..",
+            "process\0name",
+            "process\nname",
+            "café",
+        ] {
+            assert!(!is_valid_process_name(name), "accepted {:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_tags_follow_process_name_rules() {
+        let settings = GlobalSettings::default();
+
+        let mut config = process("api", "echo", &[]);
+        config.tags = vec!["backend".to_string(), "tier.1_a-b".to_string()];
+        assert!(check_process(&config, &settings).is_empty());
+
+        config.tags = vec!["backend".to_string(), "my tag".to_string(), String::new()];
+        let problems = check_process(&config, &settings);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].to_string().contains("Invalid tag 'my tag'"));
+    }
+
+    #[test]
+    fn test_invalid_schedule_is_quoted() {
+        let settings = GlobalSettings::default();
+
+        let mut config = process("sync", "echo", &[]);
+        config.schedule = Some("0 2 * * *".to_string());
+        assert!(check_process(&config, &settings).is_empty());
+
+        config.schedule = Some("0 25 * * *".to_string());
+        let problems = check_process(&config, &settings);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0]
+            .to_string()
+            .starts_with("Invalid schedule '0 25 * * *': "));
+    }
+
+    #[test]
+    fn test_invalid_health_checks() {
+        let settings = GlobalSettings::default();
+        let problems_with = |check: HealthCheckKind, timeout_ms: u64| {
+            let mut config = process("api", "echo", &[]);
+            config.health_check = Some(HealthCheck {
+                check,
+                interval_ms: None,
+                timeout_ms,
+                retries: 3,
+            });
+            check_process(&config, &settings)
+        };
+        let http = |url: &str, method: &str| HealthCheckKind::Http {
+            url: url.to_string(),
+            method: method.to_string(),
+            expected_status: None,
+        };
+
+        assert!(problems_with(http("http://localhost:8080/health", "get"), 1000).is_empty());
+        assert_eq!(
+            problems_with(http("localhost:8080/health", "GET"), 1000).len(),
+            1
+        );
+        assert_eq!(
+            problems_with(http("http://localhost:8080/health", "GET /"), 1000).len(),
+            1
+        );
+        assert_eq!(
+            problems_with(http("http://localhost:8080/health", "GET"), 0).len(),
+            1
+        );
+        assert_eq!(
+            problems_with(
+                HealthCheckKind::Tcp {
+                    host: "127.0.0.1".to_string(),
+                    port: 0,
+                },
+                1000
+            )
+            .len(),
+            1
+        );
+        assert_eq!(
+            problems_with(
+                HealthCheckKind::Command {
+                    command: "".to_string(),
+                    args: vec![],
+                },
+                1000
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_invalid_readiness_probes() {
+        let settings = GlobalSettings::default();
+        let problems_with = |check: ReadinessCheck| {
+            let mut config = process("api", "echo", &[]);
+            config.readiness = Some(ReadinessProbe {
+                check,
+                interval_ms: 500,
+                timeout_ms: 1000,
+                max_wait_ms: 30000,
+            });
+            check_process(&config, &settings)
+        };
+
+        assert!(problems_with(ReadinessCheck::Tcp {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+        })
+        .is_empty());
+        assert_eq!(
+            problems_with(ReadinessCheck::Tcp {
+                host: "127.0.0.1".to_string(),
+                port: 0,
+            })
+            .len(),
+            1
+        );
+        assert_eq!(
+            problems_with(ReadinessCheck::Http {
+                url: "localhost:8080/health".to_string(),
+                expected_status: 200,
+            })
+            .len(),
+            1
+        );
+        assert_eq!(
+            problems_with(ReadinessCheck::Command {
+                command: " ".to_string(),
+                args: vec![],
+            })
+            .len(),
+            1
+        );
+
+        let problems = problems_with(ReadinessCheck::Log {
+            pattern: "ready (".to_string(),
+        });
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0]
+            .to_string()
+            .contains("Invalid readiness pattern 'ready ('"));
+    }
+
+    #[test]
+    fn test_control_bytes_in_command_or_args() {
+        let settings = GlobalSettings::default();
+
+        assert!(validate_process(&process("api", "echo", &["ok"]), &settings).is_ok());
+        assert!(validate_process(&process("api", "echo\0", &[]), &settings).is_err());
+        assert!(validate_process(&process("api", "echo", &["a\nb"]), &settings).is_err());
+    }
+
+    #[test]
+    fn test_blocked_commands() {
+        let settings = GlobalSettings::default();
+
+        for (command, args) in [
+            ("sudo", &["npm", "start"][..]),
+            ("/usr/bin/sudo", &["npm"][..]),
+            ("sudo npm start", &[][..]),
+            ("SHUTDOWN.EXE", &["/s"][..]),
+            ("su", &[][..]),
+        ] {
+            let problems = check_process(&process("api", command, args), &settings);
+            assert_eq!(problems.len(), 1, "allowed {}", command);
+        }
+
+        // Only the program itself is checked
+        assert!(validate_process(&process("api", "sudoku", &[]), &settings).is_ok());
+        assert!(validate_process(&process("api", "echo", &["sudo"]), &settings).is_ok());
+    }
+
+    #[test]
+    fn test_blocked_commands_behind_wrappers() {
+        let settings = GlobalSettings::default();
+
+        for (command, args) in [
+            ("sh", &["-c", "sudo id"][..]),
+            ("bash -c sudo", &[][..]),
+            ("sh -c \"sudo id\"", &[][..]),
+            ("bash", &["-lc", "cd /tmp && sudo id"][..]),
+            ("sh", &["-c", "echo $(su root)"][..]),
+            ("/usr/bin/env", &["-i", "HOME=/root", "sudo", "id"][..]),
+            ("env FOO=1 sudo id", &[][..]),
+            ("nice", &["-n", "10", "sudo", "id"][..]),
+            ("nohup", &["timeout", "5", "sh", "-c", "sudo id"][..]),
+            ("cmd.exe", &["/c", "shutdown /s"][..]),
+        ] {
+            let problems = check_process(&process("api", command, args), &settings);
+            assert_eq!(problems.len(), 1, "allowed {} {:?}", command, args);
+        }
+
+        for (command, args) in [
+            ("sh", &["-c", "echo sudo"][..]),
+            ("sh", &["deploy.sh"][..]),
+            ("env", &["FOO=1", "sudoku"][..]),
+            ("nice", &["-n", "10", "npm", "start"][..]),
+        ] {
+            let config = process("api", command, args);
+            assert!(
+                validate_process(&config, &settings).is_ok(),
+                "blocked {}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_allow_privileged_commands() {
+        let settings = GlobalSettings {
+            allow_privileged_commands: true,
+            ..GlobalSettings::default()
+        };
+
+        assert!(validate_process(&process("api", "sudo", &["npm", "start"]), &settings).is_ok());
+    }
+
+    #[test]
+    fn test_any_path_allowed_without_roots() {
+        let path = Path::new("does/not/exist");
+        assert_eq!(resolve_allowed_path(path, &[]).unwrap(), path);
+    }
+
+    #[test]
+    fn test_paths_outside_roots_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        let roots = vec![root.clone()];
+
+        let resolved = resolve_allowed_path(&root.join("app"), &roots).unwrap();
+        assert_eq!(resolved, root.join("app").canonicalize().unwrap());
+
+        assert!(matches!(
+            resolve_allowed_path(&outside, &roots),
+            Err(SentinelError::PathNotAllowed { .. })
+        ));
+        assert!(matches!(
+            resolve_allowed_path(&root.join("app/../../outside"), &roots),
+            Err(SentinelError::PathNotAllowed { .. })
+        ));
+        // A sibling sharing the root's name as a prefix is not inside it
+        std::fs::create_dir_all(dir.path().join("root-other")).unwrap();
+        assert!(resolve_allowed_path(&dir.path().join("root-other"), &roots).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_cannot_escape_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(dir.path().join("secret.log"), "secret").unwrap();
+        std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret.log"), root.join("app.log")).unwrap();
+        let roots = vec![root.clone()];
+
+        for path in [root.join("escape"), root.join("app.log")] {
+            match resolve_allowed_path(&path, &roots) {
+                Err(SentinelError::PathNotAllowed { path, allowed }) => {
+                    assert!(!path.starts_with(root.canonicalize().unwrap()));
+                    assert_eq!(allowed, roots);
+                }
+                other => panic!("{:?} was not rejected: {:?}", path, other),
+            }
+        }
+
+        // A root that is itself a symlink still contains its target's contents
+        let link_root = dir.path().join("link-root");
+        std::os::unix::fs::symlink(&root, &link_root).unwrap();
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        assert!(resolve_allowed_path(&root.join("app"), &[link_root]).is_ok());
+    }
+
+    #[test]
+    fn test_priority_and_affinity() {
+        let settings = GlobalSettings::default();
+        let mut config = process("build", "cargo", &["build"]);
+        config.nice = Some(10);
+        assert!(validate_process(&config, &settings).is_ok());
+
+        config.nice = Some(42);
+        config.cpu_affinity = Some(vec![scheduling::core_count()]);
+        let problems = check_process(&config, &settings);
+        assert!(matches!(
+            problems[..],
+            [
+                SentinelError::InvalidPriority { nice: 42 },
+                SentinelError::InvalidCpuAffinity { .. }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_invalid_restart_policies() {
+        let settings = GlobalSettings::default();
+        let mut config = process("api", "node", &["server.js"]);
+        config.restart_policy = Some(RestartPolicy {
+            max_memory_bytes: Some(512 * 1024 * 1024),
+            max_uptime_hours: Some(8),
+            cron: Some("0 14 * * *".to_string()),
+        });
+        assert!(check_process(&config, &settings).is_empty());
+
+        config.restart_policy = Some(RestartPolicy {
+            max_memory_bytes: Some(0),
+            max_uptime_hours: Some(0),
+            cron: Some("0 25 * * *".to_string()),
+        });
+        config.schedule = Some("@daily".to_string());
+        let messages: Vec<String> = check_process(&config, &settings)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert_eq!(messages.len(), 4);
+        assert!(messages[0].contains("maxMemoryBytes 0"));
+        assert!(messages[1].contains("maxUptimeHours 0"));
+        assert!(messages[2].contains("has a schedule"));
+        assert!(messages[3].starts_with("Invalid schedule '0 25 * * *'"));
+    }
+
+    #[test]
+    fn test_restart_policy_warnings() {
+        let settings = GlobalSettings::default();
+        let mut config = process("api", "node", &["server.js"]);
+        config.auto_restart = Some(true);
+        assert!(process_warnings(&config, &settings).is_empty());
+
+        config.restart_policy = Some(RestartPolicy::default());
+        let warnings = process_warnings(&config, &settings);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("no settings"));
+
+        config.auto_restart = Some(false);
+        config.max_runtime_ms = Some(60_000);
+        config.restart_policy = Some(RestartPolicy {
+            max_uptime_hours: Some(1),
+            cron: Some("0 14 * * *".to_string()),
+            ..Default::default()
+        });
+        let warnings = process_warnings(&config, &settings);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("autoRestart is off"));
+        assert!(warnings[1].contains("maxRuntimeMs"));
+    }
+
+    #[test]
+    fn test_every_problem_is_reported() {
+        let problems = check_process(
+            &process("api; rm -rf /", "sudo", &["x\ny"]),
+            &GlobalSettings::default(),
+        );
+        assert_eq!(problems.len(), 3);
+    }
+}
@@ -0,0 +1,1103 @@
+//! Moving a whole Sentinel setup between machines.
+//!
+//! A workspace archive is a gzipped tar holding `manifest.json`, the config
+//! file and every file it includes under `config/`, the runtime state as
+//! `state.json` and saved terminal configs as `pty.json`. Alert rules and
+//! notification sinks live in the config file, so they travel with it;
+//! service detection patterns are built in and need no copying.
+//!
+//! Secret values in the runtime state are masked on export. Config files are
+//! copied as written, since the processes need their real environment.
+
+use crate::core::{secrets, ConfigManager, PtyProcessConfig, StateManager};
+use crate::error::{Result, SentinelError};
+use crate::models::{Config, GlobalSettings, RuntimeState};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Version of the archive layout written by this build.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const STATE_ENTRY: &str = "state.json";
+const PTY_ENTRY: &str = "pty.json";
+const CONFIG_PREFIX: &str = "config/";
+
+/// Largest file accepted from an archive.
+const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Largest total size an archive may unpack to.
+const MAX_ARCHIVE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Where the files of a workspace live on this machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePaths {
+    /// The root config file; included files are resolved next to it.
+    pub config_path: PathBuf,
+    /// The runtime state file.
+    pub state_path: PathBuf,
+}
+
+impl WorkspacePaths {
+    /// Paths for the config file at `config_path` and the default state file.
+    pub fn new(config_path: impl Into<PathBuf>) -> Self {
+        Self {
+            config_path: config_path.into(),
+            state_path: StateManager::get_state_path(),
+        }
+    }
+
+    fn config_dir(&self) -> &Path {
+        self.config_path.parent().unwrap_or(Path::new(""))
+    }
+}
+
+/// Describes a workspace archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceManifest {
+    /// Archive layout version, see [`FORMAT_VERSION`].
+    pub format_version: u32,
+    /// Version of Sentinel that wrote the archive.
+    pub sentinel_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Home directory on the machine the archive was written on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_dir: Option<PathBuf>,
+    /// Config files relative to the root config's directory, root first.
+    pub config_files: Vec<String>,
+}
+
+/// A config file inside a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceFile {
+    /// Path relative to the root config's directory, with `/` separators.
+    pub path: String,
+    pub contents: String,
+}
+
+/// Everything a workspace archive holds.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub manifest: WorkspaceManifest,
+    /// Config files, root first.
+    pub config_files: Vec<WorkspaceFile>,
+    pub state: RuntimeState,
+    pub pty_configs: Vec<PtyProcessConfig>,
+}
+
+/// Whether an import adds to the current setup or replaces it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Imported processes, alert rules and notification sinks are added,
+    /// replacing ones with the same name; everything else is kept.
+    #[default]
+    Merge,
+    /// The config files and runtime state are replaced.
+    Replace,
+}
+
+/// A path in an imported config that points into the old home directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomePathReference {
+    /// Config file, or the terminal config, the path is in.
+    pub file: String,
+    /// Line of the config file (1-based).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The line or value holding the path.
+    pub text: String,
+}
+
+/// Result of writing a workspace archive.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceExportReport {
+    pub path: PathBuf,
+    pub manifest: WorkspaceManifest,
+    pub state_entries: usize,
+    pub pty_configs: usize,
+    /// Files that were left out, and why.
+    pub warnings: Vec<String>,
+}
+
+/// What importing a workspace would change.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacePreview {
+    pub manifest: WorkspaceManifest,
+    pub mode: ImportMode,
+    /// Processes in the imported config.
+    pub processes: Vec<String>,
+    /// Imported processes with the same name as a configured one.
+    pub conflicts: Vec<String>,
+    /// Paths into the old home directory, which can be rewritten.
+    pub home_paths: Vec<HomePathReference>,
+    /// The home directory they would be rewritten to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_home_dir: Option<PathBuf>,
+    pub state_entries: usize,
+    pub pty_configs: usize,
+}
+
+/// Result of importing a workspace.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceImportReport {
+    pub mode: ImportMode,
+    pub files_written: Vec<PathBuf>,
+    /// Copy of the previous root config, if there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+    /// Processes in the imported config.
+    pub processes: Vec<String>,
+    /// Configured processes replaced by imported ones.
+    pub replaced: Vec<String>,
+    /// Runtime state entries added.
+    pub state_entries: usize,
+}
+
+/// Writes the workspace at `paths`, plus `pty_configs`, to the archive `output`.
+///
+/// # Errors
+/// Returns `FileIoError` if a file can't be read or the archive can't be written.
+pub fn export(
+    paths: &WorkspacePaths,
+    pty_configs: Vec<PtyProcessConfig>,
+    output: &Path,
+) -> Result<WorkspaceExportReport> {
+    let mut warnings = Vec::new();
+    let config_files = if paths.config_path.exists() {
+        collect_config_files(&paths.config_path, &mut warnings)?
+    } else {
+        warnings.push(format!("No config file at {}", paths.config_path.display()));
+        Vec::new()
+    };
+
+    let patterns = ConfigManager::load_from_file(&paths.config_path)
+        .map(|config| config.settings.secret_patterns)
+        .unwrap_or_else(|_| GlobalSettings::default().secret_patterns);
+    let mut state = StateManager::load_from(&paths.state_path)?;
+    for info in state.processes.values_mut() {
+        info.argv = secrets::mask_argv(&info.argv, &patterns);
+    }
+
+    let workspace = Workspace {
+        manifest: WorkspaceManifest {
+            format_version: FORMAT_VERSION,
+            sentinel_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            home_dir: dirs::home_dir(),
+            config_files: config_files.iter().map(|file| file.path.clone()).collect(),
+        },
+        config_files,
+        state,
+        pty_configs,
+    };
+    workspace.write(output)?;
+
+    Ok(WorkspaceExportReport {
+        path: output.to_path_buf(),
+        manifest: workspace.manifest,
+        state_entries: workspace.state.processes.len(),
+        pty_configs: workspace.pty_configs.len(),
+        warnings,
+    })
+}
+
+/// Reads the config at `root` and every file it includes, recursively.
+///
+/// Included files outside the root's directory are left out with a warning,
+/// as they couldn't be put back in the same place relative to it.
+fn collect_config_files(root: &Path, warnings: &mut Vec<String>) -> Result<Vec<WorkspaceFile>> {
+    let base_dir = root.parent().unwrap_or(Path::new(""));
+    let root_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "sentinel.yaml".to_string());
+
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let canonical = path
+            .canonicalize()
+            .map_err(|source| SentinelError::FileIoError {
+                path: path.clone(),
+                source,
+            })?;
+        if !seen.insert(canonical) {
+            continue;
+        }
+
+        let relative = if path == root {
+            root_name.clone()
+        } else {
+            match path
+                .strip_prefix(base_dir)
+                .ok()
+                .and_then(|rel| normalize_entry(&rel.to_string_lossy()))
+            {
+                Some(relative) => relative,
+                None => {
+                    warnings.push(format!(
+                        "{} is outside {} and was left out",
+                        path.display(),
+                        base_dir.display()
+                    ));
+                    continue;
+                }
+            }
+        };
+
+        let contents = fs::read_to_string(&path).map_err(|source| SentinelError::FileIoError {
+            path: path.clone(),
+            source,
+        })?;
+        let interpolated =
+            ConfigManager::interpolate_env_vars(&contents).unwrap_or_else(|_| contents.clone());
+        if let Ok(document) = serde_yaml::from_str::<Value>(&interpolated) {
+            let file_dir = path.parent().unwrap_or(Path::new(""));
+            let patterns = document
+                .get("include")
+                .and_then(Value::as_sequence)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str);
+            for pattern in patterns {
+                // Reversed so the stack yields them in order
+                let mut matched = ConfigManager::expand_include(file_dir, pattern);
+                matched.reverse();
+                pending.extend(matched);
+            }
+        }
+
+        files.push(WorkspaceFile {
+            path: relative,
+            contents,
+        });
+    }
+
+    Ok(files)
+}
+
+/// `path` with `/` separators if it stays inside its base directory.
+fn normalize_entry(path: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+impl Workspace {
+    /// Reads a workspace archive.
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if the archive is damaged, was written by a
+    /// newer Sentinel, or has files that would land outside the config
+    /// directory.
+    pub fn read(archive: &Path) -> Result<Self> {
+        let file = fs::File::open(archive).map_err(|source| SentinelError::FileIoError {
+            path: archive.to_path_buf(),
+            source,
+        })?;
+        let mut entries =
+            tar::read(GzDecoder::new(file)).map_err(|reason| SentinelError::InvalidInput {
+                message: format!(
+                    "{} is not a workspace archive: {}",
+                    archive.display(),
+                    reason
+                ),
+            })?;
+        let invalid = |message: String| SentinelError::InvalidInput { message };
+
+        let manifest: WorkspaceManifest = entries
+            .remove(MANIFEST_ENTRY)
+            .ok_or_else(|| invalid(format!("{} has no {}", archive.display(), MANIFEST_ENTRY)))
+            .and_then(|bytes| {
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| invalid(format!("Invalid {}: {}", MANIFEST_ENTRY, e)))
+            })?;
+        if manifest.format_version == 0 || manifest.format_version > FORMAT_VERSION {
+            return Err(invalid(format!(
+                "{} was exported by Sentinel {} in workspace format {}; this version reads format {}",
+                archive.display(),
+                manifest.sentinel_version,
+                manifest.format_version,
+                FORMAT_VERSION
+            )));
+        }
+
+        let mut config_files = Vec::new();
+        for path in &manifest.config_files {
+            let normalized = normalize_entry(path)
+                .filter(|normalized| normalized == path)
+                .ok_or_else(|| invalid(format!("Unsafe config file path '{}'", path)))?;
+            let bytes = entries
+                .remove(&format!("{}{}", CONFIG_PREFIX, normalized))
+                .ok_or_else(|| invalid(format!("Config file '{}' is missing", path)))?;
+            let contents = String::from_utf8(bytes)
+                .map_err(|_| invalid(format!("Config file '{}' is not UTF-8", path)))?;
+            config_files.push(WorkspaceFile {
+                path: normalized,
+                contents,
+            });
+        }
+
+        let state = match entries.remove(STATE_ENTRY) {
+            Some(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| invalid(format!("Invalid {}: {}", STATE_ENTRY, e)))?,
+            None => RuntimeState::new(),
+        };
+        let pty_configs = match entries.remove(PTY_ENTRY) {
+            Some(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| invalid(format!("Invalid {}: {}", PTY_ENTRY, e)))?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            manifest,
+            config_files,
+            state,
+            pty_configs,
+        })
+    }
+
+    /// Writes the workspace as a gzipped tar archive.
+    fn write(&self, output: &Path) -> Result<()> {
+        let io_error = |source| SentinelError::FileIoError {
+            path: output.to_path_buf(),
+            source,
+        };
+        let mut entries: Vec<(String, Vec<u8>)> =
+            vec![(MANIFEST_ENTRY.to_string(), json(&self.manifest)?)];
+        for file in &self.config_files {
+            entries.push((
+                format!("{}{}", CONFIG_PREFIX, file.path),
+                file.contents.clone().into_bytes(),
+            ));
+        }
+        entries.push((STATE_ENTRY.to_string(), json(&self.state)?));
+        entries.push((PTY_ENTRY.to_string(), json(&self.pty_configs)?));
+
+        if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let file = fs::File::create(output).map_err(io_error)?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        tar::write(&mut encoder, &entries, self.manifest.created_at.timestamp())
+            .map_err(io_error)?;
+        encoder.finish().map_err(io_error)?;
+        Ok(())
+    }
+
+    /// Shows what [`Workspace::apply`] would do with `mode`.
+    ///
+    /// # Errors
+    /// Returns the validation error if the imported config is invalid.
+    pub fn preview(&self, paths: &WorkspacePaths, mode: ImportMode) -> Result<WorkspacePreview> {
+        let processes = process_names(self.load_config()?.as_ref());
+        let current = current_config(paths)?;
+        let configured = process_names(current.as_ref());
+        let new_home_dir = dirs::home_dir();
+
+        Ok(WorkspacePreview {
+            manifest: self.manifest.clone(),
+            mode,
+            conflicts: processes
+                .iter()
+                .filter(|name| configured.contains(name))
+                .cloned()
+                .collect(),
+            processes,
+            home_paths: new_home_dir
+                .as_deref()
+                .map(|home| self.home_paths(home))
+                .unwrap_or_default(),
+            new_home_dir,
+            state_entries: self.state.processes.len(),
+            pty_configs: self.pty_configs.len(),
+        })
+    }
+
+    /// Paths in the config files and terminal configs that point into the
+    /// old home directory, when it differs from `new_home`.
+    pub fn home_paths(&self, new_home: &Path) -> Vec<HomePathReference> {
+        let Some(old_home) = self.old_home(new_home) else {
+            return Vec::new();
+        };
+
+        let mut references = Vec::new();
+        for file in &self.config_files {
+            for (index, line) in file.contents.lines().enumerate() {
+                if replace_home(line, &old_home, "").1 > 0 {
+                    references.push(HomePathReference {
+                        file: file.path.clone(),
+                        line: Some(index + 1),
+                        text: line.trim().to_string(),
+                    });
+                }
+            }
+        }
+        for config in &self.pty_configs {
+            if let Some(cwd) = &config.cwd {
+                if replace_home(cwd, &old_home, "").1 > 0 {
+                    references.push(HomePathReference {
+                        file: format!("terminal '{}'", config.process_id),
+                        line: None,
+                        text: cwd.clone(),
+                    });
+                }
+            }
+        }
+        references
+    }
+
+    /// Rewrites paths into the old home directory to point into `new_home`;
+    /// returns how many were rewritten.
+    pub fn rewrite_home(&mut self, new_home: &Path) -> usize {
+        let Some(old_home) = self.old_home(new_home) else {
+            return 0;
+        };
+        let new_home = new_home.to_string_lossy();
+
+        let mut rewritten = 0;
+        for file in &mut self.config_files {
+            let (contents, count) = replace_home(&file.contents, &old_home, &new_home);
+            file.contents = contents;
+            rewritten += count;
+        }
+        for config in &mut self.pty_configs {
+            if let Some(cwd) = &mut config.cwd {
+                let (replaced, count) = replace_home(cwd, &old_home, &new_home);
+                *cwd = replaced;
+                rewritten += count;
+            }
+        }
+        rewritten
+    }
+
+    fn old_home(&self, new_home: &Path) -> Option<String> {
+        self.manifest
+            .home_dir
+            .as_deref()
+            .filter(|old| *old != new_home && old.parent().is_some())
+            .map(|old| old.to_string_lossy().into_owned())
+    }
+
+    /// Writes the imported config files and runtime state to `paths`.
+    ///
+    /// The imported config is validated before anything is written, and the
+    /// previous root config is copied to `<name>.bak`. Merging goes through
+    /// the parsed config, so the merged file loses comments and `${VAR}`
+    /// references; it can't merge into a config that includes other files.
+    /// Imported state entries never carry a PID, since PIDs from another
+    /// machine mean nothing here.
+    ///
+    /// # Errors
+    /// Returns the validation error of an invalid config, `InvalidInput` when
+    /// merging into a config with includes, or `FileIoError` if a file can't
+    /// be written.
+    pub fn apply(&self, paths: &WorkspacePaths, mode: ImportMode) -> Result<WorkspaceImportReport> {
+        let imported = self.load_config()?;
+        let current = match mode {
+            ImportMode::Merge => current_config(paths)?,
+            ImportMode::Replace => None,
+        };
+        let mut report = WorkspaceImportReport {
+            mode,
+            processes: process_names(imported.as_ref()),
+            ..Default::default()
+        };
+
+        if let Some(imported) = imported {
+            if let Some(current) = &current {
+                if !current.include.is_empty() {
+                    return Err(SentinelError::InvalidInput {
+                        message: format!(
+                            "{} includes other config files, so it can't be merged into; import with replace instead",
+                            paths.config_path.display()
+                        ),
+                    });
+                }
+            }
+            report.backup = backup(&paths.config_path)?;
+
+            match current {
+                Some(current) => {
+                    let (merged, replaced) = merge_configs(current, imported);
+                    ConfigManager::save_to_file(&merged, &paths.config_path)?;
+                    report.replaced = replaced;
+                    report.files_written.push(paths.config_path.clone());
+                }
+                None => {
+                    for (index, file) in self.config_files.iter().enumerate() {
+                        let target = if index == 0 {
+                            paths.config_path.clone()
+                        } else {
+                            paths.config_dir().join(&file.path)
+                        };
+                        write_file(&target, &file.contents)?;
+                        report.files_written.push(target);
+                    }
+                }
+            }
+        }
+
+        let mut state = match mode {
+            ImportMode::Merge => StateManager::load_from(&paths.state_path)?,
+            ImportMode::Replace => RuntimeState::new(),
+        };
+        for (name, info) in &self.state.processes {
+            if state.processes.contains_key(name) {
+                continue;
+            }
+            let mut info = info.clone();
+            info.pid = None;
+            info.managed_by_sentinel = false;
+            state.processes.insert(name.clone(), info);
+            report.state_entries += 1;
+        }
+        state.mark_synced();
+        StateManager::save_to(&state, &paths.state_path)?;
+
+        Ok(report)
+    }
+
+    /// Loads the imported config, with its includes, as it would load here.
+    fn load_config(&self) -> Result<Option<Config>> {
+        let Some(root) = self.config_files.first() else {
+            return Ok(None);
+        };
+
+        let staging = Staging::new()?;
+        for file in &self.config_files {
+            write_file(&staging.0.join(&file.path), &file.contents)?;
+        }
+        ConfigManager::load_from_file(&staging.0.join(&root.path)).map(Some)
+    }
+}
+
+/// Temporary directory for loading an imported config, removed on drop.
+struct Staging(PathBuf);
+
+impl Staging {
+    fn new() -> Result<Self> {
+        let path =
+            std::env::temp_dir().join(format!("sentinel-workspace-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path).map_err(|source| SentinelError::FileIoError {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn current_config(paths: &WorkspacePaths) -> Result<Option<Config>> {
+    if paths.config_path.exists() {
+        ConfigManager::load_from_file(&paths.config_path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn process_names(config: Option<&Config>) -> Vec<String> {
+    config
+        .map(|config| config.processes.iter().map(|p| p.name.clone()).collect())
+        .unwrap_or_default()
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|source| SentinelError::FileIoError {
+            path: parent.to_path_buf(),
+            source,
+        })?;
+    }
+    fs::write(path, contents).map_err(|source| SentinelError::FileIoError {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Copies `path` to `<path>.bak` if it exists.
+fn backup(path: &Path) -> Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let backup = PathBuf::from(name);
+    fs::copy(path, &backup).map_err(|source| SentinelError::FileIoError {
+        path: backup.clone(),
+        source,
+    })?;
+    Ok(Some(backup))
+}
+
+/// Adds the processes, alert rules and notification sinks of `imported` to
+/// `current`, replacing those with the same name. Returns the merged config
+/// and the names of replaced processes.
+fn merge_configs(mut current: Config, imported: Config) -> (Config, Vec<String>) {
+    let mut replaced = Vec::new();
+    for process in imported.processes {
+        match current
+            .processes
+            .iter_mut()
+            .find(|p| p.name == process.name)
+        {
+            Some(existing) => {
+                replaced.push(process.name.clone());
+                *existing = process;
+            }
+            None => current.processes.push(process),
+        }
+    }
+    for rule in imported.alerts {
+        match current.alerts.iter_mut().find(|r| r.name == rule.name) {
+            Some(existing) => *existing = rule,
+            None => current.alerts.push(rule),
+        }
+    }
+    let sinks = &mut current.settings.notification_sinks;
+    for sink in imported.settings.notification_sinks {
+        match sinks.iter_mut().find(|s| s.name == sink.name) {
+            Some(existing) => *existing = sink,
+            None => sinks.push(sink),
+        }
+    }
+    for (key, value) in imported.global_env {
+        current.global_env.entry(key).or_insert(value);
+    }
+    (current, replaced)
+}
+
+/// Replaces `old_home` in `text` where it is a whole path component prefix,
+/// so `/home/al` doesn't match `/home/alice`. Returns the new text and the
+/// number of replacements.
+fn replace_home(text: &str, old_home: &str, new_home: &str) -> (String, usize) {
+    let mut result = String::with_capacity(text.len());
+    let mut count = 0;
+    let mut rest = text;
+    while let Some(index) = rest.find(old_home) {
+        let after = &rest[index + old_home.len()..];
+        let boundary = after
+            .chars()
+            .next()
+            .is_none_or(|c| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')));
+        result.push_str(&rest[..index]);
+        if boundary {
+            result.push_str(new_home);
+            count += 1;
+        } else {
+            result.push_str(old_home);
+        }
+        rest = after;
+    }
+    result.push_str(rest);
+    (result, count)
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    serde_json::to_vec_pretty(value)
+        .map_err(|e| SentinelError::Other(format!("Failed to serialize workspace: {}", e)))
+}
+
+/// Just enough of the ustar format for regular files.
+mod tar {
+    use super::{MAX_ARCHIVE_SIZE, MAX_ENTRY_SIZE};
+    use std::collections::HashMap;
+    use std::io::{self, Read, Write};
+
+    const BLOCK: usize = 512;
+
+    /// Writes `entries` as a tar stream with modification time `mtime`.
+    pub fn write(
+        out: &mut impl Write,
+        entries: &[(String, Vec<u8>)],
+        mtime: i64,
+    ) -> io::Result<()> {
+        for (name, data) in entries {
+            out.write_all(&header(name, data.len() as u64, mtime)?)?;
+            out.write_all(data)?;
+            let padding = (BLOCK - data.len() % BLOCK) % BLOCK;
+            out.write_all(&vec![0; padding])?;
+        }
+        out.write_all(&[0; BLOCK * 2])
+    }
+
+    fn header(name: &str, size: u64, mtime: i64) -> io::Result<[u8; BLOCK]> {
+        let (prefix, name) = split_name(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Path too long: {}", name),
+            )
+        })?;
+
+        let mut header = [0u8; BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        octal(&mut header[100..108], 0o644);
+        octal(&mut header[108..116], 0);
+        octal(&mut header[116..124], 0);
+        octal(&mut header[124..136], size);
+        octal(&mut header[136..148], mtime.max(0) as u64);
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+        octal(&mut header[148..155], u64::from(checksum));
+        Ok(header)
+    }
+
+    /// Splits a long name into ustar's prefix and name fields.
+    fn split_name(name: &str) -> Option<(&str, &str)> {
+        if name.len() <= 100 {
+            return Some(("", name));
+        }
+        name.match_indices('/')
+            .map(|(index, _)| (&name[..index], &name[index + 1..]))
+            .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100 && !name.is_empty())
+    }
+
+    /// Writes `value` as zero-padded octal, leaving the last byte NUL.
+    fn octal(field: &mut [u8], value: u64) {
+        let digits = format!("{:0width$o}", value, width = field.len() - 1);
+        field[..digits.len()].copy_from_slice(digits.as_bytes());
+    }
+
+    fn parse_octal(field: &[u8]) -> Option<u64> {
+        let text = std::str::from_utf8(field).ok()?;
+        let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+        if text.is_empty() {
+            return Some(0);
+        }
+        u64::from_str_radix(text, 8).ok()
+    }
+
+    fn field_str(field: &[u8]) -> String {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8_lossy(&field[..end]).into_owned()
+    }
+
+    /// Reads the regular files of a tar stream by name.
+    pub fn read(mut input: impl Read) -> Result<HashMap<String, Vec<u8>>, String> {
+        let mut entries = HashMap::new();
+        let mut total = 0u64;
+        let mut header = [0u8; BLOCK];
+        loop {
+            input
+                .read_exact(&mut header)
+                .map_err(|e| format!("truncated archive ({})", e))?;
+            if header.iter().all(|&b| b == 0) {
+                return Ok(entries);
+            }
+
+            let stored: u32 = parse_octal(&header[148..156]).ok_or("bad checksum field")? as u32;
+            let mut copy = header;
+            copy[148..156].fill(b' ');
+            if copy.iter().map(|&b| u32::from(b)).sum::<u32>() != stored {
+                return Err("checksum mismatch".to_string());
+            }
+
+            let size = parse_octal(&header[124..136]).ok_or("bad size field")?;
+            total += size;
+            if size > MAX_ENTRY_SIZE || total > MAX_ARCHIVE_SIZE {
+                return Err("archive is too large".to_string());
+            }
+            let mut data = vec![0; size as usize];
+            input
+                .read_exact(&mut data)
+                .map_err(|e| format!("truncated archive ({})", e))?;
+            let padding = (BLOCK - data.len() % BLOCK) % BLOCK;
+            io::copy(&mut (&mut input).take(padding as u64), &mut io::sink())
+                .map_err(|e| e.to_string())?;
+
+            if matches!(header[156], b'0' | 0) {
+                let prefix = field_str(&header[345..500]);
+                let name = field_str(&header[..100]);
+                let name = if prefix.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", prefix, name)
+                };
+                entries.insert(name, data);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EnvMode, ProcessRuntimeInfo};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn setup(dir: &Path) -> WorkspacePaths {
+        let config_dir = dir.join("sentinel");
+        fs::create_dir_all(config_dir.join("services")).unwrap();
+        fs::write(
+            config_dir.join("sentinel.yaml"),
+            r#"# Team setup
+include:
+  - services/*.yaml
+processes:
+  - name: web
+    command: npm run dev
+    cwd: /home/old/projects/web
+settings:
+  notificationSinks:
+    - name: hook
+      type: webhook
+      url: https://example.com/hook
+alerts:
+  - name: web-cpu
+    target: web
+    metric: cpu
+    threshold: 90
+"#,
+        )
+        .unwrap();
+        fs::write(
+            config_dir.join("services/api.yaml"),
+            "processes:\n  - name: api\n    command: cargo run\n    cwd: /home/older/api\n",
+        )
+        .unwrap();
+
+        let paths = WorkspacePaths {
+            config_path: config_dir.join("sentinel.yaml"),
+            state_path: config_dir.join(".sentinel-state.json"),
+        };
+        let mut state = RuntimeState::new();
+        state.upsert_process(
+            "web".to_string(),
+            ProcessRuntimeInfo::new(4242, "hash".to_string()).with_command(
+                None,
+                vec!["npm".to_string(), "--auth-token=abc123".to_string()],
+            ),
+        );
+        StateManager::save_to(&state, &paths.state_path).unwrap();
+        paths
+    }
+
+    fn pty_config() -> PtyProcessConfig {
+        PtyProcessConfig {
+            process_id: "shell".to_string(),
+            command: "bash".to_string(),
+            args: vec![],
+            cwd: Some("/home/old/projects".to_string()),
+            env: None,
+            env_mode: EnvMode::Inherit,
+        }
+    }
+
+    fn export_to(dir: &Path, paths: &WorkspacePaths, home: &str) -> PathBuf {
+        let archive = dir.join("workspace.tar.gz");
+        export(paths, vec![pty_config()], &archive).unwrap();
+
+        // Pretend it came from a machine with a different home directory
+        let mut workspace = Workspace::read(&archive).unwrap();
+        workspace.manifest.home_dir = Some(PathBuf::from(home));
+        workspace.write(&archive).unwrap();
+        archive
+    }
+
+    #[test]
+    fn test_round_trip_after_wiping_config_dir() {
+        let dir = TempDir::new().unwrap();
+        let paths = setup(dir.path());
+        let root_before = fs::read_to_string(&paths.config_path).unwrap();
+        let archive = export_to(dir.path(), &paths, "/home/old");
+
+        fs::remove_dir_all(paths.config_dir()).unwrap();
+
+        let workspace = Workspace::read(&archive).unwrap();
+        assert_eq!(workspace.manifest.format_version, FORMAT_VERSION);
+        assert_eq!(
+            workspace.manifest.config_files,
+            ["sentinel.yaml", "services/api.yaml"]
+        );
+        let preview = workspace.preview(&paths, ImportMode::Merge).unwrap();
+        assert_eq!(preview.processes, ["web", "api"]);
+        assert!(preview.conflicts.is_empty());
+        assert_eq!(preview.pty_configs, 1);
+
+        let report = workspace.apply(&paths, ImportMode::Merge).unwrap();
+        assert_eq!(report.files_written.len(), 2);
+        assert_eq!(report.backup, None);
+        assert_eq!(report.state_entries, 1);
+
+        // Files come back byte for byte, comments included
+        assert_eq!(fs::read_to_string(&paths.config_path).unwrap(), root_before);
+        let config = ConfigManager::load_from_file(&paths.config_path).unwrap();
+        assert_eq!(process_names(Some(&config)), ["web", "api"]);
+        assert_eq!(config.alerts.len(), 1);
+        assert_eq!(config.settings.notification_sinks.len(), 1);
+
+        let state = StateManager::load_from(&paths.state_path).unwrap();
+        let web = &state.processes["web"];
+        assert_eq!(web.pid, None);
+        assert_eq!(web.argv, ["npm", "--auth-token=••••"]);
+        assert_eq!(workspace.pty_configs[0].process_id, "shell");
+    }
+
+    #[test]
+    fn test_merge_reports_and_replaces_conflicts() {
+        let dir = TempDir::new().unwrap();
+        let paths = setup(dir.path());
+        let archive = export_to(dir.path(), &paths, "/home/old");
+
+        // The new machine already has its own setup
+        fs::remove_dir_all(paths.config_dir()).unwrap();
+        fs::create_dir_all(paths.config_dir()).unwrap();
+        fs::write(
+            &paths.config_path,
+            "processes:\n  - name: api\n    command: go run .\n  - name: db\n    command: postgres\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::read(&archive).unwrap();
+        let preview = workspace.preview(&paths, ImportMode::Merge).unwrap();
+        assert_eq!(preview.conflicts, ["api"]);
+
+        let report = workspace.apply(&paths, ImportMode::Merge).unwrap();
+        assert_eq!(report.replaced, ["api"]);
+        assert!(report.backup.as_ref().is_some_and(|backup| backup.exists()));
+
+        let config = ConfigManager::load_from_file(&paths.config_path).unwrap();
+        assert_eq!(process_names(Some(&config)), ["api", "db", "web"]);
+        assert_eq!(config.processes[0].command, "cargo run");
+        assert_eq!(config.alerts.len(), 1);
+    }
+
+    #[test]
+    fn test_replace_overwrites_config() {
+        let dir = TempDir::new().unwrap();
+        let paths = setup(dir.path());
+        let archive = export_to(dir.path(), &paths, "/home/old");
+        fs::write(
+            &paths.config_path,
+            "processes:\n  - name: db\n    command: postgres\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::read(&archive).unwrap();
+        workspace.apply(&paths, ImportMode::Replace).unwrap();
+        let config = ConfigManager::load_from_file(&paths.config_path).unwrap();
+        assert_eq!(process_names(Some(&config)), ["web", "api"]);
+    }
+
+    #[test]
+    fn test_home_paths_are_detected_and_rewritten() {
+        let dir = TempDir::new().unwrap();
+        let paths = setup(dir.path());
+        let archive = export_to(dir.path(), &paths, "/home/old");
+        let mut workspace = Workspace::read(&archive).unwrap();
+
+        let references = workspace.home_paths(Path::new("/Users/new"));
+        assert_eq!(
+            references,
+            [
+                HomePathReference {
+                    file: "sentinel.yaml".to_string(),
+                    line: Some(7),
+                    text: "cwd: /home/old/projects/web".to_string(),
+                },
+                HomePathReference {
+                    file: "terminal 'shell'".to_string(),
+                    line: None,
+                    text: "/home/old/projects".to_string(),
+                },
+            ]
+        );
+        // Nothing to rewrite on the same machine
+        assert!(workspace.home_paths(Path::new("/home/old")).is_empty());
+
+        assert_eq!(workspace.rewrite_home(Path::new("/Users/new")), 2);
+        assert!(workspace.config_files[0]
+            .contents
+            .contains("cwd: /Users/new/projects/web"));
+        // /home/older is another user's directory
+        assert!(workspace.config_files[1]
+            .contents
+            .contains("/home/older/api"));
+        assert_eq!(
+            workspace.pty_configs[0].cwd.as_deref(),
+            Some("/Users/new/projects")
+        );
+    }
+
+    #[test]
+    fn test_newer_format_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let paths = setup(dir.path());
+        let archive = export_to(dir.path(), &paths, "/home/old");
+        let mut workspace = Workspace::read(&archive).unwrap();
+        workspace.manifest.format_version = FORMAT_VERSION + 1;
+        workspace.write(&archive).unwrap();
+
+        let error = Workspace::read(&archive).unwrap_err();
+        assert!(matches!(error, SentinelError::InvalidInput { .. }));
+        assert!(error.to_string().contains("workspace format 2"));
+    }
+
+    #[test]
+    fn test_unsafe_config_paths_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let paths = setup(dir.path());
+        let archive = export_to(dir.path(), &paths, "/home/old");
+        let mut workspace = Workspace::read(&archive).unwrap();
+        workspace.config_files[1].path = "../../escape.yaml".to_string();
+        workspace.manifest.config_files[1] = "../../escape.yaml".to_string();
+        workspace.write(&archive).unwrap();
+
+        let error = Workspace::read(&archive).unwrap_err();
+        assert!(error.to_string().contains("Unsafe config file path"));
+    }
+
+    #[test]
+    fn test_damaged_archive_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join("broken.tar.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&archive).unwrap(), Compression::fast());
+        encoder.write_all(&[1; 700]).unwrap();
+        encoder.finish().unwrap();
+
+        assert!(matches!(
+            Workspace::read(&archive),
+            Err(SentinelError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tar_long_names() {
+        let long = format!("config/{}/file.yaml", "d".repeat(120));
+        let mut buffer = Vec::new();
+        tar::write(&mut buffer, &[(long.clone(), b"x: 1".to_vec())], 0).unwrap();
+        let entries = tar::read(buffer.as_slice()).unwrap();
+        assert_eq!(entries[&long], b"x: 1");
+    }
+}
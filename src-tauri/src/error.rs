@@ -3,8 +3,17 @@
 //! This module defines custom error types used throughout the application.
 //! All errors implement `std::error::Error` and can be converted to user-friendly
 //! messages for display in the UI.
+//!
+//! Errors serialize as `{code, message, params, details}`, so Tauri commands
+//! can return them directly and the frontend can react to the [`ErrorCode`]
+//! instead of parsing messages. `message` comes from the active locale's
+//! [message catalog](crate::core::messages); `Display` stays English for logs.
 
-use serde::Serialize;
+use crate::core::messages;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -13,7 +22,7 @@ use thiserror::Error;
 ///
 /// This enum covers all possible errors that can occur during process management,
 /// system monitoring, and configuration handling.
-#[derive(Debug, Error, Serialize)]
+#[derive(Debug, Error)]
 pub enum SentinelError {
     /// Process with the specified name was not found.
     #[error("Process '{name}' not found")]
@@ -24,7 +33,6 @@ pub enum SentinelError {
     SpawnFailed {
         name: String,
         #[source]
-        #[serde(skip)]
         source: io::Error,
     },
 
@@ -32,6 +40,42 @@ pub enum SentinelError {
     #[error("Process '{name}' is already running with PID {pid}")]
     ProcessAlreadyRunning { name: String, pid: u32 },
 
+    /// Process exists but is not running.
+    #[error("Process '{name}' is not running")]
+    ProcessNotRunning { name: String },
+
+    /// Process exited with an error within `startGraceMs` of being spawned.
+    #[error(
+        "Process '{name}' exited with code {exit_code} right after starting{}",
+        if stderr_excerpt.is_empty() { String::new() } else { format!(":\n{}", stderr_excerpt) }
+    )]
+    StartFailedEarly {
+        name: String,
+        exit_code: i32,
+        /// What the process wrote to stderr (or stdout, if nothing), shortened.
+        stderr_excerpt: String,
+    },
+
+    /// Process didn't pass its readiness probe in time.
+    #[error("Process '{name}' did not become ready within {waited_ms}ms")]
+    ReadinessTimeout { name: String, waited_ms: u64 },
+
+    /// A process others wait for didn't complete in time.
+    #[error("Process '{name}' did not complete within {waited_ms}ms")]
+    CompletionTimeout { name: String, waited_ms: u64 },
+
+    /// A process others wait for ended without completing.
+    #[error(
+        "Process '{name}' {}",
+        exit_code
+            .map(|code| format!("exited with code {} instead of completing", code))
+            .unwrap_or_else(|| "stopped before completing".to_string())
+    )]
+    NotCompleted {
+        name: String,
+        exit_code: Option<i32>,
+    },
+
     /// Process failed to stop within the timeout period.
     #[error("Process '{name}' failed to stop within {timeout_secs} seconds")]
     StopTimeout { name: String, timeout_secs: u64 },
@@ -45,11 +89,20 @@ pub enum SentinelError {
     ConfigNotFound { path: PathBuf },
 
     /// Failed to parse configuration file.
-    #[error("Failed to parse config file {}: {source}", path.display())]
+    #[error(
+        "Failed to parse config file {}: {source}{}",
+        path.display(),
+        snippet.as_deref().map(|s| format!("\n{}", s)).unwrap_or_default()
+    )]
     ConfigParseFailed {
         path: PathBuf,
+        /// Line of the error (1-based), if known.
+        line: Option<usize>,
+        /// Column of the error (1-based), if known.
+        column: Option<usize>,
+        /// Source lines leading up to the error.
+        snippet: Option<String>,
         #[source]
-        #[serde(skip)]
         source: serde_yaml::Error,
     },
 
@@ -58,7 +111,6 @@ pub enum SentinelError {
     FileIoError {
         path: PathBuf,
         #[source]
-        #[serde(skip)]
         source: io::Error,
     },
 
@@ -74,17 +126,91 @@ pub enum SentinelError {
     #[error("Process '{process}' depends on unknown process '{dependency}'")]
     UnknownDependency { process: String, dependency: String },
 
+    /// Config files include each other, directly or indirectly.
+    #[error(
+        "Config include cycle: {}",
+        chain.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(" -> ")
+    )]
+    IncludeCycle { chain: Vec<PathBuf> },
+
+    /// Dependency excluded by the selected profiles.
+    #[error(
+        "Process '{process}' depends on '{dependency}', which the selected profiles exclude; add profile {}",
+        profiles.iter().map(|p| format!("'{}'", p)).collect::<Vec<_>>().join(" or ")
+    )]
+    ExcludedDependency {
+        process: String,
+        dependency: String,
+        profiles: Vec<String>,
+    },
+
+    /// Dependency outside a tagged group that isn't running.
+    #[error(
+        "Process '{process}' depends on '{dependency}', which is not tagged '{tag}' and not running; start it first or tag it '{tag}'"
+    )]
+    DependencyNotRunning {
+        process: String,
+        dependency: String,
+        tag: String,
+    },
+
+    /// No process has the requested tag; `known` lists the tags in use.
+    #[error(
+        "No process is tagged '{tag}'{}",
+        if known.is_empty() { String::new() } else { format!("; known tags: {}", known.join(", ")) }
+    )]
+    UnknownTag { tag: String, known: Vec<String> },
+
+    /// Path lies outside `settings.allowedRoots`.
+    #[error(
+        "Path {} is outside the allowed roots: {}",
+        path.display(),
+        allowed.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    PathNotAllowed {
+        path: PathBuf,
+        allowed: Vec<PathBuf>,
+    },
+
+    /// Nice value outside the range the OS accepts.
+    #[error("Invalid priority {nice}: nice values range from -20 (highest) to 19 (lowest)")]
+    InvalidPriority { nice: i32 },
+
+    /// CPU affinity that can't be applied on this machine.
+    #[error("Invalid CPU affinity {cores:?}: {reason}")]
+    InvalidCpuAffinity { cores: Vec<usize>, reason: String },
+
+    /// Cron expression that can't be parsed.
+    #[error("Invalid schedule '{expression}': {reason}")]
+    InvalidSchedule { expression: String, reason: String },
+
+    /// Log search pattern that can't be used.
+    #[error("Invalid search pattern '{pattern}': {reason}")]
+    InvalidSearchPattern { pattern: String, reason: String },
+
+    /// The OS refused to change a process's priority or affinity.
+    #[error("Failed to change scheduling of PID {pid}: {source}")]
+    SchedulingFailed {
+        pid: u32,
+        #[source]
+        source: io::Error,
+    },
+
     /// Maximum restart limit exceeded.
     #[error("Process '{name}' exceeded restart limit of {limit} attempts")]
     RestartLimitExceeded { name: String, limit: u32 },
 
+    /// The process is only monitored, so Sentinel can't start it again.
+    #[error("Process '{name}' is only monitored and can't be restarted by Sentinel")]
+    RestartUnavailable { name: String },
+
+    /// A notification sink could not be reached.
+    #[error("Notification sink '{sink}' failed: {reason}")]
+    NotificationFailed { sink: String, reason: String },
+
     /// Generic I/O error.
     #[error("I/O error: {0}")]
-    Io(
-        #[from]
-        #[serde(skip)]
-        io::Error,
-    ),
+    Io(#[from] io::Error),
 
     /// Port discovery error.
     #[error("Port scanning failed: {0}")]
@@ -94,10 +220,64 @@ pub enum SentinelError {
     #[error("Port {0} not found")]
     PortNotFound(u16),
 
+    /// Two processes declare the same fixed port.
+    #[error("Port {port} of process '{process}' is also used by process '{other}'")]
+    PortConflict {
+        port: u16,
+        process: String,
+        other: String,
+    },
+
+    /// A port a process declares is already taken.
+    #[error("Port {port} of process '{process}' is already in use")]
+    PortInUse { port: u16, process: String },
+
+    /// A port to forward from is already taken, by the process `pid` if known.
+    #[error(
+        "Port {port} is already in use{}",
+        pid.map(|pid| format!(" by {} (PID {})", process.as_deref().unwrap_or("a process"), pid)).unwrap_or_default()
+    )]
+    ForwardPortInUse {
+        port: u16,
+        pid: Option<u32>,
+        process: Option<String>,
+    },
+
+    /// A kill confirmation token is unknown, already used, or past its
+    /// validity window.
+    #[error(
+        "{}",
+        if *expired {
+            "The kill confirmation expired; request it again"
+        } else {
+            "Unknown or already used kill confirmation"
+        }
+    )]
+    KillConfirmationInvalid { expired: bool },
+
+    /// A GeoIP database file isn't a readable MaxMind database.
+    #[error("Invalid GeoIP database {}: {reason}", path.display())]
+    InvalidGeoIpDatabase { path: PathBuf, reason: String },
+
     /// Docker error.
     #[error("Docker error: {0}")]
     DockerError(String),
 
+    /// The metrics history database can't be read or written.
+    #[error("Metrics history error: {0}")]
+    MetricsHistoryError(String),
+
+    /// Another Sentinel instance holds the instance lock.
+    #[error(
+        "Sentinel is already running{}",
+        pid.map(|pid| format!(" (PID {})", pid)).unwrap_or_default()
+    )]
+    AlreadyRunning { pid: Option<u32> },
+
+    /// Operation not available on this platform.
+    #[error("{operation} is not supported on this platform")]
+    Unsupported { operation: String },
+
     /// Invalid input provided.
     #[error("Invalid input: {message}")]
     InvalidInput { message: String },
@@ -107,6 +287,483 @@ pub enum SentinelError {
     Other(String),
 }
 
+/// Stable identifier of an error kind, shared by the app and the CLI.
+///
+/// Serialized in `SCREAMING_SNAKE_CASE`. Codes are part of the frontend and
+/// CLI contract: never rename one, only add new ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// No managed process has this name.
+    ProcessNotFound,
+    /// The process could not be spawned.
+    SpawnFailed,
+    /// The process is already running.
+    ProcessAlreadyRunning,
+    /// The process is not running.
+    ProcessNotRunning,
+    /// The process exited with an error right after starting.
+    StartFailedEarly,
+    /// The process did not pass its readiness probe in time.
+    ReadinessTimeout,
+    /// A process others wait for did not complete in time.
+    CompletionTimeout,
+    /// A process others wait for ended without completing.
+    NotCompleted,
+    /// The process did not stop in time.
+    StopTimeout,
+    /// The configuration is invalid.
+    InvalidConfig,
+    /// The configuration file does not exist.
+    ConfigNotFound,
+    /// The configuration file could not be parsed.
+    ConfigParseFailed,
+    /// A file could not be read or written.
+    FileIo,
+    /// System metrics could not be collected.
+    Monitoring,
+    /// Processes depend on each other in a cycle.
+    DependencyCycle,
+    /// A process depends on a process that doesn't exist.
+    UnknownDependency,
+    /// Config files include each other in a cycle.
+    IncludeCycle,
+    /// A dependency is excluded by the selected profiles.
+    ExcludedDependency,
+    /// A dependency outside a tagged group isn't running.
+    DependencyNotRunning,
+    /// No process has the tag.
+    UnknownTag,
+    /// A path lies outside `settings.allowedRoots`.
+    PathNotAllowed,
+    /// A nice value is out of range.
+    InvalidPriority,
+    /// A CPU affinity can't be applied.
+    InvalidCpuAffinity,
+    /// A cron expression can't be parsed.
+    InvalidSchedule,
+    /// A log search pattern can't be used.
+    InvalidSearchPattern,
+    /// The OS refused a priority or affinity change.
+    SchedulingFailed,
+    /// A process crashed more often than its restart limit allows.
+    RestartLimitExceeded,
+    /// An externally started process can't be restarted.
+    RestartUnavailable,
+    /// A notification sink could not be reached.
+    NotificationFailed,
+    /// Any other I/O failure.
+    Io,
+    /// Listening ports could not be scanned.
+    PortDiscovery,
+    /// Nothing listens on the port.
+    PortNotFound,
+    /// Two processes declare the same port.
+    PortConflict,
+    /// A declared port is already taken.
+    PortInUse,
+    /// A port to forward from is already taken.
+    ForwardPortInUse,
+    /// A kill confirmation is unknown, used or expired.
+    KillConfirmationInvalid,
+    /// A GeoIP database file can't be read.
+    InvalidGeoIpDatabase,
+    /// The Docker daemon returned an error or can't be reached.
+    Docker,
+    /// The metrics history database can't be read or written.
+    MetricsHistory,
+    /// Another Sentinel instance is running.
+    AlreadyRunning,
+    /// The operation isn't available on this platform.
+    Unsupported,
+    /// An argument is invalid.
+    InvalidInput,
+    /// Anything else.
+    Other,
+}
+
+impl SentinelError {
+    /// The stable code of this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SentinelError::ProcessNotFound { .. } => ErrorCode::ProcessNotFound,
+            SentinelError::SpawnFailed { .. } => ErrorCode::SpawnFailed,
+            SentinelError::ProcessAlreadyRunning { .. } => ErrorCode::ProcessAlreadyRunning,
+            SentinelError::ProcessNotRunning { .. } => ErrorCode::ProcessNotRunning,
+            SentinelError::StartFailedEarly { .. } => ErrorCode::StartFailedEarly,
+            SentinelError::ReadinessTimeout { .. } => ErrorCode::ReadinessTimeout,
+            SentinelError::CompletionTimeout { .. } => ErrorCode::CompletionTimeout,
+            SentinelError::NotCompleted { .. } => ErrorCode::NotCompleted,
+            SentinelError::StopTimeout { .. } => ErrorCode::StopTimeout,
+            SentinelError::InvalidConfig { .. } => ErrorCode::InvalidConfig,
+            SentinelError::ConfigNotFound { .. } => ErrorCode::ConfigNotFound,
+            SentinelError::ConfigParseFailed { .. } => ErrorCode::ConfigParseFailed,
+            SentinelError::FileIoError { .. } => ErrorCode::FileIo,
+            SentinelError::MonitoringError { .. } => ErrorCode::Monitoring,
+            SentinelError::DependencyCycle { .. } => ErrorCode::DependencyCycle,
+            SentinelError::UnknownDependency { .. } => ErrorCode::UnknownDependency,
+            SentinelError::IncludeCycle { .. } => ErrorCode::IncludeCycle,
+            SentinelError::ExcludedDependency { .. } => ErrorCode::ExcludedDependency,
+            SentinelError::DependencyNotRunning { .. } => ErrorCode::DependencyNotRunning,
+            SentinelError::UnknownTag { .. } => ErrorCode::UnknownTag,
+            SentinelError::PathNotAllowed { .. } => ErrorCode::PathNotAllowed,
+            SentinelError::InvalidPriority { .. } => ErrorCode::InvalidPriority,
+            SentinelError::InvalidCpuAffinity { .. } => ErrorCode::InvalidCpuAffinity,
+            SentinelError::InvalidSchedule { .. } => ErrorCode::InvalidSchedule,
+            SentinelError::InvalidSearchPattern { .. } => ErrorCode::InvalidSearchPattern,
+            SentinelError::SchedulingFailed { .. } => ErrorCode::SchedulingFailed,
+            SentinelError::RestartLimitExceeded { .. } => ErrorCode::RestartLimitExceeded,
+            SentinelError::RestartUnavailable { .. } => ErrorCode::RestartUnavailable,
+            SentinelError::NotificationFailed { .. } => ErrorCode::NotificationFailed,
+            SentinelError::Io(_) => ErrorCode::Io,
+            SentinelError::PortDiscoveryError(_) => ErrorCode::PortDiscovery,
+            SentinelError::PortNotFound(_) => ErrorCode::PortNotFound,
+            SentinelError::PortConflict { .. } => ErrorCode::PortConflict,
+            SentinelError::PortInUse { .. } => ErrorCode::PortInUse,
+            SentinelError::ForwardPortInUse { .. } => ErrorCode::ForwardPortInUse,
+            SentinelError::KillConfirmationInvalid { .. } => ErrorCode::KillConfirmationInvalid,
+            SentinelError::InvalidGeoIpDatabase { .. } => ErrorCode::InvalidGeoIpDatabase,
+            SentinelError::DockerError(_) => ErrorCode::Docker,
+            SentinelError::MetricsHistoryError(_) => ErrorCode::MetricsHistory,
+            SentinelError::AlreadyRunning { .. } => ErrorCode::AlreadyRunning,
+            SentinelError::Unsupported { .. } => ErrorCode::Unsupported,
+            SentinelError::InvalidInput { .. } => ErrorCode::InvalidInput,
+            SentinelError::Other(_) => ErrorCode::Other,
+        }
+    }
+
+    /// The values the message was built from, for recovery actions in the UI.
+    ///
+    /// `Null` when there's nothing beyond the message.
+    pub fn details(&self) -> Value {
+        match self {
+            SentinelError::ProcessNotFound { name }
+            | SentinelError::ProcessNotRunning { name }
+            | SentinelError::RestartUnavailable { name } => json!({ "name": name }),
+            SentinelError::SpawnFailed { name, source } => {
+                json!({ "name": name, "cause": source.to_string() })
+            }
+            SentinelError::ProcessAlreadyRunning { name, pid } => {
+                json!({ "name": name, "pid": pid })
+            }
+            SentinelError::StartFailedEarly {
+                name,
+                exit_code,
+                stderr_excerpt,
+            } => json!({ "name": name, "exitCode": exit_code, "stderrExcerpt": stderr_excerpt }),
+            SentinelError::ReadinessTimeout { name, waited_ms }
+            | SentinelError::CompletionTimeout { name, waited_ms } => {
+                json!({ "name": name, "waitedMs": waited_ms })
+            }
+            SentinelError::NotCompleted { name, exit_code } => {
+                json!({ "name": name, "exitCode": exit_code })
+            }
+            SentinelError::StopTimeout { name, timeout_secs } => {
+                json!({ "name": name, "timeoutSecs": timeout_secs })
+            }
+            SentinelError::InvalidConfig { reason } => json!({ "reason": reason }),
+            SentinelError::ConfigNotFound { path } => json!({ "path": path }),
+            SentinelError::ConfigParseFailed {
+                path,
+                line,
+                column,
+                snippet,
+                source,
+            } => json!({
+                "path": path,
+                "line": line,
+                "column": column,
+                "snippet": snippet,
+                "cause": source.to_string(),
+            }),
+            SentinelError::FileIoError { path, source } => {
+                json!({ "path": path, "cause": source.to_string() })
+            }
+            SentinelError::DependencyCycle { deps } => json!({ "deps": deps }),
+            SentinelError::UnknownDependency {
+                process,
+                dependency,
+            } => json!({ "process": process, "dependency": dependency }),
+            SentinelError::IncludeCycle { chain } => json!({ "chain": chain }),
+            SentinelError::ExcludedDependency {
+                process,
+                dependency,
+                profiles,
+            } => json!({ "process": process, "dependency": dependency, "profiles": profiles }),
+            SentinelError::DependencyNotRunning {
+                process,
+                dependency,
+                tag,
+            } => json!({ "process": process, "dependency": dependency, "tag": tag }),
+            SentinelError::UnknownTag { tag, known } => json!({ "tag": tag, "known": known }),
+            SentinelError::PathNotAllowed { path, allowed } => {
+                json!({ "path": path, "allowed": allowed })
+            }
+            SentinelError::InvalidPriority { nice } => json!({ "nice": nice }),
+            SentinelError::InvalidCpuAffinity { cores, reason } => {
+                json!({ "cores": cores, "reason": reason })
+            }
+            SentinelError::InvalidSchedule { expression, reason } => {
+                json!({ "expression": expression, "reason": reason })
+            }
+            SentinelError::InvalidSearchPattern { pattern, reason } => {
+                json!({ "pattern": pattern, "reason": reason })
+            }
+            SentinelError::SchedulingFailed { pid, source } => {
+                json!({ "pid": pid, "cause": source.to_string() })
+            }
+            SentinelError::RestartLimitExceeded { name, limit } => {
+                json!({ "name": name, "limit": limit })
+            }
+            SentinelError::NotificationFailed { sink, reason } => {
+                json!({ "sink": sink, "reason": reason })
+            }
+            SentinelError::PortNotFound(port) => json!({ "port": port }),
+            SentinelError::PortConflict {
+                port,
+                process,
+                other,
+            } => json!({ "port": port, "process": process, "other": other }),
+            SentinelError::PortInUse { port, process } => {
+                json!({ "port": port, "process": process })
+            }
+            SentinelError::ForwardPortInUse { port, pid, process } => {
+                json!({ "port": port, "pid": pid, "process": process })
+            }
+            SentinelError::KillConfirmationInvalid { expired } => json!({ "expired": expired }),
+            SentinelError::InvalidGeoIpDatabase { path, reason } => {
+                json!({ "path": path, "reason": reason })
+            }
+            SentinelError::AlreadyRunning { pid } => json!({ "pid": pid }),
+            SentinelError::Unsupported { operation } => json!({ "operation": operation }),
+            SentinelError::MonitoringError { .. }
+            | SentinelError::Io(_)
+            | SentinelError::PortDiscoveryError(_)
+            | SentinelError::DockerError(_)
+            | SentinelError::MetricsHistoryError(_)
+            | SentinelError::InvalidInput { .. }
+            | SentinelError::Other(_) => Value::Null,
+        }
+    }
+
+    /// Catalog key of the message, `error.<CODE>` with a suffix for the
+    /// variants whose message has an optional part.
+    pub fn message_key(&self) -> String {
+        let suffix = match self {
+            SentinelError::StartFailedEarly { stderr_excerpt, .. }
+                if !stderr_excerpt.is_empty() =>
+            {
+                ".with_output"
+            }
+            SentinelError::ConfigParseFailed {
+                snippet: Some(_), ..
+            } => ".with_snippet",
+            SentinelError::NotCompleted {
+                exit_code: Some(_), ..
+            } => ".with_exit_code",
+            SentinelError::AlreadyRunning { pid: Some(_) } => ".with_pid",
+            SentinelError::ForwardPortInUse { pid: Some(_), .. } => ".with_owner",
+            SentinelError::KillConfirmationInvalid { expired: true } => ".expired",
+            SentinelError::UnknownTag { known, .. } if !known.is_empty() => ".with_known",
+            _ => "",
+        };
+        let code = serde_json::to_value(self.code())
+            .ok()
+            .and_then(|code| code.as_str().map(str::to_string))
+            .unwrap_or_default();
+        format!("error.{}{}", code, suffix)
+    }
+
+    /// Values for the placeholders of [`message_key`](Self::message_key),
+    /// already formatted for display.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        fn paths(paths: &[PathBuf], separator: &str) -> String {
+            paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(separator)
+        }
+
+        match self {
+            SentinelError::ProcessNotFound { name }
+            | SentinelError::ProcessNotRunning { name }
+            | SentinelError::RestartUnavailable { name } => vec![("name", name.clone())],
+            SentinelError::SpawnFailed { name, source } => {
+                vec![("name", name.clone()), ("cause", source.to_string())]
+            }
+            SentinelError::ProcessAlreadyRunning { name, pid } => {
+                vec![("name", name.clone()), ("pid", pid.to_string())]
+            }
+            SentinelError::StartFailedEarly {
+                name,
+                exit_code,
+                stderr_excerpt,
+            } => vec![
+                ("name", name.clone()),
+                ("exitCode", exit_code.to_string()),
+                ("stderrExcerpt", stderr_excerpt.clone()),
+            ],
+            SentinelError::ReadinessTimeout { name, waited_ms }
+            | SentinelError::CompletionTimeout { name, waited_ms } => {
+                vec![("name", name.clone()), ("waitedMs", waited_ms.to_string())]
+            }
+            SentinelError::NotCompleted { name, exit_code } => {
+                let mut params = vec![("name", name.clone())];
+                if let Some(code) = exit_code {
+                    params.push(("exitCode", code.to_string()));
+                }
+                params
+            }
+            SentinelError::StopTimeout { name, timeout_secs } => vec![
+                ("name", name.clone()),
+                ("timeoutSecs", timeout_secs.to_string()),
+            ],
+            SentinelError::InvalidConfig { reason } => vec![("reason", reason.clone())],
+            SentinelError::ConfigNotFound { path } => vec![("path", path.display().to_string())],
+            SentinelError::ConfigParseFailed {
+                path,
+                snippet,
+                source,
+                ..
+            } => vec![
+                ("path", path.display().to_string()),
+                ("cause", source.to_string()),
+                ("snippet", snippet.clone().unwrap_or_default()),
+            ],
+            SentinelError::FileIoError { path, source } => vec![
+                ("path", path.display().to_string()),
+                ("cause", source.to_string()),
+            ],
+            SentinelError::MonitoringError { message }
+            | SentinelError::InvalidInput { message } => vec![("message", message.clone())],
+            SentinelError::DependencyCycle { deps } => vec![("deps", deps.join(" -> "))],
+            SentinelError::UnknownDependency {
+                process,
+                dependency,
+            } => vec![
+                ("process", process.clone()),
+                ("dependency", dependency.clone()),
+            ],
+            SentinelError::IncludeCycle { chain } => vec![("chain", paths(chain, " -> "))],
+            SentinelError::ExcludedDependency {
+                process,
+                dependency,
+                profiles,
+            } => vec![
+                ("process", process.clone()),
+                ("dependency", dependency.clone()),
+                (
+                    "profiles",
+                    profiles
+                        .iter()
+                        .map(|p| format!("'{}'", p))
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                ),
+            ],
+            SentinelError::DependencyNotRunning {
+                process,
+                dependency,
+                tag,
+            } => vec![
+                ("process", process.clone()),
+                ("dependency", dependency.clone()),
+                ("tag", tag.clone()),
+            ],
+            SentinelError::UnknownTag { tag, known } => {
+                vec![("tag", tag.clone()), ("known", known.join(", "))]
+            }
+            SentinelError::PathNotAllowed { path, allowed } => vec![
+                ("path", path.display().to_string()),
+                ("allowed", paths(allowed, ", ")),
+            ],
+            SentinelError::InvalidPriority { nice } => vec![("nice", nice.to_string())],
+            SentinelError::InvalidCpuAffinity { cores, reason } => {
+                vec![
+                    ("cores", format!("{:?}", cores)),
+                    ("reason", reason.clone()),
+                ]
+            }
+            SentinelError::InvalidSchedule { expression, reason } => {
+                vec![
+                    ("expression", expression.clone()),
+                    ("reason", reason.clone()),
+                ]
+            }
+            SentinelError::InvalidSearchPattern { pattern, reason } => {
+                vec![("pattern", pattern.clone()), ("reason", reason.clone())]
+            }
+            SentinelError::SchedulingFailed { pid, source } => {
+                vec![("pid", pid.to_string()), ("cause", source.to_string())]
+            }
+            SentinelError::RestartLimitExceeded { name, limit } => {
+                vec![("name", name.clone()), ("limit", limit.to_string())]
+            }
+            SentinelError::NotificationFailed { sink, reason } => {
+                vec![("sink", sink.clone()), ("reason", reason.clone())]
+            }
+            SentinelError::Io(source) => vec![("cause", source.to_string())],
+            SentinelError::PortDiscoveryError(message)
+            | SentinelError::DockerError(message)
+            | SentinelError::MetricsHistoryError(message)
+            | SentinelError::Other(message) => vec![("message", message.clone())],
+            SentinelError::PortNotFound(port) => vec![("port", port.to_string())],
+            SentinelError::PortConflict {
+                port,
+                process,
+                other,
+            } => vec![
+                ("port", port.to_string()),
+                ("process", process.clone()),
+                ("other", other.clone()),
+            ],
+            SentinelError::PortInUse { port, process } => {
+                vec![("port", port.to_string()), ("process", process.clone())]
+            }
+            SentinelError::ForwardPortInUse { port, pid, process } => {
+                let mut params = vec![("port", port.to_string())];
+                if let Some(pid) = pid {
+                    params.push(("pid", pid.to_string()));
+                    params.push((
+                        "process",
+                        process.clone().unwrap_or_else(|| "a process".to_string()),
+                    ));
+                }
+                params
+            }
+            SentinelError::InvalidGeoIpDatabase { path, reason } => vec![
+                ("path", path.display().to_string()),
+                ("reason", reason.clone()),
+            ],
+            SentinelError::AlreadyRunning { pid } => pid
+                .map(|pid| vec![("pid", pid.to_string())])
+                .unwrap_or_default(),
+            SentinelError::KillConfirmationInvalid { .. } => Vec::new(),
+            SentinelError::Unsupported { operation } => vec![("operation", operation.clone())],
+        }
+    }
+
+    /// The message in the active locale.
+    pub fn localized_message(&self) -> String {
+        messages::text(&self.message_key(), &self.params())
+    }
+}
+
+/// Serializes as `{code, message, params, details}`, which is what Tauri commands
+/// hand to the frontend and the CLI prints in JSON mode.
+impl Serialize for SentinelError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let params: BTreeMap<_, _> = self.params().into_iter().collect();
+        let mut error = serializer.serialize_struct("SentinelError", 4)?;
+        error.serialize_field("code", &self.code())?;
+        error.serialize_field("message", &self.localized_message())?;
+        error.serialize_field("params", &params)?;
+        error.serialize_field("details", &self.details())?;
+        error.end()
+    }
+}
+
 /// Convert anyhow::Error to SentinelError
 impl From<anyhow::Error> for SentinelError {
     fn from(err: anyhow::Error) -> Self {
@@ -121,6 +778,13 @@ impl From<bollard::errors::Error> for SentinelError {
     }
 }
 
+/// Convert rusqlite::Error to SentinelError
+impl From<rusqlite::Error> for SentinelError {
+    fn from(err: rusqlite::Error) -> Self {
+        SentinelError::MetricsHistoryError(err.to_string())
+    }
+}
+
 /// Specialized Result type for Sentinel operations.
 pub type Result<T> = std::result::Result<T, SentinelError>;
 
@@ -155,6 +819,18 @@ mod tests {
         assert_eq!(err.to_string(), "Dependency cycle detected: A -> B -> A");
     }
 
+    #[test]
+    fn test_path_not_allowed_error() {
+        let err = SentinelError::PathNotAllowed {
+            path: PathBuf::from("/etc"),
+            allowed: vec![PathBuf::from("/home/dev"), PathBuf::from("/srv")],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Path /etc is outside the allowed roots: /home/dev, /srv"
+        );
+    }
+
     #[test]
     fn test_restart_limit_exceeded() {
         let err = SentinelError::RestartLimitExceeded {
@@ -166,4 +842,351 @@ mod tests {
             "Process 'api' exceeded restart limit of 5 attempts"
         );
     }
+
+    #[test]
+    fn test_already_running_error() {
+        let err = SentinelError::AlreadyRunning { pid: Some(4242) };
+        assert_eq!(err.to_string(), "Sentinel is already running (PID 4242)");
+        let err = SentinelError::AlreadyRunning { pid: None };
+        assert_eq!(err.to_string(), "Sentinel is already running");
+    }
+
+    /// One error of every variant, with its code.
+    fn every_error() -> Vec<(SentinelError, &'static str)> {
+        let io_error = || io::Error::new(io::ErrorKind::NotFound, "gone");
+        let yaml_error = serde_yaml::from_str::<u32>("[").unwrap_err();
+        let name = || "api".to_string();
+        let table = vec![
+            (
+                SentinelError::ProcessNotFound { name: name() },
+                "PROCESS_NOT_FOUND",
+            ),
+            (
+                SentinelError::SpawnFailed {
+                    name: name(),
+                    source: io_error(),
+                },
+                "SPAWN_FAILED",
+            ),
+            (
+                SentinelError::ProcessAlreadyRunning {
+                    name: name(),
+                    pid: 1,
+                },
+                "PROCESS_ALREADY_RUNNING",
+            ),
+            (
+                SentinelError::ProcessNotRunning { name: name() },
+                "PROCESS_NOT_RUNNING",
+            ),
+            (
+                SentinelError::StartFailedEarly {
+                    name: name(),
+                    exit_code: 1,
+                    stderr_excerpt: String::new(),
+                },
+                "START_FAILED_EARLY",
+            ),
+            (
+                SentinelError::ReadinessTimeout {
+                    name: name(),
+                    waited_ms: 1,
+                },
+                "READINESS_TIMEOUT",
+            ),
+            (
+                SentinelError::CompletionTimeout {
+                    name: name(),
+                    waited_ms: 1,
+                },
+                "COMPLETION_TIMEOUT",
+            ),
+            (
+                SentinelError::NotCompleted {
+                    name: name(),
+                    exit_code: None,
+                },
+                "NOT_COMPLETED",
+            ),
+            (
+                SentinelError::StopTimeout {
+                    name: name(),
+                    timeout_secs: 1,
+                },
+                "STOP_TIMEOUT",
+            ),
+            (
+                SentinelError::InvalidConfig { reason: name() },
+                "INVALID_CONFIG",
+            ),
+            (
+                SentinelError::ConfigNotFound { path: "a".into() },
+                "CONFIG_NOT_FOUND",
+            ),
+            (
+                SentinelError::ConfigParseFailed {
+                    path: "a".into(),
+                    line: None,
+                    column: None,
+                    snippet: None,
+                    source: yaml_error,
+                },
+                "CONFIG_PARSE_FAILED",
+            ),
+            (
+                SentinelError::FileIoError {
+                    path: "a".into(),
+                    source: io_error(),
+                },
+                "FILE_IO",
+            ),
+            (
+                SentinelError::MonitoringError { message: name() },
+                "MONITORING",
+            ),
+            (
+                SentinelError::DependencyCycle { deps: vec![] },
+                "DEPENDENCY_CYCLE",
+            ),
+            (
+                SentinelError::UnknownDependency {
+                    process: name(),
+                    dependency: name(),
+                },
+                "UNKNOWN_DEPENDENCY",
+            ),
+            (
+                SentinelError::IncludeCycle { chain: vec![] },
+                "INCLUDE_CYCLE",
+            ),
+            (
+                SentinelError::ExcludedDependency {
+                    process: name(),
+                    dependency: name(),
+                    profiles: vec![],
+                },
+                "EXCLUDED_DEPENDENCY",
+            ),
+            (
+                SentinelError::DependencyNotRunning {
+                    process: name(),
+                    dependency: name(),
+                    tag: name(),
+                },
+                "DEPENDENCY_NOT_RUNNING",
+            ),
+            (
+                SentinelError::UnknownTag {
+                    tag: name(),
+                    known: vec![],
+                },
+                "UNKNOWN_TAG",
+            ),
+            (
+                SentinelError::PathNotAllowed {
+                    path: "a".into(),
+                    allowed: vec![],
+                },
+                "PATH_NOT_ALLOWED",
+            ),
+            (
+                SentinelError::InvalidPriority { nice: 99 },
+                "INVALID_PRIORITY",
+            ),
+            (
+                SentinelError::InvalidCpuAffinity {
+                    cores: vec![],
+                    reason: name(),
+                },
+                "INVALID_CPU_AFFINITY",
+            ),
+            (
+                SentinelError::InvalidSchedule {
+                    expression: name(),
+                    reason: name(),
+                },
+                "INVALID_SCHEDULE",
+            ),
+            (
+                SentinelError::InvalidSearchPattern {
+                    pattern: name(),
+                    reason: name(),
+                },
+                "INVALID_SEARCH_PATTERN",
+            ),
+            (
+                SentinelError::SchedulingFailed {
+                    pid: 1,
+                    source: io_error(),
+                },
+                "SCHEDULING_FAILED",
+            ),
+            (
+                SentinelError::RestartLimitExceeded {
+                    name: name(),
+                    limit: 1,
+                },
+                "RESTART_LIMIT_EXCEEDED",
+            ),
+            (
+                SentinelError::RestartUnavailable { name: name() },
+                "RESTART_UNAVAILABLE",
+            ),
+            (
+                SentinelError::NotificationFailed {
+                    sink: name(),
+                    reason: name(),
+                },
+                "NOTIFICATION_FAILED",
+            ),
+            (SentinelError::Io(io_error()), "IO"),
+            (SentinelError::PortDiscoveryError(name()), "PORT_DISCOVERY"),
+            (SentinelError::PortNotFound(80), "PORT_NOT_FOUND"),
+            (
+                SentinelError::PortConflict {
+                    port: 80,
+                    process: name(),
+                    other: name(),
+                },
+                "PORT_CONFLICT",
+            ),
+            (
+                SentinelError::PortInUse {
+                    port: 80,
+                    process: name(),
+                },
+                "PORT_IN_USE",
+            ),
+            (
+                SentinelError::ForwardPortInUse {
+                    port: 80,
+                    pid: None,
+                    process: None,
+                },
+                "FORWARD_PORT_IN_USE",
+            ),
+            (
+                SentinelError::KillConfirmationInvalid { expired: false },
+                "KILL_CONFIRMATION_INVALID",
+            ),
+            (
+                SentinelError::InvalidGeoIpDatabase {
+                    path: "a".into(),
+                    reason: name(),
+                },
+                "INVALID_GEO_IP_DATABASE",
+            ),
+            (SentinelError::DockerError(name()), "DOCKER"),
+            (
+                SentinelError::MetricsHistoryError(name()),
+                "METRICS_HISTORY",
+            ),
+            (
+                SentinelError::AlreadyRunning { pid: None },
+                "ALREADY_RUNNING",
+            ),
+            (
+                SentinelError::Unsupported { operation: name() },
+                "UNSUPPORTED",
+            ),
+            (
+                SentinelError::InvalidInput { message: name() },
+                "INVALID_INPUT",
+            ),
+            (SentinelError::Other(name()), "OTHER"),
+        ];
+        table
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        for (error, code) in every_error() {
+            assert_eq!(serde_json::to_value(error.code()).unwrap(), code);
+            let parsed: ErrorCode = serde_json::from_value(Value::from(code)).unwrap();
+            assert_eq!(parsed, error.code());
+        }
+    }
+
+    #[test]
+    fn test_every_error_has_an_english_message() {
+        let yaml_error = serde_yaml::from_str::<u32>("[").unwrap_err();
+        let mut errors: Vec<SentinelError> =
+            every_error().into_iter().map(|(error, _)| error).collect();
+        errors.extend([
+            SentinelError::StartFailedEarly {
+                name: "api".to_string(),
+                exit_code: 2,
+                stderr_excerpt: "boom".to_string(),
+            },
+            SentinelError::ConfigParseFailed {
+                path: "a.yaml".into(),
+                line: Some(1),
+                column: Some(1),
+                snippet: Some("1 | [".to_string()),
+                source: yaml_error,
+            },
+            SentinelError::NotCompleted {
+                name: "migrate".to_string(),
+                exit_code: Some(1),
+            },
+            SentinelError::AlreadyRunning { pid: Some(7) },
+            SentinelError::KillConfirmationInvalid { expired: true },
+            SentinelError::UnknownTag {
+                tag: "web".to_string(),
+                known: vec!["backend".to_string(), "frontend".to_string()],
+            },
+            SentinelError::ForwardPortInUse {
+                port: 8080,
+                pid: Some(7),
+                process: Some("node".to_string()),
+            },
+            SentinelError::ExcludedDependency {
+                process: "web".to_string(),
+                dependency: "db".to_string(),
+                profiles: vec!["dev".to_string(), "ci".to_string()],
+            },
+        ]);
+
+        let english = messages::Catalog::english();
+        for error in errors {
+            let key = error.message_key();
+            let template = english
+                .get(&key)
+                .unwrap_or_else(|| panic!("no English message for {}", key));
+            assert_eq!(
+                messages::format(template, &error.params()),
+                error.to_string(),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_serializes_code_message_and_details() {
+        let err = SentinelError::PortInUse {
+            port: 3000,
+            process: "web".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({
+                "code": "PORT_IN_USE",
+                "message": "Port 3000 of process 'web' is already in use",
+                "params": { "port": "3000", "process": "web" },
+                "details": { "port": 3000, "process": "web" },
+            })
+        );
+
+        let err = SentinelError::Other("Something broke".to_string());
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({
+                "code": "OTHER",
+                "message": "Something broke",
+                "params": { "message": "Something broke" },
+                "details": null,
+            })
+        );
+    }
 }
@@ -0,0 +1,268 @@
+//! Detection of the tools and interfaces features depend on
+
+use super::types::{Feature, FeatureCapabilities, FeatureCapability};
+use crate::core::executable;
+use crate::features::port_discovery::ScanBackend;
+use chrono::Utc;
+use std::path::Path;
+
+/// Looks up the tools installed on a system
+pub trait ToolLookup {
+    /// Whether `command` is an executable on `PATH`
+    fn has_command(&self, command: &str) -> bool;
+
+    /// Whether `path` exists
+    fn has_path(&self, path: &str) -> bool;
+}
+
+/// Looks tools up on this system
+pub struct SystemTools;
+
+impl ToolLookup for SystemTools {
+    fn has_command(&self, command: &str) -> bool {
+        let program = if cfg!(windows) {
+            format!("{}.exe", command)
+        } else {
+            command.to_string()
+        };
+        let path = std::env::var_os("PATH");
+        executable::which(&program, None, path.as_deref()).is_some()
+    }
+
+    fn has_path(&self, path: &str) -> bool {
+        Path::new(path).exists()
+    }
+}
+
+/// Operating system the features run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostOs {
+    Linux,
+    MacOs,
+    Windows,
+    Other,
+}
+
+impl HostOs {
+    /// The operating system Sentinel was built for
+    pub fn current() -> Self {
+        if cfg!(target_os = "linux") {
+            HostOs::Linux
+        } else if cfg!(target_os = "macos") {
+            HostOs::MacOs
+        } else if cfg!(windows) {
+            HostOs::Windows
+        } else {
+            HostOs::Other
+        }
+    }
+}
+
+/// First installed command that port scans can run on `os`, in order of preference
+pub fn scan_backend(os: HostOs, tools: &impl ToolLookup) -> Option<ScanBackend> {
+    let candidates: &[ScanBackend] = match os {
+        HostOs::Linux => &[ScanBackend::Lsof, ScanBackend::Ss],
+        HostOs::MacOs | HostOs::Other => &[ScanBackend::Lsof],
+        HostOs::Windows => &[ScanBackend::Netstat],
+    };
+    candidates
+        .iter()
+        .copied()
+        .find(|backend| tools.has_command(backend.command()))
+}
+
+/// Works out which features are available on `os`
+///
+/// Docker availability comes from the Docker monitor, which already tried
+/// to connect.
+pub fn detect(os: HostOs, tools: &impl ToolLookup, docker_available: bool) -> FeatureCapabilities {
+    let backend = scan_backend(os, tools);
+    let port_scanning = match (backend, os) {
+        (Some(ScanBackend::Ss), _) => FeatureCapability::degraded(
+            Feature::PortScanning,
+            "ss",
+            "lsof is not installed, so ports are listed with ss",
+        ),
+        (Some(backend), _) => {
+            FeatureCapability::available(Feature::PortScanning, backend.command())
+        }
+        (None, HostOs::Linux) => FeatureCapability::unavailable(
+            Feature::PortScanning,
+            "Neither lsof nor ss is installed",
+        ),
+        (None, HostOs::Windows) => {
+            FeatureCapability::unavailable(Feature::PortScanning, "netstat is not installed")
+        }
+        (None, _) => FeatureCapability::unavailable(Feature::PortScanning, "lsof is not installed"),
+    };
+
+    let connection_traffic = match os {
+        HostOs::Linux if tools.has_path("/proc/net") => {
+            FeatureCapability::available(Feature::ConnectionTraffic, "/proc")
+        }
+        HostOs::Linux => {
+            FeatureCapability::unavailable(Feature::ConnectionTraffic, "/proc is not mounted")
+        }
+        HostOs::MacOs if tools.has_command("nettop") => {
+            FeatureCapability::available(Feature::ConnectionTraffic, "nettop")
+        }
+        HostOs::MacOs => {
+            FeatureCapability::unavailable(Feature::ConnectionTraffic, "nettop is not installed")
+        }
+        HostOs::Windows | HostOs::Other => FeatureCapability::unavailable(
+            Feature::ConnectionTraffic,
+            "Per-process traffic isn't reported on this platform",
+        ),
+    };
+
+    let docker = if docker_available {
+        FeatureCapability::available(Feature::Docker, "docker")
+    } else {
+        FeatureCapability::unavailable(Feature::Docker, "The Docker daemon can't be reached")
+    };
+
+    FeatureCapabilities {
+        features: vec![port_scanning, connection_traffic, docker],
+        scan_backend: backend,
+        detected_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::capabilities::CapabilityStatus;
+
+    /// A system with exactly the given commands and paths
+    struct MockTools {
+        commands: &'static [&'static str],
+        paths: &'static [&'static str],
+    }
+
+    impl ToolLookup for MockTools {
+        fn has_command(&self, command: &str) -> bool {
+            self.commands.contains(&command)
+        }
+
+        fn has_path(&self, path: &str) -> bool {
+            self.paths.contains(&path)
+        }
+    }
+
+    fn status(capabilities: &FeatureCapabilities, feature: Feature) -> CapabilityStatus {
+        capabilities.get(feature).unwrap().status
+    }
+
+    #[test]
+    fn test_scan_backend_preference() {
+        let both = MockTools {
+            commands: &["lsof", "ss"],
+            paths: &[],
+        };
+        let ss_only = MockTools {
+            commands: &["ss"],
+            paths: &[],
+        };
+        assert_eq!(scan_backend(HostOs::Linux, &both), Some(ScanBackend::Lsof));
+        assert_eq!(scan_backend(HostOs::Linux, &ss_only), Some(ScanBackend::Ss));
+        // ss is Linux only
+        assert_eq!(scan_backend(HostOs::MacOs, &ss_only), None);
+        assert_eq!(scan_backend(HostOs::Windows, &both), None);
+    }
+
+    #[test]
+    fn test_minimal_linux_container() {
+        let tools = MockTools {
+            commands: &[],
+            paths: &[],
+        };
+        let capabilities = detect(HostOs::Linux, &tools, false);
+
+        assert_eq!(capabilities.scan_backend, None);
+        for feature in [
+            Feature::PortScanning,
+            Feature::ConnectionTraffic,
+            Feature::Docker,
+        ] {
+            assert_eq!(
+                status(&capabilities, feature),
+                CapabilityStatus::Unavailable
+            );
+            assert!(capabilities.get(feature).unwrap().reason.is_some());
+        }
+        assert_eq!(
+            capabilities
+                .get(Feature::PortScanning)
+                .unwrap()
+                .reason
+                .as_deref(),
+            Some("Neither lsof nor ss is installed")
+        );
+    }
+
+    #[test]
+    fn test_linux_with_ss_is_degraded() {
+        let tools = MockTools {
+            commands: &["ss"],
+            paths: &["/proc/net"],
+        };
+        let capabilities = detect(HostOs::Linux, &tools, true);
+
+        assert_eq!(capabilities.scan_backend, Some(ScanBackend::Ss));
+        let scanning = capabilities.get(Feature::PortScanning).unwrap();
+        assert_eq!(scanning.status, CapabilityStatus::Degraded);
+        assert_eq!(scanning.backend.as_deref(), Some("ss"));
+        assert!(capabilities.is_available(Feature::PortScanning));
+        assert_eq!(
+            status(&capabilities, Feature::ConnectionTraffic),
+            CapabilityStatus::Available
+        );
+        assert_eq!(
+            status(&capabilities, Feature::Docker),
+            CapabilityStatus::Available
+        );
+    }
+
+    #[test]
+    fn test_capability_matrix_by_platform() {
+        let tools = MockTools {
+            commands: &["lsof", "netstat", "nettop"],
+            paths: &[],
+        };
+
+        let macos = detect(HostOs::MacOs, &tools, false);
+        assert_eq!(macos.scan_backend, Some(ScanBackend::Lsof));
+        assert_eq!(
+            status(&macos, Feature::ConnectionTraffic),
+            CapabilityStatus::Available
+        );
+
+        let windows = detect(HostOs::Windows, &tools, false);
+        assert_eq!(windows.scan_backend, Some(ScanBackend::Netstat));
+        assert_eq!(
+            status(&windows, Feature::PortScanning),
+            CapabilityStatus::Available
+        );
+        assert_eq!(
+            status(&windows, Feature::ConnectionTraffic),
+            CapabilityStatus::Unavailable
+        );
+
+        // lsof without /proc still scans ports
+        let linux = detect(HostOs::Linux, &tools, false);
+        assert_eq!(
+            status(&linux, Feature::PortScanning),
+            CapabilityStatus::Available
+        );
+        assert_eq!(
+            status(&linux, Feature::ConnectionTraffic),
+            CapabilityStatus::Unavailable
+        );
+    }
+
+    #[test]
+    fn test_system_tools() {
+        assert!(!SystemTools.has_command("sentinel-no-such-tool"));
+        assert!(!SystemTools.has_path("/sentinel/no/such/path"));
+    }
+}
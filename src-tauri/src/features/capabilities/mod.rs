@@ -0,0 +1,98 @@
+//! # Feature Capabilities Module
+//!
+//! Detects which features work on this system, so that on a minimal machine
+//! (no lsof, no Docker socket) their commands return empty results instead
+//! of failing on every poll.
+//!
+//! ## Features
+//! - Each feature reported as available, degraded or unavailable, with the reason
+//! - Port scans fall back to `ss` on Linux without lsof
+//! - Re-detection on demand, e.g. after installing a missing tool
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use sentinel::features::capabilities::{detect, HostOs, SystemTools};
+//!
+//! let capabilities = detect(HostOs::current(), &SystemTools, false);
+//! for capability in &capabilities.features {
+//!     println!("{:?}: {:?} {:?}", capability.feature, capability.status, capability.reason);
+//! }
+//! ```
+
+mod detector;
+mod types;
+
+pub use detector::{detect, scan_backend, HostOs, SystemTools, ToolLookup};
+pub use types::*;
+
+use crate::error::Result;
+use crate::features::docker::DockerMonitorState;
+use crate::features::port_discovery::PortScanner;
+use std::sync::{RwLock, RwLockReadGuard};
+use tauri::State;
+
+/// Application state for feature capabilities
+pub struct CapabilitiesState(pub RwLock<FeatureCapabilities>);
+
+impl CapabilitiesState {
+    /// Detects the capabilities of this system
+    pub fn new(docker_available: bool) -> Self {
+        Self(RwLock::new(detect(
+            HostOs::current(),
+            &SystemTools,
+            docker_available,
+        )))
+    }
+
+    /// The capabilities as last detected
+    pub fn get(&self) -> FeatureCapabilities {
+        self.read().clone()
+    }
+
+    /// Detects the capabilities again
+    pub fn refresh(&self, docker_available: bool) -> FeatureCapabilities {
+        let capabilities = detect(HostOs::current(), &SystemTools, docker_available);
+        *self.0.write().unwrap_or_else(|e| {
+            tracing::error!("Failed to lock feature capabilities: {}", e);
+            e.into_inner()
+        }) = capabilities.clone();
+        capabilities
+    }
+
+    /// A port scanner, or `None` while port scanning is unavailable
+    pub fn scanner(&self) -> Option<PortScanner> {
+        self.read().scan_backend.map(PortScanner::with_backend)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, FeatureCapabilities> {
+        self.0.read().unwrap_or_else(|e| {
+            tracing::error!("Failed to lock feature capabilities: {}", e);
+            e.into_inner()
+        })
+    }
+}
+
+/// List each feature as available, degraded or unavailable, with the reason
+///
+/// With `refresh`, tools are looked up again and Docker is reconnected if it
+/// was unreachable.
+#[tauri::command]
+pub async fn get_feature_capabilities(
+    refresh: Option<bool>,
+    capabilities: State<'_, CapabilitiesState>,
+    docker: State<'_, DockerMonitorState>,
+) -> Result<FeatureCapabilities> {
+    if !refresh.unwrap_or(false) {
+        return Ok(capabilities.get());
+    }
+
+    let docker_available = {
+        let mut docker = docker.0.lock().await;
+        if !docker.is_available() {
+            docker.reconnect();
+        }
+        docker.is_available()
+    };
+    Ok(capabilities.refresh(docker_available))
+}
@@ -0,0 +1,117 @@
+//! Feature capability data types
+
+use crate::features::port_discovery::ScanBackend;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Feature that depends on tools or interfaces the system may lack
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Feature {
+    /// Listening ports and active connections with their processes
+    PortScanning,
+    /// Bytes sent and received per process
+    ConnectionTraffic,
+    /// Docker containers
+    Docker,
+}
+
+/// Whether a feature works on this system
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CapabilityStatus {
+    /// Works fully
+    Available,
+    /// Works through a fallback
+    Degraded,
+    /// Doesn't work; its commands return empty results
+    Unavailable,
+}
+
+/// What is known about one feature
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureCapability {
+    pub feature: Feature,
+    pub status: CapabilityStatus,
+    /// Tool or interface the feature uses (e.g., "lsof", "/proc")
+    pub backend: Option<String>,
+    /// Why the feature is degraded or unavailable
+    pub reason: Option<String>,
+}
+
+impl FeatureCapability {
+    pub(crate) fn available(feature: Feature, backend: &str) -> Self {
+        Self {
+            feature,
+            status: CapabilityStatus::Available,
+            backend: Some(backend.to_string()),
+            reason: None,
+        }
+    }
+
+    pub(crate) fn degraded(feature: Feature, backend: &str, reason: &str) -> Self {
+        Self {
+            feature,
+            status: CapabilityStatus::Degraded,
+            backend: Some(backend.to_string()),
+            reason: Some(reason.to_string()),
+        }
+    }
+
+    pub(crate) fn unavailable(feature: Feature, reason: &str) -> Self {
+        Self {
+            feature,
+            status: CapabilityStatus::Unavailable,
+            backend: None,
+            reason: Some(reason.to_string()),
+        }
+    }
+}
+
+/// Capabilities of every feature, as last detected
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureCapabilities {
+    pub features: Vec<FeatureCapability>,
+    /// Command port scans run, when one is installed
+    pub scan_backend: Option<ScanBackend>,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl FeatureCapabilities {
+    /// Capability of `feature`
+    pub fn get(&self, feature: Feature) -> Option<&FeatureCapability> {
+        self.features.iter().find(|c| c.feature == feature)
+    }
+
+    /// Whether `feature` works, fully or through a fallback
+    pub fn is_available(&self, feature: Feature) -> bool {
+        self.get(feature)
+            .is_some_and(|c| c.status != CapabilityStatus::Unavailable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_serialization() {
+        let capabilities = FeatureCapabilities {
+            features: vec![FeatureCapability::unavailable(
+                Feature::PortScanning,
+                "lsof and ss are not installed",
+            )],
+            scan_backend: None,
+            detected_at: Utc::now(),
+        };
+
+        let json = serde_json::to_value(&capabilities).unwrap();
+        assert_eq!(json["features"][0]["feature"], "portScanning");
+        assert_eq!(json["features"][0]["status"], "unavailable");
+        assert!(json["scanBackend"].is_null());
+        assert!(!capabilities.is_available(Feature::PortScanning));
+        assert!(!capabilities.is_available(Feature::Docker));
+    }
+}
@@ -0,0 +1,346 @@
+//! Docker daemon start/stop on Linux (systemd, Podman, Colima)
+
+use crate::error::{Result, SentinelError};
+use std::fmt;
+
+/// Output of a finished command
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stderr: String,
+}
+
+/// Runs external commands; mocked in tests so no systemd is needed
+pub trait CommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> std::io::Result<CommandOutput>;
+}
+
+/// Runs commands with `std::process::Command`
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> std::io::Result<CommandOutput> {
+        let output = std::process::Command::new(program).args(args).output()?;
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// Whether to bring the daemon up or down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaemonAction {
+    Start,
+    Stop,
+}
+
+impl DaemonAction {
+    fn verb(self) -> &'static str {
+        match self {
+            DaemonAction::Start => "start",
+            DaemonAction::Stop => "stop",
+        }
+    }
+}
+
+/// A way of controlling the daemon, tried in declaration order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaemonMechanism {
+    /// Rootless Docker managed by the user's systemd instance
+    SystemdUser,
+    /// System-wide dockerd
+    SystemdSystem,
+    /// Podman's Docker-compatible API socket
+    PodmanSocket,
+    /// Colima VM
+    Colima,
+}
+
+const MECHANISMS: [DaemonMechanism; 4] = [
+    DaemonMechanism::SystemdUser,
+    DaemonMechanism::SystemdSystem,
+    DaemonMechanism::PodmanSocket,
+    DaemonMechanism::Colima,
+];
+
+impl DaemonMechanism {
+    /// Program and arguments for the given action
+    fn command(self, action: DaemonAction) -> (&'static str, Vec<&'static str>) {
+        let verb = action.verb();
+        match self {
+            DaemonMechanism::SystemdUser => ("systemctl", vec!["--user", verb, "docker"]),
+            // Fail fast instead of waiting on a polkit prompt nobody sees
+            DaemonMechanism::SystemdSystem => {
+                ("systemctl", vec!["--no-ask-password", verb, "docker"])
+            }
+            DaemonMechanism::PodmanSocket => (
+                "systemctl",
+                vec!["--no-ask-password", verb, "podman.socket"],
+            ),
+            DaemonMechanism::Colima => ("colima", vec![verb]),
+        }
+    }
+
+    /// Command line as shown to the user
+    fn describe(self, action: DaemonAction) -> String {
+        let (program, args) = self.command(action);
+        let args: Vec<&str> = args
+            .into_iter()
+            .filter(|a| *a != "--no-ask-password")
+            .collect();
+        format!("{} {}", program, args.join(" "))
+    }
+}
+
+/// Why a mechanism did not work
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// The tool is not installed
+    NotInstalled,
+    /// systemd refused without elevated privileges
+    NeedsSudo,
+    /// The command ran and failed
+    Failed(String),
+}
+
+/// One mechanism that was tried
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemonAttempt {
+    pub command: String,
+    pub outcome: AttemptOutcome,
+}
+
+/// Every mechanism failed
+#[derive(Debug, Clone)]
+pub struct DaemonControlError {
+    pub action: DaemonAction,
+    pub attempts: Vec<DaemonAttempt>,
+}
+
+impl fmt::Display for DaemonControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not {} the Docker daemon. Tried:",
+            self.action.verb()
+        )?;
+        for attempt in &self.attempts {
+            let reason = match &attempt.outcome {
+                AttemptOutcome::NotInstalled => "not installed".to_string(),
+                AttemptOutcome::NeedsSudo => {
+                    format!("requires sudo (run `sudo {}`)", attempt.command)
+                }
+                AttemptOutcome::Failed(message) => message.clone(),
+            };
+            write!(f, "\n- {}: {}", attempt.command, reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<DaemonControlError> for SentinelError {
+    fn from(err: DaemonControlError) -> Self {
+        SentinelError::DockerError(err.to_string())
+    }
+}
+
+/// Start or stop the daemon with the first mechanism that works
+///
+/// Returns a message naming the mechanism that succeeded.
+pub fn control_daemon(runner: &dyn CommandRunner, action: DaemonAction) -> Result<String> {
+    let mut attempts = Vec::new();
+
+    for mechanism in MECHANISMS {
+        let command = mechanism.describe(action);
+
+        if mechanism == DaemonMechanism::Colima && !is_installed(runner, "colima") {
+            attempts.push(DaemonAttempt {
+                command,
+                outcome: AttemptOutcome::NotInstalled,
+            });
+            continue;
+        }
+
+        let (program, args) = mechanism.command(action);
+        tracing::debug!("Trying `{}`", command);
+        let outcome = match runner.run(program, &args) {
+            Ok(output) if output.success => {
+                tracing::info!("Docker daemon {} via `{}`", action.verb(), command);
+                return Ok(format!(
+                    "Docker daemon is {} ({})",
+                    match action {
+                        DaemonAction::Start => "starting",
+                        DaemonAction::Stop => "stopping",
+                    },
+                    command
+                ));
+            }
+            Ok(output) => classify_failure(&output),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => AttemptOutcome::NotInstalled,
+            Err(e) => AttemptOutcome::Failed(e.to_string()),
+        };
+        attempts.push(DaemonAttempt { command, outcome });
+    }
+
+    Err(DaemonControlError { action, attempts }.into())
+}
+
+/// Check whether a Linux host manages dockerd with systemd
+pub fn has_systemd_docker(runner: &dyn CommandRunner) -> bool {
+    [
+        vec!["--user", "cat", "docker.service"],
+        vec!["cat", "docker.service"],
+    ]
+    .iter()
+    .any(|args| runner.run("systemctl", args).is_ok_and(|o| o.success))
+}
+
+fn is_installed(runner: &dyn CommandRunner, program: &str) -> bool {
+    runner
+        .run(program, &["version"])
+        .is_ok_and(|output| output.success)
+}
+
+fn classify_failure(output: &CommandOutput) -> AttemptOutcome {
+    let stderr = output.stderr.trim();
+    let lower = stderr.to_lowercase();
+
+    if lower.contains("interactive authentication required")
+        || lower.contains("access denied")
+        || lower.contains("permission denied")
+    {
+        AttemptOutcome::NeedsSudo
+    } else if stderr.is_empty() {
+        AttemptOutcome::Failed("exited with an error".to_string())
+    } else {
+        // systemd errors are one useful line followed by hints
+        AttemptOutcome::Failed(stderr.lines().next().unwrap_or(stderr).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// Answers commands from a table and records what was run
+    #[derive(Default)]
+    struct MockRunner {
+        responses: HashMap<String, std::result::Result<CommandOutput, std::io::ErrorKind>>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl MockRunner {
+        fn respond(mut self, command: &str, success: bool, stderr: &str) -> Self {
+            self.responses.insert(
+                command.to_string(),
+                Ok(CommandOutput {
+                    success,
+                    stderr: stderr.to_string(),
+                }),
+            );
+            self
+        }
+
+        fn missing(mut self, command: &str) -> Self {
+            self.responses
+                .insert(command.to_string(), Err(std::io::ErrorKind::NotFound));
+            self
+        }
+    }
+
+    impl CommandRunner for MockRunner {
+        fn run(&self, program: &str, args: &[&str]) -> std::io::Result<CommandOutput> {
+            let command = format!("{} {}", program, args.join(" "));
+            self.calls.borrow_mut().push(command.clone());
+            match self.responses.get(&command) {
+                Some(Ok(output)) => Ok(output.clone()),
+                Some(Err(kind)) => Err((*kind).into()),
+                None => Ok(CommandOutput {
+                    success: false,
+                    stderr: "Unit not found.".to_string(),
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_service_wins_when_available() {
+        let runner = MockRunner::default().respond("systemctl --user start docker", true, "");
+
+        let message = control_daemon(&runner, DaemonAction::Start).unwrap();
+
+        assert!(message.contains("systemctl --user start docker"));
+        assert_eq!(runner.calls.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_falls_through_to_system_service() {
+        let runner = MockRunner::default()
+            .respond(
+                "systemctl --user start docker",
+                false,
+                "Failed to start docker.service: Unit docker.service not found.",
+            )
+            .respond("systemctl --no-ask-password start docker", true, "");
+
+        let message = control_daemon(&runner, DaemonAction::Start).unwrap();
+
+        assert!(message.contains("(systemctl start docker)"));
+    }
+
+    #[test]
+    fn test_reports_sudo_requirement_and_every_attempt() {
+        let runner = MockRunner::default()
+            .respond(
+                "systemctl --no-ask-password start docker",
+                false,
+                "Failed to start docker.service: Interactive authentication required.\nSee system logs.",
+            )
+            .missing("colima version");
+
+        let err = control_daemon(&runner, DaemonAction::Start)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("systemctl --user start docker: Unit not found."));
+        assert!(err.contains("requires sudo (run `sudo systemctl start docker`)"));
+        assert!(err.contains("systemctl start podman.socket: Unit not found."));
+        assert!(err.contains("colima start: not installed"));
+        // Colima is never started when it is not installed
+        assert!(!runner.calls.borrow().contains(&"colima start".to_string()));
+    }
+
+    #[test]
+    fn test_podman_socket_before_colima() {
+        let runner = MockRunner::default()
+            .respond("systemctl --no-ask-password start podman.socket", true, "")
+            .respond("colima version", true, "")
+            .respond("colima start", true, "");
+
+        let message = control_daemon(&runner, DaemonAction::Start).unwrap();
+
+        assert!(message.contains("podman.socket"));
+        assert!(!runner.calls.borrow().contains(&"colima start".to_string()));
+    }
+
+    #[test]
+    fn test_colima_used_last() {
+        let runner = MockRunner::default()
+            .respond("colima version", true, "")
+            .respond("colima stop", true, "");
+
+        let message = control_daemon(&runner, DaemonAction::Stop).unwrap();
+
+        assert_eq!(message, "Docker daemon is stopping (colima stop)");
+    }
+
+    #[test]
+    fn test_has_systemd_docker() {
+        let runner = MockRunner::default().respond("systemctl cat docker.service", true, "");
+        assert!(has_systemd_docker(&runner));
+        assert!(!has_systemd_docker(&MockRunner::default()));
+    }
+}
@@ -0,0 +1,341 @@
+//! Mapping Docker container states onto the health of managed processes
+//!
+//! An inspected container has its full state. A container list only has
+//! the state and Docker's status text (`Up 2 hours (healthy)`,
+//! `Exited (137) 5 minutes ago`), so whether a container was killed for
+//! running out of memory is only known from an inspect.
+
+use super::types::{ContainerHealth, ContainerHealthReason};
+use crate::core::ProcessStatus;
+use crate::features::service_detection::HealthStatus;
+use bollard::models::ContainerState;
+
+/// The parts of a container's state the mapping looks at
+struct DockerState<'a> {
+    /// created, running, paused, restarting, removing, exited or dead
+    state: &'a str,
+    /// starting, healthy or unhealthy, when the image has a health check
+    health: Option<&'a str>,
+    oom_killed: bool,
+    exit_code: Option<i64>,
+}
+
+impl DockerState<'_> {
+    fn reason(&self) -> ContainerHealthReason {
+        use ContainerHealthReason::*;
+
+        match self.state {
+            "running" => match self.health {
+                Some("healthy") => HealthCheckPassing,
+                Some("unhealthy") => HealthCheckFailing,
+                Some("starting") => HealthCheckStarting,
+                _ => NoHealthCheck,
+            },
+            "paused" => Paused,
+            "exited" | "restarting" | "dead" if self.oom_killed => OomKilled,
+            "exited" if self.exit_code.is_some_and(|code| code != 0) => ExitedWithError,
+            "exited" => Exited,
+            "restarting" => Restarting,
+            "created" => Created,
+            _ => Dead,
+        }
+    }
+}
+
+impl ContainerHealthReason {
+    /// The health and process status shown for a container with this reason
+    pub fn status(self) -> (HealthStatus, ProcessStatus) {
+        use ContainerHealthReason::*;
+
+        match self {
+            HealthCheckPassing => (HealthStatus::Healthy, ProcessStatus::Running),
+            HealthCheckFailing => (HealthStatus::Unhealthy, ProcessStatus::Running),
+            HealthCheckStarting => (HealthStatus::Degraded, ProcessStatus::Starting),
+            NoHealthCheck => (HealthStatus::Unknown, ProcessStatus::Running),
+            Paused => (HealthStatus::Degraded, ProcessStatus::Running),
+            Restarting => (HealthStatus::Unhealthy, ProcessStatus::Starting),
+            OomKilled | ExitedWithError | Dead => (HealthStatus::Unhealthy, ProcessStatus::Crashed),
+            Exited | Created => (HealthStatus::Unknown, ProcessStatus::Stopped),
+        }
+    }
+}
+
+impl ContainerHealth {
+    fn new(state: DockerState, failing_streak: Option<i64>, message: Option<String>) -> Self {
+        let reason = state.reason();
+        let (status, process_status) = reason.status();
+        Self {
+            status,
+            process_status,
+            reason,
+            exit_code: match state.state {
+                "exited" | "restarting" | "dead" => state.exit_code,
+                _ => None,
+            },
+            failing_streak,
+            message,
+        }
+    }
+
+    /// Health of an inspected container
+    pub fn from_state(state: &ContainerState) -> Self {
+        let health: Option<&str> = state
+            .health
+            .as_ref()
+            .and_then(|health| health.status.as_ref())
+            .map(|status| status.as_ref())
+            .filter(|status| !matches!(*status, "" | "none"));
+        let last_output = state
+            .health
+            .as_ref()
+            .and_then(|health| health.log.as_ref()?.last()?.output.as_deref())
+            .map(str::trim_end)
+            .filter(|output| !output.is_empty());
+        let error = state.error.as_deref().filter(|error| !error.is_empty());
+
+        Self::new(
+            DockerState {
+                state: state.status.as_ref().map_or("", |status| status.as_ref()),
+                health,
+                oom_killed: state.oom_killed.unwrap_or(false),
+                exit_code: state.exit_code,
+            },
+            state
+                .health
+                .as_ref()
+                .and_then(|check| check.failing_streak)
+                .filter(|_| health.is_some()),
+            last_output.or(error).map(str::to_string),
+        )
+    }
+
+    /// Health of a listed container, from its state (`running`) and status
+    /// text (`Up 2 hours (healthy)`)
+    pub fn from_summary(state: &str, status: &str) -> Self {
+        let health = if status.contains("(healthy)") {
+            Some("healthy")
+        } else if status.contains("(unhealthy)") {
+            Some("unhealthy")
+        } else if status.contains("(health: starting)") {
+            Some("starting")
+        } else {
+            None
+        };
+
+        Self::new(
+            DockerState {
+                state,
+                health,
+                oom_killed: false,
+                exit_code: exit_code_in_status(status),
+            },
+            None,
+            None,
+        )
+    }
+}
+
+/// The code in `Exited (137) 5 minutes ago` or `Restarting (1) 2 seconds ago`
+fn exit_code_in_status(status: &str) -> Option<i64> {
+    let rest = status
+        .strip_prefix("Exited (")
+        .or_else(|| status.strip_prefix("Restarting ("))?;
+    rest[..rest.find(')')?].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ContainerHealthReason::*;
+
+    /// The `State` of a `docker inspect`, with defaults for what isn't given
+    fn inspected(state: serde_json::Value) -> ContainerHealth {
+        let mut fixture = serde_json::json!({
+            "Status": "running",
+            "Running": true,
+            "Paused": false,
+            "Restarting": false,
+            "OOMKilled": false,
+            "Dead": false,
+            "Pid": 4242,
+            "ExitCode": 0,
+            "Error": "",
+            "StartedAt": "2024-05-01T12:00:00.000000000Z",
+            "FinishedAt": "0001-01-01T00:00:00Z"
+        });
+        for (key, value) in state.as_object().unwrap() {
+            fixture[key] = value.clone();
+        }
+        ContainerHealth::from_state(&serde_json::from_value(fixture).unwrap())
+    }
+
+    fn health_check(status: &str, failing_streak: i64, output: &str) -> serde_json::Value {
+        serde_json::json!({
+            "Status": status,
+            "FailingStreak": failing_streak,
+            "Log": [{
+                "Start": "2024-05-01T12:00:30Z",
+                "End": "2024-05-01T12:00:31Z",
+                "ExitCode": if status == "unhealthy" { 1 } else { 0 },
+                "Output": output
+            }]
+        })
+    }
+
+    #[test]
+    fn test_running_with_health_check() {
+        let healthy = inspected(serde_json::json!({
+            "Health": health_check("healthy", 0, "ok\n")
+        }));
+        assert_eq!(healthy.reason, HealthCheckPassing);
+        assert_eq!(healthy.status, HealthStatus::Healthy);
+        assert_eq!(healthy.process_status, ProcessStatus::Running);
+        assert_eq!(healthy.message.as_deref(), Some("ok"));
+        assert_eq!(healthy.exit_code, None);
+
+        let unhealthy = inspected(serde_json::json!({
+            "Health": health_check("unhealthy", 3, "connection refused")
+        }));
+        assert_eq!(unhealthy.reason, HealthCheckFailing);
+        assert_eq!(unhealthy.status, HealthStatus::Unhealthy);
+        assert_eq!(unhealthy.failing_streak, Some(3));
+        assert_eq!(unhealthy.message.as_deref(), Some("connection refused"));
+
+        let starting = inspected(serde_json::json!({
+            "Health": { "Status": "starting", "FailingStreak": 0, "Log": [] }
+        }));
+        assert_eq!(starting.reason, HealthCheckStarting);
+        assert_eq!(starting.status, HealthStatus::Degraded);
+        assert_eq!(starting.process_status, ProcessStatus::Starting);
+        assert_eq!(starting.message, None);
+    }
+
+    #[test]
+    fn test_running_without_health_check_is_unknown() {
+        for health in [
+            serde_json::json!({}),
+            serde_json::json!({ "Health": { "Status": "none" } }),
+        ] {
+            let health = inspected(health);
+            assert_eq!(health.reason, NoHealthCheck);
+            assert_eq!(health.status, HealthStatus::Unknown);
+            assert_eq!(health.process_status, ProcessStatus::Running);
+            assert_eq!(health.failing_streak, None);
+        }
+    }
+
+    #[test]
+    fn test_stopped_containers() {
+        let oom = inspected(serde_json::json!({
+            "Status": "exited", "Running": false, "OOMKilled": true, "ExitCode": 137
+        }));
+        assert_eq!(oom.reason, OomKilled);
+        assert_eq!(oom.status, HealthStatus::Unhealthy);
+        assert_eq!(oom.process_status, ProcessStatus::Crashed);
+        assert_eq!(oom.exit_code, Some(137));
+
+        let failed = inspected(serde_json::json!({
+            "Status": "exited", "Running": false, "ExitCode": 1
+        }));
+        assert_eq!(failed.reason, ExitedWithError);
+        assert_eq!(failed.status, HealthStatus::Unhealthy);
+        assert_eq!(failed.process_status, ProcessStatus::Crashed);
+        assert_eq!(failed.exit_code, Some(1));
+
+        // A health check from before it stopped doesn't make it healthy
+        let clean = inspected(serde_json::json!({
+            "Status": "exited", "Running": false, "ExitCode": 0,
+            "Health": health_check("healthy", 0, "ok")
+        }));
+        assert_eq!(clean.reason, Exited);
+        assert_eq!(clean.status, HealthStatus::Unknown);
+        assert_eq!(clean.process_status, ProcessStatus::Stopped);
+        assert_eq!(clean.exit_code, Some(0));
+
+        let created = inspected(serde_json::json!({ "Status": "created", "Running": false }));
+        assert_eq!(created.reason, Created);
+        assert_eq!(created.process_status, ProcessStatus::Stopped);
+        assert_eq!(created.exit_code, None);
+
+        let dead = inspected(serde_json::json!({
+            "Status": "dead", "Running": false, "Dead": true, "ExitCode": 255,
+            "Error": "driver failed programming external connectivity"
+        }));
+        assert_eq!(dead.reason, Dead);
+        assert_eq!(dead.status, HealthStatus::Unhealthy);
+        assert_eq!(
+            dead.message.as_deref(),
+            Some("driver failed programming external connectivity")
+        );
+    }
+
+    #[test]
+    fn test_paused_and_restarting() {
+        let paused = inspected(serde_json::json!({ "Status": "paused", "Paused": true }));
+        assert_eq!(paused.reason, Paused);
+        assert_eq!(paused.status, HealthStatus::Degraded);
+        assert_eq!(paused.process_status, ProcessStatus::Running);
+
+        let restarting = inspected(serde_json::json!({
+            "Status": "restarting", "Running": false, "Restarting": true, "ExitCode": 2
+        }));
+        assert_eq!(restarting.reason, Restarting);
+        assert_eq!(restarting.status, HealthStatus::Unhealthy);
+        assert_eq!(restarting.process_status, ProcessStatus::Starting);
+        assert_eq!(restarting.exit_code, Some(2));
+
+        let oom_loop = inspected(serde_json::json!({
+            "Status": "restarting", "Restarting": true, "OOMKilled": true, "ExitCode": 137
+        }));
+        assert_eq!(oom_loop.reason, OomKilled);
+    }
+
+    #[test]
+    fn test_from_summary_status_text() {
+        let cases = [
+            ("running", "Up 2 hours (healthy)", HealthCheckPassing, None),
+            (
+                "running",
+                "Up 5 minutes (unhealthy)",
+                HealthCheckFailing,
+                None,
+            ),
+            (
+                "running",
+                "Up 3 seconds (health: starting)",
+                HealthCheckStarting,
+                None,
+            ),
+            ("running", "Up 2 hours", NoHealthCheck, None),
+            ("paused", "Up 2 hours (Paused)", Paused, None),
+            (
+                "restarting",
+                "Restarting (1) 4 seconds ago",
+                Restarting,
+                Some(1),
+            ),
+            (
+                "exited",
+                "Exited (1) 5 minutes ago",
+                ExitedWithError,
+                Some(1),
+            ),
+            (
+                "exited",
+                "Exited (137) 5 minutes ago",
+                ExitedWithError,
+                Some(137),
+            ),
+            ("exited", "Exited (0) About an hour ago", Exited, Some(0)),
+            ("created", "Created", Created, None),
+            ("dead", "Dead", Dead, None),
+            ("removing", "Removal In Progress", Dead, None),
+        ];
+        for (state, status, reason, exit_code) in cases {
+            let health = ContainerHealth::from_summary(state, status);
+            assert_eq!(health.reason, reason, "{}", status);
+            assert_eq!(health.exit_code, exit_code, "{}", status);
+            assert_eq!(health.failing_streak, None);
+        }
+    }
+}
@@ -5,8 +5,11 @@
 //! ## Features
 //! - List Docker containers (running and stopped)
 //! - Monitor container statistics (CPU, memory, network, I/O)
-//! - Control containers (start, stop, restart, pause, unpause)
+//! - Control containers (start, stop, restart, pause, unpause, remove)
+//! - Inspect container configuration and run one-shot commands
+//! - Report container health in the terms used for managed processes
 //! - Get Docker system information
+//! - Watch the daemon and notify the frontend when it starts or stops
 //!
 //! ## Example
 //!
@@ -28,20 +31,58 @@
 //! }
 //! ```
 
+#[cfg(target_os = "linux")]
+mod daemon;
+mod health;
 mod monitor;
+mod sampler;
 mod types;
+mod watcher;
 
 pub use monitor::DockerMonitor;
+pub use sampler::{
+    ContainerStatsSampler, StatsHistory, CONTAINER_STATS_EVENT, MAX_SAMPLES_PER_CONTAINER,
+};
 pub use types::*;
+pub use watcher::{
+    DockerAvailability, DockerAvailabilityEvent, DockerWatcher, DOCKER_AVAILABILITY_EVENT,
+};
 
 use crate::error::Result;
+use crate::features::service_detection::ServiceDetectorState;
 use std::sync::Arc;
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
+/// Exec timeout used when the frontend does not pass one
+const DEFAULT_EXEC_TIMEOUT_SECS: u64 = 30;
+
 /// Application state for Docker monitor
 pub struct DockerMonitorState(pub Arc<Mutex<DockerMonitor>>);
 
+/// Application state for the Docker availability watcher
+pub struct DockerWatcherState(pub Arc<std::sync::Mutex<DockerWatcher>>);
+
+fn lock_watcher(state: &DockerWatcherState) -> std::sync::MutexGuard<'_, DockerWatcher> {
+    state.0.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock Docker watcher: {}", e);
+        e.into_inner()
+    })
+}
+
+/// Make service detection read the ports containers publish again after an
+/// operation that may have changed them
+fn ports_may_have_changed(
+    detector: &ServiceDetectorState,
+    result: Result<ContainerOperationResult>,
+) -> Result<ContainerOperationResult> {
+    if matches!(&result, Ok(outcome) if outcome.success) {
+        detector.lock().invalidate_containers();
+    }
+    result
+}
+
 /// Get Docker system information
 #[tauri::command]
 pub async fn get_docker_info(state: State<'_, DockerMonitorState>) -> Result<DockerInfo> {
@@ -61,6 +102,22 @@ pub async fn reconnect_docker(state: State<'_, DockerMonitorState>) -> Result<St
     }
 }
 
+/// Get daemon availability and the detected Docker runtime
+#[tauri::command]
+pub async fn get_docker_runtime_status(
+    state: State<'_, DockerMonitorState>,
+    watcher: State<'_, DockerWatcherState>,
+) -> Result<DockerRuntimeStatus> {
+    let available = state.0.lock().await.refresh_availability().await;
+    let starting = !available && lock_watcher(&watcher).is_starting();
+
+    Ok(DockerRuntimeStatus {
+        available,
+        starting,
+        runtime: detect_docker_runtime().await,
+    })
+}
+
 /// List Docker containers
 #[tauri::command]
 pub async fn list_docker_containers(
@@ -88,36 +145,78 @@ pub async fn get_docker_container_stats(
     monitor.get_container_stats(&container_id).await
 }
 
+/// Start background stats sampling for the given containers
+#[tauri::command]
+pub async fn start_container_stats_sampling(
+    state: State<'_, DockerMonitorState>,
+    app: AppHandle,
+    container_ids: Vec<String>,
+    interval_ms: u64,
+) -> Result<()> {
+    let mut monitor = state.0.lock().await;
+    monitor.start_stats_sampling(container_ids, Duration::from_millis(interval_ms), app)
+}
+
+/// Stop background stats sampling (all containers when none are given)
+#[tauri::command]
+pub async fn stop_container_stats_sampling(
+    state: State<'_, DockerMonitorState>,
+    container_ids: Option<Vec<String>>,
+) -> Result<usize> {
+    let mut monitor = state.0.lock().await;
+    Ok(monitor.stop_stats_sampling(container_ids.as_deref()))
+}
+
+/// Get recorded stats for a sampled container
+#[tauri::command]
+pub async fn get_container_stats_history(
+    state: State<'_, DockerMonitorState>,
+    container_id: String,
+    seconds: u64,
+) -> Result<Vec<ContainerStats>> {
+    let monitor = state.0.lock().await;
+    Ok(monitor.get_container_stats_history(&container_id, seconds))
+}
+
 /// Start a Docker container
 #[tauri::command]
 pub async fn start_docker_container(
     state: State<'_, DockerMonitorState>,
+    detector: State<'_, ServiceDetectorState>,
     container_id: String,
 ) -> Result<ContainerOperationResult> {
     let monitor = state.0.lock().await;
-    monitor.start_container(&container_id).await
+    ports_may_have_changed(&detector, monitor.start_container(&container_id).await)
 }
 
 /// Stop a Docker container
 #[tauri::command]
 pub async fn stop_docker_container(
     state: State<'_, DockerMonitorState>,
+    detector: State<'_, ServiceDetectorState>,
     container_id: String,
     timeout: Option<i64>,
 ) -> Result<ContainerOperationResult> {
     let monitor = state.0.lock().await;
-    monitor.stop_container(&container_id, timeout).await
+    ports_may_have_changed(
+        &detector,
+        monitor.stop_container(&container_id, timeout).await,
+    )
 }
 
 /// Restart a Docker container
 #[tauri::command]
 pub async fn restart_docker_container(
     state: State<'_, DockerMonitorState>,
+    detector: State<'_, ServiceDetectorState>,
     container_id: String,
     timeout: Option<i64>,
 ) -> Result<ContainerOperationResult> {
     let monitor = state.0.lock().await;
-    monitor.restart_container(&container_id, timeout).await
+    ports_may_have_changed(
+        &detector,
+        monitor.restart_container(&container_id, timeout).await,
+    )
 }
 
 /// Pause a Docker container
@@ -140,7 +239,62 @@ pub async fn unpause_docker_container(
     monitor.unpause_container(&container_id).await
 }
 
-/// Detect which Docker runtime is available (Docker Desktop, Colima, Podman, etc.)
+/// Inspect a Docker container's configuration and state
+#[tauri::command]
+pub async fn inspect_docker_container(
+    state: State<'_, DockerMonitorState>,
+    container_id: String,
+) -> Result<Option<ContainerDetails>> {
+    let monitor = state.0.lock().await;
+    monitor.inspect_container(&container_id).await
+}
+
+/// Get a Docker container's health, mapped onto process health and status
+#[tauri::command]
+pub async fn get_container_health(
+    state: State<'_, DockerMonitorState>,
+    container_id: String,
+) -> Result<Option<ContainerHealth>> {
+    let monitor = state.0.lock().await;
+    monitor.container_health(&container_id).await
+}
+
+/// Remove a Docker container
+#[tauri::command]
+pub async fn remove_docker_container(
+    state: State<'_, DockerMonitorState>,
+    detector: State<'_, ServiceDetectorState>,
+    container_id: String,
+    force: Option<bool>,
+) -> Result<ContainerOperationResult> {
+    let monitor = state.0.lock().await;
+    let result = monitor
+        .remove_container(&container_id, force.unwrap_or(false))
+        .await;
+    ports_may_have_changed(&detector, result)
+}
+
+/// Run a one-shot command inside a Docker container
+#[tauri::command]
+pub async fn exec_in_docker_container(
+    state: State<'_, DockerMonitorState>,
+    container_id: String,
+    cmd: String,
+    args: Option<Vec<String>>,
+    timeout_secs: Option<u64>,
+) -> Result<ExecResult> {
+    let monitor = state.0.lock().await;
+    monitor
+        .exec_in_container(
+            &container_id,
+            &cmd,
+            &args.unwrap_or_default(),
+            Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_EXEC_TIMEOUT_SECS)),
+        )
+        .await
+}
+
+/// Detect which Docker runtime is available (Docker Desktop, Colima, dockerd, Podman, etc.)
 async fn detect_docker_runtime() -> Option<String> {
     use std::process::Command;
 
@@ -159,6 +313,14 @@ async fn detect_docker_runtime() -> Option<String> {
         }
     }
 
+    // Check for a systemd-managed dockerd
+    #[cfg(target_os = "linux")]
+    {
+        if daemon::has_systemd_docker(&daemon::SystemCommandRunner) {
+            return Some("dockerd".to_string());
+        }
+    }
+
     // Check if Podman is available
     if let Ok(output) = Command::new("podman").arg("--version").output() {
         if output.status.success() {
@@ -169,18 +331,27 @@ async fn detect_docker_runtime() -> Option<String> {
     None
 }
 
-/// Start Docker daemon (supports Docker Desktop, Colima, Podman)
+/// Start Docker daemon (supports Docker Desktop, Colima, Podman, systemd dockerd)
+///
+/// The availability watcher reports "starting" until the daemon answers.
 #[tauri::command]
-pub async fn start_docker_desktop() -> Result<String> {
-    use std::process::Command;
+pub async fn start_docker_desktop(watcher: State<'_, DockerWatcherState>) -> Result<String> {
+    let message = launch_docker_runtime().await?;
+    lock_watcher(&watcher).mark_starting();
+    Ok(message)
+}
 
+/// Launch whichever Docker runtime is installed
+async fn launch_docker_runtime() -> Result<String> {
     tracing::info!("Attempting to start Docker daemon...");
 
-    // Detect which runtime to use
-    let runtime = detect_docker_runtime().await;
-
     #[cfg(target_os = "macos")]
     {
+        use std::process::Command;
+
+        // Detect which runtime to use
+        let runtime = detect_docker_runtime().await;
+
         match runtime.as_deref() {
             Some("colima") => {
                 tracing::info!("Starting Colima...");
@@ -278,26 +449,31 @@ pub async fn start_docker_desktop() -> Result<String> {
         }
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[cfg(target_os = "linux")]
+    {
+        control_linux_daemon(daemon::DaemonAction::Start).await
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
         Err(crate::error::SentinelError::Other(
-            "Docker Desktop control is only supported on macOS and Windows".to_string(),
+            "Docker Desktop control is only supported on macOS, Windows and Linux".to_string(),
         ))
     }
 }
 
-/// Stop Docker daemon (supports Docker Desktop, Colima, Podman)
+/// Stop Docker daemon (supports Docker Desktop, Colima, Podman, systemd dockerd)
 #[tauri::command]
 pub async fn stop_docker_desktop() -> Result<String> {
-    use std::process::Command;
-
     tracing::info!("Attempting to stop Docker daemon...");
 
-    // Detect which runtime is running
-    let runtime = detect_docker_runtime().await;
-
     #[cfg(target_os = "macos")]
     {
+        use std::process::Command;
+
+        // Detect which runtime is running
+        let runtime = detect_docker_runtime().await;
+
         match runtime.as_deref() {
             Some("colima") => {
                 tracing::info!("Stopping Colima...");
@@ -374,21 +550,37 @@ pub async fn stop_docker_desktop() -> Result<String> {
         }
     }
 
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        control_linux_daemon(daemon::DaemonAction::Stop).await
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         Err(crate::error::SentinelError::Other(
-            "Docker daemon control is only supported on macOS currently".to_string(),
+            "Docker daemon control is only supported on macOS and Linux currently".to_string(),
         ))
     }
 }
 
+/// Start or stop dockerd/Podman/Colima on Linux
+#[cfg(target_os = "linux")]
+async fn control_linux_daemon(action: daemon::DaemonAction) -> Result<String> {
+    // systemctl blocks until the unit is up, which can take a while
+    tokio::task::spawn_blocking(move || {
+        daemon::control_daemon(&daemon::SystemCommandRunner, action)
+    })
+    .await
+    .map_err(|e| crate::error::SentinelError::Other(format!("Docker control task failed: {}", e)))?
+}
+
 /// Restart Docker Desktop
 #[tauri::command]
-pub async fn restart_docker_desktop() -> Result<String> {
+pub async fn restart_docker_desktop(watcher: State<'_, DockerWatcherState>) -> Result<String> {
     stop_docker_desktop().await?;
     // Wait a bit for Docker to stop
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-    start_docker_desktop().await
+    start_docker_desktop(watcher).await
 }
 
 #[cfg(test)]
@@ -1,19 +1,36 @@
 //! Docker container monitoring implementation
 
+use super::sampler::ContainerStatsSampler;
 use super::types::{
-    ContainerInfo, ContainerOperationResult, ContainerStats, DockerInfo, ImageInfo, PortMapping,
+    ContainerDetails, ContainerExitStatus, ContainerHealth, ContainerInfo, ContainerLogLine,
+    ContainerNetworkSettings, ContainerOperationResult, ContainerStats, DockerInfo, ExecResult,
+    HealthInfo, ImageInfo, MountInfo, NetworkEndpoint, PortMapping, RestartPolicyInfo,
 };
-use bollard::container::{ListContainersOptions, Stats, StatsOptions};
+use crate::error::SentinelError;
+use bollard::container::{
+    ListContainersOptions, LogOutput, LogsOptions, RemoveContainerOptions, Stats, StatsOptions,
+};
+use bollard::exec::{CreateExecOptions, StartExecResults};
 use bollard::image::ListImagesOptions;
-use bollard::models::{ContainerSummary, ImageSummary};
+use bollard::models::{ContainerInspectResponse, ContainerSummary, ImageSummary, PortMap};
 use bollard::system::Version;
 use bollard::Docker;
 use chrono::{DateTime, Utc};
+use futures_util::stream::{Stream, StreamExt};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Output kept per stream for a single exec
+const MAX_EXEC_OUTPUT: usize = 1024 * 1024;
+
+/// Longest wait for the daemon to answer a ping
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Monitors Docker containers and provides control operations
 pub struct DockerMonitor {
     docker: Option<Docker>,
     available: bool,
+    sampler: ContainerStatsSampler,
 }
 
 impl Default for DockerMonitor {
@@ -26,34 +43,8 @@ impl DockerMonitor {
     /// Create a new Docker monitor
     /// Tries multiple connection strategies for better macOS compatibility
     pub fn new() -> Self {
-        // Try multiple connection methods in order:
-        // 1. Local defaults (works on Linux and some Docker Desktop installations)
-        // 2. Docker Desktop on macOS (~/.docker/run/docker.sock)
-        // 3. Unix socket at /var/run/docker.sock (fallback)
-
-        let docker = Docker::connect_with_local_defaults()
-            .or_else(|_| {
-                // Try macOS Docker Desktop socket path
-                #[cfg(target_os = "macos")]
-                {
-                    use bollard::API_DEFAULT_VERSION;
-                    let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
-                    let socket_path = format!("{}/.docker/run/docker.sock", home);
-                    tracing::debug!("Trying Docker socket at: {}", socket_path);
-                    Docker::connect_with_unix(&socket_path, 120, API_DEFAULT_VERSION)
-                }
-                #[cfg(not(target_os = "macos"))]
-                {
-                    Err(bollard::errors::Error::DockerConnectionError)
-                }
-            })
-            .or_else(|_| {
-                // Final fallback: try unix defaults
-                tracing::debug!("Trying Docker unix defaults");
-                Docker::connect_with_unix_defaults()
-            });
-
-        let available = docker.is_ok();
+        let docker = Self::connect();
+        let available = docker.is_some();
 
         if !available {
             tracing::warn!("Docker connection failed, feature will be unavailable");
@@ -62,11 +53,50 @@ impl DockerMonitor {
         }
 
         Self {
-            docker: docker.ok(),
+            docker,
             available,
+            sampler: ContainerStatsSampler::new(),
+        }
+    }
+
+    /// A monitor without a daemon, as on a machine without Docker
+    #[cfg(test)]
+    pub(crate) fn disconnected() -> Self {
+        Self {
+            docker: None,
+            available: false,
+            sampler: ContainerStatsSampler::new(),
         }
     }
 
+    /// Build a Docker client without checking that the daemon answers
+    fn connect() -> Option<Docker> {
+        // Try multiple connection methods in order:
+        // 1. Local defaults (works on Linux and some Docker Desktop installations)
+        // 2. Docker Desktop on macOS (~/.docker/run/docker.sock)
+        // 3. Unix socket at /var/run/docker.sock (fallback)
+
+        let docker = Docker::connect_with_local_defaults();
+
+        // Try macOS Docker Desktop socket path
+        #[cfg(target_os = "macos")]
+        let docker = docker.or_else(|_| {
+            use bollard::API_DEFAULT_VERSION;
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
+            let socket_path = format!("{}/.docker/run/docker.sock", home);
+            tracing::debug!("Trying Docker socket at: {}", socket_path);
+            Docker::connect_with_unix(&socket_path, 120, API_DEFAULT_VERSION)
+        });
+
+        docker
+            .or_else(|_| {
+                // Final fallback: try unix defaults
+                tracing::debug!("Trying Docker unix defaults");
+                Docker::connect_with_unix_defaults()
+            })
+            .ok()
+    }
+
     /// Check if Docker is available
     pub fn is_available(&self) -> bool {
         self.available
@@ -78,6 +108,41 @@ impl DockerMonitor {
         let new_monitor = Self::new();
         self.docker = new_monitor.docker;
         self.available = new_monitor.available;
+
+        // Streams opened against the old client die with the daemon
+        if let Some(docker) = self.docker.as_ref().filter(|_| self.available) {
+            self.sampler.restart(docker);
+        }
+    }
+
+    /// Ping the daemon and update availability
+    ///
+    /// A daemon that stops answering marks the monitor unavailable; once it
+    /// answers again the client is rebuilt with the same strategies as `new()`.
+    pub async fn refresh_availability(&mut self) -> bool {
+        if self.available {
+            if let Some(docker) = &self.docker {
+                if ping(docker).await {
+                    return true;
+                }
+            }
+            tracing::warn!("Docker daemon stopped responding");
+            self.available = false;
+            return false;
+        }
+
+        let Some(docker) = Self::connect() else {
+            return false;
+        };
+        if !ping(&docker).await {
+            return false;
+        }
+
+        tracing::info!("Docker daemon is reachable");
+        self.sampler.restart(&docker);
+        self.docker = Some(docker);
+        self.available = true;
+        true
     }
 
     /// Get Docker system information
@@ -142,7 +207,15 @@ impl DockerMonitor {
 
         let mut result = Vec::new();
         for container in containers {
-            result.push(self.convert_container_summary(container));
+            let mut info = self.convert_container_summary(container);
+            // 137 is SIGKILL, which is what the OOM killer sends; only an
+            // inspect tells it apart from a plain `docker kill`
+            if info.health.exit_code == Some(137) {
+                if let Ok(details) = docker.inspect_container(&info.full_id, None).await {
+                    info.health = ContainerHealth::from_state(&details.state.unwrap_or_default());
+                }
+            }
+            result.push(info);
         }
 
         Ok(result)
@@ -191,10 +264,9 @@ impl DockerMonitor {
         let mut stats_stream = docker.stats(container_id, Some(options));
 
         // Get first (and only) stats result
-        use futures_util::stream::StreamExt;
         if let Some(result) = stats_stream.next().await {
             match result {
-                Ok(stats) => Ok(Some(self.convert_stats(container_id, stats))),
+                Ok(stats) => Ok(Some(convert_stats(container_id, stats))),
                 Err(e) => {
                     tracing::warn!("Failed to get stats for container {}: {}", container_id, e);
                     Ok(None)
@@ -205,6 +277,93 @@ impl DockerMonitor {
         }
     }
 
+    /// Measure stats for several containers at once
+    ///
+    /// Unlike [`get_container_stats`](Self::get_container_stats), the daemon
+    /// measures CPU usage over about a second, so it isn't reported as 0.
+    /// The returned future doesn't borrow the monitor, so it can be awaited
+    /// after releasing a lock on it. Containers that can't be measured are
+    /// left out.
+    pub fn measure_stats(
+        &self,
+        container_ids: Vec<String>,
+    ) -> impl std::future::Future<Output = Vec<ContainerStats>> + Send + 'static {
+        let docker = self.docker.clone().filter(|_| self.available);
+        async move {
+            let Some(docker) = docker else {
+                return Vec::new();
+            };
+            let measurements = container_ids.into_iter().map(|container_id| {
+                let docker = docker.clone();
+                async move {
+                    let options = StatsOptions {
+                        stream: false,
+                        one_shot: false,
+                    };
+                    match docker.stats(&container_id, Some(options)).next().await {
+                        Some(Ok(stats)) => Some(convert_stats(&container_id, stats)),
+                        Some(Err(e)) => {
+                            tracing::debug!("Failed to measure container {}: {}", container_id, e);
+                            None
+                        }
+                        None => None,
+                    }
+                }
+            });
+            futures_util::future::join_all(measurements)
+                .await
+                .into_iter()
+                .flatten()
+                .collect()
+        }
+    }
+
+    /// Start streaming stats for the given containers in the background
+    ///
+    /// Each recorded sample is kept in a bounded per-container history and
+    /// emitted as a `container-stats` event.
+    pub fn start_stats_sampling(
+        &mut self,
+        container_ids: Vec<String>,
+        interval: Duration,
+        app: AppHandle,
+    ) -> crate::error::Result<()> {
+        let docker = match self.docker.as_ref() {
+            Some(docker) if self.available => docker,
+            _ => {
+                return Err(SentinelError::DockerError(
+                    "Docker is not available".to_string(),
+                ))
+            }
+        };
+        self.sampler.start(docker, container_ids, interval, app)
+    }
+
+    /// Stop background stats sampling
+    ///
+    /// Stops every sampled container when `container_ids` is `None`.
+    /// Returns how many containers were stopped.
+    pub fn stop_stats_sampling(&mut self, container_ids: Option<&[String]>) -> usize {
+        match container_ids {
+            Some(ids) => self.sampler.stop(ids),
+            None => self.sampler.stop_all(),
+        }
+    }
+
+    /// IDs of containers with an active stats stream
+    pub fn sampled_containers(&mut self) -> Vec<String> {
+        self.sampler.sampled_containers()
+    }
+
+    /// Recorded stats for a container from the last `seconds` seconds
+    pub fn get_container_stats_history(
+        &self,
+        container_id: &str,
+        seconds: u64,
+    ) -> Vec<ContainerStats> {
+        self.sampler.history(container_id, seconds)
+    }
+
     /// Start a container
     pub async fn start_container(
         &self,
@@ -379,6 +538,244 @@ impl DockerMonitor {
         }
     }
 
+    /// Find the running container that publishes `port` on the host
+    pub async fn find_container_by_port(
+        &self,
+        port: u16,
+    ) -> crate::error::Result<Option<ContainerInfo>> {
+        let containers = self.list_containers(false).await?;
+        Ok(container_for_port(containers, port))
+    }
+
+    /// Stream a container's logs as parsed lines
+    ///
+    /// `tail` limits the backlog to the last N lines (all lines when `None`).
+    /// With `follow` the stream stays open until the container stops.
+    /// Returns `None` when Docker is unavailable.
+    pub fn stream_logs(
+        &self,
+        container_id: &str,
+        tail: Option<usize>,
+        follow: bool,
+    ) -> Option<impl Stream<Item = crate::error::Result<Vec<ContainerLogLine>>> + Send + 'static>
+    {
+        if !self.available {
+            return None;
+        }
+        let docker = self.docker.as_ref()?;
+
+        let options = LogsOptions::<String> {
+            follow,
+            stdout: true,
+            stderr: true,
+            timestamps: true,
+            tail: tail.map_or_else(|| "all".to_string(), |n| n.to_string()),
+            ..Default::default()
+        };
+
+        Some(docker.logs(container_id, Some(options)).map(|chunk| {
+            chunk
+                .map(|output| parse_log_output(&output))
+                .map_err(Into::into)
+        }))
+    }
+
+    /// Get the current state and exit code of a container
+    pub async fn get_exit_status(
+        &self,
+        container_id: &str,
+    ) -> crate::error::Result<Option<ContainerExitStatus>> {
+        if !self.available || self.docker.is_none() {
+            return Ok(None);
+        }
+
+        let docker = self.docker.as_ref().unwrap();
+
+        let details = docker.inspect_container(container_id, None).await?;
+        Ok(details.state.map(|state| ContainerExitStatus {
+            state: state
+                .status
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            exit_code: if state.running == Some(true) {
+                None
+            } else {
+                state.exit_code
+            },
+        }))
+    }
+
+    /// Get a container's health, mapped onto process health and status
+    pub async fn container_health(
+        &self,
+        container_id: &str,
+    ) -> crate::error::Result<Option<ContainerHealth>> {
+        if !self.available || self.docker.is_none() {
+            return Ok(None);
+        }
+
+        let docker = self.docker.as_ref().unwrap();
+
+        let details = docker.inspect_container(container_id, None).await?;
+        Ok(Some(ContainerHealth::from_state(
+            &details.state.unwrap_or_default(),
+        )))
+    }
+
+    /// Get detailed configuration and state for a container
+    pub async fn inspect_container(
+        &self,
+        container_id: &str,
+    ) -> crate::error::Result<Option<ContainerDetails>> {
+        if !self.available || self.docker.is_none() {
+            return Ok(None);
+        }
+
+        let docker = self.docker.as_ref().unwrap();
+
+        let details = docker.inspect_container(container_id, None).await?;
+        Ok(Some(convert_container_details(details)))
+    }
+
+    /// Remove a container
+    pub async fn remove_container(
+        &self,
+        container_id: &str,
+        force: bool,
+    ) -> crate::error::Result<ContainerOperationResult> {
+        if !self.available || self.docker.is_none() {
+            return Ok(ContainerOperationResult {
+                success: false,
+                container_id: container_id.to_string(),
+                operation: "remove".to_string(),
+                error: Some("Docker is not available".to_string()),
+            });
+        }
+
+        let docker = self.docker.as_ref().unwrap();
+
+        let options = RemoveContainerOptions {
+            force,
+            ..Default::default()
+        };
+
+        match docker.remove_container(container_id, Some(options)).await {
+            Ok(_) => Ok(ContainerOperationResult {
+                success: true,
+                container_id: container_id.to_string(),
+                operation: "remove".to_string(),
+                error: None,
+            }),
+            Err(e) => Ok(ContainerOperationResult {
+                success: false,
+                container_id: container_id.to_string(),
+                operation: "remove".to_string(),
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    /// Run a one-shot command inside a running container
+    ///
+    /// Output is collected until the command exits or `timeout` expires;
+    /// each stream keeps at most 1 MiB.
+    pub async fn exec_in_container(
+        &self,
+        container_id: &str,
+        cmd: &str,
+        args: &[String],
+        timeout: Duration,
+    ) -> crate::error::Result<ExecResult> {
+        let failed = |error: String| ExecResult {
+            success: false,
+            container_id: container_id.to_string(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            timed_out: false,
+            error: Some(error),
+        };
+
+        if !self.available || self.docker.is_none() {
+            return Ok(failed("Docker is not available".to_string()));
+        }
+
+        let docker = self.docker.as_ref().unwrap();
+
+        let mut command = vec![cmd.to_string()];
+        command.extend(args.iter().cloned());
+
+        let exec = match docker
+            .create_exec(
+                container_id,
+                CreateExecOptions {
+                    cmd: Some(command),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+        {
+            Ok(exec) => exec,
+            Err(e) => return Ok(failed(e.to_string())),
+        };
+
+        let mut output = match docker.start_exec(&exec.id, None).await {
+            Ok(StartExecResults::Attached { output, .. }) => output,
+            Ok(StartExecResults::Detached) => {
+                return Ok(failed("Exec started detached".to_string()))
+            }
+            Err(e) => return Ok(failed(e.to_string())),
+        };
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut timed_out = false;
+
+        loop {
+            match tokio::time::timeout_at(deadline, output.next()).await {
+                Ok(Some(Ok(chunk))) => {
+                    let buffer = match chunk {
+                        LogOutput::StdErr { .. } => &mut stderr,
+                        _ => &mut stdout,
+                    };
+                    append_capped(buffer, chunk.as_ref());
+                }
+                Ok(Some(Err(e))) => {
+                    tracing::warn!("Exec output for {} failed: {}", container_id, e);
+                    break;
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    timed_out = true;
+                    break;
+                }
+            }
+        }
+
+        let exit_code = if timed_out {
+            None
+        } else {
+            docker
+                .inspect_exec(&exec.id)
+                .await
+                .ok()
+                .and_then(|inspect| inspect.exit_code)
+        };
+
+        Ok(ExecResult {
+            success: exit_code == Some(0),
+            container_id: container_id.to_string(),
+            exit_code,
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+            timed_out,
+            error: timed_out.then(|| format!("Command timed out after {:?}", timeout)),
+        })
+    }
+
     /// Convert bollard ContainerSummary to our ContainerInfo
     fn convert_container_summary(&self, summary: ContainerSummary) -> ContainerInfo {
         let id = summary.id.clone().unwrap_or_default();
@@ -444,6 +841,8 @@ impl DockerMonitor {
             .map(|l| l.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
             .unwrap_or_default();
 
+        let health = ContainerHealth::from_summary(&state, &status);
+
         ContainerInfo {
             id: short_id,
             full_id: id,
@@ -459,99 +858,7 @@ impl DockerMonitor {
             network_tx_bytes: None,
             created,
             labels,
-        }
-    }
-
-    /// Convert bollard Stats to our ContainerStats
-    fn convert_stats(&self, container_id: &str, stats: Stats) -> ContainerStats {
-        // Calculate CPU percentage
-        let cpu_percent = {
-            let cpu_stats = &stats.cpu_stats;
-            let precpu_stats = &stats.precpu_stats;
-
-            let cpu_delta =
-                cpu_stats.cpu_usage.total_usage as f64 - precpu_stats.cpu_usage.total_usage as f64;
-            let system_delta = cpu_stats.system_cpu_usage.unwrap_or(0) as f64
-                - precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
-            let num_cpus = cpu_stats.online_cpus.unwrap_or_else(|| {
-                cpu_stats
-                    .cpu_usage
-                    .percpu_usage
-                    .as_ref()
-                    .map(|v| v.len() as u64)
-                    .unwrap_or(1)
-            }) as f64;
-
-            if system_delta > 0.0 && cpu_delta > 0.0 {
-                (cpu_delta / system_delta) * num_cpus * 100.0
-            } else {
-                0.0
-            }
-        };
-
-        // Memory stats
-        let memory_usage = stats.memory_stats.usage.unwrap_or(0);
-        let memory_limit = stats.memory_stats.limit.unwrap_or(0);
-        let memory_percent = if memory_limit > 0 {
-            (memory_usage as f64 / memory_limit as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        // Network stats
-        let (network_rx_bytes, network_tx_bytes) = stats
-            .networks
-            .as_ref()
-            .map(|networks| {
-                networks.values().fold((0u64, 0u64), |(rx, tx), net| {
-                    (rx + net.rx_bytes, tx + net.tx_bytes)
-                })
-            })
-            .unwrap_or((0, 0));
-
-        // Block I/O stats
-        let (block_io_read, block_io_write) = {
-            let blkio = &stats.blkio_stats;
-            let read = blkio
-                .io_service_bytes_recursive
-                .as_ref()
-                .map(|v| {
-                    v.iter()
-                        .filter(|s| s.op == "read" || s.op == "Read")
-                        .map(|s| s.value)
-                        .sum()
-                })
-                .unwrap_or(0);
-
-            let write = blkio
-                .io_service_bytes_recursive
-                .as_ref()
-                .map(|v| {
-                    v.iter()
-                        .filter(|s| s.op == "write" || s.op == "Write")
-                        .map(|s| s.value)
-                        .sum()
-                })
-                .unwrap_or(0);
-
-            (read, write)
-        };
-
-        // PIDs
-        let pids = stats.pids_stats.current.unwrap_or(0);
-
-        ContainerStats {
-            container_id: container_id.to_string(),
-            cpu_percent,
-            memory_usage,
-            memory_limit,
-            memory_percent,
-            network_rx_bytes,
-            network_tx_bytes,
-            block_io_read,
-            block_io_write,
-            pids,
-            timestamp: Utc::now(),
+            health,
         }
     }
 
@@ -593,6 +900,302 @@ impl DockerMonitor {
     }
 }
 
+/// Convert bollard Stats to our ContainerStats
+pub(super) fn convert_stats(container_id: &str, stats: Stats) -> ContainerStats {
+    // Calculate CPU percentage
+    let cpu_percent = {
+        let cpu_stats = &stats.cpu_stats;
+        let precpu_stats = &stats.precpu_stats;
+
+        let cpu_delta =
+            cpu_stats.cpu_usage.total_usage as f64 - precpu_stats.cpu_usage.total_usage as f64;
+        let system_delta = cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+            - precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+        let num_cpus = cpu_stats.online_cpus.unwrap_or_else(|| {
+            cpu_stats
+                .cpu_usage
+                .percpu_usage
+                .as_ref()
+                .map(|v| v.len() as u64)
+                .unwrap_or(1)
+        }) as f64;
+
+        if system_delta > 0.0 && cpu_delta > 0.0 {
+            (cpu_delta / system_delta) * num_cpus * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    // Memory stats
+    let memory_usage = stats.memory_stats.usage.unwrap_or(0);
+    let memory_limit = stats.memory_stats.limit.unwrap_or(0);
+    let memory_percent = if memory_limit > 0 {
+        (memory_usage as f64 / memory_limit as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    // Network stats
+    let (network_rx_bytes, network_tx_bytes) = stats
+        .networks
+        .as_ref()
+        .map(|networks| {
+            networks.values().fold((0u64, 0u64), |(rx, tx), net| {
+                (rx + net.rx_bytes, tx + net.tx_bytes)
+            })
+        })
+        .unwrap_or((0, 0));
+
+    // Block I/O stats
+    let (block_io_read, block_io_write) = {
+        let blkio = &stats.blkio_stats;
+        let read = blkio
+            .io_service_bytes_recursive
+            .as_ref()
+            .map(|v| {
+                v.iter()
+                    .filter(|s| s.op == "read" || s.op == "Read")
+                    .map(|s| s.value)
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        let write = blkio
+            .io_service_bytes_recursive
+            .as_ref()
+            .map(|v| {
+                v.iter()
+                    .filter(|s| s.op == "write" || s.op == "Write")
+                    .map(|s| s.value)
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        (read, write)
+    };
+
+    // PIDs
+    let pids = stats.pids_stats.current.unwrap_or(0);
+
+    ContainerStats {
+        container_id: container_id.to_string(),
+        cpu_percent,
+        memory_usage,
+        memory_limit,
+        memory_percent,
+        network_rx_bytes,
+        network_tx_bytes,
+        block_io_read,
+        block_io_write,
+        pids,
+        timestamp: Utc::now(),
+    }
+}
+
+/// Convert a bollard inspect response to our ContainerDetails
+fn convert_container_details(details: ContainerInspectResponse) -> ContainerDetails {
+    let full_id = details.id.unwrap_or_default();
+    let id = if full_id.len() > 12 {
+        full_id[..12].to_string()
+    } else {
+        full_id.clone()
+    };
+
+    let config = details.config.unwrap_or_default();
+    let state = details.state.unwrap_or_default();
+
+    let mounts = details
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| MountInfo {
+            mount_type: m
+                .typ
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            source: non_empty(m.source),
+            destination: m.destination.unwrap_or_default(),
+            mode: non_empty(m.mode),
+            read_write: m.rw.unwrap_or(false),
+        })
+        .collect();
+
+    let network = details
+        .network_settings
+        .map(|settings| {
+            let mut networks: Vec<NetworkEndpoint> = settings
+                .networks
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, endpoint)| NetworkEndpoint {
+                    name,
+                    ip_address: non_empty(endpoint.ip_address),
+                    gateway: non_empty(endpoint.gateway),
+                    mac_address: non_empty(endpoint.mac_address),
+                    aliases: endpoint.aliases.unwrap_or_default(),
+                })
+                .collect();
+            networks.sort_by(|a, b| a.name.cmp(&b.name));
+
+            ContainerNetworkSettings {
+                ip_address: non_empty(settings.ip_address),
+                gateway: non_empty(settings.gateway),
+                ports: convert_port_map(settings.ports.unwrap_or_default()),
+                networks,
+            }
+        })
+        .unwrap_or_default();
+
+    let restart_policy = details
+        .host_config
+        .and_then(|host| host.restart_policy)
+        .and_then(|policy| {
+            let name = non_empty(policy.name.map(|n| n.to_string()))?;
+            Some(RestartPolicyInfo {
+                name,
+                max_retry_count: policy.maximum_retry_count,
+            })
+        });
+
+    let health = state.health.and_then(|health| {
+        let status = non_empty(health.status.map(|s| s.to_string()))?;
+        let last = health.log.and_then(|log| log.into_iter().last());
+        Some(HealthInfo {
+            status,
+            failing_streak: health.failing_streak.unwrap_or(0),
+            last_output: last
+                .as_ref()
+                .and_then(|l| l.output.as_ref())
+                .map(|o| o.trim_end().to_string()),
+            last_exit_code: last.and_then(|l| l.exit_code),
+        })
+    });
+
+    ContainerDetails {
+        id,
+        full_id,
+        name: details
+            .name
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+        image: config
+            .image
+            .or(details.image)
+            .unwrap_or_else(|| "unknown".to_string()),
+        state: state
+            .status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+        exit_code: state.exit_code,
+        created: non_empty(details.created),
+        started_at: non_empty(state.started_at),
+        entrypoint: config.entrypoint.unwrap_or_default(),
+        cmd: config.cmd.unwrap_or_default(),
+        working_dir: non_empty(config.working_dir),
+        env: config.env.unwrap_or_default(),
+        mounts,
+        network,
+        restart_policy,
+        health,
+    }
+}
+
+/// Flatten an inspect port map ("80/tcp" -> bindings) into port mappings
+fn convert_port_map(ports: PortMap) -> Vec<PortMapping> {
+    let mut result: Vec<PortMapping> = ports
+        .into_iter()
+        .filter_map(|(key, bindings)| {
+            let (port, protocol) = key.split_once('/').unwrap_or((key.as_str(), "tcp"));
+            let container_port = port.parse().ok()?;
+            let protocol = protocol.to_string();
+
+            let mappings = match bindings.filter(|b| !b.is_empty()) {
+                Some(bindings) => bindings
+                    .into_iter()
+                    .map(|b| PortMapping {
+                        container_port,
+                        host_port: b.host_port.and_then(|p| p.parse().ok()),
+                        protocol: protocol.clone(),
+                        host_ip: non_empty(b.host_ip),
+                    })
+                    .collect(),
+                None => vec![PortMapping {
+                    container_port,
+                    host_port: None,
+                    protocol,
+                    host_ip: None,
+                }],
+            };
+            Some(mappings)
+        })
+        .flatten()
+        .collect();
+
+    result.sort_by_key(|p| (p.container_port, p.host_port));
+    result
+}
+
+/// Docker reports unset strings as "" rather than omitting them
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+/// Append exec output, dropping anything past the per-stream cap
+fn append_capped(buffer: &mut Vec<u8>, data: &[u8]) {
+    let room = MAX_EXEC_OUTPUT.saturating_sub(buffer.len());
+    buffer.extend_from_slice(&data[..data.len().min(room)]);
+}
+
+/// Check that the daemon answers, without waiting on a hung socket
+async fn ping(docker: &Docker) -> bool {
+    matches!(
+        tokio::time::timeout(PING_TIMEOUT, docker.ping()).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Pick the container that publishes `port` on the host
+fn container_for_port(containers: Vec<ContainerInfo>, port: u16) -> Option<ContainerInfo> {
+    containers
+        .into_iter()
+        .find(|c| c.ports.iter().any(|p| p.host_port == Some(port)))
+}
+
+/// Split one chunk of Docker log output into lines
+///
+/// A chunk usually holds a single line, but TTY containers and bursts of
+/// output can deliver several at once.
+fn parse_log_output(output: &LogOutput) -> Vec<ContainerLogLine> {
+    let stream = match output {
+        LogOutput::StdErr { .. } => "docker-stderr",
+        _ => "docker-stdout",
+    };
+
+    String::from_utf8_lossy(output.as_ref())
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (timestamp, line) = split_log_timestamp(line);
+            ContainerLogLine {
+                timestamp,
+                line: line.to_string(),
+                stream: stream.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Strip the RFC 3339 timestamp Docker prepends when `timestamps` is set
+fn split_log_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    if let Some((prefix, rest)) = line.split_once(' ') {
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(prefix) {
+            return (Some(timestamp.with_timezone(&Utc)), rest);
+        }
+    }
+    (None, line)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -623,10 +1226,371 @@ mod tests {
         let monitor = DockerMonitor {
             docker: None,
             available: false,
+            sampler: ContainerStatsSampler::new(),
         };
 
         let result = monitor.start_container("test").await;
         assert!(result.is_ok());
         assert!(!result.unwrap().success);
     }
+
+    #[test]
+    fn test_split_log_timestamp() {
+        let (timestamp, line) =
+            split_log_timestamp("2024-03-05T14:22:01.123456789Z GET /health 200");
+
+        assert_eq!(line, "GET /health 200");
+        let timestamp = timestamp.unwrap();
+        assert_eq!(
+            timestamp.to_rfc3339(),
+            "2024-03-05T14:22:01.123456789+00:00"
+        );
+    }
+
+    #[test]
+    fn test_split_log_timestamp_without_prefix() {
+        let (timestamp, line) = split_log_timestamp("plain output line");
+        assert!(timestamp.is_none());
+        assert_eq!(line, "plain output line");
+    }
+
+    #[test]
+    fn test_parse_log_output_streams_and_lines() {
+        let stdout = LogOutput::StdOut {
+            message: "2024-03-05T14:22:01Z first\n2024-03-05T14:22:02Z second\r\n".into(),
+        };
+        let stderr = LogOutput::StdErr {
+            message: "2024-03-05T14:22:03Z boom\n".into(),
+        };
+
+        let lines = parse_log_output(&stdout);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].line, "first");
+        assert_eq!(lines[1].line, "second");
+        assert!(lines.iter().all(|l| l.stream == "docker-stdout"));
+
+        let lines = parse_log_output(&stderr);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].stream, "docker-stderr");
+        assert_eq!(lines[0].line, "boom");
+    }
+
+    #[test]
+    fn test_container_for_port_matches_host_port() {
+        let container = |id: &str, ports: Vec<(u16, Option<u16>)>| ContainerInfo {
+            id: id.to_string(),
+            full_id: format!("{}-full", id),
+            name: id.to_string(),
+            image: "nginx:latest".to_string(),
+            status: "Up".to_string(),
+            state: "running".to_string(),
+            ports: ports
+                .into_iter()
+                .map(|(container_port, host_port)| PortMapping {
+                    container_port,
+                    host_port,
+                    protocol: "tcp".to_string(),
+                    host_ip: Some("0.0.0.0".to_string()),
+                })
+                .collect(),
+            cpu_percent: None,
+            memory_usage: None,
+            memory_limit: None,
+            network_rx_bytes: None,
+            network_tx_bytes: None,
+            created: Utc::now(),
+            labels: vec![],
+            health: ContainerHealth::from_summary("running", "Up"),
+        };
+        let containers = vec![
+            container("web", vec![(80, Some(8080))]),
+            container("db", vec![(5432, None)]),
+            container("api", vec![(3000, Some(3001)), (9229, Some(9229))]),
+        ];
+
+        let found = container_for_port(containers.clone(), 8080).unwrap();
+        assert_eq!(found.full_id, "web-full");
+        assert_eq!(
+            container_for_port(containers.clone(), 9229).unwrap().id,
+            "api"
+        );
+        // Unpublished container ports are not reachable on the host
+        assert!(container_for_port(containers.clone(), 5432).is_none());
+        assert!(container_for_port(containers, 80).is_none());
+    }
+
+    #[test]
+    fn test_stream_logs_when_docker_unavailable() {
+        let monitor = DockerMonitor {
+            docker: None,
+            available: false,
+            sampler: ContainerStatsSampler::new(),
+        };
+        assert!(monitor.stream_logs("test", Some(10), true).is_none());
+    }
+
+    const INSPECT_FIXTURE: &str = r#"{
+        "Id": "4f66ad9a0b2e8d2f5c1e7a9b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d",
+        "Created": "2024-03-05T14:20:11.123456789Z",
+        "Name": "/api-server",
+        "Image": "sha256:9c7a54a9a43cca047013b82af109fe963fde787f63f9e016fdc3384500c2823d",
+        "State": {
+            "Status": "running",
+            "Running": true,
+            "ExitCode": 0,
+            "StartedAt": "2024-03-05T14:20:12.5Z",
+            "FinishedAt": "0001-01-01T00:00:00Z",
+            "Health": {
+                "Status": "healthy",
+                "FailingStreak": 0,
+                "Log": [
+                    {"ExitCode": 1, "Output": "connection refused\n"},
+                    {"ExitCode": 0, "Output": "ok\n"}
+                ]
+            }
+        },
+        "HostConfig": {
+            "RestartPolicy": {"Name": "on-failure", "MaximumRetryCount": 3}
+        },
+        "Mounts": [
+            {
+                "Type": "bind",
+                "Source": "/home/dev/app",
+                "Destination": "/app",
+                "Mode": "",
+                "RW": true
+            },
+            {
+                "Type": "volume",
+                "Name": "pgdata",
+                "Source": "/var/lib/docker/volumes/pgdata/_data",
+                "Destination": "/data",
+                "Mode": "z",
+                "RW": false
+            }
+        ],
+        "Config": {
+            "Image": "node:20-alpine",
+            "Env": ["NODE_ENV=development", "PORT=3000"],
+            "Cmd": ["node", "server.js"],
+            "Entrypoint": ["docker-entrypoint.sh"],
+            "WorkingDir": "/app"
+        },
+        "NetworkSettings": {
+            "IPAddress": "",
+            "Gateway": "",
+            "Ports": {
+                "3000/tcp": [
+                    {"HostIp": "0.0.0.0", "HostPort": "3000"},
+                    {"HostIp": "::", "HostPort": "3000"}
+                ],
+                "9229/tcp": null
+            },
+            "Networks": {
+                "backend": {
+                    "IPAddress": "172.20.0.3",
+                    "Gateway": "172.20.0.1",
+                    "MacAddress": "02:42:ac:14:00:03",
+                    "Aliases": ["api"]
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_convert_container_details_fixture() {
+        let response: ContainerInspectResponse = serde_json::from_str(INSPECT_FIXTURE).unwrap();
+
+        let details = convert_container_details(response);
+
+        assert_eq!(details.id, "4f66ad9a0b2e");
+        assert_eq!(details.name, "api-server");
+        assert_eq!(details.image, "node:20-alpine");
+        assert_eq!(details.state, "running");
+        assert_eq!(details.entrypoint, vec!["docker-entrypoint.sh"]);
+        assert_eq!(details.cmd, vec!["node", "server.js"]);
+        assert_eq!(details.working_dir.as_deref(), Some("/app"));
+        assert_eq!(details.env, vec!["NODE_ENV=development", "PORT=3000"]);
+
+        assert_eq!(details.mounts.len(), 2);
+        assert_eq!(details.mounts[0].mount_type, "bind");
+        assert_eq!(details.mounts[0].mode, None);
+        assert!(details.mounts[0].read_write);
+        assert_eq!(details.mounts[1].destination, "/data");
+        assert!(!details.mounts[1].read_write);
+
+        let restart = details.restart_policy.unwrap();
+        assert_eq!(restart.name, "on-failure");
+        assert_eq!(restart.max_retry_count, Some(3));
+
+        let health = details.health.unwrap();
+        assert_eq!(health.status, "healthy");
+        assert_eq!(health.last_output.as_deref(), Some("ok"));
+        assert_eq!(health.last_exit_code, Some(0));
+    }
+
+    #[test]
+    fn test_convert_container_details_network() {
+        let response: ContainerInspectResponse = serde_json::from_str(INSPECT_FIXTURE).unwrap();
+
+        let network = convert_container_details(response).network;
+
+        // Empty strings on the default bridge are reported as unset
+        assert_eq!(network.ip_address, None);
+        assert_eq!(network.networks.len(), 1);
+        assert_eq!(network.networks[0].name, "backend");
+        assert_eq!(
+            network.networks[0].ip_address.as_deref(),
+            Some("172.20.0.3")
+        );
+        assert_eq!(network.networks[0].aliases, vec!["api"]);
+
+        let ports: Vec<(u16, Option<u16>)> = network
+            .ports
+            .iter()
+            .map(|p| (p.container_port, p.host_port))
+            .collect();
+        assert_eq!(
+            ports,
+            vec![(3000, Some(3000)), (3000, Some(3000)), (9229, None)]
+        );
+    }
+
+    #[test]
+    fn test_convert_container_details_minimal() {
+        let response: ContainerInspectResponse = serde_json::from_str(
+            r#"{"Id": "abc", "State": {"Status": "exited", "ExitCode": 137}}"#,
+        )
+        .unwrap();
+
+        let details = convert_container_details(response);
+
+        assert_eq!(details.id, "abc");
+        assert_eq!(details.state, "exited");
+        assert_eq!(details.exit_code, Some(137));
+        assert!(details.mounts.is_empty());
+        assert!(details.restart_policy.is_none());
+        assert!(details.health.is_none());
+    }
+
+    #[test]
+    fn test_append_capped() {
+        let mut buffer = vec![0u8; MAX_EXEC_OUTPUT - 2];
+        append_capped(&mut buffer, b"abcd");
+        assert_eq!(buffer.len(), MAX_EXEC_OUTPUT);
+        assert_eq!(&buffer[buffer.len() - 2..], b"ab");
+
+        append_capped(&mut buffer, b"more");
+        assert_eq!(buffer.len(), MAX_EXEC_OUTPUT);
+    }
+
+    #[tokio::test]
+    async fn test_new_operations_when_docker_unavailable() {
+        let monitor = DockerMonitor {
+            docker: None,
+            available: false,
+            sampler: ContainerStatsSampler::new(),
+        };
+
+        assert!(monitor.inspect_container("test").await.unwrap().is_none());
+
+        let removed = monitor.remove_container("test", true).await.unwrap();
+        assert!(!removed.success);
+        assert_eq!(removed.operation, "remove");
+
+        let exec = monitor
+            .exec_in_container("test", "echo", &[], Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(!exec.success);
+        assert!(exec.error.is_some());
+    }
+
+    /// Needs a running Docker daemon; run with `--features docker-tests`
+    #[cfg(feature = "docker-tests")]
+    #[tokio::test]
+    async fn test_inspect_exec_remove_against_docker() {
+        use bollard::container::{Config, CreateContainerOptions};
+        use bollard::image::CreateImageOptions;
+
+        let monitor = DockerMonitor::new();
+        assert!(monitor.is_available(), "Docker daemon is not reachable");
+        let docker = monitor.docker.clone().unwrap();
+
+        let image = "alpine:3.19";
+        docker
+            .create_image(
+                Some(CreateImageOptions {
+                    from_image: image,
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .for_each(|_| async {})
+            .await;
+
+        let name = format!("sentinel-test-{}", std::process::id());
+        let container = docker
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: name.as_str(),
+                    platform: None,
+                }),
+                Config {
+                    image: Some(image),
+                    cmd: Some(vec!["sleep", "60"]),
+                    env: Some(vec!["SENTINEL_TEST=1"]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(
+            monitor
+                .start_container(&container.id)
+                .await
+                .unwrap()
+                .success
+        );
+
+        let details = monitor
+            .inspect_container(&container.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.name, name);
+        assert_eq!(details.state, "running");
+        assert!(details.env.iter().any(|e| e == "SENTINEL_TEST=1"));
+
+        let exec = monitor
+            .exec_in_container(
+                &container.id,
+                "sh",
+                &[
+                    "-c".to_string(),
+                    "echo out; echo err >&2; exit 3".to_string(),
+                ],
+                Duration::from_secs(10),
+            )
+            .await
+            .unwrap();
+        assert_eq!(exec.exit_code, Some(3));
+        assert_eq!(exec.stdout.trim(), "out");
+        assert_eq!(exec.stderr.trim(), "err");
+
+        let timed_out = monitor
+            .exec_in_container(
+                &container.id,
+                "sleep",
+                &["5".to_string()],
+                Duration::from_millis(200),
+            )
+            .await
+            .unwrap();
+        assert!(timed_out.timed_out);
+
+        let removed = monitor.remove_container(&container.id, true).await.unwrap();
+        assert!(removed.success, "{:?}", removed.error);
+    }
 }
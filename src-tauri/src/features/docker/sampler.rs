@@ -0,0 +1,348 @@
+//! Background container stats sampling with per-container history
+
+use super::monitor::convert_stats;
+use super::types::ContainerStats;
+use crate::error::{Result, SentinelError};
+use bollard::container::StatsOptions;
+use bollard::Docker;
+use chrono::Utc;
+use futures_util::stream::StreamExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::task::JoinHandle;
+
+/// Event emitted with every recorded container sample
+pub const CONTAINER_STATS_EVENT: &str = "container-stats";
+
+/// Docker publishes streamed stats roughly once per second
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Samples kept per container (one hour at the fastest interval)
+pub const MAX_SAMPLES_PER_CONTAINER: usize = 3600;
+
+/// Longest wait between attempts to reopen a stats stream
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Bounded sample history for a single container
+#[derive(Debug)]
+pub struct StatsHistory {
+    samples: VecDeque<ContainerStats>,
+    capacity: usize,
+}
+
+impl StatsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(64)),
+            capacity,
+        }
+    }
+
+    /// Record a sample, evicting the oldest one when full
+    pub fn push(&mut self, sample: ContainerStats) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples from the last `seconds` seconds, oldest first
+    pub fn since(&self, seconds: u64) -> Vec<ContainerStats> {
+        let cutoff = Utc::now() - chrono::Duration::seconds(seconds as i64);
+        self.samples
+            .iter()
+            .filter(|s| s.timestamp >= cutoff)
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+type SharedHistory = Arc<Mutex<HashMap<String, StatsHistory>>>;
+
+/// Keeps one streaming stats connection open per sampled container
+pub struct ContainerStatsSampler {
+    tasks: HashMap<String, JoinHandle<()>>,
+    history: SharedHistory,
+    interval: Duration,
+    app: Option<AppHandle>,
+}
+
+impl Default for ContainerStatsSampler {
+    fn default() -> Self {
+        Self {
+            tasks: HashMap::new(),
+            history: Arc::new(Mutex::new(HashMap::new())),
+            interval: MIN_INTERVAL,
+            app: None,
+        }
+    }
+}
+
+impl ContainerStatsSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start sampling the given containers every `interval`
+    ///
+    /// Containers that are already being sampled are left untouched; the
+    /// interval applies to every container started by this call.
+    pub fn start(
+        &mut self,
+        docker: &Docker,
+        container_ids: Vec<String>,
+        interval: Duration,
+        app: AppHandle,
+    ) -> Result<()> {
+        if interval < MIN_INTERVAL {
+            return Err(SentinelError::InvalidInput {
+                message: format!(
+                    "Sampling interval must be at least {}ms",
+                    MIN_INTERVAL.as_millis()
+                ),
+            });
+        }
+
+        self.prune_finished();
+        self.interval = interval;
+        self.app = Some(app);
+
+        for container_id in container_ids {
+            if self.tasks.contains_key(&container_id) {
+                continue;
+            }
+            let task = self.spawn(docker.clone(), container_id.clone());
+            self.tasks.insert(container_id, task);
+        }
+
+        tracing::info!(
+            "Container stats sampling active for {} container(s) every {:?}",
+            self.tasks.len(),
+            interval
+        );
+        Ok(())
+    }
+
+    /// Stop sampling the given containers; returns how many were stopped
+    ///
+    /// History is kept so charts can still show the recorded window.
+    pub fn stop(&mut self, container_ids: &[String]) -> usize {
+        container_ids
+            .iter()
+            .filter_map(|id| self.tasks.remove(id))
+            .map(|task| task.abort())
+            .count()
+    }
+
+    /// Stop sampling every container
+    pub fn stop_all(&mut self) -> usize {
+        let stopped = self.tasks.len();
+        for (_, task) in self.tasks.drain() {
+            task.abort();
+        }
+        stopped
+    }
+
+    /// Reopen every stats stream with a fresh Docker client
+    pub fn restart(&mut self, docker: &Docker) {
+        self.prune_finished();
+        if self.tasks.is_empty() {
+            return;
+        }
+
+        let container_ids: Vec<String> = self.tasks.keys().cloned().collect();
+        for container_id in container_ids {
+            let task = self.spawn(docker.clone(), container_id.clone());
+            if let Some(old) = self.tasks.insert(container_id, task) {
+                old.abort();
+            }
+        }
+        tracing::info!("Container stats sampling resumed after reconnect");
+    }
+
+    /// IDs of containers currently being sampled
+    pub fn sampled_containers(&mut self) -> Vec<String> {
+        self.prune_finished();
+        let mut ids: Vec<String> = self.tasks.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Recorded samples for a container from the last `seconds` seconds
+    pub fn history(&self, container_id: &str, seconds: u64) -> Vec<ContainerStats> {
+        lock_history(&self.history)
+            .get(container_id)
+            .map(|h| h.since(seconds))
+            .unwrap_or_default()
+    }
+
+    /// Forget tasks that ended on their own (container removed)
+    fn prune_finished(&mut self) {
+        self.tasks.retain(|_, task| !task.is_finished());
+    }
+
+    fn spawn(&self, docker: Docker, container_id: String) -> JoinHandle<()> {
+        let history = self.history.clone();
+        let interval = self.interval;
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            sample_container(docker, container_id, interval, history, app).await;
+        })
+    }
+}
+
+impl Drop for ContainerStatsSampler {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
+
+fn lock_history(
+    history: &SharedHistory,
+) -> std::sync::MutexGuard<'_, HashMap<String, StatsHistory>> {
+    history.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock container stats history: {}", e);
+        e.into_inner()
+    })
+}
+
+/// Follow one container's stats stream until it is removed
+async fn sample_container(
+    docker: Docker,
+    container_id: String,
+    interval: Duration,
+    history: SharedHistory,
+    app: Option<AppHandle>,
+) {
+    let options = StatsOptions {
+        stream: true,
+        one_shot: false,
+    };
+    let mut last_recorded: Option<Instant> = None;
+    let mut retry_delay = interval;
+
+    loop {
+        let mut stream = docker.stats(&container_id, Some(options));
+
+        while let Some(result) = stream.next().await {
+            let stats = match result {
+                Ok(stats) => stats,
+                Err(e) => {
+                    tracing::debug!("Stats stream for {} failed: {}", container_id, e);
+                    break;
+                }
+            };
+            retry_delay = interval;
+
+            // The daemon pushes about one sample per second; keep only the
+            // ones that fall on our interval
+            if last_recorded.is_some_and(|t| t.elapsed() < interval) {
+                continue;
+            }
+            last_recorded = Some(Instant::now());
+
+            let sample = convert_stats(&container_id, stats);
+            lock_history(&history)
+                .entry(container_id.clone())
+                .or_insert_with(|| StatsHistory::new(MAX_SAMPLES_PER_CONTAINER))
+                .push(sample.clone());
+
+            if let Some(app) = &app {
+                if let Err(e) = app.emit(CONTAINER_STATS_EVENT, &sample) {
+                    tracing::warn!("Failed to emit container stats: {}", e);
+                }
+            }
+        }
+
+        // The stream ended: the container stopped or vanished, or the daemon went away
+        match docker.inspect_container(&container_id, None).await {
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => {
+                tracing::info!("Container {} disappeared, dropping its stats", container_id);
+                lock_history(&history).remove(&container_id);
+                return;
+            }
+            Ok(details) if details.state.as_ref().and_then(|s| s.running) != Some(true) => {
+                tracing::info!("Container {} stopped, ending stats sampling", container_id);
+                return;
+            }
+            _ => {
+                tokio::time::sleep(retry_delay).await;
+                retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(container_id: &str, seconds_ago: i64) -> ContainerStats {
+        ContainerStats {
+            container_id: container_id.to_string(),
+            cpu_percent: 1.0,
+            memory_usage: 1024,
+            memory_limit: 4096,
+            memory_percent: 25.0,
+            network_rx_bytes: 0,
+            network_tx_bytes: 0,
+            block_io_read: 0,
+            block_io_write: 0,
+            pids: 1,
+            timestamp: Utc::now() - chrono::Duration::seconds(seconds_ago),
+        }
+    }
+
+    #[test]
+    fn test_history_evicts_oldest_when_full() {
+        let mut history = StatsHistory::new(3);
+        for i in 0..5 {
+            let mut s = sample("web", 0);
+            s.pids = i;
+            history.push(s);
+        }
+
+        assert_eq!(history.len(), 3);
+        let pids: Vec<u64> = history.since(60).iter().map(|s| s.pids).collect();
+        assert_eq!(pids, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_history_since_filters_by_age() {
+        let mut history = StatsHistory::new(MAX_SAMPLES_PER_CONTAINER);
+        history.push(sample("web", 120));
+        history.push(sample("web", 30));
+        history.push(sample("web", 5));
+
+        assert_eq!(history.since(60).len(), 2);
+        assert_eq!(history.since(10).len(), 1);
+        assert!(!history.is_empty());
+    }
+
+    #[test]
+    fn test_sampler_history_for_unknown_container() {
+        let sampler = ContainerStatsSampler::new();
+        assert!(sampler.history("missing", 60).is_empty());
+    }
+
+    #[test]
+    fn test_sampler_stop_without_tasks() {
+        let mut sampler = ContainerStatsSampler::new();
+        assert_eq!(sampler.stop(&["web".to_string()]), 0);
+        assert_eq!(sampler.stop_all(), 0);
+        assert!(sampler.sampled_containers().is_empty());
+    }
+}
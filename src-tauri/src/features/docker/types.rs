@@ -1,5 +1,7 @@
 //! Docker monitoring data types
 
+use crate::core::ProcessStatus;
+use crate::features::service_detection::HealthStatus;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +37,8 @@ pub struct ContainerInfo {
     pub created: DateTime<Utc>,
     /// Labels
     pub labels: Vec<(String, String)>,
+    /// Health in the terms used for managed processes
+    pub health: ContainerHealth,
 }
 
 /// Port mapping information
@@ -139,6 +143,215 @@ pub struct ContainerOperationResult {
     pub error: Option<String>,
 }
 
+/// Docker daemon availability plus the detected runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerRuntimeStatus {
+    /// Whether the daemon answers pings
+    pub available: bool,
+    /// Whether a requested start is still in progress
+    pub starting: bool,
+    /// Detected runtime (colima, docker-desktop, dockerd, podman)
+    pub runtime: Option<String>,
+}
+
+/// Detailed container configuration from an inspect call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerDetails {
+    /// Container ID (short form)
+    pub id: String,
+    /// Full container ID
+    pub full_id: String,
+    /// Container name
+    pub name: String,
+    /// Image name
+    pub image: String,
+    /// Container state (running, exited, etc.)
+    pub state: String,
+    /// Exit code of the last run
+    pub exit_code: Option<i64>,
+    /// When the container was created (RFC 3339)
+    pub created: Option<String>,
+    /// When the container last started (RFC 3339)
+    pub started_at: Option<String>,
+    /// Entrypoint override or image entrypoint
+    pub entrypoint: Vec<String>,
+    /// Command passed to the entrypoint
+    pub cmd: Vec<String>,
+    /// Working directory inside the container
+    pub working_dir: Option<String>,
+    /// Environment variables as KEY=value
+    pub env: Vec<String>,
+    /// Volume and bind mounts
+    pub mounts: Vec<MountInfo>,
+    /// Network configuration
+    pub network: ContainerNetworkSettings,
+    /// Restart policy, if one is set
+    pub restart_policy: Option<RestartPolicyInfo>,
+    /// Health check state, if the image defines a health check
+    pub health: Option<HealthInfo>,
+}
+
+/// A mount inside a container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MountInfo {
+    /// Mount type (bind, volume, tmpfs, etc.)
+    pub mount_type: String,
+    /// Host path or volume name
+    pub source: Option<String>,
+    /// Path inside the container
+    pub destination: String,
+    /// Mount mode flags
+    pub mode: Option<String>,
+    /// Whether the mount is writable
+    pub read_write: bool,
+}
+
+/// Container network configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerNetworkSettings {
+    /// IP address on the default bridge network
+    pub ip_address: Option<String>,
+    /// Gateway on the default bridge network
+    pub gateway: Option<String>,
+    /// Port mappings
+    pub ports: Vec<PortMapping>,
+    /// Networks the container is attached to
+    pub networks: Vec<NetworkEndpoint>,
+}
+
+/// A container's attachment to a Docker network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkEndpoint {
+    /// Network name
+    pub name: String,
+    /// IP address on this network
+    pub ip_address: Option<String>,
+    /// Gateway on this network
+    pub gateway: Option<String>,
+    /// MAC address on this network
+    pub mac_address: Option<String>,
+    /// DNS aliases on this network
+    pub aliases: Vec<String>,
+}
+
+/// Container restart policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartPolicyInfo {
+    /// Policy name (no, always, unless-stopped, on-failure)
+    pub name: String,
+    /// Retry limit for on-failure
+    pub max_retry_count: Option<i64>,
+}
+
+/// Container health check state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthInfo {
+    /// Health status (starting, healthy, unhealthy)
+    pub status: String,
+    /// Number of consecutive failed checks
+    pub failing_streak: i64,
+    /// Output of the most recent check
+    pub last_output: Option<String>,
+    /// Exit code of the most recent check
+    pub last_exit_code: Option<i64>,
+}
+
+/// A container's health, mapped onto the statuses of managed processes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerHealth {
+    /// Healthy (green), Degraded (yellow), Unhealthy (red), or Unknown when
+    /// there is no health check to go by
+    pub status: HealthStatus,
+    /// Whether the container is starting, running, stopped or crashed
+    pub process_status: ProcessStatus,
+    /// Why the container has this status
+    pub reason: ContainerHealthReason,
+    /// Exit code of the last run, for a container that has stopped
+    pub exit_code: Option<i64>,
+    /// Number of consecutive failed health checks
+    pub failing_streak: Option<i64>,
+    /// Output of the most recent health check, or Docker's error
+    pub message: Option<String>,
+}
+
+/// Why a container has its health status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContainerHealthReason {
+    /// Running and passing its health check
+    HealthCheckPassing,
+    /// Running and failing its health check
+    HealthCheckFailing,
+    /// Running, with its health check not passed yet
+    HealthCheckStarting,
+    /// Running, but the image defines no health check
+    NoHealthCheck,
+    /// Paused
+    Paused,
+    /// Exited and being started again by its restart policy
+    Restarting,
+    /// Killed for running out of memory
+    OomKilled,
+    /// Exited with a non-zero code
+    ExitedWithError,
+    /// Exited with code 0
+    Exited,
+    /// Created but never started
+    Created,
+    /// Dead, or being removed
+    Dead,
+}
+
+/// Result of running a command inside a container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecResult {
+    /// Whether the command ran and exited with code 0
+    pub success: bool,
+    /// Container ID the command ran in
+    pub container_id: String,
+    /// Exit code, if the command finished
+    pub exit_code: Option<i64>,
+    /// Captured standard output
+    pub stdout: String,
+    /// Captured standard error
+    pub stderr: String,
+    /// Whether the command was still running when the timeout expired
+    pub timed_out: bool,
+    /// Error message if the command could not be run
+    pub error: Option<String>,
+}
+
+/// A single line of container log output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerLogLine {
+    /// Timestamp reported by Docker, if the line carried one
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Line content without the timestamp prefix or trailing newline
+    pub line: String,
+    /// Source stream ("docker-stdout" or "docker-stderr")
+    pub stream: String,
+}
+
+/// Final state of a container whose log stream ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerExitStatus {
+    /// Container state (exited, running, etc.)
+    pub state: String,
+    /// Exit code if the container has stopped
+    pub exit_code: Option<i64>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +373,7 @@ mod tests {
             network_tx_bytes: Some(2000),
             created: Utc::now(),
             labels: vec![],
+            health: ContainerHealth::from_summary("running", "Up 2 hours"),
         };
 
         assert_eq!(info.name, "test-container");
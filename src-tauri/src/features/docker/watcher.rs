@@ -0,0 +1,197 @@
+//! Background watcher that tracks the Docker daemon coming and going
+
+use super::monitor::DockerMonitor;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+/// Event emitted whenever the daemon's availability changes
+pub const DOCKER_AVAILABILITY_EVENT: &str = "docker-availability-changed";
+
+/// Ping interval while Docker is up or starting
+const FAST_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Longest ping interval while Docker is down
+const MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a requested start may take before we report it as failed
+const STARTING_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Daemon availability as shown in the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DockerAvailability {
+    /// The daemon answers pings
+    Connected,
+    /// A start was requested and the daemon has not answered yet
+    Starting,
+    /// The daemon does not answer
+    Unavailable,
+}
+
+/// Payload of the availability event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerAvailabilityEvent {
+    /// New availability
+    pub status: DockerAvailability,
+    /// Whether Docker commands will work
+    pub available: bool,
+}
+
+/// Pings the daemon in the background and emits availability transitions
+#[derive(Default)]
+pub struct DockerWatcher {
+    task: Option<JoinHandle<()>>,
+    wake: Arc<Notify>,
+    starting_since: Arc<Mutex<Option<Instant>>>,
+}
+
+impl DockerWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching; does nothing if the watcher is already running
+    pub fn start(&mut self, monitor: Arc<tokio::sync::Mutex<DockerMonitor>>, app: AppHandle) {
+        if self.task.is_some() {
+            return;
+        }
+
+        let wake = self.wake.clone();
+        let starting_since = self.starting_since.clone();
+
+        self.task = Some(tauri::async_runtime::spawn(async move {
+            let mut last: Option<DockerAvailability> = None;
+            let mut delay = FAST_INTERVAL;
+
+            loop {
+                let available = monitor.lock().await.refresh_availability().await;
+                let starting = {
+                    let mut since = lock_starting(&starting_since);
+                    if available || since.is_some_and(|t| t.elapsed() > STARTING_TIMEOUT) {
+                        *since = None;
+                    }
+                    since.is_some()
+                };
+
+                let status = availability(available, starting);
+                if last != Some(status) {
+                    tracing::info!("Docker availability changed to {:?}", status);
+                    let event = DockerAvailabilityEvent { status, available };
+                    if let Err(e) = app.emit(DOCKER_AVAILABILITY_EVENT, &event) {
+                        tracing::warn!("Failed to emit Docker availability: {}", e);
+                    }
+                    last = Some(status);
+                }
+
+                delay = next_delay(status, delay);
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = wake.notified() => {}
+                }
+            }
+        }));
+    }
+
+    /// Stop watching
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+
+    /// Record that a daemon start was requested and check right away
+    pub fn mark_starting(&self) {
+        *lock_starting(&self.starting_since) = Some(Instant::now());
+        self.wake.notify_one();
+    }
+
+    /// Whether a requested start is still waiting for the daemon
+    pub fn is_starting(&self) -> bool {
+        lock_starting(&self.starting_since).is_some()
+    }
+}
+
+impl Drop for DockerWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn lock_starting(starting: &Mutex<Option<Instant>>) -> std::sync::MutexGuard<'_, Option<Instant>> {
+    starting.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock Docker watcher state: {}", e);
+        e.into_inner()
+    })
+}
+
+fn availability(available: bool, starting: bool) -> DockerAvailability {
+    if available {
+        DockerAvailability::Connected
+    } else if starting {
+        DockerAvailability::Starting
+    } else {
+        DockerAvailability::Unavailable
+    }
+}
+
+/// Poll quickly while Docker is up or starting, back off while it is down
+fn next_delay(status: DockerAvailability, current: Duration) -> Duration {
+    match status {
+        DockerAvailability::Unavailable => (current * 2).min(MAX_INTERVAL),
+        _ => FAST_INTERVAL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_availability_prefers_connected() {
+        assert_eq!(availability(true, true), DockerAvailability::Connected);
+        assert_eq!(availability(false, true), DockerAvailability::Starting);
+        assert_eq!(availability(false, false), DockerAvailability::Unavailable);
+    }
+
+    #[test]
+    fn test_next_delay_backs_off_while_down() {
+        let mut delay = FAST_INTERVAL;
+        for _ in 0..10 {
+            delay = next_delay(DockerAvailability::Unavailable, delay);
+        }
+        assert_eq!(delay, MAX_INTERVAL);
+
+        assert_eq!(
+            next_delay(DockerAvailability::Starting, delay),
+            FAST_INTERVAL
+        );
+        assert_eq!(
+            next_delay(DockerAvailability::Connected, delay),
+            FAST_INTERVAL
+        );
+    }
+
+    #[test]
+    fn test_mark_starting() {
+        let watcher = DockerWatcher::new();
+        assert!(!watcher.is_starting());
+
+        watcher.mark_starting();
+        assert!(watcher.is_starting());
+    }
+
+    #[test]
+    fn test_availability_event_serialization() {
+        let event = DockerAvailabilityEvent {
+            status: DockerAvailability::Starting,
+            available: false,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"status":"starting","available":false}"#);
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! This module contains all feature implementations organized by domain.
 
+pub mod capabilities;
 pub mod docker;
 pub mod network_monitor;
 pub mod port_discovery;
+pub mod port_forward;
 pub mod service_detection;
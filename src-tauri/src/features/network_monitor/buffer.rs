@@ -1,6 +1,8 @@
 //! Circular buffer for storing historical network data
 
-use super::types::NetworkSnapshot;
+use super::types::{DownsampledNetworkHistory, NetworkSnapshot};
+use crate::core::metrics_buffer::downsample;
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
 
 /// Fixed-size circular buffer for network snapshots
@@ -48,11 +50,63 @@ impl CircularBuffer {
             .collect()
     }
 
+    /// Get send/receive rates for the last N seconds, aggregated into at most
+    /// `max_points` min/max/avg buckets
+    ///
+    /// Rates are derived from consecutive snapshots, so sleep gaps show up as
+    /// missing buckets rather than one huge averaged sample.
+    pub fn get_history_downsampled(
+        &self,
+        seconds: u64,
+        max_points: usize,
+    ) -> DownsampledNetworkHistory {
+        let samples = self.get_last_seconds(seconds);
+
+        let mut sent: Vec<(DateTime<Utc>, f64)> = Vec::new();
+        let mut received: Vec<(DateTime<Utc>, f64)> = Vec::new();
+        for pair in samples.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            let elapsed_ms = (current.timestamp - previous.timestamp).num_milliseconds();
+            if elapsed_ms <= 0 {
+                continue;
+            }
+            let seconds = elapsed_ms as f64 / 1000.0;
+            sent.push((
+                current.timestamp,
+                current
+                    .total_bytes_sent
+                    .saturating_sub(previous.total_bytes_sent) as f64
+                    / seconds,
+            ));
+            received.push((
+                current.timestamp,
+                current
+                    .total_bytes_received
+                    .saturating_sub(previous.total_bytes_received) as f64
+                    / seconds,
+            ));
+        }
+
+        DownsampledNetworkHistory {
+            bytes_sent_per_sec: downsample(&sent, max_points),
+            bytes_received_per_sec: downsample(&received, max_points),
+        }
+    }
+
     /// Get the most recent snapshot
     pub fn get_latest(&self) -> Option<&NetworkSnapshot> {
         self.data.back()
     }
 
+    /// Get the two most recent snapshots as (previous, latest)
+    pub fn get_latest_pair(&self) -> Option<(&NetworkSnapshot, &NetworkSnapshot)> {
+        let len = self.data.len();
+        if len < 2 {
+            return None;
+        }
+        Some((&self.data[len - 2], &self.data[len - 1]))
+    }
+
     /// Clear all data
     pub fn clear(&mut self) {
         self.data.clear();
@@ -89,6 +143,7 @@ mod tests {
             total_packets_received: 20,
             processes: vec![],
             protocol_stats: ProtocolStats::default(),
+            interfaces: vec![],
         }
     }
 
@@ -163,6 +218,68 @@ mod tests {
         assert!(buffer.get_latest().is_none());
     }
 
+    #[test]
+    fn test_get_latest_pair() {
+        let mut buffer = CircularBuffer::new(5);
+        assert!(buffer.get_latest_pair().is_none());
+
+        buffer.push(create_test_snapshot(-2));
+        assert!(buffer.get_latest_pair().is_none());
+
+        buffer.push(create_test_snapshot(-1));
+        buffer.push(create_test_snapshot(0));
+
+        let (previous, latest) = buffer.get_latest_pair().unwrap();
+        assert!(previous.timestamp < latest.timestamp);
+        assert_eq!(latest.timestamp, buffer.get_latest().unwrap().timestamp);
+    }
+
+    fn snapshot_with_totals(
+        base: chrono::DateTime<Utc>,
+        offset_seconds: i64,
+        sent: u64,
+        received: u64,
+    ) -> NetworkSnapshot {
+        NetworkSnapshot {
+            timestamp: base + chrono::Duration::seconds(offset_seconds),
+            total_bytes_sent: sent,
+            total_bytes_received: received,
+            ..create_test_snapshot(0)
+        }
+    }
+
+    #[test]
+    fn test_downsampled_empty_buffer() {
+        let buffer = CircularBuffer::new(10);
+        let history = buffer.get_history_downsampled(60, 10);
+
+        assert!(history.bytes_sent_per_sec.is_empty());
+        assert!(history.bytes_received_per_sec.is_empty());
+    }
+
+    #[test]
+    fn test_downsampled_spike_survives() {
+        let mut buffer = CircularBuffer::new(400);
+        let base = Utc::now();
+        let mut sent = 0;
+        for i in 0..300 {
+            // Steady 1 KB/s with a single 1 MB burst
+            sent += if i == 150 { 1_000_000 } else { 1_000 };
+            buffer.push(snapshot_with_totals(base, i - 300, sent, 0));
+        }
+
+        let history = buffer.get_history_downsampled(3600, 20);
+
+        assert!(history.bytes_sent_per_sec.len() <= 20);
+        let peak = history
+            .bytes_sent_per_sec
+            .iter()
+            .map(|b| b.max)
+            .fold(0.0, f64::max);
+        assert_eq!(peak, 1_000_000.0);
+        assert!(history.bytes_sent_per_sec.iter().all(|b| b.min >= 1_000.0));
+    }
+
     #[test]
     fn test_get_all() {
         let mut buffer = CircularBuffer::new(5);
@@ -1,16 +1,33 @@
 //! Network traffic collection using sysinfo
 
 use super::buffer::CircularBuffer;
-use super::types::{NetworkInterfaceStats, NetworkSnapshot, ProcessNetworkStats, ProtocolStats};
+use super::interfaces::{self, InterfaceKind};
+use super::process_traffic::{self, ProcessTraffic};
+use super::types::{
+    DownsampledNetworkHistory, InterfaceTraffic, NetworkInterfaceStats, NetworkRates,
+    NetworkSnapshot, ProcessNetworkStats, ProtocolStats,
+};
+use crate::models::NetworkSettings;
 use chrono::Utc;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use sysinfo::{Networks, System};
 
+/// Per-process (bytes sent, bytes received) counters keyed by PID
+type ProcessCounters = HashMap<u32, (u64, u64)>;
+
 /// Collects and stores network traffic statistics
 pub struct TrafficCollector {
     system: System,
     networks: Networks,
     buffer: CircularBuffer,
     last_snapshot: Option<NetworkSnapshot>,
+    /// Previous per-process counters, for rates
+    last_process_sample: Option<(Instant, ProcessCounters)>,
+    /// Interfaces counted in totals and history
+    filter: NetworkSettings,
+    /// Kind of each interface seen so far
+    kinds: HashMap<String, InterfaceKind>,
 }
 
 impl Default for TrafficCollector {
@@ -32,6 +49,9 @@ impl TrafficCollector {
             networks: Networks::new_with_refreshed_list(),
             buffer: CircularBuffer::new(capacity),
             last_snapshot: None,
+            last_process_sample: None,
+            filter: NetworkSettings::default(),
+            kinds: HashMap::new(),
         }
     }
 
@@ -40,17 +60,30 @@ impl TrafficCollector {
         // Refresh system and network data
         self.system.refresh_all();
         self.networks.refresh(false); // Don't remove not-listed interfaces
+        self.classify_new_interfaces();
 
         // Aggregate network interface statistics
         let (total_sent, total_received, total_packets_sent, total_packets_received) =
             self.aggregate_network_stats();
 
-        // Collect per-process stats (simplified version)
+        // Collect per-process stats from OS socket accounting
         let processes = self.collect_process_stats();
 
         // Collect protocol stats
         let protocol_stats = self.collect_protocol_stats();
 
+        // Per-interface deltas since the previous refresh
+        let interfaces = self
+            .networks
+            .iter()
+            .filter(|(name, _)| self.is_selected(name))
+            .map(|(name, data)| InterfaceTraffic {
+                name: name.clone(),
+                bytes_sent: data.transmitted(),
+                bytes_received: data.received(),
+            })
+            .collect();
+
         let snapshot = NetworkSnapshot {
             timestamp: Utc::now(),
             total_bytes_sent: total_sent,
@@ -59,6 +92,7 @@ impl TrafficCollector {
             total_packets_received,
             processes,
             protocol_stats,
+            interfaces,
         };
 
         // Store in buffer
@@ -68,29 +102,110 @@ impl TrafficCollector {
         snapshot
     }
 
+    /// Get the latest snapshot if it was collected within `max_age`, such as
+    /// by the background sampler, or collect a new one
+    pub fn collect_cached(&mut self, max_age: Duration) -> NetworkSnapshot {
+        let fresh = self.last_snapshot.as_ref().filter(|snapshot| {
+            (Utc::now() - snapshot.timestamp)
+                .to_std()
+                .is_ok_and(|age| age < max_age)
+        });
+        if let Some(snapshot) = fresh {
+            return snapshot.clone();
+        }
+        self.collect()
+    }
+
     /// Get historical snapshots for the last N seconds
     pub fn get_history(&self, seconds: u64) -> Vec<NetworkSnapshot> {
         self.buffer.get_last_seconds(seconds)
     }
 
+    /// Get historical send/receive rates aggregated into at most `max_points` buckets
+    pub fn get_history_downsampled(
+        &self,
+        seconds: u64,
+        max_points: usize,
+    ) -> DownsampledNetworkHistory {
+        self.buffer.get_history_downsampled(seconds, max_points)
+    }
+
     /// Clear historical data
     pub fn clear_history(&mut self) {
         self.buffer.clear();
     }
 
+    /// Count only the interfaces `filter` selects from now on
+    ///
+    /// Clears the history if that changes which interfaces are counted, since
+    /// older snapshots summed a different set. Returns whether it did.
+    pub fn set_interface_filter(&mut self, filter: NetworkSettings) -> bool {
+        self.classify_new_interfaces();
+        let before = self.selected_interfaces();
+        self.filter = filter;
+
+        let changed = self.selected_interfaces() != before;
+        if changed {
+            self.clear_history();
+            self.last_snapshot = None;
+        }
+        changed
+    }
+
     /// Get the most recent snapshot
     pub fn get_latest(&self) -> Option<&NetworkSnapshot> {
         self.last_snapshot.as_ref()
     }
 
-    /// Aggregate stats from all network interfaces
+    /// Get throughput computed from the last two collected snapshots
+    pub fn get_rates(&self) -> Option<NetworkRates> {
+        let (previous, latest) = self.buffer.get_latest_pair()?;
+        compute_rates(previous, latest)
+    }
+
+    /// Classify interfaces that appeared since the last refresh
+    fn classify_new_interfaces(&mut self) {
+        for name in self.networks.keys() {
+            self.kinds
+                .entry(name.clone())
+                .or_insert_with(|| interfaces::detect(name));
+        }
+    }
+
+    /// Whether interface `name` is counted under the current filter
+    fn is_selected(&self, name: &str) -> bool {
+        let kind = self
+            .kinds
+            .get(name)
+            .copied()
+            .unwrap_or(InterfaceKind::Unknown);
+        interfaces::selects(&self.filter, name, kind)
+    }
+
+    /// Names of the counted interfaces, sorted
+    fn selected_interfaces(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .networks
+            .keys()
+            .filter(|name| self.is_selected(name))
+            .cloned()
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Aggregate stats from the selected network interfaces
     fn aggregate_network_stats(&self) -> (u64, u64, u64, u64) {
         let mut total_sent = 0u64;
         let mut total_received = 0u64;
         let mut total_packets_sent = 0u64;
         let mut total_packets_received = 0u64;
 
-        for (_interface_name, data) in self.networks.iter() {
+        for (_interface_name, data) in self
+            .networks
+            .iter()
+            .filter(|(name, _)| self.is_selected(name))
+        {
             total_sent += data.total_transmitted();
             total_received += data.total_received();
             total_packets_sent += data.total_packets_transmitted();
@@ -105,9 +220,10 @@ impl TrafficCollector {
         )
     }
 
-    /// Get per-interface network statistics
+    /// Get per-interface network statistics, counted or not
     pub fn get_interfaces(&mut self) -> Vec<NetworkInterfaceStats> {
         self.networks.refresh(false);
+        self.classify_new_interfaces();
 
         self.networks
             .iter()
@@ -131,7 +247,13 @@ impl TrafficCollector {
                         ))
                     },
                     interface_type,
+                    kind: self
+                        .kinds
+                        .get(name)
+                        .copied()
+                        .unwrap_or(InterfaceKind::Unknown),
                     is_up,
+                    selected: self.is_selected(name),
                 }
             })
             .collect()
@@ -163,13 +285,29 @@ impl TrafficCollector {
     }
 
     /// Collect per-process network statistics
-    /// Note: This is a simplified implementation
-    /// Full implementation would parse lsof/netstat output
-    fn collect_process_stats(&self) -> Vec<ProcessNetworkStats> {
-        // For Phase 3D initial implementation, we return empty
-        // This would be populated by parsing port discovery data
-        // and correlating with bandwidth usage
-        Vec::new()
+    ///
+    /// Returns an empty list when the platform has no real per-process
+    /// accounting; rates are `None` until two samples exist.
+    fn collect_process_stats(&mut self) -> Vec<ProcessNetworkStats> {
+        let Some(traffic) = process_traffic::collect() else {
+            return Vec::new();
+        };
+
+        let now = Instant::now();
+        let stats = compute_process_stats(
+            traffic.clone(),
+            self.last_process_sample
+                .as_ref()
+                .map(|(at, counters)| (now.duration_since(*at).as_secs_f64(), counters)),
+        );
+
+        let counters = traffic
+            .iter()
+            .map(|t| (t.pid, (t.bytes_sent, t.bytes_received)))
+            .collect();
+        self.last_process_sample = Some((now, counters));
+
+        stats
     }
 
     /// Collect protocol-level statistics
@@ -181,6 +319,66 @@ impl TrafficCollector {
     }
 }
 
+/// Per-second rates between two snapshots
+///
+/// Counters that went backwards (interface reset) are reported as zero.
+fn compute_rates(previous: &NetworkSnapshot, latest: &NetworkSnapshot) -> Option<NetworkRates> {
+    let elapsed_ms = (latest.timestamp - previous.timestamp).num_milliseconds();
+    if elapsed_ms <= 0 {
+        return None;
+    }
+    let seconds = elapsed_ms as f64 / 1000.0;
+    let rate = |new: u64, old: u64| new.saturating_sub(old) as f64 / seconds;
+
+    Some(NetworkRates {
+        timestamp: latest.timestamp,
+        interval_ms: elapsed_ms as u64,
+        bytes_sent_per_sec: rate(latest.total_bytes_sent, previous.total_bytes_sent),
+        bytes_received_per_sec: rate(latest.total_bytes_received, previous.total_bytes_received),
+        packets_sent_per_sec: rate(latest.total_packets_sent, previous.total_packets_sent),
+        packets_received_per_sec: rate(
+            latest.total_packets_received,
+            previous.total_packets_received,
+        ),
+    })
+}
+
+/// Convert raw counters to stats, deriving rates from the previous sample
+///
+/// Counters only cover currently open sockets, so they can shrink when a
+/// connection closes; such drops are reported as a zero rate.
+fn compute_process_stats(
+    traffic: Vec<ProcessTraffic>,
+    previous: Option<(f64, &ProcessCounters)>,
+) -> Vec<ProcessNetworkStats> {
+    traffic
+        .into_iter()
+        .map(|t| {
+            let rates = previous.and_then(|(elapsed, counters)| {
+                let (sent, received) = counters.get(&t.pid)?;
+                if elapsed <= 0.0 {
+                    return None;
+                }
+                Some((
+                    t.bytes_sent.saturating_sub(*sent) as f64 / elapsed,
+                    t.bytes_received.saturating_sub(*received) as f64 / elapsed,
+                ))
+            });
+
+            ProcessNetworkStats {
+                pid: t.pid,
+                process_name: t.process_name,
+                bytes_sent: Some(t.bytes_sent),
+                bytes_received: Some(t.bytes_received),
+                send_rate: rates.map(|(send, _)| send),
+                receive_rate: rates.map(|(_, receive)| receive),
+                connections: t.connections,
+                ports: t.ports,
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +390,19 @@ mod tests {
         assert!(collector.get_latest().is_none());
     }
 
+    #[test]
+    fn test_collect_cached_reuses_recent_snapshot() {
+        let mut collector = TrafficCollector::new();
+
+        let first = collector.collect_cached(Duration::from_secs(60));
+        let cached = collector.collect_cached(Duration::from_secs(60));
+        assert_eq!(cached.timestamp, first.timestamp);
+        assert_eq!(collector.buffer.len(), 1);
+
+        collector.collect_cached(Duration::ZERO);
+        assert_eq!(collector.buffer.len(), 2);
+    }
+
     #[test]
     fn test_collector_with_capacity() {
         let collector = TrafficCollector::with_capacity(100);
@@ -250,6 +461,30 @@ mod tests {
         assert_eq!(history.len(), 0);
     }
 
+    #[test]
+    fn test_interface_filter_clears_history_on_change() {
+        let mut collector = TrafficCollector::new();
+        collector.collect();
+
+        // Same selection: history is kept
+        assert!(!collector.set_interface_filter(NetworkSettings::default()));
+        assert_eq!(collector.get_history(60).len(), 1);
+
+        let counted = collector.get_interfaces().iter().any(|i| i.selected);
+        let nothing = NetworkSettings {
+            exclude_interfaces: vec!["*".to_string()],
+            include_loopback: true,
+            ..Default::default()
+        };
+        assert_eq!(collector.set_interface_filter(nothing), counted);
+        assert_eq!(collector.get_history(60).is_empty(), counted);
+
+        let snapshot = collector.collect();
+        assert_eq!(snapshot.total_bytes_received, 0);
+        assert!(snapshot.interfaces.is_empty());
+        assert!(collector.get_interfaces().iter().all(|i| !i.selected));
+    }
+
     #[test]
     fn test_get_latest() {
         let mut collector = TrafficCollector::new();
@@ -262,6 +497,98 @@ mod tests {
         assert_eq!(latest.timestamp, snapshot.timestamp);
     }
 
+    fn snapshot_at(offset_ms: i64, bytes_sent: u64, bytes_received: u64) -> NetworkSnapshot {
+        NetworkSnapshot {
+            timestamp: chrono::DateTime::from_timestamp_millis(1_700_000_000_000 + offset_ms)
+                .unwrap(),
+            total_bytes_sent: bytes_sent,
+            total_bytes_received: bytes_received,
+            total_packets_sent: bytes_sent / 100,
+            total_packets_received: bytes_received / 100,
+            processes: vec![],
+            protocol_stats: ProtocolStats::default(),
+            interfaces: vec![],
+        }
+    }
+
+    #[test]
+    fn test_compute_rates() {
+        let previous = snapshot_at(0, 10_000, 50_000);
+        let latest = snapshot_at(2_000, 30_000, 150_000);
+
+        let rates = compute_rates(&previous, &latest).unwrap();
+
+        assert_eq!(rates.interval_ms, 2_000);
+        assert_eq!(rates.bytes_sent_per_sec, 10_000.0);
+        assert_eq!(rates.bytes_received_per_sec, 50_000.0);
+        assert_eq!(rates.packets_sent_per_sec, 100.0);
+    }
+
+    #[test]
+    fn test_compute_rates_counter_reset() {
+        let previous = snapshot_at(0, 10_000, 50_000);
+        let latest = snapshot_at(1_000, 500, 50_000);
+
+        let rates = compute_rates(&previous, &latest).unwrap();
+        assert_eq!(rates.bytes_sent_per_sec, 0.0);
+
+        // Same timestamp: no meaningful rate
+        assert!(compute_rates(&latest, &latest).is_none());
+    }
+
+    #[test]
+    fn test_get_rates_needs_two_snapshots() {
+        let mut collector = TrafficCollector::new();
+        assert!(collector.get_rates().is_none());
+
+        collector.collect();
+        assert!(collector.get_rates().is_none());
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        collector.collect();
+        assert!(collector.get_rates().is_some());
+    }
+
+    fn traffic(pid: u32, sent: u64, received: u64) -> ProcessTraffic {
+        ProcessTraffic {
+            pid,
+            process_name: "node".to_string(),
+            bytes_sent: sent,
+            bytes_received: received,
+            connections: 1,
+            ports: vec![3000],
+        }
+    }
+
+    #[test]
+    fn test_process_stats_first_sample_has_no_rate() {
+        let stats = compute_process_stats(vec![traffic(1, 100, 200)], None);
+
+        assert_eq!(stats[0].bytes_sent, Some(100));
+        assert!(stats[0].send_rate.is_none());
+        assert!(stats[0].receive_rate.is_none());
+    }
+
+    #[test]
+    fn test_process_stats_rates() {
+        let previous = HashMap::from([(1, (100, 200)), (2, (500, 500))]);
+        let stats = compute_process_stats(
+            vec![
+                traffic(1, 300, 1200),
+                traffic(2, 100, 100),
+                traffic(3, 1, 1),
+            ],
+            Some((2.0, &previous)),
+        );
+
+        assert_eq!(stats[0].send_rate, Some(100.0));
+        assert_eq!(stats[0].receive_rate, Some(500.0));
+        // Closed sockets shrink the counters; never report negative rates
+        assert_eq!(stats[1].send_rate, Some(0.0));
+        // New process: no baseline yet
+        assert!(stats[2].send_rate.is_none());
+    }
+
     #[test]
     fn test_buffer_overflow() {
         let mut collector = TrafficCollector::with_capacity(2);
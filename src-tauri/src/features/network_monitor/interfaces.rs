@@ -0,0 +1,326 @@
+//! Interface classification and selection
+//!
+//! Totals over every interface mix real traffic with docker bridges, VPN
+//! tunnels and loopback, which hides whether the actual link is busy. Each
+//! interface gets a kind inferred from its name and, where the OS reports
+//! them, its flags; `NetworkSettings` then selects the interfaces counted.
+
+use crate::core::glob_match;
+use crate::models::NetworkSettings;
+use serde::{Deserialize, Serialize};
+
+/// Name prefixes of interfaces that don't correspond to a physical link
+const VIRTUAL_PREFIXES: &[&str] = &[
+    "docker",
+    "veth",
+    "br-",
+    "virbr",
+    "bridge",
+    "vmnet",
+    "vboxnet",
+    "utun",
+    "tun",
+    "tap",
+    "wg",
+    "tailscale",
+    "zt",
+    "awdl",
+    "llw",
+    "anpi",
+    "gif",
+    "stf",
+    "cni",
+    "flannel",
+    "cali",
+    "lxc",
+    "lxd",
+    "podman",
+    "ppp",
+    "ipsec",
+];
+
+/// Name prefixes of wireless interfaces
+const WIFI_PREFIXES: &[&str] = &["wlan", "wlp", "wlo", "wlx", "wifi", "ath"];
+
+/// Name prefixes of wired interfaces
+const ETHERNET_PREFIXES: &[&str] = &["eth", "en"];
+
+/// What an interface is, as far as can be told
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceKind {
+    Ethernet,
+    Wifi,
+    Virtual,
+    Loopback,
+    Unknown,
+}
+
+/// What the OS reports about an interface
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterfaceFlags {
+    pub loopback: bool,
+    /// Point-to-point link, such as a VPN tunnel
+    pub point_to_point: bool,
+    pub wireless: bool,
+    /// Whether a hardware device backs the interface, `None` if unknown
+    pub physical: Option<bool>,
+}
+
+/// Infer the kind of interface `name` from its name and `flags`
+///
+/// Flags win over the name where they're conclusive; without flags the name
+/// alone decides.
+pub fn classify(name: &str, flags: Option<InterfaceFlags>) -> InterfaceKind {
+    let flags = flags.unwrap_or_default();
+    let has_prefix = |prefixes: &[&str]| prefixes.iter().any(|prefix| name.starts_with(prefix));
+
+    if flags.loopback || is_loopback_name(name) {
+        InterfaceKind::Loopback
+    } else if flags.wireless || has_prefix(WIFI_PREFIXES) {
+        InterfaceKind::Wifi
+    } else if has_prefix(VIRTUAL_PREFIXES) || flags.point_to_point || flags.physical == Some(false)
+    {
+        InterfaceKind::Virtual
+    } else if has_prefix(ETHERNET_PREFIXES) || flags.physical == Some(true) {
+        InterfaceKind::Ethernet
+    } else {
+        InterfaceKind::Unknown
+    }
+}
+
+/// Classify interface `name` using the flags the OS reports for it
+pub fn detect(name: &str) -> InterfaceKind {
+    classify(name, os_flags(name))
+}
+
+/// Whether the interface `name` of kind `kind` is counted under `settings`
+pub fn selects(settings: &NetworkSettings, name: &str, kind: InterfaceKind) -> bool {
+    (settings.include_loopback || kind != InterfaceKind::Loopback)
+        && !settings
+            .exclude_interfaces
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), name.as_bytes()))
+}
+
+/// `lo`, `lo0` and the like
+fn is_loopback_name(name: &str) -> bool {
+    name.strip_prefix("lo")
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Read the flags of `name` from `/sys/class/net`
+#[cfg(target_os = "linux")]
+fn os_flags(name: &str) -> Option<InterfaceFlags> {
+    /// `ARPHRD_LOOPBACK`
+    const LOOPBACK_TYPE: &str = "772";
+    const IFF_LOOPBACK: u32 = 0x8;
+    const IFF_POINTOPOINT: u32 = 0x10;
+
+    let dir = std::path::Path::new("/sys/class/net").join(name);
+    if name.contains('/') || !dir.exists() {
+        return None;
+    }
+
+    let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+    let flags = read("flags")
+        .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+        .unwrap_or(0);
+
+    Some(InterfaceFlags {
+        loopback: flags & IFF_LOOPBACK != 0
+            || read("type").is_some_and(|kind| kind.trim() == LOOPBACK_TYPE),
+        point_to_point: flags & IFF_POINTOPOINT != 0,
+        wireless: dir.join("wireless").exists() || dir.join("phy80211").exists(),
+        physical: Some(dir.join("device").exists()),
+    })
+}
+
+/// Read the flags of `name` with `getifaddrs`
+#[cfg(target_os = "macos")]
+fn os_flags(name: &str) -> Option<InterfaceFlags> {
+    use std::ffi::CStr;
+
+    let mut addrs: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut addrs) } != 0 {
+        return None;
+    }
+
+    let mut found = None;
+    let mut cursor = addrs;
+    while !cursor.is_null() {
+        let entry = unsafe { &*cursor };
+        let entry_name = unsafe { CStr::from_ptr(entry.ifa_name) };
+        if entry_name.to_bytes() == name.as_bytes() {
+            let flags = entry.ifa_flags as libc::c_int;
+            found = Some(InterfaceFlags {
+                loopback: flags & libc::IFF_LOOPBACK != 0,
+                point_to_point: flags & libc::IFF_POINTOPOINT != 0,
+                wireless: false,
+                physical: None,
+            });
+            break;
+        }
+        cursor = entry.ifa_next;
+    }
+
+    unsafe { libc::freeifaddrs(addrs) };
+    found
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn os_flags(_name: &str) -> Option<InterfaceFlags> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interface names seen on Linux and macOS machines
+    const INTERFACES: &[&str] = &[
+        "lo",
+        "lo0",
+        "eth0",
+        "enp3s0",
+        "en0",
+        "wlan0",
+        "wlp2s0",
+        "docker0",
+        "veth3a1b2c",
+        "br-4f2e",
+        "virbr0",
+        "utun3",
+        "tun0",
+        "awdl0",
+        "llw0",
+        "bridge0",
+        "wg0",
+        "tailscale0",
+        "ham0",
+    ];
+
+    fn kinds(flags: Option<InterfaceFlags>) -> Vec<(&'static str, InterfaceKind)> {
+        INTERFACES
+            .iter()
+            .map(|name| (*name, classify(name, flags)))
+            .collect()
+    }
+
+    #[test]
+    fn test_classify_by_name() {
+        use InterfaceKind::*;
+
+        assert_eq!(
+            kinds(None),
+            [
+                ("lo", Loopback),
+                ("lo0", Loopback),
+                ("eth0", Ethernet),
+                ("enp3s0", Ethernet),
+                ("en0", Ethernet),
+                ("wlan0", Wifi),
+                ("wlp2s0", Wifi),
+                ("docker0", Virtual),
+                ("veth3a1b2c", Virtual),
+                ("br-4f2e", Virtual),
+                ("virbr0", Virtual),
+                ("utun3", Virtual),
+                ("tun0", Virtual),
+                ("awdl0", Virtual),
+                ("llw0", Virtual),
+                ("bridge0", Virtual),
+                ("wg0", Virtual),
+                ("tailscale0", Virtual),
+                ("ham0", Unknown),
+            ]
+        );
+
+        // Only `lo` followed by digits is loopback
+        assert_eq!(classify("lowpan0", None), Unknown);
+    }
+
+    #[test]
+    fn test_flags_refine_the_name() {
+        use InterfaceKind::*;
+
+        let wireless = InterfaceFlags {
+            wireless: true,
+            physical: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(classify("en0", Some(wireless)), Wifi);
+
+        let no_device = InterfaceFlags {
+            physical: Some(false),
+            ..Default::default()
+        };
+        // A renamed bridge without a device behind it
+        assert_eq!(classify("eth1", Some(no_device)), Virtual);
+
+        let point_to_point = InterfaceFlags {
+            point_to_point: true,
+            ..Default::default()
+        };
+        assert_eq!(classify("ham0", Some(point_to_point)), Virtual);
+
+        let loopback = InterfaceFlags {
+            loopback: true,
+            ..Default::default()
+        };
+        assert_eq!(classify("dummy", Some(loopback)), Loopback);
+
+        let hardware = InterfaceFlags {
+            physical: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(classify("ham0", Some(hardware)), Ethernet);
+    }
+
+    #[test]
+    fn test_selection() {
+        let selected = |settings: &NetworkSettings| -> Vec<&str> {
+            INTERFACES
+                .iter()
+                .copied()
+                .filter(|name| selects(settings, name, classify(name, None)))
+                .collect()
+        };
+
+        // Loopback is left out unless asked for
+        let default = NetworkSettings::default();
+        assert!(!selected(&default).contains(&"lo"));
+        assert_eq!(selected(&default).len(), INTERFACES.len() - 2);
+
+        let physical_only = NetworkSettings {
+            exclude_interfaces: vec![
+                "docker*".to_string(),
+                "veth*".to_string(),
+                "br-*".to_string(),
+                "virbr?".to_string(),
+                "*tun[0-9]".to_string(),
+                "awdl0".to_string(),
+                "llw0".to_string(),
+                "bridge*".to_string(),
+                "wg*".to_string(),
+                "tailscale*".to_string(),
+                "ham0".to_string(),
+            ],
+            include_loopback: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            selected(&physical_only),
+            ["lo", "lo0", "eth0", "enp3s0", "en0", "wlan0", "wlp2s0"]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_detect_loopback() {
+        if std::path::Path::new("/sys/class/net/lo").exists() {
+            assert_eq!(detect("lo"), InterfaceKind::Loopback);
+        }
+        assert!(os_flags("../lo").is_none());
+    }
+}
@@ -5,19 +5,29 @@
 
 mod buffer;
 mod collector;
+mod interfaces;
+mod process_traffic;
+mod sampler;
 mod types;
 
 pub use buffer::CircularBuffer;
 pub use collector::TrafficCollector;
+pub use interfaces::{classify, InterfaceFlags, InterfaceKind};
+pub use sampler::{NetworkSampler, NETWORK_SNAPSHOT_EVENT};
 pub use types::*;
 
 use crate::error::Result;
+use crate::models::NetworkSettings;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, State};
 
 /// Application state for network monitor
 pub struct NetworkMonitorState(pub Arc<Mutex<TrafficCollector>>);
 
+/// Application state for the background network sampler
+pub struct NetworkSamplerState(pub Arc<Mutex<NetworkSampler>>);
+
 /// Get current network statistics
 #[tauri::command]
 pub async fn get_network_stats(state: State<'_, NetworkMonitorState>) -> Result<NetworkSnapshot> {
@@ -43,6 +53,21 @@ pub async fn get_network_history(
     Ok(collector.get_history(duration_seconds))
 }
 
+/// Get historical send/receive rates downsampled to at most `max_points` buckets
+#[tauri::command]
+pub async fn get_network_history_downsampled(
+    state: State<'_, NetworkMonitorState>,
+    duration_seconds: u64,
+    max_points: usize,
+) -> Result<DownsampledNetworkHistory> {
+    let collector = state.0.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock network collector: {}", e);
+        e.into_inner()
+    });
+
+    Ok(collector.get_history_downsampled(duration_seconds, max_points))
+}
+
 /// Clear network statistics history
 #[tauri::command]
 pub async fn clear_network_history(state: State<'_, NetworkMonitorState>) -> Result<()> {
@@ -67,3 +92,69 @@ pub async fn get_network_interfaces(
 
     Ok(collector.get_interfaces())
 }
+
+/// Choose which interfaces are counted in totals and history
+///
+/// Takes effect with the next snapshot. History is cleared when the set of
+/// counted interfaces changes, as older samples summed a different set.
+///
+/// Returns whether the set of counted interfaces changed.
+#[tauri::command]
+pub async fn set_network_interface_filter(
+    exclude_interfaces: Vec<String>,
+    include_loopback: bool,
+    state: State<'_, NetworkMonitorState>,
+) -> Result<bool> {
+    let mut collector = state.0.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock network collector: {}", e);
+        e.into_inner()
+    });
+
+    Ok(collector.set_interface_filter(NetworkSettings {
+        exclude_interfaces,
+        include_loopback,
+        ..Default::default()
+    }))
+}
+
+/// Get bytes/sec computed from the last two snapshots
+///
+/// Returns `None` until at least two snapshots have been collected.
+#[tauri::command]
+pub async fn get_network_rates(
+    state: State<'_, NetworkMonitorState>,
+) -> Result<Option<NetworkRates>> {
+    let collector = state.0.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock network collector: {}", e);
+        e.into_inner()
+    });
+
+    Ok(collector.get_rates())
+}
+
+/// Start emitting `network-snapshot` events every `interval_ms`
+#[tauri::command]
+pub async fn start_network_sampling(
+    interval_ms: u64,
+    app: AppHandle,
+    state: State<'_, NetworkMonitorState>,
+    sampler: State<'_, NetworkSamplerState>,
+) -> Result<()> {
+    let mut sampler = sampler.0.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock network sampler: {}", e);
+        e.into_inner()
+    });
+
+    sampler.start(state.0.clone(), app, Duration::from_millis(interval_ms))
+}
+
+/// Stop the background network sampler
+#[tauri::command]
+pub async fn stop_network_sampling(sampler: State<'_, NetworkSamplerState>) -> Result<bool> {
+    let mut sampler = sampler.0.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock network sampler: {}", e);
+        e.into_inner()
+    });
+
+    Ok(sampler.stop())
+}
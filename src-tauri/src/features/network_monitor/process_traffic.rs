@@ -0,0 +1,672 @@
+//! Per-process network byte accounting from OS socket statistics
+//!
+//! - Linux: TCP byte counters from sock_diag (`NETLINK_SOCK_DIAG`), attributed
+//!   to processes through the socket inodes in `/proc/<pid>/fd`
+//! - macOS: best-effort parsing of `nettop -P -L 1`
+//! - Other platforms: no data, rather than a misleading proxy
+
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+
+/// Cumulative traffic counters for one process
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessTraffic {
+    pub pid: u32,
+    pub process_name: String,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub connections: u32,
+    pub ports: Vec<u16>,
+}
+
+/// Collect per-process traffic counters
+///
+/// Returns `None` when the platform offers no real per-process accounting.
+pub fn collect() -> Option<Vec<ProcessTraffic>> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::collect()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::collect()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// One TCP socket as reported by sock_diag
+#[cfg(any(target_os = "linux", test))]
+#[derive(Debug, Clone, PartialEq)]
+struct SocketStats {
+    inode: u32,
+    local_port: u16,
+    /// `tcpi_bytes_acked`: bytes sent and acknowledged by the peer
+    bytes_sent: u64,
+    /// `tcpi_bytes_received`
+    bytes_received: u64,
+}
+
+#[cfg(any(target_os = "linux", test))]
+const NLMSG_HDR_LEN: usize = 16;
+#[cfg(any(target_os = "linux", test))]
+const NLMSG_ERROR: u16 = 2;
+#[cfg(any(target_os = "linux", test))]
+const NLMSG_DONE: u16 = 3;
+#[cfg(any(target_os = "linux", test))]
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+#[cfg(any(target_os = "linux", test))]
+const INET_DIAG_MSG_LEN: usize = 72;
+#[cfg(any(target_os = "linux", test))]
+const INET_DIAG_INFO: u16 = 2;
+/// Offsets of `tcpi_bytes_acked` / `tcpi_bytes_received` in `struct tcp_info`
+#[cfg(any(target_os = "linux", test))]
+const TCPI_BYTES_ACKED: usize = 120;
+#[cfg(any(target_os = "linux", test))]
+const TCPI_BYTES_RECEIVED: usize = 128;
+
+#[cfg(any(target_os = "linux", test))]
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn read_u16(buf: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_ne_bytes(buf.get(at..at + 2)?.try_into().ok()?))
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn read_u32(buf: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(buf.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn read_u64(buf: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(buf.get(at..at + 8)?.try_into().ok()?))
+}
+
+/// Parse one recv() buffer of sock_diag replies
+///
+/// Returns the sockets found and whether the dump is complete. Sockets
+/// without a `tcp_info` attribute (or from kernels older than 4.1, whose
+/// `tcp_info` lacks byte counters) are skipped.
+#[cfg(any(target_os = "linux", test))]
+fn parse_sock_diag(buf: &[u8]) -> (Vec<SocketStats>, bool) {
+    let mut sockets = Vec::new();
+    let mut offset = 0;
+
+    while offset + NLMSG_HDR_LEN <= buf.len() {
+        let Some(msg_len) = read_u32(buf, offset).map(|l| l as usize) else {
+            break;
+        };
+        let msg_type = read_u16(buf, offset + 4).unwrap_or(0);
+        if msg_len < NLMSG_HDR_LEN || offset + msg_len > buf.len() {
+            break;
+        }
+
+        match msg_type {
+            NLMSG_DONE | NLMSG_ERROR => return (sockets, true),
+            SOCK_DIAG_BY_FAMILY => {
+                let msg = &buf[offset + NLMSG_HDR_LEN..offset + msg_len];
+                if let Some(socket) = parse_inet_diag_msg(msg) {
+                    sockets.push(socket);
+                }
+            }
+            _ => {}
+        }
+
+        offset += align4(msg_len);
+    }
+
+    (sockets, false)
+}
+
+/// Parse a `struct inet_diag_msg` followed by its attributes
+#[cfg(any(target_os = "linux", test))]
+fn parse_inet_diag_msg(msg: &[u8]) -> Option<SocketStats> {
+    if msg.len() < INET_DIAG_MSG_LEN {
+        return None;
+    }
+
+    // Ports are in network byte order
+    let local_port = u16::from_be_bytes([msg[4], msg[5]]);
+    let inode = read_u32(msg, 68)?;
+
+    let mut offset = INET_DIAG_MSG_LEN;
+    while offset + 4 <= msg.len() {
+        let attr_len = read_u16(msg, offset)? as usize;
+        let attr_type = read_u16(msg, offset + 2)?;
+        if attr_len < 4 || offset + attr_len > msg.len() {
+            break;
+        }
+
+        if attr_type == INET_DIAG_INFO {
+            let info = &msg[offset + 4..offset + attr_len];
+            return Some(SocketStats {
+                inode,
+                local_port,
+                bytes_sent: read_u64(info, TCPI_BYTES_ACKED)?,
+                bytes_received: read_u64(info, TCPI_BYTES_RECEIVED)?,
+            });
+        }
+
+        offset += align4(attr_len);
+    }
+
+    None
+}
+
+/// Parse `nettop -P -L 1 -x -J bytes_in,bytes_out` CSV output
+///
+/// Rows look like `Google Chrome H.1234,5678,910,`; the PID is the suffix
+/// after the last dot of the first column.
+#[cfg(any(target_os = "macos", test))]
+fn parse_nettop_output(output: &str) -> Vec<ProcessTraffic> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',');
+            let (name, pid) = fields.next()?.trim().rsplit_once('.')?;
+            let pid = pid.parse::<u32>().ok()?;
+            let bytes_received = fields.next()?.trim().parse::<u64>().ok()?;
+            let bytes_sent = fields.next()?.trim().parse::<u64>().ok()?;
+
+            Some(ProcessTraffic {
+                pid,
+                process_name: name.to_string(),
+                bytes_sent,
+                bytes_received,
+                connections: 0,
+                ports: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::io;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    const RECV_BUFFER_SIZE: usize = 32 * 1024;
+
+    /// Short enough to pick up new sockets on the next sampling tick, long
+    /// enough that back-to-back callers in the same tick share one scan
+    const INODE_INDEX_TTL: Duration = Duration::from_millis(500);
+
+    static INODE_INDEX: Mutex<Option<InodeIndex>> = Mutex::new(None);
+
+    pub(super) fn collect() -> Option<Vec<ProcessTraffic>> {
+        let mut sockets = Vec::new();
+        for family in [libc::AF_INET, libc::AF_INET6] {
+            match query_tcp_sockets(family as u8) {
+                Ok(mut found) => sockets.append(&mut found),
+                Err(e) => {
+                    tracing::debug!("sock_diag unavailable: {}", e);
+                    return None;
+                }
+            }
+        }
+
+        let mut index = INODE_INDEX.lock().unwrap_or_else(|e| e.into_inner());
+        let index = index.get_or_insert_with(|| InodeIndex::new("/proc", INODE_INDEX_TTL));
+        Some(aggregate(&sockets, index.owners()))
+    }
+
+    /// Sum socket counters per owning process
+    pub(super) fn aggregate(
+        sockets: &[SocketStats],
+        owners: &HashMap<u32, (u32, String)>,
+    ) -> Vec<ProcessTraffic> {
+        let mut by_pid: HashMap<u32, ProcessTraffic> = HashMap::new();
+
+        for socket in sockets {
+            let Some((pid, name)) = owners.get(&socket.inode) else {
+                continue;
+            };
+            let entry = by_pid.entry(*pid).or_insert_with(|| ProcessTraffic {
+                pid: *pid,
+                process_name: name.clone(),
+                bytes_sent: 0,
+                bytes_received: 0,
+                connections: 0,
+                ports: Vec::new(),
+            });
+            entry.bytes_sent += socket.bytes_sent;
+            entry.bytes_received += socket.bytes_received;
+            entry.connections += 1;
+            if !entry.ports.contains(&socket.local_port) {
+                entry.ports.push(socket.local_port);
+            }
+        }
+
+        by_pid.into_values().collect()
+    }
+
+    /// Socket inode -> owning (pid, process name) index
+    ///
+    /// Walking `/proc/*/fd` is O(processes x fds), so the index is built in a
+    /// single pass and reused for `ttl` instead of being rescanned per socket.
+    pub(super) struct InodeIndex {
+        proc_root: PathBuf,
+        ttl: Duration,
+        built_at: Option<Instant>,
+        owners: HashMap<u32, (u32, String)>,
+        scans: usize,
+        fd_dirs_read: usize,
+    }
+
+    impl InodeIndex {
+        pub(super) fn new(proc_root: impl Into<PathBuf>, ttl: Duration) -> Self {
+            Self {
+                proc_root: proc_root.into(),
+                ttl,
+                built_at: None,
+                owners: HashMap::new(),
+                scans: 0,
+                fd_dirs_read: 0,
+            }
+        }
+
+        /// Get the index, rebuilding it if older than the TTL
+        pub(super) fn owners(&mut self) -> &HashMap<u32, (u32, String)> {
+            let stale = self
+                .built_at
+                .is_none_or(|built_at| built_at.elapsed() >= self.ttl);
+            if stale {
+                self.rebuild();
+            }
+            &self.owners
+        }
+
+        /// Resolve a single socket inode
+        #[cfg(test)]
+        pub(super) fn lookup(&mut self, inode: u32) -> Option<&(u32, String)> {
+            self.owners().get(&inode)
+        }
+
+        /// Number of full `/proc` walks performed
+        #[cfg(test)]
+        pub(super) fn scans(&self) -> usize {
+            self.scans
+        }
+
+        /// Number of `/proc/<pid>/fd` directories read across all scans
+        #[cfg(test)]
+        pub(super) fn fd_dirs_read(&self) -> usize {
+            self.fd_dirs_read
+        }
+
+        /// Processes we can't inspect (other users, already exited) are skipped.
+        fn rebuild(&mut self) {
+            self.owners.clear();
+            self.scans += 1;
+            self.built_at = Some(Instant::now());
+
+            let Ok(entries) = std::fs::read_dir(&self.proc_root) else {
+                return;
+            };
+
+            for entry in entries.flatten() {
+                let Some(pid) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|s| s.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+                    continue;
+                };
+                self.fd_dirs_read += 1;
+                let name = std::fs::read_to_string(entry.path().join("comm"))
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_default();
+
+                for fd in fds.flatten() {
+                    let Ok(target) = std::fs::read_link(fd.path()) else {
+                        continue;
+                    };
+                    let Some(inode) = target
+                        .to_str()
+                        .and_then(|t| t.strip_prefix("socket:["))
+                        .and_then(|t| t.strip_suffix(']'))
+                        .and_then(|t| t.parse::<u32>().ok())
+                    else {
+                        continue;
+                    };
+                    self.owners.insert(inode, (pid, name.clone()));
+                }
+            }
+        }
+    }
+
+    /// Dump all TCP sockets of one address family with their `tcp_info`
+    fn query_tcp_sockets(family: u8) -> io::Result<Vec<SocketStats>> {
+        // SAFETY: plain socket creation; the fd is closed by OwnedFd below
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_SOCK_DIAG,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: fd is a freshly created, owned descriptor
+        let fd = unsafe { <std::os::fd::OwnedFd as std::os::fd::FromRawFd>::from_raw_fd(fd) };
+        let raw = std::os::fd::AsRawFd::as_raw_fd(&fd);
+
+        // nlmsghdr followed by inet_diag_req_v2 (56 bytes)
+        let mut request = [0u8; NLMSG_HDR_LEN + 56];
+        let len = request.len() as u32;
+        request[0..4].copy_from_slice(&len.to_ne_bytes());
+        request[4..6].copy_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+        request[6..8].copy_from_slice(&flags.to_ne_bytes());
+        request[8..12].copy_from_slice(&1u32.to_ne_bytes());
+        request[16] = family;
+        request[17] = libc::IPPROTO_TCP as u8;
+        request[18] = 1 << (INET_DIAG_INFO - 1);
+        request[20..24].copy_from_slice(&u32::MAX.to_ne_bytes()); // all states
+
+        // SAFETY: request is a valid buffer of the given length
+        let sent = unsafe {
+            libc::send(
+                raw,
+                request.as_ptr() as *const libc::c_void,
+                request.len(),
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut sockets = Vec::new();
+        let mut buf = vec![0u8; RECV_BUFFER_SIZE];
+        loop {
+            // SAFETY: buf is a valid writable buffer of the given length
+            let received =
+                unsafe { libc::recv(raw, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if received < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if received == 0 {
+                break;
+            }
+
+            let (mut found, done) = parse_sock_diag(&buf[..received as usize]);
+            sockets.append(&mut found);
+            if done {
+                break;
+            }
+        }
+
+        Ok(sockets)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::*;
+    use std::process::Command;
+
+    pub(super) fn collect() -> Option<Vec<ProcessTraffic>> {
+        let output = Command::new("nettop")
+            .args(["-P", "-L", "1", "-x", "-J", "bytes_in,bytes_out"])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            tracing::debug!("nettop failed: {}", String::from_utf8_lossy(&output.stderr));
+            return None;
+        }
+
+        Some(parse_nettop_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build an RTM-style attribute
+    fn attr(attr_type: u16, payload: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&((payload.len() + 4) as u16).to_ne_bytes());
+        out.extend_from_slice(&attr_type.to_ne_bytes());
+        out.extend_from_slice(payload);
+        out.resize(align4(out.len()), 0);
+        out
+    }
+
+    /// Build a SOCK_DIAG_BY_FAMILY reply the way the kernel lays it out
+    fn diag_reply(port: u16, inode: u32, acked: u64, received: u64) -> Vec<u8> {
+        let mut msg = vec![0u8; INET_DIAG_MSG_LEN];
+        msg[0] = 2; // AF_INET
+        msg[1] = 10; // TCP_LISTEN
+        msg[4..6].copy_from_slice(&port.to_be_bytes());
+        msg[68..72].copy_from_slice(&inode.to_ne_bytes());
+
+        let mut tcp_info = vec![0u8; 232];
+        tcp_info[TCPI_BYTES_ACKED..TCPI_BYTES_ACKED + 8].copy_from_slice(&acked.to_ne_bytes());
+        tcp_info[TCPI_BYTES_RECEIVED..TCPI_BYTES_RECEIVED + 8]
+            .copy_from_slice(&received.to_ne_bytes());
+        msg.extend(attr(1, &[0u8; 8])); // INET_DIAG_MEMINFO, ignored
+        msg.extend(attr(INET_DIAG_INFO, &tcp_info));
+
+        nlmsg(SOCK_DIAG_BY_FAMILY, &msg)
+    }
+
+    fn nlmsg(msg_type: u16, payload: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&((payload.len() + NLMSG_HDR_LEN) as u32).to_ne_bytes());
+        out.extend_from_slice(&msg_type.to_ne_bytes());
+        out.extend_from_slice(&2u16.to_ne_bytes()); // NLM_F_MULTI
+        out.extend_from_slice(&1u32.to_ne_bytes());
+        out.extend_from_slice(&0u32.to_ne_bytes());
+        out.extend_from_slice(payload);
+        out
+    }
+
+    #[test]
+    fn test_parse_sock_diag_reply() {
+        let mut buf = diag_reply(3000, 41234, 1500, 64_000);
+        buf.extend(diag_reply(5432, 41235, 10, 20));
+        buf.extend(nlmsg(NLMSG_DONE, &0u32.to_ne_bytes()));
+
+        let (sockets, done) = parse_sock_diag(&buf);
+
+        assert!(done);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(
+            sockets[0],
+            SocketStats {
+                inode: 41234,
+                local_port: 3000,
+                bytes_sent: 1500,
+                bytes_received: 64_000,
+            }
+        );
+        assert_eq!(sockets[1].local_port, 5432);
+    }
+
+    #[test]
+    fn test_parse_sock_diag_partial_dump() {
+        let buf = diag_reply(8080, 7, 1, 2);
+        let (sockets, done) = parse_sock_diag(&buf);
+
+        assert!(!done);
+        assert_eq!(sockets.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_sock_diag_old_kernel_tcp_info() {
+        // Pre-4.1 tcp_info ends before the byte counters
+        let mut msg = vec![0u8; INET_DIAG_MSG_LEN];
+        msg.extend(attr(INET_DIAG_INFO, &[0u8; 104]));
+        let buf = nlmsg(SOCK_DIAG_BY_FAMILY, &msg);
+
+        let (sockets, _) = parse_sock_diag(&buf);
+        assert!(sockets.is_empty());
+    }
+
+    #[test]
+    fn test_parse_sock_diag_truncated() {
+        let buf = diag_reply(3000, 1, 1, 1);
+        let (sockets, done) = parse_sock_diag(&buf[..buf.len() - 10]);
+
+        assert!(!done);
+        assert!(sockets.is_empty());
+    }
+
+    #[test]
+    fn test_parse_nettop_output() {
+        // Captured from `nettop -P -L 1 -x -J bytes_in,bytes_out` on macOS 14
+        let output = ",bytes_in,bytes_out,
+launchd.1,0,0,
+mDNSResponder.312,1845920,602113,
+Google Chrome H.1877,98211032,4410921,
+node.48211,52314,11876,
+";
+
+        let result = parse_nettop_output(output);
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[1].pid, 312);
+        assert_eq!(result[1].process_name, "mDNSResponder");
+        assert_eq!(result[1].bytes_received, 1_845_920);
+        assert_eq!(result[1].bytes_sent, 602_113);
+        assert_eq!(result[2].process_name, "Google Chrome H");
+        assert_eq!(result[3].pid, 48211);
+    }
+
+    #[test]
+    fn test_parse_nettop_skips_garbage() {
+        let output = "time,bytes_in,bytes_out,\nnot-a-row\nnode.abc,1,2,\n";
+        assert!(parse_nettop_output(output).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_aggregate_by_owner() {
+        let sockets = vec![
+            SocketStats {
+                inode: 1,
+                local_port: 3000,
+                bytes_sent: 100,
+                bytes_received: 200,
+            },
+            SocketStats {
+                inode: 2,
+                local_port: 3000,
+                bytes_sent: 50,
+                bytes_received: 25,
+            },
+            SocketStats {
+                inode: 3,
+                local_port: 9999,
+                bytes_sent: 1,
+                bytes_received: 1,
+            },
+        ];
+        let owners = HashMap::from([(1, (42, "node".to_string())), (2, (42, "node".to_string()))]);
+
+        let result = linux::aggregate(&sockets, &owners);
+
+        // Inode 3 has no known owner and is dropped
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].pid, 42);
+        assert_eq!(result[0].bytes_sent, 150);
+        assert_eq!(result[0].bytes_received, 225);
+        assert_eq!(result[0].connections, 2);
+        assert_eq!(result[0].ports, vec![3000]);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn mock_proc(pids: u32, sockets_per_pid: u32) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        for pid in 1..=pids {
+            let dir = root.path().join(pid.to_string());
+            std::fs::create_dir_all(dir.join("fd")).unwrap();
+            std::fs::write(dir.join("comm"), format!("proc{}\n", pid)).unwrap();
+            // stdin-style non-socket fd
+            std::os::unix::fs::symlink("/dev/null", dir.join("fd").join("0")).unwrap();
+            for n in 0..sockets_per_pid {
+                let inode = pid * 1000 + n;
+                std::os::unix::fs::symlink(
+                    format!("socket:[{}]", inode),
+                    dir.join("fd").join((n + 3).to_string()),
+                )
+                .unwrap();
+            }
+        }
+        // Non-PID entries are ignored
+        std::fs::create_dir_all(root.path().join("net")).unwrap();
+        root
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_inode_index_resolves_mock_proc() {
+        let root = mock_proc(3, 2);
+        let mut index = linux::InodeIndex::new(root.path(), std::time::Duration::from_secs(60));
+
+        assert_eq!(index.lookup(2001), Some(&(2, "proc2".to_string())));
+        assert_eq!(index.lookup(3000), Some(&(3, "proc3".to_string())));
+        assert_eq!(index.lookup(999_999), None);
+        assert_eq!(index.owners().len(), 6);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_inode_index_scans_once_per_ttl() {
+        // 50 processes x 20 sockets = 1,000 connections
+        let root = mock_proc(50, 20);
+        let mut index = linux::InodeIndex::new(root.path(), std::time::Duration::from_secs(60));
+
+        let started = std::time::Instant::now();
+        let mut resolved = 0;
+        for pid in 1..=50u32 {
+            for n in 0..20u32 {
+                if index.lookup(pid * 1000 + n).is_some() {
+                    resolved += 1;
+                }
+            }
+        }
+        let elapsed = started.elapsed();
+
+        assert_eq!(resolved, 1_000);
+        // A per-connection walk would read 50 fd dirs for each of the 1,000 lookups
+        assert_eq!(index.scans(), 1);
+        assert_eq!(index.fd_dirs_read(), 50);
+        assert!(
+            elapsed < std::time::Duration::from_millis(500),
+            "1,000 lookups took {:?}",
+            elapsed
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_inode_index_rebuilds_after_ttl() {
+        let root = mock_proc(2, 1);
+        let mut index = linux::InodeIndex::new(root.path(), std::time::Duration::ZERO);
+
+        index.lookup(1000);
+        index.lookup(1000);
+
+        assert_eq!(index.scans(), 2);
+    }
+}
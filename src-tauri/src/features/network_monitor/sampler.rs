@@ -0,0 +1,122 @@
+//! Background network sampling with event emission
+
+use super::collector::TrafficCollector;
+use crate::error::{Result, SentinelError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::task::JoinHandle;
+
+/// Event emitted with every background collection
+pub const NETWORK_SNAPSHOT_EVENT: &str = "network-snapshot";
+
+/// Shortest sampling interval accepted from the frontend
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Single background task that collects snapshots and emits them as events
+#[derive(Default)]
+pub struct NetworkSampler {
+    task: Option<JoinHandle<()>>,
+}
+
+impl NetworkSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start sampling every `interval`
+    ///
+    /// Fails if a sampler is already running.
+    pub fn start(
+        &mut self,
+        collector: Arc<Mutex<TrafficCollector>>,
+        app: AppHandle,
+        interval: Duration,
+    ) -> Result<()> {
+        if self.is_running() {
+            return Err(SentinelError::InvalidInput {
+                message: "Network sampling is already running".to_string(),
+            });
+        }
+        if interval < MIN_INTERVAL {
+            return Err(SentinelError::InvalidInput {
+                message: format!(
+                    "Sampling interval must be at least {}ms",
+                    MIN_INTERVAL.as_millis()
+                ),
+            });
+        }
+
+        self.task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                ticker.tick().await;
+
+                // Collection refreshes sysinfo and may shell out, so keep it off
+                // the async workers; the mutex is held only for the collect call
+                let collector = collector.clone();
+                let snapshot = match tokio::task::spawn_blocking(move || {
+                    collector
+                        .lock()
+                        .unwrap_or_else(|e| {
+                            tracing::error!("Failed to lock network collector: {}", e);
+                            e.into_inner()
+                        })
+                        .collect()
+                })
+                .await
+                {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => {
+                        tracing::error!("Network sampling failed: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Err(e) = app.emit(NETWORK_SNAPSHOT_EVENT, &snapshot) {
+                    tracing::warn!("Failed to emit network snapshot: {}", e);
+                }
+            }
+        }));
+
+        tracing::info!("Network sampling started every {:?}", interval);
+        Ok(())
+    }
+
+    /// Stop sampling; returns whether a sampler was running
+    pub fn stop(&mut self) -> bool {
+        match self.task.take() {
+            Some(task) => {
+                task.abort();
+                tracing::info!("Network sampling stopped");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether the background task is alive
+    pub fn is_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+}
+
+impl Drop for NetworkSampler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler_starts_stopped() {
+        let mut sampler = NetworkSampler::new();
+        assert!(!sampler.is_running());
+        assert!(!sampler.stop());
+    }
+}
@@ -1,5 +1,7 @@
 //! Network monitoring data types
 
+use super::interfaces::InterfaceKind;
+use crate::core::metrics_buffer::MetricBucket;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +23,48 @@ pub struct NetworkSnapshot {
     pub processes: Vec<ProcessNetworkStats>,
     /// Protocol breakdown
     pub protocol_stats: ProtocolStats,
+    /// Per-interface traffic since the previous collection
+    pub interfaces: Vec<InterfaceTraffic>,
+}
+
+/// Traffic on one interface between two collections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceTraffic {
+    /// Interface name (e.g., en0, lo0, eth0)
+    pub name: String,
+    /// Bytes transmitted since the previous collection
+    pub bytes_sent: u64,
+    /// Bytes received since the previous collection
+    pub bytes_received: u64,
+}
+
+/// Network throughput history aggregated into min/max/avg buckets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownsampledNetworkHistory {
+    /// Send rate buckets in bytes/sec (oldest first)
+    pub bytes_sent_per_sec: Vec<MetricBucket>,
+    /// Receive rate buckets in bytes/sec (oldest first)
+    pub bytes_received_per_sec: Vec<MetricBucket>,
+}
+
+/// Throughput computed from the last two snapshots
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkRates {
+    /// Timestamp of the newer snapshot
+    pub timestamp: DateTime<Utc>,
+    /// Milliseconds between the two snapshots
+    pub interval_ms: u64,
+    /// Bytes sent per second
+    pub bytes_sent_per_sec: f64,
+    /// Bytes received per second
+    pub bytes_received_per_sec: f64,
+    /// Packets sent per second
+    pub packets_sent_per_sec: f64,
+    /// Packets received per second
+    pub packets_received_per_sec: f64,
 }
 
 /// Network statistics for a single process
@@ -31,10 +75,14 @@ pub struct ProcessNetworkStats {
     pub pid: u32,
     /// Process name
     pub process_name: String,
-    /// Bytes sent by this process
-    pub bytes_sent: u64,
-    /// Bytes received by this process
-    pub bytes_received: u64,
+    /// Bytes sent over this process's open sockets (None if unavailable)
+    pub bytes_sent: Option<u64>,
+    /// Bytes received over this process's open sockets (None if unavailable)
+    pub bytes_received: Option<u64>,
+    /// Send rate in bytes/sec since the previous collection (None if unavailable)
+    pub send_rate: Option<f64>,
+    /// Receive rate in bytes/sec since the previous collection (None if unavailable)
+    pub receive_rate: Option<f64>,
     /// Number of active connections
     pub connections: u32,
     /// Ports used by this process
@@ -77,8 +125,12 @@ pub struct NetworkInterfaceStats {
     pub mac_address: Option<String>,
     /// Interface type/description
     pub interface_type: String,
+    /// Kind inferred from the name and interface flags
+    pub kind: InterfaceKind,
     /// Is this interface active/up
     pub is_up: bool,
+    /// Counted in totals and history under the current interface filter
+    pub selected: bool,
 }
 
 #[cfg(test)]
@@ -99,16 +151,19 @@ mod tests {
         let stats = ProcessNetworkStats {
             pid: 12345,
             process_name: "test".to_string(),
-            bytes_sent: 1000,
-            bytes_received: 2000,
+            bytes_sent: Some(1000),
+            bytes_received: Some(2000),
+            send_rate: None,
+            receive_rate: None,
             connections: 5,
             ports: vec![8080, 443],
         };
 
         assert_eq!(stats.pid, 12345);
         assert_eq!(stats.process_name, "test");
-        assert_eq!(stats.bytes_sent, 1000);
-        assert_eq!(stats.bytes_received, 2000);
+        assert_eq!(stats.bytes_sent, Some(1000));
+        assert_eq!(stats.bytes_received, Some(2000));
+        assert!(stats.send_rate.is_none());
         assert_eq!(stats.connections, 5);
         assert_eq!(stats.ports.len(), 2);
     }
@@ -0,0 +1,466 @@
+//! Enrichment of remote addresses with host names and GeoIP data
+//!
+//! Reverse DNS can take seconds per address, so listing connections never
+//! waits for it: names come from a cache, and addresses not in it are
+//! resolved in the background, a few at a time, each result reported as it
+//! arrives and cached for the next listing. GeoIP lookups are in memory and
+//! done right away.
+
+use super::geoip::GeoIpDatabase;
+use super::types::{PortInfo, RemoteInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// Event emitted when a remote address is resolved in the background
+pub const CONNECTION_ENRICHED_EVENT: &str = "connection-enriched";
+
+/// How long a reverse DNS result, found or not, is reused
+pub const DNS_TTL: Duration = Duration::from_secs(600);
+
+/// Most addresses kept in the DNS cache
+const MAX_CACHED_ADDRESSES: usize = 4096;
+
+/// Most reverse lookups running at once
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
+
+/// Payload of `CONNECTION_ENRICHED_EVENT`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionEnrichedEvent {
+    /// Remote IP address, without port
+    pub address: String,
+    pub info: RemoteInfo,
+}
+
+/// Reverse DNS results, including lookups that found no name
+pub struct DnsCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<IpAddr, (Instant, Option<String>)>,
+}
+
+impl DnsCache {
+    /// Create a cache keeping up to `capacity` results for `ttl` each
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The name `ip` resolved to, if looked up less than the TTL before `now`
+    ///
+    /// `Some(None)` means the lookup found no name.
+    pub fn get(&self, ip: IpAddr, now: Instant) -> Option<Option<String>> {
+        self.entries
+            .get(&ip)
+            .filter(|(at, _)| now.saturating_duration_since(*at) < self.ttl)
+            .map(|(_, hostname)| hostname.clone())
+    }
+
+    /// Store the result of looking up `ip` at `now`
+    ///
+    /// When full, expired results are dropped first, then the oldest.
+    pub fn insert(&mut self, ip: IpAddr, hostname: Option<String>, now: Instant) {
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&ip) {
+            let ttl = self.ttl;
+            self.entries
+                .retain(|_, (at, _)| now.saturating_duration_since(*at) < ttl);
+            if self.entries.len() >= self.capacity {
+                if let Some(oldest) = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (at, _))| *at)
+                    .map(|(ip, _)| *ip)
+                {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(ip, (now, hostname));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The GeoIP database from settings
+struct LoadedDatabase {
+    path: PathBuf,
+    /// `None` if it couldn't be opened
+    database: Option<Arc<GeoIpDatabase>>,
+}
+
+/// Adds host names and GeoIP data to connections
+pub struct ConnectionEnricher {
+    cache: Mutex<DnsCache>,
+    /// Addresses being resolved
+    pending: Mutex<HashSet<IpAddr>>,
+    lookups: Arc<Semaphore>,
+    resolve: fn(IpAddr) -> Option<String>,
+    geoip: Mutex<Option<LoadedDatabase>>,
+}
+
+impl Default for ConnectionEnricher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionEnricher {
+    /// Create an enricher resolving names with the system resolver
+    pub fn new() -> Self {
+        Self::with_resolver(reverse_lookup)
+    }
+
+    /// Create an enricher resolving names with `resolve`
+    pub fn with_resolver(resolve: fn(IpAddr) -> Option<String>) -> Self {
+        Self {
+            cache: Mutex::new(DnsCache::new(DNS_TTL, MAX_CACHED_ADDRESSES)),
+            pending: Mutex::new(HashSet::new()),
+            lookups: Arc::new(Semaphore::new(MAX_CONCURRENT_LOOKUPS)),
+            resolve,
+            geoip: Mutex::new(None),
+        }
+    }
+
+    /// Use the GeoIP database at `path`, or none
+    ///
+    /// The file is read when the path changes. A missing or corrupt file is
+    /// logged and enrichment goes on without GeoIP data.
+    pub fn use_geoip_database(&self, path: Option<&Path>) {
+        let mut geoip = lock(&self.geoip);
+        if geoip.as_ref().map(|loaded| loaded.path.as_path()) == path {
+            return;
+        }
+
+        *geoip = path.map(|path| LoadedDatabase {
+            path: path.to_path_buf(),
+            database: GeoIpDatabase::open(path)
+                .map(Arc::new)
+                .inspect_err(|e| tracing::warn!("GeoIP lookups disabled: {}", e))
+                .ok(),
+        });
+    }
+
+    /// Fill in `remote_info` of the connections with a remote address
+    ///
+    /// Host names come from the cache only. Addresses not in it are resolved
+    /// in the background, and `on_resolved` is called for each one a name is
+    /// found for; the next call picks the names up from the cache.
+    pub fn enrich<F>(self: &Arc<Self>, connections: &mut [PortInfo], on_resolved: F)
+    where
+        F: Fn(ConnectionEnrichedEvent) + Send + Sync + 'static,
+    {
+        let database = self.database();
+        let now = Instant::now();
+        let mut unresolved = Vec::new();
+
+        {
+            let cache = lock(&self.cache);
+            for connection in connections.iter_mut() {
+                let Some(ip) = connection.remote_address.as_deref().and_then(remote_ip) else {
+                    continue;
+                };
+                let mut info = geo_info(database.as_deref(), ip);
+                match cache.get(ip, now) {
+                    Some(hostname) => info.hostname = hostname,
+                    None if should_resolve(ip) => unresolved.push(ip),
+                    None => {}
+                }
+                connection.remote_info = Some(info);
+            }
+        }
+
+        // Skips duplicates and addresses already being resolved
+        let mut pending = lock(&self.pending);
+        unresolved.retain(|ip| pending.insert(*ip));
+        drop(pending);
+
+        let on_resolved = Arc::new(on_resolved);
+        for ip in unresolved {
+            let enricher = Arc::clone(self);
+            let on_resolved = Arc::clone(&on_resolved);
+            tokio::spawn(async move {
+                let hostname = enricher.lookup(ip).await;
+                lock(&enricher.cache).insert(ip, hostname.clone(), Instant::now());
+                lock(&enricher.pending).remove(&ip);
+
+                if hostname.is_some() {
+                    let mut info = geo_info(enricher.database().as_deref(), ip);
+                    info.hostname = hostname;
+                    on_resolved(ConnectionEnrichedEvent {
+                        address: ip.to_string(),
+                        info,
+                    });
+                }
+            });
+        }
+    }
+
+    /// Reverse lookup of `ip`, waiting for a free slot first
+    async fn lookup(&self, ip: IpAddr) -> Option<String> {
+        let _permit = self.lookups.acquire().await.ok()?;
+        let resolve = self.resolve;
+        tokio::task::spawn_blocking(move || resolve(ip))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    fn database(&self) -> Option<Arc<GeoIpDatabase>> {
+        lock(&self.geoip)
+            .as_ref()
+            .and_then(|loaded| loaded.database.clone())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn geo_info(database: Option<&GeoIpDatabase>, ip: IpAddr) -> RemoteInfo {
+    database
+        .and_then(|database| database.lookup(ip))
+        .unwrap_or_default()
+}
+
+/// IP address of a remote address such as `1.2.3.4:443` or `[::1]:443`
+fn remote_ip(address: &str) -> Option<IpAddr> {
+    if let Ok(addr) = address.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+    let host = address
+        .rsplit_once(':')
+        .map_or(address, |(host, _)| host)
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    // Drop an IPv6 zone such as `%en0`
+    let host = host.split('%').next().unwrap_or(host);
+    host.parse().or_else(|_| address.parse()).ok()
+}
+
+/// Whether looking up `ip` could find anything worth showing
+fn should_resolve(ip: IpAddr) -> bool {
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast())
+}
+
+/// Reverse DNS lookup through the system resolver
+#[cfg(unix)]
+fn reverse_lookup(ip: IpAddr) -> Option<String> {
+    use std::ffi::CStr;
+    use std::mem::size_of;
+
+    /// `NI_MAXHOST`
+    const MAX_HOST: usize = 1025;
+    let mut host = [0 as libc::c_char; MAX_HOST];
+
+    let status = match ip {
+        IpAddr::V4(ip) => {
+            let mut addr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+            addr.sin_family = libc::AF_INET as libc::sa_family_t;
+            addr.sin_addr = libc::in_addr {
+                s_addr: u32::from_ne_bytes(ip.octets()),
+            };
+            #[cfg(target_os = "macos")]
+            {
+                addr.sin_len = size_of::<libc::sockaddr_in>() as u8;
+            }
+            unsafe {
+                libc::getnameinfo(
+                    &addr as *const libc::sockaddr_in as *const libc::sockaddr,
+                    size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    MAX_HOST as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+        IpAddr::V6(ip) => {
+            let mut addr: libc::sockaddr_in6 = unsafe { std::mem::zeroed() };
+            addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            addr.sin6_addr = libc::in6_addr {
+                s6_addr: ip.octets(),
+            };
+            #[cfg(target_os = "macos")]
+            {
+                addr.sin6_len = size_of::<libc::sockaddr_in6>() as u8;
+            }
+            unsafe {
+                libc::getnameinfo(
+                    &addr as *const libc::sockaddr_in6 as *const libc::sockaddr,
+                    size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    MAX_HOST as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+    if status != 0 {
+        return None;
+    }
+
+    let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+    let name = name.to_string_lossy().trim_end_matches('.').to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(not(unix))]
+fn reverse_lookup(_ip: IpAddr) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::port_discovery::{NetworkTraffic, PortState, Protocol};
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cache_expires_after_ttl() {
+        let mut cache = DnsCache::new(Duration::from_secs(60), 10);
+        let start = Instant::now();
+
+        cache.insert(ip("1.1.1.1"), Some("one.one.one.one".to_string()), start);
+        cache.insert(ip("10.9.9.9"), None, start);
+
+        let later = start + Duration::from_secs(59);
+        assert_eq!(
+            cache.get(ip("1.1.1.1"), later),
+            Some(Some("one.one.one.one".to_string()))
+        );
+        // Failed lookups are cached too
+        assert_eq!(cache.get(ip("10.9.9.9"), later), Some(None));
+        assert_eq!(cache.get(ip("8.8.8.8"), later), None);
+
+        let expired = start + Duration::from_secs(60);
+        assert_eq!(cache.get(ip("1.1.1.1"), expired), None);
+    }
+
+    #[test]
+    fn test_cache_evicts_expired_then_oldest() {
+        let mut cache = DnsCache::new(Duration::from_secs(60), 2);
+        let start = Instant::now();
+
+        cache.insert(ip("10.0.0.1"), None, start);
+        cache.insert(ip("10.0.0.2"), None, start + Duration::from_secs(30));
+        cache.insert(ip("10.0.0.3"), None, start + Duration::from_secs(40));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.get(ip("10.0.0.1"), start + Duration::from_secs(40)),
+            None
+        );
+
+        // Both expired by now
+        let late = start + Duration::from_secs(200);
+        cache.insert(ip("10.0.0.4"), None, late);
+        assert_eq!(cache.len(), 1);
+
+        // Updating a cached address doesn't evict anything
+        cache.insert(ip("10.0.0.5"), None, late);
+        cache.insert(ip("10.0.0.5"), Some("five".to_string()), late);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_remote_ip() {
+        assert_eq!(remote_ip("52.84.1.1:443"), Some(ip("52.84.1.1")));
+        assert_eq!(remote_ip("[2600:9000::1]:443"), Some(ip("2600:9000::1")));
+        assert_eq!(remote_ip("[fe80::1%en0]:443"), Some(ip("fe80::1")));
+        assert_eq!(remote_ip("2600:9000::1"), Some(ip("2600:9000::1")));
+        assert_eq!(remote_ip("localhost:443"), None);
+    }
+
+    fn connection(remote: &str) -> PortInfo {
+        PortInfo {
+            port: 50000,
+            protocol: Protocol::TCP,
+            process_name: "curl".to_string(),
+            pid: 1,
+            state: PortState::Established,
+            local_address: "10.0.0.2".to_string(),
+            remote_address: Some(remote.to_string()),
+            command: None,
+            traffic: NetworkTraffic::default(),
+            remote_info: None,
+        }
+    }
+
+    fn fake_resolve(ip: IpAddr) -> Option<String> {
+        std::thread::sleep(Duration::from_millis(50));
+        (ip == "52.84.1.1".parse::<IpAddr>().unwrap()).then(|| "cloudfront.net".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_enrich_resolves_in_background() {
+        let enricher = Arc::new(ConnectionEnricher::with_resolver(fake_resolve));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut connections = vec![
+            connection("52.84.1.1:443"),
+            connection("52.84.1.1:443"),
+            connection("127.0.0.1:5432"),
+        ];
+        let started = Instant::now();
+        enricher.enrich(&mut connections, move |event| {
+            let _ = tx.send(event);
+        });
+
+        // Listing doesn't wait for the resolver
+        assert!(started.elapsed() < Duration::from_millis(50));
+        assert_eq!(connections[0].remote_info, Some(RemoteInfo::default()));
+        assert_eq!(lock(&enricher.pending).len(), 1);
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.address, "52.84.1.1");
+        assert_eq!(event.info.hostname.as_deref(), Some("cloudfront.net"));
+        // Looked up once, loopback not at all
+        assert!(rx.recv().await.is_none());
+
+        let mut connections = vec![connection("52.84.1.1:443")];
+        enricher.enrich(&mut connections, |_| panic!("already cached"));
+        assert_eq!(
+            connections[0]
+                .remote_info
+                .as_ref()
+                .unwrap()
+                .hostname
+                .as_deref(),
+            Some("cloudfront.net")
+        );
+    }
+
+    #[test]
+    fn test_unusable_geoip_database_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let corrupt = dir.path().join("corrupt.mmdb");
+        std::fs::write(&corrupt, [0u8; 64]).unwrap();
+
+        let enricher = ConnectionEnricher::new();
+        enricher.use_geoip_database(Some(&dir.path().join("missing.mmdb")));
+        assert!(enricher.database().is_none());
+        enricher.use_geoip_database(Some(&corrupt));
+        assert!(enricher.database().is_none());
+        enricher.use_geoip_database(None);
+        assert!(lock(&enricher.geoip).is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! Connection filtering applied before results cross the Tauri bridge
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::types::{PortInfo, PortState, Protocol};
+
+/// Filter for connection listings
+///
+/// All fields are optional; unset fields match everything.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionFilter {
+    /// Only this protocol
+    pub protocol: Option<Protocol>,
+    /// Only this connection state
+    pub state: Option<PortState>,
+    /// Lowest local port (inclusive)
+    pub local_port_min: Option<u16>,
+    /// Highest local port (inclusive)
+    pub local_port_max: Option<u16>,
+    /// Case-insensitive substring of the remote address
+    pub remote_address: Option<String>,
+}
+
+impl ConnectionFilter {
+    /// Check whether a connection passes this filter
+    pub fn matches(&self, connection: &PortInfo) -> bool {
+        if self
+            .protocol
+            .as_ref()
+            .is_some_and(|p| *p != connection.protocol)
+        {
+            return false;
+        }
+        if self.state.as_ref().is_some_and(|s| *s != connection.state) {
+            return false;
+        }
+        if self.local_port_min.is_some_and(|min| connection.port < min) {
+            return false;
+        }
+        if self.local_port_max.is_some_and(|max| connection.port > max) {
+            return false;
+        }
+        if let Some(needle) = &self.remote_address {
+            let needle = needle.to_lowercase();
+            let matches_remote = connection
+                .remote_address
+                .as_ref()
+                .is_some_and(|remote| remote.to_lowercase().contains(&needle));
+            if !matches_remote {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Keep only the connections that pass this filter
+    pub fn apply(&self, connections: Vec<PortInfo>) -> Vec<PortInfo> {
+        connections
+            .into_iter()
+            .filter(|c| self.matches(c))
+            .collect()
+    }
+}
+
+/// Keep only connections owned by one of the given PIDs
+pub fn filter_by_pids(connections: Vec<PortInfo>, pids: &HashSet<u32>) -> Vec<PortInfo> {
+    connections
+        .into_iter()
+        .filter(|c| pids.contains(&c.pid))
+        .collect()
+}
+
+/// Expand root PIDs with all of their descendants
+pub fn with_descendants(roots: &[u32]) -> HashSet<u32> {
+    descendants_from_parents(roots, process_parents())
+}
+
+/// Ports each root PID, or one of its descendants, listens on
+///
+/// Every root gets an entry, sorted and without duplicates; UDP sockets
+/// count as listening.
+pub fn listening_ports_by_root(connections: &[PortInfo], roots: &[u32]) -> HashMap<u32, Vec<u16>> {
+    ports_by_root(connections, roots, &process_parents())
+}
+
+/// (child, parent) PIDs of every process
+fn process_parents() -> Vec<(u32, u32)> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+
+    sys.processes()
+        .iter()
+        .filter_map(|(pid, process)| Some((pid.as_u32(), process.parent()?.as_u32())))
+        .collect()
+}
+
+/// [`listening_ports_by_root`] with the given (child, parent) PIDs
+fn ports_by_root(
+    connections: &[PortInfo],
+    roots: &[u32],
+    parents: &[(u32, u32)],
+) -> HashMap<u32, Vec<u16>> {
+    roots
+        .iter()
+        .map(|root| {
+            let pids = descendants_from_parents(&[*root], parents.iter().copied());
+            let mut ports: Vec<u16> = connections
+                .iter()
+                .filter(|c| c.state == PortState::Listen || c.protocol == Protocol::UDP)
+                .filter(|c| pids.contains(&c.pid))
+                .map(|c| c.port)
+                .collect();
+            ports.sort_unstable();
+            ports.dedup();
+            (*root, ports)
+        })
+        .collect()
+}
+
+/// Walk a (child, parent) relation down from the roots
+fn descendants_from_parents(
+    roots: &[u32],
+    parents: impl IntoIterator<Item = (u32, u32)>,
+) -> HashSet<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (child, parent) in parents {
+        children.entry(parent).or_default().push(child);
+    }
+
+    let mut result: HashSet<u32> = HashSet::new();
+    let mut pending: Vec<u32> = roots.to_vec();
+    while let Some(pid) = pending.pop() {
+        if result.insert(pid) {
+            if let Some(kids) = children.get(&pid) {
+                pending.extend(kids);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::port_discovery::NetworkTraffic;
+
+    fn connection(
+        port: u16,
+        pid: u32,
+        protocol: Protocol,
+        state: PortState,
+        remote: Option<&str>,
+    ) -> PortInfo {
+        PortInfo {
+            port,
+            protocol,
+            process_name: format!("proc-{}", pid),
+            pid,
+            state,
+            local_address: "127.0.0.1".to_string(),
+            remote_address: remote.map(String::from),
+            command: None,
+            traffic: NetworkTraffic::default(),
+            remote_info: None,
+        }
+    }
+
+    fn sample() -> Vec<PortInfo> {
+        vec![
+            connection(3000, 10, Protocol::TCP, PortState::Listen, None),
+            connection(
+                3000,
+                10,
+                Protocol::TCP,
+                PortState::Established,
+                Some("192.168.1.5:52144"),
+            ),
+            connection(5353, 20, Protocol::UDP, PortState::Unknown, None),
+            connection(
+                54022,
+                30,
+                Protocol::TCP,
+                PortState::Established,
+                Some("140.82.112.25:443"),
+            ),
+            connection(
+                8080,
+                40,
+                Protocol::TCP,
+                PortState::TimeWait,
+                Some("10.0.0.2:9000"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_default_filter_matches_everything() {
+        let result = ConnectionFilter::default().apply(sample());
+        assert_eq!(result.len(), 5);
+    }
+
+    #[test]
+    fn test_filter_by_protocol_and_state() {
+        let filter = ConnectionFilter {
+            protocol: Some(Protocol::TCP),
+            state: Some(PortState::Established),
+            ..Default::default()
+        };
+
+        let result = filter.apply(sample());
+
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|c| c.state == PortState::Established));
+    }
+
+    #[test]
+    fn test_filter_by_local_port_range() {
+        let filter = ConnectionFilter {
+            local_port_min: Some(3000),
+            local_port_max: Some(8080),
+            ..Default::default()
+        };
+
+        let ports: Vec<u16> = filter.apply(sample()).iter().map(|c| c.port).collect();
+        assert_eq!(ports, vec![3000, 3000, 5353, 8080]);
+    }
+
+    #[test]
+    fn test_filter_by_remote_substring() {
+        let filter = ConnectionFilter {
+            remote_address: Some(":443".to_string()),
+            ..Default::default()
+        };
+
+        let result = filter.apply(sample());
+
+        // Listeners have no remote address and never match
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].pid, 30);
+    }
+
+    #[test]
+    fn test_filter_by_pids() {
+        let pids = HashSet::from([10, 40]);
+        let result = filter_by_pids(sample(), &pids);
+
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|c| pids.contains(&c.pid)));
+    }
+
+    #[test]
+    fn test_descendants_from_parents() {
+        // 1 -> 10 -> 11 -> 12, 1 -> 20, 99 unrelated
+        let parents = vec![(10, 1), (11, 10), (12, 11), (20, 1), (99, 98)];
+
+        let result = descendants_from_parents(&[10], parents);
+
+        assert_eq!(result, HashSet::from([10, 11, 12]));
+    }
+
+    #[test]
+    fn test_ports_by_root_include_descendants() {
+        // 10 -> 11, 20 and 40 on their own; 30 only has an outgoing connection
+        let mut connections = sample();
+        connections.push(connection(9229, 11, Protocol::TCP, PortState::Listen, None));
+        let parents = vec![(11, 10)];
+
+        let ports = ports_by_root(&connections, &[10, 20, 30], &parents);
+
+        assert_eq!(ports[&10], vec![3000, 9229]);
+        assert_eq!(ports[&20], vec![5353]);
+        assert!(ports[&30].is_empty());
+    }
+
+    #[test]
+    fn test_with_descendants_includes_root() {
+        let own = std::process::id();
+        assert!(with_descendants(&[own]).contains(&own));
+    }
+}
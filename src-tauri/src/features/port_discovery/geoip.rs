@@ -0,0 +1,454 @@
+//! Offline GeoIP and ASN lookups in MaxMind databases
+//!
+//! Reads the MaxMind DB format (`.mmdb`) of GeoLite2 and GeoIP2 files
+//! directly: a binary search tree over the address bits whose leaves point
+//! into a section of typed values. Every read is bounds-checked, so a
+//! truncated or corrupt file yields no data rather than a panic.
+
+use super::types::RemoteInfo;
+use crate::error::{Result, SentinelError};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Marks the start of the metadata section
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+
+/// The metadata section is within this many bytes of the end of the file
+const METADATA_MAX_SIZE: usize = 128 * 1024;
+
+/// Bytes of zeros between the search tree and the data section
+const DATA_SECTION_SEPARATOR: usize = 16;
+
+/// Deepest nesting of maps, arrays and pointers decoded
+const MAX_DEPTH: usize = 16;
+
+/// A decoded value
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Uint(u64),
+    Int(i32),
+    Double(f64),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    Map(Vec<(String, Value)>),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn path(&self, keys: &[&str]) -> Option<&Value> {
+        keys.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Uint(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// An opened MaxMind database
+pub struct GeoIpDatabase {
+    data: Vec<u8>,
+    node_count: usize,
+    /// Bits per record, two records per node
+    record_size: usize,
+    ip_version: u64,
+    /// Offset of the data section
+    data_start: usize,
+    /// Node IPv4 lookups start at in an IPv6 tree, past the 96 leading zero bits
+    ipv4_start: usize,
+}
+
+impl GeoIpDatabase {
+    /// Read the database at `path`
+    ///
+    /// # Errors
+    /// Returns `FileIoError` if the file can't be read and
+    /// `InvalidGeoIpDatabase` if it isn't a MaxMind database.
+    pub fn open(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).map_err(|source| SentinelError::FileIoError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_bytes(data).map_err(|reason| SentinelError::InvalidGeoIpDatabase {
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        })
+    }
+
+    fn from_bytes(data: Vec<u8>) -> std::result::Result<Self, &'static str> {
+        let search_from = data.len().saturating_sub(METADATA_MAX_SIZE);
+        let marker = data[search_from..]
+            .windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)
+            .map(|position| search_from + position)
+            .ok_or("no metadata section")?;
+
+        let metadata_start = marker + METADATA_MARKER.len();
+        let decoder = Decoder {
+            data: &data,
+            base: metadata_start,
+        };
+        let (metadata, _) = decoder
+            .decode(metadata_start, 0)
+            .ok_or("unreadable metadata")?;
+        let field = |key: &str| {
+            metadata
+                .get(key)
+                .and_then(Value::as_u64)
+                .ok_or("metadata lacks node_count, record_size or ip_version")
+        };
+
+        let node_count = field("node_count")? as usize;
+        let record_size = field("record_size")? as usize;
+        let ip_version = field("ip_version")?;
+        if ![24, 28, 32].contains(&record_size) {
+            return Err("unsupported record size");
+        }
+        if ip_version != 4 && ip_version != 6 {
+            return Err("unsupported IP version");
+        }
+
+        let data_start = node_count
+            .checked_mul(record_size / 4)
+            .and_then(|tree_size| tree_size.checked_add(DATA_SECTION_SEPARATOR))
+            .filter(|data_start| *data_start <= marker)
+            .ok_or("search tree larger than the file")?;
+
+        let mut database = Self {
+            data,
+            node_count,
+            record_size,
+            ip_version,
+            data_start,
+            ipv4_start: 0,
+        };
+        if ip_version == 6 {
+            let mut node = 0;
+            for _ in 0..96 {
+                if node >= node_count {
+                    break;
+                }
+                node = database.record(node, 0).ok_or("truncated search tree")?;
+            }
+            database.ipv4_start = node;
+        }
+        Ok(database)
+    }
+
+    /// GeoIP and ASN data of `ip`, `None` if the database has none
+    pub fn lookup(&self, ip: IpAddr) -> Option<RemoteInfo> {
+        let (bits, bit_count, start) = match ip {
+            IpAddr::V4(ip) => (u128::from(u32::from(ip)), 32, self.ipv4_start),
+            IpAddr::V6(_) if self.ip_version == 4 => return None,
+            IpAddr::V6(ip) => (u128::from(ip), 128, 0),
+        };
+
+        let mut node = start;
+        for i in (0..bit_count).rev() {
+            if node >= self.node_count {
+                break;
+            }
+            node = self.record(node, (bits >> i) & 1)?;
+        }
+        // Equal to the node count means no data; smaller means bits ran out
+        let pointer = node.checked_sub(self.node_count + DATA_SECTION_SEPARATOR)?;
+
+        let decoder = Decoder {
+            data: &self.data,
+            base: self.data_start,
+        };
+        let (value, _) = decoder.decode(self.data_start.checked_add(pointer)?, 0)?;
+        let string = |keys: &[&str]| value.path(keys).and_then(Value::as_str).map(String::from);
+
+        let info = RemoteInfo {
+            hostname: None,
+            country_code: string(&["country", "iso_code"]),
+            country: string(&["country", "names", "en"]),
+            city: string(&["city", "names", "en"]),
+            asn: value
+                .get("autonomous_system_number")
+                .and_then(Value::as_u64)
+                .and_then(|asn| u32::try_from(asn).ok()),
+            organization: string(&["autonomous_system_organization"]),
+        };
+        (info != RemoteInfo::default()).then_some(info)
+    }
+
+    /// The left (`bit` 0) or right record of `node`
+    fn record(&self, node: usize, bit: u128) -> Option<usize> {
+        let node_size = self.record_size / 4;
+        let start = node.checked_mul(node_size)?;
+        let bytes = self.data.get(start..start + node_size)?;
+
+        Some(match (self.record_size, bit) {
+            (24, 0) => be(&bytes[0..3]),
+            (24, _) => be(&bytes[3..6]),
+            (28, 0) => ((bytes[3] as usize & 0xF0) << 20) | be(&bytes[0..3]),
+            (28, _) => ((bytes[3] as usize & 0x0F) << 24) | be(&bytes[4..7]),
+            (_, 0) => be(&bytes[0..4]),
+            (_, _) => be(&bytes[4..8]),
+        })
+    }
+}
+
+/// Big-endian unsigned integer
+fn be(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 8) | *byte as usize)
+}
+
+/// Decodes values of the data or metadata section
+struct Decoder<'a> {
+    data: &'a [u8],
+    /// Offset pointers are relative to
+    base: usize,
+}
+
+impl Decoder<'_> {
+    /// Decode the value at `offset`
+    ///
+    /// Returns the value and the offset just past it.
+    fn decode(&self, offset: usize, depth: usize) -> Option<(Value, usize)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let control = *self.data.get(offset)?;
+        let mut pos = offset + 1;
+        let mut kind = control >> 5;
+
+        if kind == 1 {
+            let size = ((control >> 3) & 0x3) as usize;
+            let bytes = self.data.get(pos..pos + size + 1)?;
+            let high = (control & 0x7) as usize;
+            let pointer = match size {
+                0 => (high << 8) | be(bytes),
+                1 => ((high << 16) | be(bytes)) + 2_048,
+                2 => ((high << 24) | be(bytes)) + 526_336,
+                _ => be(bytes),
+            };
+            let (value, _) = self.decode(self.base.checked_add(pointer)?, depth + 1)?;
+            return Some((value, pos + size + 1));
+        }
+
+        if kind == 0 {
+            kind = 7u8.checked_add(*self.data.get(pos)?)?;
+            pos += 1;
+        }
+
+        let mut size = (control & 0x1f) as usize;
+        if size >= 29 {
+            let length = size - 28;
+            let bytes = self.data.get(pos..pos + length)?;
+            pos += length;
+            size = match length {
+                1 => 29 + be(bytes),
+                2 => 285 + be(bytes),
+                _ => 65_821 + be(bytes),
+            };
+        }
+        let bytes = || self.data.get(pos..pos.checked_add(size)?);
+
+        let value = match kind {
+            2 => Value::String(std::str::from_utf8(bytes()?).ok()?.to_string()),
+            3 => Value::Double(f64::from_be_bytes(bytes()?.try_into().ok()?)),
+            4 => Value::Bytes(bytes()?.to_vec()),
+            5 | 6 | 9 | 10 if size <= 8 => Value::Uint(be(bytes()?) as u64),
+            // Wider than any field read here
+            10 => Value::Bytes(bytes()?.to_vec()),
+            8 if size <= 4 => Value::Int(be(bytes()?) as u32 as i32),
+            15 => Value::Double(f32::from_be_bytes(bytes()?.try_into().ok()?) as f64),
+            // The size is the value
+            14 => return Some((Value::Bool(size != 0), pos)),
+            7 => {
+                let mut entries = Vec::new();
+                for _ in 0..size {
+                    let (key, next) = self.decode(pos, depth + 1)?;
+                    let Value::String(key) = key else {
+                        return None;
+                    };
+                    let (value, next) = self.decode(next, depth + 1)?;
+                    entries.push((key, value));
+                    pos = next;
+                }
+                return Some((Value::Map(entries), pos));
+            }
+            11 => {
+                let mut items = Vec::new();
+                for _ in 0..size {
+                    let (item, next) = self.decode(pos, depth + 1)?;
+                    items.push(item);
+                    pos = next;
+                }
+                return Some((Value::Array(items), pos));
+            }
+            _ => return None,
+        };
+        Some((value, pos + size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Vec<u8> {
+        let mut bytes = match s.len() {
+            len @ 0..29 => vec![0x40 | len as u8],
+            // One extra size byte
+            len => vec![0x40 | 29, (len - 29) as u8],
+        };
+        bytes.extend(s.as_bytes());
+        bytes
+    }
+
+    fn uint16(n: u16) -> Vec<u8> {
+        let mut bytes = vec![0xA2];
+        bytes.extend(n.to_be_bytes());
+        bytes
+    }
+
+    fn uint32(n: u32) -> Vec<u8> {
+        let mut bytes = vec![0xC4];
+        bytes.extend(n.to_be_bytes());
+        bytes
+    }
+
+    fn map(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = vec![0xE0 | entries.len() as u8];
+        for (key, value) in entries {
+            bytes.extend(string(key));
+            bytes.extend(value);
+        }
+        bytes
+    }
+
+    /// An IPv4 database of one node: addresses below 128.0.0.0 have data,
+    /// with the organization behind a pointer
+    fn database() -> Vec<u8> {
+        let organization = string("AMAZON-02");
+        let record = map(&[
+            (
+                "country",
+                map(&[
+                    ("iso_code", string("US")),
+                    ("names", map(&[("en", string("United States"))])),
+                ]),
+            ),
+            ("autonomous_system_number", uint32(16509)),
+            // Pointer to offset 0 of the data section
+            ("autonomous_system_organization", vec![0x20, 0x00]),
+        ]);
+
+        let node_count = 1;
+        let left = (node_count + DATA_SECTION_SEPARATOR + organization.len()) as u32;
+        let right = node_count as u32;
+
+        let mut bytes = Vec::new();
+        bytes.extend(&left.to_be_bytes()[1..]);
+        bytes.extend(&right.to_be_bytes()[1..]);
+        bytes.extend([0; DATA_SECTION_SEPARATOR]);
+        bytes.extend(organization);
+        bytes.extend(record);
+        bytes.extend(METADATA_MARKER);
+        bytes.extend(map(&[
+            ("node_count", uint32(node_count as u32)),
+            ("record_size", uint16(24)),
+            ("ip_version", uint16(4)),
+            ("database_type", string("Test")),
+        ]));
+        bytes
+    }
+
+    #[test]
+    fn test_lookup() {
+        let database = GeoIpDatabase::from_bytes(database()).unwrap();
+
+        let info = database.lookup("52.84.1.1".parse().unwrap()).unwrap();
+        assert_eq!(info.country_code.as_deref(), Some("US"));
+        assert_eq!(info.country.as_deref(), Some("United States"));
+        assert_eq!(info.asn, Some(16509));
+        assert_eq!(info.organization.as_deref(), Some("AMAZON-02"));
+        assert!(info.city.is_none());
+
+        assert!(database.lookup("200.1.1.1".parse().unwrap()).is_none());
+        // IPv6 addresses aren't in an IPv4 database
+        assert!(database.lookup("2600::1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = GeoIpDatabase::open(&dir.path().join("GeoLite2-ASN.mmdb"))
+            .err()
+            .unwrap();
+        assert!(matches!(err, SentinelError::FileIoError { .. }));
+    }
+
+    #[test]
+    fn test_corrupt_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("GeoLite2-City.mmdb");
+
+        std::fs::write(&path, b"not a database").unwrap();
+        let err = GeoIpDatabase::open(&path).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Invalid GeoIP database {}: no metadata section",
+                path.display()
+            )
+        );
+
+        // Cut off inside the metadata
+        let bytes = database();
+        let cut = bytes.len() - 10;
+        assert!(GeoIpDatabase::from_bytes(bytes[..cut].to_vec()).is_err());
+
+        // More nodes than the file holds
+        let mut bytes = database();
+        let key = bytes
+            .windows(10)
+            .rposition(|window| window == b"node_count")
+            .unwrap();
+        // The key is followed by the control byte of the count
+        bytes[key + 11] = 0x7f;
+        assert_eq!(
+            GeoIpDatabase::from_bytes(bytes).err(),
+            Some("search tree larger than the file")
+        );
+    }
+
+    #[test]
+    fn test_corrupt_data_yields_nothing() {
+        let mut bytes = database();
+        // Point the left record past the end of the file
+        bytes[0..3].copy_from_slice(&[0xff, 0xff, 0xff]);
+        let database = GeoIpDatabase::from_bytes(bytes).unwrap();
+        assert!(database.lookup("52.84.1.1".parse().unwrap()).is_none());
+
+        // A pointer to itself
+        let looping = Decoder {
+            data: &[0x20, 0x00],
+            base: 0,
+        };
+        assert!(looping.decode(0, 0).is_none());
+    }
+}
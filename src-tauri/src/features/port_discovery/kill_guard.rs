@@ -0,0 +1,404 @@
+//! Two-phase kills with a soft undo
+//!
+//! A kill can't be taken back, and the wrong PID (an IDE, say) is easy to
+//! hit. So a kill is first requested, which describes the process and hands
+//! out a single-use token, and only performed when that token is confirmed
+//! within `CONFIRMATION_WINDOW`. Sentinel-managed processes are stopped
+//! gracefully through the process manager rather than signalled. The last
+//! few kills are remembered so the UI can offer to start the process again.
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users};
+
+use super::scanner::{check_killable, process_command, PortScanner};
+use super::types::{KillCandidate, KillConfirmation, KillSignal, KilledProcess};
+use crate::core::ProcessManager;
+use crate::error::{Result, SentinelError};
+use crate::models::{ProcessConfig, ProcessInfo};
+
+/// How long a kill confirmation token is accepted
+pub const CONFIRMATION_WINDOW: Duration = Duration::from_secs(30);
+
+/// Killed processes remembered for `recently_killed`
+pub const RECENTLY_KILLED_LEN: usize = 20;
+
+/// A requested kill waiting for its confirmation
+struct PendingKill {
+    candidate: KillCandidate,
+    graceful: bool,
+    expires_at: DateTime<Utc>,
+}
+
+/// Pending kill confirmations and recently killed processes
+#[derive(Default)]
+pub struct KillGuard {
+    pending: HashMap<String, PendingKill>,
+    /// Newest last
+    killed: VecDeque<KilledProcess>,
+}
+
+impl KillGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hand out a token that confirms killing `candidate` until
+    /// `CONFIRMATION_WINDOW` after `now`
+    pub fn request(
+        &mut self,
+        candidate: KillCandidate,
+        graceful: bool,
+        now: DateTime<Utc>,
+    ) -> KillConfirmation {
+        // Tokens nobody confirmed would otherwise pile up
+        self.pending.retain(|_, pending| pending.expires_at >= now);
+
+        let token = uuid::Uuid::new_v4().to_string();
+        let expires_at = now
+            + chrono::Duration::from_std(CONFIRMATION_WINDOW).unwrap_or(chrono::Duration::zero());
+        self.pending.insert(
+            token.clone(),
+            PendingKill {
+                candidate: candidate.clone(),
+                graceful,
+                expires_at,
+            },
+        );
+        KillConfirmation {
+            token,
+            expires_at,
+            candidate,
+        }
+    }
+
+    /// Redeem `token`, returning the process to kill and whether to kill it
+    /// gracefully
+    ///
+    /// A token works once, whether or not the kill then succeeds.
+    pub fn confirm(&mut self, token: &str, now: DateTime<Utc>) -> Result<(KillCandidate, bool)> {
+        let pending = self
+            .pending
+            .remove(token)
+            .ok_or(SentinelError::KillConfirmationInvalid { expired: false })?;
+        if now > pending.expires_at {
+            return Err(SentinelError::KillConfirmationInvalid { expired: true });
+        }
+        Ok((pending.candidate, pending.graceful))
+    }
+
+    /// Remember a killed process, forgetting the oldest beyond
+    /// `RECENTLY_KILLED_LEN`
+    pub fn record(&mut self, killed: KilledProcess) {
+        if self.killed.len() == RECENTLY_KILLED_LEN {
+            self.killed.pop_front();
+        }
+        self.killed.push_back(killed);
+    }
+
+    /// Recently killed processes, newest first
+    pub fn recently_killed(&self) -> Vec<KilledProcess> {
+        self.killed.iter().rev().cloned().collect()
+    }
+}
+
+/// Describe the process `pid` for a kill confirmation
+///
+/// `port` is the port the kill was requested for and `name` the process
+/// name the scan reported. `managed` and `configs` are the processes
+/// Sentinel runs and has configured, to tell whether it can start the
+/// process again.
+///
+/// # Errors
+/// Fails if no process has the PID, or it is one `kill_pid` refuses.
+pub fn describe(
+    pid: u32,
+    port: Option<u16>,
+    name: Option<&str>,
+    managed: &[ProcessInfo],
+    configs: &[ProcessConfig],
+) -> Result<KillCandidate> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cmd(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always)
+            .with_exe(UpdateKind::Always)
+            .with_user(UpdateKind::Always),
+    );
+    let process = sys
+        .process(Pid::from_u32(pid))
+        .ok_or_else(|| SentinelError::Other(format!("No process has PID {}", pid)))?;
+
+    let process_name = name
+        .map(str::to_string)
+        .unwrap_or_else(|| process.name().to_string_lossy().into_owned());
+    check_killable(pid, &process_name)?;
+
+    let cwd = process.cwd().map(|cwd| cwd.display().to_string());
+    let args: Vec<String> = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let owner = process.user_id().and_then(|uid| {
+        Users::new_with_refreshed_list()
+            .get_user_by_id(uid)
+            .map(|user| user.name().to_string())
+    });
+    let managed_process = managed
+        .iter()
+        .find(|info| info.pid == Some(pid) && info.is_running())
+        .map(|info| info.name.clone());
+    let config_process = managed_process
+        .clone()
+        .or_else(|| find_config(configs, cwd.as_deref(), &args));
+
+    let warning = managed_process.as_ref().map(|managed| {
+        format!(
+            "'{}' is managed by Sentinel; it will be stopped gracefully through Sentinel",
+            managed
+        )
+    });
+
+    Ok(KillCandidate {
+        pid,
+        port,
+        process_name,
+        command: process_command(process),
+        cwd,
+        owner,
+        started_at: DateTime::from_timestamp(process.start_time() as i64, 0),
+        managed_process,
+        config_process,
+        warning,
+    })
+}
+
+/// Configured process that runs the same program in the same directory
+fn find_config(configs: &[ProcessConfig], cwd: Option<&str>, args: &[String]) -> Option<String> {
+    let cwd = Path::new(cwd?);
+    let program = file_name(args.first()?);
+    configs
+        .iter()
+        .find(|config| config.cwd.as_deref() == Some(cwd) && file_name(&config.command) == program)
+        .map(|config| config.name.clone())
+}
+
+fn file_name(program: &str) -> &str {
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program)
+}
+
+/// Kill `candidate`, stopping it through `manager` if Sentinel manages it
+///
+/// Returns the signal that terminated an unmanaged process.
+///
+/// # Errors
+/// Fails if the PID now belongs to another process, which means the one
+/// that was confirmed already exited.
+pub async fn execute(
+    candidate: &KillCandidate,
+    graceful: bool,
+    manager: &ProcessManager,
+    scanner: &PortScanner,
+) -> Result<Option<KillSignal>> {
+    if let Some(name) = &candidate.managed_process {
+        manager.stop_gracefully(name).await?;
+        return Ok(None);
+    }
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[Pid::from_u32(candidate.pid)]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    let started_at = sys
+        .process(Pid::from_u32(candidate.pid))
+        .and_then(|process| DateTime::from_timestamp(process.start_time() as i64, 0));
+    if started_at.is_none() || started_at != candidate.started_at {
+        return Err(SentinelError::Other(format!(
+            "{} (PID {}) already exited",
+            candidate.process_name, candidate.pid
+        )));
+    }
+
+    let signal = scanner
+        .kill_pid(candidate.pid, &candidate.process_name, graceful)
+        .await?;
+    Ok(Some(signal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str, command: &str, args: &[&str], cwd: Option<&str>) -> ProcessConfig {
+        ProcessConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: cwd.map(Into::into),
+            auto_restart: Some(false),
+            restart_limit: 0,
+            restart_delay: Some(100),
+            ..Default::default()
+        }
+    }
+
+    fn candidate(pid: u32) -> KillCandidate {
+        KillCandidate {
+            pid,
+            port: Some(3000),
+            process_name: "node".to_string(),
+            command: Some("node server.js".to_string()),
+            cwd: Some("/work/app".to_string()),
+            owner: Some("dev".to_string()),
+            started_at: None,
+            managed_process: None,
+            config_process: None,
+            warning: None,
+        }
+    }
+
+    #[test]
+    fn test_token_is_single_use() {
+        let mut guard = KillGuard::new();
+        let now = Utc::now();
+        let confirmation = guard.request(candidate(42), true, now);
+        assert_eq!(confirmation.expires_at, now + chrono::Duration::seconds(30));
+
+        let (confirmed, graceful) = guard.confirm(&confirmation.token, now).unwrap();
+        assert_eq!(confirmed.pid, 42);
+        assert!(graceful);
+
+        let again = guard.confirm(&confirmation.token, now).unwrap_err();
+        assert!(matches!(
+            again,
+            SentinelError::KillConfirmationInvalid { expired: false }
+        ));
+        assert!(guard.confirm("made-up", now).is_err());
+    }
+
+    #[test]
+    fn test_token_expires() {
+        let mut guard = KillGuard::new();
+        let now = Utc::now();
+        let confirmation = guard.request(candidate(42), false, now);
+
+        let late = now + chrono::Duration::seconds(31);
+        let err = guard.confirm(&confirmation.token, late).unwrap_err();
+        assert!(matches!(
+            err,
+            SentinelError::KillConfirmationInvalid { expired: true }
+        ));
+        // Expiry uses the token up too
+        assert!(guard.confirm(&confirmation.token, now).is_err());
+
+        // Tokens still inside the window are kept when others are dropped
+        let fresh = guard.request(candidate(43), false, now);
+        let stale = guard.request(candidate(44), false, now - chrono::Duration::minutes(5));
+        guard.request(candidate(45), false, now);
+        assert!(guard.confirm(&fresh.token, now).is_ok());
+        assert!(guard.confirm(&stale.token, now).is_err());
+    }
+
+    #[test]
+    fn test_recently_killed_newest_first() {
+        let mut guard = KillGuard::new();
+        for pid in 0..RECENTLY_KILLED_LEN as u32 + 5 {
+            guard.record(KilledProcess {
+                candidate: candidate(pid),
+                killed_at: Utc::now(),
+                signal: Some(KillSignal::Sigterm),
+            });
+        }
+
+        let killed = guard.recently_killed();
+        assert_eq!(killed.len(), RECENTLY_KILLED_LEN);
+        assert_eq!(killed[0].candidate.pid, RECENTLY_KILLED_LEN as u32 + 4);
+        assert_eq!(killed.last().unwrap().candidate.pid, 5);
+    }
+
+    #[test]
+    fn test_find_config_matches_program_and_cwd() {
+        let configs = vec![
+            config("web", "node", &[], Some("/work/web")),
+            config("api", "/usr/bin/node", &[], Some("/work/api")),
+        ];
+        let args = vec!["node".to_string(), "server.js".to_string()];
+
+        assert_eq!(
+            find_config(&configs, Some("/work/api"), &args).as_deref(),
+            Some("api")
+        );
+        assert_eq!(find_config(&configs, Some("/work/other"), &args), None);
+        assert_eq!(find_config(&configs, None, &args), None);
+    }
+
+    #[test]
+    fn test_describe_missing_process() {
+        assert!(describe(u32::MAX, None, None, &[], &[]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_managed_process_is_stopped_through_sentinel() {
+        let manager = ProcessManager::new();
+        let info = manager
+            .start(config("sleeper", "sleep", &["30"], None))
+            .await
+            .unwrap();
+        let pid = info.pid.unwrap();
+
+        let candidate = describe(pid, None, None, &manager.list().await, &[]).unwrap();
+        assert_eq!(candidate.managed_process.as_deref(), Some("sleeper"));
+        assert!(candidate.warning.is_some());
+
+        let signal = execute(&candidate, false, &manager, &PortScanner::new())
+            .await
+            .unwrap();
+        // Stopped, not killed: the manager knows it went away on purpose
+        assert_eq!(signal, None);
+        assert!(manager.get("sleeper").await.unwrap().is_stopped());
+
+        let killed = KilledProcess {
+            candidate,
+            killed_at: Utc::now(),
+            signal,
+        };
+        assert_eq!(killed.restart_target(), Some("sleeper"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unmanaged_process_is_killed() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+
+        let candidate = describe(pid, None, None, &[], &[]).unwrap();
+        assert_eq!(candidate.managed_process, None);
+        assert!(candidate.command.as_deref().unwrap().contains("sleep"));
+
+        let signal = execute(
+            &candidate,
+            true,
+            &ProcessManager::new(),
+            &PortScanner::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(signal, Some(KillSignal::Sigterm));
+        child.wait().unwrap();
+    }
+}
@@ -3,9 +3,13 @@
 //! Provides automatic network port scanning and process-to-port mapping.
 //!
 //! ## Features
-//! - Cross-platform port scanning (macOS/Linux/Windows)
+//! - Cross-platform port scanning (macOS/Linux/Windows), with `ss` on Linux
+//!   without lsof
 //! - Process-to-port mapping
 //! - Network traffic statistics
+//! - Detection of idle dev servers left over from closed projects
+//! - Kills confirmed with single-use tokens, with recent kills remembered
+//! - Reverse DNS and offline GeoIP/ASN data for remote addresses
 //! - No root/sudo required
 //!
 //! ## Example
@@ -24,35 +28,424 @@
 //! }
 //! ```
 
+mod enrich;
+mod filter;
+mod geoip;
+mod kill_guard;
 mod parser;
 mod scanner;
+mod stale;
 mod types;
 
+pub use enrich::{
+    ConnectionEnrichedEvent, ConnectionEnricher, DnsCache, CONNECTION_ENRICHED_EVENT, DNS_TTL,
+};
+pub use filter::{listening_ports_by_root, ConnectionFilter};
+pub use geoip::GeoIpDatabase;
+pub use kill_guard::{KillGuard, CONFIRMATION_WINDOW, RECENTLY_KILLED_LEN};
 pub use scanner::PortScanner;
+pub use stale::{CpuSample, IdleTracker, StaleServer, StaleServerDetector};
 pub use types::*;
 
-use crate::error::Result;
+use crate::error::{Result, SentinelError};
+use crate::features::capabilities::{CapabilitiesState, Feature};
+use crate::models::{EventActor, EventType, JournalEvent};
+use crate::state::AppState;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
 
 /// Scans all active ports and returns port-to-process mapping
+///
+/// Returns no ports while port scanning is unavailable; see
+/// `get_feature_capabilities`.
 #[tauri::command]
-pub async fn scan_ports() -> Result<Vec<PortInfo>> {
+pub async fn scan_ports(capabilities: State<'_, CapabilitiesState>) -> Result<Vec<PortInfo>> {
     tracing::info!("scan_ports command called");
-    let scanner = PortScanner::new();
+    let Some(scanner) = capabilities.scanner() else {
+        return Ok(Vec::new());
+    };
     let result = scanner.scan().await?;
     tracing::info!("scan_ports found {} ports", result.len());
     Ok(result)
 }
 
 /// Kill process by port number
+///
+/// With `graceful`, sends SIGTERM first and escalates to SIGKILL after a timeout.
 #[tauri::command]
-pub async fn kill_process_by_port(port: u16) -> Result<()> {
-    let scanner = PortScanner::new();
-    Ok(scanner.kill_by_port(port).await?)
+pub async fn kill_process_by_port(
+    port: u16,
+    graceful: bool,
+    state: State<'_, AppState>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<KillResult> {
+    let scanner = scanner(&capabilities)?;
+    let result = scanner.kill_by_port(port, graceful).await?;
+    state.event_recorder.record(
+        JournalEvent::new(EventType::PortKilled, port.to_string(), EventActor::User)
+            .with_detail("pid", result.pid)
+            .with_detail("process", &result.process_name)
+            .with_detail("signal", result.signal),
+    );
+    tracing::info!(
+        "Killed {} (PID {}) on port {} with {}",
+        result.process_name,
+        result.pid,
+        port,
+        result.signal
+    );
+    Ok(result)
 }
 
 /// Get detailed information about a specific port
 #[tauri::command]
-pub async fn get_port_info(port: u16) -> Result<Option<PortInfo>> {
-    let scanner = PortScanner::new();
+pub async fn get_port_info(
+    port: u16,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<Option<PortInfo>> {
+    let Some(scanner) = capabilities.scanner() else {
+        return Ok(None);
+    };
     Ok(scanner.get_port_info(port).await?)
 }
+
+/// List active connections, filtered in Rust to keep the IPC payload small
+///
+/// With `enrich`, remote addresses get GeoIP data from the database in
+/// `settings.network.geoipDatabase` and host names already resolved. Others
+/// are resolved in the background and reported through
+/// `CONNECTION_ENRICHED_EVENT`.
+#[tauri::command]
+pub async fn get_active_connections(
+    filter: Option<ConnectionFilter>,
+    enrich: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<Vec<PortInfo>> {
+    let Some(scanner) = capabilities.scanner() else {
+        return Ok(Vec::new());
+    };
+    let mut connections = filter.unwrap_or_default().apply(scanner.scan().await?);
+
+    if enrich.unwrap_or(false) {
+        let geoip_database = state
+            .config
+            .read()
+            .await
+            .as_ref()
+            .and_then(|config| config.settings.network.geoip_database.clone());
+        let enricher = &state.connection_enricher;
+        enricher.use_geoip_database(geoip_database.as_deref());
+        enricher.enrich(&mut connections, move |event| {
+            if let Err(e) = app.emit(CONNECTION_ENRICHED_EVENT, &event) {
+                tracing::warn!("Failed to emit {}: {}", CONNECTION_ENRICHED_EVENT, e);
+            }
+        });
+    }
+    Ok(connections)
+}
+
+/// List connections owned by a single PID
+#[tauri::command]
+pub async fn get_connections_for_pid(
+    pid: u32,
+    filter: Option<ConnectionFilter>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<Vec<PortInfo>> {
+    let Some(scanner) = capabilities.scanner() else {
+        return Ok(Vec::new());
+    };
+    let connections = filter::filter_by_pids(scanner.scan().await?, &HashSet::from([pid]));
+    Ok(filter.unwrap_or_default().apply(connections))
+}
+
+/// List connections owned by a managed process and its children
+///
+/// The name is looked up among config-file processes and PTY-managed
+/// processes (which includes processes started from managed configs).
+#[tauri::command]
+pub async fn get_connections_for_process(
+    name: String,
+    filter: Option<ConnectionFilter>,
+    state: State<'_, AppState>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<Vec<PortInfo>> {
+    let mut roots = Vec::new();
+
+    if let Some(pid) = state
+        .process_manager
+        .get(&name)
+        .await
+        .and_then(|info| info.pid)
+    {
+        roots.push(pid);
+    }
+
+    roots.extend(
+        state
+            .pty_manager
+            .lock()
+            .await
+            .list_processes()
+            .await
+            .into_iter()
+            .filter(|p| p.process_id == name)
+            .map(|p| p.pid),
+    );
+
+    if roots.is_empty() {
+        return Err(SentinelError::ProcessNotFound { name });
+    }
+
+    let Some(scanner) = capabilities.scanner() else {
+        return Ok(Vec::new());
+    };
+    let pids = filter::with_descendants(&roots);
+    let connections = filter::filter_by_pids(scanner.scan().await?, &pids);
+    Ok(filter.unwrap_or_default().apply(connections))
+}
+
+/// List listening processes that have been idle for `max_idle_secs` and run
+/// in a project that isn't open in Sentinel
+///
+/// Idle time is measured across calls, so a server only shows up once it has
+/// been seen idle for long enough; call this periodically.
+#[tauri::command]
+pub async fn find_stale_dev_servers(
+    max_idle_secs: u64,
+    state: State<'_, AppState>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<Vec<StaleServer>> {
+    let Some(scanner) = capabilities.scanner() else {
+        return Ok(Vec::new());
+    };
+    let ports = scanner.scan().await?;
+    let (open_projects, managed) = open_projects(&state).await;
+
+    let mut detector = state.stale_servers.lock().await;
+    Ok(detector.scan(
+        &ports,
+        Duration::from_secs(max_idle_secs),
+        &open_projects,
+        &managed,
+    ))
+}
+
+/// Gracefully kill stale dev servers found by the last `find_stale_dev_servers`
+///
+/// Nothing is killed if any PID isn't among them.
+#[tauri::command]
+pub async fn kill_stale_dev_servers(
+    pids: Vec<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<KillResult>> {
+    let mut detector = state.stale_servers.lock().await;
+    let servers = pids
+        .iter()
+        .map(|pid| {
+            detector.found(*pid).cloned().ok_or_else(|| {
+                SentinelError::Other(format!(
+                    "PID {} is not a stale dev server, scan again first",
+                    pid
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let scanner = PortScanner::new();
+    let mut results = Vec::new();
+    for server in servers {
+        let signal = scanner
+            .kill_pid(server.pid, &server.process_name, true)
+            .await?;
+        detector.forget(server.pid);
+
+        let port = server.ports.first().copied().unwrap_or_default();
+        state.event_recorder.record(
+            JournalEvent::new(EventType::PortKilled, port.to_string(), EventActor::User)
+                .with_detail("pid", server.pid)
+                .with_detail("process", &server.process_name)
+                .with_detail("signal", signal)
+                .with_detail("cwd", &server.cwd),
+        );
+        tracing::info!(
+            "Killed stale {} (PID {}) in {} with {}",
+            server.process_name,
+            server.pid,
+            server.cwd,
+            signal
+        );
+        results.push(KillResult {
+            port,
+            pid: server.pid,
+            process_name: server.process_name,
+            signal,
+        });
+    }
+    Ok(results)
+}
+
+/// Describe what killing `target` would terminate, returning the token that
+/// confirms it
+///
+/// The kill is performed by `confirm_kill` within `CONFIRMATION_WINDOW`.
+/// With `graceful` (the default), it sends SIGTERM first and escalates to
+/// SIGKILL after a timeout.
+#[tauri::command]
+pub async fn request_kill(
+    target: KillTarget,
+    graceful: Option<bool>,
+    state: State<'_, AppState>,
+    capabilities: State<'_, CapabilitiesState>,
+) -> Result<KillConfirmation> {
+    let (pid, port, name) = match target {
+        KillTarget::Port(port) => {
+            let info = scanner(&capabilities)?
+                .get_port_info(port)
+                .await?
+                .ok_or(SentinelError::PortNotFound(port))?;
+            (info.pid, Some(port), Some(info.process_name))
+        }
+        KillTarget::Pid(pid) => (pid, None, None),
+    };
+
+    let managed = state.process_manager.list().await;
+    let configs = state
+        .config
+        .read()
+        .await
+        .as_ref()
+        .map(|config| config.processes.clone())
+        .unwrap_or_default();
+    let candidate = kill_guard::describe(pid, port, name.as_deref(), &managed, &configs)?;
+
+    Ok(state.kill_guard.lock().await.request(
+        candidate,
+        graceful.unwrap_or(true),
+        chrono::Utc::now(),
+    ))
+}
+
+/// Perform a kill requested with `request_kill`
+///
+/// A token works once; a used, unknown, or expired one is refused.
+#[tauri::command]
+pub async fn confirm_kill(token: String, state: State<'_, AppState>) -> Result<KilledProcess> {
+    let (candidate, graceful) = state
+        .kill_guard
+        .lock()
+        .await
+        .confirm(&token, chrono::Utc::now())?;
+
+    let signal = kill_guard::execute(
+        &candidate,
+        graceful,
+        &state.process_manager,
+        &PortScanner::new(),
+    )
+    .await?;
+
+    if candidate.managed_process.is_none() {
+        let event = match candidate.port {
+            Some(port) => {
+                JournalEvent::new(EventType::PortKilled, port.to_string(), EventActor::User)
+                    .with_detail("pid", candidate.pid)
+            }
+            None => JournalEvent::new(
+                EventType::ProcessKilled,
+                candidate.pid.to_string(),
+                EventActor::User,
+            ),
+        };
+        let mut event = event.with_detail("process", &candidate.process_name);
+        if let Some(signal) = signal {
+            event = event.with_detail("signal", signal);
+        }
+        if let Some(cwd) = &candidate.cwd {
+            event = event.with_detail("cwd", cwd);
+        }
+        state.event_recorder.record(event);
+    }
+    tracing::info!(
+        "Killed {} (PID {}){}",
+        candidate.process_name,
+        candidate.pid,
+        candidate
+            .managed_process
+            .as_ref()
+            .map(|name| format!(" by stopping '{}'", name))
+            .unwrap_or_default()
+    );
+
+    let killed = KilledProcess {
+        candidate,
+        killed_at: chrono::Utc::now(),
+        signal,
+    };
+    state.kill_guard.lock().await.record(killed.clone());
+    Ok(killed)
+}
+
+/// Processes killed through `confirm_kill`, newest first
+///
+/// Those with a `restart_target` can be started again through Sentinel.
+#[tauri::command]
+pub async fn recently_killed(state: State<'_, AppState>) -> Result<Vec<KilledProcess>> {
+    Ok(state.kill_guard.lock().await.recently_killed())
+}
+
+/// A port scanner, or why port scanning is unavailable
+fn scanner(capabilities: &CapabilitiesState) -> Result<PortScanner> {
+    capabilities.scanner().ok_or_else(|| {
+        let reason = capabilities
+            .get()
+            .get(Feature::PortScanning)
+            .and_then(|capability| capability.reason.clone())
+            .unwrap_or_default();
+        SentinelError::Other(format!("Port scanning is unavailable: {}", reason))
+    })
+}
+
+/// Working directories of the projects open in Sentinel, and the PIDs (with
+/// descendants) of the processes it runs
+async fn open_projects(state: &AppState) -> (Vec<PathBuf>, HashSet<u32>) {
+    let mut projects = Vec::new();
+    let mut roots = Vec::new();
+
+    if let Some(config) = state.config.read().await.as_ref() {
+        projects.extend(config.processes.iter().filter_map(|p| p.cwd.clone()));
+    }
+
+    for info in state.process_manager.list().await {
+        projects.extend(info.resolved_cwd.map(PathBuf::from));
+        roots.extend(info.pid);
+    }
+
+    projects.extend(
+        state
+            .process_config_store
+            .lock()
+            .await
+            .list()
+            .await
+            .into_iter()
+            .filter(|config| !config.working_dir.is_empty())
+            .map(|config| PathBuf::from(config.working_dir)),
+    );
+
+    roots.extend(
+        state
+            .pty_manager
+            .lock()
+            .await
+            .list_processes()
+            .await
+            .into_iter()
+            .map(|p| p.pid),
+    );
+
+    (projects, filter::with_descendants(&roots))
+}
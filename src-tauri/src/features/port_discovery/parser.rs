@@ -1,4 +1,4 @@
-//! Platform-specific output parsers for lsof and netstat
+//! Platform-specific output parsers for lsof, ss and netstat
 
 use anyhow::{Context, Result};
 use regex::Regex;
@@ -79,6 +79,7 @@ fn parse_lsof_line(line: &str, re: &Regex) -> Result<Option<PortInfo>> {
         remote_address,
         command: None, // Will be enriched later with sysinfo
         traffic: NetworkTraffic::default(),
+        remote_info: None,
     }))
 }
 
@@ -143,8 +144,8 @@ fn parse_netstat_line(line: &str, re: &Regex) -> Result<Option<PortInfo>> {
         .parse::<u32>()
         .context("Failed to parse PID")?;
 
-    // Get process name from PID (Windows-specific, simplified)
-    let process_name = format!("pid-{}", pid); // TODO: Use tasklist to get actual name
+    // netstat only reports PIDs; the scanner resolves real names afterwards
+    let process_name = placeholder_process_name(pid);
 
     Ok(Some(PortInfo {
         port,
@@ -156,9 +157,97 @@ fn parse_netstat_line(line: &str, re: &Regex) -> Result<Option<PortInfo>> {
         remote_address,
         command: None, // Will be enriched later with sysinfo
         traffic: NetworkTraffic::default(),
+        remote_info: None,
     }))
 }
 
+/// Parse `ss -tunap` output (Linux without lsof)
+///
+/// Example lines:
+/// ```text
+/// Netid State  Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+/// tcp   LISTEN 0      128        127.0.0.1:3000       0.0.0.0:*     users:(("node",pid=12345,fd=20))
+/// tcp   ESTAB  0      0          127.0.0.1:3000     127.0.0.1:54321 users:(("node",pid=12345,fd=21))
+/// udp   UNCONN 0      0              [::1]:5353          [::]:*     users:(("avahi",pid=880,fd=12))
+/// ```
+///
+/// Sockets without a `users:` column belong to processes ss can't see and
+/// are skipped, as lsof skips them. A socket shared by several processes
+/// yields one entry per process.
+pub fn parse_ss_output(output: &str) -> Result<Vec<PortInfo>> {
+    let mut ports = Vec::new();
+
+    // Captures: process name, PID
+    let users = Regex::new(r#"\("((?:[^"\\]|\\.)*)",pid=(\d+)"#)
+        .context("Failed to compile ss users regex")?;
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [netid, state, _recv_q, _send_q, local, peer, process @ ..] = fields.as_slice() else {
+            continue;
+        };
+        let protocol = match *netid {
+            "tcp" => Protocol::TCP,
+            "udp" => Protocol::UDP,
+            // Also skips the header line
+            _ => continue,
+        };
+        let Some((local_address, port)) = split_ss_address(local) else {
+            continue;
+        };
+        let Ok(port) = port.parse::<u16>() else {
+            continue;
+        };
+        let remote_address = split_ss_address(peer)
+            .filter(|(_, port)| *port != "*")
+            .map(|(address, port)| format!("{}:{}", address, port));
+        let state = match *state {
+            "ESTAB" => PortState::Established,
+            state => parse_port_state(&state.replace('-', "_")),
+        };
+
+        let mut pids = Vec::new();
+        for caps in users.captures_iter(&process.join(" ")) {
+            let pid = caps[2].parse::<u32>().context("Failed to parse PID")?;
+            if pids.contains(&pid) {
+                continue;
+            }
+            pids.push(pid);
+            ports.push(PortInfo {
+                port,
+                protocol: protocol.clone(),
+                process_name: caps[1].to_string(),
+                pid,
+                state: state.clone(),
+                local_address: local_address.clone(),
+                remote_address: remote_address.clone(),
+                command: None, // Will be enriched later with sysinfo
+                traffic: NetworkTraffic::default(),
+                remote_info: None,
+            });
+        }
+    }
+
+    Ok(ports)
+}
+
+/// Splits an ss `address:port` at the last colon, dropping any `%interface`
+/// from the address
+fn split_ss_address(address: &str) -> Option<(String, &str)> {
+    let (address, port) = address.rsplit_once(':')?;
+    let address = match address.split_once('%') {
+        Some((host, _)) if host.starts_with('[') => format!("{}]", host),
+        Some((host, _)) => host.to_string(),
+        None => address.to_string(),
+    };
+    Some((address, port))
+}
+
+/// Placeholder name used until a PID has been resolved to its image name
+pub fn placeholder_process_name(pid: u32) -> String {
+    format!("pid-{}", pid)
+}
+
 fn parse_port_state(state_str: &str) -> PortState {
     match state_str.to_uppercase().as_str() {
         "LISTEN" | "LISTENING" => PortState::Listen,
@@ -230,6 +319,110 @@ mod tests {
         assert_eq!(port.remote_address, Some("192.168.1.5:54321".to_string()));
     }
 
+    #[test]
+    fn test_parse_netstat_uses_pid_placeholder() {
+        let output = "  TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       1028";
+        let result = parse_netstat_output(output).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].process_name, "pid-1028");
+        assert!(result[0].command.is_none());
+    }
+
+    #[test]
+    fn test_parse_real_windows_netstat() {
+        // Real `netstat -ano` output from Windows 11
+        let output = "
+Active Connections
+
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       1028
+  TCP    0.0.0.0:445            0.0.0.0:0              LISTENING       4
+  TCP    127.0.0.1:3000         0.0.0.0:0              LISTENING       15872
+  TCP    127.0.0.1:3000         127.0.0.1:52144        ESTABLISHED     15872
+  TCP    192.168.1.20:52210     140.82.112.25:443      TIME_WAIT       0
+  TCP    [::]:135               [::]:0                 LISTENING       1028
+  UDP    0.0.0.0:5353           *:*                                    2260
+";
+
+        let result = parse_netstat_output(output).unwrap();
+
+        // IPv6 and UDP wildcard rows don't match the IPv4 pattern and are skipped
+        assert_eq!(result.len(), 5);
+
+        assert_eq!(result[0].port, 135);
+        assert_eq!(result[0].pid, 1028);
+        assert_eq!(result[0].process_name, "pid-1028");
+        assert_eq!(result[0].state, PortState::Listen);
+
+        assert_eq!(result[1].pid, 4);
+        assert_eq!(result[1].process_name, "pid-4");
+
+        assert_eq!(result[2].port, 3000);
+        assert_eq!(result[2].local_address, "127.0.0.1");
+        assert!(result[2].remote_address.is_none());
+
+        assert_eq!(result[3].state, PortState::Established);
+        assert_eq!(
+            result[3].remote_address,
+            Some("127.0.0.1:52144".to_string())
+        );
+
+        assert_eq!(result[4].state, PortState::TimeWait);
+        assert_eq!(result[4].pid, 0);
+    }
+
+    #[test]
+    fn test_parse_real_linux_ss() {
+        // Real `ss -tunap` output from Debian 12
+        let output = r#"Netid State  Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+udp   UNCONN 0      0              [::1]:57724         [::]:*     users:(("python3",pid=5671,fd=6))
+udp   UNCONN 0      0      127.0.0.53%lo:53         0.0.0.0:*     users:(("systemd-resolve",pid=412,fd=13))
+tcp   LISTEN 0      128        127.0.0.1:54397      0.0.0.0:*     users:(("python3",pid=5671,fd=3))
+tcp   LISTEN 0      128          0.0.0.0:22         0.0.0.0:*
+tcp   LISTEN 0      511                *:80               *:*     users:(("nginx",pid=901,fd=6),("nginx",pid=900,fd=6),("nginx",pid=901,fd=7))
+tcp   ESTAB  0      0          127.0.0.1:54397    127.0.0.1:46472 users:(("python3",pid=5671,fd=5))
+tcp   TIME-WAIT 0   0      192.168.1.20:52210 140.82.112.25:443
+tcp   CLOSE-WAIT 1  0    [fe80::1%eth0]:8080   [fe80::2%eth0]:51000 users:(("java",pid=77,fd=40))
+"#;
+
+        let result = parse_ss_output(output).unwrap();
+
+        // sshd and the TIME-WAIT socket have no visible owner
+        assert_eq!(result.len(), 7);
+
+        assert_eq!(result[0].protocol, Protocol::UDP);
+        assert_eq!(result[0].port, 57724);
+        assert_eq!(result[0].local_address, "[::1]");
+        assert_eq!(result[0].state, PortState::Unknown);
+        assert!(result[0].remote_address.is_none());
+
+        assert_eq!(result[1].local_address, "127.0.0.53");
+        assert_eq!(result[1].process_name, "systemd-resolve");
+
+        assert_eq!(result[2].port, 54397);
+        assert_eq!(result[2].pid, 5671);
+        assert_eq!(result[2].process_name, "python3");
+        assert_eq!(result[2].state, PortState::Listen);
+
+        // One entry per process sharing the socket
+        assert_eq!(result[3].local_address, "*");
+        assert_eq!((result[3].pid, result[4].pid), (901, 900));
+
+        assert_eq!(result[5].state, PortState::Established);
+        assert_eq!(
+            result[5].remote_address,
+            Some("127.0.0.1:46472".to_string())
+        );
+
+        assert_eq!(result[6].state, PortState::CloseWait);
+        assert_eq!(result[6].local_address, "[fe80::1]");
+        assert_eq!(
+            result[6].remote_address,
+            Some("[fe80::2]:51000".to_string())
+        );
+    }
+
     #[test]
     fn test_parse_port_state() {
         assert_eq!(parse_port_state("LISTEN"), PortState::Listen);
@@ -243,6 +436,7 @@ mod tests {
     fn test_parse_empty_output() {
         assert_eq!(parse_lsof_output("").unwrap().len(), 0);
         assert_eq!(parse_netstat_output("").unwrap().len(), 0);
+        assert_eq!(parse_ss_output("").unwrap().len(), 0);
     }
 
     #[test]
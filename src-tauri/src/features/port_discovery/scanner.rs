@@ -2,15 +2,22 @@
 
 use anyhow::{Context, Result};
 use std::time::Duration;
-use sysinfo::System;
+use sysinfo::{
+    Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind,
+};
 use tokio::process::Command;
 
-use super::parser::{parse_lsof_output, parse_netstat_output};
-use super::types::PortInfo;
+use super::parser::{parse_lsof_output, parse_netstat_output, parse_ss_output};
+use super::types::{KillResult, KillSignal, PortInfo, ScanBackend};
+use crate::features::capabilities::{self, HostOs, SystemTools};
 
-/// Port scanner that uses OS-native commands (lsof/netstat)
+/// How long a graceful kill waits after SIGTERM before escalating to SIGKILL
+const GRACEFUL_KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Port scanner that uses OS-native commands (lsof/ss/netstat)
 pub struct PortScanner {
     platform: Platform,
+    backend: ScanBackend,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -21,24 +28,45 @@ enum Platform {
 
 impl PortScanner {
     /// Create a new port scanner
+    ///
+    /// Uses the first of the platform's scan commands that is installed, or
+    /// its preferred one if none is, so scans fail with a useful message.
     pub fn new() -> Self {
+        let backend = capabilities::scan_backend(HostOs::current(), &SystemTools).unwrap_or(
+            if cfg!(target_os = "windows") {
+                ScanBackend::Netstat
+            } else {
+                ScanBackend::Lsof
+            },
+        );
+        Self::with_backend(backend)
+    }
+
+    /// Create a port scanner that runs `backend`
+    pub fn with_backend(backend: ScanBackend) -> Self {
         let platform = if cfg!(target_os = "windows") {
             Platform::Windows
         } else {
             Platform::Unix
         };
 
-        Self { platform }
+        Self { platform, backend }
+    }
+
+    /// Command the scanner runs
+    pub fn backend(&self) -> ScanBackend {
+        self.backend
     }
 
     /// Scan all active ports
     ///
     /// Returns a list of all ports with process information.
-    /// Uses lsof on Unix systems and netstat on Windows.
+    /// Uses lsof on Unix systems (ss on Linux without it) and netstat on Windows.
     pub async fn scan(&self) -> Result<Vec<PortInfo>> {
-        match self.platform {
-            Platform::Unix => self.scan_unix().await,
-            Platform::Windows => self.scan_windows().await,
+        match self.backend {
+            ScanBackend::Lsof => self.scan_unix().await,
+            ScanBackend::Ss => self.scan_ss().await,
+            ScanBackend::Netstat => self.scan_windows().await,
         }
     }
 
@@ -49,13 +77,56 @@ impl PortScanner {
     }
 
     /// Kill process by port number
-    pub async fn kill_by_port(&self, port: u16) -> Result<()> {
+    ///
+    /// With `graceful`, the process gets SIGTERM first and is escalated to
+    /// SIGKILL if it is still alive after a timeout. Otherwise it is killed
+    /// immediately. PID 1, Sentinel itself, and processes owned by another
+    /// user are refused.
+    pub async fn kill_by_port(&self, port: u16, graceful: bool) -> Result<KillResult> {
         let port_info = self
             .get_port_info(port)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Port {} not found", port))?;
 
-        self.kill_process(port_info.pid).await
+        let signal = self
+            .kill_pid(port_info.pid, &port_info.process_name, graceful)
+            .await?;
+
+        Ok(KillResult {
+            port,
+            pid: port_info.pid,
+            process_name: port_info.process_name,
+            signal,
+        })
+    }
+
+    /// Kill a process by PID, refusing the same processes as `kill_by_port`
+    ///
+    /// Returns the signal that actually terminated the process.
+    pub async fn kill_pid(
+        &self,
+        pid: u32,
+        process_name: &str,
+        graceful: bool,
+    ) -> Result<KillSignal> {
+        check_killable(pid, process_name)?;
+
+        if !graceful {
+            self.kill_process(pid, KillSignal::Sigkill).await?;
+            return Ok(KillSignal::Sigkill);
+        }
+
+        self.kill_process(pid, KillSignal::Sigterm).await?;
+        if wait_for_exit(pid, GRACEFUL_KILL_TIMEOUT).await {
+            return Ok(KillSignal::Sigterm);
+        }
+        tracing::warn!(
+            "Process {} (PID {}) ignored SIGTERM, sending SIGKILL",
+            process_name,
+            pid
+        );
+        self.kill_process(pid, KillSignal::Sigkill).await?;
+        Ok(KillSignal::Sigkill)
     }
 
     /// Scan using lsof (macOS/Linux)
@@ -84,6 +155,30 @@ impl PortScanner {
         Ok(ports)
     }
 
+    /// Scan using ss (Linux without lsof)
+    async fn scan_ss(&self) -> Result<Vec<PortInfo>> {
+        // -t/-u: TCP and UDP, -n: numeric, -a: all states, -p: owning processes
+        let output_future = Command::new("ss").args(["-tunap"]).output();
+
+        let output = tokio::time::timeout(Duration::from_secs(10), output_future)
+            .await
+            .context("ss command timed out after 10 seconds")?
+            .context("Failed to execute ss. Is iproute2 installed?")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("ss failed: {}", stderr);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut ports = parse_ss_output(&stdout).context("Failed to parse ss output")?;
+
+        // Enrich with process command lines
+        self.enrich_with_commands(&mut ports);
+
+        Ok(ports)
+    }
+
     /// Scan using netstat (Windows)
     async fn scan_windows(&self) -> Result<Vec<PortInfo>> {
         // Execute netstat command with timeout
@@ -104,8 +199,8 @@ impl PortScanner {
         let stdout = String::from_utf8_lossy(&output.stdout);
         let mut ports = parse_netstat_output(&stdout).context("Failed to parse netstat output")?;
 
-        // Enrich with process command lines
-        self.enrich_with_commands(&mut ports);
+        // netstat only reports PIDs, so resolve names and command lines in one pass
+        self.resolve_process_names(&mut ports);
 
         Ok(ports)
     }
@@ -115,30 +210,57 @@ impl PortScanner {
         let sys = System::new_all();
 
         for port in ports.iter_mut() {
-            if let Some(process) = sys.process(sysinfo::Pid::from_u32(port.pid)) {
-                // Get command line as a single string
-                let cmd_vec = process.cmd();
-                if !cmd_vec.is_empty() {
-                    // Convert OsStr to String
-                    let cmd_string = cmd_vec
-                        .iter()
-                        .filter_map(|s| s.to_str())
-                        .collect::<Vec<&str>>()
-                        .join(" ");
-                    if !cmd_string.is_empty() {
-                        port.command = Some(cmd_string);
-                    }
+            if let Some(process) = sys.process(Pid::from_u32(port.pid)) {
+                if let Some(command) = process_command(process) {
+                    port.command = Some(command);
                 }
             }
         }
     }
 
-    /// Kill a process by PID
-    async fn kill_process(&self, pid: u32) -> Result<()> {
+    /// Replace `pid-<pid>` placeholders with real image names using sysinfo
+    ///
+    /// Only the PIDs seen in the scan are refreshed. A process that exited
+    /// between the netstat snapshot and this lookup keeps its placeholder.
+    fn resolve_process_names(&self, ports: &mut [PortInfo]) {
+        let mut pids: Vec<Pid> = ports.iter().map(|p| Pid::from_u32(p.pid)).collect();
+        pids.sort_unstable();
+        pids.dedup();
+
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_exe(UpdateKind::OnlyIfNotSet),
+        );
+
+        for port in ports.iter_mut() {
+            let Some(process) = sys.process(Pid::from_u32(port.pid)) else {
+                continue;
+            };
+
+            let name = process.name().to_string_lossy();
+            if !name.is_empty() {
+                port.process_name = name.into_owned();
+            }
+            if let Some(command) = process_command(process) {
+                port.command = Some(command);
+            }
+        }
+    }
+
+    /// Send a termination signal to a process by PID
+    async fn kill_process(&self, pid: u32, signal: KillSignal) -> Result<()> {
         match self.platform {
             Platform::Unix => {
+                let flag = match signal {
+                    KillSignal::Sigterm => "-TERM",
+                    KillSignal::Sigkill => "-KILL",
+                };
                 let output = Command::new("kill")
-                    .arg(pid.to_string())
+                    .args([flag, &pid.to_string()])
                     .output()
                     .await
                     .context("Failed to execute kill command")?;
@@ -149,8 +271,14 @@ impl PortScanner {
                 }
             }
             Platform::Windows => {
+                // Without /F, taskkill asks the process to close
+                let pid_str = pid.to_string();
+                let mut args = vec!["/PID", pid_str.as_str()];
+                if signal == KillSignal::Sigkill {
+                    args.push("/F");
+                }
                 let output = Command::new("taskkill")
-                    .args(["/PID", &pid.to_string(), "/F"])
+                    .args(&args)
                     .output()
                     .await
                     .context("Failed to execute taskkill command")?;
@@ -166,6 +294,87 @@ impl PortScanner {
     }
 }
 
+/// Refuse to kill processes that would take down the system, Sentinel, or
+/// someone else's work
+pub(super) fn check_killable(pid: u32, process_name: &str) -> Result<()> {
+    if pid == 1 {
+        anyhow::bail!(
+            "Refusing to kill PID 1 ({}): it is the system init process",
+            process_name
+        );
+    }
+    if pid == std::process::id() {
+        anyhow::bail!("Refusing to kill PID {}: it is Sentinel itself", pid);
+    }
+
+    #[cfg(unix)]
+    {
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+            true,
+            ProcessRefreshKind::nothing().with_user(UpdateKind::Always),
+        );
+        if let Some(owner) = sys
+            .process(Pid::from_u32(pid))
+            .and_then(|process| process.user_id())
+        {
+            // SAFETY: getuid has no preconditions and cannot fail
+            let current_uid = unsafe { libc::getuid() };
+            if **owner != current_uid {
+                anyhow::bail!(
+                    "Refusing to kill {} (PID {}): it is owned by another user (uid {})",
+                    process_name,
+                    pid,
+                    **owner
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Poll until the process is gone (or a zombie), returning false on timeout
+async fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let pid = Pid::from_u32(pid);
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut sys = System::new();
+
+    loop {
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        let alive = sys
+            .process(pid)
+            .is_some_and(|process| process.status() != ProcessStatus::Zombie);
+        if !alive {
+            return true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Full command line of a process, falling back to its executable path
+pub(super) fn process_command(process: &Process) -> Option<String> {
+    let cmd_string = process
+        .cmd()
+        .iter()
+        .filter_map(|s| s.to_str())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    if !cmd_string.is_empty() {
+        return Some(cmd_string);
+    }
+
+    process.exe().map(|exe| exe.display().to_string())
+}
+
 impl Default for PortScanner {
     fn default() -> Self {
         Self::new()
@@ -187,6 +396,29 @@ mod tests {
         assert!(matches!(scanner.platform, Platform::Unix));
     }
 
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_ss_scan_finds_listener() {
+        use crate::features::capabilities::ToolLookup;
+
+        if !SystemTools.has_command("ss") {
+            return;
+        }
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let scanner = PortScanner::with_backend(ScanBackend::Ss);
+        let info = scanner
+            .get_port_info(port)
+            .await
+            .unwrap()
+            .expect("listener should be visible to ss");
+
+        assert_eq!(info.pid, std::process::id());
+        assert_eq!(info.local_address, "127.0.0.1");
+        assert!(info.command.is_some());
+    }
+
     #[tokio::test]
     async fn test_scan_basic() {
         let scanner = PortScanner::new();
@@ -239,4 +471,111 @@ mod tests {
         // Just verify the scan works
         let _ = ports;
     }
+
+    #[test]
+    fn test_resolve_process_names_current_process() {
+        let scanner = PortScanner::new();
+        let mut ports = parse_netstat_output(&format!(
+            "  TCP    127.0.0.1:3000         0.0.0.0:0              LISTENING       {}",
+            std::process::id()
+        ))
+        .unwrap();
+
+        scanner.resolve_process_names(&mut ports);
+
+        assert!(!ports[0].process_name.starts_with("pid-"));
+        assert!(ports[0].command.is_some());
+    }
+
+    #[test]
+    fn test_resolve_process_names_dead_pid_keeps_placeholder() {
+        let scanner = PortScanner::new();
+        // PIDs this high are never handed out in practice
+        let mut ports = parse_netstat_output(
+            "  TCP    127.0.0.1:3000         0.0.0.0:0              LISTENING       4294967290",
+        )
+        .unwrap();
+
+        scanner.resolve_process_names(&mut ports);
+
+        assert_eq!(ports[0].process_name, "pid-4294967290");
+        assert!(ports[0].command.is_none());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[tokio::test]
+    async fn test_scan_resolves_real_image_name() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let scanner = PortScanner::new();
+        let info = scanner
+            .get_port_info(port)
+            .await
+            .unwrap()
+            .expect("spawned listener should be visible to netstat");
+
+        let exe = std::env::current_exe().unwrap();
+        let image_name = exe.file_name().unwrap().to_string_lossy();
+
+        assert_eq!(info.pid, std::process::id());
+        assert_eq!(info.process_name, image_name);
+        assert!(info.command.is_some());
+    }
+
+    #[test]
+    fn test_check_killable_refuses_protected_pids() {
+        let err = check_killable(1, "init").unwrap_err();
+        assert!(err.to_string().contains("PID 1"));
+
+        let err = check_killable(std::process::id(), "sentinel").unwrap_err();
+        assert!(err.to_string().contains("Sentinel itself"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_by_port_graceful() {
+        // Reserve a free port, then hand it to a child listener
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut child = tokio::process::Command::new("python3")
+            .args([
+                "-c",
+                &format!(
+                    "import socket, time\n\
+                     s = socket.socket()\n\
+                     s.bind(('127.0.0.1', {}))\n\
+                     s.listen()\n\
+                     time.sleep(60)",
+                    port
+                ),
+            ])
+            .spawn()
+            .expect("python3 is required for this test");
+        let child_pid = child.id().unwrap();
+
+        let scanner = PortScanner::new();
+        let mut found = false;
+        for _ in 0..50 {
+            if scanner.get_port_info(port).await.unwrap().is_some() {
+                found = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(found, "listener on port {} never showed up", port);
+
+        let result = scanner.kill_by_port(port, true).await.unwrap();
+
+        assert_eq!(result.port, port);
+        assert_eq!(result.pid, child_pid);
+        assert!(result.process_name.starts_with("python"));
+        assert_eq!(result.signal, KillSignal::Sigterm);
+
+        let status = child.wait().await.unwrap();
+        assert!(!status.success());
+    }
 }
@@ -0,0 +1,349 @@
+//! Detection of dev servers left running from projects that are no longer open
+//!
+//! A listening process counts as stale when its CPU time has barely moved for
+//! a while, its working directory is a project, and that project isn't one
+//! Sentinel has open. Idleness can't be seen in a single scan, so every scan
+//! adds a CPU time sample per candidate PID and the idle time is measured over
+//! the samples kept between scans.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use super::types::{PortInfo, PortState};
+
+/// CPU usage at or below which a process counts as idle
+pub const IDLE_CPU_PERCENT: f64 = 1.0;
+
+/// Samples kept per PID
+const MAX_SAMPLES: usize = 64;
+
+/// Files that mark a directory as a project
+const PROJECT_MARKERS: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "pyproject.toml",
+    "requirements.txt",
+    "manage.py",
+    "go.mod",
+    "Gemfile",
+    "composer.json",
+    "pom.xml",
+    "build.gradle",
+    ".git",
+];
+
+/// A listening process that looks like a forgotten dev server
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleServer {
+    pub pid: u32,
+    pub process_name: String,
+    /// Full command line
+    pub command: Option<String>,
+    /// Working directory, the project the server belongs to
+    pub cwd: String,
+    /// Ports the process listens on
+    pub ports: Vec<u16>,
+    /// Seconds since the process started
+    pub uptime_secs: u64,
+    /// Seconds the process has been idle, as far as the samples go back
+    pub idle_secs: u64,
+}
+
+/// One reading of a process's total CPU time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CpuSample {
+    pub at: DateTime<Utc>,
+    /// CPU time used since the process started, in milliseconds
+    pub cpu_time_ms: u64,
+}
+
+/// Samples of one process
+struct History {
+    /// Start time of the process, to notice a reused PID
+    start_time: u64,
+    /// Oldest first
+    samples: VecDeque<CpuSample>,
+}
+
+/// Rolling CPU time samples per PID
+#[derive(Default)]
+pub struct IdleTracker {
+    histories: HashMap<u32, History>,
+}
+
+impl IdleTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample of `pid`, which started at `start_time` (seconds since the epoch)
+    ///
+    /// A different start time means the PID now belongs to another process,
+    /// so the earlier samples are dropped.
+    pub fn record(&mut self, pid: u32, start_time: u64, sample: CpuSample) {
+        let history = self.histories.entry(pid).or_insert_with(|| History {
+            start_time,
+            samples: VecDeque::new(),
+        });
+        if history.start_time != start_time {
+            history.start_time = start_time;
+            history.samples.clear();
+        }
+        if history.samples.len() == MAX_SAMPLES {
+            history.samples.pop_front();
+        }
+        history.samples.push_back(sample);
+    }
+
+    /// How long `pid` has used at most `IDLE_CPU_PERCENT` of a CPU, up to its
+    /// latest sample
+    ///
+    /// Zero with fewer than two samples or if it was busy most recently.
+    pub fn idle_for(&self, pid: u32) -> Duration {
+        let Some(history) = self.histories.get(&pid) else {
+            return Duration::ZERO;
+        };
+        let Some(newest) = history.samples.back() else {
+            return Duration::ZERO;
+        };
+
+        let mut idle_since = newest.at;
+        for (older, newer) in history
+            .samples
+            .iter()
+            .zip(history.samples.iter().skip(1))
+            .rev()
+        {
+            let wall_ms = (newer.at - older.at).num_milliseconds().max(0) as f64;
+            let cpu_ms = newer.cpu_time_ms.saturating_sub(older.cpu_time_ms) as f64;
+            if cpu_ms > wall_ms * IDLE_CPU_PERCENT / 100.0 {
+                break;
+            }
+            idle_since = older.at;
+        }
+
+        (newest.at - idle_since).to_std().unwrap_or_default()
+    }
+
+    /// Forget every PID not in `pids`
+    pub fn retain(&mut self, pids: &HashSet<u32>) {
+        self.histories.retain(|pid, _| pids.contains(pid));
+    }
+}
+
+/// Stale server detection state kept between scans
+#[derive(Default)]
+pub struct StaleServerDetector {
+    idle: IdleTracker,
+    /// Stale servers found by the last scan, by PID
+    found: HashMap<u32, StaleServer>,
+}
+
+impl StaleServerDetector {
+    /// Create a detector without samples
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sample the processes listening in `ports` and return those idle for
+    /// at least `max_idle`, most idle first
+    ///
+    /// Processes in `managed`, processes without a known working directory,
+    /// and processes whose working directory isn't a project or lies in one of
+    /// `open_projects` are never returned.
+    pub fn scan(
+        &mut self,
+        ports: &[PortInfo],
+        max_idle: Duration,
+        open_projects: &[PathBuf],
+        managed: &HashSet<u32>,
+    ) -> Vec<StaleServer> {
+        let mut listening: HashMap<u32, Vec<&PortInfo>> = HashMap::new();
+        for port in ports
+            .iter()
+            .filter(|port| port.state == PortState::Listen && !managed.contains(&port.pid))
+        {
+            listening.entry(port.pid).or_default().push(port);
+        }
+
+        let pids: Vec<Pid> = listening.keys().map(|pid| Pid::from_u32(*pid)).collect();
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_cwd(UpdateKind::OnlyIfNotSet),
+        );
+
+        let now = Utc::now();
+        let mut found = HashMap::new();
+        for (pid, ports) in &listening {
+            let Some(process) = sys.process(Pid::from_u32(*pid)) else {
+                continue;
+            };
+            self.idle.record(
+                *pid,
+                process.start_time(),
+                CpuSample {
+                    at: now,
+                    cpu_time_ms: process.accumulated_cpu_time(),
+                },
+            );
+
+            let idle = self.idle.idle_for(*pid);
+            let Some(cwd) = process.cwd() else {
+                continue;
+            };
+            if idle < max_idle || !is_project(cwd) || is_open(cwd, open_projects) {
+                continue;
+            }
+
+            let mut port_numbers: Vec<u16> = ports.iter().map(|port| port.port).collect();
+            port_numbers.sort_unstable();
+            port_numbers.dedup();
+            found.insert(
+                *pid,
+                StaleServer {
+                    pid: *pid,
+                    process_name: ports[0].process_name.clone(),
+                    command: ports[0].command.clone(),
+                    cwd: cwd.display().to_string(),
+                    ports: port_numbers,
+                    uptime_secs: process.run_time(),
+                    idle_secs: idle.as_secs(),
+                },
+            );
+        }
+
+        self.idle.retain(&listening.keys().copied().collect());
+        self.found = found;
+
+        let mut stale: Vec<StaleServer> = self.found.values().cloned().collect();
+        stale.sort_by(|a, b| b.idle_secs.cmp(&a.idle_secs).then(a.pid.cmp(&b.pid)));
+        stale
+    }
+
+    /// The stale server `pid` found by the last scan
+    pub fn found(&self, pid: u32) -> Option<&StaleServer> {
+        self.found.get(&pid)
+    }
+
+    /// Drop `pid` from the last scan's result, e.g. after killing it
+    pub fn forget(&mut self, pid: u32) {
+        self.found.remove(&pid);
+    }
+}
+
+/// Whether `dir` contains one of the files that mark a project
+pub fn is_project(dir: &Path) -> bool {
+    PROJECT_MARKERS
+        .iter()
+        .any(|marker| dir.join(marker).exists())
+}
+
+/// Whether `dir` lies in one of `open_projects`
+fn is_open(dir: &Path, open_projects: &[PathBuf]) -> bool {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    open_projects.iter().any(|project| {
+        let project = project
+            .canonicalize()
+            .unwrap_or_else(|_| project.to_path_buf());
+        dir.starts_with(&project)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Sample `minutes` minutes into the test with `cpu_time_ms` of CPU used
+    fn sample(minutes: i64, cpu_time_ms: u64) -> CpuSample {
+        CpuSample {
+            at: Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+                + chrono::Duration::minutes(minutes),
+            cpu_time_ms,
+        }
+    }
+
+    fn minutes(minutes: u64) -> Duration {
+        Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn test_idle_window_starts_after_last_busy_interval() {
+        let mut tracker = IdleTracker::new();
+        assert_eq!(tracker.idle_for(42), Duration::ZERO);
+
+        tracker.record(42, 1000, sample(0, 0));
+        // A single sample says nothing yet
+        assert_eq!(tracker.idle_for(42), Duration::ZERO);
+
+        // Busy: 30s of CPU in a minute
+        tracker.record(42, 1000, sample(1, 30_000));
+        assert_eq!(tracker.idle_for(42), Duration::ZERO);
+
+        // Idle: a few milliseconds per interval stays under 1%
+        tracker.record(42, 1000, sample(6, 30_050));
+        tracker.record(42, 1000, sample(16, 30_100));
+        assert_eq!(tracker.idle_for(42), minutes(15));
+
+        // Busy again resets the window
+        tracker.record(42, 1000, sample(17, 40_000));
+        assert_eq!(tracker.idle_for(42), Duration::ZERO);
+        tracker.record(42, 1000, sample(20, 40_000));
+        assert_eq!(tracker.idle_for(42), minutes(3));
+    }
+
+    #[test]
+    fn test_reused_pid_starts_over() {
+        let mut tracker = IdleTracker::new();
+        tracker.record(7, 1000, sample(0, 0));
+        tracker.record(7, 1000, sample(30, 10));
+        assert_eq!(tracker.idle_for(7), minutes(30));
+
+        tracker.record(7, 2000, sample(31, 0));
+        assert_eq!(tracker.idle_for(7), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_samples_are_capped_and_retained_per_pid() {
+        let mut tracker = IdleTracker::new();
+        for minute in 0..(MAX_SAMPLES as i64 + 10) {
+            tracker.record(1, 1000, sample(minute, 0));
+        }
+        tracker.record(2, 1000, sample(0, 0));
+        tracker.record(2, 1000, sample(5, 0));
+
+        // Only the kept samples count
+        assert_eq!(tracker.idle_for(1), minutes(MAX_SAMPLES as u64 - 1));
+
+        tracker.retain(&HashSet::from([2]));
+        assert_eq!(tracker.idle_for(1), Duration::ZERO);
+        assert_eq!(tracker.idle_for(2), minutes(5));
+    }
+
+    #[test]
+    fn test_open_projects_and_markers() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("shop");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        assert!(!is_project(&project));
+
+        std::fs::write(project.join("package.json"), "{}").unwrap();
+        assert!(is_project(&project));
+
+        assert!(is_open(
+            &project.join("src"),
+            std::slice::from_ref(&project)
+        ));
+        assert!(!is_open(&project, &[dir.path().join("other")]));
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Port information with process details
@@ -22,6 +23,33 @@ pub struct PortInfo {
     pub command: Option<String>,
     /// Network traffic statistics
     pub traffic: NetworkTraffic,
+    /// Host name and GeoIP data of the remote address, when enrichment was asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_info: Option<RemoteInfo>,
+}
+
+/// What is known about a remote address
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteInfo {
+    /// Reverse DNS name, once resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// ISO 3166 country code (e.g., "US")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    /// Country name in English
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// City name in English
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    /// Autonomous system number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asn: Option<u32>,
+    /// Organization owning the autonomous system (e.g., "AMAZON-02")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
 }
 
 /// Network protocol
@@ -67,6 +95,127 @@ impl std::fmt::Display for PortState {
     }
 }
 
+/// Outcome of killing the process bound to a port
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct KillResult {
+    /// Port the process was bound to
+    pub port: u16,
+    /// PID of the terminated process
+    pub pid: u32,
+    /// Name of the terminated process
+    pub process_name: String,
+    /// Signal that actually terminated the process
+    pub signal: KillSignal,
+}
+
+/// Termination signal (taskkill without/with `/F` on Windows)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum KillSignal {
+    /// Polite termination request
+    Sigterm,
+    /// Forced termination
+    Sigkill,
+}
+
+impl std::fmt::Display for KillSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillSignal::Sigterm => write!(f, "SIGTERM"),
+            KillSignal::Sigkill => write!(f, "SIGKILL"),
+        }
+    }
+}
+
+/// Process a kill is requested for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum KillTarget {
+    /// Whatever listens on the port
+    Port(u16),
+    Pid(u32),
+}
+
+/// What a confirmed kill would terminate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KillCandidate {
+    pub pid: u32,
+    /// Port the kill was requested for
+    pub port: Option<u16>,
+    pub process_name: String,
+    /// Full command line
+    pub command: Option<String>,
+    pub cwd: Option<String>,
+    /// User the process runs as
+    pub owner: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Sentinel-managed process with this PID, which is stopped gracefully
+    /// through Sentinel instead of being signalled
+    pub managed_process: Option<String>,
+    /// Configured process that can start it again
+    pub config_process: Option<String>,
+    /// Something to double-check before confirming
+    pub warning: Option<String>,
+}
+
+/// A pending kill, performed by `confirm_kill` with its token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KillConfirmation {
+    /// Single-use token for `confirm_kill`
+    pub token: String,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+    pub candidate: KillCandidate,
+}
+
+/// A process killed through `confirm_kill`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KilledProcess {
+    #[serde(flatten)]
+    pub candidate: KillCandidate,
+    pub killed_at: DateTime<Utc>,
+    /// Signal that terminated the process; `None` when it was stopped
+    /// through Sentinel
+    pub signal: Option<KillSignal>,
+}
+
+impl KilledProcess {
+    /// Configured process that starts it again, if any
+    pub fn restart_target(&self) -> Option<&str> {
+        self.candidate
+            .managed_process
+            .as_deref()
+            .or(self.candidate.config_process.as_deref())
+    }
+}
+
+/// Command a port scan runs
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanBackend {
+    /// `lsof -i` (macOS, Linux)
+    Lsof,
+    /// `ss -tunap` (Linux without lsof)
+    Ss,
+    /// `netstat -ano` (Windows)
+    Netstat,
+}
+
+impl ScanBackend {
+    /// Name of the command
+    pub fn command(self) -> &'static str {
+        match self {
+            ScanBackend::Lsof => "lsof",
+            ScanBackend::Ss => "ss",
+            ScanBackend::Netstat => "netstat",
+        }
+    }
+}
+
 /// Network traffic statistics
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct NetworkTraffic {
@@ -98,6 +247,15 @@ mod tests {
         assert_eq!(PortState::Established.to_string(), "ESTABLISHED");
     }
 
+    #[test]
+    fn test_kill_signal_serialization() {
+        assert_eq!(
+            serde_json::to_string(&KillSignal::Sigterm).unwrap(),
+            "\"SIGTERM\""
+        );
+        assert_eq!(KillSignal::Sigkill.to_string(), "SIGKILL");
+    }
+
     #[test]
     fn test_network_traffic_default() {
         let traffic = NetworkTraffic::default();
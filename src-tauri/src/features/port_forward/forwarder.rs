@@ -0,0 +1,577 @@
+//! Supervised TCP forwards from local ports to other hosts
+
+use super::types::PortForward;
+use crate::error::{Result, SentinelError};
+use crate::features::docker::ContainerInfo;
+use crate::features::port_discovery::PortScanner;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{JoinHandle, JoinSet};
+
+/// Ports below this one need `settings.allowPrivilegedForwards`
+pub const PRIVILEGED_PORT_LIMIT: u16 = 1024;
+
+/// How long connecting to the target may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after a failed accept, so a persistent failure doesn't spin
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+const BUFFER_SIZE: usize = 16 * 1024;
+
+/// Traffic of one forward, updated by its connections
+#[derive(Default)]
+struct Counters {
+    sent: AtomicU64,
+    received: AtomicU64,
+    active: AtomicU64,
+    total: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl Counters {
+    fn fail(&self, error: String) {
+        tracing::warn!("{}", error);
+        *lock(&self.last_error) = Some(error);
+    }
+}
+
+/// A listening forward; its connections end when it is dropped
+struct Forward {
+    local_port: u16,
+    target_host: String,
+    target_port: u16,
+    container_id: Option<String>,
+    created_at: DateTime<Utc>,
+    counters: Arc<Counters>,
+    task: JoinHandle<()>,
+}
+
+impl Forward {
+    fn snapshot(&self, id: u64) -> PortForward {
+        PortForward {
+            id,
+            local_port: self.local_port,
+            target_host: self.target_host.clone(),
+            target_port: self.target_port,
+            container_id: self.container_id.clone(),
+            bytes_sent: self.counters.sent.load(Ordering::Relaxed),
+            bytes_received: self.counters.received.load(Ordering::Relaxed),
+            active_connections: self.counters.active.load(Ordering::Relaxed),
+            total_connections: self.counters.total.load(Ordering::Relaxed),
+            last_error: lock(&self.counters.last_error).clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+impl Drop for Forward {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Forwards local ports to ports on other hosts, such as a remote Docker host
+#[derive(Default)]
+pub struct PortForwarder {
+    forwards: Mutex<BTreeMap<u64, Forward>>,
+    next_id: AtomicU64,
+    /// Containers whose published ports are forwarded automatically
+    auto_containers: Mutex<HashSet<String>>,
+}
+
+impl PortForwarder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listen on `local_port` at 127.0.0.1 and forward every connection to
+    /// `target_host:target_port`
+    ///
+    /// Port 0 picks a free port. Ports below [`PRIVILEGED_PORT_LIMIT`] are
+    /// refused unless `allow_privileged`. Must be called from within a Tokio
+    /// runtime.
+    ///
+    /// # Errors
+    /// `ForwardPortInUse` with the owner of the port, if it can be found,
+    /// when `local_port` is taken
+    pub async fn create(
+        &self,
+        local_port: u16,
+        target_host: &str,
+        target_port: u16,
+        allow_privileged: bool,
+    ) -> Result<PortForward> {
+        self.open(local_port, target_host, target_port, allow_privileged, None)
+            .await
+    }
+
+    async fn open(
+        &self,
+        local_port: u16,
+        target_host: &str,
+        target_port: u16,
+        allow_privileged: bool,
+        container_id: Option<String>,
+    ) -> Result<PortForward> {
+        if target_host.trim().is_empty() || target_port == 0 {
+            return Err(SentinelError::InvalidInput {
+                message: "A port forward needs a target host and port".to_string(),
+            });
+        }
+        if local_port != 0 && local_port < PRIVILEGED_PORT_LIMIT && !allow_privileged {
+            return Err(SentinelError::InvalidInput {
+                message: format!(
+                    "Port {} is privileged; set allowPrivilegedForwards to forward from it",
+                    local_port
+                ),
+            });
+        }
+
+        let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, local_port)).await {
+            Ok(listener) => listener,
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+                let owner = PortScanner::new()
+                    .get_port_info(local_port)
+                    .await
+                    .ok()
+                    .flatten();
+                return Err(SentinelError::ForwardPortInUse {
+                    port: local_port,
+                    pid: owner.as_ref().map(|owner| owner.pid),
+                    process: owner.map(|owner| owner.process_name),
+                });
+            }
+            Err(e) => return Err(SentinelError::Io(e)),
+        };
+        let local_port = listener.local_addr()?.port();
+
+        let counters = Arc::new(Counters::default());
+        let target = (target_host.to_string(), target_port);
+        let task = tokio::spawn(serve(listener, target, counters.clone()));
+        let forward = Forward {
+            local_port,
+            target_host: target_host.to_string(),
+            target_port,
+            container_id,
+            created_at: Utc::now(),
+            counters,
+            task,
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let snapshot = forward.snapshot(id);
+        lock(&self.forwards).insert(id, forward);
+        tracing::info!(
+            "Forwarding 127.0.0.1:{} to {}:{}",
+            local_port,
+            target_host,
+            target_port
+        );
+        Ok(snapshot)
+    }
+
+    /// Every open forward, oldest first
+    pub fn list(&self) -> Vec<PortForward> {
+        lock(&self.forwards)
+            .iter()
+            .map(|(id, forward)| forward.snapshot(*id))
+            .collect()
+    }
+
+    /// Stop listening and end the forward's connections
+    pub fn close(&self, id: u64) -> Result<()> {
+        match lock(&self.forwards).remove(&id) {
+            Some(forward) => {
+                tracing::info!("Closed port forward from {}", forward.local_port);
+                Ok(())
+            }
+            None => Err(SentinelError::InvalidInput {
+                message: format!("No port forward with ID {}", id),
+            }),
+        }
+    }
+
+    /// Close every forward, returning how many there were
+    pub fn close_all(&self) -> usize {
+        let forwards = std::mem::take(&mut *lock(&self.forwards));
+        forwards.len()
+    }
+
+    /// Turn automatic forwarding of a container's published ports on or off
+    ///
+    /// Takes effect on the next [`sync_containers`](Self::sync_containers).
+    pub fn set_container_forwarding(&self, container_id: &str, enabled: bool) {
+        let mut containers = lock(&self.auto_containers);
+        if enabled {
+            containers.insert(container_id.to_string());
+        } else {
+            containers.remove(container_id);
+        }
+    }
+
+    /// Whether a container's published ports are forwarded automatically
+    pub fn is_container_forwarded(&self, container_id: &str) -> bool {
+        lock(&self.auto_containers).contains(container_id)
+    }
+
+    /// Forward the published TCP ports of the automatically forwarded
+    /// containers among `running` from the same local ports to `host`, and
+    /// close the forwards of containers that stopped or were turned off
+    ///
+    /// # Returns
+    /// The forwards that could not be opened
+    pub async fn sync_containers(
+        &self,
+        running: &[ContainerInfo],
+        host: &str,
+        allow_privileged: bool,
+    ) -> Vec<SentinelError> {
+        let auto = lock(&self.auto_containers).clone();
+        let wanted: HashSet<(String, u16)> = running
+            .iter()
+            .filter(|container| auto.contains(&container.id))
+            .flat_map(|container| {
+                container
+                    .ports
+                    .iter()
+                    .filter(|port| port.protocol == "tcp")
+                    .filter_map(|port| port.host_port)
+                    .map(|port| (container.id.clone(), port))
+            })
+            .collect();
+
+        let existing: HashSet<(String, u16)> = {
+            let mut forwards = lock(&self.forwards);
+            forwards.retain(|_, forward| match &forward.container_id {
+                Some(container) => wanted.contains(&(container.clone(), forward.target_port)),
+                None => true,
+            });
+            forwards
+                .values()
+                .filter_map(|forward| {
+                    let container = forward.container_id.clone()?;
+                    Some((container, forward.target_port))
+                })
+                .collect()
+        };
+
+        let mut errors = Vec::new();
+        for (container, port) in wanted.difference(&existing) {
+            let opened = self
+                .open(
+                    *port,
+                    host,
+                    *port,
+                    allow_privileged,
+                    Some(container.clone()),
+                )
+                .await;
+            if let Err(e) = opened {
+                errors.push(e);
+            }
+        }
+        errors
+    }
+}
+
+/// Accept connections until the task is aborted, which also ends them
+async fn serve(listener: TcpListener, target: (String, u16), counters: Arc<Counters>) {
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((inbound, _)) => {
+                    counters.total.fetch_add(1, Ordering::Relaxed);
+                    connections.spawn(forward_connection(inbound, target.clone(), counters.clone()));
+                }
+                Err(e) => {
+                    tracing::warn!("Port forward failed to accept a connection: {}", e);
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                }
+            },
+            // Reap finished connections so the set doesn't grow
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+async fn forward_connection(
+    mut inbound: TcpStream,
+    target: (String, u16),
+    counters: Arc<Counters>,
+) {
+    let (host, port) = target;
+    let connect = TcpStream::connect((host.as_str(), port));
+    let mut outbound = match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+        Ok(Ok(outbound)) => outbound,
+        Ok(Err(e)) => {
+            counters.fail(format!("Failed to connect to {}:{}: {}", host, port, e));
+            return;
+        }
+        Err(_) => {
+            counters.fail(format!("Timed out connecting to {}:{}", host, port));
+            return;
+        }
+    };
+
+    counters.active.fetch_add(1, Ordering::Relaxed);
+    let (from_client, to_client) = inbound.split();
+    let (from_target, to_target) = outbound.split();
+    let result = tokio::try_join!(
+        pump(from_client, to_target, &counters.sent),
+        pump(from_target, to_client, &counters.received)
+    );
+    counters.active.fetch_sub(1, Ordering::Relaxed);
+
+    if let Err(e) = result {
+        tracing::debug!("Forwarded connection to {}:{} ended: {}", host, port, e);
+    }
+}
+
+/// Copy `from` to `to` until `from` ends, counting the bytes
+async fn pump(
+    mut from: impl AsyncRead + Unpin,
+    mut to: impl AsyncWrite + Unpin,
+    counter: &AtomicU64,
+) -> io::Result<()> {
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        let read = from.read(&mut buffer).await?;
+        if read == 0 {
+            return to.shutdown().await;
+        }
+        to.write_all(&buffer[..read]).await?;
+        counter.fetch_add(read as u64, Ordering::Relaxed);
+    }
+}
+
+/// Host the Docker daemon in `DOCKER_HOST` runs on, unless it is this machine
+pub fn remote_docker_host() -> Option<String> {
+    parse_docker_host(&std::env::var("DOCKER_HOST").ok()?)
+}
+
+fn parse_docker_host(value: &str) -> Option<String> {
+    let (scheme, rest) = value.split_once("://")?;
+    if !matches!(scheme, "tcp" | "ssh" | "http" | "https") {
+        return None;
+    }
+
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host_port.strip_prefix('[') {
+        // [::1]:2375
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host_port.split(':').next().unwrap_or_default(),
+    };
+
+    match host {
+        "" | "localhost" | "127.0.0.1" | "::1" => None,
+        host => Some(host.to_string()),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| {
+        tracing::error!("Failed to lock port forward state: {}", e);
+        e.into_inner()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::docker::{ContainerHealth, PortMapping};
+
+    /// An echo server on a free port
+    async fn echo_server() -> u16 {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let (mut reader, mut writer) = stream.split();
+                    let _ = tokio::io::copy(&mut reader, &mut writer).await;
+                });
+            }
+        });
+        port
+    }
+
+    async fn wait_for(mut done: impl FnMut() -> bool) {
+        for _ in 0..100 {
+            if done() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("condition not reached");
+    }
+
+    fn container(id: &str, host_ports: &[u16]) -> ContainerInfo {
+        ContainerInfo {
+            id: id.to_string(),
+            full_id: id.to_string(),
+            name: id.to_string(),
+            image: "nginx".to_string(),
+            status: "Up".to_string(),
+            state: "running".to_string(),
+            ports: host_ports
+                .iter()
+                .map(|&port| PortMapping {
+                    container_port: 80,
+                    host_port: Some(port),
+                    protocol: "tcp".to_string(),
+                    host_ip: None,
+                })
+                .collect(),
+            cpu_percent: None,
+            memory_usage: None,
+            memory_limit: None,
+            network_rx_bytes: None,
+            network_tx_bytes: None,
+            created: Utc::now(),
+            labels: vec![],
+            health: ContainerHealth::from_summary("running", "Up"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forward_carries_data_and_counts_bytes() {
+        let target = echo_server().await;
+        let forwarder = PortForwarder::new();
+        let forward = forwarder
+            .create(0, "127.0.0.1", target, false)
+            .await
+            .unwrap();
+        assert_ne!(forward.local_port, 0);
+
+        let mut client = TcpStream::connect((Ipv4Addr::LOCALHOST, forward.local_port))
+            .await
+            .unwrap();
+        client.write_all(b"hello forward").await.unwrap();
+        let mut echoed = [0; 13];
+        client.read_exact(&mut echoed).await.unwrap();
+        assert_eq!(&echoed, b"hello forward");
+        drop(client);
+
+        wait_for(|| forwarder.list()[0].active_connections == 0).await;
+        let listed = &forwarder.list()[0];
+        assert_eq!(listed.bytes_sent, 13);
+        assert_eq!(listed.bytes_received, 13);
+        assert_eq!(listed.total_connections, 1);
+        assert!(listed.last_error.is_none());
+
+        forwarder.close(forward.id).unwrap();
+        assert!(forwarder.list().is_empty());
+        assert!(forwarder.close(forward.id).is_err());
+        // The port is released once the aborted task is dropped
+        wait_for(|| std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, forward.local_port)).is_ok())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_target_is_reported() {
+        // A port nothing listens on once the listener is gone
+        let closed = {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let forwarder = PortForwarder::new();
+        let forward = forwarder
+            .create(0, "127.0.0.1", closed, false)
+            .await
+            .unwrap();
+
+        let mut client = TcpStream::connect((Ipv4Addr::LOCALHOST, forward.local_port))
+            .await
+            .unwrap();
+        let mut buffer = [0; 1];
+        assert_eq!(client.read(&mut buffer).await.unwrap_or(0), 0);
+
+        wait_for(|| forwarder.list()[0].last_error.is_some()).await;
+        assert_eq!(forwarder.close_all(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_privileged_and_taken_ports_are_refused() {
+        let forwarder = PortForwarder::new();
+        match forwarder.create(80, "127.0.0.1", 8080, false).await {
+            Err(SentinelError::InvalidInput { message }) => {
+                assert!(message.contains("allowPrivilegedForwards"))
+            }
+            other => panic!("expected InvalidInput, got {:?}", other.map(|f| f.id)),
+        }
+
+        let taken = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = taken.local_addr().unwrap().port();
+        match forwarder.create(port, "127.0.0.1", 8080, false).await {
+            Err(SentinelError::ForwardPortInUse { port: reported, .. }) => {
+                assert_eq!(reported, port)
+            }
+            other => panic!("expected ForwardPortInUse, got {:?}", other.map(|f| f.id)),
+        }
+        assert!(forwarder.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_containers_follows_toggle_and_running_containers() {
+        let target = echo_server().await;
+        let forwarder = PortForwarder::new();
+        let web = container("web", &[target]);
+
+        // Not toggled on
+        assert!(forwarder
+            .sync_containers(std::slice::from_ref(&web), "127.0.0.1", false)
+            .await
+            .is_empty());
+        assert!(forwarder.list().is_empty());
+
+        // The echo server holds the same local port, so the forward can't open
+        forwarder.set_container_forwarding("web", true);
+        assert!(forwarder.is_container_forwarded("web"));
+        let errors = forwarder
+            .sync_containers(std::slice::from_ref(&web), "127.0.0.1", false)
+            .await;
+        assert!(matches!(
+            errors.as_slice(),
+            [SentinelError::ForwardPortInUse { .. }]
+        ));
+
+        // A manual forward survives syncs
+        forwarder
+            .create(0, "127.0.0.1", target, false)
+            .await
+            .unwrap();
+        forwarder.set_container_forwarding("web", false);
+        forwarder.sync_containers(&[], "127.0.0.1", false).await;
+        assert_eq!(forwarder.list().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_docker_host() {
+        assert_eq!(
+            parse_docker_host("tcp://192.168.64.2:2376"),
+            Some("192.168.64.2".to_string())
+        );
+        assert_eq!(
+            parse_docker_host("ssh://dev@build-box"),
+            Some("build-box".to_string())
+        );
+        assert_eq!(
+            parse_docker_host("tcp://[fd00::2]:2375"),
+            Some("fd00::2".to_string())
+        );
+        assert_eq!(parse_docker_host("tcp://localhost:2375"), None);
+        assert_eq!(parse_docker_host("unix:///var/run/docker.sock"), None);
+        assert_eq!(parse_docker_host("not a url"), None);
+    }
+}
@@ -0,0 +1,134 @@
+//! # Port Forwarding Module
+//!
+//! Forwards local ports to ports on other hosts, for services that aren't
+//! reachable from localhost, such as containers on a remote Docker host
+//! (`DOCKER_HOST=tcp://...` or `ssh://...`).
+//!
+//! ## Features
+//! - Supervised TCP forwards listening on 127.0.0.1
+//! - Bytes transferred and connection counts per forward
+//! - Automatic forwards for the published ports of chosen containers
+//! - Bind conflicts report the process holding the port
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use sentinel::features::port_forward::PortForwarder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let forwarder = PortForwarder::new();
+//!     let forward = forwarder.create(8080, "192.168.64.2", 8080, false).await.unwrap();
+//!     println!("127.0.0.1:{} -> {}:{}", forward.local_port, forward.target_host, forward.target_port);
+//! }
+//! ```
+
+mod forwarder;
+mod types;
+
+pub use forwarder::{remote_docker_host, PortForwarder, PRIVILEGED_PORT_LIMIT};
+pub use types::*;
+
+use crate::error::{Result, SentinelError};
+use crate::features::docker::{DockerMonitor, DockerMonitorState};
+use crate::state::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::Mutex;
+
+/// How often automatic container forwards follow containers starting and stopping
+pub const CONTAINER_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Application state for port forwards
+pub struct PortForwardState(pub Arc<PortForwarder>);
+
+/// Forward connections to `local_port` on 127.0.0.1 to `target_host:target_port`
+#[tauri::command]
+pub async fn create_port_forward(
+    forwards: State<'_, PortForwardState>,
+    state: State<'_, AppState>,
+    local_port: u16,
+    target_host: String,
+    target_port: u16,
+) -> Result<PortForward> {
+    let allow_privileged = state.process_manager.settings().allow_privileged_forwards;
+    forwards
+        .0
+        .create(local_port, &target_host, target_port, allow_privileged)
+        .await
+}
+
+/// List open port forwards with their traffic
+#[tauri::command]
+pub async fn list_port_forwards(forwards: State<'_, PortForwardState>) -> Result<Vec<PortForward>> {
+    Ok(forwards.0.list())
+}
+
+/// Close a port forward and its connections
+#[tauri::command]
+pub async fn close_port_forward(forwards: State<'_, PortForwardState>, id: u64) -> Result<()> {
+    forwards.0.close(id)
+}
+
+/// Turn automatic forwarding of a container's published ports on or off
+///
+/// Only available when Docker runs on another host. Returns the container's
+/// forwards once they are opened.
+#[tauri::command]
+pub async fn set_container_port_forwarding(
+    forwards: State<'_, PortForwardState>,
+    docker: State<'_, DockerMonitorState>,
+    state: State<'_, AppState>,
+    container_id: String,
+    enabled: bool,
+) -> Result<Vec<PortForward>> {
+    if enabled && remote_docker_host().is_none() {
+        return Err(SentinelError::InvalidInput {
+            message: "Docker runs on this machine, so its ports are reachable already".to_string(),
+        });
+    }
+
+    forwards.0.set_container_forwarding(&container_id, enabled);
+    let allow_privileged = state.process_manager.settings().allow_privileged_forwards;
+    if let Some(e) = sync_container_forwards(&forwards.0, &docker.0, allow_privileged)
+        .await
+        .into_iter()
+        .next()
+    {
+        return Err(e);
+    }
+
+    Ok(forwards
+        .0
+        .list()
+        .into_iter()
+        .filter(|forward| forward.container_id.as_deref() == Some(container_id.as_str()))
+        .collect())
+}
+
+/// Open and close automatic container forwards to match the running containers
+///
+/// Does nothing while Docker runs on this machine or can't be reached.
+///
+/// # Returns
+/// The forwards that could not be opened
+pub async fn sync_container_forwards(
+    forwards: &PortForwarder,
+    docker: &Mutex<DockerMonitor>,
+    allow_privileged: bool,
+) -> Vec<SentinelError> {
+    let Some(host) = remote_docker_host() else {
+        return Vec::new();
+    };
+    let running = match docker.lock().await.list_containers(false).await {
+        Ok(running) => running,
+        Err(e) => {
+            tracing::debug!("Failed to list containers for port forwards: {}", e);
+            return Vec::new();
+        }
+    };
+    forwards
+        .sync_containers(&running, &host, allow_privileged)
+        .await
+}
@@ -0,0 +1,58 @@
+//! Port forwarding data types
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A forward from a local port to a port on another host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForward {
+    /// Identifier for closing the forward
+    pub id: u64,
+    /// Port listened on at 127.0.0.1
+    pub local_port: u16,
+    /// Host connections are forwarded to
+    pub target_host: String,
+    /// Port connections are forwarded to
+    pub target_port: u16,
+    /// Docker container the forward was created for, if any
+    pub container_id: Option<String>,
+    /// Bytes sent from local clients to the target
+    pub bytes_sent: u64,
+    /// Bytes sent from the target back to local clients
+    pub bytes_received: u64,
+    /// Connections currently open
+    pub active_connections: u64,
+    /// Connections accepted since the forward was created
+    pub total_connections: u64,
+    /// Latest failure to reach the target, if any
+    pub last_error: Option<String>,
+    /// When the forward was created
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_forward_serialization() {
+        let forward = PortForward {
+            id: 1,
+            local_port: 8080,
+            target_host: "10.0.0.5".to_string(),
+            target_port: 80,
+            container_id: None,
+            bytes_sent: 10,
+            bytes_received: 20,
+            active_connections: 0,
+            total_connections: 1,
+            last_error: None,
+            created_at: Utc::now(),
+        };
+        let json = serde_json::to_value(&forward).unwrap();
+        assert_eq!(json["localPort"], 8080);
+        assert_eq!(json["targetHost"], "10.0.0.5");
+        assert_eq!(json["bytesReceived"], 20);
+    }
+}
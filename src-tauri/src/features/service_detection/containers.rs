@@ -0,0 +1,134 @@
+//! Services running in Docker containers
+//!
+//! A port a container publishes on the host is listened on by Docker's port
+//! proxy (`docker-proxy`, or `com.docker.backend`/`vpnkit` on Docker Desktop)
+//! rather than by the service, so its process name says nothing about what
+//! runs behind it. Such ports are looked up in the port mappings of the
+//! running containers, and detection looks at the container's image and the
+//! port inside the container instead.
+
+use crate::features::docker::ContainerInfo;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Processes that listen on host ports on behalf of containers
+const DOCKER_PROXY_PROCESSES: &[&str] = &[
+    "docker-proxy",
+    "com.docker.backend",
+    "com.docker.vpnkit",
+    "vpnkit",
+    "vpnkit-bridge",
+    "gvproxy",
+    "rootlessport",
+];
+
+/// Width lsof truncates command names to
+const LSOF_COMMAND_WIDTH: usize = 9;
+
+/// How soon a port missing from the mappings is looked up again
+///
+/// A container started outside the app shows up before the mappings expire.
+const MISSING_PORT_RETRY: Duration = Duration::from_secs(5);
+
+/// Whether `process_name` is Docker's port proxy
+pub fn is_docker_proxy(process_name: &str) -> bool {
+    let name = process_name.to_lowercase();
+    DOCKER_PROXY_PROCESSES.iter().any(|proxy| {
+        *proxy == name || (name.len() >= LSOF_COMMAND_WIDTH && proxy.starts_with(&name))
+    })
+}
+
+/// The name of an image without registry, namespace, tag or digest,
+/// e.g. `postgres` for `docker.io/library/postgres:16-alpine`
+pub fn image_name(image: &str) -> &str {
+    let image = image.split('@').next().unwrap_or(image);
+    let name = image.rsplit('/').next().unwrap_or(image);
+    name.split(':').next().unwrap_or(name)
+}
+
+/// A host port published by a running container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedPort {
+    /// Container ID (short form)
+    pub container_id: String,
+    pub container_name: String,
+    pub image: String,
+    /// The port inside the container
+    pub container_port: u16,
+}
+
+/// Host ports published by running containers, read again after a TTL
+pub struct ContainerPorts {
+    ttl: Duration,
+    ports: HashMap<u16, PublishedPort>,
+    refreshed_at: Option<Instant>,
+}
+
+impl ContainerPorts {
+    /// Create an empty mapping that is read again `ttl` after each refresh
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            ports: HashMap::new(),
+            refreshed_at: None,
+        }
+    }
+
+    /// Read the mappings again `ttl` after each refresh from now on
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    /// Whether the mappings should be read from Docker before looking up `port`
+    pub fn needs_refresh(&self, port: u16, now: Instant) -> bool {
+        let Some(refreshed_at) = self.refreshed_at else {
+            return true;
+        };
+        let age = now.saturating_duration_since(refreshed_at);
+        age >= self.ttl || (!self.ports.contains_key(&port) && age >= MISSING_PORT_RETRY)
+    }
+
+    /// Replace the mappings with the ports `containers` publish, returning
+    /// the host ports now published by a different container, or no longer
+    pub fn refresh(&mut self, containers: &[ContainerInfo], now: Instant) -> Vec<u16> {
+        let mut ports = HashMap::new();
+        for container in containers.iter().filter(|c| c.state == "running") {
+            for mapping in &container.ports {
+                if let Some(host_port) = mapping.host_port {
+                    ports.entry(host_port).or_insert_with(|| PublishedPort {
+                        container_id: container.id.clone(),
+                        container_name: container.name.clone(),
+                        image: container.image.clone(),
+                        container_port: mapping.container_port,
+                    });
+                }
+            }
+        }
+
+        let mut changed: Vec<u16> = self
+            .ports
+            .keys()
+            .chain(ports.keys())
+            .filter(|port| self.ports.get(port) != ports.get(port))
+            .copied()
+            .collect();
+        changed.sort_unstable();
+        changed.dedup();
+
+        self.ports = ports;
+        self.refreshed_at = Some(now);
+        changed
+    }
+
+    /// Forget the mappings so the next lookup reads them again, returning the
+    /// host ports they had
+    pub fn invalidate(&mut self) -> Vec<u16> {
+        self.refreshed_at = None;
+        self.ports.drain().map(|(port, _)| port).collect()
+    }
+
+    /// The container publishing `port` on the host
+    pub fn get(&self, port: u16) -> Option<&PublishedPort> {
+        self.ports.get(&port)
+    }
+}
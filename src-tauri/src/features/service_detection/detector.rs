@@ -1,8 +1,14 @@
 //! Service detector implementation
 
+use super::containers::{self, ContainerPorts};
+use crate::features::docker::ContainerInfo;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default time a cached detection stays valid before it is re-detected
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceInfo {
@@ -19,6 +25,11 @@ pub struct ServiceInfo {
     pub icon: String,
     pub detected_at: DateTime<Utc>,
     pub confidence: f32,
+    /// Container publishing the port, when Docker's port proxy listens on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -53,9 +64,35 @@ pub struct ServicePattern {
     pub icon: String,
 }
 
+/// Service detection cache statistics
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    /// Number of cached detections
+    pub size: usize,
+    /// Lookups served from the cache
+    pub hits: u64,
+    /// Lookups that required a fresh detection
+    pub misses: u64,
+    /// Time-to-live of a cached detection, in seconds
+    pub ttl_secs: u64,
+}
+
+struct CacheEntry {
+    service: ServiceInfo,
+    process_name: String,
+    cached_at: Instant,
+}
+
 pub struct ServiceDetector {
     pub(super) patterns: Vec<ServicePattern>,
-    cache: HashMap<String, ServiceInfo>,
+    /// Cached detections keyed by (port, pid)
+    cache: HashMap<(u16, u32), CacheEntry>,
+    cache_ttl: Duration,
+    hits: u64,
+    misses: u64,
+    /// Host ports published by containers, expiring like the detections
+    containers: ContainerPorts,
 }
 
 impl Default for ServiceDetector {
@@ -65,13 +102,25 @@ impl Default for ServiceDetector {
 }
 impl ServiceDetector {
     pub fn new() -> Self {
+        Self::with_cache_ttl(DEFAULT_CACHE_TTL)
+    }
+
+    /// Create a detector whose cached detections expire after `ttl`
+    pub fn with_cache_ttl(ttl: Duration) -> Self {
         Self {
             patterns: super::patterns::get_builtin_patterns(),
             cache: HashMap::new(),
+            cache_ttl: ttl,
+            hits: 0,
+            misses: 0,
+            containers: ContainerPorts::new(ttl),
         }
     }
 
     /// Detect service from port info
+    ///
+    /// A port Docker's port proxy listens on is detected from the container
+    /// publishing it, as last given to [`Self::set_containers`].
     pub fn detect(
         &mut self,
         port: u16,
@@ -79,13 +128,81 @@ impl ServiceDetector {
         process_name: &str,
         command: Option<&str>,
     ) -> Option<ServiceInfo> {
-        // Check cache first
-        let cache_key = format!("{}:{}:{}", port, pid, process_name);
-        if let Some(cached) = self.cache.get(&cache_key) {
-            return Some(cached.clone());
+        // Check cache first; entries are only valid for the same process and within TTL
+        let cache_key = (port, pid);
+        if let Some(entry) = self.cache.get(&cache_key) {
+            if entry.process_name == process_name && entry.cached_at.elapsed() < self.cache_ttl {
+                self.hits += 1;
+                return Some(entry.service.clone());
+            }
+            self.cache.remove(&cache_key);
         }
+        self.misses += 1;
+
+        // The port may have been reused by a different process
+        self.cache
+            .retain(|(cached_port, _), _| *cached_port != port);
+
+        let published = if containers::is_docker_proxy(process_name) {
+            self.containers.get(port).cloned()
+        } else {
+            None
+        };
+        let best_match = match &published {
+            // What runs in the container, on the port it exposes
+            Some(published) => self.best_match(
+                published.container_port,
+                containers::image_name(&published.image),
+                Some(&published.image),
+            ),
+            None => self.best_match(port, process_name, command),
+        };
+
+        // Create ServiceInfo from best match
+        if let Some((pattern, confidence)) = best_match {
+            let source = published
+                .as_ref()
+                .map_or(process_name, |published| published.container_name.as_str());
+            let service_info = ServiceInfo {
+                id: format!("{}:{}:{}", port, pid, source),
+                name: pattern.name,
+                category: pattern.category,
+                port,
+                pid,
+                version: None,
+                health: HealthStatus::Unknown,
+                description: pattern.description,
+                docs_url: pattern.docs_url,
+                health_check_path: pattern.health_check_path,
+                icon: pattern.icon,
+                detected_at: Utc::now(),
+                confidence,
+                container_id: published.as_ref().map(|p| p.container_id.clone()),
+                container_name: published.map(|p| p.container_name),
+            };
+
+            // Cache the result
+            self.cache.insert(
+                cache_key,
+                CacheEntry {
+                    service: service_info.clone(),
+                    process_name: process_name.to_string(),
+                    cached_at: Instant::now(),
+                },
+            );
+            Some(service_info)
+        } else {
+            None
+        }
+    }
 
-        // Try to match against patterns
+    /// The pattern matching a service best, with its confidence
+    fn best_match(
+        &self,
+        port: u16,
+        process_name: &str,
+        command: Option<&str>,
+    ) -> Option<(ServicePattern, f32)> {
         let mut best_match: Option<(ServicePattern, f32)> = None;
         let process_lower = process_name.to_lowercase();
         let command_lower = command.map(|c| c.to_lowercase());
@@ -129,30 +246,37 @@ impl ServiceDetector {
             }
         }
 
-        // Create ServiceInfo from best match
-        if let Some((pattern, confidence)) = best_match {
-            let service_info = ServiceInfo {
-                id: cache_key.clone(),
-                name: pattern.name,
-                category: pattern.category,
-                port,
-                pid,
-                version: None,
-                health: HealthStatus::Unknown,
-                description: pattern.description,
-                docs_url: pattern.docs_url,
-                health_check_path: pattern.health_check_path,
-                icon: pattern.icon,
-                detected_at: Utc::now(),
-                confidence,
-            };
+        best_match
+    }
 
-            // Cache the result
-            self.cache.insert(cache_key, service_info.clone());
-            Some(service_info)
-        } else {
-            None
-        }
+    /// Whether the ports published by containers should be read from Docker
+    /// before detecting the service on `port`
+    pub fn needs_containers(&self, port: u16, process_name: &str) -> bool {
+        containers::is_docker_proxy(process_name)
+            && self.containers.needs_refresh(port, Instant::now())
+    }
+
+    /// Use the ports published by `containers` for ports Docker's port proxy
+    /// listens on, dropping cached detections of ports that changed hands
+    pub fn set_containers(&mut self, containers: &[ContainerInfo]) {
+        let changed = self.containers.refresh(containers, Instant::now());
+        self.cache
+            .retain(|(cached_port, _), _| !changed.contains(cached_port));
+    }
+
+    /// Forget the ports published by containers, as when one started or
+    /// stopped, along with the detections made from them
+    pub fn invalidate_containers(&mut self) {
+        let ports = self.containers.invalidate();
+        self.cache
+            .retain(|(cached_port, _), _| !ports.contains(cached_port));
+    }
+
+    /// Expire cached detections after `ttl` from now on, including those
+    /// already cached
+    pub fn set_cache_ttl(&mut self, ttl: Duration) {
+        self.cache_ttl = ttl;
+        self.containers.set_ttl(ttl);
     }
 
     /// Clear detection cache
@@ -160,10 +284,28 @@ impl ServiceDetector {
         self.cache.clear();
     }
 
+    /// Drop cached detections for a port, returning whether any were removed
+    pub fn invalidate(&mut self, port: u16) -> bool {
+        let before = self.cache.len();
+        self.cache
+            .retain(|(cached_port, _), _| *cached_port != port);
+        self.cache.len() != before
+    }
+
     /// Get number of cached detections
     pub fn cache_size(&self) -> usize {
         self.cache.len()
     }
+
+    /// Get cache size, hit/miss counters and TTL
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            size: self.cache.len(),
+            hits: self.hits,
+            misses: self.misses,
+            ttl_secs: self.cache_ttl.as_secs(),
+        }
+    }
 }
 
 #[cfg(test)]
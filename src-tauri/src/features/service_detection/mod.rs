@@ -1,23 +1,38 @@
 //! Service detection and identification module
 //!
 //! Automatically detects services running on discovered ports using pattern matching,
-//! health checks, and metadata enrichment.
+//! health checks, and metadata enrichment. Ports Docker's port proxy
+//! listens on are detected from the container that publishes them.
 
+mod containers;
 mod detector;
 mod patterns;
 
 #[cfg(test)]
 mod tests;
 
-pub use detector::{HealthStatus, ServiceCategory, ServiceDetector, ServiceInfo};
+pub use detector::{
+    CacheStats, HealthStatus, ServiceCategory, ServiceDetector, ServiceInfo, DEFAULT_CACHE_TTL,
+};
 
 use crate::error::Result;
-use std::sync::{Arc, Mutex};
+use crate::features::docker::DockerMonitorState;
+use std::sync::{Arc, Mutex, MutexGuard};
 use tauri::State;
 
 /// Application state for service detector
 pub struct ServiceDetectorState(pub Arc<Mutex<ServiceDetector>>);
 
+impl ServiceDetectorState {
+    /// Lock the detector, recovering it if a holder panicked
+    pub fn lock(&self) -> MutexGuard<'_, ServiceDetector> {
+        self.0.lock().unwrap_or_else(|e| {
+            tracing::error!("Failed to lock detector: {}", e);
+            e.into_inner()
+        })
+    }
+}
+
 /// Detect service from port information
 #[tauri::command]
 pub async fn detect_service(
@@ -26,6 +41,7 @@ pub async fn detect_service(
     process_name: String,
     command: Option<String>,
     state: State<'_, ServiceDetectorState>,
+    docker: State<'_, DockerMonitorState>,
 ) -> Result<Option<ServiceInfo>> {
     tracing::info!(
         "detect_service called for port {}, pid {}, process {}",
@@ -34,12 +50,18 @@ pub async fn detect_service(
         process_name
     );
 
-    let mut detector = state.0.lock().unwrap_or_else(|e| {
-        tracing::error!("Failed to lock detector: {}", e);
-        e.into_inner()
-    });
+    if state.lock().needs_containers(port, &process_name) {
+        // Not holding the detector while Docker answers
+        let containers = docker.0.lock().await.list_containers(false).await;
+        match containers {
+            Ok(containers) => state.lock().set_containers(&containers),
+            Err(e) => tracing::warn!("Failed to list containers for port {}: {}", port, e),
+        }
+    }
 
-    let result = detector.detect(port, pid, &process_name, command.as_deref());
+    let result = state
+        .lock()
+        .detect(port, pid, &process_name, command.as_deref());
 
     if let Some(ref service) = result {
         tracing::info!(
@@ -59,10 +81,7 @@ pub async fn detect_service(
 pub async fn clear_service_cache(state: State<'_, ServiceDetectorState>) -> Result<()> {
     tracing::info!("clear_service_cache called");
 
-    let mut detector = state.0.lock().unwrap_or_else(|e| {
-        tracing::error!("Failed to lock detector: {}", e);
-        e.into_inner()
-    });
+    let mut detector = state.lock();
 
     detector.clear_cache();
     tracing::info!("Service detection cache cleared");
@@ -70,13 +89,21 @@ pub async fn clear_service_cache(state: State<'_, ServiceDetectorState>) -> Resu
     Ok(())
 }
 
-/// Get cache size
+/// Invalidate cached detections for a single port
+///
+/// Returns `true` if an entry was removed.
 #[tauri::command]
-pub async fn get_service_cache_size(state: State<'_, ServiceDetectorState>) -> Result<usize> {
-    let detector = state.0.lock().unwrap_or_else(|e| {
-        tracing::error!("Failed to lock detector: {}", e);
-        e.into_inner()
-    });
+pub async fn invalidate_service_cache_entry(
+    port: u16,
+    state: State<'_, ServiceDetectorState>,
+) -> Result<bool> {
+    tracing::info!("invalidate_service_cache_entry called for port {}", port);
+
+    Ok(state.lock().invalidate(port))
+}
 
-    Ok(detector.cache_size())
+/// Get cache size and hit/miss counters
+#[tauri::command]
+pub async fn get_service_cache_stats(state: State<'_, ServiceDetectorState>) -> Result<CacheStats> {
+    Ok(state.lock().cache_stats())
 }
@@ -1,7 +1,12 @@
 //! Unit tests for service detection module
 
-use super::detector::{ServiceCategory, ServiceDetector};
+use super::containers::{image_name, is_docker_proxy, ContainerPorts};
+use super::detector::{ServiceCategory, ServiceDetector, DEFAULT_CACHE_TTL};
 use super::patterns::get_builtin_patterns;
+use crate::features::docker::{ContainerHealth, ContainerInfo, PortMapping};
+use crate::features::port_discovery::{NetworkTraffic, PortInfo, PortState, Protocol};
+use chrono::Utc;
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_detector_creation() {
@@ -223,6 +228,88 @@ fn test_cache_behavior() {
     assert_eq!(result1.as_ref().unwrap().id, result2.as_ref().unwrap().id);
 }
 
+#[test]
+fn test_cache_port_reused_by_new_pid() {
+    let mut detector = ServiceDetector::new();
+
+    let old = detector
+        .detect(3000, 1111, "node", Some("next dev"))
+        .unwrap();
+    assert_eq!(old.name, "Next.js");
+
+    // Same port, different process: must not return the stale entry
+    let new = detector.detect(3000, 2222, "node", Some("vite")).unwrap();
+    assert_eq!(new.pid, 2222);
+    assert_ne!(new.id, old.id);
+    assert_eq!(detector.cache_size(), 1);
+
+    let stats = detector.cache_stats();
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.misses, 2);
+}
+
+#[test]
+fn test_cache_ttl_expiry() {
+    let mut detector = ServiceDetector::with_cache_ttl(Duration::ZERO);
+
+    let first = detector.detect(5432, 4242, "postgres", None).unwrap();
+    let second = detector.detect(5432, 4242, "postgres", None).unwrap();
+
+    // Expired entries are re-detected
+    assert!(second.detected_at >= first.detected_at);
+    let stats = detector.cache_stats();
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.ttl_secs, 0);
+}
+
+#[test]
+fn test_set_cache_ttl_applies_to_cached_entries() {
+    let mut detector = ServiceDetector::new();
+    detector.detect(5432, 4242, "postgres", None);
+    detector.detect(5432, 4242, "postgres", None);
+    assert_eq!(detector.cache_stats().hits, 1);
+
+    // Settings lowered the TTL, so the entry cached earlier has expired
+    detector.set_cache_ttl(Duration::ZERO);
+    detector.detect(5432, 4242, "postgres", None);
+    let stats = detector.cache_stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.ttl_secs, 0);
+}
+
+#[test]
+fn test_cache_stats_hits() {
+    let mut detector = ServiceDetector::new();
+
+    detector.detect(6379, 77, "redis-server", None);
+    detector.detect(6379, 77, "redis-server", None);
+    detector.detect(6379, 77, "redis-server", None);
+
+    let stats = detector.cache_stats();
+    assert_eq!(stats.size, 1);
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.ttl_secs, DEFAULT_CACHE_TTL.as_secs());
+}
+
+#[test]
+fn test_invalidate_single_port() {
+    let mut detector = ServiceDetector::new();
+
+    detector.detect(5432, 11, "postgres", None);
+    detector.detect(6379, 22, "redis-server", None);
+
+    assert!(detector.invalidate(5432));
+    assert!(!detector.invalidate(5432));
+    assert_eq!(detector.cache_size(), 1);
+
+    // The invalidated port is detected again from scratch
+    detector.detect(5432, 11, "postgres", None);
+    assert_eq!(detector.cache_stats().misses, 3);
+}
+
 #[test]
 fn test_multiple_services_different_ports() {
     let mut detector = ServiceDetector::new();
@@ -328,3 +415,184 @@ fn test_springboot_detection() {
     assert_eq!(service.category, ServiceCategory::WebFramework);
     assert_eq!(service.icon, "springboot");
 }
+
+/// A running container publishing `(container port, host port)` pairs
+fn container(id: &str, name: &str, image: &str, ports: &[(u16, u16)]) -> ContainerInfo {
+    ContainerInfo {
+        id: id.to_string(),
+        full_id: id.to_string(),
+        name: name.to_string(),
+        image: image.to_string(),
+        status: "Up".to_string(),
+        state: "running".to_string(),
+        ports: ports
+            .iter()
+            .map(|&(container_port, host_port)| PortMapping {
+                container_port,
+                host_port: Some(host_port),
+                protocol: "tcp".to_string(),
+                host_ip: None,
+            })
+            .collect(),
+        cpu_percent: None,
+        memory_usage: None,
+        memory_limit: None,
+        network_rx_bytes: None,
+        network_tx_bytes: None,
+        created: Utc::now(),
+        labels: vec![],
+        health: ContainerHealth::from_summary("running", "Up"),
+    }
+}
+
+/// A listening socket as the port scan reports it
+fn listening(port: u16, pid: u32, process_name: &str) -> PortInfo {
+    PortInfo {
+        port,
+        protocol: Protocol::TCP,
+        process_name: process_name.to_string(),
+        pid,
+        state: PortState::Listen,
+        local_address: "*".to_string(),
+        remote_address: None,
+        command: None,
+        traffic: NetworkTraffic::default(),
+        remote_info: None,
+    }
+}
+
+#[test]
+fn test_docker_proxy_names() {
+    assert!(is_docker_proxy("com.docker.backend"));
+    assert!(is_docker_proxy("vpnkit"));
+    assert!(is_docker_proxy("docker-proxy"));
+    // As lsof truncates them
+    assert!(is_docker_proxy("com.docke"));
+    assert!(is_docker_proxy("docker-pr"));
+
+    assert!(!is_docker_proxy("docker"));
+    assert!(!is_docker_proxy("postgres"));
+}
+
+#[test]
+fn test_image_name() {
+    assert_eq!(image_name("postgres"), "postgres");
+    assert_eq!(image_name("postgres:16-alpine"), "postgres");
+    assert_eq!(image_name("bitnami/redis:7.2"), "redis");
+    assert_eq!(image_name("localhost:5000/team/mongo:7"), "mongo");
+    assert_eq!(image_name("nginx@sha256:abc123"), "nginx");
+}
+
+#[test]
+fn test_services_behind_docker_proxy_detected_from_container() {
+    let mut detector = ServiceDetector::new();
+    detector.set_containers(&[
+        container("a1b2c3", "db", "postgres:16", &[(5432, 5432)]),
+        container("d4e5f6", "cache", "redis:7", &[(6379, 16379)]),
+    ]);
+
+    let scan = [
+        listening(5432, 700, "com.docker.backend"),
+        listening(16379, 700, "com.docker.backend"),
+        listening(3000, 800, "node"),
+    ];
+    let services: Vec<_> = scan
+        .iter()
+        .map(|port| detector.detect(port.port, port.pid, &port.process_name, None))
+        .collect();
+
+    let postgres = services[0].as_ref().expect("Should detect PostgreSQL");
+    assert_eq!(postgres.name, "PostgreSQL");
+    assert_eq!(postgres.port, 5432);
+    assert_eq!(postgres.pid, 700);
+    assert_eq!(postgres.container_id.as_deref(), Some("a1b2c3"));
+    assert_eq!(postgres.container_name.as_deref(), Some("db"));
+
+    // Found by the port inside the container, not the one on the host
+    let redis = services[1].as_ref().expect("Should detect Redis");
+    assert_eq!(redis.name, "Redis");
+    assert_eq!(redis.port, 16379);
+    assert_eq!(redis.container_name.as_deref(), Some("cache"));
+
+    // Ports not published by a container are detected as before
+    let next = services[2].as_ref().expect("Should detect Next.js");
+    assert_eq!(next.container_id, None);
+}
+
+#[test]
+fn test_docker_proxy_port_without_container() {
+    let mut detector = ServiceDetector::new();
+    detector.set_containers(&[container("a1b2c3", "db", "postgres", &[(5432, 5432)])]);
+
+    // The proxy alone says nothing about the service
+    assert!(detector
+        .detect(9999, 700, "com.docker.backend", None)
+        .is_none());
+    // Only the proxy's ports are looked up in the containers
+    let postgres = detector.detect(5432, 900, "postgres", None).unwrap();
+    assert_eq!(postgres.container_id, None);
+}
+
+#[test]
+fn test_container_ports_changing_hands_drop_cached_detections() {
+    let mut detector = ServiceDetector::new();
+    detector.set_containers(&[container("a1b2c3", "db", "postgres", &[(5432, 5432)])]);
+    detector.detect(5432, 700, "com.docker.backend", None);
+    detector.detect(3000, 800, "node", Some("next dev"));
+    assert_eq!(detector.cache_size(), 2);
+
+    // MySQL now publishes the port, through the same proxy process
+    detector.set_containers(&[container("f0f0f0", "mysql", "mysql:8", &[(3306, 5432)])]);
+    assert_eq!(detector.cache_size(), 1);
+    let service = detector
+        .detect(5432, 700, "com.docker.backend", None)
+        .unwrap();
+    assert_eq!(service.name, "MySQL");
+    assert_eq!(service.container_name.as_deref(), Some("mysql"));
+
+    // Started or stopped containers drop what was detected from them
+    detector.invalidate_containers();
+    assert_eq!(detector.cache_size(), 1);
+    let service = detector
+        .detect(5432, 700, "com.docker.backend", None)
+        .unwrap();
+    assert_eq!(service.name, "PostgreSQL");
+    assert_eq!(service.container_id, None);
+}
+
+#[test]
+fn test_container_ports_refresh() {
+    let start = Instant::now();
+    let mut ports = ContainerPorts::new(DEFAULT_CACHE_TTL);
+    assert!(ports.needs_refresh(5432, start));
+
+    let mut stopped = container("d4e5f6", "old", "redis", &[(6379, 6379)]);
+    stopped.state = "exited".to_string();
+    let changed = ports.refresh(
+        &[
+            container("a1b2c3", "db", "postgres", &[(5432, 5432)]),
+            stopped,
+        ],
+        start,
+    );
+    assert_eq!(changed, vec![5432]);
+    assert!(ports.get(6379).is_none());
+    assert_eq!(ports.get(5432).unwrap().container_port, 5432);
+
+    // Known ports wait for the TTL, unknown ones are retried sooner
+    let later = start + Duration::from_secs(10);
+    assert!(!ports.needs_refresh(5432, later));
+    assert!(ports.needs_refresh(6379, later));
+    assert!(!ports.needs_refresh(6379, start));
+    assert!(ports.needs_refresh(5432, start + DEFAULT_CACHE_TTL));
+
+    // Unchanged mappings are not reported again
+    let changed = ports.refresh(
+        &[container("a1b2c3", "db", "postgres", &[(5432, 5432)])],
+        later,
+    );
+    assert!(changed.is_empty());
+
+    assert_eq!(ports.invalidate(), vec![5432]);
+    assert!(ports.needs_refresh(5432, later));
+}
@@ -18,7 +18,6 @@
 //! ```no_run
 //! use sentinel::core::ProcessManager;
 //! use sentinel::models::ProcessConfig;
-//! use std::collections::HashMap;
 //!
 //! # tokio_test::block_on(async {
 //! let mut manager = ProcessManager::new();
@@ -28,12 +27,9 @@
 //!     command: "npm".to_string(),
 //!     args: vec!["start".to_string()],
 //!     cwd: Some("./backend".into()),
-//!     env: HashMap::new(),
-//!     auto_restart: true,
-//!     restart_limit: 5,
-//!     restart_delay: 1000,
-//!     depends_on: vec![],
-//!     health_check: None,
+//!     auto_restart: Some(true),
+//!     restart_delay: Some(1000),
+//!     ..Default::default()
 //! };
 //!
 //! let info = manager.start(config).await?;
@@ -76,9 +72,11 @@ pub mod models;
 pub mod state;
 
 // Re-export commonly used types
-pub use error::{Result, SentinelError};
+pub use error::{ErrorCode, Result, SentinelError};
 pub use state::AppState;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 /// Runs the Tauri application.
 ///
 /// This is the main entry point called from `main.rs`.
@@ -89,11 +87,39 @@ pub fn run() {
         Manager,
     };
 
+    // Only one instance may manage the config and state; launching another
+    // brings the running one to the front instead
+    let instance = match core::InstanceLock::acquire(&core::InstanceLock::default_path()) {
+        Ok(lock) => Some(lock),
+        Err(e @ SentinelError::AlreadyRunning { .. }) => {
+            eprintln!("{}, switching to it", e);
+            let socket = core::instance::default_socket_path();
+            if let Err(e) = tauri::async_runtime::block_on(core::instance::send(
+                &socket,
+                core::ControlCommand::Focus,
+            )) {
+                eprintln!("Failed to reach the running instance: {}", e);
+            }
+            return;
+        }
+        Err(e) => {
+            eprintln!("Failed to take the instance lock: {}", e);
+            None
+        }
+    };
+    let owns_instance = instance.is_some();
+
+    // Missing tools make their features return empty results rather than errors
+    let docker_monitor = features::docker::DockerMonitor::new();
+    let capabilities =
+        features::capabilities::CapabilitiesState::new(docker_monitor.is_available());
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_pty::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState::new())
         .manage(features::service_detection::ServiceDetectorState(
             std::sync::Arc::new(std::sync::Mutex::new(
@@ -105,22 +131,61 @@ pub fn run() {
                 features::network_monitor::TrafficCollector::new(),
             )),
         ))
+        .manage(features::network_monitor::NetworkSamplerState(
+            std::sync::Arc::new(std::sync::Mutex::new(
+                features::network_monitor::NetworkSampler::new(),
+            )),
+        ))
         .manage(features::docker::DockerMonitorState(std::sync::Arc::new(
-            tokio::sync::Mutex::new(features::docker::DockerMonitor::new()),
+            tokio::sync::Mutex::new(docker_monitor),
+        )))
+        .manage(features::docker::DockerWatcherState(std::sync::Arc::new(
+            std::sync::Mutex::new(features::docker::DockerWatcher::new()),
         )))
+        .manage(features::port_forward::PortForwardState(
+            std::sync::Arc::new(features::port_forward::PortForwarder::new()),
+        ))
+        .manage(capabilities)
         .invoke_handler(tauri::generate_handler![
             // Process commands
             commands::start_process,
+            commands::preview_process_start,
             commands::start_process_by_name,
             commands::stop_process,
             commands::restart_process,
             commands::get_process,
             commands::list_processes,
+            commands::get_process_tree,
+            commands::get_orphaned_children,
+            commands::get_process_disk_usage,
+            commands::cancel_process_disk_usage,
+            commands::clean_process_cache,
+            commands::prune_logs_now,
+            commands::list_all_managed_processes,
+            commands::monitor_external_process,
+            commands::stop_any_process,
+            commands::restart_any_process,
             commands::stop_all_processes,
+            commands::start_all_processes,
+            commands::start_processes_by_tag,
+            commands::stop_processes_by_tag,
+            commands::restart_processes_by_tag,
+            commands::get_schedule_preview,
+            commands::wait_for_ready,
+            commands::set_process_priority,
+            commands::set_process_affinity,
+            commands::write_process_stdin,
+            commands::send_process_signal,
+            commands::pause_process,
+            commands::resume_process,
             // Process log commands
             commands::get_process_logs,
             commands::get_recent_process_logs,
             commands::search_process_logs,
+            commands::get_structured_logs,
+            commands::search_all_logs,
+            commands::get_merged_logs,
+            commands::get_log_memory_stats,
             commands::clear_process_logs,
             // Process health commands
             commands::check_process_health,
@@ -128,13 +193,38 @@ pub fn run() {
             // Process persistence commands
             commands::load_config,
             commands::save_process_to_config,
+            commands::clone_process,
             commands::remove_process_from_config,
             commands::get_config_file_path,
+            commands::validate_config,
+            commands::show_resolved_config,
+            commands::get_config_schema,
+            commands::import_config,
+            commands::export_workspace,
+            commands::preview_workspace_import,
+            commands::import_workspace,
+            commands::reveal_process_env_value,
+            commands::reload_config,
             commands::start_processes_from_config,
+            // Alert commands
+            commands::list_active_alerts,
+            commands::ack_alert,
+            commands::get_alert_history,
+            // Notification commands
+            commands::test_notification_sink,
+            // Event journal commands
+            commands::query_events,
+            // Metrics history commands
+            commands::query_metrics,
+            commands::get_group_metrics,
             // External process log attachment
             commands::attach_to_external_process,
             commands::tail_log_file,
             commands::detach_external_logs,
+            commands::follow_external_process,
+            commands::list_attachments,
+            commands::stream_container_logs,
+            commands::stop_container_log_stream,
             #[cfg(target_os = "macos")]
             commands::capture_with_dtrace,
             // PTY process commands
@@ -144,6 +234,9 @@ pub fn run() {
             commands::is_pty_process_running,
             commands::restart_pty_process,
             commands::get_pty_configs,
+            commands::resize_pty,
+            commands::write_to_pty,
+            commands::get_pty_scrollback,
             // Managed process commands
             commands::create_process_config,
             commands::update_process_config,
@@ -152,6 +245,10 @@ pub fn run() {
             commands::get_process_config,
             commands::detect_framework_type,
             commands::get_framework_templates_list,
+            commands::list_templates,
+            commands::save_process_as_template,
+            commands::instantiate_template,
+            commands::delete_template,
             commands::scan_directory_for_projects,
             commands::start_process_from_config,
             commands::stop_process_by_config_id,
@@ -162,44 +259,72 @@ pub fn run() {
             commands::import_process_configs,
             // System commands
             commands::get_system_stats,
+            commands::get_system_history_downsampled,
+            commands::get_dashboard_snapshot,
+            commands::get_system_details,
+            commands::get_gpu_stats,
+            commands::get_top_processes,
             commands::get_process_stats,
             commands::get_system_info,
             // Port discovery commands
             features::port_discovery::scan_ports,
             features::port_discovery::kill_process_by_port,
             features::port_discovery::get_port_info,
+            features::port_discovery::get_active_connections,
+            features::port_discovery::get_connections_for_pid,
+            features::port_discovery::get_connections_for_process,
+            features::port_discovery::find_stale_dev_servers,
+            features::port_discovery::kill_stale_dev_servers,
+            features::port_discovery::request_kill,
+            features::port_discovery::confirm_kill,
+            features::port_discovery::recently_killed,
             // Service detection commands
             features::service_detection::detect_service,
             features::service_detection::clear_service_cache,
-            features::service_detection::get_service_cache_size,
+            features::service_detection::invalidate_service_cache_entry,
+            features::service_detection::get_service_cache_stats,
             // Network monitoring commands
             features::network_monitor::get_network_stats,
             features::network_monitor::get_network_history,
+            features::network_monitor::get_network_history_downsampled,
             features::network_monitor::clear_network_history,
             features::network_monitor::get_network_interfaces,
+            features::network_monitor::set_network_interface_filter,
+            features::network_monitor::get_network_rates,
+            features::network_monitor::start_network_sampling,
+            features::network_monitor::stop_network_sampling,
             // Docker commands
             features::docker::get_docker_info,
             features::docker::reconnect_docker,
+            features::docker::get_docker_runtime_status,
             features::docker::list_docker_containers,
             features::docker::list_docker_images,
             features::docker::get_docker_container_stats,
+            features::docker::start_container_stats_sampling,
+            features::docker::stop_container_stats_sampling,
+            features::docker::get_container_stats_history,
             features::docker::start_docker_container,
             features::docker::stop_docker_container,
             features::docker::restart_docker_container,
             features::docker::pause_docker_container,
             features::docker::unpause_docker_container,
+            features::docker::inspect_docker_container,
+            features::docker::get_container_health,
+            features::docker::remove_docker_container,
+            features::docker::exec_in_docker_container,
             features::docker::start_docker_desktop,
             features::docker::stop_docker_desktop,
             features::docker::restart_docker_desktop,
+            features::port_forward::create_port_forward,
+            features::port_forward::list_port_forwards,
+            features::port_forward::close_port_forward,
+            features::port_forward::set_container_port_forwarding,
+            // Feature capability commands
+            features::capabilities::get_feature_capabilities,
         ])
-        .setup(|app| {
-            // Initialize tracing
-            tracing_subscriber::fmt()
-                .with_env_filter(
-                    tracing_subscriber::EnvFilter::from_default_env()
-                        .add_directive(tracing::Level::INFO.into()),
-                )
-                .init();
+        .setup(move |app| {
+            // Initialize tracing; the level follows settings.logLevel once a config loads
+            core::logging::init("info");
 
             tracing::info!("Sentinel starting up...");
 
@@ -244,10 +369,438 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            // Answer later launches and the `sentinel` CLI
+            if owns_instance {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let socket = core::instance::default_socket_path();
+                    let mut control = match core::ControlListener::bind(&socket) {
+                        Ok(control) => control,
+                        Err(e) => {
+                            tracing::warn!("Control socket unavailable: {}", e);
+                            return;
+                        }
+                    };
+                    while let Some(request) = control.next().await {
+                        match request.command {
+                            core::ControlCommand::Focus => {
+                                if let Some(window) = handle.get_webview_window("main") {
+                                    let _ = window.show();
+                                    let _ = window.unminimize();
+                                    let _ = window.set_focus();
+                                }
+                            }
+                            core::ControlCommand::Shutdown => handle.exit(0),
+                            // Stops can take a while, so other requests don't wait
+                            _ => {
+                                let handle = handle.clone();
+                                tauri::async_runtime::spawn(async move {
+                                    let state = handle.state::<AppState>();
+                                    let result = control_process(&state, &request.command).await;
+                                    request.reply(result);
+                                });
+                            }
+                        }
+                    }
+                });
+            }
+
+            // Track the Docker daemon so the UI notices when it starts or stops
+            let docker = app
+                .state::<features::docker::DockerMonitorState>()
+                .0
+                .clone();
+            app.state::<features::docker::DockerWatcherState>()
+                .0
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .start(docker.clone(), app.handle().clone());
+
+            // Offer a reload when sentinel.yaml is edited outside the app
+            let config = app.state::<AppState>().config.clone();
+            tauri::async_runtime::spawn(core::config_watcher::watch(
+                commands::process::get_config_path(),
+                config,
+                app.handle().clone(),
+            ));
+
+            // Tell about edits to the env files of running processes
+            let process_manager = app.state::<AppState>().process_manager.clone();
+            tauri::async_runtime::spawn(core::env_watcher::watch(
+                process_manager,
+                app.handle().clone(),
+            ));
+
+            // Collect GPU usage on its own cadence, as nvidia-smi is slow
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let gpus = handle
+                    .state::<AppState>()
+                    .system_monitor
+                    .lock()
+                    .await
+                    .gpu_monitor();
+                loop {
+                    gpus.refresh().await;
+                    tokio::time::sleep(core::gpu::REFRESH_INTERVAL).await;
+                }
+            });
+
+            // Follow remote containers chosen for automatic port forwarding
+            let handle = app.handle().clone();
+            let forward_docker = docker.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                let forwards = handle
+                    .state::<features::port_forward::PortForwardState>()
+                    .0
+                    .clone();
+                loop {
+                    tokio::time::sleep(features::port_forward::CONTAINER_SYNC_INTERVAL).await;
+                    let allow_privileged =
+                        state.process_manager.settings().allow_privileged_forwards;
+                    for e in features::port_forward::sync_container_forwards(
+                        &forwards,
+                        &forward_docker,
+                        allow_privileged,
+                    )
+                    .await
+                    {
+                        tracing::debug!("Failed to forward container port: {}", e);
+                    }
+                }
+            });
+
+            // Attribute container usage to the processes that run them
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                loop {
+                    tokio::time::sleep(core::container_link::REFRESH_INTERVAL).await;
+                    core::container_link::refresh(&state.process_manager, &docker).await;
+                }
+            });
+
+            // Keep persisted logs within settings.logRetention, from startup on
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                loop {
+                    let settings = state.process_manager.settings();
+                    let recorder = state.event_recorder.clone();
+                    let pruned = tokio::task::spawn_blocking(move || {
+                        core::log_retention::prune_logs(
+                            &settings,
+                            &recorder,
+                            models::EventActor::Supervisor,
+                        )
+                    })
+                    .await;
+                    if let Ok(Err(e)) = pruned {
+                        tracing::warn!("Failed to prune logs: {}", e);
+                    }
+                    tokio::time::sleep(core::log_retention::PRUNE_INTERVAL).await;
+                }
+            });
+
+            // Record metrics history while settings.metricsHistory enables it
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                let network = handle
+                    .state::<features::network_monitor::NetworkMonitorState>()
+                    .0
+                    .clone();
+                let mut history: Option<(models::MetricsHistorySettings, core::MetricsRecorder)> =
+                    None;
+                loop {
+                    let settings = state.process_manager.settings().metrics_history.clone();
+                    let interval = std::time::Duration::from_secs(settings.interval_secs.max(1));
+                    if !settings.enabled {
+                        history = None;
+                    } else {
+                        // Reopen when the database or retention changes
+                        let current = history.take().filter(|(opened, _)| {
+                            opened.path == settings.path
+                                && opened.retention_days == settings.retention_days
+                        });
+                        let recorder = match current {
+                            Some((_, recorder)) => recorder,
+                            None => core::MetricsRecorder::open(
+                                core::MetricsStore::path(&settings),
+                                std::time::Duration::from_secs(
+                                    u64::from(settings.retention_days) * 24 * 60 * 60,
+                                ),
+                            ),
+                        };
+                        record_metrics_history(&state, &network, interval, &recorder).await;
+                        history = Some((settings, recorder));
+                    }
+                    tokio::time::sleep(interval).await;
+                }
+            });
+
+            // Restart crashed processes, start scheduled ones, evaluate alert rules,
+            // refresh exported metrics and follow the API settings at the
+            // configured health check interval
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                // Headless setups need the API without waiting an interval
+                update_api_server(&state).await;
+                loop {
+                    let interval_ms = state.process_manager.settings().health_check_interval_ms;
+                    tokio::time::sleep(std::time::Duration::from_millis(interval_ms.max(100)))
+                        .await;
+                    state.process_manager.check_health().await;
+                    let configs = state
+                        .config
+                        .read()
+                        .await
+                        .as_ref()
+                        .map(|config| config.processes.clone())
+                        .unwrap_or_default();
+                    state.process_manager.check_schedules(&configs).await;
+                    check_alerts(&handle, &state).await;
+                    update_metrics(&state).await;
+                    update_api_server(&state).await;
+                }
+            });
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Hold the exit until running processes are stopped or detached
+            tauri::RunEvent::ExitRequested { api, .. } if !SHUTDOWN_DONE.load(Ordering::SeqCst) => {
+                api.prevent_exit();
+                if !SHUTDOWN_STARTED.swap(true, Ordering::SeqCst) {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        shut_down_processes(&app).await;
+                        SHUTDOWN_DONE.store(true, Ordering::SeqCst);
+                        app.exit(0);
+                    });
+                }
+            }
+            tauri::RunEvent::Exit => {
+                // Stop background tasks before the runtime shuts down
+                if let Some(sampler) =
+                    app.try_state::<features::network_monitor::NetworkSamplerState>()
+                {
+                    sampler.0.lock().unwrap_or_else(|e| e.into_inner()).stop();
+                }
+                if let Some(watcher) = app.try_state::<features::docker::DockerWatcherState>() {
+                    watcher.0.lock().unwrap_or_else(|e| e.into_inner()).stop();
+                }
+                if let Some(docker) = app.try_state::<features::docker::DockerMonitorState>() {
+                    if let Ok(mut monitor) = docker.0.try_lock() {
+                        monitor.stop_stats_sampling(None);
+                    }
+                }
+                if let Some(forwards) = app.try_state::<features::port_forward::PortForwardState>()
+                {
+                    forwards.0.close_all();
+                }
+                if let Ok(mut exporter) = app.state::<AppState>().metrics_exporter.try_lock() {
+                    exporter.stop();
+                }
+                if let Ok(mut server) = app.state::<AppState>().api_server.try_lock() {
+                    server.stop();
+                }
+            }
+            _ => {}
+        });
+
+    // Held until here so the lock outlives the app
+    drop(instance);
+}
+
+/// Set once a shutdown has begun, so repeated exit requests don't start another.
+static SHUTDOWN_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Set once running processes were stopped or detached, letting the exit through.
+static SHUTDOWN_DONE: AtomicBool = AtomicBool::new(false);
+
+/// Stops or detaches the running processes as `settings.onExit` says,
+/// emitting `shutdown-progress` events, and saves the runtime state.
+async fn shut_down_processes(app: &tauri::AppHandle) {
+    use tauri::{Emitter, Manager};
+
+    let state = app.state::<AppState>();
+    let settings = state.process_manager.settings();
+    let mut runtime_state = core::StateManager::load().unwrap_or_else(|e| {
+        tracing::warn!("Failed to load runtime state: {}", e);
+        models::RuntimeState::new()
+    });
+
+    core::shutdown::shutdown(
+        &state.process_manager,
+        &state.pty_manager,
+        &mut runtime_state,
+        settings.on_exit,
+        std::time::Duration::from_millis(settings.graceful_shutdown_timeout),
+        |progress| {
+            let _ = app.emit("shutdown-progress", progress);
+        },
+    )
+    .await;
+
+    if let Err(e) = core::StateManager::save(&runtime_state) {
+        tracing::error!("Failed to save runtime state: {}", e);
+    }
+}
+
+/// Evaluates the configured alert rules, emitting `alert-fired` and
+/// `alert-resolved` events and a desktop notification for each fired alert.
+async fn check_alerts(app: &tauri::AppHandle, state: &AppState) {
+    use tauri::Emitter;
+    use tauri_plugin_notification::NotificationExt;
+
+    let rules = state
+        .config
+        .read()
+        .await
+        .as_ref()
+        .map(|config| config.alerts.clone())
+        .unwrap_or_default();
+    let mut alerts = state.alert_manager.lock().await;
+    alerts.set_rules(rules);
+
+    let mut snapshot = core::AlertSnapshot::default();
+    if alerts.needs_process_usage() {
+        state.process_manager.update_resource_usage().await;
+    }
+    snapshot.processes = core::ProcessSample::collect_all(&state.process_manager).await;
+    if alerts.needs_system() {
+        let mut monitor = state.system_monitor.lock().await;
+        snapshot.system = Some(core::SystemSample::collect(
+            &mut monitor,
+            alerts.needs_disks(),
+        ));
+    }
+
+    let events = state.process_manager.events();
+    for transition in alerts.evaluate(&snapshot, chrono::Utc::now()) {
+        match transition {
+            core::AlertTransition::Fired(alert) => {
+                tracing::warn!("Alert '{}' fired: {}", alert.rule, alert.message);
+                let _ = app.emit("alert-fired", &alert);
+                events.publish(core::LiveEvent::AlertFired(alert.clone()));
+                let shown = app
+                    .notification()
+                    .builder()
+                    .title(format!("Sentinel: {}", alert.rule))
+                    .body(&alert.message)
+                    .show();
+                if let Err(e) = shown {
+                    tracing::warn!(
+                        "Failed to show notification for alert '{}': {}",
+                        alert.rule,
+                        e
+                    );
+                }
+            }
+            core::AlertTransition::Resolved(alert) => {
+                tracing::info!("Alert '{}' resolved", alert.rule);
+                let _ = app.emit("alert-resolved", &alert);
+                events.publish(core::LiveEvent::AlertResolved(alert));
+            }
+        }
+    }
+}
+
+/// Starts, moves or stops the metrics listener to match the settings and,
+/// while it runs, replaces the snapshot it serves.
+async fn update_metrics(state: &AppState) {
+    let mut exporter = state.metrics_exporter.lock().await;
+    let address = state.process_manager.settings().metrics_address;
+    if let Err(e) = exporter.set_address(address).await {
+        tracing::warn!("{}", e);
+    }
+    if !exporter.is_enabled() {
+        return;
+    }
+
+    let mut snapshot = core::MetricsSnapshot::default();
+    state.process_manager.update_resource_usage().await;
+    snapshot.processes = core::ProcessMetrics::collect_all(&state.process_manager).await;
+    snapshot.supervisor_restarts_total = state.process_manager.restarts_total();
+    snapshot.system = Some(core::SystemMetrics::collect(
+        &mut *state.system_monitor.lock().await,
+    ));
+    exporter.update(snapshot);
+}
+
+/// Samples system and process metrics and queues them for the metrics history.
+async fn record_metrics_history(
+    state: &AppState,
+    network: &std::sync::Mutex<features::network_monitor::TrafficCollector>,
+    interval: std::time::Duration,
+    recorder: &core::MetricsRecorder,
+) {
+    state.process_manager.update_resource_usage().await;
+    let processes = core::ProcessMetrics::collect_all(&state.process_manager).await;
+    let system = core::SystemMetrics::collect(&mut *state.system_monitor.lock().await);
+    let rates = {
+        let mut collector = network.lock().unwrap_or_else(|e| e.into_inner());
+        // Reuse the background sampler's snapshot when it runs
+        collector.collect_cached(interval);
+        collector.get_rates()
+    };
+
+    recorder.record(core::metrics_history::samples_from(
+        chrono::Utc::now(),
+        &system,
+        rates.as_ref(),
+        &processes,
+    ));
+}
+
+/// Starts, stops or restarts a process for the `sentinel` CLI.
+async fn control_process(state: &AppState, command: &core::ControlCommand) -> Result<()> {
+    let manager = &state.process_manager;
+    let result = match command {
+        core::ControlCommand::Start(name) => start_from_config_file(state, name).await,
+        core::ControlCommand::Stop { name, force: false } => manager.stop(name).await,
+        core::ControlCommand::Stop { name, force: true } => manager.kill(name).await,
+        core::ControlCommand::Restart { name, force } => {
+            if *force {
+                manager.kill(name).await?;
+            }
+            manager.restart(name).await.map(|_| ())
+        }
+        core::ControlCommand::Focus | core::ControlCommand::Shutdown => Ok(()),
+    };
+    match &result {
+        Ok(()) => tracing::info!("Handled '{}' on request", command.to_line()),
+        Err(e) => tracing::warn!("Failed to handle '{}': {}", command.to_line(), e),
+    }
+    result
+}
+
+/// Starts `name` as configured in `sentinel.yaml`, for `sentinel add --start`.
+async fn start_from_config_file(state: &AppState, name: &str) -> Result<()> {
+    let path = commands::process::get_config_path();
+    let config = core::ConfigManager::load_from_file(&path)?;
+    let process = config
+        .processes
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| SentinelError::ProcessNotFound {
+            name: name.to_string(),
+        })?;
+    state.process_manager.start(process).await.map(|_| ())
+}
+
+/// Starts, moves or stops the API server to match the settings.
+async fn update_api_server(state: &AppState) {
+    let settings = state.process_manager.settings().api;
+    let mut server = state.api_server.lock().await;
+    if let Err(e) = server.configure(&settings, &state.api_context()).await {
+        tracing::warn!("{}", e);
+    }
 }
 
 #[cfg(test)]
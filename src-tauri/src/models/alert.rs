@@ -0,0 +1,149 @@
+//! Alert rule and alert data models.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Rule target that refers to the whole machine instead of a process.
+pub const SYSTEM_TARGET: &str = "system";
+
+/// A condition that raises an alert while it holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AlertRule {
+    /// Unique name of the rule.
+    pub name: String,
+    /// Process name, or `"system"` for machine-wide metrics.
+    pub target: String,
+    /// Metric or event to watch.
+    pub metric: AlertMetric,
+    /// Threshold for numeric metrics; ignored for events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// How long the condition must hold before the alert fires, in milliseconds.
+    #[serde(default, rename = "durationMs")]
+    pub duration_ms: u64,
+    /// Severity of the raised alert.
+    #[serde(default)]
+    pub severity: AlertSeverity,
+}
+
+impl AlertRule {
+    /// Whether this rule watches the machine rather than a process.
+    pub fn is_system(&self) -> bool {
+        self.target == SYSTEM_TARGET
+    }
+}
+
+/// Metric or event an alert rule watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// CPU usage above `threshold` percent.
+    Cpu,
+    /// Memory above `threshold` MB for a process, or `threshold` percent used
+    /// for the system.
+    Memory,
+    /// Free space on the fullest disk below `threshold` percent (system only).
+    DiskFree,
+    /// Open file descriptors (handles on Windows) above `threshold` (process only).
+    OpenFiles,
+    /// The process exited unexpectedly and has not been restarted (process only).
+    Crashed,
+    /// The process crashed and used up its restart limit (process only).
+    RestartLimitExceeded,
+}
+
+impl AlertMetric {
+    /// Whether the metric is compared against a threshold.
+    pub fn has_threshold(self) -> bool {
+        matches!(
+            self,
+            Self::Cpu | Self::Memory | Self::DiskFree | Self::OpenFiles
+        )
+    }
+
+    /// Whether the metric applies to the `"system"` target.
+    pub fn applies_to_system(self) -> bool {
+        matches!(self, Self::Cpu | Self::Memory | Self::DiskFree)
+    }
+
+    /// Whether the metric applies to a process target.
+    pub fn applies_to_process(self) -> bool {
+        !matches!(self, Self::DiskFree)
+    }
+}
+
+/// How urgent an alert is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    /// Informational.
+    Info,
+    /// Needs attention soon.
+    #[default]
+    Warning,
+    /// Needs attention now.
+    Critical,
+}
+
+/// An alert raised by a rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    /// Unique alert ID.
+    pub id: String,
+    /// Name of the rule that raised the alert.
+    pub rule: String,
+    /// Process name, or `"system"`.
+    pub target: String,
+    /// Metric or event that triggered the alert.
+    pub metric: AlertMetric,
+    /// Severity copied from the rule.
+    pub severity: AlertSeverity,
+    /// Human readable description.
+    pub message: String,
+    /// Metric value when the alert fired, `None` for events.
+    pub value: Option<f64>,
+    /// Time when the alert fired.
+    pub fired_at: DateTime<Utc>,
+    /// Whether a user has acknowledged the alert.
+    pub acknowledged: bool,
+    /// Time when the condition stopped holding, `None` while active.
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl Alert {
+    /// Whether the alert is still firing.
+    pub fn is_active(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_defaults() {
+        let rule: AlertRule =
+            serde_yaml::from_str("name: api-down\ntarget: api\nmetric: crashed\n").unwrap();
+        assert_eq!(rule.metric, AlertMetric::Crashed);
+        assert_eq!(rule.threshold, None);
+        assert_eq!(rule.duration_ms, 0);
+        assert_eq!(rule.severity, AlertSeverity::Warning);
+        assert!(!rule.is_system());
+    }
+
+    #[test]
+    fn test_metric_targets() {
+        assert!(AlertMetric::DiskFree.applies_to_system());
+        assert!(!AlertMetric::DiskFree.applies_to_process());
+        assert!(!AlertMetric::Crashed.applies_to_system());
+        assert!(!AlertMetric::OpenFiles.applies_to_system());
+        assert!(
+            AlertMetric::OpenFiles.applies_to_process() && AlertMetric::OpenFiles.has_threshold()
+        );
+        assert!(
+            AlertMetric::Memory.applies_to_system() && AlertMetric::Memory.applies_to_process()
+        );
+    }
+}
@@ -1,27 +1,54 @@
 //! Configuration data models.
 
+use crate::models::{AlertRule, ProcessEventKind};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, ObjectValidation, Schema, SchemaObject};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 
 /// Main configuration structure for Sentinel.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     /// List of processes to manage.
+    #[serde(default)]
     pub processes: Vec<ProcessConfig>,
     /// Global settings.
     #[serde(default)]
     pub settings: GlobalSettings,
     /// Global environment variables applied to all processes.
     #[serde(default, rename = "globalEnv")]
+    #[schemars(schema_with = "env_schema")]
     pub global_env: HashMap<String, String>,
+    /// Other config files to merge in (glob patterns relative to this file).
+    ///
+    /// Kept after loading so a merged config is never saved back over its root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Alert rules evaluated by the supervisor.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<AlertRule>,
+    /// Named partial process configs that processes can `extends`.
+    ///
+    /// Kept as written; processes are loaded with them already merged in.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[schemars(schema_with = "defaults_schema")]
+    pub defaults: BTreeMap<String, serde_yaml::Value>,
 }
 
 /// Configuration for a single process.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessConfig {
     /// Unique name for the process.
     pub name: String,
+    /// Defaults entry or process to inherit settings from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     /// Command to execute.
     pub command: String,
     /// Command arguments (optional).
@@ -32,10 +59,15 @@ pub struct ProcessConfig {
     pub cwd: Option<PathBuf>,
     /// Environment variables.
     #[serde(default)]
+    #[schemars(schema_with = "env_schema")]
     pub env: HashMap<String, String>,
-    /// Whether to automatically restart on crash.
-    #[serde(default = "default_auto_restart", rename = "autoRestart")]
-    pub auto_restart: bool,
+    /// Whether to automatically restart on crash (defaults to `settings.defaultAutoRestart`).
+    #[serde(
+        default,
+        rename = "autoRestart",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auto_restart: Option<bool>,
     /// Maximum number of restart attempts (0 = unlimited).
     #[serde(
         default = "default_restart_limit",
@@ -43,52 +75,790 @@ pub struct ProcessConfig {
         alias = "max_restarts"
     )]
     pub restart_limit: u32,
-    /// Delay between restarts in milliseconds.
+    /// Delay between restarts in milliseconds (defaults to `settings.defaultRestartDelayMs`).
     #[serde(
-        default = "default_restart_delay",
+        default,
         rename = "restartDelay",
-        alias = "restart_delay_ms"
+        alias = "restart_delay_ms",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub restart_delay: Option<u64>,
+    /// Exit codes besides 0 that mean the process finished its work, so it
+    /// is `completed` rather than crashed and isn't restarted.
+    #[serde(
+        default,
+        rename = "successExitCodes",
+        skip_serializing_if = "Vec::is_empty"
     )]
-    pub restart_delay: u64,
+    pub success_exit_codes: Vec<i32>,
     /// List of process names this process depends on.
     #[serde(default, rename = "dependsOn")]
     pub depends_on: Vec<String>,
+    /// Whether the processes depending on this one wait for it to complete,
+    /// as with a migration, rather than for it to run.
+    #[serde(
+        default,
+        rename = "waitForCompletion",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub wait_for_completion: bool,
+    /// Profiles this process belongs to (empty = always started).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
+    /// Tags for starting, stopping and filtering processes as a group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Log lines to keep in memory (defaults to `settings.logBufferLines`).
+    #[serde(
+        default,
+        rename = "logBufferLines",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub log_buffer_lines: Option<usize>,
+    /// Bytes of log text to keep in memory (defaults to `settings.logBufferBytes`).
+    #[serde(
+        default,
+        rename = "logBufferBytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub log_buffer_bytes: Option<u64>,
+    /// Scheduling priority, from -20 (highest) to 19 (lowest).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    /// CPU cores (0-based) the process may run on.
+    #[serde(
+        default,
+        rename = "cpuAffinity",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// What the process's standard input is connected to.
+    #[serde(
+        default,
+        rename = "stdinMode",
+        skip_serializing_if = "StdinMode::is_null"
+    )]
+    pub stdin_mode: StdinMode,
+    /// Encoding of the process's output (detected by default).
+    #[serde(
+        default,
+        rename = "outputEncoding",
+        skip_serializing_if = "OutputEncoding::is_auto"
+    )]
+    pub output_encoding: OutputEncoding,
+    /// How lines of output are parsed; JSON lines get a level and fields.
+    #[serde(
+        default,
+        rename = "logFormat",
+        skip_serializing_if = "LogFormat::is_plain"
+    )]
+    pub log_format: LogFormat,
+    /// Environment the process starts from, before `env` is added.
+    #[serde(
+        default,
+        rename = "envMode",
+        skip_serializing_if = "EnvMode::is_inherit"
+    )]
+    pub env_mode: EnvMode,
+    /// File of `KEY=value` lines loaded into the environment before `env`,
+    /// relative to `cwd`.
+    #[serde(default, rename = "envFile", skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<PathBuf>,
+    /// What happens when the env file, or the `.env` in `cwd`, changes while
+    /// the process runs.
+    #[serde(
+        default,
+        rename = "restartOnEnvChange",
+        skip_serializing_if = "EnvChangeAction::is_prompt"
+    )]
+    pub restart_on_env_change: EnvChangeAction,
     /// Health check configuration (optional).
     #[serde(skip_serializing_if = "Option::is_none", rename = "healthCheck")]
     pub health_check: Option<HealthCheck>,
+    /// Check that the process is ready to serve, which dependents wait for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readiness: Option<ReadinessProbe>,
+    /// Delay in milliseconds before starting, once its dependencies have started.
+    #[serde(
+        default,
+        rename = "startDelayMs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_delay_ms: Option<u64>,
+    /// Cron expression; the process is started at these times rather than
+    /// with the others.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Stops the process once it has run this many milliseconds.
+    #[serde(
+        default,
+        rename = "maxRuntimeMs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_runtime_ms: Option<u64>,
+    /// When the running process is restarted even though it hasn't crashed.
+    #[serde(
+        default,
+        rename = "restartPolicy",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub restart_policy: Option<RestartPolicy>,
+    /// Docker container whose resource usage counts as this process's: a
+    /// container name, or `key=value` to match a label. Without it, the
+    /// container is found from a `docker run --name` or `docker compose`
+    /// command.
+    #[serde(
+        default,
+        rename = "containerRef",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub container_ref: Option<String>,
+    /// Restarts start the new instance first and stop the old one only once
+    /// the new one is ready.
+    #[serde(
+        default,
+        rename = "warmRestart",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub warm_restart: bool,
+    /// Warm restarts move the new instance to a free port, which it keeps,
+    /// rather than starting it on the same port, for programs that can't
+    /// bind it with `SO_REUSEPORT`.
+    #[serde(
+        default,
+        rename = "warmRestartFreePort",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub warm_restart_free_port: bool,
+    /// Kills the children a crashed process leaves running, instead of
+    /// only reporting them.
+    #[serde(
+        default,
+        rename = "reapOrphans",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub reap_orphans: bool,
+    /// Added by `sentinel run --detach` rather than written by hand.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub transient: bool,
 }
 
-/// Health check configuration for a process.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HealthCheck {
-    /// Command to execute for health check.
-    pub command: String,
-    /// Arguments for the health check command.
+impl ProcessConfig {
+    /// Hash of the whole configuration, for detecting changes without storing it.
+    pub fn config_hash(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_string(self)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Whether to restart on crash, falling back to the global default.
+    pub fn effective_auto_restart(&self, settings: &GlobalSettings) -> bool {
+        self.auto_restart.unwrap_or(settings.default_auto_restart)
+    }
+
+    /// Whether exiting with `exit_code` means the process finished its work.
+    pub fn is_success_exit(&self, exit_code: i32) -> bool {
+        exit_code == 0 || self.success_exit_codes.contains(&exit_code)
+    }
+
+    /// Restart delay in milliseconds, falling back to the global default.
+    pub fn effective_restart_delay(&self, settings: &GlobalSettings) -> u64 {
+        self.restart_delay
+            .unwrap_or(settings.default_restart_delay_ms)
+    }
+
+    /// Log buffer size in lines, falling back to the global default.
+    pub fn effective_log_buffer_lines(&self, settings: &GlobalSettings) -> usize {
+        self.log_buffer_lines.unwrap_or(settings.log_buffer_lines)
+    }
+
+    /// Log buffer size in bytes, falling back to the global default.
+    /// `None` when the limit is 0, i.e. turned off.
+    pub fn effective_log_buffer_bytes(&self, settings: &GlobalSettings) -> Option<usize> {
+        let bytes = self.log_buffer_bytes.unwrap_or(settings.log_buffer_bytes);
+        (bytes > 0).then(|| usize::try_from(bytes).unwrap_or(usize::MAX))
+    }
+}
+
+/// A process without name or command, with every other setting as if left
+/// out of the config file.
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            extends: None,
+            command: String::new(),
+            args: Vec::new(),
+            cwd: None,
+            env: HashMap::new(),
+            auto_restart: None,
+            restart_limit: default_restart_limit(),
+            restart_delay: None,
+            success_exit_codes: Vec::new(),
+            depends_on: Vec::new(),
+            wait_for_completion: false,
+            profiles: Vec::new(),
+            tags: Vec::new(),
+            log_buffer_lines: None,
+            log_buffer_bytes: None,
+            nice: None,
+            cpu_affinity: None,
+            stdin_mode: StdinMode::default(),
+            output_encoding: OutputEncoding::default(),
+            log_format: LogFormat::default(),
+            env_mode: EnvMode::default(),
+            env_file: None,
+            restart_on_env_change: EnvChangeAction::default(),
+            health_check: None,
+            readiness: None,
+            start_delay_ms: None,
+            schedule: None,
+            max_runtime_ms: None,
+            restart_policy: None,
+            container_ref: None,
+            warm_restart: false,
+            warm_restart_free_port: false,
+            reap_orphans: false,
+            transient: false,
+        }
+    }
+}
+
+/// Schema of environment variables. Any YAML scalar is read as text, so
+/// values like `PORT: 3000` don't need quotes.
+fn env_schema(_: &mut SchemaGenerator) -> Schema {
+    let value = SchemaObject {
+        instance_type: Some(
+            vec![
+                InstanceType::String,
+                InstanceType::Number,
+                InstanceType::Boolean,
+            ]
+            .into(),
+        ),
+        ..Default::default()
+    };
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(ObjectValidation {
+            additional_properties: Some(Box::new(value.into())),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Schema of `defaults`: process configs in which every key is optional.
+fn defaults_schema(_: &mut SchemaGenerator) -> Schema {
+    let entry = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    };
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(ObjectValidation {
+            additional_properties: Some(Box::new(entry.into())),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Changes made to a copy of a process configuration, e.g. to run another
+/// instance of it on a different port.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessOverrides {
+    /// Environment variables merged into the source's.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Replace the source's environment variables with `env` instead of merging.
+    #[serde(default)]
+    pub replace_env: bool,
+    /// Port for the copy, set as its `PORT` environment variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Arguments appended to the source's.
     #[serde(default)]
     pub args: Vec<String>,
-    /// Interval between health checks in milliseconds.
-    #[serde(rename = "intervalMs")]
-    pub interval_ms: u64,
-    /// Timeout for health check command in milliseconds.
-    #[serde(rename = "timeoutMs")]
+    /// Replace the source's arguments with `args` instead of appending.
+    #[serde(default)]
+    pub replace_args: bool,
+    /// Working directory for the copy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+}
+
+impl ProcessOverrides {
+    /// Copies `source` as `name` with these overrides applied.
+    pub fn apply(&self, source: &ProcessConfig, name: &str) -> ProcessConfig {
+        let mut copy = source.clone();
+        copy.name = name.to_string();
+
+        if self.replace_env {
+            copy.env = self.env.clone();
+        } else {
+            copy.env.extend(self.env.clone());
+        }
+        if let Some(port) = self.port {
+            copy.env.insert("PORT".to_string(), port.to_string());
+        }
+
+        if self.replace_args || !self.args.is_empty() {
+            // Arguments given inline in the command stop being split off once
+            // there are separate ones, so split them off here
+            if copy.args.is_empty() {
+                let mut words = copy.command.split_whitespace().map(str::to_string);
+                let program = words.next().unwrap_or_default();
+                copy.args = words.collect();
+                copy.command = program;
+            }
+            if self.replace_args {
+                copy.args = self.args.clone();
+            } else {
+                copy.args.extend(self.args.iter().cloned());
+            }
+        }
+
+        if let Some(cwd) = &self.cwd {
+            copy.cwd = Some(cwd.clone());
+        }
+
+        copy
+    }
+}
+
+/// What a process's standard input is connected to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StdinMode {
+    /// Reads see end of file immediately.
+    #[default]
+    Null,
+    /// Input can be written while the process runs.
+    Piped,
+}
+
+impl StdinMode {
+    fn is_null(&self) -> bool {
+        *self == StdinMode::Null
+    }
+}
+
+/// Text encoding of a process's output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputEncoding {
+    /// UTF-16 when the output starts with a byte order mark or looks like
+    /// it, otherwise UTF-8.
+    #[default]
+    Auto,
+    /// UTF-8, with invalid bytes shown as U+FFFD.
+    #[serde(alias = "utf-8")]
+    Utf8,
+    /// ISO 8859-1.
+    #[serde(alias = "iso-8859-1")]
+    Latin1,
+    /// UTF-16, little-endian, as written by many Windows tools.
+    #[serde(alias = "utf-16le")]
+    Utf16Le,
+    /// UTF-16, big-endian.
+    #[serde(alias = "utf-16be")]
+    Utf16Be,
+}
+
+impl OutputEncoding {
+    fn is_auto(&self) -> bool {
+        *self == OutputEncoding::Auto
+    }
+}
+
+/// How a process's output lines are parsed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Lines are plain text.
+    #[default]
+    Plain,
+    /// Each line is a JSON object, as written by pino, zap or bunyan. Lines
+    /// that aren't are kept as plain text.
+    Json {
+        /// Field holding the message.
+        #[serde(default = "default_message_key", rename = "messageKey")]
+        message_key: String,
+        /// Field holding the level, as a name or a pino/bunyan number.
+        #[serde(default = "default_level_key", rename = "levelKey")]
+        level_key: String,
+        /// Field holding the time, as an RFC 3339 string or epoch seconds
+        /// or milliseconds.
+        #[serde(default = "default_timestamp_key", rename = "timestampKey")]
+        timestamp_key: String,
+    },
+}
+
+impl LogFormat {
+    fn is_plain(&self) -> bool {
+        *self == LogFormat::Plain
+    }
+}
+
+/// Environment a process starts from.
+///
+/// Whatever the mode, variables that change how programs are loaded, such
+/// as `LD_PRELOAD`, are only passed on when set in the process's `env`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvMode {
+    /// Sentinel's own environment.
+    #[default]
+    Inherit,
+    /// Only `PATH`, `HOME`, `USER`, `LANG` and `TERM` from Sentinel's
+    /// environment.
+    Clean,
+    /// The environment of the user's login shell, as a terminal would have
+    /// it; useful when Sentinel was started from the dock with a minimal
+    /// `PATH`.
+    Shell,
+}
+
+impl EnvMode {
+    fn is_inherit(&self) -> bool {
+        *self == EnvMode::Inherit
+    }
+}
+
+/// What happens when the env file of a running process changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvChangeAction {
+    /// The app is told, and offers a restart.
+    #[default]
+    Prompt,
+    /// The process is restarted.
+    Auto,
+    /// Nothing; the file isn't watched.
+    Ignore,
+}
+
+impl EnvChangeAction {
+    fn is_prompt(&self) -> bool {
+        *self == EnvChangeAction::Prompt
+    }
+}
+
+fn default_message_key() -> String {
+    "msg".to_string()
+}
+
+fn default_level_key() -> String {
+    "level".to_string()
+}
+
+fn default_timestamp_key() -> String {
+    "time".to_string()
+}
+
+/// Health check configuration for a process.
+///
+/// Without a `type` the check is a command, as in configs written before
+/// HTTP and TCP checks existed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawHealthCheck", into = "RawHealthCheck")]
+pub struct HealthCheck {
+    /// What is checked.
+    pub check: HealthCheckKind,
+    /// Interval between health checks in milliseconds (defaults to `settings.healthCheckIntervalMs`).
+    pub interval_ms: Option<u64>,
+    /// Timeout for a single health check in milliseconds.
     pub timeout_ms: u64,
     /// Number of retries before marking as unhealthy.
     pub retries: u32,
 }
 
+/// Kind of health check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthCheckKind {
+    /// `command` exits with status 0.
+    Command { command: String, args: Vec<String> },
+    /// An HTTP request to `url` returns `expected_status`, or any 2xx status
+    /// without one.
+    Http {
+        url: String,
+        method: String,
+        expected_status: Option<u16>,
+    },
+    /// A TCP connection to `host:port` succeeds.
+    Tcp { host: String, port: u16 },
+}
+
+/// [`HealthCheck`] as written in config files, with the kind's fields inline.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    rename = "HealthCheck",
+    description = "Check that a running process is healthy. Without a `type` the check is a command."
+)]
+struct RawHealthCheck {
+    /// What is checked: `command` (the default), `http` or `tcp`.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    #[schemars(schema_with = "health_check_type_schema")]
+    kind: Option<String>,
+    /// Command that must exit with status 0 (command checks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    /// Arguments of `command`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+    /// URL to request (HTTP checks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    /// HTTP method of the request (defaults to GET).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    method: Option<String>,
+    /// Status the response must have (any 2xx status without one).
+    #[serde(
+        default,
+        rename = "expectedStatus",
+        skip_serializing_if = "Option::is_none"
+    )]
+    expected_status: Option<u16>,
+    /// Host to connect to (TCP checks, defaults to 127.0.0.1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    /// Port to connect to (TCP checks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    /// Interval between health checks in milliseconds (defaults to `settings.healthCheckIntervalMs`).
+    #[serde(
+        default,
+        rename = "intervalMs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    interval_ms: Option<u64>,
+    /// Timeout for a single health check in milliseconds.
+    #[serde(rename = "timeoutMs")]
+    timeout_ms: u64,
+    /// Number of retries before marking as unhealthy.
+    retries: u32,
+}
+
+/// Schema of a health check's `type`, which is kept as a string so the
+/// error for an unknown one can list the known ones.
+fn health_check_type_schema(_: &mut SchemaGenerator) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(vec!["command".into(), "http".into(), "tcp".into()]),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Health checks are written in their [`RawHealthCheck`] form.
+impl JsonSchema for HealthCheck {
+    fn schema_name() -> String {
+        RawHealthCheck::schema_name()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        RawHealthCheck::json_schema(generator)
+    }
+}
+
+impl TryFrom<RawHealthCheck> for HealthCheck {
+    type Error = String;
+
+    fn try_from(raw: RawHealthCheck) -> Result<Self, Self::Error> {
+        let kind = raw.kind.as_deref().unwrap_or("command");
+        let missing =
+            |field: &str| format!("missing field `{}` for health check type '{}'", field, kind);
+
+        let check = match kind {
+            "command" => HealthCheckKind::Command {
+                command: raw.command.ok_or_else(|| missing("command"))?,
+                args: raw.args,
+            },
+            "http" => HealthCheckKind::Http {
+                url: raw.url.ok_or_else(|| missing("url"))?,
+                method: raw.method.unwrap_or_else(default_http_method),
+                expected_status: raw.expected_status,
+            },
+            "tcp" => HealthCheckKind::Tcp {
+                host: raw.host.unwrap_or_else(default_probe_host),
+                port: raw.port.ok_or_else(|| missing("port"))?,
+            },
+            other => {
+                return Err(format!(
+                    "unknown health check type '{}', expected command, http or tcp",
+                    other
+                ))
+            }
+        };
+
+        Ok(Self {
+            check,
+            interval_ms: raw.interval_ms,
+            timeout_ms: raw.timeout_ms,
+            retries: raw.retries,
+        })
+    }
+}
+
+impl From<HealthCheck> for RawHealthCheck {
+    fn from(health_check: HealthCheck) -> Self {
+        let mut raw = RawHealthCheck {
+            kind: None,
+            command: None,
+            args: vec![],
+            url: None,
+            method: None,
+            expected_status: None,
+            host: None,
+            port: None,
+            interval_ms: health_check.interval_ms,
+            timeout_ms: health_check.timeout_ms,
+            retries: health_check.retries,
+        };
+
+        // Command checks keep the untyped form
+        match health_check.check {
+            HealthCheckKind::Command { command, args } => {
+                raw.command = Some(command);
+                raw.args = args;
+            }
+            HealthCheckKind::Http {
+                url,
+                method,
+                expected_status,
+            } => {
+                raw.kind = Some("http".to_string());
+                raw.url = Some(url);
+                raw.method = Some(method);
+                raw.expected_status = expected_status;
+            }
+            HealthCheckKind::Tcp { host, port } => {
+                raw.kind = Some("tcp".to_string());
+                raw.host = Some(host);
+                raw.port = Some(port);
+            }
+        }
+
+        raw
+    }
+}
+
+impl HealthCheck {
+    /// Check interval in milliseconds, falling back to the global default.
+    pub fn effective_interval_ms(&self, settings: &GlobalSettings) -> u64 {
+        self.interval_ms
+            .unwrap_or(settings.health_check_interval_ms)
+    }
+}
+
+/// Restarts a running process before it causes trouble, e.g. one that leaks
+/// memory. Each setting that is present can trigger a restart.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartPolicy {
+    /// Restart once the process uses more memory than this, in bytes.
+    ///
+    /// Separate from alert rules, which only notify.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_bytes: Option<u64>,
+    /// Restart once the process has run this many hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uptime_hours: Option<u64>,
+    /// Cron expression; restart at these times.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+}
+
+impl RestartPolicy {
+    /// Whether no setting is present, so the policy never triggers.
+    pub fn is_empty(&self) -> bool {
+        self.max_memory_bytes.is_none() && self.max_uptime_hours.is_none() && self.cron.is_none()
+    }
+}
+
+/// Readiness probe for a process: unlike a health check, it only decides
+/// when a newly started process can be relied on.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReadinessProbe {
+    /// What is checked.
+    #[serde(flatten)]
+    pub check: ReadinessCheck,
+    /// Time between attempts in milliseconds.
+    #[serde(default = "default_readiness_interval", rename = "intervalMs")]
+    pub interval_ms: u64,
+    /// Timeout of a single attempt in milliseconds.
+    #[serde(default = "default_readiness_timeout", rename = "timeoutMs")]
+    pub timeout_ms: u64,
+    /// Time after starting to give up on the process becoming ready, in milliseconds.
+    #[serde(default = "default_readiness_max_wait", rename = "maxWaitMs")]
+    pub max_wait_ms: u64,
+}
+
+/// Kind of readiness probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReadinessCheck {
+    /// A TCP connection to `host:port` succeeds.
+    Tcp {
+        /// Host to connect to.
+        #[serde(default = "default_probe_host")]
+        host: String,
+        /// Port to connect to.
+        port: u16,
+    },
+    /// An HTTP GET of `url` returns `expectedStatus`.
+    Http {
+        /// URL to request.
+        url: String,
+        /// Status the response must have.
+        #[serde(default = "default_expected_status", rename = "expectedStatus")]
+        expected_status: u16,
+    },
+    /// `command` exits with status 0.
+    Command {
+        /// Command to run.
+        command: String,
+        /// Arguments of `command`.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// A line of the process's output matches the regular expression `pattern`.
+    Log {
+        /// Regular expression a line must match.
+        pattern: String,
+    },
+}
+
 /// Global application settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GlobalSettings {
     /// Log level (trace, debug, info, warn, error).
     #[serde(default = "default_log_level", rename = "logLevel")]
     pub log_level: String,
+    /// Locale of error messages and CLI output, such as `de` or `pt_BR`.
+    /// `SENTINEL_LOCALE` overrides it; English if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
     /// Directory for storing logs.
     #[serde(skip_serializing_if = "Option::is_none", rename = "logDirectory")]
     pub log_directory: Option<PathBuf>,
+    /// Write the output of each process to `<name>.log` in `logDirectory`.
+    #[serde(
+        default,
+        rename = "persistLogs",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub persist_logs: bool,
     /// Maximum log file size in bytes.
     #[serde(default = "default_max_log_size", rename = "maxLogSize")]
     pub max_log_size: u64,
-    /// Maximum number of log files to keep.
+    /// Maximum number of rotated log files to keep per process.
     #[serde(default = "default_max_log_files", rename = "maxLogFiles")]
     pub max_log_files: u32,
     /// Graceful shutdown timeout in milliseconds.
@@ -97,20 +867,381 @@ pub struct GlobalSettings {
         rename = "gracefulShutdownTimeout"
     )]
     pub graceful_shutdown_timeout: u64,
+    /// Profiles to start when none are requested (empty = all processes).
+    #[serde(
+        default,
+        rename = "defaultProfiles",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub default_profiles: Vec<String>,
+    /// Whether processes restart on crash unless they set `autoRestart`.
+    #[serde(default = "default_auto_restart", rename = "defaultAutoRestart")]
+    pub default_auto_restart: bool,
+    /// Restart delay in milliseconds for processes that don't set `restartDelay`.
+    #[serde(default = "default_restart_delay", rename = "defaultRestartDelayMs")]
+    pub default_restart_delay_ms: u64,
+    /// Log lines kept in memory per process unless it sets `logBufferLines`.
+    #[serde(default = "default_log_buffer_lines", rename = "logBufferLines")]
+    pub log_buffer_lines: usize,
+    /// Bytes of log text kept in memory per process unless it sets
+    /// `logBufferBytes`; 0 turns the limit off.
+    #[serde(default = "default_log_buffer_bytes", rename = "logBufferBytes")]
+    pub log_buffer_bytes: u64,
+    /// Longest log line in bytes; longer ones are cut and continued on the
+    /// next line. 0 turns the limit off.
+    #[serde(default = "default_max_log_line_bytes", rename = "maxLogLineBytes")]
+    pub max_log_line_bytes: u64,
+    /// How often crashed processes are detected and restarted, in milliseconds.
+    #[serde(
+        default = "default_health_check_interval",
+        rename = "healthCheckIntervalMs"
+    )]
+    pub health_check_interval_ms: u64,
+    /// How long `start` waits for a new process to fail before reporting it
+    /// started, in milliseconds. 0 turns the wait off.
+    #[serde(default = "default_start_grace", rename = "startGraceMs")]
+    pub start_grace_ms: u64,
+    /// How long a detected service is cached for a port before it is
+    /// detected again, in seconds.
+    #[serde(default = "default_service_cache_ttl", rename = "serviceCacheTtlSecs")]
+    pub service_cache_ttl_secs: u64,
+    /// Env var names containing any of these (case-insensitive) are masked outside the backend.
+    #[serde(default = "default_secret_patterns", rename = "secretPatterns")]
+    pub secret_patterns: Vec<String>,
+    /// Commands that may not be run as a process unless privileged commands are allowed.
+    ///
+    /// Matching is best effort, to catch mistakes rather than to sandbox processes.
+    #[serde(default = "default_blocked_commands", rename = "blockedCommands")]
+    pub blocked_commands: Vec<String>,
+    /// Allow processes to run commands from `blockedCommands`.
+    #[serde(default, rename = "allowPrivilegedCommands")]
+    pub allow_privileged_commands: bool,
+    /// Allow port forwards to listen on ports below 1024.
+    #[serde(default, rename = "allowPrivilegedForwards")]
+    pub allow_privileged_forwards: bool,
+    /// Directories process working directories and tailed log files must be under (empty = anywhere).
+    #[serde(
+        default,
+        rename = "allowedRoots",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_roots: Vec<PathBuf>,
+    /// Add the CPU and memory usage of a process's descendants to its tree totals.
+    #[serde(default, rename = "aggregateChildUsage")]
+    pub aggregate_child_usage: bool,
+    /// Endpoints notified of process lifecycle events.
+    #[serde(
+        default,
+        rename = "notificationSinks",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub notification_sinks: Vec<NotificationSink>,
+    /// Address to serve Prometheus metrics on at `/metrics` (disabled when unset).
+    #[serde(
+        default,
+        rename = "metricsAddress",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub metrics_address: Option<SocketAddr>,
+    /// Which network interfaces the network monitor counts.
+    #[serde(default, skip_serializing_if = "NetworkSettings::is_default")]
+    pub network: NetworkSettings,
+    /// HTTP API for controlling Sentinel without the app window.
+    #[serde(default, skip_serializing_if = "ApiSettings::is_default")]
+    pub api: ApiSettings,
+    /// What happens to running processes when Sentinel quits.
+    #[serde(default, rename = "onExit")]
+    pub on_exit: OnExit,
+    /// Cache directories `clean_process_cache` may remove, by framework (e.g. `nextJs`),
+    /// replacing that framework's defaults.
+    #[serde(
+        default,
+        rename = "cacheDirs",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub cache_dirs: HashMap<String, Vec<String>>,
+    /// How long rotated process log files in `logDirectory` are kept.
+    #[serde(
+        default,
+        rename = "logRetention",
+        skip_serializing_if = "LogRetention::is_default"
+    )]
+    pub log_retention: LogRetention,
+    /// Metrics kept on disk for looking back further than the in-memory
+    /// history.
+    #[serde(
+        default,
+        rename = "metricsHistory",
+        skip_serializing_if = "MetricsHistorySettings::is_default"
+    )]
+    pub metrics_history: MetricsHistorySettings,
+}
+
+/// What happens to running processes when Sentinel quits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum OnExit {
+    /// Stop every process, within `gracefulShutdownTimeout` in total.
+    #[default]
+    StopAll,
+    /// Leave processes running and record them in the runtime state, so the
+    /// next launch doesn't start them twice. Their output is no longer read,
+    /// so a process that fails on a closed stdout or stderr exits when it
+    /// next writes. PTY processes lose their terminal and are stopped anyway.
+    DetachAndPersist,
+}
+
+/// Network interfaces counted by the network monitor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// Globs of interface names left out, e.g. `docker*` or `veth*`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_interfaces: Vec<String>,
+    /// Count loopback interfaces too.
+    #[serde(default)]
+    pub include_loopback: bool,
+    /// MaxMind database (`.mmdb`) to look up the country and AS of remote
+    /// addresses in, such as GeoLite2-City or GeoLite2-ASN.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geoip_database: Option<PathBuf>,
+}
+
+impl NetworkSettings {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Limits on the rotated process log files kept on disk. Without any, they
+/// are kept forever.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRetention {
+    /// Total size of all log files in bytes; the oldest rotated files are
+    /// deleted beyond it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_log_bytes: Option<u64>,
+    /// Days after which rotated log files are deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    /// Limits for the log files of single processes, by process name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub processes: HashMap<String, LogRetentionLimits>,
+}
+
+impl LogRetention {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Log retention limits for one process.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRetentionLimits {
+    /// Total size of the process's log files in bytes, on top of the
+    /// global limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_log_bytes: Option<u64>,
+    /// Days after which the process's rotated log files are deleted,
+    /// replacing the global `maxAgeDays`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+}
+
+/// System and per-process metrics recorded to a SQLite database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsHistorySettings {
+    /// Record metrics.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between recorded samples.
+    #[serde(default = "default_metrics_history_interval")]
+    pub interval_secs: u64,
+    /// Days after which samples are deleted.
+    #[serde(default = "default_metrics_history_retention")]
+    pub retention_days: u32,
+    /// Database file, `metrics.db` in the state directory by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl MetricsHistorySettings {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for MetricsHistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_metrics_history_interval(),
+            retention_days: default_metrics_history_retention(),
+            path: None,
+        }
+    }
+}
+
+/// The HTTP API served for headless use.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSettings {
+    /// Serve the API.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on; only this machine can connect unless changed.
+    #[serde(default = "default_api_bind")]
+    pub bind: IpAddr,
+    /// Port to listen on.
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// Bearer token every request must carry. The API isn't served without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl ApiSettings {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Address to listen on.
+    pub fn address(&self) -> SocketAddr {
+        SocketAddr::new(self.bind, self.port)
+    }
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_api_bind(),
+            port: default_api_port(),
+            token: None,
+        }
+    }
+}
+
+/// An endpoint notified of process lifecycle events.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationSink {
+    /// Unique name of the sink.
+    pub name: String,
+    /// How events are delivered.
+    #[serde(rename = "type")]
+    pub kind: NotificationSinkKind,
+    /// URL events are posted to.
+    pub url: String,
+    /// Extra HTTP headers sent with each request.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Events to send (empty = all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<ProcessEventKind>,
+    /// Processes to send events for (empty = all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub processes: Vec<String>,
+}
+
+impl NotificationSink {
+    /// Whether an event of `kind` for `process` should be sent to this sink.
+    pub fn accepts(&self, kind: ProcessEventKind, process: &str) -> bool {
+        (self.events.is_empty() || self.events.contains(&kind))
+            && (self.processes.is_empty() || self.processes.iter().any(|p| p == process))
+    }
+}
+
+/// How a notification sink delivers events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSinkKind {
+    /// JSON `POST` to an HTTP(S) endpoint, such as a Slack incoming webhook.
+    Webhook,
 }
 
 impl Default for GlobalSettings {
     fn default() -> Self {
         Self {
             log_level: default_log_level(),
+            locale: None,
             log_directory: None,
+            persist_logs: false,
             max_log_size: default_max_log_size(),
             max_log_files: default_max_log_files(),
             graceful_shutdown_timeout: default_graceful_shutdown_timeout(),
+            default_profiles: Vec::new(),
+            default_auto_restart: default_auto_restart(),
+            default_restart_delay_ms: default_restart_delay(),
+            log_buffer_lines: default_log_buffer_lines(),
+            log_buffer_bytes: default_log_buffer_bytes(),
+            max_log_line_bytes: default_max_log_line_bytes(),
+            health_check_interval_ms: default_health_check_interval(),
+            start_grace_ms: default_start_grace(),
+            service_cache_ttl_secs: default_service_cache_ttl(),
+            secret_patterns: default_secret_patterns(),
+            blocked_commands: default_blocked_commands(),
+            allow_privileged_commands: false,
+            allow_privileged_forwards: false,
+            allowed_roots: Vec::new(),
+            aggregate_child_usage: false,
+            notification_sinks: Vec::new(),
+            metrics_address: None,
+            network: NetworkSettings::default(),
+            api: ApiSettings::default(),
+            on_exit: OnExit::default(),
+            cache_dirs: HashMap::new(),
+            log_retention: LogRetention::default(),
+            metrics_history: MetricsHistorySettings::default(),
+        }
+    }
+}
+
+/// A problem found while validating a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    /// Description of the problem.
+    pub message: String,
+    /// File the problem is in, when it is not the file being validated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Line of the problem (1-based), if known.
+    pub line: Option<usize>,
+    /// Column of the problem (1-based), if known.
+    pub column: Option<usize>,
+    /// Source lines around the problem.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+impl ConfigIssue {
+    /// Creates an issue without a source location.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            path: None,
+            line: None,
+            column: None,
+            snippet: None,
         }
     }
 }
 
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, "line {}, column {}: ", line, column)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
 // Default value functions
 fn default_auto_restart() -> bool {
     true
@@ -140,6 +1271,182 @@ fn default_graceful_shutdown_timeout() -> u64 {
     30_000 // 30 seconds
 }
 
+fn default_log_buffer_lines() -> usize {
+    10_000
+}
+
+fn default_log_buffer_bytes() -> u64 {
+    crate::core::log_buffer::DEFAULT_MAX_BYTES as u64
+}
+
+fn default_max_log_line_bytes() -> u64 {
+    crate::core::log_decoder::DEFAULT_MAX_LINE_BYTES as u64
+}
+
+fn default_health_check_interval() -> u64 {
+    5_000 // 5 seconds
+}
+
+fn default_start_grace() -> u64 {
+    750
+}
+
+fn default_service_cache_ttl() -> u64 {
+    crate::features::service_detection::DEFAULT_CACHE_TTL.as_secs()
+}
+
+fn default_api_bind() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+fn default_api_port() -> u16 {
+    9740
+}
+
+fn default_metrics_history_interval() -> u64 {
+    10
+}
+
+fn default_metrics_history_retention() -> u32 {
+    7
+}
+
+fn default_readiness_interval() -> u64 {
+    500
+}
+
+fn default_readiness_timeout() -> u64 {
+    1_000 // 1 second
+}
+
+fn default_readiness_max_wait() -> u64 {
+    30_000 // 30 seconds
+}
+
+fn default_probe_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_http_method() -> String {
+    "GET".to_string()
+}
+
+fn default_expected_status() -> u16 {
+    200
+}
+
+fn default_secret_patterns() -> Vec<String> {
+    ["PASSWORD", "SECRET", "TOKEN", "KEY", "PRIVATE"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_blocked_commands() -> Vec<String> {
+    ["sudo", "su", "shutdown"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Debug view of environment variables that leaves out the values.
+struct EnvKeys<'a>(&'a HashMap<String, String>);
+
+impl fmt::Debug for EnvKeys<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys: Vec<&String> = self.0.keys().collect();
+        keys.sort();
+        f.debug_set().entries(keys).finish()
+    }
+}
+
+// Hand-written so env values (which may be secrets) never reach logs
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("processes", &self.processes)
+            .field("settings", &self.settings)
+            .field("global_env", &EnvKeys(&self.global_env))
+            .field("include", &self.include)
+            .field("alerts", &self.alerts)
+            .field("defaults", &self.defaults.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+// Webhook URLs and headers usually embed credentials
+impl fmt::Debug for NotificationSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let origin = match self.url.split_once("://") {
+            Some((scheme, rest)) => {
+                format!("{}://{}", scheme, rest.split('/').next().unwrap_or(""))
+            }
+            None => String::new(),
+        };
+        f.debug_struct("NotificationSink")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("url", &origin)
+            .field("headers", &EnvKeys(&self.headers))
+            .field("events", &self.events)
+            .field("processes", &self.processes)
+            .finish()
+    }
+}
+
+impl fmt::Debug for ApiSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiSettings")
+            .field("enabled", &self.enabled)
+            .field("bind", &self.bind)
+            .field("port", &self.port)
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+impl fmt::Debug for ProcessConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProcessConfig")
+            .field("name", &self.name)
+            .field("extends", &self.extends)
+            .field("command", &self.command)
+            .field("args", &self.args)
+            .field("cwd", &self.cwd)
+            .field("env", &EnvKeys(&self.env))
+            .field("auto_restart", &self.auto_restart)
+            .field("restart_limit", &self.restart_limit)
+            .field("restart_delay", &self.restart_delay)
+            .field("success_exit_codes", &self.success_exit_codes)
+            .field("depends_on", &self.depends_on)
+            .field("wait_for_completion", &self.wait_for_completion)
+            .field("profiles", &self.profiles)
+            .field("tags", &self.tags)
+            .field("log_buffer_lines", &self.log_buffer_lines)
+            .field("log_buffer_bytes", &self.log_buffer_bytes)
+            .field("nice", &self.nice)
+            .field("cpu_affinity", &self.cpu_affinity)
+            .field("stdin_mode", &self.stdin_mode)
+            .field("output_encoding", &self.output_encoding)
+            .field("log_format", &self.log_format)
+            .field("env_mode", &self.env_mode)
+            .field("env_file", &self.env_file)
+            .field("restart_on_env_change", &self.restart_on_env_change)
+            .field("health_check", &self.health_check)
+            .field("readiness", &self.readiness)
+            .field("start_delay_ms", &self.start_delay_ms)
+            .field("schedule", &self.schedule)
+            .field("max_runtime_ms", &self.max_runtime_ms)
+            .field("restart_policy", &self.restart_policy)
+            .field("container_ref", &self.container_ref)
+            .field("warm_restart", &self.warm_restart)
+            .field("warm_restart_free_port", &self.warm_restart_free_port)
+            .field("reap_orphans", &self.reap_orphans)
+            .field("transient", &self.transient)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,6 +1477,50 @@ settings:
         assert_eq!(config.settings.log_level, "debug");
     }
 
+    #[test]
+    fn test_restart_policy_deserialization() {
+        let yaml = r#"
+processes:
+  - name: legacy-api
+    command: node server.js
+    restartPolicy:
+      maxMemoryBytes: 1073741824
+      cron: "0 14 * * Mon-Fri"
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.processes[0].restart_policy,
+            Some(RestartPolicy {
+                max_memory_bytes: Some(1_073_741_824),
+                max_uptime_hours: None,
+                cron: Some("0 14 * * Mon-Fri".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_profiles_deserialization() {
+        let yaml = r#"
+processes:
+  - name: web
+    command: npm run dev
+    profiles: [frontend]
+  - name: proxy
+    command: caddy run
+settings:
+  defaultProfiles: [frontend, backend]
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.processes[0].profiles, vec!["frontend"]);
+        assert!(config.processes[1].profiles.is_empty());
+        assert_eq!(
+            config.settings.default_profiles,
+            vec!["frontend", "backend"]
+        );
+    }
+
     #[test]
     fn test_process_config_defaults() {
         let yaml = r#"
@@ -180,10 +1531,177 @@ command: echo hello
         let config: ProcessConfig = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(config.name, "test");
         assert_eq!(config.command, "echo hello");
-        assert!(config.auto_restart); // Default
+        assert_eq!(config.auto_restart, None); // Inherited
         assert_eq!(config.restart_limit, 5); // Default
-        assert_eq!(config.restart_delay, 1000); // Default
+        assert_eq!(config.restart_delay, None); // Inherited
         assert!(config.depends_on.is_empty());
+
+        let default = ProcessConfig {
+            name: "test".to_string(),
+            command: "echo hello".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(default.config_hash(), config.config_hash());
+    }
+
+    #[test]
+    fn test_health_check_without_type_is_a_command() {
+        let yaml = r#"
+command: pg_isready
+args: ["-h", "localhost"]
+intervalMs: 5000
+timeoutMs: 3000
+retries: 3
+"#;
+
+        let health_check: HealthCheck = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            health_check.check,
+            HealthCheckKind::Command {
+                command: "pg_isready".to_string(),
+                args: vec!["-h".to_string(), "localhost".to_string()],
+            }
+        );
+        assert_eq!(health_check.interval_ms, Some(5000));
+
+        // Written back the same way, without a type
+        let written = serde_yaml::to_string(&health_check).unwrap();
+        assert!(!written.contains("type"));
+        assert_eq!(
+            serde_yaml::from_str::<HealthCheck>(&written).unwrap(),
+            health_check
+        );
+    }
+
+    #[test]
+    fn test_typed_health_checks() {
+        let yaml = r#"
+- type: http
+  url: http://localhost:8080/health
+  timeoutMs: 2000
+  retries: 3
+- type: http
+  url: http://localhost:8080/health
+  method: HEAD
+  expectedStatus: 204
+  timeoutMs: 2000
+  retries: 3
+- type: tcp
+  port: 5432
+  timeoutMs: 1000
+  retries: 1
+"#;
+
+        let health_checks: Vec<HealthCheck> = serde_yaml::from_str(yaml).unwrap();
+        let checks: Vec<_> = health_checks.iter().map(|h| h.check.clone()).collect();
+        assert_eq!(
+            checks,
+            [
+                HealthCheckKind::Http {
+                    url: "http://localhost:8080/health".to_string(),
+                    method: "GET".to_string(),
+                    expected_status: None,
+                },
+                HealthCheckKind::Http {
+                    url: "http://localhost:8080/health".to_string(),
+                    method: "HEAD".to_string(),
+                    expected_status: Some(204),
+                },
+                HealthCheckKind::Tcp {
+                    host: "127.0.0.1".to_string(),
+                    port: 5432,
+                },
+            ]
+        );
+
+        let written = serde_json::to_string(&health_checks).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<HealthCheck>>(&written).unwrap(),
+            health_checks
+        );
+    }
+
+    #[test]
+    fn test_invalid_health_checks_do_not_parse() {
+        let error = |yaml: &str| {
+            serde_yaml::from_str::<HealthCheck>(yaml)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error("type: http\ntimeoutMs: 1000\nretries: 1")
+            .contains("missing field `url` for health check type 'http'"));
+        assert!(error("type: tcp\ntimeoutMs: 1000\nretries: 1")
+            .contains("missing field `port` for health check type 'tcp'"));
+        assert!(error("type: grpc\nport: 1\ntimeoutMs: 1000\nretries: 1")
+            .contains("unknown health check type 'grpc'"));
+    }
+
+    fn worker() -> ProcessConfig {
+        serde_yaml::from_str(
+            r#"
+name: worker
+command: node worker.js --queue jobs
+env:
+  PORT: "3000"
+  QUEUE: jobs
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_overrides_merge_env_and_append_args() {
+        let overrides = ProcessOverrides {
+            env: HashMap::from([("QUEUE".to_string(), "emails".to_string())]),
+            port: Some(3001),
+            args: vec!["--verbose".to_string()],
+            ..Default::default()
+        };
+
+        let copy = overrides.apply(&worker(), "worker-2");
+        assert_eq!(copy.name, "worker-2");
+        assert_eq!(
+            copy.env,
+            HashMap::from([
+                ("PORT".to_string(), "3001".to_string()),
+                ("QUEUE".to_string(), "emails".to_string()),
+            ])
+        );
+        // Inline arguments are split off before appending
+        assert_eq!(copy.command, "node");
+        assert_eq!(copy.args, ["worker.js", "--queue", "jobs", "--verbose"]);
+    }
+
+    #[test]
+    fn test_overrides_replace_env_and_args() {
+        let overrides = ProcessOverrides {
+            env: HashMap::from([("QUEUE".to_string(), "emails".to_string())]),
+            replace_env: true,
+            args: vec!["other.js".to_string()],
+            replace_args: true,
+            cwd: Some(PathBuf::from("/srv/worker")),
+            ..Default::default()
+        };
+
+        let copy = overrides.apply(&worker(), "worker-2");
+        assert_eq!(
+            copy.env,
+            HashMap::from([("QUEUE".to_string(), "emails".to_string())])
+        );
+        assert_eq!(copy.command, "node");
+        assert_eq!(copy.args, ["other.js"]);
+        assert_eq!(copy.cwd, Some(PathBuf::from("/srv/worker")));
+    }
+
+    #[test]
+    fn test_empty_overrides_only_rename() {
+        let source = worker();
+        let copy = ProcessOverrides::default().apply(&source, "worker-2");
+        assert_eq!(copy.name, "worker-2");
+        assert_eq!(copy.command, source.command);
+        assert!(copy.args.is_empty());
+        assert_eq!(copy.env, source.env);
     }
 
     #[test]
@@ -193,6 +1711,96 @@ command: echo hello
         assert_eq!(settings.max_log_size, 10 * 1024 * 1024);
         assert_eq!(settings.max_log_files, 5);
         assert_eq!(settings.graceful_shutdown_timeout, 30_000);
+        assert!(settings.default_auto_restart);
+        assert_eq!(settings.default_restart_delay_ms, 1000);
+        assert_eq!(settings.log_buffer_lines, 10_000);
+        assert_eq!(settings.log_buffer_bytes, 32 * 1024 * 1024);
+        assert_eq!(settings.on_exit, OnExit::StopAll);
+
+        let settings: GlobalSettings = serde_yaml::from_str("onExit: detachAndPersist").unwrap();
+        assert_eq!(settings.on_exit, OnExit::DetachAndPersist);
+    }
+
+    #[test]
+    fn test_process_inherits_global_defaults() {
+        let yaml = r#"
+processes:
+  - name: inherits
+    command: npm start
+  - name: overrides
+    command: npm start
+    autoRestart: true
+    restartDelay: 250
+    logBufferLines: 50
+    logBufferBytes: 0
+settings:
+  defaultAutoRestart: false
+  defaultRestartDelayMs: 3000
+  logBufferLines: 500
+  logBufferBytes: 4096
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let (inherits, overrides) = (&config.processes[0], &config.processes[1]);
+
+        assert!(!inherits.effective_auto_restart(&config.settings));
+        assert_eq!(inherits.effective_restart_delay(&config.settings), 3000);
+        assert_eq!(inherits.effective_log_buffer_lines(&config.settings), 500);
+        assert_eq!(
+            inherits.effective_log_buffer_bytes(&config.settings),
+            Some(4096)
+        );
+        assert!(overrides.effective_auto_restart(&config.settings));
+        assert_eq!(overrides.effective_restart_delay(&config.settings), 250);
+        assert_eq!(overrides.effective_log_buffer_lines(&config.settings), 50);
+        assert_eq!(overrides.effective_log_buffer_bytes(&config.settings), None);
+    }
+
+    #[test]
+    fn test_debug_leaves_out_env_values() {
+        let yaml = r#"
+processes:
+  - name: api
+    command: npm start
+    env:
+      DB_PASSWORD: hunter2
+globalEnv:
+  API_TOKEN: abc123
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let debug = format!("{:?}", config);
+        assert!(debug.contains("DB_PASSWORD"));
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("abc123"));
+        assert!(!config.processes[0].config_hash().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_notification_sink_filters() {
+        let yaml = r#"
+notificationSinks:
+  - name: slack
+    type: webhook
+    url: https://hooks.slack.com/services/T000/B000/s3cr3t
+    headers:
+      Authorization: Bearer abc123
+    events: [crashed, restart_limit_exceeded]
+    processes: [worker]
+"#;
+
+        let settings: GlobalSettings = serde_yaml::from_str(yaml).unwrap();
+        let sink = &settings.notification_sinks[0];
+        assert_eq!(sink.kind, NotificationSinkKind::Webhook);
+        assert!(sink.accepts(ProcessEventKind::Crashed, "worker"));
+        assert!(!sink.accepts(ProcessEventKind::Started, "worker"));
+        assert!(!sink.accepts(ProcessEventKind::Crashed, "api"));
+
+        let debug = format!("{:?}", sink);
+        assert!(debug.contains("https://hooks.slack.com"));
+        assert!(debug.contains("Authorization"));
+        assert!(!debug.contains("s3cr3t"));
+        assert!(!debug.contains("abc123"));
     }
 
     #[test]
@@ -201,17 +1809,16 @@ command: echo hello
             processes: vec![ProcessConfig {
                 name: "test".to_string(),
                 command: "echo test".to_string(),
-                args: vec![],
-                cwd: None,
-                env: HashMap::new(),
-                auto_restart: true,
+                auto_restart: Some(true),
                 restart_limit: 3,
-                restart_delay: 2000,
-                depends_on: vec![],
-                health_check: None,
+                restart_delay: Some(2000),
+                ..Default::default()
             }],
             settings: GlobalSettings::default(),
             global_env: HashMap::new(),
+            include: vec![],
+            alerts: vec![],
+            defaults: Default::default(),
         };
 
         let yaml = serde_yaml::to_string(&config).unwrap();
@@ -0,0 +1,188 @@
+//! Event journal data models.
+
+use super::ProcessEventKind;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Something Sentinel did, as recorded in the event journal.
+///
+/// Each record maps onto an OpenTelemetry log record: `timestamp` is the
+/// record timestamp, `event_type` the event name, and `subject`, `actor` and
+/// `details` its attributes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEvent {
+    /// Time of the event.
+    pub timestamp: DateTime<Utc>,
+    /// What happened.
+    pub event_type: EventType,
+    /// What it happened to: a process name, config path or port.
+    pub subject: String,
+    /// Extra context, such as an exit code or error message.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+    /// Who caused it.
+    pub actor: EventActor,
+}
+
+impl JournalEvent {
+    /// Creates an event that happened now.
+    pub fn new(event_type: EventType, subject: impl Into<String>, actor: EventActor) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event_type,
+            subject: subject.into(),
+            details: BTreeMap::new(),
+            actor,
+        }
+    }
+
+    /// Adds a detail.
+    pub fn with_detail(mut self, key: &str, value: impl ToString) -> Self {
+        self.details.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+/// Kind of journal event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    /// A managed process was started.
+    ProcessStarted,
+    /// A managed process was stopped.
+    ProcessStopped,
+    /// A managed process exited unexpectedly; the `orphans` detail lists the
+    /// children it left running.
+    ProcessCrashed,
+    /// A managed process exited after finishing its work.
+    ProcessCompleted,
+    /// A managed process was paused.
+    ProcessPaused,
+    /// A paused process was resumed.
+    ProcessResumed,
+    /// A managed process was restarted; the `reason` detail says why.
+    ProcessRestarted,
+    /// A crashed process could not be restarted.
+    RestartFailed,
+    /// A crashed process used up its restart limit.
+    RestartLimitExceeded,
+    /// The status of a managed process could not be checked.
+    HealthCheckFailed,
+    /// A sample event was sent to a notification sink.
+    NotificationTest,
+    /// The config file was loaded.
+    ConfigLoaded,
+    /// The config file was reloaded.
+    ConfigReloaded,
+    /// The process listening on a port was killed.
+    PortKilled,
+    /// A process was killed by PID through a confirmed kill.
+    ProcessKilled,
+    /// A scheduled process reached one of its cron times.
+    ScheduleTriggered,
+    /// A process was stopped for running longer than `maxRuntimeMs`.
+    MaxRuntimeExceeded,
+    /// A running process was restarted by its `restartPolicy`.
+    RestartPolicyTriggered,
+    /// The new instance of a warm restart didn't become ready, so the old
+    /// one was kept.
+    WarmRestartFailed,
+    /// Rotated process log files were deleted by `settings.logRetention`.
+    LogsPruned,
+    /// A set of processes was started together; the details summarize the
+    /// startup report.
+    StartupFinished,
+}
+
+impl From<ProcessEventKind> for EventType {
+    fn from(kind: ProcessEventKind) -> Self {
+        match kind {
+            ProcessEventKind::Started => Self::ProcessStarted,
+            ProcessEventKind::Stopped => Self::ProcessStopped,
+            ProcessEventKind::Crashed => Self::ProcessCrashed,
+            ProcessEventKind::Completed => Self::ProcessCompleted,
+            ProcessEventKind::Restarted => Self::ProcessRestarted,
+            ProcessEventKind::RestartLimitExceeded => Self::RestartLimitExceeded,
+            ProcessEventKind::Test => Self::NotificationTest,
+        }
+    }
+}
+
+/// Who caused a journal event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventActor {
+    /// A user, through the app or the CLI.
+    User,
+    /// The supervisor loop.
+    Supervisor,
+}
+
+/// Criteria for querying the event journal. Empty fields match everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Only events about this subject.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// Only events of these types.
+    #[serde(default, rename = "eventTypes")]
+    pub event_types: Vec<EventType>,
+    /// Only events caused by this actor.
+    #[serde(default)]
+    pub actor: Option<EventActor>,
+}
+
+impl EventFilter {
+    /// Whether `event` meets every criterion.
+    pub fn matches(&self, event: &JournalEvent) -> bool {
+        self.subject
+            .as_ref()
+            .is_none_or(|subject| *subject == event.subject)
+            && (self.event_types.is_empty() || self.event_types.contains(&event.event_type))
+            && self.actor.is_none_or(|actor| actor == event.actor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = JournalEvent::new(EventType::ProcessCrashed, "api", EventActor::Supervisor)
+            .with_detail("exit_code", 1);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event_type"], "process_crashed");
+        assert_eq!(json["actor"], "supervisor");
+        assert_eq!(json["details"]["exit_code"], "1");
+
+        let parsed: JournalEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let event = JournalEvent::new(EventType::ProcessStarted, "api", EventActor::User);
+        assert!(EventFilter::default().matches(&event));
+
+        let filter = EventFilter {
+            subject: Some("api".to_string()),
+            event_types: vec![EventType::ProcessStarted, EventType::ProcessStopped],
+            actor: Some(EventActor::User),
+        };
+        assert!(filter.matches(&event));
+
+        let other = EventFilter {
+            subject: Some("worker".to_string()),
+            ..filter.clone()
+        };
+        assert!(!other.matches(&event));
+
+        let other = EventFilter {
+            actor: Some(EventActor::Supervisor),
+            ..filter
+        };
+        assert!(!other.matches(&event));
+    }
+}
@@ -3,12 +3,29 @@
 //! This module contains all data structures used throughout the application,
 //! including process information, configuration, and system metrics.
 
+pub mod alert;
 pub mod config;
+pub mod event;
 pub mod process;
 pub mod state;
 pub mod system;
 
-pub use config::{Config, GlobalSettings, HealthCheck, ProcessConfig};
-pub use process::{ProcessInfo, ProcessState};
-pub use state::{ProcessRuntimeInfo, RuntimeState};
-pub use system::{CpuStats, DiskStats, MemoryStats, SystemStats};
+pub use alert::{Alert, AlertMetric, AlertRule, AlertSeverity, SYSTEM_TARGET};
+pub use config::{
+    ApiSettings, Config, ConfigIssue, EnvChangeAction, EnvMode, GlobalSettings, HealthCheck,
+    HealthCheckKind, LogFormat, LogRetention, LogRetentionLimits, MetricsHistorySettings,
+    NetworkSettings, NotificationSink, NotificationSinkKind, OnExit, OutputEncoding, ProcessConfig,
+    ProcessOverrides, ReadinessCheck, ReadinessProbe, RestartPolicy, StdinMode,
+};
+pub use event::{EventActor, EventFilter, EventType, JournalEvent};
+pub use process::{
+    BulkOutcome, ContainerUsage, HealthResult, ManagedProcessRef, ManagedProcessSummary,
+    OrphanInfo, ProcessEvent, ProcessEventKind, ProcessHistory, ProcessInfo, ProcessKind,
+    ProcessState, ProcessTreeNode, RestartReason, UsageSample, UsageSource, PROCESS_HISTORY_LEN,
+};
+pub use state::{ExternalProcessEntry, ProcessRuntimeInfo, RuntimeState};
+pub use system::{
+    cpu_percent_of_total, ComponentTemperature, CpuStats, DiskInfo, DiskStats, GpuStats,
+    LoadAverage, MemoryStats, ProcessResourceUsage, SystemDetails, SystemProcess, SystemStats,
+    TopProcessSort, TopProcesses,
+};
@@ -1,6 +1,8 @@
 //! Process-related data models.
 
+use crate::models::{cpu_percent_of_total, EventActor};
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Represents the state of a managed process.
@@ -13,10 +15,15 @@ pub enum ProcessState {
     Starting,
     /// Process is running normally.
     Running,
+    /// Process and its children are suspended, keeping their memory.
+    Paused,
     /// Process is being stopped.
     Stopping,
     /// Process crashed with an exit code.
     Crashed { exit_code: i32 },
+    /// Process finished its work, exiting with 0 or one of its
+    /// `successExitCodes`; it isn't restarted.
+    Completed { exit_code: i32 },
     /// Process failed to start.
     Failed { reason: String },
 }
@@ -34,16 +41,197 @@ pub struct ProcessInfo {
     pub command: String,
     /// Working directory.
     pub cwd: Option<String>,
-    /// CPU usage percentage (0-100 per core).
+    /// CPU usage in percent of one core, 0 until measured; same as
+    /// `cpu_usage_percent_of_core`.
     pub cpu_usage: f32,
+    /// CPU usage in percent of one core, so up to 100 × the number of cores.
+    /// `None` until the process has been sampled twice.
+    #[serde(default)]
+    pub cpu_usage_percent_of_core: Option<f32>,
+    /// CPU usage in percent of all cores together (0-100), on the same scale
+    /// as the system's overall CPU usage. `None` until the process has been
+    /// sampled twice.
+    #[serde(default)]
+    pub cpu_usage_percent_of_total: Option<f32>,
     /// Memory usage in bytes.
     pub memory_usage: u64,
+    /// CPU usage of the process and all its descendants, in percent of one core, when
+    /// `aggregateChildUsage` is enabled.
+    #[serde(default)]
+    pub cpu_usage_tree: Option<f32>,
+    /// Memory usage of the process and all its descendants in bytes, when
+    /// `aggregateChildUsage` is enabled.
+    #[serde(default)]
+    pub memory_usage_tree: Option<u64>,
+    /// Current scheduling priority (nice value), if known.
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// Open file descriptors (handles on Windows), if they can be counted.
+    #[serde(default)]
+    pub open_fds: Option<u64>,
+    /// Number of threads, if they can be counted.
+    #[serde(default)]
+    pub thread_count: Option<u64>,
     /// Number of restart attempts.
     pub restart_count: u32,
     /// Time when the process was started.
     pub started_at: Option<DateTime<Utc>>,
     /// Time when the process was stopped.
     pub stopped_at: Option<DateTime<Utc>>,
+    /// Exit code of the last run, once it has exited (`None` if killed by a signal).
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Tags from the process configuration.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Whether the process is running and its readiness probe, if any, has succeeded.
+    #[serde(default)]
+    pub ready: bool,
+    /// Milliseconds from starting until the readiness probe succeeded.
+    #[serde(default)]
+    pub time_to_ready_ms: Option<u64>,
+    /// Result of the latest health check while running (when there is one).
+    #[serde(default)]
+    pub health: Option<HealthResult>,
+    /// Absolute path of the executable, once started.
+    #[serde(default)]
+    pub exe_path: Option<String>,
+    /// Full argument vector, program first, once started.
+    #[serde(default)]
+    pub argv: Vec<String>,
+    /// Canonical working directory, once started.
+    #[serde(default)]
+    pub resolved_cwd: Option<String>,
+    /// PID of the leader of the process's process group, while running.
+    #[serde(default)]
+    pub pgid: Option<u32>,
+    /// The `PORT` the process was started with, if any. A warm restart with
+    /// `warmRestartFreePort` moves it.
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Recent resource usage, when asked for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<ProcessHistory>,
+    /// Whether the CPU and memory figures include linked Docker containers.
+    #[serde(default)]
+    pub usage_source: UsageSource,
+    /// Usage of the linked Docker containers, when they were found.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<ContainerUsage>,
+    /// Why the process was last restarted, if it has been since it was started.
+    #[serde(default)]
+    pub last_restart_reason: Option<RestartReason>,
+    /// Time of the last restart.
+    #[serde(default)]
+    pub last_restart_at: Option<DateTime<Utc>>,
+}
+
+/// Where a process's CPU and memory figures come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageSource {
+    /// The process and, with `aggregateChildUsage`, its descendants.
+    #[default]
+    Process,
+    /// The process plus the Docker containers it runs.
+    Container,
+}
+
+/// Resource usage of the Docker containers a process runs, such as one
+/// that only calls `docker run`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerUsage {
+    /// Names of the containers, without the leading `/`.
+    pub containers: Vec<String>,
+    /// CPU usage in percent of one core.
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    /// Sum of the containers' memory limits, 0 when unknown.
+    pub memory_limit: u64,
+    pub network_rx_bytes: u64,
+    pub network_tx_bytes: u64,
+    pub block_read_bytes: u64,
+    pub block_write_bytes: u64,
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// Number of samples in a [`ProcessHistory`].
+pub const PROCESS_HISTORY_LEN: usize = 30;
+
+/// One sample of a process's resource usage, for [`ProcessHistory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageSample {
+    /// CPU usage in percent of one core, once measured.
+    pub cpu: Option<f32>,
+    /// Memory usage in bytes.
+    pub memory: u64,
+    /// Open file descriptors, if they could be counted.
+    pub open_fds: Option<u64>,
+    /// Threads, if they could be counted.
+    pub threads: Option<u64>,
+}
+
+/// The latest resource usage samples of a process, oldest first, for sparklines.
+///
+/// Every array always holds [`PROCESS_HISTORY_LEN`] entries; a process with
+/// fewer samples so far is padded with `null` at the front. CPU usage is
+/// also `null` for the first sample, which has nothing to compare against,
+/// and the descriptor and thread counts wherever they couldn't be read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessHistory {
+    /// Average time between samples in milliseconds.
+    pub sample_interval_ms: u64,
+    /// CPU usage in percent of one core.
+    pub cpu: [Option<f32>; PROCESS_HISTORY_LEN],
+    /// Memory usage in bytes.
+    pub memory: [Option<u64>; PROCESS_HISTORY_LEN],
+    /// Open file descriptors.
+    #[serde(default)]
+    pub open_fds: [Option<u64>; PROCESS_HISTORY_LEN],
+    /// Threads.
+    #[serde(default)]
+    pub threads: [Option<u64>; PROCESS_HISTORY_LEN],
+}
+
+impl ProcessHistory {
+    /// Builds a history from samples, oldest first.
+    ///
+    /// Only the latest [`PROCESS_HISTORY_LEN`] samples are kept.
+    pub fn from_samples(sample_interval_ms: u64, samples: &[UsageSample]) -> Self {
+        let mut history = Self {
+            sample_interval_ms,
+            cpu: [None; PROCESS_HISTORY_LEN],
+            memory: [None; PROCESS_HISTORY_LEN],
+            open_fds: [None; PROCESS_HISTORY_LEN],
+            threads: [None; PROCESS_HISTORY_LEN],
+        };
+        let kept = &samples[samples.len().saturating_sub(PROCESS_HISTORY_LEN)..];
+        let offset = PROCESS_HISTORY_LEN - kept.len();
+        for (i, sample) in kept.iter().enumerate() {
+            history.cpu[offset + i] = sample.cpu;
+            history.memory[offset + i] = Some(sample.memory);
+            history.open_fds[offset + i] = sample.open_fds;
+            history.threads[offset + i] = sample.threads;
+        }
+        history
+    }
+}
+
+/// Result of a health check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthResult {
+    /// Whether the check passed.
+    pub healthy: bool,
+    /// Time the check took in milliseconds.
+    pub latency_ms: u64,
+    /// Status code of the response (HTTP checks that got one).
+    pub status_code: Option<u16>,
+    /// Why the check failed.
+    pub error: Option<String>,
+    /// Failed checks in a row, including this one.
+    pub consecutive_failures: u32,
+    /// Time of the check.
+    pub checked_at: DateTime<Utc>,
 }
 
 impl ProcessInfo {
@@ -56,16 +244,68 @@ impl ProcessInfo {
             command,
             cwd: None,
             cpu_usage: 0.0,
+            cpu_usage_percent_of_core: None,
+            cpu_usage_percent_of_total: None,
             memory_usage: 0,
+            cpu_usage_tree: None,
+            memory_usage_tree: None,
+            nice: None,
+            open_fds: None,
+            thread_count: None,
             restart_count: 0,
             started_at: None,
             stopped_at: None,
+            exit_code: None,
+            tags: Vec::new(),
+            ready: false,
+            time_to_ready_ms: None,
+            health: None,
+            exe_path: None,
+            argv: Vec::new(),
+            resolved_cwd: None,
+            pgid: None,
+            port: None,
+            history: None,
+            usage_source: UsageSource::Process,
+            container: None,
+            last_restart_reason: None,
+            last_restart_at: None,
         }
     }
 
-    /// Checks if the process is currently running.
+    /// Records CPU usage measured in percent of one core on a machine with
+    /// `core_count` cores, or `None` while there is no measurement yet.
+    pub fn set_cpu_usage(&mut self, percent_of_core: Option<f32>, core_count: usize) {
+        self.cpu_usage = percent_of_core.unwrap_or(0.0);
+        self.cpu_usage_percent_of_core = percent_of_core;
+        self.cpu_usage_percent_of_total =
+            percent_of_core.map(|cpu| cpu_percent_of_total(cpu, core_count));
+    }
+
+    /// Adds the usage of linked containers to the process's own, marking the
+    /// figures as coming from containers.
+    pub fn merge_container_usage(&mut self, usage: &ContainerUsage, core_count: usize) {
+        let own = self.cpu_usage_percent_of_core.unwrap_or(0.0);
+        self.set_cpu_usage(Some(own + usage.cpu_percent), core_count);
+        self.memory_usage += usage.memory_bytes;
+        if let Some(cpu) = self.cpu_usage_tree.as_mut() {
+            *cpu += usage.cpu_percent;
+        }
+        if let Some(memory) = self.memory_usage_tree.as_mut() {
+            *memory += usage.memory_bytes;
+        }
+        self.usage_source = UsageSource::Container;
+        self.container = Some(usage.clone());
+    }
+
+    /// Checks if the process is currently running, paused or not.
     pub fn is_running(&self) -> bool {
-        matches!(self.state, ProcessState::Running)
+        matches!(self.state, ProcessState::Running | ProcessState::Paused)
+    }
+
+    /// Checks if the process is paused.
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, ProcessState::Paused)
     }
 
     /// Checks if the process is stopped.
@@ -77,6 +317,256 @@ impl ProcessInfo {
     pub fn is_crashed(&self) -> bool {
         matches!(self.state, ProcessState::Crashed { .. })
     }
+
+    /// Checks if the process exited after finishing its work.
+    pub fn is_completed(&self) -> bool {
+        matches!(self.state, ProcessState::Completed { .. })
+    }
+}
+
+/// Why a process was restarted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RestartReason {
+    /// A user restarted it, through the app, the CLI or the API.
+    UserRequested,
+    /// The supervisor restarted it after it exited.
+    CrashAutoRestart { exit_code: i32 },
+    /// A watched file changed.
+    FileChanged { path: String },
+    /// Its `restartPolicy` was due; `name` is the policy's config key.
+    Policy { name: String },
+    /// The config file was reloaded with changes to it.
+    ConfigReload,
+}
+
+impl RestartReason {
+    /// The reason's name as stored in the event journal.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::UserRequested => "user_requested",
+            Self::CrashAutoRestart { .. } => "crash_auto_restart",
+            Self::FileChanged { .. } => "file_changed",
+            Self::Policy { .. } => "policy",
+            Self::ConfigReload => "config_reload",
+        }
+    }
+
+    /// Who the restart is recorded as caused by.
+    pub fn actor(&self) -> EventActor {
+        match self {
+            Self::UserRequested | Self::ConfigReload => EventActor::User,
+            Self::CrashAutoRestart { .. } | Self::FileChanged { .. } | Self::Policy { .. } => {
+                EventActor::Supervisor
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RestartReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UserRequested => write!(f, "requested by user"),
+            Self::CrashAutoRestart { exit_code } => {
+                write!(f, "crashed with exit code {}", exit_code)
+            }
+            Self::FileChanged { path } => write!(f, "{} changed", path),
+            Self::Policy { name } => write!(f, "{} restart policy", name),
+            Self::ConfigReload => write!(f, "config reloaded"),
+        }
+    }
+}
+
+/// A process left running after the managed process it descends from
+/// exited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanInfo {
+    /// OS process ID.
+    pub pid: u32,
+    /// Current parent, usually init or a subreaper.
+    pub parent_pid: Option<u32>,
+    /// Process name.
+    pub name: String,
+    /// Start time in seconds since the epoch, which tells it apart from a
+    /// later process with the same PID.
+    pub start_time: u64,
+    /// Whether Sentinel killed it because of `reapOrphans`.
+    #[serde(default)]
+    pub reaped: bool,
+}
+
+impl std::fmt::Display for OrphanInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.pid)
+    }
+}
+
+/// Outcome for one process of a bulk operation, such as stopping every
+/// process with a tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkOutcome {
+    /// Process name.
+    pub name: String,
+    /// Why the operation failed for this process (`None` on success).
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl<T> From<(String, crate::error::Result<T>)> for BulkOutcome {
+    fn from((name, result): (String, crate::error::Result<T>)) -> Self {
+        Self {
+            name,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Which manager runs a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessKind {
+    /// Started by `ProcessManager`, with piped output.
+    Piped,
+    /// Started by `PtyProcessManager` in a pseudo-terminal.
+    Pty,
+    /// Started outside Sentinel and only monitored.
+    External,
+}
+
+/// Summary of a process of any kind, for a single process list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedProcessSummary {
+    /// Process name (piped), process ID (PTY) or display name (external).
+    pub id: String,
+    /// Manager that runs the process.
+    pub kind: ProcessKind,
+    /// OS process ID (if running).
+    pub pid: Option<u32>,
+    /// Current state of the process.
+    pub state: ProcessState,
+    /// Time when the process was started.
+    pub started_at: Option<DateTime<Utc>>,
+    /// CPU usage in percent of one core, if measured.
+    pub cpu_usage: Option<f32>,
+    /// Memory usage in bytes, if known.
+    pub memory_usage: Option<u64>,
+    /// Ports the process listens on; only looked up for external processes.
+    #[serde(default)]
+    pub ports: Vec<u16>,
+}
+
+/// A process and its descendants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessTreeNode {
+    /// OS process ID.
+    pub pid: u32,
+    /// Process name.
+    pub name: String,
+    /// CPU usage percentage of this process alone (0-100 per core).
+    pub cpu_usage: f32,
+    /// Memory usage of this process alone in bytes.
+    pub memory: u64,
+    /// Child processes.
+    pub children: Vec<ProcessTreeNode>,
+}
+
+impl ProcessTreeNode {
+    /// CPU usage of this process and all its descendants.
+    pub fn total_cpu_usage(&self) -> f32 {
+        self.cpu_usage
+            + self
+                .children
+                .iter()
+                .map(ProcessTreeNode::total_cpu_usage)
+                .sum::<f32>()
+    }
+
+    /// Memory usage of this process and all its descendants in bytes.
+    pub fn total_memory(&self) -> u64 {
+        self.memory
+            + self
+                .children
+                .iter()
+                .map(ProcessTreeNode::total_memory)
+                .sum::<u64>()
+    }
+
+    /// PIDs of this process and all its descendants, parents first.
+    pub fn pids(&self) -> Vec<u32> {
+        std::iter::once(self.pid)
+            .chain(self.children.iter().flat_map(ProcessTreeNode::pids))
+            .collect()
+    }
+}
+
+/// Identifies a process owned or monitored by Sentinel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManagedProcessRef {
+    /// Process name (piped), process ID (PTY) or display name (external).
+    pub id: String,
+    /// Manager that runs the process.
+    pub kind: ProcessKind,
+}
+
+/// Kind of process lifecycle event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessEventKind {
+    /// The process was started.
+    Started,
+    /// The process was stopped on request.
+    Stopped,
+    /// The process exited on its own.
+    Crashed,
+    /// The process exited after finishing its work.
+    Completed,
+    /// The process was restarted after crashing.
+    Restarted,
+    /// The process crashed with no restart attempts left.
+    RestartLimitExceeded,
+    /// Sample event used to check notification sinks.
+    Test,
+}
+
+/// A change in a managed process's lifecycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessEvent {
+    /// What happened.
+    pub event: ProcessEventKind,
+    /// Name of the process.
+    pub process: String,
+    /// Exit code, for events caused by the process exiting.
+    pub exit_code: Option<i32>,
+    /// Time of the event.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ProcessEvent {
+    /// Creates an event that happened now.
+    pub fn new(event: ProcessEventKind, process: &str, exit_code: Option<i32>) -> Self {
+        Self {
+            event,
+            process: process.to_string(),
+            exit_code,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+impl From<ProcessInfo> for ManagedProcessSummary {
+    fn from(info: ProcessInfo) -> Self {
+        let running = info.pid.is_some();
+        Self {
+            id: info.name,
+            kind: ProcessKind::Piped,
+            pid: info.pid,
+            state: info.state,
+            started_at: info.started_at,
+            cpu_usage: info.cpu_usage_percent_of_core.filter(|_| running),
+            memory_usage: running.then_some(info.memory_usage),
+            ports: Vec::new(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -93,6 +583,34 @@ mod tests {
         assert!(!info.is_running());
     }
 
+    #[test]
+    fn test_merge_container_usage() {
+        let mut info = ProcessInfo::new("db".to_string(), "docker run".to_string());
+        info.set_cpu_usage(Some(0.5), 4);
+        info.memory_usage = 10_000_000;
+        let usage = ContainerUsage {
+            containers: vec!["postgres".to_string()],
+            cpu_percent: 120.0,
+            memory_bytes: 250_000_000,
+            memory_limit: 0,
+            network_rx_bytes: 1024,
+            network_tx_bytes: 2048,
+            block_read_bytes: 0,
+            block_write_bytes: 4096,
+            sampled_at: Utc::now(),
+        };
+
+        info.merge_container_usage(&usage, 4);
+        assert_eq!(info.cpu_usage, 120.5);
+        assert_eq!(info.cpu_usage_percent_of_total, Some(30.125));
+        assert_eq!(info.memory_usage, 260_000_000);
+        assert_eq!(info.usage_source, UsageSource::Container);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["usage_source"], "container");
+        assert_eq!(json["container"]["containers"][0], "postgres");
+    }
+
     #[test]
     fn test_process_state_serialization() {
         let running = ProcessState::Running;
@@ -105,6 +623,32 @@ mod tests {
         assert!(json.contains("exit_code"));
     }
 
+    #[test]
+    fn test_restart_reason_serialization() {
+        let reason = RestartReason::CrashAutoRestart { exit_code: 3 };
+        let json = serde_json::to_value(&reason).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "crash_auto_restart", "exit_code": 3})
+        );
+        assert_eq!(json["type"], reason.kind());
+        assert_eq!(reason.actor(), EventActor::Supervisor);
+        assert_eq!(
+            serde_json::to_value(RestartReason::UserRequested).unwrap(),
+            serde_json::json!({"type": "user_requested"})
+        );
+        assert_eq!(RestartReason::ConfigReload.actor(), EventActor::User);
+
+        // Infos saved before restarts were tracked still load
+        let mut json =
+            serde_json::to_value(ProcessInfo::new("api".to_string(), "npm".to_string())).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("last_restart_reason");
+        object.remove("last_restart_at");
+        let info: ProcessInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(info.last_restart_reason, None);
+    }
+
     #[test]
     fn test_is_running() {
         let mut info = ProcessInfo::new("test".to_string(), "cmd".to_string());
@@ -112,6 +656,13 @@ mod tests {
 
         info.state = ProcessState::Running;
         assert!(info.is_running());
+        assert!(!info.is_paused());
+
+        // A paused process is still alive
+        info.state = ProcessState::Paused;
+        assert!(info.is_running());
+        assert!(info.is_paused());
+        assert_eq!(serde_json::to_string(&info.state).unwrap(), "\"paused\"");
     }
 
     #[test]
@@ -122,4 +673,116 @@ mod tests {
         info.state = ProcessState::Crashed { exit_code: 1 };
         assert!(info.is_crashed());
     }
+
+    #[test]
+    fn test_is_completed() {
+        let mut info = ProcessInfo::new("test".to_string(), "cmd".to_string());
+        info.state = ProcessState::Completed { exit_code: 0 };
+        assert!(info.is_completed());
+        assert!(!info.is_crashed());
+        assert!(!info.is_running());
+        assert_eq!(
+            serde_json::to_value(&info.state).unwrap(),
+            serde_json::json!({"completed": {"exit_code": 0}})
+        );
+    }
+
+    #[test]
+    fn test_set_cpu_usage_per_core_and_total() {
+        let mut info = ProcessInfo::new("test".to_string(), "cmd".to_string());
+
+        // Two cores fully busy
+        for cores in [1, 4, 16] {
+            info.set_cpu_usage(Some(200.0), cores);
+            let of_core = info.cpu_usage_percent_of_core.unwrap();
+            let of_total = info.cpu_usage_percent_of_total.unwrap();
+            assert_eq!(of_core, 200.0);
+            assert_eq!(info.cpu_usage, of_core);
+            assert!((of_total * cores as f32 - of_core).abs() < 1e-4);
+        }
+        assert_eq!(info.cpu_usage_percent_of_total, Some(12.5));
+
+        // No measurement yet
+        info.set_cpu_usage(None, 4);
+        assert_eq!(info.cpu_usage, 0.0);
+        assert_eq!(info.cpu_usage_percent_of_core, None);
+        assert_eq!(info.cpu_usage_percent_of_total, None);
+    }
+
+    #[test]
+    fn test_process_history_json_shape() {
+        let samples = [
+            UsageSample {
+                memory: 1024,
+                threads: Some(4),
+                ..Default::default()
+            },
+            UsageSample {
+                cpu: Some(12.5),
+                memory: 2048,
+                open_fds: Some(12),
+                threads: Some(5),
+            },
+        ];
+        let history = ProcessHistory::from_samples(1000, &samples);
+        let mut cpu = vec![serde_json::Value::Null; PROCESS_HISTORY_LEN];
+        cpu[PROCESS_HISTORY_LEN - 1] = serde_json::json!(12.5);
+        let mut memory = vec![serde_json::Value::Null; PROCESS_HISTORY_LEN];
+        memory[PROCESS_HISTORY_LEN - 2] = serde_json::json!(1024);
+        memory[PROCESS_HISTORY_LEN - 1] = serde_json::json!(2048);
+        let mut open_fds = vec![serde_json::Value::Null; PROCESS_HISTORY_LEN];
+        open_fds[PROCESS_HISTORY_LEN - 1] = serde_json::json!(12);
+        let mut threads = vec![serde_json::Value::Null; PROCESS_HISTORY_LEN];
+        threads[PROCESS_HISTORY_LEN - 2] = serde_json::json!(4);
+        threads[PROCESS_HISTORY_LEN - 1] = serde_json::json!(5);
+
+        assert_eq!(
+            serde_json::to_value(&history).unwrap(),
+            serde_json::json!({
+                "sample_interval_ms": 1000,
+                "cpu": cpu,
+                "memory": memory,
+                "open_fds": open_fds,
+                "threads": threads,
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_history_keeps_latest_samples() {
+        let samples: Vec<_> = (0..40u64)
+            .map(|i| UsageSample {
+                cpu: Some(i as f32),
+                memory: i,
+                open_fds: Some(i),
+                threads: None,
+            })
+            .collect();
+        let history = ProcessHistory::from_samples(500, &samples);
+        assert_eq!(history.cpu[0], Some(10.0));
+        assert_eq!(history.memory[PROCESS_HISTORY_LEN - 1], Some(39));
+        assert!(history.cpu.iter().all(Option::is_some));
+        assert_eq!(history.open_fds[0], Some(10));
+        assert!(history.threads.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_process_info_history_only_serialized_when_present() {
+        let mut info = ProcessInfo::new("test".to_string(), "cmd".to_string());
+        let json = serde_json::to_value(&info).unwrap();
+        assert!(json.get("history").is_none());
+
+        info.history = Some(ProcessHistory::from_samples(1000, &[]));
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["history"]["sample_interval_ms"], 1000);
+        assert_eq!(
+            json["history"]["cpu"].as_array().unwrap().len(),
+            PROCESS_HISTORY_LEN
+        );
+        assert!(json["history"]["memory"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(serde_json::Value::is_null));
+    }
 }
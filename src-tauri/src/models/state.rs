@@ -3,6 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Runtime state for all managed processes.
 ///
@@ -17,6 +18,10 @@ pub struct RuntimeState {
 
     /// Last time state was synchronized with running processes
     pub last_sync: Option<DateTime<Utc>>,
+
+    /// Processes Sentinel monitors but didn't start, by display name
+    #[serde(default)]
+    pub external_processes: HashMap<String, ExternalProcessEntry>,
 }
 
 /// Runtime information for a single process.
@@ -39,6 +44,25 @@ pub struct ProcessRuntimeInfo {
 
     /// Last known exit code (if process exited)
     pub last_exit_code: Option<i32>,
+
+    /// Absolute path of the executable the process runs, if known
+    #[serde(default)]
+    pub exe_path: Option<PathBuf>,
+
+    /// Full argument vector the process was started with
+    #[serde(default)]
+    pub argv: Vec<String>,
+}
+
+/// A process started outside Sentinel that it monitors without owning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalProcessEntry {
+    /// OS process ID
+    pub pid: u32,
+
+    /// When the process started, in seconds since the epoch, so a reused
+    /// PID isn't mistaken for it
+    pub start_time: u64,
 }
 
 impl RuntimeState {
@@ -81,6 +105,25 @@ impl ProcessRuntimeInfo {
             managed_by_sentinel: true,
             restart_count: 0,
             last_exit_code: None,
+            exe_path: None,
+            argv: Vec::new(),
+        }
+    }
+
+    /// Records what the process runs, so it can be recognized later.
+    pub fn with_command(mut self, exe_path: Option<PathBuf>, argv: Vec<String>) -> Self {
+        self.exe_path = exe_path;
+        self.argv = argv;
+        self
+    }
+
+    /// Checks whether a process running `exe` can be this one.
+    ///
+    /// Unknown executables on either side are given the benefit of the doubt.
+    pub fn runs_executable(&self, exe: Option<&Path>) -> bool {
+        match (&self.exe_path, exe) {
+            (Some(expected), Some(exe)) => expected == exe,
+            _ => true,
         }
     }
 
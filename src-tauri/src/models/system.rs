@@ -1,5 +1,6 @@
 //! System monitoring data models.
 
+use crate::models::ManagedProcessRef;
 use serde::{Deserialize, Serialize};
 
 /// System-wide statistics.
@@ -11,6 +12,13 @@ pub struct SystemStats {
     pub memory: MemoryStats,
     /// Disk I/O statistics.
     pub disk: DiskStats,
+    /// Load averages, `None` on platforms without them (Windows).
+    pub load_average: Option<LoadAverage>,
+    /// Component temperatures, `None` when the platform exposes no sensors.
+    pub temperatures: Option<Vec<ComponentTemperature>>,
+    /// GPUs, as of the last GPU refresh; empty when none can be read.
+    #[serde(default)]
+    pub gpus: Vec<GpuStats>,
     /// Timestamp when stats were collected.
     pub timestamp: i64,
 }
@@ -24,6 +32,8 @@ pub struct CpuStats {
     pub cores: Vec<f32>,
     /// Number of CPU cores.
     pub core_count: usize,
+    /// Per-core frequencies in MHz, `None` when the platform doesn't report them.
+    pub frequencies: Option<Vec<u64>>,
 }
 
 /// Memory usage statistics.
@@ -56,6 +66,147 @@ pub struct DiskStats {
     pub available_space: u64,
 }
 
+/// System load averages.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadAverage {
+    /// Average load over the last minute.
+    pub one: f64,
+    /// Average load over the last 5 minutes.
+    pub five: f64,
+    /// Average load over the last 15 minutes.
+    pub fifteen: f64,
+}
+
+/// Temperature reported by a hardware sensor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentTemperature {
+    /// Sensor label (e.g., "coretemp Package id 0").
+    pub label: String,
+    /// Current temperature in °C.
+    pub temperature: f32,
+    /// Highest temperature seen in °C, if known.
+    pub max: Option<f32>,
+    /// Critical temperature threshold in °C, if known.
+    pub critical: Option<f32>,
+}
+
+/// Usage of a graphics card.
+///
+/// Fields the card or its driver doesn't report are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuStats {
+    /// Card name (e.g., "NVIDIA GeForce RTX 3080").
+    pub name: String,
+    /// Busy time in percent (0-100).
+    pub utilization: Option<f32>,
+    /// Used video memory in bytes.
+    pub memory_used: Option<u64>,
+    /// Total video memory in bytes.
+    pub memory_total: Option<u64>,
+    /// Temperature in °C.
+    pub temperature: Option<f32>,
+}
+
+/// A mounted disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskInfo {
+    /// Device name.
+    pub name: String,
+    /// Mount point path.
+    pub mount_point: String,
+    /// File system type (e.g., "ext4", "apfs").
+    pub file_system: String,
+    /// Total space in bytes.
+    pub total_space: u64,
+    /// Available space in bytes.
+    pub available_space: u64,
+    /// Whether the disk is removable.
+    pub is_removable: bool,
+}
+
+/// System details that change rarely or are costly to collect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemDetails {
+    /// Load averages, `None` on platforms without them (Windows).
+    pub load_average: Option<LoadAverage>,
+    /// Component temperatures, `None` when the platform exposes no sensors.
+    pub temperatures: Option<Vec<ComponentTemperature>>,
+    /// All mounted disks.
+    pub disks: Vec<DiskInfo>,
+}
+
+/// Column to sort the top-processes list by (descending).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopProcessSort {
+    /// CPU usage.
+    #[default]
+    Cpu,
+    /// Resident memory.
+    Memory,
+    /// Disk bytes read per second.
+    DiskRead,
+    /// Disk bytes written per second.
+    DiskWrite,
+}
+
+/// A process running on the system, managed by Sentinel or not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemProcess {
+    /// OS process ID.
+    pub pid: u32,
+    /// Process name.
+    pub name: String,
+    /// Path of the executable, if readable.
+    pub exe: Option<String>,
+    /// CPU usage percentage (0-100 per core).
+    pub cpu_usage: f32,
+    /// Resident memory in bytes.
+    pub memory: u64,
+    /// Disk bytes read per second.
+    pub disk_read_bytes_per_sec: u64,
+    /// Disk bytes written per second.
+    pub disk_write_bytes_per_sec: u64,
+    /// Parent process ID, if known.
+    pub parent_pid: Option<u32>,
+    /// Name of the owning user, if known.
+    pub user: Option<String>,
+    /// The Sentinel-managed process with this PID, if any.
+    pub managed: Option<ManagedProcessRef>,
+}
+
+/// One page of the top-processes list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopProcesses {
+    /// Processes on this page, heaviest first.
+    pub processes: Vec<SystemProcess>,
+    /// Number of processes across all pages.
+    pub total: usize,
+}
+
+/// Converts CPU usage in percent of one core to percent of all `core_count`
+/// cores together, the scale of `CpuStats::overall`.
+pub fn cpu_percent_of_total(percent_of_core: f32, core_count: usize) -> f32 {
+    percent_of_core / core_count.max(1) as f32
+}
+
+/// Resource usage of one process as of the last refresh.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessResourceUsage {
+    /// CPU usage in percent of one core, so up to 100 × the number of cores.
+    /// `None` until the process has been sampled twice.
+    pub cpu_usage_percent_of_core: Option<f32>,
+    /// CPU usage in percent of all cores together (0-100), comparable with
+    /// `CpuStats::overall`. `None` until the process has been sampled twice.
+    pub cpu_usage_percent_of_total: Option<f32>,
+    /// Resident memory in bytes.
+    pub memory_bytes: u64,
+    /// Bytes read from disk since the previous refresh.
+    pub disk_read_bytes: u64,
+    /// Bytes written to disk since the previous refresh.
+    pub disk_write_bytes: u64,
+}
+
 impl CpuStats {
     /// Creates a new CpuStats with zero values.
     pub fn zero(core_count: usize) -> Self {
@@ -63,6 +214,7 @@ impl CpuStats {
             overall: 0.0,
             cores: vec![0.0; core_count],
             core_count,
+            frequencies: None,
         }
     }
 }
@@ -106,6 +258,15 @@ impl DiskStats {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_cpu_percent_of_total() {
+        assert_eq!(cpu_percent_of_total(50.0, 1), 50.0);
+        assert_eq!(cpu_percent_of_total(400.0, 4), 100.0);
+        assert_eq!(cpu_percent_of_total(80.0, 16), 5.0);
+        // An unknown core count is taken as one core
+        assert_eq!(cpu_percent_of_total(80.0, 0), 80.0);
+    }
+
     #[test]
     fn test_cpu_stats_zero() {
         let stats = CpuStats::zero(4);
@@ -148,6 +309,9 @@ mod tests {
             cpu: CpuStats::zero(2),
             memory: MemoryStats::new(100, 50, 50, 20, 10),
             disk: DiskStats::zero(),
+            load_average: None,
+            temperatures: None,
+            gpus: Vec::new(),
             timestamp: 1234567890,
         };
 
@@ -156,4 +320,23 @@ mod tests {
         assert!(json.contains("memory"));
         assert!(json.contains("disk"));
     }
+
+    #[test]
+    fn test_missing_sensors_serialize_null() {
+        let stats = SystemStats {
+            cpu: CpuStats::zero(1),
+            memory: MemoryStats::new(100, 50, 50, 0, 0),
+            disk: DiskStats::zero(),
+            load_average: None,
+            temperatures: None,
+            gpus: Vec::new(),
+            timestamp: 0,
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&stats).unwrap();
+        assert!(json["load_average"].is_null());
+        assert!(json["temperatures"].is_null());
+        assert_eq!(json["gpus"], serde_json::json!([]));
+        assert!(json["cpu"]["frequencies"].is_null());
+    }
 }
@@ -4,9 +4,11 @@
 //! Tauri commands.
 
 use crate::core::{
-    ExternalProcessMonitor, ProcessConfigStore, ProcessController, ProcessManager,
-    PtyProcessManager, SystemMonitor,
+    AlertManager, ApiContext, ApiServer, DiskUsageCache, EventJournal, EventRecorder,
+    ExternalProcessMonitor, MetricsExporter, ProcessConfigStore, ProcessController, ProcessManager,
+    ProcessRegistry, PtyProcessManager, StateManager, SystemMonitor,
 };
+use crate::features::port_discovery::{ConnectionEnricher, KillGuard, StaleServerDetector};
 use crate::models::Config;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
@@ -15,8 +17,8 @@ use tokio::sync::{Mutex, RwLock};
 ///
 /// This struct is managed by Tauri and accessible from all commands.
 pub struct AppState {
-    /// Process manager instance.
-    pub process_manager: Arc<Mutex<ProcessManager>>,
+    /// Process manager instance; it locks each process separately.
+    pub process_manager: Arc<ProcessManager>,
     /// System monitor instance.
     pub system_monitor: Arc<Mutex<SystemMonitor>>,
     /// External process monitor instance.
@@ -27,24 +29,72 @@ pub struct AppState {
     pub process_config_store: Arc<Mutex<ProcessConfigStore>>,
     /// Process controller for managed processes.
     pub process_controller: Arc<Mutex<ProcessController>>,
+    /// Combined view of piped and PTY processes.
+    pub process_registry: ProcessRegistry,
     /// Current configuration.
     pub config: Arc<RwLock<Option<Config>>>,
+    /// Alert rule engine.
+    pub alert_manager: Arc<Mutex<AlertManager>>,
+    /// Prometheus metrics endpoint.
+    pub metrics_exporter: Arc<Mutex<MetricsExporter>>,
+    /// HTTP API for headless use.
+    pub api_server: Arc<Mutex<ApiServer>>,
+    /// Records what Sentinel did to the event journal.
+    pub event_recorder: EventRecorder,
+    /// CPU samples of listening processes, for finding stale dev servers.
+    pub stale_servers: Arc<Mutex<StaleServerDetector>>,
+    /// Host name cache and GeoIP database for remote addresses.
+    pub connection_enricher: Arc<ConnectionEnricher>,
+    /// Recent working directory disk usage reports and running scans.
+    pub disk_usage: Arc<Mutex<DiskUsageCache>>,
+    /// Kills awaiting confirmation and recently killed processes.
+    pub kill_guard: Arc<Mutex<KillGuard>>,
 }
 
 impl AppState {
     /// Creates a new AppState with default instances.
     pub fn new() -> Self {
+        let event_recorder = EventRecorder::open(EventJournal::new(EventJournal::default_path()));
+        let mut process_manager = ProcessManager::new();
+        process_manager.set_event_recorder(event_recorder.clone());
+        let process_manager = Arc::new(process_manager);
+        let system_monitor = Arc::new(Mutex::new(SystemMonitor::new()));
         let pty_manager = Arc::new(Mutex::new(PtyProcessManager::new()));
         let process_controller = Arc::new(Mutex::new(ProcessController::new(pty_manager.clone())));
+        let process_registry = ProcessRegistry::new(
+            process_manager.clone(),
+            pty_manager.clone(),
+            system_monitor.clone(),
+            StateManager::get_state_path(),
+        );
 
         Self {
-            process_manager: Arc::new(Mutex::new(ProcessManager::new())),
-            system_monitor: Arc::new(Mutex::new(SystemMonitor::new())),
+            process_manager,
+            system_monitor,
             external_process_monitor: Arc::new(Mutex::new(ExternalProcessMonitor::new())),
             pty_manager,
             process_config_store: Arc::new(Mutex::new(ProcessConfigStore::new())),
             process_controller,
+            process_registry,
             config: Arc::new(RwLock::new(None)),
+            alert_manager: Arc::new(Mutex::new(AlertManager::new())),
+            metrics_exporter: Arc::new(Mutex::new(MetricsExporter::new())),
+            api_server: Arc::new(Mutex::new(ApiServer::new())),
+            event_recorder,
+            stale_servers: Arc::new(Mutex::new(StaleServerDetector::new())),
+            connection_enricher: Arc::new(ConnectionEnricher::new()),
+            disk_usage: Arc::new(Mutex::new(DiskUsageCache::new())),
+            kill_guard: Arc::new(Mutex::new(KillGuard::new())),
+        }
+    }
+}
+
+impl AppState {
+    /// The managers the HTTP API works on.
+    pub fn api_context(&self) -> ApiContext {
+        ApiContext {
+            process_manager: self.process_manager.clone(),
+            system_monitor: self.system_monitor.clone(),
         }
     }
 }
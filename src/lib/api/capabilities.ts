@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { FeatureCapabilities } from '$lib/types/capabilities';
+
+/**
+ * List each feature as available, degraded or unavailable, with the reason
+ *
+ * Pass `refresh` to look for missing tools again, e.g. after installing one.
+ */
+export async function getFeatureCapabilities(refresh = false): Promise<FeatureCapabilities> {
+	return await invoke('get_feature_capabilities', { refresh });
+}
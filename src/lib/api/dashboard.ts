@@ -0,0 +1,13 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { DashboardSections, DashboardSnapshot } from '$lib/types/dashboard';
+
+/**
+ * Get everything the dashboard shows in one call
+ *
+ * Sections that fail carry an error instead of failing the whole call.
+ */
+export async function getDashboardSnapshot(
+	options?: DashboardSections
+): Promise<DashboardSnapshot> {
+	return await invoke('get_dashboard_snapshot', { options });
+}
@@ -1,9 +1,15 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
+	ContainerDetails,
+	ContainerHealth,
 	ContainerInfo,
 	ContainerOperationResult,
 	ContainerStats,
+	DockerAvailabilityEvent,
 	DockerInfo,
+	DockerRuntimeStatus,
+	ExecResult,
 	ImageInfo
 } from '$lib/types/docker';
 
@@ -14,6 +20,24 @@ export async function getDockerInfo(): Promise<DockerInfo> {
 	return await invoke('get_docker_info');
 }
 
+/**
+ * Get daemon availability and the detected Docker runtime
+ */
+export async function getDockerRuntimeStatus(): Promise<DockerRuntimeStatus> {
+	return await invoke('get_docker_runtime_status');
+}
+
+/**
+ * Subscribe to Docker daemon availability changes
+ */
+export async function onDockerAvailabilityChanged(
+	handler: (event: DockerAvailabilityEvent) => void
+): Promise<UnlistenFn> {
+	return await listen<DockerAvailabilityEvent>('docker-availability-changed', (event) =>
+		handler(event.payload)
+	);
+}
+
 /**
  * List Docker containers
  * @param all If true, list all containers (including stopped). If false, only running containers.
@@ -92,3 +116,116 @@ export async function unpauseDockerContainer(
 ): Promise<ContainerOperationResult> {
 	return await invoke('unpause_docker_container', { containerId });
 }
+
+/**
+ * Inspect a container's configuration and state
+ * @param containerId Container ID
+ * @returns Details, or null when Docker is unavailable
+ */
+export async function inspectDockerContainer(
+	containerId: string
+): Promise<ContainerDetails | null> {
+	return await invoke('inspect_docker_container', { containerId });
+}
+
+/**
+ * Get a Docker container's health, mapped onto process health and status
+ * @param containerId Container ID
+ * @returns Health, or null when Docker is unavailable
+ */
+export async function getContainerHealth(containerId: string): Promise<ContainerHealth | null> {
+	return await invoke('get_container_health', { containerId });
+}
+
+/**
+ * Remove a Docker container
+ * @param containerId Container ID
+ * @param force Kill the container first if it is running
+ */
+export async function removeDockerContainer(
+	containerId: string,
+	force: boolean = false
+): Promise<ContainerOperationResult> {
+	return await invoke('remove_docker_container', { containerId, force });
+}
+
+/**
+ * Run a one-shot command inside a Docker container
+ * @param containerId Container ID
+ * @param cmd Command to run
+ * @param args Command arguments
+ * @param timeoutSecs Seconds to wait for the command (defaults to 30)
+ */
+export async function execInDockerContainer(
+	containerId: string,
+	cmd: string,
+	args: string[] = [],
+	timeoutSecs?: number
+): Promise<ExecResult> {
+	return await invoke('exec_in_docker_container', { containerId, cmd, args, timeoutSecs });
+}
+
+/**
+ * Stream a container's logs as `log-line` events
+ * @param containerId Container ID
+ * @param tail Number of backlog lines to include (all when omitted)
+ * @param follow Keep streaming new output until the container stops
+ * @returns Attachment ID used to filter events and stop the stream
+ */
+export async function streamContainerLogs(
+	containerId: string,
+	tail?: number,
+	follow: boolean = true
+): Promise<string> {
+	return await invoke('stream_container_logs', { containerId, tail, follow });
+}
+
+/**
+ * Stop a container log stream
+ * @param attachmentId Attachment ID returned by streamContainerLogs
+ */
+export async function stopContainerLogStream(attachmentId: string): Promise<void> {
+	return await invoke('stop_container_log_stream', { attachmentId });
+}
+
+/**
+ * Start background stats sampling for containers
+ * @param containerIds Containers to sample
+ * @param intervalMs Sampling interval in milliseconds (at least 1000)
+ */
+export async function startContainerStatsSampling(
+	containerIds: string[],
+	intervalMs: number
+): Promise<void> {
+	return await invoke('start_container_stats_sampling', { containerIds, intervalMs });
+}
+
+/**
+ * Stop background stats sampling
+ * @param containerIds Containers to stop (all when omitted)
+ * @returns Number of containers that stopped sampling
+ */
+export async function stopContainerStatsSampling(containerIds?: string[]): Promise<number> {
+	return await invoke('stop_container_stats_sampling', { containerIds });
+}
+
+/**
+ * Get recorded stats for a sampled container
+ * @param containerId Container ID
+ * @param seconds How far back to look
+ */
+export async function getContainerStatsHistory(
+	containerId: string,
+	seconds: number
+): Promise<ContainerStats[]> {
+	return await invoke('get_container_stats_history', { containerId, seconds });
+}
+
+/**
+ * Subscribe to samples recorded by the background stats sampler
+ */
+export async function onContainerStats(
+	handler: (stats: ContainerStats) => void
+): Promise<UnlistenFn> {
+	return await listen<ContainerStats>('container-stats', (event) => handler(event.payload));
+}
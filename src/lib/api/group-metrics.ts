@@ -0,0 +1,13 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { GroupMetrics } from '$lib/types/groupMetrics';
+
+/**
+ * Sum the CPU and memory usage of the managed processes tagged `tag` over
+ * the last `seconds`
+ *
+ * Fails with UNKNOWN_TAG, listing the known tags, if no managed process has
+ * the tag.
+ */
+export async function getGroupMetrics(tag: string, seconds: number): Promise<GroupMetrics> {
+	return await invoke('get_group_metrics', { tag, seconds });
+}
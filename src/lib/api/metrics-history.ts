@@ -0,0 +1,24 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { HistoryMetric, MetricPoint } from '$lib/types/metricsHistory';
+
+/**
+ * Read a metric of `system` or a managed process from the metrics history
+ *
+ * The range is aggregated into at most `maxPoints` buckets (default 200).
+ * Returns nothing unless `settings.metricsHistory` was enabled.
+ */
+export async function queryMetrics(
+	target: string,
+	metric: HistoryMetric,
+	from: Date,
+	to?: Date,
+	maxPoints?: number
+): Promise<MetricPoint[]> {
+	return await invoke('query_metrics', {
+		target,
+		metric,
+		from: from.toISOString(),
+		to: to?.toISOString(),
+		maxPoints
+	});
+}
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { NetworkSnapshot, NetworkInterfaceStats } from '$lib/types/network';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { NetworkSnapshot, NetworkInterfaceStats, NetworkRates } from '$lib/types/network';
 
 export async function getNetworkStats(): Promise<NetworkSnapshot> {
 	return await invoke('get_network_stats');
@@ -16,3 +17,29 @@ export async function clearNetworkHistory(): Promise<void> {
 export async function getNetworkInterfaces(): Promise<NetworkInterfaceStats[]> {
 	return await invoke('get_network_interfaces');
 }
+
+/** Returns whether the set of counted interfaces changed, which clears the history. */
+export async function setNetworkInterfaceFilter(
+	excludeInterfaces: string[],
+	includeLoopback: boolean
+): Promise<boolean> {
+	return await invoke('set_network_interface_filter', { excludeInterfaces, includeLoopback });
+}
+
+export async function getNetworkRates(): Promise<NetworkRates | null> {
+	return await invoke('get_network_rates');
+}
+
+export async function startNetworkSampling(intervalMs: number): Promise<void> {
+	await invoke('start_network_sampling', { intervalMs });
+}
+
+export async function stopNetworkSampling(): Promise<boolean> {
+	return await invoke('stop_network_sampling');
+}
+
+export async function onNetworkSnapshot(
+	handler: (snapshot: NetworkSnapshot) => void
+): Promise<UnlistenFn> {
+	return await listen<NetworkSnapshot>('network-snapshot', (event) => handler(event.payload));
+}
@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { PortForward } from '$lib/types/portForward';
+
+/**
+ * Forward connections to a local port on 127.0.0.1 to another host
+ *
+ * Pass 0 as the local port to pick a free one.
+ */
+export async function createPortForward(
+	localPort: number,
+	targetHost: string,
+	targetPort: number
+): Promise<PortForward> {
+	return await invoke('create_port_forward', { localPort, targetHost, targetPort });
+}
+
+/**
+ * List open port forwards with their traffic
+ */
+export async function listPortForwards(): Promise<PortForward[]> {
+	return await invoke('list_port_forwards');
+}
+
+/**
+ * Close a port forward and its connections
+ */
+export async function closePortForward(id: number): Promise<void> {
+	return await invoke('close_port_forward', { id });
+}
+
+/**
+ * Turn automatic forwarding of a remote container's published ports on or off
+ */
+export async function setContainerPortForwarding(
+	containerId: string,
+	enabled: boolean
+): Promise<PortForward[]> {
+	return await invoke('set_container_port_forwarding', { containerId, enabled });
+}
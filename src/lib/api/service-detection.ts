@@ -4,7 +4,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import type { ServiceInfo } from '$lib/types/service';
+import type { ServiceCacheStats, ServiceInfo } from '$lib/types/service';
 
 /**
  * Detect service from port information
@@ -41,13 +41,25 @@ export async function clearServiceCache(): Promise<void> {
 }
 
 /**
- * Get service detection cache size
+ * Invalidate the cached detection for a single port
  */
-export async function getServiceCacheSize(): Promise<number> {
+export async function invalidateServiceCacheEntry(port: number): Promise<boolean> {
   try {
-    return await invoke<number>('get_service_cache_size');
+    return await invoke<boolean>('invalidate_service_cache_entry', { port });
   } catch (error) {
-    console.error('Failed to get cache size:', error);
-    return 0;
+    console.error('Failed to invalidate service cache entry:', error);
+    return false;
+  }
+}
+
+/**
+ * Get service detection cache size and hit/miss counters
+ */
+export async function getServiceCacheStats(): Promise<ServiceCacheStats | null> {
+  try {
+    return await invoke<ServiceCacheStats>('get_service_cache_stats');
+  } catch (error) {
+    console.error('Failed to get cache stats:', error);
+    return null;
   }
 }
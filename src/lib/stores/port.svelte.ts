@@ -4,11 +4,25 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import type { PortInfo, SortBy, SortOrder } from '../types/port';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type {
+	ConnectionEnrichedEvent,
+	ConnectionFilter,
+	KillConfirmation,
+	KilledProcess,
+	KillResult,
+	KillTarget,
+	PortInfo,
+	SortBy,
+	SortOrder,
+	StaleServer
+} from '../types/port';
 
 class PortStore {
 	// State
 	ports = $state<PortInfo[]>([]);
+	staleServers = $state<StaleServer[]>([]);
+	recentlyKilled = $state<KilledProcess[]>([]);
 	loading = $state(false);
 	error = $state<string | null>(null);
 	lastScan = $state<Date | null>(null);
@@ -196,19 +210,123 @@ class PortStore {
 		}
 	}
 
+	/**
+	 * List active connections
+	 *
+	 * With enrich, remote addresses carry GeoIP data and cached host names;
+	 * names still being resolved arrive through onConnectionEnriched.
+	 */
+	async getActiveConnections(filter?: ConnectionFilter, enrich = false): Promise<PortInfo[]> {
+		try {
+			return await invoke<PortInfo[]>('get_active_connections', { filter, enrich });
+		} catch (err) {
+			this.error = err instanceof Error ? err.message : 'Failed to list connections';
+			console.error('List connections error:', err);
+			return [];
+		}
+	}
+
+	/**
+	 * Listen for host names resolved in the background
+	 */
+	async onConnectionEnriched(
+		handler: (event: ConnectionEnrichedEvent) => void
+	): Promise<UnlistenFn> {
+		return await listen<ConnectionEnrichedEvent>('connection-enriched', (event) =>
+			handler(event.payload)
+		);
+	}
+
 	/**
 	 * Kill process by port
+	 *
+	 * Graceful mode sends SIGTERM first and escalates to SIGKILL after a timeout.
 	 */
-	async killProcessByPort(port: number): Promise<boolean> {
+	async killProcessByPort(port: number, graceful = true): Promise<KillResult | null> {
 		try {
-			await invoke('kill_process_by_port', { port });
+			const result = await invoke<KillResult>('kill_process_by_port', { port, graceful });
 			// Refresh ports after killing
 			await this.scanPorts(true);
-			return true;
+			return result;
 		} catch (err) {
 			this.error = err instanceof Error ? err.message : 'Failed to kill process';
 			console.error('Kill process error:', err);
-			return false;
+			return null;
+		}
+	}
+
+	/**
+	 * Describe what killing a port or PID would terminate
+	 *
+	 * The returned token performs the kill with confirmKill within 30 seconds.
+	 */
+	async requestKill(target: KillTarget, graceful = true): Promise<KillConfirmation | null> {
+		try {
+			return await invoke<KillConfirmation>('request_kill', { target, graceful });
+		} catch (err) {
+			this.error = err instanceof Error ? err.message : 'Failed to request kill';
+			console.error('Request kill error:', err);
+			return null;
+		}
+	}
+
+	/**
+	 * Perform a kill from requestKill; each token works once
+	 */
+	async confirmKill(token: string): Promise<KilledProcess | null> {
+		try {
+			const killed = await invoke<KilledProcess>('confirm_kill', { token });
+			this.recentlyKilled = [killed, ...this.recentlyKilled];
+			await this.scanPorts(true);
+			return killed;
+		} catch (err) {
+			this.error = err instanceof Error ? err.message : 'Failed to kill process';
+			console.error('Confirm kill error:', err);
+			return null;
+		}
+	}
+
+	/**
+	 * Load processes killed through confirmKill, newest first
+	 */
+	async loadRecentlyKilled(): Promise<KilledProcess[]> {
+		try {
+			this.recentlyKilled = await invoke<KilledProcess[]>('recently_killed');
+		} catch (err) {
+			console.error('Recently killed error:', err);
+		}
+		return this.recentlyKilled;
+	}
+
+	/**
+	 * Find listening processes idle for maxIdleSecs in projects not open in Sentinel
+	 *
+	 * Idle time is measured across calls, so poll this while the list is shown.
+	 */
+	async findStaleDevServers(maxIdleSecs: number): Promise<StaleServer[]> {
+		try {
+			this.staleServers = await invoke<StaleServer[]>('find_stale_dev_servers', { maxIdleSecs });
+		} catch (err) {
+			this.error = err instanceof Error ? err.message : 'Failed to find stale dev servers';
+			console.error('Find stale dev servers error:', err);
+		}
+		return this.staleServers;
+	}
+
+	/**
+	 * Gracefully kill stale dev servers from the last findStaleDevServers
+	 */
+	async killStaleDevServers(pids: number[]): Promise<KillResult[]> {
+		try {
+			const results = await invoke<KillResult[]>('kill_stale_dev_servers', { pids });
+			const killed = new Set(results.map((result) => result.pid));
+			this.staleServers = this.staleServers.filter((server) => !killed.has(server.pid));
+			await this.scanPorts(true);
+			return results;
+		} catch (err) {
+			this.error = err instanceof Error ? err.message : 'Failed to kill stale dev servers';
+			console.error('Kill stale dev servers error:', err);
+			return [];
 		}
 	}
 
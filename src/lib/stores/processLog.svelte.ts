@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '$lib/types/error';
 
 export interface ProcessInfo {
 	name: string;
@@ -14,9 +15,77 @@ export interface ProcessInfo {
 }
 
 export interface LogLine {
+	/** When Sentinel received the line */
 	timestamp: string;
 	stream: 'stdout' | 'stderr';
 	line: string;
+	/** Time written at the start of the line, if any */
+	sourceTimestamp?: string;
+	/** Position among the process's lines; breaks ties between equal times */
+	seq?: number;
+	/** Level of a line parsed as JSON */
+	level?: LogLevel;
+	/** Other fields of a line parsed as JSON, nested keys dotted (`req.id`) */
+	fields?: Record<string, unknown>;
+}
+
+export type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error' | 'fatal';
+
+/** `source` orders by the time in the line when it has one */
+export type TimestampOrder = 'source' | 'received';
+
+export interface MergedLogLine extends LogLine {
+	process: string;
+	attachmentId?: string;
+}
+
+export interface LogSearchOptions {
+	regex?: boolean;
+	caseSensitive?: boolean;
+	/** RFC 3339 times bounding the lines searched */
+	since?: string;
+	until?: string;
+	/** Which time since and until apply to (default source) */
+	orderBy?: TimestampOrder;
+	stream?: 'stdout' | 'stderr';
+	/** Most recent matches kept per process (default 100) */
+	maxPerProcess?: number;
+	/** Lines shown before and after each match */
+	context?: number;
+	includeAttachments?: boolean;
+}
+
+/** Memory held by one log buffer */
+export interface LogMemoryUsage {
+	process: string;
+	attachmentId?: string;
+	lines: number;
+	/** Bytes of line text */
+	bytes: number;
+	maxLines: number;
+	/** Byte limit, null when unlimited */
+	maxBytes: number | null;
+}
+
+/** Returned by `get_log_memory_stats` */
+export interface LogMemoryStats {
+	totalLines: number;
+	totalBytes: number;
+	/** Largest first */
+	buffers: LogMemoryUsage[];
+}
+
+export interface LogMatch {
+	line: LogLine;
+	before: LogLine[];
+	after: LogLine[];
+}
+
+export interface ProcessLogMatches {
+	process: string;
+	attachmentId?: string;
+	matches: LogMatch[];
+	truncated: boolean;
 }
 
 class ProcessLogStore {
@@ -31,7 +100,7 @@ class ProcessLogStore {
 			const processes = await invoke<ProcessInfo[]>('list_processes');
 			this.processes = processes;
 		} catch (err) {
-			this.error = String(err);
+			this.error = errorMessage(err);
 			console.error('Failed to load processes:', err);
 		} finally {
 			this.loading = false;
@@ -56,6 +125,32 @@ class ProcessLogStore {
 		}
 	}
 
+	async getMergedLogs(
+		names?: string[],
+		orderBy: TimestampOrder = 'source',
+		includeAttachments = false
+	): Promise<MergedLogLine[]> {
+		try {
+			return await invoke<MergedLogLine[]>('get_merged_logs', {
+				names,
+				orderBy,
+				includeAttachments
+			});
+		} catch (err) {
+			console.error('Failed to get merged logs:', err);
+			throw err;
+		}
+	}
+
+	async getLogMemoryStats(): Promise<LogMemoryStats> {
+		try {
+			return await invoke<LogMemoryStats>('get_log_memory_stats');
+		} catch (err) {
+			console.error('Failed to get log memory stats:', err);
+			throw err;
+		}
+	}
+
 	async searchLogs(name: string, query: string): Promise<LogLine[]> {
 		try {
 			return await invoke<LogLine[]>('search_process_logs', { name, query });
@@ -65,6 +160,25 @@ class ProcessLogStore {
 		}
 	}
 
+	/** Lines parsed as JSON whose fields equal `filter`, e.g. `{ request_id: 'abc' }` */
+	async getStructuredLogs(name: string, filter: Record<string, string> = {}): Promise<LogLine[]> {
+		try {
+			return await invoke<LogLine[]>('get_structured_logs', { name, filter });
+		} catch (err) {
+			console.error(`Failed to get structured logs for ${name}:`, err);
+			throw err;
+		}
+	}
+
+	async searchAllLogs(query: string, options: LogSearchOptions = {}): Promise<ProcessLogMatches[]> {
+		try {
+			return await invoke<ProcessLogMatches[]>('search_all_logs', { query, options });
+		} catch (err) {
+			console.error(`Failed to search logs for '${query}':`, err);
+			throw err;
+		}
+	}
+
 	async clearLogs(name: string): Promise<void> {
 		try {
 			await invoke('clear_process_logs', { name });
@@ -0,0 +1,25 @@
+/**
+ * Feature Capability Types
+ * Matches Rust backend types from features/capabilities
+ */
+
+export type Feature = 'portScanning' | 'connectionTraffic' | 'docker';
+
+/** 'degraded' works through a fallback; 'unavailable' features return empty results */
+export type CapabilityStatus = 'available' | 'degraded' | 'unavailable';
+
+export interface FeatureCapability {
+	feature: Feature;
+	status: CapabilityStatus;
+	/** Tool or interface the feature uses (e.g. "lsof", "/proc") */
+	backend: string | null;
+	/** Why the feature is degraded or unavailable */
+	reason: string | null;
+}
+
+export interface FeatureCapabilities {
+	features: FeatureCapability[];
+	/** Command port scans run, when one is installed */
+	scanBackend: 'lsof' | 'ss' | 'netstat' | null;
+	detectedAt: string;
+}
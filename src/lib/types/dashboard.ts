@@ -0,0 +1,37 @@
+/**
+ * Dashboard Snapshot Types
+ * Matches Rust backend types from core/dashboard
+ */
+
+import type { ProcessInfo, SystemStats } from '../../types';
+import type { DockerInfo } from './docker';
+import type { NetworkSnapshot } from './network';
+import type { PortInfo } from './port';
+
+/** Sections to collect; omitted ones default to true */
+export interface DashboardSections {
+	processes?: boolean;
+	system?: boolean;
+	network?: boolean;
+	ports?: boolean;
+	docker?: boolean;
+}
+
+/** One section of a snapshot, with either its data or why it's missing */
+export interface DashboardSection<T> {
+	/** When the section finished collecting */
+	timestamp: string;
+	data?: T;
+	error?: string;
+}
+
+/** The requested sections of the dashboard */
+export interface DashboardSnapshot {
+	/** When the snapshot was started */
+	timestamp: string;
+	processes?: DashboardSection<ProcessInfo[]>;
+	system?: DashboardSection<SystemStats>;
+	network?: DashboardSection<NetworkSnapshot>;
+	ports?: DashboardSection<PortInfo[]>;
+	docker?: DashboardSection<DockerInfo>;
+}
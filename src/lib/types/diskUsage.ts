@@ -0,0 +1,41 @@
+/**
+ * Disk Usage Types
+ * Matches Rust backend types from core/disk_usage
+ */
+
+/** Size of a directory inside a process's working directory */
+export interface DirUsage {
+	/** Path relative to the working directory */
+	path: string;
+	bytes: number;
+}
+
+/** Returned by `get_process_disk_usage` */
+export interface DiskUsageReport {
+	/** The working directory, with symlinks resolved */
+	root: string;
+	totalBytes: number;
+	fileCount: number;
+	/** Largest immediate subdirectories, largest first */
+	topDirs: DirUsage[];
+	/** Cache directories that exist and can be passed to `clean_process_cache` */
+	cacheDirs: DirUsage[];
+	/** Whether directories deeper than the scan limit were left out */
+	truncated: boolean;
+	measuredAt: string;
+}
+
+/** Returned by `clean_process_cache` */
+export interface CleanReport {
+	removed: DirUsage[];
+	freedBytes: number;
+}
+
+/** Returned by `prune_logs_now` */
+export interface LogPruneSummary {
+	/** Rotated log files that were deleted */
+	filesRemoved: number;
+	bytesReclaimed: number;
+	/** Entries in the log directory that aren't process logs */
+	filesSkipped: number;
+}
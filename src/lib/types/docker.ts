@@ -2,6 +2,8 @@
  * Docker integration types
  */
 
+import type { HealthStatus } from './service';
+
 export interface PortMapping {
 	containerPort: number;
 	hostPort?: number;
@@ -24,6 +26,30 @@ export interface ContainerInfo {
 	networkTxBytes?: number;
 	created: string;
 	labels: Array<[string, string]>;
+	health: ContainerHealth;
+}
+
+export type ContainerHealthReason =
+	| 'healthCheckPassing'
+	| 'healthCheckFailing'
+	| 'healthCheckStarting'
+	| 'noHealthCheck'
+	| 'paused'
+	| 'restarting'
+	| 'oomKilled'
+	| 'exitedWithError'
+	| 'exited'
+	| 'created'
+	| 'dead';
+
+/** Container health in the terms used for managed processes */
+export interface ContainerHealth {
+	status: HealthStatus;
+	processStatus: 'Starting' | 'Running' | 'Stopped' | 'Crashed';
+	reason: ContainerHealthReason;
+	exitCode?: number;
+	failingStreak?: number;
+	message?: string;
 }
 
 export interface ContainerStats {
@@ -69,3 +95,80 @@ export interface ContainerOperationResult {
 	operation: string;
 	error?: string;
 }
+
+export interface ContainerDetails {
+	id: string;
+	fullId: string;
+	name: string;
+	image: string;
+	state: string;
+	exitCode?: number;
+	created?: string;
+	startedAt?: string;
+	entrypoint: string[];
+	cmd: string[];
+	workingDir?: string;
+	env: string[];
+	mounts: MountInfo[];
+	network: ContainerNetworkSettings;
+	restartPolicy?: RestartPolicyInfo;
+	health?: HealthInfo;
+}
+
+export interface MountInfo {
+	mountType: string;
+	source?: string;
+	destination: string;
+	mode?: string;
+	readWrite: boolean;
+}
+
+export interface ContainerNetworkSettings {
+	ipAddress?: string;
+	gateway?: string;
+	ports: PortMapping[];
+	networks: NetworkEndpoint[];
+}
+
+export interface NetworkEndpoint {
+	name: string;
+	ipAddress?: string;
+	gateway?: string;
+	macAddress?: string;
+	aliases: string[];
+}
+
+export interface RestartPolicyInfo {
+	name: string;
+	maxRetryCount?: number;
+}
+
+export interface HealthInfo {
+	status: string;
+	failingStreak: number;
+	lastOutput?: string;
+	lastExitCode?: number;
+}
+
+export interface ExecResult {
+	success: boolean;
+	containerId: string;
+	exitCode?: number;
+	stdout: string;
+	stderr: string;
+	timedOut: boolean;
+	error?: string;
+}
+
+export type DockerAvailability = 'connected' | 'starting' | 'unavailable';
+
+export interface DockerAvailabilityEvent {
+	status: DockerAvailability;
+	available: boolean;
+}
+
+export interface DockerRuntimeStatus {
+	available: boolean;
+	starting: boolean;
+	runtime?: 'colima' | 'docker-desktop' | 'dockerd' | 'podman';
+}
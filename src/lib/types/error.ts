@@ -0,0 +1,73 @@
+/**
+ * Error Types
+ * Matches Rust backend types from error.rs
+ */
+
+/** Stable error codes; never renamed, only added to */
+export type ErrorCode =
+	| 'PROCESS_NOT_FOUND'
+	| 'SPAWN_FAILED'
+	| 'PROCESS_ALREADY_RUNNING'
+	| 'PROCESS_NOT_RUNNING'
+	| 'START_FAILED_EARLY'
+	| 'READINESS_TIMEOUT'
+	| 'COMPLETION_TIMEOUT'
+	| 'NOT_COMPLETED'
+	| 'STOP_TIMEOUT'
+	| 'INVALID_CONFIG'
+	| 'CONFIG_NOT_FOUND'
+	| 'CONFIG_PARSE_FAILED'
+	| 'FILE_IO'
+	| 'MONITORING'
+	| 'DEPENDENCY_CYCLE'
+	| 'UNKNOWN_DEPENDENCY'
+	| 'INCLUDE_CYCLE'
+	| 'EXCLUDED_DEPENDENCY'
+	| 'DEPENDENCY_NOT_RUNNING'
+	| 'UNKNOWN_TAG'
+	| 'PATH_NOT_ALLOWED'
+	| 'INVALID_PRIORITY'
+	| 'INVALID_CPU_AFFINITY'
+	| 'INVALID_SCHEDULE'
+	| 'INVALID_SEARCH_PATTERN'
+	| 'SCHEDULING_FAILED'
+	| 'RESTART_LIMIT_EXCEEDED'
+	| 'RESTART_UNAVAILABLE'
+	| 'NOTIFICATION_FAILED'
+	| 'IO'
+	| 'PORT_DISCOVERY'
+	| 'PORT_NOT_FOUND'
+	| 'PORT_CONFLICT'
+	| 'PORT_IN_USE'
+	| 'FORWARD_PORT_IN_USE'
+	| 'KILL_CONFIRMATION_INVALID'
+	| 'INVALID_GEO_IP_DATABASE'
+	| 'DOCKER'
+	| 'METRICS_HISTORY'
+	| 'ALREADY_RUNNING'
+	| 'UNSUPPORTED'
+	| 'INVALID_INPUT'
+	| 'OTHER';
+
+/** Error returned by commands */
+export interface SentinelError {
+	code: ErrorCode;
+	/** Message in the configured locale (`settings.locale`) */
+	message: string;
+	/** Placeholder values of the message, formatted for display */
+	params: Record<string, string>;
+	/** Values the message was built from, e.g. `{ port, process }` for PORT_IN_USE */
+	details: Record<string, unknown> | null;
+}
+
+/** Whether a rejected command value is a structured error */
+export function isSentinelError(e: unknown): e is SentinelError {
+	return typeof e === 'object' && e !== null && 'code' in e && 'message' in e;
+}
+
+/** A readable message for any rejected command value */
+export function errorMessage(e: unknown): string {
+	if (isSentinelError(e)) return e.message;
+	if (e instanceof Error) return e.message;
+	return String(e);
+}
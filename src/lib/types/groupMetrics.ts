@@ -0,0 +1,30 @@
+/**
+ * Group Metrics Types
+ * Matches Rust backend types from core/group_metrics
+ */
+
+/** Summed usage of a tag's processes at one point in time */
+export interface GroupPoint {
+	timestamp: string;
+	/** Percent of one core */
+	cpu: number;
+	/** Bytes */
+	memory: number;
+	/** Members with a sample at this point */
+	reporting: number;
+	/** Some member had no sample and counted as zero */
+	partial: boolean;
+}
+
+/** Usage of the processes with a tag, summed */
+export interface GroupMetrics {
+	tag: string;
+	members: string[];
+	sampleIntervalMs: number;
+	/** Oldest first */
+	series: GroupPoint[];
+	cpu: number;
+	memory: number;
+	/** Some point or the current totals are missing a member */
+	partial: boolean;
+}
@@ -0,0 +1,31 @@
+/**
+ * Start Preview Types
+ * Matches Rust backend types from core/launch
+ */
+
+/** A port a process declares */
+export interface PortPreview {
+	port: number;
+	/** Whether something else already listens on it */
+	inUse: boolean;
+	/** Name of the process listening on it, if it could be found */
+	owner: string | null;
+}
+
+/** Returned by `preview_process_start`: what starting a process would do */
+export interface PreviewResult {
+	name: string;
+	/** Absolute path of the binary that would run, if it can be found */
+	exePath: string | null;
+	/** Program and arguments, with secret option values masked */
+	argv: string[];
+	/** The full environment, with secret values masked */
+	env: Record<string, string>;
+	/** Canonical working directory */
+	cwd: string | null;
+	ports: PortPreview[];
+	/** Problems that would make the start fail */
+	errors: string[];
+	/** Things that would not stop the start but probably need a look */
+	warnings: string[];
+}
@@ -0,0 +1,18 @@
+/**
+ * Metrics History Types
+ * Matches Rust backend types from core/metrics_history
+ */
+
+/** Memory is in bytes, disk is the used percentage of the fullest disk */
+export type HistoryMetric = 'cpu' | 'memory' | 'disk' | 'network_rx' | 'network_tx';
+
+/** Samples aggregated over one bucket of a range query */
+export interface MetricPoint {
+	/** Start of the bucket */
+	timestamp: string;
+	avg: number;
+	min: number;
+	max: number;
+	/** Samples in the bucket */
+	samples: number;
+}
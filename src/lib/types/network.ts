@@ -11,16 +11,36 @@ export interface NetworkSnapshot {
 	totalPacketsReceived: number;
 	processes: ProcessNetworkStats[];
 	protocolStats: ProtocolStats;
+	interfaces: InterfaceTraffic[];
+}
+
+/** Traffic on one interface since the previous collection */
+export interface InterfaceTraffic {
+	name: string;
+	bytesSent: number;
+	bytesReceived: number;
+}
+
+export interface NetworkRates {
+	timestamp: string;
+	intervalMs: number;
+	bytesSentPerSec: number;
+	bytesReceivedPerSec: number;
+	packetsSentPerSec: number;
+	packetsReceivedPerSec: number;
 }
 
 export interface ProcessNetworkStats {
 	pid: number;
 	processName: string;
-	bytesSent: number;
-	bytesReceived: number;
-	packetsSent: number;
-	packetsReceived: number;
+	/** null when the platform has no per-process accounting ("unavailable") */
+	bytesSent: number | null;
+	bytesReceived: number | null;
+	/** Bytes/sec since the previous collection; null until two samples exist */
+	sendRate: number | null;
+	receiveRate: number | null;
 	connections: number;
+	ports: number[];
 }
 
 export interface ProtocolStats {
@@ -40,5 +60,10 @@ export interface NetworkInterfaceStats {
 	errorsReceived: number;
 	macAddress: string | null;
 	interfaceType: string;
+	kind: InterfaceKind;
 	isUp: boolean;
+	/** Counted in totals and history under the current interface filter */
+	selected: boolean;
 }
+
+export type InterfaceKind = 'ethernet' | 'wifi' | 'virtual' | 'loopback' | 'unknown';
@@ -13,6 +13,29 @@ export interface PortInfo {
 	remoteAddress: string | null;
 	command: string | null;
 	traffic: NetworkTraffic;
+	/** Set when connections are listed with enrichment */
+	remoteInfo?: RemoteInfo;
+}
+
+/**
+ * Host name and GeoIP data of a remote address
+ */
+export interface RemoteInfo {
+	hostname?: string;
+	countryCode?: string;
+	country?: string;
+	city?: string;
+	asn?: number;
+	organization?: string;
+}
+
+/**
+ * Payload of the connection-enriched event, sent when a host name is resolved
+ */
+export interface ConnectionEnrichedEvent {
+	/** Remote IP address, without port */
+	address: string;
+	info: RemoteInfo;
 }
 
 export type Protocol = 'TCP' | 'UDP';
@@ -27,6 +50,84 @@ export interface NetworkTraffic {
 	connections: number;
 }
 
+/**
+ * Server-side connection filter; unset fields match everything
+ */
+export interface ConnectionFilter {
+	protocol?: Protocol;
+	state?: PortState;
+	localPortMin?: number;
+	localPortMax?: number;
+	remoteAddress?: string;
+}
+
+export type KillSignal = 'SIGTERM' | 'SIGKILL';
+
+export interface KillResult {
+	port: number;
+	pid: number;
+	processName: string;
+	signal: KillSignal;
+}
+
+/**
+ * Process a kill is requested for: whatever listens on a port, or a PID
+ */
+export type KillTarget = { port: number } | { pid: number };
+
+/**
+ * What a confirmed kill would terminate
+ */
+export interface KillCandidate {
+	pid: number;
+	/** Port the kill was requested for */
+	port?: number;
+	processName: string;
+	/** Full command line */
+	command?: string;
+	cwd?: string;
+	/** User the process runs as */
+	owner?: string;
+	startedAt?: string;
+	/** Sentinel-managed process with this PID, stopped through Sentinel instead */
+	managedProcess?: string;
+	/** Configured process that can start it again */
+	configProcess?: string;
+	warning?: string;
+}
+
+/**
+ * A pending kill, performed by confirmKill within 30 seconds
+ */
+export interface KillConfirmation {
+	token: string;
+	expiresAt: string;
+	candidate: KillCandidate;
+}
+
+/**
+ * A process killed through confirmKill
+ */
+export interface KilledProcess extends KillCandidate {
+	killedAt: string;
+	/** Missing when the process was stopped through Sentinel */
+	signal?: KillSignal;
+}
+
+/**
+ * Idle listening process from a project that isn't open in Sentinel
+ */
+export interface StaleServer {
+	pid: number;
+	processName: string;
+	command?: string;
+	cwd: string;
+	ports: number[];
+	uptimeSecs: number;
+	/** How long it has been idle, as far as the samples go back */
+	idleSecs: number;
+}
+
 /**
  * UI-specific types
  */
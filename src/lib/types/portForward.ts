@@ -0,0 +1,21 @@
+/**
+ * Port Forwarding Types
+ * Matches Rust backend types from features/port_forward
+ */
+
+export interface PortForward {
+	id: number;
+	/** Port listened on at 127.0.0.1 */
+	localPort: number;
+	targetHost: string;
+	targetPort: number;
+	/** Set for forwards opened automatically for a container */
+	containerId: string | null;
+	bytesSent: number;
+	bytesReceived: number;
+	activeConnections: number;
+	totalConnections: number;
+	/** Latest failure to reach the target */
+	lastError: string | null;
+	createdAt: string;
+}
@@ -28,6 +28,19 @@ export interface ServiceInfo {
   confidence: number;
   icon: string;
   detected_at: string;
+  /** Container publishing the port, when Docker's port proxy listens on it */
+  container_id?: string;
+  container_name?: string;
+}
+
+/**
+ * Service detection cache statistics
+ */
+export interface ServiceCacheStats {
+  size: number;
+  hits: number;
+  misses: number;
+  ttlSecs: number;
 }
 
 /**
@@ -0,0 +1,20 @@
+/**
+ * Shutdown Types
+ * Matches Rust backend types from core/shutdown
+ */
+
+/** What happens to running processes when Sentinel quits */
+export type OnExit = 'stopAll' | 'detachAndPersist';
+
+/** Payload of the `shutdown-progress` event */
+export interface ShutdownProgress {
+	mode: OnExit;
+	/** Number of processes to stop or detach */
+	total: number;
+	/** Number of processes stopped or detached so far */
+	completed: number;
+	/** The process that just finished, if any */
+	process?: string | null;
+	/** Whether the shutdown is over */
+	done: boolean;
+}
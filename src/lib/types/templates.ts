@@ -0,0 +1,26 @@
+/**
+ * Template Types
+ * Matches Rust backend types from core/templates
+ */
+
+/** Where a template comes from */
+export type TemplateSource = 'builtin' | 'user';
+
+/** A `{{VARIABLE}}` placeholder and the value used when none is given */
+export interface TemplateVariable {
+	name: string;
+	default?: string;
+}
+
+/** Returned by `list_templates` and `save_process_as_template` */
+export interface TemplateSummary {
+	name: string;
+	description: string;
+	source: TemplateSource;
+	/** Only built-in templates have one */
+	icon?: string;
+	command: string;
+	args: string[];
+	/** Variables to fill in, `NAME` included */
+	variables: TemplateVariable[];
+}
@@ -0,0 +1,64 @@
+/**
+ * Workspace Types
+ * Matches Rust backend types from core/workspace
+ */
+
+/** `merge` adds imported processes to the current config; `replace` swaps it out */
+export type ImportMode = 'merge' | 'replace';
+
+/** Describes a workspace archive */
+export interface WorkspaceManifest {
+	formatVersion: number;
+	/** Version of Sentinel that wrote the archive */
+	sentinelVersion: string;
+	createdAt: string;
+	/** Home directory on the machine the archive was written on */
+	homeDir?: string;
+	/** Config files relative to the root config's directory, root first */
+	configFiles: string[];
+}
+
+/** Returned by `export_workspace` */
+export interface WorkspaceExportReport {
+	path: string;
+	manifest: WorkspaceManifest;
+	stateEntries: number;
+	ptyConfigs: number;
+	/** Files that were left out, and why */
+	warnings: string[];
+}
+
+/** A path in an imported config that points into the old home directory */
+export interface HomePathReference {
+	/** Config file, or the terminal config, the path is in */
+	file: string;
+	/** 1-based line of the config file */
+	line?: number;
+	text: string;
+}
+
+/** Returned by `preview_workspace_import` */
+export interface WorkspacePreview {
+	manifest: WorkspaceManifest;
+	mode: ImportMode;
+	processes: string[];
+	/** Imported processes with the same name as a configured one */
+	conflicts: string[];
+	homePaths: HomePathReference[];
+	/** Where `rewriteHome` would point them */
+	newHomeDir?: string;
+	stateEntries: number;
+	ptyConfigs: number;
+}
+
+/** Returned by `import_workspace` */
+export interface WorkspaceImportReport {
+	mode: ImportMode;
+	filesWritten: string[];
+	/** Copy of the previous root config */
+	backup?: string;
+	processes: string[];
+	/** Configured processes replaced by imported ones */
+	replaced: string[];
+	stateEntries: number;
+}
@@ -1,5 +1,8 @@
 import { invoke } from '@tauri-apps/api/core';
 
+/** inherit: Sentinel's environment; clean: PATH, HOME, USER, LANG and TERM only; shell: the login shell's */
+export type EnvMode = 'inherit' | 'clean' | 'shell';
+
 export interface ProcessConfig {
   id: string;
   name: string;
@@ -11,6 +14,12 @@ export interface ProcessConfig {
   port?: number;
   autoStart: boolean;
   healthCheckUrl?: string;
+  /** Scheduling priority, from -20 (highest) to 19 (lowest) */
+  nice?: number;
+  /** CPU cores (0-based) the process may run on */
+  cpuAffinity?: number[];
+  /** Environment the process starts from, before envVars is added */
+  envMode?: EnvMode;
   createdAt: string;
   updatedAt: string;
 }
@@ -39,6 +48,8 @@ export interface FrameworkDetection {
   suggestedCommand: string;
   suggestedArgs: string[];
   suggestedPort?: number;
+  /** Script names from package.json */
+  availableScripts: string[];
 }
 
 export interface ProcessTemplate {
@@ -55,6 +66,8 @@ export interface ProcessTemplate {
 
 export interface DetectedProject {
   path: string;
+  /** Path relative to the scan root ("." for the root itself) */
+  relativePath: string;
   name: string;
   frameworkType: string;
   confidence: number;
@@ -64,6 +77,15 @@ export interface DetectedProject {
   packageManager?: string;
   detectedFiles: string[];
   envVars: Record<string, string>;
+  /** Script names from package.json */
+  availableScripts: string[];
+}
+
+export interface ProjectScanResult {
+  projects: DetectedProject[];
+  /** True when the scan stopped early at the directory limit */
+  truncated: boolean;
+  directoriesVisited: number;
 }
 
 class ProcessConfigStore {
@@ -119,6 +141,13 @@ class ProcessConfigStore {
     }
   }
 
+  /**
+   * Fetch the real value of a masked env var. Only call on explicit user action.
+   */
+  async revealEnvValue(name: string, key: string): Promise<string> {
+    return await invoke<string>('reveal_process_env_value', { name, key });
+  }
+
   async deleteConfig(id: string) {
     try {
       await invoke('delete_process_config', { id });
@@ -166,8 +195,8 @@ class ProcessConfigStore {
     return await invoke('detect_framework_type', { workingDir });
   }
 
-  async scanDirectory(dirPath: string): Promise<DetectedProject[]> {
-    return await invoke('scan_directory_for_projects', { dirPath });
+  async scanDirectory(dirPath: string, maxDepth?: number): Promise<ProjectScanResult> {
+    return await invoke('scan_directory_for_projects', { dirPath, maxDepth });
   }
 
   async getTemplates(): Promise<ProcessTemplate[]> {
@@ -1,6 +1,31 @@
 import { writable, derived } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
-import type { ProcessInfo, SystemStats } from '../types';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { errorMessage } from '$lib/types/error';
+import type { PreviewResult } from '$lib/types/launch';
+import type {
+  Alert,
+  BulkOutcome,
+  ConfigFileChangedEvent,
+  EnvFileChangedEvent,
+  EventFilter,
+  GpuStats,
+  ImportReport,
+  ImportSource,
+  JournalEvent,
+  ManagedProcessSummary,
+  OrphanInfo,
+  ProcessConfig,
+  ProcessInfo,
+  ProcessKind,
+  ProcessOverrides,
+  ProcessTreeNode,
+  StartupReport,
+  SystemDetails,
+  SystemStats,
+  TopProcessSort,
+  TopProcesses
+} from '../types';
 
 export const processes = writable<ProcessInfo[]>([]);
 export const systemStats = writable<SystemStats | null>(null);
@@ -47,6 +72,49 @@ export async function fetchSystemStats() {
   }
 }
 
+/**
+ * Fetch load averages, temperatures and mounted disks from the backend
+ */
+export async function fetchSystemDetails(): Promise<SystemDetails> {
+  try {
+    return await invoke<SystemDetails>('get_system_details');
+  } catch (e) {
+    throw new Error(
+      e instanceof Error ? e.message : 'Failed to fetch system details'
+    );
+  }
+}
+
+/**
+ * Fetch the latest GPU usage from the backend
+ */
+export async function fetchGpuStats(): Promise<GpuStats[]> {
+  try {
+    return await invoke<GpuStats[]>('get_gpu_stats');
+  } catch (e) {
+    throw new Error(
+      e instanceof Error ? e.message : 'Failed to fetch GPU stats'
+    );
+  }
+}
+
+/**
+ * Fetch a page of the heaviest processes on the system
+ */
+export async function fetchTopProcesses(
+  sortBy: TopProcessSort = 'cpu',
+  limit = 50,
+  offset = 0
+): Promise<TopProcesses> {
+  try {
+    return await invoke<TopProcesses>('get_top_processes', { sortBy, limit, offset });
+  } catch (e) {
+    throw new Error(
+      e instanceof Error ? e.message : 'Failed to fetch top processes'
+    );
+  }
+}
+
 /**
  * Start a process
  */
@@ -59,6 +127,17 @@ export async function startProcess(name: string): Promise<void> {
   }
 }
 
+/**
+ * Preview what starting a process would run, without starting it
+ */
+export async function previewProcessStart(config: ProcessConfig): Promise<PreviewResult> {
+  try {
+    return await invoke<PreviewResult>('preview_process_start', { config });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
 /**
  * Stop a process
  */
@@ -83,6 +162,246 @@ export async function restartProcess(name: string): Promise<void> {
   }
 }
 
+/**
+ * Start the processes in the config file, in dependency order, and report
+ * what came up
+ */
+export async function startAllProcesses(profiles?: string[]): Promise<StartupReport> {
+  try {
+    const report = await invoke<StartupReport>('start_all_processes', { profiles });
+    await fetchProcesses();
+    return report;
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Start every process with a tag, in dependency order
+ */
+export async function startProcessesByTag(tag: string): Promise<BulkOutcome[]> {
+  try {
+    const outcomes = await invoke<BulkOutcome[]>('start_processes_by_tag', { tag });
+    await fetchProcesses();
+    return outcomes;
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Stop every process with a tag, dependents first
+ */
+export async function stopProcessesByTag(tag: string): Promise<BulkOutcome[]> {
+  try {
+    const outcomes = await invoke<BulkOutcome[]>('stop_processes_by_tag', { tag });
+    await fetchProcesses();
+    return outcomes;
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Restart every process with a tag
+ */
+export async function restartProcessesByTag(tag: string): Promise<BulkOutcome[]> {
+  try {
+    const outcomes = await invoke<BulkOutcome[]>('restart_processes_by_tag', { tag });
+    await fetchProcesses();
+    return outcomes;
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Next `count` start times of a scheduled process, as ISO 8601 local times
+ */
+export async function getSchedulePreview(
+  name: string,
+  count = 5
+): Promise<string[]> {
+  try {
+    return await invoke<string[]>('get_schedule_preview', { name, count });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Wait up to `timeoutMs` for a running process to become ready
+ */
+export async function waitForReady(
+  name: string,
+  timeoutMs: number
+): Promise<ProcessInfo> {
+  try {
+    return await invoke<ProcessInfo>('wait_for_ready', { name, timeoutMs });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Copy a process under a new name with overrides, optionally saving and starting the copy
+ */
+export async function cloneProcess(
+  name: string,
+  newName: string,
+  overrides: ProcessOverrides,
+  save = false,
+  start = false
+): Promise<ProcessConfig> {
+  try {
+    return await invoke<ProcessConfig>('clone_process', { name, newName, overrides, save, start });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * List piped and PTY processes together
+ */
+export async function listAllManagedProcesses(): Promise<ManagedProcessSummary[]> {
+  try {
+    return await invoke<ManagedProcessSummary[]>('list_all_managed_processes');
+  } catch (e) {
+    throw new Error(e instanceof Error ? e.message : 'Failed to list processes');
+  }
+}
+
+/**
+ * List a process started outside Sentinel, which it monitors but never restarts
+ */
+export async function monitorExternalProcess(
+  pid: number,
+  displayName: string
+): Promise<ManagedProcessSummary> {
+  try {
+    return await invoke<ManagedProcessSummary>('monitor_external_process', { pid, displayName });
+  } catch (e) {
+    throw new Error(e instanceof Error ? e.message : 'Failed to monitor process');
+  }
+}
+
+/**
+ * Stop a piped, PTY or external process
+ */
+export async function stopAnyProcess(kind: ProcessKind, id: string): Promise<void> {
+  try {
+    await invoke('stop_any_process', { kind, id });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(e instanceof Error ? e.message : 'Failed to stop process');
+  }
+}
+
+/**
+ * Restart a piped or PTY process
+ */
+export async function restartAnyProcess(kind: ProcessKind, id: string): Promise<void> {
+  try {
+    await invoke('restart_any_process', { kind, id });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(e instanceof Error ? e.message : 'Failed to restart process');
+  }
+}
+
+/**
+ * Change the priority (nice value) of a running process
+ */
+export async function setProcessPriority(name: string, nice: number): Promise<void> {
+  try {
+    await invoke('set_process_priority', { name, nice });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Get a running process and the tree of processes it spawned
+ */
+export async function getProcessTree(name: string): Promise<ProcessTreeNode> {
+  try {
+    return await invoke<ProcessTreeNode>('get_process_tree', { name });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Get the processes a crashed process left running
+ */
+export async function getOrphanedChildren(name: string): Promise<OrphanInfo[]> {
+  try {
+    return await invoke<OrphanInfo[]>('get_orphaned_children', { name });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Restrict a running process to the given CPU cores
+ */
+export async function setProcessAffinity(name: string, cores: number[]): Promise<void> {
+  try {
+    await invoke('set_process_affinity', { name, cores });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Write text to the stdin of a process started with stdinMode: piped
+ */
+export async function writeProcessStdin(name: string, data: string): Promise<void> {
+  try {
+    await invoke('write_process_stdin', { name, data });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Send a signal such as HUP or USR1 to a process (Unix only)
+ */
+export async function sendProcessSignal(name: string, signal: string): Promise<void> {
+  try {
+    await invoke('send_process_signal', { name, signal });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Pause a process and its children without losing their state (Unix only)
+ */
+export async function pauseProcess(name: string): Promise<void> {
+  try {
+    await invoke('pause_process', { name });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Resume a paused process
+ */
+export async function resumeProcess(name: string): Promise<void> {
+  try {
+    await invoke('resume_process', { name });
+    await fetchProcesses();
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
 /**
  * Get logs for a process
  */
@@ -106,6 +425,95 @@ export async function stopAllProcesses(): Promise<void> {
   }
 }
 
+/**
+ * List alerts that are currently firing
+ */
+export async function listActiveAlerts(): Promise<Alert[]> {
+  try {
+    return await invoke<Alert[]>('list_active_alerts');
+  } catch (e) {
+    throw new Error(e instanceof Error ? e.message : 'Failed to list alerts');
+  }
+}
+
+/**
+ * Acknowledge a firing alert
+ */
+export async function ackAlert(id: string): Promise<Alert> {
+  try {
+    return await invoke<Alert>('ack_alert', { id });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Fetch past and firing alerts, most recent first
+ */
+export async function fetchAlertHistory(limit = 100): Promise<Alert[]> {
+  try {
+    return await invoke<Alert[]>('get_alert_history', { limit });
+  } catch (e) {
+    throw new Error(
+      e instanceof Error ? e.message : 'Failed to fetch alert history'
+    );
+  }
+}
+
+/**
+ * Send a sample event to a notification sink from settings.notificationSinks
+ */
+export async function testNotificationSink(name: string): Promise<void> {
+  try {
+    await invoke('test_notification_sink', { name });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
+/**
+ * Query the event journal, oldest first
+ */
+export async function queryEvents(
+  filter?: EventFilter,
+  since?: Date,
+  limit = 500
+): Promise<JournalEvent[]> {
+  try {
+    return await invoke<JournalEvent[]>('query_events', {
+      filter,
+      since: since?.toISOString(),
+      limit
+    });
+  } catch (e) {
+    throw new Error(
+      e instanceof Error ? e.message : 'Failed to query event journal'
+    );
+  }
+}
+
+/**
+ * Convert a PM2 or Docker Compose file into a Sentinel config, writing it to
+ * `output` when given
+ */
+export async function importConfig(
+  source: ImportSource,
+  path: string,
+  output?: string,
+  force = false
+): Promise<ImportReport> {
+  try {
+    return await invoke<ImportReport>('import_config', {
+      source,
+      path,
+      output,
+      force
+    });
+  } catch (e) {
+    throw new Error(errorMessage(e));
+  }
+}
+
 /**
  * Start polling for updates
  */
@@ -123,3 +531,25 @@ export function startPolling(intervalMs: number = 2000) {
   // Return cleanup function
   return () => clearInterval(interval);
 }
+
+/**
+ * Subscribe to edits of the config file made outside the app
+ */
+export async function onConfigFileChanged(
+  handler: (event: ConfigFileChangedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<ConfigFileChangedEvent>('config-file-changed', (event) =>
+    handler(event.payload)
+  );
+}
+
+/**
+ * Subscribe to edits of the env files of running processes
+ */
+export async function onEnvFileChanged(
+  handler: (event: EnvFileChangedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<EnvFileChangedEvent>('env-file-changed', (event) =>
+    handler(event.payload)
+  );
+}
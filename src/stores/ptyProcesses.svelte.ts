@@ -78,6 +78,18 @@ class PtyProcessStore {
 		await this.refresh();
 	}
 
+	async resize(processId: string, rows: number, cols: number) {
+		await invoke('resize_pty', { processId, rows, cols });
+	}
+
+	async write(processId: string, data: string) {
+		await invoke('write_to_pty', { processId, data });
+	}
+
+	async getScrollback(processId: string): Promise<string> {
+		return await invoke<string>('get_pty_scrollback', { processId });
+	}
+
 	getProcess(processId: string): PtyProcess | undefined {
 		return this.processes.find(p => p.process_id === processId);
 	}
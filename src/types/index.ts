@@ -19,8 +19,10 @@ export type ProcessState =
   | 'stopped'
   | 'starting'
   | 'running'
+  | 'paused'
   | 'stopping'
   | { crashed: { exit_code: number } }
+  | { completed: { exit_code: number } }
   | { failed: { reason: string } };
 
 /**
@@ -33,10 +35,242 @@ export interface ProcessInfo {
   state: ProcessState;
   pid: number | null;
   started_at: string | null;
+  /** CPU usage in percent of one core, 0 until measured */
   cpu_usage: number;
+  /** CPU usage in percent of one core (up to 100 × cores), null until sampled twice */
+  cpu_usage_percent_of_core?: number | null;
+  /** CPU usage in percent of all cores together (0-100), null until sampled twice */
+  cpu_usage_percent_of_total?: number | null;
   memory_usage: number;
+  /** CPU usage including descendants, when aggregateChildUsage is enabled */
+  cpu_usage_tree?: number | null;
+  /** Memory usage including descendants, when aggregateChildUsage is enabled */
+  memory_usage_tree?: number | null;
+  /** Scheduling priority (nice value), if known */
+  nice?: number | null;
+  /** Open file descriptors (handles on Windows), null when they can't be counted */
+  open_fds?: number | null;
+  /** Number of threads, null when they can't be counted */
+  thread_count?: number | null;
   uptime?: string;
   restart_count?: number;
+  /** Exit code of the last run, once it has exited (null if killed by a signal) */
+  exit_code?: number | null;
+  /** Tags from the process configuration */
+  tags: string[];
+  /** Whether the readiness probe succeeded (true once running without one) */
+  ready: boolean;
+  /** Milliseconds from starting to being ready, once ready */
+  time_to_ready_ms?: number | null;
+  /** Latest health check result while running (when there is a health check) */
+  health?: HealthResult | null;
+  /** Absolute path of the executable, once started */
+  exe_path?: string | null;
+  /** Full argument vector, program first, once started */
+  argv: string[];
+  /** Canonical working directory, once started */
+  resolved_cwd?: string | null;
+  /** PID of the process group leader, while running */
+  pgid?: number | null;
+  /** The PORT it was started with; a warm restart with warmRestartFreePort moves it */
+  port?: number | null;
+  /** Recent usage, with list_processes({ includeHistory: true }) */
+  history?: ProcessHistory;
+  /** 'container' when CPU and memory include linked Docker containers */
+  usage_source?: UsageSource;
+  /** Usage of the linked Docker containers, when they were found */
+  container?: ContainerUsage;
+  /** Why the process was last restarted, if it has been since it was started */
+  last_restart_reason?: RestartReason | null;
+  /** Time of the last restart */
+  last_restart_at?: string | null;
+}
+
+/**
+ * Why a process was restarted
+ *
+ * @glinr/sentinel-core
+ */
+export type RestartReason =
+  | { type: 'user_requested' }
+  | { type: 'crash_auto_restart'; exit_code: number }
+  | { type: 'file_changed'; path: string }
+  | { type: 'policy'; name: string }
+  | { type: 'config_reload' };
+
+/**
+ * Where a process's CPU and memory figures come from
+ *
+ * @glinr/sentinel-core
+ */
+export type UsageSource = 'process' | 'container';
+
+/**
+ * Resource usage of the Docker containers a process runs
+ *
+ * Containers are found by the process's containerRef setting, or by the
+ * --name, --label or Compose project of its docker command.
+ *
+ * @glinr/sentinel-core
+ */
+export interface ContainerUsage {
+  /** Container names */
+  containers: string[];
+  /** CPU usage in percent of one core */
+  cpu_percent: number;
+  memory_bytes: number;
+  /** Sum of the memory limits, 0 when unknown */
+  memory_limit: number;
+  network_rx_bytes: number;
+  network_tx_bytes: number;
+  block_read_bytes: number;
+  block_write_bytes: number;
+  sampled_at: string;
+}
+
+/**
+ * The last 30 usage samples of a process, oldest first
+ *
+ * Padded with null at the front until 30 samples exist.
+ *
+ * @glinr/sentinel-core
+ */
+export interface ProcessHistory {
+  /** Average time between samples */
+  sample_interval_ms: number;
+  /** CPU usage in percent of one core; null for the first sample */
+  cpu: (number | null)[];
+  /** Memory usage in bytes */
+  memory: (number | null)[];
+  /** Open file descriptors; null where they couldn't be counted */
+  open_fds: (number | null)[];
+  /** Threads; null where they couldn't be counted */
+  threads: (number | null)[];
+}
+
+/**
+ * Result of a process health check
+ *
+ * @glinr/sentinel-core
+ */
+export interface HealthResult {
+  healthy: boolean;
+  latency_ms: number;
+  /** Response status of HTTP checks that got a response */
+  status_code: number | null;
+  error: string | null;
+  consecutive_failures: number;
+  checked_at: string;
+}
+
+/**
+ * Result for one process of a bulk start, stop or restart by tag
+ *
+ * @glinr/sentinel-core
+ */
+export interface BulkOutcome {
+  name: string;
+  /** Why the operation failed for this process, null on success */
+  error: string | null;
+}
+
+/**
+ * How far one process got when starting processes together
+ *
+ * @glinr/sentinel-core
+ */
+export interface ProcessStartup {
+  name: string;
+  state: ProcessState;
+  /** Milliseconds from the start of the run until it was running */
+  runningAfterMs: number | null;
+  /** Milliseconds until its readiness probe passed, for processes with one */
+  readyAfterMs: number | null;
+  ports: number[];
+  failed: boolean;
+  /** Why starting it failed, or why it was skipped */
+  error: string | null;
+  /** First line of its output that looks like an error */
+  firstErrorLine: string | null;
+  /** Its output around the error, for failed processes */
+  logExcerpt: string[];
+}
+
+/**
+ * What came up after starting processes together
+ *
+ * @glinr/sentinel-core
+ */
+export interface StartupReport {
+  startedAt: string;
+  durationMs: number;
+  /** In start order */
+  processes: ProcessStartup[];
+  totals: {
+    processes: number;
+    running: number;
+    ready: number;
+    completed: number;
+    failed: number;
+  };
+  /** Dependencies that held up the process that was up last, ending with it */
+  criticalPath: string[];
+  criticalPathMs: number;
+}
+
+/**
+ * A process and the processes it spawned
+ *
+ * @glinr/sentinel-core
+ */
+export interface ProcessTreeNode {
+  pid: number;
+  name: string;
+  cpu_usage: number;
+  memory: number;
+  children: ProcessTreeNode[];
+}
+
+/**
+ * A process left running after the managed process it descends from exited
+ *
+ * @glinr/sentinel-core
+ */
+export interface OrphanInfo {
+  pid: number;
+  /** Current parent, usually init */
+  parent_pid: number | null;
+  name: string;
+  /** Start time in seconds since the epoch */
+  start_time: number;
+  /** Whether Sentinel killed it because of reapOrphans */
+  reaped: boolean;
+}
+
+/**
+ * Which manager runs a process: piped output, a pseudo-terminal, or none
+ * (external processes Sentinel only monitors)
+ *
+ * @glinr/sentinel-core
+ */
+export type ProcessKind = 'piped' | 'pty' | 'external';
+
+/**
+ * Summary of a piped, PTY or external process
+ *
+ * @glinr/sentinel-core
+ */
+export interface ManagedProcessSummary {
+  /** Process name (piped), process ID (PTY) or display name (external) */
+  id: string;
+  kind: ProcessKind;
+  pid: number | null;
+  state: ProcessState;
+  started_at: string | null;
+  cpu_usage: number | null;
+  memory_usage: number | null;
+  /** Listening ports; only looked up for external processes */
+  ports: number[];
 }
 
 /**
@@ -48,6 +282,8 @@ export interface CpuStats {
   overall: number;
   cores: number[];
   core_count: number;
+  /** Per-core frequencies in MHz, null when the platform doesn't report them */
+  frequencies: number[] | null;
 }
 
 /**
@@ -85,9 +321,291 @@ export interface SystemStats {
   cpu: CpuStats;
   memory: MemoryStats;
   disk: DiskStats;
+  /** Null on platforms without load averages (Windows) */
+  load_average: LoadAverage | null;
+  /** Null when the platform exposes no temperature sensors */
+  temperatures: ComponentTemperature[] | null;
+  /** Empty when no GPU can be read */
+  gpus: GpuStats[];
   timestamp: number;
 }
 
+/**
+ * 1, 5 and 15 minute load averages
+ *
+ * @glinr/sentinel-core
+ */
+export interface LoadAverage {
+  one: number;
+  five: number;
+  fifteen: number;
+}
+
+/**
+ * Hardware sensor temperature in °C
+ *
+ * @glinr/sentinel-core
+ */
+export interface ComponentTemperature {
+  label: string;
+  temperature: number;
+  max: number | null;
+  critical: number | null;
+}
+
+/**
+ * Usage of a graphics card; null where the card doesn't report a value
+ *
+ * @glinr/sentinel-core
+ */
+export interface GpuStats {
+  name: string;
+  /** Busy time in percent */
+  utilization: number | null;
+  memory_used: number | null;
+  memory_total: number | null;
+  /** °C */
+  temperature: number | null;
+}
+
+/**
+ * A mounted disk
+ *
+ * @glinr/sentinel-core
+ */
+export interface DiskInfo {
+  name: string;
+  mount_point: string;
+  file_system: string;
+  total_space: number;
+  available_space: number;
+  is_removable: boolean;
+}
+
+/**
+ * System details returned by `get_system_details`
+ *
+ * @glinr/sentinel-core
+ */
+export interface SystemDetails {
+  load_average: LoadAverage | null;
+  temperatures: ComponentTemperature[] | null;
+  disks: DiskInfo[];
+}
+
+/**
+ * Column to sort the top-processes list by (heaviest first)
+ *
+ * @glinr/sentinel-core
+ */
+export type TopProcessSort = 'cpu' | 'memory' | 'disk_read' | 'disk_write';
+
+/**
+ * A process running on the system, managed by Sentinel or not
+ *
+ * @glinr/sentinel-core
+ */
+export interface SystemProcess {
+  pid: number;
+  name: string;
+  exe: string | null;
+  cpu_usage: number;
+  memory: number;
+  disk_read_bytes_per_sec: number;
+  disk_write_bytes_per_sec: number;
+  parent_pid: number | null;
+  user: string | null;
+  /** Set when the PID belongs to a Sentinel-managed process */
+  managed: { id: string; kind: ProcessKind } | null;
+}
+
+/**
+ * One page of the top-processes list
+ *
+ * @glinr/sentinel-core
+ */
+export interface TopProcesses {
+  processes: SystemProcess[];
+  total: number;
+}
+
+/**
+ * Metric or event an alert rule watches
+ *
+ * @glinr/sentinel-core
+ */
+export type AlertMetric =
+  | 'cpu'
+  | 'memory'
+  | 'disk_free'
+  | 'open_files'
+  | 'crashed'
+  | 'restart_limit_exceeded';
+
+/**
+ * How urgent an alert is
+ *
+ * @glinr/sentinel-core
+ */
+export type AlertSeverity = 'info' | 'warning' | 'critical';
+
+/**
+ * An alert raised by a rule in the `alerts` config section
+ *
+ * @glinr/sentinel-core
+ */
+export interface Alert {
+  id: string;
+  /** Name of the rule that raised the alert */
+  rule: string;
+  /** Process name, or "system" */
+  target: string;
+  metric: AlertMetric;
+  severity: AlertSeverity;
+  message: string;
+  /** Metric value when fired, null for events */
+  value: number | null;
+  fired_at: string;
+  acknowledged: boolean;
+  /** Null while the alert is firing */
+  resolved_at: string | null;
+}
+
+/**
+ * Kind of event recorded in the event journal
+ *
+ * @glinr/sentinel-core
+ */
+export type EventType =
+  | 'process_started'
+  | 'process_stopped'
+  | 'process_crashed'
+  | 'process_completed'
+  | 'process_paused'
+  | 'process_resumed'
+  | 'process_restarted'
+  | 'restart_failed'
+  | 'restart_limit_exceeded'
+  | 'health_check_failed'
+  | 'notification_test'
+  | 'config_loaded'
+  | 'config_reloaded'
+  | 'port_killed'
+  | 'process_killed'
+  | 'schedule_triggered'
+  | 'max_runtime_exceeded'
+  | 'restart_policy_triggered'
+  | 'warm_restart_failed'
+  | 'logs_pruned'
+  | 'startup_finished';
+
+/**
+ * Who caused a journal event
+ *
+ * @glinr/sentinel-core
+ */
+export type EventActor = 'user' | 'supervisor';
+
+/**
+ * Something Sentinel did, from the event journal
+ *
+ * @glinr/sentinel-core
+ */
+export interface JournalEvent {
+  timestamp: string;
+  event_type: EventType;
+  /** Process name, config path or port */
+  subject: string;
+  /** Extra context such as an exit code; omitted when empty */
+  details?: Record<string, string>;
+  actor: EventActor;
+}
+
+/**
+ * Criteria for querying the event journal; omitted fields match everything
+ *
+ * @glinr/sentinel-core
+ */
+export interface EventFilter {
+  subject?: string;
+  eventTypes?: EventType[];
+  actor?: EventActor;
+}
+
+/**
+ * Format of a config file to import
+ *
+ * @glinr/sentinel-core
+ */
+export type ImportSource = 'pm2' | 'compose';
+
+/**
+ * Config converted from a PM2 ecosystem file or Docker Compose file
+ *
+ * @glinr/sentinel-core
+ */
+export interface ImportReport {
+  source: ImportSource;
+  config: { processes: ProcessConfig[]; [key: string]: unknown };
+  /** Options that were skipped or need a manual look */
+  warnings: string[];
+}
+
+/**
+ * What a config reload would change, by process name
+ *
+ * @glinr/sentinel-core
+ */
+export interface ConfigDiff {
+  added: string[];
+  removed: string[];
+  modified: string[];
+  settingsChanged: boolean;
+}
+
+/**
+ * Payload of the config-file-changed event, sent when sentinel.yaml is
+ * edited outside the app
+ *
+ * @glinr/sentinel-core
+ */
+export interface ConfigFileChangedEvent {
+  path: string;
+  valid: boolean;
+  error?: string | null;
+  diff?: ConfigDiff | null;
+  /** e.g. "2 processes modified", or the validation error */
+  summary: string;
+}
+
+/**
+ * What a process does when its env file changes
+ *
+ * @glinr/sentinel-core
+ */
+export type EnvChangeAction = 'prompt' | 'auto' | 'ignore';
+
+/**
+ * Payload of the env-file-changed event, sent when the env file of a
+ * running process is edited
+ *
+ * @glinr/sentinel-core
+ */
+export interface EnvFileChangedEvent {
+  process: string;
+  path: string;
+  /** New keys, values of secret keys masked */
+  added: Record<string, string>;
+  removed: string[];
+  /** Keys with a new value, values of secret keys masked */
+  changed: Record<string, string>;
+  /** e.g. "+PORT ~API_URL -DEBUG" */
+  summary: string;
+  action: EnvChangeAction;
+  restarted: boolean;
+  error?: string | null;
+}
+
 /**
  * Log entry
  *
@@ -125,3 +643,20 @@ export interface ProcessConfig {
   max_restarts: number | null;
   restart_delay_ms: number | null;
 }
+
+/**
+ * Changes made to a copy of a process configuration
+ *
+ * @glinr/sentinel-core
+ */
+export interface ProcessOverrides {
+  /** Merged into the source's environment, or replacing it with replaceEnv */
+  env?: Record<string, string>;
+  replaceEnv?: boolean;
+  /** Set as the copy's PORT */
+  port?: number;
+  /** Appended to the source's arguments, or replacing them with replaceArgs */
+  args?: string[];
+  replaceArgs?: boolean;
+  cwd?: string;
+}
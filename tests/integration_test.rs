@@ -25,7 +25,7 @@ fn test_config(name: &str, command: &str) -> ProcessConfig {
 
 #[tokio::test]
 async fn test_process_lifecycle_integration() {
-    let mut manager = ProcessManager::new();
+    let manager = ProcessManager::new();
 
     // Start a process
     let config = test_config("test-process", "sleep 2");
@@ -36,21 +36,21 @@ async fn test_process_lifecycle_integration() {
     assert!(info.pid.is_some());
 
     // List processes
-    let list = manager.list();
+    let list = manager.list().await;
     assert_eq!(list.len(), 1);
     assert_eq!(list[0].name, "test-process");
 
     // Stop the process
     manager.stop("test-process").await.unwrap();
 
-    let info = manager.get("test-process").unwrap();
+    let info = manager.get("test-process").await.unwrap();
     assert!(!info.is_running());
     assert!(info.pid.is_none());
 }
 
 #[tokio::test]
 async fn test_multiple_processes() {
-    let mut manager = ProcessManager::new();
+    let manager = ProcessManager::new();
 
     // Start multiple processes
     manager.start(test_config("proc1", "echo 1")).await.unwrap();
@@ -58,7 +58,7 @@ async fn test_multiple_processes() {
     manager.start(test_config("proc3", "echo 3")).await.unwrap();
 
     // Verify all are tracked
-    let list = manager.list();
+    let list = manager.list().await;
     assert_eq!(list.len(), 3);
 
     // Stop all
@@ -66,25 +66,25 @@ async fn test_multiple_processes() {
 
     // Verify all stopped
     for name in &["proc1", "proc2", "proc3"] {
-        let info = manager.get(name).unwrap();
+        let info = manager.get(name).await.unwrap();
         assert!(!info.is_running());
     }
 }
 
 #[tokio::test]
 async fn test_restart_process() {
-    let mut manager = ProcessManager::new();
+    let manager = ProcessManager::new();
 
     let config = test_config("restart-test", "echo hello");
     manager.start(config).await.unwrap();
 
-    let old_pid = manager.get("restart-test").unwrap().pid;
+    let old_pid = manager.get("restart-test").await.unwrap().pid;
 
     sleep(Duration::from_millis(200)).await;
 
     manager.restart("restart-test").await.unwrap();
 
-    let new_pid = manager.get("restart-test").unwrap().pid;
+    let new_pid = manager.get("restart-test").await.unwrap().pid;
 
     // PIDs should be different (new process spawned)
     assert_ne!(old_pid, new_pid);
@@ -183,7 +183,7 @@ async fn test_system_monitor_integration() {
 
 #[tokio::test]
 async fn test_process_stats_tracking() {
-    let mut manager = ProcessManager::new();
+    let manager = ProcessManager::new();
     let mut monitor = SystemMonitor::new();
 
     // Start a process
@@ -235,7 +235,7 @@ fn test_config_save_and_reload() {
 
 #[tokio::test]
 async fn test_process_error_handling() {
-    let mut manager = ProcessManager::new();
+    let manager = ProcessManager::new();
 
     // Try to start process with invalid command
     let config = test_config("invalid", "/nonexistent/command");